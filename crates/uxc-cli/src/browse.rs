@@ -0,0 +1,706 @@
+//! Terminal endpoint browser (`uxc browse <url>`)
+//!
+//! Four panes: the operation list with a fuzzy filter, the selected
+//! operation's parameters and input schema, a form with one field per
+//! argument, and the result of the last call. [`App`] holds the state and
+//! turns key presses into [`Action`]s; [`run`] owns the terminal and performs
+//! the describe and call requests the actions ask for.
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uxc_core::adapters::{self, Adapter, Operation, OperationDetail};
+use uxc_core::datagen;
+
+/// What the browser needs done after a key press
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Load the detail of an operation
+    Describe(String),
+    /// Call an operation with the form's arguments
+    Execute(String, HashMap<String, Value>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    List,
+    Search,
+    Form,
+    Result,
+}
+
+/// One argument in the input form
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    name: String,
+    required: bool,
+    /// JSON Schema type; values of other types are parsed as JSON
+    kind: String,
+    value: String,
+}
+
+pub struct App {
+    endpoint: String,
+    protocol: String,
+    operations: Vec<Operation>,
+    query: String,
+    /// Indexes into `operations` matching `query`, best first
+    matches: Vec<usize>,
+    list: ListState,
+    focus: Focus,
+    details: HashMap<String, OperationDetail>,
+    /// Operation the form fields belong to
+    form_operation: Option<String>,
+    fields: Vec<Field>,
+    field: usize,
+    result: Option<String>,
+    result_scroll: u16,
+    status: String,
+    calls: usize,
+}
+
+impl App {
+    pub fn new(endpoint: &str, protocol: &str, operations: Vec<Operation>) -> Self {
+        let mut app = Self {
+            endpoint: endpoint.to_string(),
+            protocol: protocol.to_string(),
+            operations,
+            query: String::new(),
+            matches: Vec::new(),
+            list: ListState::default(),
+            focus: Focus::List,
+            details: HashMap::new(),
+            form_operation: None,
+            fields: Vec::new(),
+            field: 0,
+            result: None,
+            result_scroll: 0,
+            status: "/ search  enter form  q quit".to_string(),
+            calls: 0,
+        };
+        app.refilter();
+        app
+    }
+
+    /// Calls made during the session
+    pub fn calls(&self) -> usize {
+        self.calls
+    }
+
+    /// Operation under the cursor
+    pub fn selected(&self) -> Option<&Operation> {
+        self.list
+            .selected()
+            .and_then(|index| self.matches.get(index))
+            .map(|&index| &self.operations[index])
+    }
+
+    /// Action for the current selection: its detail is loaded on demand
+    fn describe_selected(&self) -> Action {
+        match self.selected() {
+            Some(op) if !self.details.contains_key(&op.operation_id) => {
+                Action::Describe(op.operation_id.clone())
+            }
+            _ => Action::None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        match self.focus {
+            Focus::List => self.list_key(key),
+            Focus::Search => self.search_key(key),
+            Focus::Form => self.form_key(key),
+            Focus::Result => self.result_key(key),
+        }
+    }
+
+    fn list_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Char('/') => {
+                self.focus = Focus::Search;
+                self.status = "type to filter  enter/esc done".to_string();
+                Action::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Enter | KeyCode::Tab => {
+                if self.selected().is_none() {
+                    return Action::None;
+                }
+                self.load_form();
+                self.focus = Focus::Form;
+                self.status =
+                    "type to edit  up/down field  enter call  tab result  esc back".to_string();
+                self.describe_selected()
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn search_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => {
+                self.focus = Focus::List;
+                self.status = "/ search  enter form  q quit".to_string();
+                Action::None
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+                self.describe_selected()
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.refilter();
+                self.describe_selected()
+            }
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Up => self.move_selection(-1),
+            _ => Action::None,
+        }
+    }
+
+    fn form_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Esc => {
+                self.focus = Focus::List;
+                self.status = "/ search  enter form  q quit".to_string();
+                Action::None
+            }
+            KeyCode::Tab => {
+                self.focus = Focus::Result;
+                self.status = "up/down scroll  esc back".to_string();
+                Action::None
+            }
+            KeyCode::Down if !self.fields.is_empty() => {
+                self.field = (self.field + 1) % self.fields.len();
+                Action::None
+            }
+            KeyCode::Up if !self.fields.is_empty() => {
+                self.field = (self.field + self.fields.len() - 1) % self.fields.len();
+                Action::None
+            }
+            KeyCode::Backspace => {
+                if let Some(field) = self.fields.get_mut(self.field) {
+                    field.value.pop();
+                }
+                Action::None
+            }
+            KeyCode::Char(c) => {
+                if let Some(field) = self.fields.get_mut(self.field) {
+                    field.value.push(c);
+                }
+                Action::None
+            }
+            KeyCode::Enter => match &self.form_operation {
+                Some(operation) => {
+                    if let Some(missing) = self
+                        .fields
+                        .iter()
+                        .find(|field| field.required && field.value.is_empty())
+                    {
+                        self.status = format!("'{}' is required", missing.name);
+                        return Action::None;
+                    }
+                    Action::Execute(operation.clone(), self.arguments())
+                }
+                None => Action::None,
+            },
+            _ => Action::None,
+        }
+    }
+
+    fn result_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Esc | KeyCode::Tab => {
+                self.focus = Focus::Form;
+                self.status =
+                    "type to edit  up/down field  enter call  tab result  esc back".to_string();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.result_scroll = self.result_scroll.saturating_add(1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.result_scroll = self.result_scroll.saturating_sub(1)
+            }
+            KeyCode::PageDown => self.result_scroll = self.result_scroll.saturating_add(20),
+            KeyCode::PageUp => self.result_scroll = self.result_scroll.saturating_sub(20),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn move_selection(&mut self, delta: isize) -> Action {
+        if self.matches.is_empty() {
+            return Action::None;
+        }
+        let len = self.matches.len() as isize;
+        let current = self.list.selected().unwrap_or(0) as isize;
+        self.list
+            .select(Some((current + delta).rem_euclid(len) as usize));
+        self.describe_selected()
+    }
+
+    fn refilter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .operations
+            .iter()
+            .enumerate()
+            .filter_map(|(index, op)| {
+                fuzzy_score(&self.query, &op.operation_id).map(|score| (score, index))
+            })
+            .collect();
+        // Best match first; ties keep the adapter's order
+        scored.sort_by_key(|&(score, index)| (std::cmp::Reverse(score), index));
+        self.matches = scored.into_iter().map(|(_, index)| index).collect();
+        self.list.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    /// Record the detail of an operation, refreshing the form if it is open
+    pub fn set_detail(&mut self, detail: OperationDetail) {
+        // Fields guessed from the operation list give way to the full schema
+        // unless the user already typed into them
+        if self.form_operation.as_deref() == Some(detail.operation_id.as_str())
+            && self.fields.iter().all(|field| field.value.is_empty())
+        {
+            self.fields = form_fields(&datagen::payload_schema(&detail));
+            self.field = 0;
+        }
+        self.details.insert(detail.operation_id.clone(), detail);
+    }
+
+    /// Fields for the selected operation, from its detail when loaded
+    fn load_form(&mut self) {
+        let Some(op) = self.selected() else {
+            return;
+        };
+        let operation_id = op.operation_id.clone();
+        if self.form_operation.as_deref() == Some(operation_id.as_str()) && !self.fields.is_empty()
+        {
+            return;
+        }
+        let fields = match self.details.get(&operation_id) {
+            Some(detail) => form_fields(&datagen::payload_schema(detail)),
+            None => op
+                .parameters
+                .iter()
+                .map(|parameter| Field {
+                    name: parameter.name.clone(),
+                    required: parameter.required,
+                    kind: parameter.param_type.clone(),
+                    value: String::new(),
+                })
+                .collect(),
+        };
+        self.form_operation = Some(operation_id);
+        self.fields = fields;
+        self.field = 0;
+    }
+
+    fn arguments(&self) -> HashMap<String, Value> {
+        self.fields
+            .iter()
+            .filter(|field| !field.value.is_empty())
+            .map(|field| {
+                let value = if field.kind == "string" {
+                    Value::String(field.value.clone())
+                } else {
+                    serde_json::from_str(&field.value)
+                        .unwrap_or_else(|_| Value::String(field.value.clone()))
+                };
+                (field.name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Show the outcome of a call
+    pub fn set_result(&mut self, operation: &str, result: Result<Value>, elapsed: Duration) {
+        self.calls += 1;
+        self.result_scroll = 0;
+        let took = uxc_core::output::format_duration(elapsed.as_millis() as u64);
+        match result {
+            Ok(data) => {
+                self.status = format!("{} ok in {}", operation, took);
+                self.result = Some(serde_json::to_string_pretty(&data).unwrap_or_default());
+            }
+            Err(err) => {
+                self.status = format!("{} failed in {}", operation, took);
+                self.result = Some(format!("{:#}", err));
+            }
+        }
+    }
+
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = status.into();
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+        let [detail, form, result] = Layout::vertical([
+            Constraint::Percentage(40),
+            Constraint::Percentage(25),
+            Constraint::Percentage(35),
+        ])
+        .areas(right);
+
+        self.draw_list(frame, left);
+        self.draw_detail(frame, detail);
+        self.draw_form(frame, form);
+        self.draw_result(frame, result);
+        frame.render_widget(
+            Paragraph::new(self.status.as_str()).style(Style::new().add_modifier(Modifier::DIM)),
+            status,
+        );
+    }
+
+    fn block(&self, title: String, focus: &[Focus]) -> Block<'static> {
+        let block = Block::bordered().title(title);
+        if focus.contains(&self.focus) {
+            block.border_style(Style::new().bold())
+        } else {
+            block
+        }
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let [search, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        let cursor = if self.focus == Focus::Search { "_" } else { "" };
+        frame.render_widget(
+            Paragraph::new(format!("{}{}", self.query, cursor))
+                .block(self.block("Search".to_string(), &[Focus::Search])),
+            search,
+        );
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&index| ListItem::new(self.operations[index].operation_id.as_str()))
+            .collect();
+        let title = format!(
+            "{} {} ({}/{})",
+            self.protocol,
+            self.endpoint,
+            self.matches.len(),
+            self.operations.len()
+        );
+        let list = List::new(items)
+            .block(self.block(title, &[Focus::List]))
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = Vec::new();
+        if let Some(op) = self.selected() {
+            lines.push(Line::from(op.operation_id.clone().bold()));
+            let detail = self.details.get(&op.operation_id);
+            let description = detail
+                .and_then(|detail| detail.description.clone())
+                .or_else(|| op.description.clone());
+            if let Some(description) = description {
+                lines.push(Line::from(description));
+            }
+            let parameters = detail.map_or(&op.parameters, |detail| &detail.parameters);
+            if !parameters.is_empty() {
+                lines.push(Line::default());
+                lines.push(Line::from("Parameters".underlined()));
+                for parameter in parameters {
+                    let mut spans = vec![
+                        Span::raw(format!("  {}", parameter.name)),
+                        Span::raw(format!(": {}", parameter.param_type)).dim(),
+                    ];
+                    if parameter.required {
+                        spans.push(Span::raw(" (required)"));
+                    }
+                    if let Some(description) = &parameter.description {
+                        spans.push(Span::raw(format!("  {}", description)).dim());
+                    }
+                    lines.push(Line::from(spans));
+                }
+            }
+            match detail.and_then(|detail| detail.input_schema.as_ref()) {
+                Some(schema) => {
+                    lines.push(Line::default());
+                    lines.push(Line::from("Input schema".underlined()));
+                    let pretty = serde_json::to_string_pretty(schema).unwrap_or_default();
+                    lines.extend(pretty.lines().map(|line| Line::from(line.to_string())));
+                }
+                None if detail.is_none() => lines.push(Line::from("loading...".dim())),
+                None => {}
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .block(self.block("Operation".to_string(), &[])),
+            area,
+        );
+    }
+
+    fn draw_form(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = if self.fields.is_empty() {
+            vec![Line::from(
+                if self.form_operation.is_some() {
+                    "No arguments; press enter to call"
+                } else {
+                    "Select an operation and press enter"
+                }
+                .dim(),
+            )]
+        } else {
+            self.fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    let marker = if index == self.field && self.focus == Focus::Form {
+                        "> "
+                    } else {
+                        "  "
+                    };
+                    let required = if field.required { "*" } else { "" };
+                    Line::from(vec![
+                        Span::raw(format!("{}{}{}", marker, field.name, required)),
+                        Span::raw(format!(" ({}) = ", field.kind)).dim(),
+                        Span::raw(field.value.clone()),
+                    ])
+                })
+                .collect()
+        };
+        let title = match &self.form_operation {
+            Some(operation) => format!("Arguments for {}", operation),
+            None => "Arguments".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(self.block(title, &[Focus::Form])),
+            area,
+        );
+    }
+
+    fn draw_result(&self, frame: &mut Frame, area: Rect) {
+        let text = self.result.as_deref().unwrap_or("");
+        frame.render_widget(
+            Paragraph::new(text)
+                .scroll((self.result_scroll, 0))
+                .block(self.block("Result".to_string(), &[Focus::Result])),
+            area,
+        );
+    }
+}
+
+/// Run the browser until the user quits; returns the number of calls made.
+/// `on_call` sees every call with its outcome and duration.
+pub async fn run(
+    adapter: &adapters::AdapterEnum,
+    url: &str,
+    mut on_call: impl FnMut(&str, std::result::Result<(), &anyhow::Error>, Duration),
+) -> Result<usize> {
+    let operations = adapter.list_operations(url).await?;
+    let mut app = App::new(url, adapter.protocol_type().as_str(), operations);
+
+    let mut terminal = ratatui::try_init()?;
+    let outcome = event_loop(&mut terminal, &mut app, adapter, url, &mut on_call).await;
+    ratatui::restore();
+    outcome.map(|_| app.calls())
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    adapter: &adapters::AdapterEnum,
+    url: &str,
+    on_call: &mut impl FnMut(&str, std::result::Result<(), &anyhow::Error>, Duration),
+) -> Result<()> {
+    let mut pending = app.describe_selected();
+    loop {
+        match std::mem::replace(&mut pending, Action::None) {
+            Action::Quit => return Ok(()),
+            Action::Describe(operation) => {
+                terminal.draw(|frame| app.draw(frame))?;
+                match adapter.describe_operation(url, &operation).await {
+                    Ok(detail) => app.set_detail(detail),
+                    Err(err) => app.set_status(format!("describe {} failed: {}", operation, err)),
+                }
+            }
+            Action::Execute(operation, args) => {
+                app.set_status(format!("calling {}...", operation));
+                terminal.draw(|frame| app.draw(frame))?;
+                let start = Instant::now();
+                let result = adapter
+                    .execute(url, &operation, args)
+                    .await
+                    .map(|result| result.data);
+                on_call(&operation, result.as_ref().map(|_| ()), start.elapsed());
+                app.set_result(&operation, result, start.elapsed());
+            }
+            Action::None => {}
+        }
+
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            pending = app.handle_key(key);
+        }
+    }
+}
+
+/// Fields for the properties of an argument schema, required ones first
+fn form_fields(schema: &Value) -> Vec<Field> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let mut fields: Vec<Field> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| Field {
+            name: name.clone(),
+            required: required.contains(&name.as_str()),
+            kind: property
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("string")
+                .to_string(),
+            value: String::new(),
+        })
+        .collect();
+    fields.sort_by_key(|field| !field.required);
+    fields
+}
+
+/// Subsequence match of `query` in `candidate`, ignoring case. Higher is
+/// better: consecutive characters and matches at word starts score more.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        let found = (position..candidate.len())
+            .find(|&index| candidate[index].to_lowercase().eq(std::iter::once(wanted)))?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        // Matches spread far apart are worth less
+        score -= (found - position) as i64 / 4;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uxc_core::adapters::Parameter;
+
+    fn operation(id: &str, parameters: Vec<Parameter>) -> Operation {
+        Operation {
+            operation_id: id.to_string(),
+            display_name: id.to_string(),
+            description: None,
+            parameters,
+            return_type: None,
+            tags: Vec::new(),
+        }
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_tight_word_start_matches() {
+        assert_eq!(fuzzy_score("", "get:/users"), Some(0));
+        assert!(fuzzy_score("xyz", "get:/users").is_none());
+        let tight = fuzzy_score("users", "get:/users").unwrap();
+        let loose = fuzzy_score("users", "get:/u/s/e/r/s").unwrap();
+        assert!(tight > loose);
+        assert!(fuzzy_score("GU", "get:/users").is_some());
+    }
+
+    #[test]
+    fn search_form_and_call_flow() {
+        let limit = Parameter {
+            name: "limit".to_string(),
+            param_type: "integer".to_string(),
+            required: true,
+            description: None,
+            location: None,
+        };
+        let mut app = App::new(
+            "https://api.test",
+            "openapi",
+            vec![
+                operation("get:/pets", Vec::new()),
+                operation("get:/users", vec![limit]),
+            ],
+        );
+        assert_eq!(app.selected().unwrap().operation_id, "get:/pets");
+
+        press(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "usr");
+        assert_eq!(app.matches.len(), 1);
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            Action::Describe("get:/users".to_string())
+        );
+
+        // Required fields must be filled before calling
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        type_text(&mut app, "5");
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            Action::Execute(
+                "get:/users".to_string(),
+                HashMap::from([("limit".to_string(), serde_json::json!(5))])
+            )
+        );
+
+        app.set_result("get:/users", Ok(serde_json::json!([])), Duration::ZERO);
+        assert_eq!(app.calls(), 1);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+}
@@ -0,0 +1,1171 @@
+//! Command-line arguments: global flags, subcommands and their options
+
+use crate::completion::CompletionShell;
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::endpoint::{parse_jsonrpc_version, parse_protocol, parse_server_var, retry_policy};
+use uxc_core::adapters::jsonrpc::JsonRpcVersion;
+use uxc_core::adapters::ProtocolType;
+use uxc_core::assertions::HeaderExpectation;
+use uxc_core::batch::BatchOptions;
+use uxc_core::codegen;
+use uxc_core::error::UxcError;
+use uxc_core::history::{self, HistoryFilter, HistoryStatus};
+use uxc_core::interop::command::CommandFormat;
+use uxc_core::mock;
+use uxc_core::units;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    Json,
+    Text,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ListSort {
+    /// Most recently called first
+    Recent,
+    /// Most frequently called first
+    Frequent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum HistoryStatusArg {
+    Ok,
+    Error,
+    Blocked,
+}
+
+impl From<HistoryStatusArg> for HistoryStatus {
+    fn from(status: HistoryStatusArg) -> Self {
+        match status {
+            HistoryStatusArg::Ok => HistoryStatus::Ok,
+            HistoryStatusArg::Error => HistoryStatus::Error,
+            HistoryStatusArg::Blocked => HistoryStatus::Blocked,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CollectionFormat {
+    Postman,
+    Insomnia,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CodegenLanguage {
+    Rust,
+    Typescript,
+    Python,
+}
+
+impl From<CodegenLanguage> for codegen::Language {
+    fn from(language: CodegenLanguage) -> Self {
+        match language {
+            CodegenLanguage::Rust => codegen::Language::Rust,
+            CodegenLanguage::Typescript => codegen::Language::TypeScript,
+            CodegenLanguage::Python => codegen::Language::Python,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CommandFormatArg {
+    Curl,
+    Grpcurl,
+    /// Pipe the messages into a stdio server
+    Shell,
+}
+
+impl From<CommandFormatArg> for CommandFormat {
+    fn from(format: CommandFormatArg) -> Self {
+        match format {
+            CommandFormatArg::Curl => CommandFormat::Curl,
+            CommandFormatArg::Grpcurl => CommandFormat::Grpcurl,
+            CommandFormatArg::Shell => CommandFormat::Shell,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ImportFormat {
+    Postman,
+    Insomnia,
+    Curl,
+    Httpie,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputMode {
+    Json,
+    Text,
+    Csv,
+}
+
+#[derive(Parser)]
+#[command(name = "uxc")]
+#[command(about = "Universal X-Protocol Call", long_about = None)]
+#[command(version = env!("CARGO_PKG_VERSION"))]
+#[command(disable_help_flag = true)]
+#[command(disable_help_subcommand = true)]
+pub(crate) struct Cli {
+    /// Show help
+    #[arg(short = 'h', long = "help", global = true)]
+    pub(crate) help: bool,
+
+    /// Authentication profile name (default: "default", overrides UXC_PROFILE env var)
+    #[arg(long, global = true)]
+    pub(crate) profile: Option<String>,
+
+    /// Disable cache for this operation
+    #[arg(long, global = true)]
+    pub(crate) no_cache: bool,
+
+    /// How long cached schemas stay fresh: seconds or a duration such as 12h
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_seconds)]
+    pub(crate) cache_ttl: Option<u64>,
+
+    /// Serve expired cached schemas immediately and refresh them in the background
+    #[arg(long, global = true)]
+    pub(crate) stale_while_revalidate: bool,
+
+    /// Never use the network; serve schemas from the cache only (or set UXC_OFFLINE=1)
+    #[arg(long, global = true)]
+    pub(crate) offline: bool,
+
+    /// Resolve schemas from the snapshots taken on or before DATE (2024-06-01 or an RFC 3339 time)
+    #[arg(long, global = true, value_name = "DATE")]
+    pub(crate) as_of: Option<String>,
+
+    /// Write the calls made and the schemas used to a cassette file
+    #[arg(
+        long,
+        global = true,
+        value_name = "CASSETTE",
+        conflicts_with = "replay"
+    )]
+    pub(crate) record: Option<String>,
+
+    /// Answer calls from a cassette written by --record, without the network
+    #[arg(long, global = true, value_name = "CASSETTE", conflicts_with = "as_of")]
+    pub(crate) replay: Option<String>,
+
+    /// Explicit schema: an OpenAPI document URL, or a GraphQL SDL or introspection result (URL or file)
+    #[arg(long, global = true)]
+    pub(crate) schema_url: Option<String>,
+
+    /// Send OpenAPI calls to a server the schema lists (by index) or to this URL
+    #[arg(long, global = true, value_name = "INDEX|URL")]
+    pub(crate) server: Option<String>,
+
+    /// Value of a variable in the server URL (repeatable; the first server unless --server)
+    #[arg(long = "server-var", global = true, value_name = "NAME=VALUE", value_parser = parse_server_var)]
+    pub(crate) server_vars: Vec<(String, String)>,
+
+    /// GraphQL fields to select from the result, e.g. 'id,name,owner{login}'
+    #[arg(long, global = true, value_name = "FIELDS")]
+    pub(crate) select: Option<String>,
+
+    /// Levels of object fields GraphQL results select by default (1: scalars only)
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub(crate) depth: Option<u32>,
+
+    /// Send GraphQL documents as persisted queries: 'apq' (hash first, document when asked) or 'id' (registered IDs only)
+    #[arg(long, global = true, value_name = "MODE", value_parser = ["apq", "id"])]
+    pub(crate) persisted_queries: Option<String>,
+
+    /// ID of the registered GraphQL document to run (implies --persisted-queries id)
+    #[arg(long, global = true, value_name = "ID")]
+    pub(crate) persisted_query_id: Option<String>,
+
+    /// gRPC HTTP/2 keepalive ping interval (seconds or a duration such as 1m)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_seconds)]
+    pub(crate) grpc_keepalive_interval: Option<u64>,
+
+    /// gRPC keepalive ping acknowledgement timeout (seconds or a duration)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_seconds)]
+    pub(crate) grpc_keepalive_timeout: Option<u64>,
+
+    /// Extra gRPC connection attempts after a failed connect
+    #[arg(long, global = true, value_name = "N")]
+    pub(crate) grpc_connect_retries: Option<u32>,
+
+    /// Reconnect gRPC channels idle for longer than this (seconds or a duration such as 5m)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_seconds)]
+    pub(crate) grpc_idle_timeout: Option<u64>,
+
+    /// Call annotated gRPC methods through this gRPC-JSON gateway when the server cannot be reached
+    #[arg(long, global = true, value_name = "URL")]
+    pub(crate) via_http: Option<String>,
+
+    /// JSON-RPC version to speak (1.0 or 2.0; auto-detected by default)
+    #[arg(long, global = true, value_name = "VERSION", value_parser = parse_jsonrpc_version)]
+    pub(crate) jsonrpc_version: Option<JsonRpcVersion>,
+
+    /// Use this protocol without probing the endpoint (see `uxc detect --all`)
+    #[arg(long, global = true, value_name = "PROTOCOL", value_parser = parse_protocol)]
+    pub(crate) protocol: Option<ProtocolType>,
+
+    /// Serve every protocol detected at the endpoint; operation IDs are prefixed with their protocol (openapi:get:/users)
+    #[arg(long, global = true, conflicts_with = "protocol")]
+    pub(crate) all_protocols: bool,
+
+    /// Output format (default: `[output] format`, else json)
+    #[arg(long, value_enum, global = true)]
+    pub(crate) format: Option<OutputFormat>,
+
+    /// Use human-readable text output
+    #[arg(long, global = true, conflicts_with = "format")]
+    pub(crate) text: bool,
+
+    /// Show timestamps in text output in UTC instead of local time
+    #[arg(long, global = true)]
+    pub(crate) utc: bool,
+
+    /// Filter the result data with a JMESPath expression (e.g. 'data[].name')
+    #[arg(long, global = true, value_name = "EXPR")]
+    pub(crate) query: Option<String>,
+
+    /// Render the envelope through a minijinja template (inline, or @file)
+    #[arg(long, global = true, value_name = "TEMPLATE")]
+    pub(crate) template: Option<String>,
+
+    /// Write the upstream response body to stdout as-is instead of an envelope
+    #[arg(long, global = true, conflicts_with_all = ["format", "text", "query", "template"])]
+    pub(crate) raw: bool,
+
+    /// Copy matching response headers into meta.headers (comma-separated, `*` wildcards)
+    #[arg(long, global = true, value_name = "PATTERNS", conflicts_with = "raw")]
+    pub(crate) include_headers: Option<String>,
+
+    /// Fail unless the response has header NAME (with VALUE, if given; repeatable)
+    #[arg(long = "expect-header", global = true, value_name = "NAME[=VALUE]", value_parser = HeaderExpectation::parse, conflicts_with = "raw")]
+    pub(crate) expect_headers: Vec<HeaderExpectation>,
+
+    /// Fail when the response departs from the schema its operation documents for the status
+    #[arg(long, global = true, conflicts_with = "raw")]
+    pub(crate) validate_response: bool,
+
+    /// Follow the pages of a list operation and return the items of all of them
+    #[arg(long, global = true, conflicts_with_all = ["raw", "dry_run", "export"])]
+    pub(crate) paginate: bool,
+
+    /// Pages fetched at most with --paginate (default: pagination.max_pages, 100)
+    #[arg(long, global = true, value_name = "N", requires = "paginate")]
+    pub(crate) max_pages: Option<u64>,
+
+    /// Continue an interrupted --paginate run from the last page it fetched
+    #[arg(long, global = true, requires = "paginate")]
+    pub(crate) resume: bool,
+
+    /// Write the result to PATH and report the path; a directory gets numbered files
+    /// (`uxc export`, `uxc codegen` and `uxc auth export` write the collection, client or
+    /// TOML export itself)
+    #[arg(short, long, global = true, value_name = "PATH")]
+    pub(crate) output: Option<String>,
+
+    /// Load variables for ${VAR} substitution from this file (default: ./.env if present)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub(crate) env_file: Option<String>,
+
+    /// Only contact hosts matching these comma-separated globs (overrides UXC_ALLOWED_HOSTS)
+    #[arg(long, global = true, value_name = "PATTERNS")]
+    pub(crate) allowed_hosts: Option<String>,
+
+    /// Follow at most N HTTP redirects (default: 10)
+    #[arg(long, global = true, value_name = "N")]
+    pub(crate) max_redirects: Option<usize>,
+
+    /// Return HTTP redirects instead of following them
+    #[arg(long, global = true, conflicts_with = "max_redirects")]
+    pub(crate) no_follow_redirects: bool,
+
+    /// Keep credentials when a redirect leads to another host (dropped by default)
+    #[arg(long, global = true)]
+    pub(crate) keep_auth_on_redirect: bool,
+
+    /// Send HTTP requests through this proxy (default: `[network] proxy`)
+    #[arg(long, global = true, value_name = "URL")]
+    pub(crate) proxy: Option<String>,
+
+    /// Fail HTTP responses larger than this, e.g. 10MB (default: `[network] max_response_bytes`)
+    #[arg(long, global = true, value_name = "SIZE", value_parser = units::parse_size)]
+    pub(crate) max_response_bytes: Option<u64>,
+
+    /// Give up on one attempt of a call after this long, e.g. 30s (default: `[call] timeout`)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_positive_duration)]
+    pub(crate) timeout: Option<std::time::Duration>,
+
+    /// Retry a call this many times after a timeout, network error or retried status (default: `[call] retries`)
+    #[arg(long, global = true, value_name = "N")]
+    pub(crate) retries: Option<u32>,
+
+    /// Wait before the first retry, doubled for each further one (default: `[call] retry_backoff`, 1s)
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_duration)]
+    pub(crate) retry_backoff: Option<std::time::Duration>,
+
+    /// HTTP statuses and gRPC codes to retry, e.g. 429,503,UNAVAILABLE (default: `[call] retry_on`, or statuses that say to try again)
+    #[arg(long, global = true, value_name = "CODES")]
+    pub(crate) retry_on: Option<String>,
+
+    /// Repeat the call every DURATION and print each result, e.g. 10s
+    #[arg(long, global = true, value_name = "DURATION", value_parser = units::parse_positive_duration, conflicts_with_all = ["dry_run", "export"])]
+    pub(crate) watch: Option<std::time::Duration>,
+
+    /// With --watch, print a result only when it differs from the previous one, with the differences
+    #[arg(long, global = true, requires = "watch", conflicts_with = "raw")]
+    pub(crate) changes_only: bool,
+
+    /// With --watch, stop after N runs
+    #[arg(long, global = true, value_name = "N", requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+    pub(crate) watch_count: Option<u64>,
+
+    /// Reject argument names the operation does not declare (default: `[call] strict_args`)
+    #[arg(long, global = true)]
+    pub(crate) strict_args: bool,
+
+    /// Send undeclared argument names even when strict mode is configured
+    #[arg(long, global = true, conflicts_with = "strict_args")]
+    pub(crate) allow_extra: bool,
+
+    /// Check and coerce the arguments and print the request a call would send, without sending it
+    #[arg(long, global = true)]
+    pub(crate) dry_run: bool,
+
+    /// Print a command that makes the call with another tool (curl, grpcurl, or shell for stdio servers), without sending it
+    #[arg(long, global = true, value_name = "FORMAT", conflicts_with = "dry_run")]
+    pub(crate) export: Option<CommandFormatArg>,
+
+    /// Hide credentials in --export commands
+    #[arg(long, global = true, requires = "export")]
+    pub(crate) redact: bool,
+
+    /// Add meta.explain: why the protocol, auth, cache and schema mapping were chosen
+    #[arg(long, global = true)]
+    pub(crate) explain: bool,
+
+    /// Trace requests and responses (headers, body, timing) to stderr, secrets redacted
+    #[arg(short, long, global = true)]
+    pub(crate) verbose: bool,
+
+    /// Run in this process even when `uxc daemon` is running
+    #[arg(long, global = true)]
+    pub(crate) no_daemon: bool,
+
+    /// Remote endpoint URL (not used with 'auth' or most 'cache' subcommands)
+    #[arg(value_name = "URL", global = true)]
+    pub(crate) url: Option<String>,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Commands>,
+}
+
+// Parsed once per process, so variant sizes do not matter.
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+pub(crate) enum Commands {
+    /// List available operations (`-v` adds details in text mode)
+    List {
+        /// Order operations by your call history
+        #[arg(long, value_enum)]
+        sort: Option<ListSort>,
+
+        /// Only operations with this tag (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Only operations using this HTTP method or GraphQL kind (repeatable)
+        #[arg(long = "method", value_name = "METHOD")]
+        methods: Vec<String>,
+
+        /// Only operations whose path matches this glob (`*` within a segment,
+        /// `**` across segments)
+        #[arg(long, value_name = "GLOB")]
+        path: Option<String>,
+    },
+
+    /// Find operations whose ID, name or description matches a term
+    Search {
+        /// Words to look for, quoted when several; near misses match too
+        #[arg(value_name = "TERM")]
+        term: String,
+    },
+
+    /// Describe one operation in detail
+    Describe {
+        /// Operation ID (e.g., "get:/users/{id}", "query/user", "ask_question")
+        #[arg(value_name = "OPERATION_ID")]
+        operation_id: String,
+    },
+
+    /// Show endpoint help, or operation help when OPERATION_ID is provided
+    Help {
+        /// Optional operation ID
+        #[arg(value_name = "OPERATION_ID")]
+        operation_id: Option<String>,
+    },
+
+    /// Inspect endpoint/schema
+    Inspect {
+        /// Show full schema
+        #[arg(short, long)]
+        full: bool,
+    },
+
+    /// Find the operation that handles a concrete request (OpenAPI)
+    Which {
+        /// HTTP method (default: GET)
+        #[arg(long)]
+        method: Option<String>,
+
+        /// Request path or full URL
+        #[arg(long, required_unless_present = "json")]
+        path: Option<String>,
+
+        /// Request sample as JSON with "method" and "url" or "path" (a HAR entry works too)
+        #[arg(long, conflicts_with_all = ["method", "path"])]
+        json: Option<String>,
+    },
+
+    /// Manage schema cache
+    Cache {
+        #[command(subcommand)]
+        cache_command: CacheCommands,
+    },
+
+    /// Manage authentication profiles
+    Auth {
+        #[command(subcommand)]
+        auth_command: AuthCommands,
+    },
+
+    /// Execute an operation explicitly
+    Call {
+        /// Operation ID
+        #[arg(value_name = "OPERATION_ID")]
+        operation_id: String,
+
+        /// Key-value arguments (e.g., "id=42", "count:=3", "owner.name=Ann")
+        #[arg(short, long)]
+        args: Vec<String>,
+
+        /// JSON input payload, `@file` to read it from a file or `-` from stdin
+        #[arg(long)]
+        json: Option<String>,
+    },
+
+    /// Run a GraphQL document of your own (queries, mutations, fragments)
+    Query {
+        /// File holding the document, or `-` for stdin
+        #[arg(long, value_name = "PATH")]
+        file: String,
+
+        /// Variables as JSON, `@file` to read them from a file or `-` from stdin
+        #[arg(long, value_name = "JSON")]
+        vars: Option<String>,
+
+        /// Operation to run when the document defines several
+        #[arg(long, value_name = "NAME")]
+        operation_name: Option<String>,
+    },
+
+    /// Show which features each protocol adapter supports
+    Support,
+
+    /// Detect the protocol an endpoint speaks
+    Detect {
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+
+        /// Probe every protocol and cache all that answer
+        #[arg(long)]
+        all: bool,
+
+        /// Run every probe and report what each saw, without using the cache
+        #[arg(long, conflicts_with = "all")]
+        report: bool,
+    },
+
+    /// Run a declarative test suite, or a request file with `expect` checks
+    /// (YAML or JSON)
+    Test {
+        /// Path to the suite or request file
+        #[arg(value_name = "SUITE")]
+        suite: String,
+
+        /// Also write a JUnit XML report to this path
+        #[arg(long, value_name = "PATH")]
+        junit: Option<String>,
+
+        /// Keep going after a failure instead of running the compensation steps
+        #[arg(long)]
+        no_compensate: bool,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+
+    /// Make the calls listed in a request file (YAML or JSON)
+    Run {
+        /// Path to the request file
+        #[arg(value_name = "FILE")]
+        file: String,
+
+        /// Number of requests run at the same time (default: the file's
+        /// `concurrency`, or 1)
+        #[arg(long, value_name = "N")]
+        concurrency: Option<usize>,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+
+    /// Export endpoint operations as a Postman or Insomnia collection
+    Export {
+        /// Collection format
+        #[arg(value_enum, value_name = "FORMAT")]
+        collection_format: CollectionFormat,
+
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+    },
+
+    /// Import requests from another tool as templates
+    Import {
+        /// Source format
+        #[arg(value_enum, value_name = "FORMAT")]
+        import_format: ImportFormat,
+
+        /// Collection file, or the command line for curl/httpie
+        #[arg(value_name = "SOURCE")]
+        source: String,
+
+        /// Save a curl/httpie import as a template with this name
+        #[arg(long, value_name = "NAME")]
+        save: Option<String>,
+
+        /// Overwrite existing templates with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Manage saved call templates
+    Template {
+        #[command(subcommand)]
+        template_command: TemplateCommands,
+    },
+
+    /// Browse an endpoint's operations and call them in a terminal UI
+    Browse {
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+    },
+
+    /// Generate random arguments for an operation from its input schema
+    GenData {
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+
+        /// Operation ID
+        #[arg(value_name = "OPERATION_ID")]
+        operation_id: String,
+
+        /// Number of payloads to generate
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+
+        /// Seed for reproducible output (random when omitted)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Compare the operations of two endpoints, or of one endpoint at
+    /// --as-of and now, and classify the changes as breaking or not
+    Diff {
+        /// Endpoint with the old version of the API
+        #[arg(value_name = "BEFORE")]
+        before: String,
+
+        /// Endpoint with the new version (default: BEFORE, compared with its
+        /// --as-of snapshot)
+        #[arg(value_name = "AFTER")]
+        after: Option<String>,
+    },
+
+    /// Generate a typed client for an endpoint's operations
+    Codegen {
+        /// Language of the client
+        #[arg(long, value_enum)]
+        lang: CodegenLanguage,
+
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+    },
+
+    /// Load-test an operation and report throughput and latency
+    Bench {
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+
+        /// Operation ID
+        #[arg(value_name = "OPERATION_ID")]
+        operation_id: String,
+
+        /// Key-value arguments (e.g., "id=42", "count:=3", "owner.name=Ann")
+        #[arg(short, long)]
+        args: Vec<String>,
+
+        /// JSON input payload, `@file` to read it from a file or `-` from stdin
+        #[arg(long)]
+        json: Option<String>,
+
+        /// Calls in flight at a time
+        #[arg(long, value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// How long to keep calling, e.g. 30s (default: 10s, unless --requests is given)
+        #[arg(long, value_name = "DURATION", value_parser = units::parse_positive_duration)]
+        duration: Option<std::time::Duration>,
+
+        /// Stop after N calls
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        requests: Option<u64>,
+    },
+
+    /// Serve example responses generated from an endpoint's schema
+    Mock {
+        /// Endpoint URL, or an OpenAPI/OpenRPC document or GraphQL
+        /// introspection result (JSON or YAML)
+        #[arg(value_name = "SOURCE")]
+        source: String,
+
+        /// Port to listen on (0 picks a free one)
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+
+        /// Seed for the generated values
+        #[arg(long, default_value_t = mock::DEFAULT_SEED)]
+        seed: u64,
+    },
+
+    /// Expose an endpoint over another protocol
+    Serve {
+        #[command(subcommand)]
+        serve_command: ServeCommands,
+    },
+
+    /// Search call history and report success rates and latency
+    History {
+        #[command(subcommand)]
+        history_command: HistoryCommands,
+    },
+
+    /// Manage endpoint aliases, usable anywhere a URL is accepted
+    Alias {
+        #[command(subcommand)]
+        alias_command: AliasCommands,
+    },
+
+    /// Show and change settings of ~/.uxc/config.toml and .uxc.toml
+    Config {
+        #[command(subcommand)]
+        config_command: ConfigCommands,
+    },
+
+    /// Trust the project's .uxc.toml to use auth profiles and run hooks
+    Project {
+        #[command(subcommand)]
+        project_command: ProjectCommands,
+    },
+
+    /// Manage protocol plugins in ~/.uxc/plugins
+    Plugin {
+        #[command(subcommand)]
+        plugin_command: PluginCommands,
+    },
+
+    /// Keep adapters, schemas and connections warm for later commands
+    Daemon {
+        #[command(subcommand)]
+        daemon_command: DaemonCommands,
+    },
+
+    /// Print a shell completion script (completes operation IDs from the schema cache)
+    Completion {
+        /// Shell to generate the script for
+        #[arg(value_enum, value_name = "SHELL")]
+        shell: CompletionShell,
+    },
+
+    /// Dynamic operation execution: `uxc <url> <operation_id> [--json ...] [--args k=v]`
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+#[derive(Subcommand)]
+pub(crate) enum CacheCommands {
+    /// Show cache statistics
+    Stats,
+
+    /// Clear cache entries
+    Clear {
+        /// Optional URL to clear specific cache entry
+        url: Option<String>,
+
+        /// Clear all cached entries
+        #[arg(long)]
+        all: bool,
+
+        /// Clear only cached detection results, of URL or of every endpoint
+        #[arg(long, conflicts_with = "all")]
+        detection: bool,
+    },
+
+    /// Drop expired entries and evict entries over the configured limits
+    Compact,
+
+    /// Fetch or refresh the schemas of several endpoints in parallel
+    Warm {
+        /// Additional endpoint to warm (repeatable)
+        #[arg(long = "url", value_name = "URL")]
+        urls: Vec<String>,
+
+        /// File listing one endpoint per line (`#` starts a comment)
+        #[arg(short = 'f', long = "file", value_name = "FILE")]
+        file: Option<String>,
+
+        /// Number of endpoints fetched at the same time
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+pub(crate) enum AuthCommands {
+    /// List all authentication profiles
+    List,
+
+    /// Show information about a specific profile
+    Info {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        profile: String,
+    },
+
+    /// Set or update an authentication profile
+    Set {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        profile: String,
+
+        /// API key or token (optional client secret for OAuth profiles)
+        #[arg(long)]
+        api_key: Option<String>,
+
+        /// Authentication type (bearer, api_key, basic, session, oauth)
+        #[arg(short = 't', long, default_value = "bearer")]
+        auth_type: String,
+
+        /// Profile description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Login URL for session auth (the API key is the password)
+        #[arg(long, value_name = "URL")]
+        login_url: Option<String>,
+
+        /// Login name for session auth
+        #[arg(long)]
+        username: Option<String>,
+
+        /// Form field carrying the login name (default: username)
+        #[arg(long, value_name = "FIELD")]
+        username_field: Option<String>,
+
+        /// Form field carrying the password (default: password)
+        #[arg(long, value_name = "FIELD")]
+        password_field: Option<String>,
+
+        /// Login body encoding for session auth (form, json)
+        #[arg(long, value_name = "FORMAT")]
+        login_format: Option<String>,
+
+        /// Authorization endpoint for OAuth auth
+        #[arg(long, value_name = "URL")]
+        authorization_url: Option<String>,
+
+        /// Token endpoint for OAuth auth
+        #[arg(long, value_name = "URL")]
+        token_url: Option<String>,
+
+        /// Client ID for OAuth auth
+        #[arg(long)]
+        client_id: Option<String>,
+
+        /// Scope to request for OAuth auth (repeatable)
+        #[arg(long = "scope", value_name = "SCOPE")]
+        scopes: Vec<String>,
+
+        /// Fixed local port for the OAuth callback (default: any free port)
+        #[arg(long, value_name = "PORT")]
+        redirect_port: Option<u16>,
+
+        /// Header carrying the key for api_key auth (default: X-API-Key)
+        #[arg(long, value_name = "NAME", conflicts_with = "api_key_query")]
+        api_key_header: Option<String>,
+
+        /// Send the key for api_key auth as this query parameter instead
+        #[arg(long, value_name = "NAME")]
+        api_key_query: Option<String>,
+
+        /// Value template for api_key auth, `{key}` is the key (e.g. "Token {key}")
+        #[arg(long, value_name = "TEMPLATE")]
+        api_key_template: Option<String>,
+    },
+
+    /// Log in with a session or OAuth profile and store its cookies or tokens
+    Login {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        profile: String,
+    },
+
+    /// Discard the stored cookies or tokens of a session or OAuth profile
+    Logout {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        profile: String,
+    },
+
+    /// Remove an authentication profile
+    Remove {
+        /// Profile name
+        #[arg(value_name = "PROFILE")]
+        profile: String,
+    },
+
+    /// Export profiles for sharing (API keys are redacted by default)
+    Export {
+        /// Include literal API keys in the export
+        #[arg(long)]
+        include_secrets: bool,
+    },
+
+    /// Import profiles from a TOML or JSON export
+    Import {
+        /// Export file
+        #[arg(value_name = "FILE")]
+        file: String,
+
+        /// How to handle existing profiles with different settings (skip, overwrite, rename)
+        #[arg(long, default_value = "skip")]
+        on_conflict: String,
+
+        /// Validate and report changes without writing profiles
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Accept `cmd:` references, which run their command whenever the profile is used
+        #[arg(long)]
+        allow_commands: bool,
+    },
+}
+
+#[derive(Args)]
+pub(crate) struct HistoryFilterArgs {
+    /// Endpoint glob (e.g., "https://api.example.com*")
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) endpoint: Option<String>,
+
+    /// Operation ID glob (e.g., "query/*")
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) operation: Option<String>,
+
+    /// Only calls newer than this age (e.g., 30m, 24h, 7d)
+    #[arg(long, value_name = "AGE")]
+    pub(crate) since: Option<String>,
+}
+
+impl HistoryFilterArgs {
+    pub(crate) fn filter(&self, status: Option<HistoryStatusArg>) -> Result<HistoryFilter> {
+        let since = match &self.since {
+            Some(age) => {
+                let age = history::parse_age(age)
+                    .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                Some(now.saturating_sub(age))
+            }
+            None => None,
+        };
+        Ok(HistoryFilter {
+            endpoint: self.endpoint.clone(),
+            operation: self.operation.clone(),
+            status: status.map(Into::into),
+            since,
+        })
+    }
+}
+
+#[derive(Subcommand)]
+pub(crate) enum HistoryCommands {
+    /// List recorded calls, most recent first
+    #[command(visible_alias = "list")]
+    Search {
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+
+        /// Only calls with this outcome
+        #[arg(long, value_enum)]
+        status: Option<HistoryStatusArg>,
+
+        /// Maximum number of calls to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+
+    /// Success rate and p50/p95 latency per endpoint and operation
+    Stats {
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+
+        /// Also break each operation down into windows of this length (e.g., 1h, 1d)
+        #[arg(long, value_name = "AGE")]
+        interval: Option<String>,
+    },
+
+    /// Show one recorded call with its arguments
+    Show {
+        /// Call ID, as listed by `uxc history list`
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+
+    /// Run a recorded call again
+    Rerun {
+        /// Call ID, as listed by `uxc history list`
+        #[arg(value_name = "ID")]
+        id: String,
+
+        /// Key-value arguments replacing recorded ones (e.g., "id=42");
+        /// redacted arguments must be given again
+        #[arg(short, long)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum AliasCommands {
+    /// List aliases
+    List,
+
+    /// Save an alias; `--profile`, `--schema-url` and `--protocol` are stored with it
+    Set {
+        /// Alias name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Endpoint URL
+        #[arg(value_name = "URL")]
+        url: String,
+
+        /// Header sent with every HTTP request to the endpoint (repeatable)
+        #[arg(short = 'H', long = "header", value_name = "NAME: VALUE")]
+        headers: Vec<String>,
+
+        /// Rhai script run before every call, changing arguments and headers
+        #[arg(long, value_name = "FILE")]
+        pre_request: Option<std::path::PathBuf>,
+
+        /// Rhai script run after every call, reshaping the result
+        #[arg(long, value_name = "FILE")]
+        post_response: Option<std::path::PathBuf>,
+
+        /// Environment variable the hook scripts may read with `env()` (repeatable)
+        #[arg(long = "hook-env", value_name = "NAME")]
+        hook_env: Vec<String>,
+    },
+
+    /// Remove an alias
+    Remove {
+        /// Alias name
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ProjectCommands {
+    /// Show the project file that applies here and whether it is trusted
+    Status,
+
+    /// Trust the project file as it is now; editing it revokes the trust
+    Trust,
+
+    /// Revoke the trust of the project file
+    Untrust,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum PluginCommands {
+    /// List installed plugins
+    List,
+
+    /// Install a plugin from a directory holding plugin.toml and its library
+    Install {
+        /// Plugin directory or plugin.toml
+        #[arg(value_name = "PATH")]
+        path: std::path::PathBuf,
+
+        /// Replace an installed plugin of the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove an installed plugin
+    Remove {
+        /// Plugin name
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum DaemonCommands {
+    /// Serve endpoint commands from this directory until stopped (Ctrl-C or `uxc daemon stop`)
+    Start {
+        /// Stop after this long without a command (seconds or a duration such as 30m)
+        #[arg(long, value_name = "DURATION", value_parser = units::parse_seconds)]
+        idle_timeout: Option<u64>,
+    },
+
+    /// Show whether a daemon is running and what it keeps warm
+    Status,
+
+    /// Stop the running daemon
+    Stop,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ConfigCommands {
+    /// List every setting with its effective value and where it comes from
+    List,
+
+    /// Show the effective value of one setting
+    Get {
+        /// Setting name, e.g. call.timeout
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+
+    /// Store a setting in ~/.uxc/config.toml
+    Set {
+        /// Setting name, e.g. call.timeout
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// New value; lists are comma-separated
+        #[arg(value_name = "VALUE")]
+        value: String,
+
+        /// Store it in the nearest .uxc.toml instead
+        #[arg(long)]
+        project: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ServeCommands {
+    /// Serve the endpoint's operations as a REST API: GET /ops lists them,
+    /// GET /ops/<operation_id> describes one and POST /ops/<operation_id>
+    /// calls it with the JSON body as arguments
+    Http {
+        /// Endpoint URL
+        #[arg(value_name = "ENDPOINT")]
+        endpoint: String,
+
+        /// Port to listen on (0 picks a free one)
+        #[arg(long, default_value_t = 9000)]
+        port: u16,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+
+        /// Serve Prometheus metrics on this port instead of at /metrics on
+        /// the gateway's own (0 picks a free one)
+        #[arg(long, value_name = "PORT")]
+        metrics_port: Option<u16>,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum TemplateCommands {
+    /// List saved templates
+    List,
+
+    /// Show one template
+    Show {
+        /// Template name
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+
+    /// Execute a template, optionally overriding its arguments
+    Run {
+        /// Template name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Key-value argument overrides (e.g., "id=42")
+        #[arg(short, long)]
+        args: Vec<String>,
+
+        /// JSON argument overrides, `@file` to read them from a file or `-` from stdin
+        #[arg(long)]
+        json: Option<String>,
+    },
+
+    /// Remove a template
+    Remove {
+        /// Template name
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+/// Per-item settings shared by commands that run many calls; each item
+/// runs under the global `--timeout` and `--retries`
+#[derive(Args, Debug, Clone)]
+pub(crate) struct BatchArgs {
+    /// Stop at the first failing item and skip the rest
+    #[arg(long)]
+    pub(crate) fail_fast: bool,
+}
+
+impl BatchArgs {
+    pub(crate) fn options(&self, cli: &Cli) -> Result<BatchOptions> {
+        Ok(BatchOptions {
+            retry: retry_policy(cli)?,
+            fail_fast: self.fail_fast,
+        })
+    }
+}
@@ -0,0 +1,427 @@
+//! `uxc auth`
+
+use anyhow::Result;
+
+use crate::cli::AuthCommands;
+use crate::data::{
+    AuthExportData, AuthImportData, AuthListData, AuthOAuthData, AuthProfileView, AuthRemoveData,
+    AuthSessionData,
+};
+use uxc_core::auth::api_key::ApiKeyPlacement;
+use uxc_core::auth::oauth::{self, OAuthConfig, OAuthTokens};
+use uxc_core::auth::session::{self, CookieJar, SessionConfig};
+use uxc_core::auth::transfer::{self, ConflictStrategy};
+use uxc_core::auth::{AuthType, Profile, Profiles};
+use uxc_core::error::UxcError;
+use uxc_core::output::OutputEnvelope;
+
+pub(crate) async fn handle_auth_command(
+    command: &AuthCommands,
+    output: Option<&str>,
+) -> Result<OutputEnvelope> {
+    match command {
+        AuthCommands::List => {
+            let profiles = Profiles::load_profiles()?;
+            let mut rendered = Vec::new();
+            for name in profiles.profile_names() {
+                let profile = profiles.get_profile(&name)?;
+                rendered.push(to_auth_profile_view(&name, profile));
+            }
+            let data = serde_json::to_value(AuthListData {
+                count: rendered.len(),
+                profiles: rendered,
+            })?;
+            Ok(OutputEnvelope::success(
+                "auth_list",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        AuthCommands::Info { profile } => {
+            let profiles = Profiles::load_profiles()?;
+            let profile_data = profiles.get_profile(profile)?;
+            let data = serde_json::to_value(to_auth_profile_view(profile, profile_data))?;
+            Ok(OutputEnvelope::success(
+                "auth_info",
+                "cli",
+                "uxc",
+                Some(profile),
+                data,
+                None,
+            ))
+        }
+        AuthCommands::Set {
+            profile,
+            api_key,
+            auth_type,
+            description,
+            login_url,
+            username,
+            username_field,
+            password_field,
+            login_format,
+            authorization_url,
+            token_url,
+            client_id,
+            scopes,
+            redirect_port,
+            api_key_header,
+            api_key_query,
+            api_key_template,
+        } => {
+            let auth_type = auth_type
+                .parse::<AuthType>()
+                .map_err(|e| anyhow::anyhow!("Invalid auth type: {}", e))?;
+            let api_key = match api_key {
+                Some(api_key) => api_key.clone(),
+                // Public OAuth clients have no secret.
+                None if auth_type == AuthType::OAuth => String::new(),
+                None => {
+                    return Err(UxcError::InvalidArguments(
+                        "--api-key is required unless --auth-type is oauth".to_string(),
+                    )
+                    .into())
+                }
+            };
+
+            let mut profile_obj = Profile::new(api_key, auth_type.clone());
+            if let Some(desc) = description {
+                profile_obj = profile_obj.with_description(desc.clone());
+            }
+
+            let has_session_flags = login_url.is_some()
+                || username.is_some()
+                || username_field.is_some()
+                || password_field.is_some()
+                || login_format.is_some();
+            if auth_type == AuthType::Session {
+                let (Some(login_url), Some(username)) = (login_url, username) else {
+                    return Err(UxcError::InvalidArguments(
+                        "Session profiles require --login-url and --username".to_string(),
+                    )
+                    .into());
+                };
+                let mut session = SessionConfig::new(login_url.clone(), username.clone());
+                if let Some(field) = username_field {
+                    session.username_field = field.clone();
+                }
+                if let Some(field) = password_field {
+                    session.password_field = field.clone();
+                }
+                if let Some(format) = login_format {
+                    session.format = format
+                        .parse()
+                        .map_err(|e: anyhow::Error| UxcError::InvalidArguments(e.to_string()))?;
+                }
+                profile_obj = profile_obj.with_session(session);
+            } else if has_session_flags {
+                return Err(UxcError::InvalidArguments(
+                    "--login-url, --username, --username-field, --password-field and --login-format require --auth-type session".to_string(),
+                )
+                .into());
+            }
+
+            let has_oauth_flags = authorization_url.is_some()
+                || token_url.is_some()
+                || client_id.is_some()
+                || !scopes.is_empty()
+                || redirect_port.is_some();
+            if auth_type == AuthType::OAuth {
+                let (Some(authorization_url), Some(token_url), Some(client_id)) =
+                    (authorization_url, token_url, client_id)
+                else {
+                    return Err(UxcError::InvalidArguments(
+                        "OAuth profiles require --authorization-url, --token-url and --client-id"
+                            .to_string(),
+                    )
+                    .into());
+                };
+                let mut config = OAuthConfig::new(
+                    authorization_url.clone(),
+                    token_url.clone(),
+                    client_id.clone(),
+                );
+                config.scopes = scopes.clone();
+                config.redirect_port = *redirect_port;
+                profile_obj = profile_obj.with_oauth(config);
+            } else if has_oauth_flags {
+                return Err(UxcError::InvalidArguments(
+                    "--authorization-url, --token-url, --client-id, --scope and --redirect-port require --auth-type oauth".to_string(),
+                )
+                .into());
+            }
+
+            let placement = ApiKeyPlacement {
+                header: api_key_header.clone(),
+                query: api_key_query.clone(),
+                template: api_key_template.clone(),
+            };
+            if placement != ApiKeyPlacement::default() {
+                if auth_type != AuthType::ApiKey {
+                    return Err(UxcError::InvalidArguments(
+                        "--api-key-header, --api-key-query and --api-key-template require --auth-type api_key".to_string(),
+                    )
+                    .into());
+                }
+                profile_obj = profile_obj.with_api_key_placement(placement);
+            }
+
+            let view = to_auth_profile_view(profile, &profile_obj);
+            let mut profiles = Profiles::load_profiles()?;
+            profiles.set_profile(profile.clone(), profile_obj)?;
+            profiles.save_profiles()?;
+            let data = serde_json::to_value(view)?;
+            Ok(OutputEnvelope::success(
+                "auth_set_result",
+                "cli",
+                "uxc",
+                Some(profile),
+                data,
+                None,
+            ))
+        }
+        AuthCommands::Remove { profile } => {
+            let mut profiles = Profiles::load_profiles()?;
+
+            if !profiles.has_profile(profile) {
+                return Err(UxcError::InvalidArguments(format!(
+                    "Profile '{}' not found. Available profiles: {}",
+                    profile,
+                    profiles.list_names()
+                ))
+                .into());
+            }
+
+            profiles.remove_profile(profile)?;
+            profiles.save_profiles()?;
+            let data = serde_json::to_value(AuthRemoveData {
+                profile: profile.clone(),
+            })?;
+            Ok(OutputEnvelope::success(
+                "auth_remove_result",
+                "cli",
+                "uxc",
+                Some(profile),
+                data,
+                None,
+            ))
+        }
+        AuthCommands::Login { profile } => {
+            let profiles = Profiles::load_profiles()?;
+            if profiles.get_profile(profile)?.auth_type == AuthType::OAuth {
+                return oauth_login(&profiles, profile).await;
+            }
+            let profile_data = session_profile(&profiles, profile)?;
+            let jar = session::login(profile_data).await?;
+            let host = profile_data
+                .session
+                .as_ref()
+                .map(|config| config.host())
+                .transpose()?
+                .unwrap_or_default();
+            let data = serde_json::to_value(AuthSessionData {
+                profile: profile.clone(),
+                host,
+                cookies: jar.cookies.into_iter().map(|cookie| cookie.name).collect(),
+                cleared: None,
+            })?;
+            Ok(OutputEnvelope::success(
+                "auth_login_result",
+                "cli",
+                "uxc",
+                Some(profile),
+                data,
+                None,
+            ))
+        }
+        AuthCommands::Logout { profile } => {
+            let profiles = Profiles::load_profiles()?;
+            if profiles.get_profile(profile)?.auth_type == AuthType::OAuth {
+                return oauth_logout(&profiles, profile);
+            }
+            let profile_data = session_profile(&profiles, profile)?;
+            let host = profile_data
+                .session
+                .as_ref()
+                .map(|config| config.host())
+                .transpose()?
+                .unwrap_or_default();
+            let cleared = CookieJar::clear(&host)?;
+            let data = serde_json::to_value(AuthSessionData {
+                profile: profile.clone(),
+                host,
+                cookies: Vec::new(),
+                cleared: Some(cleared),
+            })?;
+            Ok(OutputEnvelope::success(
+                "auth_logout_result",
+                "cli",
+                "uxc",
+                Some(profile),
+                data,
+                None,
+            ))
+        }
+        AuthCommands::Export { include_secrets } => {
+            let profiles = Profiles::load_profiles()?;
+            let exported = transfer::export_profiles(&profiles, *include_secrets);
+            let count = exported.len();
+
+            let data = match output {
+                Some(path) => {
+                    std::fs::write(path, transfer::to_toml(&exported)?).map_err(|e| {
+                        UxcError::GenericError(anyhow::anyhow!(
+                            "Failed to write profiles to {}: {}",
+                            path,
+                            e
+                        ))
+                    })?;
+                    AuthExportData {
+                        count,
+                        redacted: !include_secrets,
+                        path: Some(path.to_string()),
+                        profiles: None,
+                    }
+                }
+                None => AuthExportData {
+                    count,
+                    redacted: !include_secrets,
+                    path: None,
+                    profiles: Some(exported),
+                },
+            };
+            Ok(OutputEnvelope::success(
+                "auth_export",
+                "cli",
+                "uxc",
+                None,
+                serde_json::to_value(data)?,
+                None,
+            ))
+        }
+        AuthCommands::Import {
+            file,
+            on_conflict,
+            dry_run,
+            allow_commands,
+        } => {
+            let strategy = on_conflict
+                .parse::<ConflictStrategy>()
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            let content = std::fs::read_to_string(file).map_err(|e| {
+                UxcError::InvalidArguments(format!("Failed to read {}: {}", file, e))
+            })?;
+            let is_json = std::path::Path::new(file)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            let imported = transfer::parse_import(&content, is_json, *allow_commands)
+                .map_err(|e| UxcError::InvalidArguments(format!("{:#}", e)))?;
+
+            let mut profiles = Profiles::load_profiles()?;
+            let summary = transfer::merge_profiles(&mut profiles, imported, strategy)?;
+            if !dry_run {
+                profiles.save_profiles()?;
+            }
+
+            let data = serde_json::to_value(AuthImportData {
+                file: file.clone(),
+                dry_run: *dry_run,
+                summary,
+            })?;
+            Ok(OutputEnvelope::success(
+                "auth_import_result",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+    }
+}
+
+fn oauth_profile_config<'a>(
+    profiles: &'a Profiles,
+    name: &str,
+) -> Result<(&'a Profile, &'a OAuthConfig)> {
+    let profile = profiles.get_profile(name)?;
+    let config = oauth::oauth_config(profile)
+        .map_err(|err| UxcError::InvalidArguments(format!("Profile '{}': {}", name, err)))?;
+    Ok((profile, config))
+}
+
+async fn oauth_login(profiles: &Profiles, name: &str) -> Result<OutputEnvelope> {
+    let (profile, config) = oauth_profile_config(profiles, name)?;
+    let tokens = oauth::login(profile).await?;
+    let data = serde_json::to_value(AuthOAuthData {
+        profile: name.to_string(),
+        host: session::host_key(&config.token_url)?,
+        expires_at: tokens.expires_at,
+        refreshable: tokens.refresh_token.is_some(),
+        scope: tokens.scope,
+        cleared: None,
+    })?;
+    Ok(OutputEnvelope::success(
+        "auth_oauth_login_result",
+        "cli",
+        "uxc",
+        Some(name),
+        data,
+        None,
+    ))
+}
+
+fn oauth_logout(profiles: &Profiles, name: &str) -> Result<OutputEnvelope> {
+    let (_, config) = oauth_profile_config(profiles, name)?;
+    let cleared = OAuthTokens::clear(&config.token_key()?)?;
+    let data = serde_json::to_value(AuthOAuthData {
+        profile: name.to_string(),
+        host: session::host_key(&config.token_url)?,
+        expires_at: None,
+        refreshable: false,
+        scope: None,
+        cleared: Some(cleared),
+    })?;
+    Ok(OutputEnvelope::success(
+        "auth_oauth_logout_result",
+        "cli",
+        "uxc",
+        Some(name),
+        data,
+        None,
+    ))
+}
+
+fn session_profile<'a>(profiles: &'a Profiles, name: &str) -> Result<&'a Profile> {
+    let profile = profiles.get_profile(name)?;
+    if profile.auth_type != AuthType::Session || profile.session.is_none() {
+        return Err(UxcError::InvalidArguments(format!(
+            "Profile '{}' is not a session profile. Create one with: uxc auth set {} --auth-type session --login-url <url> --username <name> --api-key <password>",
+            name, name
+        ))
+        .into());
+    }
+    Ok(profile)
+}
+
+fn to_auth_profile_view(name: &str, profile: &Profile) -> AuthProfileView {
+    AuthProfileView {
+        name: name.to_string(),
+        auth_type: profile.auth_type.to_string(),
+        api_key_masked: profile.mask_api_key(),
+        description: profile.description.clone(),
+        login_url: profile
+            .session
+            .as_ref()
+            .map(|session| session.login_url.clone()),
+        token_url: profile.oauth.as_ref().map(|oauth| oauth.token_url.clone()),
+        api_key_placement: profile
+            .api_key_placement
+            .as_ref()
+            .map(ApiKeyPlacement::describe),
+    }
+}
@@ -0,0 +1,287 @@
+//! `uxc cache`
+
+use anyhow::Result;
+
+use crate::cli::{CacheCommands, Cli};
+use crate::data::{CacheClearData, CacheCompactData, CacheWarmData, CacheWarmEndpoint};
+use crate::endpoint::{load_auth_profile, prepare_adapter, substitute_variables};
+use crate::next::error_code;
+use crate::normalize_endpoint_url;
+use uxc_core::adapters::{self, Adapter, ProtocolDetector};
+use uxc_core::allowlist;
+use uxc_core::batch::{self, BatchOptions, BatchSummary};
+use uxc_core::cache::{self, CacheConfig};
+use uxc_core::error::UxcError;
+use uxc_core::output::OutputEnvelope;
+
+pub(crate) async fn handle_cache_command(
+    cli: &Cli,
+    command: &CacheCommands,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    if let CacheCommands::Warm {
+        urls,
+        file,
+        concurrency,
+        batch,
+    } = command
+    {
+        return warm_cache(
+            cli,
+            urls,
+            file.as_deref(),
+            *concurrency,
+            &batch.options(cli)?,
+            cache_config,
+        )
+        .await;
+    }
+
+    let cache = cache::create_cache(cache_config.clone())?;
+
+    match command {
+        CacheCommands::Stats => {
+            let stats = cache.stats()?;
+            let data = serde_json::to_value(stats)?;
+            Ok(OutputEnvelope::success(
+                "cache_stats",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        CacheCommands::Clear {
+            url,
+            all,
+            detection,
+        } => {
+            if *detection {
+                let removed = match url {
+                    Some(url) => {
+                        cache.invalidate(&ProtocolDetector::detection_cache_key(url))?;
+                        cache.invalidate(&ProtocolDetector::adapter_cache_key(url))?;
+                        None
+                    }
+                    None => Some(cache.clear_protocol(cache::DETECTION_PROTOCOL)?),
+                };
+                let data = serde_json::to_value(CacheClearData {
+                    scope: "detection".to_string(),
+                    url: url.clone(),
+                    removed,
+                })?;
+                Ok(OutputEnvelope::success(
+                    "cache_clear_result",
+                    "cli",
+                    "uxc",
+                    None,
+                    data,
+                    None,
+                ))
+            } else if *all {
+                cache.clear()?;
+                let data = serde_json::to_value(CacheClearData {
+                    scope: "all".to_string(),
+                    url: None,
+                    removed: None,
+                })?;
+                Ok(OutputEnvelope::success(
+                    "cache_clear_result",
+                    "cli",
+                    "uxc",
+                    None,
+                    data,
+                    None,
+                ))
+            } else if let Some(url) = url {
+                cache.invalidate(url)?;
+                cache.invalidate(&adapters::mcp::McpAdapter::tools_cache_key(url))?;
+                cache.invalidate(&ProtocolDetector::detection_cache_key(url))?;
+                cache.invalidate(&ProtocolDetector::adapter_cache_key(url))?;
+                let data = serde_json::to_value(CacheClearData {
+                    scope: "url".to_string(),
+                    url: Some(url.clone()),
+                    removed: None,
+                })?;
+                Ok(OutputEnvelope::success(
+                    "cache_clear_result",
+                    "cli",
+                    "uxc",
+                    None,
+                    data,
+                    None,
+                ))
+            } else {
+                Err(UxcError::InvalidArguments(
+                    "Usage: uxc cache clear <url> OR uxc cache clear --all OR uxc cache clear --detection [url]"
+                        .to_string(),
+                )
+                .into())
+            }
+        }
+        CacheCommands::Compact => {
+            let removed = cache.compact()?;
+            let data = serde_json::to_value(CacheCompactData { removed })?;
+            Ok(OutputEnvelope::success(
+                "cache_compact_result",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        CacheCommands::Warm { .. } => unreachable!("handled above"),
+    }
+}
+
+/// Endpoints to warm: the URL argument, `--url` values, then the lines of `file`.
+fn warm_endpoints(url: Option<&str>, urls: &[String], file: Option<&str>) -> Result<Vec<String>> {
+    let mut endpoints: Vec<String> = url.into_iter().map(ToString::to_string).collect();
+    endpoints.extend_from_slice(urls);
+    if let Some(path) = file {
+        let contents = std::fs::read_to_string(path).map_err(|err| {
+            UxcError::InvalidArguments(format!("Failed to read endpoint file {}: {}", path, err))
+        })?;
+        endpoints.extend(
+            contents
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim())
+                .filter(|line| !line.is_empty())
+                .map(ToString::to_string),
+        );
+    }
+    if endpoints.is_empty() {
+        return Err(UxcError::InvalidArguments(
+            "Usage: uxc cache warm <url> [--url <url>]... OR uxc cache warm --file <endpoints.txt>"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    endpoints
+        .into_iter()
+        .map(|endpoint| Ok(normalize_endpoint_url(&substitute_variables(&endpoint)?)))
+        .filter(|endpoint| match endpoint {
+            Ok(url) => seen.insert(url.clone()),
+            Err(_) => true,
+        })
+        .collect()
+}
+
+async fn warm_cache(
+    cli: &Cli,
+    urls: &[String],
+    file: Option<&str>,
+    concurrency: usize,
+    options: &BatchOptions,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    use futures::stream::{self, StreamExt};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    if !cache_config.enabled {
+        return Err(UxcError::InvalidArguments(
+            "cache warm cannot be combined with --no-cache".to_string(),
+        )
+        .into());
+    }
+    if cache::is_offline() {
+        return Err(cache::offline_miss("cache warm needs network access"));
+    }
+
+    let endpoints = warm_endpoints(cli.url.as_deref(), urls, file)?;
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let total = endpoints.len();
+    let done = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+
+    let results: Vec<CacheWarmEndpoint> = stream::iter(endpoints)
+        .map(|url| {
+            let (cache_config, auth_profile) = (&cache_config, &auth_profile);
+            let (done, stop) = (&done, &stop);
+            async move {
+                let n = || done.fetch_add(1, Ordering::Relaxed) + 1;
+                if stop.load(Ordering::Relaxed) {
+                    eprintln!("[{}/{}] {} skipped", n(), total, url);
+                    return CacheWarmEndpoint {
+                        url,
+                        ok: false,
+                        protocol: None,
+                        duration_ms: 0,
+                        attempts: 0,
+                        timed_out: false,
+                        skipped: true,
+                        error: None,
+                    };
+                }
+
+                let attempted = batch::run(
+                    options,
+                    |err| error_code(err).to_string(),
+                    || async {
+                        allowlist::enforce(&url, None)?;
+                        let adapter =
+                            prepare_adapter(cli, &url, cache_config.clone(), auth_profile.clone())
+                                .await?;
+                        adapter.fetch_schema(&url).await?;
+                        Ok::<_, anyhow::Error>(adapter.protocol_type().as_str().to_string())
+                    },
+                )
+                .await;
+
+                let timed_out = attempted.timed_out();
+                let (protocol, error) = match attempted.result {
+                    Ok(protocol) => {
+                        eprintln!("[{}/{}] {} ok ({})", n(), total, url, protocol);
+                        (Some(protocol), None)
+                    }
+                    Err(error) => {
+                        if options.fail_fast {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                        eprintln!("[{}/{}] {} failed: {}", n(), total, url, error.message);
+                        (None, Some(error))
+                    }
+                };
+                CacheWarmEndpoint {
+                    url,
+                    ok: error.is_none(),
+                    protocol,
+                    duration_ms: attempted.duration_ms,
+                    attempts: attempted.attempts,
+                    timed_out,
+                    skipped: false,
+                    error,
+                }
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let summary = BatchSummary {
+        total: results.len(),
+        succeeded: results.iter().filter(|endpoint| endpoint.ok).count(),
+        failed: results
+            .iter()
+            .filter(|endpoint| !endpoint.ok && !endpoint.skipped)
+            .count(),
+        timed_out: results.iter().filter(|endpoint| endpoint.timed_out).count(),
+        skipped: results.iter().filter(|endpoint| endpoint.skipped).count(),
+    };
+    let data = serde_json::to_value(CacheWarmData {
+        endpoints: results,
+        summary,
+    })?;
+    Ok(OutputEnvelope::success(
+        "cache_warm_result",
+        "cli",
+        "uxc",
+        None,
+        data,
+        None,
+    ))
+}
@@ -0,0 +1,265 @@
+//! `uxc config`, `uxc alias`, `uxc project` and `uxc plugin`
+
+use anyhow::Result;
+use clap::CommandFactory;
+use serde_json::{json, Value};
+
+use crate::cli::{AliasCommands, Cli, ConfigCommands, PluginCommands, ProjectCommands};
+use crate::data::{AliasListData, AliasRemoveData, AliasView, ConfigListData, PluginListData};
+use crate::normalize_endpoint_url;
+use uxc_core::alias::{Alias, AliasStore};
+use uxc_core::config::{self, Setting};
+use uxc_core::error::UxcError;
+use uxc_core::hooks::HookConfig;
+use uxc_core::output::OutputEnvelope;
+use uxc_core::plugins;
+use uxc_core::project::{self, ProjectConfig, TrustStore};
+
+pub(crate) fn handle_config_command(command: &ConfigCommands) -> Result<OutputEnvelope> {
+    let invalid = |e: anyhow::Error| UxcError::InvalidArguments(format!("{:#}", e));
+    match command {
+        ConfigCommands::List => {
+            let data = serde_json::to_value(ConfigListData {
+                settings: config::list()?,
+            })?;
+            Ok(OutputEnvelope::success(
+                "config_list",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        ConfigCommands::Get { key } => {
+            config::key(key).map_err(invalid)?;
+            let data = serde_json::to_value(config::get(key)?)?;
+            Ok(OutputEnvelope::success(
+                "config_value",
+                "cli",
+                "uxc",
+                Some(key),
+                data,
+                None,
+            ))
+        }
+        ConfigCommands::Set {
+            key,
+            value,
+            project,
+        } => {
+            let setting = config::set(key, value, *project).map_err(invalid)?;
+            let data = serde_json::to_value(setting)?;
+            Ok(OutputEnvelope::success(
+                "config_set",
+                "cli",
+                "uxc",
+                Some(key),
+                data,
+                None,
+            ))
+        }
+    }
+}
+
+/// A setting's value as written in config files
+pub(crate) fn setting_text(setting: &Setting) -> String {
+    match &setting.value {
+        None => "(unset)".to_string(),
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), str::to_string)
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        Some(value) => value.to_string(),
+    }
+}
+
+pub(crate) fn handle_alias_command(cli: &Cli, command: &AliasCommands) -> Result<OutputEnvelope> {
+    match command {
+        AliasCommands::List => {
+            let store = AliasStore::load()?;
+            let views = store
+                .aliases
+                .into_iter()
+                .map(|(name, alias)| AliasView { name, alias })
+                .collect::<Vec<_>>();
+            let data = serde_json::to_value(AliasListData {
+                count: views.len(),
+                aliases: views,
+            })?;
+            Ok(OutputEnvelope::success(
+                "alias_list",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        AliasCommands::Set {
+            name,
+            url,
+            headers,
+            pre_request,
+            post_response,
+            hook_env,
+        } => {
+            // A subcommand name would never reach the alias
+            if Cli::command()
+                .get_subcommands()
+                .any(|command| command.get_name() == name)
+            {
+                return Err(UxcError::InvalidArguments(format!(
+                    "'{}' is a uxc command and cannot be an alias",
+                    name
+                ))
+                .into());
+            }
+            let mut alias = Alias::new(normalize_endpoint_url(url));
+            alias.profile = cli.profile.clone();
+            alias.schema_url = cli.schema_url.clone();
+            alias.protocol = cli.protocol.map(|protocol| protocol.as_str().to_string());
+            for header in headers {
+                let (header_name, value) = header
+                    .split_once(':')
+                    .map(|(name, value)| (name.trim(), value.trim()))
+                    .filter(|(name, _)| !name.is_empty())
+                    .ok_or_else(|| {
+                        UxcError::InvalidArguments(format!(
+                            "Invalid header '{}', expected 'NAME: VALUE'",
+                            header
+                        ))
+                    })?;
+                alias
+                    .headers
+                    .insert(header_name.to_string(), value.to_string());
+            }
+            // Stored absolute, as the config file is elsewhere
+            let absolute = |path: &std::path::PathBuf| {
+                std::path::absolute(path).map_err(|e| {
+                    UxcError::InvalidArguments(format!("Invalid hook path {:?}: {}", path, e))
+                })
+            };
+            alias.hooks = HookConfig {
+                pre_request: pre_request.as_ref().map(absolute).transpose()?,
+                post_response: post_response.as_ref().map(absolute).transpose()?,
+                env: hook_env.clone(),
+            };
+
+            let mut store = AliasStore::load()?;
+            store
+                .insert(name.clone(), alias.clone())
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            store.save()?;
+            let data = serde_json::to_value(AliasView {
+                name: name.clone(),
+                alias,
+            })?;
+            Ok(OutputEnvelope::success(
+                "alias_set",
+                "cli",
+                "uxc",
+                Some(name),
+                data,
+                None,
+            ))
+        }
+        AliasCommands::Remove { name } => {
+            let mut store = AliasStore::load()?;
+            store
+                .remove(name)
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            store.save()?;
+            let data = serde_json::to_value(AliasRemoveData { name: name.clone() })?;
+            Ok(OutputEnvelope::success(
+                "alias_remove_result",
+                "cli",
+                "uxc",
+                Some(name),
+                data,
+                None,
+            ))
+        }
+    }
+}
+
+pub(crate) fn handle_project_command(command: &ProjectCommands) -> Result<OutputEnvelope> {
+    let project = ProjectConfig::current()?.ok_or_else(|| {
+        UxcError::InvalidArguments(format!(
+            "No {} in this directory or its parents",
+            project::PROJECT_FILE
+        ))
+    })?;
+    let mut store = TrustStore::load()?;
+    let (kind, trusted) = match command {
+        ProjectCommands::Status => ("project_status", store.is_trusted(project)),
+        ProjectCommands::Trust => {
+            store.trust(project);
+            store.save()?;
+            ("project_trusted", true)
+        }
+        ProjectCommands::Untrust => {
+            store.untrust(&project.path);
+            store.save()?;
+            ("project_untrusted", false)
+        }
+    };
+    Ok(OutputEnvelope::success(
+        kind,
+        "cli",
+        "uxc",
+        None,
+        json!({
+            "path": project.path,
+            "digest": project.digest,
+            "trusted": trusted,
+        }),
+        None,
+    ))
+}
+
+pub(crate) fn handle_plugin_command(command: &PluginCommands) -> Result<OutputEnvelope> {
+    let (kind, operation, data) = match command {
+        PluginCommands::List => {
+            let plugins = plugins::list()?;
+            let data = serde_json::to_value(PluginListData {
+                count: plugins.len(),
+                plugins,
+            })?;
+            ("plugin_list", None, data)
+        }
+        PluginCommands::Install { path, force } => {
+            let plugin = plugins::install(path, *force)
+                .map_err(|e| UxcError::InvalidArguments(format!("{:#}", e)))?;
+            let name = plugin.manifest.name.clone();
+            (
+                "plugin_installed",
+                Some(name),
+                serde_json::to_value(plugin)?,
+            )
+        }
+        PluginCommands::Remove { name } => {
+            let plugin =
+                plugins::remove(name).map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            (
+                "plugin_removed",
+                Some(name.clone()),
+                serde_json::to_value(plugin)?,
+            )
+        }
+    };
+    Ok(OutputEnvelope::success(
+        kind,
+        "cli",
+        "uxc",
+        operation.as_deref(),
+        data,
+        None,
+    ))
+}
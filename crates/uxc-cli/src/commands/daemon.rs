@@ -0,0 +1,82 @@
+//! `uxc daemon` and the commands it runs for clients
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::cli::{Cli, DaemonCommands};
+use crate::data::{DaemonStatusData, DaemonStopData};
+use crate::settings::{apply_alias, apply_project_config, apply_rerun};
+use crate::{daemon, error_envelope, error_next, execute_cli};
+use uxc_core::env_file;
+use uxc_core::explain;
+use uxc_core::http;
+use uxc_core::middleware;
+use uxc_core::output::OutputEnvelope;
+
+pub(crate) async fn handle_daemon_command(command: &DaemonCommands) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let (kind, data) = match command {
+        DaemonCommands::Start { idle_timeout } => {
+            let idle = idle_timeout.map(std::time::Duration::from_secs);
+            let status = daemon::serve(run_for_client, idle, |socket| {
+                eprintln!("uxc daemon listening on {}", socket);
+                eprintln!("Press Ctrl-C to stop");
+            })
+            .await?;
+            ("daemon_session", serde_json::to_value(status)?)
+        }
+        DaemonCommands::Status => {
+            let status = match daemon::send(&daemon::Request::Status).await? {
+                Some(daemon::Reply::Status { status }) => Some(status),
+                _ => None,
+            };
+            let data = DaemonStatusData {
+                running: status.is_some(),
+                socket: daemon::socket(),
+                status,
+            };
+            ("daemon_status", serde_json::to_value(data)?)
+        }
+        DaemonCommands::Stop => {
+            let stopped = daemon::send(&daemon::Request::Stop).await?.is_some();
+            let data = DaemonStopData {
+                stopped,
+                socket: daemon::socket(),
+            };
+            ("daemon_stop", serde_json::to_value(data)?)
+        }
+    };
+    Ok(OutputEnvelope::success(
+        kind,
+        "cli",
+        "uxc",
+        None,
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+/// Run a command line handed over by a client, for `uxc daemon`. Failures
+/// become error envelopes, as the client would have printed them.
+async fn run_for_client(args: Vec<String>) -> OutputEnvelope {
+    // Nothing the previous command set up carries over: `.env` entries,
+    // hooks, default headers and the proxy are set up again for this one
+    env_file::clear();
+    middleware::uninstall_all();
+    http::reset();
+    let result = async {
+        let mut cli = Cli::try_parse_from(args)?;
+        explain::set_enabled(cli.explain);
+        apply_rerun(&mut cli)?;
+        apply_project_config(&mut cli)?;
+        apply_alias(&mut cli)?;
+        execute_cli(&cli).await
+    }
+    .await;
+    let mut envelope = match result {
+        Ok(envelope) => envelope,
+        Err(err) => error_envelope(&err).with_next(error_next(&err)),
+    };
+    envelope.meta.explain = explain::take();
+    envelope
+}
@@ -0,0 +1,76 @@
+//! `uxc history`
+
+use anyhow::Result;
+
+use crate::cli::HistoryCommands;
+use crate::data::{HistorySearchData, HistoryStatsData};
+use uxc_core::error::UxcError;
+use uxc_core::history::{self, HistoryStore};
+use uxc_core::output::OutputEnvelope;
+
+pub(crate) fn handle_history_command(command: &HistoryCommands) -> Result<OutputEnvelope> {
+    let store = HistoryStore::open()?;
+    match command {
+        HistoryCommands::Search {
+            filter,
+            status,
+            limit,
+        } => {
+            let entries = store.search(&filter.filter(*status)?, *limit)?;
+            let data = serde_json::to_value(HistorySearchData {
+                count: entries.len(),
+                entries,
+            })?;
+            Ok(OutputEnvelope::success(
+                "history_search",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        HistoryCommands::Stats { filter, interval } => {
+            let interval = interval
+                .as_deref()
+                .map(history::parse_age)
+                .transpose()
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            if interval == Some(0) {
+                return Err(UxcError::InvalidArguments(
+                    "--interval must be longer than zero".to_string(),
+                )
+                .into());
+            }
+            let operations = store.stats(&filter.filter(None)?, interval)?;
+            let data = serde_json::to_value(HistoryStatsData {
+                operations,
+                interval,
+            })?;
+            Ok(OutputEnvelope::success(
+                "history_stats",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        HistoryCommands::Show { id } => {
+            let entry = history::find(id)?.ok_or_else(|| {
+                UxcError::InvalidArguments(format!("No call with ID '{}' in history", id))
+            })?;
+            Ok(OutputEnvelope::success(
+                "history_entry",
+                "cli",
+                "uxc",
+                None,
+                serde_json::to_value(entry)?,
+                None,
+            ))
+        }
+        HistoryCommands::Rerun { .. } => {
+            unreachable!("history rerun is turned into a call by apply_rerun")
+        }
+    }
+}
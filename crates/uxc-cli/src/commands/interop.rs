@@ -0,0 +1,282 @@
+//! `uxc export` and `uxc import`
+
+use anyhow::Result;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::cli::{Cli, CollectionFormat, ImportFormat};
+use crate::data::{CollectionExportData, ImportResultData, RequestImportData};
+use crate::endpoint::{load_auth_profile, prepare_adapter};
+use crate::normalize_endpoint_url;
+use uxc_core::adapters::{Adapter, ProtocolType};
+use uxc_core::cache::CacheConfig;
+use uxc_core::error::UxcError;
+use uxc_core::output::OutputEnvelope;
+use uxc_core::templates::{self, Template, TemplateStore};
+
+pub(crate) async fn handle_export_command(
+    cli: &Cli,
+    format: CollectionFormat,
+    endpoint: &str,
+    output: Option<&str>,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let url = normalize_endpoint_url(endpoint);
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let auth_type = auth_profile
+        .as_ref()
+        .map(|profile| profile.auth_type.clone())
+        .unwrap_or_default();
+
+    let adapter = prepare_adapter(cli, &url, cache_config, auth_profile).await?;
+    let protocol = adapter.protocol_type().as_str();
+    let operations = adapter.list_operations(&url).await?;
+    let requests = uxc_core::interop::export_requests(protocol, &operations)?;
+
+    let name = url::Url::parse(&url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(ToString::to_string))
+        .unwrap_or_else(|| url.clone());
+    let (format_name, collection) = match format {
+        CollectionFormat::Postman => (
+            "postman",
+            uxc_core::interop::postman::export_collection(&name, &url, &requests, &auth_type),
+        ),
+        CollectionFormat::Insomnia => (
+            "insomnia",
+            uxc_core::interop::insomnia::export_collection(&name, &url, &requests, &auth_type),
+        ),
+    };
+
+    let data = if let Some(path) = output {
+        std::fs::write(path, serde_json::to_string_pretty(&collection)?).map_err(|e| {
+            UxcError::GenericError(anyhow::anyhow!(
+                "Failed to write collection to {}: {}",
+                path,
+                e
+            ))
+        })?;
+        CollectionExportData {
+            format: format_name.to_string(),
+            count: requests.len(),
+            path: Some(path.to_string()),
+            collection: None,
+        }
+    } else {
+        CollectionExportData {
+            format: format_name.to_string(),
+            count: requests.len(),
+            path: None,
+            collection: Some(collection),
+        }
+    };
+
+    Ok(OutputEnvelope::success(
+        "collection_export",
+        protocol,
+        &url,
+        None,
+        serde_json::to_value(data)?,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+pub(crate) fn handle_import_command(
+    format: ImportFormat,
+    source: &str,
+    force: bool,
+) -> Result<OutputEnvelope> {
+    let content = std::fs::read_to_string(source)
+        .map_err(|e| UxcError::InvalidArguments(format!("Failed to read {}: {}", source, e)))?;
+    let document: Value = serde_json::from_str(&content)
+        .map_err(|e| UxcError::InvalidArguments(format!("Invalid JSON in {}: {}", source, e)))?;
+
+    let (format_name, imported) = match format {
+        ImportFormat::Postman => (
+            "postman",
+            uxc_core::interop::postman::import_collection(&document),
+        ),
+        ImportFormat::Insomnia => (
+            "insomnia",
+            uxc_core::interop::insomnia::import_collection(&document),
+        ),
+        ImportFormat::Curl | ImportFormat::Httpie => {
+            return Err(UxcError::InvalidArguments(
+                "Internal routing error for command import".to_string(),
+            )
+            .into())
+        }
+    };
+    let imported = imported.map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+
+    let mut store = TemplateStore::load()?;
+    let (saved, skipped) = save_templates(&mut store, imported, force)?;
+    store.save()?;
+
+    let data = serde_json::to_value(ImportResultData {
+        format: format_name.to_string(),
+        source: source.to_string(),
+        imported: saved,
+        skipped,
+    })?;
+    Ok(OutputEnvelope::success(
+        "import_result",
+        "cli",
+        "uxc",
+        None,
+        data,
+        None,
+    ))
+}
+
+/// Import a single curl or HTTPie command line.
+pub(crate) async fn handle_command_import(
+    cli: &Cli,
+    format: ImportFormat,
+    command: &str,
+    save: Option<&str>,
+    force: bool,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let (format_name, request) = match format {
+        ImportFormat::Curl => ("curl", uxc_core::interop::curl::parse_command(command)),
+        ImportFormat::Httpie => ("httpie", uxc_core::interop::httpie::parse_command(command)),
+        ImportFormat::Postman | ImportFormat::Insomnia => {
+            return Err(UxcError::InvalidArguments(
+                "Internal routing error for collection import".to_string(),
+            )
+            .into())
+        }
+    };
+    let request = request.map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+
+    let mut template =
+        uxc_core::interop::to_template(&request, &serde_json::Map::new(), format_name);
+    let matched = match_template_operation(cli, &mut template, cache_config).await;
+
+    let saved_as = match save {
+        Some(name) => {
+            let mut store = TemplateStore::load()?;
+            if store.contains(name) && !force {
+                return Err(UxcError::InvalidArguments(format!(
+                    "Template '{}' already exists. Use --force to overwrite",
+                    name
+                ))
+                .into());
+            }
+            store
+                .insert(name.to_string(), template.clone())
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            store.save()?;
+            Some(name.to_string())
+        }
+        None => None,
+    };
+
+    let data = serde_json::to_value(RequestImportData {
+        format: format_name.to_string(),
+        template,
+        matched,
+        saved_as,
+    })?;
+    Ok(OutputEnvelope::success(
+        "request_import",
+        "cli",
+        "uxc",
+        None,
+        data,
+        None,
+    ))
+}
+
+/// Resolve an imported `method:/path` operation against the endpoint schema.
+///
+/// Each path prefix is tried as the endpoint, shortest first, until an
+/// OpenAPI endpoint has an operation matching the remaining path. Concrete
+/// path segments become arguments. Returns whether a match was found; probe
+/// failures are not errors since the import is still usable unmatched.
+async fn match_template_operation(
+    cli: &Cli,
+    template: &mut Template,
+    cache_config: CacheConfig,
+) -> bool {
+    let Some((method, path)) = template.operation.split_once(':') else {
+        return false;
+    };
+    if !path.starts_with('/') {
+        return false;
+    }
+    let (method, path) = (method.to_string(), path.to_string());
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    for split in 0..segments.len() {
+        let mut endpoint = template.endpoint.clone();
+        for segment in &segments[..split] {
+            endpoint.push('/');
+            endpoint.push_str(segment);
+        }
+        let remaining = format!("/{}", segments[split..].join("/"));
+
+        let adapter = match prepare_adapter(cli, &endpoint, cache_config.clone(), None).await {
+            Ok(adapter) if adapter.protocol_type() == ProtocolType::OpenAPI => adapter,
+            Ok(_) => continue,
+            Err(e) => {
+                debug!("No schema at {}: {}", endpoint, e);
+                continue;
+            }
+        };
+        let Ok(operations) = adapter.list_operations(&endpoint).await else {
+            continue;
+        };
+
+        if let Some((operation, path_args)) =
+            uxc_core::interop::match_operation(&method, &remaining, &operations)
+        {
+            template.endpoint = endpoint;
+            template.operation = operation;
+            for (key, value) in path_args {
+                template.args.entry(key).or_insert(value);
+            }
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Store imported templates under slugified names.
+///
+/// Names repeated within one import get a numeric suffix; names that already
+/// exist in the store are skipped unless `force` is set.
+fn save_templates(
+    store: &mut TemplateStore,
+    imported: Vec<(String, Template)>,
+    force: bool,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut saved: Vec<String> = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (label, template) in imported {
+        let base = templates::slugify(&label);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while saved.contains(&name) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        if store.contains(&name) && !force {
+            skipped.push(name);
+            continue;
+        }
+
+        store.insert(name.clone(), template)?;
+        saved.push(name);
+    }
+
+    Ok((saved, skipped))
+}
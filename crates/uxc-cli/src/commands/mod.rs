@@ -0,0 +1,13 @@
+//! Handlers of the subcommands other than endpoint commands (`uxc <url> ...`)
+
+pub(crate) mod auth;
+pub(crate) mod cache;
+pub(crate) mod config;
+pub(crate) mod daemon;
+pub(crate) mod history;
+pub(crate) mod interop;
+pub(crate) mod run;
+pub(crate) mod serve;
+pub(crate) mod suite;
+pub(crate) mod template;
+pub(crate) mod tools;
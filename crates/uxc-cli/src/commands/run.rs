@@ -0,0 +1,270 @@
+//! `uxc run <file>`: request files
+
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::cli::Cli;
+use crate::endpoint::{
+    load_auth_profile, prepare_adapter, substitute_arguments, substitute_variables,
+};
+use crate::{error_envelope, normalize_endpoint_url};
+use uxc_core::adapters::{self, Adapter};
+use uxc_core::batch::BatchOptions;
+use uxc_core::cache::CacheConfig;
+use uxc_core::coerce;
+use uxc_core::env_file;
+use uxc_core::error::UxcError;
+use uxc_core::explain;
+use uxc_core::output::OutputEnvelope;
+use uxc_core::request_file::{self, RequestFile, RequestReport, RunReport};
+use uxc_core::retry::{self, RetryPolicy};
+
+pub(crate) async fn handle_run_command(
+    cli: &Cli,
+    path: &str,
+    concurrency: Option<usize>,
+    options: &BatchOptions,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let report = run_request_file(cli, path, concurrency, options, cache_config).await?;
+    let duration_ms = report.duration_ms;
+    Ok(OutputEnvelope::success(
+        "run_report",
+        "cli",
+        "uxc",
+        None,
+        serde_json::to_value(report)?,
+        Some(duration_ms),
+    ))
+}
+
+pub(crate) async fn run_request_file(
+    cli: &Cli,
+    path: &str,
+    concurrency: Option<usize>,
+    options: &BatchOptions,
+    cache_config: CacheConfig,
+) -> Result<RunReport> {
+    use futures::stream::{self, StreamExt};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let request_file = RequestFile::load(std::path::Path::new(path))?;
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let name = request_file
+        .name
+        .clone()
+        .unwrap_or_else(|| "requests".to_string());
+    let start = std::time::Instant::now();
+
+    // Endpoints, variables and expected outputs are checked before anything is sent
+    let mut planned = Vec::new();
+    for request in &request_file.requests {
+        let url =
+            normalize_endpoint_url(&substitute_variables(&request_file.endpoint_for(request)?)?);
+        let args: serde_json::Map<String, Value> =
+            substitute_arguments(request.args.clone().into_iter().collect())?
+                .into_iter()
+                .collect();
+        let for_each = request
+            .for_each
+            .as_ref()
+            .map(env_file::substitute_value)
+            .transpose()?;
+        let condition = request
+            .condition
+            .as_deref()
+            .map(substitute_variables)
+            .transpose()?;
+        let expected = request_file.expected_for(request)?;
+        planned.push((request, url, args, for_each, condition, expected));
+    }
+
+    let mut adapters_by_url: HashMap<
+        String,
+        std::result::Result<std::sync::Arc<adapters::AdapterEnum>, OutputEnvelope>,
+    > = HashMap::new();
+    for (_, url, ..) in &planned {
+        if adapters_by_url.contains_key(url) {
+            continue;
+        }
+        let prepared = retry::run(&options.retry, || {
+            prepare_adapter(cli, url, cache_config.clone(), auth_profile.clone())
+        })
+        .await;
+        adapters_by_url.insert(
+            url.clone(),
+            prepared.result.map_err(|err| error_envelope(&err)),
+        );
+    }
+
+    let mut concurrency = concurrency.or(request_file.concurrency).unwrap_or(1).max(1);
+    if concurrency > 1 && request_file.requests.iter().any(|r| r.refers_to_steps()) {
+        explain::record(
+            "run",
+            "requests refer to each other: they run one after the other",
+        );
+        concurrency = 1;
+    }
+    let scope = std::cell::RefCell::new(request_file::Scope::default());
+    let stop = AtomicBool::new(false);
+    let reports: Vec<Vec<RequestReport>> = stream::iter(planned)
+        .map(|(request, url, args, for_each, condition, expected)| {
+            let (adapters_by_url, scope, stop) = (&adapters_by_url, &scope, &stop);
+            async move {
+                if stop.load(Ordering::Relaxed) {
+                    return vec![RequestReport::skipped(request, &url)];
+                }
+                let policy = RetryPolicy {
+                    retries: request.retries.unwrap_or(options.retry.retries),
+                    ..options.retry.clone()
+                };
+                let current = scope.borrow().clone();
+                let iterations = match &for_each {
+                    Some(for_each) => current
+                        .items(for_each)
+                        .map(|items| items.into_iter().enumerate().map(Some).collect()),
+                    None => Ok(vec![None]),
+                };
+                let iterations: Vec<Option<(usize, Value)>> = match iterations {
+                    Ok(iterations) => iterations,
+                    Err(message) => {
+                        let err = UxcError::InvalidArguments(message).into();
+                        stop.store(options.fail_fast, Ordering::Relaxed);
+                        return vec![RequestReport::new(
+                            request,
+                            &url,
+                            error_envelope(&err),
+                            None,
+                            None,
+                        )];
+                    }
+                };
+
+                let mut reports = Vec::new();
+                for iteration in iterations {
+                    let (item_scope, name) = match iteration {
+                        Some((index, item)) => (
+                            current.with_item(index, item),
+                            format!("{}[{}]", request.name(), index),
+                        ),
+                        None => (current.clone(), request.name().to_string()),
+                    };
+                    let resolved = condition
+                        .as_deref()
+                        .map_or(Ok(true), |condition| item_scope.condition(condition))
+                        .and_then(|run| {
+                            run.then(|| item_scope.resolve(&Value::Object(args.clone())))
+                                .transpose()
+                        });
+                    let mut report = match resolved {
+                        Ok(Some(args)) => {
+                            let args = args.as_object().cloned().unwrap_or_default();
+                            let started = std::time::Instant::now();
+                            let (envelope, status, attempts) = request_file_call(
+                                &adapters_by_url[&url],
+                                &url,
+                                &request.operation,
+                                args.into_iter().collect(),
+                                &policy,
+                            )
+                            .await;
+                            let mut report = RequestReport::new(
+                                request,
+                                &url,
+                                envelope,
+                                status,
+                                expected.as_ref(),
+                            );
+                            report.duration_ms = started.elapsed().as_millis() as u64;
+                            report.attempts = attempts;
+                            report
+                        }
+                        // The condition does not hold
+                        Ok(None) => RequestReport::skipped(request, &url),
+                        Err(message) => {
+                            let err = UxcError::InvalidArguments(message).into();
+                            RequestReport::new(request, &url, error_envelope(&err), None, None)
+                        }
+                    };
+                    report.name = name;
+                    reports.push(report);
+                }
+
+                let ran: Vec<&RequestReport> =
+                    reports.iter().filter(|report| !report.skipped).collect();
+                let outcome = |report: &RequestReport| {
+                    let envelope = report.envelope.as_ref();
+                    serde_json::json!({
+                        "ok": report.ok,
+                        "data": envelope.and_then(|e| e.data.clone()),
+                        "error": envelope.and_then(|e| e.error.clone()),
+                    })
+                };
+                if for_each.is_some() {
+                    let outcomes: Vec<Value> = ran.iter().map(|report| outcome(report)).collect();
+                    scope.borrow_mut().record(
+                        request.name(),
+                        serde_json::json!({
+                            "ok": ran.iter().all(|report| report.ok),
+                            "data": outcomes.iter().map(|o| o["data"].clone()).collect::<Vec<_>>(),
+                            "error": outcomes.iter().map(|o| o["error"].clone()).find(|e| !e.is_null()),
+                        }),
+                    );
+                } else if let Some(report) = ran.first() {
+                    scope.borrow_mut().record(request.name(), outcome(report));
+                }
+                if options.fail_fast && ran.iter().any(|report| !report.ok) {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                reports
+            }
+        })
+        .buffered(concurrency)
+        .collect()
+        .await;
+    let reports = reports.into_iter().flatten().collect();
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    Ok(RunReport::new(&name, reports, duration_ms))
+}
+
+/// Make one call of a request file. Failures are reported as error envelopes,
+/// alongside the HTTP status of the response.
+async fn request_file_call(
+    prepared: &std::result::Result<std::sync::Arc<adapters::AdapterEnum>, OutputEnvelope>,
+    url: &str,
+    operation: &str,
+    args: HashMap<String, Value>,
+    policy: &RetryPolicy,
+) -> (OutputEnvelope, Option<u16>, u32) {
+    let adapter = match prepared {
+        Ok(adapter) => adapter,
+        Err(envelope) => return (envelope.clone(), None, 1),
+    };
+    let mut attempts = 1;
+    let mut status = None;
+    let call = async {
+        let args = match adapter.describe_operation(url, operation).await {
+            Ok(detail) => coerce::coerce(&detail, args)?.args,
+            Err(_) => args,
+        };
+        let retried = retry::run(policy, || adapter.execute(url, operation, args.clone())).await;
+        attempts = retried.attempts;
+        let result = retried.result?;
+        status = result.metadata.status;
+        Ok::<_, anyhow::Error>(OutputEnvelope::success(
+            "call_result",
+            adapter.protocol_type().as_str(),
+            url,
+            Some(operation),
+            result.data,
+            Some(result.metadata.duration_ms),
+        ))
+    };
+    let envelope = call.await.unwrap_or_else(|err| {
+        status = uxc_core::error::http_status_of(&err);
+        error_envelope(&err)
+    });
+    (envelope, status, attempts)
+}
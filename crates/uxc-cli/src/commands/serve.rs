@@ -0,0 +1,151 @@
+//! `uxc mock` and `uxc serve http`
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::cli::Cli;
+use crate::data::{GatewaySession, MockSession};
+use crate::endpoint::{
+    execute_endpoint_command, load_auth_profile, prepare_adapter, substitute_variables,
+    CallOptions, EndpointCommand,
+};
+use crate::next::error_code;
+use crate::{error_envelope, normalize_endpoint_url};
+use uxc_core::adapters::Adapter;
+use uxc_core::cache::CacheConfig;
+use uxc_core::filter::OperationFilter;
+use uxc_core::gateway::{self, GatewayRequest, GatewayResponse};
+use uxc_core::mock::{self, Mock};
+use uxc_core::output::OutputEnvelope;
+
+pub(crate) async fn handle_mock_command(
+    cli: &Cli,
+    source: &str,
+    addr: std::net::SocketAddr,
+    seed: u64,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let (mock, source) = if std::path::Path::new(source).is_file() {
+        (
+            Mock::load(std::path::Path::new(source), seed)?,
+            source.to_string(),
+        )
+    } else {
+        let url = normalize_endpoint_url(&substitute_variables(source)?);
+        let auth_profile = load_auth_profile(cli.profile.clone())?;
+        let adapter = prepare_adapter(cli, &url, cache_config, auth_profile).await?;
+        let schema = adapter.fetch_schema(&url).await?;
+        (Mock::new(adapter.protocol_type(), schema, seed)?, url)
+    };
+    let protocol = mock.protocol().as_str();
+
+    let mut address = addr;
+    let requests_served = mock::serve(mock, addr, |bound| {
+        address = bound;
+        eprintln!("Mock {} server listening on http://{}", protocol, bound);
+        eprintln!("Press Ctrl-C to stop");
+    })
+    .await?;
+
+    let data = serde_json::to_value(MockSession {
+        source,
+        address: address.to_string(),
+        requests_served,
+    })?;
+    Ok(OutputEnvelope::success(
+        "mock_session",
+        protocol,
+        &format!("http://{}", address),
+        None,
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+pub(crate) async fn handle_serve_http_command(
+    cli: &Cli,
+    endpoint: &str,
+    mut options: gateway::GatewayOptions,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let metrics = std::sync::Arc::new(uxc_core::instrumentation::PrometheusInstrumentation::new());
+    uxc_core::instrumentation::install(metrics.clone());
+    options.metrics = Some(metrics);
+    let url = normalize_endpoint_url(&substitute_variables(endpoint)?);
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let adapter =
+        std::sync::Arc::new(prepare_adapter(cli, &url, cache_config, auth_profile).await?);
+    let protocol = adapter.protocol_type().as_str();
+    let mut call = CallOptions::from_cli(cli)?;
+    // Responses go back to the HTTP client, not to stdout or files, and
+    // request bodies are the client's JSON, never this process's variables
+    call.raw = false;
+    call.output = None;
+    call.literal_args = true;
+    let call = std::sync::Arc::new(call);
+
+    let handle = {
+        let url = url.clone();
+        move |request: GatewayRequest| {
+            let (adapter, url, call) = (adapter.clone(), url.clone(), call.clone());
+            async move {
+                let command = match request {
+                    GatewayRequest::List => EndpointCommand::List {
+                        verbose: false,
+                        sort: None,
+                        filter: OperationFilter::default(),
+                    },
+                    GatewayRequest::Describe { operation_id } => {
+                        EndpointCommand::Describe { operation_id }
+                    }
+                    GatewayRequest::Call { operation_id, args } => EndpointCommand::Execute {
+                        operation_id,
+                        args: Vec::new(),
+                        json: Some(Value::Object(args).to_string()),
+                    },
+                };
+                match execute_endpoint_command(&adapter, &url, command, &call).await {
+                    Ok(envelope) => GatewayResponse::ok(envelope),
+                    Err(err) => GatewayResponse {
+                        status: gateway::status_for(
+                            error_code(&err),
+                            uxc_core::error::http_status_of(&err),
+                        ),
+                        envelope: error_envelope(&err),
+                    },
+                }
+            }
+        }
+    };
+    let mut address = gateway::Bound {
+        addr: options.addr,
+        metrics_addr: options.metrics_addr,
+    };
+    let requests_served = gateway::serve(options, handle, |bound| {
+        address = *bound;
+        eprintln!(
+            "Gateway to {} ({}) listening on http://{}",
+            url, protocol, bound.addr
+        );
+        let metrics_addr = bound.metrics_addr.unwrap_or(bound.addr);
+        eprintln!("Metrics at http://{}/metrics", metrics_addr);
+        eprintln!("Press Ctrl-C to stop");
+    })
+    .await?;
+
+    let data = serde_json::to_value(GatewaySession {
+        address: address.addr.to_string(),
+        metrics_address: address.metrics_addr.map(|addr| addr.to_string()),
+        requests_served,
+    })?;
+    Ok(OutputEnvelope::success(
+        "gateway_session",
+        protocol,
+        &url,
+        None,
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
@@ -0,0 +1,215 @@
+//! `uxc test <suite>`
+
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::cli::Cli;
+use crate::commands::run::run_request_file;
+use crate::endpoint::{
+    load_auth_profile, prepare_adapter, substitute_arguments, substitute_variables,
+};
+use crate::next::error_code;
+use crate::normalize_endpoint_url;
+use uxc_core::adapters::{self, Adapter};
+use uxc_core::batch::{self, BatchOptions, ItemError};
+use uxc_core::cache::CacheConfig;
+use uxc_core::error::UxcError;
+use uxc_core::output::OutputEnvelope;
+use uxc_core::request_file;
+use uxc_core::suite::{self, CallOutcome, SuiteReport, TestSuite};
+
+pub(crate) async fn handle_test_command(
+    cli: &Cli,
+    suite_path: &str,
+    junit_path: Option<&str>,
+    options: &BatchOptions,
+    compensate: bool,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    if request_file::is_request_file(std::path::Path::new(suite_path)) {
+        let run = run_request_file(cli, suite_path, None, options, cache_config).await?;
+        let report = run.to_suite_report();
+        if let Some(path) = junit_path {
+            write_junit_report(path, &report)?;
+        }
+        return Ok(OutputEnvelope::success(
+            "test_report",
+            "cli",
+            "uxc",
+            None,
+            serde_json::to_value(&report)?,
+            Some(run.duration_ms),
+        ));
+    }
+
+    let test_suite = TestSuite::load(std::path::Path::new(suite_path))?;
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let suite_name = test_suite
+        .name
+        .clone()
+        .unwrap_or_else(|| "suite".to_string());
+    let start = std::time::Instant::now();
+
+    let mut adapters_by_url: HashMap<String, std::sync::Arc<adapters::AdapterEnum>> =
+        HashMap::new();
+    let mut reports = Vec::new();
+
+    let classify = |err: &anyhow::Error| error_code(err).to_string();
+    let mut stopped = false;
+    // Cases whose call succeeded and that can be undone, with their response
+    let mut undo: Vec<(&suite::TestCase, String, Value)> = Vec::new();
+
+    for case in &test_suite.cases {
+        let url = normalize_endpoint_url(&substitute_variables(&test_suite.endpoint_for(case)?)?);
+        if stopped {
+            reports.push(suite::CaseReport::skipped(case, &url));
+            continue;
+        }
+
+        if !adapters_by_url.contains_key(&url) {
+            let prepared = batch::run(options, classify, || {
+                prepare_adapter(cli, &url, cache_config.clone(), auth_profile.clone())
+            })
+            .await;
+            match prepared.result {
+                Ok(adapter) => {
+                    adapters_by_url.insert(url.clone(), adapter);
+                }
+                Err(error) => {
+                    let outcome = CallOutcome::Failure {
+                        code: error.code,
+                        message: error.message,
+                        duration_ms: prepared.duration_ms,
+                        status: error.status,
+                    };
+                    let mut report = suite::evaluate_case(case, &url, &outcome);
+                    report.attempts = prepared.attempts;
+                    stopped = options.fail_fast || (compensate && !undo.is_empty());
+                    reports.push(report);
+                    continue;
+                }
+            }
+        }
+
+        let adapter = &adapters_by_url[&url];
+        let args = substitute_arguments(case.args.clone().into_iter().collect())?;
+        let attempted = batch::run(options, classify, || {
+            adapter.execute(&url, &case.operation, args.clone())
+        })
+        .await;
+        let outcome = match attempted.result {
+            Ok(result) => CallOutcome::Success {
+                data: result.data,
+                duration_ms: result.metadata.duration_ms,
+                status: result.metadata.status,
+            },
+            Err(error) => CallOutcome::Failure {
+                code: error.code,
+                message: error.message,
+                duration_ms: attempted.duration_ms,
+                status: error.status,
+            },
+        };
+        let mut report = suite::evaluate_case(case, &url, &outcome);
+        report.attempts = attempted.attempts;
+        if let (Some(_), CallOutcome::Success { data, .. }) = (&case.compensate, outcome) {
+            undo.push((case, url, data));
+        }
+        stopped = !report.passed && (options.fail_fast || (compensate && !undo.is_empty()));
+        reports.push(report);
+    }
+
+    let failed = reports
+        .iter()
+        .any(|report| !report.passed && !report.skipped);
+    let mut compensations = Vec::new();
+    if failed && compensate {
+        for (case, case_url, data) in undo.iter().rev() {
+            let Some(compensation) = &case.compensate else {
+                continue;
+            };
+            let url = match &compensation.endpoint {
+                Some(endpoint) => normalize_endpoint_url(&substitute_variables(endpoint)?),
+                None => case_url.clone(),
+            };
+            let mut report = suite::CompensationReport {
+                case: case.name.clone(),
+                endpoint: url.clone(),
+                operation: compensation.operation.clone(),
+                ..Default::default()
+            };
+            let args = match compensation.args_for(data) {
+                Ok(args) => substitute_arguments(args.into_iter().collect())?,
+                Err(message) => {
+                    report.error = Some(ItemError {
+                        code: "INVALID_ARGUMENT".to_string(),
+                        message,
+                        ..Default::default()
+                    });
+                    compensations.push(report);
+                    continue;
+                }
+            };
+
+            if !adapters_by_url.contains_key(&url) {
+                let prepared = batch::run(options, classify, || {
+                    prepare_adapter(cli, &url, cache_config.clone(), auth_profile.clone())
+                })
+                .await;
+                match prepared.result {
+                    Ok(adapter) => {
+                        adapters_by_url.insert(url.clone(), adapter);
+                    }
+                    Err(error) => {
+                        report.duration_ms = prepared.duration_ms;
+                        report.error = Some(error);
+                        compensations.push(report);
+                        continue;
+                    }
+                }
+            }
+
+            let adapter = &adapters_by_url[&url];
+            let attempted = batch::run(options, classify, || {
+                adapter.execute(&url, &compensation.operation, args.clone())
+            })
+            .await;
+            report.duration_ms = attempted.duration_ms;
+            match attempted.result {
+                Ok(_) => report.ok = true,
+                Err(error) => report.error = Some(error),
+            }
+            compensations.push(report);
+        }
+    }
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let mut report = SuiteReport::new(&suite_name, reports, duration_ms);
+    report.compensations = compensations;
+
+    if let Some(path) = junit_path {
+        write_junit_report(path, &report)?;
+    }
+
+    let data = serde_json::to_value(&report)?;
+    Ok(OutputEnvelope::success(
+        "test_report",
+        "cli",
+        "uxc",
+        None,
+        data,
+        Some(duration_ms),
+    ))
+}
+
+fn write_junit_report(path: &str, report: &SuiteReport) -> Result<()> {
+    std::fs::write(path, report.to_junit_xml()).map_err(|e| {
+        UxcError::GenericError(anyhow::anyhow!(
+            "Failed to write JUnit report to {}: {}",
+            path,
+            e
+        ))
+    })?;
+    Ok(())
+}
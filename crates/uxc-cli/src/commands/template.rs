@@ -0,0 +1,114 @@
+//! `uxc template`
+
+use anyhow::Result;
+
+use crate::cli::{Cli, TemplateCommands};
+use crate::data::{TemplateListData, TemplateRemoveData, TemplateView};
+use crate::endpoint::{
+    load_auth_profile, parse_arguments, prepare_adapter, retry_policy, substitute_arguments,
+    substitute_variables,
+};
+use crate::normalize_endpoint_url;
+use uxc_core::adapters::Adapter;
+use uxc_core::arguments;
+use uxc_core::cache::CacheConfig;
+use uxc_core::coerce;
+use uxc_core::error::UxcError;
+use uxc_core::output::OutputEnvelope;
+use uxc_core::retry;
+use uxc_core::templates::TemplateStore;
+
+pub(crate) async fn handle_template_command(
+    cli: &Cli,
+    command: &TemplateCommands,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    match command {
+        TemplateCommands::List => {
+            let store = TemplateStore::load()?;
+            let views = store
+                .templates
+                .into_iter()
+                .map(|(name, template)| TemplateView { name, template })
+                .collect::<Vec<_>>();
+            let data = serde_json::to_value(TemplateListData {
+                count: views.len(),
+                templates: views,
+            })?;
+            Ok(OutputEnvelope::success(
+                "template_list",
+                "cli",
+                "uxc",
+                None,
+                data,
+                None,
+            ))
+        }
+        TemplateCommands::Show { name } => {
+            let store = TemplateStore::load()?;
+            let template = store
+                .get(name)
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            let data = serde_json::to_value(TemplateView {
+                name: name.clone(),
+                template: template.clone(),
+            })?;
+            Ok(OutputEnvelope::success(
+                "template_detail",
+                "cli",
+                "uxc",
+                Some(name),
+                data,
+                None,
+            ))
+        }
+        TemplateCommands::Run { name, args, json } => {
+            let store = TemplateStore::load()?;
+            let template = store
+                .get(name)
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?
+                .clone();
+
+            let mut args_map = substitute_arguments(template.args.clone().into_iter().collect())?;
+            let json = json.as_deref().map(arguments::read_payload).transpose()?;
+            args_map.extend(parse_arguments(args.clone(), json)?);
+
+            let url = normalize_endpoint_url(&substitute_variables(&template.endpoint)?);
+            let auth_profile = load_auth_profile(cli.profile.clone())?;
+            let adapter = prepare_adapter(cli, &url, cache_config, auth_profile).await?;
+            let args_map = match adapter.describe_operation(&url, &template.operation).await {
+                Ok(detail) => coerce::coerce(&detail, args_map)?.args,
+                Err(_) => args_map,
+            };
+            let result = retry::run(&retry_policy(cli)?, || {
+                adapter.execute(&url, &template.operation, args_map.clone())
+            })
+            .await
+            .result?;
+            Ok(OutputEnvelope::success(
+                "call_result",
+                adapter.protocol_type().as_str(),
+                &url,
+                Some(&template.operation),
+                result.data,
+                Some(result.metadata.duration_ms),
+            ))
+        }
+        TemplateCommands::Remove { name } => {
+            let mut store = TemplateStore::load()?;
+            store
+                .remove(name)
+                .map_err(|e| UxcError::InvalidArguments(e.to_string()))?;
+            store.save()?;
+            let data = serde_json::to_value(TemplateRemoveData { name: name.clone() })?;
+            Ok(OutputEnvelope::success(
+                "template_remove_result",
+                "cli",
+                "uxc",
+                Some(name),
+                data,
+                None,
+            ))
+        }
+    }
+}
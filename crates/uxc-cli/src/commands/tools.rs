@@ -0,0 +1,331 @@
+//! Commands working on one endpoint's schema: `detect`, `browse`, `gen-data`,
+//! `diff`, `codegen` and `bench`
+
+use anyhow::Result;
+use tracing::debug;
+
+use crate::cli::Cli;
+use crate::data::{BrowseSessionData, DetectData, GeneratedClientData, GeneratedData};
+use crate::endpoint::{
+    grpc_connection_config, load_auth_profile, prepare_adapter, record_call, schema_location,
+    substitute_variables, CallOptions,
+};
+use crate::next::error_code;
+use crate::{browse, normalize_endpoint_url};
+use uxc_core::adapters::{
+    Adapter, DetectionOptions, OperationDetail, ProtocolDetector, ProtocolType,
+};
+use uxc_core::allowlist;
+use uxc_core::bench::{self, BenchOptions};
+use uxc_core::cache::{self, CacheConfig};
+use uxc_core::codegen;
+use uxc_core::datagen;
+use uxc_core::error::UxcError;
+use uxc_core::output::OutputEnvelope;
+use uxc_core::schema_diff;
+
+pub(crate) async fn handle_detect_command(
+    cli: &Cli,
+    endpoint: &str,
+    all: bool,
+    report: bool,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let url = normalize_endpoint_url(endpoint);
+    allowlist::enforce(&url, None)?;
+    let schema_url = schema_location(cli)?;
+    let options = DetectionOptions {
+        schema_url: schema_url.clone(),
+        grpc: grpc_connection_config(cli),
+        jsonrpc_version: cli.jsonrpc_version,
+        cache: Some(cache::create_cache(cache_config)?),
+        ..Default::default()
+    }
+    .with_config(&url)?;
+    let detector = ProtocolDetector::new();
+
+    if report {
+        let options = DetectionOptions {
+            protocol: cli.protocol,
+            ..options
+        };
+        let report = detector.report(&url, &options).await?;
+        let protocol = report.selected.as_deref().unwrap_or("unknown").to_string();
+        return Ok(OutputEnvelope::success(
+            "detect_report",
+            &protocol,
+            &url,
+            None,
+            serde_json::to_value(report)?,
+            Some(start.elapsed().as_millis() as u64),
+        ));
+    }
+
+    let protocols = if all {
+        detector.detect_all(&url, &options).await?
+    } else {
+        vec![detector
+            .detect_adapter_with_options(&url, &options)
+            .await?
+            .protocol_type()]
+    };
+    let selected = match cli.protocol {
+        Some(protocol) if protocols.contains(&protocol) => Some(protocol),
+        Some(protocol) => {
+            return Err(UxcError::ProtocolDetectionFailed(format!(
+                "{} was not detected at {}",
+                protocol.as_str(),
+                url
+            ))
+            .into())
+        }
+        None => protocols.first().copied(),
+    };
+
+    let data = serde_json::to_value(DetectData {
+        url: url.clone(),
+        protocols: protocols.iter().map(|p| p.as_str().to_string()).collect(),
+        selected: selected.map(|p| p.as_str().to_string()),
+    })?;
+    Ok(OutputEnvelope::success(
+        "detect_result",
+        selected.map_or("unknown", |protocol| protocol.as_str()),
+        &url,
+        None,
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+pub(crate) async fn handle_browse_command(
+    cli: &Cli,
+    endpoint: &str,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err(UxcError::InvalidArguments(
+            "uxc browse needs an interactive terminal; use list/describe for scripts".to_string(),
+        )
+        .into());
+    }
+    let start = std::time::Instant::now();
+    let url = normalize_endpoint_url(&substitute_variables(endpoint)?);
+    allowlist::enforce(&url, None)?;
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let adapter = prepare_adapter(cli, &url, cache_config, auth_profile).await?;
+
+    let calls = browse::run(&adapter, &url, |operation, outcome, elapsed| {
+        record_call(&url, Some(operation), outcome, elapsed, None, None);
+    })
+    .await?;
+
+    let data = serde_json::to_value(BrowseSessionData { calls })?;
+    Ok(OutputEnvelope::success(
+        "browse_session",
+        adapter.protocol_type().as_str(),
+        &url,
+        None,
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+pub(crate) async fn handle_gen_data_command(
+    cli: &Cli,
+    endpoint: &str,
+    operation_id: &str,
+    count: usize,
+    seed: Option<u64>,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let url = normalize_endpoint_url(&substitute_variables(endpoint)?);
+    allowlist::enforce(&url, Some(operation_id))?;
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let adapter = prepare_adapter(cli, &url, cache_config, auth_profile).await?;
+    let detail = adapter.describe_operation(&url, operation_id).await?;
+
+    let schema = datagen::payload_schema(&detail);
+    let seed = seed.unwrap_or_else(datagen::random_seed);
+    let mut generator = datagen::Generator::new(seed);
+    let payloads = (0..count).map(|_| generator.value(&schema)).collect();
+
+    let data = serde_json::to_value(GeneratedData {
+        operation_id: operation_id.to_string(),
+        seed,
+        count,
+        payloads,
+    })?;
+    Ok(OutputEnvelope::success(
+        "generated_data",
+        adapter.protocol_type().as_str(),
+        &url,
+        Some(operation_id),
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+pub(crate) async fn handle_diff_command(
+    cli: &Cli,
+    before: &str,
+    after: Option<&str>,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    if after.is_none() && cli.as_of.is_none() {
+        return Err(UxcError::InvalidArguments(
+            "Give a second endpoint, or --as-of to compare with a snapshot".to_string(),
+        )
+        .into());
+    }
+    let before_url = normalize_endpoint_url(&substitute_variables(before)?);
+    let after_url = match after {
+        Some(after) => normalize_endpoint_url(&substitute_variables(after)?),
+        None => before_url.clone(),
+    };
+
+    // --as-of applies to the old version only
+    let (protocol, old) = operation_details(cli, &before_url, cache_config.clone()).await?;
+    let cache_config = CacheConfig {
+        as_of: None,
+        ..cache_config
+    };
+    let (_, new) = operation_details(cli, &after_url, cache_config).await?;
+
+    let diff = schema_diff::compare(&old, &new);
+    let endpoint = if before_url == after_url {
+        before_url
+    } else {
+        format!("{} -> {}", before_url, after_url)
+    };
+    Ok(OutputEnvelope::success(
+        "schema_diff",
+        protocol.as_str(),
+        &endpoint,
+        None,
+        serde_json::to_value(diff)?,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+pub(crate) async fn handle_codegen_command(
+    cli: &Cli,
+    language: codegen::Language,
+    endpoint: &str,
+    output: Option<&str>,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let start = std::time::Instant::now();
+    let url = normalize_endpoint_url(&substitute_variables(endpoint)?);
+    let (protocol, details) = operation_details(cli, &url, cache_config).await?;
+    let generated = codegen::generate(language, &url, protocol.as_str(), &details);
+
+    let (path, code) = match output {
+        Some(path) => {
+            std::fs::write(path, &generated.code).map_err(|e| {
+                UxcError::GenericError(anyhow::anyhow!("Failed to write client to {}: {}", path, e))
+            })?;
+            (Some(path.to_string()), None)
+        }
+        None => (None, Some(generated.code)),
+    };
+    let data = serde_json::to_value(GeneratedClientData {
+        language: language.as_str().to_string(),
+        operations: generated.operations,
+        types: generated.types,
+        path,
+        code,
+    })?;
+    Ok(OutputEnvelope::success(
+        "generated_client",
+        protocol.as_str(),
+        &url,
+        None,
+        data,
+        Some(start.elapsed().as_millis() as u64),
+    ))
+}
+
+/// Details of every operation of an endpoint. Operations that cannot be
+/// described are compared by what the listing says about them.
+async fn operation_details(
+    cli: &Cli,
+    url: &str,
+    cache_config: CacheConfig,
+) -> Result<(ProtocolType, Vec<OperationDetail>)> {
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let adapter = prepare_adapter(cli, url, cache_config, auth_profile).await?;
+    let mut details = Vec::new();
+    for operation in adapter.list_operations(url).await? {
+        let detail = match adapter
+            .describe_operation(url, &operation.operation_id)
+            .await
+        {
+            Ok(detail) => detail,
+            Err(err) => {
+                debug!("Not describing {}: {:#}", operation.operation_id, err);
+                OperationDetail {
+                    operation_id: operation.operation_id,
+                    display_name: operation.display_name,
+                    description: operation.description,
+                    parameters: operation.parameters,
+                    return_type: operation.return_type,
+                    input_schema: None,
+                    responses: None,
+                    security: None,
+                    servers: None,
+                    fields: None,
+                }
+            }
+        };
+        details.push(detail);
+    }
+    Ok((adapter.protocol_type(), details))
+}
+
+pub(crate) async fn handle_bench_command(
+    cli: &Cli,
+    endpoint: &str,
+    operation_id: &str,
+    args: Vec<String>,
+    json: Option<String>,
+    options: &BenchOptions,
+    cache_config: CacheConfig,
+) -> Result<OutputEnvelope> {
+    let url = normalize_endpoint_url(&substitute_variables(endpoint)?);
+    allowlist::enforce(&url, Some(operation_id))?;
+    let auth_profile = load_auth_profile(cli.profile.clone())?;
+    let adapter = prepare_adapter(cli, &url, cache_config, auth_profile).await?;
+    let call = CallOptions::from_cli(cli)?;
+    let args = call
+        .arguments(&adapter, &url, operation_id, args, json)
+        .await?
+        .args;
+
+    let report = bench::run(
+        options,
+        |err| error_code(err).to_string(),
+        || async {
+            let result = call
+                .pipeline
+                .execute(adapter.as_ref(), &url, operation_id, args.clone())
+                .await?;
+            Ok(result.metadata.status)
+        },
+    )
+    .await;
+
+    let duration_ms = report.duration_ms;
+    Ok(OutputEnvelope::success(
+        "bench_report",
+        adapter.protocol_type().as_str(),
+        &url,
+        Some(operation_id),
+        serde_json::to_value(report)?,
+        Some(duration_ms),
+    ))
+}
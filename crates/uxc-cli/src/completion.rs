@@ -0,0 +1,306 @@
+//! Shell completion scripts and dynamic completion
+//!
+//! `uxc completion <shell>` prints the clap-generated script for the static
+//! parts of the command line (subcommands, flags) followed by a hook that
+//! first asks `uxc __complete <words...>` for candidates. That hidden command
+//! answers positions after an endpoint URL: operation IDs, and `name=` for
+//! the parameters of the chosen operation, read from the schema cache without
+//! touching the network. When it prints nothing, the static completion runs.
+
+use clap::{Command, ValueEnum};
+
+/// Hidden command the completion scripts call
+pub const COMPLETE_COMMAND: &str = "__complete";
+
+/// Endpoint subcommands offered next to operation IDs after a URL
+const ENDPOINT_COMMANDS: &[&str] = &[
+    "list", "search", "describe", "help", "inspect", "which", "call", "query",
+];
+
+/// Operation-level flags that take a value
+const OPERATION_VALUE_FLAGS: &[&str] = &["--json", "--args", "-a"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// What the word under the cursor completes to
+#[derive(Debug, PartialEq, Eq)]
+pub enum Target {
+    /// An operation ID (or endpoint subcommand) of `endpoint`
+    Operation {
+        endpoint: String,
+        with_commands: bool,
+    },
+    /// A `name=` argument of `operation_id`; `given` names are already set
+    Parameter {
+        endpoint: String,
+        operation_id: String,
+        given: Vec<String>,
+    },
+    /// Left to the static completion
+    Static,
+}
+
+/// Completion script for `shell`: clap's static script plus the dynamic hook
+pub fn script(shell: CompletionShell, mut cmd: Command) -> String {
+    let mut buf = Vec::new();
+    let generator = match shell {
+        CompletionShell::Bash => clap_complete::Shell::Bash,
+        CompletionShell::Zsh => clap_complete::Shell::Zsh,
+        CompletionShell::Fish => clap_complete::Shell::Fish,
+        CompletionShell::Powershell => clap_complete::Shell::PowerShell,
+    };
+    clap_complete::generate(generator, &mut cmd, "uxc", &mut buf);
+    let generated = String::from_utf8_lossy(&buf).into_owned();
+
+    match shell {
+        CompletionShell::Bash => format!("{}{}", generated, BASH_HOOK),
+        CompletionShell::Zsh => format!("{}{}", generated, ZSH_HOOK),
+        CompletionShell::Fish => format!("{}{}", generated, FISH_HOOK),
+        // Keep clap's completer as a script block to fall back to
+        CompletionShell::Powershell => format!(
+            "{}{}",
+            generated.replacen(
+                "Register-ArgumentCompleter -Native -CommandName 'uxc' -ScriptBlock {",
+                "$__uxcStaticCompleter = {",
+                1
+            ),
+            POWERSHELL_HOOK
+        ),
+    }
+}
+
+/// Work out what to complete. `words` are the words after `uxc`, the last
+/// one being the (possibly empty) word under the cursor.
+pub fn target(cmd: &Command, words: &[String]) -> Target {
+    let Some((_, preceding)) = words.split_last() else {
+        return Target::Static;
+    };
+
+    let mut positionals = Vec::new();
+    let mut iter = preceding.iter();
+    while let Some(word) = iter.next() {
+        if word.starts_with('-') {
+            if !word.contains('=') && takes_value(cmd, word) {
+                iter.next();
+            }
+            continue;
+        }
+        positionals.push(word.as_str());
+    }
+
+    let Some((&endpoint, rest)) = positionals.split_first() else {
+        return Target::Static;
+    };
+    // `uxc cache ...`, `uxc auth ...`: not an endpoint
+    if cmd.get_subcommands().any(|sub| sub.get_name() == endpoint) {
+        return Target::Static;
+    }
+    let endpoint = endpoint.to_string();
+
+    match rest {
+        [] => Target::Operation {
+            endpoint,
+            with_commands: true,
+        },
+        ["describe" | "help" | "call"] => Target::Operation {
+            endpoint,
+            with_commands: false,
+        },
+        ["call", operation_id, args @ ..] => Target::Parameter {
+            endpoint,
+            operation_id: operation_id.to_string(),
+            given: given_names(args),
+        },
+        [command, ..] if ENDPOINT_COMMANDS.contains(command) => Target::Static,
+        [operation_id, args @ ..] => Target::Parameter {
+            endpoint,
+            operation_id: operation_id.to_string(),
+            given: given_names(args),
+        },
+    }
+}
+
+/// Candidates from `values` that start with `current`, one per line
+pub fn filter(values: impl IntoIterator<Item = String>, current: &str) -> Vec<String> {
+    values
+        .into_iter()
+        .filter(|value| value.starts_with(current))
+        .collect()
+}
+
+/// Whether `flag` is a global or operation flag followed by a value
+fn takes_value(cmd: &Command, flag: &str) -> bool {
+    if OPERATION_VALUE_FLAGS.contains(&flag) {
+        return true;
+    }
+    cmd.get_arguments().any(|arg| {
+        let matches = match flag.strip_prefix("--") {
+            Some(long) => arg.get_long() == Some(long),
+            None => flag
+                .strip_prefix('-')
+                .and_then(|short| short.chars().next())
+                .is_some_and(|short| flag.len() == 2 && arg.get_short() == Some(short)),
+        };
+        matches && arg.get_action().takes_values()
+    })
+}
+
+fn given_names(args: &[&str]) -> Vec<String> {
+    args.iter()
+        .filter_map(|arg| arg.split_once('=').map(|(name, _)| name.to_string()))
+        .collect()
+}
+
+const BASH_HOOK: &str = r#"
+# Operation IDs and parameters after an endpoint URL, from the schema cache
+_uxc_dynamic() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local -a words
+    read -ra words <<< "$line"
+    [[ "$line" == *[[:space:]] ]] && words+=("")
+    local cur="${words[${#words[@]}-1]}"
+    # bash splits words at ':' and '='; complete only the part after them
+    local prefix="${cur%"${cur##*[:=]}"}"
+    local -a candidates=()
+    local candidate
+    while IFS= read -r candidate; do
+        [[ -n "$candidate" ]] && candidates+=("${candidate#"$prefix"}")
+    done < <(uxc __complete "${words[@]:1}" 2>/dev/null)
+    if (( ${#candidates[@]} )); then
+        COMPREPLY=("${candidates[@]}")
+        [[ "${candidates[0]}" == *= ]] && compopt -o nospace 2>/dev/null
+        return 0
+    fi
+    _uxc "$@"
+}
+complete -F _uxc_dynamic -o bashdefault -o default uxc
+"#;
+
+const ZSH_HOOK: &str = r#"
+# Operation IDs and parameters after an endpoint URL, from the schema cache
+_uxc_dynamic() {
+    local -a candidates operations parameters
+    candidates=(${(f)"$(uxc __complete "${(@)words[2,CURRENT]}" 2>/dev/null)"})
+    if (( ${#candidates} )); then
+        parameters=(${(M)candidates:#*=})
+        operations=(${candidates:#*=})
+        (( ${#operations} )) && compadd -a operations
+        (( ${#parameters} )) && compadd -S '' -a parameters
+        return 0
+    fi
+    _uxc "$@"
+}
+compdef _uxc_dynamic uxc
+"#;
+
+const FISH_HOOK: &str = r#"
+# Operation IDs and parameters after an endpoint URL, from the schema cache
+function __uxc_dynamic
+    uxc __complete (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null
+end
+complete -c uxc -f -n '__uxc_dynamic | string length -q' -a '(__uxc_dynamic)'
+"#;
+
+const POWERSHELL_HOOK: &str = r#"
+# Operation IDs and parameters after an endpoint URL, from the schema cache
+Register-ArgumentCompleter -Native -CommandName 'uxc' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object { $_.ToString() })
+    if ($wordToComplete -eq '') { $words += '' }
+    $candidates = @(& uxc __complete @words 2>$null | Where-Object { $_ })
+    if ($candidates.Count -gt 0) {
+        $candidates | ForEach-Object {
+            [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)
+        }
+        return
+    }
+    & $__uxcStaticCompleter $wordToComplete $commandAst $cursorPosition
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn cmd() -> Command {
+        Command::new("uxc")
+            .arg(Arg::new("profile").long("profile").global(true))
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(Command::new("cache"))
+            .subcommand(Command::new("list"))
+    }
+
+    fn words(line: &[&str]) -> Vec<String> {
+        line.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn completes_operations_and_parameters_after_an_endpoint() {
+        let cmd = cmd();
+        assert_eq!(target(&cmd, &words(&[""])), Target::Static);
+        assert_eq!(target(&cmd, &words(&["cache", ""])), Target::Static);
+        assert_eq!(
+            target(
+                &cmd,
+                &words(&["--profile", "ci", "-v", "api.example.com", "get:/u"])
+            ),
+            Target::Operation {
+                endpoint: "api.example.com".to_string(),
+                with_commands: true,
+            }
+        );
+        assert_eq!(
+            target(&cmd, &words(&["api.example.com", "describe", ""])),
+            Target::Operation {
+                endpoint: "api.example.com".to_string(),
+                with_commands: false,
+            }
+        );
+        assert_eq!(
+            target(
+                &cmd,
+                &words(&[
+                    "api.example.com",
+                    "get:/users",
+                    "limit=5",
+                    "--json",
+                    "{}",
+                    ""
+                ])
+            ),
+            Target::Parameter {
+                endpoint: "api.example.com".to_string(),
+                operation_id: "get:/users".to_string(),
+                given: vec!["limit".to_string()],
+            }
+        );
+        assert_eq!(
+            target(&cmd, &words(&["api.example.com", "list", ""])),
+            Target::Static
+        );
+    }
+
+    #[test]
+    fn scripts_hook_the_dynamic_completer() {
+        for shell in CompletionShell::value_variants() {
+            let script = script(*shell, cmd());
+            assert!(script.contains("uxc __complete"), "{:?}", shell);
+        }
+        let powershell = script(CompletionShell::Powershell, cmd());
+        assert!(powershell.contains("$__uxcStaticCompleter = {"));
+        assert_eq!(powershell.matches("Register-ArgumentCompleter").count(), 1);
+    }
+}
@@ -0,0 +1,452 @@
+//! `uxc daemon`: a long-lived process answering endpoint commands
+//!
+//! `uxc daemon start` listens on a Unix socket (`~/.uxc/daemon.sock`) or, on
+//! Windows, a named pipe. While it runs, `uxc <url> ...` commands hand their
+//! command line to it and print the envelope it answers. The adapters it
+//! prepares stay alive between commands with their parsed schemas, gRPC
+//! channels and MCP sessions, so later calls skip detection, schema parsing
+//! and connection setup.
+//!
+//! The daemon runs one command at a time, in the working directory and
+//! environment it was started with. Clients in another directory or with a
+//! different environment (an added `UXC_ALLOWED_HOSTS`, a changed variable an
+//! `env:` reference reads), and commands that read stdin, write raw bytes or
+//! trace to stderr, run in their own process as before.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::debug;
+use uxc_core::adapters::AdapterEnum;
+use uxc_core::output::OutputEnvelope;
+
+/// Environment variable naming the socket (the pipe on Windows) instead of
+/// the default one
+pub const SOCKET_ENV: &str = "UXC_DAEMON_SOCKET";
+
+/// What a client asks the daemon, one JSON line per connection
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    /// Run a command line (program name first) from the client's directory
+    Run {
+        args: Vec<String>,
+        cwd: PathBuf,
+        /// The client's environment, as digests of the values
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+    },
+    Status,
+    /// Forget warm adapters and in-memory schemas
+    Flush,
+    Stop,
+}
+
+/// What the daemon answers, one JSON line
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "reply", rename_all = "snake_case")]
+pub enum Reply {
+    /// The envelope of the command, successful or not
+    Envelope {
+        envelope: Box<OutputEnvelope>,
+    },
+    /// The client runs the command itself
+    Declined {
+        reason: String,
+    },
+    Status {
+        status: DaemonStatus,
+    },
+    Done,
+}
+
+/// A running daemon, as reported by `uxc daemon status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub socket: String,
+    /// Directory whose commands the daemon runs
+    pub cwd: String,
+    pub uptime_ms: u64,
+    pub commands_served: u64,
+    /// Adapters kept from earlier commands
+    pub warm_adapters: usize,
+}
+
+/// A failed command the daemon ran, carrying its error envelope
+#[derive(Debug)]
+pub struct DaemonError(pub OutputEnvelope);
+
+impl std::fmt::Display for DaemonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0.error {
+            Some(error) => write!(f, "{}", error.message),
+            None => write!(f, "uxc daemon reported a failure"),
+        }
+    }
+}
+
+impl std::error::Error for DaemonError {}
+
+/// The socket (pipe) clients and the daemon meet on
+pub fn socket() -> String {
+    if let Some(socket) = uxc_core::env_file::var(SOCKET_ENV).filter(|s| !s.is_empty()) {
+        return socket;
+    }
+    transport::default_socket()
+}
+
+/// Adapters prepared by earlier commands, by the settings they were prepared
+/// from. Only set while serving.
+static WARM: OnceLock<Mutex<HashMap<String, Arc<AdapterEnum>>>> = OnceLock::new();
+
+/// The adapter an earlier command prepared from `settings` while serving;
+/// otherwise the one `prepare` makes
+pub async fn warm_adapter(
+    settings: String,
+    prepare: impl Future<Output = Result<AdapterEnum>>,
+) -> Result<Arc<AdapterEnum>> {
+    let Some(warm) = WARM.get() else {
+        return Ok(Arc::new(prepare.await?));
+    };
+    if let Some(adapter) = warm.lock().unwrap().get(&settings) {
+        uxc_core::explain::record("daemon", "adapter kept warm by uxc daemon; no detection");
+        return Ok(adapter.clone());
+    }
+    let adapter = Arc::new(prepare.await?.keep_sessions());
+    warm.lock().unwrap().insert(settings, adapter.clone());
+    Ok(adapter)
+}
+
+/// Send `request` to the running daemon. `None` when no daemon listens.
+pub async fn send(request: &Request) -> Result<Option<Reply>> {
+    let socket = socket();
+    let Some(stream) = transport::connect(&socket).await else {
+        return Ok(None);
+    };
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("Failed to write to uxc daemon at {}", socket))?;
+    writer.flush().await?;
+    let mut answer = String::new();
+    BufReader::new(reader)
+        .read_line(&mut answer)
+        .await
+        .with_context(|| format!("Failed to read from uxc daemon at {}", socket))?;
+    if answer.is_empty() {
+        anyhow::bail!("uxc daemon at {} closed the connection", socket);
+    }
+    Ok(Some(serde_json::from_str(&answer)?))
+}
+
+/// Have the running daemon run `args`. `None` when no daemon listens or it
+/// declined, and the command runs here; once the daemon took the command,
+/// its failures are reported rather than the command run twice.
+pub async fn delegate(args: &[String]) -> Result<Option<OutputEnvelope>> {
+    let request = Request::Run {
+        args: args.to_vec(),
+        cwd: std::env::current_dir()?,
+        env: environment(),
+    };
+    match send(&request).await? {
+        Some(Reply::Envelope { envelope }) => Ok(Some(*envelope)),
+        Some(Reply::Declined { reason }) => {
+            debug!("uxc daemon declined the command: {}", reason);
+            Ok(None)
+        }
+        Some(other) => anyhow::bail!("Unexpected answer from uxc daemon: {:?}", other),
+        None => Ok(None),
+    }
+}
+
+/// Ask a running daemon to forget its warm adapters, after caches or
+/// settings changed
+pub async fn flush() {
+    if let Err(e) = send(&Request::Flush).await {
+        debug!("Failed to flush uxc daemon: {:#}", e);
+    }
+}
+
+/// Serve commands with `run` until stopped, Ctrl-C, or `idle` passes
+/// without a command. `ready` gets the socket once it listens.
+pub async fn serve<F, Fut>(
+    run: F,
+    idle: Option<Duration>,
+    ready: impl FnOnce(&str),
+) -> Result<DaemonStatus>
+where
+    F: Fn(Vec<String>) -> Fut,
+    Fut: Future<Output = OutputEnvelope>,
+{
+    use futures::stream::{FuturesUnordered, StreamExt};
+
+    let socket = socket();
+    let mut listener =
+        transport::bind(&socket).with_context(|| format!("Cannot listen on {}", socket))?;
+    let _ = WARM.set(Mutex::new(HashMap::new()));
+    let daemon = Daemon {
+        socket: socket.clone(),
+        cwd: std::env::current_dir()?,
+        env: environment(),
+        started: Instant::now(),
+        served: std::cell::Cell::new(0),
+        last_active: std::cell::Cell::new(Instant::now()),
+        turn: tokio::sync::Mutex::new(()),
+        stop: tokio::sync::Notify::new(),
+    };
+    ready(&socket);
+
+    let mut connections = FuturesUnordered::new();
+    loop {
+        let deadline =
+            idle.map(|idle| tokio::time::Instant::from_std(daemon.last_active.get() + idle));
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(stream) => {
+                    daemon.last_active.set(Instant::now());
+                    connections.push(daemon.answer(stream, &run));
+                }
+                Err(e) => debug!("uxc daemon failed to accept a client: {}", e),
+            },
+            Some(()) = connections.next() => daemon.last_active.set(Instant::now()),
+            _ = daemon.stop.notified() => break,
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)),
+                if deadline.is_some() && connections.is_empty() => break,
+        }
+    }
+    // Commands already taken are finished and answered
+    while connections.next().await.is_some() {}
+    Ok(daemon.status())
+}
+
+/// Variables that shells change on their own, ignored when comparing
+const SHELL_VARIABLES: &[&str] = &["_", "OLDPWD", "PWD", "SHLVL"];
+
+/// This process's environment, each value replaced by its SHA-256, so no
+/// secret crosses the socket
+fn environment() -> BTreeMap<String, String> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value)))
+        .filter(|(name, _)| !SHELL_VARIABLES.contains(&name.as_str()))
+        .map(|(name, value)| {
+            let digest = Sha256::digest(value.as_encoded_bytes())
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            (name, digest)
+        })
+        .collect()
+}
+
+/// Names set differently in `a` and `b`
+fn differing(a: &BTreeMap<String, String>, b: &BTreeMap<String, String>) -> Vec<String> {
+    let names: std::collections::BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| a.get(*name) != b.get(*name))
+        .cloned()
+        .collect()
+}
+
+/// State of the serving daemon
+struct Daemon {
+    socket: String,
+    cwd: PathBuf,
+    env: BTreeMap<String, String>,
+    started: Instant,
+    served: std::cell::Cell<u64>,
+    last_active: std::cell::Cell<Instant>,
+    /// Commands share process-wide settings, so they take turns
+    turn: tokio::sync::Mutex<()>,
+    stop: tokio::sync::Notify,
+}
+
+impl Daemon {
+    fn status(&self) -> DaemonStatus {
+        DaemonStatus {
+            pid: std::process::id(),
+            socket: self.socket.clone(),
+            cwd: self.cwd.display().to_string(),
+            uptime_ms: self.started.elapsed().as_millis() as u64,
+            commands_served: self.served.get(),
+            warm_adapters: WARM.get().map_or(0, |warm| warm.lock().unwrap().len()),
+        }
+    }
+
+    /// Read one request from `stream` and write the reply
+    async fn answer<S, F, Fut>(&self, stream: S, run: &F)
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+        F: Fn(Vec<String>) -> Fut,
+        Fut: Future<Output = OutputEnvelope>,
+    {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut line = String::new();
+        if let Err(e) = BufReader::new(reader).read_line(&mut line).await {
+            debug!("uxc daemon failed to read a request: {}", e);
+            return;
+        }
+        let reply = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => self.reply(request, run).await,
+            Err(e) => Reply::Declined {
+                reason: format!("unreadable request: {}", e),
+            },
+        };
+        let written = async {
+            let mut line = serde_json::to_string(&reply)?;
+            line.push('\n');
+            writer.write_all(line.as_bytes()).await?;
+            writer.flush().await?;
+            anyhow::Ok(())
+        };
+        if let Err(e) = written.await {
+            debug!("uxc daemon failed to answer a client: {:#}", e);
+        }
+    }
+
+    async fn reply<F, Fut>(&self, request: Request, run: &F) -> Reply
+    where
+        F: Fn(Vec<String>) -> Fut,
+        Fut: Future<Output = OutputEnvelope>,
+    {
+        match request {
+            Request::Run { cwd, .. } if cwd != self.cwd => Reply::Declined {
+                reason: format!("the daemon runs commands in {}", self.cwd.display()),
+            },
+            Request::Run { env, .. } if env != self.env => Reply::Declined {
+                reason: format!(
+                    "the daemon runs commands with another environment ({} differ)",
+                    differing(&env, &self.env).join(", ")
+                ),
+            },
+            Request::Run { args, .. } => {
+                let _turn = self.turn.lock().await;
+                let envelope = Box::new(run(args).await);
+                self.served.set(self.served.get() + 1);
+                Reply::Envelope { envelope }
+            }
+            Request::Status => Reply::Status {
+                status: self.status(),
+            },
+            Request::Flush => {
+                let _turn = self.turn.lock().await;
+                if let Some(warm) = WARM.get() {
+                    warm.lock().unwrap().clear();
+                }
+                uxc_core::cache::forget_memory();
+                Reply::Done
+            }
+            Request::Stop => {
+                self.stop.notify_one();
+                Reply::Done
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+mod transport {
+    use std::path::{Path, PathBuf};
+    use tokio::net::{UnixListener, UnixStream};
+
+    pub fn default_socket() -> String {
+        let home = std::env::var_os("HOME").map_or_else(PathBuf::new, PathBuf::from);
+        home.join(".uxc").join("daemon.sock").display().to_string()
+    }
+
+    pub async fn connect(socket: &str) -> Option<UnixStream> {
+        if !Path::new(socket).exists() {
+            return None;
+        }
+        UnixStream::connect(socket).await.ok()
+    }
+
+    /// Listens on the socket and removes it when dropped
+    pub struct Listener {
+        listener: UnixListener,
+        socket: PathBuf,
+    }
+
+    pub fn bind(socket: &str) -> std::io::Result<Listener> {
+        let path = Path::new(socket);
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    "a uxc daemon is already running",
+                ));
+            }
+            // Left behind by a daemon that did not stop cleanly
+            std::fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(Listener {
+            listener: UnixListener::bind(path)?,
+            socket: path.to_path_buf(),
+        })
+    }
+
+    impl Listener {
+        pub async fn accept(&mut self) -> std::io::Result<UnixStream> {
+            self.listener.accept().await.map(|(stream, _)| stream)
+        }
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.socket);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod transport {
+    use tokio::net::windows::named_pipe::{
+        ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions,
+    };
+
+    pub fn default_socket() -> String {
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        format!(r"\\.\pipe\uxc-daemon-{}", user)
+    }
+
+    pub async fn connect(socket: &str) -> Option<NamedPipeClient> {
+        ClientOptions::new().open(socket).ok()
+    }
+
+    /// Listens on the pipe, one server instance per client
+    pub struct Listener {
+        pipe: String,
+        next: NamedPipeServer,
+    }
+
+    pub fn bind(socket: &str) -> std::io::Result<Listener> {
+        Ok(Listener {
+            pipe: socket.to_string(),
+            next: ServerOptions::new()
+                .first_pipe_instance(true)
+                .create(socket)?,
+        })
+    }
+
+    impl Listener {
+        pub async fn accept(&mut self) -> std::io::Result<NamedPipeServer> {
+            self.next.connect().await?;
+            let next = ServerOptions::new().create(&self.pipe)?;
+            Ok(std::mem::replace(&mut self.next, next))
+        }
+    }
+}
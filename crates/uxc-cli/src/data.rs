@@ -0,0 +1,415 @@
+//! `data` payloads of the envelopes commands print
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::daemon;
+use uxc_core::adapters::{self, OperationDetail, RequestPlan};
+use uxc_core::alias::Alias;
+use uxc_core::auth::transfer::ImportSummary;
+use uxc_core::auth::Profile;
+use uxc_core::batch::{BatchSummary, ItemError};
+use uxc_core::config::Setting;
+use uxc_core::history::{CallStats, HistoryEntry, OperationUsage};
+use uxc_core::output::NextAction;
+use uxc_core::plugins;
+use uxc_core::templates::Template;
+
+/// An error carrying suggested next commands for its error envelope.
+#[derive(Debug)]
+pub(crate) struct SuggestedError {
+    pub(crate) error: anyhow::Error,
+    pub(crate) next: Vec<NextAction>,
+}
+
+impl std::fmt::Display for SuggestedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for SuggestedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OperationSummary {
+    pub(crate) operation_id: String,
+    pub(crate) display_name: String,
+    pub(crate) summary: Option<String>,
+    pub(crate) required: Vec<String>,
+    pub(crate) input_shape_hint: String,
+    pub(crate) protocol_kind: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) usage: Option<OperationUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct HostHelpData {
+    pub(crate) operations: Vec<OperationSummary>,
+    pub(crate) count: usize,
+    /// Recently called operation IDs, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) recent: Vec<String>,
+    pub(crate) auth: HostAuth,
+    /// Commands to paste, from listing to calling a real operation
+    pub(crate) quick_start: Vec<String>,
+    pub(crate) next: Vec<String>,
+}
+
+/// Whether an endpoint appears to want credentials, and which would be sent
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct HostAuth {
+    pub(crate) required: bool,
+    /// Security schemes the schema requires and observed HTTP 401 answers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) evidence: Vec<String>,
+    /// Profile calls would use; `None` sends no credentials
+    pub(crate) profile: Option<String>,
+    /// What selected the profile (`--profile`, `UXC_PROFILE`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) profile_source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OperationMatchData {
+    pub(crate) operation_id: String,
+    /// Path parameters captured from the request
+    pub(crate) path_args: serde_json::Map<String, Value>,
+    pub(crate) detail: OperationDetail,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OperationListData {
+    pub(crate) operations: Vec<OperationSummary>,
+    pub(crate) count: usize,
+    pub(crate) verbose: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GlobalHelpData {
+    pub(crate) name: String,
+    pub(crate) about: String,
+    pub(crate) usage: String,
+    pub(crate) commands: Vec<GlobalHelpCommand>,
+    pub(crate) notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GlobalHelpCommand {
+    pub(crate) name: String,
+    pub(crate) about: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheClearData {
+    pub(crate) scope: String,
+    pub(crate) url: Option<String>,
+    /// Entries removed, when clearing them took a scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) removed: Option<usize>,
+}
+
+/// Summary of a `--raw` call; the body itself already went to stdout, or to
+/// `path` with `--output`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct RawOutputData {
+    pub(crate) status: Option<u16>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) bytes: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<String>,
+}
+
+/// The request a `--dry-run` call would send, with credentials hidden
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DryRunData {
+    /// Arguments after coercion to the operation's input schema
+    pub(crate) arguments: Value,
+
+    /// Arguments whose value was converted to the declared type
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) coerced: Vec<String>,
+
+    pub(crate) request: RequestPlan,
+}
+
+/// A command reproducing a call, from `--export`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ExportData {
+    pub(crate) format: String,
+    pub(crate) command: String,
+
+    /// Whether credentials in the command are hidden
+    pub(crate) redacted: bool,
+}
+
+/// Where `--output` wrote the result of a command
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OutputFileData {
+    pub(crate) path: String,
+    pub(crate) bytes: usize,
+    /// Kind of the envelope that was written
+    pub(crate) kind: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DetectData {
+    pub(crate) url: String,
+    /// Every protocol that answered, in detection order
+    pub(crate) protocols: Vec<String>,
+    /// Protocol used for calls without `--protocol`
+    pub(crate) selected: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SupportMatrixData {
+    pub(crate) protocols: Vec<adapters::ProtocolSupport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheCompactData {
+    pub(crate) removed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheWarmData {
+    pub(crate) endpoints: Vec<CacheWarmEndpoint>,
+    #[serde(flatten)]
+    pub(crate) summary: BatchSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheWarmEndpoint {
+    pub(crate) url: String,
+    pub(crate) ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) protocol: Option<String>,
+    pub(crate) duration_ms: u64,
+    pub(crate) attempts: u32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) timed_out: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) skipped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<ItemError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthProfileView {
+    pub(crate) name: String,
+    pub(crate) auth_type: String,
+    pub(crate) api_key_masked: String,
+    pub(crate) description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) login_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) token_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) api_key_placement: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthSessionData {
+    pub(crate) profile: String,
+    pub(crate) host: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) cookies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) cleared: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthOAuthData {
+    pub(crate) profile: String,
+    pub(crate) host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) expires_at: Option<u64>,
+    #[serde(default)]
+    pub(crate) refreshable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) cleared: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthListData {
+    pub(crate) profiles: Vec<AuthProfileView>,
+    pub(crate) count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthRemoveData {
+    pub(crate) profile: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthExportData {
+    pub(crate) count: usize,
+    pub(crate) redacted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) profiles: Option<BTreeMap<String, Profile>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AuthImportData {
+    pub(crate) file: String,
+    pub(crate) dry_run: bool,
+    #[serde(flatten)]
+    pub(crate) summary: ImportSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CollectionExportData {
+    pub(crate) format: String,
+    pub(crate) count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) collection: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ImportResultData {
+    pub(crate) format: String,
+    pub(crate) source: String,
+    pub(crate) imported: Vec<String>,
+    pub(crate) skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct RequestImportData {
+    pub(crate) format: String,
+    #[serde(flatten)]
+    pub(crate) template: Template,
+    pub(crate) matched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) saved_as: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TemplateView {
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) template: Template,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AliasView {
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) alias: Alias,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AliasListData {
+    pub(crate) aliases: Vec<AliasView>,
+    pub(crate) count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct AliasRemoveData {
+    pub(crate) name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PluginListData {
+    pub(crate) plugins: Vec<plugins::InstalledPlugin>,
+    pub(crate) count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ConfigListData {
+    pub(crate) settings: Vec<Setting>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TemplateListData {
+    pub(crate) templates: Vec<TemplateView>,
+    pub(crate) count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct BrowseSessionData {
+    /// Operation calls made from the browser
+    pub(crate) calls: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GeneratedData {
+    pub(crate) operation_id: String,
+    /// Pass as `--seed` to generate the same payloads again
+    pub(crate) seed: u64,
+    pub(crate) count: usize,
+    pub(crate) payloads: Vec<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GeneratedClientData {
+    pub(crate) language: String,
+    pub(crate) operations: usize,
+    pub(crate) types: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DaemonStatusData {
+    pub(crate) running: bool,
+    pub(crate) socket: String,
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub(crate) status: Option<daemon::DaemonStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DaemonStopData {
+    pub(crate) stopped: bool,
+    pub(crate) socket: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct MockSession {
+    /// Endpoint URL or schema file the mock answered from
+    pub(crate) source: String,
+    pub(crate) address: String,
+    pub(crate) requests_served: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GatewaySession {
+    pub(crate) address: String,
+    /// Separate metrics listener, when `--metrics-port` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) metrics_address: Option<String>,
+    pub(crate) requests_served: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct HistorySearchData {
+    pub(crate) entries: Vec<HistoryEntry>,
+    pub(crate) count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct HistoryStatsData {
+    pub(crate) operations: Vec<CallStats>,
+
+    /// Window length in seconds, when `--interval` was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) interval: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TemplateRemoveData {
+    pub(crate) name: String,
+}
@@ -0,0 +1,1513 @@
+//! Endpoint commands (`uxc <url> list|describe|call ...`)
+//!
+//! Resolves the command line to an operation, prepares the adapter with its
+//! schema, auth and hooks, and runs the call with retries, pagination and
+//! response checks.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::sync::atomic::Ordering;
+use tracing::{debug, info, warn};
+
+use crate::cli::{Cli, Commands, ListSort};
+use crate::data::{
+    DryRunData, ExportData, HostAuth, HostHelpData, OperationListData, OperationMatchData,
+    OperationSummary, RawOutputData,
+};
+use crate::next::error_code;
+use crate::render::auth_summary;
+use crate::settings::UNTRUSTED_PROJECT_ENDPOINT;
+use crate::{daemon, normalize_endpoint_url};
+use uxc_core::adapters::graphql;
+use uxc_core::adapters::grpc::GrpcConnectionConfig;
+use uxc_core::adapters::jsonrpc::JsonRpcVersion;
+use uxc_core::adapters::openapi::ServerSelection;
+use uxc_core::adapters::{
+    self, Adapter, DetectionOptions, Operation, OperationDetail, ProtocolDetector, ProtocolType,
+};
+use uxc_core::allowlist;
+use uxc_core::arguments;
+use uxc_core::assertions::{self, HeaderExpectation};
+use uxc_core::auth::oauth;
+use uxc_core::auth::scheme;
+use uxc_core::auth::session;
+use uxc_core::auth::{AuthType, Profile, Profiles};
+use uxc_core::cache::{self, CacheConfig};
+use uxc_core::coerce;
+use uxc_core::config;
+use uxc_core::datagen;
+use uxc_core::env_file;
+use uxc_core::error::UxcError;
+use uxc_core::explain;
+use uxc_core::filter::OperationFilter;
+use uxc_core::history::{self, HistoryEntry, HistoryStatus, OperationUsage};
+use uxc_core::http;
+use uxc_core::interop::{
+    self,
+    command::{shell_word, CommandFormat},
+};
+use uxc_core::masking;
+use uxc_core::middleware::{AuthMiddleware, Pipeline, TracingMiddleware};
+use uxc_core::output::{self, HeaderFilter, OutputEnvelope, OutputTarget};
+use uxc_core::pagination::{self, Pager, PaginationConfig};
+use uxc_core::project::{self, ProjectConfig};
+use uxc_core::resume::{self, ResumeStore, ResumeToken};
+use uxc_core::retry::{self, RetryPolicy};
+use uxc_core::strict_args;
+use uxc_core::suggest;
+
+/// Timeout and retries of calls, from the flags or the `[call]` settings
+pub(crate) fn retry_policy(cli: &Cli) -> Result<RetryPolicy> {
+    let timeout = match cli.timeout {
+        Some(timeout) => Some(timeout),
+        None => config::get("call.timeout")?.as_duration(),
+    };
+    let retries = match cli.retries {
+        Some(retries) => retries,
+        None => config::get("call.retries")?
+            .as_u64()
+            .map_or(0, |retries| retries as u32),
+    };
+    let backoff = match cli.retry_backoff {
+        Some(backoff) => backoff,
+        None => config::get("call.retry_backoff")?
+            .as_duration()
+            .unwrap_or_default(),
+    };
+    let retry_on = match &cli.retry_on {
+        Some(spec) => Some(spec.clone()),
+        None => config::get("call.retry_on")?
+            .as_str()
+            .map(ToString::to_string),
+    };
+    let retry_on = retry_on
+        .map(|spec| retry::parse_retry_on(&spec))
+        .transpose()
+        .map_err(UxcError::InvalidArguments)?
+        .unwrap_or_default();
+    Ok(RetryPolicy {
+        timeout,
+        retries,
+        backoff,
+        retry_on,
+    })
+}
+
+/// How `--paginate` finds the items and next pages, from `--max-pages` and
+/// the `[pagination]` settings
+fn pagination_config(cli: &Cli) -> Result<PaginationConfig> {
+    let setting = |name: &str| -> Result<Option<String>> {
+        Ok(config::get(name)?.as_str().map(ToString::to_string))
+    };
+    let max_pages = match cli.max_pages {
+        Some(max_pages) => max_pages,
+        None => config::get("pagination.max_pages")?
+            .as_u64()
+            .unwrap_or(pagination::DEFAULT_MAX_PAGES),
+    };
+    Ok(PaginationConfig {
+        items: setting("pagination.items")?,
+        next: setting("pagination.next")?,
+        next_param: setting("pagination.next_param")?,
+        max_pages: max_pages.max(1),
+    })
+}
+
+pub(crate) enum EndpointCommand {
+    HostHelp,
+    List {
+        verbose: bool,
+        sort: Option<ListSort>,
+        filter: OperationFilter,
+    },
+    Search {
+        verbose: bool,
+        term: String,
+    },
+    Describe {
+        operation_id: String,
+    },
+    Example {
+        operation_id: String,
+    },
+    Inspect {
+        full: bool,
+    },
+    Which {
+        method: String,
+        path: String,
+    },
+    Execute {
+        operation_id: String,
+        args: Vec<String>,
+        json: Option<String>,
+    },
+}
+
+impl EndpointCommand {
+    pub(crate) fn operation_id(&self) -> Option<&str> {
+        match self {
+            EndpointCommand::Describe { operation_id }
+            | EndpointCommand::Example { operation_id }
+            | EndpointCommand::Execute { operation_id, .. } => Some(operation_id),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn operation_id_mut(&mut self) -> Option<&mut String> {
+        match self {
+            EndpointCommand::Describe { operation_id }
+            | EndpointCommand::Example { operation_id }
+            | EndpointCommand::Execute { operation_id, .. } => Some(operation_id),
+            _ => None,
+        }
+    }
+}
+
+/// Adapter for `url` whose schema is in the cache (offline detection)
+pub(crate) async fn cached_adapter(url: &str) -> Option<adapters::AdapterEnum> {
+    let cache = cache::create_cache(CacheConfig::load_from_file().unwrap_or_default()).ok()?;
+    let options = DetectionOptions {
+        cache: Some(cache.clone()),
+        ..Default::default()
+    };
+    let adapter = ProtocolDetector::new()
+        .detect_adapter_with_options(url, &options)
+        .await
+        .ok()?;
+    Some(adapter.with_cache(cache))
+}
+
+/// Flag schema-derived data that came from an expired cache entry.
+pub(crate) fn mark_stale_schema(envelope: &mut OutputEnvelope) {
+    if let Some(Value::Object(data)) = envelope.data.as_mut() {
+        data.insert("cache".to_string(), Value::String("stale".to_string()));
+    }
+}
+
+/// Output settings of operation calls
+pub(crate) struct CallOptions {
+    /// `--profile`, reported by host help
+    pub(crate) profile: Option<String>,
+    /// Auth type of the profile calls use
+    pub(crate) auth_type: Option<AuthType>,
+    pub(crate) raw: bool,
+    pub(crate) strict_args: bool,
+    pub(crate) dry_run: bool,
+    pub(crate) export: Option<CommandFormat>,
+    pub(crate) redact: bool,
+    /// Auth, retries and tracing around each call
+    pub(crate) pipeline: Pipeline,
+    pub(crate) headers: Option<HeaderFilter>,
+    pub(crate) expect_headers: Vec<HeaderExpectation>,
+    pub(crate) validate_response: bool,
+    /// `--paginate` with the `[pagination]` settings
+    pub(crate) paginate: Option<PaginationConfig>,
+    /// `--resume`: start from the resume token of an interrupted run
+    pub(crate) resume: bool,
+    pub(crate) output: Option<OutputTarget>,
+    /// Arguments come from someone else (`uxc serve http` clients) and are
+    /// sent as given: no `${VAR}` expansion from this process's environment
+    pub(crate) literal_args: bool,
+}
+
+impl CallOptions {
+    pub(crate) fn from_cli(cli: &Cli) -> Result<Self> {
+        let headers = match cli.include_headers.as_deref() {
+            Some(spec) => HeaderFilter::parse(spec),
+            None => HeaderFilter::load_from_file().unwrap_or_else(|err| {
+                warn!("Ignoring output settings in config file: {:#}", err);
+                None
+            }),
+        };
+        let strict_args =
+            cli.strict_args || (!cli.allow_extra && strict_args::configured_default()?);
+        let mut pipeline = Pipeline::new();
+        let mut auth_type = None;
+        if let Some(profile) = load_auth_profile(cli.profile.clone())? {
+            auth_type = Some(profile.auth_type.clone());
+            pipeline.push(std::sync::Arc::new(AuthMiddleware::new(profile)));
+        }
+        let pipeline = pipeline
+            .with(std::sync::Arc::new(retry_policy(cli)?))
+            .with(std::sync::Arc::new(TracingMiddleware));
+        Ok(Self {
+            profile: cli.profile.clone(),
+            auth_type,
+            raw: cli.raw,
+            strict_args,
+            dry_run: cli.dry_run,
+            export: cli.export.map(CommandFormat::from),
+            redact: cli.redact,
+            pipeline,
+            headers,
+            expect_headers: cli.expect_headers.clone(),
+            validate_response: cli.validate_response,
+            paginate: if cli.paginate {
+                Some(pagination_config(cli)?)
+            } else {
+                None
+            },
+            resume: cli.resume,
+            output: cli.output.as_deref().map(OutputTarget::new),
+            literal_args: false,
+        })
+    }
+
+    /// Send one request of a call and check its response against
+    /// `--expect-header` and `--validate-response`
+    pub(crate) async fn send(
+        &self,
+        adapter: &adapters::AdapterEnum,
+        url: &str,
+        operation_id: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<adapters::ExecutionResult> {
+        let result = self
+            .pipeline
+            .execute(adapter, url, operation_id, args)
+            .await?;
+        let failures: Vec<String> = self
+            .expect_headers
+            .iter()
+            .filter_map(|expectation| expectation.check(&result.metadata.response_headers))
+            .collect();
+        if !failures.is_empty() {
+            return Err(UxcError::ExpectationFailed(failures.join("; ")).into());
+        }
+        if self.validate_response {
+            self.validate_response(adapter, url, operation_id, &result)
+                .await?;
+        }
+        Ok(result)
+    }
+
+    /// Fetch the pages of a call (`--paginate`). The result holds the items
+    /// of all pages, the headers of the last one and the time of all; the
+    /// number of pages comes with it. After each page the arguments of the
+    /// next are recorded as a resume token, which `--resume` starts from.
+    pub(crate) async fn paginate(
+        &self,
+        config: &PaginationConfig,
+        adapter: &adapters::AdapterEnum,
+        url: &str,
+        operation_id: &str,
+        mut args: HashMap<String, Value>,
+    ) -> Result<(adapters::ExecutionResult, u64)> {
+        let declared = match adapter.describe_operation(url, operation_id).await {
+            Ok(detail) => strict_args::declared_names(&detail).unwrap_or_default(),
+            Err(err) => {
+                debug!(
+                    "Paginating {} without its parameters: {:#}",
+                    operation_id, err
+                );
+                Vec::new()
+            }
+        };
+        let initial = serde_json::to_value(args.iter().collect::<BTreeMap<_, _>>())?;
+        let key = resume::resume_key(url, operation_id, &initial);
+        let store = ResumeStore::open_default()?;
+        if let Err(err) = store.prune() {
+            debug!("Failed to prune resume tokens: {:#}", err);
+        }
+
+        // A cursor that had to be masked cannot be sent again
+        let resumed = if self.resume { store.load(&key)? } else { None }
+            .filter(|token| !token.next.to_string().contains(masking::MASK));
+        let mut pager = match resumed {
+            Some(token) => {
+                explain::record(
+                    "pagination",
+                    format!(
+                        "--resume: continuing after {} pages; their items are not repeated",
+                        token.pages
+                    ),
+                );
+                let next: HashMap<String, Value> = serde_json::from_value(token.next)?;
+                args.extend(next);
+                Pager::resuming(config.clone(), declared, token.pages)
+            }
+            None => {
+                if self.resume {
+                    explain::record(
+                        "pagination",
+                        "--resume: no resume token, starting from the first page",
+                    );
+                }
+                Pager::new(config.clone(), declared)
+            }
+        };
+
+        let mut result = self.send(adapter, url, operation_id, args.clone()).await?;
+        let mut duration_ms = result.metadata.duration_ms;
+        while let Some(next) = pager.page(
+            &args,
+            std::mem::take(&mut result.data),
+            &result.metadata.response_headers,
+        ) {
+            let token = ResumeToken::new(
+                url,
+                operation_id,
+                &initial,
+                &serde_json::to_value(&next)?,
+                pager.pages(),
+                resume::DEFAULT_RESUME_TTL,
+            );
+            if let Err(err) = store.save(&token) {
+                warn!("Failed to record resume token: {:#}", err);
+            }
+            result = self.send(adapter, url, operation_id, next.clone()).await?;
+            duration_ms += result.metadata.duration_ms;
+            args = next;
+        }
+        store.remove(&key)?;
+        if pager.truncated() {
+            warn!(
+                "Stopped {} after {} pages; raise --max-pages to fetch more",
+                operation_id,
+                pager.pages()
+            );
+        }
+        let pages = pager.pages();
+        result.data = pager.into_items();
+        result.metadata.duration_ms = duration_ms;
+        Ok((result, pages))
+    }
+
+    /// Fail unless `result` matches the response its operation documents for
+    /// the status (`--validate-response`)
+    pub(crate) async fn validate_response(
+        &self,
+        adapter: &adapters::AdapterEnum,
+        url: &str,
+        operation_id: &str,
+        result: &adapters::ExecutionResult,
+    ) -> Result<()> {
+        let detail = adapter.describe_operation(url, operation_id).await?;
+        let Some(responses) = detail.responses else {
+            return Err(UxcError::InvalidArguments(format!(
+                "--validate-response needs documented responses, and {} has none",
+                operation_id
+            ))
+            .into());
+        };
+        let status = result.metadata.status.unwrap_or(200);
+        let violations = assertions::response_violations(&responses, status, &result.data);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(UxcError::ExpectationFailed(format!(
+            "Response of {} (HTTP {}) departs from its documented schema: {}",
+            operation_id,
+            status,
+            violations.join("; ")
+        ))
+        .into())
+    }
+
+    /// Parse the call arguments and coerce them to the operation's input
+    /// schema, rejecting undeclared names in strict mode
+    pub(crate) async fn arguments(
+        &self,
+        adapter: &adapters::AdapterEnum,
+        url: &str,
+        operation_id: &str,
+        args: Vec<String>,
+        json: Option<String>,
+    ) -> Result<coerce::Coerced> {
+        let args_map = if self.literal_args {
+            collect_arguments(args, json)?
+        } else {
+            parse_arguments(args, json)?
+        };
+        let detail = match adapter.describe_operation(url, operation_id).await {
+            Ok(detail) => detail,
+            Err(err) if self.strict_args => return Err(err),
+            // Operations the schema does not describe are sent as given
+            Err(err) => {
+                debug!("Not coercing arguments of {}: {:#}", operation_id, err);
+                return Ok(coerce::Coerced {
+                    args: args_map,
+                    converted: Vec::new(),
+                });
+            }
+        };
+        self.check_auth(operation_id, &detail);
+        if self.strict_args {
+            strict_args::check(&detail, &args_map)?;
+        }
+        Ok(coerce::coerce(&detail, args_map)?)
+    }
+
+    /// Warn when the profile's auth type meets none of the security
+    /// requirements the operation documents
+    pub(crate) fn check_auth(&self, operation_id: &str, detail: &OperationDetail) {
+        let (Some(auth_type), Some(security)) = (&self.auth_type, &detail.security) else {
+            return;
+        };
+        if scheme::satisfied_by(security, auth_type) {
+            return;
+        }
+        let message = format!(
+            "The profile's auth type {} does not match what {} documents: {}",
+            auth_type,
+            operation_id,
+            auth_summary(security)
+        );
+        explain::record("auth", message.clone());
+        warn!("{}", message);
+    }
+}
+
+pub(crate) async fn execute_endpoint_command(
+    adapter: &adapters::AdapterEnum,
+    url: &str,
+    mut endpoint_command: EndpointCommand,
+    call: &CallOptions,
+) -> Result<OutputEnvelope> {
+    // `--all-protocols` hosts hand operations to the adapter their prefix names
+    let adapter = match endpoint_command.operation_id_mut() {
+        Some(operation_id) => {
+            let (routed, operation) = adapter.route(operation_id)?;
+            *operation_id = operation.to_string();
+            routed
+        }
+        None => adapter,
+    };
+    let envelope = match endpoint_command {
+        EndpointCommand::HostHelp => {
+            let start = std::time::Instant::now();
+            let operations = adapter.list_operations(url).await?;
+            let protocol = adapter.protocol_type().as_str();
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let mut summaries = operations
+                .iter()
+                .map(|op| to_operation_summary(adapter, op))
+                .collect::<Vec<_>>();
+            annotate_usage(url, &mut summaries);
+            let recent = recent_operations(&summaries, 5);
+            let auth = host_auth(adapter, url, call.profile.clone()).await?;
+            let quick_start = quick_start(url, &summaries, &recent, &auth);
+            let data = serde_json::to_value(HostHelpData {
+                count: summaries.len(),
+                recent,
+                auth,
+                quick_start,
+                operations: summaries,
+                next: vec![
+                    "uxc <host> list".to_string(),
+                    "uxc <host> describe <operation_id>".to_string(),
+                    "uxc <host> call <operation_id> --json '{...}'".to_string(),
+                ],
+            })?;
+            OutputEnvelope::success("host_help", protocol, url, None, data, Some(duration_ms))
+        }
+        EndpointCommand::List {
+            verbose,
+            sort,
+            filter,
+        } => {
+            let start = std::time::Instant::now();
+            let operations = adapter.list_operations(url).await?;
+            let protocol = adapter.protocol_type().as_str();
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let mut summaries = operations
+                .iter()
+                .filter(|op| {
+                    let (_, operation_id) = adapter
+                        .route(&op.operation_id)
+                        .unwrap_or((adapter, &op.operation_id));
+                    filter.matches(operation_id, op)
+                })
+                .map(|op| to_operation_summary(adapter, op))
+                .collect::<Vec<_>>();
+            annotate_usage(url, &mut summaries);
+            if let Some(sort) = sort {
+                sort_by_usage(&mut summaries, sort);
+            }
+            let data = serde_json::to_value(OperationListData {
+                count: summaries.len(),
+                operations: summaries,
+                verbose,
+            })?;
+            OutputEnvelope::success(
+                "operation_list",
+                protocol,
+                url,
+                None,
+                data,
+                Some(duration_ms),
+            )
+        }
+        EndpointCommand::Search { verbose, term } => {
+            let start = std::time::Instant::now();
+            let operations = adapter.list_operations(url).await?;
+            let protocol = adapter.protocol_type().as_str();
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let mut ranked = operations
+                .iter()
+                .filter_map(|op| {
+                    let texts = [
+                        op.display_name.as_str(),
+                        op.description.as_deref().unwrap_or(""),
+                    ];
+                    let score = suggest::search_score(&term, &op.operation_id, &texts)?;
+                    Some((score, to_operation_summary(adapter, op)))
+                })
+                .collect::<Vec<_>>();
+            // Best matches first; ties keep the listing order
+            ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            let mut summaries = ranked
+                .into_iter()
+                .map(|(_, summary)| summary)
+                .collect::<Vec<_>>();
+            annotate_usage(url, &mut summaries);
+            let data = serde_json::to_value(OperationListData {
+                count: summaries.len(),
+                operations: summaries,
+                verbose,
+            })?;
+            OutputEnvelope::success(
+                "operation_search",
+                protocol,
+                url,
+                None,
+                data,
+                Some(duration_ms),
+            )
+        }
+        EndpointCommand::Describe { operation_id } => {
+            let start = std::time::Instant::now();
+            let detail = adapter.describe_operation(url, &operation_id).await?;
+            let protocol = adapter.protocol_type().as_str();
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let data = serde_json::to_value(&detail)?;
+            OutputEnvelope::success(
+                "operation_detail",
+                protocol,
+                url,
+                Some(&detail.operation_id),
+                data,
+                Some(duration_ms),
+            )
+        }
+        EndpointCommand::Example { operation_id } => {
+            let start = std::time::Instant::now();
+            let detail = adapter.describe_operation(url, &operation_id).await?;
+            let protocol = adapter.protocol_type().as_str();
+            let duration_ms = start.elapsed().as_millis() as u64;
+            OutputEnvelope::success(
+                "operation_example",
+                protocol,
+                url,
+                Some(&detail.operation_id),
+                datagen::example(&detail),
+                Some(duration_ms),
+            )
+        }
+        EndpointCommand::Which { method, path } => {
+            let start = std::time::Instant::now();
+            let protocol = adapter.protocol_type();
+            if protocol != ProtocolType::OpenAPI {
+                return Err(UxcError::InvalidArguments(format!(
+                    "'which' only supports OpenAPI endpoints (detected {})",
+                    protocol.as_str()
+                ))
+                .into());
+            }
+
+            let operations = adapter.list_operations(url).await?;
+            let (operation_id, path_args) = uxc_core::interop::relative_paths(url, &path)
+                .iter()
+                .find_map(|candidate| {
+                    uxc_core::interop::match_operation(&method, candidate, &operations)
+                })
+                .ok_or_else(|| {
+                    UxcError::OperationNotFound(format!(
+                        "No operation matches {} {}",
+                        method.to_ascii_uppercase(),
+                        path
+                    ))
+                })?;
+            let detail = adapter.describe_operation(url, &operation_id).await?;
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let data = serde_json::to_value(OperationMatchData {
+                operation_id: operation_id.clone(),
+                path_args,
+                detail,
+            })?;
+            OutputEnvelope::success(
+                "operation_match",
+                protocol.as_str(),
+                url,
+                Some(&operation_id),
+                data,
+                Some(duration_ms),
+            )
+        }
+        EndpointCommand::Inspect { full } => {
+            let start = std::time::Instant::now();
+            let protocol = adapter.protocol_type().as_str();
+            let schema = if full {
+                Some(adapter.fetch_schema(url).await?)
+            } else {
+                None
+            };
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let data = json!({
+                "protocol": protocol,
+                "endpoint": url,
+                "schema": schema,
+            });
+            OutputEnvelope::success(
+                "inspect_result",
+                protocol,
+                url,
+                None,
+                data,
+                Some(duration_ms),
+            )
+        }
+        EndpointCommand::Execute {
+            operation_id,
+            args,
+            json,
+        } if call.dry_run => {
+            let start = std::time::Instant::now();
+            let coerced = call
+                .arguments(adapter, url, &operation_id, args, json)
+                .await?;
+            let detail = adapter.describe_operation(url, &operation_id).await?;
+            let request = adapter
+                .plan(url, &operation_id, coerced.args.clone())
+                .await?
+                .redacted();
+            let arguments = Value::Object(coerced.args.into_iter().collect());
+            let arguments = serde_json::from_str(&masking::mask(&arguments.to_string()))?;
+            let data = serde_json::to_value(DryRunData {
+                arguments,
+                coerced: coerced.converted,
+                request,
+            })?;
+            OutputEnvelope::success(
+                "dry_run",
+                adapter.protocol_type().as_str(),
+                url,
+                Some(&detail.operation_id),
+                data,
+                Some(start.elapsed().as_millis() as u64),
+            )
+        }
+        EndpointCommand::Execute {
+            operation_id,
+            args,
+            json,
+        } if call.export.is_some() => {
+            let start = std::time::Instant::now();
+            let format = call.export.unwrap_or(CommandFormat::Curl);
+            let coerced = call
+                .arguments(adapter, url, &operation_id, args, json)
+                .await?;
+            let detail = adapter.describe_operation(url, &operation_id).await?;
+            let mut request = adapter.plan(url, &operation_id, coerced.args).await?;
+            if call.redact {
+                request = request.redacted();
+            }
+            let data = serde_json::to_value(ExportData {
+                format: format.to_string(),
+                command: interop::command::command(&request, format)?,
+                redacted: call.redact,
+            })?;
+            OutputEnvelope::success(
+                "export",
+                adapter.protocol_type().as_str(),
+                url,
+                Some(&detail.operation_id),
+                data,
+                Some(start.elapsed().as_millis() as u64),
+            )
+        }
+        EndpointCommand::Execute {
+            operation_id,
+            args,
+            json,
+        } if call.raw => {
+            let args_map = call
+                .arguments(adapter, url, &operation_id, args, json)
+                .await?
+                .args;
+            let start = std::time::Instant::now();
+            let response = call
+                .pipeline
+                .execute_raw(adapter, url, &operation_id, args_map)
+                .await?;
+            let path = match &call.output {
+                Some(target) => {
+                    let extension =
+                        output::extension_for_content_type(response.content_type.as_deref());
+                    let path = target.write(&response.body, &extension)?;
+                    Some(path.display().to_string())
+                }
+                None => {
+                    let mut stdout = std::io::stdout().lock();
+                    stdout.write_all(&response.body)?;
+                    stdout.flush()?;
+                    None
+                }
+            };
+            let data = serde_json::to_value(RawOutputData {
+                status: response.status,
+                content_type: response.content_type,
+                bytes: response.body.len(),
+                path,
+            })?;
+            OutputEnvelope::success(
+                "raw_output",
+                adapter.protocol_type().as_str(),
+                url,
+                Some(&operation_id),
+                data,
+                Some(start.elapsed().as_millis() as u64),
+            )
+        }
+        EndpointCommand::Execute {
+            operation_id,
+            args,
+            json,
+        } => {
+            let args_map = call
+                .arguments(adapter, url, &operation_id, args, json)
+                .await?
+                .args;
+            let (result, pages) = match &call.paginate {
+                Some(config) => {
+                    let (result, pages) = call
+                        .paginate(config, adapter, url, &operation_id, args_map)
+                        .await?;
+                    (result, Some(pages))
+                }
+                None => (
+                    call.send(adapter, url, &operation_id, args_map).await?,
+                    None,
+                ),
+            };
+            let headers = call
+                .headers
+                .as_ref()
+                .map(|filter| filter.select(&result.metadata.response_headers));
+            let protocol = adapter.protocol_type().as_str();
+            let mut envelope = OutputEnvelope::success(
+                "call_result",
+                protocol,
+                url,
+                Some(&operation_id),
+                result.data,
+                Some(result.metadata.duration_ms),
+            );
+            envelope.meta.headers = headers;
+            envelope.meta.pages = pages;
+            envelope
+        }
+    };
+
+    Ok(envelope)
+}
+
+pub(crate) async fn prepare_adapter(
+    cli: &Cli,
+    url: &str,
+    cache_config: CacheConfig,
+    auth_profile: Option<Profile>,
+) -> Result<std::sync::Arc<adapters::AdapterEnum>> {
+    allowlist::enforce(url, None)?;
+    let schema_url = schema_location(cli)?;
+    let grpc = grpc_connection_config(cli);
+    // `uxc daemon` reuses the adapter prepared from the same settings; its
+    // HTTP client keeps the proxy it was built with
+    let settings = format!(
+        "{:?}",
+        (
+            url,
+            &cli.schema_url,
+            &cli.server,
+            &cli.server_vars,
+            (
+                &cli.select,
+                cli.depth,
+                &cli.persisted_queries,
+                &cli.persisted_query_id,
+            ),
+            &grpc,
+            cli.jsonrpc_version,
+            cli.protocol,
+            cli.all_protocols,
+            &cache_config,
+            &auth_profile,
+            http::proxy(),
+        )
+    );
+    let adapter = daemon::warm_adapter(settings, async {
+        let cache = cache::create_cache(cache_config)?;
+        let detector = ProtocolDetector::new();
+        let detection_options = DetectionOptions {
+            schema_url,
+            server: server_selection(cli),
+            selection: graphql::Selection {
+                fields: cli.select.clone(),
+                depth: cli.depth.map(|depth| depth as usize),
+            },
+            persisted_queries: persisted_queries(cli)?,
+            grpc,
+            jsonrpc_version: cli.jsonrpc_version,
+            cache: Some(cache.clone()),
+            protocol: cli.protocol,
+            ..Default::default()
+        }
+        .with_config(url)?;
+        let mut adapter = if cli.all_protocols {
+            detector
+                .detect_all_adapters(url, &detection_options)
+                .await?
+        } else {
+            detector
+                .detect_adapter_with_options(url, &detection_options)
+                .await?
+        };
+        adapter = adapter.with_cache(cache);
+        if let Some(profile) = auth_profile.clone() {
+            adapter = adapter.with_auth(profile);
+        }
+        Ok(adapter)
+    })
+    .await?;
+    // Logging in and refreshing tokens need the network; calls are refused
+    // offline anyway.
+    if let Some(profile) = auth_profile.as_ref().filter(|_| !cache::is_offline()) {
+        if profile.auth_type == AuthType::Session {
+            session::ensure_session(profile).await?;
+        }
+        if profile.auth_type == AuthType::OAuth {
+            oauth::ensure_token(profile).await?;
+        }
+    }
+    Ok(adapter)
+}
+
+/// gRPC connection settings from config.toml, overridden by `--grpc-*` flags.
+pub(crate) fn grpc_connection_config(cli: &Cli) -> GrpcConnectionConfig {
+    let mut config = GrpcConnectionConfig::load_from_file().unwrap_or_else(|err| {
+        warn!("Ignoring gRPC settings in config file: {:#}", err);
+        GrpcConnectionConfig::default()
+    });
+    if let Some(interval) = cli.grpc_keepalive_interval {
+        config.keepalive_interval = interval;
+    }
+    if let Some(timeout) = cli.grpc_keepalive_timeout {
+        config.keepalive_timeout = timeout;
+    }
+    if let Some(retries) = cli.grpc_connect_retries {
+        config.connect_retries = retries;
+    }
+    if let Some(timeout) = cli.grpc_idle_timeout {
+        config.idle_timeout = timeout;
+    }
+    if let Some(gateway) = &cli.via_http {
+        config.http_gateway = Some(normalize_endpoint_url(gateway));
+    }
+    config
+}
+
+/// Where `--schema-url` points: the absolute path of a local schema file (a
+/// GraphQL SDL), otherwise a URL checked against `--allowed-hosts`
+pub(crate) fn schema_location(cli: &Cli) -> Result<Option<String>> {
+    let Some(schema_url) = &cli.schema_url else {
+        return Ok(None);
+    };
+    let path = std::path::Path::new(schema_url);
+    if !schema_url.contains("://") && path.is_file() {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        return Ok(Some(path.display().to_string()));
+    }
+    allowlist::enforce(schema_url, None)?;
+    Ok(Some(normalize_endpoint_url(schema_url)))
+}
+
+/// How GraphQL calls refer to their document, from `--persisted-queries`
+/// and `--persisted-query-id`
+pub(crate) fn persisted_queries(cli: &Cli) -> Result<Option<graphql::PersistedQueries>> {
+    match (cli.persisted_queries.as_deref(), &cli.persisted_query_id) {
+        (None, None) => Ok(None),
+        (Some("apq"), None) => Ok(Some(graphql::PersistedQueries::Automatic)),
+        (Some("apq"), Some(_)) => Err(UxcError::InvalidArguments(
+            "--persisted-query-id sends a registered ID and cannot be used with --persisted-queries apq"
+                .to_string(),
+        )
+        .into()),
+        (_, id) => Ok(Some(graphql::PersistedQueries::Registered { id: id.clone() })),
+    }
+}
+
+/// OpenAPI server chosen with `--server` and `--server-var`
+fn server_selection(cli: &Cli) -> Option<ServerSelection> {
+    if cli.server.is_none() && cli.server_vars.is_empty() {
+        return None;
+    }
+    Some(ServerSelection {
+        server: cli.server.clone(),
+        variables: cli.server_vars.iter().cloned().collect(),
+    })
+}
+
+pub(crate) fn parse_server_var(value: &str) -> std::result::Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected NAME=VALUE, got '{}'", value)),
+    }
+}
+
+pub(crate) fn parse_jsonrpc_version(value: &str) -> std::result::Result<JsonRpcVersion, String> {
+    JsonRpcVersion::parse(value).ok_or_else(|| {
+        format!(
+            "unsupported JSON-RPC version '{}' (expected 1.0 or 2.0)",
+            value
+        )
+    })
+}
+
+pub(crate) fn parse_protocol(value: &str) -> std::result::Result<ProtocolType, String> {
+    ProtocolType::parse(value).ok_or_else(|| {
+        let names: Vec<&str> = ProtocolType::ALL.iter().map(ProtocolType::as_str).collect();
+        format!(
+            "unknown protocol '{}' (expected one of: {})",
+            value,
+            names.join(", ")
+        )
+    })
+}
+
+pub(crate) fn resolve_endpoint_command(cli: &Cli) -> Result<EndpointCommand> {
+    match &cli.command {
+        None => Ok(EndpointCommand::HostHelp),
+        Some(Commands::List {
+            sort,
+            tags,
+            methods,
+            path,
+        }) => Ok(EndpointCommand::List {
+            verbose: cli.verbose,
+            sort: *sort,
+            filter: OperationFilter {
+                tags: tags.clone(),
+                methods: methods.clone(),
+                path: path.clone(),
+            },
+        }),
+        Some(Commands::Search { term }) => Ok(EndpointCommand::Search {
+            verbose: cli.verbose,
+            term: term.clone(),
+        }),
+        Some(Commands::Describe { operation_id }) => Ok(EndpointCommand::Describe {
+            operation_id: operation_id.clone(),
+        }),
+        Some(Commands::Help {
+            operation_id: Some(operation_id),
+        }) => Ok(EndpointCommand::Describe {
+            operation_id: operation_id.clone(),
+        }),
+        Some(Commands::Help { operation_id: None }) => Ok(EndpointCommand::HostHelp),
+        Some(Commands::Inspect { full }) => Ok(EndpointCommand::Inspect { full: *full }),
+        Some(Commands::Which { method, path, json }) => {
+            let (method, path) = match (json, path) {
+                (Some(sample), _) => parse_request_sample(sample)?,
+                (None, Some(path)) => (
+                    method.clone().unwrap_or_else(|| "GET".to_string()),
+                    path.clone(),
+                ),
+                (None, None) => {
+                    return Err(UxcError::InvalidArguments(
+                        "'which' needs --path or --json".to_string(),
+                    )
+                    .into())
+                }
+            };
+            Ok(EndpointCommand::Which {
+                method: method.to_ascii_lowercase(),
+                path,
+            })
+        }
+        Some(Commands::Call {
+            operation_id,
+            args,
+            json,
+        }) => Ok(EndpointCommand::Execute {
+            operation_id: operation_id.clone(),
+            args: args.clone(),
+            json: json.as_deref().map(arguments::read_payload).transpose()?,
+        }),
+        Some(Commands::Query {
+            file,
+            vars,
+            operation_name,
+        }) => {
+            let document = if file == "-" {
+                let mut document = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut document)
+                    .map(|_| document)
+            } else {
+                std::fs::read_to_string(file)
+            }
+            .map_err(|e| {
+                UxcError::InvalidArguments(format!(
+                    "Cannot read the GraphQL document from {}: {}",
+                    file, e
+                ))
+            })?;
+            let mut payload = serde_json::Map::new();
+            payload.insert("query".to_string(), Value::String(document));
+            if let Some(vars) = vars {
+                let variables =
+                    serde_json::from_str(&arguments::read_payload(vars)?).map_err(|e| {
+                        UxcError::InvalidArguments(format!("--vars is not valid JSON: {}", e))
+                    })?;
+                payload.insert("variables".to_string(), variables);
+            }
+            if let Some(name) = operation_name {
+                payload.insert("operationName".to_string(), Value::String(name.clone()));
+            }
+            Ok(EndpointCommand::Execute {
+                operation_id: graphql::document::OPERATION.to_string(),
+                args: Vec::new(),
+                json: Some(Value::Object(payload).to_string()),
+            })
+        }
+        Some(Commands::External(tokens)) => parse_external_command(tokens, cli.help),
+        Some(Commands::Cache { .. })
+        | Some(Commands::Auth { .. })
+        | Some(Commands::Test { .. })
+        | Some(Commands::Run { .. })
+        | Some(Commands::Support)
+        | Some(Commands::Detect { .. })
+        | Some(Commands::Export { .. })
+        | Some(Commands::Import { .. })
+        | Some(Commands::Template { .. })
+        | Some(Commands::Browse { .. })
+        | Some(Commands::GenData { .. })
+        | Some(Commands::Diff { .. })
+        | Some(Commands::Codegen { .. })
+        | Some(Commands::Bench { .. })
+        | Some(Commands::Mock { .. })
+        | Some(Commands::Serve { .. })
+        | Some(Commands::History { .. })
+        | Some(Commands::Alias { .. })
+        | Some(Commands::Config { .. })
+        | Some(Commands::Project { .. })
+        | Some(Commands::Plugin { .. })
+        | Some(Commands::Daemon { .. })
+        | Some(Commands::Completion { .. }) => Err(UxcError::InvalidArguments(
+            "Internal routing error for non-endpoint command".to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Method and URL/path of a request sample such as a HAR entry.
+fn parse_request_sample(sample: &str) -> Result<(String, String)> {
+    let value: Value = serde_json::from_str(sample)
+        .map_err(|e| UxcError::InvalidArguments(format!("Invalid request sample JSON: {}", e)))?;
+    let request = value.get("request").unwrap_or(&value);
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_string();
+    let target = request
+        .get("url")
+        .or_else(|| request.get("path"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            UxcError::InvalidArguments("Request sample needs a \"url\" or \"path\"".to_string())
+        })?;
+    Ok((method, target.to_string()))
+}
+
+fn parse_external_command(tokens: &[String], global_help: bool) -> Result<EndpointCommand> {
+    if tokens.is_empty() {
+        return Err(UxcError::InvalidArguments("Operation ID is required".to_string()).into());
+    }
+
+    let operation_id = tokens[0].clone();
+
+    if global_help {
+        return Ok(EndpointCommand::Describe { operation_id });
+    }
+
+    if tokens.len() >= 2 && (tokens[1] == "help" || tokens[1] == "example") {
+        if tokens.len() > 2 {
+            return Err(UxcError::InvalidArguments(format!(
+                "Unexpected arguments after '<operation_id> {}'",
+                tokens[1]
+            ))
+            .into());
+        }
+        return Ok(match tokens[1].as_str() {
+            "help" => EndpointCommand::Describe { operation_id },
+            _ => EndpointCommand::Example { operation_id },
+        });
+    }
+
+    let mut args = Vec::new();
+    let mut json_payload = None;
+    let mut idx = 1;
+
+    while idx < tokens.len() {
+        match tokens[idx].as_str() {
+            "-h" | "--help" => {
+                return Ok(EndpointCommand::Describe { operation_id });
+            }
+            "-a" | "--args" => {
+                idx += 1;
+                let arg = tokens.get(idx).ok_or_else(|| {
+                    UxcError::InvalidArguments("Missing value for --args".to_string())
+                })?;
+                args.push(arg.clone());
+            }
+            "--json" => {
+                idx += 1;
+                let payload = tokens.get(idx).ok_or_else(|| {
+                    UxcError::InvalidArguments("Missing value for --json".to_string())
+                })?;
+                json_payload = Some(arguments::read_payload(payload)?);
+            }
+            token if token.contains('=') && !token.starts_with('-') => {
+                args.push(token.to_string());
+            }
+            unknown => {
+                return Err(UxcError::InvalidArguments(format!(
+                    "Unknown argument '{}' for operation '{}'. Use --json or --args",
+                    unknown, operation_id
+                ))
+                .into());
+            }
+        }
+
+        idx += 1;
+    }
+
+    Ok(EndpointCommand::Execute {
+        operation_id,
+        args,
+        json: json_payload,
+    })
+}
+
+pub(crate) fn parse_arguments(
+    args: Vec<String>,
+    json_payload: Option<String>,
+) -> Result<HashMap<String, Value>> {
+    substitute_arguments(collect_arguments(args, json_payload)?)
+}
+
+/// Arguments as given, before `${VAR}` substitution
+pub(crate) fn collect_arguments(
+    args: Vec<String>,
+    json_payload: Option<String>,
+) -> Result<HashMap<String, Value>> {
+    let mut args_map = HashMap::new();
+
+    if let Some(json_str) = json_payload {
+        let value: Value = serde_json::from_str(&json_str)
+            .map_err(|e| UxcError::InvalidArguments(format!("Invalid JSON payload: {}", e)))?;
+        if let Some(obj) = value.as_object() {
+            for (k, v) in obj {
+                args_map.insert(k.clone(), v.clone());
+            }
+        } else {
+            return Err(
+                UxcError::InvalidArguments("JSON payload must be an object".to_string()).into(),
+            );
+        }
+    } else {
+        args_map = arguments::parse(&args)?;
+    }
+
+    Ok(args_map)
+}
+
+/// Append a finished call to history; failures to record only warn.
+///
+/// `args` are stored with secrets redacted so the call can be rerun.
+pub(crate) fn record_call(
+    url: &str,
+    operation: Option<&str>,
+    outcome: std::result::Result<(), &anyhow::Error>,
+    elapsed: std::time::Duration,
+    args: Option<&serde_json::Map<String, Value>>,
+    profile: Option<&str>,
+) {
+    let mut entry = match outcome {
+        Ok(()) => HistoryEntry::new(HistoryStatus::Ok, url, operation),
+        Err(err) => HistoryEntry::new(HistoryStatus::Error, url, operation)
+            .with_error(error_code(err), &err.to_string()),
+    }
+    .with_duration(elapsed.as_millis() as u64)
+    .with_profile(profile);
+    if let Some(args) = args {
+        entry = entry.with_args(args);
+    }
+    if let Err(e) = history::append(&entry) {
+        warn!("Failed to record call in history: {}", e);
+    }
+}
+
+pub(crate) fn substitute_variables(input: &str) -> Result<String> {
+    env_file::substitute(input).map_err(|e| UxcError::InvalidArguments(e.to_string()).into())
+}
+
+pub(crate) fn substitute_arguments(args: HashMap<String, Value>) -> Result<HashMap<String, Value>> {
+    args.into_iter()
+        .map(|(key, value)| {
+            let value = env_file::substitute_value(&value)
+                .map_err(|e| UxcError::InvalidArguments(format!("Argument '{}': {}", key, e)))?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// The profile name calls use, whether it was chosen explicitly, and what
+/// chose it
+pub(crate) fn select_profile(cli_profile: Option<String>) -> Result<(String, bool, &'static str)> {
+    Ok(if let Some(profile) = cli_profile {
+        (profile, true, "--profile")
+    } else if let Some(profile) = env_file::var("UXC_PROFILE") {
+        (profile, true, "UXC_PROFILE")
+    } else if let Some(profile) = ProjectConfig::current()?.and_then(|p| p.profile.clone()) {
+        (profile, true, "the project config")
+    } else {
+        ("default".to_string(), false, "the default name")
+    })
+}
+
+pub(crate) fn load_auth_profile(cli_profile: Option<String>) -> Result<Option<Profile>> {
+    let (profile_name, profile_explicitly_selected, selected_by) = select_profile(cli_profile)?;
+
+    match Profiles::load_profiles() {
+        Ok(profiles) => match profiles.get_profile(&profile_name) {
+            Ok(_) if UNTRUSTED_PROJECT_ENDPOINT.load(Ordering::Relaxed) => {
+                Err(UxcError::InvalidArguments(format!(
+                    "Profile '{}' is not used for the endpoint of an untrusted project config; review {} and run 'uxc project trust' to allow it",
+                    profile_name,
+                    project::PROJECT_FILE
+                ))
+                .into())
+            }
+            Ok(profile) => {
+                explain::record(
+                    "auth",
+                    format!("profile '{}' selected by {}", profile_name, selected_by),
+                );
+                Ok(Some(profile.clone()))
+            }
+            Err(e) => {
+                if !profile_explicitly_selected && profile_name == "default" {
+                    info!("No 'default' profile found, continuing without authentication");
+                    explain::record(
+                        "auth",
+                        "no --profile, UXC_PROFILE or 'default' profile: requests are sent without credentials",
+                    );
+                    Ok(None)
+                } else {
+                    Err(e)
+                }
+            }
+        },
+        Err(e) => {
+            if !profile_explicitly_selected && profile_name == "default" {
+                info!(
+                    "Could not load profiles: {}, continuing without authentication",
+                    e
+                );
+                Ok(None)
+            } else {
+                Err(anyhow::anyhow!(
+                    "Failed to load profile '{}': {}. Please run 'uxc auth set {} --api-key <key>' to create it.",
+                    profile_name,
+                    e,
+                    profile_name
+                ))
+            }
+        }
+    }
+}
+
+fn to_operation_summary(adapter: &adapters::AdapterEnum, op: &Operation) -> OperationSummary {
+    // Operations of `--all-protocols` hosts are prefixed with their protocol
+    let (adapter, operation_id) = adapter
+        .route(&op.operation_id)
+        .unwrap_or((adapter, &op.operation_id));
+    let protocol = adapter.protocol_type().as_str();
+    let required = op
+        .parameters
+        .iter()
+        .filter(|p| p.required)
+        .map(|p| p.name.clone())
+        .collect::<Vec<_>>();
+
+    let protocol_kind = match protocol {
+        "mcp" => "tool",
+        "graphql" => {
+            if operation_id.starts_with("query/") {
+                "query"
+            } else if operation_id.starts_with("mutation/") {
+                "mutation"
+            } else if operation_id.starts_with("subscription/") {
+                "subscription"
+            } else {
+                "field"
+            }
+        }
+        "grpc" => "rpc",
+        "openapi" => "http_operation",
+        "jsonrpc" => "rpc_method",
+        _ => "operation",
+    }
+    .to_string();
+
+    let input_shape_hint = if op.parameters.is_empty() {
+        "none".to_string()
+    } else {
+        "object".to_string()
+    };
+
+    OperationSummary {
+        operation_id: op.operation_id.clone(),
+        display_name: op.display_name.clone(),
+        summary: op.description.clone(),
+        required,
+        input_shape_hint,
+        protocol_kind,
+        tags: op.tags.clone(),
+        usage: None,
+    }
+}
+
+/// Attach call history to each summary.
+fn annotate_usage(url: &str, summaries: &mut [OperationSummary]) {
+    let entries = match history::load() {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read call history: {}", e);
+            return;
+        }
+    };
+    let usage = history::operation_usage(&entries, url);
+    for summary in summaries {
+        summary.usage = usage.get(&summary.operation_id).copied();
+    }
+}
+
+/// Operation IDs with recorded usage, most recent first.
+/// Auth requirements of `url` from its schema's security schemes and from
+/// calls answered with HTTP 401, and the profile calls would use
+async fn host_auth(
+    adapter: &adapters::AdapterEnum,
+    url: &str,
+    cli_profile: Option<String>,
+) -> Result<HostAuth> {
+    let mut evidence = Vec::new();
+    if adapter.protocol_type() == ProtocolType::OpenAPI {
+        match adapter.fetch_schema(url).await {
+            Ok(spec) => evidence.extend(
+                adapters::openapi::required_security(&spec)
+                    .into_iter()
+                    .map(|scheme| format!("schema requires security scheme {}", scheme)),
+            ),
+            Err(e) => debug!("Could not read security schemes: {:#}", e),
+        }
+    }
+    let entries = history::load().unwrap_or_else(|e| {
+        warn!("Failed to read call history: {:#}", e);
+        Vec::new()
+    });
+    let unauthorized = history::unauthorized_calls(&entries, url);
+    if let Some(last) = unauthorized.last() {
+        evidence.push(format!(
+            "{} earlier call(s) answered HTTP 401 (last: {})",
+            unauthorized.len(),
+            last.operation.as_deref().unwrap_or("unknown operation")
+        ));
+    }
+
+    let (name, explicit, selected_by) = select_profile(cli_profile)?;
+    let exists = Profiles::load_profiles()
+        .map(|profiles| profiles.get_profile(&name).is_ok())
+        .unwrap_or(false);
+    Ok(HostAuth {
+        required: !evidence.is_empty(),
+        evidence,
+        profile: exists.then_some(name),
+        profile_source: (exists && explicit).then(|| selected_by.to_string()),
+    })
+}
+
+/// Copy-pastable commands from listing to calling a real operation: the most
+/// recently used one, or else the first that needs no arguments
+pub(crate) fn quick_start(
+    url: &str,
+    summaries: &[OperationSummary],
+    recent: &[String],
+    auth: &HostAuth,
+) -> Vec<String> {
+    let endpoint = shell_word(url);
+    let mut commands = Vec::new();
+    if auth.required && auth.profile.is_none() {
+        commands.push("uxc auth set default --api-key <key>".to_string());
+    }
+    commands.push(format!("uxc {} list", endpoint));
+
+    let operation = recent
+        .first()
+        .and_then(|id| summaries.iter().find(|op| &op.operation_id == id))
+        .or_else(|| summaries.iter().find(|op| op.required.is_empty()))
+        .or_else(|| summaries.first());
+    if let Some(operation) = operation {
+        let operation_id = shell_word(&operation.operation_id);
+        commands.push(format!("uxc {} describe {}", endpoint, operation_id));
+        let mut call = format!("uxc {} {}", endpoint, operation_id);
+        for name in &operation.required {
+            call.push_str(&format!(" {}", shell_word(&format!("{}=<{}>", name, name))));
+        }
+        commands.push(call);
+    }
+    commands
+}
+
+fn recent_operations(summaries: &[OperationSummary], limit: usize) -> Vec<String> {
+    let mut used = summaries
+        .iter()
+        .filter_map(|summary| Some((summary.usage?.last_used, &summary.operation_id)))
+        .collect::<Vec<_>>();
+    used.sort_by_key(|(last_used, _)| std::cmp::Reverse(*last_used));
+    used.into_iter()
+        .take(limit)
+        .map(|(_, operation_id)| operation_id.clone())
+        .collect()
+}
+
+/// Stable sort by call history; operations never called keep schema order.
+fn sort_by_usage(summaries: &mut [OperationSummary], sort: ListSort) {
+    summaries.sort_by_key(|summary| {
+        let usage = summary.usage.unwrap_or(OperationUsage {
+            count: 0,
+            last_used: 0,
+        });
+        let key = match sort {
+            ListSort::Recent => (usage.last_used, usage.count),
+            ListSort::Frequent => (usage.count, usage.last_used),
+        };
+        std::cmp::Reverse(key)
+    });
+}
@@ -0,0 +1,260 @@
+//! Global help and the protocol support matrix
+
+use anyhow::Result;
+use clap::CommandFactory;
+
+use crate::cli::{Cli, Commands};
+use crate::data::{GlobalHelpCommand, GlobalHelpData, SupportMatrixData};
+use uxc_core::adapters;
+use uxc_core::auth::AuthType;
+use uxc_core::output::OutputEnvelope;
+
+pub(crate) fn should_show_global_help(cli: &Cli) -> bool {
+    if cli.url.is_some() {
+        return false;
+    }
+
+    matches!(
+        cli.command,
+        None | Some(Commands::Help { operation_id: None })
+    )
+}
+
+pub(crate) fn print_global_help() -> Result<()> {
+    let mut cmd = Cli::command();
+    cmd.print_help()?;
+    println!();
+    Ok(())
+}
+
+pub(crate) fn support_envelope() -> Result<OutputEnvelope> {
+    let data = serde_json::to_value(SupportMatrixData {
+        protocols: adapters::support_matrix(),
+    })?;
+    Ok(OutputEnvelope::success(
+        "support_matrix",
+        "cli",
+        "uxc",
+        None,
+        data,
+        None,
+    ))
+}
+
+pub(crate) fn print_support_matrix(protocols: &[adapters::ProtocolSupport]) {
+    let mark = |supported: bool| if supported { "yes" } else { "-" };
+    let mut rows: Vec<(String, Vec<&str>)> = vec![
+        ("streaming".to_string(), Vec::new()),
+        ("batch".to_string(), Vec::new()),
+        ("validation".to_string(), Vec::new()),
+        ("schema export".to_string(), Vec::new()),
+        ("schema cache".to_string(), Vec::new()),
+        ("raw body".to_string(), Vec::new()),
+    ];
+    for support in protocols {
+        let caps = &support.capabilities;
+        for (row, supported) in rows.iter_mut().zip([
+            caps.streaming,
+            caps.batch,
+            caps.validation,
+            caps.schema_export,
+            caps.schema_cache,
+            caps.raw_body,
+        ]) {
+            row.1.push(mark(supported));
+        }
+    }
+    for auth_type in [
+        AuthType::Bearer,
+        AuthType::ApiKey,
+        AuthType::Basic,
+        AuthType::Session,
+        AuthType::OAuth,
+    ] {
+        rows.push((
+            format!("auth: {}", auth_type),
+            protocols
+                .iter()
+                .map(|support| mark(support.capabilities.auth_types.contains(&auth_type)))
+                .collect(),
+        ));
+    }
+
+    let mut header = format!("{:<16}", "Feature");
+    for support in protocols {
+        header.push_str(&format!("{:<10}", support.protocol));
+    }
+    println!("{}", header.trim_end());
+    for (feature, marks) in rows {
+        let mut line = format!("{:<16}", feature);
+        for mark in marks {
+            line.push_str(&format!("{:<10}", mark));
+        }
+        println!("{}", line.trim_end());
+    }
+
+    let notes: Vec<_> = protocols
+        .iter()
+        .flat_map(|support| {
+            support
+                .capabilities
+                .notes
+                .iter()
+                .map(move |note| format!("  {}: {}", support.protocol, note))
+        })
+        .collect();
+    if !notes.is_empty() {
+        println!("\nNotes:");
+        for note in notes {
+            println!("{}", note);
+        }
+    }
+}
+
+pub(crate) fn global_help_envelope() -> Result<OutputEnvelope> {
+    let data = serde_json::to_value(GlobalHelpData {
+        name: "uxc".to_string(),
+        about: "Universal X-Protocol Call".to_string(),
+        usage: "uxc [OPTIONS] [URL] [COMMAND]".to_string(),
+        commands: vec![
+            GlobalHelpCommand {
+                name: "list".to_string(),
+                about: "List available operations".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "search".to_string(),
+                about: "Find operations matching a term".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "describe".to_string(),
+                about: "Describe one operation in detail".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "help".to_string(),
+                about: "Show endpoint help, or operation help with OPERATION_ID".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "inspect".to_string(),
+                about: "Inspect endpoint/schema".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "query".to_string(),
+                about: "Run a GraphQL document of your own".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "which".to_string(),
+                about: "Find the operation that handles a concrete request".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "cache".to_string(),
+                about: "Manage schema cache".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "auth".to_string(),
+                about: "Manage authentication profiles".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "call".to_string(),
+                about: "Execute an operation explicitly".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "test".to_string(),
+                about: "Run a declarative test suite".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "detect".to_string(),
+                about: "Detect the protocol an endpoint speaks".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "support".to_string(),
+                about: "Show which features each protocol adapter supports".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "export".to_string(),
+                about: "Export endpoint operations as a Postman or Insomnia collection".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "import".to_string(),
+                about: "Import requests from another tool as templates".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "template".to_string(),
+                about: "Manage saved call templates".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "browse".to_string(),
+                about: "Browse and call an endpoint's operations in a terminal UI".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "gen-data".to_string(),
+                about: "Generate random arguments for an operation from its input schema"
+                    .to_string(),
+            },
+            GlobalHelpCommand {
+                name: "diff".to_string(),
+                about: "Compare the operations of two API versions and flag breaking changes"
+                    .to_string(),
+            },
+            GlobalHelpCommand {
+                name: "codegen".to_string(),
+                about: "Generate a typed Rust, TypeScript or Python client for an endpoint"
+                    .to_string(),
+            },
+            GlobalHelpCommand {
+                name: "bench".to_string(),
+                about: "Load-test an operation and report throughput and latency".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "mock".to_string(),
+                about: "Serve example responses generated from an endpoint's schema".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "serve".to_string(),
+                about: "Expose an endpoint's operations as a REST API (serve http)".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "history".to_string(),
+                about: "Search call history and report success rates and latency".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "alias".to_string(),
+                about: "Manage endpoint aliases, usable anywhere a URL is accepted".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "config".to_string(),
+                about: "Show and change settings of ~/.uxc/config.toml and .uxc.toml".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "project".to_string(),
+                about: "Trust the project's .uxc.toml to use auth profiles and run hooks (status, trust, untrust)".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "plugin".to_string(),
+                about: "Manage protocol plugins in ~/.uxc/plugins".to_string(),
+            },
+            GlobalHelpCommand {
+                name: "daemon".to_string(),
+                about:
+                    "Keep adapters and connections warm for later commands (start, status, stop)"
+                        .to_string(),
+            },
+            GlobalHelpCommand {
+                name: "completion".to_string(),
+                about: "Print a shell completion script".to_string(),
+            },
+        ],
+        notes: vec![
+            "Default output is JSON. Use --text for human-readable output.".to_string(),
+            "Examples: uxc <host> help; uxc <host> <operation> help".to_string(),
+        ],
+    })?;
+
+    Ok(OutputEnvelope::success(
+        "global_help",
+        "cli",
+        "uxc",
+        None,
+        data,
+        None,
+    ))
+}
@@ -0,0 +1,1277 @@
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use serde_json::Value;
+use std::net::IpAddr;
+use tracing::{info, warn};
+
+mod browse;
+mod cli;
+mod commands;
+mod completion;
+mod daemon;
+mod data;
+mod endpoint;
+mod help;
+mod next;
+mod render;
+mod settings;
+
+use crate::cli::{
+    AuthCommands, CacheCommands, Cli, Commands, ConfigCommands, ImportFormat, OutputFormat,
+    OutputMode, ServeCommands,
+};
+use crate::commands::auth::handle_auth_command;
+use crate::commands::cache::handle_cache_command;
+use crate::commands::config::{
+    handle_alias_command, handle_config_command, handle_plugin_command, handle_project_command,
+};
+use crate::commands::daemon::handle_daemon_command;
+use crate::commands::history::handle_history_command;
+use crate::commands::interop::{
+    handle_command_import, handle_export_command, handle_import_command,
+};
+use crate::commands::run::handle_run_command;
+use crate::commands::serve::{handle_mock_command, handle_serve_http_command};
+use crate::commands::suite::handle_test_command;
+use crate::commands::template::handle_template_command;
+use crate::commands::tools::{
+    handle_bench_command, handle_browse_command, handle_codegen_command, handle_detect_command,
+    handle_diff_command, handle_gen_data_command,
+};
+use crate::data::{OutputFileData, SuggestedError};
+use crate::endpoint::{
+    cached_adapter, collect_arguments, execute_endpoint_command, load_auth_profile,
+    mark_stale_schema, prepare_adapter, record_call, resolve_endpoint_command, select_profile,
+    substitute_variables, CallOptions, EndpointCommand,
+};
+use crate::help::{global_help_envelope, should_show_global_help, support_envelope};
+use crate::next::{
+    default_next_actions, error_code, error_detail, error_next_actions, with_next_actions,
+};
+use crate::render::{print_csv, print_json, print_query_result, render_output};
+use crate::settings::{
+    apply_alias, apply_project_config, apply_rerun, configure_proxy, configure_response_limit,
+    load_cache_config, load_env_file,
+};
+use uxc_core::adapters::{Adapter, ProtocolType};
+use uxc_core::alias;
+use uxc_core::allowlist;
+use uxc_core::arguments;
+use uxc_core::bench::BenchOptions;
+use uxc_core::cache::{self, CacheConfig};
+use uxc_core::cassette;
+use uxc_core::config::{self, Setting};
+use uxc_core::diff;
+use uxc_core::env_file;
+use uxc_core::error::UxcError;
+use uxc_core::explain;
+use uxc_core::gateway;
+use uxc_core::http;
+use uxc_core::idn;
+use uxc_core::output::{self, NextAction, OutputEnvelope, OutputTarget, OutputTemplate};
+use uxc_core::query::Query;
+use uxc_core::retry;
+use uxc_core::trace;
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive(tracing::Level::WARN.into()),
+        )
+        .with_writer(|| uxc_core::masking::MaskedStderr)
+        .init();
+    uxc_core::instrumentation::install(std::sync::Arc::new(
+        uxc_core::instrumentation::TracingInstrumentation,
+    ));
+
+    let raw_args: Vec<String> = std::env::args().collect();
+    if is_version_shortcut(&raw_args) {
+        print_version();
+        return;
+    }
+    if raw_args.get(1).map(String::as_str) == Some(completion::COMPLETE_COMMAND) {
+        print_completions(&raw_args[2..]).await;
+        return;
+    }
+
+    let normalized_args = normalize_global_args(raw_args);
+    let fallback_output_mode = output_mode_from_args(&normalized_args);
+
+    match run(normalized_args).await {
+        Ok(0) => {}
+        Ok(code) => std::process::exit(code),
+        Err(err) => {
+            render_error(&err, fallback_output_mode);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `uxc __complete <words...>`: completion candidates for the last word, one
+/// per line. Operation IDs and parameters come from the schema cache only;
+/// anything that cannot be answered from it prints nothing.
+async fn print_completions(words: &[String]) {
+    let cmd = Cli::command();
+    let (endpoint, operation_id, with_commands) = match completion::target(&cmd, words) {
+        completion::Target::Operation {
+            endpoint,
+            with_commands,
+        } => (endpoint, None, with_commands),
+        completion::Target::Parameter {
+            endpoint,
+            operation_id,
+            given,
+        } => (endpoint, Some((operation_id, given)), false),
+        completion::Target::Static => return,
+    };
+    let current = words.last().map(String::as_str).unwrap_or_default();
+
+    cache::set_offline(true);
+    let url = normalize_endpoint_url(&endpoint);
+    let Some(adapter) = cached_adapter(&url).await else {
+        return;
+    };
+    let values: Vec<String> = match operation_id {
+        None => {
+            let Ok(operations) = adapter.list_operations(&url).await else {
+                return;
+            };
+            let commands = ["list", "search", "describe", "help", "inspect", "call"]
+                .into_iter()
+                .filter(|_| with_commands)
+                .map(ToString::to_string);
+            operations
+                .into_iter()
+                .map(|operation| operation.operation_id)
+                .chain(commands)
+                .collect()
+        }
+        Some((operation_id, given)) => {
+            let Ok(detail) = adapter.describe_operation(&url, &operation_id).await else {
+                return;
+            };
+            detail
+                .parameters
+                .into_iter()
+                .filter(|parameter| !given.contains(&parameter.name))
+                .map(|parameter| format!("{}=", parameter.name))
+                .collect()
+        }
+    };
+    for candidate in completion::filter(values, current) {
+        println!("{}", candidate);
+    }
+}
+
+fn is_version_shortcut(args: &[String]) -> bool {
+    args.len() == 2 && matches!(args[1].as_str(), "-v" | "version")
+}
+
+fn print_version() {
+    println!("uxc {}", env!("CARGO_PKG_VERSION"));
+}
+
+fn render_error(err: &anyhow::Error, output_mode: OutputMode) {
+    let next = error_next(err);
+
+    if output_mode != OutputMode::Json {
+        eprintln!("{}", err);
+        print_explain(explain::take().as_deref());
+        if !next.is_empty() {
+            eprintln!("\nTry:");
+            for action in &next {
+                eprintln!("  {}    # {}", action.command, action.description);
+            }
+        }
+        return;
+    }
+
+    let mut envelope = error_envelope(err).with_next(next);
+    envelope.meta.explain = explain::take();
+    match envelope.to_json() {
+        Ok(json) => println!("{}", json),
+        Err(ser_err) => {
+            eprintln!("failed to serialize error output: {}", ser_err);
+            eprintln!("{}", err);
+        }
+    }
+}
+
+/// Suggested next commands for `err`
+fn error_next(err: &anyhow::Error) -> Vec<NextAction> {
+    if let Some(suggested) = err.downcast_ref::<SuggestedError>() {
+        return suggested.next.clone();
+    }
+    if let Some(daemon::DaemonError(envelope)) = err.downcast_ref() {
+        return envelope.next.clone();
+    }
+    vec![NextAction::new("uxc help", "Show available commands")]
+}
+
+/// Error envelope for `err`, with its code and protocol detail
+fn error_envelope(err: &anyhow::Error) -> OutputEnvelope {
+    if let Some(daemon::DaemonError(envelope)) = err.downcast_ref() {
+        return envelope.clone();
+    }
+    let code = error_code(err);
+    let cause = err
+        .downcast_ref::<SuggestedError>()
+        .map_or(err, |suggested| &suggested.error);
+    OutputEnvelope::error(code, &err.to_string())
+        .with_error_detail(error_detail(cause), retry::is_transient(cause))
+}
+
+/// `--explain` decisions for text output, on stderr
+fn print_explain(decisions: Option<&[explain::Decision]>) {
+    let Some(decisions) = decisions.filter(|decisions| !decisions.is_empty()) else {
+        return;
+    };
+    eprintln!("Explain:");
+    for decision in decisions {
+        eprintln!("  [{}] {}", decision.topic, decision.decision);
+    }
+}
+
+async fn run(args: Vec<String>) -> Result<i32> {
+    let mut cli = Cli::parse_from(&args);
+    explain::set_enabled(cli.explain);
+    apply_rerun(&mut cli)?;
+    apply_project_config(&mut cli)?;
+    apply_alias(&mut cli)?;
+    let output_mode = resolve_output_mode(&cli);
+    let query = cli.query.as_deref().map(Query::parse).transpose()?;
+    let columns = query.as_ref().and_then(Query::selected_keys);
+    let template = cli
+        .template
+        .as_deref()
+        .map(OutputTemplate::parse)
+        .transpose()?;
+    let output_target = cli
+        .output
+        .as_deref()
+        .filter(|_| !writes_own_output(&cli))
+        .map(OutputTarget::new);
+    if output_target.is_some() && output_mode == OutputMode::Text && template.is_none() {
+        return Err(UxcError::InvalidArguments(
+            "--output writes JSON, CSV or --template output; drop --text or add --template"
+                .to_string(),
+        )
+        .into());
+    }
+    if let Some(Commands::Completion { shell }) = &cli.command {
+        print!("{}", completion::script(*shell, Cli::command()));
+        return Ok(0);
+    }
+    if cli.watch.is_some() && cli.url.is_none() {
+        return Err(UxcError::InvalidArguments(
+            "--watch only applies to operation calls".to_string(),
+        )
+        .into());
+    }
+    let mut watch = Watch::new(&cli);
+    loop {
+        let started = std::time::Instant::now();
+        let exit_code = match execute_or_delegate(&cli, &args).await {
+            Ok(envelope) => print_envelope(
+                envelope,
+                output_mode,
+                query.as_ref(),
+                columns.as_deref(),
+                template.as_ref(),
+                output_target.as_ref(),
+                watch.as_mut(),
+            )?,
+            // Once a watch is running, a failed call is reported and polled again
+            Err(err) if watch.as_ref().is_some_and(|watch| watch.runs > 0) => {
+                render_error(&err, output_mode);
+                1
+            }
+            Err(err) => return Err(err),
+        };
+        let again = match watch.as_mut() {
+            Some(watch) => watch.next(started).await,
+            None => false,
+        };
+        if !again {
+            cache::wait_for_refreshes(cache::REFRESH_GRACE_PERIOD).await;
+            return Ok(exit_code);
+        }
+    }
+}
+
+/// Run the command, through `uxc daemon` when one is running and the command
+/// is one it serves
+async fn execute_or_delegate(cli: &Cli, args: &[String]) -> Result<OutputEnvelope> {
+    if !delegable(cli, args) {
+        return execute_cli(cli).await;
+    }
+    if let Some(mut envelope) = daemon::delegate(args).await? {
+        // The daemon made every decision again, those made here included
+        explain::take();
+        for decision in envelope.meta.explain.take().unwrap_or_default() {
+            explain::record(&decision.topic, decision.decision);
+        }
+        if !envelope.ok {
+            return Err(daemon::DaemonError(envelope).into());
+        }
+        return Ok(envelope);
+    }
+    execute_cli(cli).await
+}
+
+/// Endpoint commands whose whole output is their envelope. Commands reading
+/// stdin, writing raw bytes, tracing to stderr or using a cassette run here.
+fn delegable(cli: &Cli, args: &[String]) -> bool {
+    let endpoint_command = matches!(
+        &cli.command,
+        None | Some(Commands::List { .. })
+            | Some(Commands::Search { .. })
+            | Some(Commands::Describe { .. })
+            | Some(Commands::Help { .. })
+            | Some(Commands::Inspect { .. })
+            | Some(Commands::Which { .. })
+            | Some(Commands::Call { .. })
+            | Some(Commands::External(_))
+    );
+    endpoint_command
+        && cli.url.is_some()
+        && !cli.no_daemon
+        && !cli.raw
+        && !cli.verbose
+        && cli.record.is_none()
+        && cli.replay.is_none()
+        && !args.iter().any(|arg| arg == "-")
+}
+
+/// State of `--watch` between runs
+struct Watch {
+    interval: std::time::Duration,
+    changes_only: bool,
+    limit: Option<u64>,
+    runs: u64,
+    previous: Option<Value>,
+}
+
+impl Watch {
+    fn new(cli: &Cli) -> Option<Self> {
+        cli.watch.map(|interval| Watch {
+            interval,
+            changes_only: cli.changes_only,
+            limit: cli.watch_count,
+            runs: 0,
+            previous: None,
+        })
+    }
+
+    /// Whether a result is printed. Under `--changes-only` a result equal to
+    /// the previous one is not, and a changed one gets the differences in
+    /// `meta.changes`.
+    fn observe(&mut self, envelope: &mut OutputEnvelope) -> bool {
+        if !self.changes_only || !envelope.ok {
+            return true;
+        }
+        let data = envelope.data.clone().unwrap_or(Value::Null);
+        let Some(previous) = self.previous.replace(data.clone()) else {
+            return true;
+        };
+        let changes = diff::diff(&previous, &data);
+        if changes.is_empty() {
+            return false;
+        }
+        envelope.meta.changes = Some(changes);
+        true
+    }
+
+    /// Count the run that began at `started` and wait until the next one is
+    /// due; false when `--watch-count` runs are done
+    async fn next(&mut self, started: std::time::Instant) -> bool {
+        self.runs += 1;
+        if self.limit.is_some_and(|limit| self.runs >= limit) {
+            return false;
+        }
+        tokio::time::sleep(self.interval.saturating_sub(started.elapsed())).await;
+        true
+    }
+}
+
+/// Print the result of one run and return its exit code
+fn print_envelope(
+    mut envelope: OutputEnvelope,
+    output_mode: OutputMode,
+    query: Option<&Query>,
+    columns: Option<&[String]>,
+    template: Option<&OutputTemplate>,
+    output_target: Option<&OutputTarget>,
+    watch: Option<&mut Watch>,
+) -> Result<i32> {
+    envelope.meta.explain = explain::take();
+    if output_mode == OutputMode::Text {
+        print_explain(envelope.meta.explain.as_deref());
+    }
+    if envelope.next.is_empty() {
+        envelope.next = default_next_actions(&envelope);
+    }
+    let exit_code = exit_code_for(&envelope);
+    let queried = match query {
+        Some(query) if envelope.ok => {
+            let data = envelope.data.take().unwrap_or(Value::Null);
+            envelope.data = Some(query.search(&data)?);
+            true
+        }
+        _ => false,
+    };
+    if let Some(watch) = watch {
+        if !watch.observe(&mut envelope) {
+            return Ok(exit_code);
+        }
+    }
+    if output_mode == OutputMode::Text && output_target.is_none() {
+        print_changes(envelope.meta.changes.as_deref());
+    }
+    if envelope.kind.as_deref() == Some("raw_output") {
+        // The response body is the output, unless --output took it
+        if output_target.is_some() {
+            print_json(&envelope)?;
+        }
+    } else if let Some(target) = output_target {
+        let report = write_output(target, &envelope, template, output_mode, columns)?;
+        let report_mode = if output_mode == OutputMode::Text {
+            OutputMode::Text
+        } else {
+            OutputMode::Json
+        };
+        render_output(&report, report_mode)?;
+    } else if let Some(template) = template {
+        println!("{}", template.render(&envelope)?);
+    } else if output_mode == OutputMode::Csv {
+        print_csv(&envelope, columns)?;
+    } else if queried && output_mode == OutputMode::Text {
+        print_query_result(envelope.data.as_ref());
+    } else if queried {
+        print_json(&envelope)?;
+    } else {
+        render_output(&envelope, output_mode)?;
+    }
+    Ok(exit_code)
+}
+
+/// `--changes-only` differences for text output, ahead of the result
+fn print_changes(changes: Option<&[diff::Change]>) {
+    let Some(changes) = changes else {
+        return;
+    };
+    println!("Changes:");
+    for change in changes {
+        println!("  {}", change);
+    }
+    println!();
+}
+
+/// `uxc export`, `uxc codegen` and `uxc auth export` write their own files for `--output`
+fn writes_own_output(cli: &Cli) -> bool {
+    matches!(
+        &cli.command,
+        Some(Commands::Export { .. })
+            | Some(Commands::Codegen { .. })
+            | Some(Commands::Auth {
+                auth_command: AuthCommands::Export { .. }
+            })
+    )
+}
+
+/// Write the rendered envelope to `target` and return an `output_file`
+/// envelope reporting where it went.
+fn write_output(
+    target: &OutputTarget,
+    envelope: &OutputEnvelope,
+    template: Option<&OutputTemplate>,
+    output_mode: OutputMode,
+    columns: Option<&[String]>,
+) -> Result<OutputEnvelope> {
+    let (rendered, extension) = match template {
+        Some(template) => (format!("{}\n", template.render(envelope)?), "txt"),
+        None if output_mode == OutputMode::Csv => {
+            let data = envelope.data.as_ref().unwrap_or(&Value::Null);
+            (output::to_csv(data, columns)?, "csv")
+        }
+        None => (format!("{}\n", envelope.to_json()?), "json"),
+    };
+    let path = target.write(rendered.as_bytes(), extension)?;
+    let data = serde_json::to_value(OutputFileData {
+        path: path.display().to_string(),
+        bytes: rendered.len(),
+        kind: envelope.kind.clone(),
+    })?;
+    let mut report = OutputEnvelope::success(
+        "output_file",
+        envelope.protocol.as_deref().unwrap_or("cli"),
+        envelope.endpoint.as_deref().unwrap_or("uxc"),
+        envelope.operation.as_deref(),
+        data,
+        envelope.meta.duration_ms,
+    );
+    report.next = envelope.next.clone();
+    Ok(report)
+}
+
+/// Successful envelopes can still signal failure, e.g. a test run with failing cases.
+fn exit_code_for(envelope: &OutputEnvelope) -> i32 {
+    match envelope.kind.as_deref() {
+        Some("schema_diff") => {
+            let breaking = envelope
+                .data
+                .as_ref()
+                .and_then(|data| data.get("breaking"))
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            i32::from(breaking > 0)
+        }
+        Some("test_report") | Some("run_report") | Some("cache_warm_result") => {
+            let failed = envelope
+                .data
+                .as_ref()
+                .and_then(|data| data.get("failed"))
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            i32::from(failed > 0)
+        }
+        Some("raw_output") => {
+            let status = envelope
+                .data
+                .as_ref()
+                .and_then(|data| data.get("status"))
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            i32::from(status >= 400)
+        }
+        _ => 0,
+    }
+}
+
+fn resolve_output_mode(cli: &Cli) -> OutputMode {
+    if cli.text || cli.format == Some(OutputFormat::Text) {
+        OutputMode::Text
+    } else if cli.format == Some(OutputFormat::Csv) {
+        OutputMode::Csv
+    } else if cli.format == Some(OutputFormat::Json) || cli.raw {
+        OutputMode::Json
+    } else if cli.help && cli.url.is_none() && cli.command.is_none() {
+        // Preserve classic `uxc -h/--help` text UX.
+        OutputMode::Text
+    } else {
+        // A broken config is reported by the command itself
+        match config::get("output.format")
+            .ok()
+            .as_ref()
+            .and_then(Setting::as_str)
+        {
+            Some("text") => OutputMode::Text,
+            Some("csv") => OutputMode::Csv,
+            _ => OutputMode::Json,
+        }
+    }
+}
+
+fn output_mode_from_args(args: &[String]) -> OutputMode {
+    // Raw output keeps stdout for the body, so errors go to stderr as text
+    if args.iter().any(|arg| arg == "--text" || arg == "--raw") {
+        return OutputMode::Text;
+    }
+
+    for (idx, arg) in args.iter().enumerate() {
+        if arg == "--format" {
+            match args.get(idx + 1).map(String::as_str) {
+                Some("text") => return OutputMode::Text,
+                Some("csv") => return OutputMode::Csv,
+                _ => {}
+            }
+        } else if arg == "--format=text" {
+            return OutputMode::Text;
+        } else if arg == "--format=csv" {
+            return OutputMode::Csv;
+        }
+    }
+
+    OutputMode::Json
+}
+
+fn normalize_global_args(raw_args: Vec<String>) -> Vec<String> {
+    if raw_args.len() <= 1 {
+        return raw_args;
+    }
+
+    let mut normalized = vec![raw_args[0].clone()];
+    let mut global_args = Vec::new();
+    let mut rest_args = Vec::new();
+    let mut idx = 1;
+
+    while idx < raw_args.len() {
+        let arg = &raw_args[idx];
+        let is_global_bool = matches!(
+            arg.as_str(),
+            "--text"
+                | "--utc"
+                | "--raw"
+                | "--no-cache"
+                | "--stale-while-revalidate"
+                | "--offline"
+                | "--no-follow-redirects"
+                | "--keep-auth-on-redirect"
+                | "--explain"
+                | "--strict-args"
+                | "--allow-extra"
+                | "--dry-run"
+                | "--redact"
+                | "--changes-only"
+                | "--validate-response"
+                | "--paginate"
+                | "--resume"
+                | "--all-protocols"
+                | "--no-daemon"
+                | "--verbose"
+                | "-v"
+        );
+        let is_global_kv = matches!(
+            arg.as_str(),
+            "--format"
+                | "--profile"
+                | "--cache-ttl"
+                | "--schema-url"
+                | "--server"
+                | "--server-var"
+                | "--select"
+                | "--depth"
+                | "--persisted-queries"
+                | "--persisted-query-id"
+                | "--env-file"
+                | "--allowed-hosts"
+                | "--max-redirects"
+                | "--proxy"
+                | "--max-response-bytes"
+                | "--max-pages"
+                | "--timeout"
+                | "--retries"
+                | "--retry-backoff"
+                | "--retry-on"
+                | "--watch"
+                | "--watch-count"
+                | "--as-of"
+                | "--record"
+                | "--replay"
+                | "--grpc-keepalive-interval"
+                | "--grpc-keepalive-timeout"
+                | "--grpc-connect-retries"
+                | "--grpc-idle-timeout"
+                | "--via-http"
+                | "--export"
+                | "--jsonrpc-version"
+                | "--protocol"
+                | "--query"
+                | "--template"
+                | "--include-headers"
+                | "--expect-header"
+                | "--output"
+                | "-o"
+        );
+        let is_global_inline = arg.starts_with("--format=")
+            || arg.starts_with("--profile=")
+            || arg.starts_with("--cache-ttl=")
+            || arg.starts_with("--schema-url=")
+            || arg.starts_with("--server=")
+            || arg.starts_with("--server-var=")
+            || arg.starts_with("--select=")
+            || arg.starts_with("--depth=")
+            || arg.starts_with("--persisted-queries=")
+            || arg.starts_with("--persisted-query-id=")
+            || arg.starts_with("--env-file=")
+            || arg.starts_with("--allowed-hosts=")
+            || arg.starts_with("--max-redirects=")
+            || arg.starts_with("--proxy=")
+            || arg.starts_with("--max-response-bytes=")
+            || arg.starts_with("--max-pages=")
+            || arg.starts_with("--timeout=")
+            || arg.starts_with("--retries=")
+            || arg.starts_with("--retry-backoff=")
+            || arg.starts_with("--retry-on=")
+            || arg.starts_with("--watch=")
+            || arg.starts_with("--watch-count=")
+            || arg.starts_with("--as-of=")
+            || arg.starts_with("--record=")
+            || arg.starts_with("--replay=")
+            || arg.starts_with("--grpc-keepalive-interval=")
+            || arg.starts_with("--grpc-keepalive-timeout=")
+            || arg.starts_with("--grpc-connect-retries=")
+            || arg.starts_with("--grpc-idle-timeout=")
+            || arg.starts_with("--via-http=")
+            || arg.starts_with("--export=")
+            || arg.starts_with("--jsonrpc-version=")
+            || arg.starts_with("--protocol=")
+            || arg.starts_with("--query=")
+            || arg.starts_with("--template=")
+            || arg.starts_with("--include-headers=")
+            || arg.starts_with("--expect-header=")
+            || arg.starts_with("--output=");
+
+        if is_global_bool || is_global_inline {
+            global_args.push(arg.clone());
+            idx += 1;
+            continue;
+        }
+
+        if is_global_kv {
+            global_args.push(arg.clone());
+            if let Some(value) = raw_args.get(idx + 1) {
+                if !value.starts_with("--") {
+                    global_args.push(value.clone());
+                    idx += 2;
+                } else {
+                    idx += 1;
+                }
+            } else {
+                idx += 1;
+            }
+            continue;
+        }
+
+        rest_args.push(arg.clone());
+        idx += 1;
+    }
+
+    normalized.extend(global_args);
+    normalized.extend(rest_args);
+    normalized
+}
+
+fn normalize_endpoint_url(input: &str) -> String {
+    match alias::resolve(input) {
+        Ok(Some(alias)) => return alias.url,
+        Ok(None) => {}
+        Err(e) => warn!("Ignoring endpoint aliases: {:#}", e),
+    }
+    let url = match infer_scheme_for_endpoint(input) {
+        Some(scheme) => format!("{}://{}", scheme, input),
+        None => input.to_string(),
+    };
+    idn::to_ascii_endpoint(&url)
+}
+
+fn infer_scheme_for_endpoint(input: &str) -> Option<&'static str> {
+    if input.is_empty()
+        || input.contains("://")
+        || input.chars().any(char::is_whitespace)
+        || input.starts_with('-')
+        || input.starts_with('/')
+        || input.starts_with("./")
+        || input.starts_with("../")
+        || input.starts_with('~')
+        || input.contains('\\')
+        || looks_like_operation_id(input)
+    {
+        return None;
+    }
+
+    let parsed = url::Url::parse(&format!("http://{}", input)).ok()?;
+    let host = parsed.host_str()?;
+    let is_ip = host.parse::<IpAddr>().is_ok();
+    let is_local = host.eq_ignore_ascii_case("localhost") || host.ends_with(".local");
+    let has_dot = host.contains('.');
+
+    // Keep short single-segment tokens unchanged (e.g. operation IDs or aliases).
+    if !(has_dot || is_local || is_ip) {
+        return None;
+    }
+
+    let has_non_root_path = parsed.path() != "/";
+    let has_explicit_port = parsed.port().is_some();
+
+    // host:port without path is ambiguous (could be gRPC/MCP/http); require explicit scheme.
+    if has_explicit_port && !has_non_root_path && !is_local && !is_ip {
+        return None;
+    }
+
+    if is_local || is_ip {
+        Some("http")
+    } else {
+        Some("https")
+    }
+}
+
+fn looks_like_operation_id(input: &str) -> bool {
+    let lower = input.to_ascii_lowercase();
+    [
+        "get:/",
+        "post:/",
+        "put:/",
+        "patch:/",
+        "delete:/",
+        "head:/",
+        "options:/",
+        "trace:/",
+    ]
+    .iter()
+    .any(|prefix| lower.starts_with(prefix))
+        || lower.starts_with("query/")
+        || lower.starts_with("mutation/")
+        || lower.starts_with("subscription/")
+}
+
+async fn execute_cli(cli: &Cli) -> Result<OutputEnvelope> {
+    if should_show_global_help(cli) {
+        return global_help_envelope();
+    }
+
+    load_env_file(cli.env_file.as_deref())?;
+    output::set_utc_timestamps(cli.utc);
+    let offline_env =
+        env_file::var(cache::OFFLINE_ENV).is_some_and(|v| cache::offline_env_enabled(&v));
+    cache::set_offline(cli.offline || offline_env || cli.replay.is_some());
+    trace::set_enabled(cli.verbose);
+    if let Some(path) = &cli.record {
+        cassette::start_recording(std::path::Path::new(path))?;
+        explain::record("replay", format!("calls recorded to {}", path));
+    }
+    if let Some(path) = &cli.replay {
+        cassette::start_replay(std::path::Path::new(path))?;
+        explain::record(
+            "replay",
+            format!(
+                "calls and schemas come from {}; the network is not used",
+                path
+            ),
+        );
+    }
+    if cli.offline || offline_env {
+        explain::record(
+            "cache",
+            format!(
+                "offline mode ({}): schemas come from the cache only",
+                if cli.offline {
+                    "--offline"
+                } else {
+                    cache::OFFLINE_ENV
+                }
+            ),
+        );
+    }
+    let as_of = cli.as_of.as_deref().map(cache::parse_as_of).transpose()?;
+    if let Some(date) = &cli.as_of {
+        explain::record(
+            "cache",
+            format!("--as-of {}: schemas come from snapshots", date),
+        );
+    }
+    allowlist::set_allowed_hosts(cli.allowed_hosts.as_deref());
+    http::set_redirect_policy(http::RedirectPolicy {
+        follow: !cli.no_follow_redirects,
+        max_redirects: cli.max_redirects.unwrap_or(http::DEFAULT_MAX_REDIRECTS),
+        keep_auth: cli.keep_auth_on_redirect,
+    });
+    configure_proxy(cli)?;
+    configure_response_limit(cli)?;
+
+    let mut cache_config = if cli.no_cache {
+        explain::record("cache", "schema cache bypassed: --no-cache");
+        CacheConfig {
+            enabled: false,
+            ..Default::default()
+        }
+    } else if let Some(ttl) = cli.cache_ttl {
+        explain::record(
+            "cache",
+            format!("schema cache TTL {}s from --cache-ttl", ttl),
+        );
+        CacheConfig {
+            ttl,
+            ..load_cache_config()?
+        }
+    } else {
+        load_cache_config()?
+    };
+    if cli.stale_while_revalidate {
+        cache_config.stale_while_revalidate = true;
+    }
+    cache_config.as_of = as_of;
+    if !cli.no_cache && !cache_config.enabled {
+        explain::record("cache", "schema cache disabled in the cache config file");
+    }
+
+    if let Some(Commands::Cache { cache_command }) = &cli.command {
+        let envelope = handle_cache_command(cli, cache_command, cache_config).await?;
+        if matches!(cache_command, CacheCommands::Clear { .. }) {
+            daemon::flush().await;
+        }
+        return Ok(envelope);
+    }
+
+    if let Some(Commands::Auth { auth_command }) = &cli.command {
+        return handle_auth_command(auth_command, cli.output.as_deref()).await;
+    }
+
+    if let Some(Commands::Test {
+        suite,
+        junit,
+        no_compensate,
+        batch,
+    }) = &cli.command
+    {
+        return handle_test_command(
+            cli,
+            suite,
+            junit.as_deref(),
+            &batch.options(cli)?,
+            !no_compensate,
+            cache_config,
+        )
+        .await;
+    }
+
+    if let Some(Commands::Run {
+        file,
+        concurrency,
+        batch,
+    }) = &cli.command
+    {
+        return handle_run_command(cli, file, *concurrency, &batch.options(cli)?, cache_config)
+            .await;
+    }
+
+    if let Some(Commands::Support) = &cli.command {
+        return support_envelope();
+    }
+
+    if let Some(Commands::Detect {
+        endpoint,
+        all,
+        report,
+    }) = &cli.command
+    {
+        return handle_detect_command(cli, endpoint, *all, *report, cache_config).await;
+    }
+
+    if let Some(Commands::Export {
+        collection_format,
+        endpoint,
+    }) = &cli.command
+    {
+        return handle_export_command(
+            cli,
+            *collection_format,
+            endpoint,
+            cli.output.as_deref(),
+            cache_config,
+        )
+        .await;
+    }
+
+    if let Some(Commands::Import {
+        import_format,
+        source,
+        save,
+        force,
+    }) = &cli.command
+    {
+        return match import_format {
+            ImportFormat::Curl | ImportFormat::Httpie => {
+                handle_command_import(
+                    cli,
+                    *import_format,
+                    source,
+                    save.as_deref(),
+                    *force,
+                    cache_config,
+                )
+                .await
+            }
+            ImportFormat::Postman | ImportFormat::Insomnia => {
+                if save.is_some() {
+                    return Err(UxcError::InvalidArguments(
+                        "--save is only supported for curl and httpie imports".to_string(),
+                    )
+                    .into());
+                }
+                handle_import_command(*import_format, source, *force)
+            }
+        };
+    }
+
+    if let Some(Commands::Template { template_command }) = &cli.command {
+        return handle_template_command(cli, template_command, cache_config).await;
+    }
+
+    if let Some(Commands::Browse { endpoint }) = &cli.command {
+        return handle_browse_command(cli, endpoint, cache_config).await;
+    }
+
+    if let Some(Commands::GenData {
+        endpoint,
+        operation_id,
+        count,
+        seed,
+    }) = &cli.command
+    {
+        return handle_gen_data_command(cli, endpoint, operation_id, *count, *seed, cache_config)
+            .await;
+    }
+
+    if let Some(Commands::Diff { before, after }) = &cli.command {
+        return handle_diff_command(cli, before, after.as_deref(), cache_config).await;
+    }
+
+    if let Some(Commands::Codegen { lang, endpoint }) = &cli.command {
+        return handle_codegen_command(
+            cli,
+            (*lang).into(),
+            endpoint,
+            cli.output.as_deref(),
+            cache_config,
+        )
+        .await;
+    }
+
+    if let Some(Commands::Bench {
+        endpoint,
+        operation_id,
+        args,
+        json,
+        concurrency,
+        duration,
+        requests,
+    }) = &cli.command
+    {
+        let options = BenchOptions {
+            concurrency: *concurrency as usize,
+            duration: *duration,
+            requests: *requests,
+        };
+        let json = json.as_deref().map(arguments::read_payload).transpose()?;
+        return handle_bench_command(
+            cli,
+            endpoint,
+            operation_id,
+            args.clone(),
+            json,
+            &options,
+            cache_config,
+        )
+        .await;
+    }
+
+    if let Some(Commands::Mock {
+        source,
+        port,
+        host,
+        seed,
+    }) = &cli.command
+    {
+        return handle_mock_command(cli, source, (*host, *port).into(), *seed, cache_config).await;
+    }
+
+    if let Some(Commands::Serve {
+        serve_command:
+            ServeCommands::Http {
+                endpoint,
+                port,
+                host,
+                metrics_port,
+            },
+    }) = &cli.command
+    {
+        let options = gateway::GatewayOptions {
+            addr: (*host, *port).into(),
+            metrics: None,
+            metrics_addr: metrics_port.map(|port| (*host, port).into()),
+        };
+        return handle_serve_http_command(cli, endpoint, options, cache_config).await;
+    }
+
+    if let Some(Commands::History { history_command }) = &cli.command {
+        return handle_history_command(history_command);
+    }
+
+    if let Some(Commands::Alias { alias_command }) = &cli.command {
+        return handle_alias_command(cli, alias_command);
+    }
+
+    if let Some(Commands::Project { project_command }) = &cli.command {
+        return handle_project_command(project_command);
+    }
+
+    if let Some(Commands::Plugin { plugin_command }) = &cli.command {
+        return handle_plugin_command(plugin_command);
+    }
+
+    if let Some(Commands::Config { config_command }) = &cli.command {
+        let envelope = handle_config_command(config_command)?;
+        if !matches!(
+            config_command,
+            ConfigCommands::List | ConfigCommands::Get { .. }
+        ) {
+            daemon::flush().await;
+        }
+        return Ok(envelope);
+    }
+
+    if let Some(Commands::Daemon { daemon_command }) = &cli.command {
+        return handle_daemon_command(daemon_command).await;
+    }
+
+    let url = cli
+        .url
+        .clone()
+        .ok_or_else(|| UxcError::InvalidArguments("URL is required".to_string()))?;
+    let url = normalize_endpoint_url(&substitute_variables(&url)?);
+
+    info!("UXC v{} - connecting to {}", env!("CARGO_PKG_VERSION"), url);
+
+    let endpoint_command = resolve_endpoint_command(cli)?;
+    let operation = endpoint_command.operation_id().map(ToString::to_string);
+    // Recorded before substitution so history never holds resolved secrets
+    let recorded_args = match &endpoint_command {
+        EndpointCommand::Execute { args, json, .. } => {
+            collect_arguments(args.clone(), json.clone()).ok()
+        }
+        _ => None,
+    };
+    let prepared = async {
+        allowlist::enforce(&url, operation.as_deref())?;
+        let auth_profile = load_auth_profile(cli.profile.clone())?;
+        prepare_adapter(cli, &url, cache_config, auth_profile).await
+    }
+    .await;
+    let adapter = match prepared {
+        Ok(adapter) => adapter,
+        Err(err) => {
+            let next = error_next_actions(&err, &url, operation.as_deref(), None).await;
+            return Err(with_next_actions(err, next));
+        }
+    };
+
+    if matches!(cli.command, Some(Commands::Query { .. }))
+        && adapter.protocol_type() != ProtocolType::GraphQL
+    {
+        return Err(UxcError::InvalidArguments(format!(
+            "'query' only runs GraphQL documents (detected {})",
+            adapter.protocol_type().as_str()
+        ))
+        .into());
+    }
+    let is_call = matches!(endpoint_command, EndpointCommand::Execute { .. });
+    if !is_call
+        && (cli.raw
+            || cli.dry_run
+            || cli.export.is_some()
+            || cli.include_headers.is_some()
+            || !cli.expect_headers.is_empty()
+            || cli.validate_response
+            || cli.paginate
+            || cli.watch.is_some())
+    {
+        return Err(UxcError::InvalidArguments(
+            "--raw, --dry-run, --export, --include-headers, --expect-header, --validate-response, --paginate and --watch only apply to operation calls"
+                .to_string(),
+        )
+        .into());
+    }
+    let call_options = CallOptions::from_cli(cli)?;
+    let started = std::time::Instant::now();
+    let result = execute_endpoint_command(&adapter, &url, endpoint_command, &call_options).await;
+    // Dry runs and exports send nothing, so there is no call to record
+    if is_call && !cli.dry_run && cli.export.is_none() {
+        let profile = select_profile(cli.profile.clone())
+            .ok()
+            .filter(|(_, explicit, _)| *explicit)
+            .map(|(name, ..)| name);
+        let recorded_args = recorded_args.map(|args| args.into_iter().collect());
+        record_call(
+            &url,
+            operation.as_deref(),
+            result.as_ref().map(|_| ()),
+            started.elapsed(),
+            recorded_args.as_ref(),
+            profile.as_deref(),
+        );
+    }
+    match result {
+        Ok(mut envelope) => {
+            if !is_call && cache::served_stale() {
+                mark_stale_schema(&mut envelope);
+            }
+            Ok(envelope)
+        }
+        Err(err) => {
+            let next = error_next_actions(&err, &url, operation.as_deref(), Some(&adapter)).await;
+            Err(with_next_actions(err, next))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer_scheme_for_endpoint, normalize_endpoint_url};
+    use uxc_core::interop::command::shell_word;
+
+    #[test]
+    fn infer_scheme_for_public_host() {
+        assert_eq!(
+            normalize_endpoint_url("petstore3.swagger.io/api/v3"),
+            "https://petstore3.swagger.io/api/v3"
+        );
+        assert_eq!(
+            normalize_endpoint_url("petstore3.swagger.io"),
+            "https://petstore3.swagger.io"
+        );
+    }
+
+    #[test]
+    fn infer_http_for_local_hosts() {
+        assert_eq!(
+            normalize_endpoint_url("localhost:8080/graphql"),
+            "http://localhost:8080/graphql"
+        );
+        assert_eq!(
+            normalize_endpoint_url("127.0.0.1:8080"),
+            "http://127.0.0.1:8080"
+        );
+    }
+
+    #[test]
+    fn keep_explicit_or_non_http_targets_unchanged() {
+        assert_eq!(
+            normalize_endpoint_url("https://petstore3.swagger.io/api/v3"),
+            "https://petstore3.swagger.io/api/v3"
+        );
+        assert_eq!(normalize_endpoint_url("mcp://server"), "mcp://server");
+        assert_eq!(normalize_endpoint_url("post:/pet"), "post:/pet");
+        assert_eq!(normalize_endpoint_url("query/viewer"), "query/viewer");
+    }
+
+    #[test]
+    fn convert_unicode_hosts_to_punycode() {
+        assert_eq!(
+            normalize_endpoint_url("例え.テスト/api"),
+            "https://xn--r8jz45g.xn--zckzah/api"
+        );
+        assert_eq!(
+            normalize_endpoint_url("https://例え.テスト"),
+            "https://xn--r8jz45g.xn--zckzah"
+        );
+        assert_eq!(
+            normalize_endpoint_url("grpc.例え.テスト:9000"),
+            "grpc.xn--r8jz45g.xn--zckzah:9000"
+        );
+    }
+
+    #[test]
+    fn skip_ambiguous_host_port_without_path() {
+        assert_eq!(infer_scheme_for_endpoint("grpcb.in:9000"), None);
+    }
+
+    #[test]
+    fn shell_word_quotes_only_when_needed() {
+        assert_eq!(shell_word("get:/users"), "get:/users");
+        assert_eq!(shell_word("get:/users/{id}"), "'get:/users/{id}'");
+        assert_eq!(shell_word("it's"), "'it'\\''s'");
+    }
+}
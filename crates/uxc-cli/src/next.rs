@@ -0,0 +1,289 @@
+//! `next` suggested commands for envelopes and errors
+
+use serde_json::Value;
+
+use crate::data::SuggestedError;
+use uxc_core::adapters::{self, Adapter};
+use uxc_core::error::{ErrorDetail, UxcError};
+use uxc_core::interop::command::shell_word;
+use uxc_core::masking;
+use uxc_core::output::{NextAction, OutputEnvelope};
+use uxc_core::suggest;
+
+/// Follow-up commands for a successful envelope, based on its kind.
+pub(crate) fn default_next_actions(envelope: &OutputEnvelope) -> Vec<NextAction> {
+    let endpoint = shell_word(envelope.endpoint.as_deref().unwrap_or("<host>"));
+    let data = envelope.data.as_ref().unwrap_or(&Value::Null);
+    let first_operation = data
+        .pointer("/operations/0/operation_id")
+        .and_then(Value::as_str);
+    let names = |pointer: &str| -> Vec<String> {
+        data.pointer(pointer)
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().or_else(|| item["name"].as_str()))
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    match envelope.kind.as_deref() {
+        Some("global_help") => vec![NextAction::new(
+            "uxc <host> list",
+            "List the operations of an endpoint",
+        )],
+        Some("host_help") | Some("operation_list") | Some("operation_search") => first_operation
+            .map(|op| {
+                vec![NextAction::new(
+                    format!("uxc {} describe {}", endpoint, shell_word(op)),
+                    "Show parameters and input schema",
+                )]
+            })
+            .unwrap_or_default(),
+        Some("operation_detail") => {
+            let Some(op) = envelope.operation.as_deref() else {
+                return Vec::new();
+            };
+            let required = data
+                .get("parameters")
+                .and_then(Value::as_array)
+                .map(|params| {
+                    params
+                        .iter()
+                        .filter(|param| param["required"].as_bool().unwrap_or(false))
+                        .filter_map(|param| {
+                            let name = param["name"].as_str()?;
+                            let hint = param["param_type"].as_str().unwrap_or("value");
+                            Some((name.to_string(), Value::String(format!("<{}>", hint))))
+                        })
+                        .collect::<serde_json::Map<_, _>>()
+                })
+                .unwrap_or_default();
+            let mut command = format!("uxc {} {}", endpoint, shell_word(op));
+            if !required.is_empty() {
+                command.push_str(&format!(
+                    " --json {}",
+                    shell_word(&Value::Object(required).to_string())
+                ));
+            }
+            vec![NextAction::new(
+                command,
+                "Call the operation with its required arguments",
+            )]
+        }
+        Some("operation_example") => {
+            let Some(op) = envelope.operation.as_deref() else {
+                return Vec::new();
+            };
+            vec![NextAction::new(
+                format!(
+                    "uxc {} {} --json {}",
+                    endpoint,
+                    shell_word(op),
+                    shell_word(&data.to_string())
+                ),
+                "Call the operation with the example, edited",
+            )]
+        }
+        Some("operation_match") => {
+            let Some(op) = envelope.operation.as_deref() else {
+                return Vec::new();
+            };
+            let mut command = format!("uxc {} {}", endpoint, shell_word(op));
+            if let Some(args) = data["path_args"]
+                .as_object()
+                .filter(|args| !args.is_empty())
+            {
+                command.push_str(&format!(
+                    " --json {}",
+                    shell_word(&Value::Object(args.clone()).to_string())
+                ));
+            }
+            vec![NextAction::new(
+                command,
+                "Call the operation with the captured path parameters",
+            )]
+        }
+        Some("inspect_result") => vec![NextAction::new(
+            format!("uxc {} list", endpoint),
+            "List available operations",
+        )],
+        Some("auth_list") if data["count"].as_u64() == Some(0) => vec![NextAction::new(
+            "uxc auth set default --api-key <key>",
+            "Create a default profile",
+        )],
+        Some("auth_import_result") => names("/needs_secret")
+            .into_iter()
+            .map(|name| {
+                NextAction::new(
+                    format!("uxc auth set {} --api-key <key>", shell_word(&name)),
+                    "Imported without its secret",
+                )
+            })
+            .collect(),
+        Some("import_result") => names("/imported")
+            .first()
+            .map(|name| {
+                vec![NextAction::new(
+                    format!("uxc template run {}", shell_word(name)),
+                    "Run an imported request",
+                )]
+            })
+            .unwrap_or_default(),
+        Some("request_import") => data["saved_as"]
+            .as_str()
+            .map(|name| {
+                vec![NextAction::new(
+                    format!("uxc template run {}", shell_word(name)),
+                    "Run the saved template",
+                )]
+            })
+            .unwrap_or_default(),
+        Some("history_entry") => match (data["id"].as_str(), data["operation"].as_str()) {
+            (Some(id), Some(_)) if data.get("args").is_some() => {
+                let redacted = names("/redacted")
+                    .iter()
+                    .map(|name| format!(" -a {}=<value>", shell_word(name)))
+                    .collect::<String>();
+                vec![NextAction::new(
+                    format!("uxc history rerun {}{}", shell_word(id), redacted),
+                    "Run the call again",
+                )]
+            }
+            _ => Vec::new(),
+        },
+        Some("dry_run") => match envelope.operation.as_deref() {
+            Some(op) if !data["arguments"].to_string().contains(masking::MASK) => {
+                let mut command = format!("uxc {} {}", endpoint, shell_word(op));
+                if data["arguments"]
+                    .as_object()
+                    .is_some_and(|args| !args.is_empty())
+                {
+                    command.push_str(&format!(
+                        " --json {}",
+                        shell_word(&data["arguments"].to_string())
+                    ));
+                }
+                vec![NextAction::new(command, "Send the request")]
+            }
+            _ => Vec::new(),
+        },
+        Some("config_set") => vec![NextAction::new(
+            "uxc config list",
+            "Show every setting and where it comes from",
+        )],
+        Some("alias_set") => data["name"]
+            .as_str()
+            .map(|name| {
+                vec![NextAction::new(
+                    format!("uxc {} list", shell_word(name)),
+                    "List the aliased endpoint's operations",
+                )]
+            })
+            .unwrap_or_default(),
+        Some("template_list") => names("/templates")
+            .first()
+            .map(|name| {
+                vec![NextAction::new(
+                    format!("uxc template show {}", shell_word(name)),
+                    "Show the template",
+                )]
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Attach suggested next commands to an error, if there are any.
+pub(crate) fn with_next_actions(error: anyhow::Error, next: Vec<NextAction>) -> anyhow::Error {
+    if next.is_empty() {
+        return error;
+    }
+    anyhow::Error::new(SuggestedError { error, next })
+}
+
+/// Suggested recovery commands for a failed endpoint command.
+///
+/// For an unknown operation the closest operation IDs are suggested when the
+/// adapter is available.
+pub(crate) async fn error_next_actions(
+    err: &anyhow::Error,
+    url: &str,
+    operation: Option<&str>,
+    adapter: Option<&adapters::AdapterEnum>,
+) -> Vec<NextAction> {
+    let endpoint = shell_word(url);
+    let describe = |op: &str, description: String| {
+        NextAction::new(
+            format!("uxc {} describe {}", endpoint, shell_word(op)),
+            description,
+        )
+    };
+
+    match (error_code(err), operation) {
+        ("OPERATION_NOT_FOUND", Some(operation)) => {
+            let mut next = Vec::new();
+            if let Some(adapter) = adapter {
+                if let Ok(operations) = adapter.list_operations(url).await {
+                    let ids = operations
+                        .iter()
+                        .map(|op| op.operation_id.as_str())
+                        .collect::<Vec<_>>();
+                    next.extend(
+                        suggest::closest_matches(operation, &ids, 3)
+                            .into_iter()
+                            .map(|id| describe(id, format!("Did you mean '{}'?", id))),
+                    );
+                }
+            }
+            next.push(NextAction::new(
+                format!("uxc {} list", endpoint),
+                "List available operations",
+            ));
+            next
+        }
+        ("INVALID_ARGUMENT", Some(operation)) | ("EXECUTION_FAILED", Some(operation)) => {
+            vec![describe(
+                operation,
+                "Check the operation's parameters".to_string(),
+            )]
+        }
+        ("OPERATION_NOT_FOUND", None) => vec![NextAction::new(
+            format!("uxc {} list", endpoint),
+            "List available operations",
+        )],
+        ("PROTOCOL_DETECTION_FAILED", _) => vec![NextAction::new(
+            format!("uxc {} --schema-url <schema_url> list", endpoint),
+            "Point to the OpenAPI schema, or the GraphQL SDL, if it is served elsewhere",
+        )],
+        ("OFFLINE_MISS", _) => vec![NextAction::new(
+            format!("uxc {} list", endpoint),
+            "Run once online to fetch and cache the schema",
+        )],
+        ("SNAPSHOT_NOT_FOUND", _) => vec![NextAction::new(
+            format!("uxc {} list", endpoint),
+            "Drop --as-of to use the current schema",
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Protocol detail of the first [`UxcError::CallFailed`] in the chain
+pub(crate) fn error_detail(err: &anyhow::Error) -> Option<ErrorDetail> {
+    err.chain()
+        .find_map(|cause| match cause.downcast_ref::<UxcError>() {
+            Some(UxcError::CallFailed { detail, .. }) => Some(detail.clone()),
+            _ => None,
+        })
+}
+
+pub(crate) fn error_code(err: &anyhow::Error) -> &'static str {
+    if let Some(suggested) = err.downcast_ref::<SuggestedError>() {
+        return error_code(&suggested.error);
+    }
+
+    uxc_core::error::code_of(err)
+}
@@ -0,0 +1,1169 @@
+//! Text and CSV rendering of envelopes (`--text`, `--format csv`)
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::cli::OutputMode;
+use crate::commands::config::setting_text;
+use crate::daemon;
+use crate::data::{
+    AliasListData, AliasRemoveData, AliasView, AuthExportData, AuthImportData, AuthListData,
+    AuthOAuthData, AuthProfileView, AuthRemoveData, AuthSessionData, BrowseSessionData,
+    CacheClearData, CacheCompactData, CacheWarmData, CollectionExportData, ConfigListData,
+    DaemonStatusData, DaemonStopData, DetectData, DryRunData, ExportData, GatewaySession,
+    GeneratedClientData, GeneratedData, HistorySearchData, HistoryStatsData, HostHelpData,
+    ImportResultData, MockSession, OperationListData, OperationMatchData, OperationSummary,
+    OutputFileData, PluginListData, RequestImportData, SupportMatrixData, TemplateListData,
+    TemplateRemoveData,
+};
+use crate::help::{print_global_help, print_support_matrix};
+use uxc_core::adapters::transcoding::HttpBinding;
+use uxc_core::adapters::{self, OperationDetail, RequestPlan};
+use uxc_core::auth::scheme::SecurityScheme;
+use uxc_core::auth::transfer;
+use uxc_core::bench::BenchReport;
+use uxc_core::cache;
+use uxc_core::config::Setting;
+use uxc_core::error::UxcError;
+use uxc_core::history::HistoryEntry;
+use uxc_core::idn;
+use uxc_core::output::{self, OutputEnvelope};
+use uxc_core::plugins;
+use uxc_core::request_file::RunReport;
+use uxc_core::schema_diff::SchemaDiff;
+use uxc_core::suite::SuiteReport;
+
+pub(crate) fn render_output(envelope: &OutputEnvelope, output_mode: OutputMode) -> Result<()> {
+    match output_mode {
+        OutputMode::Json => print_json(envelope),
+        OutputMode::Text => render_text_output(envelope),
+        OutputMode::Csv => print_csv(envelope, None),
+    }
+}
+
+/// CSV rendering of envelope data. Errors go to stderr as in text mode, so
+/// stdout only ever holds rows.
+pub(crate) fn print_csv(envelope: &OutputEnvelope, columns: Option<&[String]>) -> Result<()> {
+    let data = envelope.data.as_ref().unwrap_or(&Value::Null);
+    print!("{}", output::to_csv(data, columns)?);
+    Ok(())
+}
+
+/// Text rendering of `--query` results: strings unquoted, scalar lists one per
+/// line, anything else as pretty JSON.
+pub(crate) fn print_query_result(data: Option<&Value>) {
+    let scalar = |value: &Value| match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(_) | Value::Object(_) => None,
+        other => Some(other.to_string()),
+    };
+    match data {
+        None => {}
+        Some(Value::Array(items)) if items.iter().all(|item| scalar(item).is_some()) => {
+            for item in items {
+                println!("{}", scalar(item).unwrap_or_default());
+            }
+        }
+        Some(value) => match scalar(value) {
+            Some(text) => println!("{}", text),
+            None => println!(
+                "{}",
+                serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+            ),
+        },
+    }
+}
+
+/// Print a planned request like `curl -v` shows one: the request line,
+/// headers, then the body
+fn print_request_plan(plan: &RequestPlan) -> Result<()> {
+    let line = match (plan.transport.as_str(), &plan.method) {
+        ("http", Some(method)) => format!("{} {}", method, plan.target),
+        (transport, Some(method)) => format!("{} {} {}", transport, plan.target, method),
+        (transport, None) => format!("{} {}", transport, plan.target),
+    };
+    println!("> {}", line);
+    for header in &plan.headers {
+        println!("> {}", header);
+    }
+    for note in &plan.notes {
+        println!("* {}", note);
+    }
+    if let Some(body) = &plan.body {
+        println!();
+        match body {
+            Value::String(text) => println!("{}", text),
+            body => println!("{}", serde_json::to_string_pretty(body)?),
+        }
+    }
+    Ok(())
+}
+
+fn render_text_output(envelope: &OutputEnvelope) -> Result<()> {
+    if !envelope.ok {
+        if let Some(err) = &envelope.error {
+            println!("{}", err.message);
+        }
+        return Ok(());
+    }
+
+    match envelope.kind.as_deref() {
+        Some("global_help") => print_global_help(),
+        Some("host_help") => {
+            let endpoint = envelope.endpoint.as_deref().unwrap_or("unknown");
+            let protocol = envelope.protocol.as_deref().unwrap_or("unknown");
+            let data: HostHelpData = decode_envelope_data(envelope)?;
+            print_host_help_text_from_summaries(protocol, endpoint, &data);
+            Ok(())
+        }
+        Some("operation_list") | Some("operation_search") => {
+            let protocol = envelope.protocol.as_deref().unwrap_or("unknown");
+            let data: OperationListData = decode_envelope_data(envelope)?;
+            print_list_text_from_summaries(protocol, &data.operations, data.verbose);
+            Ok(())
+        }
+        Some("operation_detail") => {
+            let endpoint = envelope.endpoint.as_deref().unwrap_or("unknown");
+            let protocol = envelope.protocol.as_deref().unwrap_or("unknown");
+            let detail: OperationDetail = decode_envelope_data(envelope)?;
+            print_detail_text(protocol, endpoint, &detail);
+            Ok(())
+        }
+        Some("operation_match") => {
+            let endpoint = envelope.endpoint.as_deref().unwrap_or("unknown");
+            let protocol = envelope.protocol.as_deref().unwrap_or("unknown");
+            let data: OperationMatchData = decode_envelope_data(envelope)?;
+            println!("Matched: {}", data.operation_id);
+            for (name, value) in &data.path_args {
+                println!("  {} = {}", name, value.as_str().unwrap_or_default());
+            }
+            println!();
+            print_detail_text(protocol, endpoint, &data.detail);
+            Ok(())
+        }
+        Some("inspect_result") => {
+            let protocol = envelope.protocol.as_deref().unwrap_or("unknown");
+            let endpoint = envelope.endpoint.as_deref().unwrap_or("unknown");
+            let data = envelope.data.clone().unwrap_or(Value::Null);
+            println!("Protocol: {}", protocol);
+            println!("Endpoint: {}", idn::to_unicode_endpoint(endpoint));
+            if let Some(schema) = data.get("schema").filter(|v| !v.is_null()) {
+                println!("\nSchema:\n{}", serde_json::to_string_pretty(schema)?);
+            }
+            Ok(())
+        }
+        Some("dry_run") => {
+            let data: DryRunData = decode_envelope_data(envelope)?;
+            print_request_plan(&data.request)?;
+            Ok(())
+        }
+        Some("export") => {
+            let data: ExportData = decode_envelope_data(envelope)?;
+            println!("{}", data.command);
+            Ok(())
+        }
+        Some("call_result") => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&envelope.data.clone().unwrap_or(Value::Null))?
+            );
+            Ok(())
+        }
+        Some("cache_stats") => {
+            let stats: cache::CacheStats = decode_envelope_data(envelope)?;
+            println!("{}", stats.display());
+            Ok(())
+        }
+        Some("cache_clear_result") => {
+            let data: CacheClearData = decode_envelope_data(envelope)?;
+            if data.scope == "all" {
+                println!("Cache cleared successfully.");
+            } else if data.scope == "detection" {
+                match (data.url, data.removed) {
+                    (Some(url), _) => println!("Detection results cleared for: {}", url),
+                    (None, Some(removed)) => {
+                        println!("Cleared {} cached detection results.", removed)
+                    }
+                    (None, None) => println!("Detection results cleared."),
+                }
+            } else if let Some(url) = data.url {
+                println!("Cache entry cleared for: {}", url);
+            } else {
+                println!("Cache cleared.");
+            }
+            Ok(())
+        }
+        Some("output_file") => {
+            let data: OutputFileData = decode_envelope_data(envelope)?;
+            println!(
+                "Wrote {} to {}",
+                output::format_size(data.bytes as u64),
+                data.path
+            );
+            Ok(())
+        }
+        Some("detect_result") => {
+            let data: DetectData = decode_envelope_data(envelope)?;
+            if data.protocols.is_empty() {
+                println!("No protocol detected at {}", data.url);
+            }
+            for protocol in &data.protocols {
+                let marker = if data.selected.as_ref() == Some(protocol) {
+                    " (selected)"
+                } else {
+                    ""
+                };
+                println!("{}{}", protocol, marker);
+            }
+            Ok(())
+        }
+        Some("detect_report") => {
+            let data: adapters::probe::DetectionReport = decode_envelope_data(envelope)?;
+            for probe in &data.probes {
+                let outcome = match (&probe.error, probe.matched) {
+                    (Some(error), _) => format!("error: {}", error),
+                    (None, true) => "matched".to_string(),
+                    (None, false) => "no match".to_string(),
+                };
+                println!("{}: {} ({}ms)", probe.adapter, outcome, probe.duration_ms);
+                for note in &probe.evidence {
+                    println!("  - {}", note);
+                }
+            }
+            match &data.selected {
+                Some(selected) => println!("Selected: {}", selected),
+                None => println!("No protocol detected at {}", data.url),
+            }
+            Ok(())
+        }
+        Some("support_matrix") => {
+            let data: SupportMatrixData = decode_envelope_data(envelope)?;
+            print_support_matrix(&data.protocols);
+            Ok(())
+        }
+        Some("cache_compact_result") => {
+            let data: CacheCompactData = decode_envelope_data(envelope)?;
+            println!("Cache compacted, removed {} entries.", data.removed);
+            Ok(())
+        }
+        Some("cache_warm_result") => {
+            let data: CacheWarmData = decode_envelope_data(envelope)?;
+            for endpoint in &data.endpoints {
+                let status = if endpoint.ok {
+                    "ok"
+                } else if endpoint.skipped {
+                    "skipped"
+                } else if endpoint.timed_out {
+                    "timeout"
+                } else {
+                    "failed"
+                };
+                let mut line = format!("  {:<8}{}", status, endpoint.url);
+                if let Some(protocol) = &endpoint.protocol {
+                    line.push_str(&format!(" ({})", protocol));
+                }
+                if let Some(error) = &endpoint.error {
+                    line.push_str(&format!(": [{}] {}", error.code, error.message));
+                }
+                if !endpoint.skipped {
+                    line.push_str(&format!(
+                        " in {}",
+                        output::format_duration(endpoint.duration_ms)
+                    ));
+                }
+                if endpoint.attempts > 1 {
+                    line.push_str(&format!(" after {} attempts", endpoint.attempts));
+                }
+                println!("{}", line);
+            }
+            println!(
+                "\nWarmed {} endpoints, {} failed ({} timed out), {} skipped.",
+                data.summary.succeeded,
+                data.summary.failed,
+                data.summary.timed_out,
+                data.summary.skipped
+            );
+            Ok(())
+        }
+        Some("auth_list") => {
+            let data: AuthListData = decode_envelope_data(envelope)?;
+            if data.profiles.is_empty() {
+                println!("No profiles found.");
+                println!("\nCreate a profile with: uxc auth set <profile> --api-key <key>");
+                return Ok(());
+            }
+
+            println!("Authentication Profiles:\n");
+            for profile in data.profiles {
+                println!("  {}", profile.name);
+                println!("    Type: {}", profile.auth_type);
+                println!("    API Key: {}", profile.api_key_masked);
+                if let Some(desc) = profile.description {
+                    println!("    Description: {}", desc);
+                }
+                println!();
+            }
+            Ok(())
+        }
+        Some("auth_info") | Some("auth_set_result") => {
+            let profile: AuthProfileView = decode_envelope_data(envelope)?;
+            println!("Profile: {}", profile.name);
+            println!("  Type: {}", profile.auth_type);
+            println!("  API Key: {}", profile.api_key_masked);
+            if let Some(login_url) = profile.login_url {
+                println!("  Login URL: {}", login_url);
+            }
+            if let Some(token_url) = profile.token_url {
+                println!("  Token URL: {}", token_url);
+            }
+            if let Some(placement) = profile.api_key_placement {
+                println!("  Sent As: {}", placement);
+            }
+            if let Some(desc) = profile.description {
+                println!("  Description: {}", desc);
+            }
+            Ok(())
+        }
+        Some("auth_login_result") => {
+            let data: AuthSessionData = decode_envelope_data(envelope)?;
+            println!(
+                "Logged in with '{}'; stored cookies for {}: {}",
+                data.profile,
+                data.host,
+                data.cookies.join(", ")
+            );
+            Ok(())
+        }
+        Some("auth_logout_result") => {
+            let data: AuthSessionData = decode_envelope_data(envelope)?;
+            if data.cleared == Some(true) {
+                println!("Cleared session cookies for {}.", data.host);
+            } else {
+                println!("No session cookies stored for {}.", data.host);
+            }
+            Ok(())
+        }
+        Some("auth_oauth_login_result") => {
+            let data: AuthOAuthData = decode_envelope_data(envelope)?;
+            print!(
+                "Logged in with '{}'; stored tokens for {}",
+                data.profile, data.host
+            );
+            if let Some(scope) = data.scope {
+                print!(" (scope: {})", scope);
+            }
+            if let Some(expires_at) = data.expires_at {
+                print!(
+                    ", access token expires {}",
+                    output::format_timestamp(expires_at)
+                );
+            }
+            println!();
+            Ok(())
+        }
+        Some("auth_oauth_logout_result") => {
+            let data: AuthOAuthData = decode_envelope_data(envelope)?;
+            if data.cleared == Some(true) {
+                println!("Cleared OAuth tokens for {}.", data.host);
+            } else {
+                println!("No OAuth tokens stored for {}.", data.host);
+            }
+            Ok(())
+        }
+        Some("auth_remove_result") => {
+            let data: AuthRemoveData = decode_envelope_data(envelope)?;
+            println!("Profile '{}' removed successfully.", data.profile);
+            Ok(())
+        }
+        Some("auth_export") => {
+            let data: AuthExportData = decode_envelope_data(envelope)?;
+            match (data.path, data.profiles) {
+                (Some(path), _) => println!("Exported {} profiles to {}", data.count, path),
+                (None, Some(profiles)) => print!("{}", transfer::to_toml(&profiles)?),
+                (None, None) => {}
+            }
+            Ok(())
+        }
+        Some("auth_import_result") => {
+            let data: AuthImportData = decode_envelope_data(envelope)?;
+            let summary = data.summary;
+            if data.dry_run {
+                println!("Dry run: no profiles were written.");
+            }
+            for (label, names) in [
+                ("Added", &summary.added),
+                ("Updated", &summary.updated),
+                ("Unchanged", &summary.unchanged),
+                (
+                    "Skipped (use --on-conflict overwrite|rename)",
+                    &summary.skipped,
+                ),
+            ] {
+                if !names.is_empty() {
+                    println!("{}: {}", label, names.join(", "));
+                }
+            }
+            for (from, to) in &summary.renamed {
+                println!("Renamed: {} -> {}", from, to);
+            }
+            for (setting, command) in &summary.commands {
+                println!("Runs a command: {} = {}", setting, command);
+            }
+            if !summary.needs_secret.is_empty() {
+                println!(
+                    "Set API keys with `uxc auth set <profile> --api-key <key>` for: {}",
+                    summary.needs_secret.join(", ")
+                );
+            }
+            Ok(())
+        }
+        Some("collection_export") => {
+            let data: CollectionExportData = decode_envelope_data(envelope)?;
+            match (data.path, data.collection) {
+                (Some(path), _) => {
+                    println!(
+                        "Exported {} requests to {} ({})",
+                        data.count, path, data.format
+                    )
+                }
+                (None, Some(collection)) => {
+                    println!("{}", serde_json::to_string_pretty(&collection)?)
+                }
+                (None, None) => {}
+            }
+            Ok(())
+        }
+        Some("import_result") => {
+            let data: ImportResultData = decode_envelope_data(envelope)?;
+            println!(
+                "Imported {} templates from {}",
+                data.imported.len(),
+                data.source
+            );
+            for name in &data.imported {
+                println!("  {}", name);
+            }
+            if !data.skipped.is_empty() {
+                println!(
+                    "Skipped existing templates (use --force to overwrite): {}",
+                    data.skipped.join(", ")
+                );
+            }
+            Ok(())
+        }
+        Some("request_import") => {
+            let data: RequestImportData = decode_envelope_data(envelope)?;
+            let mut command = format!("uxc {} {}", data.template.endpoint, data.template.operation);
+            if !data.template.args.is_empty() {
+                command.push_str(&format!(
+                    " --json '{}'",
+                    Value::Object(data.template.args.clone())
+                ));
+            }
+            println!("{}", command);
+            if !data.matched {
+                println!("(not matched against an endpoint schema)");
+            }
+            if let Some(name) = data.saved_as {
+                println!("Saved as template '{}'.", name);
+            }
+            Ok(())
+        }
+        Some("template_list") => {
+            let data: TemplateListData = decode_envelope_data(envelope)?;
+            if data.templates.is_empty() {
+                println!("No templates found.");
+                return Ok(());
+            }
+            for view in data.templates {
+                println!(
+                    "{}  {} {}",
+                    view.name, view.template.endpoint, view.template.operation
+                );
+            }
+            Ok(())
+        }
+        Some("browse_session") => {
+            let data: BrowseSessionData = decode_envelope_data(envelope)?;
+            let endpoint = envelope.endpoint.as_deref().unwrap_or("unknown");
+            println!("Made {} calls to {}", data.calls, endpoint);
+            Ok(())
+        }
+        Some("schema_diff") => {
+            let diff: SchemaDiff = decode_envelope_data(envelope)?;
+            if diff.is_empty() {
+                println!("No changes.");
+                return Ok(());
+            }
+            println!(
+                "{} breaking of {} changes ({} operations added, {} removed, {} changed)",
+                diff.breaking,
+                diff.changes.len(),
+                diff.operations_added,
+                diff.operations_removed,
+                diff.operations_changed
+            );
+            for change in &diff.changes {
+                println!("  {}", change);
+            }
+            Ok(())
+        }
+        Some("generated_client") => {
+            let data: GeneratedClientData = decode_envelope_data(envelope)?;
+            match (data.path, data.code) {
+                (Some(path), _) => println!(
+                    "Wrote a {} client with {} operations to {}",
+                    data.language, data.operations, path
+                ),
+                (None, Some(code)) => print!("{}", code),
+                (None, None) => {}
+            }
+            Ok(())
+        }
+        Some("bench_report") => {
+            let report: BenchReport = decode_envelope_data(envelope)?;
+            let ms = |ms: f64| {
+                if ms >= 1000.0 {
+                    format!("{:.2}s", ms / 1000.0)
+                } else {
+                    format!("{:.1}ms", ms)
+                }
+            };
+            println!(
+                "{} calls in {} ({:.1}/s, concurrency {}): {} ok, {} failed",
+                report.requests,
+                output::format_duration(report.duration_ms),
+                report.throughput,
+                report.concurrency,
+                report.succeeded,
+                report.failed
+            );
+            let latency = &report.latency;
+            println!(
+                "Latency: min {}, mean {}, p50 {}, p90 {}, p95 {}, p99 {}, max {}",
+                ms(latency.min_ms),
+                ms(latency.mean_ms),
+                ms(latency.p50_ms),
+                ms(latency.p90_ms),
+                ms(latency.p95_ms),
+                ms(latency.p99_ms),
+                ms(latency.max_ms)
+            );
+            if !report.statuses.is_empty() {
+                let statuses: Vec<String> = report
+                    .statuses
+                    .iter()
+                    .map(|(status, count)| format!("{} x{}", status, count))
+                    .collect();
+                println!("Statuses: {}", statuses.join(", "));
+            }
+            if !report.errors.is_empty() {
+                println!("Errors:");
+                for error in &report.errors {
+                    println!("  {:>6}  {}  {}", error.count, error.code, error.message);
+                }
+            }
+            Ok(())
+        }
+        Some("daemon_status") => {
+            let data: DaemonStatusData = decode_envelope_data(envelope)?;
+            match data.status {
+                Some(status) => {
+                    println!(
+                        "uxc daemon running (pid {}) on {}",
+                        status.pid, status.socket
+                    );
+                    println!("  Directory: {}", status.cwd);
+                    println!(
+                        "  Up {}, {} commands served, {} warm adapters",
+                        output::format_duration(status.uptime_ms),
+                        status.commands_served,
+                        status.warm_adapters
+                    );
+                }
+                None => println!("No uxc daemon is running on {}", data.socket),
+            }
+            Ok(())
+        }
+        Some("daemon_stop") => {
+            let data: DaemonStopData = decode_envelope_data(envelope)?;
+            if data.stopped {
+                println!("Stopped the uxc daemon on {}", data.socket);
+            } else {
+                println!("No uxc daemon is running on {}", data.socket);
+            }
+            Ok(())
+        }
+        Some("daemon_session") => {
+            let data: daemon::DaemonStatus = decode_envelope_data(envelope)?;
+            println!(
+                "Served {} commands on {} from {}",
+                data.commands_served, data.socket, data.cwd
+            );
+            Ok(())
+        }
+        Some("mock_session") => {
+            let data: MockSession = decode_envelope_data(envelope)?;
+            println!(
+                "Served {} requests on http://{} from {}",
+                data.requests_served, data.address, data.source
+            );
+            Ok(())
+        }
+        Some("gateway_session") => {
+            let data: GatewaySession = decode_envelope_data(envelope)?;
+            println!(
+                "Served {} requests on http://{} for {}",
+                data.requests_served,
+                data.address,
+                envelope.endpoint.as_deref().unwrap_or("unknown")
+            );
+            Ok(())
+        }
+        Some("generated_data") => {
+            let data: GeneratedData = decode_envelope_data(envelope)?;
+            println!("{}", serde_json::to_string_pretty(&data.payloads)?);
+            Ok(())
+        }
+        Some("history_search") => {
+            let data: HistorySearchData = decode_envelope_data(envelope)?;
+            if data.entries.is_empty() {
+                println!("No calls found.");
+                return Ok(());
+            }
+            for entry in &data.entries {
+                let mut line = format!(
+                    "{:<8}  {}  {:<8}{} {}",
+                    entry.id.as_deref().unwrap_or("-"),
+                    output::format_timestamp(entry.timestamp),
+                    entry.status.as_str(),
+                    entry.endpoint,
+                    entry.operation.as_deref().unwrap_or("-")
+                );
+                if let Some(duration_ms) = entry.duration_ms {
+                    line.push_str(&format!(" ({})", output::format_duration(duration_ms)));
+                }
+                if let Some(code) = &entry.error_code {
+                    line.push_str(&format!(": [{}]", code));
+                }
+                println!("{}", line);
+            }
+            Ok(())
+        }
+        Some("history_entry") => {
+            let entry: HistoryEntry = decode_envelope_data(envelope)?;
+            println!("ID:        {}", entry.id.as_deref().unwrap_or("-"));
+            println!("Time:      {}", output::format_timestamp(entry.timestamp));
+            println!("Endpoint:  {}", entry.endpoint);
+            println!("Operation: {}", entry.operation.as_deref().unwrap_or("-"));
+            let mut status = entry.status.as_str().to_string();
+            if let Some(duration_ms) = entry.duration_ms {
+                status.push_str(&format!(" ({})", output::format_duration(duration_ms)));
+            }
+            println!("Status:    {}", status);
+            if let Some(profile) = &entry.profile {
+                println!("Profile:   {}", profile);
+            }
+            if let Some(message) = &entry.message {
+                println!(
+                    "Error:     [{}] {}",
+                    entry.error_code.as_deref().unwrap_or("-"),
+                    message
+                );
+            }
+            if let Some(args) = &entry.args {
+                println!("Arguments: {}", serde_json::to_string_pretty(args)?);
+            }
+            if !entry.redacted.is_empty() {
+                println!("Redacted:  {}", entry.redacted.join(", "));
+            }
+            Ok(())
+        }
+        Some("history_stats") => {
+            let data: HistoryStatsData = decode_envelope_data(envelope)?;
+            if data.operations.is_empty() {
+                println!("No calls found.");
+                return Ok(());
+            }
+            let latency = |ms: Option<u64>| ms.map(output::format_duration).unwrap_or("-".into());
+            for stats in &data.operations {
+                println!(
+                    "{} {}",
+                    stats.endpoint,
+                    stats.operation.as_deref().unwrap_or("-")
+                );
+                println!(
+                    "  {} calls, {:.1}% ok, p50 {}, p95 {}, last {}",
+                    stats.calls,
+                    stats.success_rate * 100.0,
+                    latency(stats.p50_ms),
+                    latency(stats.p95_ms),
+                    output::format_timestamp(stats.last_called)
+                );
+                for window in &stats.windows {
+                    println!(
+                        "    {}  {} calls, {:.1}% ok, p50 {}, p95 {}",
+                        output::format_timestamp(window.start),
+                        window.calls,
+                        window.success_rate * 100.0,
+                        latency(window.p50_ms),
+                        latency(window.p95_ms)
+                    );
+                }
+            }
+            Ok(())
+        }
+        Some("alias_list") => {
+            let data: AliasListData = decode_envelope_data(envelope)?;
+            if data.aliases.is_empty() {
+                println!("No aliases found.");
+                return Ok(());
+            }
+            for view in data.aliases {
+                let mut extras = Vec::new();
+                if let Some(profile) = &view.alias.profile {
+                    extras.push(format!("profile={}", profile));
+                }
+                if let Some(schema_url) = &view.alias.schema_url {
+                    extras.push(format!("schema-url={}", schema_url));
+                }
+                if let Some(protocol) = &view.alias.protocol {
+                    extras.push(format!("protocol={}", protocol));
+                }
+                if !view.alias.headers.is_empty() {
+                    extras.push(format!("headers={}", view.alias.headers.len()));
+                }
+                if let Some(path) = &view.alias.hooks.pre_request {
+                    extras.push(format!("pre-request={}", path.display()));
+                }
+                if let Some(path) = &view.alias.hooks.post_response {
+                    extras.push(format!("post-response={}", path.display()));
+                }
+                if extras.is_empty() {
+                    println!("{}  {}", view.name, view.alias.url);
+                } else {
+                    println!("{}  {}  ({})", view.name, view.alias.url, extras.join(", "));
+                }
+            }
+            Ok(())
+        }
+        Some("alias_set") => {
+            let data: AliasView = decode_envelope_data(envelope)?;
+            println!("Alias '{}' -> {}", data.name, data.alias.url);
+            Ok(())
+        }
+        Some("alias_remove_result") => {
+            let data: AliasRemoveData = decode_envelope_data(envelope)?;
+            println!("Alias '{}' removed successfully.", data.name);
+            Ok(())
+        }
+        Some("plugin_list") => {
+            let data: PluginListData = decode_envelope_data(envelope)?;
+            if data.plugins.is_empty() {
+                println!("No plugins installed.");
+                return Ok(());
+            }
+            for plugin in data.plugins {
+                let manifest = plugin.manifest;
+                print!(
+                    "{} {}  {}://",
+                    manifest.name,
+                    manifest.version,
+                    manifest.schemes.join("://, ")
+                );
+                match manifest.description {
+                    Some(description) => println!("  {}", description),
+                    None => println!(),
+                }
+            }
+            Ok(())
+        }
+        Some("plugin_installed") => {
+            let plugin: plugins::InstalledPlugin = decode_envelope_data(envelope)?;
+            println!(
+                "Plugin '{}' {} installed in {}; it serves {}://",
+                plugin.manifest.name,
+                plugin.manifest.version,
+                plugin.path.display(),
+                plugin.manifest.schemes.join("://, ")
+            );
+            Ok(())
+        }
+        Some("plugin_removed") => {
+            let plugin: plugins::InstalledPlugin = decode_envelope_data(envelope)?;
+            println!("Plugin '{}' removed successfully.", plugin.manifest.name);
+            Ok(())
+        }
+        Some("config_list") => {
+            let data: ConfigListData = decode_envelope_data(envelope)?;
+            for setting in data.settings {
+                println!(
+                    "{} = {}  ({})",
+                    setting.key,
+                    setting_text(&setting),
+                    setting.describe_source()
+                );
+            }
+            Ok(())
+        }
+        Some("config_value") => {
+            let setting: Setting = decode_envelope_data(envelope)?;
+            println!("{}", setting_text(&setting));
+            Ok(())
+        }
+        Some("config_set") => {
+            let setting: Setting = decode_envelope_data(envelope)?;
+            println!(
+                "{} = {} saved to {}",
+                setting.key,
+                setting_text(&setting),
+                setting.origin.as_deref().unwrap_or("config")
+            );
+            Ok(())
+        }
+        Some("template_remove_result") => {
+            let data: TemplateRemoveData = decode_envelope_data(envelope)?;
+            println!("Template '{}' removed successfully.", data.name);
+            Ok(())
+        }
+        Some("run_report") => {
+            let report: RunReport = decode_envelope_data(envelope)?;
+            for request in &report.requests {
+                let status = if request.ok {
+                    "OK"
+                } else if request.skipped {
+                    "SKIP"
+                } else if request.timed_out {
+                    "TIMEOUT"
+                } else {
+                    "FAIL"
+                };
+                let duration = output::format_duration(request.duration_ms);
+                if request.attempts > 1 {
+                    println!(
+                        "{} {} ({}, {} attempts)",
+                        status, request.name, duration, request.attempts
+                    );
+                } else {
+                    println!("{} {} ({})", status, request.name, duration);
+                }
+                if let Some(error) = request.envelope.as_ref().and_then(|e| e.error.as_ref()) {
+                    println!("  - {}: {}", error.code, error.message);
+                }
+                for failure in &request.failures {
+                    println!("  - {}", failure);
+                }
+                for difference in &request.differences {
+                    println!("  {}", difference);
+                }
+            }
+            println!();
+            println!(
+                "{}: {} ok, {} failed ({} timed out), {} skipped, {} total in {}",
+                report.name,
+                report.summary.succeeded,
+                report.summary.failed,
+                report.summary.timed_out,
+                report.summary.skipped,
+                report.summary.total,
+                output::format_duration(report.duration_ms)
+            );
+            Ok(())
+        }
+        Some("test_report") => {
+            let report: SuiteReport = decode_envelope_data(envelope)?;
+            for case in &report.cases {
+                let status = if case.passed {
+                    "PASS"
+                } else if case.skipped {
+                    "SKIP"
+                } else if case.timed_out {
+                    "TIMEOUT"
+                } else {
+                    "FAIL"
+                };
+                let duration = output::format_duration(case.duration_ms);
+                if case.attempts > 1 {
+                    println!(
+                        "{} {} ({}, {} attempts)",
+                        status, case.name, duration, case.attempts
+                    );
+                } else {
+                    println!("{} {} ({})", status, case.name, duration);
+                }
+                for failure in &case.failures {
+                    println!("  - {}", failure);
+                }
+            }
+            for compensation in &report.compensations {
+                let status = if compensation.ok {
+                    "UNDO"
+                } else {
+                    "UNDO FAILED"
+                };
+                println!(
+                    "{} {} ({} {})",
+                    status, compensation.case, compensation.operation, compensation.endpoint
+                );
+                if let Some(error) = &compensation.error {
+                    println!("  - {}: {}", error.code, error.message);
+                }
+            }
+            println!();
+            println!(
+                "{}: {} passed, {} failed ({} timed out), {} skipped, {} total in {}",
+                report.suite,
+                report.passed,
+                report.failed,
+                report.timed_out,
+                report.skipped,
+                report.total,
+                output::format_duration(report.duration_ms)
+            );
+            Ok(())
+        }
+        _ => {
+            if let Some(data) = &envelope.data {
+                println!("{}", serde_json::to_string_pretty(data)?);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn decode_envelope_data<T: DeserializeOwned>(envelope: &OutputEnvelope) -> Result<T> {
+    let value = envelope
+        .data
+        .as_ref()
+        .ok_or_else(|| UxcError::GenericError(anyhow::anyhow!("Envelope data is missing")))?;
+    Ok(T::deserialize(value)?)
+}
+
+pub(crate) fn print_json(envelope: &OutputEnvelope) -> Result<()> {
+    println!("{}", envelope.to_json()?);
+    Ok(())
+}
+
+fn print_host_help_text_from_summaries(protocol: &str, endpoint: &str, data: &HostHelpData) {
+    println!("Protocol: {}", protocol);
+    println!("Endpoint: {}", idn::to_unicode_endpoint(endpoint));
+    let auth = &data.auth;
+    match (&auth.profile, &auth.profile_source) {
+        (Some(profile), Some(source)) => {
+            println!("Auth: profile '{}' (selected by {})", profile, source)
+        }
+        (Some(profile), None) => println!("Auth: profile '{}'", profile),
+        (None, _) if auth.required => println!("Auth: required, but no profile is set up"),
+        (None, _) => println!("Auth: none"),
+    }
+    for evidence in &auth.evidence {
+        println!("  - {}", evidence);
+    }
+    println!();
+    if !data.recent.is_empty() {
+        println!("Recently used:");
+        for operation_id in &data.recent {
+            println!("- {}", operation_id);
+        }
+        println!();
+    }
+    println!("Available operations:");
+    for op in &data.operations {
+        if let Some(desc) = &op.summary {
+            println!("- {} ({}) : {}", op.display_name, op.operation_id, desc);
+        } else {
+            println!("- {} ({})", op.display_name, op.operation_id);
+        }
+    }
+
+    if !data.quick_start.is_empty() {
+        println!();
+        println!("Quick start:");
+        for line in &data.quick_start {
+            println!("  {}", line);
+        }
+    }
+}
+
+fn print_list_text_from_summaries(protocol: &str, operations: &[OperationSummary], verbose: bool) {
+    if verbose {
+        println!("Detected protocol: {}", protocol);
+        println!();
+    }
+    for op in operations {
+        println!("{} ({})", op.display_name, op.operation_id);
+        if verbose {
+            if let Some(desc) = &op.summary {
+                println!("  {}", desc);
+            }
+            if !op.required.is_empty() {
+                println!("  Required: {}", op.required.join(", "));
+            }
+            if !op.tags.is_empty() {
+                println!("  Tags: {}", op.tags.join(", "));
+            }
+            if let Some(usage) = op.usage {
+                println!(
+                    "  Called {} time(s), last {}",
+                    usage.count,
+                    output::format_timestamp(usage.last_used)
+                );
+            }
+        }
+    }
+}
+
+/// Security requirements as `a (apiKey in header X) or b (http bearer)`;
+/// schemes used together are joined with `and`, and `none` stands for an
+/// alternative without auth
+pub(crate) fn auth_summary(security: &[Vec<SecurityScheme>]) -> String {
+    if security.is_empty() {
+        return "none".to_string();
+    }
+    security
+        .iter()
+        .map(|schemes| {
+            if schemes.is_empty() {
+                return "none".to_string();
+            }
+            schemes
+                .iter()
+                .map(SecurityScheme::describe)
+                .collect::<Vec<_>>()
+                .join(" and ")
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+fn print_detail_text(protocol: &str, endpoint: &str, detail: &OperationDetail) {
+    println!("Protocol: {}", protocol);
+    println!("Endpoint: {}", idn::to_unicode_endpoint(endpoint));
+    println!("Operation ID: {}", detail.operation_id);
+    println!("Display Name: {}", detail.display_name);
+
+    if let Some(description) = &detail.description {
+        println!("Description: {}", description);
+    }
+
+    if let Some(return_type) = &detail.return_type {
+        println!("Return Type: {}", return_type);
+    }
+
+    if let Some(security) = &detail.security {
+        println!("Auth: {}", auth_summary(security));
+    }
+
+    if let Some(servers) = &detail.servers {
+        println!("\nServers:");
+        for (index, server) in servers.iter().enumerate() {
+            let description = server
+                .get("description")
+                .and_then(Value::as_str)
+                .map(|description| format!(": {}", description))
+                .unwrap_or_default();
+            println!(
+                "- [{}] {}{}",
+                index,
+                server
+                    .get("url")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default(),
+                description
+            );
+            for (name, variable) in server
+                .get("variables")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+            {
+                let default = variable
+                    .get("default")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let allowed = variable
+                    .get("enum")
+                    .and_then(Value::as_array)
+                    .map(|values| {
+                        let values: Vec<&str> = values.iter().filter_map(Value::as_str).collect();
+                        format!(" ({})", values.join(", "))
+                    })
+                    .unwrap_or_default();
+                println!("  {}={}{}", name, default, allowed);
+            }
+        }
+    }
+
+    let http = detail
+        .input_schema
+        .as_ref()
+        .and_then(|schema| schema.get("http"))
+        .and_then(|http| serde_json::from_value::<Vec<HttpBinding>>(http.clone()).ok())
+        .unwrap_or_default();
+    if !http.is_empty() {
+        println!("\nHTTP Mappings:");
+        for binding in &http {
+            println!("- {}", binding.display());
+        }
+    }
+
+    if !detail.parameters.is_empty() {
+        println!("\nParameters:");
+        for param in &detail.parameters {
+            println!(
+                "- {} ({}){}",
+                param.name,
+                param.param_type,
+                if param.required { " required" } else { "" }
+            );
+            if let Some(desc) = &param.description {
+                println!("  {}", desc);
+            }
+        }
+    }
+
+    if let Some(Value::Object(responses)) = &detail.responses {
+        println!("\nResponses:");
+        for (status, response) in responses {
+            let schema_type = response
+                .pointer("/schema/type")
+                .and_then(Value::as_str)
+                .map(|schema_type| format!(" ({})", schema_type))
+                .unwrap_or_default();
+            let description = response
+                .get("description")
+                .and_then(Value::as_str)
+                .map(|description| format!(": {}", description))
+                .unwrap_or_default();
+            println!("- {}{}{}", status, schema_type, description);
+        }
+    }
+
+    if let Some(fields) = &detail.fields {
+        println!("\nResult Fields (--select):");
+        for field in fields {
+            println!(
+                "- {} ({})",
+                field
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default(),
+                field
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+            );
+            if let Some(description) = field.get("description").and_then(Value::as_str) {
+                println!("  {}", description);
+            }
+        }
+    }
+
+    if let Some(input_schema) = &detail.input_schema {
+        println!(
+            "\nInput Schema:\n{}",
+            serde_json::to_string_pretty(input_schema).unwrap_or_else(|_| "{}".to_string())
+        );
+    }
+}
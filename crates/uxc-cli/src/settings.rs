@@ -0,0 +1,318 @@
+//! Settings applied before a command runs: `.env` files, `~/.uxc/config.toml`,
+//! `.uxc.toml`, `--rerun` and aliases
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{debug, warn};
+
+use crate::cli::{Cli, Commands, HistoryCommands, ServeCommands};
+use crate::endpoint::parse_protocol;
+use uxc_core::alias::{self, AliasStore};
+use uxc_core::cache::CacheConfig;
+use uxc_core::config;
+use uxc_core::env_file;
+use uxc_core::error::UxcError;
+use uxc_core::explain;
+use uxc_core::history;
+use uxc_core::hooks::{self, Hooks};
+use uxc_core::http;
+use uxc_core::project::ProjectConfig;
+
+/// Load `--env-file`, or `./.env` when it exists, into the invocation scope.
+pub(crate) fn load_env_file(path: Option<&str>) -> Result<()> {
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let default = std::path::PathBuf::from(env_file::DEFAULT_ENV_FILE);
+            if !default.is_file() {
+                return Ok(());
+            }
+            default
+        }
+    };
+
+    let count =
+        env_file::load(&path).map_err(|e| UxcError::InvalidArguments(format!("{:#}", e)))?;
+    debug!("Loaded {} variables from {}", count, path.display());
+    Ok(())
+}
+
+/// Cache settings of `~/.uxc/config.toml`, overridden by the project config
+pub(crate) fn load_cache_config() -> Result<CacheConfig> {
+    // Name the settings that environment or project layers override
+    for key in config::KEYS
+        .iter()
+        .filter(|key| key.name.starts_with("cache."))
+    {
+        let setting = config::get(key.name)?;
+        if matches!(
+            setting.source,
+            config::Source::Env | config::Source::Project
+        ) {
+            explain::record(
+                "cache",
+                format!("{} from {}", setting.key, setting.describe_source()),
+            );
+        }
+    }
+    Ok(CacheConfig::load_from_file().unwrap_or_default())
+}
+
+/// Route HTTP requests through `--proxy` or the configured proxy
+pub(crate) fn configure_proxy(cli: &Cli) -> Result<()> {
+    let configured = config::get("network.proxy")?;
+    let proxy = match cli.proxy.as_deref() {
+        Some(proxy) => {
+            explain::record("network", "proxy from --proxy");
+            proxy
+        }
+        None => match configured.as_str() {
+            Some(proxy) => {
+                explain::record(
+                    "network",
+                    format!("proxy from {}", configured.describe_source()),
+                );
+                proxy
+            }
+            None => return Ok(()),
+        },
+    };
+    http::set_proxy(proxy).map_err(|e| UxcError::InvalidArguments(format!("{:#}", e)).into())
+}
+
+/// Limit HTTP response sizes to `--max-response-bytes` or the configured limit
+pub(crate) fn configure_response_limit(cli: &Cli) -> Result<()> {
+    let limit = match cli.max_response_bytes {
+        Some(limit) => {
+            explain::record("network", "response size limit from --max-response-bytes");
+            Some(limit)
+        }
+        None => {
+            let configured = config::get("network.max_response_bytes")?;
+            if configured.is_configured() {
+                explain::record(
+                    "network",
+                    format!("response size limit from {}", configured.describe_source()),
+                );
+            }
+            configured.as_u64()
+        }
+    };
+    http::set_max_response_bytes(limit);
+    Ok(())
+}
+
+/// Set while the endpoint of the command comes from a project file that is
+/// not trusted, whose calls must not use auth profiles
+pub(crate) static UNTRUSTED_PROJECT_ENDPOINT: AtomicBool = AtomicBool::new(false);
+
+/// Apply the project's `.uxc.toml`: its endpoint stands in for a missing URL
+/// of an endpoint command, its headers are added to HTTP requests and its
+/// hooks run around every call. The default profile and cache settings are
+/// read where those are resolved.
+pub(crate) fn apply_project_config(cli: &mut Cli) -> Result<()> {
+    UNTRUSTED_PROJECT_ENDPOINT.store(false, Ordering::Relaxed);
+    let Some(project) = ProjectConfig::current()? else {
+        return Ok(());
+    };
+    explain::record(
+        "project",
+        format!("project config {} applies", project.path.display()),
+    );
+
+    let endpoint_command = matches!(
+        cli.command,
+        Some(Commands::List { .. })
+            | Some(Commands::Search { .. })
+            | Some(Commands::Describe { .. })
+            | Some(Commands::Help {
+                operation_id: Some(_)
+            })
+            | Some(Commands::Inspect { .. })
+            | Some(Commands::Which { .. })
+            | Some(Commands::Call { .. })
+            | Some(Commands::Query { .. })
+    );
+    if let (None, true, Some(endpoint)) = (&cli.url, endpoint_command, &project.endpoint) {
+        explain::record(
+            "project",
+            format!("endpoint {} from the project config", endpoint),
+        );
+        cli.url = Some(endpoint.clone());
+        UNTRUSTED_PROJECT_ENDPOINT.store(!project.trusted, Ordering::Relaxed);
+    }
+    let headers = project
+        .header_map()
+        .map_err(|e| UxcError::InvalidArguments(format!("{:#}", e)))?;
+    http::add_default_headers(headers);
+    if !project.hooks.is_empty() {
+        if !project.trusted {
+            skip_untrusted_hooks(project);
+            return Ok(());
+        }
+        let project_hooks = Hooks::load(&project.hooks, project.dir(), None)
+            .map_err(|e| UxcError::InvalidArguments(format!("{:#}", e)))?;
+        hooks::install(project_hooks);
+    }
+    Ok(())
+}
+
+/// Report hooks of `project` that do not run because it is not trusted
+fn skip_untrusted_hooks(project: &ProjectConfig) {
+    warn!(
+        "Hooks of {} do not run until it is trusted with 'uxc project trust'",
+        project.path.display()
+    );
+    explain::record(
+        "hooks",
+        format!(
+            "hooks of untrusted {} skipped; run 'uxc project trust'",
+            project.path.display()
+        ),
+    );
+}
+
+/// Turn `uxc history rerun <ID>` into the recorded call. Arguments given to
+/// rerun replace recorded ones; redacted arguments must be given again. The
+/// recorded profile applies unless `--profile` is given.
+pub(crate) fn apply_rerun(cli: &mut Cli) -> Result<()> {
+    let Some(Commands::History {
+        history_command: HistoryCommands::Rerun { id, args },
+    }) = &cli.command
+    else {
+        return Ok(());
+    };
+    let entry = history::find(id)?.ok_or_else(|| {
+        UxcError::InvalidArguments(format!("No call with ID '{}' in history", id))
+    })?;
+    let (Some(operation_id), Some(mut payload)) = (entry.operation.clone(), entry.args.clone())
+    else {
+        return Err(UxcError::InvalidArguments(format!(
+            "Call '{}' was recorded without its arguments and cannot be rerun",
+            id
+        ))
+        .into());
+    };
+
+    let mut overridden = Vec::new();
+    for arg in args {
+        let (name, value) = arg.split_once('=').ok_or_else(|| {
+            UxcError::InvalidArguments(format!("Invalid argument '{}', expected KEY=VALUE", arg))
+        })?;
+        payload.insert(name.to_string(), json!(value));
+        overridden.push(name);
+    }
+    let missing = entry
+        .redacted
+        .iter()
+        .filter(|name| !overridden.contains(&name.as_str()))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(UxcError::InvalidArguments(format!(
+            "Call '{}' has redacted arguments; give them again: uxc history rerun {} {}",
+            id,
+            id,
+            missing
+                .iter()
+                .map(|name| format!("-a {}=...", name))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ))
+        .into());
+    }
+
+    explain::record(
+        "history",
+        format!("rerun of call {} to {}", id, entry.endpoint),
+    );
+    if cli.profile.is_none() {
+        cli.profile = entry.profile;
+    }
+    cli.url = Some(entry.endpoint);
+    cli.command = Some(Commands::Call {
+        operation_id,
+        args: Vec::new(),
+        json: Some(Value::Object(payload).to_string()),
+    });
+    Ok(())
+}
+
+/// Expand an endpoint alias given as the URL, or as the ENDPOINT of
+/// `detect`, `export`, `browse`, `gen-data`, `codegen`, `bench` and `mock`. The alias URL
+/// replaces the name; its profile, schema URL and protocol apply unless
+/// `--profile`, `--schema-url` or `--protocol` is given, its headers are added to HTTP requests and its
+/// hooks run around calls to its URL.
+pub(crate) fn apply_alias(cli: &mut Cli) -> Result<()> {
+    let endpoint = match &mut cli.command {
+        Some(Commands::Detect { endpoint, .. })
+        | Some(Commands::Export { endpoint, .. })
+        | Some(Commands::Browse { endpoint })
+        | Some(Commands::GenData { endpoint, .. })
+        | Some(Commands::Codegen { endpoint, .. })
+        | Some(Commands::Bench { endpoint, .. })
+        | Some(Commands::Mock {
+            source: endpoint, ..
+        })
+        | Some(Commands::Serve {
+            serve_command: ServeCommands::Http { endpoint, .. },
+        }) => endpoint,
+        _ => match &mut cli.url {
+            Some(url) => url,
+            None => return Ok(()),
+        },
+    };
+    let Some(alias) = alias::resolve(endpoint)? else {
+        return Ok(());
+    };
+    let project =
+        ProjectConfig::current()?.filter(|project| project.aliases.contains_key(endpoint.as_str()));
+    if project.is_some_and(|project| !project.trusted) {
+        UNTRUSTED_PROJECT_ENDPOINT.store(true, Ordering::Relaxed);
+    }
+    let headers = alias
+        .header_map()
+        .map_err(|e| UxcError::InvalidArguments(format!("Alias headers: {:#}", e)))?;
+    match project {
+        _ if alias.hooks.is_empty() => {}
+        Some(project) if !project.trusted => skip_untrusted_hooks(project),
+        _ => {
+            // Relative paths start from the file defining the alias
+            let base = match project {
+                Some(project) => project.dir().to_path_buf(),
+                None => AliasStore::config_dir()?,
+            };
+            let alias_hooks = Hooks::load(&alias.hooks, &base, Some(alias.url.clone()))
+                .map_err(|e| UxcError::InvalidArguments(format!("Alias hooks: {:#}", e)))?;
+            hooks::install(alias_hooks);
+        }
+    }
+    explain::record(
+        "alias",
+        format!("'{}' is an alias of {}", endpoint, alias.url),
+    );
+    *endpoint = alias.url;
+
+    if let (None, Some(profile)) = (&cli.profile, alias.profile) {
+        explain::record(
+            "alias",
+            format!("profile '{}' bundled with the alias", profile),
+        );
+        cli.profile = Some(profile);
+    }
+    if cli.schema_url.is_none() {
+        cli.schema_url = alias.schema_url;
+    }
+    if let (None, Some(protocol)) = (cli.protocol, alias.protocol) {
+        let protocol = parse_protocol(&protocol)
+            .map_err(|e| UxcError::InvalidArguments(format!("Alias protocol: {}", e)))?;
+        explain::record(
+            "alias",
+            format!("protocol {} bundled with the alias", protocol.as_str()),
+        );
+        cli.protocol = Some(protocol);
+    }
+    http::add_default_headers(headers);
+    Ok(())
+}
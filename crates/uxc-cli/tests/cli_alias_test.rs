@@ -0,0 +1,96 @@
+//! Endpoint alias integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn json_output(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.assert().success();
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+#[test]
+fn alias_stands_in_for_the_url_and_sends_its_headers() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let pets = server
+        .mock("GET", "/pets")
+        .match_header("x-api-version", "2")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"name":"rex"}]"#)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".uxc")).unwrap();
+    std::fs::write(home.path().join(".uxc/config.toml"), "[cache]\nttl = 60\n").unwrap();
+
+    let set = json_output(uxc(&home).args([
+        "alias",
+        "set",
+        "pets",
+        &server.url(),
+        "-H",
+        "X-Api-Version: 2",
+    ]));
+    assert_eq!(set["kind"], "alias_set");
+    assert_eq!(set["data"]["url"], server.url());
+
+    let config = std::fs::read_to_string(home.path().join(".uxc/config.toml")).unwrap();
+    assert!(config.starts_with("[cache]\nttl = 60\n\n[aliases.pets]\n"));
+
+    let list = json_output(uxc(&home).args(["alias", "list"]));
+    assert_eq!(list["data"]["count"], 1);
+    assert_eq!(list["data"]["aliases"][0]["name"], "pets");
+    assert_eq!(list["data"]["aliases"][0]["headers"]["X-Api-Version"], "2");
+
+    let call = json_output(uxc(&home).args(["pets", "get:/pets"]));
+    assert_eq!(call["endpoint"], server.url());
+    assert_eq!(call["data"][0]["name"], "rex");
+    pets.assert();
+
+    let detect = json_output(uxc(&home).args(["detect", "pets"]));
+    assert_eq!(detect["endpoint"], server.url());
+
+    uxc(&home)
+        .args(["alias", "remove", "pets"])
+        .assert()
+        .success();
+    let list = json_output(uxc(&home).args(["alias", "list"]));
+    assert_eq!(list["data"]["count"], 0);
+}
+
+#[test]
+fn alias_names_must_not_shadow_commands() {
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args(["alias", "set", "list", "https://api.example.com"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+
+    uxc(&home)
+        .args(["alias", "set", "api.example", "https://api.example.com"])
+        .assert()
+        .failure();
+}
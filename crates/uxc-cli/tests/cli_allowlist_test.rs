@@ -0,0 +1,118 @@
+//! Host allowlist integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_ALLOWED_HOSTS")
+        .current_dir(home.path());
+    cmd
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": { "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create()
+}
+
+#[test]
+fn blocked_host_fails_and_is_recorded_in_history() {
+    let mut server = Server::new();
+    let schema = mock_schema(&mut server).expect(0);
+
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .env("UXC_ALLOWED_HOSTS", "*.example.com")
+        .args([&server.url(), "get:/pets"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "HOST_NOT_ALLOWED");
+    schema.assert();
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(history.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["status"], "BLOCKED");
+    assert_eq!(entry["operation"], "get:/pets");
+    assert_eq!(entry["error_code"], "HOST_NOT_ALLOWED");
+}
+
+#[test]
+fn allowed_hosts_flag_overrides_env() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let pets = server
+        .mock("GET", "/pets")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .create();
+
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .env("UXC_ALLOWED_HOSTS", "*.example.com")
+        .args([&server.url(), "get:/pets", "--allowed-hosts", "127.0.0.1:*"])
+        .assert()
+        .success();
+    pets.assert();
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    assert!(!history.contains("BLOCKED"));
+}
+
+#[test]
+fn unicode_endpoints_are_checked_and_recorded_as_punycode() {
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args([
+            "--allowed-hosts",
+            "*.example.com",
+            "例え.テスト/api",
+            "list",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "HOST_NOT_ALLOWED");
+    assert!(envelope["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("xn--r8jz45g.xn--zckzah:443 is not in the allowed hosts"));
+
+    // A Unicode pattern admits the endpoint; offline mode stops before the network
+    let output = uxc(&home)
+        .args([
+            "--allowed-hosts",
+            "例え.テスト",
+            "--offline",
+            "https://例え.テスト",
+            "list",
+        ])
+        .output()
+        .unwrap();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "OFFLINE_MISS");
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(history.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["endpoint"], "https://xn--r8jz45g.xn--zckzah/api");
+}
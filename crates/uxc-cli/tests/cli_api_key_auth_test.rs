@@ -0,0 +1,191 @@
+//! API key header/query placement integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path());
+    cmd
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/openapi.json")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": { "/me": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#,
+        )
+        .create()
+}
+
+#[test]
+fn profile_places_api_key_in_custom_header_with_template() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let me = server
+        .mock("GET", "/me")
+        .match_header("authorization", "Token k-123")
+        .match_header("x-api-key", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user": "alice"}"#)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args([
+            "auth",
+            "set",
+            "tracker",
+            "-t",
+            "api_key",
+            "--api-key",
+            "k-123",
+            "--api-key-header",
+            "Authorization",
+            "--api-key-template",
+            "Token {key}",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(
+        json["data"]["api_key_placement"],
+        "header Authorization (Token {key})"
+    );
+
+    uxc(&home)
+        .args(["--profile", "tracker", &server.url(), "get:/me"])
+        .assert()
+        .success();
+    me.assert();
+}
+
+#[test]
+fn host_config_places_api_key_in_query_parameter() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let me = server
+        .mock("GET", "/me")
+        .match_query(Matcher::UrlEncoded("key".into(), "k-456".into()))
+        .match_header("x-api-key", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user": "bob"}"#)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".uxc")).unwrap();
+    std::fs::write(
+        home.path().join(".uxc/config.toml"),
+        "[api_keys.\"127.0.0.1:*\"]\nquery = \"key\"\n",
+    )
+    .unwrap();
+    uxc(&home)
+        .args(["auth", "set", "maps", "-t", "api_key", "--api-key", "k-456"])
+        .assert()
+        .success();
+
+    uxc(&home)
+        .args(["--profile", "maps", &server.url(), "get:/me"])
+        .assert()
+        .success();
+    me.assert();
+
+    uxc(&home)
+        .args([
+            "auth",
+            "set",
+            "broken",
+            "--api-key",
+            "k",
+            "--api-key-query",
+            "key",
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn documented_security_schemes_place_keys_and_flag_mismatched_profiles() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "security": [{ "key": [] }],
+  "components": { "securitySchemes": {
+    "key": { "type": "apiKey", "in": "query", "name": "api_key" },
+    "jwt": { "type": "http", "scheme": "bearer" }
+  } },
+  "paths": {
+    "/me": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/admin": {
+      "get": { "security": [{ "jwt": [] }], "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create();
+    let me = server
+        .mock("GET", "/me")
+        .match_query(Matcher::UrlEncoded("api_key".into(), "k-789".into()))
+        .match_header("x-api-key", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user": "carol"}"#)
+        .create();
+    let _admin = server
+        .mock("GET", "/admin")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args([&server.url(), "describe", "get:/admin"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["security"][0][0]["name"], "jwt");
+    assert_eq!(json["data"]["security"][0][0]["scheme"], "bearer");
+
+    uxc(&home)
+        .args(["auth", "set", "docs", "-t", "api_key", "--api-key", "k-789"])
+        .assert()
+        .success();
+    let output = uxc(&home)
+        .args(["--profile", "docs", &server.url(), "get:/me"])
+        .assert()
+        .success();
+    me.assert();
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(!stderr.contains("does not match"), "{}", stderr);
+
+    let output = uxc(&home)
+        .args(["--profile", "docs", &server.url(), "get:/admin"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(
+        stderr.contains(
+            "auth type api_key does not match what get:/admin documents: jwt (http bearer)"
+        ),
+        "{}",
+        stderr
+    );
+}
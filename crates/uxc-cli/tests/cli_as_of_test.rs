@@ -0,0 +1,93 @@
+//! `--as-of` schema snapshot integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn openapi(path: &str) -> String {
+    format!(
+        r#"{{
+  "openapi": "3.0.0",
+  "info": {{ "title": "test", "version": "1.0.0" }},
+  "paths": {{
+    "{}": {{ "get": {{ "responses": {{ "200": {{ "description": "ok" }} }} }} }}
+  }}
+}}"#,
+        path
+    )
+}
+
+fn operations(home: &TempDir, args: &[&str]) -> Vec<String> {
+    let output = uxc(home).args(args).assert().success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    json["data"]["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|op| op["operation_id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn schemas_resolve_from_snapshots() {
+    let mut server = Server::new();
+    let v1 = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(openapi("/pets"))
+        .create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+
+    assert_eq!(operations(&home, &[&url, "list"]), ["get:/pets"]);
+    assert_eq!(
+        operations(&home, &[&url, "list", "--as-of", "2999-01-01"]),
+        ["get:/pets"]
+    );
+
+    let output = uxc(&home)
+        .args([&url, "list", "--as-of", "2000-01-01"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "SNAPSHOT_NOT_FOUND");
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("taken on or before 2000-01-01 23:59:59 UTC"),
+        "{}",
+        message
+    );
+    assert!(message.contains("the earliest is from"), "{}", message);
+
+    // Snapshots outlive the cache and follow schema changes
+    v1.remove();
+    let _v2 = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(openapi("/toys"))
+        .create();
+    uxc(&home)
+        .args(["cache", "clear", "--all"])
+        .assert()
+        .success();
+    assert_eq!(operations(&home, &[&url, "list"]), ["get:/toys"]);
+    assert_eq!(
+        operations(&home, &[&url, "list", "--as-of", "2999-01-01T00:00:00Z"]),
+        ["get:/toys"]
+    );
+
+    let output = uxc(&home)
+        .args([&url, "list", "--as-of", "yesterday"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+}
@@ -0,0 +1,152 @@
+//! `uxc auth export` / `uxc auth import` integration tests
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn export_redacts_and_import_round_trips() {
+    let source = TempDir::new().unwrap();
+    uxc(&source)
+        .args(["auth", "set", "prod", "--api-key", "sk-live-1234567890"])
+        .assert()
+        .success();
+    uxc(&source)
+        .args([
+            "auth",
+            "set",
+            "ci",
+            "--api-key",
+            "env:CI_TOKEN",
+            "-t",
+            "api_key",
+        ])
+        .assert()
+        .success();
+
+    let export_path = source.path().join("team.toml");
+    uxc(&source)
+        .args(["auth", "export", "-o"])
+        .arg(&export_path)
+        .assert()
+        .success();
+    let exported = std::fs::read_to_string(&export_path).unwrap();
+    assert!(exported.contains("<redacted>"));
+    assert!(!exported.contains("sk-live-1234567890"));
+    assert!(exported.contains("env:CI_TOKEN"));
+
+    let target = TempDir::new().unwrap();
+    let output = uxc(&target)
+        .args(["auth", "import"])
+        .arg(&export_path)
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["kind"], "auth_import_result");
+    assert_eq!(json["data"]["added"], serde_json::json!(["ci", "prod"]));
+    assert_eq!(json["data"]["needs_secret"], serde_json::json!(["prod"]));
+
+    let output = uxc(&target).args(["auth", "info", "ci"]).assert().success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"]["auth_type"], "api_key");
+}
+
+#[test]
+fn import_conflicts_default_to_skip_and_support_rename() {
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["auth", "set", "prod", "--api-key", "old-key-123"])
+        .assert()
+        .success();
+
+    let file = home.path().join("incoming.json");
+    std::fs::write(
+        &file,
+        r#"{"prod": {"api_key": "new-key-456", "auth_type": "bearer"}}"#,
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .args(["auth", "import"])
+        .arg(&file)
+        .assert()
+        .success();
+    assert_eq!(
+        stdout_json(output.get_output())["data"]["skipped"],
+        serde_json::json!(["prod"])
+    );
+
+    let output = uxc(&home)
+        .args(["auth", "import", "--on-conflict", "rename"])
+        .arg(&file)
+        .assert()
+        .success();
+    assert_eq!(
+        stdout_json(output.get_output())["data"]["renamed"]["prod"],
+        "prod-2"
+    );
+}
+
+#[test]
+fn import_rejects_invalid_entries_without_writing() {
+    let home = TempDir::new().unwrap();
+    let file = home.path().join("bad.toml");
+    std::fs::write(
+        &file,
+        "[good]\napi_key = \"k\"\n\n[bad]\napi_key = \"k\"\nauth_type = \"digest\"\n",
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .args(["auth", "import"])
+        .arg(&file)
+        .assert()
+        .failure();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Profile 'bad'"));
+
+    let output = uxc(&home).args(["auth", "list"]).assert().success();
+    assert_eq!(stdout_json(output.get_output())["data"]["count"], 0);
+}
+
+#[test]
+fn import_refuses_command_references_by_default() {
+    let home = TempDir::new().unwrap();
+    let file = home.path().join("shared.toml");
+    std::fs::write(&file, "[shared]\napi_key = \"cmd:echo pwned\"\n").unwrap();
+
+    let output = uxc(&home)
+        .args(["auth", "import"])
+        .arg(&file)
+        .assert()
+        .failure();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("--allow-commands"));
+    let output = uxc(&home).args(["auth", "list"]).assert().success();
+    assert_eq!(stdout_json(output.get_output())["data"]["count"], 0);
+
+    let output = uxc(&home)
+        .args(["auth", "import", "--allow-commands"])
+        .arg(&file)
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"]["commands"]["shared.api_key"], "cmd:echo pwned");
+}
@@ -0,0 +1,105 @@
+//! `uxc bench` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn jobs() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/job": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/busy": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn bench_reports_throughput_latency_and_statuses() {
+    let mut server = jobs();
+    let job = server
+        .mock("GET", "/job")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"status":"done"}"#)
+        .expect(12)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            "bench",
+            &server.url(),
+            "get:/job",
+            "--requests",
+            "12",
+            "--concurrency",
+            "3",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["kind"], "bench_report");
+    assert_eq!(envelope["operation"], "get:/job");
+    let report = &envelope["data"];
+    assert_eq!(report["requests"], 12);
+    assert_eq!(report["succeeded"], 12);
+    assert_eq!(report["concurrency"], 3);
+    assert_eq!(report["statuses"]["200"], 12);
+    assert!(report["throughput"].as_f64().unwrap() > 0.0);
+    assert!(
+        report["latency"]["p99_ms"].as_f64().unwrap()
+            >= report["latency"]["p50_ms"].as_f64().unwrap()
+    );
+    assert!(report.get("errors").is_none());
+    job.assert();
+}
+
+#[test]
+fn bench_groups_failures_by_code_and_status() {
+    let mut server = jobs();
+    server
+        .mock("GET", "/busy")
+        .with_status(503)
+        .with_body("try later")
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            "bench",
+            &server.url(),
+            "get:/busy",
+            "--requests",
+            "5",
+            "--text",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("5 calls in"), "{}", stdout);
+    assert!(stdout.contains("0 ok, 5 failed"), "{}", stdout);
+    assert!(stdout.contains("Statuses: 503 x5"), "{}", stdout);
+    assert!(stdout.contains("Errors:"), "{}", stdout);
+}
@@ -0,0 +1,24 @@
+//! `uxc browse` integration tests
+//!
+//! The browser itself needs a terminal; these check how it behaves without one.
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+#[test]
+fn browse_without_a_terminal_fails_before_connecting() {
+    let home = TempDir::new().unwrap();
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+        .env("HOME", home.path())
+        .args(["browse", "http://127.0.0.1:9"])
+        .assert()
+        .failure();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("interactive terminal"));
+}
@@ -0,0 +1,156 @@
+//! Schema cache CLI integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+#[test]
+fn stale_while_revalidate_marks_stale_schema() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+
+    let home = TempDir::new().unwrap();
+    let list = |home: &TempDir| -> serde_json::Value {
+        let output = uxc(home)
+            .args([
+                server.url().as_str(),
+                "list",
+                "--cache-ttl",
+                "0",
+                "--stale-while-revalidate",
+            ])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    // First run fetches and caches; TTL 0 makes the entry expire at once
+    let fresh = list(&home);
+    assert_eq!(fresh["ok"], true);
+    assert!(fresh["data"].get("cache").is_none());
+
+    let stale = list(&home);
+    assert_eq!(stale["ok"], true);
+    assert_eq!(stale["data"]["cache"], "stale");
+    assert_eq!(stale["data"]["operations"][0]["operation_id"], "get:/users");
+}
+
+#[test]
+fn offline_mode_serves_cached_schemas_only() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+
+    uxc(&home).args([url.as_str(), "list"]).assert().success();
+    drop(server);
+
+    let output = uxc(&home)
+        .args(["--offline", url.as_str(), "list"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["operations"][0]["operation_id"], "get:/users");
+
+    let output = uxc(&home)
+        .env("UXC_OFFLINE", "1")
+        .args([url.as_str(), "get:/users"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "OFFLINE_MISS");
+
+    let output = uxc(&home)
+        .args(["--offline", "http://127.0.0.1:9/uncached", "list"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "OFFLINE_MISS");
+}
+
+#[test]
+fn cache_warm_prefetches_endpoints_from_args_and_file() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+    let list = home.path().join("endpoints.txt");
+    std::fs::write(
+        &list,
+        format!(
+            "# prefetch list\n{}\nhttp://127.0.0.1:9/down  # unreachable\n",
+            url
+        ),
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .args([
+            "cache",
+            "warm",
+            url.as_str(),
+            "--url",
+            url.as_str(),
+            "--file",
+        ])
+        .arg(&list)
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "cache_warm_result");
+    assert_eq!(json["data"]["succeeded"], 1);
+    assert_eq!(json["data"]["failed"], 1);
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains("[2/2]"));
+    drop(server);
+
+    uxc(&home)
+        .args(["--offline", url.as_str(), "list"])
+        .assert()
+        .success();
+}
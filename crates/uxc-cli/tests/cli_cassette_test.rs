@@ -0,0 +1,89 @@
+//! `--record` and `--replay` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .env_remove("UXC_OFFLINE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &assert_cmd::assert::Assert) -> serde_json::Value {
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+#[test]
+fn recorded_calls_replay_without_the_network() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/gone": { "get": { "responses": { "404": { "description": "gone" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+        .mock("GET", "/pets")
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"name":"Rex"}]"#)
+        .create();
+    server.mock("GET", "/gone").with_status(404).create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+
+    uxc(&home)
+        .args(["--record", "pets.json", &url, "get:/pets", "--no-cache"])
+        .assert()
+        .success();
+    let rejected = uxc(&home)
+        .args(["--record", "pets.json", &url, "get:/gone", "--no-cache"])
+        .assert()
+        .failure();
+    assert_eq!(stdout_json(&rejected)["error"]["detail"]["status"], 404);
+
+    let cassette: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(home.path().join("pets.json")).unwrap())
+            .unwrap();
+    assert_eq!(cassette["interactions"].as_array().unwrap().len(), 2);
+    assert_eq!(cassette["interactions"][0]["operation"], "get:/pets");
+    drop(server);
+
+    // A fresh home has no schema cache: everything comes from the cassette
+    let replay_home = TempDir::new().unwrap();
+    std::fs::copy(
+        home.path().join("pets.json"),
+        replay_home.path().join("pets.json"),
+    )
+    .unwrap();
+    let replayed = uxc(&replay_home)
+        .args(["--replay", "pets.json", &url, "get:/pets"])
+        .assert()
+        .success();
+    assert_eq!(
+        stdout_json(&replayed)["data"],
+        serde_json::json!([{ "name": "Rex" }])
+    );
+    let replayed = uxc(&replay_home)
+        .args(["--replay", "pets.json", &url, "get:/gone"])
+        .assert()
+        .failure();
+    assert_eq!(stdout_json(&replayed)["error"]["detail"]["status"], 404);
+
+    let missing = uxc(&replay_home)
+        .args(["--replay", "pets.json", &url, "get:/pets", "limit=1"])
+        .assert()
+        .failure();
+    assert_eq!(stdout_json(&missing)["error"]["code"], "OFFLINE_MISS");
+}
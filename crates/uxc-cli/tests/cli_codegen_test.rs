@@ -0,0 +1,132 @@
+//! `uxc codegen` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn pets() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "pets", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "summary": "Add a pet",
+        "requestBody": {
+          "required": true,
+          "content": {
+            "application/json": {
+              "schema": {
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                  "name": { "type": "string" },
+                  "tags": { "type": "array", "items": { "type": "string" } }
+                }
+              }
+            }
+          }
+        },
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/pets/{petId}": {
+      "get": {
+        "parameters": [
+          { "name": "petId", "in": "path", "required": true, "schema": { "type": "integer" } }
+        ],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn codegen_prints_a_client_for_each_language() {
+    let server = pets();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args(["codegen", "--lang", "typescript", &server.url()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["kind"], "generated_client");
+    assert_eq!(envelope["protocol"], "openapi");
+    let data = &envelope["data"];
+    assert_eq!(data["language"], "typescript");
+    assert_eq!(data["operations"], 2);
+    assert_eq!(data["types"], 2);
+    let code = data["code"].as_str().unwrap();
+    assert!(code.contains("export interface PostPetsArgs {"), "{}", code);
+    assert!(code.contains("  tags?: string[];"), "{}", code);
+    assert!(
+        code.contains("  getPetsPetId(args: GetPetsPetIdArgs): Promise<unknown> {"),
+        "{}",
+        code
+    );
+
+    for (lang, expected) in [
+        (
+            "rust",
+            "pub fn post_pets(&self, args: &PostPetsArgs) -> Result<Value> {",
+        ),
+        (
+            "python",
+            "def get_pets_pet_id(self, args: GetPetsPetIdArgs) -> Any:",
+        ),
+    ] {
+        let output = uxc(&home)
+            .args(["codegen", "--lang", lang, &server.url(), "--text"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        let code = String::from_utf8(output.stdout).unwrap();
+        assert!(code.contains(expected), "{}", code);
+        assert!(code.contains(&format!("\"{}\"", server.url())), "{}", code);
+    }
+}
+
+#[test]
+fn codegen_writes_the_client_to_output() {
+    let server = pets();
+    let home = TempDir::new().unwrap();
+    let path = home.path().join("client.rs");
+
+    let output = uxc(&home)
+        .args(["codegen", "--lang", "rust", &server.url(), "-o"])
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["data"]["path"], path.display().to_string());
+    assert!(envelope["data"].get("code").is_none());
+    let code = std::fs::read_to_string(&path).unwrap();
+    assert!(code.contains("pub struct GetPetsPetIdArgs {"), "{}", code);
+    assert!(
+        code.contains("    #[serde(rename = \"petId\")]\n    pub pet_id: i64,"),
+        "{}",
+        code
+    );
+}
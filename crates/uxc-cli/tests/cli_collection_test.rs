@@ -0,0 +1,181 @@
+//! `uxc export` / `uxc import` / `uxc template` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+}
+
+fn mock_petstore(server: &mut Server) -> Vec<mockito::Mock> {
+    let schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": {
+        "summary": "list pets",
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .expect_at_least(1)
+        .create();
+    let pets = server
+        .mock("GET", "/pets")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id": 1, "name": "cat"}]"#)
+        .create();
+    vec![schema, pets]
+}
+
+#[test]
+fn postman_export_import_and_template_run() {
+    let mut server = Server::new();
+    let _mocks = mock_petstore(&mut server);
+    let dir = TempDir::new().unwrap();
+    let collection_path = dir.path().join("petstore.postman.json");
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["export", "postman", &server.url(), "-o"])
+        .arg(&collection_path)
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "collection_export");
+    assert_eq!(json["data"]["count"], 1);
+
+    let collection: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&collection_path).unwrap()).unwrap();
+    assert_eq!(
+        collection["item"][0]["request"]["url"]["raw"],
+        "{{baseUrl}}/pets"
+    );
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["import", "postman"])
+        .arg(&collection_path)
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "import_result");
+    let name = json["data"]["imported"][0].as_str().unwrap().to_string();
+
+    // A second import without --force keeps the existing template.
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["import", "postman"])
+        .arg(&collection_path)
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["skipped"][0], name.as_str());
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["template", "run", &name])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "call_result");
+    assert_eq!(json["data"][0]["name"], "cat");
+}
+
+#[test]
+fn import_rejects_wrong_format() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("not-insomnia.json");
+    std::fs::write(&path, r#"{"item": []}"#).unwrap();
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["import", "insomnia"])
+        .arg(&path)
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+}
+
+#[test]
+fn curl_import_matches_schema_and_saves_template() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets/{id}": {
+      "put": {
+        "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .expect_at_least(1)
+        .create();
+    let dir = TempDir::new().unwrap();
+    let command = format!(
+        r#"curl -X PUT '{}/pets/42' -H 'Content-Type: application/json' -d '{{"name": "cat"}}'"#,
+        server.url()
+    );
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["import", "curl", &command, "--save", "update-pet"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "request_import");
+    assert_eq!(json["data"]["matched"], true);
+    assert_eq!(json["data"]["endpoint"], server.url());
+    assert_eq!(json["data"]["operation"], "put:/pets/{id}");
+    assert_eq!(json["data"]["args"]["id"], "42");
+    assert_eq!(json["data"]["args"]["name"], "cat");
+    assert_eq!(json["data"]["saved_as"], "update-pet");
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args(["template", "show", "update-pet"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["source"], "curl");
+}
+
+#[test]
+fn httpie_import_without_schema_is_unmatched() {
+    let dir = TempDir::new().unwrap();
+    let output = uxc()
+        .env("HOME", dir.path())
+        .args([
+            "import",
+            "httpie",
+            "http POST 127.0.0.1:9/pets name=cat age:=2",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["matched"], false);
+    assert_eq!(json["data"]["endpoint"], "http://127.0.0.1:9");
+    assert_eq!(json["data"]["operation"], "post:/pets");
+    assert_eq!(json["data"]["args"]["age"], 2);
+}
@@ -0,0 +1,93 @@
+//! Shell completion integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn complete(home: &TempDir, words: &[&str]) -> Vec<String> {
+    let output = uxc(home).arg("__complete").args(words).assert().success();
+    String::from_utf8_lossy(&output.get_output().stdout)
+        .lines()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn completion_scripts_call_the_dynamic_completer() {
+    let home = TempDir::new().unwrap();
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = uxc(&home).args(["completion", shell]).assert().success();
+        let script = String::from_utf8_lossy(&output.get_output().stdout);
+        assert!(script.contains("uxc __complete"), "{}", shell);
+        assert!(script.contains("describe"), "{}", shell);
+    }
+}
+
+#[test]
+fn complete_reads_operations_and_parameters_from_the_schema_cache() {
+    let mut server = Server::new();
+    let spec = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/users/{id}": {
+      "get": {
+        "parameters": [
+          { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } },
+          { "name": "expand", "in": "query", "schema": { "type": "string" } }
+        ],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .expect_at_least(1)
+        .create();
+    let home = TempDir::new().unwrap();
+    let endpoint = server.url();
+
+    // Nothing is cached yet, and completion never goes to the network
+    assert!(complete(&home, &[endpoint.as_str(), "get:"]).is_empty());
+
+    uxc(&home)
+        .args([endpoint.as_str(), "list"])
+        .assert()
+        .success();
+    spec.assert();
+    drop(server);
+
+    assert_eq!(
+        complete(&home, &[endpoint.as_str(), "get:/users"]),
+        ["get:/users", "get:/users/{id}"]
+    );
+    assert!(complete(&home, &[endpoint.as_str(), ""]).contains(&"describe".to_string()));
+    assert_eq!(
+        complete(&home, &[endpoint.as_str(), "describe", "get:/users/"]),
+        ["get:/users/{id}"]
+    );
+    assert_eq!(
+        complete(&home, &[endpoint.as_str(), "get:/users/{id}", ""]),
+        ["id=", "expand="]
+    );
+    assert_eq!(
+        complete(
+            &home,
+            &["--text", endpoint.as_str(), "get:/users/{id}", "id=1", ""]
+        ),
+        ["expand="]
+    );
+    assert!(complete(&home, &["cache", ""]).is_empty());
+}
@@ -0,0 +1,156 @@
+//! `uxc config` and layered settings integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir, dir: &Path) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_CALL_RETRIES")
+        .env_remove("UXC_OUTPUT_FORMAT")
+        .env_remove("UXC_DETECTION_ORDER")
+        .env_remove("UXC_NETWORK_PROXY")
+        .current_dir(dir);
+    cmd
+}
+
+fn json_output(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.assert().success();
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn settings_are_layered_env_then_project_then_user() {
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".uxc")).unwrap();
+    std::fs::write(home.path().join(".uxc/config.toml"), "[cache]\nttl = 60\n").unwrap();
+
+    let set = json_output(uxc(&home, home.path()).args(["config", "set", "call.retries", "2"]));
+    assert_eq!(set["kind"], "config_set");
+    assert_eq!(set["data"]["source"], "user");
+    let file = std::fs::read_to_string(home.path().join(".uxc/config.toml")).unwrap();
+    assert_eq!(file, "[cache]\nttl = 60\n\n[call]\nretries = 2\n");
+
+    let get = json_output(uxc(&home, project.path()).args(["config", "get", "call.retries"]));
+    assert_eq!(get["data"]["value"], 2);
+    assert_eq!(get["data"]["source"], "user");
+
+    std::fs::write(project.path().join(".uxc.toml"), "[call]\nretries = 3\n").unwrap();
+    let get = json_output(uxc(&home, project.path()).args(["config", "get", "call.retries"]));
+    assert_eq!(get["data"]["value"], 3);
+    assert_eq!(get["data"]["source"], "project");
+
+    let get = json_output(
+        uxc(&home, project.path())
+            .env("UXC_CALL_RETRIES", "5")
+            .args(["config", "get", "call.retries"]),
+    );
+    assert_eq!(get["data"]["value"], 5);
+    assert_eq!(get["data"]["origin"], "UXC_CALL_RETRIES");
+
+    let list = json_output(uxc(&home, project.path()).args(["config", "list"]));
+    let settings = list["data"]["settings"].as_array().unwrap();
+    let ttl = settings.iter().find(|s| s["key"] == "cache.ttl").unwrap();
+    assert_eq!(ttl["value"], 60);
+    let timeout = settings
+        .iter()
+        .find(|s| s["key"] == "call.timeout")
+        .unwrap();
+    assert_eq!(timeout["source"], "default");
+    assert!(timeout["value"].is_null());
+}
+
+#[test]
+fn invalid_keys_and_values_are_rejected() {
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home, home.path())
+        .args(["config", "set", "output.format", "yaml"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+
+    let output = uxc(&home, home.path())
+        .args(["config", "get", "call.timeot"])
+        .assert()
+        .failure();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(stdout.contains("did you mean 'call.timeout'"), "{}", stdout);
+
+    uxc(&home, home.path())
+        .args(["config", "set", "call.retries", "1", "--project"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn configured_format_and_detection_order_apply_to_calls() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".uxc")).unwrap();
+    std::fs::write(
+        home.path().join(".uxc/config.toml"),
+        "[output]\nformat = \"text\"\n\n[detection]\norder = [\"openapi\"]\n",
+    )
+    .unwrap();
+
+    let output = uxc(&home, home.path())
+        .args([&server.url(), "list"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(serde_json::from_str::<serde_json::Value>(&stdout).is_err());
+    assert!(stdout.contains("get:/pets"), "{}", stdout);
+
+    // Without the cache, detection probes again
+    let json = json_output(uxc(&home, home.path()).args([
+        &server.url(),
+        "list",
+        "--format",
+        "json",
+        "--explain",
+        "--no-cache",
+    ]));
+    let explained = json["meta"]["explain"].to_string();
+    assert!(
+        explained.contains("openapi probed first: [detection] order"),
+        "{}",
+        explained
+    );
+}
+
+#[test]
+fn configured_proxy_carries_http_requests() {
+    let proxy = petstore();
+    let home = TempDir::new().unwrap();
+
+    let json = json_output(
+        uxc(&home, home.path())
+            .env("UXC_NETWORK_PROXY", proxy.url())
+            .args(["http://api.example.invalid", "list", "--no-cache"]),
+    );
+    assert_eq!(json["data"]["operations"][0]["operation_id"], "get:/pets");
+}
@@ -0,0 +1,269 @@
+//! `uxc daemon` integration tests
+
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use mockito::Server;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStderr, Stdio};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .env_remove("UXC_DAEMON_SOCKET")
+        .current_dir(home.path());
+    cmd
+}
+
+/// Kills the daemon when the test ends, passing or not
+struct Daemon {
+    child: Child,
+    /// Kept open so the daemon can go on logging
+    _stderr: BufReader<ChildStderr>,
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn start_daemon(home: &TempDir) -> Daemon {
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+        .env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .env_remove("UXC_DAEMON_SOCKET")
+        .current_dir(home.path())
+        .args(["daemon", "start"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("listening on") {
+        line.clear();
+        assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "daemon exited");
+    }
+    Daemon {
+        child,
+        _stderr: stderr,
+    }
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn endpoint_commands_run_in_the_daemon_with_warm_adapters() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let users = server
+        .mock("GET", "/users")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id":1}]"#)
+        .expect(2)
+        .create();
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+
+    let status = uxc(&home).args(["daemon", "status"]).assert().success();
+    assert_eq!(stdout_json(status.get_output())["data"]["running"], false);
+
+    let mut daemon = start_daemon(&home);
+    let output = uxc(&home)
+        .args([url.as_str(), "get:/users"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output())["data"][0]["id"], 1);
+
+    // The adapter of the first call serves the second, without detection
+    let output = uxc(&home)
+        .args([url.as_str(), "get:/users", "--explain"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"][0]["id"], 1);
+    assert!(json["meta"]["explain"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|decision| decision["topic"] == "daemon"));
+    users.assert();
+
+    // Failures come back as the error envelope of the command
+    let output = uxc(&home)
+        .args([url.as_str(), "describe", "get:/missing"])
+        .assert()
+        .failure();
+    assert_eq!(
+        stdout_json(output.get_output())["error"]["code"],
+        "OPERATION_NOT_FOUND"
+    );
+
+    let status = uxc(&home).args(["daemon", "status"]).assert().success();
+    let json = stdout_json(status.get_output());
+    assert_eq!(json["data"]["running"], true);
+    assert_eq!(json["data"]["commands_served"], 3);
+    assert_eq!(json["data"]["warm_adapters"], 1);
+
+    let stop = uxc(&home).args(["daemon", "stop"]).assert().success();
+    assert_eq!(stdout_json(stop.get_output())["data"]["stopped"], true);
+    let stopped = daemon.child.wait().unwrap();
+    assert!(stopped.success());
+    assert!(!home.path().join(".uxc/daemon.sock").exists());
+}
+
+#[test]
+fn no_daemon_and_other_directories_run_in_process() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"openapi":"3.0.0","info":{"title":"t","version":"1"},"paths":{"/ping":{"get":{"responses":{"200":{"description":"ok"}}}}}}"#,
+        )
+        .create();
+    let _ping = server
+        .mock("GET", "/ping")
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+    let home = TempDir::new().unwrap();
+    let elsewhere = home.path().join("elsewhere");
+    std::fs::create_dir(&elsewhere).unwrap();
+    let url = server.url();
+    let _daemon = start_daemon(&home);
+
+    uxc(&home)
+        .args([url.as_str(), "get:/ping", "--no-daemon"])
+        .assert()
+        .success();
+    uxc(&home)
+        .current_dir(&elsewhere)
+        .args([url.as_str(), "list"])
+        .assert()
+        .success();
+
+    let status = uxc(&home).args(["daemon", "status"]).assert().success();
+    assert_eq!(
+        stdout_json(status.get_output())["data"]["commands_served"],
+        0
+    );
+}
+
+#[test]
+fn clients_with_another_environment_run_in_process() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"openapi":"3.0.0","info":{"title":"t","version":"1"},"paths":{"/ping":{"get":{"responses":{"200":{"description":"ok"}}}}}}"#,
+        )
+        .create();
+    let _ping = server
+        .mock("GET", "/ping")
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+    let _daemon = start_daemon(&home);
+
+    // The allowlist of the client applies, not the daemon's lack of one
+    let output = uxc(&home)
+        .env("UXC_ALLOWED_HOSTS", "example.com")
+        .args([url.as_str(), "get:/ping"])
+        .assert()
+        .failure();
+    assert_eq!(
+        stdout_json(output.get_output())["error"]["code"],
+        "HOST_NOT_ALLOWED"
+    );
+
+    uxc(&home)
+        .args([url.as_str(), "get:/ping"])
+        .assert()
+        .success();
+    let status = uxc(&home).args(["daemon", "status"]).assert().success();
+    assert_eq!(
+        stdout_json(status.get_output())["data"]["commands_served"],
+        1
+    );
+}
+
+#[test]
+fn commands_do_not_inherit_hooks_or_proxies_of_earlier_ones() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"openapi":"3.0.0","info":{"title":"t","version":"1"},"paths":{"/ping":{"get":{"responses":{"200":{"description":"ok"}}}}}}"#,
+        )
+        .create();
+    let _ping = server
+        .mock("GET", "/ping")
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("count.rhai"),
+        r#"if "runs" in result { result.runs += 1; } else { result.runs = 1; }"#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.path().join(".uxc.toml"),
+        "[hooks]\npost_response = \"count.rhai\"\n",
+    )
+    .unwrap();
+    uxc(&home).args(["project", "trust"]).assert().success();
+    let url = server.url();
+    let _daemon = start_daemon(&home);
+
+    for _ in 0..3 {
+        let output = uxc(&home)
+            .args([url.as_str(), "get:/ping"])
+            .assert()
+            .success();
+        assert_eq!(stdout_json(output.get_output())["data"]["runs"], 1);
+    }
+
+    uxc(&home)
+        .args([url.as_str(), "get:/ping", "--proxy", "http://127.0.0.1:9"])
+        .assert()
+        .failure();
+    uxc(&home)
+        .args([url.as_str(), "get:/ping"])
+        .assert()
+        .success();
+
+    let status = uxc(&home).args(["daemon", "status"]).assert().success();
+    assert_eq!(
+        stdout_json(status.get_output())["data"]["commands_served"],
+        5
+    );
+}
@@ -0,0 +1,356 @@
+//! `uxc detect` and `--protocol` integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+/// One endpoint answering both GraphQL introspection and an OpenAPI schema
+fn mock_graphql_and_openapi(server: &mut Server) -> Vec<mockito::Mock> {
+    let graphql = server
+        .mock("POST", "/")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "data": {
+                    "__schema": {
+                        "queryType": { "name": "Query", "fields": [] },
+                        "mutationType": null,
+                        "subscriptionType": null
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .create();
+    let openapi = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": {
+      "get": { "summary": "List users", "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create();
+    vec![graphql, openapi]
+}
+
+#[test]
+fn detect_all_reports_every_protocol_and_protocol_picks_one() {
+    let mut server = Server::new();
+    let _mocks = mock_graphql_and_openapi(&mut server);
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+
+    let output = uxc(&home)
+        .args(["detect", "--all", url.as_str()])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "detect_result");
+    assert_eq!(
+        json["data"]["protocols"],
+        serde_json::json!(["graphql", "openapi"])
+    );
+    assert_eq!(json["data"]["selected"], "graphql");
+
+    let output = uxc(&home)
+        .args(["--protocol", "openapi", url.as_str(), "list"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["protocol"], "openapi");
+    assert_eq!(json["data"]["operations"][0]["operation_id"], "get:/users");
+
+    // The cached detection rejects protocols the endpoint did not answer as
+    let output = uxc(&home)
+        .args(["--protocol=jsonrpc", url.as_str(), "list"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "PROTOCOL_DETECTION_FAILED");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("detected: graphql, openapi"));
+}
+
+#[test]
+fn protocol_skips_probes_and_aliases_bundle_it() {
+    let mut server = Server::new();
+    let probe = server.mock("POST", "/").expect(0).create();
+    let _openapi = server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": {
+      "get": { "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+
+    let output = uxc(&home)
+        .args([
+            "alias",
+            "set",
+            "users",
+            url.as_str(),
+            "--protocol",
+            "openapi",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["protocol"], "openapi");
+
+    let output = uxc(&home).args(["users", "list"]).assert().success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["protocol"], "openapi");
+    assert_eq!(json["data"]["operations"][0]["operation_id"], "get:/users");
+    probe.assert();
+}
+
+#[test]
+fn detection_results_are_cached_until_cleared() {
+    let mut server = Server::new();
+    let graphql_probe = server
+        .mock("POST", "/")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_status(404)
+        .expect(2)
+        .create();
+    let _openapi = server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": {
+      "get": { "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+
+    uxc(&home).args([url.as_str(), "list"]).assert().success();
+    let output = uxc(&home)
+        .args([url.as_str(), "list", "--explain"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["protocol"], "openapi");
+    let explained = json["meta"]["explain"].to_string();
+    assert!(
+        explained.contains("openapi from the detection cache; no probes"),
+        "{}",
+        explained
+    );
+
+    let output = uxc(&home)
+        .args(["cache", "clear", "--detection"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["scope"], "detection");
+    assert_eq!(json["data"]["removed"], 1);
+
+    // Cleared, the next call probes again
+    uxc(&home).args([url.as_str(), "list"]).assert().success();
+    graphql_probe.assert();
+}
+
+#[test]
+fn detect_report_shows_the_evidence_of_every_probe() {
+    let mut server = Server::new();
+    let _mocks = mock_graphql_and_openapi(&mut server);
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+
+    let output = uxc(&home)
+        .args(["detect", "--report", url.as_str()])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "detect_report");
+    assert_eq!(json["data"]["selected"], "graphql");
+    let probes = json["data"]["probes"].as_array().unwrap();
+    let adapters: Vec<&str> = probes
+        .iter()
+        .map(|probe| probe["adapter"].as_str().unwrap())
+        .collect();
+    assert_eq!(adapters, ["mcp", "graphql", "openapi", "jsonrpc", "grpc"]);
+    let openapi = &probes[2];
+    assert_eq!(openapi["matched"], true);
+    assert!(openapi["evidence"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|note| note == &format!("GET {}/openapi.json: OpenAPI document", url)));
+    assert_eq!(probes[3]["matched"], false);
+    assert!(!probes[3]["evidence"].as_array().unwrap().is_empty());
+
+    let output = uxc(&home)
+        .args(["--protocol", "openapi", "detect", "--report", "--text"])
+        .arg(url.as_str())
+        .assert()
+        .success();
+    let text = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(text.contains("graphql: matched"), "{}", text);
+    assert!(text.contains("jsonrpc: no match"), "{}", text);
+    assert!(text.contains("Selected: openapi"), "{}", text);
+}
+
+#[test]
+fn detection_settings_add_paths_and_disable_protocols_per_host() {
+    let mut server = Server::new();
+    let graphql = server
+        .mock("POST", "/graphql/v2")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "data": {
+                    "__schema": {
+                        "queryType": { "name": "Query", "fields": [] },
+                        "mutationType": null,
+                        "subscriptionType": null
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .expect_at_least(2)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".uxc")).unwrap();
+    std::fs::write(
+        home.path().join(".uxc/config.toml"),
+        r#"[detection]
+disabled = ["mcp"]
+graphql_paths = ["/graphql/v2"]
+
+[detection.hosts."127.0.0.1"]
+disabled = ["jsonrpc", "grpc"]
+"#,
+    )
+    .unwrap();
+    let url = server.url();
+
+    let output = uxc(&home)
+        .args(["detect", "--report", url.as_str()])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let probes = json["data"]["probes"].as_array().unwrap();
+    let adapters: Vec<&str> = probes
+        .iter()
+        .map(|probe| probe["adapter"].as_str().unwrap())
+        .collect();
+    assert_eq!(adapters, ["graphql", "openapi"]);
+    assert_eq!(json["data"]["selected"], "graphql");
+    assert!(probes[0]["evidence"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|note| note == &format!("POST {}/graphql/v2 introspection: answered", url)));
+
+    // Calls go to the endpoint found under the configured path
+    let output = uxc(&home).args([url.as_str(), "list"]).assert().success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["protocol"], "graphql");
+    graphql.assert();
+}
+
+#[test]
+fn all_protocols_lists_and_routes_operations_of_every_protocol() {
+    let mut server = Server::new();
+    let _mocks = mock_graphql_and_openapi(&mut server);
+    let users = server
+        .mock("GET", "/users")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id":1}]"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+
+    let output = uxc(&home)
+        .args(["--all-protocols", url.as_str(), "list"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let operations = json["data"]["operations"].as_array().unwrap();
+    assert!(operations
+        .iter()
+        .any(|op| op["operation_id"] == "openapi:get:/users"));
+    assert!(operations.iter().all(|op| {
+        let id = op["operation_id"].as_str().unwrap();
+        id.starts_with("graphql:") || id.starts_with("openapi:")
+    }));
+
+    // The prefix picks the adapter
+    let output = uxc(&home)
+        .args([
+            "--all-protocols",
+            url.as_str(),
+            "describe",
+            "openapi:get:/users",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["protocol"], "openapi");
+    assert_eq!(json["operation"], "get:/users");
+
+    let output = uxc(&home)
+        .args([url.as_str(), "openapi:get:/users", "--all-protocols"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["protocol"], "openapi");
+    assert_eq!(json["data"][0]["id"], 1);
+    users.assert();
+
+    let output = uxc(&home)
+        .args(["--all-protocols", url.as_str(), "describe", "get:/users"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "OPERATION_NOT_FOUND");
+}
+
+#[test]
+fn unknown_protocol_is_rejected() {
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["--protocol", "soap", "detect", "http://127.0.0.1:9"])
+        .assert()
+        .failure();
+}
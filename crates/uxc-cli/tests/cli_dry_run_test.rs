@@ -0,0 +1,151 @@
+//! `--dry-run` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object",
+          "required": ["name"],
+          "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer" },
+            "status": { "type": "string", "enum": ["available", "sold"] }
+          }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn dry_run_prints_the_request_without_sending_it() {
+    let mut server = petstore();
+    let pets = server.mock("POST", "/pets").expect(0).create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["auth", "set", "petstore", "--api-key", "secret-token-123"])
+        .assert()
+        .success();
+
+    let output = uxc(&home)
+        .args([
+            &url,
+            "post:/pets",
+            "name=Rex",
+            "age=3",
+            "--profile",
+            "petstore",
+            "--dry-run",
+        ])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).into_owned();
+    assert!(!stdout.contains("secret-token-123"), "{}", stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "dry_run");
+    assert_eq!(json["operation"], "post:/pets");
+    let data = &json["data"];
+    assert_eq!(
+        data["arguments"],
+        serde_json::json!({ "name": "Rex", "age": 3 })
+    );
+    assert_eq!(data["coerced"], serde_json::json!(["age"]));
+    assert_eq!(data["request"]["transport"], "http");
+    assert_eq!(data["request"]["method"], "POST");
+    assert_eq!(data["request"]["target"], format!("{}/pets", url));
+    assert_eq!(
+        data["request"]["body"],
+        serde_json::json!({ "name": "Rex", "age": 3 })
+    );
+    let headers = data["request"]["headers"].to_string();
+    assert!(headers.contains("***"), "{}", headers);
+    assert!(
+        headers.contains("content-type: application/json"),
+        "{}",
+        headers
+    );
+    assert_eq!(
+        json["next"][0]["command"],
+        format!(
+            "uxc {} post:/pets --json '{{\"age\":3,\"name\":\"Rex\"}}'",
+            url
+        )
+    );
+
+    let output = uxc(&home)
+        .args([&url, "post:/pets", "name=Rex", "--dry-run", "--text"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(
+        stdout.starts_with(&format!("> POST {}/pets\n", url)),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("\n\n{\n  \"name\": \"Rex\"\n}"),
+        "{}",
+        stdout
+    );
+
+    // Nothing was sent, and nothing was recorded
+    pets.assert();
+    let history = uxc(&home).args(["history", "list"]).assert().success();
+    let history: serde_json::Value = serde_json::from_slice(&history.get_output().stdout).unwrap();
+    assert_eq!(history["data"]["count"], 0);
+}
+
+#[test]
+fn dry_run_reports_every_invalid_argument() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "post:/pets",
+            "age=three",
+            "status=lost",
+            "--dry-run",
+        ])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("missing required argument 'name'"),
+        "{}",
+        message
+    );
+    assert!(message.contains("'age' must be an integer"), "{}", message);
+    assert!(message.contains("'status' must be one of"), "{}", message);
+}
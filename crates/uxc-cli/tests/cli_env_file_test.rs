@@ -0,0 +1,127 @@
+//! `.env` / `--env-file` integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": { "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .expect_at_least(1)
+        .create()
+}
+
+#[test]
+fn env_file_feeds_url_args_and_secret_references() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let pets = server
+        .mock("POST", "/pets")
+        .match_header("authorization", "Bearer from-env-file")
+        .match_body(Matcher::Json(serde_json::json!({"name": "cat"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1}"#)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["auth", "set", "default", "--api-key", "env:PET_TOKEN"])
+        .assert()
+        .success();
+    let env_path = home.path().join("staging.env");
+    std::fs::write(
+        &env_path,
+        format!(
+            "API_URL={}\nPET_NAME=cat\nPET_TOKEN=from-env-file\n",
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .arg("--env-file")
+        .arg(&env_path)
+        .args(["${API_URL}", "post:/pets", "--args", "name=${PET_NAME}"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["id"], 1);
+    pets.assert();
+
+    // Variables are scoped to the invocation and never persisted.
+    let profiles = std::fs::read_to_string(home.path().join(".uxc/profiles.toml")).unwrap();
+    assert!(!profiles.contains("from-env-file"));
+}
+
+#[test]
+fn dotenv_in_working_directory_is_loaded_by_default() {
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join(".env"), "NOT_A_VALID_LINE\n").unwrap();
+
+    let output = uxc(&home)
+        .args(["http://127.0.0.1:9", "list"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    assert!(json["error"]["message"].as_str().unwrap().contains(".env"));
+}
+
+#[test]
+fn unset_variable_is_rejected() {
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args(["${UXC_TEST_UNSET_HOST}", "list"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("'UXC_TEST_UNSET_HOST' is not set"));
+}
+
+#[cfg(unix)]
+#[test]
+fn dotenv_cannot_run_a_cache_key_command() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let home = TempDir::new().unwrap();
+    let marker = home.path().join("ran");
+    std::fs::write(
+        home.path().join(".env"),
+        format!(
+            "UXC_CACHE_ENCRYPTION_KEY='cmd:touch {} && echo key'\n",
+            marker.display()
+        ),
+    )
+    .unwrap();
+
+    uxc(&home)
+        .env_remove("UXC_CACHE_ENCRYPTION_KEY")
+        .args([server.url().as_str(), "list"])
+        .assert()
+        .success();
+    assert!(!marker.exists());
+}
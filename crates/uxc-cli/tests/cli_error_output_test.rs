@@ -0,0 +1,202 @@
+//! CLI error output integration tests
+//!
+//! Tests that CLI failures return structured JSON error envelopes
+
+use assert_cmd::Command;
+use mockito::Server;
+
+fn uxc() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+}
+
+#[test]
+fn protocol_detection_failure_uses_error_envelope() {
+    let output = uxc()
+        .arg("http://127.0.0.1:9")
+        .arg("list")
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("PROTOCOL_DETECTION_FAILED"))
+        .stderr(predicates::str::is_empty());
+
+    // Verify JSON error envelope structure
+    let stdout = output.get_output().stdout.clone();
+    let json: serde_json::Value =
+        serde_json::from_slice(&stdout).expect("stdout should be valid JSON");
+
+    assert_eq!(json["ok"], false, "ok should be false");
+    assert_eq!(json["error"]["code"], "PROTOCOL_DETECTION_FAILED");
+    assert!(
+        json["error"]["message"].is_string(),
+        "error.message should be a string"
+    );
+}
+
+#[test]
+fn operation_execution_failure_uses_error_envelope() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": {
+        "summary": "list pets",
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+
+    // Call without operation should succeed
+    uxc().arg(server.url()).arg("list").assert().success();
+
+    // Call with non-existent operation should fail with error envelope
+    let output = uxc()
+        .arg(server.url())
+        .arg("nonexistent")
+        .assert()
+        .failure();
+
+    // Verify JSON error envelope structure
+    let stdout = output.get_output().stdout.clone();
+    let json: serde_json::Value =
+        serde_json::from_slice(&stdout).expect("stdout should be valid JSON");
+
+    assert_eq!(json["ok"], false, "ok should be false");
+    assert!(json["error"].is_object(), "error should be an object");
+    assert!(
+        json["error"]["code"].is_string(),
+        "error.code should be a string"
+    );
+    assert!(
+        json["error"]["message"].is_string(),
+        "error.message should be a string"
+    );
+}
+
+#[test]
+fn operation_not_found_suggests_closest_operations() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": { "responses": { "200": { "description": "ok" } } },
+      "post": { "responses": { "200": { "description": "ok" } } }
+    },
+    "/owners": {
+      "get": { "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create();
+
+    let home = tempfile::TempDir::new().unwrap();
+    let output = uxc()
+        .env("HOME", home.path())
+        .arg(server.url())
+        .args(["describe", "get:/pet"])
+        .assert()
+        .failure();
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.get_output().stdout).expect("stdout should be valid JSON");
+    assert_eq!(json["error"]["code"], "OPERATION_NOT_FOUND");
+    let commands = json["next"]
+        .as_array()
+        .expect("next should be an array")
+        .iter()
+        .map(|action| action["command"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(commands.len(), 4);
+    assert_eq!(
+        commands[0],
+        format!("uxc {} describe get:/pets", server.url())
+    );
+    assert_eq!(commands[3], format!("uxc {} list", server.url()));
+
+    // Successful envelopes carry suggestions too
+    let output = uxc()
+        .env("HOME", home.path())
+        .arg(server.url())
+        .arg("list")
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.get_output().stdout).expect("stdout should be valid JSON");
+    assert!(json["next"][0]["command"]
+        .as_str()
+        .unwrap()
+        .contains(" describe "));
+}
+
+#[test]
+fn http_error_status_adds_detail_and_retriable_flag() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/gone": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _busy = server
+        .mock("GET", "/pets")
+        .with_status(503)
+        .with_body(r#"{"error": "maintenance"}"#)
+        .create();
+    let _missing = server.mock("GET", "/gone").with_status(404).create();
+    let home = tempfile::TempDir::new().unwrap();
+
+    let output = uxc()
+        .env("HOME", home.path())
+        .args([server.url().as_str(), "get:/pets"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "EXECUTION_FAILED");
+    assert_eq!(json["error"]["retriable"], true);
+    assert_eq!(
+        json["error"]["detail"],
+        serde_json::json!({"type": "http", "status": 503, "body": "{\"error\": \"maintenance\"}"})
+    );
+
+    let output = uxc()
+        .env("HOME", home.path())
+        .args([server.url().as_str(), "get:/gone"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["retriable"], false);
+    assert_eq!(
+        json["error"]["detail"],
+        serde_json::json!({"type": "http", "status": 404})
+    );
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("failed with HTTP 404 Not Found"));
+}
@@ -0,0 +1,91 @@
+//! `uxc <host> <operation_id> example` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+const SCHEMA: &str = r##"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "parameters": [{ "name": "dry_run", "in": "query", "schema": { "type": "boolean" } }],
+        "requestBody": {
+          "content": {
+            "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+          }
+        },
+        "responses": { "201": { "description": "created" } }
+      }
+    }
+  },
+  "components": {
+    "schemas": {
+      "Pet": {
+        "type": "object",
+        "required": ["name"],
+        "properties": {
+          "name": { "type": "string", "example": "Rex" },
+          "status": { "type": "string", "enum": ["available", "sold"] },
+          "tags": { "type": "array", "items": { "type": "string" } }
+        }
+      }
+    }
+  }
+}"##;
+
+#[test]
+fn example_prints_a_complete_payload() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(SCHEMA)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "post:/pets", "example"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["kind"], "operation_example");
+    let example = serde_json::json!({
+        "name": "Rex",
+        "status": "available",
+        "tags": ["string"],
+        "dry_run": false
+    });
+    assert_eq!(json["data"], example);
+    let next = json["next"][0]["command"].as_str().unwrap();
+    assert!(
+        next.contains(&format!("post:/pets --json '{}'", example)),
+        "{}",
+        next
+    );
+
+    // Text output is the payload itself, ready to edit
+    let output = uxc(&home)
+        .args([&server.url(), "post:/pets", "example", "--text"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output()), example);
+
+    uxc(&home)
+        .args([&server.url(), "post:/pets", "example", "extra"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,104 @@
+//! `--explain` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn decisions(json: &serde_json::Value, topic: &str) -> Vec<String> {
+    json["meta"]["explain"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|decision| decision["topic"] == topic)
+        .map(|decision| decision["decision"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn explain_reports_detection_auth_cache_and_mapping_decisions() {
+    let mut server = Server::new();
+    let _spec = server
+        .mock("GET", "/specs/api.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/v1/ping": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _ping = server
+        .mock("GET", "/v1/ping")
+        .match_header("authorization", "Bearer secret-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"pong": true}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    let mappings = home.path().join("mappings.json");
+    std::fs::write(
+        &mappings,
+        format!(
+            r#"{{"openapi": [{{"host": "127.0.0.1", "path_prefix": "/v1", "schema_url": "{}/specs/api.json", "priority": 5}}]}}"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+    uxc(&home)
+        .args(["auth", "set", "ci", "--api-key", "secret-token"])
+        .assert()
+        .success();
+
+    let endpoint = format!("{}/v1", server.url());
+    let output = uxc(&home)
+        .env("UXC_SCHEMA_MAPPINGS_FILE", &mappings)
+        .args([
+            "--explain",
+            "--no-cache",
+            "--profile",
+            "ci",
+            "--protocol",
+            "openapi",
+            endpoint.as_str(),
+            "get:/ping",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["pong"], true);
+
+    let detection = decisions(&json, "detection");
+    assert_eq!(detection, ["openapi chosen by --protocol; no probes"]);
+    assert!(decisions(&json, "cache")
+        .iter()
+        .any(|d| d == "schema cache bypassed: --no-cache"));
+    let auth = decisions(&json, "auth");
+    assert!(auth.contains(&"profile 'ci' selected by --profile".to_string()));
+    assert!(auth.contains(&"bearer profile: Authorization: Bearer header".to_string()));
+    assert!(decisions(&json, "schema_mapping")[0]
+        .starts_with("user_mapping rule host=127.0.0.1 path_prefix=/v1 priority=5 won"));
+    assert!(!output
+        .get_output()
+        .stdout
+        .windows(12)
+        .any(|w| w == b"secret-token"));
+
+    // Without the flag the envelope carries no explanation
+    let output = uxc(&home)
+        .env("UXC_SCHEMA_MAPPINGS_FILE", &mappings)
+        .args(["--profile", "ci", endpoint.as_str(), "get:/ping"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json["meta"].get("explain").is_none());
+}
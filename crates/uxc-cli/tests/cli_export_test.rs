@@ -0,0 +1,138 @@
+//! `--export` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object",
+          "required": ["name"],
+          "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer" },
+            "status": { "type": "string", "enum": ["available", "sold"] }
+          }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn export_prints_a_curl_command_with_credentials() {
+    let mut server = petstore();
+    let pets = server.mock("POST", "/pets").expect(0).create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["auth", "set", "petstore", "--api-key", "secret-token-123"])
+        .assert()
+        .success();
+
+    let output = uxc(&home)
+        .args([
+            &url,
+            "post:/pets",
+            "name=Rex",
+            "age=3",
+            "--profile",
+            "petstore",
+            "--export",
+            "curl",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "export");
+    assert_eq!(json["data"]["format"], "curl");
+    assert_eq!(json["data"]["redacted"], false);
+    let command = json["data"]["command"].as_str().unwrap();
+    assert!(
+        command.starts_with(&format!("curl -X POST {}/pets \\\n", url)),
+        "{}",
+        command
+    );
+    assert!(command.contains("secret-token-123"), "{}", command);
+    assert!(
+        command.contains("-H 'content-type: application/json'"),
+        "{}",
+        command
+    );
+    assert!(
+        command.ends_with(r#"--data-raw '{"age":3,"name":"Rex"}'"#),
+        "{}",
+        command
+    );
+
+    let output = uxc(&home)
+        .args([
+            &url,
+            "post:/pets",
+            "name=Rex",
+            "--profile",
+            "petstore",
+            "--export",
+            "curl",
+            "--redact",
+            "--text",
+        ])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(stdout.starts_with("curl -X POST"), "{}", stdout);
+    assert!(!stdout.contains("secret-token-123"), "{}", stdout);
+    assert!(stdout.contains("***"), "{}", stdout);
+
+    // Nothing was sent, and nothing was recorded
+    pets.assert();
+    let history = uxc(&home).args(["history", "list"]).assert().success();
+    let history: serde_json::Value = serde_json::from_slice(&history.get_output().stdout).unwrap();
+    assert_eq!(history["data"]["count"], 0);
+}
+
+#[test]
+fn export_names_the_format_that_fits() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "post:/pets",
+            "name=Rex",
+            "--export",
+            "grpcurl",
+        ])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(message.contains("use --export curl"), "{}", message);
+}
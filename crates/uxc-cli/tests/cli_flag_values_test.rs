@@ -0,0 +1,91 @@
+//! Duration and size flag value integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+#[test]
+fn responses_larger_than_the_limit_fail() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/report": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _report = server
+        .mock("GET", "/report")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!("\"{}\"", "x".repeat(4000)))
+        .create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+
+    uxc(&home)
+        .args([&url, "get:/report", "--max-response-bytes", "10KB"])
+        .assert()
+        .success();
+
+    let output = uxc(&home)
+        .args([&url, "get:/report", "--max-response-bytes=2KiB"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "EXECUTION_FAILED");
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("larger than --max-response-bytes 2048 (4002 bytes)"),
+        "{}",
+        message
+    );
+
+    uxc(&home)
+        .env("UXC_NETWORK_MAX_RESPONSE_BYTES", "1kb")
+        .args([&url, "get:/report"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn durations_and_sizes_are_validated() {
+    let home = TempDir::new().unwrap();
+
+    for (flag, value, expected) in [
+        ("--cache-ttl", "12x", "expected seconds or a duration"),
+        ("--grpc-idle-timeout", "1.5s", "must be whole seconds"),
+        ("--max-response-bytes", "10XB", "expected bytes or a size"),
+    ] {
+        let output = uxc(&home)
+            .args(["https://api.example.com", "list", flag, value])
+            .assert()
+            .failure();
+        let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+        assert!(stderr.contains(expected), "{}", stderr);
+    }
+
+    uxc(&home)
+        .args(["config", "set", "call.timeout", "2m30s"])
+        .assert()
+        .success();
+    let output = uxc(&home)
+        .args(["config", "get", "call.timeout"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["value"], 150);
+}
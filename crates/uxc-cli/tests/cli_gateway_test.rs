@@ -0,0 +1,284 @@
+//! `uxc serve http` integration tests
+
+use mockito::{Server, ServerGuard};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, ChildStderr, Stdio};
+use tempfile::TempDir;
+
+/// Kills the gateway when the test ends, passing or not
+struct Gateway {
+    child: Child,
+    /// Kept open so the gateway can go on logging
+    _stderr: BufReader<ChildStderr>,
+    address: String,
+    metrics_address: String,
+}
+
+impl Drop for Gateway {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Gateway {
+    fn start(home: &TempDir, endpoint: &str) -> Self {
+        Self::start_with(home, endpoint, &[])
+    }
+
+    fn start_with(home: &TempDir, endpoint: &str, args: &[&str]) -> Self {
+        let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+            .env("HOME", home.path())
+            .env_remove("UXC_PROFILE")
+            .current_dir(home.path())
+            .args(["serve", "http", endpoint, "--port", "0"])
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stderr = BufReader::new(child.stderr.take().unwrap());
+        let (mut address, mut metrics_address) = (String::new(), String::new());
+        let mut line = String::new();
+        while !line.contains("Ctrl-C") {
+            line.clear();
+            assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "gateway exited");
+            let url = line.split_whitespace().last().unwrap_or_default();
+            if line.contains("listening on") {
+                address = url.trim_start_matches("http://").to_string();
+            } else if line.starts_with("Metrics at") {
+                metrics_address = url
+                    .trim_start_matches("http://")
+                    .trim_end_matches("/metrics")
+                    .to_string();
+            }
+        }
+        Gateway {
+            child,
+            _stderr: stderr,
+            address,
+            metrics_address,
+        }
+    }
+
+    /// Raw status and body of a request to `address`
+    fn send(address: &str, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            address,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    /// Metrics scraped from the gateway
+    fn metrics(&self) -> String {
+        let (status, body) = Self::send(&self.metrics_address, "GET", "/metrics", "");
+        assert_eq!(status, 200);
+        body
+    }
+
+    /// Status and envelope of a request to the gateway
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, serde_json::Value) {
+        let (status, body) = Self::send(&self.address, method, path, body);
+        (status, serde_json::from_str(&body).unwrap())
+    }
+}
+
+fn pets() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "pets", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "requestBody": {
+          "content": {
+            "application/json": {
+              "schema": {
+                "type": "object",
+                "properties": { "name": { "type": "string" }, "age": { "type": "integer" } }
+              }
+            }
+          }
+        },
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/pets/{id}": {
+      "get": {
+        "parameters": [
+          { "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } }
+        ],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn gateway_lists_describes_and_calls_operations() {
+    let mut server = pets();
+    let created = server
+        .mock("POST", "/pets")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "name": "Rex", "age": 3 }),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1, "name": "Rex"}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    let gateway = Gateway::start(&home, &server.url());
+
+    let (status, envelope) = gateway.request("GET", "/ops", "");
+    assert_eq!(status, 200);
+    assert_eq!(envelope["kind"], "operation_list");
+    let ids: Vec<&str> = envelope["data"]["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|operation| operation["operation_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 2, "{}", envelope);
+    assert!(ids.contains(&"post:/pets"), "{:?}", ids);
+
+    let (status, envelope) = gateway.request("GET", "/ops/get:%2Fpets%2F%7Bid%7D", "");
+    assert_eq!(status, 200);
+    assert_eq!(envelope["kind"], "operation_detail");
+    assert_eq!(envelope["operation"], "get:/pets/{id}");
+
+    // Arguments are coerced to the schema like on the command line
+    let (status, envelope) = gateway.request(
+        "POST",
+        "/ops/post:%2Fpets",
+        r#"{"name": "Rex", "age": "3"}"#,
+    );
+    assert_eq!(status, 200, "{}", envelope);
+    assert_eq!(envelope["ok"], true);
+    assert_eq!(envelope["kind"], "call_result");
+    assert_eq!(
+        envelope["data"],
+        serde_json::json!({ "id": 1, "name": "Rex" })
+    );
+    created.assert();
+}
+
+#[test]
+fn gateway_sends_client_arguments_without_expanding_variables() {
+    let mut server = pets();
+    let created = server
+        .mock("POST", "/pets")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "name": "${SECRET_TOKEN}" }),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join(".env"), "SECRET_TOKEN=hunter2\n").unwrap();
+    let gateway = Gateway::start(&home, &server.url());
+
+    let (status, envelope) = gateway.request(
+        "POST",
+        "/ops/post:%2Fpets",
+        r#"{"name": "${SECRET_TOKEN}"}"#,
+    );
+    assert_eq!(status, 200, "{}", envelope);
+    assert!(!envelope.to_string().contains("hunter2"), "{}", envelope);
+    created.assert();
+}
+
+#[test]
+fn gateway_reports_failures_with_http_statuses() {
+    let mut server = pets();
+    server
+        .mock("POST", "/pets")
+        .with_status(409)
+        .with_body(r#"{"message": "Rex exists"}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    let gateway = Gateway::start(&home, &server.url());
+
+    // The upstream status is passed on
+    let (status, envelope) = gateway.request("POST", "/ops/post:%2Fpets", r#"{"name": "Rex"}"#);
+    assert_eq!(status, 409, "{}", envelope);
+    assert_eq!(envelope["ok"], false);
+    assert_eq!(envelope["error"]["code"], "EXECUTION_FAILED");
+
+    let (status, envelope) = gateway.request("GET", "/ops/nope", "");
+    assert_eq!(status, 400, "{}", envelope);
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+
+    let (status, envelope) = gateway.request("POST", "/ops/post:%2Fpets", "[1, 2]");
+    assert_eq!(status, 400);
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+
+    let (status, _) = gateway.request("DELETE", "/ops/post:%2Fpets", "");
+    assert_eq!(status, 405);
+    let (status, _) = gateway.request("GET", "/pets", "");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn gateway_serves_metrics() {
+    let mut server = pets();
+    server
+        .mock("POST", "/pets")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 1}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    let gateway = Gateway::start(&home, &server.url());
+    assert_eq!(gateway.metrics_address, gateway.address);
+
+    let (status, _) = gateway.request("POST", "/ops/post:%2Fpets", r#"{"name": "Rex"}"#);
+    assert_eq!(status, 200);
+    let metrics = gateway.metrics();
+    assert!(
+        metrics.contains(
+            r#"uxc_call_duration_seconds_count{protocol="openapi",operation="post:/pets",outcome="ok"} 1"#
+        ),
+        "{}",
+        metrics
+    );
+    assert!(
+        metrics.contains("# TYPE uxc_cache_lookups_total counter"),
+        "{}",
+        metrics
+    );
+}
+
+#[test]
+fn gateway_serves_metrics_on_their_own_port() {
+    let server = pets();
+    let home = TempDir::new().unwrap();
+    let gateway = Gateway::start_with(&home, &server.url(), &["--metrics-port", "0"]);
+    assert_ne!(gateway.metrics_address, gateway.address);
+
+    let (status, _) = gateway.request("GET", "/ops", "");
+    assert_eq!(status, 200);
+    assert!(gateway.metrics().contains("uxc_cache_lookups_total"));
+    let (status, _) = gateway.request("GET", "/metrics", "");
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,85 @@
+//! `uxc gen-data` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn json_output(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.assert().success();
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+#[test]
+fn gen_data_builds_reproducible_payloads_from_the_request_schema() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r##"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "requestBody": {
+          "required": true,
+          "content": {
+            "application/json": {
+              "schema": { "$ref": "#/components/schemas/Pet" }
+            }
+          }
+        },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  },
+  "components": {
+    "schemas": {
+      "Pet": {
+        "type": "object",
+        "required": ["name", "status", "age"],
+        "properties": {
+          "name": { "type": "string", "maxLength": 8 },
+          "status": { "type": "string", "enum": ["available", "sold"] },
+          "age": { "type": "integer", "minimum": 1, "maximum": 20 }
+        }
+      }
+    }
+  }
+}"##,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+    let url = server.url();
+    let args = [
+        "gen-data",
+        url.as_str(),
+        "post:/pets",
+        "--count",
+        "3",
+        "--seed",
+        "42",
+    ];
+
+    let json = json_output(uxc(&home).args(args));
+    assert_eq!(json["kind"], "generated_data");
+    assert_eq!(json["data"]["seed"], 42);
+    let payloads = json["data"]["payloads"].as_array().unwrap();
+    assert_eq!(payloads.len(), 3);
+    for payload in payloads {
+        assert!(payload["name"].as_str().unwrap().len() <= 8);
+        assert!(["available", "sold"].contains(&payload["status"].as_str().unwrap()));
+        assert!((1..=20).contains(&payload["age"].as_i64().unwrap()));
+    }
+
+    let again = json_output(uxc(&home).args(args));
+    assert_eq!(again["data"]["payloads"], json["data"]["payloads"]);
+}
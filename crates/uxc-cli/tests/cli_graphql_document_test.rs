@@ -0,0 +1,111 @@
+//! `uxc <graphql-host> query --file` integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+const DOCUMENT: &str = r#"
+query Viewer($login: String!) {
+  user(login: $login) { ...Profile }
+}
+
+mutation Follow($login: String!) {
+  follow(login: $login) { ...Profile }
+}
+
+fragment Profile on User { login name }
+"#;
+
+fn graphql_server() -> mockito::ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "data": {
+                    "__schema": {
+                        "queryType": { "name": "Query", "fields": [] },
+                        "mutationType": null,
+                        "subscriptionType": null
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .create();
+    server
+}
+
+#[test]
+fn query_runs_the_named_operation_of_a_document() {
+    let mut server = graphql_server();
+    let call = server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "query": DOCUMENT,
+            "operationName": "Viewer",
+            "variables": { "login": "octocat" }
+        })))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data":{"user":{"login":"octocat","name":"The Octocat"}}}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("viewer.graphql"), DOCUMENT).unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "query",
+            "--file",
+            "viewer.graphql",
+            "--vars",
+            r#"{"login":"octocat"}"#,
+            "--operation-name",
+            "Viewer",
+        ])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["operation"], "document");
+    assert_eq!(json["data"]["user"]["name"], "The Octocat");
+    call.assert();
+}
+
+#[test]
+fn query_needs_an_operation_name_for_several_operations() {
+    let server = graphql_server();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("viewer.graphql"), DOCUMENT).unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "query", "--file", "viewer.graphql"])
+        .assert()
+        .failure();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("several operations (Viewer, Follow)"),
+        "{}",
+        message
+    );
+
+    uxc(&home)
+        .args([&server.url(), "query", "--file", "missing.graphql"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,165 @@
+//! GraphQL persisted queries (`--persisted-queries`, `--persisted-query-id`)
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use serde_json::json;
+use tempfile::TempDir;
+use uxc_core::adapters::graphql::persisted;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+const DOCUMENT: &str = "query Viewer { viewer { login } }";
+
+fn graphql_server() -> mockito::ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "data": { "__schema": {
+                "queryType": { "name": "Query", "fields": [] },
+                "mutationType": null,
+                "subscriptionType": null
+            } } })
+            .to_string(),
+        )
+        .create();
+    server
+}
+
+fn persisted_query(id: &str) -> Matcher {
+    Matcher::PartialJson(json!({
+        "extensions": { "persistedQuery": { "version": 1, "sha256Hash": id } }
+    }))
+}
+
+fn has_document(request: &mockito::Request) -> bool {
+    request
+        .utf8_lossy_body()
+        .is_ok_and(|body| body.contains("\"query\""))
+}
+
+#[test]
+fn apq_sends_the_document_only_when_the_hash_is_unknown() {
+    let mut server = graphql_server();
+    let hash = persisted::hash(DOCUMENT);
+    let unknown = server
+        .mock("POST", "/")
+        .match_body(persisted_query(&hash))
+        .match_request(|request| !has_document(request))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errors":[{"message":"PersistedQueryNotFound"}]}"#)
+        .create();
+    let registered = server
+        .mock("POST", "/")
+        .match_body(Matcher::AllOf(vec![
+            persisted_query(&hash),
+            Matcher::PartialJson(json!({ "query": DOCUMENT })),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data":{"viewer":{"login":"octocat"}}}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("viewer.graphql"), DOCUMENT).unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "query",
+            "--file",
+            "viewer.graphql",
+            "--persisted-queries",
+            "apq",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        stdout_json(output.get_output())["data"]["viewer"]["login"],
+        "octocat"
+    );
+    unknown.assert();
+    registered.assert();
+}
+
+#[test]
+fn registered_ids_are_sent_without_the_document() {
+    let mut server = graphql_server();
+    let call = server
+        .mock("POST", "/")
+        .match_body(persisted_query("viewer-v1"))
+        .match_request(|request| !has_document(request))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data":{"viewer":{"login":"octocat"}}}"#)
+        .create();
+    let missing = server
+        .mock("POST", "/")
+        .match_body(persisted_query("viewer-v0"))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"errors":[{"message":"not registered","extensions":{"code":"PERSISTED_QUERY_NOT_FOUND"}}]}"#,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("viewer.graphql"), DOCUMENT).unwrap();
+
+    uxc(&home)
+        .args([
+            &server.url(),
+            "query",
+            "--file",
+            "viewer.graphql",
+            "--persisted-query-id",
+            "viewer-v1",
+        ])
+        .assert()
+        .success();
+    call.assert();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "query",
+            "--file",
+            "viewer.graphql",
+            "--persisted-query-id",
+            "viewer-v0",
+        ])
+        .assert()
+        .failure();
+    let message = stdout_json(output.get_output())["error"]["message"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(
+        message.contains("no persisted query 'viewer-v0'"),
+        "{}",
+        message
+    );
+    missing.assert();
+
+    uxc(&home)
+        .args([
+            &server.url(),
+            "query",
+            "--file",
+            "viewer.graphql",
+            "--persisted-queries",
+            "apq",
+            "--persisted-query-id",
+            "viewer-v1",
+        ])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,122 @@
+//! GraphQL schemas given as SDL with `--schema-url`
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use serde_json::json;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+const SDL: &str = r#"
+type Query {
+  "A user by login"
+  user(login: String!): User
+}
+
+type User {
+  login: String!
+  name: String
+}
+"#;
+
+/// A server that rejects introspection, as production servers often do,
+/// answering with `status`
+fn server_without_introspection(status: usize) -> mockito::ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("POST", "/graphql")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_status(status)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errors":[{"message":"GraphQL introspection is not allowed"}]}"#)
+        .create();
+    server
+}
+
+#[test]
+fn sdl_file_stands_in_for_disabled_introspection() {
+    let mut server = server_without_introspection(400);
+    let call = server
+        .mock("POST", "/graphql")
+        .match_body(Matcher::PartialJson(json!({
+            "query": r#"query { user(login: "octocat") { login name } }"#
+        })))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data":{"user":{"login":"octocat","name":"The Octocat"}}}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("schema.graphql"), SDL).unwrap();
+    let endpoint = format!("{}/graphql", server.url());
+
+    let output = uxc(&home)
+        .args([&endpoint, "--schema-url", "schema.graphql", "list"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["protocol"], "graphql");
+    assert_eq!(json["data"]["operations"][0]["operation_id"], "query/user");
+
+    let output = uxc(&home)
+        .args([
+            &endpoint,
+            "--schema-url",
+            "schema.graphql",
+            "query/user",
+            "login=octocat",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        stdout_json(output.get_output())["data"]["user"]["name"],
+        "The Octocat"
+    );
+    call.assert();
+}
+
+#[test]
+fn sdl_is_fetched_from_a_url() {
+    let mut server = server_without_introspection(400);
+    server
+        .mock("GET", "/schema.graphql")
+        .with_body(SDL)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &format!("{}/graphql", server.url()),
+            "--schema-url",
+            &format!("{}/schema.graphql", server.url()),
+            "query/user",
+            "help",
+        ])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"]["description"], "A user by login");
+    assert_eq!(json["data"]["fields"][1]["name"], "name");
+}
+
+#[test]
+fn disabled_introspection_points_at_schema_url() {
+    let server = server_without_introspection(200);
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&format!("{}/graphql", server.url()), "list"])
+        .assert()
+        .failure();
+    let json = stdout_json(output.get_output());
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains("--schema-url"), "{}", message);
+}
@@ -0,0 +1,170 @@
+//! GraphQL `--select` and `--depth` integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use serde_json::json;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn graphql_server() -> mockito::ServerGuard {
+    let scalar = |name: &str| json!({ "kind": "SCALAR", "name": name, "ofType": null });
+    let object = |name: &str| json!({ "kind": "OBJECT", "name": name, "ofType": null });
+    let field = |name: &str, type_ref: serde_json::Value| json!({ "name": name, "description": null, "args": [], "type": type_ref });
+    let repository = json!({
+        "name": "repository",
+        "description": "A repository by name",
+        "args": [{ "name": "name", "description": null, "type": scalar("String") }],
+        "type": object("Repository")
+    });
+    let schema = json!({ "data": { "__schema": {
+        "queryType": { "name": "Query", "fields": [repository] },
+        "mutationType": null,
+        "subscriptionType": null,
+        "types": [
+            { "kind": "OBJECT", "name": "Repository", "fields": [
+                field("id", scalar("ID")),
+                field("name", scalar("String")),
+                field("owner", object("User"))
+            ] },
+            { "kind": "OBJECT", "name": "User", "fields": [
+                field("login", scalar("String"))
+            ] },
+            { "kind": "SCALAR", "name": "ID" },
+            { "kind": "SCALAR", "name": "String" }
+        ]
+    } } });
+
+    let mut server = Server::new();
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::Regex("__schema".to_string()))
+        .with_header("content-type", "application/json")
+        .with_body(schema.to_string())
+        .create();
+    server
+}
+
+fn expect_query(server: &mut mockito::ServerGuard, query: &str) -> mockito::Mock {
+    server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({ "query": query })))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data":{"repository":{"id":"1"}}}"#)
+        .create()
+}
+
+#[test]
+fn calls_select_scalar_fields_by_default_and_deeper_with_depth() {
+    let mut server = graphql_server();
+    let home = TempDir::new().unwrap();
+
+    let shallow = expect_query(
+        &mut server,
+        r#"query { repository(name: "uxc") { id name } }"#,
+    );
+    uxc(&home)
+        .args([&server.url(), "query/repository", "name=uxc"])
+        .assert()
+        .success();
+    shallow.assert();
+
+    let deep = expect_query(
+        &mut server,
+        r#"query { repository(name: "uxc") { id name owner { login } } }"#,
+    );
+    uxc(&home)
+        .args([
+            &server.url(),
+            "query/repository",
+            "name=uxc",
+            "--depth",
+            "2",
+        ])
+        .assert()
+        .success();
+    deep.assert();
+}
+
+#[test]
+fn select_picks_fields_and_rejects_unknown_ones() {
+    let mut server = graphql_server();
+    let home = TempDir::new().unwrap();
+
+    let selected = expect_query(
+        &mut server,
+        r#"query { repository(name: "uxc") { name owner { login } } }"#,
+    );
+    uxc(&home)
+        .args([
+            &server.url(),
+            "query/repository",
+            "name=uxc",
+            "--select",
+            "name,owner{login}",
+        ])
+        .assert()
+        .success();
+    selected.assert();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "query/repository",
+            "name=uxc",
+            "--select",
+            "name,stars",
+        ])
+        .assert()
+        .failure();
+    let message = stdout_json(output.get_output())["error"]["message"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(
+        message.contains("Repository has no field 'stars' (fields: id, name, owner)"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn describe_lists_the_selectable_fields() {
+    let server = graphql_server();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "query/repository", "help"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(
+        json["data"]["fields"],
+        json!([
+            { "name": "id", "type": "ID" },
+            { "name": "name", "type": "String" },
+            { "name": "owner", "type": "User" }
+        ])
+    );
+
+    let output = uxc(&home)
+        .args([&server.url(), "query/repository", "help", "--text"])
+        .assert()
+        .success();
+    let text = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(
+        text.contains("Result Fields (--select):\n- id (ID)"),
+        "{}",
+        text
+    );
+}
@@ -74,6 +74,40 @@ fn cache_and_auth_commands_default_to_json() {
     assert_eq!(auth_json["kind"], "auth_list");
 }
 
+#[test]
+fn support_matrix_covers_every_protocol() {
+    let output = uxc_command()
+        .arg("support")
+        .output()
+        .expect("failed to run uxc support");
+
+    assert!(output.status.success(), "support should succeed");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be valid JSON");
+    assert_eq!(json["kind"], "support_matrix");
+    let protocols = json["data"]["protocols"].as_array().unwrap();
+    let names: Vec<_> = protocols
+        .iter()
+        .map(|p| p["protocol"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["mcp", "graphql", "openapi", "jsonrpc", "grpc"]);
+    let grpc = &protocols[4];
+    assert_eq!(grpc["streaming"], true);
+    assert_eq!(grpc["schema_export"], false);
+    assert!(!grpc["auth_types"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("session")));
+
+    let text = uxc_command()
+        .args(["support", "--text"])
+        .output()
+        .expect("failed to run uxc support --text");
+    let stdout = String::from_utf8_lossy(&text.stdout);
+    assert!(stdout.starts_with("Feature"));
+    assert!(stdout.contains("auth: oauth"));
+}
+
 #[test]
 fn operation_help_works_with_dynamic_syntax() {
     let mut server = mockito::Server::new();
@@ -0,0 +1,242 @@
+//! Call history integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn json_output(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.assert().success();
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+#[test]
+fn calls_are_recorded_and_rank_operations() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/owners": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/toys": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    for path in ["/pets", "/toys"] {
+        server
+            .mock("GET", path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create();
+    }
+
+    let home = TempDir::new().unwrap();
+    for operation in ["get:/pets", "get:/pets", "get:/toys"] {
+        uxc(&home)
+            .args([&server.url(), operation])
+            .assert()
+            .success();
+    }
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    assert_eq!(history.lines().count(), 3);
+    assert!(history
+        .lines()
+        .all(|line| line.contains(r#""status":"OK""#)));
+
+    let list = json_output(uxc(&home).args([&server.url(), "list", "--sort", "frequent"]));
+    let ids = list["data"]["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|op| op["operation_id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["get:/pets", "get:/toys", "get:/owners"]);
+    assert_eq!(list["data"]["operations"][0]["usage"]["count"], 2);
+    assert!(list["data"]["operations"][2].get("usage").is_none());
+
+    let help = json_output(uxc(&home).arg(server.url()));
+    let recent = help["data"]["recent"].as_array().unwrap();
+    assert_eq!(recent.len(), 2);
+}
+
+#[test]
+fn history_search_and_stats_filter_recorded_calls() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/toys": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _pets = server
+        .mock("GET", "/pets")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .create();
+    let _toys = server
+        .mock("GET", "/toys")
+        .with_status(500)
+        .with_body("boom")
+        .create();
+
+    let home = TempDir::new().unwrap();
+    for operation in ["get:/pets", "get:/pets", "get:/toys"] {
+        uxc(&home)
+            .args([&server.url(), operation])
+            .output()
+            .unwrap();
+    }
+
+    let errors = json_output(uxc(&home).args([
+        "history",
+        "search",
+        "--operation",
+        "get:/t*",
+        "--status",
+        "error",
+        "--since",
+        "24h",
+    ]));
+    assert_eq!(errors["kind"], "history_search");
+    assert_eq!(errors["data"]["count"], 1);
+    let entry = &errors["data"]["entries"][0];
+    assert_eq!(entry["operation"], "get:/toys");
+    assert_eq!(entry["status"], "ERROR");
+    assert!(entry["duration_ms"].is_u64());
+
+    let stats = json_output(uxc(&home).args(["history", "stats", "--interval", "1d"]));
+    assert_eq!(stats["kind"], "history_stats");
+    assert_eq!(stats["data"]["interval"], 86_400);
+    let operations = stats["data"]["operations"].as_array().unwrap();
+    assert_eq!(operations.len(), 2);
+    assert_eq!(operations[0]["operation"], "get:/pets");
+    assert_eq!(operations[0]["calls"], 2);
+    assert_eq!(operations[0]["success_rate"], 1.0);
+    assert!(operations[0]["p95_ms"].is_u64());
+    assert_eq!(operations[1]["errors"], 1);
+    assert_eq!(operations[0]["windows"].as_array().unwrap().len(), 1);
+
+    uxc(&home)
+        .args(["history", "search", "--since", "soon"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn recorded_calls_can_be_shown_and_rerun() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "post": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let first = server
+        .mock("POST", "/pets")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "status": "available", "api_key": "sekret" }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .create();
+    let home = TempDir::new().unwrap();
+
+    uxc(&home)
+        .args([
+            &server.url(),
+            "post:/pets",
+            "status=available",
+            "api_key=sekret",
+        ])
+        .assert()
+        .success();
+    first.assert();
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    assert!(!history.contains("sekret"), "{}", history);
+
+    let list = json_output(uxc(&home).args(["history", "list"]));
+    let id = list["data"]["entries"][0]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let show = json_output(uxc(&home).args(["history", "show", &id]));
+    assert_eq!(show["kind"], "history_entry");
+    assert_eq!(show["data"]["args"]["status"], "available");
+    assert_eq!(show["data"]["args"]["api_key"], "***");
+    assert_eq!(show["data"]["redacted"], serde_json::json!(["api_key"]));
+    assert_eq!(
+        show["next"][0]["command"],
+        format!("uxc history rerun {} -a api_key=<value>", id)
+    );
+
+    // Redacted arguments are not replayed as the mask
+    let output = uxc(&home)
+        .args(["history", "rerun", &id])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+
+    let second = server
+        .mock("POST", "/pets")
+        .match_body(mockito::Matcher::Json(
+            serde_json::json!({ "status": "sold", "api_key": "other" }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .create();
+    let rerun = json_output(uxc(&home).args([
+        "history",
+        "rerun",
+        &id,
+        "-a",
+        "api_key=other",
+        "-a",
+        "status=sold",
+    ]));
+    assert_eq!(rerun["operation"], "post:/pets");
+    second.assert();
+
+    let missing = uxc(&home)
+        .args(["history", "show", "nope"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&missing.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+}
@@ -0,0 +1,182 @@
+//! Hook script integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn envelope(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.output().unwrap();
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|_| panic!("{:?}", output))
+}
+
+fn pets() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "pets", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": {
+        "responses": { "200": { "description": "ok" } }
+      },
+      "post": {
+        "requestBody": {
+          "content": {
+            "application/json": {
+              "schema": {
+                "type": "object",
+                "properties": {
+                  "name": { "type": "string" },
+                  "owner": { "type": "string" }
+                }
+              }
+            }
+          }
+        },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn project_hooks_change_requests_and_results() {
+    let mut server = pets();
+    let created = server
+        .mock("POST", "/pets")
+        .match_body(Matcher::Json(
+            serde_json::json!({ "name": "Rex", "owner": "ops" }),
+        ))
+        .match_header("x-signature", Matcher::Regex("^[0-9a-f]{64}$".into()))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"meta": {"id": 7}, "items": [{"name": "Rex"}]}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir(home.path().join("hooks")).unwrap();
+    std::fs::write(
+        home.path().join("hooks/sign.rhai"),
+        r#"
+        args.owner = "ops";
+        headers["X-Signature"] = hmac_sha256("secret", operation + to_json(args));
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        home.path().join("hooks/unwrap.rhai"),
+        "result = result.items;",
+    )
+    .unwrap();
+    std::fs::write(
+        home.path().join(".uxc.toml"),
+        "[hooks]\npre_request = \"hooks/sign.rhai\"\npost_response = \"hooks/unwrap.rhai\"\n",
+    )
+    .unwrap();
+
+    // Hooks of a project file run only once it is trusted
+    let plan = envelope(uxc(&home).args([&server.url(), "post:/pets", "name=Rex", "--dry-run"]));
+    assert!(
+        !plan["data"].to_string().contains("x-signature"),
+        "{}",
+        plan
+    );
+    let trusted = envelope(uxc(&home).args(["project", "trust"]));
+    assert_eq!(trusted["ok"], true, "{}", trusted);
+
+    let result = envelope(uxc(&home).args([&server.url(), "post:/pets", "name=Rex"]));
+    assert_eq!(result["ok"], true, "{}", result);
+    assert_eq!(result["data"], serde_json::json!([{ "name": "Rex" }]));
+    created.assert();
+
+    // Dry runs show the request as the hook changed it
+    let plan = envelope(uxc(&home).args([&server.url(), "post:/pets", "name=Rex", "--dry-run"]));
+    let plan = plan["data"].to_string();
+    assert!(plan.contains("\"owner\":\"ops\""), "{}", plan);
+    assert!(plan.contains("x-signature"), "{}", plan);
+}
+
+#[test]
+fn alias_hooks_apply_to_the_alias_endpoint() {
+    let mut server = pets();
+    server
+        .mock("GET", "/pets")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"items": [{"name": "Rex"}]}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("count.rhai"),
+        "result = result.items.len();",
+    )
+    .unwrap();
+    std::fs::write(
+        home.path().join("deny.rhai"),
+        r#"if env("PET_TOKEN") == "" { throw "PET_TOKEN is not set"; }"#,
+    )
+    .unwrap();
+
+    let set = envelope(uxc(&home).args([
+        "alias",
+        "set",
+        "pets",
+        &server.url(),
+        "--post-response",
+        "count.rhai",
+    ]));
+    assert_eq!(set["ok"], true, "{}", set);
+    assert_eq!(
+        set["data"]["hooks"]["post_response"],
+        home.path().join("count.rhai").display().to_string()
+    );
+    let result = envelope(uxc(&home).args(["pets", "get:/pets"]));
+    assert_eq!(result["data"], 1, "{}", result);
+    // Called by URL, the alias hooks do not run
+    let result = envelope(uxc(&home).args([&server.url(), "get:/pets"]));
+    assert_eq!(result["data"]["items"][0]["name"], "Rex");
+
+    envelope(uxc(&home).args([
+        "alias",
+        "set",
+        "pets",
+        &server.url(),
+        "--pre-request",
+        "deny.rhai",
+        "--hook-env",
+        "PET_TOKEN",
+    ]));
+    let denied = envelope(
+        uxc(&home)
+            .env_remove("PET_TOKEN")
+            .args(["pets", "get:/pets"]),
+    );
+    assert_eq!(denied["ok"], false);
+    let message = denied["error"]["message"].as_str().unwrap();
+    assert!(message.contains("PET_TOKEN is not set"), "{}", message);
+
+    // Variables the alias does not list are out of reach
+    std::fs::write(
+        home.path().join("deny.rhai"),
+        r#"if env("HOME") == "" { throw "HOME is not set"; }"#,
+    )
+    .unwrap();
+    let denied = envelope(uxc(&home).args(["pets", "get:/pets"]));
+    assert_eq!(denied["ok"], false);
+    let message = denied["error"]["message"].as_str().unwrap();
+    assert!(message.contains("not listed"), "{}", message);
+}
@@ -0,0 +1,119 @@
+//! Host help auth summary and quick-start integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn host_help(home: &TempDir, args: &[&str]) -> serde_json::Value {
+    let output = uxc(home).args(args).assert().success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "host_help");
+    json["data"].clone()
+}
+
+#[test]
+fn host_help_reports_auth_and_a_quick_start() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "security": [{ "api_key": [] }],
+  "components": { "securitySchemes": {
+    "api_key": { "type": "apiKey", "in": "header", "name": "X-API-Key" }
+  } },
+  "paths": {
+    "/pets/{petId}": { "get": {
+      "parameters": [{ "name": "petId", "in": "path", "required": true, "schema": { "type": "string" } }],
+      "responses": { "200": { "description": "ok" } }
+    } },
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _pets = server
+        .mock("GET", "/pets")
+        .with_status(401)
+        .with_body("unauthorized")
+        .create();
+    let url = server.url();
+    let home = TempDir::new().unwrap();
+
+    let data = host_help(&home, &[&url]);
+    assert_eq!(data["auth"]["required"], true);
+    assert!(data["auth"]["profile"].is_null());
+    assert_eq!(
+        data["auth"]["evidence"][0],
+        "schema requires security scheme api_key (apiKey in header X-API-Key)"
+    );
+    assert_eq!(
+        data["quick_start"],
+        serde_json::json!([
+            "uxc auth set default --api-key <key>",
+            format!("uxc {} list", url),
+            format!("uxc {} describe get:/pets", url),
+            format!("uxc {} get:/pets", url),
+        ])
+    );
+
+    uxc(&home).args([&url, "get:/pets"]).assert().failure();
+    uxc(&home)
+        .args(["auth", "set", "petstore", "--api-key", "secret"])
+        .assert()
+        .success();
+
+    let data = host_help(&home, &[&url, "--profile", "petstore"]);
+    assert_eq!(data["auth"]["profile"], "petstore");
+    assert_eq!(data["auth"]["profile_source"], "--profile");
+    assert_eq!(
+        data["auth"]["evidence"][1],
+        "1 earlier call(s) answered HTTP 401 (last: get:/pets)"
+    );
+    // With a profile set up, the quick start begins with list
+    assert_eq!(data["quick_start"][0], format!("uxc {} list", url));
+    assert_eq!(data["quick_start"][2], format!("uxc {} get:/pets", url));
+}
+
+#[test]
+fn open_endpoints_need_no_auth() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets/{petId}": { "get": {
+      "parameters": [{ "name": "petId", "in": "path", "required": true, "schema": { "type": "string" } }],
+      "responses": { "200": { "description": "ok" } }
+    } }
+  }
+}"#,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let data = host_help(&home, &[&server.url()]);
+    assert_eq!(data["auth"]["required"], false);
+    assert!(data["auth"].get("evidence").is_none());
+    assert_eq!(
+        data["quick_start"][2],
+        format!("uxc {} 'get:/pets/{{petId}}' 'petId=<petId>'", server.url())
+    );
+}
@@ -0,0 +1,80 @@
+//! Secret masking integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+#[test]
+fn secrets_quoted_in_error_bodies_are_masked() {
+    let secret = "refresh-9f8e7d6c5b4a";
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": { "/me": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#,
+        )
+        .create();
+    let _token = server
+        .mock("POST", "/token")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"error": "invalid_grant", "detail": "{} is revoked"}}"#,
+            secret
+        ))
+        .create();
+
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args([
+            "auth",
+            "set",
+            "default",
+            "-t",
+            "oauth",
+            "--client-id",
+            "cli",
+        ])
+        .arg("--authorization-url")
+        .arg(format!("{}/authorize", server.url()))
+        .arg("--token-url")
+        .arg(format!("{}/token", server.url()))
+        .assert()
+        .success();
+    let tokens = home.path().join(".uxc/tokens");
+    std::fs::create_dir_all(&tokens).unwrap();
+    std::fs::write(
+        tokens.join(format!(
+            "{}_cli.json",
+            server.host_with_port().replace(':', "_")
+        )),
+        format!(
+            r#"{{"access_token": "expired", "refresh_token": "{}", "expires_at": 1}}"#,
+            secret
+        ),
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .env("RUST_LOG", "debug")
+        .args([server.url().as_str(), "get:/me"])
+        .assert()
+        .failure();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr).to_string();
+    assert!(stdout.contains("*** is revoked"), "{}", stdout);
+    assert!(!stdout.contains(secret), "{}", stdout);
+    assert!(!stderr.contains(secret), "{}", stderr);
+}
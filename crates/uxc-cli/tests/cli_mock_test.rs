@@ -0,0 +1,132 @@
+//! `uxc mock` integration tests
+
+use assert_cmd::Command;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStderr, Stdio};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+/// Kills the mock server when the test ends, passing or not
+struct MockServer {
+    child: Child,
+    /// Kept open so the server can go on logging
+    _stderr: BufReader<ChildStderr>,
+    url: String,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn start_mock(home: &TempDir, schema: &str) -> MockServer {
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+        .env("HOME", home.path())
+        .current_dir(home.path())
+        .args(["mock", schema, "--port", "0"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("listening on") {
+        line.clear();
+        assert_ne!(
+            stderr.read_line(&mut line).unwrap(),
+            0,
+            "mock server exited"
+        );
+    }
+    let url = line.split_whitespace().last().unwrap().to_string();
+    MockServer {
+        child,
+        _stderr: stderr,
+        url,
+    }
+}
+
+#[test]
+fn mock_serves_examples_and_generated_values_from_an_openapi_document() {
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("petstore.yaml"),
+        r#"
+openapi: 3.0.0
+info: { title: petstore, version: 1.0.0 }
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              example: [{ id: 1, name: Rex }]
+  /pets/{id}:
+    get:
+      operationId: getPet
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: integer } }
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema:
+                type: object
+                required: [id, tag]
+                properties:
+                  id: { type: integer }
+                  tag: { type: string, enum: [cat, dog] }
+"#,
+    )
+    .unwrap();
+    let server = start_mock(&home, "petstore.yaml");
+
+    let output = uxc(&home)
+        .args([&server.url, "get:/pets"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["protocol"], "openapi");
+    assert_eq!(
+        envelope["data"],
+        serde_json::json!([{ "id": 1, "name": "Rex" }])
+    );
+
+    let output = uxc(&home)
+        .args([&server.url, "get:/pets/{id}", "id=7"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(envelope["data"]["id"].is_i64());
+    assert!(["cat", "dog"].contains(&envelope["data"]["tag"].as_str().unwrap()));
+}
+
+#[test]
+fn mock_rejects_files_that_are_not_schemas() {
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("notes.json"), r#"{"hello": "world"}"#).unwrap();
+
+    let output = uxc(&home)
+        .args(["mock", "notes.json", "--port", "0"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["ok"], false);
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+}
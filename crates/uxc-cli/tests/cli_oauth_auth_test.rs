@@ -0,0 +1,131 @@
+//! OAuth (authorization code + PKCE) profile integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn set_oauth_profile(home: &TempDir, server: &Server) {
+    uxc(home)
+        .args(["auth", "set", "app", "-t", "oauth", "--client-id", "cli"])
+        .args(["--scope", "read", "--authorization-url"])
+        .arg(format!("{}/authorize", server.url()))
+        .arg("--token-url")
+        .arg(format!("{}/token", server.url()))
+        .assert()
+        .success();
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": { "/me": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#,
+        )
+        .create()
+}
+
+fn write_tokens(home: &TempDir, server: &Server, tokens: serde_json::Value) {
+    let host = server.host_with_port().replace(':', "_");
+    let dir = home.path().join(".uxc/tokens");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(format!("{}_cli.json", host)), tokens.to_string()).unwrap();
+}
+
+#[test]
+fn expired_oauth_token_is_refreshed_before_the_call() {
+    let mut server = Server::new();
+    let refresh = server
+        .mock("POST", "/token")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+            Matcher::UrlEncoded("refresh_token".into(), "refresh-1".into()),
+            Matcher::UrlEncoded("client_id".into(), "cli".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token": "fresh", "token_type": "Bearer", "expires_in": 3600}"#)
+        .expect(1)
+        .create();
+    let _schema = mock_schema(&mut server);
+    let me = server
+        .mock("GET", "/me")
+        .match_header("authorization", "Bearer fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user": "alice"}"#)
+        .expect(2)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    set_oauth_profile(&home, &server);
+    write_tokens(
+        &home,
+        &server,
+        serde_json::json!({"access_token": "stale", "refresh_token": "refresh-1", "expires_at": 1}),
+    );
+
+    for _ in 0..2 {
+        let output = uxc(&home)
+            .args(["--profile", "app", &server.url(), "get:/me"])
+            .assert()
+            .success();
+        assert_eq!(stdout_json(output.get_output())["data"]["user"], "alice");
+    }
+    refresh.assert();
+    me.assert();
+
+    let output = uxc(&home)
+        .args(["auth", "logout", "app"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["kind"], "auth_oauth_logout_result");
+    assert_eq!(json["data"]["cleared"], true);
+}
+
+#[test]
+fn oauth_profile_requires_endpoints_and_a_login() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args(["auth", "set", "app", "-t", "oauth", "--client-id", "cli"])
+        .assert()
+        .failure();
+    assert_eq!(
+        stdout_json(output.get_output())["error"]["code"],
+        "INVALID_ARGUMENT"
+    );
+    uxc(&home)
+        .args(["auth", "set", "plain", "--client-id", "cli"])
+        .args(["--api-key", "token-123"])
+        .assert()
+        .failure();
+
+    set_oauth_profile(&home, &server);
+    let output = uxc(&home)
+        .args(["--profile", "app", &server.url(), "list"])
+        .assert()
+        .failure();
+    assert!(stdout_json(output.get_output())["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("uxc auth login"));
+}
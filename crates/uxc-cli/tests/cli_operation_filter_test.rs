@@ -0,0 +1,125 @@
+//! `list --tag/--method/--path` and `search` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn operation_ids(json: &serde_json::Value) -> Vec<String> {
+    json["data"]["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|op| op["operation_id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+const SCHEMA: &str = r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/repos/{owner}/{repo}": {
+      "get": { "tags": ["repos"], "summary": "Get a repository", "responses": { "200": { "description": "ok" } } },
+      "delete": { "tags": ["repos"], "summary": "Delete a repository", "responses": { "204": { "description": "gone" } } }
+    },
+    "/repos/{owner}/{repo}/issues": {
+      "get": { "tags": ["issues"], "summary": "List issues of a repository", "responses": { "200": { "description": "ok" } } }
+    },
+    "/users/{username}": {
+      "get": { "tags": ["users"], "summary": "Get a user", "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#;
+
+fn schema_server() -> mockito::ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(SCHEMA)
+        .create();
+    server
+}
+
+#[test]
+fn list_filters_by_tag_method_and_path() {
+    let server = schema_server();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "list", "--tag", "repos", "--method", "get"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(operation_ids(&json), vec!["get:/repos/{owner}/{repo}"]);
+    assert_eq!(json["data"]["operations"][0]["tags"][0], "repos");
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "list",
+            "--path",
+            "/repos/**",
+            "--method",
+            "GET",
+        ])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(
+        operation_ids(&json),
+        vec![
+            "get:/repos/{owner}/{repo}",
+            "get:/repos/{owner}/{repo}/issues"
+        ]
+    );
+
+    let output = uxc(&home)
+        .args([&server.url(), "list", "--path", "/repos/*/*"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output())["data"]["count"], 2);
+}
+
+#[test]
+fn search_ranks_fuzzy_matches() {
+    let server = schema_server();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "search", "issues"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["kind"], "operation_search");
+    assert_eq!(
+        operation_ids(&json),
+        vec!["get:/repos/{owner}/{repo}/issues"]
+    );
+
+    // A misspelt word still finds the repository operations, and words in
+    // the id outrank words in the summary
+    let output = uxc(&home)
+        .args([&server.url(), "search", "repositry delete"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(operation_ids(&json)[0], "delete:/repos/{owner}/{repo}");
+
+    let output = uxc(&home)
+        .args([&server.url(), "search", "webhooks"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output())["data"]["count"], 0);
+}
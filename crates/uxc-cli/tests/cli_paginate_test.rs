@@ -0,0 +1,181 @@
+//! `--paginate` integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+const SCHEMA: &str = r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": {
+      "get": {
+        "parameters": [{ "name": "page", "in": "query", "schema": { "type": "integer" } }],
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/events": {
+      "get": {
+        "parameters": [{ "name": "cursor", "in": "query", "schema": { "type": "string" } }],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#;
+
+#[test]
+fn paginate_follows_link_headers() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(SCHEMA)
+        .create();
+    let link = format!("<{}/users?page=2>; rel=\"next\"", server.url());
+    server
+        .mock("GET", "/users")
+        .match_query(Matcher::Exact(String::new()))
+        .with_header("content-type", "application/json")
+        .with_header("link", &link)
+        .with_body(r#"[{"id": 1}, {"id": 2}]"#)
+        .create();
+    server
+        .mock("GET", "/users")
+        .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id": 3}]"#)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "get:/users", "--paginate"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(
+        json["data"],
+        serde_json::json!([{"id": 1}, {"id": 2}, {"id": 3}])
+    );
+    assert_eq!(json["meta"]["pages"], 2);
+
+    // Without the flag only the first page comes back
+    let output = uxc(&home)
+        .args([&server.url(), "get:/users"])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+    assert!(json["meta"].get("pages").is_none());
+}
+
+#[test]
+fn paginate_sends_body_cursors_up_to_max_pages() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(SCHEMA)
+        .create();
+    for (cursor, next) in [(None, "b"), (Some("b"), "c"), (Some("c"), "d")] {
+        let query = match cursor {
+            Some(cursor) => Matcher::UrlEncoded("cursor".into(), cursor.into()),
+            None => Matcher::Exact(String::new()),
+        };
+        server
+            .mock("GET", "/events")
+            .match_query(query)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"events": ["{}"], "next_cursor": "{}"}}"#,
+                next, next
+            ))
+            .create();
+    }
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/events",
+            "--paginate",
+            "--max-pages",
+            "3",
+        ])
+        .assert()
+        .success();
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"], serde_json::json!(["b", "c", "d"]));
+    assert_eq!(json["meta"]["pages"], 3);
+
+    uxc(&home)
+        .args([&server.url(), "list", "--paginate"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn resume_continues_an_interrupted_run() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(SCHEMA)
+        .create();
+    let first = server
+        .mock("GET", "/events")
+        .match_query(Matcher::Exact(String::new()))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"events": ["a"], "next_cursor": "b"}"#)
+        .expect(1)
+        .create();
+    let failing = server
+        .mock("GET", "/events")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "b".into()))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"error": "try again"}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    uxc(&home)
+        .args([&server.url(), "get:/events", "--paginate"])
+        .assert()
+        .failure();
+    let tokens: Vec<_> = std::fs::read_dir(home.path().join(".uxc/resume"))
+        .unwrap()
+        .collect();
+    assert_eq!(tokens.len(), 1);
+
+    failing.remove();
+    server
+        .mock("GET", "/events")
+        .match_query(Matcher::UrlEncoded("cursor".into(), "b".into()))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"events": ["b"]}"#)
+        .create();
+    let output = uxc(&home)
+        .args([&server.url(), "get:/events", "--paginate", "--resume"])
+        .assert()
+        .success();
+    // Items of pages fetched before the interruption are not stored
+    let json = stdout_json(output.get_output());
+    assert_eq!(json["data"], serde_json::json!(["b"]));
+    assert_eq!(json["meta"]["pages"], 2);
+    first.assert();
+
+    // The finished run leaves no token behind
+    let tokens = std::fs::read_dir(home.path().join(".uxc/resume")).unwrap();
+    assert_eq!(tokens.count(), 0);
+}
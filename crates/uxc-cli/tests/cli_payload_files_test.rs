@@ -0,0 +1,123 @@
+//! `--json @file`, `--json -` and `name=@file` argument tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object",
+          "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer" },
+            "bio": { "type": "string" },
+            "avatar": { "type": "string" },
+            "owner": { "type": "object" }
+          }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn json_payloads_come_from_files_and_stdin() {
+    let mut server = petstore();
+    let pets = server
+        .mock("POST", "/pets")
+        .match_body(Matcher::Json(
+            serde_json::json!({ "name": "Rex", "age": 3 }),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":1}"#)
+        .expect(3)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("pet.json"), r#"{"name":"Rex","age":3}"#).unwrap();
+
+    uxc(&home)
+        .args([&server.url(), "post:/pets", "--json", "@pet.json"])
+        .assert()
+        .success();
+    uxc(&home)
+        .args([&server.url(), "call", "post:/pets", "--json", "@pet.json"])
+        .assert()
+        .success();
+    uxc(&home)
+        .args([&server.url(), "post:/pets", "--json", "-"])
+        .write_stdin(r#"{"name":"Rex","age":3}"#)
+        .assert()
+        .success();
+    pets.assert();
+
+    let output = uxc(&home)
+        .args([&server.url(), "post:/pets", "--json", "@missing.json"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("Cannot read --json payload from missing.json"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn argument_values_come_from_files() {
+    let mut server = petstore();
+    let pets = server
+        .mock("POST", "/pets")
+        .match_body(Matcher::Json(serde_json::json!({
+            "name": "Rex",
+            "bio": "Good dog.\n",
+            "avatar": "data:image/png;base64,iVD/",
+            "owner": { "name": "Ann" }
+        })))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":1}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("bio.txt"), "Good dog.\n").unwrap();
+    std::fs::write(home.path().join("owner.json"), r#"{"name":"Ann"}"#).unwrap();
+    std::fs::write(home.path().join("rex.png"), [0x89, 0x50, 0xff]).unwrap();
+
+    uxc(&home)
+        .args([
+            &server.url(),
+            "post:/pets",
+            "name=Rex",
+            "bio=@bio.txt",
+            "avatar=@rex.png;type=image/png",
+            "owner=@owner.json",
+        ])
+        .assert()
+        .success();
+    pets.assert();
+}
@@ -0,0 +1,154 @@
+//! `uxc plugin` integration tests, with a plugin built from source by rustc
+
+use assert_cmd::Command;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Plugin serving `echo://` endpoints with one operation, `echo`, answering
+/// with its arguments. Requests are matched as text: uxc sends compact JSON
+/// with sorted keys.
+const ECHO_PLUGIN: &str = r##"
+use std::ffi::{c_char, CStr, CString};
+
+#[no_mangle]
+pub extern "C" fn uxc_plugin_abi_version() -> u32 {
+    1
+}
+
+#[no_mangle]
+pub extern "C" fn uxc_plugin_call(request: *const c_char) -> *mut c_char {
+    let request = unsafe { CStr::from_ptr(request) }.to_string_lossy().into_owned();
+    let method = |name: &str| request.contains(&format!("\"method\":\"{}\"", name));
+    let response = if method("can_handle") {
+        r#"{"result":true}"#.to_string()
+    } else if method("fetch_schema") {
+        r#"{"result":{"protocol":"echo"}}"#.to_string()
+    } else if method("list_operations") {
+        r#"{"result":[{"operation_id":"echo","display_name":"echo","description":"Answer with the arguments","parameters":[{"name":"message","param_type":"string","required":true,"description":null}],"return_type":null}]}"#.to_string()
+    } else if !request.contains("\"operation\":\"echo\"") {
+        r#"{"error":{"code":"OPERATION_NOT_FOUND","message":"echo has no such operation"}}"#.to_string()
+    } else if method("describe_operation") {
+        r#"{"result":{"operation_id":"echo","display_name":"echo","description":"Answer with the arguments","parameters":[{"name":"message","param_type":"string","required":true,"description":null}],"return_type":null,"input_schema":{"type":"object","properties":{"message":{"type":"string"}},"required":["message"]}}}"#.to_string()
+    } else if method("execute") {
+        let args = &request[request.find("\"args\":").unwrap() + 7..request.find(",\"method\"").unwrap()];
+        format!(r#"{{"result":{{"data":{}}}}}"#, args)
+    } else {
+        r#"{"error":{"code":"METHOD_NOT_FOUND","message":"not supported"}}"#.to_string()
+    };
+    CString::new(response).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn uxc_plugin_free(response: *mut c_char) {
+    drop(unsafe { CString::from_raw(response) });
+}
+"##;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn envelope(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.output().unwrap();
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|_| panic!("{:?}", output))
+}
+
+/// Directory holding the echo plugin, built, and its manifest
+fn build_echo_plugin(dir: &Path) -> PathBuf {
+    let plugin_dir = dir.join("echo-plugin");
+    std::fs::create_dir_all(&plugin_dir).unwrap();
+    let source = plugin_dir.join("echo.rs");
+    std::fs::write(&source, ECHO_PLUGIN).unwrap();
+    let library = format!(
+        "{}echo{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    let status = std::process::Command::new(std::env::var("RUSTC").unwrap_or("rustc".into()))
+        .args(["--edition", "2021", "--crate-type", "cdylib", "-o"])
+        .arg(plugin_dir.join(&library))
+        .arg(&source)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    std::fs::write(
+        plugin_dir.join("plugin.toml"),
+        format!(
+            "name = \"echo\"\nversion = \"0.1.0\"\ndescription = \"Echoes arguments\"\nlibrary = \"{}\"\nschemes = [\"echo\"]\n",
+            library
+        ),
+    )
+    .unwrap();
+    plugin_dir
+}
+
+#[test]
+fn plugins_are_installed_used_and_removed() {
+    let home = TempDir::new().unwrap();
+    let plugin_dir = build_echo_plugin(home.path());
+
+    let installed = envelope(uxc(&home).args(["plugin", "install"]).arg(&plugin_dir));
+    assert_eq!(installed["kind"], "plugin_installed", "{}", installed);
+    assert_eq!(installed["data"]["schemes"], serde_json::json!(["echo"]));
+    assert!(home.path().join(".uxc/plugins/echo/plugin.toml").exists());
+
+    let listed = envelope(uxc(&home).args(["plugin", "list"]));
+    assert_eq!(listed["data"]["count"], 1);
+    assert_eq!(listed["data"]["plugins"][0]["name"], "echo");
+
+    // Endpoints with the plugin's scheme go to the plugin
+    let operations = envelope(uxc(&home).args(["echo://local", "list"]));
+    assert_eq!(operations["protocol"], "plugin", "{}", operations);
+    assert_eq!(operations["data"]["operations"][0]["operation_id"], "echo");
+
+    let result = envelope(uxc(&home).args(["echo://local", "echo", "message=hi"]));
+    assert_eq!(result["ok"], true, "{}", result);
+    assert_eq!(result["data"], serde_json::json!({ "message": "hi" }));
+
+    let missing = envelope(uxc(&home).args(["echo://local", "nope"]));
+    assert_eq!(
+        missing["error"]["code"], "OPERATION_NOT_FOUND",
+        "{}",
+        missing
+    );
+
+    // A second install needs --force
+    let again = envelope(uxc(&home).args(["plugin", "install"]).arg(&plugin_dir));
+    assert_eq!(again["error"]["code"], "INVALID_ARGUMENT");
+    let forced = envelope(
+        uxc(&home)
+            .args(["plugin", "install", "--force"])
+            .arg(&plugin_dir),
+    );
+    assert_eq!(forced["ok"], true, "{}", forced);
+
+    let removed = envelope(uxc(&home).args(["plugin", "remove", "echo"]));
+    assert_eq!(removed["kind"], "plugin_removed");
+    let listed = envelope(uxc(&home).args(["plugin", "list"]));
+    assert_eq!(listed["data"]["count"], 0);
+}
+
+#[test]
+fn libraries_without_the_plugin_abi_are_refused() {
+    let home = TempDir::new().unwrap();
+    let plugin_dir = home.path().join("broken");
+    std::fs::create_dir_all(&plugin_dir).unwrap();
+    std::fs::write(plugin_dir.join("libbroken.so"), b"not a library").unwrap();
+    std::fs::write(
+        plugin_dir.join("plugin.toml"),
+        "name = \"broken\"\nversion = \"0.1.0\"\nlibrary = \"libbroken.so\"\nschemes = [\"broken\"]\n",
+    )
+    .unwrap();
+
+    let output = envelope(uxc(&home).args(["plugin", "install"]).arg(&plugin_dir));
+    assert_eq!(output["error"]["code"], "INVALID_ARGUMENT", "{}", output);
+    assert!(output["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("is not a uxc plugin"));
+    assert!(!home.path().join(".uxc/plugins/broken").exists());
+}
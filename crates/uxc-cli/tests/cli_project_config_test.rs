@@ -0,0 +1,184 @@
+//! Project-local `.uxc.toml` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir, dir: &Path) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(dir);
+    cmd
+}
+
+fn json_output(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.assert().success();
+    serde_json::from_slice(&output.get_output().stdout).unwrap()
+}
+
+#[test]
+fn project_file_supplies_endpoint_profile_headers_and_aliases() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let pets = server
+        .mock("GET", "/pets")
+        .match_header("authorization", "Bearer staging-token")
+        .match_header("x-team", "payments")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"name":"rex"}]"#)
+        .expect(2)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    std::fs::write(
+        project.path().join(".uxc.toml"),
+        format!(
+            r#"endpoint = "{url}"
+profile = "staging"
+headers = {{ "X-Team" = "payments" }}
+
+[aliases.api]
+url = "{url}"
+"#,
+            url = server.url()
+        ),
+    )
+    .unwrap();
+    let nested = project.path().join("services/billing");
+    std::fs::create_dir_all(&nested).unwrap();
+
+    uxc(&home, &nested)
+        .args(["auth", "set", "staging", "--api-key", "staging-token"])
+        .assert()
+        .success();
+
+    // The project's endpoint gets no credentials until the file is trusted
+    for args in [["call", "get:/pets"], ["api", "get:/pets"]] {
+        let output = uxc(&home, &nested).args(args).assert().failure();
+        let envelope: serde_json::Value =
+            serde_json::from_slice(&output.get_output().stdout).unwrap();
+        assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+        assert!(envelope["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("uxc project trust"));
+    }
+    let status = json_output(uxc(&home, &nested).args(["project", "status"]));
+    assert_eq!(status["data"]["trusted"], false);
+    let trusted = json_output(uxc(&home, &nested).args(["project", "trust"]));
+    assert_eq!(trusted["data"]["trusted"], true);
+
+    let call = json_output(uxc(&home, &nested).args(["call", "get:/pets"]));
+    assert_eq!(call["endpoint"], server.url());
+    assert_eq!(call["data"][0]["name"], "rex");
+
+    let aliased = json_output(uxc(&home, &nested).args(["api", "get:/pets"]));
+    assert_eq!(aliased["data"][0]["name"], "rex");
+    pets.assert();
+
+    // Outside the project nothing applies
+    let outside = TempDir::new().unwrap();
+    let output = uxc(&home, outside.path())
+        .args(["call", "get:/pets"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+}
+
+#[test]
+fn editing_a_trusted_project_file_revokes_its_trust() {
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    let file = project.path().join(".uxc.toml");
+    std::fs::write(&file, "endpoint = \"https://staging.example.com\"\n").unwrap();
+
+    json_output(uxc(&home, project.path()).args(["project", "trust"]));
+    let status = json_output(uxc(&home, project.path()).args(["project", "status"]));
+    assert_eq!(status["data"]["trusted"], true);
+
+    std::fs::write(&file, "endpoint = \"https://evil.example.com\"\n").unwrap();
+    let status = json_output(uxc(&home, project.path()).args(["project", "status"]));
+    assert_eq!(status["data"]["trusted"], false);
+
+    json_output(uxc(&home, project.path()).args(["project", "trust"]));
+    let untrusted = json_output(uxc(&home, project.path()).args(["project", "untrust"]));
+    assert_eq!(untrusted["data"]["trusted"], false);
+    let status = json_output(uxc(&home, project.path()).args(["project", "status"]));
+    assert_eq!(status["data"]["trusted"], false);
+}
+
+#[test]
+fn broken_project_file_is_reported() {
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    std::fs::write(project.path().join(".uxc.toml"), "endpoint = [").unwrap();
+
+    let output = uxc(&home, project.path())
+        .args(["call", "get:/pets"])
+        .assert()
+        .failure();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(stdout.contains(".uxc.toml"), "{}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn untrusted_project_files_cannot_set_the_cache_key_proxy_or_location() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"openapi":"3.0.0","info":{"title":"t","version":"1"},"paths":{"/ping":{"get":{"responses":{"200":{"description":"ok"}}}}}}"#,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    let marker = project.path().join("ran");
+    std::fs::write(
+        project.path().join(".uxc.toml"),
+        format!(
+            r#"[cache]
+encryption_key = "cmd:touch {marker} && echo key"
+location = "{location}"
+
+[network]
+proxy = "http://127.0.0.1:9"
+"#,
+            marker = marker.display(),
+            location = project.path().join("stolen").display(),
+        ),
+    )
+    .unwrap();
+
+    json_output(uxc(&home, project.path()).args([server.url().as_str(), "list"]));
+    assert!(!marker.exists());
+    assert!(!project.path().join("stolen").exists());
+    for key in ["cache.encryption_key", "cache.location", "network.proxy"] {
+        let get = json_output(uxc(&home, project.path()).args(["config", "get", key]));
+        assert_ne!(get["data"]["source"], "project", "{}", key);
+    }
+
+    json_output(uxc(&home, project.path()).args(["project", "trust"]));
+    let get = json_output(uxc(&home, project.path()).args(["config", "get", "network.proxy"]));
+    assert_eq!(get["data"]["source"], "project");
+}
@@ -0,0 +1,243 @@
+//! Output shaping integration tests: `--query`, `--template`, `--format csv`,
+//! response headers and `--output`
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn mock_users(server: &mut Server) -> Vec<mockito::Mock> {
+    let schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": {
+      "get": { "summary": "List users", "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#,
+        )
+        .create();
+    let users = server
+        .mock("GET", "/users")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v7\"")
+        .with_header("x-request-id", "req-1")
+        .with_body(r#"{"data": [{"name": "ann", "age": 31}, {"name": "bob", "age": 25}]}"#)
+        .create();
+    vec![schema, users]
+}
+
+#[test]
+fn query_filters_call_result_in_json_and_text() {
+    let mut server = Server::new();
+    let _mocks = mock_users(&mut server);
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            server.url().as_str(),
+            "get:/users",
+            "--query",
+            "data[].name",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["kind"], "call_result");
+    assert_eq!(json["data"], serde_json::json!(["ann", "bob"]));
+
+    let output = uxc(&home)
+        .args([
+            "--text",
+            "--query=data[?age > `30`] | [0].name",
+            server.url().as_str(),
+            "get:/users",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8_lossy(&output.get_output().stdout).trim(),
+        "ann"
+    );
+}
+
+#[test]
+fn invalid_query_is_rejected_before_the_call() {
+    let mut server = Server::new();
+    let users = server.mock("GET", "/users").expect(0).create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([server.url().as_str(), "get:/users", "--query", "data[?"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["error"]["code"], "INVALID_ARGUMENT");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid --query"));
+    users.assert();
+}
+
+#[test]
+fn template_renders_queried_envelope_from_file() {
+    let mut server = Server::new();
+    let _mocks = mock_users(&mut server);
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("report.j2"),
+        "{{ kind }}:{% for name in data %} {{ name }}{% endfor %}",
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .args([
+            server.url().as_str(),
+            "get:/users",
+            "--query",
+            "data[].name",
+            "--template",
+            "@report.j2",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8_lossy(&output.get_output().stdout),
+        "call_result: ann bob\n"
+    );
+}
+
+#[test]
+fn csv_format_writes_rows_with_query_selected_columns() {
+    let mut server = Server::new();
+    let _mocks = mock_users(&mut server);
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([server.url().as_str(), "get:/users", "--format", "csv"])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8_lossy(&output.get_output().stdout),
+        "age,name\n31,ann\n25,bob\n"
+    );
+
+    let output = uxc(&home)
+        .args([
+            "--format=csv",
+            "--query",
+            "data[].{years: age, user: name}",
+            server.url().as_str(),
+            "get:/users",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8_lossy(&output.get_output().stdout),
+        "years,user\n31,ann\n25,bob\n"
+    );
+}
+
+#[test]
+fn include_headers_and_expect_header() {
+    let mut server = Server::new();
+    let _mocks = mock_users(&mut server);
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            server.url().as_str(),
+            "get:/users",
+            "--include-headers",
+            "ETag,x-request-*",
+            "--expect-header",
+            "x-request-id=req-1",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(
+        json["meta"]["headers"],
+        serde_json::json!({"etag": "\"v7\"", "x-request-id": "req-1"})
+    );
+
+    let output = uxc(&home)
+        .args([
+            server.url().as_str(),
+            "get:/users",
+            "--expect-header",
+            "location",
+        ])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "EXPECTATION_FAILED");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("header location: expected to be present"));
+}
+
+#[test]
+fn output_writes_file_or_numbered_files_and_reports_path() {
+    let mut server = Server::new();
+    let _mocks = mock_users(&mut server);
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            server.url().as_str(),
+            "get:/users",
+            "--query",
+            "data[].name",
+            "--output",
+            "out/users.json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "output_file");
+    assert_eq!(json["data"]["kind"], "call_result");
+    assert_eq!(json["data"]["path"], "out/users.json");
+    let written: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(home.path().join("out/users.json")).unwrap())
+            .unwrap();
+    assert_eq!(written["data"], serde_json::json!(["ann", "bob"]));
+
+    for _ in 0..2 {
+        uxc(&home)
+            .args([
+                "--format=csv",
+                "-o",
+                "runs/",
+                server.url().as_str(),
+                "get:/users",
+            ])
+            .assert()
+            .success();
+    }
+    assert_eq!(
+        std::fs::read_to_string(home.path().join("runs/000002.csv")).unwrap(),
+        "age,name\n31,ann\n25,bob\n"
+    );
+
+    uxc(&home)
+        .args([server.url().as_str(), "get:/users", "--text", "-o", "x.txt"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,93 @@
+//! `--raw` response body passthrough integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/logo.png": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/missing": { "get": { "responses": { "404": { "description": "gone" } } } }
+  }
+}"#,
+        )
+        .create()
+}
+
+#[test]
+fn raw_writes_binary_body_unmodified() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let body: &[u8] = &[0x89, b'P', b'N', b'G', 0x00, 0xff, 0x10];
+    let _logo = server
+        .mock("GET", "/logo.png")
+        .with_status(200)
+        .with_header("content-type", "image/png")
+        .with_body(body)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([server.url().as_str(), "get:/logo.png", "--raw"])
+        .assert()
+        .success();
+    assert_eq!(output.get_output().stdout, body);
+
+    let output = uxc(&home)
+        .args([
+            server.url().as_str(),
+            "get:/logo.png",
+            "--raw",
+            "-o",
+            "img/",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "raw_output");
+    assert_eq!(json["data"]["path"], "img/000001.png");
+    assert_eq!(
+        std::fs::read(home.path().join("img/000001.png")).unwrap(),
+        body
+    );
+}
+
+#[test]
+fn raw_error_status_keeps_body_and_fails() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let _missing = server
+        .mock("GET", "/missing")
+        .with_status(404)
+        .with_header("content-type", "text/plain")
+        .with_body("not here")
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args(["--raw", server.url().as_str(), "get:/missing"])
+        .assert()
+        .failure();
+    assert_eq!(output.get_output().stdout, b"not here");
+
+    uxc(&home)
+        .args([server.url().as_str(), "list", "--raw"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicates::str::contains("only apply to operation calls"));
+}
@@ -0,0 +1,197 @@
+//! Redirect policy integration tests: `--max-redirects`, `--no-follow-redirects`
+//! and credential stripping on cross-origin redirects
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/report": { "get": { "responses": { "200": { "description": "ok" } } } },
+    "/loop": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create()
+}
+
+fn set_bearer_profile(home: &TempDir) {
+    uxc(home)
+        .args(["auth", "set", "ci", "--api-key", "secret-token"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn cross_origin_redirect_drops_credentials_unless_kept() {
+    let mut api = Server::new();
+    let mut cdn = Server::new();
+    let _schema = mock_schema(&mut api);
+    let _report = api
+        .mock("GET", "/report")
+        .with_status(302)
+        .with_header("location", &format!("{}/files/report", cdn.url()))
+        .create();
+    let anonymous = cdn
+        .mock("GET", "/files/report")
+        .match_header("authorization", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"rows": 3}"#)
+        .expect(1)
+        .create();
+    let home = TempDir::new().unwrap();
+    set_bearer_profile(&home);
+
+    let output = uxc(&home)
+        .args(["--profile", "ci", api.url().as_str(), "get:/report"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["rows"], 3);
+    anonymous.assert();
+
+    let authorized = cdn
+        .mock("GET", "/files/report")
+        .match_header("authorization", "Bearer secret-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"rows": 4}"#)
+        .expect(1)
+        .create();
+    uxc(&home)
+        .args([
+            "--profile",
+            "ci",
+            "--keep-auth-on-redirect",
+            api.url().as_str(),
+            "get:/report",
+        ])
+        .assert()
+        .success();
+    authorized.assert();
+}
+
+#[test]
+fn cross_origin_redirect_drops_configured_headers() {
+    let mut api = Server::new();
+    let mut cdn = Server::new();
+    let _schema = mock_schema(&mut api);
+    let _report = api
+        .mock("GET", "/report")
+        .match_header("x-api-key", "project-key")
+        .with_status(302)
+        .with_header("location", &format!("{}/files/report", cdn.url()))
+        .create();
+    let anonymous = cdn
+        .mock("GET", "/files/report")
+        .match_header("x-api-key", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"rows": 3}"#)
+        .expect(1)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join(".uxc.toml"),
+        r#"headers = { "X-Api-Key" = "project-key" }"#,
+    )
+    .unwrap();
+
+    uxc(&home)
+        .args([api.url().as_str(), "get:/report"])
+        .assert()
+        .success();
+    anonymous.assert();
+}
+
+#[test]
+fn redirect_limits_and_no_follow() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let _loop = server
+        .mock("GET", "/loop")
+        .with_status(307)
+        .with_header("location", "/loop")
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([server.url().as_str(), "get:/loop", "--max-redirects", "2"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Too many redirects (more than 2)"));
+
+    uxc(&home)
+        .args(["--no-follow-redirects", server.url().as_str(), "get:/loop"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "HTTP 307 redirect to /loop was not followed",
+        ));
+}
+
+#[test]
+fn redirect_to_a_host_outside_the_allowlist_is_refused() {
+    let mut api = Server::new();
+    let mut elsewhere = Server::new();
+    let _schema = mock_schema(&mut api);
+    let _report = api
+        .mock("GET", "/report")
+        .with_status(302)
+        .with_header("location", &format!("{}/files/report", elsewhere.url()))
+        .create();
+    let followed = elsewhere
+        .mock("GET", "/files/report")
+        .with_status(200)
+        .with_body(r#"{"rows": 3}"#)
+        .expect(0)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let api_host = api.host_with_port();
+    let output = uxc(&home)
+        .env_remove("UXC_ALLOWED_HOSTS")
+        .args([
+            "--allowed-hosts",
+            api_host.as_str(),
+            api.url().as_str(),
+            "get:/report",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "HOST_NOT_ALLOWED");
+    followed.assert();
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    let blocked: Vec<serde_json::Value> = history
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .filter(|entry: &serde_json::Value| entry["status"] == "BLOCKED")
+        .collect();
+    assert_eq!(blocked.len(), 1, "{}", history);
+    assert!(blocked[0]["endpoint"]
+        .as_str()
+        .unwrap()
+        .starts_with(&elsewhere.url()));
+}
@@ -0,0 +1,139 @@
+//! Documented responses and `--validate-response` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r##"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": {
+        "responses": {
+          "200": {
+            "description": "The pets",
+            "content": {
+              "application/json": {
+                "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } }
+              }
+            }
+          },
+          "default": { "description": "An error" }
+        }
+      }
+    },
+    "/pets/{id}": {
+      "get": {
+        "parameters": [{ "name": "id", "in": "path", "required": true }],
+        "responses": {
+          "200": {
+            "description": "One pet",
+            "content": {
+              "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+            }
+          }
+        }
+      }
+    }
+  },
+  "components": {
+    "schemas": {
+      "Pet": {
+        "type": "object",
+        "required": ["id", "name"],
+        "properties": { "id": { "type": "integer" }, "name": { "type": "string" } }
+      }
+    }
+  }
+}"##,
+        )
+        .create();
+    server
+        .mock("GET", "/pets")
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id": 1, "name": "Rex"}]"#)
+        .create();
+    server
+        .mock("GET", "/pets/2")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": "2", "color": "red"}"#)
+        .create();
+    server
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn describe_shows_responses_by_status() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "describe", "get:/pets"])
+        .assert()
+        .success();
+    let data = &stdout_json(output.get_output())["data"];
+    assert_eq!(data["return_type"], "array");
+    assert_eq!(data["responses"]["200"]["description"], "The pets");
+    assert_eq!(
+        data["responses"]["200"]["schema"]["items"]["properties"]["id"]["type"],
+        "integer"
+    );
+    assert_eq!(data["responses"]["default"]["description"], "An error");
+}
+
+#[test]
+fn validate_response_flags_undocumented_fields_and_types() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "get:/pets", "--validate-response"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output())["data"][0]["name"], "Rex");
+
+    // Without the flag the response is returned as is
+    uxc(&home)
+        .args([&server.url(), "get:/pets/{id}", "id=2"])
+        .assert()
+        .success();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/pets/{id}",
+            "id=2",
+            "--validate-response",
+        ])
+        .assert()
+        .failure();
+    let error = &stdout_json(output.get_output())["error"];
+    assert_eq!(error["code"], "EXPECTATION_FAILED");
+    let message = error["message"].as_str().unwrap();
+    for violation in [
+        "$.id: expected type integer but got string",
+        "$: missing required property 'name'",
+        "$: property 'color' is not documented",
+    ] {
+        assert!(message.contains(violation), "{}", message);
+    }
+}
@@ -0,0 +1,106 @@
+//! `--retries`, `--retry-backoff` and `--retry-on` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn temporary_failures_are_retried_after_the_requested_delay() {
+    let mut server = petstore();
+    let busy = server
+        .mock("GET", "/pets")
+        .with_status(503)
+        .with_header("retry-after", "0")
+        .expect(1)
+        .create();
+    let pets = server
+        .mock("GET", "/pets")
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"name":"Rex"}]"#)
+        .expect(1)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "get:/pets", "--retries", "2"])
+        .assert()
+        .success();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["data"], serde_json::json!([{ "name": "Rex" }]));
+    busy.assert();
+    pets.assert();
+}
+
+#[test]
+fn retry_on_chooses_the_retried_statuses() {
+    let mut server = petstore();
+    let home = TempDir::new().unwrap();
+
+    let failing = server
+        .mock("GET", "/pets")
+        .with_status(500)
+        .expect(1)
+        .create();
+    uxc(&home)
+        .args([&server.url(), "get:/pets", "--retries", "2"])
+        .assert()
+        .failure();
+    failing.assert();
+    failing.remove();
+
+    let failing = server
+        .mock("GET", "/pets")
+        .with_status(500)
+        .expect(3)
+        .create();
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/pets",
+            "--retries",
+            "2",
+            "--retry-on",
+            "500,UNAVAILABLE",
+            "--retry-backoff",
+            "10ms",
+        ])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["detail"]["status"], 500);
+    failing.assert();
+
+    let output = uxc(&home)
+        .args([&server.url(), "get:/pets", "--retry-on", "sometimes"])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+}
@@ -0,0 +1,149 @@
+//! `uxc run <file>` integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": { "responses": { "200": { "description": "ok" } } },
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object",
+          "properties": { "name": { "type": "string" }, "age": { "type": "integer" } }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/broken": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+        .mock("GET", "/pets")
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"name":"Rex"}]"#)
+        .create();
+    server
+}
+
+#[test]
+fn requests_run_with_an_envelope_each() {
+    let mut server = petstore();
+    let create = server
+        .mock("POST", "/pets")
+        .match_body(Matcher::Json(
+            serde_json::json!({ "name": "Tom", "age": 2 }),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":7}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("pets.json"), r#"[{"name":"Rex"}]"#).unwrap();
+    std::fs::write(
+        home.path().join("requests.yaml"),
+        format!(
+            r#"
+endpoint: {}
+concurrency: 2
+requests:
+  - name: list
+    operation: get:/pets
+    expected: pets.json
+  - name: create
+    operation: post:/pets
+    args: {{ name: Tom, age: "2" }}
+"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let output = uxc(&home).args(["run", "requests.yaml"]).assert().success();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["kind"], "run_report");
+    let data = &envelope["data"];
+    assert_eq!(data["name"], "requests");
+    assert_eq!(
+        (data["total"].as_u64(), data["succeeded"].as_u64()),
+        (Some(2), Some(2))
+    );
+    assert_eq!(data["requests"][0]["name"], "list");
+    assert_eq!(data["requests"][0]["envelope"]["kind"], "call_result");
+    assert_eq!(data["requests"][1]["envelope"]["data"]["id"], 7);
+    create.assert();
+}
+
+#[test]
+fn mismatches_and_failures_fail_the_run() {
+    let mut server = petstore();
+    server.mock("GET", "/broken").with_status(500).create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(home.path().join("pets.yaml"), "- name: Tom\n").unwrap();
+    std::fs::write(
+        home.path().join("requests.json"),
+        serde_json::json!({
+            "endpoint": server.url(),
+            "requests": [
+                { "operation": "get:/pets", "expected": "pets.yaml" },
+                { "operation": "get:/broken" },
+                { "operation": "get:/pets" }
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = uxc(&home).args(["run", "requests.json"]).assert().code(1);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let data = &envelope["data"];
+    assert_eq!(
+        (data["failed"].as_u64(), data["succeeded"].as_u64()),
+        (Some(2), Some(1))
+    );
+    assert_eq!(
+        data["requests"][0]["differences"],
+        serde_json::json!([{ "path": "/0/name", "before": "Tom", "after": "Rex" }])
+    );
+    assert_eq!(
+        data["requests"][1]["envelope"]["error"]["code"],
+        "EXECUTION_FAILED"
+    );
+
+    let output = uxc(&home)
+        .args(["run", "requests.json", "--fail-fast", "--text"])
+        .assert()
+        .code(1);
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(stdout.contains("FAIL get:/pets"), "{}", stdout);
+    assert!(
+        stdout.contains(r#"~ /0/name: "Tom" -> "Rex""#),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("SKIP get:/broken"), "{}", stdout);
+    assert!(
+        stdout.contains("0 ok, 1 failed (0 timed out), 2 skipped, 3 total"),
+        "{}",
+        stdout
+    );
+}
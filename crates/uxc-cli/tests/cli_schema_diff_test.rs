@@ -0,0 +1,146 @@
+//! `uxc diff` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn api(document: serde_json::Value) -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(document.to_string())
+        .create();
+    server
+}
+
+fn pets(body: serde_json::Value, extra_path: Option<&str>) -> serde_json::Value {
+    let mut document = serde_json::json!({
+        "openapi": "3.0.0",
+        "info": { "title": "pets", "version": "1.0.0" },
+        "paths": {
+            "/pets": {
+                "post": {
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": body } }
+                    },
+                    "responses": { "200": { "description": "ok" } }
+                }
+            }
+        }
+    });
+    if let Some(path) = extra_path {
+        document["paths"][path] =
+            serde_json::json!({ "get": { "responses": { "200": { "description": "ok" } } } });
+    }
+    document
+}
+
+#[test]
+fn diff_reports_breaking_changes_and_fails() {
+    let v1 = api(pets(
+        serde_json::json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" }
+            }
+        }),
+        Some("/owners"),
+    ));
+    let v2 = api(pets(
+        serde_json::json!({
+            "type": "object",
+            "required": ["name", "kind"],
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "string" },
+                "kind": { "type": "string" },
+                "nickname": { "type": "string" }
+            }
+        }),
+        Some("/toys"),
+    ));
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args(["diff", &v1.url(), &v2.url()])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["ok"], true);
+    assert_eq!(envelope["kind"], "schema_diff");
+    let diff = &envelope["data"];
+    assert_eq!(diff["operations_added"], 1);
+    assert_eq!(diff["operations_removed"], 1);
+    assert_eq!(diff["operations_changed"], 1);
+    assert_eq!(diff["breaking"], 3);
+    let changes: Vec<(String, String, String)> = diff["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| {
+            (
+                change["severity"].as_str().unwrap().to_string(),
+                change["kind"].as_str().unwrap().to_string(),
+                change["operation"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    let expected = [
+        ("breaking", "operation_removed", "get:/owners"),
+        ("breaking", "type_changed", "post:/pets"),
+        ("breaking", "parameter_added", "post:/pets"),
+        ("non_breaking", "parameter_added", "post:/pets"),
+        ("non_breaking", "operation_added", "get:/toys"),
+    ];
+    assert_eq!(
+        changes,
+        expected.map(|(a, b, c)| (a.to_string(), b.to_string(), c.to_string()))
+    );
+
+    let text = uxc(&home)
+        .args(["diff", &v1.url(), &v2.url(), "--text"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(text.stdout).unwrap();
+    assert!(stdout.contains("3 breaking of 5 changes"), "{}", stdout);
+    assert!(
+        stdout.contains("post:/pets: 'age' type changed from integer to string"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn diff_of_an_endpoint_with_its_snapshot() {
+    let server = api(pets(serde_json::json!({ "type": "object" }), None));
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home).args(["diff", &server.url()]).output().unwrap();
+    assert!(!output.status.success());
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+
+    uxc(&home).args([&server.url(), "list"]).assert().success();
+    let output = uxc(&home)
+        .args(["diff", &server.url(), "--as-of", "2999-01-01"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["data"]["changes"], serde_json::json!([]));
+}
@@ -0,0 +1,146 @@
+//! `--server` and `--server-var` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn api() -> ServerGuard {
+    let mut server = Server::new();
+    let schema = r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "servers": [
+    {
+      "url": "BASE/{version}",
+      "description": "Production",
+      "variables": { "version": { "default": "v1", "enum": ["v1", "v2"] } }
+    },
+    { "url": "/sandbox", "description": "Sandbox" }
+  ],
+  "paths": { "/pets": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#
+    .replace("BASE", &server.url());
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(schema)
+        .create();
+    for path in ["/pets", "/v1/pets", "/v2/pets", "/sandbox/pets"] {
+        server
+            .mock("GET", path)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"served_by": "{}"}}"#, path))
+            .create();
+    }
+    server
+}
+
+#[test]
+fn server_and_server_vars_choose_the_base_url() {
+    let server = api();
+    let home = TempDir::new().unwrap();
+    let served_by = |args: &[&str]| {
+        let output = uxc(&home).arg(server.url()).args(args).assert().success();
+        stdout_json(output.get_output())["data"]["served_by"].clone()
+    };
+
+    assert_eq!(served_by(&["get:/pets"]), "/pets");
+    assert_eq!(served_by(&["get:/pets", "--server", "0"]), "/v1/pets");
+    assert_eq!(
+        served_by(&["get:/pets", "--server-var", "version=v2"]),
+        "/v2/pets"
+    );
+    assert_eq!(served_by(&["get:/pets", "--server", "1"]), "/sandbox/pets");
+
+    let output = uxc(&home)
+        .args([&server.url(), "get:/pets", "--server-var", "version=v3"])
+        .assert()
+        .failure();
+    let error = &stdout_json(output.get_output())["error"];
+    assert_eq!(error["code"], "INVALID_ARGUMENT");
+    assert!(error["message"]
+        .as_str()
+        .unwrap()
+        .contains("expected one of: v1, v2"));
+}
+
+#[test]
+fn describe_lists_the_servers() {
+    let server = api();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([&server.url(), "describe", "get:/pets"])
+        .assert()
+        .success();
+    let servers = &stdout_json(output.get_output())["data"]["servers"];
+    assert_eq!(servers[0]["variables"]["version"]["default"], "v1");
+    assert_eq!(servers[1]["url"], "/sandbox");
+
+    let output = uxc(&home)
+        .args([&server.url(), "describe", "get:/pets", "--text"])
+        .assert()
+        .success();
+    let text = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(text.contains("- [1] /sandbox: Sandbox"), "{}", text);
+    assert!(text.contains("  version=v1 (v1, v2)"), "{}", text);
+}
+
+#[test]
+fn servers_from_the_schema_are_checked_against_the_allowlist() {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "servers": [{ "url": "http://evil.invalid:1/api" }],
+  "paths": { "/items": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#,
+        )
+        .create();
+    let home = TempDir::new().unwrap();
+    let allowed = server.host_with_port();
+
+    let output = uxc(&home)
+        .env_remove("UXC_ALLOWED_HOSTS")
+        .args([
+            "--allowed-hosts",
+            allowed.as_str(),
+            server.url().as_str(),
+            "get:/items",
+            "--server",
+            "0",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json = stdout_json(&output);
+    assert_eq!(json["error"]["code"], "HOST_NOT_ALLOWED");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("evil.invalid:1"));
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    let blocked: serde_json::Value = history
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|entry| entry["status"] == "BLOCKED")
+        .unwrap();
+    assert_eq!(blocked["operation"], "get:/items");
+}
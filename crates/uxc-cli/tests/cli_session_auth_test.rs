@@ -0,0 +1,211 @@
+//! Session (cookie jar) authentication integration tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path());
+    cmd
+}
+
+fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn session_profile_logs_in_once_and_reuses_cookies() {
+    let mut server = Server::new();
+    let login = server
+        .mock("POST", "/login")
+        .match_header("content-type", "application/x-www-form-urlencoded")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("user".into(), "alice".into()),
+            Matcher::UrlEncoded("password".into(), "s3cret".into()),
+        ]))
+        .with_status(302)
+        .with_header("location", "/")
+        .with_header("set-cookie", "sid=abc123; Path=/; HttpOnly")
+        .expect(1)
+        .create();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": { "/me": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#,
+        )
+        .create();
+    let me = server
+        .mock("GET", "/me")
+        .match_header("cookie", "sid=abc123")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user": "alice"}"#)
+        .expect(2)
+        .create();
+
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args([
+            "auth",
+            "set",
+            "intranet",
+            "-t",
+            "session",
+            "--api-key",
+            "s3cret",
+            "--username",
+            "alice",
+            "--username-field",
+            "user",
+            "--login-url",
+        ])
+        .arg(format!("{}/login", server.url()))
+        .assert()
+        .success();
+
+    for _ in 0..2 {
+        let output = uxc(&home)
+            .args(["--profile", "intranet", &server.url(), "get:/me"])
+            .assert()
+            .success();
+        assert_eq!(stdout_json(output.get_output())["data"]["user"], "alice");
+    }
+    login.assert();
+    me.assert();
+
+    let output = uxc(&home)
+        .args(["auth", "logout", "intranet"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output())["data"]["cleared"], true);
+}
+
+#[test]
+fn login_reports_missing_cookies_and_validates_profile() {
+    let mut server = Server::new();
+    let _login = server.mock("POST", "/login").with_status(200).create();
+
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["auth", "set", "plain", "--api-key", "token-123"])
+        .assert()
+        .success();
+    let output = uxc(&home)
+        .args(["auth", "login", "plain"])
+        .assert()
+        .failure();
+    assert_eq!(
+        stdout_json(output.get_output())["error"]["code"],
+        "INVALID_ARGUMENT"
+    );
+
+    uxc(&home)
+        .args(["auth", "set", "app", "-t", "session", "--api-key", "pw"])
+        .assert()
+        .failure();
+
+    uxc(&home)
+        .args([
+            "auth",
+            "set",
+            "app",
+            "-t",
+            "session",
+            "--api-key",
+            "pw",
+            "--username",
+            "bob",
+            "--login-url",
+        ])
+        .arg(format!("{}/login", server.url()))
+        .assert()
+        .success();
+    let output = uxc(&home).args(["auth", "login", "app"]).assert().failure();
+    assert!(stdout_json(output.get_output())["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("no session cookies"));
+}
+
+#[test]
+fn expired_sessions_are_renewed_once_on_401() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": { "/me": { "get": { "responses": { "200": { "description": "ok" } } } } }
+}"#,
+        )
+        .create();
+    let first_login = server
+        .mock("POST", "/login")
+        .with_status(200)
+        .with_header("set-cookie", "sid=old; Path=/")
+        .create();
+
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args([
+            "auth",
+            "set",
+            "intranet",
+            "-t",
+            "session",
+            "--api-key",
+            "s3cret",
+            "--username",
+            "alice",
+            "--login-url",
+        ])
+        .arg(format!("{}/login", server.url()))
+        .assert()
+        .success();
+    uxc(&home)
+        .args(["auth", "login", "intranet"])
+        .assert()
+        .success();
+    first_login.remove();
+
+    // The server has since forgotten sid=old
+    let login = server
+        .mock("POST", "/login")
+        .with_status(200)
+        .with_header("set-cookie", "sid=new; Path=/")
+        .expect(1)
+        .create();
+    let expired = server
+        .mock("GET", "/me")
+        .match_header("cookie", "sid=old")
+        .with_status(401)
+        .expect(1)
+        .create();
+    let me = server
+        .mock("GET", "/me")
+        .match_header("cookie", "sid=new")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"user": "alice"}"#)
+        .expect(1)
+        .create();
+
+    let output = uxc(&home)
+        .args(["--profile", "intranet", &server.url(), "get:/me"])
+        .assert()
+        .success();
+    assert_eq!(stdout_json(output.get_output())["data"]["user"], "alice");
+    login.assert();
+    expired.assert();
+    me.assert();
+}
@@ -0,0 +1,105 @@
+//! `--strict-args` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": {
+        "parameters": [
+          { "name": "status", "in": "query", "schema": { "type": "string" } }
+        ],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+        .mock("GET", "/pets")
+        .match_query(mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .create();
+    server
+}
+
+#[test]
+fn unknown_arguments_are_rejected_in_strict_mode() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+
+    // Forwarded by default
+    uxc(&home)
+        .args([&server.url(), "get:/pets", "statsu=available"])
+        .assert()
+        .success();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/pets",
+            "statsu=available",
+            "--strict-args",
+        ])
+        .assert()
+        .failure();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("'statsu' (did you mean 'status'?)"),
+        "{}",
+        message
+    );
+
+    uxc(&home)
+        .args([
+            &server.url(),
+            "get:/pets",
+            "status=available",
+            "--strict-args",
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_default_and_allow_extra() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".uxc")).unwrap();
+    std::fs::write(
+        home.path().join(".uxc/config.toml"),
+        "[call]\nstrict_args = true\n",
+    )
+    .unwrap();
+
+    uxc(&home)
+        .args([&server.url(), "get:/pets", "color=red"])
+        .assert()
+        .failure();
+    uxc(&home)
+        .args([&server.url(), "get:/pets", "color=red", "--allow-extra"])
+        .assert()
+        .success();
+}
@@ -0,0 +1,341 @@
+//! `uxc test` integration tests
+//!
+//! Runs declarative suites against a mock OpenAPI server and checks the report.
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use tempfile::TempDir;
+
+fn uxc() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("uxc"))
+}
+
+fn mock_petstore(server: &mut Server) -> Vec<mockito::Mock> {
+    let schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "get": {
+        "summary": "list pets",
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .expect_at_least(1)
+        .create();
+    let pets = server
+        .mock("GET", "/pets")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id": 1, "name": "cat"}]"#)
+        .create();
+    vec![schema, pets]
+}
+
+#[test]
+fn passing_suite_reports_counts_and_writes_junit() {
+    let mut server = Server::new();
+    let _mocks = mock_petstore(&mut server);
+    let dir = TempDir::new().unwrap();
+    let suite_path = dir.path().join("smoke.yaml");
+    let junit_path = dir.path().join("report.xml");
+    std::fs::write(
+        &suite_path,
+        format!(
+            r#"
+endpoint: {}
+cases:
+  - name: list pets
+    operation: get:/pets
+    expect:
+      assertions:
+        - path: "[0].name"
+          equals: cat
+        - path: "$"
+          length: 1
+"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .arg("test")
+        .arg(&suite_path)
+        .arg("--junit")
+        .arg(&junit_path)
+        .assert()
+        .success();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["kind"], "test_report");
+    assert_eq!(json["data"]["suite"], "smoke");
+    assert_eq!(json["data"]["passed"], 1);
+    assert_eq!(json["data"]["failed"], 0);
+
+    let junit = std::fs::read_to_string(&junit_path).unwrap();
+    assert!(junit.contains("<testsuite name=\"smoke\" tests=\"1\" failures=\"0\""));
+}
+
+#[test]
+fn failing_case_exits_non_zero() {
+    let mut server = Server::new();
+    let _mocks = mock_petstore(&mut server);
+    let dir = TempDir::new().unwrap();
+    let suite_path = dir.path().join("suite.json");
+    std::fs::write(
+        &suite_path,
+        serde_json::json!({
+            "name": "regressions",
+            "endpoint": server.url(),
+            "cases": [
+                {
+                    "name": "wrong name",
+                    "operation": "get:/pets",
+                    "expect": { "assertions": [{ "path": "[0].name", "equals": "dog" }] }
+                },
+                {
+                    "name": "missing route fails",
+                    "operation": "get:/missing",
+                    "expect": { "ok": false }
+                }
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .arg("test")
+        .arg(&suite_path)
+        .assert()
+        .failure();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "test_report");
+    assert_eq!(json["data"]["total"], 2);
+    assert_eq!(json["data"]["failed"], 1);
+    assert_eq!(json["data"]["cases"][0]["passed"], false);
+}
+
+#[test]
+fn slow_case_times_out_and_fail_fast_skips_the_rest() {
+    let mut server = Server::new();
+    let _mocks = mock_petstore(&mut server);
+    let _slow = server
+        .mock("GET", "/slow")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_chunked_body(|writer| {
+            std::thread::sleep(std::time::Duration::from_secs(3));
+            writer.write_all(b"[]")
+        })
+        .create();
+    let dir = TempDir::new().unwrap();
+    let suite_path = dir.path().join("suite.json");
+    std::fs::write(
+        &suite_path,
+        serde_json::json!({
+            "name": "slow",
+            "endpoint": server.url(),
+            "cases": [
+                { "name": "fast", "operation": "get:/pets" },
+                { "name": "slow", "operation": "get:/slow" },
+                { "name": "after", "operation": "get:/pets" }
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .arg("test")
+        .arg(&suite_path)
+        .args(["--timeout", "0.5", "--retries", "1", "--fail-fast"])
+        .assert()
+        .failure();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["passed"], 1);
+    assert_eq!(data["failed"], 1);
+    assert_eq!(data["timed_out"], 1);
+    assert_eq!(data["skipped"], 1);
+    assert_eq!(data["cases"][1]["error"]["code"], "TIMEOUT");
+    assert_eq!(data["cases"][1]["attempts"], 2);
+    assert_eq!(data["cases"][2]["skipped"], true);
+}
+
+#[test]
+fn failure_runs_compensations_in_reverse_unless_disabled() {
+    let mut server = Server::new();
+    let _schema = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": { "responses": { "200": { "description": "ok" } } },
+      "delete": { "responses": { "200": { "description": "deleted" } } }
+    },
+    "/broken": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _create = server
+        .mock("POST", "/pets")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 7}"#)
+        .create();
+    let _broken = server.mock("GET", "/broken").with_status(500).create();
+    let dir = TempDir::new().unwrap();
+    let suite_path = dir.path().join("provision.yaml");
+    std::fs::write(
+        &suite_path,
+        format!(
+            r#"
+endpoint: {}
+cases:
+  - name: create pet
+    operation: post:/pets
+    compensate:
+      operation: delete:/pets
+      args_from: {{ id: id }}
+  - name: broken step
+    operation: get:/broken
+  - name: never runs
+    operation: post:/pets
+"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let delete = server
+        .mock("DELETE", "/pets")
+        .match_body(Matcher::Json(serde_json::json!({"id": 7})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .expect(1)
+        .create();
+    let output = uxc()
+        .env("HOME", dir.path())
+        .arg("test")
+        .arg(&suite_path)
+        .assert()
+        .failure();
+    delete.assert();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["skipped"], 1);
+    assert_eq!(data["compensations"][0]["case"], "create pet");
+    assert_eq!(data["compensations"][0]["operation"], "delete:/pets");
+    assert_eq!(data["compensations"][0]["ok"], true);
+
+    delete.remove();
+    let delete = server.mock("DELETE", "/pets").expect(0).create();
+    let output = uxc()
+        .env("HOME", dir.path())
+        .arg("test")
+        .arg(&suite_path)
+        .arg("--no-compensate")
+        .assert()
+        .failure();
+    delete.assert();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["skipped"], 0);
+    assert!(json["data"].get("compensations").is_none());
+}
+
+#[test]
+fn request_files_run_as_contract_tests() {
+    let mut server = Server::new();
+    let _mocks = mock_petstore(&mut server);
+    server.mock("GET", "/gone").with_status(404).create();
+    let dir = TempDir::new().unwrap();
+    let file_path = dir.path().join("contract.yaml");
+    let junit_path = dir.path().join("report.xml");
+    std::fs::write(
+        &file_path,
+        format!(
+            r#"
+endpoint: {}
+requests:
+  - name: list
+    operation: get:/pets
+    expect:
+      status: 200
+      max_duration_ms: 10000
+      assertions:
+        - path: "[0].name"
+          matches: "^c[a-z]+$"
+      schema:
+        type: array
+        items:
+          type: object
+          required: [id, name]
+          properties:
+            id: {{ type: integer }}
+  - name: gone
+    operation: get:/gone
+    expect:
+      status: 404
+  - name: typed
+    operation: get:/pets
+    expect:
+      schema:
+        type: array
+        items:
+          properties:
+            id: {{ type: string }}
+"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let output = uxc()
+        .env("HOME", dir.path())
+        .arg("test")
+        .arg(&file_path)
+        .arg("--junit")
+        .arg(&junit_path)
+        .assert()
+        .code(1);
+
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "test_report");
+    let data = &json["data"];
+    assert_eq!(data["suite"], "contract");
+    assert_eq!(
+        (data["passed"].as_u64(), data["failed"].as_u64()),
+        (Some(2), Some(1))
+    );
+    assert_eq!(data["cases"][1]["passed"], true);
+    assert_eq!(
+        data["cases"][2]["failures"][0],
+        "schema: $[0].id: expected type string but got number"
+    );
+
+    let junit = std::fs::read_to_string(&junit_path).unwrap();
+    assert!(junit.contains("<testsuite name=\"contract\" tests=\"3\" failures=\"1\""));
+}
@@ -0,0 +1,109 @@
+//! Typed `k:=json` and nested `a.b=v` argument tests
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/pets": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object",
+          "properties": {
+            "name": { "type": "string" },
+            "age": { "type": "integer" },
+            "vaccinated": { "type": "boolean" },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "owner": {
+              "type": "object",
+              "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" }
+              }
+            }
+          }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn calls_send_typed_nested_and_coerced_values() {
+    let mut server = petstore();
+    let pets = server
+        .mock("POST", "/pets")
+        .match_body(Matcher::Json(serde_json::json!({
+            "name": "Rex",
+            "age": 3,
+            "vaccinated": true,
+            "tags": ["good", "dog"],
+            "owner": { "name": "Ann", "age": 30 }
+        })))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id":1}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    uxc(&home)
+        .args([
+            &server.url(),
+            "post:/pets",
+            "name=Rex",
+            "age=3",
+            "vaccinated:=true",
+            r#"tags:=["good","dog"]"#,
+            "owner.name=Ann",
+            "owner.age=30",
+        ])
+        .assert()
+        .success();
+    pets.assert();
+}
+
+#[test]
+fn malformed_and_mistyped_arguments_are_rejected() {
+    let mut server = petstore();
+    let pets = server.mock("POST", "/pets").expect(0).create();
+    let home = TempDir::new().unwrap();
+
+    for (arg, expected) in [
+        ("age:=three", "value is not JSON"),
+        ("vaccinated:=1", "'vaccinated' must be a boolean"),
+        ("owner.age=old", "'owner.age' must be an integer"),
+    ] {
+        let output = uxc(&home)
+            .args([&server.url(), "post:/pets", arg])
+            .assert()
+            .failure();
+        let envelope: serde_json::Value =
+            serde_json::from_slice(&output.get_output().stdout).unwrap();
+        assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT");
+        let message = envelope["error"]["message"].as_str().unwrap();
+        assert!(message.contains(expected), "{}: {}", arg, message);
+    }
+    pets.assert();
+}
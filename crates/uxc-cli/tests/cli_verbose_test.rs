@@ -0,0 +1,78 @@
+//! `--verbose` request/response tracing integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+#[test]
+fn verbose_traces_requests_and_responses_with_secrets_redacted() {
+    let mut server = Server::new();
+    let _spec = server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/items": { "post": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    let _items = server
+        .mock("POST", "/items")
+        .match_header("authorization", "Bearer trace-secret-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("x-request-id", "req-42")
+        .with_body(r#"{"created": true}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+    uxc(&home)
+        .args(["auth", "set", "ci", "--api-key", "trace-secret-token"])
+        .assert()
+        .success();
+
+    // A host name, so the trace includes a DNS lookup
+    let endpoint = server.url().replace("127.0.0.1", "localhost");
+    let output = uxc(&home)
+        .args([
+            "-v",
+            "--no-cache",
+            "--profile",
+            "ci",
+            endpoint.as_str(),
+            "post:/items",
+            "--json",
+            r#"{"name": "widget"}"#,
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["data"]["created"], true);
+
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains(&format!("> POST {}/items", endpoint)));
+    assert!(stderr.contains("> authorization: ***"));
+    assert!(stderr.contains(r#"{"name":"widget"}"#));
+    assert!(stderr.contains("< HTTP/1.1 200 OK"));
+    assert!(stderr.contains("< x-request-id: req-42"));
+    assert!(stderr.contains("* timing: dns "));
+    assert!(stderr.contains("first byte"));
+    assert!(!stderr.contains("trace-secret-token"));
+
+    // Nothing is traced without the flag
+    let output = uxc(&home)
+        .args(["--profile", "ci", endpoint.as_str(), "post:/items"])
+        .assert()
+        .success();
+    assert!(!String::from_utf8_lossy(&output.get_output().stderr).contains("> POST"));
+}
@@ -0,0 +1,171 @@
+//! `--watch`, `--changes-only` and `--watch-count` integration tests
+
+use assert_cmd::Command;
+use mockito::{Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn jobs() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/job": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+fn envelopes(stdout: &[u8]) -> Vec<serde_json::Value> {
+    serde_json::Deserializer::from_slice(stdout)
+        .into_iter()
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn watch_prints_every_result_until_the_count() {
+    let mut server = jobs();
+    let job = server
+        .mock("GET", "/job")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"status":"running"}"#)
+        .expect(3)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/job",
+            "--watch",
+            "10ms",
+            "--watch-count",
+            "3",
+        ])
+        .assert()
+        .success();
+    let envelopes = envelopes(&output.get_output().stdout);
+    assert_eq!(envelopes.len(), 3);
+    assert!(envelopes
+        .iter()
+        .all(|envelope| envelope["data"]["status"] == "running"));
+    job.assert();
+}
+
+#[test]
+fn changes_only_prints_changed_results_with_their_differences() {
+    let mut server = jobs();
+    let running = server
+        .mock("GET", "/job")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"status":"running","progress":40}"#)
+        .expect(2)
+        .create();
+    let done = server
+        .mock("GET", "/job")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"status":"done","progress":100}"#)
+        .expect(2)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/job",
+            "--watch",
+            "10ms",
+            "--watch-count",
+            "4",
+            "--changes-only",
+            "--query",
+            "status",
+        ])
+        .assert()
+        .success();
+    let envelopes = envelopes(&output.get_output().stdout);
+    assert_eq!(envelopes.len(), 2);
+    assert_eq!(envelopes[0]["data"], "running");
+    assert!(envelopes[0]["meta"].get("changes").is_none());
+    assert_eq!(envelopes[1]["data"], "done");
+    assert_eq!(
+        envelopes[1]["meta"]["changes"],
+        serde_json::json!([{ "path": "", "before": "running", "after": "done" }])
+    );
+    running.assert();
+    done.assert();
+}
+
+#[test]
+fn changes_are_listed_ahead_of_text_results() {
+    let mut server = jobs();
+    server
+        .mock("GET", "/job")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"status":"running"}"#)
+        .expect(1)
+        .create();
+    server
+        .mock("GET", "/job")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"status":"done"}"#)
+        .create();
+    let home = TempDir::new().unwrap();
+
+    let output = uxc(&home)
+        .args([
+            &server.url(),
+            "get:/job",
+            "--text",
+            "--watch",
+            "10ms",
+            "--watch-count",
+            "2",
+            "--changes-only",
+        ])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert_eq!(stdout.matches("Changes:").count(), 1, "{}", stdout);
+    assert!(
+        stdout.contains(r#"~ /status: "running" -> "done""#),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn watch_only_applies_to_calls() {
+    let server = jobs();
+    let home = TempDir::new().unwrap();
+
+    for args in [
+        vec![server.url(), "list".to_string()],
+        vec!["history".to_string(), "list".to_string()],
+    ] {
+        let output = uxc(&home)
+            .args(&args)
+            .args(["--watch", "1s"])
+            .assert()
+            .failure();
+        let envelope: serde_json::Value =
+            serde_json::from_slice(&output.get_output().stdout).unwrap();
+        assert_eq!(envelope["error"]["code"], "INVALID_ARGUMENT", "{:?}", args);
+    }
+}
@@ -0,0 +1,101 @@
+//! `uxc <url> which` integration tests
+
+use assert_cmd::Command;
+use mockito::Server;
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path()).current_dir(home.path());
+    cmd
+}
+
+fn mock_schema(server: &mut Server) -> mockito::Mock {
+    server
+        .mock("GET", "/v2/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/users": {
+      "post": { "summary": "Create user", "responses": { "200": { "description": "ok" } } }
+    },
+    "/users/{id}": {
+      "get": {
+        "summary": "Get user",
+        "parameters": [
+          { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+        ],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#,
+        )
+        .create()
+}
+
+#[test]
+fn which_finds_operation_from_method_and_path() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let endpoint = format!("{}/v2", server.url());
+
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args([
+            &endpoint,
+            "which",
+            "--method",
+            "POST",
+            "--path",
+            "/v2/users",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["kind"], "operation_match");
+    assert_eq!(json["operation"], "post:/users");
+    assert_eq!(json["data"]["detail"]["operation_id"], "post:/users");
+}
+
+#[test]
+fn which_accepts_har_request_sample() {
+    let mut server = Server::new();
+    let _schema = mock_schema(&mut server);
+    let endpoint = format!("{}/v2", server.url());
+    let sample = format!(
+        r#"{{"request": {{"method": "GET", "url": "{}/v2/users/42?expand=1"}}}}"#,
+        server.url()
+    );
+
+    let home = TempDir::new().unwrap();
+    let output = uxc(&home)
+        .args([&endpoint, "which", "--json", &sample])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["operation"], "get:/users/{id}");
+    assert_eq!(json["data"]["path_args"]["id"], "42");
+    assert!(json["next"][0]["command"]
+        .as_str()
+        .unwrap()
+        .contains(r#"--json '{"id":"42"}'"#));
+
+    let output = uxc(&home)
+        .args([
+            &endpoint,
+            "which",
+            "--method",
+            "DELETE",
+            "--path",
+            "/users/42",
+        ])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["error"]["code"], "OPERATION_NOT_FOUND");
+}
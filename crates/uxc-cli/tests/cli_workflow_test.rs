@@ -0,0 +1,183 @@
+//! Request file workflows: step references, conditions, loops and retries
+
+use assert_cmd::Command;
+use mockito::{Matcher, Server, ServerGuard};
+use tempfile::TempDir;
+
+fn uxc(home: &TempDir) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("uxc"));
+    cmd.env("HOME", home.path())
+        .env_remove("UXC_PROFILE")
+        .current_dir(home.path());
+    cmd
+}
+
+fn petstore() -> ServerGuard {
+    let mut server = Server::new();
+    server
+        .mock("GET", "/openapi.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "test", "version": "1.0.0" },
+  "paths": {
+    "/login": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object", "properties": { "user": { "type": "string" } }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/pets/search": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object", "properties": { "token": { "type": "string" } }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/feed": {
+      "post": {
+        "requestBody": { "content": { "application/json": { "schema": {
+          "type": "object",
+          "properties": { "pet": { "type": "integer" }, "token": { "type": "string" } }
+        } } } },
+        "responses": { "200": { "description": "ok" } }
+      }
+    },
+    "/admin": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#,
+        )
+        .create();
+    server
+}
+
+#[test]
+fn steps_pass_outputs_to_later_steps() {
+    let mut server = petstore();
+    server
+        .mock("POST", "/login")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"token":"t0k","role":"user"}"#)
+        .create();
+    let pets = server
+        .mock("POST", "/pets/search")
+        .match_body(Matcher::Json(serde_json::json!({ "token": "t0k" })))
+        .with_status(503)
+        .expect(1)
+        .create();
+    let pets_again = server
+        .mock("POST", "/pets/search")
+        .match_body(Matcher::Json(serde_json::json!({ "token": "t0k" })))
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"id":1},{"id":2}]"#)
+        .expect(1)
+        .create();
+    let feed: Vec<_> = [1, 2]
+        .into_iter()
+        .map(|pet| {
+            server
+                .mock("POST", "/feed")
+                .match_body(Matcher::Json(
+                    serde_json::json!({ "pet": pet, "token": "t0k" }),
+                ))
+                .with_header("content-type", "application/json")
+                .with_body(r#"{"fed":true}"#)
+                .create()
+        })
+        .collect();
+    let admin = server.mock("GET", "/admin").expect(0).create();
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("feed.yaml"),
+        format!(
+            r#"
+endpoint: {}
+concurrency: 4
+steps:
+  - name: login
+    operation: post:/login
+    args: {{ user: ann }}
+  - name: pets
+    operation: post:/pets/search
+    args: {{ token: "${{steps.login.data.token}}" }}
+    retries: 1
+  - name: feed
+    operation: post:/feed
+    for_each: "${{steps.pets.data}}"
+    args:
+      pet: "${{loop.item.id}}"
+      token: "${{steps.login.data.token}}"
+  - name: admin
+    operation: get:/admin
+    if: "${{steps.login.data.role}} == admin"
+"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let output = uxc(&home)
+        .args(["run", "feed.yaml", "--retry-backoff", "10ms"])
+        .assert()
+        .success();
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let data = &envelope["data"];
+    let names: Vec<&str> = data["requests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|request| request["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["login", "pets", "feed[0]", "feed[1]", "admin"]);
+    assert_eq!(data["requests"][1]["attempts"], 2);
+    assert_eq!(data["requests"][4]["skipped"], true);
+    assert_eq!(
+        (data["succeeded"].as_u64(), data["skipped"].as_u64()),
+        (Some(4), Some(1))
+    );
+    pets.assert();
+    pets_again.assert();
+    feed.iter().for_each(|mock| mock.assert());
+    admin.assert();
+}
+
+#[test]
+fn broken_references_fail_their_step() {
+    let server = petstore();
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("broken.yaml"),
+        format!(
+            r#"
+endpoint: {}
+steps:
+  - operation: post:/pets/search
+    args: {{ token: "${{steps.login.data.token}}" }}
+  - operation: get:/admin
+    for_each: "${{steps.missing.data}}"
+"#,
+            server.url()
+        ),
+    )
+    .unwrap();
+
+    let output = uxc(&home).args(["run", "broken.yaml"]).assert().code(1);
+    let envelope: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    for request in envelope["data"]["requests"].as_array().unwrap() {
+        let error = &request["envelope"]["error"];
+        assert_eq!(error["code"], "INVALID_ARGUMENT");
+        assert!(
+            error["message"]
+                .as_str()
+                .unwrap()
+                .contains("refers to nothing"),
+            "{}",
+            error
+        );
+    }
+}
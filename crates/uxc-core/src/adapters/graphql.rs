@@ -5,23 +5,47 @@
 //! - Query and mutation execution
 //! - Variable binding and serialization
 //! - Comprehensive error handling
+//! - User-provided documents ([`document`])
+//! - Field selection of schema-built operations ([`selection`])
+//! - Schemas given as SDL when introspection is disabled (`--schema-url`)
+//! - Persisted queries, automatic or registered ([`persisted`])
 
 use super::{
-    Adapter, ExecutionMetadata, ExecutionResult, Operation, OperationDetail, Parameter,
-    ProtocolType,
+    probe, Adapter, Capabilities, CapabilitySet, ExecutionMetadata, ExecutionResult, Operation,
+    OperationDetail, Parameter, ProtocolType, RequestPlan,
 };
-use crate::auth::Profile;
-use anyhow::{anyhow, bail, Result};
+use crate::auth::{AuthType, Profile};
+use crate::error::{ErrorDetail, UxcError};
+use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
 use serde_json::{Map, Value};
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tracing::{debug, info};
 
+pub mod document;
+pub mod persisted;
+mod sdl;
+pub mod selection;
+
+pub use persisted::PersistedQueries;
+pub use selection::Selection;
+
 pub struct GraphQLAdapter {
     client: reqwest::Client,
     cache: Option<Arc<dyn crate::cache::Cache>>,
     auth_profile: Option<Profile>,
+    /// Paths probed when the URL itself does not answer introspection
+    endpoint_paths: Vec<String>,
+    /// Endpoint found under one of `endpoint_paths`, per URL
+    discovered_endpoints: Arc<RwLock<HashMap<String, String>>>,
+    /// Fields selected from results of schema-built operations
+    selection: Selection,
+    /// SDL or introspection result used instead of introspecting the
+    /// endpoint: a URL or a local file
+    schema_url: Option<String>,
+    /// How calls refer to their document; `None` sends it in full
+    persisted_queries: Option<PersistedQueries>,
 }
 
 impl GraphQLAdapter {
@@ -29,9 +53,14 @@ impl GraphQLAdapter {
 
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: crate::http::client(),
             cache: None,
             auth_profile: None,
+            endpoint_paths: Vec::new(),
+            discovered_endpoints: Arc::new(RwLock::new(HashMap::new())),
+            selection: Selection::default(),
+            schema_url: None,
+            persisted_queries: None,
         }
     }
 
@@ -45,14 +74,189 @@ impl GraphQLAdapter {
         self
     }
 
-    /// Execute a GraphQL query/mutation with optional variables
-    async fn execute_graphql(
-        &self,
-        url: &str,
-        query: &str,
-        variables: Option<Value>,
-        operation_name: Option<&str>,
-    ) -> Result<Value> {
+    /// Also probe `paths` of endpoints that do not answer introspection
+    /// themselves
+    pub fn with_endpoint_paths(mut self, paths: Vec<String>) -> Self {
+        self.endpoint_paths = paths;
+        self
+    }
+
+    /// Select `selection` from the results of schema-built operations
+    /// instead of their scalar fields
+    pub fn with_selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Read the schema from `schema_url` (SDL or an introspection result, at
+    /// a URL or in a file) instead of introspecting the endpoint
+    pub fn with_schema_url(mut self, schema_url: Option<String>) -> Self {
+        self.schema_url = schema_url;
+        self
+    }
+
+    /// Refer to documents by their persisted query ID; `None` sends them in
+    /// full
+    pub fn with_persisted_queries(mut self, persisted_queries: Option<PersistedQueries>) -> Self {
+        self.persisted_queries = persisted_queries;
+        self
+    }
+
+    /// Send the requests for `url` to `endpoint`, found by an earlier
+    /// detection
+    pub fn with_discovered_endpoint(self, url: &str, endpoint: String) -> Self {
+        self.discovered_endpoints
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(Self::endpoint_key(url), endpoint);
+        self
+    }
+
+    /// Endpoint found under a probed path for `url`, if it was not `url`
+    pub fn discovered_endpoint(&self, url: &str) -> Option<String> {
+        self.discovered_endpoints
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&Self::endpoint_key(url))
+            .cloned()
+    }
+
+    fn endpoint_key(url: &str) -> String {
+        url.trim_end_matches('/').to_string()
+    }
+
+    /// Where requests for `url` go
+    fn endpoint(&self, url: &str) -> String {
+        self.discovered_endpoint(url)
+            .unwrap_or_else(|| url.to_string())
+    }
+
+    /// Whether `url` answers a GraphQL introspection query
+    async fn answers_introspection(&self, url: &str) -> Result<bool> {
+        // Try GraphQL introspection with timeout
+        let query = r#"
+            {
+                __schema {
+                    queryType {
+                        name
+                    }
+                }
+            }
+        "#;
+
+        let mut req = self
+            .client
+            .post(url)
+            .timeout(probe::timeout(std::time::Duration::from_secs(2)))
+            .header("Content-Type", "application/json");
+
+        // Apply authentication if profile is set
+        if let Some(profile) = &self.auth_profile {
+            req = profile.apply_to_request(req)?;
+        }
+
+        let resp = match crate::http::send(req.json(&serde_json::json!({ "query": query }))).await {
+            Ok(r) => r,
+            Err(e) => {
+                probe::evidence(format!("POST {} introspection: {:#}", url, e));
+                return Ok(false);
+            }
+        };
+
+        if !resp.status().is_success() {
+            probe::evidence(format!(
+                "POST {} introspection: HTTP {}",
+                url,
+                resp.status()
+            ));
+            return Ok(false);
+        }
+
+        // Check if response is valid GraphQL with __schema
+        if let Ok(body) = resp.json::<Value>().await {
+            // A valid GraphQL introspection response should have data.__schema
+            // or errors (which still indicates GraphQL)
+            if body.get("data").is_some() || body.get("__schema").is_some() {
+                probe::evidence(format!("POST {} introspection: answered", url));
+                return Ok(true);
+            }
+            if let Some(errors) = body.get("errors") {
+                let message = errors
+                    .pointer("/0/message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("no message");
+                probe::evidence(format!(
+                    "POST {} introspection: GraphQL errors ({})",
+                    url, message
+                ));
+                return Ok(true);
+            }
+        }
+
+        probe::evidence(format!("POST {} introspection: not a GraphQL answer", url));
+        Ok(false)
+    }
+
+    /// Cache key of the schema of `url`
+    fn schema_key(&self, url: &str) -> String {
+        match &self.schema_url {
+            Some(schema_url) => format!("{}#schema={}", url, schema_url),
+            None => url.to_string(),
+        }
+    }
+
+    /// The schema at `schema_url`, as an introspection result
+    async fn load_schema_url(&self, schema_url: &str) -> Result<Value> {
+        let text = if schema_url.starts_with("http://") || schema_url.starts_with("https://") {
+            let resp = crate::http::send(self.client.get(schema_url)).await?;
+            if !resp.status().is_success() {
+                bail!("HTTP {}", resp.status());
+            }
+            resp.text().await?
+        } else {
+            let path = schema_url.strip_prefix("file://").unwrap_or(schema_url);
+            std::fs::read_to_string(path)?
+        };
+        sdl::schema(&text)
+    }
+
+    /// The introspection result of the endpoint of `url`
+    async fn introspect(&self, url: &str) -> Result<Value> {
+        let introspection_query = Self::get_introspection_query();
+
+        let mut req = self
+            .client
+            .post(self.endpoint(url))
+            .header("Content-Type", "application/json");
+
+        // Apply authentication if profile is set
+        if let Some(profile) = &self.auth_profile {
+            req = profile.apply_to_request(req)?;
+        }
+
+        let resp =
+            crate::http::send(req.json(&serde_json::json!({ "query": introspection_query })))
+                .await?;
+
+        if !resp.status().is_success() {
+            bail!("Failed to fetch GraphQL schema: HTTP {}", resp.status());
+        }
+
+        let body: Value = resp.json().await?;
+
+        // Check for GraphQL errors in introspection
+        if let Some(errors) = body.get("errors") {
+            bail!(
+                "GraphQL introspection failed (pass the SDL with --schema-url if it is disabled): {}",
+                serde_json::to_string_pretty(errors)?
+            );
+        }
+
+        Ok(body)
+    }
+
+    /// Request body of a GraphQL query/mutation with optional variables
+    fn payload(query: &str, variables: Option<Value>, operation_name: Option<&str>) -> Value {
         let mut payload = serde_json::json!({
             "query": query
         });
@@ -65,29 +269,72 @@ impl GraphQLAdapter {
             payload["operationName"] = serde_json::json!(op_name);
         }
 
+        payload
+    }
+
+    /// The POST request carrying `payload`
+    fn graphql_request(&self, url: &str, payload: &Value) -> Result<reqwest::RequestBuilder> {
         let mut req = self
             .client
-            .post(url)
+            .post(self.endpoint(url))
             .header("Content-Type", "application/json");
 
         // Apply authentication if profile is set
         if let Some(profile) = &self.auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+            req = profile.apply_to_request(req)?;
         }
 
-        let resp = req.json(&payload).send().await?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let error_text = resp.text().await.unwrap_or_default();
-            bail!(
-                "GraphQL request failed with status {}: {}",
-                status,
-                error_text
-            );
-        }
+        Ok(req.json(payload))
+    }
 
-        let body: Value = resp.json().await?;
+    /// Execute a GraphQL query/mutation with optional variables
+    async fn execute_graphql(
+        &self,
+        url: &str,
+        query: &str,
+        variables: Option<Value>,
+        operation_name: Option<&str>,
+    ) -> Result<Value> {
+        let payload = Self::payload(query, variables, operation_name);
+        let body = match &self.persisted_queries {
+            None => self.post(url, &payload).await?,
+            Some(mode) => {
+                let id = mode.id(query);
+                let body = self
+                    .post(url, &persisted::payload(&payload, &id, false))
+                    .await?;
+                match mode {
+                    PersistedQueries::Automatic if persisted::not_found(&body) => {
+                        crate::explain::record(
+                            "persisted_query",
+                            format!("persisted query {} not known yet; sent the document", id),
+                        );
+                        self.post(url, &persisted::payload(&payload, &id, true))
+                            .await?
+                    }
+                    PersistedQueries::Automatic if persisted::not_supported(&body) => {
+                        crate::explain::record(
+                            "persisted_query",
+                            "persisted queries not supported; sent the document alone",
+                        );
+                        self.post(url, &payload).await?
+                    }
+                    PersistedQueries::Registered { .. } if persisted::not_found(&body) => {
+                        return Err(UxcError::call_failed(
+                            format!(
+                                "The server has no persisted query '{}'; register the document or use --persisted-queries apq",
+                                id
+                            ),
+                            ErrorDetail::Graphql {
+                                errors: body["errors"].as_array().cloned().unwrap_or_default(),
+                            },
+                        )
+                        .into());
+                    }
+                    _ => body,
+                }
+            }
+        };
 
         // Check for GraphQL errors
         if let Some(errors) = body.get("errors") {
@@ -123,13 +370,46 @@ impl GraphQLAdapter {
                     })
                     .collect();
 
-                bail!("GraphQL errors:\n{}", error_messages.join("\n"));
+                return Err(UxcError::call_failed(
+                    format!("GraphQL errors:\n{}", error_messages.join("\n")),
+                    ErrorDetail::Graphql {
+                        errors: error_array.clone(),
+                    },
+                )
+                .into());
             }
         }
 
         Ok(body)
     }
 
+    /// POST `payload` and read the response; persisted query errors answered
+    /// with an error status are returned for the caller to act on
+    async fn post(&self, url: &str, payload: &Value) -> Result<Value> {
+        let resp = crate::http::send(self.graphql_request(url, payload)?).await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let retry_after = crate::http::retry_after(resp.headers());
+            let error_text = resp.text().await.unwrap_or_default();
+            if let Ok(body) = serde_json::from_str::<Value>(&error_text) {
+                if persisted::not_found(&body) || persisted::not_supported(&body) {
+                    return Ok(body);
+                }
+            }
+            return Err(UxcError::call_failed(
+                format!(
+                    "GraphQL request failed with status {}: {}",
+                    status, error_text
+                ),
+                ErrorDetail::http(status.as_u16(), &error_text).with_retry_after(retry_after),
+            )
+            .into());
+        }
+
+        Ok(resp.json().await?)
+    }
+
     /// Get the full introspection query
     fn get_introspection_query() -> &'static str {
         r#"
@@ -171,6 +451,9 @@ impl GraphQLAdapter {
                                 ...TypeRef
                             }
                         }
+                        fields {
+                            ...FieldInfo
+                        }
                     }
                 }
             }
@@ -300,6 +583,7 @@ impl GraphQLAdapter {
                         description,
                         parameters,
                         return_type,
+                        tags: Vec::new(),
                     });
                 }
             }
@@ -331,6 +615,7 @@ impl GraphQLAdapter {
                         description,
                         parameters,
                         return_type,
+                        tags: Vec::new(),
                     });
                 }
             }
@@ -362,6 +647,7 @@ impl GraphQLAdapter {
                         description,
                         parameters,
                         return_type,
+                        tags: Vec::new(),
                     });
                 }
             }
@@ -390,6 +676,7 @@ impl GraphQLAdapter {
                                 .get("description")
                                 .and_then(|d| d.as_str())
                                 .map(|s| s.to_string()),
+                            location: None,
                         })
                     })
                     .collect()
@@ -660,6 +947,130 @@ impl GraphQLAdapter {
         }
     }
 
+    /// Document, variables and operation name sent for `operation` with
+    /// `args`; `schema`, when known, shapes and checks the selection
+    fn request(
+        &self,
+        operation: &str,
+        args: HashMap<String, Value>,
+        schema: Option<&Value>,
+    ) -> Result<(String, Option<Value>, Option<String>)> {
+        if operation == document::OPERATION {
+            return document::request(args);
+        }
+        let return_type = schema
+            .and_then(|schema| Self::find_operation_field(schema, operation))
+            .and_then(|field| field.get("type"));
+        let types = schema.map(Self::build_type_index).unwrap_or_default();
+        let selection_set = selection::selection_set(return_type, &types, &self.selection)?;
+        let (query, variables) = Self::document(operation, args, selection_set.as_deref())?;
+        Ok((query, variables, None))
+    }
+
+    /// Schema shaping the selection of `operation`, when it can be fetched
+    async fn selection_schema(&self, url: &str, operation: &str) -> Option<Value> {
+        if operation == document::OPERATION {
+            return None;
+        }
+        match self.fetch_schema(url).await {
+            Ok(schema) => Some(schema),
+            Err(e) => {
+                debug!("Selecting without the GraphQL schema of {}: {}", url, e);
+                None
+            }
+        }
+    }
+
+    /// The document and variables sent for `operation` with `args`,
+    /// selecting `selection_set` from its result (nothing for leaf results)
+    fn document(
+        operation: &str,
+        args: HashMap<String, Value>,
+        selection_set: Option<&str>,
+    ) -> Result<(String, Option<Value>)> {
+        // Parse operation name to determine type
+        let (op_type, field_name) = Self::parse_operation_name(operation)?;
+
+        // Build query arguments string
+        let args_str = if !args.is_empty() {
+            let args_parts: Vec<String> = args
+                .iter()
+                .map(|(k, v)| {
+                    let value_str = match v {
+                        Value::String(s) => format!("\"{}\"", s),
+                        Value::Bool(b) => b.to_string(),
+                        Value::Number(n) => n.to_string(),
+                        Value::Null => "null".to_string(),
+                        Value::Array(arr) => {
+                            let items: Vec<String> = arr
+                                .iter()
+                                .map(|item| match item {
+                                    Value::String(s) => format!("\"{}\"", s),
+                                    _ => item.to_string(),
+                                })
+                                .collect();
+                            format!("[{}]", items.join(", "))
+                        }
+                        Value::Object(_obj) => {
+                            // For nested objects, use variable syntax
+                            format!("${}", k)
+                        }
+                    };
+                    format!("{}: {}", k, value_str)
+                })
+                .collect();
+            format!("({})", args_parts.join(", "))
+        } else {
+            String::new()
+        };
+
+        let selection_set = selection_set
+            .map(|selection| format!(" {{ {} }}", selection))
+            .unwrap_or_default();
+
+        // Check if we have complex nested objects that need variables
+        let has_complex_objects = args.values().any(|v| matches!(v, Value::Object(_)));
+
+        let (query_string, variables) = if has_complex_objects {
+            // Use variables for complex types
+            let var_names: Vec<String> = args
+                .keys()
+                .map(|k| format!("${}: String", k)) // Simplified type
+                .collect();
+
+            let query = format!(
+                "{} {}{} {{ {}{} }}",
+                match op_type {
+                    OperationType::Query => "query",
+                    OperationType::Mutation => "mutation",
+                    OperationType::Subscription => "subscription",
+                },
+                field_name,
+                var_names.join(", "),
+                field_name,
+                selection_set
+            );
+
+            (query, Some(Value::Object(args.into_iter().collect())))
+        } else {
+            let query = format!(
+                "{} {{ {}{}{} }}",
+                match op_type {
+                    OperationType::Query => "query",
+                    OperationType::Mutation => "mutation",
+                    OperationType::Subscription => "subscription",
+                },
+                field_name,
+                args_str,
+                selection_set
+            );
+
+            (query, None)
+        };
+
+        Ok((query_string, variables))
+    }
+
     /// Build a GraphQL query string from operation name and selection set
     #[allow(dead_code)]
     fn build_query(
@@ -694,6 +1105,31 @@ enum OperationType {
     Subscription,
 }
 
+impl Capabilities for GraphQLAdapter {
+    const PROTOCOL: ProtocolType = ProtocolType::GraphQL;
+
+    fn capabilities() -> CapabilitySet {
+        CapabilitySet {
+            streaming: false,
+            batch: false,
+            validation: false,
+            auth_types: vec![
+                AuthType::Bearer,
+                AuthType::ApiKey,
+                AuthType::Basic,
+                AuthType::Session,
+                AuthType::OAuth,
+            ],
+            schema_export: true,
+            schema_cache: true,
+            raw_body: false,
+            notes: vec![
+                "Subscriptions are listed but executed as a single HTTP request".to_string(),
+            ],
+        }
+    }
+}
+
 #[async_trait]
 impl Adapter for GraphQLAdapter {
     fn protocol_type(&self) -> ProtocolType {
@@ -701,60 +1137,43 @@ impl Adapter for GraphQLAdapter {
     }
 
     async fn can_handle(&self, url: &str) -> Result<bool> {
-        // Try GraphQL introspection with timeout
-        let query = r#"
-            {
-                __schema {
-                    queryType {
-                        name
-                    }
+        if let Some(schema_url) = &self.schema_url {
+            match self.fetch_schema(url).await {
+                Ok(_) => {
+                    probe::evidence(format!("{}: GraphQL schema", schema_url));
+                    return Ok(true);
                 }
+                Err(e) => probe::evidence(format!("{}: {:#}", schema_url, e)),
             }
-        "#;
-
-        let mut req = self
-            .client
-            .post(url)
-            .timeout(std::time::Duration::from_secs(2))
-            .header("Content-Type", "application/json");
-
-        // Apply authentication if profile is set
-        if let Some(profile) = &self.auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
         }
-
-        let resp = match req
-            .json(&serde_json::json!({ "query": query }))
-            .send()
-            .await
-        {
-            Ok(r) => r,
-            Err(_) => return Ok(false),
-        };
-
-        if !resp.status().is_success() {
-            return Ok(false);
+        if self.answers_introspection(url).await? {
+            return Ok(true);
         }
-
-        // Check if response is valid GraphQL with __schema
-        if let Ok(body) = resp.json::<Value>().await {
-            // A valid GraphQL introspection response should have data.__schema
-            // or errors (which still indicates GraphQL)
-            if body.get("data").is_some()
-                || body.get("errors").is_some()
-                || body.get("__schema").is_some()
-            {
+        for path in &self.endpoint_paths {
+            let endpoint = format!("{}{}", url.trim_end_matches('/'), path);
+            if self.answers_introspection(&endpoint).await? {
+                self.discovered_endpoints
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(Self::endpoint_key(url), endpoint);
                 return Ok(true);
             }
         }
-
         Ok(false)
     }
-
     async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        let key = self.schema_key(url);
+        if crate::cache::schemas_from_store(self.cache.as_ref()) {
+            return crate::cache::cached_schema(self.cache.as_ref(), &key, |schema| {
+                schema.pointer("/data/__schema").is_some()
+            });
+        }
+
         // Try cache first if available
         if let Some(cache) = &self.cache {
-            match cache.get(url)? {
+            let cached = cache.get(&key)?;
+            crate::cache::record_lookup(ProtocolType::GraphQL, &key, &cached);
+            match cached {
                 crate::cache::CacheResult::Hit(schema) => {
                     debug!("GraphQL cache hit for: {}", url);
                     return Ok(schema);
@@ -768,44 +1187,28 @@ impl Adapter for GraphQLAdapter {
             }
         }
 
-        // Fetch from remote
-        let introspection_query = Self::get_introspection_query();
-
-        let mut req = self
-            .client
-            .post(url)
-            .header("Content-Type", "application/json");
-
-        // Apply authentication if profile is set
-        if let Some(profile) = &self.auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
-        }
-
-        let resp = req
-            .json(&serde_json::json!({ "query": introspection_query }))
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            bail!("Failed to fetch GraphQL schema: HTTP {}", resp.status());
-        }
-
-        let body: Value = resp.json().await?;
-
-        // Check for GraphQL errors in introspection
-        if let Some(errors) = body.get("errors") {
-            bail!(
-                "GraphQL introspection failed: {}",
-                serde_json::to_string_pretty(errors)?
-            );
-        }
+        let body = match &self.schema_url {
+            Some(schema_url) => {
+                crate::explain::record(
+                    "schema",
+                    format!("GraphQL schema from --schema-url {}", schema_url),
+                );
+                self.load_schema_url(schema_url).await.with_context(|| {
+                    format!(
+                        "Failed to load the GraphQL schema from --schema-url '{}'",
+                        schema_url
+                    )
+                })?
+            }
+            None => self.introspect(url).await?,
+        };
 
         // Store in cache if available
         if let Some(cache) = &self.cache {
-            if let Err(e) = cache.put(url, &body) {
+            if let Err(e) = cache.put(&key, &body) {
                 debug!("Failed to cache GraphQL schema: {}", e);
             } else {
-                info!("Cached GraphQL schema for: {}", url);
+                info!("Cached GraphQL schema for: {}", key);
             }
         }
 
@@ -818,11 +1221,18 @@ impl Adapter for GraphQLAdapter {
     }
 
     async fn describe_operation(&self, url: &str, operation: &str) -> Result<OperationDetail> {
+        if operation == document::OPERATION {
+            return Ok(document::detail());
+        }
         let schema = self.fetch_schema(url).await?;
 
         let op = Self::find_operation(&schema, operation)
-            .ok_or_else(|| anyhow!("Operation '{}' not found", operation))?;
+            .ok_or_else(|| UxcError::OperationNotFound(operation.to_string()))?;
         let input_schema = Self::build_operation_input_schema(&schema, operation);
+        let fields = selection::selectable(
+            Self::find_operation_field(&schema, operation).and_then(|field| field.get("type")),
+            &Self::build_type_index(&schema),
+        );
 
         Ok(OperationDetail {
             operation_id: op.operation_id,
@@ -831,6 +1241,10 @@ impl Adapter for GraphQLAdapter {
             parameters: op.parameters,
             return_type: op.return_type,
             input_schema,
+            responses: None,
+            security: None,
+            servers: None,
+            fields,
         })
     }
 
@@ -842,97 +1256,11 @@ impl Adapter for GraphQLAdapter {
     ) -> Result<ExecutionResult> {
         let start = std::time::Instant::now();
 
-        // Parse operation name to determine type
-        let (op_type, field_name) = Self::parse_operation_name(operation)?;
-
-        // Build query arguments string
-        let args_str = if !args.is_empty() {
-            let args_parts: Vec<String> = args
-                .iter()
-                .map(|(k, v)| {
-                    let value_str = match v {
-                        Value::String(s) => format!("\"{}\"", s),
-                        Value::Bool(b) => b.to_string(),
-                        Value::Number(n) => n.to_string(),
-                        Value::Null => "null".to_string(),
-                        Value::Array(arr) => {
-                            let items: Vec<String> = arr
-                                .iter()
-                                .map(|item| match item {
-                                    Value::String(s) => format!("\"{}\"", s),
-                                    _ => item.to_string(),
-                                })
-                                .collect();
-                            format!("[{}]", items.join(", "))
-                        }
-                        Value::Object(_obj) => {
-                            // For nested objects, use variable syntax
-                            format!("${}", k)
-                        }
-                    };
-                    format!("{}: {}", k, value_str)
-                })
-                .collect();
-            format!("({})", args_parts.join(", "))
-        } else {
-            String::new()
-        };
-
-        // For GraphQL, we need to introspect to get the return type fields
-        // For now, use a default selection set that requests common fields
-        // This is a pragmatic approach since we can't know the schema without introspection
-        let selection_set = match field_name.as_str() {
-            "country" => "name code native capital emoji currency languages { name code native }",
-            "countries" => "name code",
-            "continent" => "name code",
-            "continents" => "name code",
-            "language" => "name code native",
-            "languages" => "name code native",
-            _ => "__typename",
-        };
-
-        // Check if we have complex nested objects that need variables
-        let has_complex_objects = args.values().any(|v| matches!(v, Value::Object(_)));
-
-        let (query_string, variables) = if has_complex_objects {
-            // Use variables for complex types
-            let var_names: Vec<String> = args
-                .keys()
-                .map(|k| format!("${}: String", k)) // Simplified type
-                .collect();
-
-            let query = format!(
-                "{} {}{} {{ {} {{ {} }} }}",
-                match op_type {
-                    OperationType::Query => "query",
-                    OperationType::Mutation => "mutation",
-                    OperationType::Subscription => "subscription",
-                },
-                field_name,
-                var_names.join(", "),
-                field_name,
-                selection_set
-            );
-
-            (query, Some(Value::Object(args.into_iter().collect())))
-        } else {
-            let query = format!(
-                "{} {{ {}{} {{ {} }} }}",
-                match op_type {
-                    OperationType::Query => "query",
-                    OperationType::Mutation => "mutation",
-                    OperationType::Subscription => "subscription",
-                },
-                field_name,
-                args_str,
-                selection_set
-            );
-
-            (query, None)
-        };
-
+        let schema = self.selection_schema(url, operation).await;
+        let (query_string, variables, operation_name) =
+            self.request(operation, args, schema.as_ref())?;
         let result = self
-            .execute_graphql(url, &query_string, variables, None)
+            .execute_graphql(url, &query_string, variables, operation_name.as_deref())
             .await?;
 
         // Extract data from response
@@ -943,9 +1271,27 @@ impl Adapter for GraphQLAdapter {
             metadata: ExecutionMetadata {
                 duration_ms: start.elapsed().as_millis() as u64,
                 operation: operation.to_string(),
+                response_headers: Vec::new(),
+                status: None,
             },
         })
     }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        let schema = self.selection_schema(url, operation).await;
+        let (query, variables, operation_name) = self.request(operation, args, schema.as_ref())?;
+        let mut payload = Self::payload(&query, variables, operation_name.as_deref());
+        // The first request of a persisted query carries only its ID
+        if let Some(mode) = &self.persisted_queries {
+            payload = persisted::payload(&payload, &mode.id(&query), false);
+        }
+        RequestPlan::http(self.graphql_request(url, &payload)?)
+    }
 }
 
 #[cfg(test)]
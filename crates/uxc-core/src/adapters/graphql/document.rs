@@ -0,0 +1,265 @@
+//! User-provided GraphQL documents
+//!
+//! Besides the `query/<field>` operations built from the schema, a GraphQL
+//! endpoint takes whole documents through the [`OPERATION`] operation
+//! (`uxc <host> query --file`). Its arguments are the fields of a GraphQL
+//! request: `query` (the document, which may define several operations and
+//! fragments), `variables` and `operationName`. Documents defining several
+//! operations need `operationName` to pick one.
+
+use crate::adapters::{OperationDetail, Parameter};
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Operation ID of user-provided documents
+pub const OPERATION: &str = "document";
+
+/// Description of the [`OPERATION`] operation
+pub(super) fn detail() -> OperationDetail {
+    let parameter = |name: &str, param_type: &str, required: bool, description: &str| Parameter {
+        name: name.to_string(),
+        param_type: param_type.to_string(),
+        required,
+        description: Some(description.to_string()),
+        location: None,
+    };
+    OperationDetail {
+        operation_id: OPERATION.to_string(),
+        display_name: "GraphQL document".to_string(),
+        description: Some(
+            "Run a GraphQL document: queries, mutations and fragments of your own".to_string(),
+        ),
+        parameters: vec![
+            parameter("query", "string", true, "The GraphQL document"),
+            parameter("variables", "object", false, "Values of its variables"),
+            parameter(
+                "operationName",
+                "string",
+                false,
+                "Operation to run when the document defines several",
+            ),
+        ],
+        return_type: None,
+        input_schema: Some(json!({
+            "type": "object",
+            "required": ["query"],
+            "properties": {
+                "query": { "type": "string" },
+                "variables": { "type": "object" },
+                "operationName": { "type": "string" }
+            }
+        })),
+        responses: None,
+        security: None,
+        servers: None,
+        fields: None,
+    }
+}
+
+/// Document, variables and operation name of a call to [`OPERATION`]
+pub(super) fn request(
+    mut args: HashMap<String, Value>,
+) -> Result<(String, Option<Value>, Option<String>)> {
+    let Some(Value::String(document)) = args.remove("query") else {
+        return Err(
+            UxcError::InvalidArguments("A GraphQL document needs 'query'".to_string()).into(),
+        );
+    };
+    let variables = args.remove("variables").filter(|value| !value.is_null());
+    let operation_name = match args.remove("operationName") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(name)) => Some(name),
+        Some(other) => {
+            return Err(UxcError::InvalidArguments(format!(
+                "'operationName' must be a string, not {}",
+                other
+            ))
+            .into())
+        }
+    };
+    if let Some(unknown) = args.keys().next() {
+        return Err(UxcError::InvalidArguments(format!(
+            "Unknown argument '{}' for a GraphQL document; pass variables in 'variables'",
+            unknown
+        ))
+        .into());
+    }
+
+    let defined = operations(&document);
+    let names = || {
+        defined
+            .iter()
+            .map(|name| name.as_deref().unwrap_or("<anonymous>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match &operation_name {
+        _ if defined.is_empty() => {
+            return Err(UxcError::InvalidArguments(
+                "The GraphQL document defines no operation".to_string(),
+            )
+            .into())
+        }
+        Some(name) if !defined.iter().any(|defined| defined.as_ref() == Some(name)) => {
+            return Err(UxcError::InvalidArguments(format!(
+                "The GraphQL document has no operation '{}' (it defines: {})",
+                name,
+                names()
+            ))
+            .into())
+        }
+        None if defined.len() > 1 => {
+            return Err(UxcError::InvalidArguments(format!(
+                "The document defines several operations ({}); pick one with --operation-name",
+                names()
+            ))
+            .into())
+        }
+        _ => {}
+    }
+    Ok((document, variables, operation_name))
+}
+
+/// Names of the operations a document defines, `None` for anonymous ones
+fn operations(document: &str) -> Vec<Option<String>> {
+    enum Pending {
+        Nothing,
+        Operation { named: bool },
+        Fragment,
+    }
+
+    let mut found = Vec::new();
+    let mut pending = Pending::Nothing;
+    let (mut braces, mut parens) = (0usize, 0usize);
+    let mut chars = document.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '#' => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let block = document[start..].starts_with("\"\"\"");
+                if block {
+                    chars.next();
+                    chars.next();
+                }
+                let mut escaped = false;
+                while let Some((at, c)) = chars.next() {
+                    if block {
+                        if document[at..].starts_with("\"\"\"") {
+                            chars.next();
+                            chars.next();
+                            break;
+                        }
+                    } else if escaped {
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == '"' {
+                        break;
+                    }
+                }
+            }
+            '(' => parens += 1,
+            ')' => parens = parens.saturating_sub(1),
+            '{' if parens > 0 => {}
+            '}' if parens > 0 => {}
+            '{' => {
+                if braces == 0 {
+                    if let Pending::Nothing | Pending::Operation { named: false } = pending {
+                        found.push(None);
+                    }
+                    pending = Pending::Nothing;
+                }
+                braces += 1;
+            }
+            '}' => braces = braces.saturating_sub(1),
+            c if braces == 0 && parens == 0 && (c.is_alphabetic() || c == '_') => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(at, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = at + c.len_utf8();
+                    chars.next();
+                }
+                let word = &document[start..end];
+                pending = match (pending, word) {
+                    (Pending::Nothing, "query" | "mutation" | "subscription") => {
+                        Pending::Operation { named: false }
+                    }
+                    (Pending::Nothing, "fragment") => Pending::Fragment,
+                    (Pending::Operation { named: false }, name) => {
+                        found.push(Some(name.to_string()));
+                        Pending::Operation { named: true }
+                    }
+                    (pending, _) => pending,
+                };
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_lists_named_and_anonymous_operations() {
+        let document = r#"
+            # query Commented { x }
+            query Viewer($first: Int = 10, $filter: Filter = { tag: "a{" }) @cached {
+              viewer { ...Profile }
+            }
+            fragment Profile on User { login description(format: "}") }
+            mutation Star { addStar(input: { id: "1" }) { clientMutationId } }
+            { anonymous }
+        "#;
+        assert_eq!(
+            operations(document),
+            vec![Some("Viewer".to_string()), Some("Star".to_string()), None]
+        );
+        assert_eq!(operations("query { a }"), vec![None]);
+        assert!(operations("fragment F on T { a }").is_empty());
+    }
+
+    #[test]
+    fn request_needs_an_operation_name_for_several_operations() {
+        let args = |pairs: &[(&str, Value)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+        let document = json!("query A { a } query B { b }");
+
+        let err = request(args(&[("query", document.clone())])).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("several operations (A, B); pick one"));
+        let err = request(args(&[
+            ("query", document.clone()),
+            ("operationName", json!("C")),
+        ]))
+        .unwrap_err();
+        assert!(err.to_string().contains("no operation 'C'"));
+
+        let (query, variables, name) = request(args(&[
+            ("query", document),
+            ("operationName", json!("B")),
+            ("variables", json!({ "id": 1 })),
+        ]))
+        .unwrap();
+        assert_eq!(query, "query A { a } query B { b }");
+        assert_eq!(variables, Some(json!({ "id": 1 })));
+        assert_eq!(name.as_deref(), Some("B"));
+    }
+}
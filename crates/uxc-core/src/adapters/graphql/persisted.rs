@@ -0,0 +1,123 @@
+//! Persisted queries
+//!
+//! Gateways behind a CDN often refuse raw query strings. With
+//! [`PersistedQueries::Automatic`] (Automatic Persisted Queries) a call first
+//! sends only the SHA-256 hash of its document, and sends the document along
+//! with the hash when the server answers `PersistedQueryNotFound`, which
+//! registers it for the calls after. [`PersistedQueries::Registered`] sends
+//! only the ID of a document registered ahead of time: the ID given, or the
+//! document's hash. Both put the ID in the `persistedQuery` extension.
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// How calls refer to their document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersistedQueries {
+    /// Send the hash, and the document only when the server asks for it
+    Automatic,
+    /// Send only the ID of a registered document; `None` uses its hash
+    Registered { id: Option<String> },
+}
+
+impl PersistedQueries {
+    /// ID sent for `document`
+    pub fn id(&self, document: &str) -> String {
+        match self {
+            PersistedQueries::Registered { id: Some(id) } => id.clone(),
+            _ => hash(document),
+        }
+    }
+}
+
+/// Hex SHA-256 of `document`, the ID Automatic Persisted Queries use
+pub fn hash(document: &str) -> String {
+    Sha256::digest(document.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// `payload` referring to its document by `id`, with the document itself
+/// only when `with_document`
+pub(super) fn payload(payload: &Value, id: &str, with_document: bool) -> Value {
+    let mut payload = payload.clone();
+    if let Some(fields) = payload.as_object_mut() {
+        if !with_document {
+            fields.remove("query");
+        }
+        fields.insert(
+            "extensions".to_string(),
+            json!({ "persistedQuery": { "version": 1, "sha256Hash": id } }),
+        );
+    }
+    payload
+}
+
+/// Whether `body` says the server does not know the document's ID
+pub(super) fn not_found(body: &Value) -> bool {
+    has_error(body, "PersistedQueryNotFound", "PERSISTED_QUERY_NOT_FOUND")
+}
+
+/// Whether `body` says the server does not take persisted queries
+pub(super) fn not_supported(body: &Value) -> bool {
+    has_error(
+        body,
+        "PersistedQueryNotSupported",
+        "PERSISTED_QUERY_NOT_SUPPORTED",
+    )
+}
+
+fn has_error(body: &Value, message: &str, code: &str) -> bool {
+    body.get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .any(|error| {
+            error.get("message").and_then(Value::as_str) == Some(message)
+                || error.pointer("/extensions/code").and_then(Value::as_str) == Some(code)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_carries_the_hash_and_the_document_on_request() {
+        let document = "{ viewer { login } }";
+        let id = PersistedQueries::Automatic.id(document);
+        assert_eq!(
+            id,
+            "2367287a77942a7f34c7413064250e3c57a0e4254b2ac932b876ae5133554a1f"
+        );
+
+        let full = json!({ "query": document, "variables": { "a": 1 } });
+        let hashed = payload(&full, &id, false);
+        assert!(hashed.get("query").is_none());
+        assert_eq!(hashed["variables"]["a"], 1);
+        assert_eq!(hashed["extensions"]["persistedQuery"]["sha256Hash"], id);
+        assert_eq!(payload(&full, &id, true)["query"], document);
+
+        let registered = PersistedQueries::Registered {
+            id: Some("viewer-v1".to_string()),
+        };
+        assert_eq!(registered.id(document), "viewer-v1");
+    }
+
+    #[test]
+    fn recognizes_persisted_query_errors() {
+        let by_message = json!({ "errors": [{ "message": "PersistedQueryNotFound" }] });
+        let by_code = json!({ "errors": [{
+            "message": "not found",
+            "extensions": { "code": "PERSISTED_QUERY_NOT_FOUND" }
+        }] });
+        assert!(not_found(&by_message));
+        assert!(not_found(&by_code));
+        assert!(!not_supported(&by_code));
+        assert!(not_supported(
+            &json!({ "errors": [{ "message": "PersistedQueryNotSupported" }] })
+        ));
+        assert!(!not_found(&json!({ "data": {} })));
+    }
+}
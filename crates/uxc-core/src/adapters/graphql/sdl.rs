@@ -0,0 +1,312 @@
+//! GraphQL schemas written in SDL
+//!
+//! Servers that disable introspection can still be used with their schema in
+//! SDL (`--schema-url schema.graphql`). The SDL is turned into the result the
+//! introspection query would have returned, so listing, describing and
+//! building calls work the same either way.
+
+use crate::error::UxcError;
+use anyhow::Result;
+use graphql_parser::schema::{
+    parse_schema, Definition, Field, InputValue, Type, TypeDefinition, TypeExtension,
+};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+const BUILTIN_SCALARS: [&str; 5] = ["String", "Int", "Float", "Boolean", "ID"];
+
+/// The schema behind `text`: an introspection result as JSON, or SDL
+pub(super) fn schema(text: &str) -> Result<Value> {
+    if text.trim_start().starts_with('{') {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|e| UxcError::InvalidArguments(format!("Invalid schema JSON: {}", e)))?;
+        return match value {
+            value if value.pointer("/data/__schema").is_some() => Ok(value),
+            value if value.get("__schema").is_some() => Ok(json!({ "data": value })),
+            _ => Err(UxcError::InvalidArguments(
+                "The JSON document is not a GraphQL introspection result".to_string(),
+            )
+            .into()),
+        };
+    }
+    introspection(text)
+}
+
+/// The introspection result describing the schema `sdl` defines
+fn introspection(sdl: &str) -> Result<Value> {
+    let document = parse_schema::<&str>(sdl)
+        .map_err(|e| UxcError::InvalidArguments(format!("Invalid GraphQL SDL: {}", e)))?;
+
+    // Without a `schema` definition the root types go by their default names
+    let mut roots = ["Query", "Mutation", "Subscription"].map(|name| Some(name.to_string()));
+    let mut definitions = Vec::new();
+    let mut extensions = Vec::new();
+    for definition in &document.definitions {
+        match definition {
+            Definition::SchemaDefinition(schema) => {
+                roots = [
+                    schema.query.map(ToString::to_string),
+                    schema.mutation.map(ToString::to_string),
+                    schema.subscription.map(ToString::to_string),
+                ];
+            }
+            Definition::TypeDefinition(definition) => definitions.push(definition),
+            Definition::TypeExtension(extension) => extensions.push(extension),
+            Definition::DirectiveDefinition(_) => {}
+        }
+    }
+
+    let kinds: HashMap<&str, &str> = definitions
+        .iter()
+        .map(|definition| match definition {
+            TypeDefinition::Scalar(t) => (t.name, "SCALAR"),
+            TypeDefinition::Object(t) => (t.name, "OBJECT"),
+            TypeDefinition::Interface(t) => (t.name, "INTERFACE"),
+            TypeDefinition::Union(t) => (t.name, "UNION"),
+            TypeDefinition::Enum(t) => (t.name, "ENUM"),
+            TypeDefinition::InputObject(t) => (t.name, "INPUT_OBJECT"),
+        })
+        .collect();
+
+    let mut types: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for definition in &definitions {
+        let (name, description, entry) = match definition {
+            TypeDefinition::Scalar(t) => (t.name, &t.description, Map::new()),
+            TypeDefinition::Object(t) => (t.name, &t.description, {
+                let mut entry = Map::new();
+                entry.insert("fields".into(), fields(&t.fields, &kinds));
+                entry.insert(
+                    "interfaces".into(),
+                    named("INTERFACE", &t.implements_interfaces),
+                );
+                entry
+            }),
+            TypeDefinition::Interface(t) => (t.name, &t.description, {
+                let mut entry = Map::new();
+                entry.insert("fields".into(), fields(&t.fields, &kinds));
+                entry
+            }),
+            TypeDefinition::Union(t) => (t.name, &t.description, {
+                let mut entry = Map::new();
+                entry.insert("possibleTypes".into(), named("OBJECT", &t.types));
+                entry
+            }),
+            TypeDefinition::Enum(t) => (t.name, &t.description, {
+                let mut entry = Map::new();
+                let values = t
+                    .values
+                    .iter()
+                    .map(|value| json!({ "name": value.name, "description": value.description }))
+                    .collect();
+                entry.insert("enumValues".into(), Value::Array(values));
+                entry
+            }),
+            TypeDefinition::InputObject(t) => (t.name, &t.description, {
+                let mut entry = Map::new();
+                entry.insert("inputFields".into(), input_values(&t.fields, &kinds));
+                entry
+            }),
+        };
+        let mut type_def = Map::new();
+        type_def.insert("kind".into(), json!(kinds[name]));
+        type_def.insert("name".into(), json!(name));
+        type_def.insert("description".into(), json!(description));
+        type_def.extend(entry);
+        types.insert(name.to_string(), type_def);
+    }
+    for scalar in BUILTIN_SCALARS {
+        types.entry(scalar.to_string()).or_insert_with(|| {
+            let mut type_def = Map::new();
+            type_def.insert("kind".into(), json!("SCALAR"));
+            type_def.insert("name".into(), json!(scalar));
+            type_def
+        });
+    }
+
+    // `extend type` adds to the types defined above
+    for extension in extensions {
+        let (name, key, added) = match extension {
+            TypeExtension::Object(t) => (t.name, "fields", fields(&t.fields, &kinds)),
+            TypeExtension::Interface(t) => (t.name, "fields", fields(&t.fields, &kinds)),
+            TypeExtension::Union(t) => (t.name, "possibleTypes", named("OBJECT", &t.types)),
+            TypeExtension::Enum(t) => (
+                t.name,
+                "enumValues",
+                t.values
+                    .iter()
+                    .map(|value| json!({ "name": value.name, "description": value.description }))
+                    .collect(),
+            ),
+            TypeExtension::InputObject(t) => {
+                (t.name, "inputFields", input_values(&t.fields, &kinds))
+            }
+            TypeExtension::Scalar(_) => continue,
+        };
+        let Some(type_def) = types.get_mut(name) else {
+            return Err(UxcError::InvalidArguments(format!(
+                "Invalid GraphQL SDL: 'extend' of undefined type '{}'",
+                name
+            ))
+            .into());
+        };
+        let entry = type_def
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let (Some(entry), Value::Array(added)) = (entry.as_array_mut(), added) {
+            entry.extend(added);
+        }
+    }
+
+    let root = |index: usize| -> Value {
+        match roots[index].clone().filter(|name| types.contains_key(name)) {
+            Some(name) => {
+                let type_def = &types[&name];
+                json!({
+                    "name": name,
+                    "description": type_def.get("description").cloned().unwrap_or(Value::Null),
+                    "fields": type_def.get("fields").cloned().unwrap_or_else(|| json!([])),
+                })
+            }
+            None => Value::Null,
+        }
+    };
+    let (query_type, mutation_type, subscription_type) = (root(0), root(1), root(2));
+    if query_type.is_null() {
+        return Err(UxcError::InvalidArguments(
+            "Invalid GraphQL SDL: the schema has no query type".to_string(),
+        )
+        .into());
+    }
+
+    Ok(json!({
+        "data": {
+            "__schema": {
+                "queryType": query_type,
+                "mutationType": mutation_type,
+                "subscriptionType": subscription_type,
+                "types": types.into_values().map(Value::Object).collect::<Vec<_>>(),
+            }
+        }
+    }))
+}
+
+fn type_ref<'a>(ty: &Type<'a, &'a str>, kinds: &HashMap<&str, &str>) -> Value {
+    match ty {
+        Type::NamedType(name) => json!({
+            "kind": kinds.get(name).copied().unwrap_or("SCALAR"),
+            "name": name,
+            "ofType": null,
+        }),
+        Type::ListType(inner) => json!({
+            "kind": "LIST",
+            "name": null,
+            "ofType": type_ref(inner, kinds),
+        }),
+        Type::NonNullType(inner) => json!({
+            "kind": "NON_NULL",
+            "name": null,
+            "ofType": type_ref(inner, kinds),
+        }),
+    }
+}
+
+fn fields<'a>(fields: &[Field<'a, &'a str>], kinds: &HashMap<&str, &str>) -> Value {
+    fields
+        .iter()
+        .map(|field| {
+            json!({
+                "name": field.name,
+                "description": field.description,
+                "args": input_values(&field.arguments, kinds),
+                "type": type_ref(&field.field_type, kinds),
+            })
+        })
+        .collect()
+}
+
+fn input_values<'a>(values: &[InputValue<'a, &'a str>], kinds: &HashMap<&str, &str>) -> Value {
+    values
+        .iter()
+        .map(|value| {
+            json!({
+                "name": value.name,
+                "description": value.description,
+                "type": type_ref(&value.value_type, kinds),
+                "defaultValue": value.default_value.as_ref().map(ToString::to_string),
+            })
+        })
+        .collect()
+}
+
+fn named(kind: &str, names: &[&str]) -> Value {
+    names
+        .iter()
+        .map(|name| json!({ "kind": kind, "name": name, "ofType": null }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sdl_becomes_an_introspection_result() {
+        let sdl = r#"
+            schema { query: Root, mutation: Mutation }
+
+            "A person"
+            type User implements Node {
+              id: ID!
+              login: String
+              repositories(first: Int = 10): [Repository!]!
+            }
+            interface Node { id: ID! }
+            type Repository { name: String, visibility: Visibility }
+            enum Visibility { PUBLIC PRIVATE }
+            input UserFilter { login: String }
+            type Root { user(filter: UserFilter): User }
+            extend type Root { viewer: User! }
+            type Mutation { follow(login: String!): User }
+        "#;
+        let schema = introspection(sdl).unwrap();
+        let schema = &schema["data"]["__schema"];
+
+        assert_eq!(schema["queryType"]["name"], "Root");
+        assert_eq!(schema["queryType"]["fields"][1]["name"], "viewer");
+        assert_eq!(schema["mutationType"]["fields"][0]["name"], "follow");
+        assert!(schema["subscriptionType"].is_null());
+
+        let types = schema["types"].as_array().unwrap();
+        let user = types.iter().find(|t| t["name"] == "User").unwrap();
+        assert_eq!(user["kind"], "OBJECT");
+        assert_eq!(user["description"], "A person");
+        assert_eq!(
+            user["fields"][2]["type"],
+            json!({ "kind": "NON_NULL", "name": null, "ofType": {
+                "kind": "LIST", "name": null, "ofType": {
+                    "kind": "NON_NULL", "name": null, "ofType": {
+                        "kind": "OBJECT", "name": "Repository", "ofType": null
+                    }
+                }
+            } })
+        );
+        assert_eq!(user["fields"][2]["args"][0]["defaultValue"], "10");
+        assert_eq!(
+            schema["queryType"]["fields"][0]["args"][0]["type"]["kind"],
+            "INPUT_OBJECT"
+        );
+        assert!(types.iter().any(|t| t["name"] == "Boolean"));
+    }
+
+    #[test]
+    fn schema_accepts_introspection_json_and_rejects_other_documents() {
+        let introspected = schema(r#"{"__schema": {"queryType": {"name": "Query"}}}"#).unwrap();
+        assert_eq!(
+            introspected["data"]["__schema"]["queryType"]["name"],
+            "Query"
+        );
+        assert!(schema(r#"{"openapi": "3.0.0"}"#).is_err());
+        assert!(schema("openapi: 3.0.0\ninfo: {}").is_err());
+        assert!(schema("type Mutation { a: Int }").is_err());
+    }
+}
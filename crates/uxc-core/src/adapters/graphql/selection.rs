@@ -0,0 +1,368 @@
+//! Selection sets of schema-built operations
+//!
+//! `query/<field>` and friends select fields of the return type: by default
+//! its scalar and enum fields, objects [`Selection::depth`] levels deep, and
+//! with `--select 'id,name,owner{login}'` the fields named (objects named
+//! without braces get the default selection). Selections are checked
+//! against the introspected schema when its types list their fields.
+
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Depth of the default selection: the scalar fields of the return type
+pub const DEFAULT_DEPTH: usize = 1;
+
+/// Fields selected from the results of schema-built operations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    /// Fields to select, `id,name,owner{login}`; `None` selects by depth
+    pub fields: Option<String>,
+    /// Levels of object fields the default selection descends into
+    /// ([`DEFAULT_DEPTH`] when `None`)
+    pub depth: Option<usize>,
+}
+
+/// A field of a `--select` list and its own selection, if braced
+#[derive(Debug, PartialEq)]
+struct Field {
+    name: String,
+    children: Option<Vec<Field>>,
+}
+
+/// Selection set for an operation returning `return_type`, without the
+/// outer braces; `None` when it returns a scalar or enum
+pub(super) fn selection_set(
+    return_type: Option<&Value>,
+    types: &HashMap<String, &Value>,
+    selection: &Selection,
+) -> Result<Option<String>> {
+    let depth = selection.depth.unwrap_or(DEFAULT_DEPTH);
+    if depth == 0 {
+        return Err(UxcError::InvalidArguments("--depth must be at least 1".to_string()).into());
+    }
+    let requested = selection.fields.as_deref().map(parse).transpose()?;
+    let type_name = return_type.and_then(named_type);
+    let type_def = type_name.and_then(|name| types.get(name).copied());
+
+    if let Some(type_def) = type_def {
+        if is_leaf(type_def) {
+            if requested.is_some() {
+                return Err(UxcError::InvalidArguments(format!(
+                    "The operation returns {}, which has no fields to select",
+                    type_name.unwrap_or_default()
+                ))
+                .into());
+            }
+            return Ok(None);
+        }
+    }
+    let rendered = match requested {
+        Some(fields) => render(&fields, type_def, types, depth)?,
+        None => default_selection(type_def, types, depth),
+    };
+    Ok(Some(rendered))
+}
+
+/// Fields of `return_type` that `--select` can pick, with their types
+pub(super) fn selectable(
+    return_type: Option<&Value>,
+    types: &HashMap<String, &Value>,
+) -> Option<Vec<Value>> {
+    let type_def = types.get(return_type.and_then(named_type)?)?;
+    let fields = type_def.get("fields")?.as_array()?;
+    Some(
+        fields
+            .iter()
+            .filter_map(|field| {
+                let mut entry = json!({
+                    "name": field.get("name")?.as_str()?,
+                    "type": super::GraphQLAdapter::type_to_string(field.get("type")?),
+                });
+                if let Some(description) = field.get("description").and_then(Value::as_str) {
+                    entry["description"] = json!(description);
+                }
+                Some(entry)
+            })
+            .collect(),
+    )
+}
+
+/// Name of the type under the list and non-null wrappers of `type_ref`
+fn named_type(type_ref: &Value) -> Option<&str> {
+    match type_ref.get("kind")?.as_str()? {
+        "NON_NULL" | "LIST" => named_type(type_ref.get("ofType")?),
+        _ => type_ref.get("name")?.as_str(),
+    }
+}
+
+fn is_leaf(type_def: &Value) -> bool {
+    matches!(
+        type_def.get("kind").and_then(Value::as_str),
+        Some("SCALAR" | "ENUM")
+    )
+}
+
+fn fields_of(type_def: &Value) -> Option<&Vec<Value>> {
+    type_def.get("fields")?.as_array()
+}
+
+/// Whether `field` can be selected without arguments
+fn selectable_bare(field: &Value) -> bool {
+    !field
+        .get("args")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .any(|arg| arg.pointer("/type/kind").and_then(Value::as_str) == Some("NON_NULL"))
+}
+
+/// Leaf fields of `type_def`, and its object fields `depth - 1` levels deep;
+/// `__typename` when nothing else can be selected
+fn default_selection(
+    type_def: Option<&Value>,
+    types: &HashMap<String, &Value>,
+    depth: usize,
+) -> String {
+    let mut selected = Vec::new();
+    for field in type_def.and_then(fields_of).into_iter().flatten() {
+        let Some(name) = field.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        if !selectable_bare(field) {
+            continue;
+        }
+        let field_type = field
+            .get("type")
+            .and_then(named_type)
+            .and_then(|name| types.get(name).copied());
+        match field_type {
+            Some(field_type) if is_leaf(field_type) => selected.push(name.to_string()),
+            Some(field_type) if depth > 1 => selected.push(format!(
+                "{} {{ {} }}",
+                name,
+                default_selection(Some(field_type), types, depth - 1)
+            )),
+            _ => {}
+        }
+    }
+    if selected.is_empty() {
+        "__typename".to_string()
+    } else {
+        selected.join(" ")
+    }
+}
+
+/// `fields` as a selection set of `type_def`, checked against its fields
+/// when the schema lists them
+fn render(
+    fields: &[Field],
+    type_def: Option<&Value>,
+    types: &HashMap<String, &Value>,
+    depth: usize,
+) -> Result<String> {
+    let known = type_def.and_then(fields_of);
+    let mut rendered = Vec::new();
+    for field in fields {
+        if field.name == "__typename" {
+            rendered.push(field.name.clone());
+            continue;
+        }
+        let definition = match known {
+            Some(known) => Some(
+                known
+                    .iter()
+                    .find(|known| known.get("name").and_then(Value::as_str) == Some(&field.name))
+                    .ok_or_else(|| unknown_field(&field.name, type_def, known))?,
+            ),
+            None => None,
+        };
+        let field_type_name = definition
+            .and_then(|definition| definition.get("type"))
+            .and_then(named_type);
+        let field_type = field_type_name.and_then(|name| types.get(name).copied());
+        let leaf = field_type.is_some_and(is_leaf);
+        match &field.children {
+            Some(_) if leaf => {
+                return Err(UxcError::InvalidArguments(format!(
+                    "'{}' is a {}, which has no fields to select",
+                    field.name,
+                    field_type_name.unwrap_or_default()
+                ))
+                .into())
+            }
+            Some(children) => rendered.push(format!(
+                "{} {{ {} }}",
+                field.name,
+                render(children, field_type, types, depth)?
+            )),
+            None if leaf || field_type.is_none() => rendered.push(field.name.clone()),
+            None => rendered.push(format!(
+                "{} {{ {} }}",
+                field.name,
+                default_selection(field_type, types, depth)
+            )),
+        }
+    }
+    Ok(rendered.join(" "))
+}
+
+fn unknown_field(name: &str, type_def: Option<&Value>, known: &[Value]) -> anyhow::Error {
+    let names: Vec<&str> = known
+        .iter()
+        .filter_map(|field| field.get("name").and_then(Value::as_str))
+        .collect();
+    UxcError::InvalidArguments(format!(
+        "{} has no field '{}' (fields: {})",
+        type_def
+            .and_then(|type_def| type_def.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or("The result"),
+        name,
+        names.join(", ")
+    ))
+    .into()
+}
+
+/// Parse `id,name,owner{login}`; commas and whitespace both separate fields
+fn parse(text: &str) -> Result<Vec<Field>> {
+    let invalid = |why: &str| -> anyhow::Error {
+        UxcError::InvalidArguments(format!("Invalid --select '{}': {}", text, why)).into()
+    };
+    let mut stack: Vec<Vec<Field>> = vec![Vec::new()];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            ',' => {}
+            c if c.is_whitespace() => {}
+            '{' => {
+                let Some(parent) = stack.last().and_then(|level| level.last()) else {
+                    return Err(invalid("'{' must follow a field name"));
+                };
+                if parent.children.is_some() {
+                    return Err(invalid("'{' must follow a field name"));
+                }
+                stack.push(Vec::new());
+            }
+            '}' => {
+                if stack.len() == 1 {
+                    return Err(invalid("unbalanced '}'"));
+                }
+                let children = stack.pop().unwrap_or_default();
+                if children.is_empty() {
+                    return Err(invalid("empty braces"));
+                }
+                if let Some(parent) = stack.last_mut().and_then(|level| level.last_mut()) {
+                    parent.children = Some(children);
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start + 1;
+                while let Some(&(at, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = at + 1;
+                    chars.next();
+                }
+                if let Some(level) = stack.last_mut() {
+                    level.push(Field {
+                        name: text[start..end].to_string(),
+                        children: None,
+                    });
+                }
+            }
+            other => return Err(invalid(&format!("unexpected '{}'", other))),
+        }
+    }
+    if stack.len() > 1 {
+        return Err(invalid("missing '}'"));
+    }
+    let fields = stack.pop().unwrap_or_default();
+    if fields.is_empty() {
+        return Err(invalid("no fields"));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types() -> Value {
+        let scalar = |name: &str| json!({ "kind": "SCALAR", "name": name });
+        let field =
+            |name: &str, type_ref: Value| json!({ "name": name, "args": [], "type": type_ref });
+        json!([
+            { "kind": "OBJECT", "name": "Repository", "fields": [
+                field("id", json!({ "kind": "NON_NULL", "ofType": scalar("ID") })),
+                field("name", scalar("String")),
+                field("owner", json!({ "kind": "OBJECT", "name": "User" })),
+                { "name": "issues", "args": [
+                    { "name": "first", "type": { "kind": "NON_NULL", "ofType": scalar("Int") } }
+                ], "type": { "kind": "LIST", "ofType": { "kind": "OBJECT", "name": "Issue" } } }
+            ] },
+            { "kind": "OBJECT", "name": "User", "fields": [
+                field("login", scalar("String")),
+                field("status", json!({ "kind": "ENUM", "name": "Status" })),
+                field("repository", json!({ "kind": "OBJECT", "name": "Repository" }))
+            ] },
+            { "kind": "ENUM", "name": "Status" },
+            { "kind": "SCALAR", "name": "ID" },
+            { "kind": "SCALAR", "name": "String" },
+            { "kind": "SCALAR", "name": "Int" }
+        ])
+    }
+
+    fn select(fields: Option<&str>, depth: Option<usize>) -> Result<Option<String>> {
+        let types = types();
+        let index = types
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| (t["name"].as_str().unwrap().to_string(), t))
+            .collect();
+        let selection = Selection {
+            fields: fields.map(ToString::to_string),
+            depth,
+        };
+        let return_type =
+            json!({ "kind": "NON_NULL", "ofType": { "kind": "OBJECT", "name": "Repository" } });
+        selection_set(Some(&return_type), &index, &selection)
+    }
+
+    #[test]
+    fn default_selection_follows_depth() {
+        assert_eq!(select(None, None).unwrap().unwrap(), "id name");
+        assert_eq!(
+            select(None, Some(2)).unwrap().unwrap(),
+            "id name owner { login status }"
+        );
+        assert!(select(None, Some(0)).is_err());
+    }
+
+    #[test]
+    fn explicit_selection_is_checked_against_the_schema() {
+        assert_eq!(
+            select(Some("id, owner{login repository{name}}"), None)
+                .unwrap()
+                .unwrap(),
+            "id owner { login repository { name } }"
+        );
+        // Objects named without braces get the default selection
+        assert_eq!(
+            select(Some("owner"), None).unwrap().unwrap(),
+            "owner { login status }"
+        );
+        let err = select(Some("id,stars"), None).unwrap_err().to_string();
+        assert!(
+            err.contains("Repository has no field 'stars' (fields: id, name, owner, issues)"),
+            "{}",
+            err
+        );
+        assert!(select(Some("name{x}"), None).is_err());
+        assert!(select(Some("owner{login"), None).is_err());
+        assert!(select(Some("{login}"), None).is_err());
+    }
+}
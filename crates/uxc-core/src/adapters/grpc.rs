@@ -7,9 +7,13 @@
 //! - TLS and h2c (cleartext) support
 //! - Proper error handling and status code mapping
 
-use super::{Adapter, ExecutionResult, Operation, OperationDetail, Parameter, ProtocolType};
-use crate::auth::Profile;
-use crate::error::UxcError;
+use super::transcoding::{self, HttpBinding};
+use super::{
+    probe, Adapter, Capabilities, CapabilitySet, ExecutionResult, Operation, OperationDetail,
+    Parameter, ProtocolType, RequestPlan,
+};
+use crate::auth::{AuthType, Profile};
+use crate::error::{ErrorDetail, UxcError};
 use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
 use prost::Message;
@@ -18,17 +22,136 @@ use prost_types::{
     DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorProto,
 };
 use reflection::{server_reflection_request, ServerReflectionRequest};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::transport::Endpoint;
+use tonic::transport::{Channel, Endpoint};
 use tonic::Status;
 use tonic_reflection::pb as reflection;
 use tracing::{debug, info};
 
+/// Delay before the first connect retry; doubled on every further attempt
+const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Connection settings for gRPC channels and grpcurl calls
+///
+/// Read from the `grpc.*` settings, the `[grpc]` section of
+/// `~/.uxc/config.toml` or `.uxc.toml` (see [`crate::config`]). Durations are
+/// in seconds, given as numbers or as durations such as `"5m"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcConnectionConfig {
+    /// Timeout for a single request
+    #[serde(deserialize_with = "crate::units::deserialize_seconds")]
+    pub request_timeout: u64,
+    /// Timeout for establishing a connection
+    #[serde(deserialize_with = "crate::units::deserialize_seconds")]
+    pub connect_timeout: u64,
+    /// TCP keepalive interval
+    #[serde(deserialize_with = "crate::units::deserialize_seconds")]
+    pub tcp_keepalive: u64,
+    /// Interval between HTTP/2 keepalive pings
+    #[serde(deserialize_with = "crate::units::deserialize_seconds")]
+    pub keepalive_interval: u64,
+    /// How long to wait for a keepalive ping acknowledgement
+    #[serde(deserialize_with = "crate::units::deserialize_seconds")]
+    pub keepalive_timeout: u64,
+    /// Additional connection attempts after a failed connect
+    pub connect_retries: u32,
+    /// Reconnect instead of reusing a channel idle for longer than this
+    #[serde(deserialize_with = "crate::units::deserialize_seconds")]
+    pub idle_timeout: u64,
+    /// gRPC-JSON gateway to call annotated methods through when the server
+    /// cannot be reached (`--via-http`)
+    pub http_gateway: Option<String>,
+}
+
+impl Default for GrpcConnectionConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: 30,
+            connect_timeout: 10,
+            tcp_keepalive: 60,
+            keepalive_interval: 30,
+            keepalive_timeout: 10,
+            connect_retries: 0,
+            idle_timeout: 300,
+            http_gateway: None,
+        }
+    }
+}
+
+impl GrpcConnectionConfig {
+    /// Load the `grpc.*` settings (see [`crate::config`]), or defaults
+    pub fn load_from_file() -> Result<Self> {
+        let mut settings = Vec::new();
+        for key in crate::config::KEYS {
+            if key.name.starts_with("grpc.") {
+                let setting = crate::config::get(key.name)?;
+                if setting.is_configured() {
+                    settings.push(setting);
+                }
+            }
+        }
+        Ok(Self::from_settings(&settings))
+    }
+
+    /// Defaults overridden by the configured `grpc.*` `settings`
+    pub fn from_settings(settings: &[crate::config::Setting]) -> Self {
+        let mut config = Self::default();
+        for setting in settings {
+            let seconds = setting.as_duration().map(|duration| duration.as_secs());
+            match setting.key.as_str() {
+                "grpc.request_timeout" => {
+                    config.request_timeout = seconds.unwrap_or(config.request_timeout)
+                }
+                "grpc.connect_timeout" => {
+                    config.connect_timeout = seconds.unwrap_or(config.connect_timeout)
+                }
+                "grpc.tcp_keepalive" => {
+                    config.tcp_keepalive = seconds.unwrap_or(config.tcp_keepalive)
+                }
+                "grpc.keepalive_interval" => {
+                    config.keepalive_interval = seconds.unwrap_or(config.keepalive_interval)
+                }
+                "grpc.keepalive_timeout" => {
+                    config.keepalive_timeout = seconds.unwrap_or(config.keepalive_timeout)
+                }
+                "grpc.connect_retries" => {
+                    if let Some(retries) = setting.as_u64() {
+                        config.connect_retries = retries as u32;
+                    }
+                }
+                "grpc.idle_timeout" => config.idle_timeout = seconds.unwrap_or(config.idle_timeout),
+                "grpc.http_gateway" => {
+                    config.http_gateway = setting.as_str().map(ToString::to_string)
+                }
+                _ => {}
+            }
+        }
+        config
+    }
+}
+
+/// A connected channel kept for reuse across calls
+struct PooledChannel {
+    channel: Channel,
+    last_used: Instant,
+}
+
+type ChannelPool = Arc<Mutex<HashMap<String, PooledChannel>>>;
+
+/// The channel pool of this process, so every adapter built for a server
+/// (by detection, then for the call) shares one HTTP/2 connection
+fn shared_channels() -> ChannelPool {
+    static CHANNELS: OnceLock<ChannelPool> = OnceLock::new();
+    CHANNELS.get_or_init(Default::default).clone()
+}
+
 /// Trait for executing grpcurl commands (abstracted for testing)
 #[async_trait]
 trait GrpcurlExecutor: Send + Sync {
@@ -52,7 +175,9 @@ struct GrpcurlResult {
 }
 
 /// Default grpcurl executor using tokio::process::Command
-struct DefaultGrpcurlExecutor;
+struct DefaultGrpcurlExecutor {
+    connection: GrpcConnectionConfig,
+}
 
 #[async_trait]
 impl GrpcurlExecutor for DefaultGrpcurlExecutor {
@@ -65,7 +190,13 @@ impl GrpcurlExecutor for DefaultGrpcurlExecutor {
         method: &str,
     ) -> Result<GrpcurlResult> {
         let mut cmd = tokio::process::Command::new("grpcurl");
-        cmd.arg("-format").arg("json");
+        cmd.arg("-format").arg("json").arg("-format-error");
+        cmd.arg("-connect-timeout")
+            .arg(self.connection.connect_timeout.to_string())
+            .arg("-keepalive-time")
+            .arg(self.connection.keepalive_interval.to_string())
+            .arg("-max-time")
+            .arg(self.connection.request_timeout.to_string());
 
         if plaintext {
             cmd.arg("-plaintext");
@@ -105,6 +236,10 @@ pub struct GrpcAdapter {
     auth_profile: Option<Profile>,
     /// grpcurl executor (abstracted for testing)
     grpcurl_executor: Arc<dyn GrpcurlExecutor>,
+    /// Keepalive, timeout and reconnect settings
+    connection: GrpcConnectionConfig,
+    /// Channels reused across calls, keyed by URL and connection settings
+    channels: ChannelPool,
 }
 
 /// Cached reflection data for a server
@@ -131,6 +266,8 @@ struct MethodInfo {
     is_server_streaming: bool,
     is_client_streaming: bool,
     description: Option<String>,
+    /// REST mappings from `google.api.http` annotations
+    http: Vec<HttpBinding>,
 }
 
 impl GrpcAdapter {
@@ -139,10 +276,22 @@ impl GrpcAdapter {
             in_memory_cache: Arc::new(RwLock::new(HashMap::new())),
             schema_cache: None,
             auth_profile: None,
-            grpcurl_executor: Arc::new(DefaultGrpcurlExecutor),
+            grpcurl_executor: Arc::new(DefaultGrpcurlExecutor {
+                connection: GrpcConnectionConfig::default(),
+            }),
+            connection: GrpcConnectionConfig::default(),
+            channels: shared_channels(),
         }
     }
 
+    pub fn with_connection_config(mut self, connection: GrpcConnectionConfig) -> Self {
+        self.grpcurl_executor = Arc::new(DefaultGrpcurlExecutor {
+            connection: connection.clone(),
+        });
+        self.connection = connection;
+        self
+    }
+
     /// Create a new adapter with a custom grpcurl executor (for testing)
     #[cfg(test)]
     fn with_executor(mut self, executor: Arc<dyn GrpcurlExecutor>) -> Self {
@@ -183,13 +332,66 @@ impl GrpcAdapter {
         Ok(format!("{}:{}", host, port))
     }
 
-    /// Check if the server has reflection enabled
-    async fn has_reflection(endpoint: &Endpoint) -> Result<bool> {
-        let channel = endpoint
-            .connect()
-            .await
-            .context("Failed to connect to gRPC server")?;
+    /// Get a channel for `url`, reusing a pooled one unless it has been idle
+    /// for longer than the configured idle timeout
+    async fn channel(&self, url: &str) -> Result<Channel> {
+        let idle_timeout = Duration::from_secs(self.connection.idle_timeout);
+        let key = self.channel_key(url);
+        let mut channels = self.channels.lock().await;
+        if let Some(pooled) = channels.get_mut(&key) {
+            if pooled.last_used.elapsed() < idle_timeout {
+                pooled.last_used = Instant::now();
+                return Ok(pooled.channel.clone());
+            }
+            debug!("Reconnecting idle gRPC channel for {}", url);
+            channels.remove(&key);
+        }
+
+        let channel = self.connect(url).await?;
+        channels.insert(
+            key,
+            PooledChannel {
+                channel: channel.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        Ok(channel)
+    }
+
+    /// Drop a pooled channel so the next call reconnects
+    async fn discard_channel(&self, url: &str) {
+        self.channels.lock().await.remove(&self.channel_key(url));
+    }
+
+    /// Pool key of the channel for `url`; adapters with other connection
+    /// settings get channels of their own
+    fn channel_key(&self, url: &str) -> String {
+        format!("{} {:?}", url, self.connection)
+    }
+
+    /// Connect, retrying with exponential backoff
+    async fn connect(&self, url: &str) -> Result<Channel> {
+        let endpoint = self.create_endpoint(url)?;
+        let mut attempt = 0;
+        loop {
+            match endpoint.connect().await {
+                Ok(channel) => return Ok(channel),
+                Err(e) if attempt < self.connection.connect_retries => {
+                    let delay = CONNECT_RETRY_DELAY * 2u32.pow(attempt);
+                    attempt += 1;
+                    debug!(
+                        "gRPC connect to {} failed ({}), retry {} in {:?}",
+                        url, e, attempt, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e).context("Failed to connect to gRPC server"),
+            }
+        }
+    }
 
+    /// Check if the server has reflection enabled
+    async fn has_reflection(channel: Channel) -> Result<bool> {
         let mut reflection_client =
             reflection::server_reflection_client::ServerReflectionClient::new(channel)
                 .max_decoding_message_size(usize::MAX);
@@ -206,8 +408,9 @@ impl GrpcAdapter {
         let _ = tx.send(request).await;
         drop(tx); // Close the channel
 
+        let wait = probe::timeout(Duration::from_secs(3));
         let result = tokio::time::timeout(
-            Duration::from_secs(3),
+            wait,
             async {
                 let response = reflection_client.server_reflection_info(ReceiverStream::new(rx)).await;
                 match response {
@@ -220,17 +423,18 @@ impl GrpcAdapter {
                                 ) => return Ok::<bool, anyhow::Error>(true),
                                 Some(
                                     reflection::server_reflection_response::MessageResponse::ErrorResponse(err),
-                                ) => {
-                                    if err.error_code == tonic::Code::Unimplemented as i32 {
-                                        return Ok::<bool, anyhow::Error>(false);
-                                    }
+                                ) if err.error_code == tonic::Code::Unimplemented as i32 => {
+                                    return Ok::<bool, anyhow::Error>(false);
                                 }
                                 _ => {}
                             }
                         }
                         Ok::<bool, anyhow::Error>(false)
                     }
-                    Err(_) => Ok::<bool, anyhow::Error>(false),
+                    Err(status) => {
+                        probe::evidence(format!("server reflection: {}", status.message()));
+                        Ok::<bool, anyhow::Error>(false)
+                    }
                 }
             },
         )
@@ -239,13 +443,18 @@ impl GrpcAdapter {
         match result {
             Ok(Ok(has_reflection)) => Ok(has_reflection),
             Ok(Err(_)) => Ok(false),
-            Err(_) => Ok(false), // Timeout
+            Err(_) => {
+                probe::evidence(format!(
+                    "server reflection: no answer within {}",
+                    crate::output::format_duration(wait.as_millis() as u64)
+                ));
+                Ok(false)
+            }
         }
     }
 
     /// List all services via reflection
-    async fn list_services_reflection(&self, endpoint: &Endpoint) -> Result<Vec<String>> {
-        let channel = endpoint.connect().await?;
+    async fn list_services_reflection(&self, channel: Channel) -> Result<Vec<String>> {
         let mut client = reflection::server_reflection_client::ServerReflectionClient::new(channel)
             .max_decoding_message_size(usize::MAX);
 
@@ -297,13 +506,13 @@ impl GrpcAdapter {
         })
     }
 
-    /// Get file descriptors for a service symbol.
+    /// Get file descriptors for a service symbol, with the REST mappings of
+    /// their methods.
     async fn get_service_descriptors(
         &self,
-        endpoint: &Endpoint,
+        channel: Channel,
         service_name: &str,
-    ) -> Result<Vec<FileDescriptorProto>> {
-        let channel = endpoint.connect().await?;
+    ) -> Result<(Vec<FileDescriptorProto>, HashMap<String, Vec<HttpBinding>>)> {
         let mut client = reflection::server_reflection_client::ServerReflectionClient::new(channel)
             .max_decoding_message_size(usize::MAX);
 
@@ -332,13 +541,15 @@ impl GrpcAdapter {
             ) = response.message_response
             {
                 let mut descriptors = Vec::new();
+                let mut bindings = HashMap::new();
                 for descriptor_bytes in fd.file_descriptor_proto {
                     let descriptor = FileDescriptorProto::decode(descriptor_bytes.as_slice())
                         .context("Failed to decode file descriptor")?;
                     descriptors.push(descriptor);
+                    bindings.extend(transcoding::http_bindings(&descriptor_bytes));
                 }
                 if !descriptors.is_empty() {
-                    return Ok(descriptors);
+                    return Ok((descriptors, bindings));
                 }
             }
         }
@@ -351,6 +562,7 @@ impl GrpcAdapter {
         &self,
         service_descriptor: &FileDescriptorProto,
         all_descriptors: Vec<FileDescriptorProto>,
+        bindings: &HashMap<String, Vec<HttpBinding>>,
     ) -> Result<ServiceInfo> {
         let mut methods = HashMap::new();
         let package = service_descriptor.package.clone().unwrap_or_default();
@@ -372,6 +584,10 @@ impl GrpcAdapter {
                     is_server_streaming: method.server_streaming.unwrap_or(false),
                     is_client_streaming: method.client_streaming.unwrap_or(false),
                     description: None, // Comments are in source_code_info
+                    http: bindings
+                        .get(&format!("{}/{}", full_service_name, method_name))
+                        .cloned()
+                        .unwrap_or_default(),
                 };
                 methods.insert(method_name, method_info);
             }
@@ -393,9 +609,22 @@ impl GrpcAdapter {
             }
         }
 
-        // Load from reflection
-        let endpoint = self.create_endpoint(url)?;
-        let service_names = self.list_services_reflection(&endpoint).await?;
+        if crate::cache::schemas_from_store(self.schema_cache.as_ref()) {
+            let schema = self.fetch_schema(url).await?;
+            return Ok(Self::services_from_schema(&schema));
+        }
+
+        // Load from reflection, reconnecting once if the pooled channel went bad
+        let mut channel = self.channel(url).await?;
+        let service_names = match self.list_services_reflection(channel.clone()).await {
+            Ok(names) => names,
+            Err(e) => {
+                debug!("gRPC reflection on pooled channel failed: {}", e);
+                self.discard_channel(url).await;
+                channel = self.channel(url).await?;
+                self.list_services_reflection(channel.clone()).await?
+            }
+        };
 
         let mut services = HashMap::new();
         for service_name in service_names {
@@ -404,8 +633,11 @@ impl GrpcAdapter {
                 continue;
             }
 
-            match self.get_service_descriptors(&endpoint, &service_name).await {
-                Ok(descriptors) => {
+            match self
+                .get_service_descriptors(channel.clone(), &service_name)
+                .await
+            {
+                Ok((descriptors, bindings)) => {
                     let service_descriptor = descriptors
                         .iter()
                         .find(|descriptor| {
@@ -415,7 +647,9 @@ impl GrpcAdapter {
                         .or_else(|| descriptors.first().cloned());
 
                     if let Some(descriptor) = service_descriptor {
-                        if let Ok(info) = self.parse_service_info(&descriptor, descriptors) {
+                        if let Ok(info) =
+                            self.parse_service_info(&descriptor, descriptors, &bindings)
+                        {
                             services.insert(service_name.clone(), info);
                         }
                     } else {
@@ -446,17 +680,80 @@ impl GrpcAdapter {
     /// Create a gRPC endpoint with proper configuration
     fn create_endpoint(&self, url: &str) -> Result<Endpoint> {
         let addr = Self::parse_url(url)?;
+        crate::allowlist::enforce(&addr, None)?;
+        let connection = &self.connection;
         let endpoint = Endpoint::from_shared(format!("http://{}", addr))?
-            .timeout(Duration::from_secs(30))
-            .connect_timeout(Duration::from_secs(10))
-            .tcp_keepalive(Some(Duration::from_secs(60)))
-            .http2_keep_alive_interval(Duration::from_secs(30))
-            .keep_alive_timeout(Duration::from_secs(10));
+            .timeout(Duration::from_secs(connection.request_timeout))
+            .connect_timeout(Duration::from_secs(connection.connect_timeout))
+            .tcp_keepalive(Some(Duration::from_secs(connection.tcp_keepalive)))
+            .http2_keep_alive_interval(Duration::from_secs(connection.keepalive_interval))
+            .keep_alive_timeout(Duration::from_secs(connection.keepalive_timeout))
+            .keep_alive_while_idle(true);
 
         Ok(endpoint)
     }
 
     /// Find method by full name (ServiceName/MethodName)
+    /// Service info rebuilt from a cached schema. The schema keeps method
+    /// signatures only, so message descriptors are empty.
+    fn services_from_schema(schema: &Value) -> HashMap<String, ServiceInfo> {
+        let mut services = HashMap::new();
+        let entries = schema
+            .get("services")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten();
+        for service in entries {
+            let Some(service_name) = service.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            let mut methods = HashMap::new();
+            let method_entries = service
+                .get("methods")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten();
+            for method in method_entries {
+                let Some(name) = method.get("name").and_then(Value::as_str) else {
+                    continue;
+                };
+                let text = |key: &str| {
+                    method
+                        .get(key)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let flag = |key: &str| method.get(key).and_then(Value::as_bool).unwrap_or(false);
+                methods.insert(
+                    name.to_string(),
+                    MethodInfo {
+                        name: name.to_string(),
+                        service_name: service_name.to_string(),
+                        input_type: text("input_type"),
+                        output_type: text("output_type"),
+                        is_server_streaming: flag("server_streaming"),
+                        is_client_streaming: flag("client_streaming"),
+                        description: None,
+                        http: method
+                            .get("http")
+                            .cloned()
+                            .and_then(|http| serde_json::from_value(http).ok())
+                            .unwrap_or_default(),
+                    },
+                );
+            }
+            services.insert(
+                service_name.to_string(),
+                ServiceInfo {
+                    methods,
+                    file_descriptors: Vec::new(),
+                },
+            );
+        }
+        services
+    }
+
     async fn find_method(&self, url: &str, operation: &str) -> Result<MethodInfo> {
         let (method_info, _) = self.find_method_context(url, operation).await?;
         Ok(method_info)
@@ -765,6 +1062,107 @@ impl GrpcAdapter {
         method_info: &MethodInfo,
         args: HashMap<String, Value>,
     ) -> Result<Value> {
+        Self::ensure_unary(method_info)?;
+
+        let target = Self::parse_url(url)?;
+        let full_method = format!("{}/{}", method_info.service_name, method_info.name);
+        let request_data = self.build_request_message(&args)?;
+
+        let result = self
+            .invoke_unary_with_grpcurl(url, &target, &full_method, &request_data)
+            .await;
+        match (result, self.gateway_binding(method_info)) {
+            // A gRPC status means the server answered; anything else means it
+            // could not be reached
+            (Err(err), Some((gateway, binding))) if !Self::is_grpc_status(&err) => {
+                debug!("gRPC call failed ({:#}), retrying through {}", err, gateway);
+                crate::explain::record(
+                    "grpc",
+                    format!(
+                        "{} sent through the HTTP gateway {} as {}: {}",
+                        full_method,
+                        gateway,
+                        binding.display(),
+                        err
+                    ),
+                );
+                self.call_via_http(gateway, binding, &request_data).await
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// The configured gateway and the method's primary REST mapping, if both exist
+    fn gateway_binding<'a>(
+        &'a self,
+        method_info: &'a MethodInfo,
+    ) -> Option<(&'a str, &'a HttpBinding)> {
+        let gateway = self.connection.http_gateway.as_deref()?;
+        Some((gateway, method_info.http.first()?))
+    }
+
+    fn is_grpc_status(err: &anyhow::Error) -> bool {
+        err.chain().any(|cause| {
+            matches!(
+                cause.downcast_ref::<UxcError>(),
+                Some(UxcError::CallFailed {
+                    detail: ErrorDetail::Grpc { .. },
+                    ..
+                })
+            )
+        })
+    }
+
+    /// The HTTP request `binding` maps the message to, at `gateway`
+    fn gateway_request(
+        &self,
+        gateway: &str,
+        binding: &HttpBinding,
+        request_data: &Value,
+    ) -> Result<reqwest::RequestBuilder> {
+        let call = binding.request(request_data)?;
+        let method = reqwest::Method::from_bytes(call.method.as_bytes())
+            .with_context(|| format!("Invalid HTTP method in google.api.http: {}", call.method))?;
+        let url = format!("{}{}", gateway.trim_end_matches('/'), call.path_and_query);
+        let mut req = crate::http::client().request(method, url);
+        if let Some(profile) = &self.auth_profile {
+            req = profile.apply_to_request(req)?;
+        }
+        if let Some(body) = &call.body {
+            req = req.json(body);
+        }
+        Ok(req)
+    }
+
+    /// Call a method through a gRPC-JSON gateway
+    async fn call_via_http(
+        &self,
+        gateway: &str,
+        binding: &HttpBinding,
+        request_data: &Value,
+    ) -> Result<Value> {
+        let response =
+            crate::http::send(self.gateway_request(gateway, binding, request_data)?).await?;
+        let status = response.status();
+        let retry_after = crate::http::retry_after(response.headers());
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(UxcError::call_failed(
+                format!(
+                    "{} {} through the HTTP gateway failed with HTTP {}",
+                    binding.method, binding.path, status
+                ),
+                ErrorDetail::http(status.as_u16(), &body).with_retry_after(retry_after),
+            )
+            .into());
+        }
+        if body.trim().is_empty() {
+            return Ok(serde_json::json!({}));
+        }
+        serde_json::from_str(&body).or_else(|_| Ok(serde_json::json!({ "raw": body })))
+    }
+
+    fn ensure_unary(method_info: &MethodInfo) -> Result<()> {
         if method_info.is_server_streaming || method_info.is_client_streaming {
             bail!(
                 "Unsupported gRPC call type for '{}/{}': only unary methods are supported",
@@ -772,13 +1170,7 @@ impl GrpcAdapter {
                 method_info.name
             );
         }
-
-        let target = Self::parse_url(url)?;
-        let full_method = format!("{}/{}", method_info.service_name, method_info.name);
-        let request_data = self.build_request_message(&args)?;
-
-        self.invoke_unary_with_grpcurl(url, &target, &full_method, &request_data)
-            .await
+        Ok(())
     }
 
     async fn invoke_unary_with_grpcurl(
@@ -788,6 +1180,7 @@ impl GrpcAdapter {
         full_method: &str,
         request_data: &Value,
     ) -> Result<Value> {
+        crate::allowlist::enforce(target, None)?;
         let request_json = serde_json::to_string(request_data)?;
         let attempts = Self::grpcurl_attempts(original_url, target);
         let mut last_error = String::new();
@@ -799,6 +1192,20 @@ impl GrpcAdapter {
         };
 
         for plaintext in attempts {
+            crate::trace::rpc_request(
+                &format!(
+                    "grpc {}/{} ({})",
+                    target,
+                    full_method,
+                    if plaintext { "plaintext" } else { "tls" }
+                ),
+                &headers
+                    .iter()
+                    .map(|header| crate::trace::split_header(header))
+                    .collect::<Vec<_>>(),
+                &request_json,
+            );
+            let started = std::time::Instant::now();
             let result = self
                 .grpcurl_executor
                 .execute(
@@ -809,6 +1216,14 @@ impl GrpcAdapter {
                     full_method,
                 )
                 .await?;
+            crate::trace::rpc_response(
+                if result.success {
+                    "OK"
+                } else {
+                    result.stderr.lines().next().unwrap_or("failed")
+                },
+                started.elapsed(),
+            );
 
             if result.success {
                 if result.stdout.is_empty() {
@@ -828,9 +1243,72 @@ impl GrpcAdapter {
             }
         }
 
+        if let Some(err) = Self::parse_grpcurl_status(&last_error) {
+            return Err(err.into());
+        }
         bail!("gRPC unary invocation failed: {}", last_error)
     }
 
+    /// Status grpcurl reported for a failed call: JSON with `-format-error`,
+    /// or the `Code:` / `Message:` text form of older versions
+    fn parse_grpcurl_status(stderr: &str) -> Option<UxcError> {
+        let json = stderr.find('{').and_then(|start| {
+            serde_json::Deserializer::from_str(&stderr[start..])
+                .into_iter::<Value>()
+                .next()?
+                .ok()
+        });
+        if let Some(status) = json.filter(|status| status.get("code").is_some()) {
+            let code = status.get("code").and_then(Value::as_i64).unwrap_or(2) as i32;
+            let message = status
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let details = status
+                .get("details")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            return Some(Self::grpc_call_failed(code, message, details));
+        }
+
+        let field = |name: &str| {
+            stderr
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(name).map(str::trim))
+        };
+        let name = field("Code:")?;
+        let code = (0..=16).find(|&code| format!("{:?}", tonic::Code::from_i32(code)) == name)?;
+        Some(Self::grpc_call_failed(
+            code,
+            field("Message:").unwrap_or_default(),
+            Vec::new(),
+        ))
+    }
+
+    /// [`UxcError::CallFailed`] for a gRPC status, named canonically (`NOT_FOUND`)
+    fn grpc_call_failed(code: i32, message: &str, details: Vec<Value>) -> UxcError {
+        let mut status = String::new();
+        for (i, c) in format!("{:?}", tonic::Code::from_i32(code))
+            .chars()
+            .enumerate()
+        {
+            if c.is_ascii_uppercase() && i > 0 {
+                status.push('_');
+            }
+            status.push(c.to_ascii_uppercase());
+        }
+        UxcError::call_failed(
+            format!("gRPC call failed with {}: {}", status, message),
+            ErrorDetail::Grpc {
+                code,
+                status,
+                message: message.to_string(),
+                details,
+            },
+        )
+    }
+
     fn grpcurl_attempts(original_url: &str, target: &str) -> Vec<bool> {
         let mut attempts = Vec::new();
 
@@ -893,6 +1371,28 @@ impl Default for GrpcAdapter {
     }
 }
 
+impl Capabilities for GrpcAdapter {
+    const PROTOCOL: ProtocolType = ProtocolType::GRpc;
+
+    fn capabilities() -> CapabilitySet {
+        CapabilitySet {
+            streaming: true,
+            batch: false,
+            validation: false,
+            auth_types: vec![
+                AuthType::Bearer,
+                AuthType::ApiKey,
+                AuthType::Basic,
+                AuthType::OAuth,
+            ],
+            schema_export: false,
+            schema_cache: true,
+            raw_body: false,
+            notes: vec!["Calls need grpcurl on PATH".to_string()],
+        }
+    }
+}
+
 #[async_trait]
 impl Adapter for GrpcAdapter {
     fn protocol_type(&self) -> ProtocolType {
@@ -900,18 +1400,42 @@ impl Adapter for GrpcAdapter {
     }
 
     async fn can_handle(&self, url: &str) -> Result<bool> {
-        let endpoint = match self.create_endpoint(url) {
-            Ok(endpoint) => endpoint,
-            Err(_) => return Ok(false),
+        let channel = match self.channel(url).await {
+            Ok(channel) => channel,
+            Err(e) => {
+                probe::evidence(format!("connect {}: {:#}", url, e));
+                return Ok(false);
+            }
         };
 
-        Ok(Self::has_reflection(&endpoint).await.unwrap_or(false))
+        match Self::has_reflection(channel).await {
+            Ok(true) => {
+                probe::evidence("server reflection lists services");
+                Ok(true)
+            }
+            Ok(false) => {
+                probe::evidence("server reflection is unavailable");
+                Ok(false)
+            }
+            Err(e) => {
+                probe::evidence(format!("server reflection: {:#}", e));
+                Ok(false)
+            }
+        }
     }
 
     async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        if crate::cache::schemas_from_store(self.schema_cache.as_ref()) {
+            return crate::cache::cached_schema(self.schema_cache.as_ref(), url, |schema| {
+                schema.get("protocol").and_then(Value::as_str) == Some("gRPC")
+            });
+        }
+
         // Try persistent cache first if available
         if let Some(cache) = &self.schema_cache {
-            match cache.get(url)? {
+            let cached = cache.get(url)?;
+            crate::cache::record_lookup(ProtocolType::GRpc, url, &cached);
+            match cached {
                 crate::cache::CacheResult::Hit(schema) => {
                     debug!("gRPC cache hit for: {}", url);
                     return Ok(schema);
@@ -931,13 +1455,17 @@ impl Adapter for GrpcAdapter {
         for (name, info) in &services {
             let mut methods = Vec::new();
             for (method_name, method_info) in &info.methods {
-                methods.push(serde_json::json!({
+                let mut method = serde_json::json!({
                     "name": method_name,
                     "input_type": method_info.input_type,
                     "output_type": method_info.output_type,
                     "server_streaming": method_info.is_server_streaming,
                     "client_streaming": method_info.is_client_streaming,
-                }));
+                });
+                if !method_info.http.is_empty() {
+                    method["http"] = serde_json::to_value(&method_info.http)?;
+                }
+                methods.push(method);
             }
 
             service_list.push(serde_json::json!({
@@ -981,8 +1509,10 @@ impl Adapter for GrpcAdapter {
                             "Request message of type {}",
                             method_info.input_type
                         )),
+                        location: None,
                     }],
                     return_type: Some(method_info.output_type.clone()),
+                    tags: Vec::new(),
                 });
             }
         }
@@ -1003,6 +1533,10 @@ impl Adapter for GrpcAdapter {
         };
         let input_type = method_info.input_type.clone();
         let output_type = method_info.output_type.clone();
+        let mut input_schema = Self::build_operation_input_schema(&descriptors, &input_type);
+        if !method_info.http.is_empty() {
+            input_schema["http"] = serde_json::to_value(&method_info.http)?;
+        }
 
         Ok(OperationDetail {
             operation_id: format!("{}/{}", method_info.service_name, method_info.name),
@@ -1013,12 +1547,14 @@ impl Adapter for GrpcAdapter {
                 param_type: input_type.clone(),
                 required: true,
                 description: Some(format!("gRPC request payload ({})", stream_type)),
+                location: None,
             }],
             return_type: Some(output_type),
-            input_schema: Some(Self::build_operation_input_schema(
-                &descriptors,
-                &input_type,
-            )),
+            input_schema: Some(input_schema),
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
         })
     }
 
@@ -1038,9 +1574,51 @@ impl Adapter for GrpcAdapter {
             metadata: super::ExecutionMetadata {
                 duration_ms: start.elapsed().as_millis() as u64,
                 operation: operation.to_string(),
+                response_headers: Vec::new(),
+                status: None,
             },
         })
     }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        let method_info = self.find_method(url, operation).await?;
+        Self::ensure_unary(&method_info)?;
+
+        let target = Self::parse_url(url)?;
+        let full_method = format!("{}/{}", method_info.service_name, method_info.name);
+        let headers = match &self.auth_profile {
+            Some(profile) => profile.to_grpcurl_headers()?,
+            None => Vec::new(),
+        };
+        let attempts = Self::grpcurl_attempts(url, &target);
+        let plaintext = attempts.first().copied().unwrap_or(false);
+        let attempts = attempts
+            .into_iter()
+            .map(|plaintext| if plaintext { "plaintext" } else { "TLS" })
+            .collect::<Vec<_>>();
+        let message = self.build_request_message(&args)?;
+        let mut plan = RequestPlan::new("grpc", Some(full_method), target, message.clone())
+            .with_headers(headers, true)
+            .with_plaintext(plaintext)
+            .with_note(format!(
+                "sent with grpcurl over {}",
+                attempts.join(", then ")
+            ));
+        if let Some((gateway, binding)) = self.gateway_binding(&method_info) {
+            let request = crate::http::build(self.gateway_request(gateway, binding, &message)?)?;
+            plan = plan.with_note(format!(
+                "if the server cannot be reached: {} {}",
+                request.method(),
+                request.url()
+            ));
+        }
+        Ok(plan)
+    }
 }
 
 trait GrpcurlAuthHeaders {
@@ -1051,13 +1629,23 @@ impl GrpcurlAuthHeaders for Profile {
     fn to_grpcurl_headers(&self) -> Result<Vec<String>> {
         use base64::Engine;
 
+        let api_key = if self.auth_type == crate::auth::AuthType::OAuth {
+            crate::auth::oauth::access_token(self)?
+        } else {
+            self.resolve_api_key()?
+        };
         let header = match self.auth_type {
-            crate::auth::AuthType::Bearer => format!("authorization: Bearer {}", self.api_key),
-            crate::auth::AuthType::ApiKey => format!("x-api-key: {}", self.api_key),
+            crate::auth::AuthType::Bearer | crate::auth::AuthType::OAuth => {
+                format!("authorization: Bearer {}", api_key)
+            }
+            crate::auth::AuthType::ApiKey => format!("x-api-key: {}", api_key),
             crate::auth::AuthType::Basic => {
-                let encoded = base64::engine::general_purpose::STANDARD.encode(&self.api_key);
+                let encoded = base64::engine::general_purpose::STANDARD.encode(&api_key);
                 format!("authorization: Basic {}", encoded)
             }
+            crate::auth::AuthType::Session => {
+                anyhow::bail!("Session authentication is not supported for gRPC")
+            }
         };
 
         Ok(vec![header])
@@ -1133,6 +1721,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_connection_config_from_settings() {
+        let setting = |name: &str, raw: &str| {
+            let key = crate::config::key(name).unwrap();
+            crate::config::Setting {
+                key: name.to_string(),
+                value: serde_json::to_value(key.parse(raw).unwrap()).ok(),
+                source: crate::config::Source::User,
+                origin: None,
+            }
+        };
+        let config = GrpcConnectionConfig::from_settings(&[
+            setting("grpc.keepalive_interval", "15"),
+            setting("grpc.idle_timeout", "5m"),
+            setting("grpc.connect_retries", "3"),
+        ]);
+        assert_eq!(config.keepalive_interval, 15);
+        assert_eq!(config.idle_timeout, 300);
+        assert_eq!(config.connect_retries, 3);
+        assert_eq!(
+            config.keepalive_timeout,
+            GrpcConnectionConfig::default().keepalive_timeout
+        );
+
+        assert_eq!(
+            GrpcConnectionConfig::from_settings(&[]),
+            GrpcConnectionConfig::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_retries_with_backoff() {
+        // Bind and drop a listener to get a port nothing listens on
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let adapter = GrpcAdapter::new().with_connection_config(GrpcConnectionConfig {
+            connect_retries: 2,
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        let err = adapter
+            .channel(&format!("127.0.0.1:{}", port))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to connect"));
+        // 200ms + 400ms of backoff between the three attempts
+        assert!(start.elapsed() >= Duration::from_millis(600));
+    }
+
     #[tokio::test]
     async fn test_can_handle_rejects_non_grpc_endpoint() {
         let mut server = mockito::Server::new_async().await;
@@ -1159,6 +1800,7 @@ mod tests {
             is_server_streaming: true,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let err = adapter
@@ -1352,6 +1994,7 @@ mod tests {
             is_server_streaming: false,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let result = adapter
@@ -1381,6 +2024,7 @@ mod tests {
             is_server_streaming: false,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let result = adapter
@@ -1410,6 +2054,7 @@ mod tests {
             is_server_streaming: false,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let err = adapter
@@ -1421,6 +2066,112 @@ mod tests {
         assert!(err.to_string().contains("method not found"));
     }
 
+    #[test]
+    fn test_parse_grpcurl_status_detail() {
+        let detail = |stderr: &str| match GrpcAdapter::parse_grpcurl_status(stderr) {
+            Some(UxcError::CallFailed { detail, .. }) => detail,
+            other => panic!("expected a gRPC status, got {:?}", other),
+        };
+
+        let formatted = r#"{
+  "code": 14,
+  "message": "backend draining",
+  "details": [
+    { "@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "2s" }
+  ]
+}"#;
+        let status = detail(formatted);
+        assert!(status.is_retriable());
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            serde_json::json!({
+                "type": "grpc",
+                "code": 14,
+                "status": "UNAVAILABLE",
+                "message": "backend draining",
+                "details": [
+                    { "@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "2s" }
+                ]
+            })
+        );
+
+        let text = "ERROR:\n  Code: NotFound\n  Message: user 7 not found";
+        assert_eq!(
+            detail(text),
+            ErrorDetail::Grpc {
+                code: 5,
+                status: "NOT_FOUND".to_string(),
+                message: "user 7 not found".to_string(),
+                details: Vec::new(),
+            }
+        );
+        assert!(GrpcAdapter::parse_grpcurl_status("Failed to dial target host").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_falls_back_to_http_gateway() {
+        let mut gateway = mockito::Server::new_async().await;
+        let shelf = gateway
+            .mock("GET", "/v1/shelves/7?view=FULL")
+            .with_status(200)
+            .with_body(r#"{"name":"shelves/7"}"#)
+            .create_async()
+            .await;
+        let failing = |stderr: &str| {
+            Arc::new(MockGrpcurlExecutor {
+                response: Some(GrpcurlResult {
+                    success: false,
+                    stdout: String::new(),
+                    stderr: stderr.to_string(),
+                }),
+            })
+        };
+        let connection = GrpcConnectionConfig {
+            http_gateway: Some(gateway.url()),
+            ..Default::default()
+        };
+        let method = MethodInfo {
+            name: "GetShelf".to_string(),
+            service_name: "library.v1.Library".to_string(),
+            input_type: "library.v1.GetShelfRequest".to_string(),
+            output_type: "library.v1.Shelf".to_string(),
+            is_server_streaming: false,
+            is_client_streaming: false,
+            description: None,
+            http: vec![HttpBinding {
+                method: "GET".to_string(),
+                path: "/v1/shelves/{shelf}".to_string(),
+                body: None,
+            }],
+        };
+        let args = HashMap::from([
+            ("shelf".to_string(), serde_json::json!(7)),
+            ("view".to_string(), serde_json::json!("FULL")),
+        ]);
+
+        let adapter = GrpcAdapter::new()
+            .with_connection_config(connection.clone())
+            .with_executor(failing("Failed to dial target host \"localhost:50051\""));
+        let result = adapter
+            .call_method("localhost:50051", &method, args.clone())
+            .await
+            .unwrap();
+        assert_eq!(result["name"], "shelves/7");
+        shelf.assert_async().await;
+
+        // A server that answers with a status is not bypassed
+        let adapter = GrpcAdapter::new()
+            .with_connection_config(connection)
+            .with_executor(failing(
+                "ERROR:\n  Code: NotFound\n  Message: no such shelf",
+            ));
+        let err = adapter
+            .call_method("localhost:50051", &method, args)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("NOT_FOUND"), "{}", err);
+    }
+
     #[tokio::test]
     async fn test_invoke_unary_failure_without_stderr() {
         let mock_executor = Arc::new(MockGrpcurlExecutor {
@@ -1440,6 +2191,7 @@ mod tests {
             is_server_streaming: false,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let err = adapter
@@ -1471,6 +2223,7 @@ mod tests {
             is_server_streaming: false,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let result = adapter
@@ -1497,6 +2250,7 @@ mod tests {
             is_server_streaming: true,
             is_client_streaming: false,
             description: None,
+            http: Vec::new(),
         };
 
         let err = adapter
@@ -1522,6 +2276,7 @@ mod tests {
             is_server_streaming: false,
             is_client_streaming: true,
             description: None,
+            http: Vec::new(),
         };
 
         let err = adapter
@@ -1547,6 +2302,7 @@ mod tests {
             is_server_streaming: true,
             is_client_streaming: true,
             description: None,
+            http: Vec::new(),
         };
 
         let err = adapter
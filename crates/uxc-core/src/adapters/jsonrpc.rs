@@ -3,13 +3,17 @@
 //! This adapter intentionally prioritizes discoverable JSON-RPC services:
 //! - `rpc.discover` (OpenRPC service discovery)
 //! - static OpenRPC documents (`openrpc.json`)
+//!
+//! Requests use JSON-RPC 2.0 unless 1.0 is requested explicitly or a server
+//! answers in the 1.0 format (no `jsonrpc` member), after which the adapter
+//! keeps speaking 1.0 to it.
 
 use super::{
-    Adapter, ExecutionMetadata, ExecutionResult, Operation, OperationDetail, Parameter,
-    ProtocolType,
+    probe, Adapter, Capabilities, CapabilitySet, ExecutionMetadata, ExecutionResult, Operation,
+    OperationDetail, Parameter, ProtocolType, RequestPlan,
 };
-use crate::auth::Profile;
-use crate::error::UxcError;
+use crate::auth::{AuthType, Profile};
+use crate::error::{ErrorDetail, UxcError};
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 use serde_json::{json, Map, Value};
@@ -18,6 +22,41 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, info};
 
+/// JSON-RPC wire format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonRpcVersion {
+    /// JSON-RPC 1.0: no `jsonrpc` member, positional params, `result`/`error` both present
+    V1,
+    /// JSON-RPC 2.0
+    V2,
+}
+
+impl JsonRpcVersion {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "1" | "1.0" => Some(Self::V1),
+            "2" | "2.0" => Some(Self::V2),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V1 => "1.0",
+            Self::V2 => "2.0",
+        }
+    }
+
+    /// Version a response was encoded with
+    fn of_response(body: &Map<String, Value>) -> Self {
+        if body.contains_key("jsonrpc") {
+            Self::V2
+        } else {
+            Self::V1
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ResolvedOpenRpc {
     rpc_url: String,
@@ -30,6 +69,10 @@ pub struct JsonRpcAdapter {
     auth_profile: Option<Profile>,
     discovered: Arc<RwLock<HashMap<String, ResolvedOpenRpc>>>,
     next_id: Arc<Mutex<i64>>,
+    /// Version forced with `--jsonrpc-version`; `None` auto-detects
+    version: Option<JsonRpcVersion>,
+    /// Version seen in the server's responses
+    detected_version: Arc<RwLock<Option<JsonRpcVersion>>>,
 }
 
 impl JsonRpcAdapter {
@@ -38,11 +81,158 @@ impl JsonRpcAdapter {
 
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: crate::http::client(),
             cache: None,
             auth_profile: None,
             discovered: Arc::new(RwLock::new(HashMap::new())),
             next_id: Arc::new(Mutex::new(1)),
+            version: None,
+            detected_version: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn with_version(mut self, version: Option<JsonRpcVersion>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Version to encode the next request with
+    async fn request_version(&self) -> JsonRpcVersion {
+        match self.version {
+            Some(version) => version,
+            None => self
+                .detected_version
+                .read()
+                .await
+                .unwrap_or(JsonRpcVersion::V2),
+        }
+    }
+
+    /// Remember the version a server answered with when auto-detecting
+    async fn observe_version(&self, body: &Map<String, Value>) {
+        if self.version.is_none() {
+            let version = JsonRpcVersion::of_response(body);
+            let mut detected = self.detected_version.write().await;
+            if *detected != Some(version) {
+                debug!("JSON-RPC server speaks version {}", version.as_str());
+                *detected = Some(version);
+            }
+        }
+    }
+
+    fn encode_request(
+        version: JsonRpcVersion,
+        id: i64,
+        method: &str,
+        params: Option<Value>,
+    ) -> Value {
+        let mut request = Map::new();
+        if version == JsonRpcVersion::V2 {
+            request.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+        }
+        request.insert(
+            "id".to_string(),
+            Value::Number(serde_json::Number::from(id)),
+        );
+        request.insert("method".to_string(), Value::String(method.to_string()));
+        match (version, params) {
+            (_, Some(params)) => {
+                request.insert("params".to_string(), params);
+            }
+            // 1.0 requires params, always as an array
+            (JsonRpcVersion::V1, None) => {
+                request.insert("params".to_string(), Value::Array(Vec::new()));
+            }
+            (JsonRpcVersion::V2, None) => {}
+        }
+        Value::Object(request)
+    }
+
+    /// Extract the result from a response, or turn its error into an `Err`
+    fn decode_response(body: &Map<String, Value>) -> Result<Value> {
+        match JsonRpcVersion::of_response(body) {
+            JsonRpcVersion::V2 => {
+                if let Some(err) = body.get("error").and_then(|v| v.as_object()) {
+                    return Err(Self::error_from_object(err));
+                }
+                body.get("result").cloned().ok_or_else(|| {
+                    anyhow::anyhow!("Invalid JSON-RPC response: missing result field")
+                })
+            }
+            // 1.0 sends both members, with `error: null` on success; the
+            // error itself may be any value
+            JsonRpcVersion::V1 => match body.get("error") {
+                None | Some(Value::Null) => body.get("result").cloned().ok_or_else(|| {
+                    anyhow::anyhow!("Invalid JSON-RPC response: missing result field")
+                }),
+                Some(Value::Object(err)) if err.contains_key("message") => {
+                    Err(Self::error_from_object(err))
+                }
+                Some(Value::String(message)) => Err(UxcError::call_failed(
+                    format!("JSON-RPC error: {}", message),
+                    ErrorDetail::JsonRpc {
+                        code: None,
+                        message: message.clone(),
+                        data: None,
+                    },
+                )
+                .into()),
+                Some(other) => Err(UxcError::call_failed(
+                    format!("JSON-RPC error: {}", other),
+                    ErrorDetail::JsonRpc {
+                        code: None,
+                        message: other.to_string(),
+                        data: Some(other.clone()),
+                    },
+                )
+                .into()),
+            },
+        }
+    }
+
+    fn error_from_object(err: &Map<String, Value>) -> anyhow::Error {
+        let number = err.get("code").and_then(|v| v.as_i64());
+        let code = number
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let message = err
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown JSON-RPC error");
+        let data = err.get("data").cloned().unwrap_or(Value::Null);
+
+        let text = if data.is_null() {
+            format!("JSON-RPC error {}: {}", code, message)
+        } else {
+            format!("JSON-RPC error {}: {} (data: {})", code, message, data)
+        };
+        UxcError::call_failed(
+            text,
+            ErrorDetail::JsonRpc {
+                code: number,
+                message: message.to_string(),
+                data: (!data.is_null()).then_some(data),
+            },
+        )
+        .into()
+    }
+
+    /// 1.0 only supports positional params, so named arguments are reordered
+    /// by the OpenRPC parameter list, or sent as a single object otherwise
+    fn v1_params(
+        method: &Value,
+        args: &HashMap<String, Value>,
+        params: Option<Value>,
+    ) -> Result<Option<Value>> {
+        match params {
+            Some(Value::Object(object)) => {
+                let ordered_specs = Self::ordered_parameter_specs(method);
+                match Self::build_positional_params(&ordered_specs, args)? {
+                    Some(positional) => Ok(Some(positional)),
+                    None => Ok(Some(Value::Array(vec![Value::Object(object)]))),
+                }
+            }
+            params => Ok(params),
         }
     }
 
@@ -132,6 +322,7 @@ impl JsonRpcAdapter {
                             param_type,
                             required,
                             description,
+                            location: None,
                         })
                     })
                     .collect::<Vec<_>>()
@@ -169,6 +360,7 @@ impl JsonRpcAdapter {
             description: Self::method_description(method),
             parameters: Self::parse_parameters(method),
             return_type: Self::parse_return_type(method),
+            tags: Vec::new(),
         })
     }
 
@@ -405,25 +597,25 @@ impl JsonRpcAdapter {
     }
 
     async fn discover_via_rpc_discover(&self, url: &str) -> Result<Option<ResolvedOpenRpc>> {
-        let request = json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "rpc.discover",
-            "params": []
-        });
+        let request = Self::encode_request(
+            self.request_version().await,
+            1,
+            "rpc.discover",
+            Some(json!([])),
+        );
 
         let mut req = self
             .client
             .post(url)
-            .timeout(std::time::Duration::from_secs(3))
+            .timeout(probe::timeout(std::time::Duration::from_secs(3)))
             .header("Content-Type", "application/json")
             .header("Accept", "application/json");
 
         if let Some(profile) = &self.auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+            req = profile.apply_to_request(req)?;
         }
 
-        let response = match req.json(&request).send().await {
+        let response = match crate::http::send(req.json(&request)).await {
             Ok(response) => response,
             Err(_) => return Ok(None),
         };
@@ -437,17 +629,21 @@ impl JsonRpcAdapter {
             Err(_) => return Ok(None),
         };
 
-        let Some(result) = body.get("result") else {
+        let Some(body) = body.as_object() else {
+            return Ok(None);
+        };
+        let Ok(result) = Self::decode_response(body) else {
             return Ok(None);
         };
 
-        if body.get("error").is_some() || !Self::is_openrpc_document(result) {
+        if !Self::is_openrpc_document(&result) {
             return Ok(None);
         }
+        self.observe_version(body).await;
 
         Ok(Some(ResolvedOpenRpc {
             rpc_url: url.to_string(),
-            schema: result.clone(),
+            schema: result,
         }))
     }
 
@@ -456,14 +652,14 @@ impl JsonRpcAdapter {
             let mut req = self
                 .client
                 .get(&schema_url)
-                .timeout(std::time::Duration::from_secs(3))
+                .timeout(probe::timeout(std::time::Duration::from_secs(3)))
                 .header("Accept", "application/json");
 
             if let Some(profile) = &self.auth_profile {
-                req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+                req = profile.apply_to_request(req)?;
             }
 
-            let response = match req.send().await {
+            let response = match crate::http::send(req).await {
                 Ok(response) => response,
                 Err(_) => continue,
             };
@@ -493,6 +689,7 @@ impl JsonRpcAdapter {
 
     async fn discover_openrpc(&self, url: &str) -> Result<Option<ResolvedOpenRpc>> {
         if !Self::is_http_url(url) {
+            probe::evidence("not an HTTP URL");
             return Ok(None);
         }
 
@@ -512,11 +709,13 @@ impl JsonRpcAdapter {
         };
 
         if let Some(found) = discovered {
+            probe::evidence(format!("OpenRPC document for {}", found.rpc_url));
             let mut cache = self.discovered.write().await;
             cache.insert(normalized, found.clone());
             return Ok(Some(found));
         }
 
+        probe::evidence("no OpenRPC document from rpc.discover or the schema paths");
         Ok(None)
     }
 
@@ -544,23 +743,26 @@ impl JsonRpcAdapter {
         id
     }
 
-    async fn execute_jsonrpc(
+    /// Params of `operation` with `args`, shaped as its method declares
+    async fn operation_params(
         &self,
-        rpc_url: &str,
+        url: &str,
         operation: &str,
-        params: Option<Value>,
-    ) -> Result<Value> {
-        let mut request = Map::new();
-        request.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
-        request.insert(
-            "id".to_string(),
-            Value::Number(serde_json::Number::from(self.next_request_id().await)),
-        );
-        request.insert("method".to_string(), Value::String(operation.to_string()));
-        if let Some(params) = params {
-            request.insert("params".to_string(), params);
+        args: &HashMap<String, Value>,
+    ) -> Result<Option<Value>> {
+        let schema = self.fetch_schema(url).await?;
+        let method = Self::find_method(&schema, operation)
+            .ok_or_else(|| UxcError::OperationNotFound(operation.to_string()))?;
+
+        let mut params = Self::build_params(method, args)?;
+        if self.request_version().await == JsonRpcVersion::V1 {
+            params = Self::v1_params(method, args, params)?;
         }
+        Ok(params)
+    }
 
+    /// The POST request carrying `request` to `rpc_url`
+    fn jsonrpc_request(&self, rpc_url: &str, request: &Value) -> Result<reqwest::RequestBuilder> {
         let mut req = self
             .client
             .post(rpc_url)
@@ -568,24 +770,42 @@ impl JsonRpcAdapter {
             .header("Accept", "application/json");
 
         if let Some(profile) = &self.auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+            req = profile.apply_to_request(req)?;
         }
 
-        let response = req
-            .json(&request)
-            .send()
+        Ok(req.json(request))
+    }
+
+    async fn execute_jsonrpc(
+        &self,
+        rpc_url: &str,
+        operation: &str,
+        params: Option<Value>,
+    ) -> Result<Value> {
+        let request = Self::encode_request(
+            self.request_version().await,
+            self.next_request_id().await,
+            operation,
+            params,
+        );
+
+        let response = crate::http::send(self.jsonrpc_request(rpc_url, &request)?)
             .await
             .context("Failed to send JSON-RPC request")?;
 
         let status = response.status();
+        let retry_after = crate::http::retry_after(response.headers());
         let body_text = response.text().await.unwrap_or_default();
 
         if !status.is_success() {
-            bail!(
-                "JSON-RPC server returned HTTP error: {} - {}",
-                status,
-                body_text
-            );
+            return Err(UxcError::call_failed(
+                format!(
+                    "JSON-RPC server returned HTTP error: {} - {}",
+                    status, body_text
+                ),
+                ErrorDetail::http(status.as_u16(), &body_text).with_retry_after(retry_after),
+            )
+            .into());
         }
 
         let body: Value = serde_json::from_str(&body_text)
@@ -599,27 +819,8 @@ impl JsonRpcAdapter {
             bail!("Invalid JSON-RPC response: expected object");
         };
 
-        if let Some(err) = obj.get("error").and_then(|v| v.as_object()) {
-            let code = err
-                .get("code")
-                .and_then(|v| v.as_i64())
-                .map(|n| n.to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-            let message = err
-                .get("message")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown JSON-RPC error");
-            let data = err.get("data").cloned().unwrap_or(Value::Null);
-
-            if data.is_null() {
-                bail!("JSON-RPC error {}: {}", code, message);
-            }
-            bail!("JSON-RPC error {}: {} (data: {})", code, message, data);
-        }
-
-        obj.get("result")
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Invalid JSON-RPC response: missing result field"))
+        self.observe_version(obj).await;
+        Self::decode_response(obj)
     }
 }
 
@@ -629,6 +830,29 @@ impl Default for JsonRpcAdapter {
     }
 }
 
+impl Capabilities for JsonRpcAdapter {
+    const PROTOCOL: ProtocolType = ProtocolType::JsonRpc;
+
+    fn capabilities() -> CapabilitySet {
+        CapabilitySet {
+            streaming: false,
+            batch: false,
+            validation: true,
+            auth_types: vec![
+                AuthType::Bearer,
+                AuthType::ApiKey,
+                AuthType::Basic,
+                AuthType::Session,
+                AuthType::OAuth,
+            ],
+            schema_export: true,
+            schema_cache: true,
+            raw_body: false,
+            notes: vec!["Batch responses are rejected".to_string()],
+        }
+    }
+}
+
 #[async_trait]
 impl Adapter for JsonRpcAdapter {
     fn protocol_type(&self) -> ProtocolType {
@@ -649,8 +873,18 @@ impl Adapter for JsonRpcAdapter {
             }
         }
 
+        if crate::cache::schemas_from_store(self.cache.as_ref()) {
+            return crate::cache::cached_schema(
+                self.cache.as_ref(),
+                url,
+                Self::is_openrpc_document,
+            );
+        }
+
         if let Some(cache) = &self.cache {
-            match cache.get(url)? {
+            let cached = cache.get(url)?;
+            crate::cache::record_lookup(ProtocolType::JsonRpc, url, &cached);
+            match cached {
                 crate::cache::CacheResult::Hit(schema) => {
                     debug!("JSON-RPC cache hit for: {}", url);
                     return Ok(schema);
@@ -707,6 +941,10 @@ impl Adapter for JsonRpcAdapter {
             parameters: Self::parse_parameters(method),
             return_type: Self::parse_return_type(method),
             input_schema: Some(Self::build_operation_input_schema(method)),
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
         })
     }
 
@@ -717,11 +955,7 @@ impl Adapter for JsonRpcAdapter {
         args: HashMap<String, Value>,
     ) -> Result<ExecutionResult> {
         let start = std::time::Instant::now();
-        let schema = self.fetch_schema(url).await?;
-        let method = Self::find_method(&schema, operation)
-            .ok_or_else(|| UxcError::OperationNotFound(operation.to_string()))?;
-
-        let params = Self::build_params(method, &args)?;
+        let params = self.operation_params(url, operation, &args).await?;
         let rpc_url = self.resolve_rpc_url(url).await?;
         let data = self.execute_jsonrpc(&rpc_url, operation, params).await?;
 
@@ -730,9 +964,25 @@ impl Adapter for JsonRpcAdapter {
             metadata: ExecutionMetadata {
                 duration_ms: start.elapsed().as_millis() as u64,
                 operation: operation.to_string(),
+                response_headers: Vec::new(),
+                status: None,
             },
         })
     }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        let params = self.operation_params(url, operation, &args).await?;
+        let rpc_url = self.resolve_rpc_url(url).await?;
+        // The id the call would use, without taking it
+        let id = *self.next_id.lock().await;
+        let request = Self::encode_request(self.request_version().await, id, operation, params);
+        RequestPlan::http(self.jsonrpc_request(&rpc_url, &request)?)
+    }
 }
 
 #[cfg(test)]
@@ -793,6 +1043,53 @@ mod tests {
         assert_eq!(params, Some(json!({"minuend": 42, "subtrahend": 23})));
     }
 
+    #[test]
+    fn v1_requests_omit_jsonrpc_and_always_send_params() {
+        let request = JsonRpcAdapter::encode_request(JsonRpcVersion::V1, 7, "ping", None);
+        assert_eq!(request, json!({"id": 7, "method": "ping", "params": []}));
+
+        let request = JsonRpcAdapter::encode_request(JsonRpcVersion::V2, 7, "ping", None);
+        assert_eq!(
+            request,
+            json!({"jsonrpc": "2.0", "id": 7, "method": "ping"})
+        );
+    }
+
+    #[test]
+    fn v1_params_reorders_named_arguments() {
+        let schema = openrpc_schema_with_structure("by-name");
+        let method = JsonRpcAdapter::find_method(&schema, "subtract").unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("minuend".to_string(), json!(42));
+        args.insert("subtrahend".to_string(), json!(23));
+
+        let params = JsonRpcAdapter::build_params(method, &args).unwrap();
+        let params = JsonRpcAdapter::v1_params(method, &args, params).unwrap();
+        assert_eq!(params, Some(json!([42, 23])));
+    }
+
+    #[test]
+    fn decode_v1_responses() {
+        let ok = json!({"id": 1, "result": 19, "error": null});
+        assert_eq!(
+            JsonRpcAdapter::decode_response(ok.as_object().unwrap()).unwrap(),
+            json!(19)
+        );
+
+        let err = json!({"id": 1, "result": null, "error": "no such method"});
+        let message = JsonRpcAdapter::decode_response(err.as_object().unwrap())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(message, "JSON-RPC error: no such method");
+
+        let err = json!({"id": 1, "result": null, "error": {"code": -32601, "message": "Method not found"}});
+        let message = JsonRpcAdapter::decode_response(err.as_object().unwrap())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(message, "JSON-RPC error -32601: Method not found");
+    }
+
     #[test]
     fn default_rpc_url_trims_openrpc_suffix() {
         assert_eq!(
@@ -25,15 +25,11 @@ impl McpStdioClient {
         args: &[String],
         executor: Arc<dyn StdioProcessExecutor>,
     ) -> Result<Self> {
-        let mut transport = McpStdioTransport::connect_with_executor(command, args, executor).await?;
+        let mut transport =
+            McpStdioTransport::connect_with_executor(command, args, executor).await?;
 
         // Initialize the session
-        let client_info = ClientInfo {
-            name: env!("CARGO_PKG_NAME").to_string(),
-            version: env!("CARGO_PKG_VERSION").to_string(),
-        };
-
-        let init_result = transport.initialize(client_info).await?;
+        let init_result = transport.initialize(ClientInfo::uxc()).await?;
         tracing::info!(
             "Connected to MCP server: {} v{}",
             init_result
@@ -81,33 +77,39 @@ impl McpStdioClient {
             .is_some()
     }
 
-    /// List available tools
+    /// List available tools, following `nextCursor` across pages
     pub async fn list_tools(&mut self) -> Result<Vec<Tool>> {
         if !self.supports_tools() {
             bail!("Server does not support tools");
         }
 
-        let result = self
-            .transport
-            .send_request("tools/list", None)
-            .await
-            .context("Failed to list tools")?;
-
-        // Parse the response - tools are in result.tools
-        let tools_value = result
-            .get("tools")
-            .context("Response missing 'tools' field")?
-            .as_array()
-            .context("'tools' is not an array")?;
-
+        // Follow nextCursor until the last page
         let mut tools = Vec::new();
-        for tool_value in tools_value {
-            let tool: Tool =
-                serde_json::from_value(tool_value.clone()).context("Failed to parse tool")?;
-            tools.push(tool);
+        let mut cursor = None;
+        let mut seen = Vec::new();
+        loop {
+            let result = self
+                .transport
+                .send_request("tools/list", tools_list_params(cursor.as_deref()))
+                .await
+                .context("Failed to list tools")?;
+
+            if result.get("tools").and_then(JsonValue::as_array).is_none() {
+                bail!("Response missing 'tools' array");
+            }
+            let page: ToolsListResponse =
+                serde_json::from_value(result).context("Failed to parse tools/list response")?;
+            cursor = page.next_page(&mut seen)?;
+            tools.extend(page.tools);
+            if cursor.is_none() {
+                return Ok(tools);
+            }
         }
+    }
 
-        Ok(tools)
+    /// Whether the server announced a tool list change since the last check
+    pub fn take_tools_list_changed(&self) -> bool {
+        self.transport.take_tools_list_changed()
     }
 
     /// Call a tool
@@ -260,10 +262,9 @@ mod tests {
             done
         "#;
 
-        let client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         assert!(!client.supports_tools());
     }
@@ -276,10 +277,9 @@ mod tests {
             while read line; do sleep 1; done
         "#;
 
-        let client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         assert!(client.supports_tools());
     }
@@ -292,10 +292,9 @@ mod tests {
             while read line; do sleep 1; done
         "#;
 
-        let client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         assert!(client.supports_resources());
     }
@@ -308,10 +307,9 @@ mod tests {
             while read line; do sleep 1; done
         "#;
 
-        let client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         assert!(client.supports_prompts());
     }
@@ -324,14 +322,16 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let result = client.list_tools().await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("does not support tools"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not support tools"));
     }
 
     #[tokio::test]
@@ -343,10 +343,9 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"test_tool","description":"A test tool","inputSchema":{"type":"object"}}]}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let tools = client.list_tools().await.unwrap();
         assert_eq!(tools.len(), 1);
@@ -354,6 +353,32 @@ mod tests {
         assert_eq!(tools[0].description, "A test tool");
     }
 
+    #[tokio::test]
+    async fn list_tools_follows_next_cursor_and_flags_list_changed() {
+        let script = r#"
+            read line
+            echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{"listChanged":true}},"serverInfo":{"name":"test","version":"1.0"}}}'
+            read line
+            read line
+            echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"first","description":"Page one"}],"nextCursor":"page-2"}}'
+            read line
+            case "$line" in *'"cursor":"page-2"'*) ;; *) exit 1 ;; esac
+            echo '{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}'
+            echo '{"jsonrpc":"2.0","id":3,"result":{"tools":[{"name":"second","description":"Page two"}]}}'
+            while read line; do sleep 1; done
+        "#;
+
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
+
+        let tools = client.list_tools().await.unwrap();
+        let names: Vec<_> = tools.iter().map(|tool| tool.name.as_str()).collect();
+        assert_eq!(names, ["first", "second"]);
+        assert!(client.take_tools_list_changed());
+        assert!(!client.take_tools_list_changed());
+    }
+
     #[tokio::test]
     async fn call_tool_executes_tool_with_arguments() {
         let script = r#"
@@ -363,10 +388,9 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{"content":[{"type":"text","text":"Tool executed successfully"}]}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let args = serde_json::json!({"param1": "value1"});
         let result = client.call_tool("test_tool", Some(args)).await.unwrap();
@@ -385,14 +409,16 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{},"serverInfo":{"name":"test","version":"1.0"}}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let result = client.call_tool("test", None).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("does not support tools"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not support tools"));
     }
 
     #[tokio::test]
@@ -402,14 +428,16 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"test","version":"1.0"}}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let result = client.list_resources().await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("does not support resources"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not support resources"));
     }
 
     #[tokio::test]
@@ -421,10 +449,9 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{"resources":[{"name":"test_resource","uri":"test://resource","description":"A test resource"}]}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let resources = client.list_resources().await.unwrap();
         assert_eq!(resources.len(), 1);
@@ -441,22 +468,27 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{"contents":[{"uri":"test://resource"}]}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         // The response structure has a "contents" array, so we need to handle that
         let result = client
             .transport
-            .send_request("resources/read", Some(serde_json::json!({"uri": "test://resource"})))
+            .send_request(
+                "resources/read",
+                Some(serde_json::json!({"uri": "test://resource"})),
+            )
             .await
             .unwrap();
 
         // Parse the contents array
         let contents_array = result.get("contents").and_then(|v| v.as_array()).unwrap();
         let first_content = &contents_array[0];
-        assert_eq!(first_content.get("uri").unwrap().as_str().unwrap(), "test://resource");
+        assert_eq!(
+            first_content.get("uri").unwrap().as_str().unwrap(),
+            "test://resource"
+        );
     }
 
     #[tokio::test]
@@ -466,14 +498,16 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"test","version":"1.0"}}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let result = client.list_prompts().await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("does not support prompts"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not support prompts"));
     }
 
     #[tokio::test]
@@ -485,10 +519,9 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{"prompts":[{"name":"test_prompt","description":"A test prompt"}]}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let prompts = client.list_prompts().await.unwrap();
         assert_eq!(prompts.len(), 1);
@@ -504,10 +537,9 @@ mod tests {
             echo '{"jsonrpc":"2.0","id":2,"result":{"description":"Test prompt","messages":[{"role":"user","content":"Test content"}]}}'
         "#;
 
-        let mut client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let mut client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         let result = client.get_prompt("test_prompt", None).await.unwrap();
         assert_eq!(result.description, "Test prompt");
@@ -524,10 +556,9 @@ mod tests {
             while read line; do sleep 1; done
         "#;
 
-        let client =
-            McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let client = McpStdioClient::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         assert!(client.supports_tools());
         assert!(client.supports_resources());
@@ -3,13 +3,18 @@
 #![allow(dead_code)]
 
 use super::types::*;
+use crate::adapters::RequestPlan;
 use crate::auth::Profile;
 use anyhow::{bail, Context, Result};
 use reqwest::Client;
 use serde_json::Value as JsonValue;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// How long a request waits for the server
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// MCP HTTP transport client
 #[derive(Debug)]
 pub struct McpHttpTransport {
@@ -21,6 +26,8 @@ pub struct McpHttpTransport {
     next_id: Arc<Mutex<i64>>,
     /// Authentication profile
     auth_profile: Option<Profile>,
+    /// Set when a response stream carries a tools listChanged notification
+    tools_list_changed: Arc<AtomicBool>,
 }
 
 impl McpHttpTransport {
@@ -42,16 +49,12 @@ impl McpHttpTransport {
             );
         }
 
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
-
         Ok(Self {
-            client,
+            client: crate::http::client(),
             server_url: url,
             next_id: Arc::new(Mutex::new(1i64)),
             auth_profile,
+            tools_list_changed: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -65,34 +68,13 @@ impl McpHttpTransport {
             id
         };
 
-        // Build JSON-RPC request
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            method: method.to_string(),
-            params,
-            id: RequestId::Number(id),
-        };
-
         tracing::debug!(
             "Sending MCP HTTP request: {} to {}",
             method,
             self.server_url
         );
 
-        // Build request with authentication if profile is set
-        let mut req = self
-            .client
-            .post(&self.server_url)
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json, text/event-stream");
-
-        if let Some(profile) = &self.auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
-        }
-
-        let response = req
-            .json(&request)
-            .send()
+        let response = crate::http::send(self.request(id, method, params)?)
             .await
             .context("Failed to send HTTP request to MCP server")?;
 
@@ -112,6 +94,11 @@ impl McpHttpTransport {
             bail!("MCP server returned HTTP error: {} - {}", status, body);
         }
 
+        if Self::sse_notifies_tools_list_changed(content_type.as_deref(), &body) {
+            tracing::debug!("MCP server tool list changed");
+            self.tools_list_changed.store(true, Ordering::Relaxed);
+        }
+
         // Parse JSON or streamable HTTP (SSE) response
         let json_response = Self::parse_jsonrpc_response(content_type.as_deref(), &body)
             .context("Failed to parse MCP server response")?;
@@ -131,6 +118,45 @@ impl McpHttpTransport {
             .context("MCP server response missing result field")
     }
 
+    /// The POST request carrying JSON-RPC request `id`
+    fn request(
+        &self,
+        id: i64,
+        method: &str,
+        params: Option<JsonValue>,
+    ) -> Result<reqwest::RequestBuilder> {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id: RequestId::Number(id),
+        };
+
+        // Build request with authentication if profile is set
+        let mut req = self
+            .client
+            .post(&self.server_url)
+            .timeout(REQUEST_TIMEOUT)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream");
+
+        if let Some(profile) = &self.auth_profile {
+            req = profile.apply_to_request(req)?;
+        }
+
+        Ok(req.json(&request))
+    }
+
+    /// The request the next [`Self::send_request`] would send
+    pub async fn plan_request(
+        &self,
+        method: &str,
+        params: Option<JsonValue>,
+    ) -> Result<RequestPlan> {
+        let id = *self.next_id.lock().await;
+        RequestPlan::http(self.request(id, method, params)?)
+    }
+
     fn parse_jsonrpc_response(content_type: Option<&str>, body: &str) -> Result<JsonRpcResponse> {
         let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
 
@@ -143,6 +169,26 @@ impl McpHttpTransport {
             .context("Response is neither JSON-RPC JSON nor JSON-RPC SSE")
     }
 
+    /// Whether a streamed response carries a tools listChanged notification
+    /// alongside the result
+    fn sse_notifies_tools_list_changed(content_type: Option<&str>, body: &str) -> bool {
+        let streamed = content_type
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .contains("text/event-stream");
+        streamed
+            && body
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("data:"))
+                .filter_map(|data| serde_json::from_str::<JsonValue>(data.trim()).ok())
+                .any(|message| is_tools_list_changed(&message))
+    }
+
+    /// Whether a tools listChanged notification arrived since the last check
+    pub fn take_tools_list_changed(&self) -> bool {
+        self.tools_list_changed.swap(false, Ordering::Relaxed)
+    }
+
     fn parse_sse_response(body: &str) -> Result<JsonRpcResponse> {
         for line in body.lines() {
             let trimmed = line.trim();
@@ -163,11 +209,6 @@ impl McpHttpTransport {
 
     /// Lightweight MCP HTTP probe used for endpoint discovery.
     pub async fn probe_initialize(url: &str, auth_profile: Option<Profile>) -> Result<bool> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(3))
-            .build()
-            .context("Failed to create MCP probe HTTP client")?;
-
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             method: "initialize".to_string(),
@@ -182,16 +223,19 @@ impl McpHttpTransport {
             id: RequestId::Number(1),
         };
 
-        let mut req = client
+        let mut req = crate::http::client()
             .post(url)
+            .timeout(crate::adapters::probe::timeout(
+                std::time::Duration::from_secs(3),
+            ))
             .header("Content-Type", "application/json")
             .header("Accept", "application/json, text/event-stream");
 
         if let Some(profile) = &auth_profile {
-            req = crate::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+            req = profile.apply_to_request(req)?;
         }
 
-        let response = match req.json(&request).send().await {
+        let response = match crate::http::send(req.json(&request)).await {
             Ok(response) => response,
             Err(_) => return Ok(false),
         };
@@ -245,14 +289,24 @@ impl McpHttpTransport {
         serde_json::from_value(result).context("Failed to parse initialize result")
     }
 
-    /// List available tools
+    /// List available tools, following `nextCursor` across pages
     pub async fn list_tools(&self) -> Result<Vec<Tool>> {
-        let result = self.send_request("tools/list", None).await?;
-
-        let response: ToolsListResponse =
-            serde_json::from_value(result).context("Failed to parse tools/list response")?;
-
-        Ok(response.tools)
+        let mut tools = Vec::new();
+        let mut cursor = None;
+        let mut seen = Vec::new();
+        loop {
+            let result = self
+                .send_request("tools/list", tools_list_params(cursor.as_deref()))
+                .await?;
+
+            let page: ToolsListResponse =
+                serde_json::from_value(result).context("Failed to parse tools/list response")?;
+            cursor = page.next_page(&mut seen)?;
+            tools.extend(page.tools);
+            if cursor.is_none() {
+                return Ok(tools);
+            }
+        }
     }
 
     /// Call a tool
@@ -261,16 +315,23 @@ impl McpHttpTransport {
         name: &str,
         arguments: Option<JsonValue>,
     ) -> Result<ToolCallResult> {
-        let params = serde_json::json!({
-            "name": name,
-            "arguments": arguments
-        });
-
-        let result = self.send_request("tools/call", Some(params)).await?;
+        let result = self
+            .send_request("tools/call", Some(call_tool_params(name, arguments)))
+            .await?;
 
         serde_json::from_value(result).context("Failed to parse tools/call result")
     }
 
+    /// The request [`Self::call_tool`] would send
+    pub async fn plan_call_tool(
+        &self,
+        name: &str,
+        arguments: Option<JsonValue>,
+    ) -> Result<RequestPlan> {
+        self.plan_request("tools/call", Some(call_tool_params(name, arguments)))
+            .await
+    }
+
     /// List available resources
     pub async fn list_resources(&self) -> Result<Vec<Resource>> {
         let result = self.send_request("resources/list", None).await?;
@@ -373,19 +434,14 @@ mod tests {
     #[test]
     fn with_auth_succeeds() {
         let profile = Profile::new("test-key".to_string(), AuthType::Bearer);
-        let transport = McpHttpTransport::with_auth(
-            "https://example.com/mcp".to_string(),
-            Some(profile),
-        );
+        let transport =
+            McpHttpTransport::with_auth("https://example.com/mcp".to_string(), Some(profile));
         assert!(transport.is_ok());
     }
 
     #[test]
     fn with_auth_none_succeeds() {
-        let transport = McpHttpTransport::with_auth(
-            "https://example.com/mcp".to_string(),
-            None,
-        );
+        let transport = McpHttpTransport::with_auth("https://example.com/mcp".to_string(), None);
         assert!(transport.is_ok());
     }
 
@@ -471,7 +527,10 @@ data: invalid json
 
         let result = McpHttpTransport::parse_jsonrpc_response(Some("text/event-stream"), sse);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("No JSON-RPC payload found"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("No JSON-RPC payload found"));
     }
 
     #[test]
@@ -510,8 +569,7 @@ data: invalid json
     fn parse_json_response_without_content_type() {
         let json = r#"{"jsonrpc":"2.0","id":1,"result":{"status":"ok"}}"#;
 
-        let response =
-            McpHttpTransport::parse_jsonrpc_response(None, json).unwrap();
+        let response = McpHttpTransport::parse_jsonrpc_response(None, json).unwrap();
         assert_eq!(response.jsonrpc, "2.0");
     }
 
@@ -530,10 +588,12 @@ data: invalid json
     fn parse_invalid_json_response_fails() {
         let invalid = "not json at all";
 
-        let result =
-            McpHttpTransport::parse_jsonrpc_response(Some("application/json"), invalid);
+        let result = McpHttpTransport::parse_jsonrpc_response(Some("application/json"), invalid);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("neither JSON-RPC JSON nor JSON-RPC SSE"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("neither JSON-RPC JSON nor JSON-RPC SSE"));
     }
 
     #[test]
@@ -569,14 +629,16 @@ data: invalid json
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let mock2 = server
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":2,"result":{}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -596,7 +658,8 @@ data: invalid json
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -613,7 +676,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(500)
             .with_body("Internal Server Error")
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -632,7 +696,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(404)
             .with_body("Not Found")
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -650,8 +715,11 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"Method not found"}}"#)
-            .create_async().await;
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"Method not found"}}"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -671,13 +739,17 @@ data: invalid json
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
         let result = transport.send_request("test", None).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("missing result field"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("missing result field"));
     }
 
     #[tokio::test]
@@ -689,7 +761,8 @@ data: invalid json
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body("invalid json{{{")
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -700,7 +773,8 @@ data: invalid json
     #[tokio::test]
     async fn network_failure_returns_error() {
         // Use an invalid URL to simulate network failure
-        let transport = McpHttpTransport::new("http://localhost:59999/nonexistent".to_string()).unwrap();
+        let transport =
+            McpHttpTransport::new("http://localhost:59999/nonexistent".to_string()).unwrap();
 
         let result = transport.send_request("test", None).await;
         assert!(result.is_err());
@@ -724,7 +798,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -737,8 +812,10 @@ data: invalid json
                         "version":"1.0.0"
                     }
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -777,15 +854,18 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "error":{
                     "code":-32600,
                     "message":"Invalid Request"
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -801,20 +881,26 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
                     "invalid":"data"
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
         let result = transport.initialize().await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Failed to parse initialize result"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Failed to parse initialize result"));
     }
 
     // ===== Tool Listing Tests =====
@@ -827,14 +913,17 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
                     "tools":[]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -852,7 +941,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -869,8 +959,10 @@ data: invalid json
                         }
                     ]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -882,6 +974,25 @@ data: invalid json
         assert_eq!(tools[1].name, "tool2");
     }
 
+    #[tokio::test]
+    async fn list_tools_rejects_repeated_cursor() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _mock = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[],"nextCursor":"again"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let transport = McpHttpTransport::new(server.url()).unwrap();
+
+        let err = transport.list_tools().await.unwrap_err();
+        assert!(err.to_string().contains("did not terminate"));
+    }
+
     #[tokio::test]
     async fn list_tools_with_sse_response_succeeds() {
         let mut server = mockito::Server::new_async().await;
@@ -912,7 +1023,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -923,8 +1035,10 @@ data: invalid json
                         }
                     ]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -942,7 +1056,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -953,8 +1068,10 @@ data: invalid json
                         }
                     ]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -971,15 +1088,18 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "error":{
                     "code":-32602,
                     "message":"Invalid params"
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -996,15 +1116,18 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
                     "content":[],
                     "isError":true
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -1024,7 +1147,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -1036,8 +1160,10 @@ data: invalid json
                         }
                     ]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -1056,15 +1182,18 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
                     "uri":"file:///test.txt",
                     "text":"Resource content"
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -1084,7 +1213,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -1095,8 +1225,10 @@ data: invalid json
                         }
                     ]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -1115,7 +1247,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -1127,8 +1260,10 @@ data: invalid json
                         }
                     ]
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -1148,7 +1283,8 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -1159,12 +1295,14 @@ data: invalid json
                         "version":"1.0"
                     }
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let result = McpHttpTransport::probe_initialize(&server.url(), None).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 
     #[tokio::test]
@@ -1175,25 +1313,29 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
                     "invalid":"data"
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let result = McpHttpTransport::probe_initialize(&server.url(), None).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), false);
+        assert!(!result.unwrap());
     }
 
     #[tokio::test]
     async fn probe_initialize_with_network_error_returns_false() {
-        let result = McpHttpTransport::probe_initialize("http://localhost:59999/nonexistent", None).await;
+        let result =
+            McpHttpTransport::probe_initialize("http://localhost:59999/nonexistent", None).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), false);
+        assert!(!result.unwrap());
     }
 
     #[tokio::test]
@@ -1204,11 +1346,12 @@ data: invalid json
             .mock("POST", "/")
             .with_status(500)
             .with_body("Internal Server Error")
-            .create_async().await;
+            .create_async()
+            .await;
 
         let result = McpHttpTransport::probe_initialize(&server.url(), None).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), false);
+        assert!(!result.unwrap());
     }
 
     #[tokio::test]
@@ -1219,19 +1362,22 @@ data: invalid json
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "error":{
                     "code":-32600,
                     "message":"Invalid Request"
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let result = McpHttpTransport::probe_initialize(&server.url(), None).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), false);
+        assert!(!result.unwrap());
     }
 
     #[tokio::test]
@@ -1248,7 +1394,7 @@ data: invalid json
 
         let result = McpHttpTransport::probe_initialize(&server.url(), None).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 
     // ===== Authentication Tests =====
@@ -1259,11 +1405,15 @@ data: invalid json
 
         let _mock = server
             .mock("POST", "/")
-            .match_header("authorization", mockito::Matcher::Regex("Bearer .*".to_string()))
+            .match_header(
+                "authorization",
+                mockito::Matcher::Regex("Bearer .*".to_string()),
+            )
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let profile = Profile::new("test-token".to_string(), AuthType::Bearer);
         let transport = McpHttpTransport::with_auth(server.url(), Some(profile)).unwrap();
@@ -1282,7 +1432,8 @@ data: invalid json
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let profile = Profile::new("test-key".to_string(), AuthType::ApiKey);
         let transport = McpHttpTransport::with_auth(server.url(), Some(profile)).unwrap();
@@ -1297,10 +1448,14 @@ data: invalid json
 
         let _mock = server
             .mock("POST", "/")
-            .match_header("authorization", mockito::Matcher::Regex("Bearer .*".to_string()))
+            .match_header(
+                "authorization",
+                mockito::Matcher::Regex("Bearer .*".to_string()),
+            )
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{
+            .with_body(
+                r#"{
                 "jsonrpc":"2.0",
                 "id":1,
                 "result":{
@@ -1308,13 +1463,15 @@ data: invalid json
                     "capabilities":{},
                     "serverInfo":{"name":"test","version":"1.0"}
                 }
-            }"#)
-            .create_async().await;
+            }"#,
+            )
+            .create_async()
+            .await;
 
         let profile = Profile::new("test-token".to_string(), AuthType::Bearer);
         let result = McpHttpTransport::probe_initialize(&server.url(), Some(profile)).await;
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), true);
+        assert!(result.unwrap());
     }
 
     // ===== Content Type Tests =====
@@ -1324,7 +1481,8 @@ data: invalid json
         let json = r#"{"jsonrpc":"2.0","id":1,"result":{}}"#;
 
         let response =
-            McpHttpTransport::parse_jsonrpc_response(Some("application/json; charset=utf-8"), json).unwrap();
+            McpHttpTransport::parse_jsonrpc_response(Some("application/json; charset=utf-8"), json)
+                .unwrap();
         assert_eq!(response.jsonrpc, "2.0");
     }
 
@@ -1334,7 +1492,8 @@ data: invalid json
 "#;
 
         let response =
-            McpHttpTransport::parse_jsonrpc_response(Some("text/event-stream; charset=utf-8"), sse).unwrap();
+            McpHttpTransport::parse_jsonrpc_response(Some("text/event-stream; charset=utf-8"), sse)
+                .unwrap();
         assert_eq!(response.jsonrpc, "2.0");
     }
 
@@ -1370,7 +1529,8 @@ data: {"jsonrpc":"2.0","id":1,"result":{}}
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"status":"ok"}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -1387,7 +1547,8 @@ data: {"jsonrpc":"2.0","id":1,"result":{}}
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"status":"ok"}}"#)
-            .create_async().await;
+            .create_async()
+            .await;
 
         let transport = McpHttpTransport::new(server.url()).unwrap();
 
@@ -7,25 +7,40 @@ pub mod http_transport;
 pub mod transport;
 pub mod types;
 
-use super::{Adapter, ExecutionResult, Operation, OperationDetail, ProtocolType};
-use crate::auth::Profile;
+use super::{
+    probe, Adapter, Capabilities, CapabilitySet, ExecutionResult, Operation, OperationDetail,
+    ProtocolType, RequestPlan,
+};
+use crate::auth::{AuthType, Profile};
+use crate::error::UxcError;
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 pub use client::McpStdioClient;
 pub use http_transport::McpHttpTransport;
-pub use transport::{DefaultStdioProcessExecutor, SpawnedProcess, StdioProcessExecutor};
-#[cfg(test)]
-pub use transport::MockStdioExecutor;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
+#[cfg(test)]
+pub use transport::MockStdioExecutor;
+pub use transport::{DefaultStdioProcessExecutor, SpawnedProcess, StdioProcessExecutor};
 
 pub struct McpAdapter {
     cache: Option<Arc<dyn crate::cache::Cache>>,
     auth_profile: Option<Profile>,
     discovered_http_endpoints: Arc<RwLock<HashMap<String, String>>>,
+    /// Open sessions, when kept between calls
+    sessions: Option<Arc<Sessions>>,
+}
+
+/// Sessions kept open between calls ([`McpAdapter::keep_sessions`])
+#[derive(Default)]
+struct Sessions {
+    /// Idle stdio servers, by command line
+    stdio: std::sync::Mutex<HashMap<String, McpStdioClient>>,
+    /// HTTP transports, by endpoint
+    http: std::sync::Mutex<HashMap<String, Arc<McpHttpTransport>>>,
 }
 
 impl McpAdapter {
@@ -34,9 +49,18 @@ impl McpAdapter {
             cache: None,
             auth_profile: None,
             discovered_http_endpoints: Arc::new(RwLock::new(HashMap::new())),
+            sessions: None,
         }
     }
 
+    /// Keep stdio servers running and HTTP transports open between calls
+    /// instead of starting a session per call, for long-lived processes such
+    /// as `uxc daemon`
+    pub fn keep_sessions(mut self) -> Self {
+        self.sessions = Some(Arc::default());
+        self
+    }
+
     pub fn with_cache(mut self, cache: Arc<dyn crate::cache::Cache>) -> Self {
         self.cache = Some(cache);
         self
@@ -47,6 +71,21 @@ impl McpAdapter {
         self
     }
 
+    /// Use `endpoint`, found by an earlier detection, as the MCP endpoint of
+    /// `url`
+    pub fn with_discovered_endpoint(self, url: &str, endpoint: String) -> Self {
+        if let Ok(mut discovered) = self.discovered_http_endpoints.try_write() {
+            discovered.insert(Self::normalize_http_url(url), endpoint);
+        }
+        self
+    }
+
+    /// MCP endpoint of the HTTP server at `url`, once it was discovered
+    pub async fn discovered_endpoint(&self, url: &str) -> Option<String> {
+        let discovered = self.discovered_http_endpoints.read().await;
+        discovered.get(&Self::normalize_http_url(url)).cloned()
+    }
+
     /// Check if a URL/command looks like an MCP stdio command
     pub fn is_stdio_command(url: &str) -> bool {
         // Check if it looks like a command (not a URL)
@@ -125,6 +164,11 @@ impl McpAdapter {
             }
         }
 
+        // Probing is a network round trip
+        if crate::cache::is_offline() {
+            return None;
+        }
+
         for candidate in Self::http_endpoint_candidates(url) {
             match McpHttpTransport::probe_initialize(&candidate, self.auth_profile.clone()).await {
                 Ok(true) => {
@@ -141,12 +185,172 @@ impl McpAdapter {
     }
 }
 
+impl McpAdapter {
+    /// Cache key of the aggregated tool list for an endpoint
+    pub fn tools_cache_key(url: &str) -> String {
+        format!("{}#mcp-tools", url)
+    }
+
+    /// All tools of a server, across `tools/list` pages, cached per endpoint
+    async fn tools(&self, url: &str) -> Result<Vec<types::Tool>> {
+        let key = Self::tools_cache_key(url);
+        let parse = |schema: Value| -> Result<Vec<types::Tool>> {
+            Ok(serde_json::from_value(schema["tools"].clone())?)
+        };
+
+        if crate::cache::schemas_from_store(self.cache.as_ref()) && !Self::is_stdio_command(url) {
+            return parse(crate::cache::cached_schema(
+                self.cache.as_ref(),
+                &key,
+                |schema| schema.get("tools").is_some_and(Value::is_array),
+            )?);
+        }
+        if let Some(cache) = &self.cache {
+            if let crate::cache::CacheResult::Hit(schema) = cache.get(&key)? {
+                match parse(schema) {
+                    Ok(tools) => {
+                        debug!("MCP tool list cache hit for: {}", url);
+                        return Ok(tools);
+                    }
+                    Err(e) => debug!("Ignoring unreadable MCP tool list cache: {}", e),
+                }
+            }
+        }
+
+        // A listChanged notification while paging means the pages may mix two
+        // versions of the list, so list once more
+        let tools = if Self::is_stdio_command(url) {
+            let mut client = self.stdio_client(url).await?;
+            let mut tools = client.list_tools().await?;
+            if client.take_tools_list_changed() {
+                tools = client.list_tools().await?;
+            }
+            self.release_stdio_client(url, client);
+            tools
+        } else {
+            let transport = self.http_transport(url).await?;
+            let tools = transport.list_tools().await?;
+            if transport.take_tools_list_changed() {
+                transport.list_tools().await?
+            } else {
+                tools
+            }
+        };
+
+        if let Some(cache) = &self.cache {
+            let schema = serde_json::json!({ "protocol": "MCP", "tools": tools });
+            if let Err(e) = cache.put(&key, &schema) {
+                debug!("Failed to cache MCP tool list: {}", e);
+            }
+        }
+        Ok(tools)
+    }
+
+    /// Drop the cached tool list after the server announced a change
+    fn invalidate_tools(&self, url: &str) {
+        info!("MCP tool list changed for {}, dropping cached list", url);
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.invalidate(&Self::tools_cache_key(url)) {
+                debug!("Failed to invalidate MCP tool list cache: {}", e);
+            }
+        }
+    }
+
+    /// A stdio session with the server of `url`: an idle kept one, or a new one
+    async fn stdio_client(&self, url: &str) -> Result<McpStdioClient> {
+        let kept = self
+            .sessions
+            .as_ref()
+            .and_then(|sessions| sessions.stdio.lock().unwrap().remove(url));
+        if let Some(client) = kept {
+            debug!("Reusing MCP stdio session for: {}", url);
+            return Ok(client);
+        }
+        let (cmd, args) = Self::parse_stdio_command(url)?;
+        McpStdioClient::connect(&cmd, &args).await
+    }
+
+    /// Keep a stdio session that served a call without error, when sessions
+    /// are kept; otherwise it ends here
+    fn release_stdio_client(&self, url: &str, client: McpStdioClient) {
+        if let Some(sessions) = &self.sessions {
+            sessions
+                .stdio
+                .lock()
+                .unwrap()
+                .insert(url.to_string(), client);
+        }
+    }
+
+    /// The HTTP transport for the MCP endpoint of `url`
+    async fn http_transport(&self, url: &str) -> Result<Arc<McpHttpTransport>> {
+        let endpoint = self.http_endpoint(url).await?;
+        let Some(sessions) = &self.sessions else {
+            return Ok(Arc::new(McpHttpTransport::with_auth(
+                endpoint,
+                self.auth_profile.clone(),
+            )?));
+        };
+        if let Some(transport) = sessions.http.lock().unwrap().get(&endpoint) {
+            return Ok(transport.clone());
+        }
+        let transport = Arc::new(McpHttpTransport::with_auth(
+            endpoint.clone(),
+            self.auth_profile.clone(),
+        )?);
+        sessions
+            .http
+            .lock()
+            .unwrap()
+            .insert(endpoint, transport.clone());
+        Ok(transport)
+    }
+
+    async fn http_endpoint(&self, url: &str) -> Result<String> {
+        if crate::cache::is_offline() {
+            return Err(crate::cache::offline_miss(format!(
+                "discovering the MCP endpoint for {} needs network access",
+                url
+            )));
+        }
+        self.resolve_http_endpoint(url)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Unable to discover MCP HTTP endpoint for {}", url))
+    }
+}
+
 impl Default for McpAdapter {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl Capabilities for McpAdapter {
+    const PROTOCOL: ProtocolType = ProtocolType::Mcp;
+
+    fn capabilities() -> CapabilitySet {
+        CapabilitySet {
+            streaming: true,
+            batch: false,
+            validation: false,
+            auth_types: vec![
+                AuthType::Bearer,
+                AuthType::ApiKey,
+                AuthType::Basic,
+                AuthType::Session,
+                AuthType::OAuth,
+            ],
+            schema_export: true,
+            schema_cache: true,
+            raw_body: false,
+            notes: vec![
+                "Auth applies to HTTP servers only; stdio servers get none".to_string(),
+                "Streaming means SSE responses on the HTTP transport".to_string(),
+            ],
+        }
+    }
+}
+
 #[async_trait]
 impl Adapter for McpAdapter {
     fn protocol_type(&self) -> ProtocolType {
@@ -156,20 +360,35 @@ impl Adapter for McpAdapter {
     async fn can_handle(&self, url: &str) -> Result<bool> {
         // First, check if it's a stdio command
         if Self::is_stdio_command(url) {
+            probe::evidence("stdio command");
             return Ok(true);
         }
 
         if Self::is_http_url(url) {
-            return Ok(self.resolve_http_endpoint(url).await.is_some());
+            let endpoint = self.resolve_http_endpoint(url).await;
+            probe::evidence(match &endpoint {
+                Some(endpoint) => format!("{} answered initialize", endpoint),
+                None => "no MCP endpoint answered initialize".to_string(),
+            });
+            return Ok(endpoint.is_some());
         }
 
+        probe::evidence("neither an HTTP URL nor a command");
         Ok(false)
     }
 
     async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        if crate::cache::schemas_from_store(self.cache.as_ref()) && !Self::is_stdio_command(url) {
+            return crate::cache::cached_schema(self.cache.as_ref(), url, |schema| {
+                schema.get("protocol").and_then(Value::as_str) == Some("MCP")
+            });
+        }
+
         // Try cache first if available
         if let Some(cache) = &self.cache {
-            match cache.get(url)? {
+            let cached = cache.get(url)?;
+            crate::cache::record_lookup(ProtocolType::Mcp, url, &cached);
+            match cached {
                 crate::cache::CacheResult::Hit(schema) => {
                     debug!("MCP cache hit for: {}", url);
                     return Ok(schema);
@@ -185,8 +404,8 @@ impl Adapter for McpAdapter {
 
         // If it's a stdio command, connect and get server info
         if Self::is_stdio_command(url) {
-            let (cmd, args) = Self::parse_stdio_command(url)?;
-            let client = McpStdioClient::connect(&cmd, &args).await?;
+            let (cmd, _) = Self::parse_stdio_command(url)?;
+            let client = self.stdio_client(url).await?;
 
             // Build schema from server capabilities
             let schema = serde_json::json!({
@@ -200,6 +419,7 @@ impl Adapter for McpAdapter {
                     "prompts": client.supports_prompts(),
                 }
             });
+            self.release_stdio_client(url, client);
 
             // Store in cache if available
             if let Some(cache) = &self.cache {
@@ -215,10 +435,7 @@ impl Adapter for McpAdapter {
 
         // For HTTP-based MCP, connect and get server info
         if Self::is_http_url(url) {
-            let endpoint = self.resolve_http_endpoint(url).await.ok_or_else(|| {
-                anyhow::anyhow!("Unable to discover MCP HTTP endpoint for {}", url)
-            })?;
-            let transport = McpHttpTransport::with_auth(endpoint, self.auth_profile.clone())?;
+            let transport = self.http_transport(url).await?;
             let init_result = transport.initialize().await?;
 
             let schema = serde_json::json!({
@@ -254,12 +471,9 @@ impl Adapter for McpAdapter {
     }
 
     async fn list_operations(&self, url: &str) -> Result<Vec<Operation>> {
-        if Self::is_stdio_command(url) {
-            let (cmd, args) = Self::parse_stdio_command(url)?;
-            let mut client = McpStdioClient::connect(&cmd, &args).await?;
-
-            // List tools as operations
-            let tools = client.list_tools().await?;
+        // List tools as operations, over stdio or HTTP
+        if Self::is_stdio_command(url) || Self::is_http_url(url) {
+            let tools = self.tools(url).await?;
 
             let operations = tools
                 .into_iter()
@@ -277,36 +491,7 @@ impl Adapter for McpAdapter {
                         description: Some(tool.description),
                         parameters,
                         return_type: Some("ToolContent".to_string()),
-                    }
-                })
-                .collect();
-
-            return Ok(operations);
-        }
-
-        // For HTTP-based MCP
-        if Self::is_http_url(url) {
-            let endpoint = self.resolve_http_endpoint(url).await.ok_or_else(|| {
-                anyhow::anyhow!("Unable to discover MCP HTTP endpoint for {}", url)
-            })?;
-            let transport = McpHttpTransport::with_auth(endpoint, self.auth_profile.clone())?;
-            let tools = transport.list_tools().await?;
-
-            let operations = tools
-                .into_iter()
-                .map(|tool| {
-                    let parameters = if let Some(schema) = tool.inputSchema {
-                        parse_schema_to_parameters(&schema)
-                    } else {
-                        Vec::new()
-                    };
-
-                    Operation {
-                        operation_id: tool.name.clone(),
-                        display_name: tool.name.clone(),
-                        description: Some(tool.description),
-                        parameters,
-                        return_type: Some("ToolContent".to_string()),
+                        tags: Vec::new(),
                     }
                 })
                 .collect();
@@ -319,11 +504,8 @@ impl Adapter for McpAdapter {
     }
 
     async fn describe_operation(&self, url: &str, operation: &str) -> Result<OperationDetail> {
-        if Self::is_stdio_command(url) {
-            let (cmd, args) = Self::parse_stdio_command(url)?;
-            let mut client = McpStdioClient::connect(&cmd, &args).await?;
-
-            let tools = client.list_tools().await?;
+        if Self::is_stdio_command(url) || Self::is_http_url(url) {
+            let tools = self.tools(url).await?;
 
             for tool in tools {
                 if tool.name == operation {
@@ -338,42 +520,18 @@ impl Adapter for McpAdapter {
                             .unwrap_or_default(),
                         return_type: Some("ToolContent".to_string()),
                         input_schema: tool.inputSchema,
+                        responses: None,
+                        security: None,
+                        servers: None,
+                        fields: None,
                     });
                 }
             }
 
-            bail!("Tool '{}' not found", operation);
+            return Err(UxcError::OperationNotFound(operation.to_string()).into());
         }
 
-        // For HTTP-based MCP
-        if Self::is_http_url(url) {
-            let endpoint = self.resolve_http_endpoint(url).await.ok_or_else(|| {
-                anyhow::anyhow!("Unable to discover MCP HTTP endpoint for {}", url)
-            })?;
-            let transport = McpHttpTransport::with_auth(endpoint, self.auth_profile.clone())?;
-            let tools = transport.list_tools().await?;
-
-            for tool in tools {
-                if tool.name == operation {
-                    return Ok(OperationDetail {
-                        operation_id: tool.name.clone(),
-                        display_name: tool.name,
-                        description: Some(tool.description),
-                        parameters: tool
-                            .inputSchema
-                            .as_ref()
-                            .map(parse_schema_to_parameters)
-                            .unwrap_or_default(),
-                        return_type: Some("ToolContent".to_string()),
-                        input_schema: tool.inputSchema,
-                    });
-                }
-            }
-
-            bail!("Tool '{}' not found", operation);
-        }
-
-        bail!("Operation '{}' not found", operation);
+        Err(UxcError::OperationNotFound(operation.to_string()).into())
     }
 
     async fn execute(
@@ -385,8 +543,7 @@ impl Adapter for McpAdapter {
         let start = std::time::Instant::now();
 
         if Self::is_stdio_command(url) {
-            let (cmd, args_list) = Self::parse_stdio_command(url)?;
-            let mut client = McpStdioClient::connect(&cmd, &args_list).await?;
+            let mut client = self.stdio_client(url).await?;
 
             // Build arguments JSON
             let arguments = if args.is_empty() {
@@ -396,6 +553,10 @@ impl Adapter for McpAdapter {
             };
 
             let result = client.call_tool(operation, arguments).await?;
+            if client.take_tools_list_changed() {
+                self.invalidate_tools(url);
+            }
+            self.release_stdio_client(url, client);
 
             // Convert tool content to a simple JSON output
             let output = convert_tool_content_to_value(&result.content);
@@ -405,16 +566,15 @@ impl Adapter for McpAdapter {
                 metadata: super::ExecutionMetadata {
                     duration_ms: start.elapsed().as_millis() as u64,
                     operation: operation.to_string(),
+                    response_headers: Vec::new(),
+                    status: None,
                 },
             });
         }
 
         // For HTTP-based MCP
         if Self::is_http_url(url) {
-            let endpoint = self.resolve_http_endpoint(url).await.ok_or_else(|| {
-                anyhow::anyhow!("Unable to discover MCP HTTP endpoint for {}", url)
-            })?;
-            let transport = McpHttpTransport::with_auth(endpoint, self.auth_profile.clone())?;
+            let transport = self.http_transport(url).await?;
 
             // Build arguments JSON
             let arguments = if args.is_empty() {
@@ -424,6 +584,9 @@ impl Adapter for McpAdapter {
             };
 
             let result = transport.call_tool(operation, arguments).await?;
+            if transport.take_tools_list_changed() {
+                self.invalidate_tools(url);
+            }
 
             // Convert tool content to a simple JSON output
             let output = convert_tool_content_to_value(&result.content);
@@ -433,12 +596,65 @@ impl Adapter for McpAdapter {
                 metadata: super::ExecutionMetadata {
                     duration_ms: start.elapsed().as_millis() as u64,
                     operation: operation.to_string(),
+                    response_headers: Vec::new(),
+                    status: None,
                 },
             });
         }
 
         bail!("Unsupported MCP URL format: {}", url)
     }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        let arguments = if args.is_empty() {
+            None
+        } else {
+            Some(Value::Object(args.into_iter().collect()))
+        };
+
+        if Self::is_stdio_command(url) {
+            // The handshake McpStdioClient::connect performs, then the call
+            let initialize = types::InitializeParams {
+                protocolVersion: types::MCP_PROTOCOL_VERSION.to_string(),
+                capabilities: types::ClientCapabilities::default(),
+                clientInfo: types::ClientInfo::uxc(),
+            };
+            let preamble = vec![
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "initialize",
+                    "params": initialize,
+                }),
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/initialized",
+                }),
+            ];
+            let message = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": types::call_tool_params(operation, arguments),
+            });
+            return Ok(RequestPlan::new("stdio", None, url.to_string(), message)
+                .with_preamble(preamble)
+                .with_note("written to the server's stdin after the initialize handshake"));
+        }
+
+        if Self::is_http_url(url) {
+            let endpoint = self.http_endpoint(url).await?;
+            let transport = McpHttpTransport::with_auth(endpoint, self.auth_profile.clone())?;
+            return transport.plan_call_tool(operation, arguments).await;
+        }
+
+        bail!("Unsupported MCP URL format: {}", url)
+    }
 }
 
 /// Parse JSON Schema to our Parameter format
@@ -474,6 +690,7 @@ fn parse_schema_to_parameters(schema: &Value) -> Vec<super::Parameter> {
                     param_type,
                     required: required.contains(name.as_str()),
                     description,
+                    location: None,
                 });
             }
         }
@@ -573,4 +790,82 @@ mod tests {
         let resolved = adapter.resolve_http_endpoint(&server.url()).await.unwrap();
         assert!(resolved.ends_with("/mcp"));
     }
+
+    #[tokio::test]
+    async fn tool_list_follows_cursors_and_is_dropped_on_list_changed() {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let _init = server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r#""method":"initialize""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(initialize_response())
+            .create_async()
+            .await;
+        let first_page = server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r#""method":"tools/list"}$"#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"a","description":"A"}],"nextCursor":"p2"}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r#""cursor":"p2""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"b","description":"B"}]}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let _call = server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex(r#""method":"tools/call""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/tools/list_changed\"}\n\n",
+                "data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"content\":[]}}\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = crate::cache::create_cache(crate::cache::CacheConfig {
+            location: temp.path().to_path_buf(),
+            ..Default::default()
+        })
+        .unwrap();
+        let adapter = McpAdapter::new().with_cache(cache);
+        let url = server.url();
+
+        let names = |ops: Vec<Operation>| {
+            ops.into_iter()
+                .map(|op| op.operation_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(adapter.list_operations(&url).await.unwrap()),
+            ["a", "b"]
+        );
+        // Served from the cached aggregate
+        assert!(adapter.describe_operation(&url, "b").await.is_ok());
+
+        adapter.execute(&url, "a", HashMap::new()).await.unwrap();
+        assert_eq!(
+            names(adapter.list_operations(&url).await.unwrap()),
+            ["a", "b"]
+        );
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
 }
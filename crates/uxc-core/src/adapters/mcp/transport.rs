@@ -5,6 +5,7 @@ use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
 use serde_json::Value as JsonValue;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
@@ -59,7 +60,11 @@ impl StdioProcessExecutor for DefaultStdioProcessExecutor {
         let stdin = child.stdin.take().context("Failed to get stdin handle")?;
         let stdout = child.stdout.take().context("Failed to get stdout handle")?;
 
-        Ok(SpawnedProcess { child, stdin, stdout })
+        Ok(SpawnedProcess {
+            child,
+            stdin,
+            stdout,
+        })
     }
 }
 
@@ -136,7 +141,11 @@ impl StdioProcessExecutor for MockStdioExecutor {
         let stdin = child.stdin.take().context("Failed to get stdin handle")?;
         let stdout = child.stdout.take().context("Failed to get stdout handle")?;
 
-        Ok(SpawnedProcess { child, stdin, stdout })
+        Ok(SpawnedProcess {
+            child,
+            stdin,
+            stdout,
+        })
     }
 }
 
@@ -154,6 +163,8 @@ pub struct McpStdioTransport {
     >,
     /// Process executor (abstracted for testing)
     _executor: Arc<dyn StdioProcessExecutor>,
+    /// Set when the server sends a tools listChanged notification
+    tools_list_changed: Arc<AtomicBool>,
 }
 
 // Manual Debug implementation since we can't derive it for executor trait object
@@ -185,7 +196,11 @@ impl McpStdioTransport {
         args: &[String],
         executor: Arc<dyn StdioProcessExecutor>,
     ) -> Result<Self> {
-        let SpawnedProcess { child, stdin, stdout } = executor.spawn(command, args).await?;
+        let SpawnedProcess {
+            child,
+            stdin,
+            stdout,
+        } = executor.spawn(command, args).await?;
 
         // Create channels for sending requests
         let (request_tx, mut request_rx) = mpsc::unbounded_channel::<OutboundMessage>();
@@ -233,6 +248,8 @@ impl McpStdioTransport {
 
         // Spawn a task to read responses from stdout
         let response_channels_clone = response_channels.clone();
+        let tools_list_changed = Arc::new(AtomicBool::new(false));
+        let tools_list_changed_for_reader = tools_list_changed.clone();
         tokio::spawn(async move {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
@@ -257,7 +274,14 @@ impl McpStdioTransport {
                             }
                         }
                         Ok(None) => {
-                            // Notification (no response expected), ignore
+                            // Notification (no response expected); only tool
+                            // list changes matter to the client
+                            let changed = serde_json::from_str::<JsonValue>(&json_str)
+                                .is_ok_and(|message| is_tools_list_changed(&message));
+                            if changed {
+                                tracing::debug!("MCP server tool list changed");
+                                tools_list_changed_for_reader.store(true, Ordering::Relaxed);
+                            }
                         }
                         Err(e) => {
                             tracing::warn!("Failed to parse JSON-RPC message: {}", e);
@@ -273,9 +297,15 @@ impl McpStdioTransport {
             request_tx,
             response_channels,
             _executor: executor,
+            tools_list_changed,
         })
     }
 
+    /// Whether a tools listChanged notification arrived since the last check
+    pub fn take_tools_list_changed(&self) -> bool {
+        self.tools_list_changed.swap(false, Ordering::Relaxed)
+    }
+
     /// Send a request and wait for the response
     pub async fn send_request(
         &mut self,
@@ -299,6 +329,8 @@ impl McpStdioTransport {
 
         let request_json = serde_json::to_string(&request)?;
         tracing::debug!("Sending request: {}", request_json);
+        crate::trace::rpc_request(&format!("mcp stdio {}", method), &[], &request_json);
+        let started = std::time::Instant::now();
 
         // Create a response channel
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
@@ -325,6 +357,13 @@ impl McpStdioTransport {
 
         // Wait for the response
         let response = response_rx.await.context("Response channel closed")?;
+        crate::trace::rpc_response(
+            &match &response.error {
+                Some(error) => format!("error {}: {}", error.code, error.message),
+                None => "result".to_string(),
+            },
+            started.elapsed(),
+        );
 
         if let Some(error) = response.error {
             bail!("JSON-RPC error: {} - {}", error.code, error.message);
@@ -347,6 +386,11 @@ impl McpStdioTransport {
 
         let notification_json = serde_json::to_string(&notification)?;
         tracing::debug!("Sending notification: {}", notification_json);
+        crate::trace::rpc_request(
+            &format!("mcp stdio notification {}", method),
+            &[],
+            &notification_json,
+        );
 
         self.request_tx
             .send(OutboundMessage {
@@ -479,7 +523,10 @@ mod tests {
     #[tokio::test]
     async fn parse_command_handles_command_with_args() {
         let parts = parse_command("npx @modelcontextprotocol/server-everything");
-        assert_eq!(parts, vec!["npx", "@modelcontextprotocol/server-everything"]);
+        assert_eq!(
+            parts,
+            vec!["npx", "@modelcontextprotocol/server-everything"]
+        );
     }
 
     #[tokio::test]
@@ -659,7 +706,8 @@ mod tests {
 
     #[tokio::test]
     async fn initialized_sends_notification() {
-        let script = "while read line; do echo '{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}'; done";
+        let script =
+            "while read line; do echo '{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}'; done";
         let mut transport =
             McpStdioTransport::connect("sh", &["-c".to_string(), script.to_string()])
                 .await
@@ -679,8 +727,7 @@ mod tests {
     #[tokio::test]
     async fn connect_with_mock_executor_succeeds() {
         let mock = Arc::new(MockStdioExecutor::new());
-        let result =
-            McpStdioTransport::connect_with_executor("test", &[], mock).await;
+        let result = McpStdioTransport::connect_with_executor("test", &[], mock).await;
         // The mock will spawn a real echo process, so this should succeed
         // but may fail on initialization - that's ok for this test
         // We're just testing that the executor is being used
@@ -690,19 +737,18 @@ mod tests {
     #[tokio::test]
     async fn connect_with_failing_mock_executor_fails() {
         let mock = Arc::new(MockStdioExecutor::with_spawn_failure());
-        let result =
-            McpStdioTransport::connect_with_executor("test", &[], mock).await;
+        let result = McpStdioTransport::connect_with_executor("test", &[], mock).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("failed to spawn"));
     }
 
     #[tokio::test]
     async fn request_id_increments_with_each_request() {
-        let script = "while read line; do echo '{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}'; done";
-        let transport =
-            McpStdioTransport::connect("sh", &["-c".to_string(), script.to_string()])
-                .await
-                .unwrap();
+        let script =
+            "while read line; do echo '{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}'; done";
+        let transport = McpStdioTransport::connect("sh", &["-c".to_string(), script.to_string()])
+            .await
+            .unwrap();
 
         // Check that ID counter starts at 1 and increments
         let id1 = {
@@ -55,6 +55,31 @@ pub struct JsonRpcError {
 /// MCP Protocol Version
 pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
 
+/// Notification a server sends when its tool list changes
+pub const TOOLS_LIST_CHANGED: &str = "notifications/tools/list_changed";
+
+/// Upper bound on `tools/list` pages followed for one listing
+pub const MAX_TOOLS_LIST_PAGES: usize = 1000;
+
+/// Whether a JSON-RPC message is a tools `listChanged` notification
+pub fn is_tools_list_changed(message: &JsonValue) -> bool {
+    message.get("id").is_none()
+        && message.get("method").and_then(JsonValue::as_str) == Some(TOOLS_LIST_CHANGED)
+}
+
+/// `tools/list` params requesting the page after `cursor`
+pub fn tools_list_params(cursor: Option<&str>) -> Option<JsonValue> {
+    cursor.map(|cursor| serde_json::json!({ "cursor": cursor }))
+}
+
+/// `tools/call` params calling tool `name`
+pub fn call_tool_params(name: &str, arguments: Option<JsonValue>) -> JsonValue {
+    serde_json::json!({
+        "name": name,
+        "arguments": arguments
+    })
+}
+
 /// Initialize request parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitializeParams {
@@ -91,6 +116,16 @@ pub struct ClientInfo {
     pub version: String,
 }
 
+impl ClientInfo {
+    /// How uxc introduces itself to servers
+    pub fn uxc() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
 /// Initialize response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitializeResult {
@@ -249,6 +284,26 @@ pub struct GetPromptResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolsListResponse {
     pub tools: Vec<Tool>,
+    /// Cursor of the next page; absent on the last page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nextCursor: Option<String>,
+}
+
+impl ToolsListResponse {
+    /// Cursor to request next, failing if the server repeats one
+    pub fn next_page(&self, seen: &mut Vec<String>) -> anyhow::Result<Option<String>> {
+        let Some(cursor) = self.nextCursor.clone().filter(|c| !c.is_empty()) else {
+            return Ok(None);
+        };
+        if seen.contains(&cursor) || seen.len() >= MAX_TOOLS_LIST_PAGES {
+            anyhow::bail!(
+                "tools/list pagination did not terminate (cursor '{}')",
+                cursor
+            );
+        }
+        seen.push(cursor.clone());
+        Ok(Some(cursor))
+    }
 }
 
 /// Tool call result (alias for CallToolResult)
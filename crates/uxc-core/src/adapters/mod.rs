@@ -0,0 +1,1323 @@
+//! Protocol adapters for different schema types
+//!
+//! Each adapter implements a common interface for:
+//! - Protocol detection
+//! - Schema retrieval
+//! - Operation discovery
+//! - Execution
+
+pub mod graphql;
+pub mod grpc;
+pub mod jsonrpc;
+pub mod mcp;
+pub mod multi;
+pub mod openapi;
+pub mod plugin;
+pub mod probe;
+pub mod registry;
+pub mod transcoding;
+
+use crate::auth::AuthType;
+use crate::error::UxcError;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use tracing::debug;
+
+/// Enum of all available adapters
+#[allow(non_camel_case_types)]
+pub enum AdapterEnum {
+    OpenAPI(openapi::OpenAPIAdapter),
+    GRpc(grpc::GrpcAdapter),
+    JsonRpc(jsonrpc::JsonRpcAdapter),
+    Mcp(mcp::McpAdapter),
+    GraphQL(graphql::GraphQLAdapter),
+    /// An adapter added to the [`registry`], or a protocol added by a
+    /// plugin ([`crate::plugins`])
+    Custom(std::sync::Arc<dyn Adapter>),
+    /// Every protocol detected at a host ([`multi`])
+    Multi(multi::MultiAdapter),
+}
+
+#[async_trait]
+impl Adapter for AdapterEnum {
+    fn protocol_type(&self) -> ProtocolType {
+        match self {
+            AdapterEnum::OpenAPI(_) => ProtocolType::OpenAPI,
+            AdapterEnum::GRpc(_) => ProtocolType::GRpc,
+            AdapterEnum::JsonRpc(_) => ProtocolType::JsonRpc,
+            AdapterEnum::Mcp(_) => ProtocolType::Mcp,
+            AdapterEnum::GraphQL(_) => ProtocolType::GraphQL,
+            AdapterEnum::Custom(a) => a.protocol_type(),
+            AdapterEnum::Multi(a) => a
+                .adapters()
+                .first()
+                .map_or(ProtocolType::Plugin, Adapter::protocol_type),
+        }
+    }
+
+    async fn can_handle(&self, url: &str) -> Result<bool> {
+        match self {
+            AdapterEnum::OpenAPI(a) => a.can_handle(url).await,
+            AdapterEnum::GRpc(a) => a.can_handle(url).await,
+            AdapterEnum::JsonRpc(a) => a.can_handle(url).await,
+            AdapterEnum::Mcp(a) => a.can_handle(url).await,
+            AdapterEnum::GraphQL(a) => a.can_handle(url).await,
+            AdapterEnum::Custom(a) => a.can_handle(url).await,
+            AdapterEnum::Multi(a) => a.can_handle(url).await,
+        }
+    }
+
+    async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        if let AdapterEnum::Multi(a) = self {
+            return a.fetch_schema(url).await;
+        }
+        let protocol = self.protocol_type();
+        crate::instrumentation::schema_fetch(url, protocol, None, async {
+            match self {
+                AdapterEnum::OpenAPI(a) => a.fetch_schema(url).await,
+                AdapterEnum::GRpc(a) => a.fetch_schema(url).await,
+                AdapterEnum::JsonRpc(a) => a.fetch_schema(url).await,
+                AdapterEnum::Mcp(a) => a.fetch_schema(url).await,
+                AdapterEnum::GraphQL(a) => a.fetch_schema(url).await,
+                AdapterEnum::Custom(a) => a.fetch_schema(url).await,
+                AdapterEnum::Multi(_) => unreachable!("routed above"),
+            }
+        })
+        .await
+    }
+
+    async fn list_operations(&self, url: &str) -> Result<Vec<Operation>> {
+        if let AdapterEnum::Multi(a) = self {
+            return a.list_operations(url).await;
+        }
+        let protocol = self.protocol_type();
+        crate::instrumentation::schema_fetch(url, protocol, None, async {
+            match self {
+                AdapterEnum::OpenAPI(a) => a.list_operations(url).await,
+                AdapterEnum::GRpc(a) => a.list_operations(url).await,
+                AdapterEnum::JsonRpc(a) => a.list_operations(url).await,
+                AdapterEnum::Mcp(a) => a.list_operations(url).await,
+                AdapterEnum::GraphQL(a) => a.list_operations(url).await,
+                AdapterEnum::Custom(a) => a.list_operations(url).await,
+                AdapterEnum::Multi(_) => unreachable!("routed above"),
+            }
+        })
+        .await
+    }
+
+    async fn describe_operation(&self, url: &str, operation: &str) -> Result<OperationDetail> {
+        if let AdapterEnum::Multi(a) = self {
+            return a.describe_operation(url, operation).await;
+        }
+        let protocol = self.protocol_type();
+        crate::instrumentation::schema_fetch(url, protocol, Some(operation), async {
+            match self {
+                AdapterEnum::OpenAPI(a) => a.describe_operation(url, operation).await,
+                AdapterEnum::GRpc(a) => a.describe_operation(url, operation).await,
+                AdapterEnum::JsonRpc(a) => a.describe_operation(url, operation).await,
+                AdapterEnum::Mcp(a) => a.describe_operation(url, operation).await,
+                AdapterEnum::GraphQL(a) => a.describe_operation(url, operation).await,
+                AdapterEnum::Custom(a) => a.describe_operation(url, operation).await,
+                AdapterEnum::Multi(_) => unreachable!("routed above"),
+            }
+        })
+        .await
+    }
+
+    async fn execute(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult> {
+        if let AdapterEnum::Multi(a) = self {
+            let (adapter, operation) = a.route(operation)?;
+            return adapter.execute(url, operation, args).await;
+        }
+        let protocol = self.protocol_type();
+        let pipeline = crate::middleware::installed();
+        pipeline
+            .run(protocol, url, operation, args, |args| {
+                crate::cassette::execute(protocol, url, operation, args, |args| async move {
+                    self.ensure_callable(url, operation)?;
+                    crate::instrumentation::execute(url, protocol, operation, async {
+                        match self {
+                            AdapterEnum::OpenAPI(a) => a.execute(url, operation, args).await,
+                            AdapterEnum::GRpc(a) => a.execute(url, operation, args).await,
+                            AdapterEnum::JsonRpc(a) => a.execute(url, operation, args).await,
+                            AdapterEnum::Mcp(a) => a.execute(url, operation, args).await,
+                            AdapterEnum::GraphQL(a) => a.execute(url, operation, args).await,
+                            AdapterEnum::Custom(a) => a.execute(url, operation, args).await,
+                            AdapterEnum::Multi(_) => unreachable!("routed above"),
+                        }
+                    })
+                    .await
+                })
+            })
+            .await
+    }
+
+    async fn execute_raw(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RawResponse> {
+        if let AdapterEnum::Multi(a) = self {
+            let (adapter, operation) = a.route(operation)?;
+            return adapter.execute_raw(url, operation, args).await;
+        }
+        self.ensure_callable(url, operation)?;
+        let protocol = self.protocol_type();
+        let pipeline = crate::middleware::installed();
+        let call = pipeline.call(protocol, url, operation, args);
+        let (_, response) = pipeline
+            .attempts(call, |args| {
+                crate::instrumentation::execute(url, protocol, operation, async {
+                    match self {
+                        AdapterEnum::OpenAPI(a) => a.execute_raw(url, operation, args).await,
+                        AdapterEnum::GRpc(a) => a.execute_raw(url, operation, args).await,
+                        AdapterEnum::JsonRpc(a) => a.execute_raw(url, operation, args).await,
+                        AdapterEnum::Mcp(a) => a.execute_raw(url, operation, args).await,
+                        AdapterEnum::GraphQL(a) => a.execute_raw(url, operation, args).await,
+                        AdapterEnum::Custom(a) => a.execute_raw(url, operation, args).await,
+                        AdapterEnum::Multi(_) => unreachable!("routed above"),
+                    }
+                })
+            })
+            .await?;
+        Ok(response)
+    }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        if let AdapterEnum::Multi(a) = self {
+            let (adapter, operation) = a.route(operation)?;
+            return adapter.plan(url, operation, args).await;
+        }
+        let pipeline = crate::middleware::installed();
+        let mut call = pipeline.call(self.protocol_type(), url, operation, args);
+        pipeline.prepare(&mut call).await?;
+        let args = call.args;
+        let plan = async {
+            match self {
+                AdapterEnum::OpenAPI(a) => a.plan(url, operation, args).await,
+                AdapterEnum::GRpc(a) => a.plan(url, operation, args).await,
+                AdapterEnum::JsonRpc(a) => a.plan(url, operation, args).await,
+                AdapterEnum::Mcp(a) => a.plan(url, operation, args).await,
+                AdapterEnum::GraphQL(a) => a.plan(url, operation, args).await,
+                AdapterEnum::Custom(a) => a.plan(url, operation, args).await,
+                AdapterEnum::Multi(_) => unreachable!("routed above"),
+            }
+        };
+        crate::middleware::with_headers(call.headers, plan).await
+    }
+}
+
+impl AdapterEnum {
+    /// Use `cache` for the endpoint's schema. Custom adapters and plugins keep
+    /// their own state.
+    pub fn with_cache(self, cache: std::sync::Arc<dyn crate::cache::Cache>) -> Self {
+        match self {
+            AdapterEnum::OpenAPI(a) => AdapterEnum::OpenAPI(a.with_cache(cache)),
+            AdapterEnum::GraphQL(a) => AdapterEnum::GraphQL(a.with_cache(cache)),
+            AdapterEnum::GRpc(a) => AdapterEnum::GRpc(a.with_cache(cache)),
+            AdapterEnum::JsonRpc(a) => AdapterEnum::JsonRpc(a.with_cache(cache)),
+            AdapterEnum::Mcp(a) => AdapterEnum::Mcp(a.with_cache(cache)),
+            custom @ AdapterEnum::Custom(_) => custom,
+            AdapterEnum::Multi(a) => AdapterEnum::Multi(a.map(|a| a.with_cache(cache.clone()))),
+        }
+    }
+
+    /// Where the adapter found the endpoint at `url` while probing it: the
+    /// OpenAPI schema URL, the MCP endpoint or the GraphQL endpoint under a
+    /// configured path
+    async fn discovered_location(&self, url: &str) -> Option<String> {
+        match self {
+            AdapterEnum::OpenAPI(a) => a.discovered_schema_url(url).await,
+            AdapterEnum::Mcp(a) => a.discovered_endpoint(url).await,
+            AdapterEnum::GraphQL(a) => a.discovered_endpoint(url),
+            _ => None,
+        }
+    }
+
+    /// Start from a location [`AdapterEnum::discovered_location`] reported
+    fn with_discovered_location(self, url: &str, location: String) -> Self {
+        match self {
+            AdapterEnum::OpenAPI(a) => {
+                AdapterEnum::OpenAPI(a.with_discovered_schema_url(url, location))
+            }
+            AdapterEnum::Mcp(a) => AdapterEnum::Mcp(a.with_discovered_endpoint(url, location)),
+            AdapterEnum::GraphQL(a) => {
+                AdapterEnum::GraphQL(a.with_discovered_endpoint(url, location))
+            }
+            other => other,
+        }
+    }
+
+    /// Send the credentials of `profile` with every request. Custom adapters
+    /// and plugins handle their own credentials.
+    pub fn with_auth(self, profile: crate::auth::Profile) -> Self {
+        match self {
+            AdapterEnum::OpenAPI(a) => AdapterEnum::OpenAPI(a.with_auth(profile)),
+            AdapterEnum::GraphQL(a) => AdapterEnum::GraphQL(a.with_auth(profile)),
+            AdapterEnum::GRpc(a) => AdapterEnum::GRpc(a.with_auth(profile)),
+            AdapterEnum::JsonRpc(a) => AdapterEnum::JsonRpc(a.with_auth(profile)),
+            AdapterEnum::Mcp(a) => AdapterEnum::Mcp(a.with_auth(profile)),
+            custom @ AdapterEnum::Custom(_) => custom,
+            AdapterEnum::Multi(a) => AdapterEnum::Multi(a.map(|a| a.with_auth(profile.clone()))),
+        }
+    }
+
+    /// Keep sessions open between calls where the protocol has them; see
+    /// [`mcp::McpAdapter::keep_sessions`]
+    pub fn keep_sessions(self) -> Self {
+        match self {
+            AdapterEnum::Mcp(a) => AdapterEnum::Mcp(a.keep_sessions()),
+            AdapterEnum::Multi(a) => AdapterEnum::Multi(a.map(AdapterEnum::keep_sessions)),
+            other => other,
+        }
+    }
+
+    /// The adapter serving `operation`, and its ID for that adapter. Only
+    /// [`AdapterEnum::Multi`] namespaces its operations; other adapters serve
+    /// them as they are.
+    pub fn route<'o>(&self, operation: &'o str) -> Result<(&AdapterEnum, &'o str)> {
+        match self {
+            AdapterEnum::Multi(a) => a.route(operation),
+            adapter => Ok((adapter, operation)),
+        }
+    }
+
+    /// Calls need the network, except for local MCP servers (processes)
+    fn ensure_callable(&self, url: &str, operation: &str) -> Result<()> {
+        let local = matches!(self, AdapterEnum::Mcp(_)) && mcp::McpAdapter::is_stdio_command(url);
+        if crate::cache::is_offline() && !local {
+            return Err(crate::cache::offline_miss(format!(
+                "calling {} needs the network",
+                operation
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Supported protocol types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(non_camel_case_types)]
+pub enum ProtocolType {
+    OpenAPI,
+    GRpc,
+    JsonRpc,
+    Mcp,
+    GraphQL,
+    /// Served by a plugin or a custom adapter of the [`registry`]; not in
+    /// [`ProtocolType::ALL`]
+    Plugin,
+}
+
+impl ProtocolType {
+    /// Every protocol, in detection order
+    pub const ALL: [ProtocolType; 5] = [
+        ProtocolType::Mcp,
+        ProtocolType::GraphQL,
+        ProtocolType::OpenAPI,
+        ProtocolType::JsonRpc,
+        ProtocolType::GRpc,
+    ];
+
+    /// Parse a protocol name as printed by [`ProtocolType::as_str`]
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|protocol| protocol.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProtocolType::OpenAPI => "openapi",
+            ProtocolType::GRpc => "grpc",
+            ProtocolType::JsonRpc => "jsonrpc",
+            ProtocolType::Mcp => "mcp",
+            ProtocolType::GraphQL => "graphql",
+            ProtocolType::Plugin => "plugin",
+        }
+    }
+}
+
+/// Operation metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub operation_id: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub parameters: Vec<Parameter>,
+    #[allow(dead_code)]
+    pub return_type: Option<String>,
+    /// Tags grouping the operation, e.g. OpenAPI operation tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Parameter definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    pub param_type: String,
+    pub required: bool,
+    pub description: Option<String>,
+    /// Where the parameter is sent (OpenAPI `in`: `path`, `query`, `header`,
+    /// `cookie`, or `body`/`formData` in Swagger 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+/// Rich operation metadata for progressive discovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationDetail {
+    pub operation_id: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+    pub input_schema: Option<Value>,
+    /// Documented responses by status code (`200`, `4XX`, `default`), each
+    /// with its description and JSON schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub responses: Option<Value>,
+    /// Security schemes the operation accepts: alternatives of schemes used
+    /// together, where an empty alternative means no auth is needed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<Vec<crate::auth::scheme::SecurityScheme>>>,
+    /// Servers the operation can be called on, each with its `url`,
+    /// `description` and `variables`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<Value>>,
+    /// Fields of the result that can be selected, each with its `name`,
+    /// `type` and `description` (GraphQL `--select`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<Value>>,
+}
+
+/// Execution result
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    pub data: Value,
+    pub metadata: ExecutionMetadata,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionMetadata {
+    pub duration_ms: u64,
+    #[allow(dead_code)]
+    pub operation: String,
+    /// HTTP response headers (lowercase names), when the call was one request
+    pub response_headers: Vec<(String, String)>,
+    /// HTTP status of the response, when the call was one request
+    pub status: Option<u16>,
+}
+
+/// Adapter trait - must be implemented by all protocol adapters
+#[async_trait::async_trait]
+pub trait Adapter: Send + Sync {
+    /// Get the protocol type this adapter handles
+    fn protocol_type(&self) -> ProtocolType;
+
+    /// Detect if this adapter can handle the given endpoint
+    async fn can_handle(&self, url: &str) -> Result<bool>;
+
+    /// Retrieve schema from the endpoint
+    async fn fetch_schema(&self, url: &str) -> Result<Value>;
+
+    /// List available operations
+    async fn list_operations(&self, url: &str) -> Result<Vec<Operation>>;
+
+    /// Get rich metadata for a specific operation
+    async fn describe_operation(&self, url: &str, operation: &str) -> Result<OperationDetail>;
+
+    /// Execute an operation
+    async fn execute(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult>;
+
+    /// Execute an operation and return the upstream response body unmodified.
+    ///
+    /// Adapters whose results are not a plain response body return the
+    /// result of [`Adapter::execute`] serialized as JSON.
+    async fn execute_raw(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RawResponse> {
+        let result = self.execute(url, operation, args).await?;
+        Ok(RawResponse {
+            status: None,
+            content_type: Some("application/json".to_string()),
+            body: serde_json::to_vec_pretty(&result.data)?,
+        })
+    }
+
+    /// Describe the request [`Adapter::execute`] would send, without sending it
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan>;
+}
+
+/// The request a call would send, as shown by `--dry-run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestPlan {
+    /// How the request travels: `http`, `grpc` or `stdio`
+    pub transport: String,
+
+    /// HTTP method, or the full gRPC method (`package.Service/Method`)
+    pub method: Option<String>,
+
+    /// URL, gRPC target or server command
+    pub target: String,
+
+    /// Headers or gRPC metadata, as `name: value`
+    pub headers: Vec<String>,
+
+    pub body: Option<Value>,
+
+    /// Messages written on the same connection before the request (the MCP
+    /// stdio handshake)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preamble: Vec<Value>,
+
+    /// Whether a gRPC target is first tried without TLS
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub plaintext: bool,
+
+    /// How the request is sent, where it differs from a single exchange
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+
+    /// Names of headers holding credentials
+    #[serde(skip)]
+    sensitive: Vec<String>,
+}
+
+impl RequestPlan {
+    /// A plan without headers or notes
+    pub fn new(transport: &str, method: Option<String>, target: String, body: Value) -> Self {
+        Self {
+            transport: transport.to_string(),
+            method,
+            target,
+            headers: Vec::new(),
+            body: Some(body),
+            preamble: Vec::new(),
+            plaintext: false,
+            notes: Vec::new(),
+            sensitive: Vec::new(),
+        }
+    }
+
+    /// Plan of an HTTP request, as [`crate::http::send`] would send it
+    pub fn http(request: reqwest::RequestBuilder) -> Result<Self> {
+        let request = crate::http::build(request)?;
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, String::from_utf8_lossy(value.as_bytes())))
+            .collect();
+        let sensitive = request
+            .headers()
+            .iter()
+            .filter(|(_, value)| value.is_sensitive())
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .filter(|bytes| !bytes.is_empty())
+            .map(|bytes| {
+                serde_json::from_slice(bytes)
+                    .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+            });
+        Ok(Self {
+            transport: "http".to_string(),
+            method: Some(request.method().to_string()),
+            target: request.url().to_string(),
+            headers,
+            body,
+            preamble: Vec::new(),
+            plaintext: false,
+            notes: Vec::new(),
+            sensitive,
+        })
+    }
+
+    /// Add headers, marking them as credentials when `sensitive` is set
+    pub fn with_headers(mut self, headers: Vec<String>, sensitive: bool) -> Self {
+        if sensitive {
+            self.sensitive.extend(
+                headers
+                    .iter()
+                    .map(|header| crate::trace::split_header(header).0),
+            );
+        }
+        self.headers.extend(headers);
+        self
+    }
+
+    pub fn with_preamble(mut self, messages: Vec<Value>) -> Self {
+        self.preamble = messages;
+        self
+    }
+
+    pub fn with_plaintext(mut self, plaintext: bool) -> Self {
+        self.plaintext = plaintext;
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// The plan as it may be shown: credential headers as `***` and
+    /// registered secrets masked everywhere
+    pub fn redacted(&self) -> Self {
+        let headers = self
+            .headers
+            .iter()
+            .map(|header| {
+                let (name, value) = crate::trace::split_header(header);
+                let value = if self
+                    .sensitive
+                    .iter()
+                    .any(|sensitive| sensitive.eq_ignore_ascii_case(&name))
+                {
+                    crate::masking::MASK.to_string()
+                } else {
+                    crate::masking::mask(&crate::trace::redact(&name, &value))
+                };
+                format!("{}: {}", name, value)
+            })
+            .collect();
+        let body = self.body.as_ref().map(|body| {
+            let masked = crate::masking::mask(&body.to_string());
+            serde_json::from_str(&masked).unwrap_or(Value::String(masked))
+        });
+        Self {
+            target: crate::masking::mask(&self.target),
+            headers,
+            body,
+            ..self.clone()
+        }
+    }
+}
+
+/// Response body of a `--raw` call, as returned by the endpoint
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    /// HTTP status, when the call was a single HTTP request
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl RawResponse {
+    /// Whether the endpoint answered with an error status
+    pub fn is_error(&self) -> bool {
+        self.status.is_some_and(|status| status >= 400)
+    }
+}
+
+/// Features an adapter supports, as reported by `uxc support`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilitySet {
+    /// Streaming calls (server, client or bidirectional streams)
+    pub streaming: bool,
+    /// Several calls sent in one request
+    pub batch: bool,
+    /// Arguments checked against the schema before the call is sent
+    pub validation: bool,
+    /// Auth profile types the adapter can send
+    pub auth_types: Vec<AuthType>,
+    /// Operations can be exported with `uxc export`
+    pub schema_export: bool,
+    /// Schemas are cached and served by `--offline`
+    pub schema_cache: bool,
+    /// `--raw` returns the response body exactly as sent by the endpoint
+    pub raw_body: bool,
+    /// Caveats that do not fit a flag
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Static feature support of an adapter, known without contacting an endpoint
+pub trait Capabilities {
+    /// Protocol these capabilities belong to
+    const PROTOCOL: ProtocolType;
+
+    fn capabilities() -> CapabilitySet;
+}
+
+/// One row of the support matrix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolSupport {
+    pub protocol: String,
+    #[serde(flatten)]
+    pub capabilities: CapabilitySet,
+}
+
+impl ProtocolSupport {
+    fn of<A: Capabilities>() -> Self {
+        Self {
+            protocol: A::PROTOCOL.as_str().to_string(),
+            capabilities: A::capabilities(),
+        }
+    }
+}
+
+/// Capabilities of every adapter, in detection order
+pub fn support_matrix() -> Vec<ProtocolSupport> {
+    vec![
+        ProtocolSupport::of::<mcp::McpAdapter>(),
+        ProtocolSupport::of::<graphql::GraphQLAdapter>(),
+        ProtocolSupport::of::<openapi::OpenAPIAdapter>(),
+        ProtocolSupport::of::<jsonrpc::JsonRpcAdapter>(),
+        ProtocolSupport::of::<grpc::GrpcAdapter>(),
+    ]
+}
+
+/// Protocol detector - attempts to identify the protocol type
+pub struct ProtocolDetector;
+
+/// How long a probe that matched waits for higher-priority probes still
+/// running before it wins
+pub const RACE_WINDOW: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Optional settings that affect protocol detection.
+#[derive(Clone, Default)]
+pub struct DetectionOptions {
+    pub schema_url: Option<String>,
+    /// OpenAPI server calls go to (`--server`, `--server-var`)
+    pub server: Option<openapi::ServerSelection>,
+    /// Fields GraphQL operations select (`--select`, `--depth`)
+    pub selection: graphql::Selection,
+    /// How GraphQL calls refer to their document (`--persisted-queries`)
+    pub persisted_queries: Option<graphql::PersistedQueries>,
+    pub grpc: grpc::GrpcConnectionConfig,
+    /// Forced JSON-RPC version; `None` auto-detects
+    pub jsonrpc_version: Option<jsonrpc::JsonRpcVersion>,
+    /// Schema cache consulted instead of the network in offline mode
+    pub cache: Option<std::sync::Arc<dyn crate::cache::Cache>>,
+    /// Use this protocol without probing (`--protocol`)
+    pub protocol: Option<ProtocolType>,
+    /// Protocols probed before the others (`[detection] order`)
+    pub order: Vec<ProtocolType>,
+    /// How long a matching probe waits for higher-priority probes still
+    /// running (`[detection] race_window`); [`RACE_WINDOW`] when `None`
+    pub race_window: Option<std::time::Duration>,
+    /// Protocols not probed (`[detection] disabled`, also per host)
+    pub disabled: Vec<ProtocolType>,
+    /// How long each probe may take (`[detection] timeout`)
+    pub timeout: Option<std::time::Duration>,
+    /// Probe timeouts of single protocols (`[detection] protocol_timeouts`)
+    pub protocol_timeouts: HashMap<ProtocolType, std::time::Duration>,
+    /// Paths probed besides the built-in ones (`[detection] openapi_paths`
+    /// and `graphql_paths`)
+    pub paths: HashMap<ProtocolType, Vec<String>>,
+}
+
+impl DetectionOptions {
+    /// These options with the `[detection]` settings (see [`crate::config`])
+    /// for `url` applied
+    pub fn with_config(mut self, url: &str) -> Result<Self> {
+        self.order = crate::config::get("detection.order")?.as_protocols();
+        self.disabled = crate::config::disabled_protocols(url)?;
+        self.timeout = crate::config::get("detection.timeout")?.as_duration();
+        self.race_window = crate::config::get("detection.race_window")?.as_duration();
+        self.protocol_timeouts = crate::config::get("detection.protocol_timeouts")?
+            .as_protocol_durations()
+            .into_iter()
+            .collect();
+        for (protocol, key) in [
+            (ProtocolType::OpenAPI, "detection.openapi_paths"),
+            (ProtocolType::GraphQL, "detection.graphql_paths"),
+        ] {
+            let paths = crate::config::get(key)?.as_strings();
+            if !paths.is_empty() {
+                self.paths.insert(protocol, paths);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Extra paths probed for `protocol`
+    pub fn paths(&self, protocol: ProtocolType) -> Vec<String> {
+        self.paths.get(&protocol).cloned().unwrap_or_default()
+    }
+
+    fn is_disabled(&self, registration: &registry::Registration) -> bool {
+        registration
+            .protocol()
+            .is_some_and(|protocol| self.disabled.contains(&protocol))
+    }
+
+    /// Timeout of probes of `protocol`, `None` for custom adapters
+    fn probe_timeout(&self, protocol: Option<ProtocolType>) -> Option<std::time::Duration> {
+        protocol
+            .and_then(|protocol| self.protocol_timeouts.get(&protocol).copied())
+            .or(self.timeout)
+    }
+}
+
+impl ProtocolDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get adapter for a URL (auto-detects protocol)
+    pub async fn detect_adapter(&self, url: &str) -> Result<AdapterEnum> {
+        self.detect_adapter_with_options(url, &DetectionOptions::default())
+            .await
+    }
+
+    /// Get adapter for a URL using explicit detection options.
+    pub async fn detect_adapter_with_options(
+        &self,
+        url: &str,
+        options: &DetectionOptions,
+    ) -> Result<AdapterEnum> {
+        crate::instrumentation::detect(url, AdapterEnum::protocol_type, self.detect(url, options))
+            .await
+    }
+
+    async fn detect(&self, url: &str, options: &DetectionOptions) -> Result<AdapterEnum> {
+        crate::allowlist::enforce(url, None)?;
+        if let Some(plugin) = crate::plugins::for_url(url)? {
+            crate::explain::record(
+                "detection",
+                format!(
+                    "scheme served by plugin '{}'; no probes",
+                    plugin.manifest.name
+                ),
+            );
+            return Ok(AdapterEnum::Custom(std::sync::Arc::new(
+                plugin::PluginAdapter::new(plugin)?,
+            )));
+        }
+        if crate::cache::schemas_from_store(options.cache.as_ref()) {
+            return self.detect_cached_adapter(url, options).await;
+        }
+
+        // A previous `detect --all` narrows the probes to protocols it found
+        let detected = Self::cached_detection(url, options);
+        if let (Some(protocol), Some(detected)) = (options.protocol, &detected) {
+            if !detected.contains(&protocol) {
+                return Err(Self::not_detected(url, protocol, detected).into());
+            }
+        }
+        // A protocol named by the caller is not probed: the endpoint may block
+        // probes, and calls report what does not answer
+        if let Some(protocol) = options.protocol {
+            crate::explain::record(
+                "detection",
+                format!("{} chosen by --protocol; no probes", protocol.as_str()),
+            );
+            return Ok(registry::builtin(protocol, options));
+        }
+        let registry = registry::registry();
+        if let Some((name, adapter)) = Self::cached_adapter(url, options, &registry) {
+            crate::explain::record(
+                "detection",
+                format!("{} from the detection cache; no probes", name),
+            );
+            return Ok(adapter);
+        }
+        let mut candidates: Vec<registry::Registration> = match detected {
+            Some(detected) if !detected.is_empty() => {
+                let names: Vec<&str> = detected.iter().map(ProtocolType::as_str).collect();
+                crate::explain::record(
+                    "detection",
+                    format!(
+                        "probes narrowed to {} by a cached `detect --all` result",
+                        names.join(", ")
+                    ),
+                );
+                detected
+                    .into_iter()
+                    .map(registry::Registration::of)
+                    .collect()
+            }
+            _ => {
+                if !options.order.is_empty() {
+                    let names: Vec<&str> = options.order.iter().map(ProtocolType::as_str).collect();
+                    crate::explain::record(
+                        "detection",
+                        format!("{} probed first: [detection] order", names.join(", ")),
+                    );
+                }
+                Self::probe_order(&registry, options)
+            }
+        };
+        candidates.retain(|candidate| {
+            let disabled = options.is_disabled(candidate);
+            if disabled {
+                crate::explain::record(
+                    "detection",
+                    format!("{} not probed: [detection] disabled", candidate.name()),
+                );
+            }
+            !disabled
+        });
+
+        // By default MCP first (stdio commands are distinct), GraphQL next
+        // (introspection is reliable), then OpenAPI, JSON-RPC (OpenRPC
+        // discovery) and gRPC last (least reliable detection), with custom
+        // adapters placed among them by priority
+        if let Some((candidate, adapter)) = Self::race(url, candidates, options).await? {
+            Self::remember_adapter(url, candidate.name(), &adapter, options).await;
+            return Ok(adapter);
+        }
+
+        Err(UxcError::ProtocolDetectionFailed(format!("No adapter found for URL: {}", url)).into())
+    }
+
+    /// Probe `candidates` concurrently and pick the first in their order
+    /// that matches. A match is used as soon as every candidate before it
+    /// has failed to match; while some still run, it waits for them up to
+    /// the tie-break window (`[detection] race_window`), then wins over
+    /// those still running. Probes left running are dropped.
+    async fn race(
+        url: &str,
+        candidates: Vec<registry::Registration>,
+        options: &DetectionOptions,
+    ) -> Result<Option<(registry::Registration, AdapterEnum)>> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+
+        let mut adapters: Vec<AdapterEnum> = candidates
+            .iter()
+            .map(|candidate| candidate.adapter(options))
+            .collect();
+        let mut results: Vec<Option<Result<bool>>> = candidates.iter().map(|_| None).collect();
+        let window = options.race_window.unwrap_or(RACE_WINDOW);
+
+        let winner = {
+            let mut probes: FuturesUnordered<_> = adapters
+                .iter()
+                .zip(&candidates)
+                .enumerate()
+                .map(|(index, (adapter, candidate))| {
+                    let timeout = options.probe_timeout(candidate.protocol());
+                    async move { (index, probe::limit(timeout, adapter.can_handle(url)).await) }
+                })
+                .collect();
+            let mut deadline = None;
+            let mut expired = false;
+            loop {
+                if let ControlFlow::Break(winner) = Self::race_winner(&results, expired) {
+                    break winner;
+                }
+                let next = match deadline {
+                    Some(deadline) => tokio::select! {
+                        next = probes.next() => next,
+                        _ = tokio::time::sleep_until(deadline) => {
+                            expired = true;
+                            continue;
+                        }
+                    },
+                    None => probes.next().await,
+                };
+                let Some((index, result)) = next else {
+                    break None;
+                };
+                if deadline.is_none() && matches!(result, Ok(true)) {
+                    deadline = Some(tokio::time::Instant::now() + window);
+                }
+                results[index] = Some(result);
+            }
+        };
+
+        let decided = winner.unwrap_or(candidates.len());
+        for (candidate, result) in candidates.iter().zip(&results).take(decided) {
+            let note = match result {
+                Some(Ok(false)) => "did not match",
+                None => "still running after the tie-break window",
+                Some(_) => continue,
+            };
+            crate::explain::record("detection", format!("{} probe {}", candidate.name(), note));
+        }
+        let Some(index) = winner else {
+            return Ok(None);
+        };
+        let result = results[index].take().expect("decided probes have finished");
+        let candidate = candidates[index].clone();
+        result?;
+        crate::explain::record(
+            "detection",
+            format!(
+                "{} probe matched; using {}",
+                candidate.name(),
+                candidate.name()
+            ),
+        );
+        Ok(Some((candidate, adapters.swap_remove(index))))
+    }
+
+    /// Index of the probe that decides a race: the first that matched or
+    /// failed, once every probe before it did not match or, after the
+    /// tie-break window, is still running. `None` when no probe matched.
+    fn race_winner(results: &[Option<Result<bool>>], expired: bool) -> ControlFlow<Option<usize>> {
+        for (index, result) in results.iter().enumerate() {
+            match result {
+                Some(Ok(false)) => {}
+                Some(_) => return ControlFlow::Break(Some(index)),
+                None if expired => {}
+                None => return ControlFlow::Continue(()),
+            }
+        }
+        ControlFlow::Break(None)
+    }
+
+    /// Registrations in the order detection probes them: the protocols of
+    /// `[detection] order` first, then the registry by priority. Disabled
+    /// protocols are left out by the callers.
+    fn probe_order(
+        registry: &registry::Registry,
+        options: &DetectionOptions,
+    ) -> Vec<registry::Registration> {
+        let first = options
+            .order
+            .iter()
+            .copied()
+            .map(registry::Registration::of);
+        let mut candidates: Vec<registry::Registration> = Vec::new();
+        for candidate in first.chain(registry.registrations().iter().cloned()) {
+            if !candidates.iter().any(|c| c.name() == candidate.name()) {
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+
+    /// Run every probe of the [`registry`] in parallel and report what each
+    /// found, for `uxc detect --report`. Unlike detection, nothing is read
+    /// from or written to the cache.
+    pub async fn report(
+        &self,
+        url: &str,
+        options: &DetectionOptions,
+    ) -> Result<probe::DetectionReport> {
+        if let Some(plugin) = crate::plugins::for_url(url)? {
+            let name = plugin.manifest.name.clone();
+            return Ok(probe::DetectionReport {
+                url: url.to_string(),
+                probes: vec![probe::ProbeReport {
+                    adapter: name.clone(),
+                    matched: true,
+                    evidence: vec![format!("scheme served by plugin '{}'; no probes", name)],
+                    error: None,
+                    duration_ms: 0,
+                }],
+                selected: Some(name),
+            });
+        }
+        if crate::cache::is_offline() {
+            return Err(crate::cache::offline_miss(format!(
+                "probing {} needs the network",
+                url
+            )));
+        }
+
+        let mut candidates = Self::probe_order(&registry::registry(), options);
+        candidates.retain(|candidate| !options.is_disabled(candidate));
+        let probes = candidates.iter().map(|candidate| async move {
+            let adapter = candidate.adapter(options);
+            let timeout = options.probe_timeout(candidate.protocol());
+            probe::run(
+                candidate.name(),
+                probe::limit(timeout, adapter.can_handle(url)),
+            )
+            .await
+        });
+        let probes = futures::future::join_all(probes).await;
+        let selected = match options.protocol {
+            Some(protocol) => probes
+                .iter()
+                .find(|probe| probe.matched && probe.adapter == protocol.as_str()),
+            None => probes.iter().find(|probe| probe.matched),
+        }
+        .map(|probe| probe.adapter.clone());
+        Ok(probe::DetectionReport {
+            url: url.to_string(),
+            probes,
+            selected,
+        })
+    }
+
+    /// Probe every built-in protocol of the [`registry`] and return all that
+    /// answer, in detection order.
+    ///
+    /// The result is cached under [`ProtocolDetector::detection_cache_key`] and
+    /// later used to pick between protocols with `--protocol`.
+    pub async fn detect_all(
+        &self,
+        url: &str,
+        options: &DetectionOptions,
+    ) -> Result<Vec<ProtocolType>> {
+        crate::allowlist::enforce(url, None)?;
+        if crate::cache::is_offline() {
+            return Self::cached_detection(url, options).ok_or_else(|| {
+                crate::cache::offline_miss(format!("no cached detection result for {}", url))
+            });
+        }
+
+        let mut protocols = registry::registry().protocols();
+        protocols.retain(|protocol| !options.disabled.contains(protocol));
+        let probes = protocols.iter().map(|&protocol| async move {
+            let adapter = registry::builtin(protocol, options);
+            let timeout = options.probe_timeout(Some(protocol));
+            match probe::limit(timeout, adapter.can_handle(url)).await {
+                Ok(found) => found,
+                Err(e) => {
+                    debug!("{} probe of {} failed: {:#}", protocol.as_str(), url, e);
+                    false
+                }
+            }
+        });
+        let found = futures::future::join_all(probes).await;
+        let detected: Vec<ProtocolType> = protocols
+            .into_iter()
+            .zip(found)
+            .filter_map(|(protocol, found)| found.then_some(protocol))
+            .collect();
+
+        if let Some(cache) = &options.cache {
+            let names: Vec<&str> = detected.iter().map(ProtocolType::as_str).collect();
+            let result = serde_json::json!({ "protocols": names });
+            if let Err(e) = cache.put(&Self::detection_cache_key(url), &result) {
+                debug!("Failed to cache detection result: {}", e);
+            }
+        }
+        Ok(detected)
+    }
+
+    /// Adapters of every protocol [`ProtocolDetector::detect_all`] finds,
+    /// served as one [`multi::MultiAdapter`] when there are several
+    pub async fn detect_all_adapters(
+        &self,
+        url: &str,
+        options: &DetectionOptions,
+    ) -> Result<AdapterEnum> {
+        let mut adapters: Vec<AdapterEnum> = self
+            .detect_all(url, options)
+            .await?
+            .into_iter()
+            .map(|protocol| registry::builtin(protocol, options))
+            .collect();
+        let names: Vec<&str> = adapters
+            .iter()
+            .map(|adapter| adapter.protocol_type().as_str())
+            .collect();
+        crate::explain::record(
+            "detection",
+            format!("--all-protocols found: {}", names.join(", ")),
+        );
+        match adapters.len() {
+            0 => Err(UxcError::ProtocolDetectionFailed(format!(
+                "No adapter found for URL: {}",
+                url
+            ))
+            .into()),
+            1 => Ok(adapters.remove(0)),
+            _ => Ok(AdapterEnum::Multi(multi::MultiAdapter::new(adapters))),
+        }
+    }
+
+    /// Cache key of the `detect --all` result for `url`
+    pub fn detection_cache_key(url: &str) -> String {
+        format!("{}{}all", url, crate::cache::DETECTION_KEY_MARKER)
+    }
+
+    /// Cache key of the adapter detection picked for `url`
+    pub fn adapter_cache_key(url: &str) -> String {
+        format!("{}{}adapter", url, crate::cache::DETECTION_KEY_MARKER)
+    }
+
+    /// The registered adapter a previous detection picked for `url`, set up
+    /// with the schema URL or MCP endpoint it found
+    fn cached_adapter(
+        url: &str,
+        options: &DetectionOptions,
+        registry: &registry::Registry,
+    ) -> Option<(String, AdapterEnum)> {
+        let cache = options.cache.as_ref()?;
+        let crate::cache::CacheResult::Hit(entry) =
+            cache.get(&Self::adapter_cache_key(url)).ok()?
+        else {
+            return None;
+        };
+        let name = entry.get("adapter")?.as_str()?;
+        let registration = registry
+            .registrations()
+            .iter()
+            .find(|registration| registration.name() == name)
+            .filter(|registration| !options.is_disabled(registration))?;
+        let mut adapter = registration.adapter(options);
+        // `--schema-url` wins over the schema found before
+        if let (Some(location), None) = (entry.get("location"), &options.schema_url) {
+            adapter = adapter.with_discovered_location(url, location.as_str()?.to_string());
+        }
+        Some((name.to_string(), adapter))
+    }
+
+    /// Cache the adapter detection picked for `url` and where it found the
+    /// endpoint, so later detections skip the probes
+    async fn remember_adapter(
+        url: &str,
+        name: &str,
+        adapter: &AdapterEnum,
+        options: &DetectionOptions,
+    ) {
+        // Commands are recognised without probes
+        let Some(cache) = options
+            .cache
+            .as_ref()
+            .filter(|_| !mcp::McpAdapter::is_stdio_command(url))
+        else {
+            return;
+        };
+        let mut entry = serde_json::json!({ "adapter": name });
+        if let Some(location) = adapter.discovered_location(url).await {
+            entry["location"] = Value::String(location);
+        }
+        if let Err(e) = cache.put(&Self::adapter_cache_key(url), &entry) {
+            debug!("Failed to cache detection result: {}", e);
+        }
+    }
+
+    fn cached_detection(url: &str, options: &DetectionOptions) -> Option<Vec<ProtocolType>> {
+        let cache = options.cache.as_ref()?;
+        let crate::cache::CacheResult::Hit(result) =
+            cache.get(&Self::detection_cache_key(url)).ok()?
+        else {
+            return None;
+        };
+        Some(
+            result
+                .get("protocols")?
+                .as_array()?
+                .iter()
+                .filter_map(|name| ProtocolType::parse(name.as_str()?))
+                .collect(),
+        )
+    }
+
+    fn not_detected(url: &str, protocol: ProtocolType, detected: &[ProtocolType]) -> UxcError {
+        let names: Vec<&str> = detected.iter().map(ProtocolType::as_str).collect();
+        UxcError::ProtocolDetectionFailed(format!(
+            "{} was not detected at {} (detected: {})",
+            protocol.as_str(),
+            url,
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        ))
+    }
+
+    /// Offline or `--as-of` detection: the first adapter with a stored schema
+    /// for the URL.
+    async fn detect_cached_adapter(
+        &self,
+        url: &str,
+        options: &DetectionOptions,
+    ) -> Result<AdapterEnum> {
+        if mcp::McpAdapter::is_stdio_command(url) {
+            crate::explain::record("detection", "offline: stdio command run as mcp");
+            return Ok(AdapterEnum::Mcp(mcp::McpAdapter::new()));
+        }
+        let Some(cache) = options.cache.clone() else {
+            return Err(crate::cache::stored_schema_miss(
+                None,
+                format!("schema for {} (no schema cache available)", url),
+            ));
+        };
+
+        let candidates = vec![
+            AdapterEnum::OpenAPI(
+                openapi::OpenAPIAdapter::new()
+                    .with_schema_url_override(options.schema_url.clone())
+                    .with_server(options.server.clone())
+                    .with_cache(cache.clone()),
+            ),
+            AdapterEnum::GraphQL(
+                graphql::GraphQLAdapter::new()
+                    .with_schema_url(options.schema_url.clone())
+                    .with_selection(options.selection.clone())
+                    .with_persisted_queries(options.persisted_queries.clone())
+                    .with_cache(cache.clone()),
+            ),
+            AdapterEnum::JsonRpc(
+                jsonrpc::JsonRpcAdapter::new()
+                    .with_version(options.jsonrpc_version)
+                    .with_cache(cache.clone()),
+            ),
+            AdapterEnum::GRpc(
+                grpc::GrpcAdapter::new()
+                    .with_connection_config(options.grpc.clone())
+                    .with_cache(cache.clone()),
+            ),
+            AdapterEnum::Mcp(mcp::McpAdapter::new().with_cache(cache.clone())),
+        ];
+        let candidates = candidates.into_iter().filter(|adapter| {
+            options
+                .protocol
+                .is_none_or(|p| p == adapter.protocol_type())
+        });
+        let store = if cache.as_of().is_some() {
+            "as-of: {} chosen because its schema has a snapshot"
+        } else {
+            "offline: {} chosen because its schema is cached"
+        };
+        for adapter in candidates {
+            if adapter.fetch_schema(url).await.is_ok() {
+                crate::explain::record(
+                    "detection",
+                    store.replace("{}", adapter.protocol_type().as_str()),
+                );
+                return Ok(adapter);
+            }
+        }
+
+        let earliest = cache
+            .snapshots()
+            .filter(|_| cache.as_of().is_some())
+            .and_then(|snapshots| snapshots.earliest(url));
+        Err(crate::cache::stored_schema_miss(
+            Some(&cache),
+            match earliest {
+                Some(earliest) => format!("schema for {} (the earliest is from {})", url, earliest),
+                None => format!("schema for {}", url),
+            },
+        ))
+    }
+}
+
+impl Default for ProtocolDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
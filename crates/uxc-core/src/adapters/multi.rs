@@ -0,0 +1,125 @@
+//! Hosts that speak several protocols
+//!
+//! Some hosts serve REST next to GraphQL or MCP. With `--all-protocols`,
+//! detection keeps every adapter that matched and [`MultiAdapter`] serves
+//! them as one: operation IDs carry the protocol as a prefix
+//! (`openapi:get:/users`, `graphql:query/users`), and calls are routed to
+//! the adapter it names.
+
+use super::{Adapter, AdapterEnum, Operation, OperationDetail, ProtocolType};
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+/// Adapters of one host, in detection order
+pub struct MultiAdapter {
+    adapters: Vec<AdapterEnum>,
+}
+
+impl MultiAdapter {
+    pub fn new(adapters: Vec<AdapterEnum>) -> Self {
+        Self { adapters }
+    }
+
+    pub fn adapters(&self) -> &[AdapterEnum] {
+        &self.adapters
+    }
+
+    /// Protocols of the adapters, in detection order
+    pub fn protocols(&self) -> Vec<ProtocolType> {
+        self.adapters.iter().map(Adapter::protocol_type).collect()
+    }
+
+    /// `operation` of `protocol`, namespaced
+    pub fn operation_id(protocol: ProtocolType, operation: &str) -> String {
+        format!("{}:{}", protocol.as_str(), operation)
+    }
+
+    /// The adapter named by the prefix of `operation`, and the operation ID
+    /// without it
+    pub fn route<'o>(&self, operation: &'o str) -> Result<(&AdapterEnum, &'o str)> {
+        let routed = operation.split_once(':').and_then(|(prefix, rest)| {
+            let protocol = ProtocolType::parse(prefix)?;
+            let adapter = self
+                .adapters
+                .iter()
+                .find(|adapter| adapter.protocol_type() == protocol)?;
+            Some((adapter, rest))
+        });
+        routed.ok_or_else(|| {
+            let prefixes: Vec<String> = self
+                .protocols()
+                .iter()
+                .map(|protocol| format!("{}:", protocol.as_str()))
+                .collect();
+            UxcError::OperationNotFound(format!(
+                "Operation '{}' does not start with the protocol serving it ({})",
+                operation,
+                prefixes.join(", ")
+            ))
+            .into()
+        })
+    }
+
+    pub(crate) fn map(self, f: impl Fn(AdapterEnum) -> AdapterEnum) -> Self {
+        Self {
+            adapters: self.adapters.into_iter().map(f).collect(),
+        }
+    }
+
+    pub(crate) async fn can_handle(&self, url: &str) -> Result<bool> {
+        for adapter in &self.adapters {
+            if adapter.can_handle(url).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Schemas of the adapters, keyed by protocol
+    pub(crate) async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        let mut schemas = Map::new();
+        for adapter in &self.adapters {
+            schemas.insert(
+                adapter.protocol_type().as_str().to_string(),
+                adapter.fetch_schema(url).await?,
+            );
+        }
+        Ok(Value::Object(schemas))
+    }
+
+    /// Operations of every adapter, namespaced by protocol
+    pub(crate) async fn list_operations(&self, url: &str) -> Result<Vec<Operation>> {
+        let lists = futures::future::try_join_all(
+            self.adapters
+                .iter()
+                .map(|adapter| adapter.list_operations(url)),
+        )
+        .await?;
+        let mut operations = Vec::new();
+        for (adapter, list) in self.adapters.iter().zip(lists) {
+            let protocol = adapter.protocol_type();
+            operations.extend(list.into_iter().map(|operation| Operation {
+                operation_id: Self::operation_id(protocol, &operation.operation_id),
+                display_name: Self::operation_id(protocol, &operation.display_name),
+                ..operation
+            }));
+        }
+        Ok(operations)
+    }
+
+    pub(crate) async fn describe_operation(
+        &self,
+        url: &str,
+        operation: &str,
+    ) -> Result<OperationDetail> {
+        let (adapter, routed) = self.route(operation)?;
+        let protocol = adapter.protocol_type();
+        let detail = adapter.describe_operation(url, routed).await?;
+        Ok(OperationDetail {
+            operation_id: Self::operation_id(protocol, &detail.operation_id),
+            display_name: Self::operation_id(protocol, &detail.display_name),
+            ..detail
+        })
+    }
+}
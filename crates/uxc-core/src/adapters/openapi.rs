@@ -0,0 +1,1764 @@
+//! OpenAPI/Swagger adapter
+
+mod params;
+mod servers;
+mod xml;
+
+pub use servers::ServerSelection;
+
+use super::{
+    probe, Adapter, Capabilities, CapabilitySet, ExecutionMetadata, ExecutionResult, Operation,
+    OperationDetail, Parameter, ProtocolType, RawResponse, RequestPlan,
+};
+use crate::auth::scheme::SecurityScheme;
+use crate::auth::{AuthType, Profile};
+use crate::error::{ErrorDetail, UxcError};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+pub struct OpenAPIAdapter {
+    client: reqwest::Client,
+    cache: Option<Arc<dyn crate::cache::Cache>>,
+    auth_profile: Option<Profile>,
+    discovered_schema_urls: Arc<RwLock<HashMap<String, String>>>,
+    schema_url_override: Option<String>,
+    /// Paths probed before [`Self::SCHEMA_ENDPOINTS`]
+    schema_paths: Vec<String>,
+    /// Server calls go to instead of the endpoint URL
+    server: Option<ServerSelection>,
+}
+
+impl OpenAPIAdapter {
+    const MAX_SCHEMA_EXPANSION_DEPTH: usize = 8;
+    const SCHEMA_ENDPOINTS: [&'static str; 7] = [
+        "/openapi.json",
+        "/swagger.json",
+        "/api-docs",
+        "/swagger/v1/swagger.json",
+        "/api/docs",
+        "/docs/swagger.json",
+        "/swagger-docs",
+    ];
+    const HTTP_METHODS: [&'static str; 8] = [
+        "get", "post", "put", "patch", "delete", "head", "options", "trace",
+    ];
+
+    pub fn new() -> Self {
+        Self {
+            client: crate::http::client(),
+            cache: None,
+            auth_profile: None,
+            discovered_schema_urls: Arc::new(RwLock::new(HashMap::new())),
+            schema_url_override: None,
+            schema_paths: Vec::new(),
+            server: None,
+        }
+    }
+
+    pub fn with_cache(mut self, cache: Arc<dyn crate::cache::Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn with_auth(mut self, profile: Profile) -> Self {
+        self.auth_profile = Some(profile);
+        self
+    }
+
+    pub fn with_schema_url_override(mut self, schema_url: Option<String>) -> Self {
+        self.schema_url_override = schema_url;
+        self
+    }
+
+    /// Send calls to a server the schema lists (or any URL) instead of the
+    /// endpoint URL; `None` keeps the endpoint URL
+    pub fn with_server(mut self, server: Option<ServerSelection>) -> Self {
+        self.server = server;
+        self
+    }
+
+    /// Also probe `paths` of endpoints for their schema, before the
+    /// well-known ones
+    pub fn with_schema_paths(mut self, paths: Vec<String>) -> Self {
+        self.schema_paths = paths;
+        self
+    }
+
+    /// Use `schema_url`, found by an earlier detection, as the schema of `url`
+    pub fn with_discovered_schema_url(self, url: &str, schema_url: String) -> Self {
+        if let Ok(mut discovered) = self.discovered_schema_urls.try_write() {
+            discovered.insert(Self::normalized_url(url), schema_url);
+        }
+        self
+    }
+
+    /// Schema URL of `url`, once it was discovered
+    pub async fn discovered_schema_url(&self, url: &str) -> Option<String> {
+        let discovered = self.discovered_schema_urls.read().await;
+        discovered.get(&Self::normalized_url(url)).cloned()
+    }
+
+    fn normalized_url(url: &str) -> String {
+        url.trim_end_matches('/').to_string()
+    }
+
+    fn schema_cache_key(url: &str, schema_url: &str) -> String {
+        format!("{}#schema={}", Self::normalized_url(url), schema_url)
+    }
+
+    fn conditional_get(
+        client: &reqwest::Client,
+        schema_url: &str,
+        validators: &crate::cache::CacheValidators,
+    ) -> reqwest::RequestBuilder {
+        let mut request = client.get(schema_url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+
+    /// Revalidate a cached schema and store the result
+    async fn refresh_schema(
+        client: &reqwest::Client,
+        cache: &dyn crate::cache::Cache,
+        schema_url: &str,
+        cache_key: &str,
+        validators: &crate::cache::CacheValidators,
+    ) -> Result<()> {
+        let resp = crate::http::send(Self::conditional_get(client, schema_url, validators)).await?;
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return cache.refresh(cache_key);
+        }
+
+        let resp = resp.error_for_status()?;
+        let validators = Self::response_validators(resp.headers());
+        let schema = Self::read_document(resp).await?;
+        cache.put_with_validators(cache_key, &schema, &validators)?;
+        info!("Refreshed cached OpenAPI schema for: {}", cache_key);
+        Ok(())
+    }
+
+    fn response_validators(headers: &reqwest::header::HeaderMap) -> crate::cache::CacheValidators {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+        crate::cache::CacheValidators {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    fn schema_candidates(url: &str, paths: &[String]) -> Vec<String> {
+        let normalized = Self::normalized_url(url);
+        if Self::SCHEMA_ENDPOINTS
+            .iter()
+            .copied()
+            .chain(paths.iter().map(String::as_str))
+            .any(|endpoint| normalized.ends_with(endpoint))
+        {
+            return vec![normalized];
+        }
+
+        let mut candidates = Vec::new();
+        for endpoint in Self::SCHEMA_ENDPOINTS {
+            candidates.push(format!("{}{}", normalized, endpoint));
+        }
+
+        candidates.sort();
+        candidates.dedup();
+        let mut configured: Vec<String> = Vec::new();
+        for path in paths {
+            let candidate = format!("{}{}", normalized, path);
+            if !configured.contains(&candidate) {
+                configured.push(candidate);
+            }
+        }
+        candidates.retain(|candidate| !configured.contains(candidate));
+        configured.extend(candidates);
+        configured
+    }
+
+    /// Schema document of a response, in JSON or YAML
+    async fn read_document(response: reqwest::Response) -> Result<Value> {
+        let text = response.text().await?;
+        match serde_json::from_str(&text) {
+            Ok(document) => Ok(document),
+            Err(err) => match serde_yaml::from_str::<Value>(&text) {
+                Ok(document) if document.is_object() => Ok(document),
+                _ => Err(err.into()),
+            },
+        }
+    }
+
+    fn is_openapi_document(body: &Value) -> bool {
+        body.get("openapi").is_some() || body.get("swagger").is_some()
+    }
+
+    async fn check_schema_url(&self, schema_url: &str) -> Result<bool> {
+        let response = crate::http::send(
+            self.client
+                .get(schema_url)
+                .timeout(probe::timeout(std::time::Duration::from_secs(10)))
+                .header("Accept", "application/json"),
+        )
+        .await
+        .inspect_err(|e| probe::evidence(format!("GET {}: {:#}", schema_url, e)))?;
+
+        if !response.status().is_success() {
+            probe::evidence(format!("GET {}: HTTP {}", schema_url, response.status()));
+            return Ok(false);
+        }
+
+        let body = Self::read_document(response).await?;
+        let is_openapi = Self::is_openapi_document(&body);
+        Self::note_document(schema_url, is_openapi);
+        Ok(is_openapi)
+    }
+
+    fn note_document(schema_url: &str, is_openapi: bool) {
+        probe::evidence(if is_openapi {
+            format!("GET {}: OpenAPI document", schema_url)
+        } else {
+            format!("GET {}: not an OpenAPI document", schema_url)
+        });
+    }
+
+    fn is_http_method(method: &str) -> bool {
+        Self::HTTP_METHODS.contains(&method)
+    }
+
+    fn operation_id(method: &str, path: &str) -> String {
+        format!("{}:{}", method.to_lowercase(), path)
+    }
+
+    fn display_name(method: &str, path: &str) -> String {
+        format!("{} {}", method.to_uppercase(), path)
+    }
+
+    fn parse_operation_id(operation_id: &str) -> Result<(String, String)> {
+        let (method, path) = operation_id.split_once(':').ok_or_else(|| {
+            UxcError::InvalidArguments(
+                "Invalid operation ID format. Use 'method:/path'".to_string(),
+            )
+        })?;
+
+        if method.is_empty() || path.is_empty() || !path.starts_with('/') {
+            return Err(UxcError::InvalidArguments(
+                "Invalid operation ID format. Use 'method:/path'".to_string(),
+            )
+            .into());
+        }
+
+        let method = method.to_lowercase();
+        if !Self::is_http_method(&method) {
+            return Err(UxcError::InvalidArguments(format!(
+                "Unsupported HTTP method in operation ID: {}",
+                method
+            ))
+            .into());
+        }
+
+        Ok((method, path.to_string()))
+    }
+
+    /// Offline or `--as-of` lookup: the first schema URL candidate with a
+    /// stored schema.
+    async fn cached_schema(&self, url: &str) -> Result<Value> {
+        let normalized = Self::normalized_url(url);
+        let mut candidates = Vec::new();
+        if let Some(discovered) = self.discovered_schema_urls.read().await.get(&normalized) {
+            candidates.push(discovered.clone());
+        }
+        candidates.extend(self.schema_url_override.clone());
+        if let Some(mapping) = crate::schema_mapping::resolve_openapi_schema_mapping(&normalized) {
+            candidates.push(mapping.schema_url);
+        }
+        candidates.extend(Self::schema_candidates(&normalized, &self.schema_paths));
+
+        for schema_url in candidates {
+            let cache_key = Self::schema_cache_key(url, &schema_url);
+            if let Ok(schema) = crate::cache::cached_schema(
+                self.cache.as_ref(),
+                &cache_key,
+                Self::is_openapi_document,
+            ) {
+                debug!("OpenAPI schema served offline from: {}", cache_key);
+                let mut discovered = self.discovered_schema_urls.write().await;
+                discovered.insert(normalized, schema_url);
+                return Ok(schema);
+            }
+        }
+
+        Err(crate::cache::stored_schema_miss(
+            self.cache.as_ref(),
+            format!("OpenAPI schema for {}", url),
+        ))
+    }
+
+    /// A schema URL candidate whose schema is cached and unexpired, so a new
+    /// adapter need not probe the endpoint again
+    fn fresh_cached_schema_url(&self, normalized: &str) -> Option<String> {
+        let cache = self.cache.as_ref()?;
+        let mut candidates: Vec<String> = self.schema_url_override.iter().cloned().collect();
+        if self.schema_url_override.is_none() {
+            candidates.extend(
+                crate::schema_mapping::resolve_openapi_schema_mapping(normalized)
+                    .map(|mapping| mapping.schema_url),
+            );
+            candidates.extend(Self::schema_candidates(normalized, &self.schema_paths));
+        }
+        candidates.into_iter().find(|schema_url| {
+            matches!(
+                cache.get_stale(&Self::schema_cache_key(normalized, schema_url)),
+                Ok(Some(entry)) if !entry.is_expired()
+            )
+        })
+    }
+
+    async fn discover_schema_url(&self, url: &str) -> Result<Option<String>> {
+        let normalized = Self::normalized_url(url);
+        {
+            let cache = self.discovered_schema_urls.read().await;
+            if let Some(discovered) = cache.get(&normalized) {
+                return Ok(Some(discovered.clone()));
+            }
+        }
+
+        if let Some(schema_url) = self.fresh_cached_schema_url(&normalized) {
+            debug!("OpenAPI schema URL known from the cache: {}", schema_url);
+            probe::evidence(format!("schema {} is in the schema cache", schema_url));
+            let mut cache = self.discovered_schema_urls.write().await;
+            cache.insert(normalized, schema_url.clone());
+            return Ok(Some(schema_url));
+        }
+
+        if let Some(schema_url) = &self.schema_url_override {
+            crate::explain::record(
+                "schema",
+                format!("schema URL {} from --schema-url", schema_url),
+            );
+            let is_openapi = self.check_schema_url(schema_url).await.with_context(|| {
+                format!(
+                    "Failed to fetch OpenAPI schema from --schema-url '{}'",
+                    schema_url
+                )
+            })?;
+            if !is_openapi {
+                return Err(anyhow::anyhow!(
+                    "Schema URL does not contain an OpenAPI document: {}",
+                    schema_url
+                ));
+            }
+            let mut cache = self.discovered_schema_urls.write().await;
+            cache.insert(normalized, schema_url.clone());
+            return Ok(Some(schema_url.clone()));
+        }
+
+        if let Some(mapping) = crate::schema_mapping::resolve_openapi_schema_mapping(&normalized) {
+            match self.check_schema_url(&mapping.schema_url).await {
+                Ok(true) => {
+                    crate::explain::record(
+                        "schema",
+                        format!("schema URL {} from the schema mapping", mapping.schema_url),
+                    );
+                    info!(
+                        "Resolved OpenAPI schema via {}: {} -> {}",
+                        mapping.source.as_str(),
+                        normalized,
+                        mapping.schema_url
+                    );
+                    let mut cache = self.discovered_schema_urls.write().await;
+                    cache.insert(normalized, mapping.schema_url.clone());
+                    return Ok(Some(mapping.schema_url));
+                }
+                Ok(false) => {
+                    debug!(
+                        "Mapped schema URL did not contain OpenAPI document: {}",
+                        mapping.schema_url
+                    );
+                }
+                Err(err) => {
+                    debug!(
+                        "Failed to fetch mapped schema URL '{}': {}",
+                        mapping.schema_url, err
+                    );
+                }
+            }
+        }
+
+        for full_url in Self::schema_candidates(&normalized, &self.schema_paths) {
+            let resp = match crate::http::send(
+                self.client
+                    .get(&full_url)
+                    .timeout(probe::timeout(std::time::Duration::from_secs(2)))
+                    .header("Accept", "application/json"),
+            )
+            .await
+            {
+                Ok(r) => r,
+                Err(e) => {
+                    probe::evidence(format!("GET {}: {:#}", full_url, e));
+                    continue;
+                }
+            };
+
+            if !resp.status().is_success() {
+                probe::evidence(format!("GET {}: HTTP {}", full_url, resp.status()));
+                continue;
+            }
+
+            if let Ok(body) = Self::read_document(resp).await {
+                let is_openapi = Self::is_openapi_document(&body);
+                Self::note_document(&full_url, is_openapi);
+                if is_openapi {
+                    crate::explain::record(
+                        "schema",
+                        format!("schema URL {} found by probing well-known paths", full_url),
+                    );
+                    let mut cache = self.discovered_schema_urls.write().await;
+                    cache.insert(normalized, full_url.clone());
+                    return Ok(Some(full_url));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn resolve_local_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+        if !reference.starts_with("#/") {
+            return None;
+        }
+        root.pointer(&reference[1..])
+    }
+
+    fn dereference_value<'a>(value: &'a Value, root: &'a Value) -> &'a Value {
+        let mut current = value;
+        for _ in 0..Self::MAX_SCHEMA_EXPANSION_DEPTH {
+            let Some(reference) = current.get("$ref").and_then(|v| v.as_str()) else {
+                break;
+            };
+            let Some(resolved) = Self::resolve_local_ref(root, reference) else {
+                break;
+            };
+            current = resolved;
+        }
+        current
+    }
+
+    fn schema_type_hint(schema: &Value, root: &Value) -> String {
+        let resolved = Self::dereference_value(schema, root);
+        if let Some(type_name) = resolved.get("type").and_then(|t| t.as_str()) {
+            return type_name.to_string();
+        }
+        if resolved.get("properties").is_some()
+            || resolved.get("allOf").is_some()
+            || resolved.get("oneOf").is_some()
+            || resolved.get("anyOf").is_some()
+        {
+            return "object".to_string();
+        }
+        if resolved.get("items").is_some() {
+            return "array".to_string();
+        }
+        "string".to_string()
+    }
+
+    fn parse_parameter(parameter: &Value, root: &Value) -> Option<Parameter> {
+        let resolved = Self::dereference_value(parameter, root);
+        let name = resolved.get("name").and_then(|n| n.as_str())?;
+        let param_type = resolved
+            .get("schema")
+            .map(|schema| Self::schema_type_hint(schema, root))
+            .or_else(|| {
+                if resolved.get("content").is_some() {
+                    Some("object".to_string())
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_else(|| "string".to_string());
+
+        Some(Parameter {
+            name: name.to_string(),
+            param_type,
+            required: resolved
+                .get("required")
+                .and_then(|r| r.as_bool())
+                .unwrap_or(false),
+            description: resolved
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(|s| s.to_string()),
+            location: resolved
+                .get("in")
+                .and_then(|l| l.as_str())
+                .map(|s| s.to_string()),
+        })
+    }
+
+    fn collect_parameters(
+        path_item: &Value,
+        operation_spec: &Value,
+        root: &Value,
+    ) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut seen = HashSet::new();
+
+        for source in [
+            path_item.get("parameters").and_then(|p| p.as_array()),
+            operation_spec.get("parameters").and_then(|p| p.as_array()),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for parameter in source {
+                let resolved = Self::dereference_value(parameter, root);
+                let key = format!(
+                    "{}:{}",
+                    resolved.get("in").and_then(|v| v.as_str()).unwrap_or(""),
+                    resolved.get("name").and_then(|v| v.as_str()).unwrap_or("")
+                );
+                if seen.contains(&key) {
+                    continue;
+                }
+                if let Some(parsed) = Self::parse_parameter(parameter, root) {
+                    seen.insert(key);
+                    parameters.push(parsed);
+                }
+            }
+        }
+
+        parameters
+    }
+
+    fn expand_schema(
+        value: &Value,
+        root: &Value,
+        visited: &mut HashSet<String>,
+        depth: usize,
+    ) -> Value {
+        if depth == 0 {
+            return value.clone();
+        }
+
+        match value {
+            Value::Object(object) => {
+                if let Some(reference) = object.get("$ref").and_then(|v| v.as_str()) {
+                    if !visited.insert(reference.to_string()) {
+                        return serde_json::json!({ "$ref": reference });
+                    }
+
+                    let expanded_target = Self::resolve_local_ref(root, reference)
+                        .map(|target| Self::expand_schema(target, root, visited, depth - 1))
+                        .unwrap_or_else(|| value.clone());
+                    visited.remove(reference);
+
+                    if object.len() == 1 {
+                        return expanded_target;
+                    }
+
+                    if let Value::Object(mut merged) = expanded_target {
+                        for (key, nested) in object {
+                            if key == "$ref" {
+                                continue;
+                            }
+                            merged.insert(
+                                key.clone(),
+                                Self::expand_schema(nested, root, visited, depth - 1),
+                            );
+                        }
+                        return Value::Object(merged);
+                    }
+
+                    let mut merged = Map::new();
+                    merged.insert("allOf".to_string(), Value::Array(vec![expanded_target]));
+                    for (key, nested) in object {
+                        if key == "$ref" {
+                            continue;
+                        }
+                        merged.insert(
+                            key.clone(),
+                            Self::expand_schema(nested, root, visited, depth - 1),
+                        );
+                    }
+                    return Value::Object(merged);
+                }
+
+                let mut expanded = Map::new();
+                for (key, nested) in object {
+                    expanded.insert(
+                        key.clone(),
+                        Self::expand_schema(nested, root, visited, depth - 1),
+                    );
+                }
+                Value::Object(expanded)
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| Self::expand_schema(item, root, visited, depth - 1))
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
+
+    fn extract_request_body_input_schema(operation_spec: &Value, root: &Value) -> Option<Value> {
+        let request_body_raw = operation_spec.get("requestBody")?;
+        let request_body = Self::dereference_value(request_body_raw, root);
+        let content = request_body.get("content")?.as_object()?;
+
+        let mut content_map = Map::new();
+        for (media_type, media_spec) in content {
+            let Some(schema) = media_spec.get("schema") else {
+                continue;
+            };
+
+            let source_ref = schema
+                .get("$ref")
+                .and_then(|r| r.as_str())
+                .map(|s| s.to_string());
+            let expanded_schema = Self::expand_schema(
+                schema,
+                root,
+                &mut HashSet::new(),
+                Self::MAX_SCHEMA_EXPANSION_DEPTH,
+            );
+
+            let mut media_obj = Map::new();
+            media_obj.insert("schema".to_string(), expanded_schema);
+            if let Some(reference) = source_ref {
+                media_obj.insert("source_ref".to_string(), Value::String(reference));
+            }
+            if let Some(example) = media_spec.get("example") {
+                media_obj.insert("example".to_string(), example.clone());
+            }
+            content_map.insert(media_type.clone(), Value::Object(media_obj));
+        }
+        if content_map.is_empty() {
+            return None;
+        }
+
+        let mut body = Map::new();
+        body.insert(
+            "kind".to_string(),
+            Value::String("openapi_request_body".to_string()),
+        );
+        body.insert(
+            "required".to_string(),
+            Value::Bool(
+                request_body
+                    .get("required")
+                    .and_then(|r| r.as_bool())
+                    .unwrap_or(false),
+            ),
+        );
+        if let Some(description) = request_body.get("description").and_then(|d| d.as_str()) {
+            body.insert(
+                "description".to_string(),
+                Value::String(description.to_string()),
+            );
+        }
+        body.insert("content".to_string(), Value::Object(content_map));
+        Some(Value::Object(body))
+    }
+
+    /// Documented responses by status code, each with its description and
+    /// JSON schema (references expanded)
+    fn extract_responses(operation_spec: &Value, root: &Value) -> Option<Value> {
+        let responses = operation_spec.get("responses")?.as_object()?;
+        let mut documented = Map::new();
+        for (status, response) in responses {
+            let response = Self::dereference_value(response, root);
+            let mut entry = Map::new();
+            if let Some(description) = response.get("description").and_then(|d| d.as_str()) {
+                entry.insert(
+                    "description".to_string(),
+                    Value::String(description.to_string()),
+                );
+            }
+            if let Some(schema) = Self::response_schema(response) {
+                entry.insert(
+                    "schema".to_string(),
+                    Self::expand_schema(
+                        schema,
+                        root,
+                        &mut HashSet::new(),
+                        Self::MAX_SCHEMA_EXPANSION_DEPTH,
+                    ),
+                );
+            }
+            documented.insert(status.clone(), Value::Object(entry));
+        }
+        Some(Value::Object(documented))
+    }
+
+    /// Security requirements of an operation (its own `security`, else the
+    /// document's) with the schemes they name; `None` when neither says
+    fn operation_security(
+        operation_spec: &Value,
+        root: &Value,
+    ) -> Option<Vec<Vec<SecurityScheme>>> {
+        let security = operation_spec
+            .get("security")
+            .or_else(|| root.get("security"))?
+            .as_array()?;
+        let schemes = security_schemes(root);
+        let requirements = security
+            .iter()
+            .filter_map(Value::as_object)
+            .map(|requirement| {
+                requirement
+                    .iter()
+                    .filter_map(|(name, scopes)| {
+                        let declaration = Self::dereference_value(schemes?.get(name)?, root);
+                        let scopes = scopes
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Value::as_str)
+                            .map(ToString::to_string)
+                            .collect();
+                        Some(SecurityScheme::from_openapi(name, declaration, scopes))
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(requirements)
+    }
+
+    /// Schema of the first documented 2xx response
+    fn success_schema<'a>(operation_spec: &'a Value, root: &'a Value) -> Option<&'a Value> {
+        let responses = operation_spec.get("responses")?.as_object()?;
+        let mut statuses: Vec<&String> = responses
+            .keys()
+            .filter(|status| status.starts_with('2'))
+            .collect();
+        statuses.sort();
+        statuses.into_iter().find_map(|status| {
+            Self::response_schema(Self::dereference_value(&responses[status], root))
+        })
+    }
+
+    /// Schema of a response: Swagger 2 `schema`, or the schema of the JSON
+    /// media type (else the first one) of OpenAPI 3 `content`
+    fn response_schema(response: &Value) -> Option<&Value> {
+        if let Some(schema) = response.get("schema") {
+            return Some(schema);
+        }
+        let content = response.get("content")?.as_object()?;
+        content
+            .iter()
+            .find(|(media_type, _)| media_type.contains("json"))
+            .or_else(|| content.iter().next())
+            .and_then(|(_, media)| media.get("schema"))
+    }
+}
+
+impl Default for OpenAPIAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Capabilities for OpenAPIAdapter {
+    const PROTOCOL: ProtocolType = ProtocolType::OpenAPI;
+
+    fn capabilities() -> CapabilitySet {
+        CapabilitySet {
+            streaming: false,
+            batch: false,
+            validation: false,
+            auth_types: vec![
+                AuthType::Bearer,
+                AuthType::ApiKey,
+                AuthType::Basic,
+                AuthType::Session,
+                AuthType::OAuth,
+            ],
+            schema_export: true,
+            schema_cache: true,
+            raw_body: true,
+            notes: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Adapter for OpenAPIAdapter {
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::OpenAPI
+    }
+
+    async fn can_handle(&self, url: &str) -> Result<bool> {
+        Ok(self.discover_schema_url(url).await?.is_some())
+    }
+
+    async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        if crate::cache::schemas_from_store(self.cache.as_ref()) {
+            return self.cached_schema(url).await;
+        }
+
+        let schema_url = self
+            .discover_schema_url(url)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("OpenAPI schema endpoint not found for {}", url))?;
+
+        let cache_key = Self::schema_cache_key(url, &schema_url);
+
+        // Try cache first if available; keep an expired copy for revalidation
+        let mut stale = None;
+        if let Some(cache) = &self.cache {
+            let cached = cache.get(&cache_key)?;
+            crate::cache::record_lookup(ProtocolType::OpenAPI, &cache_key, &cached);
+            match cached {
+                crate::cache::CacheResult::Hit(schema) => {
+                    debug!("OpenAPI cache hit for: {}", cache_key);
+                    return Ok(schema);
+                }
+                crate::cache::CacheResult::Bypassed => {
+                    debug!("OpenAPI cache bypassed for: {}", cache_key);
+                }
+                crate::cache::CacheResult::Miss => {
+                    debug!("OpenAPI cache miss for: {}", cache_key);
+                    stale = cache.get_stale(&cache_key)?;
+                }
+            }
+
+            // Serve the expired copy now and refresh it in the background
+            if let (Some(entry), true) = (&stale, cache.stale_while_revalidate()) {
+                debug!("Serving stale OpenAPI schema for: {}", cache_key);
+                crate::explain::record(
+                    "cache",
+                    "expired OpenAPI schema served: stale-while-revalidate is on; refreshing in the background",
+                );
+                crate::cache::mark_served_stale();
+                let client = self.client.clone();
+                let cache = cache.clone();
+                let schema_url = schema_url.clone();
+                let cache_key = cache_key.clone();
+                let validators = entry.validators();
+                crate::cache::spawn_refresh(async move {
+                    if let Err(e) = Self::refresh_schema(
+                        &client,
+                        cache.as_ref(),
+                        &schema_url,
+                        &cache_key,
+                        &validators,
+                    )
+                    .await
+                    {
+                        debug!("Background OpenAPI schema refresh failed: {}", e);
+                    }
+                });
+                return Ok(entry.schema.clone());
+            }
+        }
+
+        // Fetch from remote, conditionally when the stale copy has validators
+        let validators = stale
+            .as_ref()
+            .map(|entry| entry.validators())
+            .unwrap_or_default();
+        let result = crate::http::send(Self::conditional_get(
+            &self.client,
+            &schema_url,
+            &validators,
+        ))
+        .await;
+        let failure = match &result {
+            Ok(resp) if resp.status().is_server_error() => Some(format!("HTTP {}", resp.status())),
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        if let (Some(failure), Some(entry)) = (failure, stale.as_ref()) {
+            warn!(
+                "Schema fetch from {} failed ({}), using stale cached copy",
+                schema_url, failure
+            );
+            return Ok(entry.schema.clone());
+        }
+        let resp = result?;
+
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let (Some(cache), Some(entry)) = (&self.cache, stale) {
+                debug!("OpenAPI schema not modified: {}", cache_key);
+                if let Err(e) = cache.refresh(&cache_key) {
+                    debug!("Failed to refresh cached OpenAPI schema: {}", e);
+                }
+                return Ok(entry.schema);
+            }
+        }
+
+        let validators = Self::response_validators(resp.headers());
+        let schema = Self::read_document(resp).await?;
+
+        // Store in cache if available
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put_with_validators(&cache_key, &schema, &validators) {
+                debug!("Failed to cache OpenAPI schema: {}", e);
+            } else {
+                info!("Cached OpenAPI schema for: {}", cache_key);
+            }
+        }
+
+        Ok(schema)
+    }
+
+    async fn list_operations(&self, url: &str) -> Result<Vec<Operation>> {
+        let schema = self.fetch_schema(url).await?;
+        let mut operations = Vec::new();
+
+        if let Some(paths) = schema.get("paths").and_then(|p| p.as_object()) {
+            for (path, methods) in paths {
+                if let Some(methods_obj) = methods.as_object() {
+                    for (method, spec) in methods_obj {
+                        let method = method.to_lowercase();
+                        if !Self::is_http_method(&method) {
+                            continue;
+                        }
+
+                        let operation_id = Self::operation_id(&method, path);
+                        let display_name = Self::display_name(&method, path);
+                        let parameters = Self::collect_parameters(methods, spec, &schema);
+
+                        operations.push(Operation {
+                            operation_id,
+                            display_name,
+                            description: spec
+                                .get("description")
+                                .or(spec.get("summary"))
+                                .and_then(|d| d.as_str())
+                                .map(|s| s.to_string()),
+                            parameters,
+                            return_type: None,
+                            tags: spec
+                                .get("tags")
+                                .and_then(Value::as_array)
+                                .map(|tags| {
+                                    tags.iter()
+                                        .filter_map(Value::as_str)
+                                        .map(ToString::to_string)
+                                        .collect()
+                                })
+                                .unwrap_or_default(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(operations)
+    }
+
+    async fn describe_operation(&self, url: &str, operation: &str) -> Result<OperationDetail> {
+        let (method, path) = Self::parse_operation_id(operation)?;
+        let schema = self.fetch_schema(url).await?;
+        let paths = schema
+            .get("paths")
+            .and_then(|p| p.as_object())
+            .ok_or_else(|| {
+                UxcError::SchemaRetrievalFailed("OpenAPI schema missing paths".to_string())
+            })?;
+        let path_item = paths
+            .get(&path)
+            .ok_or_else(|| UxcError::OperationNotFound(operation.to_string()))?;
+        let operation_spec = path_item
+            .get(&method)
+            .ok_or_else(|| UxcError::OperationNotFound(operation.to_string()))?;
+
+        let parameters = Self::collect_parameters(path_item, operation_spec, &schema);
+        let description = operation_spec
+            .get("description")
+            .or(operation_spec.get("summary"))
+            .and_then(|d| d.as_str())
+            .map(|s| s.to_string());
+        let input_schema = Self::extract_request_body_input_schema(operation_spec, &schema);
+        let return_type = Self::success_schema(operation_spec, &schema)
+            .map(|response_schema| Self::schema_type_hint(response_schema, &schema));
+        let responses = Self::extract_responses(operation_spec, &schema);
+        let security = Self::operation_security(operation_spec, &schema);
+        let servers = servers::documented(path_item, operation_spec, &schema);
+
+        Ok(OperationDetail {
+            operation_id: operation.to_string(),
+            display_name: Self::display_name(&method, &path),
+            description,
+            parameters,
+            return_type,
+            input_schema,
+            responses,
+            security,
+            servers: (!servers.is_empty()).then_some(servers),
+            fields: None,
+        })
+    }
+
+    async fn execute(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult> {
+        let start = std::time::Instant::now();
+        let resp = self.send_operation(url, operation, &args).await?;
+        // Only reachable with --no-follow-redirects (or a missing Location)
+        if resp.status().is_redirection() {
+            let location = resp
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("nowhere");
+            return Err(UxcError::ExecutionFailed(format!(
+                "HTTP {} redirect to {} was not followed",
+                resp.status().as_u16(),
+                location
+            ))
+            .into());
+        }
+        let status = resp.status();
+        if status.is_client_error() || status.is_server_error() {
+            let retry_after = crate::http::retry_after(resp.headers());
+            let body = resp.text().await.unwrap_or_default();
+            return Err(UxcError::call_failed(
+                format!("{} failed with HTTP {}", operation, status),
+                ErrorDetail::http(status.as_u16(), &body).with_retry_after(retry_after),
+            )
+            .into());
+        }
+        let response_headers = resp
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        let is_xml = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(xml::is_xml);
+        let data: Value = if is_xml {
+            let text = resp.text().await?;
+            self.xml_response(url, operation, status.as_u16(), &text)
+                .await?
+        } else {
+            resp.json().await?
+        };
+
+        Ok(ExecutionResult {
+            data,
+            metadata: ExecutionMetadata {
+                duration_ms: start.elapsed().as_millis() as u64,
+                operation: operation.to_string(),
+                response_headers,
+                status: Some(status.as_u16()),
+            },
+        })
+    }
+
+    async fn execute_raw(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RawResponse> {
+        let resp = self.send_operation(url, operation, &args).await?;
+        let status = resp.status().as_u16();
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+        let body = resp.bytes().await?.to_vec();
+        Ok(RawResponse {
+            status: Some(status),
+            content_type,
+            body,
+        })
+    }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        RequestPlan::http(self.operation_request(url, operation, &args).await?)
+    }
+}
+
+impl OpenAPIAdapter {
+    /// Send the request for `operation` and return the response as received
+    async fn send_operation(
+        &self,
+        url: &str,
+        operation: &str,
+        args: &HashMap<String, Value>,
+    ) -> Result<reqwest::Response> {
+        crate::http::send(self.operation_request(url, operation, args).await?).await
+    }
+
+    /// An XML response as JSON, following the schema documented for its
+    /// status when there is one
+    async fn xml_response(
+        &self,
+        url: &str,
+        operation: &str,
+        status: u16,
+        text: &str,
+    ) -> Result<Value> {
+        let schema = self.fetch_schema(url).await.ok();
+        let (method, path) = Self::parse_operation_id(operation)?;
+        let operation_spec = schema
+            .as_ref()
+            .and_then(|schema| schema.get("paths")?.get(&path)?.get(&method));
+        let documented = match (operation_spec, &schema) {
+            (Some(operation_spec), Some(root)) => {
+                xml::response_schema(operation_spec, root, status)
+            }
+            _ => None,
+        };
+        crate::explain::record(
+            "xml",
+            match documented {
+                Some(_) => format!("read the XML response of {} by its schema", operation),
+                None => format!("read the XML response of {} without a schema", operation),
+            },
+        );
+        xml::from_xml(text, documented, schema.as_ref().unwrap_or(&Value::Null))
+    }
+
+    /// Serialization of the parameters of an operation, operation-level
+    /// parameters first so they override path-level ones
+    fn parameter_specs(
+        path_item: &Value,
+        operation_spec: &Value,
+        root: &Value,
+    ) -> Vec<params::ParameterSpec> {
+        let mut specs: Vec<params::ParameterSpec> = Vec::new();
+        for source in [
+            operation_spec.get("parameters").and_then(|p| p.as_array()),
+            path_item.get("parameters").and_then(|p| p.as_array()),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for parameter in source {
+                let Some(spec) =
+                    params::ParameterSpec::parse(Self::dereference_value(parameter, root))
+                else {
+                    continue;
+                };
+                if !specs
+                    .iter()
+                    .any(|seen| seen.name == spec.name && seen.location == spec.location)
+                {
+                    specs.push(spec);
+                }
+            }
+        }
+        specs
+    }
+
+    /// Name of the Swagger 2 `in: body` parameter of an operation
+    fn body_parameter(operation_spec: &Value, root: &Value) -> Option<String> {
+        operation_spec
+            .get("parameters")?
+            .as_array()?
+            .iter()
+            .map(|parameter| Self::dereference_value(parameter, root))
+            .find(|parameter| parameter.get("in").and_then(Value::as_str) == Some("body"))?
+            .get("name")?
+            .as_str()
+            .map(ToString::to_string)
+    }
+
+    /// The request for `operation`, ready to send. Arguments named by the
+    /// operation's parameters are serialized into the path, query, headers
+    /// and cookies (see [`params`]); the rest make up the body, sent as JSON
+    /// unless the operation only takes XML (see [`xml`]).
+    async fn operation_request(
+        &self,
+        url: &str,
+        operation: &str,
+        args: &HashMap<String, Value>,
+    ) -> Result<reqwest::RequestBuilder> {
+        let (method, path) = Self::parse_operation_id(operation)?;
+
+        let schema = match self.fetch_schema(url).await {
+            Ok(schema) => Some(schema),
+            Err(e) => {
+                debug!("Calling {} without its OpenAPI schema: {}", operation, e);
+                None
+            }
+        };
+        let spec = schema.as_ref().and_then(|schema| {
+            let path_item = schema.get("paths")?.get(&path)?;
+            Some((path_item, path_item.get(&method)?, schema))
+        });
+        // API keys go where the operation's scheme documents them, unless
+        // the profile or host config says otherwise
+        let documented_key = spec.and_then(|(_, operation_spec, root)| {
+            Self::operation_security(operation_spec, root)?
+                .into_iter()
+                .flatten()
+                .find(|scheme| scheme.placement().is_some())
+        });
+        let parameters = spec
+            .map(|(path_item, operation_spec, root)| {
+                Self::parameter_specs(path_item, operation_spec, root)
+            })
+            .unwrap_or_default();
+
+        let mut rest: Map<String, Value> = args
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let serialized = params::serialize(&path, &parameters, &mut rest)?;
+        let body = match spec {
+            Some((_, operation_spec, root)) => match Self::body_parameter(operation_spec, root) {
+                Some(name) => Some(rest.remove(&name).unwrap_or(Value::Object(rest))),
+                None if operation_spec.get("requestBody").is_some() || !rest.is_empty() => {
+                    Some(Value::Object(rest))
+                }
+                None => None,
+            },
+            // Without the operation, arguments are all the body can carry
+            None => Some(Value::Object(rest)),
+        };
+
+        let base = match &self.server {
+            Some(selection) => {
+                let documented = spec
+                    .map(|(path_item, operation_spec, root)| {
+                        servers::documented(path_item, operation_spec, root)
+                    })
+                    .unwrap_or_default();
+                let base = servers::resolve(url, &documented, selection)?;
+                // Servers from the schema may point anywhere
+                crate::allowlist::enforce(&base, Some(operation))?;
+                base
+            }
+            None => url.to_string(),
+        };
+        let full_url = format!(
+            "{}{}",
+            base.trim_end_matches('/'),
+            serialized.path_and_query
+        );
+
+        let mut req = match method.as_str() {
+            "get" => self.client.get(&full_url),
+            "post" => self.client.post(&full_url),
+            "put" => self.client.put(&full_url),
+            "delete" => self.client.delete(&full_url),
+            "patch" => self.client.patch(&full_url),
+            "head" => self.client.head(&full_url),
+            "options" => self.client.request(reqwest::Method::OPTIONS, &full_url),
+            "trace" => self.client.request(reqwest::Method::TRACE, &full_url),
+            _ => {
+                return Err(UxcError::InvalidArguments(format!(
+                    "Unsupported HTTP method: {}",
+                    method
+                ))
+                .into())
+            }
+        };
+
+        for (name, value) in &serialized.headers {
+            req = req.header(name.as_str(), value.as_str());
+        }
+        if let Some(cookie) = &serialized.cookie {
+            req = req.header(reqwest::header::COOKIE, cookie.as_str());
+        }
+        let xml_body = spec.and_then(|(_, operation_spec, root)| {
+            xml::request_body(operation_spec, root)
+                .map(|(media_type, schema)| (media_type, schema, root))
+        });
+        match (&body, xml_body) {
+            (Some(body), Some((media_type, schema, root))) => {
+                crate::explain::record(
+                    "xml",
+                    format!(
+                        "{} only takes {}; the body is sent as XML",
+                        operation, media_type
+                    ),
+                );
+                req = req
+                    .header(reqwest::header::CONTENT_TYPE, media_type)
+                    .body(xml::to_xml(body, &schema, root));
+            }
+            (Some(body), None) => req = req.json(body),
+            (None, _) => {}
+        }
+        if let Some(media_type) =
+            spec.and_then(|(_, operation_spec, root)| xml::accept(operation_spec, root))
+        {
+            req = req.header(reqwest::header::ACCEPT, media_type);
+        }
+
+        // Apply authentication if profile is set
+        let req = if let Some(profile) = &self.auth_profile {
+            profile.apply_to_request_for(req, documented_key.as_ref())?
+        } else {
+            req
+        };
+
+        Ok(req)
+    }
+}
+
+/// Security schemes an OpenAPI document declares, by name
+fn security_schemes(spec: &Value) -> Option<&Map<String, Value>> {
+    spec.pointer("/components/securitySchemes")
+        .or_else(|| spec.get("securityDefinitions"))
+        .and_then(Value::as_object)
+}
+
+/// Security schemes the operations of an OpenAPI document require, each
+/// described as `name (type ...)`. Schemes that are declared but never
+/// required, or only offered next to an anonymous `{}` alternative, are left
+/// out.
+pub fn required_security(spec: &Value) -> Vec<String> {
+    let Some(schemes) = security_schemes(spec) else {
+        return Vec::new();
+    };
+
+    // Names of the schemes a `security` array requires
+    let required_by = |security: &Value| -> Vec<String> {
+        let Some(alternatives) = security.as_array() else {
+            return Vec::new();
+        };
+        let anonymous = alternatives
+            .iter()
+            .any(|requirement| requirement.as_object().is_some_and(Map::is_empty));
+        if anonymous {
+            return Vec::new();
+        }
+        alternatives
+            .iter()
+            .filter_map(Value::as_object)
+            .flat_map(|requirement| requirement.keys().cloned())
+            .collect()
+    };
+
+    let global = spec.get("security");
+    let mut names = Vec::new();
+    for item in spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|paths| paths.values())
+    {
+        for method in OpenAPIAdapter::HTTP_METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            if let Some(security) = operation.get("security").or(global) {
+                for name in required_by(security) {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+    }
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let declaration = OpenAPIAdapter::dereference_value(schemes.get(&name)?, spec);
+            Some(SecurityScheme::from_openapi(&name, declaration, Vec::new()).describe())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn swagger_doc() -> &'static str {
+        r#"{
+  "swagger": "2.0",
+  "info": { "title": "Test", "version": "1.0.0" },
+  "paths": {}
+}"#
+    }
+
+    fn openapi_doc() -> &'static str {
+        r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "Test", "version": "1.0.0" },
+  "paths": {}
+}"#
+    }
+
+    #[tokio::test]
+    async fn can_handle_discovers_swagger_json() {
+        let mut server = mockito::Server::new_async().await;
+        let _swagger = server
+            .mock("GET", "/swagger.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(swagger_doc())
+            .create_async()
+            .await;
+
+        let adapter = OpenAPIAdapter::new();
+        assert!(adapter.can_handle(&server.url()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn fetch_schema_uses_discovered_swagger_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let _swagger = server
+            .mock("GET", "/swagger.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(swagger_doc())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let adapter = OpenAPIAdapter::new();
+        assert!(adapter.can_handle(&server.url()).await.unwrap());
+        let schema = adapter.fetch_schema(&server.url()).await.unwrap();
+        assert_eq!(schema["swagger"], "2.0");
+    }
+
+    #[tokio::test]
+    async fn fetch_schema_supports_api_docs_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let _api_docs = server
+            .mock("GET", "/api-docs")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(openapi_doc())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let adapter = OpenAPIAdapter::new();
+        assert!(adapter.can_handle(&server.url()).await.unwrap());
+        let schema = adapter.fetch_schema(&server.url()).await.unwrap();
+        assert_eq!(schema["openapi"], "3.0.0");
+    }
+
+    #[tokio::test]
+    async fn configured_schema_paths_are_probed_first_and_may_be_yaml() {
+        let mut server = mockito::Server::new_async().await;
+        let _yaml = server
+            .mock("GET", "/api/openapi.yaml")
+            .with_header("content-type", "application/yaml")
+            .with_body("openapi: 3.0.0\ninfo:\n  title: t\n  version: '1'\npaths: {}\n")
+            .create_async()
+            .await;
+        let well_known = server
+            .mock("GET", "/openapi.json")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let adapter =
+            OpenAPIAdapter::new().with_schema_paths(vec!["/api/openapi.yaml".to_string()]);
+        assert!(adapter.can_handle(&server.url()).await.unwrap());
+        let schema = adapter.fetch_schema(&server.url()).await.unwrap();
+        assert_eq!(schema["openapi"], "3.0.0");
+        well_known.assert_async().await;
+    }
+
+    #[test]
+    fn schema_candidates_do_not_append_to_schema_url() {
+        let candidates = OpenAPIAdapter::schema_candidates("https://example.com/openapi.json", &[]);
+        assert_eq!(candidates, vec!["https://example.com/openapi.json"]);
+    }
+
+    #[test]
+    fn parse_operation_id_accepts_method_path_format() {
+        let (method, path) = OpenAPIAdapter::parse_operation_id("post:/pet").unwrap();
+        assert_eq!(method, "post");
+        assert_eq!(path, "/pet");
+    }
+
+    #[test]
+    fn parse_operation_id_rejects_legacy_display_format() {
+        let err = OpenAPIAdapter::parse_operation_id("POST /pet").unwrap_err();
+        assert!(
+            err.to_string().contains("method:/path"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn describe_operation_includes_expanded_request_body_schema() {
+        let mut server = mockito::Server::new_async().await;
+        let _openapi = server
+            .mock("GET", "/openapi.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r##"{
+  "openapi": "3.0.0",
+  "info": { "title": "Test", "version": "1.0.0" },
+  "paths": {
+    "/pet": {
+      "post": {
+        "summary": "Add a new pet",
+        "requestBody": {
+          "required": true,
+          "content": {
+            "application/json": {
+              "schema": { "$ref": "#/components/schemas/PetRequest" }
+            }
+          }
+        },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  },
+  "components": {
+    "schemas": {
+      "PetRequest": {
+        "type": "object",
+        "required": ["name"],
+        "properties": {
+          "name": { "type": "string" },
+          "category": { "$ref": "#/components/schemas/Category" }
+        }
+      },
+      "Category": {
+        "type": "object",
+        "properties": {
+          "id": { "type": "integer" }
+        }
+      }
+    }
+  }
+}"##,
+            )
+            .create_async()
+            .await;
+
+        let adapter = OpenAPIAdapter::new();
+        let detail = adapter
+            .describe_operation(&server.url(), "post:/pet")
+            .await
+            .unwrap();
+
+        let input_schema = detail.input_schema.expect("input schema should exist");
+        assert_eq!(input_schema["kind"], "openapi_request_body");
+        assert_eq!(input_schema["required"], true);
+        assert_eq!(
+            input_schema["content"]["application/json"]["source_ref"],
+            "#/components/schemas/PetRequest"
+        );
+        assert_eq!(
+            input_schema["content"]["application/json"]["schema"]["properties"]["category"]
+                ["properties"]["id"]["type"],
+            "integer"
+        );
+    }
+
+    #[tokio::test]
+    async fn describe_operation_omits_input_schema_when_request_body_has_no_schema() {
+        let mut server = mockito::Server::new_async().await;
+        let _openapi = server
+            .mock("GET", "/openapi.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r##"{
+  "openapi": "3.0.0",
+  "info": { "title": "Test", "version": "1.0.0" },
+  "paths": {
+    "/pet": {
+      "post": {
+        "summary": "Add a new pet",
+        "requestBody": {
+          "required": true,
+          "content": {
+            "application/json": {
+              "example": { "name": "doggie" }
+            }
+          }
+        },
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"##,
+            )
+            .create_async()
+            .await;
+
+        let adapter = OpenAPIAdapter::new();
+        let detail = adapter
+            .describe_operation(&server.url(), "post:/pet")
+            .await
+            .unwrap();
+        assert!(detail.input_schema.is_none());
+    }
+
+    #[test]
+    fn required_security_follows_global_and_operation_requirements() {
+        let spec = serde_json::json!({
+            "openapi": "3.0.0",
+            "security": [{ "api_key": [] }],
+            "components": { "securitySchemes": {
+                "api_key": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+                "bearer": { "type": "http", "scheme": "bearer" },
+                "unused": { "type": "oauth2" }
+            } },
+            "paths": {
+                "/pets": { "get": {}, "post": { "security": [{ "bearer": [] }] } },
+                "/health": { "get": { "security": [] } },
+                "/public": { "get": { "security": [{}, { "bearer": [] }] } }
+            }
+        });
+        assert_eq!(
+            required_security(&spec),
+            vec![
+                "api_key (apiKey in header X-API-Key)".to_string(),
+                "bearer (http bearer)".to_string()
+            ]
+        );
+
+        let open = serde_json::json!({
+            "components": { "securitySchemes": { "bearer": { "type": "http" } } },
+            "paths": { "/pets": { "get": {} } }
+        });
+        assert!(required_security(&open).is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_serializes_parameters_by_location() {
+        let mut server = mockito::Server::new_async().await;
+        let _schema = server
+            .mock("GET", "/openapi.json")
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "openapi": "3.0.0",
+                    "info": { "title": "pets", "version": "1" },
+                    "paths": {
+                        "/pets/{id}": {
+                            "parameters": [{ "name": "id", "in": "path", "required": true }],
+                            "put": {
+                                "parameters": [
+                                    { "name": "tags", "in": "query", "explode": false },
+                                    { "name": "filter", "in": "query", "style": "deepObject" },
+                                    { "name": "X-Request-Id", "in": "header" },
+                                    { "name": "session", "in": "cookie" }
+                                ],
+                                "requestBody": { "content": { "application/json": {} } }
+                            }
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let call = server
+            .mock("PUT", "/pets/7?tags=a,b&filter[kind]=cat")
+            .match_header("x-request-id", "r1")
+            .match_header("cookie", "session=s1")
+            .match_body(mockito::Matcher::Json(json!({ "name": "Rex" })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"ok": true}"#)
+            .create_async()
+            .await;
+
+        let args = HashMap::from([
+            ("id".to_string(), json!(7)),
+            ("tags".to_string(), json!(["a", "b"])),
+            ("filter".to_string(), json!({ "kind": "cat" })),
+            ("X-Request-Id".to_string(), json!("r1")),
+            ("session".to_string(), json!("s1")),
+            ("name".to_string(), json!("Rex")),
+        ]);
+        let result = OpenAPIAdapter::new()
+            .execute(&server.url(), "put:/pets/{id}", args)
+            .await
+            .unwrap();
+        assert_eq!(result.data, json!({ "ok": true }));
+        call.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn execute_sends_and_reads_xml_for_xml_only_operations() {
+        let mut server = mockito::Server::new_async().await;
+        let pet = json!({
+            "type": "object",
+            "xml": { "name": "pet" },
+            "properties": {
+                "id": { "type": "integer", "xml": { "attribute": true } },
+                "name": { "type": "string" }
+            }
+        });
+        let _schema = server
+            .mock("GET", "/openapi.json")
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "openapi": "3.0.0",
+                    "info": { "title": "pets", "version": "1" },
+                    "paths": {
+                        "/pets": {
+                            "post": {
+                                "requestBody": { "content": { "application/xml": { "schema": pet } } },
+                                "responses": {
+                                    "201": { "content": { "application/xml": { "schema": pet } } }
+                                }
+                            }
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let call = server
+            .mock("POST", "/pets")
+            .match_header("content-type", "application/xml")
+            .match_header("accept", "application/xml")
+            .match_body(
+                r#"<?xml version="1.0" encoding="UTF-8"?><pet id="7"><name>Rex</name></pet>"#,
+            )
+            .with_status(201)
+            .with_header("content-type", "application/xml; charset=utf-8")
+            .with_body(r#"<pet id="7"><name>Rex</name></pet>"#)
+            .create_async()
+            .await;
+
+        let args = HashMap::from([
+            ("id".to_string(), json!(7)),
+            ("name".to_string(), json!("Rex")),
+        ]);
+        let result = OpenAPIAdapter::new()
+            .execute(&server.url(), "post:/pets", args)
+            .await
+            .unwrap();
+        assert_eq!(result.data, json!({ "id": 7, "name": "Rex" }));
+        call.assert_async().await;
+    }
+}
@@ -0,0 +1,510 @@
+//! OpenAPI parameter serialization
+//!
+//! Arguments named by the operation's parameters are taken out of the call
+//! and serialized where the parameter lives: substituted into the path,
+//! added to the query string, or sent as headers and cookies. Each follows
+//! its `style` and `explode` (OpenAPI 3) or `collectionFormat` (Swagger 2):
+//!
+//! | style            | array `[3, 4]`        | object `{"a": 1}`     |
+//! |------------------|-----------------------|-----------------------|
+//! | `simple`         | `3,4`                 | `a,1` / `a=1`         |
+//! | `label`          | `.3,4` / `.3.4`       | `.a,1` / `.a=1`       |
+//! | `matrix`         | `;id=3,4` / `;id=3;id=4` | `;id=a,1` / `;a=1` |
+//! | `form`           | `id=3,4` / `id=3&id=4` | `id=a,1` / `a=1`     |
+//! | `spaceDelimited` | `id=3%204`            | `id=a%201`            |
+//! | `pipeDelimited`  | `id=3\|4`             | `id=a\|1`             |
+//! | `deepObject`     |                       | `id[a]=1`             |
+//!
+//! (second forms with `explode: true`). Values are percent-encoded, except
+//! in headers and, with `allowReserved`, reserved characters in the query.
+//! Parameters described with `content` are sent as JSON text.
+
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+/// Where a parameter is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Location {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Simple,
+    Label,
+    Matrix,
+    Form,
+    SpaceDelimited,
+    PipeDelimited,
+    /// Swagger 2 `collectionFormat: tsv`
+    TabDelimited,
+    DeepObject,
+}
+
+/// How one parameter is serialized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ParameterSpec {
+    pub name: String,
+    pub location: Location,
+    style: Style,
+    explode: bool,
+    allow_reserved: bool,
+    /// Described with `content`: the value is sent as JSON text
+    json: bool,
+}
+
+impl ParameterSpec {
+    /// The serialization of a (dereferenced) parameter object; `None` for
+    /// Swagger 2 `body` and `formData` parameters
+    pub fn parse(parameter: &Value) -> Option<Self> {
+        let name = parameter.get("name")?.as_str()?.to_string();
+        let location = match parameter.get("in")?.as_str()? {
+            "path" => Location::Path,
+            "query" => Location::Query,
+            "header" => Location::Header,
+            "cookie" => Location::Cookie,
+            _ => return None,
+        };
+        let default_style = match location {
+            Location::Path | Location::Header => Style::Simple,
+            Location::Query | Location::Cookie => Style::Form,
+        };
+        let swagger2 = parameter.get("schema").is_none() && parameter.get("type").is_some();
+        let (style, default_explode) = if swagger2 {
+            match parameter.get("collectionFormat").and_then(Value::as_str) {
+                Some("ssv") => (Style::SpaceDelimited, false),
+                Some("tsv") => (Style::TabDelimited, false),
+                Some("pipes") => (Style::PipeDelimited, false),
+                Some("multi") => (Style::Form, true),
+                _ => (default_style, false),
+            }
+        } else {
+            let style = match parameter.get("style").and_then(Value::as_str) {
+                Some("simple") => Style::Simple,
+                Some("label") => Style::Label,
+                Some("matrix") => Style::Matrix,
+                Some("form") => Style::Form,
+                Some("spaceDelimited") => Style::SpaceDelimited,
+                Some("pipeDelimited") => Style::PipeDelimited,
+                Some("deepObject") => Style::DeepObject,
+                _ => default_style,
+            };
+            (style, style == Style::Form)
+        };
+        Some(Self {
+            name,
+            location,
+            style,
+            explode: parameter
+                .get("explode")
+                .and_then(Value::as_bool)
+                .unwrap_or(default_explode),
+            allow_reserved: parameter
+                .get("allowReserved")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            json: parameter.get("content").is_some(),
+        })
+    }
+
+    /// A `simple` path parameter, for template variables the operation does
+    /// not declare
+    fn path(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            location: Location::Path,
+            style: Style::Simple,
+            explode: false,
+            allow_reserved: false,
+            json: false,
+        }
+    }
+
+    fn encode(&self, text: &str) -> String {
+        match self.location {
+            Location::Header => text.to_string(),
+            Location::Query => encode(text, self.allow_reserved),
+            Location::Path | Location::Cookie => encode(text, false),
+        }
+    }
+
+    /// `value` as the path segment text of this parameter
+    fn path_text(&self, value: &Value) -> String {
+        let parts = self.parts(value);
+        let (prefix, separator) = match self.style {
+            Style::Label => (".", if self.explode { "." } else { "," }),
+            Style::Matrix => return self.matrix(&parts),
+            _ => ("", ","),
+        };
+        let text = match parts {
+            Parts::Scalar(text) => self.encode(&text),
+            Parts::List(items) => self.join(&items, separator),
+            Parts::Pairs(pairs) => self.join_pairs(&pairs, separator),
+        };
+        format!("{}{}", prefix, text)
+    }
+
+    fn matrix(&self, parts: &Parts) -> String {
+        let name = self.encode(&self.name);
+        match parts {
+            Parts::Scalar(text) if text.is_empty() => format!(";{}", name),
+            Parts::Scalar(text) => format!(";{}={}", name, self.encode(text)),
+            Parts::List(items) if self.explode => items
+                .iter()
+                .map(|item| format!(";{}={}", name, self.encode(item)))
+                .collect(),
+            Parts::List(items) => format!(";{}={}", name, self.join(items, ",")),
+            Parts::Pairs(pairs) if self.explode => pairs
+                .iter()
+                .map(|(key, value)| format!(";{}={}", self.encode(key), self.encode(value)))
+                .collect(),
+            Parts::Pairs(pairs) => format!(";{}={}", name, self.join_pairs(pairs, ",")),
+        }
+    }
+
+    /// `value` as encoded `name=value` pairs of the query string or cookie
+    fn pairs(&self, value: &Value) -> Vec<String> {
+        let name = self.encode(&self.name);
+        if self.style == Style::DeepObject && !self.json && value.is_object() {
+            let mut pairs = Vec::new();
+            deep_object(&name, value, &mut |key, text| {
+                pairs.push(format!("{}={}", key, self.encode(text)))
+            });
+            return pairs;
+        }
+        let separator = match self.style {
+            Style::SpaceDelimited => "%20",
+            Style::PipeDelimited => "|",
+            Style::TabDelimited => "%09",
+            _ => ",",
+        };
+        match self.parts(value) {
+            Parts::Scalar(text) => vec![format!("{}={}", name, self.encode(&text))],
+            Parts::List(items) if self.explode => items
+                .iter()
+                .map(|item| format!("{}={}", name, self.encode(item)))
+                .collect(),
+            Parts::List(items) => vec![format!("{}={}", name, self.join(&items, separator))],
+            Parts::Pairs(pairs) if self.explode => pairs
+                .iter()
+                .map(|(key, value)| format!("{}={}", self.encode(key), self.encode(value)))
+                .collect(),
+            Parts::Pairs(pairs) => {
+                let flat: Vec<String> = pairs
+                    .into_iter()
+                    .flat_map(|(key, value)| [key, value])
+                    .collect();
+                vec![format!("{}={}", name, self.join(&flat, separator))]
+            }
+        }
+    }
+
+    fn join(&self, items: &[String], separator: &str) -> String {
+        items
+            .iter()
+            .map(|item| self.encode(item))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn join_pairs(&self, pairs: &[(String, String)], separator: &str) -> String {
+        if self.explode {
+            pairs
+                .iter()
+                .map(|(key, value)| format!("{}={}", self.encode(key), self.encode(value)))
+                .collect::<Vec<_>>()
+                .join(separator)
+        } else {
+            let flat: Vec<String> = pairs
+                .iter()
+                .flat_map(|(key, value)| [key.clone(), value.clone()])
+                .collect();
+            self.join(&flat, ",")
+        }
+    }
+
+    fn parts(&self, value: &Value) -> Parts {
+        if self.json {
+            return Parts::Scalar(value.to_string());
+        }
+        match value {
+            Value::Array(items) => Parts::List(items.iter().map(scalar_text).collect()),
+            Value::Object(fields) => Parts::Pairs(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), scalar_text(value)))
+                    .collect(),
+            ),
+            scalar => Parts::Scalar(scalar_text(scalar)),
+        }
+    }
+}
+
+/// A value split the way styles serialize it
+enum Parts {
+    Scalar(String),
+    List(Vec<String>),
+    Pairs(Vec<(String, String)>),
+}
+
+/// Parameters of a request, serialized
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct SerializedParameters {
+    /// Path with variables substituted, and the query string
+    pub path_and_query: String,
+
+    pub headers: Vec<(String, String)>,
+
+    /// The `Cookie` header, if any parameter is a cookie
+    pub cookie: Option<String>,
+}
+
+/// Take the arguments named by `parameters` out of `args` and serialize
+/// them for `template`. Template variables without a declared parameter are
+/// substituted as `simple` path parameters.
+pub(super) fn serialize(
+    template: &str,
+    parameters: &[ParameterSpec],
+    args: &mut Map<String, Value>,
+) -> Result<SerializedParameters> {
+    let mut path = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        path.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let name = &rest[start + 1..end];
+        let value = args.remove(name).ok_or_else(|| {
+            UxcError::InvalidArguments(format!(
+                "'{}' is required to build the path {}",
+                name, template
+            ))
+        })?;
+        let text = match parameters
+            .iter()
+            .find(|parameter| parameter.location == Location::Path && parameter.name == name)
+        {
+            Some(parameter) => parameter.path_text(&value),
+            None => ParameterSpec::path(name).path_text(&value),
+        };
+        path.push_str(&text);
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+
+    let mut query = Vec::new();
+    let mut serialized = SerializedParameters::default();
+    let mut cookies = Vec::new();
+    for parameter in parameters {
+        if parameter.location == Location::Path {
+            continue;
+        }
+        let Some(value) = args.remove(&parameter.name) else {
+            continue;
+        };
+        if value.is_null() {
+            continue;
+        }
+        match parameter.location {
+            Location::Query => query.extend(parameter.pairs(&value)),
+            Location::Cookie => cookies.extend(parameter.pairs(&value)),
+            Location::Header => serialized
+                .headers
+                .push((parameter.name.clone(), parameter.path_text(&value))),
+            Location::Path => unreachable!("substituted above"),
+        }
+    }
+
+    serialized.path_and_query = if query.is_empty() {
+        path
+    } else {
+        format!("{}?{}", path, query.join("&"))
+    };
+    if !cookies.is_empty() {
+        serialized.cookie = Some(cookies.join("; "));
+    }
+    Ok(serialized)
+}
+
+/// `key[a][b]=...` pairs for the leaves of `value`
+fn deep_object(key: &str, value: &Value, emit: &mut impl FnMut(&str, &str)) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                deep_object(&format!("{}[{}]", key, encode(name, false)), value, emit);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                deep_object(key, item, emit);
+            }
+        }
+        Value::Null => {}
+        scalar => emit(key, &scalar_text(scalar)),
+    }
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Percent-encode everything but unreserved characters (and reserved ones
+/// when `keep_reserved`)
+fn encode(text: &str, keep_reserved: bool) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        let unreserved = byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+        let reserved = b":/?#[]@!$&'()*+,;=".contains(&byte);
+        if unreserved || (keep_reserved && reserved) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(parameter: Value) -> ParameterSpec {
+        ParameterSpec::parse(&parameter).unwrap()
+    }
+
+    fn path_text(parameter: Value, value: Value) -> String {
+        spec(parameter).path_text(&value)
+    }
+
+    fn query(parameter: Value, value: Value) -> String {
+        spec(parameter).pairs(&value).join("&")
+    }
+
+    #[test]
+    fn path_styles() {
+        let array = json!([3, 4, 5]);
+        let object = json!({ "firstName": "Alex", "role": "admin" });
+        let simple = json!({ "name": "id", "in": "path" });
+        assert_eq!(path_text(simple.clone(), json!("a b/c")), "a%20b%2Fc");
+        assert_eq!(path_text(simple.clone(), array.clone()), "3,4,5");
+        assert_eq!(
+            path_text(simple, object.clone()),
+            "firstName,Alex,role,admin"
+        );
+        assert_eq!(
+            path_text(
+                json!({ "name": "id", "in": "path", "explode": true }),
+                object.clone()
+            ),
+            "firstName=Alex,role=admin"
+        );
+
+        let label = json!({ "name": "id", "in": "path", "style": "label" });
+        assert_eq!(path_text(label, array.clone()), ".3,4,5");
+        let label = json!({ "name": "id", "in": "path", "style": "label", "explode": true });
+        assert_eq!(path_text(label.clone(), array.clone()), ".3.4.5");
+        assert_eq!(
+            path_text(label, object.clone()),
+            ".firstName=Alex.role=admin"
+        );
+
+        let matrix = json!({ "name": "id", "in": "path", "style": "matrix" });
+        assert_eq!(path_text(matrix.clone(), json!(5)), ";id=5");
+        assert_eq!(path_text(matrix, array.clone()), ";id=3,4,5");
+        let matrix = json!({ "name": "id", "in": "path", "style": "matrix", "explode": true });
+        assert_eq!(path_text(matrix.clone(), array), ";id=3;id=4;id=5");
+        assert_eq!(path_text(matrix, object), ";firstName=Alex;role=admin");
+    }
+
+    #[test]
+    fn query_styles() {
+        let array = json!(["a b", "c"]);
+        let object = json!({ "firstName": "Alex", "role": "admin" });
+        let form = json!({ "name": "id", "in": "query" });
+        assert_eq!(query(form.clone(), array.clone()), "id=a%20b&id=c");
+        assert_eq!(query(form, object.clone()), "firstName=Alex&role=admin");
+        let form = json!({ "name": "id", "in": "query", "explode": false });
+        assert_eq!(query(form.clone(), array.clone()), "id=a%20b,c");
+        assert_eq!(query(form, object.clone()), "id=firstName,Alex,role,admin");
+
+        let space =
+            json!({ "name": "id", "in": "query", "style": "spaceDelimited", "explode": false });
+        assert_eq!(query(space, array.clone()), "id=a%20b%20c");
+        let pipe =
+            json!({ "name": "id", "in": "query", "style": "pipeDelimited", "explode": false });
+        assert_eq!(query(pipe, array), "id=a%20b|c");
+
+        let deep =
+            json!({ "name": "filter", "in": "query", "style": "deepObject", "explode": true });
+        assert_eq!(
+            query(deep, json!({ "age": { "gte": 18 }, "role": "admin" })),
+            "filter[age][gte]=18&filter[role]=admin"
+        );
+
+        let reserved = json!({ "name": "q", "in": "query", "allowReserved": true });
+        assert_eq!(query(reserved, json!("a/b?c")), "q=a/b?c");
+        let content = json!({ "name": "q", "in": "query", "content": { "application/json": {} } });
+        assert_eq!(query(content, json!({ "a": 1 })), "q=%7B%22a%22%3A1%7D");
+    }
+
+    #[test]
+    fn swagger2_collection_formats() {
+        let csv = json!({ "name": "ids", "in": "query", "type": "array" });
+        assert_eq!(query(csv, json!([1, 2])), "ids=1,2");
+        let multi =
+            json!({ "name": "ids", "in": "query", "type": "array", "collectionFormat": "multi" });
+        assert_eq!(query(multi, json!([1, 2])), "ids=1&ids=2");
+        let tsv =
+            json!({ "name": "ids", "in": "query", "type": "array", "collectionFormat": "tsv" });
+        assert_eq!(query(tsv, json!([1, 2])), "ids=1%092");
+        assert!(ParameterSpec::parse(&json!({ "name": "pet", "in": "body" })).is_none());
+    }
+
+    #[test]
+    fn arguments_are_taken_by_location() {
+        let parameters = [
+            spec(json!({ "name": "id", "in": "path", "style": "label" })),
+            spec(json!({ "name": "tags", "in": "query" })),
+            spec(json!({ "name": "X-Trace", "in": "header" })),
+            spec(json!({ "name": "session", "in": "cookie" })),
+            spec(json!({ "name": "lang", "in": "cookie" })),
+        ];
+        let mut args = json!({
+            "id": 7,
+            "owner": "me",
+            "tags": ["a", "b"],
+            "X-Trace": ["x", "y"],
+            "session": "s 1",
+            "lang": "en",
+            "name": "Rex"
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+        let serialized = serialize("/users/{owner}/pets/{id}", &parameters, &mut args).unwrap();
+        assert_eq!(
+            serialized,
+            SerializedParameters {
+                path_and_query: "/users/me/pets/.7?tags=a&tags=b".to_string(),
+                headers: vec![("X-Trace".to_string(), "x,y".to_string())],
+                cookie: Some("session=s%201; lang=en".to_string()),
+            }
+        );
+        assert_eq!(Value::Object(args), json!({ "name": "Rex" }));
+
+        let err = serialize("/pets/{id}", &parameters, &mut Map::new())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'id' is required"), "{}", err);
+    }
+}
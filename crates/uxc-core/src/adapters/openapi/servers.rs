@@ -0,0 +1,277 @@
+//! OpenAPI server selection
+//!
+//! Calls go to the endpoint URL given on the command line unless a server is
+//! selected (`--server`, `--server-var`). The servers of an operation are its
+//! own `servers`, else its path's, else the document's; Swagger 2 documents
+//! have one, made of `schemes`, `host` and `basePath`. A selected server's
+//! `{variables}` take the given values, else their defaults, and relative
+//! server URLs are resolved against the endpoint URL.
+
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// The server calls go to instead of the endpoint URL
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerSelection {
+    /// Index into the listed servers, or a server URL; the first server when
+    /// only variables are given
+    pub server: Option<String>,
+    /// Values of server variables
+    pub variables: BTreeMap<String, String>,
+}
+
+/// Servers documented for an operation, in the form `describe` shows:
+/// `url`, `description` and `variables` with their `default`, `enum` and
+/// `description`
+pub(super) fn documented(path_item: &Value, operation_spec: &Value, root: &Value) -> Vec<Value> {
+    let listed = [operation_spec, path_item, root]
+        .into_iter()
+        .find_map(|level| level.get("servers")?.as_array().filter(|s| !s.is_empty()));
+    if let Some(servers) = listed {
+        return servers
+            .iter()
+            .filter(|server| server.get("url").is_some_and(Value::is_string))
+            .cloned()
+            .collect();
+    }
+    // Swagger 2
+    let host = root.get("host").and_then(Value::as_str);
+    let base_path = root.get("basePath").and_then(Value::as_str).unwrap_or("");
+    if host.is_none() && base_path.is_empty() {
+        return Vec::new();
+    }
+    let url = match host {
+        Some(host) => {
+            let scheme = root
+                .get("schemes")
+                .and_then(Value::as_array)
+                .and_then(|schemes| {
+                    let schemes: Vec<&str> = schemes.iter().filter_map(Value::as_str).collect();
+                    schemes
+                        .iter()
+                        .find(|scheme| **scheme == "https")
+                        .or(schemes.first())
+                        .copied()
+                })
+                .unwrap_or("https");
+            format!("{}://{}{}", scheme, host, base_path)
+        }
+        None => base_path.to_string(),
+    };
+    vec![json!({ "url": url })]
+}
+
+/// Base URL of calls to `endpoint` with `selection` among `servers`
+pub(super) fn resolve(
+    endpoint: &str,
+    servers: &[Value],
+    selection: &ServerSelection,
+) -> Result<String> {
+    let no_variables = Map::new();
+    let variables_of = |server: &Value| server.get("variables").and_then(Value::as_object).cloned();
+    let (template, variables) = match selection.server.as_deref() {
+        Some(choice) if choice.parse::<usize>().is_ok() => {
+            let index: usize = choice.parse()?;
+            let server = servers.get(index).ok_or_else(|| {
+                UxcError::InvalidArguments(format!(
+                    "Server {} is not listed; the schema lists {} server(s)",
+                    index,
+                    servers.len()
+                ))
+            })?;
+            (url_of(server), variables_of(server))
+        }
+        Some(url) => {
+            let listed = servers.iter().find(|server| url_of(server) == url);
+            (url.to_string(), listed.and_then(variables_of))
+        }
+        None => {
+            let server = servers.first().ok_or_else(|| {
+                UxcError::InvalidArguments(
+                    "--server-var needs a server, and the schema lists none".to_string(),
+                )
+            })?;
+            (url_of(server), variables_of(server))
+        }
+    };
+    let url = substitute(
+        &template,
+        variables.as_ref().unwrap_or(&no_variables),
+        selection,
+    )?;
+    crate::explain::record(
+        "server",
+        format!("calls go to {} (server {})", url, template),
+    );
+    absolute(endpoint, &url)
+}
+
+fn url_of(server: &Value) -> String {
+    server
+        .get("url")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// `template` with its `{variables}` replaced
+fn substitute(
+    template: &str,
+    variables: &Map<String, Value>,
+    selection: &ServerSelection,
+) -> Result<String> {
+    let mut url = String::new();
+    let mut used = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        url.push_str(&rest[..start]);
+        let name = &rest[start + 1..start + end];
+        let variable = variables.get(name);
+        let value = match selection.variables.get(name) {
+            Some(value) => value.clone(),
+            None => variable
+                .and_then(|variable| variable.get("default"))
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+                .ok_or_else(|| {
+                    UxcError::InvalidArguments(format!(
+                        "Server {} needs a value for '{}'; pass --server-var {}=<value>",
+                        template, name, name
+                    ))
+                })?,
+        };
+        let allowed: Vec<&str> = variable
+            .and_then(|variable| variable.get("enum"))
+            .and_then(Value::as_array)
+            .map(|values| values.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if !allowed.is_empty() && !allowed.contains(&value.as_str()) {
+            return Err(UxcError::InvalidArguments(format!(
+                "'{}' is not an allowed value of server variable '{}' (expected one of: {})",
+                value,
+                name,
+                allowed.join(", ")
+            ))
+            .into());
+        }
+        url.push_str(&value);
+        used.push(name.to_string());
+        rest = &rest[start + end + 1..];
+    }
+    url.push_str(rest);
+
+    if let Some(unused) = selection.variables.keys().find(|name| !used.contains(name)) {
+        return Err(UxcError::InvalidArguments(format!(
+            "Server {} has no variable '{}'",
+            template, unused
+        ))
+        .into());
+    }
+    Ok(url)
+}
+
+/// `url` resolved against the endpoint when it is relative
+fn absolute(endpoint: &str, url: &str) -> Result<String> {
+    if url.contains("://") {
+        return Ok(url.to_string());
+    }
+    let base = url::Url::parse(&format!("{}/", endpoint.trim_end_matches('/')))
+        .map_err(|e| UxcError::InvalidArguments(format!("Invalid endpoint URL: {}", e)))?;
+    let joined = base
+        .join(url)
+        .map_err(|e| UxcError::InvalidArguments(format!("Invalid server URL '{}': {}", url, e)))?;
+    Ok(joined.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(server: Option<&str>, variables: &[(&str, &str)]) -> ServerSelection {
+        ServerSelection {
+            server: server.map(ToString::to_string),
+            variables: variables
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn servers_follow_operation_path_and_document_levels() {
+        let root = json!({
+            "servers": [{ "url": "https://api.example.com" }],
+            "host": "ignored.example.com"
+        });
+        let path_item = json!({ "servers": [{ "url": "/v2" }] });
+        let operation = json!({});
+        assert_eq!(
+            documented(&path_item, &operation, &root),
+            vec![json!({ "url": "/v2" })]
+        );
+        assert_eq!(
+            documented(&json!({}), &operation, &root),
+            vec![json!({ "url": "https://api.example.com" })]
+        );
+
+        let swagger = json!({ "host": "petstore.example.com", "basePath": "/v1", "schemes": ["http", "https"] });
+        assert_eq!(
+            documented(&json!({}), &operation, &swagger),
+            vec![json!({ "url": "https://petstore.example.com/v1" })]
+        );
+    }
+
+    #[test]
+    fn resolve_substitutes_variables_and_relative_urls() {
+        let servers = vec![
+            json!({
+                "url": "https://{region}.api.example.com/{version}",
+                "variables": {
+                    "region": { "default": "us", "enum": ["us", "eu"] },
+                    "version": { "default": "v1" }
+                }
+            }),
+            json!({ "url": "/sandbox" }),
+        ];
+        let endpoint = "http://localhost:8080/api";
+        assert_eq!(
+            resolve(endpoint, &servers, &selection(None, &[("region", "eu")])).unwrap(),
+            "https://eu.api.example.com/v1"
+        );
+        assert_eq!(
+            resolve(endpoint, &servers, &selection(Some("1"), &[])).unwrap(),
+            "http://localhost:8080/sandbox"
+        );
+        assert_eq!(
+            resolve(
+                endpoint,
+                &servers,
+                &selection(
+                    Some("https://{region}.api.example.com/{version}"),
+                    &[("version", "v2")]
+                )
+            )
+            .unwrap(),
+            "https://us.api.example.com/v2"
+        );
+
+        for (choice, variables, message) in [
+            (Some("2"), vec![], "Server 2 is not listed"),
+            (None, vec![("region", "ap")], "expected one of: us, eu"),
+            (None, vec![("zone", "a")], "has no variable 'zone'"),
+            (
+                Some("https://{tenant}.example.com"),
+                vec![],
+                "--server-var tenant=",
+            ),
+        ] {
+            let err = resolve(endpoint, &servers, &selection(choice, &variables)).unwrap_err();
+            assert!(err.to_string().contains(message), "{}", err);
+        }
+    }
+}
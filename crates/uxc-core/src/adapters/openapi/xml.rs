@@ -0,0 +1,564 @@
+//! XML request and response bodies
+//!
+//! Operations whose request body is documented only as XML
+//! (`application/xml`, `text/xml`, `*+xml`; Swagger 2 `consumes`) take their
+//! arguments as JSON and send them as XML, and XML responses are returned as
+//! JSON. Both directions follow the schema and its `xml` annotations:
+//!
+//! - `name` renames an element or attribute, and names the root element
+//!   (else the name of the referenced component)
+//! - `attribute: true` properties are attributes of their object's element
+//! - arrays are repeated elements named after their items, inside an element
+//!   named after the property when `wrapped: true`
+//! - `namespace` and `prefix` qualify elements when sending
+//!
+//! Scalars are read back with their schema type. XML without a schema is read
+//! generically: `{"root": {"@attribute": "…", "child": "…", "#text": "…"}}`,
+//! with repeated children as arrays.
+
+use super::OpenAPIAdapter;
+use crate::error::UxcError;
+use anyhow::Result;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+/// Whether a media type is XML
+pub(super) fn is_xml(media_type: &str) -> bool {
+    let essence = media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence == "application/xml" || essence == "text/xml" || essence.ends_with("+xml")
+}
+
+/// Media type and schema of an operation's request body when it can only be
+/// sent as XML
+pub(super) fn request_body(operation_spec: &Value, root: &Value) -> Option<(String, Value)> {
+    if let Some(request_body) = operation_spec.get("requestBody") {
+        let content = OpenAPIAdapter::dereference_value(request_body, root)
+            .get("content")?
+            .as_object()?;
+        return only_xml(content.keys()).map(|media_type| {
+            let schema = content[&media_type]
+                .get("schema")
+                .cloned()
+                .unwrap_or(Value::Null);
+            (media_type, schema)
+        });
+    }
+    // Swagger 2
+    let consumes = operation_spec
+        .get("consumes")
+        .or_else(|| root.get("consumes"))?
+        .as_array()?;
+    let media_type = only_xml(consumes.iter().filter_map(Value::as_str))?;
+    let schema = operation_spec
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|parameter| OpenAPIAdapter::dereference_value(parameter, root))
+        .find(|parameter| parameter.get("in").and_then(Value::as_str) == Some("body"))
+        .and_then(|parameter| parameter.get("schema"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    Some((media_type, schema))
+}
+
+/// Media type to ask for when an operation documents XML responses and no
+/// JSON ones
+pub(super) fn accept(operation_spec: &Value, root: &Value) -> Option<String> {
+    let responses = operation_spec.get("responses")?.as_object()?;
+    let mut media_types = Vec::new();
+    for response in responses.values() {
+        let response = OpenAPIAdapter::dereference_value(response, root);
+        if let Some(content) = response.get("content").and_then(Value::as_object) {
+            media_types.extend(content.keys().map(String::as_str));
+        }
+    }
+    if media_types.is_empty() {
+        // Swagger 2
+        let produces = operation_spec
+            .get("produces")
+            .or_else(|| root.get("produces"))?
+            .as_array()?;
+        media_types.extend(produces.iter().filter_map(Value::as_str));
+    }
+    only_xml(media_types)
+}
+
+/// Schema of the XML response an operation documents for `status`
+pub(super) fn response_schema<'a>(
+    operation_spec: &'a Value,
+    root: &'a Value,
+    status: u16,
+) -> Option<&'a Value> {
+    let responses = operation_spec.get("responses")?;
+    let status = status.to_string();
+    let range = format!("{}XX", &status[..1]);
+    let response = [status.as_str(), range.as_str(), "default"]
+        .into_iter()
+        .find_map(|key| responses.get(key))?;
+    let response = OpenAPIAdapter::dereference_value(response, root);
+    if let Some(schema) = response.get("schema") {
+        return Some(schema);
+    }
+    let content = response.get("content")?.as_object()?;
+    content
+        .iter()
+        .find(|(media_type, _)| is_xml(media_type))
+        .and_then(|(_, media)| media.get("schema"))
+}
+
+fn only_xml<S: AsRef<str>>(media_types: impl IntoIterator<Item = S>) -> Option<String> {
+    let media_types: Vec<S> = media_types.into_iter().collect();
+    if media_types
+        .iter()
+        .any(|media_type| media_type.as_ref().contains("json"))
+    {
+        return None;
+    }
+    media_types
+        .iter()
+        .map(AsRef::as_ref)
+        .find(|media_type| is_xml(media_type))
+        .map(ToString::to_string)
+}
+
+/// `value` as an XML document following `schema`
+pub(super) fn to_xml(value: &Value, schema: &Value, root: &Value) -> String {
+    let name = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.rsplit('/').next())
+        .unwrap_or("root");
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    write_element(&mut out, name, value, schema, root);
+    out
+}
+
+/// An XML document read into JSON following `schema`, or generically
+/// without one
+pub(super) fn from_xml(text: &str, schema: Option<&Value>, root: &Value) -> Result<Value> {
+    let element = parse(text)?;
+    Ok(match schema {
+        Some(schema) => convert(&element, schema, root),
+        None => {
+            let mut document = Map::new();
+            document.insert(element.name.clone(), generic(&element));
+            Value::Object(document)
+        }
+    })
+}
+
+fn annotation<'a>(schema: &'a Value, field: &str) -> Option<&'a Value> {
+    schema.get("xml")?.get(field)
+}
+
+fn xml_name<'a>(schema: &'a Value, default: &'a str) -> &'a str {
+    annotation(schema, "name")
+        .and_then(Value::as_str)
+        .unwrap_or(default)
+}
+
+fn is_attribute(schema: &Value) -> bool {
+    annotation(schema, "attribute").and_then(Value::as_bool) == Some(true)
+}
+
+fn is_wrapped(schema: &Value) -> bool {
+    annotation(schema, "wrapped").and_then(Value::as_bool) == Some(true)
+}
+
+/// Properties of an object schema, `allOf` parts included
+fn properties<'a>(schema: &'a Value, root: &'a Value) -> Vec<(&'a String, &'a Value)> {
+    let mut found = Vec::new();
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        found.extend(
+            properties
+                .iter()
+                .map(|(name, property)| (name, OpenAPIAdapter::dereference_value(property, root))),
+        );
+    }
+    for part in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        found.extend(properties(
+            OpenAPIAdapter::dereference_value(part, root),
+            root,
+        ));
+    }
+    found
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn write_element(
+    out: &mut String,
+    default_name: &str,
+    value: &Value,
+    schema: &Value,
+    root: &Value,
+) {
+    let schema = OpenAPIAdapter::dereference_value(schema, root);
+    let name = xml_name(schema, default_name);
+    let tag = match annotation(schema, "prefix").and_then(Value::as_str) {
+        Some(prefix) => format!("{}:{}", prefix, name),
+        None => name.to_string(),
+    };
+    out.push('<');
+    out.push_str(&tag);
+    if let Some(namespace) = annotation(schema, "namespace").and_then(Value::as_str) {
+        let attribute = match annotation(schema, "prefix").and_then(Value::as_str) {
+            Some(prefix) => format!("xmlns:{}", prefix),
+            None => "xmlns".to_string(),
+        };
+        out.push_str(&format!(" {}=\"{}\"", attribute, escape(namespace)));
+    }
+
+    let properties = properties(schema, root);
+    match value {
+        Value::Object(map) => {
+            for (property, property_schema) in &properties {
+                if let (true, Some(value)) = (is_attribute(property_schema), map.get(*property)) {
+                    out.push_str(&format!(
+                        " {}=\"{}\"",
+                        xml_name(property_schema, property),
+                        escape(scalar_text(value))
+                    ));
+                }
+            }
+            out.push('>');
+            // Documented properties in schema order, then the others
+            let documented = properties
+                .iter()
+                .filter(|(property, _)| map.contains_key(*property))
+                .map(|(property, property_schema)| (property.as_str(), *property_schema));
+            let others = map
+                .keys()
+                .filter(|key| !properties.iter().any(|(property, _)| property == key))
+                .map(|key| (key.as_str(), &Value::Null));
+            for (property, property_schema) in documented.chain(others) {
+                if is_attribute(property_schema) || map[property].is_null() {
+                    continue;
+                }
+                write_property(out, property, &map[property], property_schema, root);
+            }
+        }
+        Value::Array(items) => {
+            out.push('>');
+            let item_schema = schema.get("items").unwrap_or(&Value::Null);
+            for item in items {
+                write_element(out, name, item, item_schema, root);
+            }
+        }
+        scalar => {
+            out.push('>');
+            out.push_str(&escape(scalar_text(scalar)));
+        }
+    }
+    out.push_str("</");
+    out.push_str(&tag);
+    out.push('>');
+}
+
+/// A property of an object: arrays are repeated item elements, wrapped in an
+/// element of their own when the schema says so
+fn write_property(out: &mut String, property: &str, value: &Value, schema: &Value, root: &Value) {
+    let Value::Array(items) = value else {
+        return write_element(out, property, value, schema, root);
+    };
+    let item_schema =
+        OpenAPIAdapter::dereference_value(schema.get("items").unwrap_or(&Value::Null), root);
+    let item_name = xml_name(item_schema, property);
+    if is_wrapped(schema) {
+        return write_element(out, property, value, schema, root);
+    }
+    for item in items {
+        write_element(out, item_name, item, item_schema, root);
+    }
+}
+
+/// An element of a parsed document
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+fn malformed(error: impl std::fmt::Display) -> UxcError {
+    UxcError::ExecutionFailed(format!("Response is not well-formed XML: {}", error))
+}
+
+fn local_name(qualified: &[u8]) -> String {
+    let name = String::from_utf8_lossy(qualified);
+    match name.rsplit_once(':') {
+        Some((_, local)) => local.to_string(),
+        None => name.into_owned(),
+    }
+}
+
+fn start(event: &quick_xml::events::BytesStart) -> Result<Element> {
+    let mut element = Element {
+        name: local_name(event.name().as_ref()),
+        ..Default::default()
+    };
+    for attribute in event.attributes() {
+        let attribute = attribute.map_err(malformed)?;
+        let key = attribute.key.as_ref();
+        if key == b"xmlns" || key.starts_with(b"xmlns:") {
+            continue;
+        }
+        let value = attribute.unescape_value().map_err(malformed)?;
+        element
+            .attributes
+            .push((local_name(key), value.into_owned()));
+    }
+    Ok(element)
+}
+
+fn parse(text: &str) -> Result<Element> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut document = None;
+    loop {
+        let finished = match reader.read_event().map_err(malformed)? {
+            Event::Start(event) => {
+                stack.push(start(&event)?);
+                None
+            }
+            Event::Empty(event) => Some(start(&event)?),
+            Event::End(_) => stack.pop(),
+            Event::Text(event) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&event.unescape().map_err(malformed)?);
+                }
+                None
+            }
+            Event::CData(event) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&event.decode().map_err(malformed)?);
+                }
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+        if let Some(element) = finished {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => document = Some(element),
+            }
+        }
+    }
+    document.ok_or_else(|| malformed("no root element").into())
+}
+
+/// `element` as the JSON value `schema` describes
+fn convert(element: &Element, schema: &Value, root: &Value) -> Value {
+    let schema = OpenAPIAdapter::dereference_value(schema, root);
+    let properties = properties(schema, root);
+    if schema.get("type").and_then(Value::as_str) == Some("array") {
+        let item_schema = schema.get("items").unwrap_or(&Value::Null);
+        return Value::Array(
+            element
+                .children
+                .iter()
+                .map(|child| convert(child, item_schema, root))
+                .collect(),
+        );
+    }
+    if properties.is_empty() {
+        return match schema.get("type").and_then(Value::as_str) {
+            Some(_) => typed(&element.text, schema),
+            None => generic(element),
+        };
+    }
+
+    let mut object = Map::new();
+    for (property, property_schema) in properties {
+        let name = xml_name(property_schema, property);
+        if is_attribute(property_schema) {
+            if let Some((_, value)) = element.attributes.iter().find(|(key, _)| key == name) {
+                object.insert(property.clone(), typed(value, property_schema));
+            }
+            continue;
+        }
+        if property_schema.get("type").and_then(Value::as_str) == Some("array") {
+            let item_schema = OpenAPIAdapter::dereference_value(
+                property_schema.get("items").unwrap_or(&Value::Null),
+                root,
+            );
+            let items: Vec<Value> = if is_wrapped(property_schema) {
+                let Some(wrapper) = element.children_named(name).next() else {
+                    continue;
+                };
+                wrapper
+                    .children
+                    .iter()
+                    .map(|child| convert(child, item_schema, root))
+                    .collect()
+            } else {
+                element
+                    .children_named(xml_name(item_schema, property))
+                    .map(|child| convert(child, item_schema, root))
+                    .collect()
+            };
+            if !items.is_empty() || is_wrapped(property_schema) {
+                object.insert(property.clone(), Value::Array(items));
+            }
+            continue;
+        }
+        if let Some(child) = element.children_named(name).next() {
+            object.insert(property.clone(), convert(child, property_schema, root));
+        }
+    }
+    Value::Object(object)
+}
+
+/// `text` as the scalar type of `schema`, falling back to the text
+fn typed(text: &str, schema: &Value) -> Value {
+    let parsed = match schema.get("type").and_then(Value::as_str) {
+        Some("integer") => text.parse::<i64>().ok().map(Value::from),
+        Some("number") => text.parse::<f64>().ok().map(Value::from),
+        Some("boolean") => text.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+/// `element` read without a schema
+fn generic(element: &Element) -> Value {
+    if element.attributes.is_empty() && element.children.is_empty() {
+        return Value::String(element.text.clone());
+    }
+    let mut object = Map::new();
+    for (name, value) in &element.attributes {
+        object.insert(format!("@{}", name), Value::String(value.clone()));
+    }
+    for child in &element.children {
+        let value = generic(child);
+        match object.get_mut(&child.name) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(first) => *first = Value::Array(vec![first.take(), value]),
+            None => {
+                object.insert(child.name.clone(), value);
+            }
+        }
+    }
+    if !element.text.is_empty() {
+        object.insert("#text".to_string(), Value::String(element.text.clone()));
+    }
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn petstore() -> Value {
+        json!({
+            "components": { "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "xml": { "name": "pet" },
+                    "properties": {
+                        "id": { "type": "integer", "xml": { "attribute": true } },
+                        "name": { "type": "string" },
+                        "photoUrls": {
+                            "type": "array",
+                            "xml": { "wrapped": true },
+                            "items": { "type": "string", "xml": { "name": "photoUrl" } }
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": { "$ref": "#/components/schemas/Tag" }
+                        }
+                    }
+                },
+                "Tag": {
+                    "type": "object",
+                    "xml": { "name": "tag" },
+                    "properties": { "name": { "type": "string" } }
+                }
+            }}
+        })
+    }
+
+    #[test]
+    fn json_round_trips_through_annotated_xml() {
+        let root = petstore();
+        let schema = json!({ "$ref": "#/components/schemas/Pet" });
+        let pet = json!({
+            "id": 7,
+            "name": "Rex & co",
+            "photoUrls": ["a.png", "b.png"],
+            "tags": [{ "name": "good" }, { "name": "dog" }]
+        });
+
+        let xml = to_xml(&pet, &schema, &root);
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><pet id=\"7\"><name>Rex &amp; co</name>\
+             <photoUrls><photoUrl>a.png</photoUrl><photoUrl>b.png</photoUrl></photoUrls>\
+             <tag><name>good</name></tag><tag><name>dog</name></tag></pet>"
+        );
+        assert_eq!(from_xml(&xml, Some(&schema), &root).unwrap(), pet);
+    }
+
+    #[test]
+    fn xml_without_schema_reads_generically() {
+        let xml = r#"<ns:list xmlns:ns="urn:x" count="2"><item>a</item><item>b</item><note><![CDATA[<ok>]]></note></ns:list>"#;
+        assert_eq!(
+            from_xml(xml, None, &Value::Null).unwrap(),
+            json!({ "list": { "@count": "2", "item": ["a", "b"], "note": "<ok>" } })
+        );
+        assert!(from_xml("<a><b></a>", None, &Value::Null).is_err());
+    }
+
+    #[test]
+    fn only_xml_bodies_are_sent_as_xml() {
+        let root = json!({ "consumes": ["application/xml"] });
+        let xml_only = json!({
+            "requestBody": { "content": { "application/xml": { "schema": { "type": "object" } } } }
+        });
+        let both = json!({
+            "requestBody": { "content": {
+                "application/json": {}, "application/xml": {}
+            } }
+        });
+        assert_eq!(
+            request_body(&xml_only, &Value::Null).map(|(media_type, _)| media_type),
+            Some("application/xml".to_string())
+        );
+        assert!(request_body(&both, &Value::Null).is_none());
+        assert_eq!(
+            request_body(&json!({}), &root).map(|(media_type, _)| media_type),
+            Some("application/xml".to_string())
+        );
+        assert!(is_xml("application/atom+xml; charset=utf-8"));
+        assert!(!is_xml("application/json"));
+    }
+}
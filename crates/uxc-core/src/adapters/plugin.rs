@@ -0,0 +1,309 @@
+//! Adapter for protocol plugins
+//!
+//! A plugin is a dynamic library exporting three C functions, version 1 of
+//! the plugin ABI:
+//!
+//! ```c
+//! uint32_t uxc_plugin_abi_version(void);   /* returns 1 */
+//! char *uxc_plugin_call(const char *request);
+//! void uxc_plugin_free(char *response);
+//! ```
+//!
+//! `uxc_plugin_call` takes a request and returns a response, both
+//! NUL-terminated UTF-8 JSON; the response is released with
+//! `uxc_plugin_free`. Calls may come from several threads at once. A request
+//! names an [`Adapter`] method with its arguments:
+//!
+//! ```json
+//! {"method": "execute", "url": "fix://broker:9876", "operation": "NewOrderSingle", "args": {"qty": 100}}
+//! ```
+//!
+//! and is answered with `{"result": ...}` or
+//! `{"error": {"code": "OPERATION_NOT_FOUND", "message": "..."}}`. The
+//! methods and their results:
+//!
+//! - `can_handle` (`url`): a boolean
+//! - `fetch_schema` (`url`): any JSON
+//! - `list_operations` (`url`): an array of [`Operation`]
+//! - `describe_operation` (`url`, `operation`): an [`OperationDetail`]
+//! - `execute` (`url`, `operation`, `args`): `{"data": ..., "status": 200}`,
+//!   `status` being optional
+//! - `plan` (`url`, `operation`, `args`): a [`RequestPlan`]; plugins without
+//!   one answer with the `METHOD_NOT_FOUND` error code
+//!
+//! Error codes are those of uxc envelopes (`INVALID_ARGUMENT`, `TIMEOUT`,
+//! ...); others are reported as `EXECUTION_FAILED`.
+
+use super::{
+    Adapter, ExecutionMetadata, ExecutionResult, Operation, OperationDetail, ProtocolType,
+    RequestPlan,
+};
+use crate::error::UxcError;
+use crate::plugins::InstalledPlugin;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Version of the plugin ABI this build speaks
+pub const ABI_VERSION: u32 = 1;
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type CallFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// A loaded plugin library
+#[derive(Debug)]
+pub struct PluginLibrary {
+    library: libloading::Library,
+}
+
+impl PluginLibrary {
+    /// Load the library at `path` and check it exports the plugin ABI
+    pub fn load(path: &Path) -> Result<Self> {
+        // SAFETY: loading a library runs its initializers; installing a
+        // plugin is trusting its code
+        let library = unsafe { libloading::Library::new(path) }
+            .with_context(|| format!("Failed to load {:?}", path))?;
+        // SAFETY: the symbol has the signature the ABI prescribes
+        let version = unsafe {
+            let abi_version: libloading::Symbol<AbiVersionFn> = library
+                .get(b"uxc_plugin_abi_version\0")
+                .context("The library does not export uxc_plugin_abi_version")?;
+            abi_version()
+        };
+        if version != ABI_VERSION {
+            anyhow::bail!(
+                "The library speaks plugin ABI version {}; this uxc speaks version {}",
+                version,
+                ABI_VERSION
+            );
+        }
+        // SAFETY: only checks the symbols exist
+        unsafe {
+            library
+                .get::<CallFn>(b"uxc_plugin_call\0")
+                .context("The library does not export uxc_plugin_call")?;
+            library
+                .get::<FreeFn>(b"uxc_plugin_free\0")
+                .context("The library does not export uxc_plugin_free")?;
+        }
+        Ok(Self { library })
+    }
+
+    /// Send `request` and return the `result` of the response
+    pub fn call(&self, request: &Value) -> Result<Value> {
+        let request = CString::new(request.to_string())?;
+        // SAFETY: the symbols were checked by `load` and have the ABI's
+        // signatures; the response is copied before it is handed back to
+        // `uxc_plugin_free`
+        let response = unsafe {
+            let call: libloading::Symbol<CallFn> = self.library.get(b"uxc_plugin_call\0")?;
+            let free: libloading::Symbol<FreeFn> = self.library.get(b"uxc_plugin_free\0")?;
+            let response = call(request.as_ptr());
+            if response.is_null() {
+                return Err(UxcError::ExecutionFailed(
+                    "The plugin returned no response".to_string(),
+                )
+                .into());
+            }
+            let copied = CStr::from_ptr(response).to_string_lossy().into_owned();
+            free(response);
+            copied
+        };
+
+        let mut response: Value = serde_json::from_str(&response).map_err(|e| {
+            UxcError::ExecutionFailed(format!("The plugin answered with invalid JSON: {}", e))
+        })?;
+        if let Some(error) = response.get("error") {
+            return Err(plugin_error(error).into());
+        }
+        response.get_mut("result").map(Value::take).ok_or_else(|| {
+            UxcError::ExecutionFailed("The plugin answered without a result".to_string()).into()
+        })
+    }
+}
+
+/// Error answered by a plugin, under the code it gave
+fn plugin_error(error: &Value) -> UxcError {
+    let message = error
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("The plugin reported an error")
+        .to_string();
+    match error
+        .get("code")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+    {
+        "OPERATION_NOT_FOUND" => UxcError::OperationNotFound(message),
+        "INVALID_ARGUMENT" => UxcError::InvalidArguments(message),
+        "TIMEOUT" => UxcError::Timeout(message),
+        "HOST_NOT_ALLOWED" => UxcError::HostNotAllowed(message),
+        "METHOD_NOT_FOUND" => UxcError::UnsupportedProtocol(message),
+        _ => UxcError::ExecutionFailed(message),
+    }
+}
+
+/// Adapter forwarding every call to a plugin
+#[derive(Debug, Clone)]
+pub struct PluginAdapter {
+    plugin: InstalledPlugin,
+    library: Arc<PluginLibrary>,
+}
+
+impl PluginAdapter {
+    pub fn new(plugin: InstalledPlugin) -> Result<Self> {
+        let library = Arc::new(plugin.load()?);
+        Ok(Self { plugin, library })
+    }
+
+    pub fn plugin(&self) -> &InstalledPlugin {
+        &self.plugin
+    }
+
+    /// Call `method` on the plugin, off the async runtime
+    async fn call(&self, request: Value) -> Result<Value> {
+        let library = self.library.clone();
+        tokio::task::spawn_blocking(move || library.call(&request))
+            .await
+            .with_context(|| format!("Plugin '{}' panicked", self.plugin.manifest.name))?
+    }
+
+    async fn call_for<T: serde::de::DeserializeOwned>(&self, request: Value) -> Result<T> {
+        let method = request["method"].clone();
+        let result = self.call(request).await?;
+        serde_json::from_value(result).map_err(|e| {
+            UxcError::ExecutionFailed(format!(
+                "Plugin '{}' answered {} with an unexpected result: {}",
+                self.plugin.manifest.name, method, e
+            ))
+            .into()
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Adapter for PluginAdapter {
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Plugin
+    }
+
+    async fn can_handle(&self, url: &str) -> Result<bool> {
+        self.call_for(json!({ "method": "can_handle", "url": url }))
+            .await
+    }
+
+    async fn fetch_schema(&self, url: &str) -> Result<Value> {
+        self.call(json!({ "method": "fetch_schema", "url": url }))
+            .await
+    }
+
+    async fn list_operations(&self, url: &str) -> Result<Vec<Operation>> {
+        self.call_for(json!({ "method": "list_operations", "url": url }))
+            .await
+    }
+
+    async fn describe_operation(&self, url: &str, operation: &str) -> Result<OperationDetail> {
+        self.call_for(json!({
+            "method": "describe_operation",
+            "url": url,
+            "operation": operation,
+        }))
+        .await
+    }
+
+    async fn execute(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult> {
+        let start = Instant::now();
+        let mut result = self
+            .call(json!({
+                "method": "execute",
+                "url": url,
+                "operation": operation,
+                "args": args,
+            }))
+            .await?;
+        let status = result
+            .get("status")
+            .and_then(Value::as_u64)
+            .and_then(|status| u16::try_from(status).ok());
+        Ok(ExecutionResult {
+            data: result.get_mut("data").map(Value::take).unwrap_or_default(),
+            metadata: ExecutionMetadata {
+                duration_ms: start.elapsed().as_millis() as u64,
+                operation: operation.to_string(),
+                response_headers: Vec::new(),
+                status,
+            },
+        })
+    }
+
+    async fn plan(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        let request = json!({
+            "method": "plan",
+            "url": url,
+            "operation": operation,
+            "args": args,
+        });
+        match self.call_for(request).await {
+            Err(e)
+                if e.chain().any(|cause| {
+                    matches!(cause.downcast_ref(), Some(UxcError::UnsupportedProtocol(_)))
+                }) =>
+            {
+                Ok(RequestPlan::new(
+                    "plugin",
+                    Some(operation.to_string()),
+                    url.to_string(),
+                    json!(args),
+                )
+                .with_note(format!(
+                    "sent by plugin '{}', which does not describe its requests",
+                    self.plugin.manifest.name
+                )))
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_errors_keep_their_codes() {
+        let error = plugin_error(&json!({ "code": "OPERATION_NOT_FOUND", "message": "no Foo" }));
+        assert_eq!(error.code(), "OPERATION_NOT_FOUND");
+        assert_eq!(error.to_string(), "Operation not found: no Foo");
+        assert_eq!(
+            plugin_error(&json!({ "code": "TIMEOUT" })).code(),
+            "TIMEOUT"
+        );
+        assert_eq!(
+            plugin_error(&json!({ "code": "SESSION_REJECTED", "message": "logon" })).code(),
+            "EXECUTION_FAILED"
+        );
+    }
+
+    #[test]
+    fn libraries_without_the_abi_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("libnot_a_plugin.so");
+        std::fs::write(&path, b"not a library").unwrap();
+        assert!(PluginLibrary::load(&path).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Probe evidence for detection reports
+//!
+//! While [`ProtocolDetector::report`](super::ProtocolDetector::report) runs
+//! a probe, the adapter notes what it saw with [`evidence`]: the paths it
+//! tried, the answers it got, why an answer did not count. Outside a report
+//! the notes are dropped.
+//!
+//! Probes also run under the timeout configured for their protocol
+//! (`[detection] timeout`): [`limit`] stops them when it passes, and their
+//! requests wait as long as [`timeout`] says.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    static EVIDENCE: Arc<Mutex<Vec<String>>>;
+    static TIMEOUT: Duration;
+}
+
+/// Outcome of one probe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeReport {
+    /// Name of the adapter in the [`super::registry`], or of the plugin
+    pub adapter: String,
+    pub matched: bool,
+    /// What the probe saw, in order
+    pub evidence: Vec<String>,
+    /// Why the probe could not finish
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Every probe of an endpoint, and the adapter detection would pick
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionReport {
+    pub url: String,
+    /// Probes in detection order
+    pub probes: Vec<ProbeReport>,
+    /// First matching probe in detection order, or the `--protocol` one
+    /// when it matched
+    pub selected: Option<String>,
+}
+
+/// Note what the running probe saw. Credentials in `note` are masked.
+pub fn evidence(note: impl Into<String>) {
+    let _ = EVIDENCE.try_with(|evidence| {
+        evidence
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(crate::masking::mask(&note.into()));
+    });
+}
+
+/// How long a request of the running probe may take: the configured probe
+/// timeout, or `default` outside probes and when none is configured
+pub fn timeout(default: Duration) -> Duration {
+    TIMEOUT.try_with(|timeout| *timeout).unwrap_or(default)
+}
+
+/// Run `probe` within `timeout`. A probe that runs out of time did not
+/// match.
+pub(crate) async fn limit<F>(timeout: Option<Duration>, probe: F) -> Result<bool>
+where
+    F: Future<Output = Result<bool>>,
+{
+    let Some(timeout) = timeout else {
+        return probe.await;
+    };
+    match tokio::time::timeout(timeout, TIMEOUT.scope(timeout, probe)).await {
+        Ok(result) => result,
+        Err(_) => {
+            evidence(format!(
+                "no answer within {}",
+                crate::output::format_duration(timeout.as_millis() as u64)
+            ));
+            Ok(false)
+        }
+    }
+}
+
+/// Run the probe of `adapter`, collecting the evidence it notes
+pub(crate) async fn run<F>(adapter: &str, probe: F) -> ProbeReport
+where
+    F: Future<Output = Result<bool>>,
+{
+    let evidence = Arc::new(Mutex::new(Vec::new()));
+    let start = Instant::now();
+    let result = EVIDENCE.scope(evidence.clone(), probe).await;
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let evidence = std::mem::take(&mut *evidence.lock().unwrap_or_else(|e| e.into_inner()));
+    ProbeReport {
+        adapter: adapter.to_string(),
+        matched: matches!(result, Ok(true)),
+        evidence,
+        error: result.err().map(|e| format!("{:#}", e)),
+        duration_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn probes_collect_their_own_evidence() {
+        crate::masking::register("secret-value");
+        evidence("outside any probe");
+        let (first, second) = tokio::join!(
+            run("first", async {
+                evidence("GET /openapi.json: HTTP 404");
+                tokio::task::yield_now().await;
+                evidence("GET /swagger.json: OpenAPI document");
+                Ok(true)
+            }),
+            run("second", async {
+                evidence("token=secret-value");
+                anyhow::bail!("connection refused")
+            }),
+        );
+
+        assert!(first.matched);
+        assert_eq!(
+            first.evidence,
+            [
+                "GET /openapi.json: HTTP 404",
+                "GET /swagger.json: OpenAPI document"
+            ]
+        );
+        assert!(!second.matched);
+        assert_eq!(second.error.as_deref(), Some("connection refused"));
+        assert!(!second.evidence[0].contains("secret-value"));
+    }
+
+    #[tokio::test]
+    async fn probes_stop_at_their_timeout() {
+        let limited = Some(Duration::from_millis(50));
+        let slow = run(
+            "slow",
+            limit(limited, async {
+                assert_eq!(timeout(Duration::from_secs(2)), Duration::from_millis(50));
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(true)
+            }),
+        )
+        .await;
+        assert!(!slow.matched);
+        assert!(slow.error.is_none());
+        assert_eq!(slow.evidence, ["no answer within 50 ms"]);
+
+        assert!(limit(None, async { Ok(true) }).await.unwrap());
+        assert_eq!(timeout(Duration::from_secs(2)), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,307 @@
+//! Adapter registry
+//!
+//! Detection probes the adapters of the registry, highest priority first,
+//! and uses the first that can handle the endpoint. The registry starts with
+//! the built-in protocols; programs that embed uxc add their own adapters
+//! with [`register`]:
+//!
+//! ```no_run
+//! # fn setup(adapter: std::sync::Arc<dyn uxc_core::adapters::Adapter>) {
+//! use uxc_core::adapters::registry;
+//!
+//! // Probed after GraphQL (400) and before OpenAPI (300)
+//! registry::register("thrift", 350, adapter);
+//! # }
+//! ```
+//!
+//! Built-in priorities: mcp 500, graphql 400, openapi 300, jsonrpc 200 and
+//! grpc 100. Adapters of the same priority are probed in the order they were
+//! registered. Plugins ([`crate::plugins`]) serve URL schemes and are chosen
+//! before any probe.
+
+use super::{graphql, Adapter};
+use super::{grpc, jsonrpc, mcp, openapi, AdapterEnum, DetectionOptions, ProtocolType};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Where the adapter of a registration comes from
+#[derive(Clone)]
+enum Source {
+    /// Built in, configured with the detection options when probed
+    Builtin(ProtocolType),
+    Custom(Arc<dyn Adapter>),
+}
+
+/// An adapter detection may pick
+#[derive(Clone)]
+pub struct Registration {
+    name: String,
+    priority: i32,
+    source: Source,
+}
+
+impl Registration {
+    fn builtin(protocol: ProtocolType, priority: i32) -> Self {
+        Self {
+            name: protocol.as_str().to_string(),
+            priority,
+            source: Source::Builtin(protocol),
+        }
+    }
+
+    /// Registration of `protocol` outside any registry, for probes that
+    /// name it
+    pub(crate) fn of(protocol: ProtocolType) -> Self {
+        Self::builtin(protocol, 0)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Built-in protocol of the registration, `None` for custom adapters
+    pub fn protocol(&self) -> Option<ProtocolType> {
+        match self.source {
+            Source::Builtin(protocol) => Some(protocol),
+            Source::Custom(_) => None,
+        }
+    }
+
+    /// The adapter to probe, with `options` applied to built-in ones
+    pub fn adapter(&self, options: &DetectionOptions) -> AdapterEnum {
+        match &self.source {
+            Source::Builtin(protocol) => builtin(*protocol, options),
+            Source::Custom(adapter) => AdapterEnum::Custom(adapter.clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Registration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registration")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .field("builtin", &self.protocol().is_some())
+            .finish()
+    }
+}
+
+/// Adapters in probe order
+#[derive(Debug, Clone)]
+pub struct Registry {
+    registrations: Vec<Registration>,
+}
+
+impl Registry {
+    /// The built-in protocols, in their default detection order
+    pub fn builtin() -> Self {
+        Self {
+            registrations: vec![
+                Registration::builtin(ProtocolType::Mcp, 500),
+                Registration::builtin(ProtocolType::GraphQL, 400),
+                Registration::builtin(ProtocolType::OpenAPI, 300),
+                Registration::builtin(ProtocolType::JsonRpc, 200),
+                Registration::builtin(ProtocolType::GRpc, 100),
+            ],
+        }
+    }
+
+    /// Add `adapter` under `name`, replacing the registration of that name
+    pub fn register(&mut self, name: impl Into<String>, priority: i32, adapter: Arc<dyn Adapter>) {
+        let name = name.into();
+        self.unregister(&name);
+        let at = self
+            .registrations
+            .iter()
+            .position(|registration| registration.priority < priority)
+            .unwrap_or(self.registrations.len());
+        self.registrations.insert(
+            at,
+            Registration {
+                name,
+                priority,
+                source: Source::Custom(adapter),
+            },
+        );
+    }
+
+    /// Remove the registration named `name`, built-in ones included.
+    /// Returns whether there was one.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let before = self.registrations.len();
+        self.registrations
+            .retain(|registration| registration.name != name);
+        self.registrations.len() != before
+    }
+
+    /// Registrations, highest priority first
+    pub fn registrations(&self) -> &[Registration] {
+        &self.registrations
+    }
+
+    /// Registrations of built-in protocols, highest priority first
+    pub fn protocols(&self) -> Vec<ProtocolType> {
+        self.registrations
+            .iter()
+            .filter_map(Registration::protocol)
+            .collect()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn global() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::builtin()))
+}
+
+/// Add `adapter` to the registry of the process under `name`, replacing the
+/// registration of that name
+pub fn register(name: impl Into<String>, priority: i32, adapter: Arc<dyn Adapter>) {
+    global()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(name, priority, adapter);
+}
+
+/// Remove `name` from the registry of the process, so detection no longer
+/// probes it. Returns whether it was registered.
+pub fn unregister(name: &str) -> bool {
+    global()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .unregister(name)
+}
+
+/// The registry of the process, as it is now
+pub fn registry() -> Registry {
+    global().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Unconfigured adapter for `protocol` with the detection options applied
+pub(crate) fn builtin(protocol: ProtocolType, options: &DetectionOptions) -> AdapterEnum {
+    match protocol {
+        ProtocolType::Mcp => AdapterEnum::Mcp(mcp::McpAdapter::new()),
+        ProtocolType::GraphQL => AdapterEnum::GraphQL(
+            graphql::GraphQLAdapter::new()
+                .with_schema_url(options.schema_url.clone())
+                .with_selection(options.selection.clone())
+                .with_persisted_queries(options.persisted_queries.clone())
+                .with_endpoint_paths(options.paths(protocol)),
+        ),
+        ProtocolType::OpenAPI => AdapterEnum::OpenAPI(
+            openapi::OpenAPIAdapter::new()
+                .with_schema_url_override(options.schema_url.clone())
+                .with_server(options.server.clone())
+                .with_schema_paths(options.paths(protocol)),
+        ),
+        ProtocolType::JsonRpc => AdapterEnum::JsonRpc(
+            jsonrpc::JsonRpcAdapter::new().with_version(options.jsonrpc_version),
+        ),
+        ProtocolType::GRpc => {
+            AdapterEnum::GRpc(grpc::GrpcAdapter::new().with_connection_config(options.grpc.clone()))
+        }
+        // Plugins are picked by URL scheme, and `parse` never yields them
+        ProtocolType::Plugin => unreachable!("plugins are not probed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{ExecutionResult, Operation, OperationDetail, RequestPlan};
+    use anyhow::Result;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    struct Nothing;
+
+    #[async_trait::async_trait]
+    impl Adapter for Nothing {
+        fn protocol_type(&self) -> ProtocolType {
+            ProtocolType::Plugin
+        }
+
+        async fn can_handle(&self, _url: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn fetch_schema(&self, _url: &str) -> Result<Value> {
+            Ok(Value::Null)
+        }
+
+        async fn list_operations(&self, _url: &str) -> Result<Vec<Operation>> {
+            Ok(Vec::new())
+        }
+
+        async fn describe_operation(&self, _url: &str, _op: &str) -> Result<OperationDetail> {
+            unimplemented!()
+        }
+
+        async fn execute(
+            &self,
+            _url: &str,
+            _op: &str,
+            _args: HashMap<String, Value>,
+        ) -> Result<ExecutionResult> {
+            unimplemented!()
+        }
+
+        async fn plan(
+            &self,
+            _url: &str,
+            _op: &str,
+            _args: HashMap<String, Value>,
+        ) -> Result<RequestPlan> {
+            unimplemented!()
+        }
+    }
+
+    fn names(registry: &Registry) -> Vec<&str> {
+        registry
+            .registrations()
+            .iter()
+            .map(Registration::name)
+            .collect()
+    }
+
+    #[test]
+    fn registrations_are_ordered_by_priority_then_age() {
+        let mut registry = Registry::builtin();
+        registry.register("first", 300, Arc::new(Nothing));
+        registry.register("second", 300, Arc::new(Nothing));
+        registry.register("last", -1, Arc::new(Nothing));
+        assert_eq!(
+            names(&registry),
+            ["mcp", "graphql", "openapi", "first", "second", "jsonrpc", "grpc", "last"]
+        );
+        assert!(registry.registrations()[3].protocol().is_none());
+    }
+
+    #[test]
+    fn registering_a_name_again_replaces_it() {
+        let mut registry = Registry::builtin();
+        registry.register("grpc", 1000, Arc::new(Nothing));
+        assert_eq!(names(&registry)[0], "grpc");
+        assert_eq!(registry.registrations().len(), 5);
+        assert!(!registry.protocols().contains(&ProtocolType::GRpc));
+
+        assert!(registry.unregister("mcp"));
+        assert!(!registry.unregister("mcp"));
+        assert_eq!(
+            registry.protocols(),
+            [
+                ProtocolType::GraphQL,
+                ProtocolType::OpenAPI,
+                ProtocolType::JsonRpc
+            ]
+        );
+    }
+}
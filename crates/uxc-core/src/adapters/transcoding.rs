@@ -0,0 +1,384 @@
+//! gRPC-JSON transcoding (`google.api.http` annotations)
+//!
+//! Methods annotated with `option (google.api.http) = { get: "/v1/{name}" }`
+//! can also be called as REST requests through a gRPC-JSON gateway
+//! (grpc-gateway, Envoy, Cloud Endpoints). The gRPC adapter reads the
+//! annotations from the reflected file descriptors, shows them in `describe`
+//! and, with `--via-http <gateway>` (or `[grpc] http_gateway`), sends calls
+//! through the gateway when the gRPC server cannot be reached.
+//!
+//! Annotations are an extension of `MethodOptions` that the generic
+//! descriptor types drop while decoding, so [`http_bindings`] decodes the raw
+//! descriptor bytes with just the fields it needs.
+//!
+//! Requests follow the `google.api.HttpRule` mapping: fields named in the
+//! path template are substituted into the path, `body: "*"` sends the rest of
+//! the message as the JSON body, `body: "<field>"` sends that field, and
+//! everything else becomes query parameters (nested fields as `a.b=...`).
+
+use crate::error::UxcError;
+use anyhow::Result;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// Just enough of descriptor.proto and google/api/http.proto to reach the
+// annotation; other fields are skipped while decoding.
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptor {
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "6")]
+    service: Vec<ServiceDescriptor>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ServiceDescriptor {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    method: Vec<MethodDescriptor>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodDescriptor {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    options: Option<MethodOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodOptions {
+    /// The `google.api.http` extension
+    #[prost(message, optional, tag = "72295728")]
+    http: Option<HttpRule>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HttpRule {
+    #[prost(string, tag = "2")]
+    get: String,
+    #[prost(string, tag = "3")]
+    put: String,
+    #[prost(string, tag = "4")]
+    post: String,
+    #[prost(string, tag = "5")]
+    delete: String,
+    #[prost(string, tag = "6")]
+    patch: String,
+    #[prost(string, tag = "7")]
+    body: String,
+    #[prost(message, optional, tag = "8")]
+    custom: Option<CustomHttpPattern>,
+    #[prost(message, repeated, tag = "11")]
+    additional_bindings: Vec<HttpRule>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CustomHttpPattern {
+    #[prost(string, tag = "1")]
+    kind: String,
+    #[prost(string, tag = "2")]
+    path: String,
+}
+
+/// One REST mapping of a gRPC method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpBinding {
+    /// HTTP method, upper case
+    pub method: String,
+
+    /// Path template, e.g. `/v1/{name=shelves/*}/books`
+    pub path: String,
+
+    /// `*` for the whole message, a field name, or `None` for no body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl HttpRule {
+    fn binding(&self) -> Option<HttpBinding> {
+        let (method, path) = [
+            ("GET", &self.get),
+            ("PUT", &self.put),
+            ("POST", &self.post),
+            ("DELETE", &self.delete),
+            ("PATCH", &self.patch),
+        ]
+        .into_iter()
+        .find(|(_, path)| !path.is_empty())
+        .map(|(method, path)| (method.to_string(), path.clone()))
+        .or_else(|| {
+            let custom = self.custom.as_ref()?;
+            Some((custom.kind.to_uppercase(), custom.path.clone()))
+        })?;
+        Some(HttpBinding {
+            method,
+            path,
+            body: Some(self.body.clone()).filter(|body| !body.is_empty()),
+        })
+    }
+}
+
+/// REST mappings of the methods in a serialized `FileDescriptorProto`, keyed
+/// by `package.Service/Method`; the primary binding comes first
+pub fn http_bindings(file_descriptor: &[u8]) -> HashMap<String, Vec<HttpBinding>> {
+    let Ok(file) = FileDescriptor::decode(file_descriptor) else {
+        return HashMap::new();
+    };
+    let package = file.package.unwrap_or_default();
+    let mut bindings = HashMap::new();
+    for service in &file.service {
+        let service_name = service.name.clone().unwrap_or_default();
+        let full_service = if package.is_empty() {
+            service_name
+        } else {
+            format!("{}.{}", package, service_name)
+        };
+        for method in &service.method {
+            let Some(rule) = method
+                .options
+                .as_ref()
+                .and_then(|options| options.http.as_ref())
+            else {
+                continue;
+            };
+            let found = std::iter::once(rule)
+                .chain(&rule.additional_bindings)
+                .filter_map(HttpRule::binding)
+                .collect::<Vec<_>>();
+            if !found.is_empty() {
+                let method_name = method.name.clone().unwrap_or_default();
+                bindings.insert(format!("{}/{}", full_service, method_name), found);
+            }
+        }
+    }
+    bindings
+}
+
+/// A REST request derived from a binding
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpCall {
+    pub method: String,
+
+    /// Path with variables substituted, and the query string
+    pub path_and_query: String,
+
+    pub body: Option<Value>,
+}
+
+impl HttpBinding {
+    /// `GET /v1/{name}` as shown by `describe`
+    pub fn display(&self) -> String {
+        match &self.body {
+            Some(body) => format!("{} {} (body: {})", self.method, self.path, body),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+
+    /// The request this binding maps `message` to
+    pub fn request(&self, message: &Value) -> Result<HttpCall> {
+        let mut fields = message.as_object().cloned().unwrap_or_default();
+        let path = substitute_path(&self.path, &mut fields)?;
+
+        let body = match self.body.as_deref() {
+            Some("*") => Some(Value::Object(std::mem::take(&mut fields))),
+            Some(field) => Some(fields.remove(field).unwrap_or(Value::Null)),
+            None => None,
+        };
+        let mut query = Vec::new();
+        query_pairs("", &Value::Object(fields), &mut query);
+        let path_and_query = if query.is_empty() {
+            path
+        } else {
+            let query = query
+                .iter()
+                .map(|(name, value)| format!("{}={}", encode(name, false), encode(value, false)))
+                .collect::<Vec<_>>();
+            format!("{}?{}", path, query.join("&"))
+        };
+        Ok(HttpCall {
+            method: self.method.clone(),
+            path_and_query,
+            body,
+        })
+    }
+}
+
+/// Substitute `{field}` and `{field=pattern}` variables, taking the fields
+/// out of `fields`
+fn substitute_path(template: &str, fields: &mut Map<String, Value>) -> Result<String> {
+    let mut path = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        path.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Unterminated variable in path template {}", template)
+            })?;
+        let variable = &rest[start + 1..end];
+        let (field, pattern) = match variable.split_once('=') {
+            Some((field, pattern)) => (field, Some(pattern)),
+            None => (variable, None),
+        };
+        let value = take_field(fields, field).ok_or_else(|| {
+            UxcError::InvalidArguments(format!(
+                "'{}' is required to build the HTTP path {}",
+                field, template
+            ))
+        })?;
+        let text = scalar_text(&value);
+        // Multi-segment patterns (`shelves/*`, `**`) keep their slashes
+        let multi_segment = pattern.is_some_and(|pattern| pattern != "*");
+        path.push_str(&encode(&text, multi_segment));
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+    Ok(path)
+}
+
+/// Remove the field at dotted `path` from `fields`
+fn take_field(fields: &mut Map<String, Value>, path: &str) -> Option<Value> {
+    match path.split_once('.') {
+        Some((head, tail)) => take_field(fields.get_mut(head)?.as_object_mut()?, tail),
+        None => fields.remove(path),
+    }
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Query parameters for the fields of `value`, nested fields as `a.b`
+fn query_pairs(prefix: &str, value: &Value, pairs: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) => {
+            let mut names = fields.keys().collect::<Vec<_>>();
+            names.sort();
+            for name in names {
+                let nested = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", prefix, name)
+                };
+                query_pairs(&nested, &fields[name], pairs);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                query_pairs(prefix, item, pairs);
+            }
+        }
+        Value::Null => {}
+        scalar => pairs.push((prefix.to_string(), scalar_text(scalar))),
+    }
+}
+
+/// Percent-encode everything but unreserved characters (and `/` in paths
+/// that span segments)
+fn encode(text: &str, keep_slashes: bool) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        let unreserved = byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+        if unreserved || (keep_slashes && byte == b'/') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn descriptor() -> Vec<u8> {
+        let get = HttpRule {
+            get: "/v1/{name=shelves/*}".to_string(),
+            additional_bindings: vec![HttpRule {
+                get: "/v1/shelf/{name}".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let create = HttpRule {
+            post: "/v1/shelves/{shelf_id}/books".to_string(),
+            body: "book".to_string(),
+            ..Default::default()
+        };
+        let method = |name: &str, http: Option<HttpRule>| MethodDescriptor {
+            name: Some(name.to_string()),
+            options: Some(MethodOptions { http }),
+        };
+        FileDescriptor {
+            package: Some("library.v1".to_string()),
+            service: vec![ServiceDescriptor {
+                name: Some("Library".to_string()),
+                method: vec![
+                    method("GetShelf", Some(get)),
+                    method("CreateBook", Some(create)),
+                    method("Ping", None),
+                ],
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn annotations_are_read_from_raw_descriptors() {
+        let bindings = http_bindings(&descriptor());
+        assert_eq!(bindings.len(), 2);
+        let get = &bindings["library.v1.Library/GetShelf"];
+        assert_eq!(get[0].display(), "GET /v1/{name=shelves/*}");
+        assert_eq!(get[1].path, "/v1/shelf/{name}");
+        assert_eq!(
+            bindings["library.v1.Library/CreateBook"][0].body.as_deref(),
+            Some("book")
+        );
+    }
+
+    #[test]
+    fn messages_map_to_path_body_and_query() {
+        let get = HttpBinding {
+            method: "GET".to_string(),
+            path: "/v1/{name=shelves/*}".to_string(),
+            body: None,
+        };
+        let call = get
+            .request(&json!({
+                "name": "shelves/my shelf",
+                "view": "FULL",
+                "filter": { "genre": ["sf", "crime"] }
+            }))
+            .unwrap();
+        assert_eq!(
+            call.path_and_query,
+            "/v1/shelves/my%20shelf?filter.genre=sf&filter.genre=crime&view=FULL"
+        );
+        assert_eq!(call.body, None);
+
+        let create = HttpBinding {
+            method: "POST".to_string(),
+            path: "/v1/shelves/{shelf_id}/books".to_string(),
+            body: Some("book".to_string()),
+        };
+        let call = create
+            .request(&json!({ "shelf_id": 7, "book": { "title": "Dune" }, "validate": true }))
+            .unwrap();
+        assert_eq!(call.path_and_query, "/v1/shelves/7/books?validate=true");
+        assert_eq!(call.body, Some(json!({ "title": "Dune" })));
+
+        let err = create.request(&json!({})).unwrap_err().to_string();
+        assert!(err.contains("'shelf_id' is required"), "{}", err);
+    }
+}
@@ -0,0 +1,327 @@
+//! Endpoint aliases
+//!
+//! An alias is a short name for an endpoint URL, stored in the `[aliases]`
+//! tables of `~/.uxc/config.toml` and accepted anywhere a URL is:
+//!
+//! ```toml
+//! [aliases.gh]
+//! url = "https://api.github.com"
+//! profile = "github"
+//! protocol = "openapi"
+//! headers = { "Accept" = "application/vnd.github+json" }
+//! ```
+//!
+//! Besides the URL an alias can bundle a default auth profile, an OpenAPI
+//! schema URL, the protocol of the endpoint (which skips detection), extra
+//! HTTP headers and hook scripts ([`crate::hooks`]). Aliases are managed with
+//! `uxc alias set|list|remove`; saving rewrites only the alias tables and
+//! leaves the rest of the file as it is.
+
+use crate::hooks::HookConfig;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_DIR: &str = ".uxc";
+const CONFIG_FILE: &str = "config.toml";
+
+/// One aliased endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alias {
+    pub url: String,
+
+    /// Auth profile used unless `--profile` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// OpenAPI schema URL used unless `--schema-url` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_url: Option<String>,
+
+    /// Protocol used unless `--protocol` is given, as named by
+    /// [`crate::adapters::ProtocolType::as_str`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+
+    /// Headers added to every HTTP request to the endpoint
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+
+    /// Scripts run around calls to the endpoint
+    #[serde(default, skip_serializing_if = "HookConfig::is_empty")]
+    pub hooks: HookConfig,
+}
+
+impl Alias {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            profile: None,
+            schema_url: None,
+            protocol: None,
+            headers: BTreeMap::new(),
+            hooks: HookConfig::default(),
+        }
+    }
+
+    /// [`Alias::headers`] as request headers
+    pub fn header_map(&self) -> Result<reqwest::header::HeaderMap> {
+        crate::http::header_map(&self.headers)
+    }
+
+    /// The alias as a `[aliases.<name>]` table
+    fn to_toml(&self, name: &str) -> String {
+        let quote = |value: &str| toml::Value::String(value.to_string()).to_string();
+        let mut table = format!("[aliases.{}]\nurl = {}\n", name, quote(&self.url));
+        if let Some(profile) = &self.profile {
+            table.push_str(&format!("profile = {}\n", quote(profile)));
+        }
+        if let Some(schema_url) = &self.schema_url {
+            table.push_str(&format!("schema_url = {}\n", quote(schema_url)));
+        }
+        if let Some(protocol) = &self.protocol {
+            table.push_str(&format!("protocol = {}\n", quote(protocol)));
+        }
+        if !self.headers.is_empty() {
+            let headers = self
+                .headers
+                .iter()
+                .map(|(name, value)| format!("{} = {}", quote(name), quote(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            table.push_str(&format!("headers = {{ {} }}\n", headers));
+        }
+        if !self.hooks.is_empty() {
+            let hooks = [
+                ("pre_request", &self.hooks.pre_request),
+                ("post_response", &self.hooks.post_response),
+            ]
+            .into_iter()
+            .filter_map(|(name, path)| {
+                Some(format!(
+                    "{} = {}",
+                    name,
+                    quote(&path.as_ref()?.to_string_lossy())
+                ))
+            })
+            .chain((!self.hooks.env.is_empty()).then(|| {
+                let names = self
+                    .hooks
+                    .env
+                    .iter()
+                    .map(|name| quote(name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("env = [{}]", names)
+            }))
+            .collect::<Vec<_>>()
+            .join(", ");
+            table.push_str(&format!("hooks = {{ {} }}\n", hooks));
+        }
+        table
+    }
+}
+
+/// Aliases of `~/.uxc/config.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AliasStore {
+    pub aliases: BTreeMap<String, Alias>,
+}
+
+impl AliasStore {
+    /// Directory of `~/.uxc/config.toml`, which relative hook paths of its
+    /// aliases start from
+    pub fn config_dir() -> Result<PathBuf> {
+        let home = home_dir().context("Could not determine home directory")?;
+        Ok(home.join(CONFIG_DIR))
+    }
+
+    fn config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join(CONFIG_FILE))
+    }
+
+    /// Load aliases. A missing file yields an empty store.
+    pub fn load() -> Result<Self> {
+        let Some(table) = crate::config::user_table("aliases")? else {
+            return Ok(Self::default());
+        };
+        Self::from_table(table).context("Failed to parse [aliases] in ~/.uxc/config.toml")
+    }
+
+    /// Parse the `[aliases]` table of a config file
+    pub fn from_table(table: toml::Table) -> Result<Self> {
+        Ok(Self {
+            aliases: toml::Value::Table(table).try_into()?,
+        })
+    }
+
+    /// Write the aliases back, keeping every other section of the file
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create config directory: {:?}", parent))?;
+        }
+
+        let contents = if path.exists() {
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file: {:?}", path))?
+        } else {
+            String::new()
+        };
+        fs::write(&path, self.merge_into(&contents))
+            .with_context(|| format!("Failed to write config file: {:?}", path))
+    }
+
+    /// `contents` with its alias tables replaced by this store's
+    fn merge_into(&self, contents: &str) -> String {
+        let mut merged = String::new();
+        let mut in_aliases = false;
+        for line in contents.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_aliases = is_alias_header(trimmed);
+            }
+            if !in_aliases {
+                merged.push_str(line);
+                merged.push('\n');
+            }
+        }
+
+        let mut merged = merged.trim_end().to_string();
+        for (name, alias) in &self.aliases {
+            if !merged.is_empty() {
+                merged.push_str("\n\n");
+            }
+            merged.push_str(alias.to_toml(name).trim_end());
+        }
+        if !merged.is_empty() {
+            merged.push('\n');
+        }
+        merged
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Alias> {
+        self.aliases.get(name)
+    }
+
+    pub fn insert(&mut self, name: String, alias: Alias) -> Result<()> {
+        validate_alias_name(&name)?;
+        self.aliases.insert(name, alias);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Alias> {
+        self.aliases
+            .remove(name)
+            .with_context(|| format!("Alias '{}' not found", name))
+    }
+}
+
+fn is_alias_header(line: &str) -> bool {
+    line == "[aliases]" || line.starts_with("[aliases.")
+}
+
+/// Whether `name` can be an alias: letters, digits, `_` and `-`. Dots,
+/// slashes and colons are left to host names, paths and operation IDs.
+pub fn is_alias_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+fn validate_alias_name(name: &str) -> Result<()> {
+    if !is_alias_name(name) {
+        bail!(
+            "Alias name '{}' is not valid. Allowed characters: letters, digits, '_', '-'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// The alias called `input`, if `input` is an alias name and one is
+/// configured. Aliases of the project's `.uxc.toml` win over
+/// `~/.uxc/config.toml`.
+pub fn resolve(input: &str) -> Result<Option<Alias>> {
+    if !is_alias_name(input) {
+        return Ok(None);
+    }
+    if let Some(alias) =
+        crate::project::ProjectConfig::current()?.and_then(|project| project.aliases.get(input))
+    {
+        return Ok(Some(alias.clone()));
+    }
+    Ok(AliasStore::load()?.aliases.remove(input))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(user_profile));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_replaces_only_alias_tables() {
+        let existing = "[cache]\nttl = 60\n\n[aliases.old]\nurl = \"https://old.example.com\"\n\n[output]\ninclude_headers = [\"etag\"]\n";
+        let mut alias = Alias::new("https://api.github.com");
+        alias.profile = Some("github".to_string());
+        alias.protocol = Some("openapi".to_string());
+        alias
+            .headers
+            .insert("X-GitHub-Api-Version".to_string(), "2022-11-28".to_string());
+        alias.hooks.post_response = Some(PathBuf::from("/hooks/unwrap.rhai"));
+        alias.hooks.env = vec!["GH_SIGNING_KEY".to_string()];
+        let mut store = AliasStore::default();
+        store.insert("gh".to_string(), alias.clone()).unwrap();
+
+        let merged = store.merge_into(existing);
+        assert!(merged.starts_with("[cache]\nttl = 60\n\n[output]\n"));
+        assert!(!merged.contains("old.example.com"));
+        assert!(merged.ends_with(
+            "[aliases.gh]\nurl = \"https://api.github.com\"\nprofile = \"github\"\nprotocol = \"openapi\"\nheaders = { \"X-GitHub-Api-Version\" = \"2022-11-28\" }\nhooks = { post_response = \"/hooks/unwrap.rhai\", env = [\"GH_SIGNING_KEY\"] }\n"
+        ));
+
+        let mut file: toml::Table = toml::from_str(&merged).unwrap();
+        let aliases = file.remove("aliases").unwrap();
+        let reloaded = AliasStore::from_table(aliases.try_into().unwrap()).unwrap();
+        assert_eq!(reloaded.get("gh"), Some(&alias));
+        assert_eq!(reloaded.aliases.len(), 1);
+
+        store.remove("gh").unwrap();
+        assert_eq!(
+            store.merge_into(&merged),
+            "[cache]\nttl = 60\n\n[output]\ninclude_headers = [\"etag\"]\n"
+        );
+    }
+
+    #[test]
+    fn alias_names_exclude_urls_and_operation_ids() {
+        assert!(is_alias_name("gh"));
+        assert!(is_alias_name("pet_store-2"));
+        assert!(!is_alias_name("api.github.com"));
+        assert!(!is_alias_name("get:/pets"));
+        assert!(!is_alias_name("query/viewer"));
+        assert!(!is_alias_name("-v"));
+        assert!(AliasStore::default()
+            .insert("a b".to_string(), Alias::new("https://x"))
+            .is_err());
+    }
+}
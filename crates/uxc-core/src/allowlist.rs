@@ -0,0 +1,217 @@
+//! Host allowlist for sandboxed runs
+//!
+//! `UXC_ALLOWED_HOSTS` (or `--allowed-hosts`) holds comma-separated host
+//! patterns. When set, only matching hosts may be contacted. `*` matches any
+//! run of characters, so `*.example.com` allows every subdomain and
+//! `localhost:*` any local port. A pattern without a port matches all ports.
+//!
+//! [`enforce`] checks a URL against the allowlist in effect. The HTTP layer
+//! ([`crate::http::send`]) calls it for every request and redirect hop, and
+//! gRPC connections and auth flows call it before they connect, so library
+//! callers are held to the allowlist as well as the CLI. Blocked attempts
+//! are recorded in history with a `BLOCKED` status.
+
+use crate::error::UxcError;
+use crate::history::{self, HistoryEntry, HistoryStatus};
+use anyhow::Result;
+use std::sync::RwLock;
+use tracing::warn;
+
+/// Environment variable holding the allowlist
+pub const ALLOWED_HOSTS_ENV: &str = "UXC_ALLOWED_HOSTS";
+
+// Patterns set with [`set_allowed_hosts`], which override the environment
+static ALLOWED_HOSTS: RwLock<Option<String>> = RwLock::new(None);
+
+/// Use `spec` (`--allowed-hosts`) instead of [`ALLOWED_HOSTS_ENV`] for this
+/// process; `None` goes back to the environment
+pub fn set_allowed_hosts(spec: Option<&str>) {
+    *ALLOWED_HOSTS.write().unwrap_or_else(|e| e.into_inner()) = spec.map(ToString::to_string);
+}
+
+/// The allowlist in effect: the one set with [`set_allowed_hosts`],
+/// otherwise [`ALLOWED_HOSTS_ENV`]
+pub fn current() -> Option<HostAllowlist> {
+    let spec = ALLOWED_HOSTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .or_else(|| crate::env_file::var(ALLOWED_HOSTS_ENV))?;
+    HostAllowlist::parse(&spec)
+}
+
+/// Fail unless the allowlist in effect allows `url`. Blocked attempts are
+/// recorded in history, with `operation` when known.
+pub fn enforce(url: &str, operation: Option<&str>) -> Result<()> {
+    let Some(allowlist) = current() else {
+        return Ok(());
+    };
+    let Err(err) = allowlist.check(url) else {
+        return Ok(());
+    };
+    let entry = HistoryEntry::new(HistoryStatus::Blocked, url, operation)
+        .with_error(crate::error::code_of(&err), &err.to_string());
+    if let Err(e) = history::append(&entry) {
+        warn!("Failed to record blocked call in history: {}", e);
+    }
+    Err(err)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostAllowlist {
+    patterns: Vec<String>,
+}
+
+impl HostAllowlist {
+    /// Parse a comma-separated pattern list. Returns `None` when empty.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let patterns = spec
+            .split(',')
+            .map(|pattern| ascii_pattern(pattern.trim()).to_ascii_lowercase())
+            .filter(|pattern| !pattern.is_empty())
+            .collect::<Vec<_>>();
+        if patterns.is_empty() {
+            None
+        } else {
+            Some(Self { patterns })
+        }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether `url` (or a bare `host[:port]`) may be contacted.
+    pub fn allows(&self, url: &str) -> bool {
+        let Some((host, port)) = host_and_port(url) else {
+            return false;
+        };
+        let with_port = port.map(|port| format!("{}:{}", host, port));
+
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains(':') {
+                with_port
+                    .as_deref()
+                    .is_some_and(|candidate| glob_match(pattern, candidate))
+            } else {
+                glob_match(pattern, &host)
+            }
+        })
+    }
+
+    /// Fail with [`UxcError::HostNotAllowed`] unless `url` is allowed.
+    pub fn check(&self, url: &str) -> Result<()> {
+        if self.allows(url) {
+            return Ok(());
+        }
+        let host = host_and_port(url)
+            .map(|(host, port)| match port {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            })
+            .unwrap_or_else(|| url.to_string());
+        Err(UxcError::HostNotAllowed(format!(
+            "{} is not in the allowed hosts ({})",
+            host,
+            self.patterns.join(", ")
+        ))
+        .into())
+    }
+}
+
+/// Pattern with Unicode host labels converted to punycode, as URL hosts are
+fn ascii_pattern(pattern: &str) -> String {
+    match pattern.split_once(':') {
+        Some((host, port)) => format!("{}:{}", crate::idn::host_to_ascii(host), port),
+        None => crate::idn::host_to_ascii(pattern),
+    }
+}
+
+/// Host and effective port of a URL or `host[:port]` string.
+fn host_and_port(url: &str) -> Option<(String, Option<u16>)> {
+    let candidate = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{}", url)
+    };
+    let parsed = url::Url::parse(&candidate).ok()?;
+    let host = parsed
+        .host_str()?
+        .trim_matches(['[', ']'])
+        .to_ascii_lowercase();
+    Some((host, parsed.port_or_known_default()))
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let text = text.as_bytes();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcards_and_ports() {
+        let allowlist =
+            HostAllowlist::parse("*.example.com, localhost:*, 127.0.0.1:8080,api-*.internal")
+                .unwrap();
+
+        assert!(allowlist.allows("https://api.example.com/v1"));
+        assert!(!allowlist.allows("https://example.com.evil.io"));
+        assert!(allowlist.allows("http://localhost:3000"));
+        assert!(allowlist.allows("http://127.0.0.1:8080/rpc"));
+        assert!(!allowlist.allows("http://127.0.0.1:9090"));
+        assert!(allowlist.allows("api-eu.internal:50051"));
+        assert!(!allowlist.allows("grpc.internal"));
+    }
+
+    #[test]
+    fn empty_spec_disables_allowlist() {
+        assert!(HostAllowlist::parse(" , ").is_none());
+        assert!(HostAllowlist::parse("*")
+            .unwrap()
+            .allows("https://anything.io"));
+    }
+
+    #[test]
+    fn unicode_patterns_match_punycode_hosts() {
+        let allowlist = HostAllowlist::parse("*.例え.テスト, bücher.example:*").unwrap();
+        assert!(allowlist.allows("https://api.xn--r8jz45g.xn--zckzah/v1"));
+        assert!(allowlist.allows("https://api.例え.テスト/v1"));
+        assert!(allowlist.allows("http://xn--bcher-kva.example:8080"));
+        assert!(!allowlist.allows("https://例え.com"));
+    }
+
+    #[test]
+    fn check_reports_host() {
+        let err = HostAllowlist::parse("example.com")
+            .unwrap()
+            .check("https://evil.io:8443/x")
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("evil.io:8443 is not in the allowed hosts"));
+    }
+}
@@ -0,0 +1,236 @@
+//! `k=v` arguments as the command line takes them
+//!
+//! - `name=value` sets `name` to the string `value`; [`crate::coerce`] later
+//!   converts it to the type the operation declares.
+//! - `name:=json` sets `name` to a JSON value as written: `count:=3`,
+//!   `flag:=true`, `tags:='["a","b"]'`, `filter:='{"tag":"a"}'`.
+//! - Dots in a name nest objects: `owner.name=Ann owner.age:=30` sets
+//!   `owner` to `{"name":"Ann","age":30}`. A dot that is part of a name is
+//!   written `\.`.
+//! - `name=@path` reads the value from a file: text as a string, other
+//!   content base64-encoded, and `name=@photo.png;type=image/png` as a
+//!   `data:image/png;base64,...` URL. `name:=@path` reads a JSON file. A
+//!   value that starts with a literal `@` is written `\@`.
+//!
+//! A later argument for the same name replaces an earlier one.
+
+use crate::error::UxcError;
+use base64::Engine;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// The `--json` payload `spec` names: `-` reads stdin, `@path` a file, and
+/// anything else is the payload itself
+pub fn read_payload(spec: &str) -> Result<String, UxcError> {
+    let unreadable = |from: &str, err: std::io::Error| {
+        UxcError::InvalidArguments(format!("Cannot read --json payload from {}: {}", from, err))
+    };
+    if spec == "-" {
+        let mut payload = String::new();
+        std::io::stdin()
+            .read_to_string(&mut payload)
+            .map_err(|err| unreadable("stdin", err))?;
+        return Ok(payload);
+    }
+    match spec.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).map_err(|err| unreadable(path, err)),
+        None => Ok(spec.to_string()),
+    }
+}
+
+/// Parse `k=v` / `k:=json` arguments into named values
+pub fn parse(args: &[String]) -> Result<HashMap<String, Value>, UxcError> {
+    let mut values = Map::new();
+    for arg in args {
+        let (path, value) = parse_one(arg)?;
+        insert(&mut values, &path, value).map_err(|conflict| {
+            UxcError::InvalidArguments(format!(
+                "Argument '{}' conflicts with '{}', which is not an object",
+                arg, conflict
+            ))
+        })?;
+    }
+    Ok(values.into_iter().collect())
+}
+
+/// The name path and value of one argument
+fn parse_one(arg: &str) -> Result<(Vec<String>, Value), UxcError> {
+    let invalid =
+        |why: String| UxcError::InvalidArguments(format!("Invalid argument '{}': {}", arg, why));
+    let Some(split) = arg.find('=') else {
+        return Err(invalid(
+            "expected name=value, or name:=json for typed values".to_string(),
+        ));
+    };
+    let (name, typed) = match arg[..split].strip_suffix(':') {
+        Some(name) => (name, true),
+        None => (&arg[..split], false),
+    };
+    let text = &arg[split + 1..];
+
+    let path = split_name(name);
+    if path.iter().any(String::is_empty) {
+        return Err(invalid("the name is empty".to_string()));
+    }
+    let value = match (text.strip_prefix('@'), typed) {
+        (Some(file), typed) => read_file_value(file, typed).map_err(invalid)?,
+        (None, true) => {
+            serde_json::from_str(text).map_err(|e| invalid(format!("value is not JSON ({})", e)))?
+        }
+        (None, false) => match text.strip_prefix("\\@") {
+            Some(rest) => Value::String(format!("@{}", rest)),
+            None => Value::String(text.to_string()),
+        },
+    };
+    Ok((path, value))
+}
+
+/// The value of `@file`: JSON when `typed`, otherwise text, or base64 for
+/// binary content and for `;type=` media types
+fn read_file_value(file: &str, typed: bool) -> Result<Value, String> {
+    let (path, media_type) = match file.rsplit_once(";type=") {
+        Some((path, media_type)) if !typed => (path, Some(media_type)),
+        _ => (file, None),
+    };
+    let bytes = std::fs::read(path).map_err(|e| format!("cannot read {} ({})", path, e))?;
+    if typed {
+        return serde_json::from_slice(&bytes).map_err(|e| format!("{} is not JSON ({})", path, e));
+    }
+    let encoded = || base64::engine::general_purpose::STANDARD.encode(&bytes);
+    Ok(Value::String(match media_type {
+        Some(media_type) => format!("data:{};base64,{}", media_type, encoded()),
+        None => match std::str::from_utf8(&bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => encoded(),
+        },
+    }))
+}
+
+/// `name` split at dots that are not escaped as `\.`
+fn split_name(name: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'.') => {
+                chars.next();
+                part.push('.');
+            }
+            '.' => parts.push(std::mem::take(&mut part)),
+            c => part.push(c),
+        }
+    }
+    parts.push(part);
+    parts
+}
+
+/// Set `path` in `target`, creating objects on the way; on conflict, the
+/// dotted name that already holds a value other than an object
+fn insert(target: &mut Map<String, Value>, path: &[String], value: Value) -> Result<(), String> {
+    let (name, rest) = path.split_first().expect("paths are never empty");
+    if rest.is_empty() {
+        target.insert(name.clone(), value);
+        return Ok(());
+    }
+    let entry = target
+        .entry(name.clone())
+        .or_insert_with(|| Value::Object(Map::new()));
+    match entry {
+        Value::Object(inner) => {
+            insert(inner, rest, value).map_err(|conflict| format!("{}.{}", name, conflict))
+        }
+        _ => Err(name.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parsed(args: &[&str]) -> Result<Value, UxcError> {
+        let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        Ok(Value::Object(parse(&args)?.into_iter().collect()))
+    }
+
+    #[test]
+    fn typed_and_nested_arguments() {
+        assert_eq!(
+            parsed(&[
+                "name=Rex",
+                "count:=3",
+                "flag:=true",
+                r#"tags:=["a"]"#,
+                "owner.name=Ann",
+                "owner.age:=30",
+                r"filter\.tag=a=b",
+                "empty=",
+            ])
+            .unwrap(),
+            json!({
+                "name": "Rex",
+                "count": 3,
+                "flag": true,
+                "tags": ["a"],
+                "owner": { "name": "Ann", "age": 30 },
+                "filter.tag": "a=b",
+                "empty": ""
+            })
+        );
+    }
+
+    #[test]
+    fn values_are_read_from_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name).display().to_string();
+        std::fs::write(path("note.txt"), "hello\n").unwrap();
+        std::fs::write(path("body.json"), r#"{"a":[1]}"#).unwrap();
+        std::fs::write(path("pixel.png"), [0x89, 0x50, 0xff]).unwrap();
+
+        assert_eq!(
+            parsed(&[
+                &format!("note=@{}", path("note.txt")),
+                &format!("body:=@{}", path("body.json")),
+                &format!("raw=@{}", path("pixel.png")),
+                &format!("avatar=@{};type=image/png", path("pixel.png")),
+                r"handle=\@rex",
+            ])
+            .unwrap(),
+            json!({
+                "note": "hello\n",
+                "body": { "a": [1] },
+                "raw": "iVD/",
+                "avatar": "data:image/png;base64,iVD/",
+                "handle": "@rex"
+            })
+        );
+
+        let err = parsed(&[&format!("body:=@{}", path("note.txt"))])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is not JSON"), "{}", err);
+        let err = parsed(&[&format!("x=@{}", path("missing"))])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("cannot read"), "{}", err);
+    }
+
+    #[test]
+    fn malformed_arguments_are_rejected() {
+        for (args, expected) in [
+            (vec!["count"], "expected name=value"),
+            (vec!["count:=three"], "value is not JSON"),
+            (vec!["=1"], "the name is empty"),
+            (vec!["owner..name=x"], "the name is empty"),
+            (
+                vec!["owner=Ann", "owner.name=Ann"],
+                "conflicts with 'owner'",
+            ),
+        ] {
+            let err = parsed(&args).unwrap_err().to_string();
+            assert!(err.contains(expected), "{:?}: {}", args, err);
+        }
+    }
+}
@@ -0,0 +1,741 @@
+//! Response assertions
+//!
+//! Assertions address values inside a call result with a small path syntax
+//! (`items[0].name`, `items.0.name`, `$` for the root) and check them with
+//! simple predicates. Every predicate that is set on an assertion is checked.
+//!
+//! [`schema_violations`] checks a whole result against a JSON Schema, and
+//! [`response_violations`] checks a response against the schema documented
+//! for its status (`--validate-response`).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One check against a value addressed by `path`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Assertion {
+    /// Path to the value under test (`$` or empty for the whole result)
+    #[serde(default)]
+    pub path: String,
+
+    /// Value must equal this JSON value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<Value>,
+
+    /// Value must not equal this JSON value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_equals: Option<Value>,
+
+    /// Whether the path must (true) or must not (false) resolve
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exists: Option<bool>,
+
+    /// Strings: substring; arrays: element; objects: key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<Value>,
+
+    /// JSON type name (null, boolean, number, integer, string, array, object)
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+
+    /// Length of a string, array or object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+
+    /// Regular expression the value, as text, must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matches: Option<String>,
+}
+
+impl Assertion {
+    /// Evaluate the assertion and return one message per failed predicate.
+    pub fn check(&self, data: &Value) -> Vec<String> {
+        let path = display_path(&self.path);
+        let actual = lookup_path(data, &self.path);
+        let mut failures = Vec::new();
+
+        if let Some(expected) = self.exists {
+            if actual.is_some() != expected {
+                failures.push(if expected {
+                    format!("{}: expected path to exist", path)
+                } else {
+                    format!("{}: expected path to be absent", path)
+                });
+            }
+        }
+
+        let has_value_checks = self.equals.is_some()
+            || self.not_equals.is_some()
+            || self.contains.is_some()
+            || self.value_type.is_some()
+            || self.length.is_some()
+            || self.matches.is_some();
+        if !has_value_checks {
+            return failures;
+        }
+
+        let Some(actual) = actual else {
+            failures.push(format!("{}: path not found", path));
+            return failures;
+        };
+
+        if let Some(expected) = &self.equals {
+            if actual != expected {
+                failures.push(format!(
+                    "{}: expected {} but got {}",
+                    path, expected, actual
+                ));
+            }
+        }
+
+        if let Some(unexpected) = &self.not_equals {
+            if actual == unexpected {
+                failures.push(format!(
+                    "{}: expected value other than {}",
+                    path, unexpected
+                ));
+            }
+        }
+
+        if let Some(needle) = &self.contains {
+            if !value_contains(actual, needle) {
+                failures.push(format!(
+                    "{}: expected {} to contain {}",
+                    path, actual, needle
+                ));
+            }
+        }
+
+        if let Some(expected_type) = &self.value_type {
+            if !type_matches(actual, expected_type) {
+                failures.push(format!(
+                    "{}: expected type {} but got {}",
+                    path,
+                    expected_type,
+                    type_name(actual)
+                ));
+            }
+        }
+
+        if let Some(expected_len) = self.length {
+            match value_length(actual) {
+                Some(len) if len == expected_len => {}
+                Some(len) => failures.push(format!(
+                    "{}: expected length {} but got {}",
+                    path, expected_len, len
+                )),
+                None => failures.push(format!(
+                    "{}: length is undefined for {}",
+                    path,
+                    type_name(actual)
+                )),
+            }
+        }
+
+        if let Some(pattern) = &self.matches {
+            match regex::Regex::new(pattern) {
+                Ok(regex) if regex.is_match(&value_text(actual)) => {}
+                Ok(_) => failures.push(format!(
+                    "{}: expected {} to match /{}/",
+                    path, actual, pattern
+                )),
+                Err(err) => failures.push(format!("{}: invalid pattern: {}", path, err)),
+            }
+        }
+
+        failures
+    }
+}
+
+/// Where `value` does not conform to a JSON Schema, one message per problem.
+///
+/// Supports the keywords responses are usually described with: `type`,
+/// `enum`, `const`, `properties`, `required`, `additionalProperties`,
+/// `items`, `minItems`/`maxItems`, `minLength`/`maxLength`, `pattern`,
+/// `minimum`/`maximum`, `allOf`, `anyOf`, `oneOf` and OpenAPI's `nullable`.
+/// Others are ignored.
+pub fn schema_violations(schema: &Value, value: &Value) -> Vec<String> {
+    let mut violations = Vec::new();
+    check_schema(schema, value, "$", &mut violations);
+    violations
+}
+
+fn check_schema(schema: &Value, value: &Value, path: &str, violations: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        if schema == &Value::Bool(false) {
+            violations.push(format!("{}: no value is allowed", path));
+        }
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let nullable = value.is_null() && schema.get("nullable") == Some(&Value::Bool(true));
+        if !types.is_empty() && !nullable && !types.iter().any(|name| type_matches(value, name)) {
+            violations.push(format!(
+                "{}: expected type {} but got {}",
+                path,
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            violations.push(format!(
+                "{}: {} is not one of {}",
+                path,
+                value,
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            violations.push(format!("{}: expected {} but got {}", path, expected, value));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for name in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !map.contains_key(name) {
+                    violations.push(format!("{}: missing required property '{}'", path, name));
+                }
+            }
+            for (name, item) in map {
+                let child = format!("{}.{}", path, name);
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(property) => check_schema(property, item, &child, violations),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            violations.push(format!("{}: property '{}' is not allowed", path, name))
+                        }
+                        Some(additional @ Value::Object(_)) => {
+                            check_schema(additional, item, &child, violations)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check_schema(
+                        item_schema,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        violations,
+                    );
+                }
+            }
+            check_bounds(
+                schema,
+                "minItems",
+                "maxItems",
+                items.len(),
+                "items",
+                path,
+                violations,
+            );
+        }
+        Value::String(text) => {
+            let length = text.chars().count();
+            check_bounds(
+                schema,
+                "minLength",
+                "maxLength",
+                length,
+                "characters",
+                path,
+                violations,
+            );
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                if regex::Regex::new(pattern).is_ok_and(|regex| !regex.is_match(text)) {
+                    violations.push(format!("{}: {} does not match /{}/", path, value, pattern));
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < minimum {
+                    violations.push(format!("{}: {} is less than {}", path, value, minimum));
+                }
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > maximum {
+                    violations.push(format!("{}: {} is more than {}", path, value, maximum));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(Value::Array(all)) = schema.get("allOf") {
+        for sub in all {
+            check_schema(sub, value, path, violations);
+        }
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        if let Some(Value::Array(options)) = schema.get(keyword) {
+            let matching = options
+                .iter()
+                .filter(|sub| schema_violations(sub, value).is_empty())
+                .count();
+            if matching == 0 || (exactly_one && matching > 1) {
+                violations.push(format!(
+                    "{}: expected to match {} of the {} schemas, matched {}",
+                    path,
+                    if exactly_one {
+                        "exactly one"
+                    } else {
+                        "at least one"
+                    },
+                    keyword,
+                    matching
+                ));
+            }
+        }
+    }
+}
+
+/// Where a response with `status` and `body` departs from `responses`, the
+/// documented responses of its operation (see
+/// [`crate::adapters::OperationDetail::responses`]): a status that is not
+/// documented, [`schema_violations`] of the body, and object properties the
+/// schema does not list.
+///
+/// The response documented for the exact status is used, else its `2XX`
+/// range, else `default`.
+pub fn response_violations(responses: &Value, status: u16, body: &Value) -> Vec<String> {
+    let code = status.to_string();
+    let range = format!("{}XX", status / 100);
+    let documented = responses.as_object().and_then(|responses| {
+        responses.get(&code).or_else(|| {
+            responses
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(&range))
+                .map(|(_, response)| response)
+                .or_else(|| responses.get("default"))
+        })
+    });
+    let Some(response) = documented else {
+        return vec![format!("HTTP {} is not a documented response", status)];
+    };
+    let Some(schema) = response.get("schema") else {
+        return Vec::new();
+    };
+    let mut violations = schema_violations(schema, body);
+    undocumented_properties(schema, body, "$", &mut violations);
+    violations
+}
+
+/// Report properties of `value` that `schema` does not list, unless it
+/// allows additional properties explicitly
+fn undocumented_properties(
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    match value {
+        Value::Object(map) => {
+            let mut properties = serde_json::Map::new();
+            let mut open = false;
+            for part in std::iter::once(schema).chain(
+                schema
+                    .get("allOf")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten(),
+            ) {
+                if let Some(listed) = part.get("properties").and_then(Value::as_object) {
+                    properties.extend(listed.clone());
+                }
+                open |= part.get("additionalProperties").is_some()
+                    || part.get("anyOf").is_some()
+                    || part.get("oneOf").is_some();
+            }
+            if properties.is_empty() && !open {
+                // A bare `type: object` documents no fields either way
+                return;
+            }
+            for (name, item) in map {
+                match properties.get(name) {
+                    Some(property) => undocumented_properties(
+                        property,
+                        item,
+                        &format!("{}.{}", path, name),
+                        violations,
+                    ),
+                    None if !open => {
+                        violations.push(format!("{}: property '{}' is not documented", path, name))
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    undocumented_properties(
+                        item_schema,
+                        item,
+                        &format!("{}[{}]", path, index),
+                        violations,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_bounds(
+    schema: &serde_json::Map<String, Value>,
+    min_keyword: &str,
+    max_keyword: &str,
+    count: usize,
+    unit: &str,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let bound = |keyword| schema.get(keyword).and_then(Value::as_u64);
+    if let Some(min) = bound(min_keyword) {
+        if (count as u64) < min {
+            violations.push(format!(
+                "{}: expected at least {} {} but got {}",
+                path, min, unit, count
+            ));
+        }
+    }
+    if let Some(max) = bound(max_keyword) {
+        if count as u64 > max {
+            violations.push(format!(
+                "{}: expected at most {} {} but got {}",
+                path, max, unit, count
+            ));
+        }
+    }
+}
+
+/// Resolve a path such as `items[0].name` or `items.0.name` against `value`.
+pub fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path_segments(path) {
+        current = match current {
+            Value::Object(map) => map.get(&segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+fn path_segments(path: &str) -> Vec<String> {
+    let trimmed = path.trim();
+    let trimmed = trimmed.strip_prefix('$').unwrap_or(trimmed);
+    trimmed
+        .replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn display_path(path: &str) -> &str {
+    if path.trim().is_empty() {
+        "$"
+    } else {
+        path
+    }
+}
+
+fn value_contains(actual: &Value, needle: &Value) -> bool {
+    match actual {
+        Value::String(text) => match needle {
+            Value::String(sub) => text.contains(sub.as_str()),
+            other => text.contains(&other.to_string()),
+        },
+        Value::Array(items) => items.contains(needle),
+        Value::Object(map) => needle.as_str().is_some_and(|key| map.contains_key(key)),
+        _ => false,
+    }
+}
+
+/// Strings as they are, other values as JSON
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn value_length(value: &Value) -> Option<usize> {
+    match value {
+        Value::String(text) => Some(text.chars().count()),
+        Value::Array(items) => Some(items.len()),
+        Value::Object(map) => Some(map.len()),
+        _ => None,
+    }
+}
+
+fn type_matches(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.as_i64().is_some() || value.as_u64().is_some(),
+        other => type_name(value) == other,
+    }
+}
+
+/// JSON type name of a value.
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// `--expect-header NAME[=VALUE]`: the response must carry header `NAME`,
+/// with exactly `VALUE` when given. Names are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderExpectation {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl HeaderExpectation {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, value) = match spec.split_once('=') {
+            Some((name, value)) => (name, Some(value.trim().to_string())),
+            None => (spec, None),
+        };
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty() {
+            return Err(format!("expected NAME or NAME=VALUE, got '{}'", spec));
+        }
+        Ok(Self { name, value })
+    }
+
+    /// Failure message, or `None` when `headers` satisfy the expectation
+    pub fn check(&self, headers: &[(String, String)]) -> Option<String> {
+        let values: Vec<&str> = headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(&self.name))
+            .map(|(_, value)| value.as_str())
+            .collect();
+        match (&self.value, values.as_slice()) {
+            (_, []) => Some(format!("header {}: expected to be present", self.name)),
+            (Some(expected), actual) if !actual.contains(&expected.as_str()) => Some(format!(
+                "header {}: expected {:?} but got {:?}",
+                self.name,
+                expected,
+                actual.join(", ")
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "items": [{"id": 1, "name": "cat"}, {"id": 2, "name": "dog"}],
+            "total": 2,
+            "title": "pets"
+        })
+    }
+
+    #[test]
+    fn lookup_path_supports_brackets_and_dots() {
+        let data = sample();
+        assert_eq!(lookup_path(&data, "items[1].name"), Some(&json!("dog")));
+        assert_eq!(lookup_path(&data, "items.0.id"), Some(&json!(1)));
+        assert_eq!(lookup_path(&data, "$"), Some(&data));
+        assert_eq!(lookup_path(&data, "items[5]"), None);
+    }
+
+    #[test]
+    fn passing_assertion_reports_no_failures() {
+        let assertion = Assertion {
+            path: "items".to_string(),
+            value_type: Some("array".to_string()),
+            length: Some(2),
+            contains: Some(json!({"id": 1, "name": "cat"})),
+            ..Default::default()
+        };
+        assert!(assertion.check(&sample()).is_empty());
+    }
+
+    #[test]
+    fn failing_predicates_are_reported_individually() {
+        let assertion = Assertion {
+            path: "total".to_string(),
+            equals: Some(json!(3)),
+            value_type: Some("string".to_string()),
+            ..Default::default()
+        };
+        let failures = assertion.check(&sample());
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].contains("expected 3 but got 2"));
+    }
+
+    #[test]
+    fn exists_false_passes_for_missing_path() {
+        let assertion = Assertion {
+            path: "missing".to_string(),
+            exists: Some(false),
+            ..Default::default()
+        };
+        assert!(assertion.check(&sample()).is_empty());
+
+        let assertion = Assertion {
+            path: "missing".to_string(),
+            equals: Some(json!(1)),
+            ..Default::default()
+        };
+        assert_eq!(assertion.check(&sample()), vec!["missing: path not found"]);
+    }
+
+    #[test]
+    fn matches_checks_values_as_text() {
+        let assertion = |path: &str, pattern: &str| Assertion {
+            path: path.to_string(),
+            matches: Some(pattern.to_string()),
+            ..Default::default()
+        };
+        assert!(assertion("items[0].name", "^c.t$")
+            .check(&sample())
+            .is_empty());
+        assert!(assertion("total", r"^\d+$").check(&sample()).is_empty());
+        assert_eq!(
+            assertion("title", "^dogs").check(&sample()),
+            vec![r#"title: expected "pets" to match /^dogs/"#]
+        );
+        assert!(assertion("title", "(").check(&sample())[0].starts_with("title: invalid pattern"));
+    }
+
+    #[test]
+    fn schema_violations_are_reported_by_path() {
+        let schema = json!({
+            "type": "object",
+            "required": ["items", "total", "next"],
+            "properties": {
+                "items": {
+                    "type": "array",
+                    "maxItems": 1,
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer", "minimum": 2 },
+                            "name": { "enum": ["cat", "dog"] }
+                        },
+                        "additionalProperties": false
+                    }
+                },
+                "total": { "type": "integer" },
+                "title": { "type": "string", "pattern": "^p" }
+            }
+        });
+        assert_eq!(
+            schema_violations(&schema, &sample()),
+            vec![
+                "$: missing required property 'next'",
+                "$.items[0].id: 1 is less than 2",
+                "$.items: expected at most 1 items but got 2",
+            ]
+        );
+        assert!(schema_violations(&json!({ "type": ["object", "null"] }), &Value::Null).is_empty());
+        assert_eq!(
+            schema_violations(
+                &json!({ "oneOf": [{ "type": "number" }, { "type": "integer" }] }),
+                &json!(1)
+            ),
+            vec!["$: expected to match exactly one of the oneOf schemas, matched 2"]
+        );
+    }
+
+    #[test]
+    fn responses_are_checked_against_the_schema_of_their_status() {
+        let responses = json!({
+            "200": {
+                "description": "ok",
+                "schema": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["id"],
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "tag": { "type": "string", "nullable": true }
+                        }
+                    }
+                }
+            },
+            "2XX": { "description": "accepted" },
+            "default": { "schema": { "type": "object", "additionalProperties": true } }
+        });
+        let body = json!([{ "id": 1, "tag": null }, { "id": "2", "color": "red" }]);
+        assert_eq!(
+            response_violations(&responses, 200, &body),
+            vec![
+                "$[1].id: expected type integer but got string",
+                "$[1]: property 'color' is not documented",
+            ]
+        );
+        assert!(response_violations(&responses, 202, &json!("anything")).is_empty());
+        assert!(response_violations(&responses, 500, &json!({ "error": "x" })).is_empty());
+        assert_eq!(
+            response_violations(&json!({ "200": {} }), 201, &json!({})),
+            vec!["HTTP 201 is not a documented response"]
+        );
+    }
+
+    #[test]
+    fn header_expectations_check_presence_and_value() {
+        let headers = vec![("x-request-id".to_string(), "r-2".to_string())];
+
+        assert!(HeaderExpectation::parse("X-Request-Id")
+            .unwrap()
+            .check(&headers)
+            .is_none());
+        assert!(HeaderExpectation::parse("x-request-id=r-2")
+            .unwrap()
+            .check(&headers)
+            .is_none());
+        assert_eq!(
+            HeaderExpectation::parse("x-request-id=r-1")
+                .unwrap()
+                .check(&headers)
+                .unwrap(),
+            "header x-request-id: expected \"r-1\" but got \"r-2\""
+        );
+        assert!(HeaderExpectation::parse("location")
+            .unwrap()
+            .check(&headers)
+            .is_some());
+        assert!(HeaderExpectation::parse("=x").is_err());
+    }
+}
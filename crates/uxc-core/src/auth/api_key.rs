@@ -0,0 +1,219 @@
+//! API key placement
+//!
+//! Profiles with `auth_type = "api_key"` send the key in an `X-API-Key` header
+//! by default. A profile can name another header or a query parameter and wrap
+//! the key in a template, where `{key}` stands for the resolved key:
+//!
+//! ```toml
+//! [tracker]
+//! auth_type = "api_key"
+//! api_key = "env:TRACKER_TOKEN"
+//!
+//! [tracker.api_key_placement]
+//! header = "Authorization"
+//! template = "Token {key}"
+//! ```
+//!
+//! Placements can also be set per host in `~/.uxc/config.toml`, keyed by host
+//! patterns as used by the allowlist (`*.example.com`, `localhost:*`):
+//!
+//! ```toml
+//! [api_keys."maps.example.com"]
+//! query = "key"
+//! ```
+//!
+//! A placement on the profile wins over a host entry.
+
+use crate::allowlist::HostAllowlist;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Header used when nothing else is configured
+pub const DEFAULT_API_KEY_HEADER: &str = "X-API-Key";
+
+/// Where and how an API key is sent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyPlacement {
+    /// Header carrying the key (default: `X-API-Key`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+
+    /// Query parameter carrying the key, instead of a header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Value template; `{key}` is replaced by the key (default: `{key}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl ApiKeyPlacement {
+    /// Check that the placement names at most one of header and query
+    pub fn validate(&self) -> Result<()> {
+        if self.header.is_some() && self.query.is_some() {
+            bail!("API key placement sets both a header and a query parameter; choose one");
+        }
+        Ok(())
+    }
+
+    /// Header or query value for `key`
+    pub fn value(&self, key: &str) -> String {
+        match &self.template {
+            Some(template) => template.replace("{key}", key),
+            None => key.to_string(),
+        }
+    }
+
+    /// Short description such as `header Authorization (Token {key})`
+    pub fn describe(&self) -> String {
+        let target = match &self.query {
+            Some(name) => format!("query parameter {}", name),
+            None => format!(
+                "header {}",
+                self.header.as_deref().unwrap_or(DEFAULT_API_KEY_HEADER)
+            ),
+        };
+        match &self.template {
+            Some(template) => format!("{} ({})", target, template),
+            None => target,
+        }
+    }
+
+    /// Add `key` to `request` as configured
+    pub fn apply(&self, request: &mut reqwest::Request, key: &str) -> Result<()> {
+        self.validate()?;
+        let value = self.value(key);
+        if let Some(name) = &self.query {
+            request
+                .url_mut()
+                .query_pairs_mut()
+                .append_pair(name, &value);
+            return Ok(());
+        }
+        let name = self.header.as_deref().unwrap_or(DEFAULT_API_KEY_HEADER);
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid API key header name '{}'", name))?;
+        // A cookie joins the ones the request already sends
+        let value = match request.headers().get(&name).map(|v| v.to_str()) {
+            Some(Ok(cookies)) if name == reqwest::header::COOKIE => {
+                format!("{}; {}", cookies, value)
+            }
+            _ => value,
+        };
+        let mut value = reqwest::header::HeaderValue::from_str(&value)
+            .context("API key contains characters not allowed in a header")?;
+        value.set_sensitive(true);
+        request.headers_mut().insert(name, value);
+        Ok(())
+    }
+
+    /// Placement configured for the host of `url` in `~/.uxc/config.toml`
+    pub fn for_host(url: &str) -> Result<Option<Self>> {
+        let Some(table) = crate::config::user_table("api_keys")? else {
+            return Ok(None);
+        };
+        let hosts = Self::hosts_from_table(table)
+            .context("Failed to parse [api_keys] in ~/.uxc/config.toml")?;
+        Ok(Self::match_host(&hosts, url))
+    }
+
+    /// Parse the `[api_keys]` table of a config file
+    pub fn hosts_from_table(table: toml::Table) -> Result<BTreeMap<String, Self>> {
+        Ok(toml::Value::Table(table).try_into()?)
+    }
+
+    /// First entry whose host pattern matches `url`, preferring exact patterns
+    pub fn match_host(hosts: &BTreeMap<String, Self>, url: &str) -> Option<Self> {
+        let matches = |pattern: &String| {
+            HostAllowlist::parse(pattern).is_some_and(|allowlist| allowlist.allows(url))
+        };
+        let (exact, globs): (Vec<_>, Vec<_>) = hosts
+            .iter()
+            .filter(|(pattern, _)| matches(pattern))
+            .partition(|(pattern, _)| !pattern.contains('*'));
+        exact
+            .into_iter()
+            .chain(globs)
+            .next()
+            .map(|(_, placement)| placement.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> reqwest::Request {
+        reqwest::Client::new().get(url).build().unwrap()
+    }
+
+    #[test]
+    fn header_query_and_template_placement() {
+        let mut req = request("https://api.example.com/items");
+        ApiKeyPlacement::default().apply(&mut req, "k1").unwrap();
+        assert_eq!(req.headers()["x-api-key"], "k1");
+
+        let mut req = request("https://api.example.com/items");
+        let placement = ApiKeyPlacement {
+            header: Some("Authorization".to_string()),
+            template: Some("Token {key}".to_string()),
+            ..Default::default()
+        };
+        placement.apply(&mut req, "k2").unwrap();
+        assert_eq!(req.headers()["authorization"], "Token k2");
+
+        let mut req = request("https://api.example.com/items?page=2");
+        let placement = ApiKeyPlacement {
+            query: Some("api_key".to_string()),
+            ..Default::default()
+        };
+        placement.apply(&mut req, "k 3").unwrap();
+        assert_eq!(req.url().query(), Some("page=2&api_key=k+3"));
+
+        let mut req = reqwest::Client::new()
+            .get("https://api.example.com/items")
+            .header("cookie", "theme=dark")
+            .build()
+            .unwrap();
+        let placement = ApiKeyPlacement {
+            header: Some("Cookie".to_string()),
+            template: Some("SID={key}".to_string()),
+            ..Default::default()
+        };
+        placement.apply(&mut req, "k4").unwrap();
+        assert_eq!(req.headers()["cookie"], "theme=dark; SID=k4");
+
+        let both = ApiKeyPlacement {
+            header: Some("Api-Key".to_string()),
+            query: Some("key".to_string()),
+            template: None,
+        };
+        assert!(both
+            .apply(&mut request("https://a.example.com"), "k")
+            .is_err());
+    }
+
+    #[test]
+    fn host_entries_prefer_exact_patterns() {
+        let hosts = ApiKeyPlacement::hosts_from_table(
+            toml::from_str(
+                r#"
+["*.example.com"]
+header = "Api-Key"
+
+["maps.example.com"]
+query = "key"
+"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let maps = ApiKeyPlacement::match_host(&hosts, "https://maps.example.com/v1").unwrap();
+        assert_eq!(maps.query.as_deref(), Some("key"));
+        let other = ApiKeyPlacement::match_host(&hosts, "https://api.example.com").unwrap();
+        assert_eq!(other.header.as_deref(), Some("Api-Key"));
+        assert!(ApiKeyPlacement::match_host(&hosts, "https://example.org").is_none());
+    }
+}
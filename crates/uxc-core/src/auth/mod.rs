@@ -13,7 +13,19 @@
 //! [production]
 //! api_key = "sk-prod-..."
 //! auth_type = "bearer"
+//!
+//! [github]
+//! api_key = "env:GITHUB_TOKEN"
+//!
+//! [vault]
+//! api_key = "cmd:op read op://vault/item/credential"
 //! ```
+//!
+//! `env:` and `cmd:` values are resolved at request time (see [`secret`]).
+//! API key profiles can choose the header or query parameter the key is sent
+//! in (see [`api_key`]).
+//! Session profiles log in and reuse cookies (see [`session`]); OAuth profiles
+//! log in through the browser and refresh their tokens (see [`oauth`]).
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -21,12 +33,22 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+pub mod api_key;
+pub mod oauth;
+pub mod scheme;
+pub mod secret;
+pub mod session;
+pub mod transfer;
+
 /// Default profiles directory relative to home directory
 pub const DEFAULT_PROFILES_DIR: &str = ".uxc";
 
 /// Default profiles file name
 pub const PROFILES_FILE: &str = "profiles.toml";
 
+/// Placeholder written in place of a literal API key by `uxc auth export`
+pub const REDACTED_API_KEY: &str = "<redacted>";
+
 /// Authentication type
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthType {
@@ -36,6 +58,10 @@ pub enum AuthType {
     ApiKey,
     /// Basic authentication
     Basic,
+    /// Cookie session established by a login request
+    Session,
+    /// Bearer token obtained through an OAuth browser login
+    OAuth,
 }
 
 impl serde::Serialize for AuthType {
@@ -47,6 +73,8 @@ impl serde::Serialize for AuthType {
             AuthType::Bearer => "bearer",
             AuthType::ApiKey => "api_key",
             AuthType::Basic => "basic",
+            AuthType::Session => "session",
+            AuthType::OAuth => "oauth",
         };
         serializer.serialize_str(s)
     }
@@ -62,8 +90,10 @@ impl<'de> serde::Deserialize<'de> for AuthType {
             "bearer" => Ok(AuthType::Bearer),
             "api_key" => Ok(AuthType::ApiKey),
             "basic" => Ok(AuthType::Basic),
+            "session" => Ok(AuthType::Session),
+            "oauth" => Ok(AuthType::OAuth),
             _ => Err(serde::de::Error::custom(format!(
-                "Invalid auth type: {}. Valid values: bearer, api_key, basic",
+                "Invalid auth type: {}. Valid values: bearer, api_key, basic, session, oauth",
                 s
             ))),
         }
@@ -83,6 +113,8 @@ impl std::fmt::Display for AuthType {
             AuthType::Bearer => write!(f, "bearer"),
             AuthType::ApiKey => write!(f, "api_key"),
             AuthType::Basic => write!(f, "basic"),
+            AuthType::Session => write!(f, "session"),
+            AuthType::OAuth => write!(f, "oauth"),
         }
     }
 }
@@ -95,8 +127,10 @@ impl std::str::FromStr for AuthType {
             "bearer" => Ok(AuthType::Bearer),
             "api_key" => Ok(AuthType::ApiKey),
             "basic" => Ok(AuthType::Basic),
+            "session" => Ok(AuthType::Session),
+            "oauth" => Ok(AuthType::OAuth),
             _ => anyhow::bail!(
-                "Invalid auth type: {}. Valid values: bearer, api_key, basic",
+                "Invalid auth type: {}. Valid values: bearer, api_key, basic, session, oauth",
                 s
             ),
         }
@@ -104,7 +138,7 @@ impl std::str::FromStr for AuthType {
 }
 
 /// Authentication profile
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Profile {
     /// API key or token
     pub api_key: String,
@@ -116,6 +150,18 @@ pub struct Profile {
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// Login settings for session profiles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<session::SessionConfig>,
+
+    /// Authorization server settings for OAuth profiles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<oauth::OAuthConfig>,
+
+    /// Header or query parameter for API key profiles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_placement: Option<api_key::ApiKeyPlacement>,
 }
 
 impl Profile {
@@ -125,6 +171,9 @@ impl Profile {
             api_key,
             auth_type,
             description: None,
+            session: None,
+            oauth: None,
+            api_key_placement: None,
         }
     }
 
@@ -135,13 +184,130 @@ impl Profile {
     }
 
     /// Mask the API key for display (show only first 8 and last 4 characters)
+    ///
+    /// Secret references (`env:`/`cmd:`) and redacted keys are shown as-is since
+    /// they hold no secret.
     pub fn mask_api_key(&self) -> String {
         let key = &self.api_key;
+        if self.is_redacted() || secret::SecretSource::parse(key).is_reference() {
+            return key.clone();
+        }
         if key.len() <= 12 {
             return "*".repeat(key.len());
         }
         format!("{}...{}", &key[..8], &key[key.len() - 4..])
     }
+
+    /// Whether the API key is the export placeholder rather than a secret.
+    pub fn is_redacted(&self) -> bool {
+        self.api_key == REDACTED_API_KEY
+    }
+
+    /// Resolve the API key, following `env:`/`cmd:` references.
+    pub fn resolve_api_key(&self) -> Result<String> {
+        if self.is_redacted() {
+            anyhow::bail!(
+                "Profile api_key is redacted; set it with `uxc auth set <profile> --api-key <key>`"
+            );
+        }
+        secret::resolve_secret(&self.api_key).context("Failed to resolve profile api_key")
+    }
+
+    /// Create a new profile with session login settings
+    pub fn with_session(mut self, session: session::SessionConfig) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Create a new profile with OAuth settings
+    pub fn with_oauth(mut self, oauth: oauth::OAuthConfig) -> Self {
+        self.oauth = Some(oauth);
+        self
+    }
+
+    /// Create a new profile with an API key placement
+    pub fn with_api_key_placement(mut self, placement: api_key::ApiKeyPlacement) -> Self {
+        self.api_key_placement = Some(placement);
+        self
+    }
+
+    /// Apply this profile's authentication to a request.
+    ///
+    /// Session profiles attach stored cookies and OAuth profiles their stored
+    /// access token; logging in and refreshing happen before adapters run (see
+    /// [`session::ensure_session`] and [`oauth::ensure_token`]). API keys go
+    /// where the profile or the request host's config places them.
+    pub fn apply_to_request(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder> {
+        self.apply_to_request_for(request_builder, None)
+    }
+
+    /// [`Self::apply_to_request`] for an operation that documents an API key
+    /// scheme: without a placement from the profile or host config, the key
+    /// goes where `documented` says.
+    pub fn apply_to_request_for(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+        documented: Option<&scheme::SecurityScheme>,
+    ) -> Result<reqwest::RequestBuilder> {
+        if self.auth_type == AuthType::Session {
+            crate::explain::record("auth", "session profile: stored login cookies attached");
+            return session::apply_cookies(self, request_builder);
+        }
+        if self.auth_type == AuthType::OAuth {
+            crate::explain::record(
+                "auth",
+                "oauth profile: stored access token sent as Authorization: Bearer",
+            );
+            return Ok(request_builder.bearer_auth(oauth::access_token(self)?));
+        }
+        let api_key = self.resolve_api_key()?;
+        if self.auth_type == AuthType::ApiKey {
+            let (client, request) = request_builder.build_split();
+            let mut request = request?;
+            let (placement, source) = match &self.api_key_placement {
+                Some(placement) => (placement.clone(), "the profile's placement".to_string()),
+                None => match api_key::ApiKeyPlacement::for_host(request.url().as_str())? {
+                    Some(placement) => (
+                        placement,
+                        "[api_keys] host match in ~/.uxc/config.toml".to_string(),
+                    ),
+                    None => match documented.and_then(|scheme| Some((scheme.placement()?, scheme)))
+                    {
+                        Some((placement, scheme)) => (
+                            placement,
+                            format!("the operation's '{}' security scheme", scheme.name),
+                        ),
+                        None => (
+                            api_key::ApiKeyPlacement::default(),
+                            "default (no profile placement, host config or documented scheme)"
+                                .to_string(),
+                        ),
+                    },
+                },
+            };
+            crate::explain::record(
+                "auth",
+                format!("API key sent in {} from {}", placement.describe(), source),
+            );
+            placement.apply(&mut request, &api_key)?;
+            return Ok(reqwest::RequestBuilder::from_parts(client, request));
+        }
+        crate::explain::record(
+            "auth",
+            match self.auth_type {
+                AuthType::Basic => "basic profile: Authorization: Basic header",
+                _ => "bearer profile: Authorization: Bearer header",
+            },
+        );
+        Ok(apply_auth_to_request(
+            request_builder,
+            &self.auth_type,
+            &api_key,
+        ))
+    }
 }
 
 /// Profiles collection
@@ -309,7 +475,8 @@ pub fn apply_auth_to_request(
     api_key: &str,
 ) -> reqwest::RequestBuilder {
     match auth_type {
-        AuthType::Bearer => request_builder.bearer_auth(api_key),
+        // For OAuth profiles `api_key` is the access token.
+        AuthType::Bearer | AuthType::OAuth => request_builder.bearer_auth(api_key),
         AuthType::ApiKey => request_builder.header("X-API-Key", api_key),
         AuthType::Basic => {
             // For basic auth, api_key should be in format "username:password"
@@ -322,6 +489,8 @@ pub fn apply_auth_to_request(
                 request_builder.basic_auth(api_key, Option::<&str>::None)
             }
         }
+        // Cookies are attached by `Profile::apply_to_request`.
+        AuthType::Session => request_builder,
     }
 }
 
@@ -339,7 +508,7 @@ pub fn auth_to_metadata(
     let mut metadata = tonic::metadata::MetadataMap::new();
 
     match auth_type {
-        AuthType::Bearer => {
+        AuthType::Bearer | AuthType::OAuth => {
             let value = tonic::metadata::MetadataValue::try_from(&format!("Bearer {}", api_key))
                 .map_err(|_| {
                     anyhow::anyhow!("Invalid Bearer token: contains invalid metadata characters")
@@ -369,6 +538,9 @@ pub fn auth_to_metadata(
                 })?;
             metadata.insert("authorization", value);
         }
+        AuthType::Session => {
+            anyhow::bail!("Session authentication is not supported for gRPC")
+        }
     }
 
     Ok(metadata)
@@ -443,6 +615,7 @@ mod tests {
         assert_eq!(AuthType::from_str("BEARER").unwrap(), AuthType::Bearer);
         assert_eq!(AuthType::from_str("api_key").unwrap(), AuthType::ApiKey);
         assert_eq!(AuthType::from_str("basic").unwrap(), AuthType::Basic);
+        assert_eq!(AuthType::from_str("oauth").unwrap(), AuthType::OAuth);
         assert!(AuthType::from_str("invalid").is_err());
     }
 
@@ -0,0 +1,653 @@
+//! OAuth 2.0 browser login (authorization code with PKCE)
+//!
+//! Profiles with `auth_type = "oauth"` get a bearer token by logging in through
+//! the browser:
+//!
+//! ```toml
+//! [github]
+//! auth_type = "oauth"
+//! api_key = ""
+//!
+//! [github.oauth]
+//! authorization_url = "https://github.com/login/oauth/authorize"
+//! token_url = "https://github.com/login/oauth/access_token"
+//! client_id = "Iv1.abc123"
+//! scopes = ["repo"]
+//! ```
+//!
+//! `uxc auth login <profile>` opens the authorization URL, receives the code on
+//! a `127.0.0.1` callback listener and exchanges it for tokens. The profile
+//! `api_key` is the client secret and may be empty for public clients. Tokens
+//! are kept in `~/.uxc/tokens/<key>.json` and refreshed automatically when they
+//! expire.
+
+use super::secret::resolve_secret;
+use super::{dirs, Profile, DEFAULT_PROFILES_DIR};
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Token store directory relative to the uxc directory
+pub const TOKENS_DIR: &str = "tokens";
+
+/// Path the callback listener expects the authorization server to redirect to
+pub const CALLBACK_PATH: &str = "/callback";
+
+/// How long `uxc auth login` waits for the browser to come back
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long one connection to the callback listener may take to send its
+/// request
+const CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Tokens are refreshed this many seconds before they expire
+const EXPIRY_SKEW_SECS: u64 = 60;
+
+/// Authorization server settings for an OAuth profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthConfig {
+    pub authorization_url: String,
+    pub token_url: String,
+    pub client_id: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+
+    /// Fixed callback port, for servers that only accept registered redirect
+    /// URIs; a free port is picked when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+}
+
+impl OAuthConfig {
+    pub fn new(authorization_url: String, token_url: String, client_id: String) -> Self {
+        Self {
+            authorization_url,
+            token_url,
+            client_id,
+            scopes: Vec::new(),
+            redirect_port: None,
+        }
+    }
+
+    /// Key the tokens are stored under: token endpoint host and client id.
+    pub fn token_key(&self) -> Result<String> {
+        let host = super::session::host_key(&self.token_url)?;
+        Ok(format!("{}_{}", host, self.client_id).replace([':', '/', '\\'], "_"))
+    }
+}
+
+/// Tokens issued for an OAuth profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OAuthTokens {
+    pub access_token: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+
+    /// Expiry as seconds since the Unix epoch; `None` if the server gave none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl OAuthTokens {
+    /// Whether the access token is expired or about to expire.
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at
+            .is_some_and(|expires| expires <= now + EXPIRY_SKEW_SECS)
+    }
+
+    fn register_for_masking(&self) {
+        crate::masking::register(&self.access_token);
+        if let Some(refresh_token) = &self.refresh_token {
+            crate::masking::register(refresh_token);
+        }
+    }
+
+    fn path(key: &str) -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(home
+            .join(DEFAULT_PROFILES_DIR)
+            .join(TOKENS_DIR)
+            .join(format!("{}.json", key)))
+    }
+
+    /// Load stored tokens, or `None` when there are none.
+    pub fn load(key: &str) -> Result<Option<Self>> {
+        let path = Self::path(key)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read OAuth tokens: {:?}", path))?;
+        let tokens: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse OAuth tokens: {:?}", path))?;
+        tokens.register_for_masking();
+        Ok(Some(tokens))
+    }
+
+    pub fn save(&self, key: &str) -> Result<()> {
+        let path = Self::path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create token directory: {:?}", parent))?;
+        }
+        self.write_to(&path)
+    }
+
+    fn write_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize tokens")?;
+        // Written to a fresh owner-only file, so the tokens are never readable
+        // by others, not even briefly or through an older file's permissions
+        let tmp = path.with_extension("json.tmp");
+        let _ = fs::remove_file(&tmp);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&tmp)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write OAuth tokens: {:?}", tmp))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write OAuth tokens: {:?}", path))
+    }
+
+    /// Remove stored tokens. Returns whether any existed.
+    pub fn clear(key: &str) -> Result<bool> {
+        let path = Self::path(key)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove OAuth tokens: {:?}", path))?;
+        Ok(true)
+    }
+}
+
+/// Token endpoint response (RFC 6749 section 5.1).
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+impl TokenResponse {
+    fn into_tokens(self, now: u64) -> OAuthTokens {
+        OAuthTokens {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            expires_at: self.expires_in.map(|secs| now + secs),
+            scope: self.scope,
+        }
+    }
+}
+
+/// PKCE verifier and its S256 challenge (RFC 7636).
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Result<Self> {
+        Ok(Self::from_verifier(random_token()?))
+    }
+
+    fn from_verifier(verifier: String) -> Self {
+        let digest = Sha256::digest(verifier.as_bytes());
+        let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest);
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// 32 random bytes, base64url-encoded (43 characters).
+fn random_token() -> Result<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|err| anyhow::anyhow!("Failed to generate random bytes: {}", err))?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Authorization URL the browser is sent to.
+pub fn authorization_url(
+    config: &OAuthConfig,
+    redirect_uri: &str,
+    pkce: &Pkce,
+    state: &str,
+) -> Result<String> {
+    let mut url = url::Url::parse(&config.authorization_url)
+        .with_context(|| format!("Invalid authorization URL: {}", config.authorization_url))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("code_challenge", &pkce.challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", state);
+        if !config.scopes.is_empty() {
+            query.append_pair("scope", &config.scopes.join(" "));
+        }
+    }
+    Ok(url.to_string())
+}
+
+/// Outcome of one request to the callback listener.
+#[derive(Debug, PartialEq)]
+enum Callback {
+    Code(String),
+    Denied(String),
+    /// Anything else the browser asks for, e.g. `/favicon.ico`, and
+    /// malformed requests
+    Ignored,
+    /// A callback for another login, or a forged one
+    Mismatched,
+}
+
+/// Parse the request line of a callback request, checking `state`.
+fn parse_callback(request: &str, state: &str) -> Result<Callback> {
+    let url = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|target| url::Url::parse(&format!("http://127.0.0.1{}", target)).ok());
+    let Some(url) = url.filter(|url| url.path() == CALLBACK_PATH) else {
+        return Ok(Callback::Ignored);
+    };
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    if param("state").as_deref() != Some(state) {
+        return Ok(Callback::Mismatched);
+    }
+    if let Some(error) = param("error") {
+        let description = param("error_description")
+            .map(|description| format!(": {}", description))
+            .unwrap_or_default();
+        return Ok(Callback::Denied(format!("{}{}", error, description)));
+    }
+    match param("code") {
+        Some(code) => Ok(Callback::Code(code)),
+        None => bail!("OAuth callback did not include an authorization code"),
+    }
+}
+
+/// Accept callback requests until one carries the authorization code.
+/// Connections that stall, fail or carry another `state` are answered (when
+/// possible) and skipped, so they cannot hold up or abort the login.
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .context("Failed to accept OAuth callback")?;
+
+        let mut buf = vec![0u8; 8192];
+        let mut len = 0;
+        let read = tokio::time::timeout(CALLBACK_READ_TIMEOUT, async {
+            while len < buf.len() {
+                let read = stream.read(&mut buf[len..]).await?;
+                if read == 0 {
+                    break;
+                }
+                len += read;
+                if buf[..len].windows(4).any(|window| window == b"\r\n\r\n") {
+                    break;
+                }
+            }
+            std::io::Result::Ok(())
+        })
+        .await;
+        match read {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                tracing::debug!("Skipping OAuth callback connection: {}", err);
+                continue;
+            }
+            Err(_) => {
+                tracing::debug!("Skipping OAuth callback connection that sent no request");
+                continue;
+            }
+        }
+        let request = String::from_utf8_lossy(&buf[..len]);
+
+        let (status, body, outcome) = match parse_callback(&request, state) {
+            Ok(Callback::Ignored) => ("404 Not Found", "Not found", None),
+            Ok(Callback::Mismatched) => {
+                tracing::warn!("Ignoring an OAuth callback whose state does not match this login");
+                (
+                    "400 Bad Request",
+                    "This callback does not belong to the login in progress.",
+                    None,
+                )
+            }
+            Ok(Callback::Code(code)) => (
+                "200 OK",
+                "Login complete. You can close this window and return to the terminal.",
+                Some(Ok(code)),
+            ),
+            Ok(Callback::Denied(reason)) => (
+                "400 Bad Request",
+                "Login was denied. Check the terminal for details.",
+                Some(Err(anyhow::anyhow!("Authorization denied: {}", reason))),
+            ),
+            Err(err) => (
+                "400 Bad Request",
+                "Login failed. Check the terminal for details.",
+                Some(Err(err)),
+            ),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+    }
+}
+
+/// Post a token request and parse the response.
+async fn request_tokens(
+    config: &OAuthConfig,
+    client_secret: Option<&str>,
+    mut form: Vec<(&str, &str)>,
+) -> Result<OAuthTokens> {
+    form.push(("client_id", config.client_id.as_str()));
+    if let Some(secret) = client_secret {
+        form.push(("client_secret", secret));
+    }
+
+    crate::allowlist::enforce(&config.token_url, None)?;
+    let response = crate::http::client()
+        .post(&config.token_url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .form(&form)
+        .send()
+        .await
+        .with_context(|| format!("Token request to {} failed", config.token_url))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .context("Failed to read token response")?;
+    if !status.is_success() {
+        bail!(
+            "Token request to {} failed with HTTP {}: {}",
+            config.token_url,
+            status,
+            body
+        );
+    }
+
+    let parsed: TokenResponse = serde_json::from_str(&body)
+        .with_context(|| format!("Invalid token response from {}", config.token_url))?;
+    let tokens = parsed.into_tokens(now_secs());
+    tokens.register_for_masking();
+    Ok(tokens)
+}
+
+fn client_secret(profile: &Profile) -> Result<Option<String>> {
+    if profile.api_key.is_empty() {
+        return Ok(None);
+    }
+    resolve_secret(&profile.api_key)
+        .map(Some)
+        .context("Failed to resolve client secret")
+}
+
+/// Run the browser login and store the issued tokens.
+pub async fn login(profile: &Profile) -> Result<OAuthTokens> {
+    let config = oauth_config(profile)?;
+    let secret = client_secret(profile)?;
+    crate::allowlist::enforce(&config.authorization_url, None)?;
+    crate::allowlist::enforce(&config.token_url, None)?;
+
+    let listener = TcpListener::bind(("127.0.0.1", config.redirect_port.unwrap_or(0)))
+        .await
+        .context("Failed to start the OAuth callback listener")?;
+    let port = listener.local_addr()?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+    let pkce = Pkce::generate()?;
+    let state = random_token()?;
+    let url = authorization_url(config, &redirect_uri, &pkce, &state)?;
+
+    eprintln!(
+        "Opening the browser to log in. If it does not open, visit:\n\n  {}\n",
+        url
+    );
+    if let Err(err) = open::that(&url) {
+        tracing::warn!("Failed to open the browser: {}", err);
+    }
+
+    let code = tokio::time::timeout(LOGIN_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out waiting for the browser login"))??;
+
+    let tokens = request_tokens(
+        config,
+        secret.as_deref(),
+        vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("code_verifier", pkce.verifier.as_str()),
+        ],
+    )
+    .await?;
+    tokens.save(&config.token_key()?)?;
+    Ok(tokens)
+}
+
+/// Exchange the refresh token for a new access token and store it.
+///
+/// Servers that do not rotate refresh tokens omit one from the response; the
+/// previous refresh token is kept in that case.
+pub async fn refresh(profile: &Profile, tokens: &OAuthTokens) -> Result<OAuthTokens> {
+    let config = oauth_config(profile)?;
+    let Some(refresh_token) = tokens.refresh_token.as_deref() else {
+        bail!("OAuth access token expired and no refresh token was issued; run `uxc auth login <profile>`");
+    };
+
+    let mut refreshed = request_tokens(
+        config,
+        client_secret(profile)?.as_deref(),
+        vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ],
+    )
+    .await
+    .map_err(|err| {
+        anyhow::anyhow!(
+            "Failed to refresh OAuth token ({:#}); run `uxc auth login <profile>`",
+            err
+        )
+    })?;
+    if refreshed.refresh_token.is_none() {
+        refreshed.refresh_token = tokens.refresh_token.clone();
+    }
+    refreshed.save(&config.token_key()?)?;
+    Ok(refreshed)
+}
+
+/// Refresh the stored access token if it is expired.
+pub async fn ensure_token(profile: &Profile) -> Result<()> {
+    let config = oauth_config(profile)?;
+    let Some(tokens) = OAuthTokens::load(&config.token_key()?)? else {
+        bail!("No OAuth tokens stored for this profile; run `uxc auth login <profile>`");
+    };
+    if tokens.is_expired(now_secs()) {
+        refresh(profile, &tokens).await?;
+    }
+    Ok(())
+}
+
+/// Stored access token for an OAuth profile.
+pub fn access_token(profile: &Profile) -> Result<String> {
+    let config = oauth_config(profile)?;
+    OAuthTokens::load(&config.token_key()?)?
+        .map(|tokens| tokens.access_token)
+        .context("No OAuth tokens stored for this profile; run `uxc auth login <profile>`")
+}
+
+pub fn oauth_config(profile: &Profile) -> Result<&OAuthConfig> {
+    profile.oauth.as_ref().context(
+        "OAuth profile is missing its [<profile>.oauth] settings (authorization_url, token_url, client_id)",
+    )
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkce_challenge_matches_rfc_example() {
+        // RFC 7636 appendix B
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn authorization_url_carries_pkce_and_scopes() {
+        let mut config = OAuthConfig::new(
+            "https://auth.example.com/authorize?audience=api".to_string(),
+            "https://auth.example.com/token".to_string(),
+            "cli".to_string(),
+        );
+        config.scopes = vec!["read".to_string(), "write".to_string()];
+        let pkce = Pkce::from_verifier("verifier".to_string());
+
+        let url =
+            authorization_url(&config, "http://127.0.0.1:8765/callback", &pkce, "xyz").unwrap();
+        let url = url::Url::parse(&url).unwrap();
+        let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["audience"], "api");
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["code_challenge"], pkce.challenge);
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(query["redirect_uri"], "http://127.0.0.1:8765/callback");
+        assert_eq!(query["scope"], "read write");
+        assert_eq!(query["state"], "xyz");
+    }
+
+    #[test]
+    fn parses_callback_requests() {
+        assert_eq!(
+            parse_callback("GET /callback?code=abc&state=s1 HTTP/1.1\r\n", "s1").unwrap(),
+            Callback::Code("abc".to_string())
+        );
+        assert_eq!(
+            parse_callback("GET /favicon.ico HTTP/1.1\r\n", "s1").unwrap(),
+            Callback::Ignored
+        );
+        assert_eq!(
+            parse_callback(
+                "GET /callback?error=access_denied&error_description=nope&state=s1 HTTP/1.1\r\n",
+                "s1"
+            )
+            .unwrap(),
+            Callback::Denied("access_denied: nope".to_string())
+        );
+        assert_eq!(
+            parse_callback("GET /callback?code=abc&state=other HTTP/1.1\r\n", "s1").unwrap(),
+            Callback::Mismatched
+        );
+        assert_eq!(
+            parse_callback("GET /callback?error=access_denied HTTP/1.1\r\n", "s1").unwrap(),
+            Callback::Mismatched
+        );
+        assert_eq!(parse_callback("", "s1").unwrap(), Callback::Ignored);
+        assert!(parse_callback("GET /callback?state=s1 HTTP/1.1\r\n", "s1").is_err());
+    }
+
+    #[tokio::test]
+    async fn stray_callbacks_do_not_abort_the_login() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let browser = tokio::spawn(async move {
+            let mut responses = Vec::new();
+            for request in [
+                "",
+                "GET /callback?code=forged&state=other HTTP/1.1\r\n\r\n",
+                "GET /callback?code=abc&state=s1 HTTP/1.1\r\n\r\n",
+            ] {
+                let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+                stream.write_all(request.as_bytes()).await.unwrap();
+                stream.shutdown().await.unwrap();
+                let mut response = String::new();
+                let _ = stream.read_to_string(&mut response).await;
+                responses.push(response);
+            }
+            responses
+        });
+
+        assert_eq!(wait_for_code(&listener, "s1").await.unwrap(), "abc");
+        let responses = browser.await.unwrap();
+        assert!(responses[1].starts_with("HTTP/1.1 400"), "{}", responses[1]);
+        assert!(responses[2].starts_with("HTTP/1.1 200"), "{}", responses[2]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saved_tokens_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let home = tempfile::TempDir::new().unwrap();
+        let path = home.path().join("tokens.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let tokens = OAuthTokens {
+            access_token: "access".to_string(),
+            refresh_token: None,
+            expires_at: None,
+            scope: None,
+        };
+        tokens.write_to(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
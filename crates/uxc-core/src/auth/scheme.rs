@@ -0,0 +1,178 @@
+//! Documented security schemes
+//!
+//! OpenAPI documents declare security schemes (`components.securitySchemes`,
+//! Swagger 2 `securityDefinitions`) and which of them each operation requires.
+//! A [`SecurityScheme`] is one such requirement as `describe` reports it: it
+//! knows which profile auth types satisfy it and, for API keys, where the key
+//! goes.
+
+use super::api_key::ApiKeyPlacement;
+use super::AuthType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A security scheme an operation accepts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityScheme {
+    /// Name of the scheme in the document
+    pub name: String,
+
+    /// `apiKey`, `http`, `oauth2`, `openIdConnect` or `mutualTLS`
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// Scheme of `http` schemes, e.g. `bearer` or `basic`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+
+    /// Where an API key goes: `header`, `query` or `cookie`
+    #[serde(rename = "in", default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// Header, query parameter or cookie carrying an API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+
+    /// OAuth scopes the operation needs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+}
+
+impl SecurityScheme {
+    /// Scheme `name` as declared in an OpenAPI document; Swagger 2 `basic`
+    /// schemes become `http` `basic`
+    pub fn from_openapi(name: &str, declaration: &Value, scopes: Vec<String>) -> Self {
+        let text = |field: &str| {
+            declaration
+                .get(field)
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+        };
+        let (kind, scheme) = match text("type").as_deref() {
+            Some("basic") => ("http".to_string(), Some("basic".to_string())),
+            Some("http") => (
+                "http".to_string(),
+                text("scheme").map(|scheme| scheme.to_ascii_lowercase()),
+            ),
+            Some(kind) => (kind.to_string(), None),
+            None => ("unknown".to_string(), None),
+        };
+        let api_key = kind == "apiKey";
+        Self {
+            name: name.to_string(),
+            kind,
+            scheme,
+            location: api_key.then(|| text("in").unwrap_or_else(|| "header".to_string())),
+            parameter: if api_key { text("name") } else { None },
+            scopes,
+        }
+    }
+
+    /// Profile auth types that satisfy the scheme; empty when uxc cannot
+    /// tell (mutual TLS, uncommon HTTP schemes)
+    pub fn auth_types(&self) -> Vec<AuthType> {
+        match (self.kind.as_str(), self.scheme.as_deref()) {
+            ("apiKey", _) if self.location.as_deref() == Some("cookie") => {
+                vec![AuthType::ApiKey, AuthType::Session]
+            }
+            ("apiKey", _) => vec![AuthType::ApiKey],
+            ("http", Some("bearer")) => vec![AuthType::Bearer, AuthType::OAuth],
+            ("http", Some("basic")) => vec![AuthType::Basic],
+            ("oauth2" | "openIdConnect", _) => vec![AuthType::OAuth, AuthType::Bearer],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Where an API key scheme wants the key
+    pub fn placement(&self) -> Option<ApiKeyPlacement> {
+        if self.kind != "apiKey" {
+            return None;
+        }
+        let parameter = self.parameter.clone()?;
+        Some(match self.location.as_deref() {
+            Some("query") => ApiKeyPlacement {
+                query: Some(parameter),
+                ..Default::default()
+            },
+            Some("cookie") => ApiKeyPlacement {
+                header: Some("Cookie".to_string()),
+                template: Some(format!("{}={{key}}", parameter)),
+                ..Default::default()
+            },
+            _ => ApiKeyPlacement {
+                header: Some(parameter),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Short description such as `api_key (apiKey in header X-API-Key)`
+    pub fn describe(&self) -> String {
+        let detail = match self.kind.as_str() {
+            "apiKey" => format!(
+                "apiKey in {} {}",
+                self.location.as_deref().unwrap_or("header"),
+                self.parameter.as_deref().unwrap_or("?")
+            ),
+            "http" => format!("http {}", self.scheme.as_deref().unwrap_or("auth")),
+            other => other.to_string(),
+        };
+        format!("{} ({})", self.name, detail)
+    }
+}
+
+/// Whether a profile of `auth_type` meets `requirements`, alternatives of
+/// schemes used together. An alternative that needs nothing, or a scheme
+/// uxc cannot judge, counts as met.
+pub fn satisfied_by(requirements: &[Vec<SecurityScheme>], auth_type: &AuthType) -> bool {
+    requirements.is_empty()
+        || requirements.iter().any(|schemes| {
+            schemes.is_empty()
+                || schemes.iter().any(|scheme| {
+                    let types = scheme.auth_types();
+                    types.is_empty() || types.contains(auth_type)
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn schemes_map_to_auth_types_and_placements() {
+        let key = SecurityScheme::from_openapi(
+            "key",
+            &json!({"type": "apiKey", "in": "query", "name": "api_key"}),
+            Vec::new(),
+        );
+        assert_eq!(key.describe(), "key (apiKey in query api_key)");
+        assert_eq!(key.placement().unwrap().query.as_deref(), Some("api_key"));
+
+        let cookie = SecurityScheme::from_openapi(
+            "sid",
+            &json!({"type": "apiKey", "in": "cookie", "name": "SID"}),
+            Vec::new(),
+        );
+        let placement = cookie.placement().unwrap();
+        assert_eq!(placement.header.as_deref(), Some("Cookie"));
+        assert_eq!(placement.value("k"), "SID=k");
+        assert!(cookie.auth_types().contains(&AuthType::Session));
+
+        let basic = SecurityScheme::from_openapi("b", &json!({"type": "basic"}), Vec::new());
+        assert_eq!(basic.describe(), "b (http basic)");
+        assert!(basic.placement().is_none());
+
+        let bearer = SecurityScheme::from_openapi(
+            "jwt",
+            &json!({"type": "http", "scheme": "Bearer"}),
+            Vec::new(),
+        );
+        let requirements = vec![vec![bearer], vec![key]];
+        assert!(satisfied_by(&requirements, &AuthType::OAuth));
+        assert!(satisfied_by(&requirements, &AuthType::ApiKey));
+        assert!(!satisfied_by(&requirements, &AuthType::Basic));
+        assert!(satisfied_by(&[Vec::new()], &AuthType::Basic));
+    }
+}
@@ -0,0 +1,166 @@
+//! Secret references for profile fields
+//!
+//! A profile value may point at a secret instead of containing it:
+//!
+//! - `env:NAME` reads the environment variable `NAME` (or a loaded `.env` entry)
+//! - `cmd:<command>` runs `<command>` through the shell and uses its trimmed stdout
+//!
+//! Anything else is used literally. References are resolved when a request is
+//! built, so secrets never need to be written to `profiles.toml`.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+const ENV_PREFIX: &str = "env:";
+const CMD_PREFIX: &str = "cmd:";
+
+/// Where a profile value comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretSource<'a> {
+    Literal(&'a str),
+    Env(&'a str),
+    Command(&'a str),
+}
+
+impl<'a> SecretSource<'a> {
+    pub fn parse(value: &'a str) -> Self {
+        if let Some(name) = value.strip_prefix(ENV_PREFIX) {
+            SecretSource::Env(name.trim())
+        } else if let Some(command) = value.strip_prefix(CMD_PREFIX) {
+            SecretSource::Command(command.trim())
+        } else {
+            SecretSource::Literal(value)
+        }
+    }
+
+    /// Whether the value is a reference rather than the secret itself.
+    pub fn is_reference(&self) -> bool {
+        !matches!(self, SecretSource::Literal(_))
+    }
+}
+
+/// Resolve a profile value to the secret it refers to.
+///
+/// Command output is memoized for the lifetime of the process so a command
+/// like a password manager lookup runs at most once per invocation. Resolved
+/// values are registered for masking (see [`crate::masking`]).
+pub fn resolve_secret(value: &str) -> Result<String> {
+    let secret = resolve_source(value)?;
+    crate::masking::register(&secret);
+    Ok(secret)
+}
+
+fn resolve_source(value: &str) -> Result<String> {
+    match SecretSource::parse(value) {
+        SecretSource::Literal(literal) => Ok(literal.to_string()),
+        SecretSource::Env(name) => {
+            if name.is_empty() {
+                bail!("Secret reference 'env:' is missing a variable name");
+            }
+            match crate::env_file::var(name) {
+                Some(secret) if !secret.is_empty() => Ok(secret),
+                Some(_) => bail!(
+                    "Environment variable '{}' referenced by profile is empty",
+                    name
+                ),
+                None => bail!(
+                    "Environment variable '{}' referenced by profile is not set",
+                    name
+                ),
+            }
+        }
+        SecretSource::Command(command) => {
+            if command.is_empty() {
+                bail!("Secret reference 'cmd:' is missing a command");
+            }
+
+            let cache = command_cache();
+            if let Some(secret) = cache.lock().ok().and_then(|c| c.get(command).cloned()) {
+                return Ok(secret);
+            }
+
+            let secret = run_secret_command(command)?;
+            if let Ok(mut cache) = cache.lock() {
+                cache.insert(command.to_string(), secret.clone());
+            }
+            Ok(secret)
+        }
+    }
+}
+
+fn command_cache() -> &'static Mutex<HashMap<String, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn run_secret_command(command: &str) -> Result<String> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .with_context(|| format!("Failed to run secret command '{}'", command))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "Secret command '{}' failed ({}): {}",
+            command,
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    let secret = String::from_utf8(output.stdout)
+        .with_context(|| format!("Secret command '{}' produced non-UTF-8 output", command))?
+        .trim()
+        .to_string();
+    if secret.is_empty() {
+        bail!("Secret command '{}' produced no output", command);
+    }
+
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_recognizes_prefixes() {
+        assert_eq!(SecretSource::parse("env:TOKEN"), SecretSource::Env("TOKEN"));
+        assert_eq!(
+            SecretSource::parse("cmd:op read op://vault/item"),
+            SecretSource::Command("op read op://vault/item")
+        );
+        assert_eq!(
+            SecretSource::parse("sk-123"),
+            SecretSource::Literal("sk-123")
+        );
+        assert!(!SecretSource::parse("sk-123").is_reference());
+    }
+
+    #[test]
+    fn resolve_env_reference() {
+        std::env::set_var("UXC_SECRET_TEST_TOKEN", "from-env");
+        assert_eq!(
+            resolve_secret("env:UXC_SECRET_TEST_TOKEN").unwrap(),
+            "from-env"
+        );
+
+        let err = resolve_secret("env:UXC_SECRET_TEST_MISSING").unwrap_err();
+        assert!(err.to_string().contains("UXC_SECRET_TEST_MISSING"));
+        assert!(err.to_string().contains("not set"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_command_reference() {
+        assert_eq!(resolve_secret("cmd:echo ' s3cret '").unwrap(), "s3cret");
+
+        let err = resolve_secret("cmd:exit 3").unwrap_err();
+        assert!(err.to_string().contains("Secret command 'exit 3' failed"));
+    }
+}
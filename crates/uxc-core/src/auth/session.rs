@@ -0,0 +1,575 @@
+//! Session (cookie) authentication
+//!
+//! Profiles with `auth_type = "session"` log in by submitting credentials to a
+//! login URL and reuse the returned cookies for later calls:
+//!
+//! ```toml
+//! [intranet]
+//! auth_type = "session"
+//! api_key = "env:INTRANET_PASSWORD"
+//!
+//! [intranet.session]
+//! login_url = "https://intranet.example.com/login"
+//! username = "alice"
+//! ```
+//!
+//! The profile `api_key` is the password. The cookies the login sets are kept
+//! in `~/.uxc/cookies/<login host>.json` and sent like a browser would: to the
+//! login host, or to every host under their `Domain`, below their `Path`, and
+//! over HTTPS only when they are `Secure`. Cookies without an expiry are kept
+//! for [`SESSION_COOKIE_LIFETIME`]; a call rejected with HTTP 401 logs in
+//! again, and `uxc auth login <profile>` forces a fresh login.
+
+use super::secret::resolve_secret;
+use super::{dirs, Profile, DEFAULT_PROFILES_DIR};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cookie jar directory relative to the uxc directory
+pub const COOKIES_DIR: &str = "cookies";
+
+/// How long cookies without `Expires` or `Max-Age` are kept
+pub const SESSION_COOKIE_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// How credentials are submitted to the login URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginFormat {
+    /// `application/x-www-form-urlencoded`
+    #[default]
+    Form,
+    /// `application/json`
+    Json,
+}
+
+impl std::str::FromStr for LoginFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "form" => Ok(LoginFormat::Form),
+            "json" => Ok(LoginFormat::Json),
+            _ => bail!("Invalid login format: {}. Valid values: form, json", s),
+        }
+    }
+}
+
+/// Login settings for a session profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// URL the credentials are posted to
+    pub login_url: String,
+
+    /// Login name (may be an `env:`/`cmd:` reference)
+    pub username: String,
+
+    #[serde(default = "default_username_field")]
+    pub username_field: String,
+
+    #[serde(default = "default_password_field")]
+    pub password_field: String,
+
+    #[serde(default)]
+    pub format: LoginFormat,
+}
+
+fn default_username_field() -> String {
+    "username".to_string()
+}
+
+fn default_password_field() -> String {
+    "password".to_string()
+}
+
+impl SessionConfig {
+    pub fn new(login_url: String, username: String) -> Self {
+        Self {
+            login_url,
+            username,
+            username_field: default_username_field(),
+            password_field: default_password_field(),
+            format: LoginFormat::default(),
+        }
+    }
+
+    /// Host key of the login URL; session cookies are stored under it.
+    pub fn host(&self) -> Result<String> {
+        host_key(&self.login_url)
+    }
+}
+
+/// One stored cookie.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Host the cookie was set by, or its `Domain` without the leading dot
+    #[serde(default)]
+    pub domain: String,
+    /// Sent to `domain` itself only, not its subdomains (no `Domain` given)
+    #[serde(default)]
+    pub host_only: bool,
+    /// Path the cookie is sent below
+    #[serde(default = "default_cookie_path")]
+    pub path: String,
+    /// Sent over HTTPS only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secure: bool,
+    /// Expiry as seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<u64>,
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+impl Cookie {
+    /// Expired, or stored before cookies had an expiry and a domain
+    fn is_expired(&self, now: u64) -> bool {
+        self.domain.is_empty() || self.expires.is_none_or(|expires| expires <= now)
+    }
+
+    /// Whether the cookie is sent with a request to `url`
+    fn applies_to(&self, url: &url::Url) -> bool {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let domain_matches = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+        domain_matches
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+    }
+}
+
+/// Whether `host` is `domain` or a subdomain of it (RFC 6265, 5.1.3)
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.ends_with(domain)
+            && host[..host.len() - domain.len()].ends_with('.')
+            && host.parse::<std::net::IpAddr>().is_err())
+}
+
+/// Whether `request_path` is below `cookie_path` (RFC 6265, 5.1.4)
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// Path of cookies set without one: the directory of `path` (RFC 6265, 5.1.4)
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => path[..end].to_string(),
+    }
+}
+
+/// Cookies for one host, persisted under `~/.uxc/cookies`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CookieJar {
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    fn path(host: &str) -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        let file = host.replace([':', '/', '\\'], "_");
+        Ok(home
+            .join(DEFAULT_PROFILES_DIR)
+            .join(COOKIES_DIR)
+            .join(format!("{}.json", file)))
+    }
+
+    /// Load the jar for a host, dropping expired cookies. A missing file
+    /// yields an empty jar.
+    pub fn load(host: &str) -> Result<Self> {
+        let path = Self::path(host)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read cookie jar: {:?}", path))?;
+        let mut jar: CookieJar = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse cookie jar: {:?}", path))?;
+        let now = now_secs();
+        jar.cookies.retain(|cookie| !cookie.is_expired(now));
+        for cookie in &jar.cookies {
+            crate::masking::register(&cookie.value);
+        }
+        Ok(jar)
+    }
+
+    pub fn save(&self, host: &str) -> Result<()> {
+        let path = Self::path(host)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create cookie directory: {:?}", parent))?;
+        }
+        self.write_to(&path)
+    }
+
+    fn write_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize cookies")?;
+        // Written to a fresh owner-only file, then moved over the old jar
+        let tmp = path.with_extension("json.tmp");
+        let _ = fs::remove_file(&tmp);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&tmp)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write cookie jar: {:?}", tmp))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write cookie jar: {:?}", path))
+    }
+
+    /// Remove the stored jar for a host. Returns whether one existed.
+    pub fn clear(host: &str) -> Result<bool> {
+        let path = Self::path(host)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove cookie jar: {:?}", path))?;
+        Ok(true)
+    }
+
+    /// Add or replace cookies from `Set-Cookie` header values of a response
+    /// to `origin`. Cookies for a `Domain` that `origin` is not in, and
+    /// `Secure` cookies from plain HTTP, are ignored.
+    pub fn store_set_cookies<'a>(
+        &mut self,
+        origin: &url::Url,
+        headers: impl IntoIterator<Item = &'a str>,
+    ) {
+        let now = now_secs();
+        for header in headers {
+            let Some(cookie) = parse_set_cookie(header, origin, now) else {
+                continue;
+            };
+            self.cookies.retain(|existing| {
+                (&existing.name, &existing.domain, &existing.path)
+                    != (&cookie.name, &cookie.domain, &cookie.path)
+            });
+            if !cookie.is_expired(now) {
+                crate::masking::register(&cookie.value);
+                self.cookies.push(cookie);
+            }
+        }
+    }
+
+    /// `Cookie` request header value for `url`, or `None` when no stored
+    /// cookie applies to it. Cookies with longer paths come first.
+    pub fn header_value(&self, url: &url::Url) -> Option<String> {
+        let mut cookies = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.applies_to(url))
+            .collect::<Vec<_>>();
+        if cookies.is_empty() {
+            return None;
+        }
+        cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        Some(
+            cookies
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+}
+
+/// Parse one `Set-Cookie` header of a response to `origin`. `Max-Age` takes
+/// precedence over `Expires`; without either the cookie is kept for
+/// [`SESSION_COOKIE_LIFETIME`].
+fn parse_set_cookie(header: &str, origin: &url::Url, now: u64) -> Option<Cookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.trim().split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let host = origin.host_str()?.to_ascii_lowercase();
+
+    let mut expires = None;
+    let mut max_age = None;
+    let mut domain = None;
+    let mut path = None;
+    let mut secure = false;
+    for attribute in parts {
+        let (key, attr_value) = attribute.trim().split_once('=').unwrap_or((attribute, ""));
+        let attr_value = attr_value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "max-age" => max_age = attr_value.parse::<i64>().ok(),
+            "expires" => {
+                expires = httpdate::parse_http_date(attr_value)
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs())
+            }
+            "domain" => {
+                domain = Some(attr_value.trim_start_matches('.').to_ascii_lowercase())
+                    .filter(|domain| !domain.is_empty())
+            }
+            "path" if attr_value.starts_with('/') => path = Some(attr_value.to_string()),
+            "secure" => secure = true,
+            _ => {}
+        }
+    }
+
+    if domain
+        .as_ref()
+        .is_some_and(|domain| !domain_matches(&host, domain))
+    {
+        return None;
+    }
+    if secure && origin.scheme() != "https" {
+        return None;
+    }
+
+    let expires = match max_age {
+        Some(age) if age <= 0 => 0,
+        Some(age) => now + age as u64,
+        None => expires.unwrap_or(now + SESSION_COOKIE_LIFETIME.as_secs()),
+    };
+
+    Some(Cookie {
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        host_only: domain.is_none(),
+        domain: domain.unwrap_or(host),
+        path: path.unwrap_or_else(|| default_path(origin.path())),
+        secure,
+        expires: Some(expires),
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// Host key (`host` or `host:port`) for a URL.
+pub fn host_key(url: &str) -> Result<String> {
+    let parsed = url::Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+    let host = parsed
+        .host_str()
+        .with_context(|| format!("URL has no host: {}", url))?;
+    Ok(match parsed.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// Submit the profile credentials to the login URL and store the cookies.
+///
+/// Redirects are not followed so cookies set on a `302` after login are
+/// captured. Returns the stored jar.
+pub async fn login(profile: &Profile) -> Result<CookieJar> {
+    let config = session_config(profile)?;
+    let username = resolve_secret(&config.username).context("Failed to resolve username")?;
+    let password = profile.resolve_api_key()?;
+
+    crate::allowlist::enforce(&config.login_url, None)?;
+    let client = crate::http::client();
+    let fields = [
+        (config.username_field.as_str(), username.as_str()),
+        (config.password_field.as_str(), password.as_str()),
+    ];
+    let request = client.post(&config.login_url);
+    let request = match config.format {
+        LoginFormat::Form => request.form(&fields),
+        LoginFormat::Json => request.json(
+            &fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<std::collections::HashMap<_, _>>(),
+        ),
+    };
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Login request to {} failed", config.login_url))?;
+    let status = response.status();
+    if !(status.is_success() || status.is_redirection()) {
+        bail!("Login to {} failed with HTTP {}", config.login_url, status);
+    }
+
+    let mut jar = CookieJar::default();
+    jar.store_set_cookies(
+        response.url(),
+        response
+            .headers()
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok()),
+    );
+    if jar.cookies.is_empty() {
+        bail!(
+            "Login to {} succeeded but no session cookies were set",
+            config.login_url
+        );
+    }
+
+    jar.save(&config.host()?)?;
+    Ok(jar)
+}
+
+/// Log in unless the jar already holds unexpired cookies for the login host.
+pub async fn ensure_session(profile: &Profile) -> Result<()> {
+    let config = session_config(profile)?;
+    if CookieJar::load(&config.host()?)?.cookies.is_empty() {
+        login(profile).await?;
+    }
+    Ok(())
+}
+
+fn session_config(profile: &Profile) -> Result<&SessionConfig> {
+    profile.session.as_ref().context(
+        "Session profile is missing its [<profile>.session] login settings (login_url, username)",
+    )
+}
+
+/// Attach the stored cookies of a session profile that apply to the request.
+pub fn apply_cookies(
+    profile: &Profile,
+    request_builder: reqwest::RequestBuilder,
+) -> Result<reqwest::RequestBuilder> {
+    let (client, request) = request_builder.build_split();
+    let mut request = request.context("Failed to build request")?;
+
+    let jar = CookieJar::load(&session_config(profile)?.host()?)?;
+    if let Some(value) = jar.header_value(request.url()) {
+        let value = reqwest::header::HeaderValue::from_str(&value)
+            .context("Stored cookies contain invalid header characters")?;
+        request.headers_mut().insert(reqwest::header::COOKIE, value);
+    }
+
+    Ok(reqwest::RequestBuilder::from_parts(client, request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(text: &str) -> url::Url {
+        url::Url::parse(text).unwrap()
+    }
+
+    #[test]
+    fn parses_set_cookie_expiry() {
+        let origin = url("https://example.com/login");
+        let cookie =
+            parse_set_cookie("sid=abc123; Path=/; Max-Age=60; HttpOnly", &origin, 1_000).unwrap();
+        assert_eq!(cookie.name, "sid");
+        assert_eq!(cookie.value, "abc123");
+        assert_eq!(cookie.expires, Some(1_060));
+
+        let cookie = parse_set_cookie(
+            "token=x; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            &origin,
+            1_000,
+        )
+        .unwrap();
+        assert_eq!(cookie.expires, Some(1_445_412_480));
+
+        let cookie = parse_set_cookie("sid=x", &origin, 1_000).unwrap();
+        assert_eq!(
+            cookie.expires,
+            Some(1_000 + SESSION_COOKIE_LIFETIME.as_secs())
+        );
+
+        assert!(parse_set_cookie("garbage", &origin, 0).is_none());
+    }
+
+    #[test]
+    fn jar_replaces_and_expires_cookies() {
+        let origin = url("https://example.com/login");
+        let mut jar = CookieJar::default();
+        jar.store_set_cookies(&origin, ["sid=one", "csrf=t"]);
+        jar.store_set_cookies(&origin, ["sid=two", "csrf=; Max-Age=0"]);
+
+        assert_eq!(jar.header_value(&origin).as_deref(), Some("sid=two"));
+    }
+
+    #[test]
+    fn cookies_follow_domain_path_and_secure() {
+        let mut jar = CookieJar::default();
+        jar.store_set_cookies(
+            &url("https://auth.example.com/sso/login"),
+            [
+                "shared=1; Domain=.example.com; Path=/",
+                "local=2",
+                "api=3; Domain=example.com; Path=/v1",
+                "tls=4; Domain=example.com; Path=/; Secure",
+                "foreign=5; Domain=other.com",
+            ],
+        );
+        assert_eq!(jar.cookies.len(), 4);
+
+        let value = |text: &str| jar.header_value(&url(text));
+        assert_eq!(
+            value("https://api.example.com/v1/users").as_deref(),
+            Some("api=3; shared=1; tls=4")
+        );
+        assert_eq!(
+            value("http://api.example.com/v10").as_deref(),
+            Some("shared=1")
+        );
+        assert_eq!(
+            value("https://auth.example.com/sso/me").as_deref(),
+            Some("local=2; shared=1; tls=4")
+        );
+        assert_eq!(value("https://example.org/"), None);
+    }
+
+    #[test]
+    fn secure_cookies_need_https() {
+        let origin = url("http://example.com/login");
+        let mut jar = CookieJar::default();
+        jar.store_set_cookies(&origin, ["sid=1; Secure", "plain=2"]);
+        assert_eq!(jar.header_value(&origin).as_deref(), Some("plain=2"));
+    }
+
+    #[test]
+    fn host_key_includes_port() {
+        assert_eq!(
+            host_key("http://127.0.0.1:8080/login").unwrap(),
+            "127.0.0.1:8080"
+        );
+        assert_eq!(host_key("https://example.com/x").unwrap(), "example.com");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saved_jars_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("example.com.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        CookieJar::default().write_to(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
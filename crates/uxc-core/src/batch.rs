@@ -0,0 +1,168 @@
+//! Per-item execution for multi-target commands (`uxc test`, `uxc cache warm`)
+//!
+//! Each item runs under the timeout and retries of a [`RetryPolicy`] (see
+//! [`crate::retry`]). The outcome records the error code
+//! and message, timing and attempt count, so one slow or broken target shows
+//! up in the report instead of aborting or hiding the rest of the run.
+
+use crate::retry::RetryPolicy;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Instant;
+
+/// Error code of an item that exceeded its timeout
+pub const TIMEOUT_CODE: &str = "TIMEOUT";
+
+/// Timeout, retry and fail-fast settings for a batch run
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// Timeout and retries of one item
+    pub retry: RetryPolicy,
+
+    /// Skip the remaining items once one fails
+    pub fail_fast: bool,
+}
+
+/// Error of one item, in the same code taxonomy as error envelopes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemError {
+    pub code: String,
+    pub message: String,
+
+    /// HTTP status, when the endpoint rejected the call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+}
+
+/// Result of running one item, with its timing and attempt count
+#[derive(Debug)]
+pub struct Attempted<T> {
+    pub result: std::result::Result<T, ItemError>,
+    pub attempts: u32,
+    pub duration_ms: u64,
+}
+
+impl<T> Attempted<T> {
+    /// Whether the last attempt ran into the timeout
+    pub fn timed_out(&self) -> bool {
+        matches!(&self.result, Err(error) if error.code == TIMEOUT_CODE)
+    }
+}
+
+/// Counts over all items of a run. `timed_out` items are also `failed`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+    pub skipped: usize,
+}
+
+/// Run `call` under the batch timeout, retrying transient failures.
+///
+/// `classify` maps a failure to its error code.
+pub async fn run<T, F, Fut>(
+    options: &BatchOptions,
+    classify: impl Fn(&anyhow::Error) -> String,
+    call: F,
+) -> Attempted<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let start = Instant::now();
+    let retried = crate::retry::run(&options.retry, call).await;
+    Attempted {
+        result: retried.result.map_err(|err| ItemError {
+            code: classify(&err),
+            message: err.to_string(),
+            status: crate::error::http_status_of(&err),
+        }),
+        attempts: retried.attempts,
+        duration_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::UxcError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    fn classify(err: &anyhow::Error) -> String {
+        match err.downcast_ref::<UxcError>() {
+            Some(UxcError::Timeout(_)) => TIMEOUT_CODE.to_string(),
+            _ => "EXECUTION_FAILED".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn timeouts_are_retried_then_reported() {
+        let options = BatchOptions {
+            retry: RetryPolicy {
+                timeout: Some(Duration::from_millis(20)),
+                retries: 2,
+                ..Default::default()
+            },
+            fail_fast: false,
+        };
+        let calls = AtomicU32::new(0);
+
+        let attempted: Attempted<()> = run(&options, classify, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        })
+        .await;
+
+        assert_eq!(attempted.attempts, 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(attempted.timed_out());
+        assert!(attempted.result.unwrap_err().message.contains("20ms"));
+    }
+
+    #[tokio::test]
+    async fn permanent_failures_are_not_retried() {
+        let options = BatchOptions {
+            retry: RetryPolicy {
+                retries: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let attempted: Attempted<()> = run(&options, classify, || async {
+            Err(UxcError::OperationNotFound("get:/x".to_string()).into())
+        })
+        .await;
+
+        assert_eq!(attempted.attempts, 1);
+        assert_eq!(attempted.result.unwrap_err().code, "EXECUTION_FAILED");
+    }
+
+    #[tokio::test]
+    async fn success_after_transient_failure() {
+        let options = BatchOptions {
+            retry: RetryPolicy {
+                retries: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let calls = AtomicU32::new(0);
+
+        let attempted = run(&options, classify, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(UxcError::Timeout("slow".to_string()).into())
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+
+        assert_eq!(attempted.attempts, 2);
+        assert_eq!(attempted.result.unwrap(), 42);
+    }
+}
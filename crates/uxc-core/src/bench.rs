@@ -0,0 +1,301 @@
+//! Load tests (`uxc bench`)
+//!
+//! [`run`] keeps a number of calls in flight until a duration has passed or a
+//! number of calls were made, then reports throughput, latency percentiles and
+//! what went wrong, grouped by error code and HTTP status. Calls go through
+//! whatever the caller passes in, normally an adapter's `execute`, so every
+//! protocol can be benchmarked the same way.
+
+use crate::batch::ItemError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long a benchmark runs when neither a duration nor a call count is given
+pub const DEFAULT_DURATION: Duration = Duration::from_secs(10);
+
+/// When a benchmark stops and how hard it pushes
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Calls in flight at a time
+    pub concurrency: usize,
+
+    /// Stop starting calls after this long
+    pub duration: Option<Duration>,
+
+    /// Stop after this many calls
+    pub requests: Option<u64>,
+}
+
+/// Outcome of a benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub concurrency: usize,
+    pub duration_ms: u64,
+    pub requests: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+
+    /// Calls completed per second
+    pub throughput: f64,
+
+    /// Latency of every call, failed ones included
+    pub latency: Latency,
+
+    /// Calls per HTTP status, for protocols that report one
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub statuses: BTreeMap<u16, u64>,
+
+    /// Failed calls by error code and status, most frequent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorCount>,
+}
+
+/// Latency distribution in milliseconds; percentiles are nearest-rank
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Latency {
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Failed calls sharing an error code and status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorCount {
+    pub code: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+
+    pub count: u64,
+
+    /// Message of the first such failure
+    pub message: String,
+}
+
+struct Sample {
+    latency: Duration,
+    /// HTTP status of a successful call, or the error of a failed one
+    result: Result<Option<u16>, ItemError>,
+}
+
+/// Call `call` from `options.concurrency` workers until the duration is over
+/// or the call count is reached. Calls in flight when time runs out are
+/// waited for and counted.
+///
+/// A call resolves to the HTTP status of the response, if any; `classify`
+/// maps a failure to its error code.
+pub async fn run<F, Fut>(
+    options: &BenchOptions,
+    classify: impl Fn(&anyhow::Error) -> String,
+    call: F,
+) -> BenchReport
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<Option<u16>>>,
+{
+    let duration = match (options.duration, options.requests) {
+        (None, None) => Some(DEFAULT_DURATION),
+        (duration, _) => duration,
+    };
+    let start = Instant::now();
+    let deadline = duration.map(|duration| start + duration);
+    let started = AtomicU64::new(0);
+
+    let worker = || async {
+        let mut samples = Vec::new();
+        loop {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            let number = started.fetch_add(1, Ordering::Relaxed);
+            if options.requests.is_some_and(|requests| number >= requests) {
+                break;
+            }
+            let began = Instant::now();
+            let result = call().await.map_err(|err| ItemError {
+                code: classify(&err),
+                message: err.to_string(),
+                status: crate::error::http_status_of(&err),
+            });
+            samples.push(Sample {
+                latency: began.elapsed(),
+                result,
+            });
+        }
+        samples
+    };
+    let samples: Vec<Sample> =
+        futures::future::join_all((0..options.concurrency.max(1)).map(|_| worker()))
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+    BenchReport::new(options.concurrency.max(1), start.elapsed(), samples)
+}
+
+impl BenchReport {
+    fn new(concurrency: usize, elapsed: Duration, samples: Vec<Sample>) -> Self {
+        let mut latencies: Vec<Duration> = samples.iter().map(|sample| sample.latency).collect();
+        latencies.sort_unstable();
+
+        let mut statuses = BTreeMap::new();
+        let mut errors: Vec<ErrorCount> = Vec::new();
+        for sample in &samples {
+            let status = match &sample.result {
+                Ok(status) => *status,
+                Err(error) => {
+                    match errors
+                        .iter_mut()
+                        .find(|count| count.code == error.code && count.status == error.status)
+                    {
+                        Some(count) => count.count += 1,
+                        None => errors.push(ErrorCount {
+                            code: error.code.clone(),
+                            status: error.status,
+                            count: 1,
+                            message: error.message.clone(),
+                        }),
+                    }
+                    error.status
+                }
+            };
+            if let Some(status) = status {
+                *statuses.entry(status).or_insert(0) += 1;
+            }
+        }
+        errors.sort_by_key(|error| std::cmp::Reverse(error.count));
+
+        let requests = samples.len() as u64;
+        let failed = errors.iter().map(|error| error.count).sum();
+        let seconds = elapsed.as_secs_f64();
+        Self {
+            concurrency,
+            duration_ms: elapsed.as_millis() as u64,
+            requests,
+            succeeded: requests - failed,
+            failed,
+            throughput: if seconds > 0.0 {
+                requests as f64 / seconds
+            } else {
+                0.0
+            },
+            latency: Latency::of(&latencies),
+            statuses,
+            errors,
+        }
+    }
+}
+
+impl Latency {
+    fn of(sorted: &[Duration]) -> Self {
+        let (Some(min), Some(max)) = (sorted.first(), sorted.last()) else {
+            return Self::default();
+        };
+        let total: Duration = sorted.iter().sum();
+        Self {
+            min_ms: millis(*min),
+            mean_ms: millis(total) / sorted.len() as f64,
+            p50_ms: millis(percentile(sorted, 50)),
+            p90_ms: millis(percentile(sorted, 90)),
+            p95_ms: millis(percentile(sorted, 95)),
+            p99_ms: millis(percentile(sorted, 99)),
+            max_ms: millis(*max),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Nearest-rank percentile of sorted, non-empty values
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ErrorDetail, UxcError};
+
+    fn classify(err: &anyhow::Error) -> String {
+        crate::error::code_of(err).to_string()
+    }
+
+    #[tokio::test]
+    async fn counts_calls_statuses_and_errors() {
+        let options = BenchOptions {
+            concurrency: 4,
+            duration: None,
+            requests: Some(20),
+        };
+        let made = AtomicU64::new(0);
+
+        let report = run(&options, classify, || async {
+            let number = made.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+            if number.is_multiple_of(4) {
+                Err(UxcError::call_failed("HTTP 503", ErrorDetail::http(503, "busy")).into())
+            } else {
+                Ok(Some(200))
+            }
+        })
+        .await;
+
+        assert_eq!(made.load(Ordering::SeqCst), 20);
+        assert_eq!(
+            (report.requests, report.succeeded, report.failed),
+            (20, 15, 5)
+        );
+        assert_eq!(report.statuses, BTreeMap::from([(200, 15), (503, 5)]));
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].status, Some(503));
+        assert_eq!(report.errors[0].count, 5);
+        assert!(report.latency.min_ms >= 2.0);
+        assert!(report.latency.p50_ms <= report.latency.p99_ms);
+        assert!(report.throughput > 0.0);
+    }
+
+    #[tokio::test]
+    async fn stops_starting_calls_after_the_duration() {
+        let options = BenchOptions {
+            concurrency: 2,
+            duration: Some(Duration::from_millis(50)),
+            requests: None,
+        };
+
+        let report = run(&options, classify, || async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(None)
+        })
+        .await;
+
+        assert!(
+            report.requests >= 2 && report.requests <= 12,
+            "{:?}",
+            report
+        );
+        assert!(report.duration_ms < 200);
+        assert!(report.statuses.is_empty());
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let sorted: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let latency = Latency::of(&sorted);
+        assert_eq!(latency.p50_ms, 50.0);
+        assert_eq!(latency.p99_ms, 99.0);
+        assert_eq!(latency.max_ms, 100.0);
+        assert_eq!(latency.mean_ms, 50.5);
+        assert_eq!(Latency::of(&[]), Latency::default());
+    }
+}
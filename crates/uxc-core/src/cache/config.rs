@@ -0,0 +1,414 @@
+//! Cache configuration
+
+use crate::auth::secret::SecretSource;
+use crate::config::{Setting, Source};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Storage backend used for cached schemas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    /// One JSON file per schema under the cache location
+    #[default]
+    Files,
+    /// A single SQLite database (`cache.db`) in the cache location
+    Sqlite,
+}
+
+impl CacheBackend {
+    /// Parse a backend name as written in config.toml
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "files" | "file" => Some(Self::Files),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+}
+
+/// Cache configuration options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Whether caching is enabled
+    pub enabled: bool,
+
+    /// Time-to-live for cache entries in seconds; `cache.ttl` also takes
+    /// durations such as `"12h"`
+    pub ttl: u64,
+
+    /// Maximum cache size in bytes (0 = unlimited); `cache.max_bytes`, where
+    /// sizes such as `"500MB"` are accepted too
+    pub max_size: u64,
+
+    /// Maximum number of cache entries (0 = unlimited)
+    #[serde(default)]
+    pub max_entries: usize,
+
+    /// Cache directory path
+    pub location: PathBuf,
+
+    /// Storage backend for cache entries
+    #[serde(default)]
+    pub backend: CacheBackend,
+
+    /// Serve expired entries immediately and refresh them in the background
+    #[serde(default)]
+    pub stale_while_revalidate: bool,
+
+    /// Secret reference (`env:NAME`, `cmd:<command>` or literal) for encrypting entries
+    #[serde(default)]
+    pub encryption_key: Option<String>,
+
+    /// Schema snapshots kept per cache key (0 = unlimited)
+    #[serde(default = "default_max_snapshots")]
+    pub max_snapshots: usize,
+
+    /// Resolve schemas from the snapshots taken on or before this time (Unix
+    /// seconds) instead of the cache or the endpoint, for `--as-of`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<u64>,
+}
+
+fn default_max_snapshots() -> usize {
+    super::DEFAULT_MAX_SNAPSHOTS
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: super::DEFAULT_CACHE_TTL,
+            max_size: 0, // Unlimited
+            max_entries: 0,
+            location: dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(super::DEFAULT_CACHE_DIR),
+            backend: CacheBackend::Files,
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: super::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Create a new cache configuration with custom settings
+    #[allow(dead_code)]
+    pub fn new(enabled: bool, ttl: u64, max_size: u64, location: PathBuf) -> Self {
+        Self {
+            enabled,
+            ttl,
+            max_size,
+            max_entries: 0,
+            location,
+            backend: CacheBackend::Files,
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: super::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        }
+    }
+
+    /// Create configuration from cache options
+    #[allow(dead_code)]
+    pub fn from_options(options: CacheOptions) -> Self {
+        let mut config = Self::default();
+
+        if let Some(enabled) = options.enabled {
+            config.enabled = enabled;
+        }
+
+        if let Some(ttl) = options.ttl {
+            config.ttl = ttl;
+        }
+
+        if let Some(max_size) = options.max_size {
+            config.max_size = max_size;
+        }
+
+        if let Some(location) = options.location {
+            config.location = location;
+        }
+
+        config
+    }
+
+    /// Load the `cache.*` settings: the `[cache]` sections of
+    /// `~/.uxc/config.toml` and `.uxc.toml` and their `UXC_CACHE_*`
+    /// variables (see [`crate::config`]). Invalid values are ignored with a
+    /// warning, and unset ones keep their defaults.
+    pub fn load_from_file() -> Result<Self> {
+        Ok(Self::from_settings(|name| match crate::config::get(name) {
+            Ok(setting) => Some(setting).filter(|setting| setting.is_configured()),
+            Err(err) => {
+                tracing::warn!("Ignoring {}: {:#}", name, err);
+                None
+            }
+        }))
+    }
+
+    /// Configuration from the configured `cache.*` settings `setting` returns
+    pub fn from_settings(setting: impl Fn(&str) -> Option<Setting>) -> Self {
+        let mut config = Self::default();
+        if let Some(enabled) = setting("cache.enabled").and_then(|s| s.as_bool()) {
+            config.enabled = enabled;
+        }
+        if let Some(ttl) = setting("cache.ttl").and_then(|s| s.as_duration()) {
+            config.ttl = ttl.as_secs();
+        }
+        if let Some(max_bytes) = setting("cache.max_bytes")
+            .or_else(|| setting("cache.max_size"))
+            .and_then(|s| s.as_u64())
+        {
+            config.max_size = max_bytes;
+        }
+        if let Some(max_entries) = setting("cache.max_entries").and_then(|s| s.as_u64()) {
+            config.max_entries = max_entries as usize;
+        }
+        if let Some(max_snapshots) = setting("cache.max_snapshots").and_then(|s| s.as_u64()) {
+            config.max_snapshots = max_snapshots as usize;
+        }
+        if let Some(location) = setting("cache.location") {
+            if let Some(location) = location.as_str() {
+                config.location = expand_home(location);
+            }
+        }
+        if let Some(backend) = setting("cache.backend") {
+            if let Some(backend) = backend.as_str().and_then(CacheBackend::parse) {
+                config.backend = backend;
+            }
+        }
+        if let Some(swr) = setting("cache.stale_while_revalidate").and_then(|s| s.as_bool()) {
+            config.stale_while_revalidate = swr;
+        }
+        if let Some(key) = setting("cache.encryption_key") {
+            config.encryption_key = key
+                .as_str()
+                .filter(|reference| !reference.is_empty())
+                .filter(|reference| {
+                    let runs_command =
+                        matches!(SecretSource::parse(reference), SecretSource::Command(_));
+                    if runs_command && !may_run_commands(&key) {
+                        tracing::warn!(
+                            "Ignoring cache.encryption_key from {}: cmd: references are only \
+                             run from ~/.uxc/config.toml or the process environment",
+                            key.describe_source()
+                        );
+                        return false;
+                    }
+                    true
+                })
+                .map(ToString::to_string);
+        }
+        config
+    }
+
+    /// Whether entry count or size limits are configured
+    pub fn has_limits(&self) -> bool {
+        self.max_entries > 0 || self.max_size > 0
+    }
+
+    /// Whether `entries` entries totalling `bytes` exceed the configured limits
+    pub fn exceeds_limits(&self, entries: usize, bytes: u64) -> bool {
+        (self.max_entries > 0 && entries > self.max_entries)
+            || (self.max_size > 0 && bytes > self.max_size)
+    }
+
+    /// Path of the SQLite database used by the `sqlite` backend
+    pub fn sqlite_path(&self) -> PathBuf {
+        self.location.join(super::SQLITE_CACHE_FILE)
+    }
+
+    /// Ensure the cache directory exists
+    #[allow(dead_code)]
+    pub fn ensure_cache_dir(&self) -> Result<()> {
+        if !self.location.exists() {
+            fs::create_dir_all(&self.location).with_context(|| {
+                format!("Failed to create cache directory: {:?}", self.location)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a `cmd:` reference in `setting` may run: values of
+/// `~/.uxc/config.toml` and of variables set in the process environment do,
+/// `.env` entries and project files, which come with a checkout, do not
+fn may_run_commands(setting: &Setting) -> bool {
+    match setting.source {
+        Source::User => true,
+        Source::Env => setting
+            .origin
+            .as_deref()
+            .is_some_and(|var| std::env::var_os(var).is_some()),
+        Source::Project | Source::Default => false,
+    }
+}
+
+/// Runtime cache options that can override configuration
+///
+/// These are typically set via CLI flags like --no-cache or --cache-ttl.
+#[derive(Debug, Clone, Default)]
+#[allow(dead_code)]
+pub struct CacheOptions {
+    /// Override the enabled setting
+    pub enabled: Option<bool>,
+
+    /// Override the TTL setting
+    pub ttl: Option<u64>,
+
+    /// Override the max_size setting
+    pub max_size: Option<u64>,
+
+    /// Override the location setting
+    pub location: Option<PathBuf>,
+}
+
+impl CacheOptions {
+    /// Create new cache options
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the enabled flag
+    #[allow(dead_code)]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// Set the TTL
+    #[allow(dead_code)]
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set the max size
+    #[allow(dead_code)]
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Set the location
+    pub fn with_location(mut self, location: PathBuf) -> Self {
+        self.location = Some(location);
+        self
+    }
+}
+
+/// `path` with a leading `~/` replaced by the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Helper functions for directory resolution
+mod dirs {
+    use std::path::PathBuf;
+
+    /// Get the home directory
+    pub fn home_dir() -> Option<PathBuf> {
+        if let Some(home) = std::env::var_os("HOME") {
+            return Some(PathBuf::from(home));
+        }
+
+        #[cfg(windows)]
+        {
+            if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+                return Some(PathBuf::from(user_profile));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = CacheConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.ttl, 86400);
+        assert_eq!(config.max_size, 0);
+    }
+
+    #[test]
+    fn test_config_from_options() {
+        let options = CacheOptions::new().with_enabled(false).with_ttl(3600);
+
+        let config = CacheConfig::from_options(options);
+        assert!(!config.enabled);
+        assert_eq!(config.ttl, 3600);
+    }
+
+    #[test]
+    fn settings_fill_in_the_config() {
+        let settings = [
+            ("cache.backend", serde_json::json!("sqlite")),
+            ("cache.location", serde_json::json!("/tmp/uxc cache")),
+            ("cache.max_size", serde_json::json!(1024)),
+            ("cache.ttl", serde_json::json!(1.5)),
+        ];
+        let config = CacheConfig::from_settings(|name| {
+            let (key, value) = settings.iter().find(|(key, _)| *key == name)?;
+            Some(Setting {
+                key: key.to_string(),
+                value: Some(value.clone()),
+                source: crate::config::Source::User,
+                origin: None,
+            })
+        });
+        assert_eq!(config.backend, CacheBackend::Sqlite);
+        assert_eq!(config.location, PathBuf::from("/tmp/uxc cache"));
+        assert_eq!(config.max_size, 1024);
+        assert_eq!(config.ttl, 1);
+        assert!(config.enabled);
+    }
+
+    #[test]
+    fn command_keys_only_come_from_the_user_file_or_process_environment() {
+        let key = |source, origin: &str| {
+            CacheConfig::from_settings(|name| {
+                (name == "cache.encryption_key").then(|| Setting {
+                    key: name.to_string(),
+                    value: Some(serde_json::json!("cmd:echo key")),
+                    source,
+                    origin: Some(origin.to_string()),
+                })
+            })
+            .encryption_key
+        };
+        assert_eq!(
+            key(Source::User, "/home/me/.uxc/config.toml"),
+            Some("cmd:echo key".to_string())
+        );
+        assert_eq!(key(Source::Project, "/repo/.uxc.toml"), None);
+        // Not in the process environment, so it came from a `.env` file
+        assert_eq!(key(Source::Env, "UXC_TEST_UNSET_CACHE_KEY"), None);
+        assert_eq!(key(Source::Env, "PATH"), Some("cmd:echo key".to_string()));
+    }
+
+    #[test]
+    fn test_cache_options_builder() {
+        let options = CacheOptions::new()
+            .with_enabled(true)
+            .with_ttl(7200)
+            .with_max_size(1024);
+
+        assert_eq!(options.enabled, Some(true));
+        assert_eq!(options.ttl, Some(7200));
+        assert_eq!(options.max_size, Some(1024));
+    }
+}
@@ -0,0 +1,244 @@
+//! Cache encryption at rest
+//!
+//! With `encryption_key` set in the `[cache]` section of `~/.uxc/config.toml`,
+//! entries are sealed with AES-256-GCM before they are written and opened
+//! again on read. The key is a secret reference resolved like profile values
+//! (`env:NAME`, `cmd:<command>` or a literal, see [`crate::auth::secret`]);
+//! `cmd:` keys of `.env` files and project files are dropped before they get
+//! here (see [`crate::cache::CacheConfig::from_settings`]).
+//! The 256-bit cipher key is derived from it with Argon2id and a random salt
+//! kept in [`SALT_FILE`] next to the entries, so a stolen cache cannot be
+//! attacked with precomputed tables and every guess is slow. A long random
+//! value is still recommended.
+//!
+//! Sealed data starts with [`SEALED_MAGIC`], followed by a random 96-bit nonce
+//! and the ciphertext. Entry metadata (URLs, timestamps, validators) is not
+//! encrypted. Plain entries written before encryption was turned on are still
+//! read, and sealed when they are next stored; `uxc cache clear` removes them.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use std::fs;
+use std::path::Path;
+
+/// Prefix of sealed entries
+pub const SEALED_MAGIC: &[u8] = b"UXCENC1";
+
+/// Prefix of sealed entries stored as text (SQLite backend)
+const SEALED_TEXT_PREFIX: &str = "uxc-enc:v1:";
+
+/// File in the cache location holding the key derivation salt
+pub const SALT_FILE: &str = "encryption.salt";
+
+const SALT_LEN: usize = 16;
+
+const NONCE_LEN: usize = 12;
+
+/// Cipher for cache entries
+#[derive(Clone)]
+pub struct CacheCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for CacheCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CacheCipher")
+    }
+}
+
+impl CacheCipher {
+    /// Derive a cipher from a resolved key and the salt of a cache
+    pub fn new(secret: &str, salt: &[u8]) -> Result<Self> {
+        if secret.is_empty() {
+            bail!("Cache encryption key is empty");
+        }
+        let mut key = [0u8; 32];
+        argon2::Argon2::default()
+            .hash_password_into(secret.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow!("Failed to derive cache encryption key: {}", e))?;
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        })
+    }
+
+    /// Cipher for a configured `encryption_key` reference of the cache at
+    /// `location`, if any
+    pub fn from_reference(reference: Option<&str>, location: &Path) -> Result<Option<Self>> {
+        let Some(reference) = reference.map(str::trim).filter(|r| !r.is_empty()) else {
+            return Ok(None);
+        };
+        let secret = crate::auth::secret::resolve_secret(reference)
+            .context("Failed to resolve cache encryption_key")?;
+        Self::new(&secret, &load_salt(location)?).map(Some)
+    }
+
+    /// Encrypt `plaintext` under a fresh nonce
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::getrandom(&mut nonce)
+            .map_err(|e| anyhow!("Failed to generate cache nonce: {}", e))?;
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| anyhow!("Failed to encrypt cache entry"))?;
+
+        let mut sealed = Vec::with_capacity(SEALED_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(SEALED_MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypt data produced by [`CacheCipher::seal`]
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        let body = sealed
+            .strip_prefix(SEALED_MAGIC)
+            .filter(|body| body.len() > NONCE_LEN)
+            .ok_or_else(|| anyhow!("Cache entry is not encrypted"))?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Failed to decrypt cache entry (wrong encryption_key?)"))
+    }
+
+    /// Encrypt text for a text column
+    pub fn seal_text(&self, plaintext: &str) -> Result<String> {
+        let sealed = self.seal(plaintext.as_bytes())?;
+        Ok(format!(
+            "{}{}",
+            SEALED_TEXT_PREFIX,
+            base64::engine::general_purpose::STANDARD.encode(sealed)
+        ))
+    }
+
+    /// Decrypt text produced by [`CacheCipher::seal_text`]
+    pub fn open_text(&self, sealed: &str) -> Result<String> {
+        let encoded = sealed
+            .strip_prefix(SEALED_TEXT_PREFIX)
+            .ok_or_else(|| anyhow!("Cache entry is not encrypted"))?;
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .context("Failed to decode encrypted cache entry")?;
+        String::from_utf8(self.open(&sealed)?).context("Encrypted cache entry is not UTF-8")
+    }
+}
+
+/// The salt of the cache at `location`, created on first use
+fn load_salt(location: &Path) -> Result<Vec<u8>> {
+    let path = location.join(SALT_FILE);
+    match fs::read(&path) {
+        Ok(salt) if salt.len() >= SALT_LEN => return Ok(salt),
+        Ok(_) => bail!("Cache encryption salt is truncated: {:?}", path),
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(e).with_context(|| format!("Failed to read {:?}", path));
+        }
+        Err(_) => {}
+    }
+
+    let mut salt = vec![0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt)
+        .map_err(|e| anyhow!("Failed to generate cache encryption salt: {}", e))?;
+    fs::create_dir_all(location)
+        .with_context(|| format!("Failed to create cache directory: {:?}", location))?;
+    fs::write(&path, &salt).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(salt)
+}
+
+/// Whether `data` was sealed by a [`CacheCipher`]
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(SEALED_MAGIC)
+}
+
+/// Whether text was sealed by [`CacheCipher::seal_text`]
+pub fn is_sealed_text(data: &str) -> bool {
+    data.starts_with(SEALED_TEXT_PREFIX)
+}
+
+/// Open `data` when it is sealed, or pass it through unchanged
+pub fn open_if_sealed(cipher: Option<&CacheCipher>, data: Vec<u8>) -> Result<Vec<u8>> {
+    if !is_sealed(&data) {
+        return Ok(data);
+    }
+    match cipher {
+        Some(cipher) => cipher.open(&data),
+        None => bail!("Cache entry is encrypted but no encryption_key is configured"),
+    }
+}
+
+/// Text counterpart of [`open_if_sealed`]
+pub fn open_text_if_sealed(cipher: Option<&CacheCipher>, data: String) -> Result<String> {
+    if !is_sealed_text(&data) {
+        return Ok(data);
+    }
+    match cipher {
+        Some(cipher) => cipher.open_text(&data),
+        None => bail!("Cache entry is encrypted but no encryption_key is configured"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SALT: &[u8] = b"0123456789abcdef";
+
+    #[test]
+    fn seal_round_trips_and_rejects_other_keys() {
+        let cipher = CacheCipher::new("correct horse battery staple", SALT).unwrap();
+        let sealed = cipher.seal(br#"{"openapi":"3.0"}"#).unwrap();
+
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.windows(7).any(|w| w == b"openapi"));
+        assert_eq!(cipher.open(&sealed).unwrap(), br#"{"openapi":"3.0"}"#);
+        assert_ne!(cipher.seal(b"same").unwrap(), cipher.seal(b"same").unwrap());
+
+        let other = CacheCipher::new("another key", SALT).unwrap();
+        assert!(other.open(&sealed).is_err());
+        assert!(open_if_sealed(None, sealed).is_err());
+        assert_eq!(open_if_sealed(None, b"plain".to_vec()).unwrap(), b"plain");
+    }
+
+    #[test]
+    fn text_sealing_round_trips() {
+        let cipher = CacheCipher::new("text key", SALT).unwrap();
+        let sealed = cipher.seal_text(r#"{"a":1}"#).unwrap();
+
+        assert!(is_sealed_text(&sealed));
+        assert_eq!(
+            open_text_if_sealed(Some(&cipher), sealed).unwrap(),
+            r#"{"a":1}"#
+        );
+        assert_eq!(
+            open_text_if_sealed(Some(&cipher), "{}".to_string()).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn each_cache_derives_its_key_with_its_own_salt() {
+        let (first, second) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let cipher = CacheCipher::from_reference(Some("shared key"), first.path())
+            .unwrap()
+            .unwrap();
+        let salt = fs::read(first.path().join(SALT_FILE)).unwrap();
+        assert_eq!(salt.len(), SALT_LEN);
+
+        let sealed = cipher.seal(b"schema").unwrap();
+        let reopened = CacheCipher::from_reference(Some("shared key"), first.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(reopened.open(&sealed).unwrap(), b"schema");
+        assert_eq!(fs::read(first.path().join(SALT_FILE)).unwrap(), salt);
+
+        let elsewhere = CacheCipher::from_reference(Some("shared key"), second.path())
+            .unwrap()
+            .unwrap();
+        assert!(elsewhere.open(&sealed).is_err());
+        assert!(CacheCipher::from_reference(None, second.path())
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,133 @@
+//! Process-level in-memory tier in front of the filesystem cache
+//!
+//! Entries are keyed by their cache file path, so caches with different
+//! locations never share entries. Repeated lookups within one process skip
+//! the disk read and JSON parse of large schemas.
+
+use super::storage::CacheEntry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Maximum number of schemas kept in memory
+pub const DEFAULT_MEMORY_CAPACITY: usize = 16;
+
+/// Least-recently-used map of cache entries
+pub(super) struct MemoryTier {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<PathBuf, (u64, CacheEntry)>,
+}
+
+impl MemoryTier {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Get an entry and mark it as most recently used
+    pub(super) fn get(&mut self, path: &Path) -> Option<CacheEntry> {
+        let tick = self.next_tick();
+        let (last_used, entry) = self.entries.get_mut(path)?;
+        *last_used = tick;
+        Some(entry.clone())
+    }
+
+    /// Insert an entry, evicting the least recently used one when full
+    pub(super) fn insert(&mut self, path: PathBuf, entry: CacheEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let tick = self.next_tick();
+        self.entries.insert(path, (tick, entry));
+
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    pub(super) fn remove(&mut self, path: &Path) {
+        self.entries.remove(path);
+    }
+
+    /// Remove every entry stored under `dir`
+    pub(super) fn remove_dir(&mut self, dir: &Path) {
+        self.entries.retain(|path, _| !path.starts_with(dir));
+    }
+
+    /// Number of entries stored under `dir`
+    pub(super) fn len_in(&self, dir: &Path) -> usize {
+        self.entries
+            .keys()
+            .filter(|path| path.starts_with(dir))
+            .count()
+    }
+}
+
+/// Forget every entry of this process, so the next lookups read the disk
+pub fn forget() {
+    if let Ok(mut tier) = shared().lock() {
+        tier.entries.clear();
+    }
+}
+
+/// The tier shared by every cache in this process
+pub(super) fn shared() -> &'static Mutex<MemoryTier> {
+    static TIER: OnceLock<Mutex<MemoryTier>> = OnceLock::new();
+    TIER.get_or_init(|| Mutex::new(MemoryTier::new(DEFAULT_MEMORY_CAPACITY)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str) -> CacheEntry {
+        CacheEntry::new(
+            serde_json::json!({ "title": title }),
+            3600,
+            "openapi".to_string(),
+        )
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut tier = MemoryTier::new(2);
+        tier.insert(PathBuf::from("/c/a.json"), entry("a"));
+        tier.insert(PathBuf::from("/c/b.json"), entry("b"));
+
+        // Touch `a` so `b` becomes the eviction candidate
+        assert!(tier.get(Path::new("/c/a.json")).is_some());
+        tier.insert(PathBuf::from("/c/c.json"), entry("c"));
+
+        assert!(tier.get(Path::new("/c/a.json")).is_some());
+        assert!(tier.get(Path::new("/c/b.json")).is_none());
+        assert!(tier.get(Path::new("/c/c.json")).is_some());
+    }
+
+    #[test]
+    fn removes_by_directory() {
+        let mut tier = MemoryTier::new(4);
+        tier.insert(PathBuf::from("/one/a.json"), entry("a"));
+        tier.insert(PathBuf::from("/two/b.json"), entry("b"));
+
+        tier.remove_dir(Path::new("/one"));
+        assert_eq!(tier.len_in(Path::new("/one")), 0);
+        assert_eq!(tier.len_in(Path::new("/two")), 1);
+    }
+}
@@ -0,0 +1,253 @@
+//! Schema caching module for improved performance
+//!
+//! Provides filesystem-based caching for schemas across all protocols (OpenAPI, gRPC, GraphQL, MCP).
+//! Cache is stored in ~/.uxc/cache/schemas/ with TTL-based expiration, behind a
+//! process-level in-memory LRU tier. Setting `backend = "sqlite"` keeps all
+//! entries in a single SQLite database instead of one JSON file per schema.
+//! With `stale_while_revalidate` enabled, expired schemas are served at once
+//! while a background task refreshes them. In offline mode adapters serve
+//! schemas from the cache only. An `encryption_key` encrypts entries at rest.
+//! New schema versions are also kept as dated snapshots, which `--as-of`
+//! ([`CacheConfig::as_of`]) resolves schemas from.
+
+mod config;
+mod encryption;
+mod memory;
+mod offline;
+mod revalidate;
+mod snapshot;
+mod sqlite;
+mod stats;
+mod storage;
+
+#[allow(unused_imports)]
+pub use config::CacheOptions;
+pub use config::{CacheBackend, CacheConfig};
+pub use encryption::CacheCipher;
+pub use memory::{forget as forget_memory, DEFAULT_MEMORY_CAPACITY};
+pub use offline::{
+    cached_schema, is_offline, offline_env_enabled, offline_miss, set_offline, OFFLINE_ENV,
+};
+pub use revalidate::{
+    mark_served_stale, served_stale, spawn_refresh, wait_for_refreshes, REFRESH_GRACE_PERIOD,
+};
+pub use snapshot::{parse_as_of, snapshot_missing, SnapshotStore, DEFAULT_MAX_SNAPSHOTS};
+pub use sqlite::SqliteCache;
+pub use stats::CacheStats;
+#[allow(unused_imports)]
+pub use storage::CacheStorage;
+pub use storage::{CacheEntry, SchemaCache};
+
+use crate::adapters::ProtocolType;
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Default cache TTL in seconds (24 hours)
+pub const DEFAULT_CACHE_TTL: u64 = 86400;
+
+/// Default cache directory relative to home directory
+pub const DEFAULT_CACHE_DIR: &str = ".uxc/cache/schemas";
+
+/// Database file name used by the SQLite backend, inside the cache location
+pub const SQLITE_CACHE_FILE: &str = "cache.db";
+
+/// Marker of the cache keys holding protocol detection results
+pub const DETECTION_KEY_MARKER: &str = "#detection=";
+
+/// [`CacheEntry::protocol`] of protocol detection results
+pub const DETECTION_PROTOCOL: &str = "detection";
+
+/// HTTP validators used to revalidate an expired schema with a conditional GET
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    /// `ETag` response header, sent back as `If-None-Match`
+    pub etag: Option<String>,
+    /// `Last-Modified` response header, sent back as `If-Modified-Since`
+    pub last_modified: Option<String>,
+}
+
+/// Cache result indicating whether the value was retrieved from cache
+#[derive(Debug, Clone)]
+pub enum CacheResult {
+    /// Value was retrieved from cache
+    Hit(Value),
+    /// Value was not in cache or was expired
+    Miss,
+    /// Cache was bypassed (e.g., --no-cache flag)
+    Bypassed,
+}
+
+impl CacheResult {
+    #[allow(dead_code)]
+    pub fn is_hit(&self) -> bool {
+        matches!(self, CacheResult::Hit(_))
+    }
+
+    #[allow(dead_code)]
+    pub fn is_miss(&self) -> bool {
+        matches!(self, CacheResult::Miss)
+    }
+
+    #[allow(dead_code)]
+    pub fn is_bypassed(&self) -> bool {
+        matches!(self, CacheResult::Bypassed)
+    }
+}
+
+/// The `--as-of` time of `cache`, if any (see [`CacheConfig::as_of`])
+pub fn as_of(cache: Option<&Arc<dyn Cache>>) -> Option<u64> {
+    cache.and_then(|cache| cache.as_of())
+}
+
+/// Whether adapters resolve schemas from local stores only: the cache in
+/// offline mode, snapshots when `cache` is set to an `--as-of` time
+pub fn schemas_from_store(cache: Option<&Arc<dyn Cache>>) -> bool {
+    is_offline() || as_of(cache).is_some()
+}
+
+/// Error for a schema missing from the store [`schemas_from_store`] reads
+pub fn stored_schema_miss(
+    cache: Option<&Arc<dyn Cache>>,
+    what: impl std::fmt::Display,
+) -> anyhow::Error {
+    match as_of(cache) {
+        Some(at) => snapshot_missing(at, format!("the {}", what)),
+        None => offline_miss(format!("no cached {}", what)),
+    }
+}
+
+/// Record a schema cache lookup for `--explain` and instrumentation
+pub fn record_lookup(protocol: ProtocolType, key: &str, result: &CacheResult) {
+    let (name, outcome) = match result {
+        CacheResult::Hit(_) => ("hit", "hit: cached schema used"),
+        CacheResult::Miss => ("miss", "miss: fetching from the endpoint"),
+        CacheResult::Bypassed => ("bypassed", "bypassed: caching is disabled"),
+    };
+    crate::instrumentation::cache_lookup(key, protocol, name);
+    let protocol = match protocol {
+        ProtocolType::OpenAPI => "OpenAPI",
+        ProtocolType::GRpc => "gRPC",
+        ProtocolType::JsonRpc => "JSON-RPC",
+        ProtocolType::Mcp => "MCP",
+        ProtocolType::GraphQL => "GraphQL",
+        ProtocolType::Plugin => "plugin",
+    };
+    crate::explain::record(
+        "cache",
+        format!("{} schema cache {} ({})", protocol, outcome, key),
+    );
+}
+
+/// Main cache interface for adapters
+///
+/// This trait provides a simple interface for protocol adapters to interact
+/// with the cache system.
+pub trait Cache: Send + Sync {
+    /// Get a schema from cache
+    ///
+    /// Returns `CacheResult::Hit` if the schema is found and valid,
+    /// `CacheResult::Miss` if not found or expired, or `CacheResult::Bypassed`
+    /// if caching is disabled.
+    fn get(&self, url: &str) -> Result<CacheResult>;
+
+    /// Get the stored entry for a URL even if it has expired
+    ///
+    /// Used to revalidate with the stored validators and as a fallback when
+    /// the remote cannot be reached. Returns `None` when caching is disabled.
+    fn get_stale(&self, url: &str) -> Result<Option<CacheEntry>>;
+
+    /// Put a schema into cache
+    ///
+    /// Stores the schema with metadata including timestamp and TTL.
+    /// If caching is disabled, this is a no-op.
+    fn put(&self, url: &str, schema: &Value) -> Result<()>;
+
+    /// Put a schema into cache along with its HTTP validators
+    fn put_with_validators(
+        &self,
+        url: &str,
+        schema: &Value,
+        validators: &CacheValidators,
+    ) -> Result<()>;
+
+    /// Restart the TTL of an existing entry after a `304 Not Modified`
+    fn refresh(&self, url: &str) -> Result<()>;
+
+    /// Invalidate a specific cache entry
+    fn invalidate(&self, url: &str) -> Result<()>;
+
+    /// Clear all cache entries
+    fn clear(&self) -> Result<()>;
+
+    /// Remove the entries whose [`CacheEntry::protocol`] is `protocol`, such
+    /// as [`DETECTION_PROTOCOL`]
+    ///
+    /// Returns the number of entries removed.
+    fn clear_protocol(&self, protocol: &str) -> Result<usize>;
+
+    /// Drop expired entries and evict least-recently-used ones over the limits
+    ///
+    /// Returns the number of entries removed.
+    fn compact(&self) -> Result<usize>;
+
+    /// Get cache statistics
+    fn stats(&self) -> Result<CacheStats>;
+
+    /// Check if caching is enabled
+    #[allow(dead_code)]
+    fn is_enabled(&self) -> bool;
+
+    /// Whether expired entries should be served while they are refreshed
+    fn stale_while_revalidate(&self) -> bool;
+
+    /// Dated copies of the schemas this cache stored, for `--as-of`
+    fn snapshots(&self) -> Option<&SnapshotStore> {
+        None
+    }
+
+    /// Time schemas are resolved from snapshots as of, if any
+    fn as_of(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Create a new schema cache instance with the given configuration
+///
+/// While a cassette is recorded or replayed, schemas also go to or come from
+/// the cassette (see [`crate::cassette`]).
+pub fn create_cache(config: CacheConfig) -> Result<Arc<dyn Cache>> {
+    let cache: Arc<dyn Cache> = match config.backend {
+        CacheBackend::Files => Arc::new(SchemaCache::new(config)?),
+        CacheBackend::Sqlite => Arc::new(SqliteCache::new(config)?),
+    };
+    Ok(crate::cassette::wrap_cache(cache))
+}
+
+/// Create a cache with default settings
+pub fn create_default_cache() -> Result<Arc<dyn Cache>> {
+    Ok(Arc::new(SchemaCache::with_default_config()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_result() {
+        let hit = CacheResult::Hit(serde_json::json!( {"test": "data"} ));
+        assert!(hit.is_hit());
+        assert!(!hit.is_miss());
+        assert!(!hit.is_bypassed());
+
+        let miss = CacheResult::Miss;
+        assert!(!miss.is_hit());
+        assert!(miss.is_miss());
+        assert!(!miss.is_bypassed());
+
+        let bypassed = CacheResult::Bypassed;
+        assert!(!bypassed.is_hit());
+        assert!(!bypassed.is_miss());
+        assert!(bypassed.is_bypassed());
+    }
+}
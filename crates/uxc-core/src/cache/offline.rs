@@ -0,0 +1,76 @@
+//! Offline mode
+//!
+//! With `--offline` (or `UXC_OFFLINE=1`) adapters never touch the network:
+//! schemas are served from the cache only, expired entries included, and
+//! anything that is not cached fails with [`UxcError::OfflineMiss`].
+
+use super::{Cache, CacheResult};
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Environment variable that enables offline mode
+pub const OFFLINE_ENV: &str = "UXC_OFFLINE";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable offline mode for this process
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether network access is disabled for this process
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Whether an `UXC_OFFLINE` value turns offline mode on (`1`, `true`, `yes`, `on`)
+pub fn offline_env_enabled(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// Error for data that would have to be fetched while offline
+pub fn offline_miss(what: impl std::fmt::Display) -> anyhow::Error {
+    UxcError::OfflineMiss(what.to_string()).into()
+}
+
+/// Cached schema for `key`, fresh or expired, if `accepts` recognises it.
+/// With `--as-of` the snapshot of that time is used instead.
+///
+/// `accepts` guards against entries another protocol stored under the same
+/// URL.
+pub fn cached_schema(
+    cache: Option<&Arc<dyn Cache>>,
+    key: &str,
+    accepts: impl Fn(&Value) -> bool,
+) -> Result<Value> {
+    if let Some(at) = super::as_of(cache) {
+        return match cache.and_then(|cache| cache.snapshots()) {
+            Some(snapshots) => snapshots.schema_at(key, at, accepts),
+            None => Err(super::snapshot_missing(at, key)),
+        };
+    }
+
+    let Some(cache) = cache.filter(|cache| cache.is_enabled()) else {
+        return Err(offline_miss(format!(
+            "no schema cache available for {} (offline mode cannot be combined with --no-cache)",
+            key
+        )));
+    };
+
+    let schema = match cache.get(key)? {
+        CacheResult::Hit(schema) => Some(schema),
+        CacheResult::Miss | CacheResult::Bypassed => {
+            cache.get_stale(key)?.map(|entry| entry.schema)
+        }
+    };
+    match schema {
+        Some(schema) if accepts(&schema) => Ok(schema),
+        _ => Err(offline_miss(format!("no cached schema for {}", key))),
+    }
+}
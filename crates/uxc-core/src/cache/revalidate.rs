@@ -0,0 +1,63 @@
+//! Background refreshes for stale-while-revalidate
+//!
+//! Adapters that serve an expired schema hand the refresh to [`spawn_refresh`]
+//! and record it with [`mark_served_stale`]. The CLI prints its output first and
+//! then gives pending refreshes [`REFRESH_GRACE_PERIOD`] to finish before exiting.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// How long the CLI waits for background refreshes after printing output
+pub const REFRESH_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+static SERVED_STALE: AtomicBool = AtomicBool::new(false);
+
+fn pending() -> &'static Mutex<Vec<JoinHandle<()>>> {
+    static PENDING: OnceLock<Mutex<Vec<JoinHandle<()>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Record that an expired schema was served in this process
+pub fn mark_served_stale() {
+    SERVED_STALE.store(true, Ordering::Relaxed);
+}
+
+/// Whether an expired schema has been served in this process
+pub fn served_stale() -> bool {
+    SERVED_STALE.load(Ordering::Relaxed)
+}
+
+/// Run a cache refresh in the background
+pub fn spawn_refresh<F>(refresh: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let handle = tokio::spawn(refresh);
+    if let Ok(mut pending) = pending().lock() {
+        pending.push(handle);
+    }
+}
+
+/// Wait for pending background refreshes, up to `timeout`
+pub async fn wait_for_refreshes(timeout: Duration) {
+    let handles = match pending().lock() {
+        Ok(mut pending) => std::mem::take(&mut *pending),
+        Err(_) => return,
+    };
+    if handles.is_empty() {
+        return;
+    }
+
+    let all = async {
+        for handle in handles {
+            let _ = handle.await;
+        }
+    };
+    if tokio::time::timeout(timeout, all).await.is_err() {
+        debug!("Background cache refresh did not finish in {:?}", timeout);
+    }
+}
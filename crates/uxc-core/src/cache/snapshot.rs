@@ -0,0 +1,394 @@
+//! Schema snapshots for `--as-of`
+//!
+//! Whenever a schema is stored in the cache and differs from the previous
+//! version, a dated copy is kept under `snapshots/` in the cache location (one
+//! directory per cache key). Snapshots do not expire; each key keeps its
+//! newest `max_snapshots` (see [`CacheConfig`]), and `uxc cache clear` removes
+//! them with the entries. With an `encryption_key` they are sealed like cache
+//! entries.
+//!
+//! With `--as-of <date>` ([`CacheConfig::as_of`]) adapters resolve schemas
+//! from the newest snapshot taken on or before that date instead of the cache
+//! or the endpoint, so `list`, `describe` and calls see the API as it was
+//! then. Calls themselves still go to the live endpoint; anything without a
+//! snapshot that old fails with [`UxcError::SnapshotNotFound`].
+
+use super::encryption::{self, CacheCipher};
+use super::CacheConfig;
+use crate::error::UxcError;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory of the snapshot store, inside the cache location
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Snapshots kept per cache key by default
+pub const DEFAULT_MAX_SNAPSHOTS: usize = 20;
+
+/// Parse an `--as-of` value: a date (`2024-06-01`, the end of that day in UTC)
+/// or an RFC 3339 time (`2024-06-01T12:00:00Z`)
+pub fn parse_as_of(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let at = if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        date.and_hms_opt(23, 59, 59)
+            .map(|end| end.and_utc().timestamp())
+    } else {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|time| time.timestamp())
+    };
+    match at {
+        Some(at) if at > 0 => Ok(at as u64),
+        _ => Err(UxcError::InvalidArguments(format!(
+            "Invalid --as-of '{}': expected a date (2024-06-01) or an RFC 3339 time",
+            value
+        ))
+        .into()),
+    }
+}
+
+/// `at` as shown in snapshot errors
+fn format_time(at: u64) -> String {
+    chrono::DateTime::from_timestamp(at as i64, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| at.to_string())
+}
+
+/// Error for a schema without a snapshot taken on or before `at`
+pub fn snapshot_missing(at: u64, what: impl std::fmt::Display) -> anyhow::Error {
+    UxcError::SnapshotNotFound(format!(
+        "no snapshot of {} taken on or before {}",
+        what,
+        format_time(at)
+    ))
+    .into()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    key: String,
+    captured_at: u64,
+    schema: Value,
+}
+
+/// Dated schema copies, one directory per cache key
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+    cipher: Option<CacheCipher>,
+    /// Snapshots kept per key (0 = unlimited)
+    max_per_key: usize,
+}
+
+impl SnapshotStore {
+    /// Store at `dir` keeping every snapshot in plain text
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            cipher: None,
+            max_per_key: 0,
+        }
+    }
+
+    /// Store of a cache, sealing snapshots with the cache's cipher
+    pub fn for_config(config: &CacheConfig, cipher: Option<CacheCipher>) -> Self {
+        Self {
+            dir: config.location.join(SNAPSHOT_DIR),
+            cipher,
+            max_per_key: config.max_snapshots,
+        }
+    }
+
+    fn key_dir(&self, key: &str) -> PathBuf {
+        let digest = Sha256::digest(key.as_bytes());
+        let name = digest[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        self.dir.join(name)
+    }
+
+    /// Directories of the keys with snapshots
+    fn key_dirs(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_dir())
+            .collect()
+    }
+
+    /// Capture times of the snapshots in `dir`, oldest first
+    fn times(dir: &Path) -> Vec<u64> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut times = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                name.to_str()?.strip_suffix(".json")?.parse().ok()
+            })
+            .collect::<Vec<u64>>();
+        times.sort_unstable();
+        times
+    }
+
+    fn path(dir: &Path, at: u64) -> PathBuf {
+        dir.join(format!("{}.json", at))
+    }
+
+    fn load(&self, dir: &Path, at: u64) -> Result<Snapshot> {
+        let path = Self::path(dir, at);
+        let data =
+            fs::read(&path).with_context(|| format!("Failed to read snapshot: {:?}", path))?;
+        let data = encryption::open_if_sealed(self.cipher.as_ref(), data)
+            .with_context(|| format!("Failed to open snapshot: {:?}", path))?;
+        serde_json::from_slice(&data).with_context(|| format!("Corrupt snapshot: {:?}", path))
+    }
+
+    /// Number of snapshots and the bytes they take on disk
+    pub fn usage(&self) -> (usize, u64) {
+        self.key_dirs()
+            .iter()
+            .flat_map(|dir| Self::times(dir).into_iter().map(|at| Self::path(dir, at)))
+            .fold((0, 0), |(count, bytes), path| {
+                let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+                (count + 1, bytes + size)
+            })
+    }
+
+    /// Remove every snapshot; returns how many there were
+    pub fn clear(&self) -> Result<usize> {
+        let (count, _) = self.usage();
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)
+                .with_context(|| format!("Failed to remove snapshots: {:?}", self.dir))?;
+        }
+        Ok(count)
+    }
+
+    /// When the oldest snapshot of any key starting with `prefix` was taken
+    pub fn earliest(&self, prefix: &str) -> Option<String> {
+        let dirs = fs::read_dir(&self.dir).ok()?;
+        dirs.filter_map(|entry| {
+            let dir = entry.ok()?.path();
+            let first = *Self::times(&dir).first()?;
+            let snapshot = self.load(&dir, first).ok()?;
+            snapshot.key.starts_with(prefix).then_some(first)
+        })
+        .min()
+        .map(format_time)
+    }
+
+    /// Keep `schema` as a snapshot of `key` unless it equals the latest one,
+    /// then drop the oldest snapshots over the limit
+    pub fn record(&self, key: &str, schema: &Value) -> Result<()> {
+        let dir = self.key_dir(key);
+        if let Some(latest) = Self::times(&dir).last() {
+            if self
+                .load(&dir, *latest)
+                .is_ok_and(|snapshot| &snapshot.schema == schema)
+            {
+                return Ok(());
+            }
+        }
+
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create snapshot directory: {:?}", dir))?;
+        let captured_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let snapshot = Snapshot {
+            key: key.to_string(),
+            captured_at,
+            schema: schema.clone(),
+        };
+        let mut data = serde_json::to_vec(&snapshot)?;
+        if let Some(cipher) = &self.cipher {
+            data = cipher.seal(&data)?;
+        }
+        let path = Self::path(&dir, captured_at);
+        fs::write(&path, data).with_context(|| format!("Failed to write snapshot: {:?}", path))?;
+        self.prune(&dir)
+    }
+
+    /// Remove the oldest snapshots in `dir` beyond the per-key limit
+    fn prune(&self, dir: &Path) -> Result<()> {
+        let times = Self::times(dir);
+        if self.max_per_key == 0 || times.len() <= self.max_per_key {
+            return Ok(());
+        }
+        for at in &times[..times.len() - self.max_per_key] {
+            let path = Self::path(dir, *at);
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove snapshot: {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// The schema `key` had at `at`: its newest snapshot taken no later, if
+    /// `accepts` recognises it
+    pub fn schema_at(&self, key: &str, at: u64, accepts: impl Fn(&Value) -> bool) -> Result<Value> {
+        let dir = self.key_dir(key);
+        let times = Self::times(&dir);
+        let Some(taken) = times.iter().rev().find(|taken| **taken <= at) else {
+            return Err(match times.first() {
+                Some(first) => snapshot_missing(
+                    at,
+                    format_args!("{} (the earliest is from {})", key, format_time(*first)),
+                ),
+                None => snapshot_missing(at, key),
+            });
+        };
+
+        let snapshot = self.load(&dir, *taken)?;
+        if !accepts(&snapshot.schema) {
+            return Err(snapshot_missing(at, key));
+        }
+        crate::explain::record(
+            "cache",
+            format!(
+                "schema snapshot from {} used for --as-of ({})",
+                format_time(snapshot.captured_at),
+                key
+            ),
+        );
+        Ok(snapshot.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn write(store: &SnapshotStore, key: &str, at: u64, schema: Value) {
+        let dir = store.key_dir(key);
+        fs::create_dir_all(&dir).unwrap();
+        let snapshot = Snapshot {
+            key: key.to_string(),
+            captured_at: at,
+            schema,
+        };
+        fs::write(
+            dir.join(format!("{}.json", at)),
+            serde_json::to_vec(&snapshot).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn the_newest_snapshot_before_the_date_is_used() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().to_path_buf());
+        let june = parse_as_of("2024-06-01").unwrap();
+        write(&store, "api", june - 86400, json!({ "v": 1 }));
+        write(&store, "api", june - 60, json!({ "v": 2 }));
+        write(&store, "api", june + 60, json!({ "v": 3 }));
+
+        let any = |_: &Value| true;
+        assert_eq!(
+            store.schema_at("api", june, any).unwrap(),
+            json!({ "v": 2 })
+        );
+        assert_eq!(
+            store.schema_at("api", june - 3600, any).unwrap(),
+            json!({ "v": 1 })
+        );
+
+        let err = store.schema_at("api", june - 7 * 86400, any).unwrap_err();
+        assert_eq!(crate::error::code_of(&err), "SNAPSHOT_NOT_FOUND");
+        assert!(
+            err.to_string()
+                .contains("the earliest is from 2024-05-31 23:59:59 UTC"),
+            "{}",
+            err
+        );
+        assert!(store.schema_at("other", june, any).is_err());
+    }
+
+    #[test]
+    fn unchanged_schemas_are_not_recorded_again() {
+        let temp = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp.path().to_path_buf());
+        write(&store, "api", 1000, json!({ "v": 1 }));
+
+        store.record("api", &json!({ "v": 1 })).unwrap();
+        assert_eq!(SnapshotStore::times(&store.key_dir("api")), vec![1000]);
+        store.record("api", &json!({ "v": 2 })).unwrap();
+        assert_eq!(SnapshotStore::times(&store.key_dir("api")).len(), 2);
+    }
+
+    #[test]
+    fn only_the_newest_snapshots_are_kept() {
+        let temp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            location: temp.path().to_path_buf(),
+            max_snapshots: 2,
+            ..Default::default()
+        };
+        let store = SnapshotStore::for_config(&config, None);
+        write(&store, "api", 1000, json!({ "v": 1 }));
+        write(&store, "api", 2000, json!({ "v": 2 }));
+        write(&store, "other", 1000, json!({ "v": 1 }));
+
+        store.record("api", &json!({ "v": 3 })).unwrap();
+        let times = SnapshotStore::times(&store.key_dir("api"));
+        assert_eq!(times.len(), 2);
+        assert_eq!(times[0], 2000);
+        assert_eq!(store.usage().0, 3);
+
+        assert_eq!(store.clear().unwrap(), 3);
+        assert_eq!(store.usage(), (0, 0));
+    }
+
+    #[test]
+    fn encrypted_caches_seal_their_snapshots() {
+        let temp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            location: temp.path().to_path_buf(),
+            ..Default::default()
+        };
+        let cipher = CacheCipher::new("snapshot-test-key", b"snapshot-salt-16").unwrap();
+        let store = SnapshotStore::for_config(&config, Some(cipher));
+        store
+            .record("api", &json!({ "secret": "internal-only" }))
+            .unwrap();
+
+        let dir = store.key_dir("api");
+        let at = SnapshotStore::times(&dir)[0];
+        let raw = fs::read(SnapshotStore::path(&dir, at)).unwrap();
+        assert!(encryption::is_sealed(&raw));
+        assert_eq!(
+            store.schema_at("api", at, |_| true).unwrap(),
+            json!({ "secret": "internal-only" })
+        );
+
+        let other = SnapshotStore::for_config(
+            &config,
+            Some(CacheCipher::new("other", b"snapshot-salt-16").unwrap()),
+        );
+        assert!(other.schema_at("api", at, |_| true).is_err());
+    }
+
+    #[test]
+    fn as_of_accepts_dates_and_times() {
+        assert_eq!(
+            parse_as_of("2024-06-01").unwrap(),
+            parse_as_of("2024-06-01T23:59:59Z").unwrap()
+        );
+        assert_eq!(
+            parse_as_of("2024-06-01T02:00:00+02:00").unwrap(),
+            parse_as_of("2024-06-01T00:00:00Z").unwrap()
+        );
+        assert!(parse_as_of("June 1st").is_err());
+    }
+}
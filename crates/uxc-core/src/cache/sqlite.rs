@@ -0,0 +1,599 @@
+//! SQLite-backed cache storage
+
+use super::config::CacheConfig;
+use super::encryption::{self, CacheCipher};
+use super::stats::{CacheStats, ProtocolStats};
+use super::storage::detect_protocol;
+use super::{Cache, CacheEntry, CacheResult, CacheValidators, SnapshotStore};
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::fs;
+use std::sync::{Mutex, MutexGuard, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS schemas (
+    url TEXT PRIMARY KEY NOT NULL,
+    schema TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    etag TEXT,
+    last_modified TEXT,
+    protocol TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    accessed_at INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_schemas_url ON schemas(url);
+CREATE INDEX IF NOT EXISTS idx_schemas_expires_at ON schemas(expires_at);
+CREATE INDEX IF NOT EXISTS idx_schemas_accessed_at ON schemas(accessed_at);
+CREATE TABLE IF NOT EXISTS counters (
+    name TEXT PRIMARY KEY NOT NULL,
+    value INTEGER NOT NULL
+);
+";
+
+/// Schema cache stored in a single SQLite database
+///
+/// Keeps every entry in one `cache.db` file, so stats and clear run as single
+/// queries instead of walking thousands of small JSON files.
+pub struct SqliteCache {
+    config: CacheConfig,
+    conn: Mutex<Connection>,
+
+    // Set when the schema column is encrypted at rest
+    cipher: Option<CacheCipher>,
+
+    snapshots: SnapshotStore,
+
+    // In-memory stats tracking
+    stats: RwLock<CacheStats>,
+}
+
+impl SqliteCache {
+    /// Open (or create) the cache database for the given configuration
+    pub fn new(config: CacheConfig) -> Result<Self> {
+        if !config.location.exists() {
+            fs::create_dir_all(&config.location).with_context(|| {
+                format!("Failed to create cache directory: {:?}", config.location)
+            })?;
+            info!("Created cache directory: {:?}", config.location);
+        }
+
+        let cipher =
+            CacheCipher::from_reference(config.encryption_key.as_deref(), &config.location)?;
+        let path = config.sqlite_path();
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open cache database: {:?}", path))?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("Failed to initialize cache database: {:?}", path))?;
+
+        Ok(Self {
+            snapshots: SnapshotStore::for_config(&config, cipher.clone()),
+            config,
+            conn: Mutex::new(conn),
+            cipher,
+            stats: RwLock::new(CacheStats::new()),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// Access timestamp in milliseconds, fine-grained enough to order lookups
+    fn access_time() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64
+    }
+
+    /// Mark an entry as recently used when limits are configured
+    fn touch(&self, url: &str) {
+        if !self.config.has_limits() {
+            return;
+        }
+        let touched = self.conn().execute(
+            "UPDATE schemas SET accessed_at = ?1 WHERE url = ?2",
+            params![Self::access_time(), url],
+        );
+        if let Err(e) = touched {
+            debug!("Failed to touch cache entry {}: {}", url, e);
+        }
+    }
+
+    /// Evict least-recently-used entries until the cache is within its limits
+    fn enforce_limits(&self, conn: &Connection, keep: Option<&str>) -> Result<usize> {
+        if !self.config.has_limits() {
+            return Ok(0);
+        }
+
+        let (count, bytes) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM schemas",
+            [],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+        )?;
+        let (mut count, mut bytes) = (count as usize, bytes as u64);
+        if !self.config.exceeds_limits(count, bytes) {
+            return Ok(0);
+        }
+
+        let mut statement =
+            conn.prepare("SELECT url, size FROM schemas ORDER BY accessed_at, rowid")?;
+        let candidates = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut evicted = 0;
+        for (url, size) in candidates {
+            if !self.config.exceeds_limits(count, bytes) {
+                break;
+            }
+            if Some(url.as_str()) == keep {
+                continue;
+            }
+            conn.execute("DELETE FROM schemas WHERE url = ?1", params![url])?;
+            count -= 1;
+            bytes = bytes.saturating_sub(size as u64);
+            evicted += 1;
+        }
+
+        if evicted > 0 {
+            info!("Evicted {} cache entries to stay within limits", evicted);
+            conn.execute(
+                "INSERT INTO counters (name, value) VALUES ('evictions', ?1)
+                 ON CONFLICT(name) DO UPDATE SET value = value + excluded.value",
+                params![evicted as i64],
+            )?;
+        }
+        Ok(evicted)
+    }
+
+    fn load_entry(&self, url: &str) -> Result<Option<CacheEntry>> {
+        let row = self
+            .conn()
+            .query_row(
+                "SELECT schema, fetched_at, expires_at, etag, last_modified, protocol
+                 FROM schemas WHERE url = ?1",
+                params![url],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, String>(5)?,
+                    ))
+                },
+            )
+            .optional()
+            .context("Failed to read cache entry")?;
+
+        let Some((schema, fetched_at, expires_at, etag, last_modified, protocol)) = row else {
+            return Ok(None);
+        };
+
+        let sealed = encryption::is_sealed_text(&schema);
+        let schema = encryption::open_text_if_sealed(self.cipher.as_ref(), schema)?;
+        if let (Some(cipher), false) = (&self.cipher, sealed) {
+            // Encrypt rows written before a key was configured
+            self.conn()
+                .execute(
+                    "UPDATE schemas SET schema = ?1 WHERE url = ?2",
+                    params![cipher.seal_text(&schema)?, url],
+                )
+                .context("Failed to encrypt cache entry")?;
+            debug!("Encrypted cache entry for: {}", url);
+        }
+
+        Ok(Some(CacheEntry {
+            schema: serde_json::from_str(&schema).context("Failed to parse cached schema")?,
+            fetched_at: fetched_at as u64,
+            expires_at: expires_at as u64,
+            etag,
+            last_modified,
+            protocol,
+        }))
+    }
+
+    fn record_hit(&self) {
+        if let Ok(mut stats) = self.stats.write() {
+            stats.hits += 1;
+            stats.calculate_hit_rate();
+        }
+    }
+
+    fn record_miss(&self) {
+        if let Ok(mut stats) = self.stats.write() {
+            stats.misses += 1;
+            stats.calculate_hit_rate();
+        }
+    }
+}
+
+impl Cache for SqliteCache {
+    fn get(&self, url: &str) -> Result<CacheResult> {
+        if !self.config.enabled {
+            debug!("Cache is disabled, bypassing");
+            return Ok(CacheResult::Bypassed);
+        }
+
+        match self.load_entry(url) {
+            Ok(Some(entry)) if !entry.is_expired() => {
+                debug!("Cache hit: {}", url);
+                self.touch(url);
+                self.record_hit();
+                Ok(CacheResult::Hit(entry.schema))
+            }
+            Ok(_) => {
+                debug!("Cache miss: {}", url);
+                self.record_miss();
+                Ok(CacheResult::Miss)
+            }
+            Err(e) => {
+                warn!("Failed to load cache entry: {}", e);
+                self.record_miss();
+                Ok(CacheResult::Miss)
+            }
+        }
+    }
+
+    fn get_stale(&self, url: &str) -> Result<Option<CacheEntry>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+
+        match self.load_entry(url) {
+            Ok(entry) => Ok(entry),
+            Err(e) => {
+                warn!("Failed to load cache entry: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    fn put(&self, url: &str, schema: &Value) -> Result<()> {
+        self.put_with_validators(url, schema, &CacheValidators::default())
+    }
+
+    fn put_with_validators(
+        &self,
+        url: &str,
+        schema: &Value,
+        validators: &CacheValidators,
+    ) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let entry = CacheEntry::new(schema.clone(), self.config.ttl, detect_protocol(url))
+            .with_validators(validators);
+        let mut schema = serde_json::to_string(&entry.schema)?;
+        if let Some(cipher) = &self.cipher {
+            schema = cipher.seal_text(&schema)?;
+        }
+
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO schemas
+             (url, schema, fetched_at, expires_at, etag, last_modified, protocol, size, accessed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                url,
+                schema,
+                entry.fetched_at as i64,
+                entry.expires_at as i64,
+                entry.etag,
+                entry.last_modified,
+                entry.protocol,
+                entry.size() as i64,
+                Self::access_time(),
+            ],
+        )
+        .context("Failed to write cache entry")?;
+        info!("Cached schema for: {}", url);
+        self.enforce_limits(&conn, Some(url))?;
+        if let Err(e) = self.snapshots.record(url, &entry.schema) {
+            warn!("Failed to record schema snapshot: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn refresh(&self, url: &str) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let expires_at = Self::now() + self.config.ttl;
+        self.conn()
+            .execute(
+                "UPDATE schemas SET expires_at = ?1 WHERE url = ?2",
+                params![expires_at as i64, url],
+            )
+            .context("Failed to refresh cache entry")?;
+        debug!("Revalidated cache entry for: {}", url);
+        Ok(())
+    }
+
+    fn invalidate(&self, url: &str) -> Result<()> {
+        self.conn()
+            .execute("DELETE FROM schemas WHERE url = ?1", params![url])
+            .context("Failed to delete cache entry")?;
+        info!("Invalidated cache for: {}", url);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.conn()
+            .execute_batch("DELETE FROM schemas; DELETE FROM counters;")
+            .context("Failed to clear cache database")?;
+        self.snapshots.clear()?;
+        info!("Cleared all cache entries");
+
+        // Reset stats
+        if let Ok(mut stats) = self.stats.write() {
+            *stats = CacheStats::new();
+        }
+
+        Ok(())
+    }
+
+    fn clear_protocol(&self, protocol: &str) -> Result<usize> {
+        let removed = self
+            .conn()
+            .execute("DELETE FROM schemas WHERE protocol = ?1", params![protocol])
+            .context("Failed to delete cache entries")?;
+        info!("Cleared {} {} cache entries", removed, protocol);
+        Ok(removed)
+    }
+
+    fn compact(&self) -> Result<usize> {
+        let conn = self.conn();
+        let mut removed = conn
+            .execute(
+                "DELETE FROM schemas WHERE expires_at <= ?1",
+                params![Self::now() as i64],
+            )
+            .context("Failed to prune expired cache entries")?;
+        removed += self.enforce_limits(&conn, None)?;
+        conn.execute_batch("VACUUM")
+            .context("Failed to vacuum cache database")?;
+        info!("Compacted cache, removed {} entries", removed);
+        Ok(removed)
+    }
+
+    fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats::new();
+        {
+            let conn = self.conn();
+            conn.execute(
+                "DELETE FROM schemas WHERE expires_at <= ?1",
+                params![Self::now() as i64],
+            )
+            .context("Failed to prune expired cache entries")?;
+
+            let mut statement = conn.prepare(
+                "SELECT protocol, COUNT(*), COALESCE(SUM(size), 0)
+                 FROM schemas GROUP BY protocol",
+            )?;
+            let rows = statement.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+
+            for row in rows {
+                let (protocol, entries, size) = row?;
+                stats.total_entries += entries as usize;
+                stats.total_size += size as u64;
+                stats.by_protocol.insert(
+                    protocol,
+                    ProtocolStats {
+                        entries: entries as usize,
+                        size: size as u64,
+                    },
+                );
+            }
+
+            stats.evictions = conn
+                .query_row(
+                    "SELECT value FROM counters WHERE name = 'evictions'",
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()?
+                .unwrap_or(0) as u64;
+        }
+
+        stats.disk_size =
+            fs::metadata(self.config.sqlite_path()).map_or(0, |metadata| metadata.len());
+        (stats.snapshots, stats.snapshot_size) = self.snapshots.usage();
+
+        // Add in-memory hit/miss counters
+        if let Ok(memory_stats) = self.stats.try_read() {
+            stats.hits = memory_stats.hits;
+            stats.misses = memory_stats.misses;
+            stats.calculate_hit_rate();
+        }
+
+        Ok(stats)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn stale_while_revalidate(&self) -> bool {
+        self.config.stale_while_revalidate
+    }
+
+    fn snapshots(&self) -> Option<&SnapshotStore> {
+        Some(&self.snapshots)
+    }
+
+    fn as_of(&self) -> Option<u64> {
+        self.config.as_of
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheBackend;
+
+    use tempfile::TempDir;
+
+    fn create_test_cache(ttl: u64) -> (SqliteCache, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            enabled: true,
+            ttl,
+            max_size: 0,
+            max_entries: 0,
+            location: temp_dir.path().to_path_buf(),
+            backend: CacheBackend::Sqlite,
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: crate::cache::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        };
+
+        (SqliteCache::new(config).unwrap(), temp_dir)
+    }
+
+    #[test]
+    fn test_sqlite_put_get_invalidate() {
+        let (cache, temp_dir) = create_test_cache(3600);
+        let url = "https://api.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+
+        assert!(cache.get(url).unwrap().is_miss());
+        cache.put(url, &schema).unwrap();
+        match cache.get(url).unwrap() {
+            CacheResult::Hit(value) => assert_eq!(value, schema),
+            _ => panic!("Expected cache hit"),
+        }
+        assert!(temp_dir.path().join("cache.db").exists());
+
+        cache.invalidate(url).unwrap();
+        assert!(cache.get(url).unwrap().is_miss());
+    }
+
+    #[test]
+    fn test_sqlite_stats_and_clear() {
+        let (cache, _temp_dir) = create_test_cache(3600);
+        let schema = serde_json::json!({"openapi": "3.0"});
+        cache
+            .put("https://api.example.com/openapi.json", &schema)
+            .unwrap();
+        cache
+            .put("https://api.example.com/graphql", &schema)
+            .unwrap();
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.by_protocol["openapi"].entries, 1);
+        assert_eq!(stats.by_protocol["graphql"].entries, 1);
+        assert!(stats.total_size > 0);
+
+        assert_eq!(cache.clear_protocol("graphql").unwrap(), 1);
+        assert_eq!(cache.stats().unwrap().total_entries, 1);
+        cache.clear().unwrap();
+        assert_eq!(cache.stats().unwrap().total_entries, 0);
+    }
+
+    #[test]
+    fn test_sqlite_evicts_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = SqliteCache::new(CacheConfig {
+            max_entries: 2,
+            location: temp_dir.path().to_path_buf(),
+            backend: CacheBackend::Sqlite,
+            ..CacheConfig::default()
+        })
+        .unwrap();
+        let schema = serde_json::json!({"openapi": "3.0"});
+        let pause = || std::thread::sleep(std::time::Duration::from_millis(5));
+
+        cache.put("https://a.example.com", &schema).unwrap();
+        pause();
+        cache.put("https://b.example.com", &schema).unwrap();
+        pause();
+        assert!(cache.get("https://a.example.com").unwrap().is_hit());
+        pause();
+        cache.put("https://c.example.com", &schema).unwrap();
+
+        assert!(cache.get("https://b.example.com").unwrap().is_miss());
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.evictions, 1);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.stats().unwrap().evictions, 0);
+    }
+
+    #[test]
+    fn test_sqlite_encrypts_schemas() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = |key: &str| CacheConfig {
+            location: temp_dir.path().to_path_buf(),
+            backend: CacheBackend::Sqlite,
+            encryption_key: Some(key.to_string()),
+            ..CacheConfig::default()
+        };
+        let url = "https://internal.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0", "paths": {"/internal-admin": {}}});
+
+        let cache = SqliteCache::new(config("cache-key-one")).unwrap();
+        cache.put(url, &schema).unwrap();
+        let stored: String = cache
+            .conn()
+            .query_row("SELECT schema FROM schemas", [], |row| row.get(0))
+            .unwrap();
+        assert!(!stored.contains("internal-admin"));
+        match cache.get(url).unwrap() {
+            CacheResult::Hit(value) => assert_eq!(value, schema),
+            _ => panic!("Expected cache hit"),
+        }
+
+        let wrong = SqliteCache::new(config("cache-key-two")).unwrap();
+        assert!(wrong.get(url).unwrap().is_miss());
+    }
+
+    #[test]
+    fn test_sqlite_revalidation() {
+        let (cache, _temp_dir) = create_test_cache(0);
+        let url = "https://api.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+        let validators = CacheValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        cache
+            .put_with_validators(url, &schema, &validators)
+            .unwrap();
+
+        // Expired entries miss but stay available for revalidation
+        assert!(cache.get(url).unwrap().is_miss());
+        let stale = cache.get_stale(url).unwrap().unwrap();
+        assert_eq!(stale.schema, schema);
+        assert_eq!(stale.validators(), validators);
+    }
+}
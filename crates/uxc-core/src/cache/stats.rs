@@ -1,5 +1,6 @@
 //! Cache statistics
 
+use crate::output::format_size;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,9 +10,13 @@ pub struct CacheStats {
     /// Total number of cache entries
     pub total_entries: usize,
 
-    /// Total size of cache in bytes
+    /// Total size of cache in bytes, uncompressed
     pub total_size: u64,
 
+    /// Bytes the entries take on disk after compression
+    #[serde(default)]
+    pub disk_size: u64,
+
     /// Number of cache hits since startup
     pub hits: u64,
 
@@ -21,6 +26,30 @@ pub struct CacheStats {
     /// Cache hit rate (0.0 to 1.0)
     pub hit_rate: f64,
 
+    /// Lookups answered by the in-memory tier
+    #[serde(default)]
+    pub memory_hits: u64,
+
+    /// Lookups that fell through to disk
+    #[serde(default)]
+    pub memory_misses: u64,
+
+    /// Entries currently held in the in-memory tier
+    #[serde(default)]
+    pub memory_entries: usize,
+
+    /// Entries evicted to stay within `max_entries`/`max_bytes`
+    #[serde(default)]
+    pub evictions: u64,
+
+    /// Schema snapshots kept for `--as-of`
+    #[serde(default)]
+    pub snapshots: usize,
+
+    /// Bytes the snapshots take on disk
+    #[serde(default)]
+    pub snapshot_size: u64,
+
     /// Per-protocol statistics
     pub by_protocol: HashMap<String, ProtocolStats>,
 }
@@ -31,9 +60,16 @@ impl CacheStats {
         Self {
             total_entries: 0,
             total_size: 0,
+            disk_size: 0,
             hits: 0,
             misses: 0,
             hit_rate: 0.0,
+            memory_hits: 0,
+            memory_misses: 0,
+            memory_entries: 0,
+            evictions: 0,
+            snapshots: 0,
+            snapshot_size: 0,
             by_protocol: HashMap::new(),
         }
     }
@@ -48,42 +84,36 @@ impl CacheStats {
         };
     }
 
-    /// Get human-readable size string
-    pub fn format_size(size: u64) -> String {
-        const KB: u64 = 1024;
-        const MB: u64 = 1024 * KB;
-        const GB: u64 = 1024 * MB;
-
-        if size >= GB {
-            format!("{:.2} GB", size as f64 / GB as f64)
-        } else if size >= MB {
-            format!("{:.2} MB", size as f64 / MB as f64)
-        } else if size >= KB {
-            format!("{:.2} KB", size as f64 / KB as f64)
-        } else {
-            format!("{} B", size)
-        }
-    }
-
     /// Display statistics in a human-readable format
     pub fn display(&self) -> String {
         let mut output = String::new();
         output.push_str("Cache Statistics:\n");
         output.push_str(&format!("  Total entries: {}\n", self.total_entries));
         output.push_str(&format!(
-            "  Total size: {}\n",
-            Self::format_size(self.total_size)
+            "  Total size: {} ({} on disk)\n",
+            format_size(self.total_size),
+            format_size(self.disk_size)
         ));
         output.push_str(&format!("  Hits: {}\n", self.hits));
         output.push_str(&format!("  Misses: {}\n", self.misses));
         output.push_str(&format!("  Hit rate: {:.1}%\n", self.hit_rate * 100.0));
+        output.push_str(&format!(
+            "  Memory tier: {} entries, {} hits, {} misses\n",
+            self.memory_entries, self.memory_hits, self.memory_misses
+        ));
+        output.push_str(&format!("  Evictions: {}\n", self.evictions));
+        output.push_str(&format!(
+            "  Snapshots: {} ({})\n",
+            self.snapshots,
+            format_size(self.snapshot_size)
+        ));
 
         if !self.by_protocol.is_empty() {
             output.push_str("\nBy protocol:\n");
             for (protocol, stats) in &self.by_protocol {
                 output.push_str(&format!("  {}:\n", protocol.to_uppercase()));
                 output.push_str(&format!("    Entries: {}\n", stats.entries));
-                output.push_str(&format!("    Size: {}\n", Self::format_size(stats.size)));
+                output.push_str(&format!("    Size: {}\n", format_size(stats.size)));
             }
         }
 
@@ -145,14 +175,6 @@ mod tests {
         assert_eq!(stats.hit_rate, 0.8);
     }
 
-    #[test]
-    fn test_format_size() {
-        assert_eq!(CacheStats::format_size(500), "500 B");
-        assert_eq!(CacheStats::format_size(2048), "2.00 KB");
-        assert_eq!(CacheStats::format_size(3 * 1024 * 1024), "3.00 MB");
-        assert_eq!(CacheStats::format_size(2 * 1024 * 1024 * 1024), "2.00 GB");
-    }
-
     #[test]
     fn test_protocol_stats() {
         let stats = ProtocolStats::new();
@@ -0,0 +1,1124 @@
+//! Cache storage implementation
+
+use super::config::CacheConfig;
+use super::encryption::{self, CacheCipher};
+use super::memory;
+use super::stats::{CacheStats, ProtocolStats};
+use super::{Cache, CacheResult, CacheValidators, SnapshotStore};
+use super::{DETECTION_KEY_MARKER, DETECTION_PROTOCOL};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// Running eviction count, kept next to the cache entries
+const EVICTIONS_FILE: &str = "evictions.count";
+
+/// File suffix of zstd-compressed entries
+const ENTRY_SUFFIX: &str = ".json.zst";
+
+/// File suffix of plain JSON entries written before compression was added
+const LEGACY_ENTRY_SUFFIX: &str = ".json";
+
+/// zstd level used for new entries
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Whether `path` is a cache entry file, compressed or plain
+fn is_entry_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(ENTRY_SUFFIX) || name.ends_with(LEGACY_ENTRY_SUFFIX))
+}
+
+/// Cache entry containing the schema and metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The cached schema data
+    pub schema: Value,
+
+    /// When the schema was cached (Unix timestamp)
+    pub fetched_at: u64,
+
+    /// When the cache entry expires (Unix timestamp)
+    pub expires_at: u64,
+
+    /// ETag for validation (optional)
+    pub etag: Option<String>,
+
+    /// Last-Modified for validation (optional)
+    #[serde(default)]
+    pub last_modified: Option<String>,
+
+    /// Protocol type (openapi, grpc, graphql, mcp), or `detection` for
+    /// detection results
+    pub protocol: String,
+}
+
+impl CacheEntry {
+    /// Create a new cache entry
+    pub fn new(schema: Value, ttl: u64, protocol: String) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Self {
+            schema,
+            fetched_at: now,
+            expires_at: now + ttl,
+            etag: None,
+            last_modified: None,
+            protocol,
+        }
+    }
+
+    /// Attach HTTP validators for conditional revalidation
+    pub fn with_validators(mut self, validators: &CacheValidators) -> Self {
+        self.etag = validators.etag.clone();
+        self.last_modified = validators.last_modified.clone();
+        self
+    }
+
+    /// Validators stored with this entry
+    pub fn validators(&self) -> CacheValidators {
+        CacheValidators {
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+        }
+    }
+
+    /// Check if the cache entry is expired
+    pub fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        now >= self.expires_at
+    }
+
+    /// Get the size of this cache entry in bytes
+    pub fn size(&self) -> u64 {
+        // Approximate size based on JSON serialization
+        serde_json::to_string(self).map_or(0, |s| s.len() as u64)
+    }
+}
+
+/// Filesystem-based cache storage
+pub struct CacheStorage {
+    config: CacheConfig,
+    cache_dir: PathBuf,
+
+    // Set when entries are encrypted at rest
+    cipher: Option<CacheCipher>,
+
+    // In-memory stats tracking
+    stats: Arc<RwLock<CacheStats>>,
+}
+
+impl CacheStorage {
+    /// Create a new cache storage instance
+    pub fn new(config: CacheConfig) -> Result<Self> {
+        let cache_dir = config.location.clone();
+
+        // Ensure cache directory exists
+        if !cache_dir.exists() {
+            fs::create_dir_all(&cache_dir)
+                .with_context(|| format!("Failed to create cache directory: {:?}", cache_dir))?;
+            info!("Created cache directory: {:?}", cache_dir);
+        }
+        let cipher = CacheCipher::from_reference(config.encryption_key.as_deref(), &cache_dir)?;
+
+        Ok(Self {
+            config,
+            cache_dir,
+            cipher,
+            stats: Arc::new(RwLock::new(CacheStats::new())),
+        })
+    }
+
+    /// Generate a cache key from a URL
+    ///
+    /// Uses a hash of the URL to generate a unique filename.
+    fn generate_cache_key(&self, url: &str) -> String {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        format!("{:x}{}", hasher.finish(), ENTRY_SUFFIX)
+    }
+
+    /// Path of the plain JSON file an entry used before compression
+    fn legacy_path(&self, key: &str) -> Option<PathBuf> {
+        key.strip_suffix(".zst")
+            .map(|legacy| self.cache_dir.join(legacy))
+    }
+
+    /// Get the full path for a cache key
+    fn cache_path(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(key)
+    }
+
+    /// Load a cache entry, from the memory tier when possible
+    fn load_entry(&self, key: &str) -> Result<Option<CacheEntry>> {
+        let path = self.cache_path(key);
+
+        if let Some(entry) = memory::shared()
+            .lock()
+            .ok()
+            .and_then(|mut tier| tier.get(&path))
+        {
+            self.record_memory(true);
+            self.touch(&path);
+            return Ok(Some(entry));
+        }
+        self.record_memory(false);
+
+        if !path.exists() {
+            // Migrate a plain entry to the compressed format on first read
+            return match self.legacy_path(key).filter(|legacy| legacy.exists()) {
+                Some(legacy) => {
+                    let (entry, _) = self.read_entry(&legacy)?;
+                    self.save_entry(key, &entry)?;
+                    debug!("Migrated cache entry to zstd: {}", key);
+                    Ok(Some(entry))
+                }
+                None => Ok(None),
+            };
+        }
+
+        let (entry, sealed) = self.read_entry(&path)?;
+        if self.cipher.is_some() && !sealed {
+            // Encrypt entries written before a key was configured
+            self.save_entry(key, &entry)?;
+            debug!("Encrypted cache entry: {}", key);
+        }
+        self.touch(&path);
+        if let Ok(mut tier) = memory::shared().lock() {
+            tier.insert(path, entry.clone());
+        }
+        Ok(Some(entry))
+    }
+
+    /// Mark an entry as recently used by bumping its modification time
+    ///
+    /// Only needed when limits are configured, since eviction order follows
+    /// modification times.
+    fn touch(&self, path: &Path) {
+        if !self.config.has_limits() {
+            return;
+        }
+        let touched = File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        if let Err(e) = touched {
+            debug!("Failed to touch cache file {:?}: {}", path, e);
+        }
+    }
+
+    /// Evict least-recently-used entries until the cache is within its limits
+    ///
+    /// `keep` is never evicted, so a freshly written entry survives even when
+    /// it alone exceeds `max_bytes`.
+    fn enforce_limits(&self, keep: Option<&Path>) -> Result<usize> {
+        if !self.config.has_limits() {
+            return Ok(0);
+        }
+
+        let mut files = Vec::new();
+        let entries = fs::read_dir(&self.cache_dir)
+            .with_context(|| format!("Failed to read cache directory: {:?}", self.cache_dir))?;
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            if !is_entry_file(&path) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            files.push((modified, metadata.len(), path));
+        }
+
+        let mut count = files.len();
+        let mut bytes: u64 = files.iter().map(|(_, size, _)| size).sum();
+        files.sort_by_key(|(modified, _, _)| *modified);
+
+        let mut evicted = 0;
+        for (_, size, path) in files {
+            if !self.config.exceeds_limits(count, bytes) {
+                break;
+            }
+            if Some(path.as_path()) == keep {
+                continue;
+            }
+            if let Ok(mut tier) = memory::shared().lock() {
+                tier.remove(&path);
+            }
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove cache file: {:?}", path))?;
+            count -= 1;
+            bytes = bytes.saturating_sub(size);
+            evicted += 1;
+        }
+
+        if evicted > 0 {
+            info!("Evicted {} cache entries to stay within limits", evicted);
+            self.add_evictions(evicted as u64);
+        }
+        Ok(evicted)
+    }
+
+    fn evictions_path(&self) -> PathBuf {
+        self.cache_dir.join(EVICTIONS_FILE)
+    }
+
+    /// Total evictions recorded for this cache location
+    fn evictions(&self) -> u64 {
+        fs::read_to_string(self.evictions_path())
+            .ok()
+            .and_then(|count| count.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn add_evictions(&self, evicted: u64) {
+        let total = self.evictions() + evicted;
+        if let Err(e) = fs::write(self.evictions_path(), total.to_string()) {
+            debug!("Failed to record cache evictions: {}", e);
+        }
+    }
+
+    /// Read a cache entry file from disk, decrypting and decompressing it when
+    /// needed; also reports whether the file was encrypted
+    fn read_entry(&self, path: &Path) -> Result<(CacheEntry, bool)> {
+        let mut data = Vec::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_end(&mut data))
+            .with_context(|| format!("Failed to open cache file: {:?}", path))?;
+        let sealed = encryption::is_sealed(&data);
+        let data = encryption::open_if_sealed(self.cipher.as_ref(), data)
+            .with_context(|| format!("Failed to open cache file: {:?}", path))?;
+
+        let compressed = path
+            .to_str()
+            .is_some_and(|path| path.ends_with(ENTRY_SUFFIX));
+        let entry = if compressed {
+            let decoder = zstd::Decoder::new(data.as_slice())
+                .with_context(|| format!("Failed to open cache file: {:?}", path))?;
+            serde_json::from_reader(decoder)
+                .with_context(|| format!("Failed to parse cache file: {:?}", path))?
+        } else {
+            serde_json::from_slice(&data)
+                .with_context(|| format!("Failed to parse cache file: {:?}", path))?
+        };
+        Ok((entry, sealed))
+    }
+
+    /// Save a cache entry to disk
+    fn save_entry(&self, key: &str, entry: &CacheEntry) -> Result<()> {
+        let path = self.cache_path(key);
+
+        let mut encoder = zstd::Encoder::new(Vec::new(), COMPRESSION_LEVEL)
+            .with_context(|| format!("Failed to create cache file: {:?}", path))?;
+        serde_json::to_writer(&mut encoder, entry)
+            .with_context(|| format!("Failed to write cache file: {:?}", path))?;
+        let mut data = encoder
+            .finish()
+            .with_context(|| format!("Failed to write cache file: {:?}", path))?;
+        if let Some(cipher) = &self.cipher {
+            data = cipher.seal(&data)?;
+        }
+
+        File::create(&path)
+            .and_then(|mut file| file.write_all(&data))
+            .with_context(|| format!("Failed to write cache file: {:?}", path))?;
+        if let Ok(mut tier) = memory::shared().lock() {
+            tier.insert(path.clone(), entry.clone());
+        }
+        if let Some(legacy) = self.legacy_path(key).filter(|legacy| legacy.exists()) {
+            fs::remove_file(&legacy)
+                .with_context(|| format!("Failed to remove cache file: {:?}", legacy))?;
+        }
+
+        debug!("Saved cache entry: {}", key);
+        self.enforce_limits(Some(&path))?;
+        Ok(())
+    }
+
+    /// Delete a cache entry from disk
+    fn delete_entry(&self, key: &str) -> Result<()> {
+        let path = self.cache_path(key);
+        if let Ok(mut tier) = memory::shared().lock() {
+            tier.remove(&path);
+        }
+
+        for path in std::iter::once(path).chain(self.legacy_path(key)) {
+            if path.exists() {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove cache file: {:?}", path))?;
+                debug!("Deleted cache entry: {}", key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scan cache directory and collect statistics
+    fn scan_cache(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats::new();
+        let mut by_protocol: HashMap<String, ProtocolStats> = HashMap::new();
+
+        let entries = fs::read_dir(&self.cache_dir)
+            .with_context(|| format!("Failed to read cache directory: {:?}", self.cache_dir))?;
+
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+
+            if !is_entry_file(&path) {
+                continue;
+            }
+
+            // Read the entry from disk to get metadata
+            if let Ok((cache_entry, _)) = self.read_entry(&path) {
+                // Check if expired
+                if cache_entry.is_expired() {
+                    // Delete expired entries
+                    let _ =
+                        self.delete_entry(path.file_name().and_then(|s| s.to_str()).unwrap_or(""));
+                    continue;
+                }
+
+                let size = cache_entry.size();
+                stats.total_entries += 1;
+                stats.total_size += size;
+                stats.disk_size += entry.metadata().map_or(0, |metadata| metadata.len());
+
+                by_protocol
+                    .entry(cache_entry.protocol.clone())
+                    .or_default()
+                    .entries += 1;
+
+                by_protocol.get_mut(&cache_entry.protocol).unwrap().size += size;
+            }
+        }
+
+        stats.by_protocol = by_protocol;
+        stats.evictions = self.evictions();
+        Ok(stats)
+    }
+
+    /// Record a cache hit
+    fn record_hit(&self, _protocol: &str) {
+        if let Ok(mut stats) = self.stats.write() {
+            stats.hits += 1;
+            stats.calculate_hit_rate();
+        }
+    }
+
+    /// Record a cache miss
+    fn record_miss(&self) {
+        if let Ok(mut stats) = self.stats.write() {
+            stats.misses += 1;
+            stats.calculate_hit_rate();
+        }
+    }
+
+    /// Record a memory tier lookup
+    fn record_memory(&self, hit: bool) {
+        if let Ok(mut stats) = self.stats.write() {
+            if hit {
+                stats.memory_hits += 1;
+            } else {
+                stats.memory_misses += 1;
+            }
+        }
+    }
+}
+
+/// Public schema cache that implements the Cache trait
+pub struct SchemaCache {
+    storage: CacheStorage,
+    snapshots: SnapshotStore,
+}
+
+impl SchemaCache {
+    /// Create a new schema cache
+    pub fn new(config: CacheConfig) -> Result<Self> {
+        let storage = CacheStorage::new(config)?;
+        let snapshots = SnapshotStore::for_config(&storage.config, storage.cipher.clone());
+        Ok(Self { storage, snapshots })
+    }
+
+    /// Create with default configuration
+    pub fn with_default_config() -> Result<Self> {
+        Self::new(CacheConfig::default())
+    }
+
+    /// Detect protocol from URL
+    fn detect_protocol(&self, url: &str) -> String {
+        detect_protocol(url)
+    }
+}
+
+/// Detect the protocol a cached schema URL belongs to
+pub(super) fn detect_protocol(url: &str) -> String {
+    let lower = url.to_lowercase();
+
+    // Results of protocol detection, not schemas
+    if lower.contains(DETECTION_KEY_MARKER) {
+        return DETECTION_PROTOCOL.to_string();
+    }
+    if lower.contains("grpc") || lower.starts_with("grpc://") {
+        "grpc".to_string()
+    } else if lower.contains("graphql") || lower.ends_with("/graphql") {
+        "graphql".to_string()
+    } else if lower.contains("mcp") || lower.contains("model-context-protocol") {
+        "mcp".to_string()
+    } else {
+        // Default to openapi for HTTP/HTTPS URLs
+        "openapi".to_string()
+    }
+}
+
+impl Cache for SchemaCache {
+    fn get(&self, url: &str) -> Result<CacheResult> {
+        if !self.storage.config.enabled {
+            debug!("Cache is disabled, bypassing");
+            return Ok(CacheResult::Bypassed);
+        }
+
+        let key = self.storage.generate_cache_key(url);
+
+        match self.storage.load_entry(&key) {
+            Ok(Some(entry)) => {
+                if entry.is_expired() {
+                    // Kept on disk so callers can revalidate or fall back to it
+                    debug!("Cache entry expired: {}", key);
+                    self.storage.record_miss();
+                    Ok(CacheResult::Miss)
+                } else {
+                    debug!("Cache hit: {}", key);
+                    self.storage.record_hit(&entry.protocol);
+                    Ok(CacheResult::Hit(entry.schema))
+                }
+            }
+            Ok(None) => {
+                debug!("Cache miss: entry not found");
+                self.storage.record_miss();
+                Ok(CacheResult::Miss)
+            }
+            Err(e) => {
+                warn!("Failed to load cache entry: {}", e);
+                self.storage.record_miss();
+                Ok(CacheResult::Miss)
+            }
+        }
+    }
+
+    fn get_stale(&self, url: &str) -> Result<Option<CacheEntry>> {
+        if !self.storage.config.enabled {
+            return Ok(None);
+        }
+
+        let key = self.storage.generate_cache_key(url);
+        match self.storage.load_entry(&key) {
+            Ok(entry) => Ok(entry),
+            Err(e) => {
+                warn!("Failed to load cache entry: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    fn put(&self, url: &str, schema: &Value) -> Result<()> {
+        self.put_with_validators(url, schema, &CacheValidators::default())
+    }
+
+    fn put_with_validators(
+        &self,
+        url: &str,
+        schema: &Value,
+        validators: &CacheValidators,
+    ) -> Result<()> {
+        if !self.storage.config.enabled {
+            return Ok(());
+        }
+
+        let key = self.storage.generate_cache_key(url);
+        let protocol = self.detect_protocol(url);
+        let entry = CacheEntry::new(schema.clone(), self.storage.config.ttl, protocol)
+            .with_validators(validators);
+
+        self.storage.save_entry(&key, &entry)?;
+        info!("Cached schema for: {}", url);
+        if let Err(e) = self.snapshots.record(url, schema) {
+            warn!("Failed to record schema snapshot: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn refresh(&self, url: &str) -> Result<()> {
+        if !self.storage.config.enabled {
+            return Ok(());
+        }
+
+        let key = self.storage.generate_cache_key(url);
+        let Some(mut entry) = self.storage.load_entry(&key)? else {
+            return Ok(());
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        entry.expires_at = now + self.storage.config.ttl;
+
+        self.storage.save_entry(&key, &entry)?;
+        debug!("Revalidated cache entry for: {}", url);
+        Ok(())
+    }
+
+    fn invalidate(&self, url: &str) -> Result<()> {
+        let key = self.storage.generate_cache_key(url);
+        self.storage.delete_entry(&key)?;
+        info!("Invalidated cache for: {}", url);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        let entries = fs::read_dir(&self.storage.cache_dir)
+            .with_context(|| "Failed to read cache directory")?;
+
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+
+            if is_entry_file(&path) {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove cache file: {:?}", path))?;
+            }
+        }
+
+        if let Ok(mut tier) = memory::shared().lock() {
+            tier.remove_dir(&self.storage.cache_dir);
+        }
+        let evictions = self.storage.evictions_path();
+        if evictions.exists() {
+            fs::remove_file(&evictions)
+                .with_context(|| format!("Failed to remove cache file: {:?}", evictions))?;
+        }
+        self.snapshots.clear()?;
+        info!("Cleared all cache entries");
+
+        // Reset stats
+        if let Ok(mut stats) = self.storage.stats.write() {
+            *stats = CacheStats::new();
+        }
+
+        Ok(())
+    }
+
+    fn clear_protocol(&self, protocol: &str) -> Result<usize> {
+        let mut removed = 0;
+        let entries = fs::read_dir(&self.storage.cache_dir)
+            .with_context(|| "Failed to read cache directory")?;
+        for entry in entries {
+            let path = entry?.path();
+            if !is_entry_file(&path) {
+                continue;
+            }
+            let matches = self
+                .storage
+                .read_entry(&path)
+                .is_ok_and(|(entry, _)| entry.protocol == protocol);
+            if matches {
+                if let Ok(mut tier) = memory::shared().lock() {
+                    tier.remove(&path);
+                }
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove cache file: {:?}", path))?;
+                removed += 1;
+            }
+        }
+        info!("Cleared {} {} cache entries", removed, protocol);
+        Ok(removed)
+    }
+
+    fn compact(&self) -> Result<usize> {
+        let mut removed = 0;
+        let entries = fs::read_dir(&self.storage.cache_dir)
+            .with_context(|| "Failed to read cache directory")?;
+        for entry in entries {
+            let path = entry?.path();
+            if !is_entry_file(&path) {
+                continue;
+            }
+
+            // Unreadable files are dropped along with expired entries
+            let expired = self
+                .storage
+                .read_entry(&path)
+                .map_or(true, |(entry, _)| entry.is_expired());
+            if expired {
+                if let Ok(mut tier) = memory::shared().lock() {
+                    tier.remove(&path);
+                }
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove cache file: {:?}", path))?;
+                removed += 1;
+            }
+        }
+
+        removed += self.storage.enforce_limits(None)?;
+        info!("Compacted cache, removed {} entries", removed);
+        Ok(removed)
+    }
+
+    fn stats(&self) -> Result<CacheStats> {
+        let mut stats = self.storage.scan_cache()?;
+
+        // Add in-memory hit/miss counters
+        if let Ok(memory_stats) = self.storage.stats.try_read() {
+            stats.hits = memory_stats.hits;
+            stats.misses = memory_stats.misses;
+            stats.memory_hits = memory_stats.memory_hits;
+            stats.memory_misses = memory_stats.memory_misses;
+            stats.calculate_hit_rate();
+        }
+        if let Ok(tier) = memory::shared().lock() {
+            stats.memory_entries = tier.len_in(&self.storage.cache_dir);
+        }
+        (stats.snapshots, stats.snapshot_size) = self.snapshots.usage();
+
+        Ok(stats)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.storage.config.enabled
+    }
+
+    fn stale_while_revalidate(&self) -> bool {
+        self.storage.config.stale_while_revalidate
+    }
+
+    fn snapshots(&self) -> Option<&SnapshotStore> {
+        Some(&self.snapshots)
+    }
+
+    fn as_of(&self) -> Option<u64> {
+        self.storage.config.as_of
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::TempDir;
+
+    fn create_test_cache() -> (SchemaCache, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            enabled: true,
+            ttl: 3600,
+            max_size: 0,
+            max_entries: 0,
+            location: temp_dir.path().to_path_buf(),
+            backend: Default::default(),
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: crate::cache::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        };
+
+        (SchemaCache::new(config).unwrap(), temp_dir)
+    }
+
+    #[test]
+    fn test_cache_entry_new() {
+        let schema = serde_json::json!({"test": "data"});
+        let entry = CacheEntry::new(schema.clone(), 3600, "openapi".to_string());
+
+        assert_eq!(entry.schema, schema);
+        assert_eq!(entry.protocol, "openapi");
+        assert!(!entry.is_expired());
+    }
+
+    #[test]
+    fn test_cache_entry_expired() {
+        let schema = serde_json::json!({"test": "data"});
+        let entry = CacheEntry::new(schema, 0, "openapi".to_string());
+
+        // Should be expired immediately with 0 TTL
+        assert!(entry.is_expired());
+    }
+
+    #[test]
+    fn test_cache_put_get() {
+        let (cache, _temp) = create_test_cache();
+
+        let url = "https://api.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0", "info": {"title": "Test API"}});
+
+        // Put schema in cache
+        cache.put(url, &schema).unwrap();
+
+        // Get schema from cache
+        match cache.get(url).unwrap() {
+            CacheResult::Hit(cached_schema) => {
+                assert_eq!(cached_schema, schema);
+            }
+            _ => panic!("Expected cache hit"),
+        }
+    }
+
+    #[test]
+    fn test_cache_miss() {
+        let (cache, _temp) = create_test_cache();
+
+        let url = "https://api.example.com/openapi.json";
+
+        // Should miss since we haven't cached anything
+        match cache.get(url).unwrap() {
+            CacheResult::Miss => {
+                // Expected
+            }
+            _ => panic!("Expected cache miss"),
+        }
+    }
+
+    #[test]
+    fn test_cache_invalidate() {
+        let (cache, _temp) = create_test_cache();
+
+        let url = "https://api.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+
+        // Put schema in cache
+        cache.put(url, &schema).unwrap();
+
+        // Invalidate
+        cache.invalidate(url).unwrap();
+
+        // Should now miss
+        match cache.get(url).unwrap() {
+            CacheResult::Miss => {
+                // Expected
+            }
+            _ => panic!("Expected cache miss after invalidation"),
+        }
+    }
+
+    #[test]
+    fn test_cache_clear() {
+        let (cache, _temp) = create_test_cache();
+
+        let url1 = "https://api1.example.com/openapi.json";
+        let url2 = "https://api2.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+
+        // Put multiple schemas in cache
+        cache.put(url1, &schema).unwrap();
+        cache.put(url2, &schema).unwrap();
+
+        // Clear all
+        cache.clear().unwrap();
+
+        // Both should miss now
+        match cache.get(url1).unwrap() {
+            CacheResult::Miss => {}
+            _ => panic!("Expected cache miss after clear"),
+        }
+
+        match cache.get(url2).unwrap() {
+            CacheResult::Miss => {}
+            _ => panic!("Expected cache miss after clear"),
+        }
+    }
+
+    #[test]
+    fn test_cache_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            enabled: false,
+            ttl: 3600,
+            max_size: 0,
+            max_entries: 0,
+            location: temp_dir.path().to_path_buf(),
+            backend: Default::default(),
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: crate::cache::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        };
+
+        let cache = SchemaCache::new(config).unwrap();
+
+        let url = "https://api.example.com/openapi.json";
+
+        // Should bypass when disabled
+        match cache.get(url).unwrap() {
+            CacheResult::Bypassed => {
+                // Expected
+            }
+            _ => panic!("Expected cache bypassed"),
+        }
+
+        // Put should be a no-op
+        let schema = serde_json::json!({"openapi": "3.0"});
+        cache.put(url, &schema).unwrap();
+
+        // Still should bypass
+        match cache.get(url).unwrap() {
+            CacheResult::Bypassed => {
+                // Expected
+            }
+            _ => panic!("Expected cache bypassed"),
+        }
+    }
+
+    #[test]
+    fn test_cache_revalidation() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            enabled: true,
+            ttl: 0,
+            max_size: 0,
+            max_entries: 0,
+            location: temp_dir.path().to_path_buf(),
+            backend: Default::default(),
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: crate::cache::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        };
+        let cache = SchemaCache::new(config).unwrap();
+
+        let url = "https://api.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+        let validators = CacheValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+        cache
+            .put_with_validators(url, &schema, &validators)
+            .unwrap();
+
+        // Expired entries miss but stay available for revalidation
+        assert!(cache.get(url).unwrap().is_miss());
+        let stale = cache.get_stale(url).unwrap().unwrap();
+        assert_eq!(stale.schema, schema);
+        assert_eq!(stale.validators(), validators);
+
+        cache.refresh(url).unwrap();
+        assert!(cache.get_stale(url).unwrap().unwrap().expires_at >= stale.expires_at);
+    }
+
+    #[test]
+    fn test_memory_tier_serves_repeated_gets() {
+        let (cache, temp) = create_test_cache();
+
+        let url = "https://api.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+        cache.put(url, &schema).unwrap();
+
+        // Remove the file behind the cache; the memory tier still answers
+        for entry in fs::read_dir(temp.path()).unwrap() {
+            let path = entry.unwrap().path();
+            if is_entry_file(&path) {
+                fs::remove_file(path).unwrap();
+            }
+        }
+        assert!(cache.get(url).unwrap().is_hit());
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.memory_hits, 1);
+        assert_eq!(stats.memory_entries, 1);
+
+        cache.invalidate(url).unwrap();
+        assert!(cache.get(url).unwrap().is_miss());
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            max_entries: 2,
+            location: temp_dir.path().to_path_buf(),
+            ..CacheConfig::default()
+        };
+        let cache = SchemaCache::new(config).unwrap();
+        let schema = serde_json::json!({"openapi": "3.0"});
+        let pause = || std::thread::sleep(std::time::Duration::from_millis(20));
+
+        cache.put("https://a.example.com", &schema).unwrap();
+        pause();
+        cache.put("https://b.example.com", &schema).unwrap();
+        pause();
+        // Reading `a` makes `b` the least recently used entry
+        assert!(cache.get("https://a.example.com").unwrap().is_hit());
+        pause();
+        cache.put("https://c.example.com", &schema).unwrap();
+
+        assert!(cache.get("https://a.example.com").unwrap().is_hit());
+        assert!(cache.get("https://b.example.com").unwrap().is_miss());
+        assert!(cache.get("https://c.example.com").unwrap().is_hit());
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.evictions, 1);
+    }
+
+    #[test]
+    fn test_cache_compact_removes_expired_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            ttl: 0,
+            location: temp_dir.path().to_path_buf(),
+            ..CacheConfig::default()
+        };
+        let cache = SchemaCache::new(config).unwrap();
+        let schema = serde_json::json!({"openapi": "3.0"});
+        cache.put("https://a.example.com", &schema).unwrap();
+        cache.put("https://b.example.com", &schema).unwrap();
+
+        assert_eq!(cache.compact().unwrap(), 2);
+        assert!(cache.get_stale("https://a.example.com").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cache_entries_are_compressed() {
+        let (cache, temp) = create_test_cache();
+        let url = "https://api.example.com/openapi.json";
+        let paths: serde_json::Map<String, Value> = (0..200)
+            .map(|i| {
+                (
+                    format!("/resource/{}", i),
+                    serde_json::json!({"get": {"summary": "Fetch the resource"}}),
+                )
+            })
+            .collect();
+        let schema = serde_json::json!({"openapi": "3.0", "paths": paths});
+        cache.put(url, &schema).unwrap();
+
+        let key = cache.storage.generate_cache_key(url);
+        assert!(key.ends_with(".json.zst"));
+        assert!(temp.path().join(&key).exists());
+        assert_eq!(
+            cache
+                .storage
+                .read_entry(&temp.path().join(&key))
+                .unwrap()
+                .0
+                .schema,
+            schema
+        );
+
+        let stats = cache.stats().unwrap();
+        assert!(stats.disk_size > 0);
+        assert!(stats.disk_size < stats.total_size);
+    }
+
+    #[test]
+    fn test_encrypted_entries_need_the_key() {
+        let temp = TempDir::new().unwrap();
+        let config = |key: Option<&str>| CacheConfig {
+            location: temp.path().to_path_buf(),
+            encryption_key: key.map(ToString::to_string),
+            ..CacheConfig::default()
+        };
+        let url = "https://internal.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0", "paths": {"/internal-admin": {}}});
+
+        // A plain entry is encrypted once a key is configured
+        SchemaCache::new(config(None))
+            .unwrap()
+            .put(url, &schema)
+            .unwrap();
+        let cache = SchemaCache::new(config(Some("cache-key-one"))).unwrap();
+        let path = temp.path().join(cache.storage.generate_cache_key(url));
+        // Skip the in-process memory tier so the file itself is read
+        let forget = || memory::shared().lock().unwrap().remove(&path);
+        forget();
+        assert!(cache.get(url).unwrap().is_hit());
+
+        let sealed = fs::read(&path).unwrap();
+        assert!(sealed.starts_with(encryption::SEALED_MAGIC));
+        assert!(!String::from_utf8_lossy(&sealed).contains("internal-admin"));
+
+        forget();
+        assert_eq!(cache.get_stale(url).unwrap().unwrap().schema, schema);
+        forget();
+        let wrong = SchemaCache::new(config(Some("cache-key-two"))).unwrap();
+        assert!(wrong.get(url).unwrap().is_miss());
+        forget();
+        assert!(SchemaCache::new(config(None))
+            .unwrap()
+            .get(url)
+            .unwrap()
+            .is_miss());
+    }
+
+    #[test]
+    fn test_plain_entries_are_migrated_on_read() {
+        let (cache, temp) = create_test_cache();
+        let url = "https://legacy.example.com/openapi.json";
+        let schema = serde_json::json!({"openapi": "3.0"});
+
+        let key = cache.storage.generate_cache_key(url);
+        let legacy = cache.storage.legacy_path(&key).unwrap();
+        let entry = CacheEntry::new(schema.clone(), 3600, "openapi".to_string());
+        fs::write(&legacy, serde_json::to_vec_pretty(&entry).unwrap()).unwrap();
+
+        match cache.get(url).unwrap() {
+            CacheResult::Hit(value) => assert_eq!(value, schema),
+            _ => panic!("Expected cache hit from plain entry"),
+        }
+        assert!(!legacy.exists());
+        assert!(temp.path().join(&key).exists());
+    }
+
+    #[test]
+    fn test_protocol_detection() {
+        let (cache, _temp) = create_test_cache();
+
+        assert_eq!(
+            cache.detect_protocol("https://api.example.com/openapi.json"),
+            "openapi"
+        );
+        assert_eq!(cache.detect_protocol("grpc://api.example.com"), "grpc");
+        assert_eq!(
+            cache.detect_protocol("https://api.example.com/graphql"),
+            "graphql"
+        );
+        assert_eq!(cache.detect_protocol("https://api.example.com/mcp"), "mcp");
+        assert_eq!(
+            cache.detect_protocol("grpc://api.example.com#detection=adapter"),
+            "detection"
+        );
+    }
+
+    #[test]
+    fn test_cache_clear_protocol() {
+        let (cache, _temp) = create_test_cache();
+        let schema = "https://api.example.com/openapi.json";
+        let detection = "https://api.example.com#detection=adapter";
+        cache
+            .put(schema, &serde_json::json!({"openapi": "3.0"}))
+            .unwrap();
+        cache
+            .put(detection, &serde_json::json!({"adapter": "openapi"}))
+            .unwrap();
+
+        assert_eq!(cache.clear_protocol("detection").unwrap(), 1);
+        assert!(cache.get(detection).unwrap().is_miss());
+        assert!(cache.get(schema).unwrap().is_hit());
+    }
+}
@@ -0,0 +1,589 @@
+//! Recorded interactions (`--record` / `--replay`)
+//!
+//! With `--record <cassette>` every call an adapter makes is added to a JSON
+//! cassette, along with the schemas the adapter used: the protocol,
+//! endpoint, operation and arguments of the call, and the response data or
+//! the rejection the endpoint answered with. Recording into an existing
+//! cassette adds to it, so the commands of a script can share one.
+//!
+//! With `--replay <cassette>` nothing goes over the network. Replay implies
+//! offline mode: schemas come from the cassette instead of the cache, and
+//! each call is answered with the recorded response of the same protocol,
+//! endpoint, operation and arguments. Calls repeated with the same arguments
+//! in one process get their recordings in order, then the last one again. A call the
+//! cassette lacks fails with [`UxcError::OfflineMiss`].
+//!
+//! Arguments are stored with credentials masked as in the call history, and
+//! matched the same way. Responses are stored with registered secrets (see
+//! [`crate::masking`]) masked wherever they appear, and with credential
+//! headers such as `Set-Cookie` masked as in `--trace`. Timeouts and network failures are not recorded, as
+//! they are not responses.
+
+use crate::adapters::{ExecutionMetadata, ExecutionResult, ProtocolType};
+use crate::cache::{Cache, CacheEntry, CacheResult, CacheStats, CacheValidators, SnapshotStore};
+use crate::error::{ErrorDetail, UxcError};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Format version written to new cassettes
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// Recorded schemas and calls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    #[serde(default)]
+    pub version: u32,
+
+    /// Schemas by cache key
+    #[serde(default)]
+    pub schemas: BTreeMap<String, Value>,
+
+    /// Calls, in the order they were made
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+/// One recorded call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub protocol: String,
+    pub endpoint: String,
+    pub operation: String,
+
+    /// Arguments, credentials masked
+    #[serde(default)]
+    pub args: Value,
+
+    #[serde(flatten)]
+    pub response: Response,
+}
+
+/// What the endpoint answered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    /// The call succeeded
+    Result {
+        data: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<u16>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        headers: Vec<(String, String)>,
+        #[serde(default)]
+        duration_ms: u64,
+    },
+
+    /// The endpoint rejected the call
+    Error {
+        message: String,
+        detail: ErrorDetail,
+    },
+}
+
+impl Cassette {
+    /// Load a cassette written by `--record`
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            UxcError::InvalidArguments(format!("Failed to read cassette {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            UxcError::InvalidArguments(format!("Invalid cassette {}: {}", path.display(), e)).into()
+        })
+    }
+
+    /// Write the cassette as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?).map_err(|e| {
+            UxcError::GenericError(anyhow::anyhow!(
+                "Failed to write cassette {}: {}",
+                path.display(),
+                e
+            ))
+            .into()
+        })
+    }
+
+    /// Indexes of the interactions recorded for a call, in recording order
+    pub fn matches(
+        &self,
+        protocol: ProtocolType,
+        endpoint: &str,
+        operation: &str,
+        args: &Value,
+    ) -> Vec<usize> {
+        self.interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| {
+                interaction.protocol == protocol.as_str()
+                    && interaction.endpoint == endpoint
+                    && interaction.operation == operation
+                    && &interaction.args == args
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+impl Response {
+    /// The response as a call outcome
+    pub fn to_result(&self, operation: &str) -> Result<ExecutionResult> {
+        match self {
+            Response::Result {
+                data,
+                status,
+                headers,
+                duration_ms,
+            } => Ok(ExecutionResult {
+                data: data.clone(),
+                metadata: ExecutionMetadata {
+                    duration_ms: *duration_ms,
+                    operation: operation.to_string(),
+                    response_headers: headers.clone(),
+                    status: *status,
+                },
+            }),
+            Response::Error { message, detail } => {
+                Err(UxcError::call_failed(message.clone(), detail.clone()).into())
+            }
+        }
+    }
+
+    /// The response a call outcome records, `None` when the endpoint did not
+    /// answer. Secrets in it are masked.
+    pub fn of(outcome: &Result<ExecutionResult>) -> Option<Self> {
+        match outcome {
+            Ok(result) => Some(Response::Result {
+                data: masked(&result.data),
+                status: result.metadata.status,
+                headers: result
+                    .metadata
+                    .response_headers
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = crate::trace::redact(name, value);
+                        (name.clone(), crate::masking::mask(&value))
+                    })
+                    .collect(),
+                duration_ms: result.metadata.duration_ms,
+            }),
+            Err(err) => err.chain().find_map(|cause| match cause.downcast_ref() {
+                Some(UxcError::CallFailed { detail, .. }) => Some(Response::Error {
+                    message: crate::masking::mask(&err.to_string()),
+                    detail: serde_json::to_value(detail)
+                        .ok()
+                        .and_then(|value| serde_json::from_value(masked(&value)).ok())
+                        .unwrap_or_else(|| detail.clone()),
+                }),
+                _ => None,
+            }),
+        }
+    }
+}
+
+/// `value` with registered secrets masked in every string
+fn masked(value: &Value) -> Value {
+    match value {
+        Value::String(text) => Value::String(crate::masking::mask(text)),
+        Value::Array(items) => Value::Array(items.iter().map(masked).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(field, item)| (field.clone(), masked(item)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Whether the cassette is being written or served
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+struct Active {
+    mode: Mode,
+    path: PathBuf,
+    cassette: Cassette,
+    /// Replay: interactions already served
+    served: Vec<bool>,
+}
+
+static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+
+fn active() -> std::sync::MutexGuard<'static, Option<Active>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Record the calls of this process to the cassette at `path`, after the
+/// ones it already holds
+pub fn start_recording(path: &Path) -> Result<()> {
+    let cassette = if path.exists() {
+        Cassette::load(path)?
+    } else {
+        Cassette {
+            version: CASSETTE_VERSION,
+            ..Default::default()
+        }
+    };
+    cassette.save(path)?;
+    *active() = Some(Active {
+        mode: Mode::Record,
+        path: path.to_path_buf(),
+        cassette,
+        served: Vec::new(),
+    });
+    Ok(())
+}
+
+/// Answer the calls of this process from the cassette at `path`
+pub fn start_replay(path: &Path) -> Result<()> {
+    let cassette = Cassette::load(path)?;
+    *active() = Some(Active {
+        mode: Mode::Replay,
+        path: path.to_path_buf(),
+        served: vec![false; cassette.interactions.len()],
+        cassette,
+    });
+    Ok(())
+}
+
+/// Whether a cassette is being written or served
+pub fn mode() -> Option<Mode> {
+    active().as_ref().map(|active| active.mode)
+}
+
+/// Arguments as a cassette stores and matches them
+fn recorded_args(args: &HashMap<String, Value>) -> Value {
+    let args: serde_json::Map<String, Value> = args
+        .iter()
+        .map(|(name, value)| (name.clone(), crate::history::redact(name, value)))
+        .collect();
+    Value::Object(args)
+}
+
+/// Make a call through the cassette: `call` runs unless it is replayed, and
+/// its response is written down when recording
+pub async fn execute<F, Fut>(
+    protocol: ProtocolType,
+    endpoint: &str,
+    operation: &str,
+    args: HashMap<String, Value>,
+    call: F,
+) -> Result<ExecutionResult>
+where
+    F: FnOnce(HashMap<String, Value>) -> Fut,
+    Fut: Future<Output = Result<ExecutionResult>>,
+{
+    let Some(mode) = mode() else {
+        return call(args).await;
+    };
+    let key = recorded_args(&args);
+
+    if mode == Mode::Replay {
+        let response = active().as_mut().and_then(|active| {
+            let matches = active.cassette.matches(protocol, endpoint, operation, &key);
+            let index = matches
+                .iter()
+                .copied()
+                .find(|index| !active.served[*index])
+                .or(matches.last().copied())?;
+            active.served[index] = true;
+            Some((
+                active.cassette.interactions[index].response.clone(),
+                active.path.clone(),
+            ))
+        });
+        return match response {
+            Some((response, path)) => {
+                crate::explain::record(
+                    "replay",
+                    format!("{} answered from {}", operation, path.display()),
+                );
+                response.to_result(operation)
+            }
+            None => Err(crate::cache::offline_miss(format!(
+                "no recorded {} call of {} with these arguments",
+                operation, endpoint
+            ))),
+        };
+    }
+
+    let outcome = call(args).await;
+    if let Some(response) = Response::of(&outcome) {
+        let mut guard = active();
+        if let Some(active) = guard.as_mut() {
+            active.cassette.interactions.push(Interaction {
+                protocol: protocol.as_str().to_string(),
+                endpoint: endpoint.to_string(),
+                operation: operation.to_string(),
+                args: key,
+                response,
+            });
+            if let Err(e) = active.cassette.save(&active.path) {
+                tracing::warn!("{:#}", e);
+            }
+        }
+    }
+    outcome
+}
+
+fn record_schema(key: &str, schema: &Value) {
+    let mut guard = active();
+    let Some(active) = guard.as_mut().filter(|active| active.mode == Mode::Record) else {
+        return;
+    };
+    if active.cassette.schemas.get(key) == Some(schema) {
+        return;
+    }
+    active
+        .cassette
+        .schemas
+        .insert(key.to_string(), schema.clone());
+    if let Err(e) = active.cassette.save(&active.path) {
+        tracing::warn!("{:#}", e);
+    }
+}
+
+fn replayed_schema(key: &str) -> Option<Value> {
+    active()
+        .as_ref()
+        .and_then(|active| active.cassette.schemas.get(key).cloned())
+}
+
+/// `cache`, or a cache that writes the schemas it holds to the cassette when
+/// recording, or serves them from the cassette when replaying
+pub fn wrap_cache(cache: Arc<dyn Cache>) -> Arc<dyn Cache> {
+    match mode() {
+        Some(mode) => Arc::new(CassetteCache { inner: cache, mode }),
+        None => cache,
+    }
+}
+
+/// Schema cache in front of a cassette. Entry management (`uxc cache ...`)
+/// still applies to the real cache.
+struct CassetteCache {
+    inner: Arc<dyn Cache>,
+    mode: Mode,
+}
+
+impl Cache for CassetteCache {
+    fn get(&self, url: &str) -> Result<CacheResult> {
+        if self.mode == Mode::Replay {
+            return Ok(replayed_schema(url).map_or(CacheResult::Miss, CacheResult::Hit));
+        }
+        let result = self.inner.get(url)?;
+        if let CacheResult::Hit(schema) = &result {
+            record_schema(url, schema);
+        }
+        Ok(result)
+    }
+
+    fn get_stale(&self, url: &str) -> Result<Option<CacheEntry>> {
+        if self.mode == Mode::Replay {
+            return Ok(None);
+        }
+        let entry = self.inner.get_stale(url)?;
+        if let Some(entry) = &entry {
+            record_schema(url, &entry.schema);
+        }
+        Ok(entry)
+    }
+
+    fn put(&self, url: &str, schema: &Value) -> Result<()> {
+        if self.mode == Mode::Replay {
+            return Ok(());
+        }
+        record_schema(url, schema);
+        self.inner.put(url, schema)
+    }
+
+    fn put_with_validators(
+        &self,
+        url: &str,
+        schema: &Value,
+        validators: &CacheValidators,
+    ) -> Result<()> {
+        if self.mode == Mode::Replay {
+            return Ok(());
+        }
+        record_schema(url, schema);
+        self.inner.put_with_validators(url, schema, validators)
+    }
+
+    fn refresh(&self, url: &str) -> Result<()> {
+        self.inner.refresh(url)
+    }
+
+    fn invalidate(&self, url: &str) -> Result<()> {
+        self.inner.invalidate(url)
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.inner.clear()
+    }
+
+    fn clear_protocol(&self, protocol: &str) -> Result<usize> {
+        self.inner.clear_protocol(protocol)
+    }
+
+    fn compact(&self) -> Result<usize> {
+        self.inner.compact()
+    }
+
+    fn stats(&self) -> Result<CacheStats> {
+        self.inner.stats()
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.mode == Mode::Replay || self.inner.is_enabled()
+    }
+
+    fn stale_while_revalidate(&self) -> bool {
+        self.mode == Mode::Record && self.inner.stale_while_revalidate()
+    }
+
+    fn snapshots(&self) -> Option<&SnapshotStore> {
+        self.inner.snapshots()
+    }
+
+    fn as_of(&self) -> Option<u64> {
+        self.inner.as_of()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn responses_round_trip_through_a_cassette() {
+        let ok: Result<ExecutionResult> = Ok(ExecutionResult {
+            data: json!([{ "name": "Rex" }]),
+            metadata: ExecutionMetadata {
+                duration_ms: 12,
+                operation: "get:/pets".to_string(),
+                response_headers: vec![("x-total".to_string(), "1".to_string())],
+                status: Some(200),
+            },
+        });
+        let rejected: Result<ExecutionResult> = Err(UxcError::call_failed(
+            "HTTP 404: not found",
+            ErrorDetail::http(404, "not found"),
+        )
+        .into());
+        let timed_out: Result<ExecutionResult> =
+            Err(UxcError::Timeout("no result within 1s".to_string()).into());
+        assert!(Response::of(&timed_out).is_none());
+
+        let args = |id: &str, token: &str| {
+            recorded_args(&HashMap::from([
+                ("id".to_string(), json!(id)),
+                ("api_token".to_string(), json!(token)),
+            ]))
+        };
+        let interaction = |args, outcome| Interaction {
+            protocol: "openapi".to_string(),
+            endpoint: "https://api.example.com".to_string(),
+            operation: "get:/pets".to_string(),
+            args,
+            response: Response::of(outcome).unwrap(),
+        };
+        let cassette = Cassette {
+            version: CASSETTE_VERSION,
+            schemas: BTreeMap::from([("https://api.example.com".to_string(), json!({}))]),
+            interactions: vec![
+                interaction(args("1", "s3cret-1"), &ok),
+                interaction(args("2", "s3cret-1"), &rejected),
+            ],
+        };
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("pets.cassette.json");
+        cassette.save(&path).unwrap();
+        let loaded = Cassette::load(&path).unwrap();
+        assert_eq!(loaded, cassette);
+        assert_eq!(loaded.interactions[0].args["api_token"], "***");
+
+        // Credentials are masked, so another token replays the same call
+        let found = loaded.matches(
+            ProtocolType::OpenAPI,
+            "https://api.example.com",
+            "get:/pets",
+            &args("1", "other-token"),
+        );
+        assert_eq!(found, vec![0]);
+        let replayed = loaded.interactions[0]
+            .response
+            .to_result("get:/pets")
+            .unwrap();
+        assert_eq!(replayed.data, json!([{ "name": "Rex" }]));
+        assert_eq!(replayed.metadata.status, Some(200));
+
+        let err = loaded.interactions[1]
+            .response
+            .to_result("get:/pets")
+            .unwrap_err();
+        assert_eq!(crate::error::http_status_of(&err), Some(404));
+        assert!(loaded
+            .matches(
+                ProtocolType::GraphQL,
+                "https://api.example.com",
+                "get:/pets",
+                &args("1", "x")
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn recorded_responses_mask_secrets() {
+        crate::masking::register("cassette-secret-7f3a");
+        let ok: Result<ExecutionResult> = Ok(ExecutionResult {
+            data: json!({ "echo": { "auth": "Bearer cassette-secret-7f3a" } }),
+            metadata: ExecutionMetadata {
+                duration_ms: 3,
+                operation: "post:/echo".to_string(),
+                response_headers: vec![
+                    ("set-cookie".to_string(), "SID=abc123".to_string()),
+                    (
+                        "x-echo".to_string(),
+                        "token=cassette-secret-7f3a".to_string(),
+                    ),
+                ],
+                status: Some(200),
+            },
+        });
+        let rejected: Result<ExecutionResult> = Err(UxcError::call_failed(
+            "HTTP 401: bad key cassette-secret-7f3a",
+            ErrorDetail::http(401, "key cassette-secret-7f3a is revoked"),
+        )
+        .into());
+
+        let recorded =
+            serde_json::to_string(&[Response::of(&ok).unwrap(), Response::of(&rejected).unwrap()])
+                .unwrap();
+        assert!(!recorded.contains("cassette-secret-7f3a"));
+        assert!(!recorded.contains("SID=abc123"));
+
+        let replayed = Response::of(&ok).unwrap().to_result("post:/echo").unwrap();
+        assert_eq!(replayed.data, json!({ "echo": { "auth": "Bearer ***" } }));
+        assert_eq!(
+            replayed.metadata.response_headers[1],
+            ("x-echo".to_string(), "token=***".to_string())
+        );
+        match Response::of(&rejected).unwrap() {
+            Response::Error { detail, .. } => {
+                assert_eq!(detail, ErrorDetail::http(401, "key *** is revoked"))
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+}
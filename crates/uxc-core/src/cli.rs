@@ -4,11 +4,10 @@
 //! It provides abstractions and core logic that can be tested independently
 //! of the main binary entry point.
 
-use crate::adapters::{Operation, OperationDetail};
+use crate::adapters::Operation;
 use crate::auth::{Profile, Profiles};
 use crate::cache::CacheConfig;
 use crate::error::UxcError;
-use crate::output::OutputEnvelope;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -83,7 +82,9 @@ impl AuthProfileLoader for DefaultAuthProfileLoader {
                 Ok(profile) => Ok(Some(profile.clone())),
                 Err(e) => {
                     if !profile_explicitly_selected && profile_name == "default" {
-                        tracing::info!("No 'default' profile found, continuing without authentication");
+                        tracing::info!(
+                            "No 'default' profile found, continuing without authentication"
+                        );
                         Ok(None)
                     } else {
                         Err(e)
@@ -124,7 +125,7 @@ impl CacheConfigBuilder {
         } else if let Some(ttl) = cache_ttl {
             CacheConfig {
                 ttl,
-                ..Default::default()
+                ..CacheConfig::load_from_file().unwrap_or_default()
             }
         } else {
             CacheConfig::load_from_file().unwrap_or_default()
@@ -157,12 +158,7 @@ impl ArgumentParser {
                 .into());
             }
         } else {
-            for arg in args {
-                let parts: Vec<&str> = arg.splitn(2, '=').collect();
-                if parts.len() == 2 {
-                    args_map.insert(parts[0].to_string(), serde_json::json!(parts[1]));
-                }
-            }
+            args_map = crate::arguments::parse(&args)?;
         }
 
         Ok(args_map)
@@ -326,6 +322,7 @@ mod tests {
             description: Some("List users".to_string()),
             parameters: vec![],
             return_type: Some("User[]".to_string()),
+            tags: Vec::new(),
         };
 
         let summary = to_operation_summary("openapi", &operation);
@@ -342,15 +339,15 @@ mod tests {
             operation_id: "query/viewer".to_string(),
             display_name: "viewer".to_string(),
             description: None,
-            parameters: vec![
-                crate::adapters::Parameter {
-                    name: "id".to_string(),
-                    param_type: "ID!".to_string(),
-                    required: true,
-                    description: Some("User ID".to_string()),
-                }
-            ],
+            parameters: vec![crate::adapters::Parameter {
+                name: "id".to_string(),
+                param_type: "ID!".to_string(),
+                required: true,
+                description: Some("User ID".to_string()),
+                location: None,
+            }],
             return_type: Some("User".to_string()),
+            tags: Vec::new(),
         };
 
         let summary = to_operation_summary("graphql", &operation);
@@ -376,7 +373,7 @@ mod tests {
     fn test_cache_config_builder_default() {
         let config = CacheConfigBuilder::from_cli_flags(false, None);
         // Should load from file or use defaults
-        assert!(config.enabled || config.ttl > 0 || !config.enabled);
+        assert!(!config.enabled || config.ttl > 0);
     }
 
     #[test]
@@ -400,7 +397,10 @@ mod tests {
     #[test]
     fn test_mock_auth_loader() {
         let loader = MockAuthLoader {
-            profile: Some(Profile::new("key".to_string(), crate::auth::AuthType::Bearer)),
+            profile: Some(Profile::new(
+                "key".to_string(),
+                crate::auth::AuthType::Bearer,
+            )),
         };
         let profile = loader.load_profile(Some("test".to_string())).unwrap();
         assert!(profile.is_some());
@@ -421,6 +421,7 @@ mod tests {
             description: None,
             parameters: vec![],
             return_type: None,
+            tags: Vec::new(),
         };
 
         assert_eq!(
@@ -445,6 +446,7 @@ mod tests {
             description: None,
             parameters: vec![],
             return_type: None,
+            tags: Vec::new(),
         };
 
         assert_eq!(
@@ -458,6 +460,7 @@ mod tests {
             description: None,
             parameters: vec![],
             return_type: None,
+            tags: Vec::new(),
         };
         assert_eq!(
             to_operation_summary("graphql", &query_op).protocol_kind,
@@ -470,6 +473,7 @@ mod tests {
             description: None,
             parameters: vec![],
             return_type: None,
+            tags: Vec::new(),
         };
         assert_eq!(
             to_operation_summary("graphql", &mutation_op).protocol_kind,
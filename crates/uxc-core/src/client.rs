@@ -0,0 +1,240 @@
+//! Library client
+//!
+//! A [`Client`] calls one endpoint, whose protocol is detected once, when the
+//! client is built. It is what the `uxc` command line does for a call, for
+//! Rust programs that embed uxc:
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use serde_json::json;
+//! use uxc_core::Client;
+//!
+//! let client = Client::builder()
+//!     .endpoint("https://petstore3.swagger.io/api/v3")
+//!     .profile("petstore")
+//!     .build()
+//!     .await?;
+//! for operation in client.list_operations().await? {
+//!     println!("{}", operation.operation_id);
+//! }
+//! let pet: serde_json::Value = client
+//!     .execute_as("get:/pet/{petId}", json!({ "petId": 1 }))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Calls go through the client's [`Pipeline`]: the [`AuthMiddleware`] of its
+//! profile, then the middleware added with [`ClientBuilder::middleware`], in
+//! order. Schemas are cached as configured in `~/.uxc/config.toml` unless
+//! [`ClientBuilder::cache`] says otherwise.
+
+use crate::adapters::{
+    Adapter, AdapterEnum, DetectionOptions, ExecutionResult, Operation, OperationDetail,
+    ProtocolDetector, ProtocolType,
+};
+use crate::auth::{Profile, Profiles};
+use crate::cache::{self, CacheConfig};
+use crate::error::UxcError;
+use crate::middleware::{AuthMiddleware, Middleware, Pipeline};
+use anyhow::Result;
+use futures::stream::{self, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Settings of a [`Client`], from [`Client::builder`]
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    endpoint: Option<String>,
+    profile: Option<String>,
+    auth: Option<Profile>,
+    cache: Option<CacheConfig>,
+    schema_url: Option<String>,
+    protocol: Option<ProtocolType>,
+    pipeline: Pipeline,
+}
+
+impl ClientBuilder {
+    /// URL of the endpoint, scheme included (or an MCP server command)
+    pub fn endpoint(mut self, url: impl Into<String>) -> Self {
+        self.endpoint = Some(url.into());
+        self
+    }
+
+    /// Authenticate with the named profile of `~/.uxc/profiles.toml`
+    pub fn profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Authenticate with `profile`, which need not be stored
+    pub fn auth(mut self, profile: Profile) -> Self {
+        self.auth = Some(profile);
+        self
+    }
+
+    /// Cache schemas as `config` says, instead of as `~/.uxc/config.toml` says
+    pub fn cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(config);
+        self
+    }
+
+    /// Read the OpenAPI schema from `url` instead of looking for it
+    pub fn schema_url(mut self, url: impl Into<String>) -> Self {
+        self.schema_url = Some(url.into());
+        self
+    }
+
+    /// Use `protocol` for the endpoint without probing it
+    pub fn protocol(mut self, protocol: ProtocolType) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Run `middleware` around every call, after the middleware already
+    /// added
+    pub fn middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.pipeline.push(middleware);
+        self
+    }
+
+    /// Detect the protocol of the endpoint and log in if the profile needs
+    /// it
+    pub async fn build(self) -> Result<Client> {
+        let url = self
+            .endpoint
+            .ok_or_else(|| UxcError::InvalidArguments("A client needs an endpoint".to_string()))?;
+        let profile = match (self.auth, self.profile) {
+            (Some(profile), _) => Some(profile),
+            (None, Some(name)) => Some(Profiles::load_profiles()?.get_profile(&name)?.clone()),
+            (None, None) => None,
+        };
+        let cache = cache::create_cache(match self.cache {
+            Some(config) => config,
+            None => CacheConfig::load_from_file()?,
+        })?;
+
+        let options = DetectionOptions {
+            schema_url: self.schema_url,
+            cache: Some(cache.clone()),
+            protocol: self.protocol,
+            ..Default::default()
+        };
+        let mut adapter = ProtocolDetector::new()
+            .detect_adapter_with_options(&url, &options)
+            .await?
+            .with_cache(cache);
+
+        let mut pipeline = Pipeline::new();
+        if let Some(profile) = profile {
+            let auth = AuthMiddleware::new(profile.clone());
+            auth.ensure().await?;
+            pipeline.push(Arc::new(auth));
+            adapter = adapter.with_auth(profile);
+        }
+        for middleware in self.pipeline.layers() {
+            pipeline.push(middleware.clone());
+        }
+        Ok(Client {
+            url,
+            adapter,
+            pipeline,
+        })
+    }
+}
+
+/// Client of one endpoint
+pub struct Client {
+    url: String,
+    adapter: AdapterEnum,
+    pipeline: Pipeline,
+}
+
+impl Client {
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.url
+    }
+
+    /// Protocol the endpoint was detected to speak
+    pub fn protocol(&self) -> ProtocolType {
+        self.adapter.protocol_type()
+    }
+
+    /// Adapter serving the endpoint, for what the client does not cover
+    pub fn adapter(&self) -> &AdapterEnum {
+        &self.adapter
+    }
+
+    pub async fn list_operations(&self) -> Result<Vec<Operation>> {
+        self.adapter.list_operations(&self.url).await
+    }
+
+    pub async fn describe(&self, operation: &str) -> Result<OperationDetail> {
+        self.adapter.describe_operation(&self.url, operation).await
+    }
+
+    /// Call `operation` with `args`, a JSON object (or null for none)
+    pub async fn execute(&self, operation: &str, args: Value) -> Result<ExecutionResult> {
+        self.pipeline
+            .execute(&self.adapter, &self.url, operation, arguments(args)?)
+            .await
+    }
+
+    /// Call `operation` and decode its result as a `T`
+    pub async fn execute_as<T: DeserializeOwned>(&self, operation: &str, args: Value) -> Result<T> {
+        let result = self.execute(operation, args).await?;
+        decode(operation, result.data)
+    }
+
+    /// Call `operation` and yield the items of its result, decoded as `T`:
+    /// each element of a list, or the whole result when it is not a list.
+    /// Results arrive whole, so the first item comes when the call is done.
+    pub fn execute_stream<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        operation: &'a str,
+        args: Value,
+    ) -> impl Stream<Item = Result<T>> + 'a {
+        stream::once(self.execute(operation, args)).flat_map(move |result| {
+            let items: Vec<Result<T>> = match result {
+                Ok(result) => match result.data {
+                    Value::Array(items) => items
+                        .into_iter()
+                        .map(|item| decode(operation, item))
+                        .collect(),
+                    data => vec![decode(operation, data)],
+                },
+                Err(err) => vec![Err(err)],
+            };
+            stream::iter(items)
+        })
+    }
+}
+
+/// Call arguments from a JSON object
+fn arguments(args: Value) -> Result<HashMap<String, Value>> {
+    match args {
+        Value::Object(args) => Ok(args.into_iter().collect()),
+        Value::Null => Ok(HashMap::new()),
+        other => Err(UxcError::InvalidArguments(format!(
+            "Arguments must be a JSON object, not {}",
+            other
+        ))
+        .into()),
+    }
+}
+
+fn decode<T: DeserializeOwned>(operation: &str, data: Value) -> Result<T> {
+    serde_json::from_value(data).map_err(|e| {
+        UxcError::ExecutionFailed(format!(
+            "The result of {} does not have the expected shape: {}",
+            operation, e
+        ))
+        .into()
+    })
+}
@@ -0,0 +1,462 @@
+//! Client code generation (`uxc codegen`)
+//!
+//! The operations of an endpoint, as its adapter describes them, become a
+//! typed client: one type per argument object (from
+//! [`crate::datagen::payload_schema`], nested objects included) and one
+//! function per operation. The generated client makes its calls through the
+//! uxc CLI (`uxc <endpoint> <operation> --json <args>`), so it works the same
+//! for every protocol, with the profiles, caching and retries uxc applies.
+//! Results are untyped JSON: adapters describe what operations take, not
+//! what they return beyond a type name.
+
+mod python;
+mod rust;
+mod typescript;
+
+use crate::adapters::OperationDetail;
+use crate::datagen::payload_schema;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Nesting beyond which argument values are left untyped
+const MAX_DEPTH: usize = 8;
+
+/// Language of a generated client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    TypeScript,
+    Python,
+}
+
+impl Language {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::TypeScript => "typescript",
+            Language::Python => "python",
+        }
+    }
+}
+
+/// Source of a generated client
+#[derive(Debug, Clone)]
+pub struct Generated {
+    pub code: String,
+    pub operations: usize,
+    pub types: usize,
+}
+
+/// Generate a client in `language` for the operations of `endpoint`
+pub fn generate(
+    language: Language,
+    endpoint: &str,
+    protocol: &str,
+    operations: &[OperationDetail],
+) -> Generated {
+    let model = Model::build(endpoint, protocol, operations);
+    let code = match language {
+        Language::Rust => rust::render(&model),
+        Language::TypeScript => typescript::render(&model),
+        Language::Python => python::render(&model),
+    };
+    Generated {
+        code,
+        operations: model.operations.len(),
+        types: model.types.len(),
+    }
+}
+
+/// What every language renders: named argument types and operations
+struct Model {
+    endpoint: String,
+    protocol: String,
+    /// In dependency order: a type comes after the types of its fields
+    types: Vec<TypeDef>,
+    operations: Vec<OperationModel>,
+}
+
+struct OperationModel {
+    id: String,
+    /// Words of the function name (`["get", "users", "id"]`)
+    words: Vec<String>,
+    description: Option<String>,
+    /// Type of the arguments, `None` for operations without any
+    args: Option<Ty>,
+    return_type: Option<String>,
+}
+
+struct TypeDef {
+    name: String,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    /// Name on the wire
+    name: String,
+    ty: Ty,
+    required: bool,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ty {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// Any JSON value
+    Any,
+    /// An object without declared properties
+    Map,
+    Array(Box<Ty>),
+    Enum(Vec<String>),
+    Named(String),
+}
+
+impl Model {
+    fn build(endpoint: &str, protocol: &str, details: &[OperationDetail]) -> Self {
+        let mut model = Model {
+            endpoint: endpoint.to_string(),
+            protocol: protocol.to_string(),
+            types: Vec::new(),
+            operations: Vec::new(),
+        };
+        let mut type_names = HashSet::new();
+        let mut function_names = HashSet::new();
+        for detail in details {
+            let mut words = words(&detail.operation_id);
+            if words.is_empty() {
+                words.push("call".to_string());
+            }
+            let function = unique(&mut function_names, &words.join("_"));
+            let words = function.split('_').map(ToString::to_string).collect();
+
+            let schema = payload_schema(detail);
+            let takes_nothing = schema.get("type").and_then(Value::as_str) == Some("object")
+                && schema
+                    .get("properties")
+                    .and_then(Value::as_object)
+                    .is_none_or(|properties| properties.is_empty())
+                && schema.get("additionalProperties").is_none();
+            let args = (!takes_nothing).then(|| {
+                let name = format!("{}Args", pascal(&function));
+                model.ty(&mut type_names, &schema, &name, 0)
+            });
+            model.operations.push(OperationModel {
+                id: detail.operation_id.clone(),
+                words,
+                description: detail.description.clone().filter(|d| !d.trim().is_empty()),
+                args,
+                return_type: detail.return_type.clone(),
+            });
+        }
+        model
+    }
+
+    /// Type of the values `schema` accepts; objects with properties become
+    /// named types, called `name` unless taken
+    fn ty(&mut self, names: &mut HashSet<String>, schema: &Value, name: &str, depth: usize) -> Ty {
+        if depth > MAX_DEPTH {
+            return Ty::Any;
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            let values: Option<Vec<String>> = values
+                .iter()
+                .map(|value| value.as_str().map(ToString::to_string))
+                .collect();
+            if let Some(values) = values.filter(|values| !values.is_empty()) {
+                return Ty::Enum(values);
+            }
+        }
+        let declared = match schema.get("type") {
+            Some(Value::String(name)) => Some(name.as_str()),
+            Some(Value::Array(names)) => names
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|name| *name != "null"),
+            _ => None,
+        };
+        let properties = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .filter(|properties| !properties.is_empty());
+        match (declared, properties) {
+            (Some("string"), _) => Ty::String,
+            (Some("integer"), _) => Ty::Integer,
+            (Some("number"), _) => Ty::Number,
+            (Some("boolean"), _) => Ty::Boolean,
+            (Some("array"), _) => match schema.get("items") {
+                Some(items) => Ty::Array(Box::new(self.ty(
+                    names,
+                    items,
+                    &format!("{}Item", name),
+                    depth + 1,
+                ))),
+                None => Ty::Array(Box::new(Ty::Any)),
+            },
+            (None | Some("object"), Some(properties)) => {
+                let name = unique(names, name);
+                let required: HashSet<&str> = schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .collect();
+                let fields = properties
+                    .iter()
+                    .map(|(field, property)| Field {
+                        name: field.clone(),
+                        ty: self.ty(
+                            names,
+                            property,
+                            &format!("{}{}", name, pascal(field)),
+                            depth + 1,
+                        ),
+                        required: required.contains(field.as_str()),
+                        description: property
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .filter(|d| !d.trim().is_empty())
+                            .map(ToString::to_string),
+                    })
+                    .collect();
+                self.types.push(TypeDef {
+                    name: name.clone(),
+                    fields,
+                });
+                Ty::Named(name)
+            }
+            (Some("object"), None) => Ty::Map,
+            _ => Ty::Any,
+        }
+    }
+}
+
+/// `name`, or `name2`, `name3`... when it is taken
+fn unique(taken: &mut HashSet<String>, name: &str) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}{}", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Lowercase words of an identifier, split at punctuation and camelCase
+/// humps: `get:/users/{userId}` is `get users user id`
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in text.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// `PascalCase` of an identifier; a leading digit gets a `T` in front
+fn pascal(text: &str) -> String {
+    let name: String = words(text)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    match name.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("T{}", name),
+        Some(_) => name,
+        None => "Value".to_string(),
+    }
+}
+
+/// Lines of a description, without trailing blanks
+fn lines(description: &str) -> impl Iterator<Item = &str> {
+    description.trim().lines().map(str::trim_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::Parameter;
+    use serde_json::json;
+
+    fn pets() -> Vec<OperationDetail> {
+        vec![
+            OperationDetail {
+                operation_id: "post:/pets".to_string(),
+                display_name: "createPet".to_string(),
+                description: Some("Add a pet to the store".to_string()),
+                parameters: Vec::new(),
+                return_type: Some("Pet".to_string()),
+                input_schema: Some(json!({
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string", "description": "Name shown to owners" },
+                        "kind": { "type": "string", "enum": ["cat", "dog"] },
+                        "tags": { "type": "array", "items": { "type": "string" } },
+                        "owner": {
+                            "type": "object",
+                            "properties": { "e-mail": { "type": "string" } }
+                        },
+                        "type": { "type": "integer" }
+                    }
+                })),
+                responses: None,
+                security: None,
+                servers: None,
+                fields: None,
+            },
+            OperationDetail {
+                operation_id: "get:/pets/{petId}".to_string(),
+                display_name: "getPet".to_string(),
+                description: None,
+                parameters: vec![Parameter {
+                    name: "petId".to_string(),
+                    param_type: "integer".to_string(),
+                    required: true,
+                    description: None,
+                    location: None,
+                }],
+                return_type: None,
+                input_schema: None,
+                responses: None,
+                security: None,
+                servers: None,
+                fields: None,
+            },
+            OperationDetail {
+                operation_id: "get:/health".to_string(),
+                display_name: "health".to_string(),
+                description: None,
+                parameters: Vec::new(),
+                return_type: None,
+                input_schema: None,
+                responses: None,
+                security: None,
+                servers: None,
+                fields: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn names_follow_each_language() {
+        assert_eq!(words("get:/users/{userId}"), ["get", "users", "user", "id"]);
+        assert_eq!(words("query/listPets"), ["query", "list", "pets"]);
+        assert_eq!(pascal("e-mail"), "EMail");
+        assert_eq!(pascal("2fa"), "T2fa");
+    }
+
+    #[test]
+    fn argument_objects_become_named_types() {
+        let model = Model::build("https://api.example.com", "openapi", &pets());
+
+        let names: Vec<&str> = model.types.iter().map(|ty| ty.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["PostPetsArgsOwner", "PostPetsArgs", "GetPetsPetIdArgs"]
+        );
+        let args = &model.types[1];
+        let name = args.fields.iter().find(|f| f.name == "name").unwrap();
+        assert!(name.required);
+        assert_eq!(name.description.as_deref(), Some("Name shown to owners"));
+        let kind = args.fields.iter().find(|f| f.name == "kind").unwrap();
+        assert_eq!(kind.ty, Ty::Enum(vec!["cat".into(), "dog".into()]));
+        assert!(model.operations[2].args.is_none());
+    }
+
+    #[test]
+    fn clients_call_every_operation_through_uxc() {
+        let rust = generate(
+            Language::Rust,
+            "https://api.example.com",
+            "openapi",
+            &pets(),
+        );
+        assert_eq!((rust.operations, rust.types), (3, 3));
+        for expected in [
+            "pub const ENDPOINT: &str = \"https://api.example.com\";",
+            "pub struct PostPetsArgs {",
+            "    pub name: String,",
+            "    pub r#type: Option<i64>,",
+            "    #[serde(rename = \"e-mail\")]",
+            "    pub tags: Option<Vec<String>>,",
+            "    pub fn post_pets(&self, args: &PostPetsArgs) -> Result<Value> {",
+            "        self.call(\"post:/pets\", args)",
+            "    pub fn get_health(&self) -> Result<Value> {",
+        ] {
+            assert!(
+                rust.code.contains(expected),
+                "{}\n---\n{}",
+                expected,
+                rust.code
+            );
+        }
+
+        let typescript = generate(
+            Language::TypeScript,
+            "https://api.example.com",
+            "openapi",
+            &pets(),
+        );
+        for expected in [
+            "export interface PostPetsArgs {",
+            "  name: string;",
+            "  kind?: \"cat\" | \"dog\";",
+            "  \"e-mail\"?: string;",
+            "  tags?: string[];",
+            "  postPets(args: PostPetsArgs): Promise<unknown> {",
+            "  getHealth(): Promise<unknown> {",
+        ] {
+            assert!(
+                typescript.code.contains(expected),
+                "{}\n---\n{}",
+                expected,
+                typescript.code
+            );
+        }
+
+        let python = generate(
+            Language::Python,
+            "https://api.example.com",
+            "openapi",
+            &pets(),
+        );
+        for expected in [
+            "PostPetsArgsOwner = TypedDict(\"PostPetsArgsOwner\", {\"e-mail\": NotRequired[str]})",
+            "\"kind\": NotRequired[Literal[\"cat\", \"dog\"]]",
+            "\"tags\": NotRequired[list[str]]",
+            "    def post_pets(self, args: PostPetsArgs) -> Any:",
+            "    def get_health(self) -> Any:",
+        ] {
+            assert!(
+                python.code.contains(expected),
+                "{}\n---\n{}",
+                expected,
+                python.code
+            );
+        }
+    }
+}
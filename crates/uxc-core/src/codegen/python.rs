@@ -0,0 +1,206 @@
+//! Python clients: `TypedDict`s and a `Client` running the uxc CLI
+
+use super::{lines, Model, Ty, TypeDef};
+use std::fmt::Write;
+
+const KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda",
+    "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Longest `TypedDict` kept on one line
+const LINE_WIDTH: usize = 88;
+
+pub(super) fn render(model: &Model) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "\"\"\"Client for {} ({})",
+        docstring(&model.endpoint),
+        model.protocol
+    );
+    out.push_str(
+        r#"
+Generated by `uxc codegen`. Calls run the uxc CLI, which must be on the PATH.
+Requires Python 3.11 or later.
+"""
+
+import json
+import subprocess
+from typing import Any, Literal, NotRequired, TypedDict
+
+"#,
+    );
+    let _ = writeln!(out, "ENDPOINT = {}", string(&model.endpoint));
+
+    for def in &model.types {
+        let _ = writeln!(out);
+        render_type(&mut out, def);
+    }
+
+    out.push_str(
+        r#"
+
+class UxcError(Exception):
+    """A call that uxc reported as failed"""
+
+    def __init__(self, code: str, message: str) -> None:
+        super().__init__(f"{code}: {message}")
+        self.code = code
+        self.message = message
+
+
+class Client:
+    def __init__(self, endpoint: str = ENDPOINT, program: str = "uxc") -> None:
+        self.endpoint = endpoint
+        self.program = program
+"#,
+    );
+    for operation in &model.operations {
+        let _ = writeln!(out);
+        let name = method(&operation.words.join("_"));
+        match &operation.args {
+            Some(args) => {
+                let _ = writeln!(out, "    def {}(self, args: {}) -> Any:", name, ty(args));
+            }
+            None => {
+                let _ = writeln!(out, "    def {}(self) -> Any:", name);
+            }
+        }
+        let mut doc = format!("`{}`", operation.id);
+        if let Some(description) = &operation.description {
+            doc.push_str("\n\n");
+            doc.push_str(description.trim());
+        }
+        if let Some(return_type) = &operation.return_type {
+            let _ = write!(doc, "\n\nReturns `{}`.", return_type);
+        }
+        let doc = docstring(&doc);
+        let mut doc_lines = lines(&doc);
+        let first = doc_lines.next().unwrap_or_default();
+        let rest: Vec<&str> = doc_lines.collect();
+        if rest.is_empty() {
+            let _ = writeln!(out, "        \"\"\"{}\"\"\"", first);
+        } else {
+            let _ = writeln!(out, "        \"\"\"{}", first);
+            for line in rest {
+                if line.is_empty() {
+                    let _ = writeln!(out);
+                } else {
+                    let _ = writeln!(out, "        {}", line);
+                }
+            }
+            let _ = writeln!(out, "        \"\"\"");
+        }
+        let args = if operation.args.is_some() {
+            "args"
+        } else {
+            "{}"
+        };
+        let _ = writeln!(
+            out,
+            "        return self._call({}, {})",
+            string(&operation.id),
+            args
+        );
+    }
+    out.push_str(
+        r#"
+    def _call(self, operation: str, args: Any) -> Any:
+        completed = subprocess.run(
+            [self.program, self.endpoint, operation, "--json", json.dumps(args)],
+            capture_output=True,
+            text=True,
+        )
+        try:
+            envelope = json.loads(completed.stdout)
+        except json.JSONDecodeError:
+            raise UxcError("EXECUTION_FAILED", completed.stderr.strip() or completed.stdout)
+        if envelope.get("ok") is True:
+            return envelope.get("data")
+        error = envelope.get("error") or {}
+        raise UxcError(error.get("code", "EXECUTION_FAILED"), error.get("message", ""))
+"#,
+    );
+    out
+}
+
+/// A `TypedDict` in the functional syntax, which takes any key
+fn render_type(out: &mut String, def: &TypeDef) {
+    let entries: Vec<String> = def
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = ty(&field.ty);
+            if field.required {
+                format!("{}: {}", string(&field.name), ty)
+            } else {
+                format!("{}: NotRequired[{}]", string(&field.name), ty)
+            }
+        })
+        .collect();
+    let name = string(&def.name);
+    let line = format!(
+        "{} = TypedDict({}, {{{}}})",
+        def.name,
+        name,
+        entries.join(", ")
+    );
+    let described = def.fields.iter().any(|field| field.description.is_some());
+    if line.len() <= LINE_WIDTH && !described {
+        let _ = writeln!(out, "{}", line);
+        return;
+    }
+    let _ = writeln!(out, "{} = TypedDict(", def.name);
+    let _ = writeln!(out, "    {},", name);
+    let _ = writeln!(out, "    {{");
+    for (field, entry) in def.fields.iter().zip(&entries) {
+        if let Some(description) = &field.description {
+            for line in lines(description) {
+                let _ = writeln!(out, "        # {}", line);
+            }
+        }
+        let _ = writeln!(out, "        {},", entry);
+    }
+    let _ = writeln!(out, "    }},");
+    let _ = writeln!(out, ")");
+}
+
+fn method(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Text safe inside a triple-quoted string
+fn docstring(text: &str) -> String {
+    text.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"")
+}
+
+fn string(text: &str) -> String {
+    serde_json::Value::String(text.to_string()).to_string()
+}
+
+fn ty(ty: &Ty) -> String {
+    match ty {
+        Ty::String => "str".to_string(),
+        Ty::Integer => "int".to_string(),
+        Ty::Number => "float".to_string(),
+        Ty::Boolean => "bool".to_string(),
+        Ty::Any => "Any".to_string(),
+        Ty::Map => "dict[str, Any]".to_string(),
+        Ty::Array(items) => format!("list[{}]", self::ty(items)),
+        Ty::Enum(values) => format!(
+            "Literal[{}]",
+            values
+                .iter()
+                .map(|value| string(value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Ty::Named(name) => name.clone(),
+    }
+}
@@ -0,0 +1,234 @@
+//! Rust clients: serde structs and a `Client` running the uxc CLI
+
+use super::{lines, words, Field, Model, Ty};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Keywords that cannot be raw identifiers
+const RESERVED: &[&str] = &["crate", "self", "Self", "super"];
+
+pub(super) fn render(model: &Model) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "//! Client for {} ({})",
+        model.endpoint, model.protocol
+    );
+    let _ = writeln!(out, "//!");
+    let _ = writeln!(
+        out,
+        "//! Generated by `uxc codegen`. Calls run the uxc CLI, which must be on the PATH."
+    );
+    out.push_str(
+        r#"
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Command;
+
+"#,
+    );
+    let _ = writeln!(out, "pub const ENDPOINT: &str = {:?};", model.endpoint);
+    out.push_str(
+        r#"
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// A call that uxc reported as failed
+#[derive(Debug, Clone)]
+pub struct CallError {
+    pub code: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for CallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for CallError {}
+"#,
+    );
+
+    for def in &model.types {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]"
+        );
+        let _ = writeln!(out, "pub struct {} {{", def.name);
+        let mut idents = HashSet::new();
+        for field in &def.fields {
+            render_field(&mut out, &mut idents, field);
+        }
+        let _ = writeln!(out, "}}");
+    }
+
+    out.push_str(
+        r#"
+pub struct Client {
+    endpoint: String,
+    program: String,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(ENDPOINT)
+    }
+}
+
+impl Client {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            program: "uxc".to_string(),
+        }
+    }
+
+    /// Run calls with another uxc executable
+    pub fn with_program(mut self, program: impl Into<String>) -> Self {
+        self.program = program.into();
+        self
+    }
+"#,
+    );
+    for operation in &model.operations {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "    /// `{}`", operation.id);
+        if let Some(description) = &operation.description {
+            let _ = writeln!(out, "    ///");
+            for line in lines(description) {
+                doc(&mut out, "    ", line);
+            }
+        }
+        if let Some(return_type) = &operation.return_type {
+            let _ = writeln!(out, "    ///");
+            let _ = writeln!(out, "    /// Returns `{}`.", return_type);
+        }
+        let name = ident(&operation.words.join("_"));
+        match &operation.args {
+            Some(args) => {
+                let _ = writeln!(
+                    out,
+                    "    pub fn {}(&self, args: {}) -> Result<Value> {{",
+                    name,
+                    borrowed(args)
+                );
+                let _ = writeln!(out, "        self.call({:?}, args)", operation.id);
+            }
+            None => {
+                let _ = writeln!(out, "    pub fn {}(&self) -> Result<Value> {{", name);
+                let _ = writeln!(
+                    out,
+                    "        self.call({:?}, &serde_json::json!({{}}))",
+                    operation.id
+                );
+            }
+        }
+        let _ = writeln!(out, "    }}");
+    }
+    out.push_str(
+        r#"
+    fn call(&self, operation: &str, args: &(impl Serialize + ?Sized)) -> Result<Value> {
+        let output = Command::new(&self.program)
+            .arg(&self.endpoint)
+            .arg(operation)
+            .arg("--json")
+            .arg(serde_json::to_string(args)?)
+            .output()?;
+        let envelope: Value = serde_json::from_slice(&output.stdout)?;
+        if envelope["ok"] == Value::Bool(true) {
+            return Ok(envelope["data"].clone());
+        }
+        let error = &envelope["error"];
+        Err(Box::new(CallError {
+            code: error["code"].as_str().unwrap_or("EXECUTION_FAILED").to_string(),
+            message: error["message"].as_str().unwrap_or_default().to_string(),
+        }))
+    }
+}
+"#,
+    );
+    out
+}
+
+fn render_field(out: &mut String, idents: &mut HashSet<String>, field: &Field) {
+    if let Some(description) = &field.description {
+        for line in lines(description) {
+            doc(out, "    ", line);
+        }
+    }
+    if let Ty::Enum(values) = &field.ty {
+        let values: Vec<String> = values.iter().map(|value| format!("`{}`", value)).collect();
+        if field.description.is_some() {
+            let _ = writeln!(out, "    ///");
+        }
+        let _ = writeln!(out, "    /// One of {}.", values.join(", "));
+    }
+
+    let mut name = words(&field.name).join("_");
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("field_{}", name);
+    }
+    let name = ident(&super::unique(idents, &name));
+    if name.trim_start_matches("r#") != field.name {
+        let _ = writeln!(out, "    #[serde(rename = {:?})]", field.name);
+    }
+    if field.required {
+        let _ = writeln!(out, "    pub {}: {},", name, ty(&field.ty));
+    } else {
+        let _ = writeln!(
+            out,
+            "    #[serde(default, skip_serializing_if = \"Option::is_none\")]"
+        );
+        let _ = writeln!(out, "    pub {}: Option<{}>,", name, ty(&field.ty));
+    }
+}
+
+fn doc(out: &mut String, indent: &str, line: &str) {
+    if line.is_empty() {
+        let _ = writeln!(out, "{}///", indent);
+    } else {
+        let _ = writeln!(out, "{}/// {}", indent, line);
+    }
+}
+
+/// `name`, made raw or suffixed when it is a keyword
+fn ident(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn ty(ty: &Ty) -> String {
+    match ty {
+        Ty::String | Ty::Enum(_) => "String".to_string(),
+        Ty::Integer => "i64".to_string(),
+        Ty::Number => "f64".to_string(),
+        Ty::Boolean => "bool".to_string(),
+        Ty::Any => "Value".to_string(),
+        Ty::Map => "serde_json::Map<String, Value>".to_string(),
+        Ty::Array(items) => format!("Vec<{}>", self::ty(items)),
+        Ty::Named(name) => name.clone(),
+    }
+}
+
+/// Type of an argument passed by reference
+fn borrowed(args: &Ty) -> String {
+    match args {
+        Ty::String | Ty::Enum(_) => "&str".to_string(),
+        Ty::Array(items) => format!("&[{}]", ty(items)),
+        other => format!("&{}", ty(other)),
+    }
+}
@@ -0,0 +1,190 @@
+//! TypeScript clients: interfaces and a `Client` running the uxc CLI (Node.js)
+
+use super::{lines, Model, Ty};
+use std::fmt::Write;
+
+pub(super) fn render(model: &Model) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "// Client for {} ({})", model.endpoint, model.protocol);
+    let _ = writeln!(
+        out,
+        "// Generated by `uxc codegen`. Calls run the uxc CLI, which must be on the PATH."
+    );
+    out.push_str(
+        r#"
+import { execFile } from "node:child_process";
+
+"#,
+    );
+    let _ = writeln!(out, "export const ENDPOINT = {};", string(&model.endpoint));
+    out.push_str(
+        r#"
+/** A call that uxc reported as failed */
+export class UxcError extends Error {
+  constructor(
+    readonly code: string,
+    message: string,
+  ) {
+    super(`${code}: ${message}`);
+    this.name = "UxcError";
+  }
+}
+"#,
+    );
+
+    for def in &model.types {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "export interface {} {{", def.name);
+        for field in &def.fields {
+            if let Some(description) = &field.description {
+                doc(&mut out, "  ", description);
+            }
+            let _ = writeln!(
+                out,
+                "  {}{}: {};",
+                property(&field.name),
+                if field.required { "" } else { "?" },
+                ty(&field.ty)
+            );
+        }
+        let _ = writeln!(out, "}}");
+    }
+
+    out.push_str(
+        r#"
+export class Client {
+  constructor(
+    readonly endpoint: string = ENDPOINT,
+    readonly program: string = "uxc",
+  ) {}
+"#,
+    );
+    for operation in &model.operations {
+        let _ = writeln!(out);
+        let mut comment = format!("`{}`", operation.id);
+        if let Some(description) = &operation.description {
+            comment.push_str("\n\n");
+            comment.push_str(description.trim());
+        }
+        if let Some(return_type) = &operation.return_type {
+            let _ = write!(comment, "\n\n@returns `{}`", return_type);
+        }
+        doc(&mut out, "  ", &comment);
+        let name = camel(&operation.words);
+        match &operation.args {
+            Some(args) => {
+                let _ = writeln!(out, "  {}(args: {}): Promise<unknown> {{", name, ty(args));
+                let _ = writeln!(
+                    out,
+                    "    return this.call({}, args);",
+                    string(&operation.id)
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  {}(): Promise<unknown> {{", name);
+                let _ = writeln!(
+                    out,
+                    "    return this.call({}, {{}});",
+                    string(&operation.id)
+                );
+            }
+        }
+        let _ = writeln!(out, "  }}");
+    }
+    out.push_str(
+        r#"
+  private call(operation: string, args: unknown): Promise<unknown> {
+    const argv = [this.endpoint, operation, "--json", JSON.stringify(args)];
+    return new Promise((resolve, reject) => {
+      execFile(this.program, argv, { maxBuffer: 64 * 1024 * 1024 }, (failure, stdout) => {
+        let envelope;
+        try {
+          envelope = JSON.parse(stdout);
+        } catch {
+          reject(failure ?? new Error(`uxc printed no envelope: ${stdout}`));
+          return;
+        }
+        if (envelope.ok === true) {
+          resolve(envelope.data);
+        } else {
+          reject(new UxcError(envelope.error?.code ?? "EXECUTION_FAILED", envelope.error?.message ?? ""));
+        }
+      });
+    });
+  }
+}
+"#,
+    );
+    out
+}
+
+/// A JSDoc comment, single-line when it fits
+fn doc(out: &mut String, indent: &str, text: &str) {
+    let text = text.replace("*/", "*\\/");
+    let lines: Vec<&str> = lines(&text).collect();
+    if let [line] = lines.as_slice() {
+        let _ = writeln!(out, "{}/** {} */", indent, line);
+        return;
+    }
+    let _ = writeln!(out, "{}/**", indent);
+    for line in lines {
+        if line.is_empty() {
+            let _ = writeln!(out, "{} *", indent);
+        } else {
+            let _ = writeln!(out, "{} * {}", indent, line);
+        }
+    }
+    let _ = writeln!(out, "{} */", indent);
+}
+
+fn camel(words: &[String]) -> String {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) if i > 0 => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                _ => word.clone(),
+            }
+        })
+        .collect()
+}
+
+/// A property name, quoted unless it is an identifier
+fn property(name: &str) -> String {
+    let mut chars = name.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        string(name)
+    }
+}
+
+fn string(text: &str) -> String {
+    serde_json::Value::String(text.to_string()).to_string()
+}
+
+fn ty(ty: &Ty) -> String {
+    match ty {
+        Ty::String => "string".to_string(),
+        Ty::Integer | Ty::Number => "number".to_string(),
+        Ty::Boolean => "boolean".to_string(),
+        Ty::Any => "unknown".to_string(),
+        Ty::Map => "Record<string, unknown>".to_string(),
+        Ty::Array(items) => match items.as_ref() {
+            Ty::Enum(_) => format!("({})[]", self::ty(items)),
+            items => format!("{}[]", self::ty(items)),
+        },
+        Ty::Enum(values) => values
+            .iter()
+            .map(|value| string(value))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        Ty::Named(name) => name.clone(),
+    }
+}
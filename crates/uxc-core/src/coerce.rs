@@ -0,0 +1,287 @@
+//! Argument checking and coercion against an operation's input schema
+//!
+//! `k=v` arguments arrive as strings. [`coerce`] converts each one to the type
+//! the operation's input schema declares for it (see
+//! [`crate::datagen::payload_schema`]): integers, numbers and booleans are
+//! parsed, arrays and objects are read as JSON. Values that are already typed
+//! (from `--json`) are checked as they are. Enum values and required
+//! arguments are checked too, and every problem is reported at once.
+//!
+//! Fields of object arguments (`owner.age=3`, see [`crate::arguments`]) are
+//! converted the same way against the object's properties.
+//!
+//! Arguments the schema does not describe, and schemas without a type, are
+//! left alone; `--strict-args` (see [`crate::strict_args`]) is what rejects
+//! undeclared names.
+
+use crate::adapters::OperationDetail;
+use crate::error::UxcError;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Arguments after [`coerce`]
+#[derive(Debug, Clone, Default)]
+pub struct Coerced {
+    pub args: HashMap<String, Value>,
+
+    /// Names of the arguments whose value was converted, sorted
+    pub converted: Vec<String>,
+}
+
+/// Convert `args` to the types `detail` declares, or report why they do not fit
+pub fn coerce(detail: &OperationDetail, args: HashMap<String, Value>) -> Result<Coerced, UxcError> {
+    let schema = crate::datagen::payload_schema(detail);
+    let mut problems = Vec::new();
+    let mut converted = Vec::new();
+    let args = coerce_fields(&schema, args, "", &mut problems, &mut converted);
+
+    if problems.is_empty() {
+        converted.sort();
+        return Ok(Coerced {
+            args: args.into_iter().collect(),
+            converted,
+        });
+    }
+    problems.sort();
+    Err(UxcError::InvalidArguments(format!(
+        "Invalid arguments for {}: {}",
+        detail.operation_id,
+        problems.join("; ")
+    )))
+}
+
+/// `fields` of an object described by `schema`, converted, with problems and
+/// converted names (dotted below the top level) collected on the way
+fn coerce_fields(
+    schema: &Value,
+    fields: impl IntoIterator<Item = (String, Value)>,
+    prefix: &str,
+    problems: &mut Vec<String>,
+    converted: &mut Vec<String>,
+) -> Map<String, Value> {
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+
+    let fields: Map<String, Value> = fields.into_iter().collect();
+    problems.extend(
+        schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter(|name| !fields.contains_key(*name))
+            .map(|name| format!("missing required argument '{}{}'", prefix, name)),
+    );
+    fields
+        .into_iter()
+        .map(|(name, value)| {
+            let Some(property) = properties.get(&name) else {
+                return (name, value);
+            };
+            let path = format!("{}{}", prefix, name);
+            let value = match coerce_value(property, &value) {
+                Ok(Some(value)) => {
+                    converted.push(path.clone());
+                    value
+                }
+                Ok(None) => value,
+                Err(problem) => {
+                    problems.push(format!("'{}' {}", path, problem));
+                    return (name, value);
+                }
+            };
+            // Nested fields, as `owner.age=3` gives them, are converted too
+            let value = match value {
+                Value::Object(inner) if property.get("properties").is_some() => Value::Object(
+                    coerce_fields(property, inner, &format!("{}.", path), problems, converted),
+                ),
+                value => value,
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// Types a property accepts; empty when it does not say
+fn declared_types(property: &Value) -> Vec<&str> {
+    match property.get("type") {
+        Some(Value::String(kind)) => vec![kind.as_str()],
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn has_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// `text` read as a value of `kind`
+fn parse_as(text: &str, kind: &str) -> Option<Value> {
+    let parsed = match kind {
+        "integer" | "number" | "boolean" | "null" => serde_json::from_str(text.trim()).ok()?,
+        "array" | "object" => serde_json::from_str(text).ok()?,
+        _ => return None,
+    };
+    has_type(&parsed, kind).then_some(parsed)
+}
+
+/// The value converted to a declared type (`None` when it already fits), or
+/// why it cannot be
+fn coerce_value(property: &Value, value: &Value) -> Result<Option<Value>, String> {
+    let kinds = declared_types(property);
+    let converted = if kinds.is_empty() || kinds.iter().any(|kind| has_type(value, kind)) {
+        None
+    } else {
+        let parsed = value
+            .as_str()
+            .and_then(|text| kinds.iter().find_map(|kind| parse_as(text, kind)));
+        match parsed {
+            Some(parsed) => Some(parsed),
+            None => return Err(format!("must be {}, got {}", describe(&kinds), value)),
+        }
+    };
+
+    if let Some(allowed) = property.get("enum").and_then(Value::as_array) {
+        let value = converted.as_ref().unwrap_or(value);
+        if !allowed.contains(value) {
+            let allowed = allowed.iter().map(Value::to_string).collect::<Vec<_>>();
+            return Err(format!(
+                "must be one of {}, got {}",
+                allowed.join(", "),
+                value
+            ));
+        }
+    }
+    Ok(converted)
+}
+
+fn describe(kinds: &[&str]) -> String {
+    kinds
+        .iter()
+        .map(|kind| match *kind {
+            "integer" | "array" | "object" => format!("an {}", kind),
+            "null" => "null".to_string(),
+            _ => format!("a {}", kind),
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn detail() -> OperationDetail {
+        OperationDetail {
+            operation_id: "post:/pets".to_string(),
+            display_name: "post /pets".to_string(),
+            description: None,
+            parameters: Vec::new(),
+            return_type: None,
+            input_schema: Some(json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": { "type": "integer" },
+                    "weight": { "type": ["number", "null"] },
+                    "vaccinated": { "type": "boolean" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "status": { "type": "string", "enum": ["available", "sold"] },
+                    "owner": {
+                        "type": "object",
+                        "properties": { "age": { "type": "integer" } }
+                    },
+                    "notes": {}
+                }
+            })),
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
+        }
+    }
+
+    fn args(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn strings_are_converted_to_declared_types() {
+        let coerced = coerce(
+            &detail(),
+            args(&[
+                ("name", json!("Rex")),
+                ("age", json!("3")),
+                ("weight", json!("4.5")),
+                ("vaccinated", json!("true")),
+                ("tags", json!(r#"["good","dog"]"#)),
+                ("status", json!("sold")),
+                ("owner", json!({ "name": "Ann", "age": "30" })),
+                ("notes", json!("12")),
+                ("extra", json!("1")),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(coerced.args["age"], json!(3));
+        assert_eq!(coerced.args["weight"], json!(4.5));
+        assert_eq!(coerced.args["vaccinated"], json!(true));
+        assert_eq!(coerced.args["tags"], json!(["good", "dog"]));
+        assert_eq!(coerced.args["notes"], json!("12"));
+        assert_eq!(coerced.args["extra"], json!("1"));
+        assert_eq!(coerced.args["owner"], json!({ "name": "Ann", "age": 30 }));
+        assert_eq!(
+            coerced.converted,
+            ["age", "owner.age", "tags", "vaccinated", "weight"]
+        );
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let err = coerce(
+            &detail(),
+            args(&[
+                ("age", json!("three")),
+                ("vaccinated", json!(1)),
+                ("status", json!("lost")),
+            ]),
+        )
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("missing required argument 'name'"), "{}", err);
+        assert!(
+            err.contains(r#"'age' must be an integer, got "three""#),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("'vaccinated' must be a boolean, got 1"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains(r#"'status' must be one of "available", "sold", got "lost""#),
+            "{}",
+            err
+        );
+    }
+}
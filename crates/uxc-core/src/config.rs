@@ -0,0 +1,1084 @@
+//! Layered settings
+//!
+//! `~/.uxc/config.toml` holds user defaults, and a project `.uxc.toml` (see
+//! [`crate::project`]) can override them with the same sections:
+//!
+//! ```toml
+//! [output]
+//! format = "text"
+//!
+//! [call]
+//! timeout = "30s"       # per attempt
+//! retries = 2
+//! retry_backoff = "500ms"
+//! retry_on = "429,503,UNAVAILABLE"
+//! strict_args = true
+//!
+//! [cache]
+//! ttl = "12h"
+//! backend = "sqlite"
+//! max_bytes = "500MB"
+//!
+//! [grpc]
+//! keepalive_interval = "15s"
+//! connect_retries = 3
+//!
+//! [detection]
+//! order = ["openapi", "graphql"]
+//! disabled = ["grpc"]
+//! timeout = "3s"                        # per probe
+//! race_window = "200ms"
+//! protocol_timeouts = { graphql = "10s" }
+//! openapi_paths = ["/api/openapi.yaml"]
+//! graphql_paths = ["/graphql/v2"]
+//!
+//! [detection.hosts."*.internal.example.com"]
+//! disabled = ["mcp", "jsonrpc"]
+//!
+//! [network]
+//! proxy = "http://proxy.internal:3128"
+//! max_response_bytes = "10MB"
+//!
+//! [pagination]
+//! max_pages = 20
+//! items = "data.users"      # where the items of a page are
+//! next = "meta.after"       # where the next-page cursor is
+//! next_param = "after"      # argument the cursor is sent as
+//! ```
+//!
+//! Durations and sizes are numbers of seconds and bytes, or strings with units
+//! (see [`crate::units`]).
+//!
+//! A setting is taken from the first of: its command line flag, the
+//! `UXC_<SECTION>_<NAME>` environment variable (e.g. `UXC_CALL_TIMEOUT`),
+//! the project file, the user file and the built-in default. [`KEYS`] lists
+//! the settings; `uxc config get|set|list` reads and writes them.
+//!
+//! `[detection.hosts."<pattern>"]` entries, keyed by host patterns as used by
+//! the allowlist, disable more protocols for matching hosts (see
+//! [`disabled_protocols`]). Tables keyed by names or hosts rather than
+//! settings (`[aliases]`, `[api_keys]`) are read from the user file with
+//! [`user_table`]; this module is the only reader of the file.
+
+use crate::adapters::ProtocolType;
+use crate::project::ProjectConfig;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Type of a setting's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Bool,
+    /// Non-negative integer
+    Integer,
+    /// Positive number of seconds, fractions allowed, or a duration (`2m30s`)
+    Seconds,
+    /// Number of bytes, or a size (`10MB`)
+    Bytes,
+    String,
+    /// One of the listed strings
+    Choice(&'static [&'static str]),
+    /// Protocol names; comma-separated on the command line
+    Protocols,
+    /// URL paths starting with `/`; comma-separated on the command line
+    Paths,
+    /// Strings; comma-separated on the command line
+    Strings,
+    /// Seconds per protocol name; `grpc=1s,graphql=5s` on the command line
+    ProtocolSeconds,
+}
+
+/// A known setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    /// `<section>.<name>`
+    pub name: &'static str,
+    pub kind: Kind,
+    /// Built-in default, as it would be given on the command line
+    pub default: Option<&'static str>,
+    pub description: &'static str,
+}
+
+/// Every setting
+pub const KEYS: &[Key] = &[
+    Key {
+        name: "output.format",
+        kind: Kind::Choice(&["json", "text", "csv"]),
+        default: Some("json"),
+        description: "Output format when --format and --text are not given",
+    },
+    Key {
+        name: "call.timeout",
+        kind: Kind::Seconds,
+        default: None,
+        description: "How long one attempt of a call runs before it gives up",
+    },
+    Key {
+        name: "call.retries",
+        kind: Kind::Integer,
+        default: Some("0"),
+        description: "Retries of a call after a timeout, network error or retried status",
+    },
+    Key {
+        name: "call.retry_backoff",
+        kind: Kind::Seconds,
+        default: Some("1"),
+        description: "Wait before the first retry, doubled for each further one",
+    },
+    Key {
+        name: "call.retry_on",
+        kind: Kind::String,
+        default: None,
+        description: "HTTP statuses and gRPC codes that are retried, e.g. 429,503,UNAVAILABLE",
+    },
+    Key {
+        name: "call.strict_args",
+        kind: Kind::Bool,
+        default: Some("false"),
+        description: "Reject argument names the operation does not declare",
+    },
+    Key {
+        name: "output.include_headers",
+        kind: Kind::Strings,
+        default: None,
+        description: "Response headers copied into meta.headers, e.g. etag,x-ratelimit-*",
+    },
+    Key {
+        name: "cache.enabled",
+        kind: Kind::Bool,
+        default: Some("true"),
+        description: "Cache fetched schemas",
+    },
+    Key {
+        name: "cache.ttl",
+        kind: Kind::Seconds,
+        default: Some("86400"),
+        description: "How long a cached schema stays fresh",
+    },
+    Key {
+        name: "cache.stale_while_revalidate",
+        kind: Kind::Bool,
+        default: Some("false"),
+        description: "Serve expired schemas and refresh them in the background",
+    },
+    Key {
+        name: "cache.location",
+        kind: Kind::String,
+        default: Some("~/.uxc/cache/schemas"),
+        description: "Directory of cached schemas",
+    },
+    Key {
+        name: "cache.backend",
+        kind: Kind::Choice(&["files", "sqlite"]),
+        default: Some("files"),
+        description: "Storage of cached schemas: one file per schema, or one SQLite database",
+    },
+    Key {
+        name: "cache.max_entries",
+        kind: Kind::Integer,
+        default: Some("0"),
+        description: "Cached schemas kept at most, least recently used evicted first (0: no limit)",
+    },
+    Key {
+        name: "cache.max_snapshots",
+        kind: Kind::Integer,
+        default: Some("20"),
+        description: "Schema snapshots kept per cached schema for --as-of, oldest dropped first (0: no limit)",
+    },
+    Key {
+        name: "cache.max_bytes",
+        kind: Kind::Bytes,
+        default: Some("0"),
+        description: "Size of the cache at most, least recently used evicted first (0: no limit)",
+    },
+    Key {
+        name: "cache.max_size",
+        kind: Kind::Bytes,
+        default: None,
+        description: "Older name of cache.max_bytes",
+    },
+    Key {
+        name: "cache.encryption_key",
+        kind: Kind::String,
+        default: None,
+        description:
+            "Secret reference (env:NAME, cmd:<command> or literal) encrypting cached schemas",
+    },
+    Key {
+        name: "detection.order",
+        kind: Kind::Protocols,
+        default: Some("mcp,graphql,openapi,jsonrpc,grpc"),
+        description: "Protocols probed first when detecting an endpoint",
+    },
+    Key {
+        name: "detection.disabled",
+        kind: Kind::Protocols,
+        default: None,
+        description: "Protocols never probed when detecting an endpoint",
+    },
+    Key {
+        name: "detection.timeout",
+        kind: Kind::Seconds,
+        default: None,
+        description: "How long each detection probe waits before it counts as no match",
+    },
+    Key {
+        name: "detection.race_window",
+        kind: Kind::Seconds,
+        default: Some("200ms"),
+        description: "How long a matching probe waits for higher-priority probes still running",
+    },
+    Key {
+        name: "detection.protocol_timeouts",
+        kind: Kind::ProtocolSeconds,
+        default: None,
+        description: "Probe timeouts of single protocols, e.g. grpc=1s,graphql=10s",
+    },
+    Key {
+        name: "detection.openapi_paths",
+        kind: Kind::Paths,
+        default: None,
+        description: "Paths probed for an OpenAPI document before the well-known ones",
+    },
+    Key {
+        name: "detection.graphql_paths",
+        kind: Kind::Paths,
+        default: None,
+        description: "Paths probed for a GraphQL endpoint when the URL itself does not answer",
+    },
+    Key {
+        name: "grpc.request_timeout",
+        kind: Kind::Seconds,
+        default: Some("30"),
+        description: "How long one gRPC request runs before it gives up",
+    },
+    Key {
+        name: "grpc.connect_timeout",
+        kind: Kind::Seconds,
+        default: Some("10"),
+        description: "How long connecting to a gRPC server may take",
+    },
+    Key {
+        name: "grpc.tcp_keepalive",
+        kind: Kind::Seconds,
+        default: Some("60"),
+        description: "Interval of TCP keepalive probes on gRPC connections",
+    },
+    Key {
+        name: "grpc.keepalive_interval",
+        kind: Kind::Seconds,
+        default: Some("30"),
+        description: "Interval of HTTP/2 keepalive pings on gRPC connections",
+    },
+    Key {
+        name: "grpc.keepalive_timeout",
+        kind: Kind::Seconds,
+        default: Some("10"),
+        description: "How long a keepalive ping waits for its acknowledgement",
+    },
+    Key {
+        name: "grpc.connect_retries",
+        kind: Kind::Integer,
+        default: Some("0"),
+        description: "Further connection attempts after a failed gRPC connect",
+    },
+    Key {
+        name: "grpc.idle_timeout",
+        kind: Kind::Seconds,
+        default: Some("300"),
+        description: "Reconnect instead of reusing a gRPC channel idle for longer than this",
+    },
+    Key {
+        name: "grpc.http_gateway",
+        kind: Kind::String,
+        default: None,
+        description: "gRPC-JSON gateway that calls go through when the server cannot be reached",
+    },
+    Key {
+        name: "network.proxy",
+        kind: Kind::String,
+        default: None,
+        description: "Proxy URL for HTTP requests (HTTP_PROXY/HTTPS_PROXY otherwise)",
+    },
+    Key {
+        name: "network.max_response_bytes",
+        kind: Kind::Bytes,
+        default: None,
+        description: "Largest HTTP response body read before the request fails",
+    },
+    Key {
+        name: "pagination.max_pages",
+        kind: Kind::Integer,
+        default: Some("100"),
+        description: "Pages fetched at most by --paginate",
+    },
+    Key {
+        name: "pagination.items",
+        kind: Kind::String,
+        default: None,
+        description: "Path of the items of a page in the response, e.g. data.users",
+    },
+    Key {
+        name: "pagination.next",
+        kind: Kind::String,
+        default: None,
+        description: "Path of the next-page cursor or URL in the response, e.g. meta.after",
+    },
+    Key {
+        name: "pagination.next_param",
+        kind: Kind::String,
+        default: Some("cursor"),
+        description: "Argument the cursor at pagination.next is sent as",
+    },
+];
+
+impl Key {
+    /// Environment variable that overrides the configured value
+    pub fn env_var(&self) -> String {
+        format!("UXC_{}", self.name.replace('.', "_").to_uppercase())
+    }
+
+    fn section_and_field(&self) -> (&'static str, &'static str) {
+        self.name
+            .split_once('.')
+            .expect("setting names are <section>.<name>")
+    }
+
+    /// Parse a value given on the command line or in the environment
+    pub fn parse(&self, raw: &str) -> Result<toml::Value> {
+        let raw = raw.trim();
+        let value = match self.kind {
+            Kind::Bool => match raw.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => toml::Value::Boolean(true),
+                "false" | "0" | "no" | "off" => toml::Value::Boolean(false),
+                _ => bail!("expected true or false, got '{}'", raw),
+            },
+            Kind::Integer => toml::Value::Integer(
+                raw.parse::<u32>()
+                    .map_err(|_| anyhow!("expected a whole number, got '{}'", raw))?
+                    .into(),
+            ),
+            Kind::Seconds | Kind::Bytes => toml::Value::String(raw.to_string()),
+            Kind::String | Kind::Choice(_) => toml::Value::String(raw.to_string()),
+            Kind::Protocols | Kind::Paths | Kind::Strings => toml::Value::Array(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| toml::Value::String(name.to_string()))
+                    .collect(),
+            ),
+            Kind::ProtocolSeconds => {
+                let mut timeouts = toml::Table::new();
+                for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                    let (name, seconds) = entry.split_once('=').ok_or_else(|| {
+                        anyhow!("expected <protocol>=<duration>, got '{}'", entry)
+                    })?;
+                    timeouts.insert(
+                        name.trim().to_string(),
+                        toml::Value::String(seconds.trim().to_string()),
+                    );
+                }
+                toml::Value::Table(timeouts)
+            }
+        };
+        self.check(value)
+    }
+
+    /// Seconds given as a duration string converted to a number
+    fn seconds(value: toml::Value) -> Result<toml::Value> {
+        Ok(match value {
+            toml::Value::String(text) => {
+                let duration = crate::units::parse_duration(&text).map_err(|e| anyhow!(e))?;
+                if duration.subsec_nanos() == 0 {
+                    toml::Value::Integer(duration.as_secs() as i64)
+                } else {
+                    toml::Value::Float(duration.as_secs_f64())
+                }
+            }
+            value => value,
+        })
+    }
+
+    fn is_positive_seconds(value: &toml::Value) -> bool {
+        match value {
+            toml::Value::Integer(n) => *n > 0,
+            toml::Value::Float(secs) => secs.is_finite() && *secs > 0.0,
+            _ => false,
+        }
+    }
+
+    fn check_protocol_name(name: &str) -> Result<()> {
+        if ProtocolType::parse(name).is_none() {
+            bail!(
+                "expected protocol names ({}), got {}",
+                ProtocolType::ALL
+                    .map(|protocol| protocol.as_str())
+                    .join(", "),
+                name
+            );
+        }
+        Ok(())
+    }
+
+    /// `value` if it has the type of this setting; durations and sizes given
+    /// as strings are converted to seconds and bytes
+    fn check(&self, value: toml::Value) -> Result<toml::Value> {
+        let value = match (self.kind, value) {
+            (Kind::Seconds, value) => Self::seconds(value)?,
+            (Kind::ProtocolSeconds, toml::Value::Table(timeouts)) => toml::Value::Table(
+                timeouts
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, Self::seconds(value)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            (Kind::Bytes, toml::Value::String(text)) => {
+                toml::Value::Integer(crate::units::parse_size(&text).map_err(|e| anyhow!(e))? as i64)
+            }
+            (_, value) => value,
+        };
+        let valid = match (self.kind, &value) {
+            (Kind::Bool, toml::Value::Boolean(_)) => true,
+            (Kind::Integer, toml::Value::Integer(n)) => *n >= 0 && *n <= u32::MAX as i64,
+            (Kind::Seconds, value) => Self::is_positive_seconds(value),
+            (Kind::Bytes, toml::Value::Integer(n)) => *n >= 0,
+            (Kind::String, toml::Value::String(_)) => true,
+            (Kind::Choice(choices), toml::Value::String(choice)) => {
+                if !choices.contains(&choice.as_str()) {
+                    bail!("expected one of {}, got '{}'", choices.join(", "), choice);
+                }
+                true
+            }
+            (Kind::Protocols, toml::Value::Array(names)) => {
+                for name in names {
+                    match name.as_str() {
+                        Some(name) => Self::check_protocol_name(name)?,
+                        None => Self::check_protocol_name(&name.to_string())?,
+                    }
+                }
+                true
+            }
+            (Kind::Paths, toml::Value::Array(paths)) => {
+                for path in paths {
+                    if !path.as_str().is_some_and(|path| path.starts_with('/')) {
+                        bail!("expected paths starting with '/', got {}", path);
+                    }
+                }
+                true
+            }
+            (Kind::Strings, toml::Value::Array(items)) => {
+                if let Some(item) = items.iter().find(|item| !item.is_str()) {
+                    bail!("expected strings, got {}", item);
+                }
+                true
+            }
+            (Kind::ProtocolSeconds, toml::Value::Table(timeouts)) => {
+                for (name, seconds) in timeouts {
+                    Self::check_protocol_name(name)?;
+                    if !Self::is_positive_seconds(seconds) {
+                        bail!("{} is not a valid timeout for {}", seconds, name);
+                    }
+                }
+                true
+            }
+            _ => false,
+        };
+        if !valid {
+            bail!("{} is not a valid value for {}", value, self.name);
+        }
+        Ok(value)
+    }
+}
+
+/// The setting called `name`
+pub fn key(name: &str) -> Result<&'static Key> {
+    if let Some(key) = KEYS.iter().find(|key| key.name == name) {
+        return Ok(key);
+    }
+    let names = KEYS.iter().map(|key| key.name).collect::<Vec<_>>();
+    match crate::suggest::closest_matches(name, &names, 1).first() {
+        Some(suggestion) => bail!(
+            "Unknown config key '{}' (did you mean '{}'?)",
+            name,
+            suggestion
+        ),
+        None => bail!("Unknown config key '{}'", name),
+    }
+}
+
+/// Where a setting's value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Env,
+    Project,
+    User,
+    Default,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Env => "env",
+            Source::Project => "project",
+            Source::User => "user",
+            Source::Default => "default",
+        }
+    }
+}
+
+/// The effective value of a setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Setting {
+    pub key: String,
+
+    /// `None` when the setting is unset and has no default
+    pub value: Option<Value>,
+
+    pub source: Source,
+
+    /// Environment variable or file the value came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+impl Setting {
+    fn new(key: &Key, value: Option<toml::Value>, source: Source, origin: Option<String>) -> Self {
+        Self {
+            key: key.name.to_string(),
+            value: value.and_then(|value| serde_json::to_value(value).ok()),
+            source,
+            origin,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        self.value.as_ref()?.as_bool()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.value.as_ref()?.as_u64()
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        self.value.as_ref()?.as_str()
+    }
+
+    pub fn as_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(self.value.as_ref()?.as_f64()?))
+    }
+
+    pub fn as_protocols(&self) -> Vec<ProtocolType> {
+        self.value
+            .as_ref()
+            .and_then(Value::as_array)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().and_then(ProtocolType::parse))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn as_strings(&self) -> Vec<String> {
+        self.value
+            .as_ref()
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn as_protocol_durations(&self) -> Vec<(ProtocolType, Duration)> {
+        self.value
+            .as_ref()
+            .and_then(Value::as_object)
+            .map(|durations| {
+                durations
+                    .iter()
+                    .filter_map(|(name, seconds)| {
+                        Some((
+                            ProtocolType::parse(name)?,
+                            Duration::from_secs_f64(seconds.as_f64()?),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether the value was configured rather than built in
+    pub fn is_configured(&self) -> bool {
+        self.source != Source::Default
+    }
+
+    /// Where the value came from, for `--explain` and text output
+    pub fn describe_source(&self) -> String {
+        match &self.origin {
+            Some(origin) => format!("{} ({})", self.source.as_str(), origin),
+            None => self.source.as_str().to_string(),
+        }
+    }
+}
+
+/// Settings an untrusted project file does not get to set: the cache key
+/// may be a `cmd:` reference, and the proxy and cache location decide where
+/// requests and responses go
+pub const TRUSTED_ONLY: &[&str] = &["cache.location", "cache.encryption_key", "network.proxy"];
+
+/// Effective value of the setting called `name`
+pub fn get(name: &str) -> Result<Setting> {
+    let key = key(name)?;
+
+    let var = key.env_var();
+    if let Some(raw) = crate::env_file::var(&var) {
+        let value = key
+            .parse(&raw)
+            .with_context(|| format!("Invalid value for {}", var))?;
+        return Ok(Setting::new(key, Some(value), Source::Env, Some(var)));
+    }
+
+    if let Some(project) = ProjectConfig::current()? {
+        let value = table_value(&project.settings, key);
+        if value.is_some() && !project.trusted && TRUSTED_ONLY.contains(&key.name) {
+            tracing::warn!(
+                "Ignoring {} of {} until it is trusted with 'uxc project trust'",
+                key.name,
+                project.path.display()
+            );
+            crate::explain::record(
+                "config",
+                format!(
+                    "{} of untrusted {} ignored; run 'uxc project trust'",
+                    key.name,
+                    project.path.display()
+                ),
+            );
+        } else if let Some(value) = value {
+            let value = key
+                .check(value)
+                .with_context(|| format!("Invalid {} in {:?}", key.name, project.path))?;
+            return Ok(Setting::new(
+                key,
+                Some(value),
+                Source::Project,
+                Some(project.path.display().to_string()),
+            ));
+        }
+    }
+
+    if let Some(path) = user_config_path().filter(|path| path.exists()) {
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?;
+        if let Some(value) = file_value(&contents, key) {
+            let value = key
+                .check(value)
+                .with_context(|| format!("Invalid {} in {:?}", key.name, path))?;
+            return Ok(Setting::new(
+                key,
+                Some(value),
+                Source::User,
+                Some(path.display().to_string()),
+            ));
+        }
+    }
+
+    let value = key.default.map(|default| key.parse(default)).transpose()?;
+    Ok(Setting::new(key, value, Source::Default, None))
+}
+
+/// Effective values of every setting
+pub fn list() -> Result<Vec<Setting>> {
+    KEYS.iter().map(|key| get(key.name)).collect()
+}
+
+/// Protocols never probed for `url`: `detection.disabled` and the
+/// `disabled` lists of every `[detection.hosts."<pattern>"]` entry whose
+/// pattern matches its host, in the project and user files
+pub fn disabled_protocols(url: &str) -> Result<Vec<ProtocolType>> {
+    let key = key("detection.disabled")?;
+    let mut disabled = get(key.name)?.as_protocols();
+    let mut add = |hosts: &toml::Table, origin: &Path| -> Result<()> {
+        for (pattern, entry) in hosts {
+            let matches = crate::allowlist::HostAllowlist::parse(pattern)
+                .is_some_and(|allowlist| allowlist.allows(url));
+            let Some(value) = entry.get("disabled").filter(|_| matches) else {
+                continue;
+            };
+            let value = key.check(value.clone()).with_context(|| {
+                format!(
+                    "Invalid detection.hosts.\"{}\".disabled in {:?}",
+                    pattern, origin
+                )
+            })?;
+            let protocols = value.as_array().into_iter().flatten();
+            for protocol in protocols.filter_map(|name| ProtocolType::parse(name.as_str()?)) {
+                if !disabled.contains(&protocol) {
+                    disabled.push(protocol);
+                }
+            }
+        }
+        Ok(())
+    };
+
+    if let Some(project) = ProjectConfig::current()? {
+        if let Some(hosts) = detection_hosts(&project.settings) {
+            add(hosts, &project.path)?;
+        }
+    }
+    if let Some(path) = user_config_path().filter(|path| path.exists()) {
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?;
+        if let Some(detection) = section_table(&contents, "detection") {
+            if let Some(hosts) = detection_hosts(&detection) {
+                add(hosts, &path)?;
+            }
+        }
+    }
+    Ok(disabled)
+}
+
+/// The `[section]` table of the user config file, for tables keyed by names
+/// or hosts rather than settings (`[aliases]`, `[api_keys]`)
+pub fn user_table(section: &str) -> Result<Option<toml::Table>> {
+    let Some(path) = user_config_path().filter(|path| path.exists()) else {
+        return Ok(None);
+    };
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config file: {:?}", path))?;
+    Ok(section_table(&contents, section)
+        .and_then(|mut table| table.remove(section))
+        .and_then(|value| match value {
+            toml::Value::Table(table) => Some(table),
+            _ => None,
+        }))
+}
+
+fn detection_hosts(table: &toml::Table) -> Option<&toml::Table> {
+    table.get("detection")?.as_table()?.get("hosts")?.as_table()
+}
+
+/// `[section]` and its subtables in a user config file, as a table holding
+/// `section`. When the file is not valid TOML (see [`file_value`]), the lines
+/// of those sections are parsed on their own.
+fn section_table(contents: &str, section: &str) -> Option<toml::Table> {
+    if let Ok(table) = toml::from_str::<toml::Table>(contents) {
+        return Some(table);
+    }
+    let mut lines = Vec::new();
+    let mut inside = false;
+    for line in contents.lines() {
+        if let Some(header) = section_header(line.trim()) {
+            inside = header == section || header.starts_with(&format!("{}.", section));
+        }
+        if inside {
+            lines.push(line);
+        }
+    }
+    toml::from_str(&lines.join("\n")).ok()
+}
+
+/// Store `raw` as the value of `name` in the user config file, or in the
+/// project file with `project`. Returns the stored setting.
+pub fn set(name: &str, raw: &str, project: bool) -> Result<Setting> {
+    let key = key(name)?;
+    let value = key
+        .parse(raw)
+        .with_context(|| format!("Invalid value for {}", key.name))?;
+
+    let (path, source) = if project {
+        let project = ProjectConfig::current()?.with_context(|| {
+            format!(
+                "No {} found in this directory or its parents",
+                crate::project::PROJECT_FILE
+            )
+        })?;
+        (project.path.clone(), Source::Project)
+    } else {
+        (
+            user_config_path().context("Could not determine home directory")?,
+            Source::User,
+        )
+    };
+    write_value(&path, key, &value)?;
+    Ok(Setting::new(
+        key,
+        Some(value),
+        source,
+        Some(path.display().to_string()),
+    ))
+}
+
+fn write_value(path: &Path, key: &Key, value: &toml::Value) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create config directory: {:?}", parent))?;
+    }
+    let contents = if path.exists() {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?
+    } else {
+        String::new()
+    };
+    let (section, field) = key.section_and_field();
+    fs::write(path, set_in(&contents, section, field, &value.to_string()))
+        .with_context(|| format!("Failed to write config file: {:?}", path))
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .map(|home| home.join(".uxc/config.toml"))
+}
+
+fn table_value(table: &toml::Table, key: &Key) -> Option<toml::Value> {
+    let (section, field) = key.section_and_field();
+    table.get(section)?.as_table()?.get(field).cloned()
+}
+
+/// Value of `key` in a user config file. Older sections use a looser
+/// line-based format, so files that are not valid TOML are read line by
+/// line.
+fn file_value(contents: &str, key: &Key) -> Option<toml::Value> {
+    if let Ok(table) = toml::from_str::<toml::Table>(contents) {
+        return table_value(&table, key);
+    }
+
+    let (section, field) = key.section_and_field();
+    let mut current = None;
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(header) = section_header(line) {
+            current = Some(header);
+            continue;
+        }
+        if current != Some(section) {
+            continue;
+        }
+        let Some((name, raw)) = line.split_once('=') else {
+            continue;
+        };
+        if name.trim() != field {
+            continue;
+        }
+        let raw = raw.trim();
+        return Some(
+            toml::from_str::<toml::Table>(&format!("value = {}", raw))
+                .ok()
+                .and_then(|mut table| table.remove("value"))
+                .unwrap_or_else(|| toml::Value::String(raw.to_string())),
+        );
+    }
+    None
+}
+
+fn section_header(line: &str) -> Option<&str> {
+    line.strip_prefix('[')?
+        .strip_suffix(']')
+        .map(str::trim)
+        .filter(|header| !header.starts_with('['))
+}
+
+/// `contents` with `field = rendered` in `[section]`, replacing an existing
+/// value and keeping everything else as it is
+fn set_in(contents: &str, section: &str, field: &str, rendered: &str) -> String {
+    let assignment = format!("{} = {}", field, rendered);
+    let mut lines = contents.lines().map(str::to_string).collect::<Vec<_>>();
+
+    let mut current = None;
+    let mut insert_at = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index].trim().to_string();
+        if let Some(header) = section_header(&line) {
+            current = Some(header.to_string());
+            if header == section {
+                insert_at = Some(index + 1);
+            }
+            index += 1;
+            continue;
+        }
+        if current.as_deref() == Some(section) {
+            if !line.is_empty() && !line.starts_with('#') {
+                insert_at = Some(index + 1);
+            }
+            if line
+                .split_once('=')
+                .is_some_and(|(name, _)| name.trim() == field)
+            {
+                // A multi-line array continues until its closing bracket
+                let mut end = index + 1;
+                let value = line.split_once('=').map(|(_, value)| value.trim());
+                if value.is_some_and(|value| value.starts_with('[') && !value.contains(']')) {
+                    while end < lines.len() && !lines[end - 1].contains(']') {
+                        end += 1;
+                    }
+                }
+                lines.splice(index..end, [assignment]);
+                return join_lines(lines);
+            }
+        }
+        index += 1;
+    }
+
+    match insert_at {
+        Some(at) => lines.insert(at, assignment),
+        None => {
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.push(assignment);
+        }
+    }
+    join_lines(lines)
+}
+
+fn join_lines(lines: Vec<String>) -> String {
+    let mut joined = lines.join("\n");
+    joined.push('\n');
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_parsed_and_checked_by_kind() {
+        let timeout = key("call.timeout").unwrap();
+        assert_eq!(timeout.parse("2.5").unwrap(), toml::Value::Float(2.5));
+        assert_eq!(timeout.parse("2m30s").unwrap(), toml::Value::Integer(150));
+        assert!(timeout.parse("0").is_err());
+        assert!(timeout.parse("soon").is_err());
+
+        let limit = key("network.max_response_bytes").unwrap();
+        assert_eq!(
+            limit.parse("10MB").unwrap(),
+            toml::Value::Integer(10_000_000)
+        );
+        assert_eq!(timeout.env_var(), "UXC_CALL_TIMEOUT");
+
+        let format = key("output.format").unwrap();
+        assert!(format
+            .parse("yaml")
+            .unwrap_err()
+            .to_string()
+            .contains("json"));
+
+        let order = key("detection.order").unwrap();
+        assert_eq!(
+            order.parse("graphql, openapi").unwrap().to_string(),
+            r#"["graphql", "openapi"]"#
+        );
+        assert!(order.parse("soap").is_err());
+
+        let timeouts = key("detection.protocol_timeouts").unwrap();
+        let value = timeouts.parse("grpc=1s, graphql=1m").unwrap();
+        assert_eq!(value.to_string(), "{ graphql = 60, grpc = 1 }");
+        let setting = Setting::new(timeouts, Some(value), Source::User, None);
+        assert_eq!(
+            setting.as_protocol_durations(),
+            [
+                (ProtocolType::GraphQL, Duration::from_secs(60)),
+                (ProtocolType::GRpc, Duration::from_secs(1))
+            ]
+        );
+        assert!(timeouts.parse("soap=1s").is_err());
+        assert!(timeouts.parse("grpc").is_err());
+        assert!(timeouts.parse("grpc=0").is_err());
+
+        let paths = key("detection.openapi_paths").unwrap();
+        assert_eq!(
+            paths.parse("/api/openapi.yaml").unwrap().to_string(),
+            r#"["/api/openapi.yaml"]"#
+        );
+        assert!(paths.parse("api/openapi.yaml").is_err());
+
+        let err = key("call.timeot").unwrap_err().to_string();
+        assert!(err.contains("did you mean 'call.timeout'"), "{}", err);
+    }
+
+    #[test]
+    fn user_files_are_read_strictly_or_line_by_line() {
+        let retries = key("call.retries").unwrap();
+        assert_eq!(
+            file_value("[call]\nretries = 3\n", retries),
+            Some(toml::Value::Integer(3))
+        );
+        // Not valid TOML: the loose cache syntax
+        let loose = "[cache]\nlocation = /tmp/uxc cache\n\n[call]\nretries = 2\n";
+        assert_eq!(file_value(loose, retries), Some(toml::Value::Integer(2)));
+        assert_eq!(file_value("[cache]\nretries = 2\n", retries), None);
+
+        // Keys are only read from their own section, and strings are unquoted
+        let ttl = key("cache.ttl").unwrap();
+        assert_eq!(file_value("[grpc]\nttl = 5\n", ttl), None);
+        let location = key("cache.location").unwrap();
+        assert_eq!(
+            file_value(
+                "[cache]\nlocation = \"/tmp/uxc\"\nbackend = sqlite\n",
+                location
+            ),
+            Some(toml::Value::String("/tmp/uxc".to_string()))
+        );
+        let backend = key("cache.backend").unwrap();
+        assert_eq!(
+            file_value(
+                "[cache]\nlocation = /tmp/uxc cache\nbackend = sqlite\n",
+                backend
+            ),
+            Some(toml::Value::String("sqlite".to_string()))
+        );
+    }
+
+    #[test]
+    fn detection_sections_are_read_from_loose_files() {
+        let loose = "[cache]\nlocation = /tmp/uxc cache\n\n[detection.hosts.\"*.internal\"]\ndisabled = [\"grpc\"]\n";
+        let table = section_table(loose, "detection").unwrap();
+        let hosts = detection_hosts(&table).unwrap();
+        assert_eq!(hosts["*.internal"]["disabled"].to_string(), r#"["grpc"]"#);
+        assert!(table.get("cache").is_none());
+    }
+
+    #[test]
+    fn setting_a_value_keeps_the_rest_of_the_file() {
+        let existing = "# defaults\n[cache]\nttl = 60\n\n[call]\nretries = 1\n\n[aliases.api]\nurl = \"https://api.example.com\"\n";
+        assert_eq!(
+            set_in(existing, "call", "retries", "3"),
+            existing.replace("retries = 1", "retries = 3")
+        );
+        assert_eq!(
+            set_in(existing, "call", "timeout", "30"),
+            existing.replace("retries = 1\n", "retries = 1\ntimeout = 30\n")
+        );
+        assert_eq!(
+            set_in(existing, "network", "proxy", "\"http://proxy:3128\""),
+            format!("{}\n[network]\nproxy = \"http://proxy:3128\"\n", existing)
+        );
+        assert_eq!(
+            set_in(
+                "[detection]\norder = [\n  \"grpc\",\n]\n",
+                "detection",
+                "order",
+                "[\"mcp\"]"
+            ),
+            "[detection]\norder = [\"mcp\"]\n"
+        );
+        assert_eq!(
+            set_in("", "output", "format", "\"text\""),
+            "[output]\nformat = \"text\"\n"
+        );
+    }
+}
@@ -0,0 +1,774 @@
+//! Schema-driven random test data (`uxc gen-data`)
+//!
+//! [`payload_schema`] turns an operation's input description into one JSON
+//! Schema for its arguments, whatever the protocol, and [`Generator`] draws
+//! values that satisfy it: enums, formats, numeric bounds, string lengths and
+//! array sizes are respected, and required properties are always present.
+//! Generation is deterministic for a given seed.
+//!
+//! [`example`] builds the one ready-to-edit payload `uxc <host> <op> example`
+//! prints instead: declared examples and defaults, first enum values and a
+//! placeholder of the right type for everything else, with every property
+//! present.
+//!
+//! For example responses (`uxc mock`) the generator can also resolve local
+//! `$ref`s against the document a schema comes from and use the `example`,
+//! `examples` and `default` values a schema declares.
+
+use crate::adapters::{OperationDetail, Parameter};
+use serde_json::{json, Map, Number, Value};
+
+/// Nesting depth after which objects and arrays are left empty
+const MAX_DEPTH: usize = 8;
+
+/// Upper bound for arrays without `maxItems`
+const DEFAULT_MAX_ITEMS: u64 = 3;
+
+/// Upper bound for strings without `maxLength`
+const DEFAULT_MAX_LENGTH: u64 = 12;
+
+const WORDS: &[&str] = &[
+    "alpha", "bravo", "cedar", "delta", "ember", "falcon", "garnet", "harbor", "indigo", "juniper",
+    "kestrel", "lumen", "maple", "nova", "orbit", "pixel", "quartz", "river", "summit", "tango",
+];
+
+/// JSON Schema for the arguments of an operation.
+///
+/// Understands the `input_schema` shapes the adapters produce (OpenAPI
+/// request bodies, gRPC messages, OpenRPC params, GraphQL arguments and plain
+/// JSON Schema from MCP) and adds parameters that are only listed by name
+/// and type.
+pub fn payload_schema(detail: &OperationDetail) -> Value {
+    let input = detail.input_schema.as_ref();
+    let base = match input
+        .and_then(|schema| schema.get("kind"))
+        .and_then(Value::as_str)
+    {
+        Some("openapi_request_body") => input.and_then(request_body_schema),
+        Some("grpc_message") => input.and_then(|schema| schema.get("schema")).cloned(),
+        Some("openrpc_method") => input.map(openrpc_params_schema),
+        _ => input.filter(|schema| schema.is_object()).cloned(),
+    };
+
+    let mut schema = match base {
+        Some(Value::Object(schema)) if is_object_schema(&schema) => schema,
+        // A non-object body cannot take named parameters next to it
+        Some(schema) if detail.parameters.is_empty() => return schema,
+        _ => Map::from_iter([("type".to_string(), json!("object"))]),
+    };
+    for parameter in &detail.parameters {
+        let properties = schema
+            .entry("properties")
+            .or_insert_with(|| json!({}))
+            .as_object_mut();
+        let Some(properties) = properties else {
+            continue;
+        };
+        if properties.contains_key(&parameter.name) {
+            continue;
+        }
+        properties.insert(parameter.name.clone(), parameter_schema(parameter));
+        if parameter.required {
+            if let Some(required) = schema
+                .entry("required")
+                .or_insert_with(|| json!([]))
+                .as_array_mut()
+            {
+                required.push(Value::String(parameter.name.clone()));
+            }
+        }
+    }
+    Value::Object(schema)
+}
+
+/// Example arguments for an operation: [`example_value`] of its
+/// [`payload_schema`], with the example its request body documents laid
+/// over it
+pub fn example(detail: &OperationDetail) -> Value {
+    let mut payload = example_value(&payload_schema(detail));
+    let documented = detail
+        .input_schema
+        .as_ref()
+        .and_then(|input| input.get("content")?.as_object())
+        .and_then(|content| content.values().find_map(|media| media.get("example")));
+    match (&mut payload, documented) {
+        (Value::Object(payload), Some(Value::Object(documented))) => {
+            payload.extend(documented.clone());
+        }
+        (_, Some(documented)) if !documented.is_object() => payload = documented.clone(),
+        _ => {}
+    }
+    payload
+}
+
+/// A value of `schema` to start editing from: its declared example or
+/// default, else its first enum value or option, else a placeholder of its
+/// type; objects have all their properties and arrays one item
+pub fn example_value(schema: &Value) -> Value {
+    example_at(schema, 0)
+}
+
+fn example_at(schema: &Value, depth: usize) -> Value {
+    let Some(schema) = schema.as_object() else {
+        return Value::String("value".to_string());
+    };
+    if let Some(example) = declared_example(schema) {
+        return example;
+    }
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|options| options.first())
+    {
+        return first.clone();
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(first) = schema
+            .get(key)
+            .and_then(Value::as_array)
+            .and_then(|options| options.first())
+        {
+            return example_at(first, depth + 1);
+        }
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        return example_at(&merge_all_of(schema, parts), depth + 1);
+    }
+
+    let minimum = || schema.get("minimum").cloned();
+    match schema_type(schema).as_deref() {
+        Some("null") => Value::Null,
+        Some("boolean") => Value::Bool(false),
+        Some("integer") => minimum().unwrap_or_else(|| json!(0)),
+        Some("number") => minimum().unwrap_or_else(|| json!(0.0)),
+        Some("array") if depth < MAX_DEPTH => {
+            let items = schema.get("items").cloned().unwrap_or(Value::Bool(true));
+            Value::Array(vec![example_at(&items, depth + 1)])
+        }
+        Some("object") if depth < MAX_DEPTH => Value::Object(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .map(|(name, property)| (name.clone(), example_at(property, depth + 1)))
+                .collect(),
+        ),
+        Some("array") => json!([]),
+        Some("object") => json!({}),
+        _ => Value::String(
+            match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("time") => "00:00:00",
+                Some("email") => "user@example.com",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("uri") | Some("url") => "https://example.com",
+                Some("hostname") => "example.com",
+                Some("ipv4") => "192.0.2.1",
+                Some("ipv6") => "2001:db8::1",
+                Some("byte") => "",
+                _ => "string",
+            }
+            .to_string(),
+        ),
+    }
+}
+
+/// Seed for runs without `--seed`
+pub fn random_seed() -> u64 {
+    let mut bytes = [0u8; 8];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        return nanos;
+    }
+    u64::from_le_bytes(bytes)
+}
+
+/// Seeded generator of schema-conforming values.
+pub struct Generator {
+    state: u64,
+    /// Document `#/...` references are resolved against
+    document: Option<Value>,
+    /// Use the examples and defaults schemas declare
+    examples: bool,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            document: None,
+            examples: false,
+        }
+    }
+
+    /// Resolve `$ref`s such as `#/components/schemas/Pet` against `document`
+    pub fn with_document(mut self, document: Value) -> Self {
+        self.document = Some(document);
+        self
+    }
+
+    /// Prefer the `example`, `examples` and `default` of a schema to
+    /// generated values
+    pub fn with_examples(mut self) -> Self {
+        self.examples = true;
+        self
+    }
+
+    /// Generate one value satisfying `schema`
+    pub fn value(&mut self, schema: &Value) -> Value {
+        self.generate(schema, 0)
+    }
+
+    fn generate(&mut self, schema: &Value, depth: usize) -> Value {
+        let Some(schema) = schema.as_object() else {
+            // `true` or a missing schema accepts anything
+            return Value::String(self.word());
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = self
+                .document
+                .as_ref()
+                .and_then(|document| document.pointer(reference.strip_prefix('#')?))
+                .cloned();
+            if let Some(target) = target.filter(|_| depth < MAX_DEPTH) {
+                return self.generate(&target, depth + 1);
+            }
+        }
+        if self.examples {
+            if let Some(example) = declared_example(schema) {
+                return example;
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.is_empty() {
+                return options[self.below(options.len() as u64) as usize].clone();
+            }
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(options) = schema.get(key).and_then(Value::as_array) {
+                if !options.is_empty() {
+                    let choice = &options[self.below(options.len() as u64) as usize];
+                    return self.generate(choice, depth + 1);
+                }
+            }
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            return self.generate(&merge_all_of(schema, parts), depth + 1);
+        }
+
+        match schema_type(schema).as_deref() {
+            Some("null") => Value::Null,
+            Some("boolean") => Value::Bool(self.next() & 1 == 1),
+            Some("integer") => self.integer(schema),
+            Some("number") => self.number(schema),
+            Some("array") => self.array(schema, depth),
+            Some("object") => self.object(schema, depth),
+            _ => self.string(schema),
+        }
+    }
+
+    fn object(&mut self, schema: &Map<String, Value>, depth: usize) -> Value {
+        let mut object = Map::new();
+        if depth >= MAX_DEPTH {
+            return Value::Object(object);
+        }
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                // Optional properties are present about half the time
+                if required.contains(&name.as_str()) || self.next() & 1 == 1 {
+                    object.insert(name.clone(), self.generate(property, depth + 1));
+                }
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn array(&mut self, schema: &Map<String, Value>, depth: usize) -> Value {
+        if depth >= MAX_DEPTH {
+            return Value::Array(Vec::new());
+        }
+        let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+        let max = schema
+            .get("maxItems")
+            .and_then(Value::as_u64)
+            .unwrap_or(min.max(DEFAULT_MAX_ITEMS));
+        let len = self.between(min, max.max(min));
+        let items = schema.get("items").cloned().unwrap_or(Value::Bool(true));
+        Value::Array((0..len).map(|_| self.generate(&items, depth + 1)).collect())
+    }
+
+    fn integer(&mut self, schema: &Map<String, Value>) -> Value {
+        let (min, max) = bounds(schema, 0.0, 1000.0, 1.0);
+        let (min, max) = (min.ceil() as i64, max.floor() as i64);
+        let multiple = schema
+            .get("multipleOf")
+            .and_then(Value::as_i64)
+            .filter(|&m| m > 0)
+            .unwrap_or(1);
+        let low = min.div_euclid(multiple) + i64::from(min.rem_euclid(multiple) != 0);
+        let high = max.div_euclid(multiple).max(low);
+        let span = high.abs_diff(low);
+        let step = if span == u64::MAX {
+            self.next()
+        } else {
+            self.below(span + 1)
+        };
+        Value::Number(Number::from(low.wrapping_add(step as i64) * multiple))
+    }
+
+    fn number(&mut self, schema: &Map<String, Value>) -> Value {
+        let (min, max) = bounds(schema, 0.0, 1000.0, f64::EPSILON);
+        let fraction = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        // Two decimals keep fixtures readable; fall back to the raw draw if
+        // rounding would leave the range
+        let raw = min + (max - min) * fraction;
+        let rounded = (raw * 100.0).round() / 100.0;
+        let value = if (min..=max).contains(&rounded) {
+            rounded
+        } else {
+            raw
+        };
+        Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or_else(|| json!(min))
+    }
+
+    fn string(&mut self, schema: &Map<String, Value>) -> Value {
+        let format = schema.get("format").and_then(Value::as_str);
+        let value = match format {
+            Some("date-time") => self.timestamp().to_rfc3339(),
+            Some("date") => self.timestamp().format("%Y-%m-%d").to_string(),
+            Some("time") => self.timestamp().format("%H:%M:%S").to_string(),
+            Some("email") => format!("{}.{}@example.com", self.word(), self.below(1000)),
+            Some("uuid") => self.uuid(),
+            Some("uri") | Some("url") => format!("https://example.com/{}", self.word()),
+            Some("hostname") => format!("{}.example.com", self.word()),
+            Some("ipv4") => format!(
+                "10.{}.{}.{}",
+                self.below(256),
+                self.below(256),
+                self.between(1, 254)
+            ),
+            Some("ipv6") => format!("fd00::{:x}:{:x}", self.below(0x10000), self.below(0x10000)),
+            Some("byte") => "dXhj".to_string(),
+            _ => {
+                let min = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0);
+                let max = schema
+                    .get("maxLength")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(min.max(DEFAULT_MAX_LENGTH));
+                let len = self.between(min.max(1).min(max), max.max(min)) as usize;
+                let mut text = String::new();
+                while text.len() < len {
+                    if !text.is_empty() {
+                        text.push('-');
+                    }
+                    text.push_str(&self.word());
+                }
+                text.truncate(len);
+                return Value::String(text);
+            }
+        };
+        Value::String(value)
+    }
+
+    fn timestamp(&mut self) -> chrono::DateTime<chrono::Utc> {
+        // 2000-01-01 to 2030-01-01
+        let secs = self.between(946_684_800, 1_893_456_000) as i64;
+        chrono::DateTime::from_timestamp(secs, 0).unwrap_or_default()
+    }
+
+    fn uuid(&mut self) -> String {
+        let (high, low) = (self.next(), self.next());
+        // Version 4, RFC 4122 variant
+        let high = (high & !0xf000) | 0x4000;
+        let low = (low & !(0b11 << 62)) | (0b10 << 62);
+        format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff
+        )
+    }
+
+    fn word(&mut self) -> String {
+        WORDS[self.below(WORDS.len() as u64) as usize].to_string()
+    }
+
+    /// Uniform value in `min..=max`
+    fn between(&mut self, min: u64, max: u64) -> u64 {
+        min + self.below(max - min + 1)
+    }
+
+    /// Uniform value in `0..bound` (`bound` > 0)
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    /// SplitMix64: small, fast and stable across releases, so a seed always
+    /// reproduces the same fixtures
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Inclusive numeric range from `minimum`/`maximum` and their exclusive forms
+fn bounds(
+    schema: &Map<String, Value>,
+    default_min: f64,
+    default_max: f64,
+    step: f64,
+) -> (f64, f64) {
+    let exclusive = |key: &str| match schema.get(key) {
+        Some(Value::Number(n)) => n.as_f64(),
+        _ => None,
+    };
+    // OpenAPI 3.0 marks bounds exclusive with a boolean next to them
+    let flag = |key: &str| schema.get(key).and_then(Value::as_bool).unwrap_or(false);
+    let min = schema.get("minimum").and_then(Value::as_f64);
+    let max = schema.get("maximum").and_then(Value::as_f64);
+    let min = match (exclusive("exclusiveMinimum"), min) {
+        (Some(bound), _) => Some(bound + step),
+        (None, Some(min)) if flag("exclusiveMinimum") => Some(min + step),
+        (None, min) => min,
+    };
+    let max = match (exclusive("exclusiveMaximum"), max) {
+        (Some(bound), _) => Some(bound - step),
+        (None, Some(max)) if flag("exclusiveMaximum") => Some(max - step),
+        (None, max) => max,
+    };
+    match (min, max) {
+        (Some(min), Some(max)) => (min, max.max(min)),
+        (Some(min), None) => (min, min + (default_max - default_min)),
+        (None, Some(max)) => (max.min(default_min), max),
+        (None, None) => (default_min, default_max),
+    }
+}
+
+fn schema_type(schema: &Map<String, Value>) -> Option<String> {
+    match schema.get("type") {
+        Some(Value::String(name)) => Some(name.clone()),
+        // `["string", "null"]`: prefer the non-null type
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|name| *name != "null")
+            .or_else(|| names.first().and_then(Value::as_str))
+            .map(ToString::to_string),
+        _ if schema.contains_key("properties") => Some("object".to_string()),
+        _ if schema.contains_key("items") => Some("array".to_string()),
+        _ => None,
+    }
+}
+
+/// `example`, the first of `examples` (a JSON Schema array or an OpenAPI map
+/// of named examples), or `default`
+fn declared_example(schema: &Map<String, Value>) -> Option<Value> {
+    if let Some(example) = schema.get("example") {
+        return Some(example.clone());
+    }
+    match schema.get("examples") {
+        Some(Value::Array(examples)) if !examples.is_empty() => return Some(examples[0].clone()),
+        Some(Value::Object(examples)) => {
+            if let Some(first) = examples.values().next() {
+                return Some(first.get("value").unwrap_or(first).clone());
+            }
+        }
+        _ => {}
+    }
+    schema.get("default").cloned()
+}
+
+fn is_object_schema(schema: &Map<String, Value>) -> bool {
+    schema_type(schema).as_deref() == Some("object")
+}
+
+/// One schema with the properties and required names of every `allOf` part
+fn merge_all_of(schema: &Map<String, Value>, parts: &[Value]) -> Value {
+    let mut merged = schema.clone();
+    merged.remove("allOf");
+    for part in parts.iter().filter_map(Value::as_object) {
+        for (key, value) in part {
+            match (key.as_str(), merged.get_mut(key), value) {
+                ("properties", Some(Value::Object(existing)), Value::Object(extra)) => {
+                    existing.extend(extra.clone());
+                }
+                ("required", Some(Value::Array(existing)), Value::Array(extra)) => {
+                    existing.extend(extra.iter().cloned());
+                }
+                (_, Some(_), _) => {}
+                (_, None, _) => {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+    Value::Object(merged)
+}
+
+/// Schema of a JSON request body, preferring `application/json`
+fn request_body_schema(input: &Value) -> Option<Value> {
+    let content = input.get("content")?.as_object()?;
+    content
+        .get("application/json")
+        .or_else(|| {
+            content
+                .iter()
+                .find(|(media_type, _)| media_type.contains("json"))
+                .map(|(_, media)| media)
+        })
+        .or_else(|| content.values().next())
+        .and_then(|media| media.get("schema"))
+        .cloned()
+}
+
+fn openrpc_params_schema(input: &Value) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for param in input
+        .get("params")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(name) = param.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        properties.insert(
+            name.to_string(),
+            param.get("schema").cloned().unwrap_or(Value::Bool(true)),
+        );
+        if param.get("required").and_then(Value::as_bool) == Some(true) {
+            required.push(Value::String(name.to_string()));
+        }
+    }
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+/// Schema for a parameter known only by its type name, which may be a JSON
+/// Schema type or a protocol type such as GraphQL's `Int!` or `[String]`
+fn parameter_schema(parameter: &Parameter) -> Value {
+    let type_name = parameter.param_type.trim_end_matches('!');
+    if let Some(inner) = type_name
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        let item = Parameter {
+            param_type: inner.to_string(),
+            ..parameter.clone()
+        };
+        return json!({ "type": "array", "items": parameter_schema(&item) });
+    }
+    let json_type = match type_name.to_ascii_lowercase().as_str() {
+        "integer" | "int" | "int32" | "int64" | "uint32" | "uint64" | "long" => "integer",
+        "number" | "float" | "double" => "number",
+        "boolean" | "bool" => "boolean",
+        "array" => "array",
+        "object" => "object",
+        _ => "string",
+    };
+    json!({ "type": json_type })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail(input_schema: Option<Value>, parameters: Vec<Parameter>) -> OperationDetail {
+        OperationDetail {
+            operation_id: "post:/pets".to_string(),
+            display_name: "POST /pets".to_string(),
+            description: None,
+            parameters,
+            return_type: None,
+            input_schema,
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn values_respect_enums_formats_and_bounds() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "status", "age", "email", "tags", "score"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "status": { "type": "string", "enum": ["available", "sold"] },
+                "age": { "type": "integer", "minimum": 3, "exclusiveMaximum": 6 },
+                "email": { "type": "string", "format": "email" },
+                "tags": {
+                    "type": "array",
+                    "minItems": 2,
+                    "maxItems": 2,
+                    "items": { "type": "string", "minLength": 4, "maxLength": 4 }
+                },
+                "score": { "type": "number", "minimum": 0.5, "maximum": 0.75 }
+            }
+        });
+
+        let mut generator = Generator::new(42);
+        for _ in 0..50 {
+            let value = generator.value(&schema);
+            let id = value["id"].as_str().unwrap();
+            assert_eq!(id.len(), 36);
+            assert_eq!(&id[14..15], "4");
+            assert!(["available", "sold"].contains(&value["status"].as_str().unwrap()));
+            assert!((3..6).contains(&value["age"].as_i64().unwrap()));
+            assert!(value["email"].as_str().unwrap().ends_with("@example.com"));
+            let tags = value["tags"].as_array().unwrap();
+            assert_eq!(tags.len(), 2);
+            assert!(tags.iter().all(|tag| tag.as_str().unwrap().len() == 4));
+            assert!((0.5..=0.75).contains(&value["score"].as_f64().unwrap()));
+        }
+
+        // The same seed reproduces the same values
+        assert_eq!(
+            Generator::new(7).value(&schema),
+            Generator::new(7).value(&schema)
+        );
+    }
+
+    #[test]
+    fn example_prefers_declared_values_then_enums_then_placeholders() {
+        let body = json!({
+            "kind": "openapi_request_body",
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": { "type": "string", "example": "Rex" },
+                            "status": { "type": "string", "enum": ["available", "sold"] },
+                            "age": { "type": "integer", "minimum": 1 },
+                            "vaccinated": { "type": "boolean", "default": true },
+                            "born": { "type": "string", "format": "date" },
+                            "owner": { "type": "object", "properties": { "email": { "type": "string", "format": "email" } } },
+                            "tags": { "type": "array", "items": { "type": "string" } }
+                        }
+                    }
+                }
+            }
+        });
+        let dry_run = Parameter {
+            name: "dry_run".to_string(),
+            param_type: "boolean".to_string(),
+            required: false,
+            description: None,
+            location: None,
+        };
+        assert_eq!(
+            example(&detail(Some(body), vec![dry_run])),
+            json!({
+                "name": "Rex",
+                "status": "available",
+                "age": 1,
+                "vaccinated": true,
+                "born": "2024-01-01",
+                "owner": { "email": "user@example.com" },
+                "tags": ["string"],
+                "dry_run": false
+            })
+        );
+    }
+
+    #[test]
+    fn payload_schema_merges_body_and_parameters() {
+        let body = json!({
+            "kind": "openapi_request_body",
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": { "name": { "type": "string" } }
+                    }
+                }
+            }
+        });
+        let limit = Parameter {
+            name: "limit".to_string(),
+            param_type: "integer".to_string(),
+            required: true,
+            description: None,
+            location: None,
+        };
+
+        let schema = payload_schema(&detail(Some(body), vec![limit.clone()]));
+        assert_eq!(schema["properties"]["name"]["type"], "string");
+        assert_eq!(schema["properties"]["limit"]["type"], "integer");
+        assert_eq!(schema["required"], json!(["name", "limit"]));
+
+        let graphql = Parameter {
+            name: "ids".to_string(),
+            param_type: "[Int!]!".to_string(),
+            required: true,
+            description: None,
+            location: None,
+        };
+        let schema = payload_schema(&detail(None, vec![graphql]));
+        assert_eq!(schema["properties"]["ids"]["items"]["type"], "integer");
+
+        let openrpc = json!({
+            "kind": "openrpc_method",
+            "params": [{ "name": "a", "required": true, "schema": { "type": "boolean" } }]
+        });
+        let schema = payload_schema(&detail(Some(openrpc), Vec::new()));
+        assert_eq!(schema["properties"]["a"]["type"], "boolean");
+        assert_eq!(schema["required"], json!(["a"]));
+    }
+
+    #[test]
+    fn examples_and_references_are_used_when_asked() {
+        let document = json!({
+            "components": { "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name", "kind"],
+                    "properties": {
+                        "name": { "type": "string", "example": "Rex" },
+                        "kind": { "type": "string", "default": "dog" },
+                        "owner": { "$ref": "#/components/schemas/Pet" }
+                    }
+                }
+            } }
+        });
+        let schema = json!({ "type": "array", "minItems": 1, "items": { "$ref": "#/components/schemas/Pet" } });
+
+        let pets = Generator::new(7)
+            .with_document(document.clone())
+            .with_examples()
+            .value(&schema);
+        assert_eq!(pets[0]["name"], "Rex");
+        assert_eq!(pets[0]["kind"], "dog");
+
+        let pets = Generator::new(7).with_document(document).value(&schema);
+        assert!(pets[0]["name"].is_string());
+        assert_ne!(pets[0]["name"], "Rex");
+    }
+}
@@ -0,0 +1,142 @@
+//! Structural differences between two JSON values
+//!
+//! Used by `--watch --changes-only` to report what changed between two
+//! results. Objects are compared key by key and arrays index by index; every
+//! other value is compared whole. Each difference is reported at the JSON
+//! pointer (RFC 6901) of the value that changed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// One difference between two values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    /// JSON pointer of the changed value, `""` for the whole value
+    pub path: String,
+
+    /// Value before the change, absent when it was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+
+    /// Value after the change, absent when it was removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+impl fmt::Display for Change {
+    /// `+ /path: value`, `- /path: value` or `~ /path: before -> after`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        match (&self.before, &self.after) {
+            (None, Some(after)) => write!(f, "+ {}: {}", path, after),
+            (Some(before), None) => write!(f, "- {}: {}", path, before),
+            (Some(before), Some(after)) => write!(f, "~ {}: {} -> {}", path, before, after),
+            (None, None) => write!(f, "~ {}", path),
+        }
+    }
+}
+
+/// Differences that turn `before` into `after`, empty when they are equal
+pub fn diff(before: &Value, after: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_at(String::new(), before, after, &mut changes);
+    changes
+}
+
+fn diff_at(path: String, before: &Value, after: &Value, changes: &mut Vec<Change>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let child = format!("{}/{}", path, escape(key));
+                match new.get(key) {
+                    Some(new_value) => diff_at(child, old_value, new_value, changes),
+                    None => changes.push(Change {
+                        path: child,
+                        before: Some(old_value.clone()),
+                        after: None,
+                    }),
+                }
+            }
+            for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                changes.push(Change {
+                    path: format!("{}/{}", path, escape(key)),
+                    before: None,
+                    after: Some(new_value.clone()),
+                });
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let child = format!("{}/{}", path, index);
+                match (old.get(index), new.get(index)) {
+                    (Some(old_value), Some(new_value)) => {
+                        diff_at(child, old_value, new_value, changes)
+                    }
+                    (old_value, new_value) => changes.push(Change {
+                        path: child,
+                        before: old_value.cloned(),
+                        after: new_value.cloned(),
+                    }),
+                }
+            }
+        }
+        _ if before != after => changes.push(Change {
+            path,
+            before: Some(before.clone()),
+            after: Some(after.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Escape a key for use in a JSON pointer
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn changes_are_reported_at_their_pointer() {
+        let before = json!({
+            "status": "running",
+            "progress": { "done": 3, "total": 10 },
+            "steps": ["fetch", "build"],
+            "a/b": 1,
+            "eta": 30
+        });
+        let after = json!({
+            "status": "done",
+            "progress": { "done": 10, "total": 10 },
+            "steps": ["fetch", "build", "deploy"],
+            "a/b": 2,
+            "result": "ok"
+        });
+        let changes = diff(&before, &after);
+        let rendered: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "~ /a~1b: 1 -> 2",
+                "- /eta: 30",
+                "~ /progress/done: 3 -> 10",
+                "~ /status: \"running\" -> \"done\"",
+                "+ /steps/2: \"deploy\"",
+                "+ /result: \"ok\"",
+            ]
+        );
+        assert!(diff(&after, &after).is_empty());
+        assert_eq!(
+            diff(&json!(1), &json!("1"))[0].to_string(),
+            "~ /: 1 -> \"1\""
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! `.env` files and `${VAR}` substitution
+//!
+//! Variables from a `.env` file (or `--env-file`) are held in memory for the
+//! current invocation only; they are never written to the process
+//! environment or to disk. Real environment variables take precedence over
+//! file entries, matching the usual dotenv convention.
+//!
+//! `${NAME}` and `${NAME:-default}` are expanded in endpoint URLs and call
+//! arguments, and `env:NAME` secret references see file entries too.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+/// File loaded from the working directory when `--env-file` is not given
+pub const DEFAULT_ENV_FILE: &str = ".env";
+
+fn overlay() -> &'static RwLock<HashMap<String, String>> {
+    static OVERLAY: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+    OVERLAY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Load variables from `path` into the invocation scope.
+///
+/// Later files override earlier ones. Returns the number of variables read.
+pub fn load(path: &Path) -> Result<usize> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file {}", path.display()))?;
+    let vars = parse(&content).with_context(|| format!("Invalid env file {}", path.display()))?;
+    let count = vars.len();
+
+    if let Ok(mut overlay) = overlay().write() {
+        overlay.extend(vars);
+    }
+    Ok(count)
+}
+
+/// Forget loaded files, before a long-lived process runs another command.
+pub fn clear() {
+    if let Ok(mut overlay) = overlay().write() {
+        overlay.clear();
+    }
+}
+
+/// Look up a variable: the process environment first, then loaded files.
+pub fn var(name: &str) -> Option<String> {
+    if let Ok(value) = std::env::var(name) {
+        return Some(value);
+    }
+    overlay()
+        .read()
+        .ok()
+        .and_then(|overlay| overlay.get(name).cloned())
+}
+
+/// Parse dotenv syntax.
+///
+/// Supports `KEY=value`, an optional `export ` prefix, `#` comments, and
+/// single- or double-quoted values. Double-quoted values understand `\n`,
+/// `\t`, `\"` and `\\`; unquoted values end at ` #`.
+pub fn parse(content: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, raw)) = line.split_once('=') else {
+            bail!("line {}: expected KEY=value", idx + 1);
+        };
+
+        let key = key.trim();
+        if !is_valid_name(key) {
+            bail!("line {}: invalid variable name '{}'", idx + 1, key);
+        }
+        let value = parse_value(raw.trim())
+            .with_context(|| format!("line {}: invalid value for {}", idx + 1, key))?;
+        vars.push((key.to_string(), value));
+    }
+
+    Ok(vars)
+}
+
+fn parse_value(raw: &str) -> Result<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let Some(end) = rest.find('\'') else {
+            bail!("unterminated single quote");
+        };
+        return Ok(rest[..end].to_string());
+    }
+
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Ok(value),
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        bail!("unterminated double quote");
+    }
+
+    let value = match raw.find(" #") {
+        Some(idx) => &raw[..idx],
+        None => raw,
+    };
+    Ok(value.trim_end().to_string())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Expand `${NAME}` and `${NAME:-default}` using [`var`].
+///
+/// `$` not followed by a valid `{NAME}` is kept as-is. Unset variables
+/// without a default are an error so a typo never reaches the wire.
+pub fn substitute(input: &str) -> Result<String> {
+    substitute_with(input, &var)
+}
+
+fn substitute_with(input: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            output.push_str(&rest[start..]);
+            return Ok(output);
+        };
+
+        let expr = &after[..end];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+        if !is_valid_name(name) {
+            output.push_str(&rest[start..start + 2 + end + 1]);
+        } else {
+            // Like the shell, `:-` also replaces an empty value.
+            match (lookup(name), default) {
+                (Some(value), Some(default)) if value.is_empty() => output.push_str(default),
+                (Some(value), _) => {
+                    if crate::masking::is_secret_name(name) {
+                        crate::masking::register(&value);
+                    }
+                    output.push_str(&value)
+                }
+                (None, Some(default)) => output.push_str(default),
+                (None, None) => bail!("Variable '{}' is not set", name),
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Apply [`substitute`] to every string inside a JSON value.
+pub fn substitute_value(value: &Value) -> Result<Value> {
+    Ok(match value {
+        Value::String(text) => Value::String(substitute(text)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(substitute_value)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), substitute_value(value)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dotenv_syntax() {
+        let vars = parse(
+            r#"
+# comment
+API_URL=https://staging.example.com
+export TOKEN="a\"b\nc"
+RAW='${NOT_EXPANDED}'
+TRAILING=value # note
+EMPTY=
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "API_URL".to_string(),
+                    "https://staging.example.com".to_string()
+                ),
+                ("TOKEN".to_string(), "a\"b\nc".to_string()),
+                ("RAW".to_string(), "${NOT_EXPANDED}".to_string()),
+                ("TRAILING".to_string(), "value".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_lines() {
+        assert!(parse("NOT A PAIR").is_err());
+        assert!(parse("1BAD=x").is_err());
+        assert!(parse("OPEN=\"unterminated").is_err());
+    }
+
+    #[test]
+    fn substitutes_variables_and_defaults() {
+        let lookup = |name: &str| match name {
+            "HOST" => Some("api.example.com".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+
+        assert_eq!(
+            substitute_with("https://${HOST}/v${VERSION:-1}", &lookup).unwrap(),
+            "https://api.example.com/v1"
+        );
+        assert_eq!(substitute_with("${EMPTY:-x}", &lookup).unwrap(), "x");
+        assert_eq!(substitute_with("[${EMPTY}]", &lookup).unwrap(), "[]");
+        assert_eq!(
+            substitute_with("cost: $5 ${not valid} ${", &lookup).unwrap(),
+            "cost: $5 ${not valid} ${"
+        );
+        assert!(substitute_with("${MISSING}", &lookup)
+            .unwrap_err()
+            .to_string()
+            .contains("'MISSING' is not set"));
+    }
+}
@@ -0,0 +1,233 @@
+//! UXC error types
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, UxcError>;
+
+#[derive(Error, Debug)]
+pub enum UxcError {
+    #[error("Protocol detection failed: {0}")]
+    ProtocolDetectionFailed(String),
+
+    #[error("Unsupported protocol: {0}")]
+    UnsupportedProtocol(String),
+
+    #[error("Schema retrieval failed: {0}")]
+    SchemaRetrievalFailed(String),
+
+    #[error("Operation not found: {0}")]
+    OperationNotFound(String),
+
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+
+    #[error("Execution failed: {0}")]
+    ExecutionFailed(String),
+
+    #[error("Host not allowed: {0}")]
+    HostNotAllowed(String),
+
+    #[error("Offline: {0}")]
+    OfflineMiss(String),
+
+    #[error("Snapshot not found: {0}")]
+    SnapshotNotFound(String),
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Expectation failed: {0}")]
+    ExpectationFailed(String),
+
+    /// A call rejected by the remote side, with the protocol's own error data
+    #[error("{message}")]
+    CallFailed {
+        message: String,
+        detail: ErrorDetail,
+    },
+
+    #[error("Network error: {0}")]
+    NetworkError(#[from] reqwest::Error),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Generic error: {0}")]
+    GenericError(#[from] anyhow::Error),
+}
+
+impl UxcError {
+    /// [`UxcError::CallFailed`] with `message` and `detail`
+    pub fn call_failed(message: impl Into<String>, detail: ErrorDetail) -> Self {
+        Self::CallFailed {
+            message: message.into(),
+            detail,
+        }
+    }
+
+    /// Stable code reported as `error.code` in envelopes
+    pub fn code(&self) -> &'static str {
+        match self {
+            UxcError::ProtocolDetectionFailed(_) | UxcError::UnsupportedProtocol(_) => {
+                "PROTOCOL_DETECTION_FAILED"
+            }
+            UxcError::OperationNotFound(_) => "OPERATION_NOT_FOUND",
+            UxcError::InvalidArguments(_) => "INVALID_ARGUMENT",
+            UxcError::HostNotAllowed(_) => "HOST_NOT_ALLOWED",
+            UxcError::OfflineMiss(_) => "OFFLINE_MISS",
+            UxcError::SnapshotNotFound(_) => "SNAPSHOT_NOT_FOUND",
+            UxcError::Timeout(_) => "TIMEOUT",
+            UxcError::ExpectationFailed(_) => "EXPECTATION_FAILED",
+            UxcError::ExecutionFailed(_)
+            | UxcError::CallFailed { .. }
+            | UxcError::SchemaRetrievalFailed(_)
+            | UxcError::NetworkError(_)
+            | UxcError::JsonError(_)
+            | UxcError::IoError(_)
+            | UxcError::GenericError(_) => "EXECUTION_FAILED",
+        }
+    }
+}
+
+/// Code of the first [`UxcError`] in the chain of `err`; malformed JSON input
+/// is an `INVALID_ARGUMENT`, anything else an `EXECUTION_FAILED`
+pub fn code_of(err: &anyhow::Error) -> &'static str {
+    for cause in err.chain() {
+        if let Some(uxc_error) = cause.downcast_ref::<UxcError>() {
+            return uxc_error.code();
+        }
+        if cause.downcast_ref::<serde_json::Error>().is_some() {
+            return "INVALID_ARGUMENT";
+        }
+    }
+    "EXECUTION_FAILED"
+}
+
+/// HTTP status of the first rejected call in the chain of `err`
+pub fn http_status_of(err: &anyhow::Error) -> Option<u16> {
+    err.chain().find_map(|cause| match cause.downcast_ref() {
+        Some(UxcError::CallFailed {
+            detail: ErrorDetail::Http { status, .. },
+            ..
+        }) => Some(*status),
+        _ => None,
+    })
+}
+
+/// Longest HTTP body excerpt kept in an [`ErrorDetail`], in bytes
+pub const BODY_EXCERPT_LIMIT: usize = 2048;
+
+/// Protocol-level detail of a failed call, reported as `error.detail`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ErrorDetail {
+    /// Non-success HTTP response
+    Http {
+        status: u16,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+        /// Seconds the server asked to wait before retrying (`Retry-After`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retry_after: Option<u64>,
+    },
+
+    /// gRPC status, with `google.rpc.Status` details when the server sent them
+    Grpc {
+        code: i32,
+        status: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        details: Vec<Value>,
+    },
+
+    /// `errors` array of a GraphQL response
+    Graphql { errors: Vec<Value> },
+
+    /// JSON-RPC error object
+    JsonRpc {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<i64>,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        data: Option<Value>,
+    },
+}
+
+impl ErrorDetail {
+    /// HTTP detail with the body cut to [`BODY_EXCERPT_LIMIT`] bytes
+    pub fn http(status: u16, body: &str) -> Self {
+        let body = body.trim();
+        let excerpt = if body.len() > BODY_EXCERPT_LIMIT {
+            let mut end = BODY_EXCERPT_LIMIT;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}...", &body[..end])
+        } else {
+            body.to_string()
+        };
+        Self::Http {
+            status,
+            body: (!excerpt.is_empty()).then_some(excerpt),
+            retry_after: None,
+        }
+    }
+
+    /// The detail with the delay an HTTP response asked for (see
+    /// [`crate::http::retry_after`]); other details are returned as they are
+    pub fn with_retry_after(mut self, secs: Option<u64>) -> Self {
+        if let Self::Http { retry_after, .. } = &mut self {
+            *retry_after = secs;
+        }
+        self
+    }
+
+    /// Whether the same call may succeed when repeated: throttling, gateway
+    /// and availability failures
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Http { status, .. } => matches!(status, 408 | 425 | 429 | 502 | 503 | 504),
+            // DEADLINE_EXCEEDED, RESOURCE_EXHAUSTED, ABORTED, UNAVAILABLE
+            Self::Grpc { code, .. } => matches!(code, 4 | 8 | 10 | 14),
+            Self::Graphql { .. } | Self::JsonRpc { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_detail_excerpt_and_retriable() {
+        let long = "é".repeat(BODY_EXCERPT_LIMIT);
+        let ErrorDetail::Http {
+            body: Some(body), ..
+        } = ErrorDetail::http(500, &long)
+        else {
+            panic!("expected an HTTP body excerpt");
+        };
+        assert!(body.len() <= BODY_EXCERPT_LIMIT + 3);
+        assert!(body.ends_with("..."));
+
+        assert_eq!(
+            ErrorDetail::http(404, "  "),
+            ErrorDetail::Http {
+                status: 404,
+                body: None,
+                retry_after: None
+            }
+        );
+        assert!(ErrorDetail::http(503, "").is_retriable());
+        assert!(!ErrorDetail::http(400, "").is_retriable());
+        assert_eq!(
+            serde_json::to_value(ErrorDetail::http(429, "slow down")).unwrap(),
+            serde_json::json!({"type": "http", "status": 429, "body": "slow down"})
+        );
+    }
+}
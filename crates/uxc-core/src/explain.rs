@@ -0,0 +1,82 @@
+//! Decision recording for `--explain`
+//!
+//! Modules call [`record`] when they pick one path over another: which
+//! protocol probe matched, where an API key goes, why the schema cache was
+//! skipped, which schema mapping rule won. With `--explain` the decisions
+//! are collected and attached to the envelope as `meta.explain`; otherwise
+//! recording is a no-op.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DECISIONS: Mutex<Vec<Decision>> = Mutex::new(Vec::new());
+
+/// One recorded decision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Area the decision belongs to: `detection`, `auth`, `cache`,
+    /// `schema_mapping`, `schema`
+    pub topic: String,
+
+    /// What was decided and why
+    pub decision: String,
+}
+
+/// Enable or disable decision recording for this process
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether decisions are being recorded
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Record a decision. Repeats of the same decision (one per request of a
+/// paginated or batched call, say) are kept once.
+pub fn record(topic: &str, decision: impl Into<String>) {
+    if !is_enabled() {
+        return;
+    }
+    let decision = Decision {
+        topic: topic.to_string(),
+        decision: crate::masking::mask(&decision.into()),
+    };
+    let mut decisions = DECISIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if !decisions.contains(&decision) {
+        decisions.push(decision);
+    }
+}
+
+/// Decisions recorded so far, in order; `None` when recording is disabled
+pub fn take() -> Option<Vec<Decision>> {
+    if !is_enabled() {
+        return None;
+    }
+    let mut decisions = DECISIONS.lock().unwrap_or_else(|e| e.into_inner());
+    Some(std::mem::take(&mut *decisions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_only_when_enabled_and_drops_repeats() {
+        record("cache", "ignored");
+        assert_eq!(take(), None);
+
+        set_enabled(true);
+        record("detection", "openapi probe matched");
+        record("auth", "bearer token");
+        record("detection", "openapi probe matched");
+        let decisions = take().unwrap();
+        set_enabled(false);
+
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].topic, "detection");
+        assert_eq!(decisions[1].decision, "bearer token");
+    }
+}
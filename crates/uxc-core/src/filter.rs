@@ -0,0 +1,138 @@
+//! Operation filtering for `list`
+//!
+//! `list --tag`, `--method` and `--path` narrow the operations of an endpoint.
+//! The method of an operation is the HTTP method of OpenAPI ids
+//! (`get:/users`) or the kind of GraphQL ids (`query/user`); its path is the
+//! rest of an OpenAPI id, or the whole id for other protocols, so
+//! `--path 'pkg.Users/*'` narrows gRPC services too.
+
+use crate::adapters::Operation;
+
+/// Criteria an operation must meet to be listed; empty criteria match all
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationFilter {
+    /// Tags, any of which the operation must have (case-insensitive)
+    pub tags: Vec<String>,
+    /// Methods, any of which the operation must use (case-insensitive)
+    pub methods: Vec<String>,
+    /// Glob the operation path must match: `*` within a segment, `**` across
+    /// segments
+    pub path: Option<String>,
+}
+
+impl OperationFilter {
+    /// Whether nothing is filtered out
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.methods.is_empty() && self.path.is_none()
+    }
+
+    /// Whether `operation`, listed as `operation_id`, meets the criteria
+    pub fn matches(&self, operation_id: &str, operation: &Operation) -> bool {
+        let (method, path) = split(operation_id);
+        let tagged = self.tags.is_empty()
+            || self.tags.iter().any(|tag| {
+                operation
+                    .tags
+                    .iter()
+                    .any(|have| have.eq_ignore_ascii_case(tag))
+            });
+        let method_matches = self.methods.is_empty()
+            || method.is_some_and(|method| {
+                self.methods
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(method))
+            });
+        let path_matches = self
+            .path
+            .as_deref()
+            .is_none_or(|pattern| glob(pattern, path));
+        tagged && method_matches && path_matches
+    }
+}
+
+/// Method and path of an operation id
+fn split(operation_id: &str) -> (Option<&str>, &str) {
+    if let Some((method, path)) = operation_id.split_once(':') {
+        if path.starts_with('/') {
+            return (Some(method), path);
+        }
+    }
+    if let Some((kind, _)) = operation_id.split_once('/') {
+        if matches!(kind, "query" | "mutation" | "subscription") {
+            return (Some(kind), operation_id);
+        }
+    }
+    (None, operation_id)
+}
+
+/// Match `text` against `pattern`: `*` matches within a path segment, `**`
+/// across segments, and a trailing `/**` also matches the bare prefix
+pub fn glob(pattern: &str, text: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix("/**") {
+        if glob(prefix, text) {
+            return true;
+        }
+    }
+    glob_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|i| glob_bytes(rest, &text[i..])),
+        [b'*', rest @ ..] => {
+            let segment = text.iter().position(|&c| c == b'/').unwrap_or(text.len());
+            (0..=segment).any(|i| glob_bytes(rest, &text[i..]))
+        }
+        [c, rest @ ..] => text.first() == Some(c) && glob_bytes(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(tags: &[&str]) -> Operation {
+        Operation {
+            operation_id: String::new(),
+            display_name: String::new(),
+            description: None,
+            parameters: Vec::new(),
+            return_type: None,
+            tags: tags.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn glob_separates_segments() {
+        assert!(glob("/repos/*", "/repos/{owner}"));
+        assert!(!glob("/repos/*", "/repos/{owner}/{repo}"));
+        assert!(glob("/repos/**", "/repos/{owner}/{repo}"));
+        assert!(glob("/repos/**", "/repos"));
+        assert!(!glob("/repos/**", "/reposx"));
+        assert!(glob("/**/issues", "/repos/{owner}/{repo}/issues"));
+        assert!(glob("*.Users/*", "pkg.Users/Get"));
+    }
+
+    #[test]
+    fn filter_matches_tags_methods_and_paths() {
+        let filter = OperationFilter {
+            tags: vec!["Repos".to_string()],
+            methods: vec!["GET".to_string()],
+            path: Some("/repos/**".to_string()),
+        };
+        assert!(filter.matches("get:/repos/{owner}", &operation(&["repos"])));
+        assert!(!filter.matches("post:/repos/{owner}", &operation(&["repos"])));
+        assert!(!filter.matches("get:/repos/{owner}", &operation(&["users"])));
+        assert!(!filter.matches("get:/users", &operation(&["repos"])));
+
+        let graphql = OperationFilter {
+            methods: vec!["mutation".to_string()],
+            ..Default::default()
+        };
+        assert!(graphql.matches("mutation/createUser", &operation(&[])));
+        assert!(!graphql.matches("query/user", &operation(&[])));
+        assert!(!graphql.matches("pkg.Users/Get", &operation(&[])));
+        assert!(OperationFilter::default().matches("pkg.Users/Get", &operation(&[])));
+    }
+}
@@ -0,0 +1,365 @@
+//! HTTP gateway (`uxc serve http`)
+//!
+//! Exposes the operations of one endpoint, whatever its protocol, as a small
+//! REST API, so tools that only speak HTTP can call gRPC, MCP or GraphQL
+//! services through uxc:
+//!
+//! - `GET /ops` lists the operations
+//! - `GET /ops/<operation_id>` describes one
+//! - `POST /ops/<operation_id>` calls it with the JSON object in the body
+//!
+//! Operation IDs are percent-decoded, so `get:/users/{id}` can be sent as
+//! `get:%2Fusers%2F%7Bid%7D` or as is. Responses are uxc envelopes; what
+//! serves a [`GatewayRequest`] is up to the caller. With metrics, `GET
+//! /metrics` returns them in the Prometheus text format, on the gateway's
+//! address or a separate one.
+
+use crate::error::UxcError;
+use crate::instrumentation::PrometheusInstrumentation;
+use crate::output::OutputEnvelope;
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// What a gateway request asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum GatewayRequest {
+    List,
+    Describe {
+        operation_id: String,
+    },
+    Call {
+        operation_id: String,
+        args: Map<String, Value>,
+    },
+}
+
+/// Envelope answering a request, with its HTTP status
+#[derive(Debug, Clone)]
+pub struct GatewayResponse {
+    pub status: u16,
+    pub envelope: OutputEnvelope,
+}
+
+impl GatewayResponse {
+    pub fn ok(envelope: OutputEnvelope) -> Self {
+        Self {
+            status: 200,
+            envelope,
+        }
+    }
+}
+
+/// A request turned away before it reaches the endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    pub status: u16,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Rejection {
+    fn new(status: u16, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Rejection> for GatewayResponse {
+    fn from(rejection: Rejection) -> Self {
+        Self {
+            status: rejection.status,
+            envelope: OutputEnvelope::error(rejection.code, &rejection.message),
+        }
+    }
+}
+
+/// The request for `method` and `path`, or the response rejecting it
+pub fn route(
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> std::result::Result<GatewayRequest, Rejection> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let operation_id = match path.trim_end_matches('/') {
+        "/ops" => None,
+        _ => match path.strip_prefix("/ops/") {
+            Some(id) if !id.is_empty() => Some(
+                percent_encoding::percent_decode_str(id)
+                    .decode_utf8_lossy()
+                    .into_owned(),
+            ),
+            _ => {
+                return Err(Rejection::new(
+                    404,
+                    "NOT_FOUND",
+                    format!("No route for {}; use /ops or /ops/<operation_id>", path),
+                ))
+            }
+        },
+    };
+
+    match (method, operation_id) {
+        ("GET", None) => Ok(GatewayRequest::List),
+        ("GET", Some(operation_id)) => Ok(GatewayRequest::Describe { operation_id }),
+        ("POST", Some(operation_id)) => Ok(GatewayRequest::Call {
+            operation_id,
+            args: arguments(body)?,
+        }),
+        (_, None) => Err(Rejection::new(
+            405,
+            "METHOD_NOT_ALLOWED",
+            format!("{} is not allowed on /ops; use GET", method),
+        )),
+        (_, Some(_)) => Err(Rejection::new(
+            405,
+            "METHOD_NOT_ALLOWED",
+            format!(
+                "{} is not allowed on an operation; use GET to describe it or POST to call it",
+                method
+            ),
+        )),
+    }
+}
+
+/// Arguments in a request body: a JSON object, or nothing
+fn arguments(body: &[u8]) -> std::result::Result<Map<String, Value>, Rejection> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Map::new());
+    }
+    match serde_json::from_slice(body) {
+        Ok(Value::Object(args)) => Ok(args),
+        Ok(_) => Err(Rejection::new(
+            400,
+            "INVALID_ARGUMENT",
+            "The request body must be a JSON object of arguments",
+        )),
+        Err(err) => Err(Rejection::new(
+            400,
+            "INVALID_ARGUMENT",
+            format!("The request body is not valid JSON: {}", err),
+        )),
+    }
+}
+
+/// HTTP status for a failure with error `code`. A call the upstream service
+/// rejected keeps the status it answered with.
+pub fn status_for(code: &str, upstream: Option<u16>) -> u16 {
+    if let Some(status) = upstream.filter(|status| (400..600).contains(status)) {
+        return status;
+    }
+    match code {
+        "INVALID_ARGUMENT" => 400,
+        "HOST_NOT_ALLOWED" => 403,
+        "OPERATION_NOT_FOUND" => 404,
+        "TIMEOUT" => 504,
+        _ => 502,
+    }
+}
+
+/// Where a gateway listens and what it exposes besides the operations
+#[derive(Debug, Clone)]
+pub struct GatewayOptions {
+    pub addr: SocketAddr,
+
+    /// Metrics served at `GET /metrics`
+    pub metrics: Option<Arc<PrometheusInstrumentation>>,
+
+    /// Serve the metrics on this address instead of `addr`
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+/// Addresses a gateway is bound to
+#[derive(Debug, Clone, Copy)]
+pub struct Bound {
+    pub addr: SocketAddr,
+    /// Separate metrics listener, when there is one
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+/// Serve requests with `handle` until Ctrl-C. `ready` is called with the
+/// bound addresses once the gateway accepts connections; the number of
+/// requests served, metrics scrapes aside, is returned.
+pub async fn serve<H, Fut>(
+    options: GatewayOptions,
+    handle: H,
+    ready: impl FnOnce(&Bound),
+) -> Result<u64>
+where
+    H: Fn(GatewayRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = GatewayResponse> + Send,
+{
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response};
+
+    let handle = Arc::new(handle);
+    let served = Arc::new(AtomicU64::new(0));
+    let inline_metrics = options
+        .metrics
+        .clone()
+        .filter(|_| options.metrics_addr.is_none());
+    let make_service = {
+        let served = served.clone();
+        make_service_fn(move |_| {
+            let (handle, served, metrics) =
+                (handle.clone(), served.clone(), inline_metrics.clone());
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
+                    let (handle, served, metrics) =
+                        (handle.clone(), served.clone(), metrics.clone());
+                    async move {
+                        let method = request.method().to_string();
+                        let path = request.uri().path().to_string();
+                        if let Some(metrics) = metrics.filter(|_| is_scrape(&method, &path)) {
+                            return Ok(metrics_response(&metrics));
+                        }
+                        let body = hyper::body::to_bytes(request.into_body()).await?;
+                        let response = match route(&method, &path, &body) {
+                            Ok(request) => handle(request).await,
+                            Err(rejection) => rejection.into(),
+                        };
+                        served.fetch_add(1, Ordering::Relaxed);
+                        tracing::info!("{} {} -> {}", method, path, response.status);
+
+                        let body = response
+                            .envelope
+                            .to_json()
+                            .unwrap_or_else(|err| err.to_string());
+                        let response = Response::builder()
+                            .status(response.status)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body));
+                        Ok::<_, hyper::Error>(response.unwrap_or_default())
+                    }
+                }))
+            }
+        })
+    };
+    let gateway = bind(options.addr)?.serve(make_service);
+
+    let metrics_server = match (options.metrics, options.metrics_addr) {
+        (Some(metrics), Some(addr)) => {
+            let make_service = make_service_fn(move |_| {
+                let metrics = metrics.clone();
+                async move {
+                    Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
+                        let metrics = metrics.clone();
+                        async move {
+                            let response =
+                                if is_scrape(request.method().as_str(), request.uri().path()) {
+                                    metrics_response(&metrics)
+                                } else {
+                                    Response::builder()
+                                        .status(404)
+                                        .body(Body::from("Metrics are served at /metrics\n"))
+                                        .unwrap_or_default()
+                                };
+                            Ok::<_, hyper::Error>(response)
+                        }
+                    }))
+                }
+            });
+            Some(bind(addr)?.serve(make_service))
+        }
+        _ => None,
+    };
+
+    ready(&Bound {
+        addr: gateway.local_addr(),
+        metrics_addr: metrics_server.as_ref().map(|server| server.local_addr()),
+    });
+    let gateway = gateway.with_graceful_shutdown(shutdown());
+    match metrics_server {
+        Some(metrics_server) => {
+            futures::future::try_join(gateway, metrics_server.with_graceful_shutdown(shutdown()))
+                .await?;
+        }
+        None => gateway.await?,
+    }
+    Ok(served.load(Ordering::Relaxed))
+}
+
+fn bind(addr: SocketAddr) -> Result<hyper::server::Builder<hyper::server::conn::AddrIncoming>> {
+    Ok(hyper::Server::try_bind(&addr)
+        .map_err(|e| UxcError::InvalidArguments(format!("Cannot listen on {}: {}", addr, e)))?)
+}
+
+async fn shutdown() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+fn is_scrape(method: &str, path: &str) -> bool {
+    method == "GET" && path == "/metrics"
+}
+
+fn metrics_response(metrics: &PrometheusInstrumentation) -> hyper::Response<hyper::Body> {
+    hyper::Response::builder()
+        .header("content-type", "text/plain; version=0.0.4")
+        .body(hyper::Body::from(metrics.render()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn routes_list_describe_and_call() {
+        assert_eq!(route("GET", "/ops", b"").unwrap(), GatewayRequest::List);
+        assert_eq!(
+            route("GET", "/ops/?verbose", b"").unwrap(),
+            GatewayRequest::List
+        );
+        assert_eq!(
+            route("GET", "/ops/get:%2Fusers%2F%7Bid%7D", b"").unwrap(),
+            GatewayRequest::Describe {
+                operation_id: "get:/users/{id}".to_string()
+            }
+        );
+        assert_eq!(
+            route("POST", "/ops/query/user", br#"{"id": 1}"#).unwrap(),
+            GatewayRequest::Call {
+                operation_id: "query/user".to_string(),
+                args: json!({ "id": 1 }).as_object().unwrap().clone(),
+            }
+        );
+        assert_eq!(
+            route("POST", "/ops/ping", b"").unwrap(),
+            GatewayRequest::Call {
+                operation_id: "ping".to_string(),
+                args: Map::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_routes_methods_and_bodies() {
+        let status = |method, path, body: &[u8]| route(method, path, body).unwrap_err().status;
+        assert_eq!(status("GET", "/", b""), 404);
+        assert_eq!(status("GET", "/users", b""), 404);
+        assert_eq!(status("POST", "/ops", b"{}"), 405);
+        assert_eq!(status("DELETE", "/ops/ping", b""), 405);
+        assert_eq!(status("POST", "/ops/ping", b"[1]"), 400);
+
+        let rejection = route("POST", "/ops/ping", b"{").unwrap_err();
+        assert_eq!(rejection.code, "INVALID_ARGUMENT");
+        assert!(rejection.message.contains("not valid JSON"));
+    }
+
+    #[test]
+    fn failures_map_to_http_statuses() {
+        assert_eq!(status_for("INVALID_ARGUMENT", None), 400);
+        assert_eq!(status_for("OPERATION_NOT_FOUND", None), 404);
+        assert_eq!(status_for("TIMEOUT", None), 504);
+        assert_eq!(status_for("EXECUTION_FAILED", None), 502);
+        assert_eq!(status_for("EXECUTION_FAILED", Some(401)), 401);
+        assert_eq!(status_for("EXECUTION_FAILED", Some(200)), 502);
+    }
+}
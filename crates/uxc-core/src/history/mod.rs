@@ -0,0 +1,364 @@
+//! Call history
+//!
+//! Entries are appended as JSON lines to `~/.uxc/history.jsonl`. Operation
+//! calls are recorded as `OK` or `ERROR` and feed usage ranking in `list` and
+//! host help. Blocked attempts (see [`crate::allowlist`]) are recorded with a
+//! `BLOCKED` status so sandboxed agent runs leave an audit trail.
+//!
+//! `uxc history search` and `uxc history stats` query a SQLite index of the
+//! log ([`store::HistoryStore`]) kept in `~/.uxc/history.db`.
+//!
+//! Each entry has a short random ID. Calls also record their arguments as
+//! given, before `${VAR}` substitution, so `uxc history rerun <id>` can
+//! replay them. Arguments with credential-like names and registered secrets
+//! (see [`crate::masking`]) are stored as `***` and listed in `redacted`; a
+//! rerun needs them passed again.
+
+pub mod store;
+
+pub use store::{parse_age, CallStats, HistoryFilter, HistoryStore, WindowStats};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_HISTORY_DIR: &str = ".uxc";
+const DEFAULT_HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HistoryStatus {
+    Ok,
+    Error,
+    Blocked,
+}
+
+impl HistoryStatus {
+    /// Name as written to the history file
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryStatus::Ok => "OK",
+            HistoryStatus::Error => "ERROR",
+            HistoryStatus::Blocked => "BLOCKED",
+        }
+    }
+}
+
+/// One recorded invocation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Short random ID; missing for entries written by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub status: HistoryStatus,
+    pub endpoint: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// How long the call took; missing for blocked attempts and old entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+
+    /// Call arguments as given, secrets replaced by `***`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Map<String, Value>>,
+
+    /// Names of arguments in `args` that were redacted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted: Vec<String>,
+
+    /// Auth profile the call selected explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(status: HistoryStatus, endpoint: &str, operation: Option<&str>) -> Self {
+        Self {
+            id: Some(new_id()),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            status,
+            endpoint: endpoint.to_string(),
+            operation: operation.map(ToString::to_string),
+            error_code: None,
+            message: None,
+            duration_ms: None,
+            args: None,
+            redacted: Vec::new(),
+            profile: None,
+        }
+    }
+
+    /// Record how long the call took.
+    pub fn with_duration(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = Some(duration_ms);
+        self
+    }
+
+    /// Record an error. Registered secrets in `message` are masked.
+    pub fn with_error(mut self, code: &str, message: &str) -> Self {
+        self.error_code = Some(code.to_string());
+        self.message = Some(crate::masking::mask(message));
+        self
+    }
+
+    /// Record the call arguments, redacting secrets.
+    pub fn with_args(mut self, args: &Map<String, Value>) -> Self {
+        let mut redacted = Vec::new();
+        let args = args
+            .iter()
+            .map(|(name, value)| {
+                let stored = redact(name, value);
+                if &stored != value {
+                    redacted.push(name.clone());
+                }
+                (name.clone(), stored)
+            })
+            .collect();
+        redacted.sort();
+        self.args = Some(args);
+        self.redacted = redacted;
+        self
+    }
+
+    /// Record the auth profile the call selected.
+    pub fn with_profile(mut self, profile: Option<&str>) -> Self {
+        self.profile = profile.map(ToString::to_string);
+        self
+    }
+}
+
+/// `value` of argument `name` with credential-like fields and registered
+/// secrets replaced by [`crate::masking::MASK`]
+pub(crate) fn redact(name: &str, value: &Value) -> Value {
+    if crate::masking::is_secret_name(name) {
+        return Value::String(crate::masking::MASK.to_string());
+    }
+    match value {
+        Value::String(text) => Value::String(crate::masking::mask(text)),
+        Value::Array(items) => Value::Array(items.iter().map(|item| redact("", item)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(field, item)| (field.clone(), redact(field, item)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Eight random hex digits
+fn new_id() -> String {
+    let mut bytes = [0u8; 4];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        // Fall back to the clock; IDs only need to be unique within one log
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.subsec_nanos())
+            .unwrap_or_default();
+        bytes = nanos.to_be_bytes();
+    }
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// How often and how recently an operation was called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationUsage {
+    pub count: u64,
+    /// Seconds since the Unix epoch
+    pub last_used: u64,
+}
+
+/// Usage per operation on `endpoint`. Blocked attempts are not counted.
+pub fn operation_usage(
+    entries: &[HistoryEntry],
+    endpoint: &str,
+) -> HashMap<String, OperationUsage> {
+    let mut usage: HashMap<String, OperationUsage> = HashMap::new();
+    for entry in entries {
+        if entry.status == HistoryStatus::Blocked || entry.endpoint != endpoint {
+            continue;
+        }
+        let Some(operation) = &entry.operation else {
+            continue;
+        };
+        let stats = usage.entry(operation.clone()).or_insert(OperationUsage {
+            count: 0,
+            last_used: 0,
+        });
+        stats.count += 1;
+        stats.last_used = stats.last_used.max(entry.timestamp);
+    }
+    usage
+}
+
+/// Failed calls to `endpoint` that the server answered with HTTP 401, a
+/// sign that it wants credentials.
+pub fn unauthorized_calls<'a>(
+    entries: &'a [HistoryEntry],
+    endpoint: &str,
+) -> Vec<&'a HistoryEntry> {
+    entries
+        .iter()
+        .filter(|entry| entry.status == HistoryStatus::Error && entry.endpoint == endpoint)
+        .filter(|entry| {
+            entry
+                .message
+                .as_deref()
+                .is_some_and(|message| message.contains("HTTP 401"))
+        })
+        .collect()
+}
+
+/// The most recent entry with ID `id`
+pub fn find(id: &str) -> Result<Option<HistoryEntry>> {
+    Ok(load()?
+        .into_iter()
+        .rev()
+        .find(|entry| entry.id.as_deref() == Some(id)))
+}
+
+pub(crate) fn history_path() -> Result<PathBuf> {
+    let home = home_dir().context("Could not determine home directory")?;
+    Ok(home.join(DEFAULT_HISTORY_DIR).join(DEFAULT_HISTORY_FILE))
+}
+
+/// Append an entry to the history file.
+pub fn append(entry: &HistoryEntry) -> Result<()> {
+    let path = history_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create history directory: {:?}", parent))?;
+    }
+
+    let line = serde_json::to_string(entry).context("Failed to serialize history entry")?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open history file: {:?}", path))?;
+    writeln!(file, "{}", line).with_context(|| format!("Failed to write history file: {:?}", path))
+}
+
+/// Load all entries, oldest first. Malformed lines are skipped.
+pub fn load() -> Result<Vec<HistoryEntry>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read history file: {:?}", path))?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(user_profile));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_serializes_status_in_uppercase() {
+        let entry = HistoryEntry::new(
+            HistoryStatus::Blocked,
+            "https://evil.example.com",
+            Some("get:/x"),
+        )
+        .with_error("HOST_NOT_ALLOWED", "blocked");
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["status"], "BLOCKED");
+        assert_eq!(json["operation"], "get:/x");
+        assert_eq!(json["error_code"], "HOST_NOT_ALLOWED");
+    }
+
+    #[test]
+    fn arguments_are_stored_with_secrets_redacted() {
+        crate::masking::register("sk-live-history-test");
+        let args = serde_json::json!({
+            "name": "rex",
+            "api_key": "abc",
+            "note": "token sk-live-history-test",
+            "owner": { "password": "hunter2", "email": "a@example.com" },
+            "url": "${BASE_URL}/pets"
+        });
+        let entry = HistoryEntry::new(HistoryStatus::Ok, "https://a.test", Some("post:/pets"))
+            .with_args(args.as_object().unwrap());
+
+        let stored = entry.args.as_ref().unwrap();
+        assert_eq!(stored["name"], "rex");
+        assert_eq!(stored["api_key"], "***");
+        assert_eq!(stored["note"], "token ***");
+        assert_eq!(stored["owner"]["password"], "***");
+        assert_eq!(stored["owner"]["email"], "a@example.com");
+        assert_eq!(stored["url"], "${BASE_URL}/pets");
+        assert_eq!(entry.redacted, vec!["api_key", "note", "owner"]);
+        assert_eq!(entry.id.as_ref().map(String::len), Some(8));
+    }
+
+    #[test]
+    fn usage_counts_calls_per_endpoint() {
+        let at = |timestamp, status, endpoint: &str, operation: &str| HistoryEntry {
+            timestamp,
+            ..HistoryEntry::new(status, endpoint, Some(operation))
+        };
+        let entries = vec![
+            at(10, HistoryStatus::Ok, "https://a.test", "get:/users"),
+            at(30, HistoryStatus::Error, "https://a.test", "get:/users"),
+            at(20, HistoryStatus::Ok, "https://a.test", "get:/pets"),
+            at(40, HistoryStatus::Blocked, "https://a.test", "get:/pets"),
+            at(50, HistoryStatus::Ok, "https://b.test", "get:/pets"),
+        ];
+
+        let usage = operation_usage(&entries, "https://a.test");
+        assert_eq!(
+            usage["get:/users"],
+            OperationUsage {
+                count: 2,
+                last_used: 30
+            }
+        );
+        assert_eq!(
+            usage["get:/pets"],
+            OperationUsage {
+                count: 1,
+                last_used: 20
+            }
+        );
+    }
+}
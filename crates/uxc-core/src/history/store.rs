@@ -0,0 +1,536 @@
+//! SQLite index over the call history log
+//!
+//! `history.jsonl` stays the append-only record; `history.db` next to it
+//! holds the same entries in a `calls` table so searches and statistics are
+//! indexed queries. Opening the store imports whatever was appended to the
+//! log since the last import. The database can be deleted at any time; it is
+//! rebuilt from the log on the next open.
+
+use super::{HistoryEntry, HistoryStatus};
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const DEFAULT_INDEX_FILE: &str = "history.db";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS calls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    operation TEXT,
+    error_code TEXT,
+    message TEXT,
+    duration_ms INTEGER,
+    entry TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_calls_timestamp ON calls(timestamp);
+CREATE INDEX IF NOT EXISTS idx_calls_endpoint_operation ON calls(endpoint, operation);
+CREATE TABLE IF NOT EXISTS sync (
+    name TEXT PRIMARY KEY NOT NULL,
+    value INTEGER NOT NULL
+);
+";
+
+/// Bytes of `history.jsonl` already imported
+const LOG_OFFSET: &str = "log_offset";
+
+/// `PRAGMA user_version` of the current schema; older indexes are rebuilt
+const SCHEMA_VERSION: i64 = 2;
+
+/// Which entries a search or statistics query covers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Endpoint glob (`*` matches any run of characters)
+    pub endpoint: Option<String>,
+
+    /// Operation glob, e.g. `query/*`
+    pub operation: Option<String>,
+
+    pub status: Option<HistoryStatus>,
+
+    /// Only entries at or after this Unix timestamp
+    pub since: Option<u64>,
+}
+
+/// Success rate and latency of one operation on one endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallStats {
+    pub endpoint: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+
+    pub calls: u64,
+    pub ok: u64,
+    pub errors: u64,
+
+    /// Share of calls that succeeded, 0.0 to 1.0
+    pub success_rate: f64,
+
+    /// Median latency of calls with a recorded duration
+    pub p50_ms: Option<u64>,
+
+    /// 95th percentile latency of calls with a recorded duration
+    pub p95_ms: Option<u64>,
+
+    /// Seconds since the Unix epoch
+    pub last_called: u64,
+
+    /// Per-interval breakdown, oldest first, when an interval was requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<WindowStats>,
+}
+
+/// Success rate and latency within one time window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowStats {
+    /// Window start, seconds since the Unix epoch
+    pub start: u64,
+    pub calls: u64,
+    pub ok: u64,
+    pub errors: u64,
+    pub success_rate: f64,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+}
+
+/// SQLite index of the call history
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Open `~/.uxc/history.db` and import new log entries
+    pub fn open() -> Result<Self> {
+        let log = super::history_path()?;
+        let index = log.with_file_name(DEFAULT_INDEX_FILE);
+        Self::open_at(&index, &log)
+    }
+
+    /// Open the index at `index` and import new entries from `log`
+    pub fn open_at(index: &Path, log: &Path) -> Result<Self> {
+        if let Some(parent) = index.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create history directory: {:?}", parent))?;
+        }
+        let conn = Connection::open(index)
+            .with_context(|| format!("Failed to open history index: {:?}", index))?;
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(&format!(
+                "DROP TABLE IF EXISTS calls; DROP TABLE IF EXISTS sync; PRAGMA user_version = {};",
+                SCHEMA_VERSION
+            ))
+            .with_context(|| format!("Failed to upgrade history index: {:?}", index))?;
+        }
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("Failed to initialize history index: {:?}", index))?;
+        let mut store = Self { conn };
+        store.sync(log)?;
+        Ok(store)
+    }
+
+    /// Import complete lines appended to `log` since the last sync. A log
+    /// shorter than what was imported was truncated or replaced, so the
+    /// index is rebuilt from scratch.
+    fn sync(&mut self, log: &Path) -> Result<()> {
+        let mut offset: u64 = self
+            .conn
+            .query_row(
+                "SELECT value FROM sync WHERE name = ?1",
+                params![LOG_OFFSET],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|value| value as u64)
+            .unwrap_or(0);
+        let len = fs::metadata(log).map(|meta| meta.len()).unwrap_or(0);
+        if len < offset {
+            self.conn.execute("DELETE FROM calls", [])?;
+            offset = 0;
+        }
+        if len == offset {
+            return Ok(());
+        }
+
+        let mut file = fs::File::open(log)
+            .with_context(|| format!("Failed to open history file: {:?}", log))?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)
+            .with_context(|| format!("Failed to read history file: {:?}", log))?;
+        // A line still being written is picked up next time
+        let Some(end) = appended.iter().rposition(|&byte| byte == b'\n') else {
+            return Ok(());
+        };
+        let complete = String::from_utf8_lossy(&appended[..=end]);
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO calls (timestamp, status, endpoint, operation, error_code, message, duration_ms, entry)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for (line, entry) in complete.lines().filter_map(|line| {
+                serde_json::from_str::<HistoryEntry>(line)
+                    .ok()
+                    .map(|entry| (line, entry))
+            }) {
+                insert.execute(params![
+                    entry.timestamp as i64,
+                    entry.status.as_str(),
+                    entry.endpoint,
+                    entry.operation,
+                    entry.error_code,
+                    entry.message,
+                    entry.duration_ms.map(|ms| ms as i64),
+                    line,
+                ])?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO sync (name, value) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET value = excluded.value",
+            params![LOG_OFFSET, (offset + end as u64 + 1) as i64],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Entries matching `filter`, most recent first, at most `limit`
+    pub fn search(&self, filter: &HistoryFilter, limit: usize) -> Result<Vec<HistoryEntry>> {
+        let (clause, values) = where_clause(filter);
+        let sql = format!(
+            "SELECT entry FROM calls {} ORDER BY timestamp DESC, id DESC LIMIT {}",
+            clause, limit
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            row.get::<_, String>(0)
+        })?;
+        rows.map(|line| {
+            let line = line?;
+            serde_json::from_str(&line).context("Invalid entry in history index")
+        })
+        .collect()
+    }
+
+    /// Statistics per endpoint and operation for calls matching `filter`,
+    /// most called first. Blocked attempts are not calls and are left out.
+    /// With `interval` (seconds) each group is also split into windows.
+    pub fn stats(&self, filter: &HistoryFilter, interval: Option<u64>) -> Result<Vec<CallStats>> {
+        let (clause, values) = where_clause(filter);
+        let blocked = format!("status != '{}'", HistoryStatus::Blocked.as_str());
+        let clause = if clause.is_empty() {
+            format!("WHERE {}", blocked)
+        } else {
+            format!("{} AND {}", clause, blocked)
+        };
+        let sql = format!(
+            "SELECT endpoint, operation, status, duration_ms, timestamp FROM calls {}",
+            clause
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)? == HistoryStatus::Ok.as_str(),
+                row.get::<_, Option<i64>>(3)?.map(|ms| ms as u64),
+                row.get::<_, i64>(4)? as u64,
+            ))
+        })?;
+
+        let mut groups: BTreeMap<(String, Option<String>), Vec<Sample>> = BTreeMap::new();
+        for row in rows {
+            let (endpoint, operation, ok, duration_ms, timestamp) = row?;
+            groups
+                .entry((endpoint, operation))
+                .or_default()
+                .push(Sample {
+                    ok,
+                    duration_ms,
+                    timestamp,
+                });
+        }
+
+        let mut stats: Vec<CallStats> = groups
+            .into_iter()
+            .map(|((endpoint, operation), samples)| {
+                let summary = Summary::of(samples.iter());
+                let windows = match interval.filter(|&interval| interval > 0) {
+                    Some(interval) => {
+                        let mut windows: BTreeMap<u64, Vec<&Sample>> = BTreeMap::new();
+                        for sample in &samples {
+                            windows
+                                .entry(sample.timestamp - sample.timestamp % interval)
+                                .or_default()
+                                .push(sample);
+                        }
+                        windows
+                            .into_iter()
+                            .map(|(start, samples)| {
+                                let summary = Summary::of(samples.into_iter());
+                                WindowStats {
+                                    start,
+                                    calls: summary.calls,
+                                    ok: summary.ok,
+                                    errors: summary.calls - summary.ok,
+                                    success_rate: summary.success_rate(),
+                                    p50_ms: summary.p50_ms,
+                                    p95_ms: summary.p95_ms,
+                                }
+                            })
+                            .collect()
+                    }
+                    None => Vec::new(),
+                };
+                CallStats {
+                    endpoint,
+                    operation,
+                    calls: summary.calls,
+                    ok: summary.ok,
+                    errors: summary.calls - summary.ok,
+                    success_rate: summary.success_rate(),
+                    p50_ms: summary.p50_ms,
+                    p95_ms: summary.p95_ms,
+                    last_called: summary.last_called,
+                    windows,
+                }
+            })
+            .collect();
+        stats.sort_by_key(|stats| std::cmp::Reverse(stats.calls));
+        Ok(stats)
+    }
+}
+
+/// Parse an age such as `30s`, `15m`, `24h`, `7d` or `2w` into seconds
+pub fn parse_age(age: &str) -> Result<u64> {
+    let age = age.trim();
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (number, unit) = age.split_at(split);
+    let Ok(number) = number.parse::<u64>() else {
+        bail!(
+            "Invalid age '{}': expected a number with a unit, e.g. 24h",
+            age
+        );
+    };
+    let unit = match unit {
+        "s" | "" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => bail!("Invalid age '{}': unit must be one of s, m, h, d, w", age),
+    };
+    Ok(number * unit)
+}
+
+struct Sample {
+    ok: bool,
+    duration_ms: Option<u64>,
+    timestamp: u64,
+}
+
+struct Summary {
+    calls: u64,
+    ok: u64,
+    p50_ms: Option<u64>,
+    p95_ms: Option<u64>,
+    last_called: u64,
+}
+
+impl Summary {
+    fn of<'a>(samples: impl Iterator<Item = &'a Sample>) -> Self {
+        let mut summary = Summary {
+            calls: 0,
+            ok: 0,
+            p50_ms: None,
+            p95_ms: None,
+            last_called: 0,
+        };
+        let mut durations = Vec::new();
+        for sample in samples {
+            summary.calls += 1;
+            summary.ok += u64::from(sample.ok);
+            summary.last_called = summary.last_called.max(sample.timestamp);
+            durations.extend(sample.duration_ms);
+        }
+        durations.sort_unstable();
+        summary.p50_ms = percentile(&durations, 50);
+        summary.p95_ms = percentile(&durations, 95);
+        summary
+    }
+
+    fn success_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.ok as f64 / self.calls as f64
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+fn where_clause(filter: &HistoryFilter) -> (String, Vec<rusqlite::types::Value>) {
+    let mut conditions = Vec::new();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    if let Some(endpoint) = &filter.endpoint {
+        values.push(endpoint.clone().into());
+        conditions.push(format!("endpoint GLOB ?{}", values.len()));
+    }
+    if let Some(operation) = &filter.operation {
+        values.push(operation.clone().into());
+        conditions.push(format!("operation GLOB ?{}", values.len()));
+    }
+    if let Some(status) = filter.status {
+        values.push(status.as_str().to_string().into());
+        conditions.push(format!("status = ?{}", values.len()));
+    }
+    if let Some(since) = filter.since {
+        values.push((since as i64).into());
+        conditions.push(format!("timestamp >= ?{}", values.len()));
+    }
+    if conditions.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!("WHERE {}", conditions.join(" AND ")), values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn entry(
+        timestamp: u64,
+        status: HistoryStatus,
+        operation: &str,
+        duration_ms: u64,
+    ) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            duration_ms: Some(duration_ms),
+            ..HistoryEntry::new(status, "https://api.test", Some(operation))
+        }
+    }
+
+    fn append(log: &Path, entries: &[HistoryEntry]) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log)
+            .unwrap();
+        for entry in entries {
+            writeln!(file, "{}", serde_json::to_string(entry).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn imports_appended_entries_and_filters_them() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("history.jsonl");
+        let index = dir.path().join("history.db");
+        append(
+            &log,
+            &[
+                entry(100, HistoryStatus::Ok, "query/user", 10),
+                entry(200, HistoryStatus::Error, "query/users", 20),
+            ],
+        );
+        let store = HistoryStore::open_at(&index, &log).unwrap();
+        assert_eq!(
+            store.search(&HistoryFilter::default(), 10).unwrap().len(),
+            2
+        );
+
+        // Only the new line is imported on the next open
+        append(
+            &log,
+            &[entry(300, HistoryStatus::Ok, "mutation/addUser", 30)],
+        );
+        let store = HistoryStore::open_at(&index, &log).unwrap();
+        let all = store.search(&HistoryFilter::default(), 10).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].operation.as_deref(), Some("mutation/addUser"));
+        assert!(all[0].id.is_some());
+
+        let errors = store
+            .search(
+                &HistoryFilter {
+                    operation: Some("query/*".to_string()),
+                    status: Some(HistoryStatus::Error),
+                    ..Default::default()
+                },
+                10,
+            )
+            .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].duration_ms, Some(20));
+
+        let recent = HistoryFilter {
+            since: Some(200),
+            ..Default::default()
+        };
+        assert_eq!(store.search(&recent, 10).unwrap().len(), 2);
+
+        // A truncated log rebuilds the index
+        fs::write(&log, "").unwrap();
+        append(&log, &[entry(400, HistoryStatus::Ok, "query/user", 5)]);
+        let store = HistoryStore::open_at(&index, &log).unwrap();
+        assert_eq!(
+            store.search(&HistoryFilter::default(), 10).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn stats_report_success_rate_and_percentiles_per_window() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("history.jsonl");
+        let mut entries: Vec<HistoryEntry> = (1..=20)
+            .map(|i| entry(i * 10, HistoryStatus::Ok, "get:/users", i * 10))
+            .collect();
+        entries.push(entry(250, HistoryStatus::Error, "get:/users", 1000));
+        entries.push(HistoryEntry {
+            timestamp: 260,
+            ..HistoryEntry::new(
+                HistoryStatus::Blocked,
+                "https://api.test",
+                Some("get:/users"),
+            )
+        });
+        append(&log, &entries);
+
+        let store = HistoryStore::open_at(&dir.path().join("history.db"), &log).unwrap();
+        let stats = store.stats(&HistoryFilter::default(), Some(100)).unwrap();
+        assert_eq!(stats.len(), 1);
+        let users = &stats[0];
+        assert_eq!((users.calls, users.ok, users.errors), (21, 20, 1));
+        assert_eq!(users.p50_ms, Some(110));
+        assert_eq!(users.p95_ms, Some(200));
+        assert_eq!(users.last_called, 250);
+        let starts: Vec<u64> = users.windows.iter().map(|w| w.start).collect();
+        assert_eq!(starts, [0, 100, 200]);
+        assert_eq!(users.windows[2].errors, 1);
+
+        assert_eq!(parse_age("24h").unwrap(), 86_400);
+        assert_eq!(parse_age("15m").unwrap(), 900);
+        assert!(parse_age("soon").is_err());
+        assert!(parse_age("3y").is_err());
+    }
+}
@@ -0,0 +1,458 @@
+//! Request and response hooks
+//!
+//! Hooks are [Rhai](https://rhai.rs) scripts run around calls, for the long
+//! tail of signing schemes and response formats uxc has no flag for. They
+//! are set in `.uxc.toml` for every call below it, or on an alias for calls
+//! to its endpoint:
+//!
+//! ```toml
+//! [hooks]
+//! pre_request = "hooks/sign.rhai"
+//! post_response = "hooks/unwrap.rhai"
+//!
+//! [aliases.legacy]
+//! url = "https://legacy.example.com"
+//! hooks = { post_response = "~/.uxc/hooks/legacy.rhai" }
+//! ```
+//!
+//! Relative paths are resolved against the directory of the file that
+//! names them. Hooks of a project file only run once it is trusted (see
+//! [`crate::project`]).
+//!
+//! A `pre_request` script sees `url`, `operation`, `args` and `headers` (the
+//! headers earlier middleware set, usually none); what it leaves in `args`
+//! is sent, and `headers` are set on the HTTP requests of the call,
+//! replacing the adapter's own. A `post_response` script sees `url`,
+//! `operation`, `args` and `result`; what it leaves in `result` is the
+//! result of the call. `throw` fails the call. Hooks are [`Middleware`]:
+//! `pre_request` scripts run in the order they were installed, project hooks
+//! first, and `post_response` scripts in the reverse order.
+//!
+//! Scripts are limited to [`MAX_OPERATIONS`] operations, [`MAX_CALL_DEPTH`]
+//! nested function calls and [`MAX_DATA_SIZE`] characters per string or
+//! items per array or map; a script over a limit fails the call instead of
+//! hanging it.
+//!
+//! Scripts can call `sha256(text)` and `hmac_sha256(key, text)` (hex
+//! digests), `base64(text)`, `timestamp()` and `timestamp_ms()` (Unix time),
+//! `env(name)`, `to_json(value)` and `parse_json(text)`. `env` only reads the
+//! variables the configuration lists, and is empty for unset ones:
+//!
+//! ```toml
+//! [hooks]
+//! pre_request = "hooks/sign.rhai"
+//! env = ["SIGNING_KEY"]
+//! ```
+
+use crate::adapters::ExecutionResult;
+use crate::middleware::{Call, Middleware};
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use rhai::{Dynamic, Scope, AST};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Operations a script may run before it is stopped
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Nested function calls a script may make
+pub const MAX_CALL_DEPTH: usize = 32;
+
+/// Characters of a string, or items of an array or map, a script may build
+pub const MAX_DATA_SIZE: usize = 1_000_000;
+
+/// Hook scripts as configured
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Script run before a call, changing its arguments and headers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_request: Option<PathBuf>,
+
+    /// Script run after a call, reshaping its result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_response: Option<PathBuf>,
+
+    /// Environment variables the scripts may read with `env(name)`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+impl HookConfig {
+    pub fn is_empty(&self) -> bool {
+        self.pre_request.is_none() && self.post_response.is_none()
+    }
+}
+
+/// A compiled script
+#[derive(Debug)]
+struct Script {
+    path: PathBuf,
+    ast: AST,
+    engine: Arc<rhai::Engine>,
+}
+
+impl Script {
+    fn load(path: &Path, base: &Path, engine: Arc<rhai::Engine>) -> Result<Self> {
+        let path = resolve(path, base);
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read hook script {:?}", path))?;
+        let ast = engine
+            .compile(&source)
+            .map_err(|e| anyhow!("Hook script {:?} does not compile: {}", path, e))?;
+        Ok(Self { path, ast, engine })
+    }
+
+    /// Run the script with `variables` in scope and return their final values
+    fn run(&self, variables: Vec<(&'static str, Value)>) -> Result<Map<String, Value>> {
+        let mut scope = Scope::new();
+        for (name, value) in &variables {
+            scope.push_dynamic(
+                *name,
+                rhai::serde::to_dynamic(value).map_err(|e| anyhow!("{}", e))?,
+            );
+        }
+        self.engine
+            .run_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| self.failure(*e))?;
+        variables
+            .into_iter()
+            .map(|(name, _)| {
+                let value = scope.get_value::<Dynamic>(name).unwrap_or(Dynamic::UNIT);
+                let value = rhai::serde::from_dynamic(&value).map_err(|e| {
+                    anyhow!(
+                        "Hook script {:?} left `{}` as a value that is not JSON: {}",
+                        self.path,
+                        name,
+                        e
+                    )
+                })?;
+                Ok((name.to_string(), value))
+            })
+            .collect()
+    }
+
+    fn failure(&self, error: rhai::EvalAltResult) -> anyhow::Error {
+        use rhai::EvalAltResult::*;
+        let limit = match &error {
+            ErrorTooManyOperations(_) => {
+                format!("ran more than {} operations", MAX_OPERATIONS)
+            }
+            ErrorStackOverflow(_) => {
+                format!("nested more than {} function calls", MAX_CALL_DEPTH)
+            }
+            ErrorDataTooLarge(what, _) => {
+                format!("built a {} over {} items", what, MAX_DATA_SIZE)
+            }
+            _ => return anyhow!("Hook script {:?} failed: {}", self.path, error),
+        };
+        anyhow!(
+            "Hook script {:?} stopped: it {} (hooks are limited to keep calls from hanging)",
+            self.path,
+            limit
+        )
+    }
+}
+
+/// Hooks loaded from one configuration
+#[derive(Debug)]
+pub struct Hooks {
+    /// Endpoint the hooks are limited to; `None` for every call
+    endpoint: Option<String>,
+    pre_request: Option<Script>,
+    post_response: Option<Script>,
+}
+
+impl Hooks {
+    /// Compile the scripts of `config`, whose relative paths are relative to
+    /// `base`. With `endpoint` the hooks only run for calls to it.
+    pub fn load(config: &HookConfig, base: &Path, endpoint: Option<String>) -> Result<Self> {
+        let engine = Arc::new(engine(config.env.clone()));
+        Ok(Self {
+            endpoint,
+            pre_request: config
+                .pre_request
+                .as_deref()
+                .map(|path| Script::load(path, base, engine.clone()))
+                .transpose()?,
+            post_response: config
+                .post_response
+                .as_deref()
+                .map(|path| Script::load(path, base, engine.clone()))
+                .transpose()?,
+        })
+    }
+
+    fn applies_to(&self, url: &str) -> bool {
+        self.endpoint.as_deref().is_none_or(|endpoint| {
+            let endpoint = endpoint.trim_end_matches('/');
+            url == endpoint
+                || url
+                    .strip_prefix(endpoint)
+                    .is_some_and(|rest| rest.starts_with(['/', '?', '#']))
+        })
+    }
+}
+
+/// Run `hooks` around the calls of this process
+pub fn install(hooks: Hooks) {
+    crate::middleware::install(Arc::new(hooks));
+}
+
+#[async_trait]
+impl Middleware for Hooks {
+    async fn on_request(&self, call: &mut Call) -> Result<()> {
+        let Some(script) = self
+            .pre_request
+            .as_ref()
+            .filter(|_| self.applies_to(&call.url))
+        else {
+            return Ok(());
+        };
+        let headers = call
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                (name.to_string(), Value::String(value))
+            })
+            .collect();
+        let mut values = script.run(vec![
+            ("url", Value::String(call.url.clone())),
+            ("operation", Value::String(call.operation.clone())),
+            (
+                "args",
+                Value::Object(call.args.clone().into_iter().collect()),
+            ),
+            ("headers", Value::Object(headers)),
+        ])?;
+
+        let Some(Value::Object(args)) = values.remove("args") else {
+            return Err(anyhow!(
+                "Hook script {:?} left `args` as something other than a map",
+                script.path
+            ));
+        };
+        let Some(Value::Object(headers)) = values.remove("headers") else {
+            return Err(anyhow!(
+                "Hook script {:?} left `headers` as something other than a map",
+                script.path
+            ));
+        };
+        let headers: BTreeMap<String, String> = headers
+            .into_iter()
+            .map(|(name, value)| match value {
+                Value::String(value) => (name, value),
+                other => (name, other.to_string()),
+            })
+            .collect();
+        call.args = args.into_iter().collect();
+        call.headers = crate::http::header_map(&headers)
+            .with_context(|| format!("Hook script {:?} set an invalid header", script.path))?;
+        crate::explain::record(
+            "hooks",
+            format!("pre_request hook {} ran", script.path.display()),
+        );
+        Ok(())
+    }
+
+    async fn on_response(&self, call: &Call, result: &mut ExecutionResult) -> Result<()> {
+        let Some(script) = self
+            .post_response
+            .as_ref()
+            .filter(|_| self.applies_to(&call.url))
+        else {
+            return Ok(());
+        };
+        let mut values = script.run(vec![
+            ("url", Value::String(call.url.clone())),
+            ("operation", Value::String(call.operation.clone())),
+            (
+                "args",
+                Value::Object(call.args.clone().into_iter().collect()),
+            ),
+            ("result", result.data.take()),
+        ])?;
+        result.data = values.remove("result").unwrap_or_default();
+        crate::explain::record(
+            "hooks",
+            format!("post_response hook {} ran", script.path.display()),
+        );
+        Ok(())
+    }
+}
+
+/// Engine with the script functions and limits; `env` reads only the
+/// variables in `allowed_env`
+fn engine(allowed_env: Vec<String>) -> rhai::Engine {
+    let mut engine = rhai::Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_DEPTH)
+        .set_max_string_size(MAX_DATA_SIZE)
+        .set_max_array_size(MAX_DATA_SIZE)
+        .set_max_map_size(MAX_DATA_SIZE);
+    engine
+        .register_fn("sha256", |text: &str| hex(&Sha256::digest(text.as_bytes())))
+        .register_fn("hmac_sha256", |key: &str, text: &str| {
+            hex(&hmac_sha256(key.as_bytes(), text.as_bytes()))
+        })
+        .register_fn("base64", |text: &str| {
+            base64::engine::general_purpose::STANDARD.encode(text)
+        })
+        .register_fn("timestamp", || chrono::Utc::now().timestamp())
+        .register_fn("timestamp_ms", || chrono::Utc::now().timestamp_millis())
+        .register_fn(
+            "env",
+            move |name: &str| -> Result<String, Box<rhai::EvalAltResult>> {
+                if !allowed_env.iter().any(|allowed| allowed == name) {
+                    return Err(format!(
+                        "env: '{}' is not listed in the `env` of the hook configuration",
+                        name
+                    )
+                    .into());
+                }
+                Ok(crate::env_file::var(name).unwrap_or_default())
+            },
+        )
+        .register_fn(
+            "to_json",
+            |value: Dynamic| -> Result<String, Box<rhai::EvalAltResult>> {
+                let value: Value = rhai::serde::from_dynamic(&value)?;
+                Ok(value.to_string())
+            },
+        )
+        .register_fn(
+            "parse_json",
+            |text: &str| -> Result<Dynamic, Box<rhai::EvalAltResult>> {
+                let value: Value =
+                    serde_json::from_str(text).map_err(|e| format!("parse_json: {}", e))?;
+                rhai::serde::to_dynamic(value)
+            },
+        );
+    engine
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// `path`, with `~` expanded and relative to `base`
+fn resolve(path: &Path, base: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~") {
+        if let Some(home) = std::env::var_os("HOME") {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    base.join(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn script(source: &str) -> Script {
+        let engine = Arc::new(engine(vec!["UXC_HOOK_TEST_KEY".to_string()]));
+        Script {
+            path: PathBuf::from("test.rhai"),
+            ast: engine.compile(source).unwrap(),
+            engine,
+        }
+    }
+
+    #[test]
+    fn scripts_change_their_variables() {
+        let hook = script(
+            r#"
+            args.limit = 10;
+            headers["X-Signature"] = hmac_sha256("secret", operation + to_json(args));
+            "#,
+        );
+        let values = hook
+            .run(vec![
+                ("operation", json!("list")),
+                ("args", json!({ "q": "rust" })),
+                ("headers", json!({})),
+            ])
+            .unwrap();
+        assert_eq!(values["args"], json!({ "q": "rust", "limit": 10 }));
+        let expected = hex(&hmac_sha256(b"secret", br#"list{"limit":10,"q":"rust"}"#));
+        assert_eq!(values["headers"]["X-Signature"], json!(expected));
+
+        let err = script(r#"throw "no token""#)
+            .run(vec![("args", json!({}))])
+            .unwrap_err();
+        assert!(err.to_string().contains("no token"), "{}", err);
+    }
+
+    #[test]
+    fn env_reads_only_listed_variables() {
+        std::env::set_var("UXC_HOOK_TEST_KEY", "listed");
+        std::env::set_var("UXC_HOOK_TEST_OTHER", "unlisted");
+        let values = script(r#"key = env("UXC_HOOK_TEST_KEY");"#)
+            .run(vec![("key", json!(""))])
+            .unwrap();
+        assert_eq!(values["key"], json!("listed"));
+
+        let err = script(r#"key = env("UXC_HOOK_TEST_OTHER");"#)
+            .run(vec![("key", json!(""))])
+            .unwrap_err();
+        assert!(err.to_string().contains("not listed"), "{}", err);
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        let err = script("loop { args.n = 1; }")
+            .run(vec![("args", json!({}))])
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("more than 1000000 operations"),
+            "{}",
+            err
+        );
+
+        let err = script("fn deep(n) { deep(n + 1) } deep(0);")
+            .run(vec![("args", json!({}))])
+            .unwrap_err();
+        assert!(err.to_string().contains("function calls"), "{}", err);
+
+        let err = script(r#"let text = "x"; loop { text += text; }"#)
+            .run(vec![("args", json!({}))])
+            .unwrap_err();
+        assert!(err.to_string().contains("stopped"), "{}", err);
+    }
+
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        // Test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn endpoint_hooks_apply_below_their_url() {
+        let hooks = Hooks {
+            endpoint: Some("https://api.example.com/v1/".to_string()),
+            pre_request: None,
+            post_response: None,
+        };
+        assert!(hooks.applies_to("https://api.example.com/v1"));
+        assert!(hooks.applies_to("https://api.example.com/v1/users"));
+        assert!(!hooks.applies_to("https://api.example.com/v10"));
+        assert!(!hooks.applies_to("https://other.example.com"));
+    }
+}
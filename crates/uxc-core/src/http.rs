@@ -0,0 +1,542 @@
+//! Shared HTTP client and redirect handling
+//!
+//! Adapters, auth flows and probes send through [`client`]: one pooled
+//! client per process, so a command that lists, describes and then calls
+//! (or a REPL session, or a workflow) reuses the connections it opened.
+//! Idle connections are kept for [`POOL_IDLE_TIMEOUT`], TCP and HTTP/2
+//! keepalives hold them open, and HTTPS hosts that offer HTTP/2 get one
+//! multiplexed connection. Timeouts are set per request, not on the client.
+//! The client is rebuilt when the proxy or `--verbose` changes.
+//!
+//! [`client`] and [`client_builder`] leave redirects to [`send`]. Redirects
+//! are followed up to `--max-redirects` hops (default 10), or not at all with
+//! `--no-follow-redirects`. When a redirect leaves the original origin,
+//! credentials (`Authorization`, `Cookie`, `Proxy-Authorization` and API key
+//! headers) are dropped unless `--keep-auth-on-redirect` is given. Every hop
+//! is logged at `info` level, and with `--verbose` every hop is traced (see
+//! [`crate::trace`]).
+//!
+//! Headers added with [`add_default_headers`] (from `.uxc.toml` and endpoint
+//! aliases, see [`crate::project`] and [`crate::alias`]) are sent with every
+//! request that does not set them itself. Headers set by middleware, such as
+//! `pre_request` hooks ([`crate::middleware`], [`crate::hooks`]), replace the
+//! request's own. Both kinds may hold keys or signatures, so they are dropped
+//! with the credentials on cross-origin redirects.
+//!
+//! [`send`] refuses requests to hosts outside the allowlist in effect (see
+//! [`crate::allowlist`]), and checks every redirect target before it follows
+//! it.
+//!
+//! A proxy set with [`set_proxy`] (`--proxy` or `[network] proxy`) carries
+//! every request; otherwise reqwest honours `HTTP_PROXY` / `HTTPS_PROXY`.
+//!
+//! With a limit set by [`set_max_response_bytes`] (`--max-response-bytes` or
+//! `[network] max_response_bytes`), [`send`] reads the body of the final
+//! response and fails once it grows past the limit, so adapters never see
+//! larger responses.
+
+use anyhow::{bail, Context, Result};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, COOKIE, LOCATION, PROXY_AUTHORIZATION,
+    RETRY_AFTER,
+};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// How long an idle pooled connection is kept for reuse
+pub const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Interval of TCP keepalive probes on pooled connections
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Interval of HTTP/2 pings on pooled connections
+const HTTP2_KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// Redirect hops followed when nothing else is configured
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// How HTTP redirects are handled for this process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedirectPolicy {
+    /// Follow redirects at all
+    pub follow: bool,
+
+    /// Maximum number of hops before giving up
+    pub max_redirects: usize,
+
+    /// Keep credentials when a redirect leaves the original origin
+    pub keep_auth: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            follow: true,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            keep_auth: false,
+        }
+    }
+}
+
+static REDIRECT_POLICY: RwLock<RedirectPolicy> = RwLock::new(RedirectPolicy {
+    follow: true,
+    max_redirects: DEFAULT_MAX_REDIRECTS,
+    keep_auth: false,
+});
+
+/// Set the redirect policy for this process
+pub fn set_redirect_policy(policy: RedirectPolicy) {
+    *REDIRECT_POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Redirect policy in effect for this process
+pub fn redirect_policy() -> RedirectPolicy {
+    *REDIRECT_POLICY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+static DEFAULT_HEADERS: RwLock<Option<HeaderMap>> = RwLock::new(None);
+
+/// Add `headers` to every request sent by [`send`] for this process. A
+/// header added again replaces the earlier value.
+pub fn add_default_headers(headers: HeaderMap) {
+    if headers.is_empty() {
+        return;
+    }
+    let mut defaults = DEFAULT_HEADERS.write().unwrap_or_else(|e| e.into_inner());
+    let defaults = defaults.get_or_insert_with(HeaderMap::new);
+    for (name, value) in headers {
+        if let Some(name) = name {
+            defaults.insert(name, value);
+        }
+    }
+}
+
+/// Request headers from configured name/value pairs
+pub fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid header name '{}'", name))?;
+        let header_value = HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for header '{}'", name))?;
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+// The proxy of this process and the URL it was set from
+static PROXY: RwLock<Option<(String, reqwest::Proxy)>> = RwLock::new(None);
+
+/// Send every request of this process through the proxy at `url`
+pub fn set_proxy(url: &str) -> Result<()> {
+    let proxy = reqwest::Proxy::all(url).with_context(|| format!("Invalid proxy URL '{}'", url))?;
+    *PROXY.write().unwrap_or_else(|e| e.into_inner()) = Some((url.to_string(), proxy));
+    *SHARED_CLIENT.write().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(())
+}
+
+/// URL of the proxy set with [`set_proxy`], if any
+pub fn proxy() -> Option<String> {
+    PROXY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|(url, _)| url.clone())
+}
+
+// Largest response body [`send`] reads; 0 when there is no limit
+static MAX_RESPONSE_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Fail responses whose body is larger than `limit` bytes, for this process
+pub fn set_max_response_bytes(limit: Option<u64>) {
+    MAX_RESPONSE_BYTES.store(limit.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// The response size limit of this process, if any
+pub fn max_response_bytes() -> Option<u64> {
+    match MAX_RESPONSE_BYTES.load(Ordering::Relaxed) {
+        0 => None,
+        limit => Some(limit),
+    }
+}
+
+/// Forget default headers, the proxy, the redirect policy and the response
+/// size limit, before a long-lived process runs another command. The pooled
+/// client is dropped when it was built with the proxy.
+pub fn reset() {
+    *DEFAULT_HEADERS.write().unwrap_or_else(|e| e.into_inner()) = None;
+    set_redirect_policy(RedirectPolicy::default());
+    set_max_response_bytes(None);
+    if PROXY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .is_some()
+    {
+        *SHARED_CLIENT.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// Client builder that leaves redirects to [`send`], with the pool
+/// settings of [`client`]
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .http2_keep_alive_interval(HTTP2_KEEPALIVE)
+        .http2_keep_alive_while_idle(true);
+    if let Some((_, proxy)) = &*PROXY.read().unwrap_or_else(|e| e.into_inner()) {
+        builder = builder.proxy(proxy.clone());
+    }
+    if crate::trace::is_enabled() {
+        // Time host lookups for the `--verbose` trace
+        builder.dns_resolver(std::sync::Arc::new(crate::trace::TimedResolver))
+    } else {
+        builder
+    }
+}
+
+// The pooled client of this process, and whether it was built for tracing
+static SHARED_CLIENT: RwLock<Option<(bool, reqwest::Client)>> = RwLock::new(None);
+
+/// The pooled client of this process, which leaves redirects to [`send`].
+/// Clones share its connections.
+pub fn client() -> reqwest::Client {
+    let traced = crate::trace::is_enabled();
+    if let Some((built_traced, client)) = &*SHARED_CLIENT.read().unwrap_or_else(|e| e.into_inner())
+    {
+        if *built_traced == traced {
+            return client.clone();
+        }
+    }
+    let mut shared = SHARED_CLIENT.write().unwrap_or_else(|e| e.into_inner());
+    match &*shared {
+        Some((built_traced, client)) if *built_traced == traced => client.clone(),
+        _ => {
+            let client = client_builder().build().unwrap_or_default();
+            *shared = Some((traced, client.clone()));
+            client
+        }
+    }
+}
+
+/// `request` as [`send`] would first send it, default headers included
+pub fn build(request: RequestBuilder) -> Result<reqwest::Request> {
+    let mut request = request.build()?;
+    add_default_headers_to(&mut request);
+    Ok(request)
+}
+
+/// Add the default and hook headers to `request`, returning the names added
+fn add_default_headers_to(request: &mut reqwest::Request) -> Vec<HeaderName> {
+    let mut added = Vec::new();
+    if let Some(defaults) = &*DEFAULT_HEADERS.read().unwrap_or_else(|e| e.into_inner()) {
+        for (name, value) in defaults {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+                added.push(name.clone());
+            }
+        }
+    }
+    if let Some(headers) = crate::middleware::headers() {
+        for name in headers.keys() {
+            request.headers_mut().remove(name);
+            added.push(name.clone());
+        }
+        for (name, value) in &headers {
+            request.headers_mut().append(name, value.clone());
+        }
+    }
+    added
+}
+
+/// Send `request`, following redirects according to [`redirect_policy`]
+pub async fn send(request: RequestBuilder) -> Result<Response> {
+    let policy = redirect_policy();
+    let (client, request) = request.build_split();
+    let mut request = request?;
+    // Configured and hook headers may carry keys or signatures for this host
+    let added = add_default_headers_to(&mut request);
+    crate::allowlist::enforce(request.url().as_str(), None)?;
+    let mut hops = 0;
+
+    loop {
+        let next = request.try_clone();
+        let from = request.url().clone();
+        crate::trace::http_request(&request);
+        let started = Instant::now();
+        let response = client.execute(request).await?;
+        crate::trace::http_response(
+            &response,
+            crate::trace::Timing {
+                dns: from.host_str().and_then(crate::trace::take_dns_timing),
+                first_byte: started.elapsed(),
+            },
+        );
+
+        let status = response.status();
+        if !policy.follow || !status.is_redirection() || status == StatusCode::NOT_MODIFIED {
+            return limit_body(response).await;
+        }
+        let Some(to) = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|location| from.join(location).ok())
+        else {
+            return limit_body(response).await;
+        };
+        // Streaming bodies cannot be replayed; hand the redirect back as-is
+        let Some(mut next) = next else {
+            debug!(
+                "Not following redirect to {}: request body cannot be resent",
+                to
+            );
+            return limit_body(response).await;
+        };
+        crate::allowlist::enforce(to.as_str(), None)?;
+        if hops >= policy.max_redirects {
+            bail!(
+                "Too many redirects (more than {}) starting at {}",
+                policy.max_redirects,
+                from
+            );
+        }
+        hops += 1;
+
+        if switches_to_get(status, next.method()) {
+            *next.method_mut() = Method::GET;
+            *next.body_mut() = None;
+            let headers = next.headers_mut();
+            headers.remove(reqwest::header::CONTENT_TYPE);
+            headers.remove(reqwest::header::CONTENT_LENGTH);
+        }
+        let cross_origin = !same_origin(&from, &to);
+        if cross_origin && !policy.keep_auth {
+            strip_credentials(next.headers_mut());
+            for name in &added {
+                next.headers_mut().remove(name);
+            }
+        }
+        info!(
+            "Redirect {} {} -> {}{}",
+            status.as_u16(),
+            from,
+            to,
+            match (cross_origin, policy.keep_auth) {
+                (true, false) => " (credentials dropped)",
+                (true, true) => " (credentials kept)",
+                _ => "",
+            }
+        );
+
+        *next.url_mut() = to;
+        request = next;
+    }
+}
+
+/// Seconds a response asks clients to wait before retrying: its `Retry-After`
+/// header, given in seconds or as a date
+pub fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(
+        at.duration_since(std::time::SystemTime::now())
+            .map_or(0, |wait| wait.as_secs()),
+    )
+}
+
+/// `response`, read in full when [`max_response_bytes`] is set
+async fn limit_body(response: Response) -> Result<Response> {
+    match max_response_bytes() {
+        Some(limit) => read_limited(response, limit).await,
+        None => Ok(response),
+    }
+}
+
+/// `response` with its body read, or an error once the body is larger than
+/// `limit` bytes
+async fn read_limited(mut response: Response, limit: u64) -> Result<Response> {
+    let too_large = |url: &Url, size: &str| -> anyhow::Error {
+        crate::error::UxcError::ExecutionFailed(format!(
+            "Response from {} is larger than --max-response-bytes {} ({})",
+            url, limit, size
+        ))
+        .into()
+    };
+    if let Some(length) = response.content_length().filter(|length| *length > limit) {
+        return Err(too_large(response.url(), &format!("{} bytes", length)));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Err(too_large(response.url(), "body not fully read"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let mut limited = ::http::Response::new(body);
+    *limited.status_mut() = response.status();
+    *limited.version_mut() = response.version();
+    *limited.headers_mut() = response.headers().clone();
+    Ok(limited.into())
+}
+
+/// 303, and 301/302 after a POST, are retried as a body-less GET
+fn switches_to_get(status: StatusCode, method: &Method) -> bool {
+    match status {
+        StatusCode::SEE_OTHER => *method != Method::HEAD,
+        StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => *method == Method::POST,
+        _ => false,
+    }
+}
+
+/// Remove headers that carry credentials
+fn strip_credentials(headers: &mut HeaderMap) {
+    headers.remove(AUTHORIZATION);
+    headers.remove(COOKIE);
+    headers.remove(PROXY_AUTHORIZATION);
+    // API key headers are marked sensitive when they are added
+    let sensitive: Vec<_> = headers
+        .iter()
+        .filter(|(_, value)| value.is_sensitive())
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in sensitive {
+        headers.remove(name);
+    }
+}
+
+/// Whether `a` and `b` share scheme, host and port
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.origin() == b.origin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn retry_after_takes_seconds_or_a_date() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(7));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(0));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn method_changes_and_credential_stripping() {
+        assert!(switches_to_get(StatusCode::SEE_OTHER, &Method::PUT));
+        assert!(switches_to_get(StatusCode::FOUND, &Method::POST));
+        assert!(!switches_to_get(StatusCode::FOUND, &Method::PUT));
+        assert!(!switches_to_get(
+            StatusCode::TEMPORARY_REDIRECT,
+            &Method::POST
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer t"));
+        headers.insert(COOKIE, HeaderValue::from_static("sid=1"));
+        let mut key = HeaderValue::from_static("k");
+        key.set_sensitive(true);
+        headers.insert("x-api-key", key);
+        headers.insert("accept", HeaderValue::from_static("application/json"));
+        strip_credentials(&mut headers);
+        assert_eq!(headers.len(), 1);
+        assert!(headers.contains_key("accept"));
+
+        let a = Url::parse("https://api.example.com/a").unwrap();
+        assert!(same_origin(&a, &a.join("/b").unwrap()));
+        assert!(!same_origin(
+            &a,
+            &Url::parse("https://cdn.example.com/b").unwrap()
+        ));
+        assert!(!same_origin(
+            &a,
+            &Url::parse("http://api.example.com/a").unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn bodies_past_the_limit_fail() {
+        let mut server = mockito::Server::new_async().await;
+        let _sized = server
+            .mock("GET", "/sized")
+            .with_body("x".repeat(100))
+            .create_async()
+            .await;
+        let _chunked = server
+            .mock("GET", "/chunked")
+            .with_chunked_body(|writer| writer.write_all(&[b'x'; 100]))
+            .create_async()
+            .await;
+
+        for path in ["/sized", "/chunked"] {
+            let get = || client().get(format!("{}{}", server.url(), path));
+            let response = read_limited(get().send().await.unwrap(), 100)
+                .await
+                .unwrap();
+            assert_eq!(response.text().await.unwrap().len(), 100);
+
+            let err = read_limited(get().send().await.unwrap(), 99)
+                .await
+                .unwrap_err();
+            assert_eq!(crate::error::code_of(&err), "EXECUTION_FAILED");
+            assert!(
+                err.to_string()
+                    .contains("larger than --max-response-bytes 99"),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn clients_share_pooled_connections() {
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    // One request per read is enough for body-less GETs
+                    while matches!(socket.read(&mut buf).await, Ok(n) if n > 0) {
+                        let reply = b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok";
+                        if socket.write_all(reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        for _ in 0..3 {
+            let response = client().get(&url).send().await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "ok");
+        }
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,111 @@
+//! International domain names in endpoints
+//!
+//! Endpoints are canonicalized to their ASCII (punycode) form, so
+//! `https://例え.テスト` and `https://xn--r8jz45g.xn--zckzah` share cache
+//! entries, history and allowlist matches, and gRPC targets get a host that
+//! HTTP/2 accepts. JSON envelopes carry the ASCII form; text output shows the
+//! Unicode form.
+
+/// Convert the host of `endpoint` to ASCII. Paths, queries and inputs
+/// without a host are returned unchanged.
+pub fn to_ascii_endpoint(endpoint: &str) -> String {
+    map_host(endpoint, host_to_ascii)
+}
+
+/// Convert punycode labels in the host of `endpoint` to Unicode for display
+pub fn to_unicode_endpoint(endpoint: &str) -> String {
+    map_host(endpoint, host_to_unicode)
+}
+
+/// ASCII form of a host name, label by label so `*` globs survive
+pub fn host_to_ascii(host: &str) -> String {
+    if host.is_ascii() {
+        return host.to_string();
+    }
+    host.split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                label.to_string()
+            } else {
+                idna::domain_to_ascii(label).unwrap_or_else(|_| label.to_string())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Unicode form of a host name; labels that are not valid punycode are kept
+pub fn host_to_unicode(host: &str) -> String {
+    if !host.to_ascii_lowercase().contains("xn--") {
+        return host.to_string();
+    }
+    match idna::domain_to_unicode(host) {
+        (unicode, Ok(())) => unicode,
+        (_, Err(_)) => host.to_string(),
+    }
+}
+
+/// Apply `convert` to the host in `scheme://[user@]host[:port]/...` or in a
+/// bare `host:port` / `host/path`
+fn map_host(endpoint: &str, convert: fn(&str) -> String) -> String {
+    if endpoint.chars().any(char::is_whitespace) {
+        return endpoint.to_string();
+    }
+    let (prefix, rest) = match endpoint.split_once("://") {
+        Some((scheme, rest)) => (format!("{}://", scheme), rest),
+        None => (String::new(), endpoint),
+    };
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(authority_end);
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((userinfo, host_port)) => (format!("{}@", userinfo), host_port),
+        None => (String::new(), authority),
+    };
+    let (host, port) = match host_port.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => {
+            (host, format!(":{}", port))
+        }
+        _ => (host_port, String::new()),
+    };
+    // Without a scheme, only treat dotted names as hosts (not commands or ids)
+    if host.is_empty() || host.starts_with('[') || (prefix.is_empty() && !host.contains('.')) {
+        return endpoint.to_string();
+    }
+    format!("{}{}{}{}{}", prefix, userinfo, convert(host), port, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_round_trip_between_unicode_and_punycode() {
+        assert_eq!(
+            to_ascii_endpoint("https://例え.テスト/api?q=ü"),
+            "https://xn--r8jz45g.xn--zckzah/api?q=ü"
+        );
+        assert_eq!(
+            to_ascii_endpoint("user@bücher.example:9000"),
+            "user@xn--bcher-kva.example:9000"
+        );
+        assert_eq!(
+            to_unicode_endpoint("https://xn--r8jz45g.xn--zckzah/api"),
+            "https://例え.テスト/api"
+        );
+        assert_eq!(host_to_ascii("*.bücher.example"), "*.xn--bcher-kva.example");
+
+        for unchanged in [
+            "https://api.example.com/v1",
+            "query/ユーザー",
+            "ツール",
+            "npx -y サーバー",
+            "http://[::1]:8080",
+        ] {
+            assert_eq!(to_ascii_endpoint(unchanged), unchanged);
+        }
+        assert_eq!(
+            to_unicode_endpoint("https://xn--broken-.example"),
+            "https://xn--broken-.example"
+        );
+    }
+}
@@ -0,0 +1,558 @@
+//! Adapter instrumentation
+//!
+//! An [`Instrumentation`] observes protocol detection, schema fetches, schema
+//! cache lookups, retries and calls with their timings and outcomes. Instruments are installed once per
+//! process with [`install`] and notified by [`crate::adapters::AdapterEnum`]
+//! and [`crate::adapters::ProtocolDetector`], so every adapter is covered
+//! without adapter-specific code.
+//!
+//! Built-in instruments:
+//!
+//! - [`TracingInstrumentation`]: one `tracing` event per step, at debug level
+//! - [`PrometheusInstrumentation`]: latency histograms and counters in the
+//!   Prometheus text exposition format
+//! - [`LedgerInstrumentation`]: calls appended to the usage ledger
+//!   (`~/.uxc/history.jsonl`, see [`crate::history`])
+
+use crate::adapters::ProtocolType;
+use crate::history::{self, HistoryEntry, HistoryStatus};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+static INSTRUMENTS: RwLock<Vec<Arc<dyn Instrumentation>>> = RwLock::new(Vec::new());
+
+/// Observer of adapter activity. Every method defaults to doing nothing.
+pub trait Instrumentation: Send + Sync {
+    /// Protocol detection for an endpoint finished
+    fn on_detect(&self, _event: &DetectEvent<'_>) {}
+
+    /// An adapter resolved the schema of an endpoint (to fetch, list or
+    /// describe operations)
+    fn on_schema_fetch(&self, _event: &SchemaFetchEvent<'_>) {}
+
+    /// An operation call finished
+    fn on_execute(&self, _event: &ExecuteEvent<'_>) {}
+
+    /// An adapter looked up the schema of an endpoint in the schema cache
+    fn on_cache_lookup(&self, _event: &CacheLookupEvent<'_>) {}
+
+    /// A failed attempt is about to be retried
+    fn on_retry(&self, _event: &RetryEvent<'_>) {}
+}
+
+/// Result of a detection
+#[derive(Debug, Clone, Copy)]
+pub struct DetectEvent<'a> {
+    pub url: &'a str,
+    /// Detected protocol; `None` when detection failed
+    pub protocol: Option<ProtocolType>,
+    pub duration: Duration,
+    pub error: Option<&'a anyhow::Error>,
+}
+
+/// Result of a schema fetch
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaFetchEvent<'a> {
+    pub url: &'a str,
+    pub protocol: ProtocolType,
+    /// Operation being described, if any
+    pub operation: Option<&'a str>,
+    pub duration: Duration,
+    pub error: Option<&'a anyhow::Error>,
+}
+
+/// Result of an operation call
+#[derive(Debug, Clone, Copy)]
+pub struct ExecuteEvent<'a> {
+    pub url: &'a str,
+    pub protocol: ProtocolType,
+    pub operation: &'a str,
+    pub duration: Duration,
+    pub error: Option<&'a anyhow::Error>,
+}
+
+/// Result of a schema cache lookup
+#[derive(Debug, Clone, Copy)]
+pub struct CacheLookupEvent<'a> {
+    /// Cache key, normally the endpoint URL
+    pub key: &'a str,
+    pub protocol: ProtocolType,
+    /// `hit`, `miss` or `bypassed`
+    pub result: &'static str,
+}
+
+/// A failed attempt of a call that will be retried
+#[derive(Debug, Clone, Copy)]
+pub struct RetryEvent<'a> {
+    /// Number of the attempt that failed, from 1
+    pub attempt: u32,
+    /// Wait before the next attempt
+    pub delay: Duration,
+    pub error: &'a anyhow::Error,
+}
+
+/// Add an instrument for the rest of the process
+pub fn install(instrument: Arc<dyn Instrumentation>) {
+    INSTRUMENTS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(instrument);
+}
+
+/// Whether any instrument is installed
+pub fn is_enabled() -> bool {
+    !INSTRUMENTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_empty()
+}
+
+fn notify(f: impl Fn(&dyn Instrumentation)) {
+    let instruments = INSTRUMENTS.read().unwrap_or_else(|e| e.into_inner());
+    for instrument in instruments.iter() {
+        f(instrument.as_ref());
+    }
+}
+
+/// Run `future` and report it as a detection of `url`
+pub(crate) async fn detect<T>(
+    url: &str,
+    protocol: impl Fn(&T) -> ProtocolType,
+    future: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    let started = Instant::now();
+    let result = future.await;
+    if is_enabled() {
+        let event = DetectEvent {
+            url,
+            protocol: result.as_ref().ok().map(protocol),
+            duration: started.elapsed(),
+            error: result.as_ref().err(),
+        };
+        notify(|instrument| instrument.on_detect(&event));
+    }
+    result
+}
+
+/// Run `future` and report it as a schema fetch of `url`
+pub(crate) async fn schema_fetch<T>(
+    url: &str,
+    protocol: ProtocolType,
+    operation: Option<&str>,
+    future: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    let started = Instant::now();
+    let result = future.await;
+    if is_enabled() {
+        let event = SchemaFetchEvent {
+            url,
+            protocol,
+            operation,
+            duration: started.elapsed(),
+            error: result.as_ref().err(),
+        };
+        notify(|instrument| instrument.on_schema_fetch(&event));
+    }
+    result
+}
+
+/// Run `future` and report it as a call of `operation`
+pub(crate) async fn execute<T>(
+    url: &str,
+    protocol: ProtocolType,
+    operation: &str,
+    future: impl Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    let started = Instant::now();
+    let result = future.await;
+    if is_enabled() {
+        let event = ExecuteEvent {
+            url,
+            protocol,
+            operation,
+            duration: started.elapsed(),
+            error: result.as_ref().err(),
+        };
+        notify(|instrument| instrument.on_execute(&event));
+    }
+    result
+}
+
+/// Report a schema cache lookup
+pub(crate) fn cache_lookup(key: &str, protocol: ProtocolType, result: &'static str) {
+    if is_enabled() {
+        let event = CacheLookupEvent {
+            key,
+            protocol,
+            result,
+        };
+        notify(|instrument| instrument.on_cache_lookup(&event));
+    }
+}
+
+/// Report a failed attempt that will be retried after `delay`
+pub(crate) fn retry(attempt: u32, delay: Duration, error: &anyhow::Error) {
+    if is_enabled() {
+        let event = RetryEvent {
+            attempt,
+            delay,
+            error,
+        };
+        notify(|instrument| instrument.on_retry(&event));
+    }
+}
+
+fn outcome(error: Option<&anyhow::Error>) -> &'static str {
+    match error {
+        None => "ok",
+        Some(err) => crate::error::code_of(err),
+    }
+}
+
+/// Logs every step as a `tracing` debug event (`RUST_LOG=uxc_core=debug`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingInstrumentation;
+
+impl Instrumentation for TracingInstrumentation {
+    fn on_detect(&self, event: &DetectEvent<'_>) {
+        debug!(
+            url = event.url,
+            protocol = event.protocol.map(|p| p.as_str()),
+            duration_ms = event.duration.as_millis() as u64,
+            outcome = outcome(event.error),
+            "detect"
+        );
+    }
+
+    fn on_schema_fetch(&self, event: &SchemaFetchEvent<'_>) {
+        debug!(
+            url = event.url,
+            protocol = event.protocol.as_str(),
+            operation = event.operation,
+            duration_ms = event.duration.as_millis() as u64,
+            outcome = outcome(event.error),
+            "schema fetch"
+        );
+    }
+
+    fn on_execute(&self, event: &ExecuteEvent<'_>) {
+        debug!(
+            url = event.url,
+            protocol = event.protocol.as_str(),
+            operation = event.operation,
+            duration_ms = event.duration.as_millis() as u64,
+            outcome = outcome(event.error),
+            "execute"
+        );
+    }
+
+    fn on_cache_lookup(&self, event: &CacheLookupEvent<'_>) {
+        debug!(
+            key = event.key,
+            protocol = event.protocol.as_str(),
+            result = event.result,
+            "cache lookup"
+        );
+    }
+
+    fn on_retry(&self, event: &RetryEvent<'_>) {
+        debug!(
+            attempt = event.attempt,
+            delay_ms = event.delay.as_millis() as u64,
+            outcome = outcome(Some(event.error)),
+            "retry"
+        );
+    }
+}
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// One labelled histogram series
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket (not cumulative)
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// Collects latency histograms and counters, rendered with
+/// [`PrometheusInstrumentation::render`]:
+///
+/// - `uxc_detect_duration_seconds{protocol, outcome}`
+/// - `uxc_schema_fetch_duration_seconds{protocol, outcome}`
+/// - `uxc_call_duration_seconds{protocol, operation, outcome}`
+/// - `uxc_cache_lookups_total{protocol, result}`
+/// - `uxc_retries_total{outcome}`
+///
+/// `outcome` is `ok` or the error code of a failure. Each histogram's
+/// `_count` doubles as the counter of its step, so calls by operation and
+/// errors by code are `uxc_call_duration_seconds_count`.
+#[derive(Debug, Default)]
+pub struct PrometheusInstrumentation {
+    /// Metric name -> rendered label set -> series
+    series: Mutex<BTreeMap<&'static str, BTreeMap<String, Histogram>>>,
+    /// Metric name -> rendered label set -> count
+    counters: Mutex<BTreeMap<&'static str, BTreeMap<String, u64>>>,
+}
+
+impl PrometheusInstrumentation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn observe(&self, metric: &'static str, labels: &[(&str, &str)], duration: Duration) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        series
+            .entry(metric)
+            .or_default()
+            .entry(render_labels(labels))
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    fn increment(&self, metric: &'static str, labels: &[(&str, &str)]) {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        *counters
+            .entry(metric)
+            .or_default()
+            .entry(render_labels(labels))
+            .or_default() += 1;
+    }
+
+    /// Everything observed so far, in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for (metric, by_labels) in series.iter() {
+            let _ = writeln!(out, "# TYPE {} histogram", metric);
+            for (labels, histogram) in by_labels {
+                let mut cumulative = 0;
+                for (bound, observed) in BUCKETS.iter().zip(histogram.buckets) {
+                    cumulative += observed;
+                    let _ = writeln!(
+                        out,
+                        "{}_bucket{{{},le=\"{}\"}} {}",
+                        metric, labels, bound, cumulative
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{{},le=\"+Inf\"}} {}",
+                    metric, labels, histogram.count
+                );
+                let _ = writeln!(out, "{}_sum{{{}}} {}", metric, labels, histogram.sum);
+                let _ = writeln!(out, "{}_count{{{}}} {}", metric, labels, histogram.count);
+            }
+        }
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        for (metric, by_labels) in counters.iter() {
+            let _ = writeln!(out, "# TYPE {} counter", metric);
+            for (labels, count) in by_labels {
+                let _ = writeln!(out, "{}{{{}}} {}", metric, labels, count);
+            }
+        }
+        out
+    }
+}
+
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Instrumentation for PrometheusInstrumentation {
+    fn on_detect(&self, event: &DetectEvent<'_>) {
+        let protocol = event.protocol.map_or("none", |p| p.as_str());
+        self.observe(
+            "uxc_detect_duration_seconds",
+            &[("protocol", protocol), ("outcome", outcome(event.error))],
+            event.duration,
+        );
+    }
+
+    fn on_schema_fetch(&self, event: &SchemaFetchEvent<'_>) {
+        self.observe(
+            "uxc_schema_fetch_duration_seconds",
+            &[
+                ("protocol", event.protocol.as_str()),
+                ("outcome", outcome(event.error)),
+            ],
+            event.duration,
+        );
+    }
+
+    fn on_execute(&self, event: &ExecuteEvent<'_>) {
+        self.observe(
+            "uxc_call_duration_seconds",
+            &[
+                ("protocol", event.protocol.as_str()),
+                ("operation", event.operation),
+                ("outcome", outcome(event.error)),
+            ],
+            event.duration,
+        );
+    }
+
+    fn on_cache_lookup(&self, event: &CacheLookupEvent<'_>) {
+        self.increment(
+            "uxc_cache_lookups_total",
+            &[
+                ("protocol", event.protocol.as_str()),
+                ("result", event.result),
+            ],
+        );
+    }
+
+    fn on_retry(&self, event: &RetryEvent<'_>) {
+        self.increment(
+            "uxc_retries_total",
+            &[("outcome", outcome(Some(event.error)))],
+        );
+    }
+}
+
+/// Appends every call to the usage ledger (`~/.uxc/history.jsonl`), which
+/// feeds `list --sort frequent`, host help and `uxc history`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LedgerInstrumentation;
+
+impl LedgerInstrumentation {
+    fn entry(event: &ExecuteEvent<'_>) -> HistoryEntry {
+        match event.error {
+            None => HistoryEntry::new(HistoryStatus::Ok, event.url, Some(event.operation)),
+            Some(err) => HistoryEntry::new(HistoryStatus::Error, event.url, Some(event.operation))
+                .with_error(crate::error::code_of(err), &err.to_string()),
+        }
+        .with_duration(event.duration.as_millis() as u64)
+    }
+}
+
+impl Instrumentation for LedgerInstrumentation {
+    fn on_execute(&self, event: &ExecuteEvent<'_>) {
+        if let Err(e) = history::append(&Self::entry(event)) {
+            warn!("Failed to record call in history: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::UxcError;
+
+    fn call<'a>(operation: &'a str, ms: u64, error: Option<&'a anyhow::Error>) -> ExecuteEvent<'a> {
+        ExecuteEvent {
+            url: "https://api.example.com",
+            protocol: ProtocolType::OpenAPI,
+            operation,
+            duration: Duration::from_millis(ms),
+            error,
+        }
+    }
+
+    #[test]
+    fn prometheus_renders_cumulative_histograms() {
+        let metrics = PrometheusInstrumentation::new();
+        let failure = anyhow::Error::from(UxcError::OperationNotFound("get:/x".into()));
+        metrics.on_execute(&call("get:/pets", 20, None));
+        metrics.on_execute(&call("get:/pets", 300, None));
+        metrics.on_execute(&call("get:/x", 1, Some(&failure)));
+        metrics.on_detect(&DetectEvent {
+            url: "https://api.example.com",
+            protocol: None,
+            duration: Duration::from_secs(30),
+            error: None,
+        });
+
+        let text = metrics.render();
+        let ok = r#"protocol="openapi",operation="get:/pets",outcome="ok""#;
+        assert!(text.contains("# TYPE uxc_call_duration_seconds histogram"));
+        assert!(text.contains(&format!(
+            "uxc_call_duration_seconds_bucket{{{},le=\"0.025\"}} 1",
+            ok
+        )));
+        assert!(text.contains(&format!(
+            "uxc_call_duration_seconds_bucket{{{},le=\"0.5\"}} 2",
+            ok
+        )));
+        assert!(text.contains(&format!("uxc_call_duration_seconds_count{{{}}} 2", ok)));
+        assert!(text.contains(r#"outcome="OPERATION_NOT_FOUND""#));
+        // Slower than the last bucket: only counted in +Inf
+        assert!(text.contains(
+            r#"uxc_detect_duration_seconds_bucket{protocol="none",outcome="ok",le="10"} 0"#
+        ));
+        assert!(text.contains(
+            r#"uxc_detect_duration_seconds_bucket{protocol="none",outcome="ok",le="+Inf"} 1"#
+        ));
+    }
+
+    #[test]
+    fn prometheus_counts_cache_lookups_and_retries() {
+        let metrics = PrometheusInstrumentation::new();
+        let timeout = anyhow::Error::from(UxcError::Timeout("slow".into()));
+        for result in ["hit", "hit", "miss"] {
+            metrics.on_cache_lookup(&CacheLookupEvent {
+                key: "https://api.example.com",
+                protocol: ProtocolType::GraphQL,
+                result,
+            });
+        }
+        metrics.on_retry(&RetryEvent {
+            attempt: 1,
+            delay: Duration::from_millis(200),
+            error: &timeout,
+        });
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE uxc_cache_lookups_total counter"));
+        assert!(text.contains(r#"uxc_cache_lookups_total{protocol="graphql",result="hit"} 2"#));
+        assert!(text.contains(r#"uxc_cache_lookups_total{protocol="graphql",result="miss"} 1"#));
+        assert!(text.contains(r#"uxc_retries_total{outcome="TIMEOUT"} 1"#));
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        assert_eq!(escape_label("a\"b\\c\nd"), r#"a\"b\\c\nd"#);
+    }
+
+    #[test]
+    fn ledger_entries_carry_status_code_and_duration() {
+        let entry = LedgerInstrumentation::entry(&call("get:/pets", 42, None));
+        assert_eq!(entry.status, HistoryStatus::Ok);
+        assert_eq!(entry.operation.as_deref(), Some("get:/pets"));
+        assert_eq!(entry.duration_ms, Some(42));
+
+        let failure = anyhow::Error::from(UxcError::Timeout("slow".into()));
+        let entry = LedgerInstrumentation::entry(&call("get:/pets", 5, Some(&failure)));
+        assert_eq!(entry.status, HistoryStatus::Error);
+        assert_eq!(entry.error_code.as_deref(), Some("TIMEOUT"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Command lines reproducing a call
+//!
+//! `--export` renders the [`RequestPlan`] of a call as a command other tools
+//! run: curl for HTTP requests, grpcurl for gRPC calls, and a shell pipeline
+//! for MCP servers spoken to over stdio. Words are quoted for POSIX shells.
+
+use crate::adapters::RequestPlan;
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::Value;
+use std::fmt;
+
+/// Tool a call is exported for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandFormat {
+    Curl,
+    Grpcurl,
+    /// `printf ... | <server command>`, for stdio servers
+    Shell,
+}
+
+impl CommandFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandFormat::Curl => "curl",
+            CommandFormat::Grpcurl => "grpcurl",
+            CommandFormat::Shell => "shell",
+        }
+    }
+
+    /// The format that reproduces requests sent over `transport`
+    pub fn for_transport(transport: &str) -> Option<Self> {
+        match transport {
+            "http" => Some(CommandFormat::Curl),
+            "grpc" => Some(CommandFormat::Grpcurl),
+            "stdio" => Some(CommandFormat::Shell),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CommandFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `word` quoted for a POSIX shell, left bare when that is safe
+pub fn shell_word(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@=,+%".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// `plan` as a `format` command, one option per line
+pub fn command(plan: &RequestPlan, format: CommandFormat) -> Result<String> {
+    match CommandFormat::for_transport(&plan.transport) {
+        Some(fits) if fits == format => {}
+        Some(fits) => {
+            return Err(UxcError::InvalidArguments(format!(
+                "--export {} cannot reproduce a {} request; use --export {}",
+                format, plan.transport, fits
+            ))
+            .into())
+        }
+        None => {
+            return Err(UxcError::InvalidArguments(format!(
+                "{} requests cannot be exported",
+                plan.transport
+            ))
+            .into())
+        }
+    }
+
+    let lines = match format {
+        CommandFormat::Curl => curl(plan),
+        CommandFormat::Grpcurl => grpcurl(plan),
+        CommandFormat::Shell => return Ok(shell(plan)),
+    };
+    Ok(lines.join(" \\\n  "))
+}
+
+/// A body as sent: strings as they are, anything else as compact JSON
+fn body_text(body: &Value) -> String {
+    match body {
+        Value::String(text) => text.clone(),
+        body => body.to_string(),
+    }
+}
+
+fn curl(plan: &RequestPlan) -> Vec<String> {
+    let mut first = "curl".to_string();
+    if let Some(method) = plan.method.as_deref().filter(|method| *method != "GET") {
+        first.push_str(&format!(" -X {}", method));
+    }
+    first.push_str(&format!(" {}", shell_word(&plan.target)));
+
+    let mut lines = vec![first];
+    lines.extend(
+        plan.headers
+            .iter()
+            .map(|header| format!("-H {}", shell_word(header))),
+    );
+    if let Some(body) = &plan.body {
+        lines.push(format!("--data-raw {}", shell_word(&body_text(body))));
+    }
+    lines
+}
+
+fn grpcurl(plan: &RequestPlan) -> Vec<String> {
+    let mut lines = vec![if plan.plaintext {
+        "grpcurl -plaintext".to_string()
+    } else {
+        "grpcurl".to_string()
+    }];
+    lines.extend(
+        plan.headers
+            .iter()
+            .map(|header| format!("-H {}", shell_word(header))),
+    );
+    if let Some(body) = &plan.body {
+        lines.push(format!("-d {}", shell_word(&body_text(body))));
+    }
+    let method = plan.method.as_deref().unwrap_or_default();
+    lines.push(format!(
+        "{} {}",
+        shell_word(&plan.target),
+        shell_word(method)
+    ));
+    lines
+}
+
+/// The preamble and request as lines on the server's stdin; the target is
+/// already a shell command
+fn shell(plan: &RequestPlan) -> String {
+    let mut lines = vec!["printf '%s\\n'".to_string()];
+    lines.extend(
+        plan.preamble
+            .iter()
+            .chain(plan.body.as_ref())
+            .map(|message| shell_word(&message.to_string())),
+    );
+    format!("{} \\\n  | {}", lines.join(" \\\n  "), plan.target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn http_requests_become_curl_commands() {
+        let plan = RequestPlan::new(
+            "http",
+            Some("POST".to_string()),
+            "https://api.example.com/pets?tag=a b".to_string(),
+            json!({ "name": "Rex's" }),
+        )
+        .with_headers(vec!["authorization: Bearer t0k".to_string()], true);
+
+        assert_eq!(
+            command(&plan, CommandFormat::Curl).unwrap(),
+            "curl -X POST 'https://api.example.com/pets?tag=a b' \\\n  \
+             -H 'authorization: Bearer t0k' \\\n  \
+             --data-raw '{\"name\":\"Rex'\\''s\"}'"
+        );
+        assert_eq!(
+            command(&plan.redacted(), CommandFormat::Curl)
+                .unwrap()
+                .lines()
+                .nth(1),
+            Some("  -H 'authorization: ***' \\")
+        );
+
+        let err = command(&plan, CommandFormat::Grpcurl).unwrap_err();
+        assert_eq!(crate::error::code_of(&err), "INVALID_ARGUMENT");
+        assert!(err.to_string().contains("use --export curl"), "{}", err);
+    }
+
+    #[test]
+    fn grpc_and_stdio_calls_use_their_tools() {
+        let plan = RequestPlan::new(
+            "grpc",
+            Some("pkg.Greeter/SayHello".to_string()),
+            "localhost:50051".to_string(),
+            json!({ "name": "Ann" }),
+        )
+        .with_plaintext(true);
+        assert_eq!(
+            command(&plan, CommandFormat::Grpcurl).unwrap(),
+            "grpcurl -plaintext \\\n  -d '{\"name\":\"Ann\"}' \\\n  localhost:50051 pkg.Greeter/SayHello"
+        );
+
+        let plan = RequestPlan::new(
+            "stdio",
+            None,
+            "npx -y server".to_string(),
+            json!({ "id": 2 }),
+        )
+        .with_preamble(vec![json!({ "id": 1 })]);
+        assert_eq!(
+            command(&plan, CommandFormat::Shell).unwrap(),
+            "printf '%s\\n' \\\n  '{\"id\":1}' \\\n  '{\"id\":2}' \\\n  | npx -y server"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! curl command import
+
+use super::{parse_body, split_command, split_query, ImportedRequest};
+use anyhow::{bail, Context, Result};
+
+/// Options whose value is the next word (or attached, as in `-XPOST`).
+const VALUE_OPTIONS: &[&str] = &[
+    "-A",
+    "-b",
+    "-c",
+    "-e",
+    "-E",
+    "-m",
+    "-o",
+    "-r",
+    "-T",
+    "-u",
+    "-w",
+    "-x",
+    "-y",
+    "-Y",
+    "-z",
+    "--user-agent",
+    "--cookie",
+    "--cookie-jar",
+    "--referer",
+    "--cert",
+    "--cacert",
+    "--key",
+    "--max-time",
+    "--connect-timeout",
+    "--output",
+    "--proxy",
+    "--range",
+    "--write-out",
+    "--retry",
+    "--resolve",
+    "--upload-file",
+    "--user",
+    "--oauth2-bearer",
+];
+
+/// Parse a curl command line into a request.
+///
+/// Supported options: `-X/--request`, `-H/--header`, `-d/--data`,
+/// `--data-raw`, `--data-binary`, `--data-ascii`, `--data-urlencode`,
+/// `-F/--form`, `--json`, `-G/--get`, `-I/--head` and `--url`. Other options
+/// are skipped.
+///
+/// Headers are parsed but not kept; credentials belong in auth profiles.
+pub fn parse_command(command: &str) -> Result<ImportedRequest> {
+    let words = split_command(command)?;
+    let mut words = words.into_iter();
+    match words.next() {
+        Some(program) if program == "curl" || program.ends_with("/curl") => {}
+        _ => bail!("Not a curl command: expected it to start with 'curl'"),
+    }
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut data: Vec<String> = Vec::new();
+    let mut json_body = false;
+    let mut get = false;
+    let mut head = false;
+
+    while let Some(word) = words.next() {
+        let (option, attached) = split_option(&word);
+        let mut value = |name: &str| -> Result<String> {
+            match &attached {
+                Some(value) => Ok(value.clone()),
+                None => words
+                    .next()
+                    .with_context(|| format!("Missing value for curl option {}", name)),
+            }
+        };
+
+        match option.as_str() {
+            "-X" | "--request" => method = Some(value(&option)?.to_ascii_uppercase()),
+            "-H" | "--header" => {
+                value(&option)?;
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => {
+                let body = value(&option)?;
+                if body.starts_with('@') && option != "--data-raw" {
+                    bail!(
+                        "Reading request bodies from files ({}) is not supported",
+                        body
+                    );
+                }
+                data.push(body);
+            }
+            "-F" | "--form" => {
+                let field = value(&option)?;
+                if field.contains("=@") || field.contains("=<") {
+                    bail!(
+                        "Reading form fields from files ({}) is not supported",
+                        field
+                    );
+                }
+                data.push(field);
+            }
+            "--data-urlencode" => {
+                let pair = value(&option)?;
+                data.push(match pair.split_once('=') {
+                    Some((key, raw)) => format!(
+                        "{}={}",
+                        key,
+                        url::form_urlencoded::byte_serialize(raw.as_bytes()).collect::<String>()
+                    ),
+                    None => pair,
+                });
+            }
+            "--json" => {
+                json_body = true;
+                data.push(value(&option)?);
+            }
+            "--url" => url = Some(value(&option)?),
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => head = true,
+            option if VALUE_OPTIONS.contains(&option) => {
+                value(option)?;
+            }
+            option if option.starts_with('-') && option.len() > 1 => {}
+            _ => {
+                if url.is_none() {
+                    url = Some(word);
+                }
+            }
+        }
+    }
+
+    let url = url.context("curl command has no URL")?;
+    let url = if url.contains("://") {
+        url
+    } else {
+        format!("http://{}", url)
+    };
+    let (base, mut query) = split_query(&url);
+
+    let joined = data.join("&");
+    let body = if get {
+        query.extend(
+            url::form_urlencoded::parse(joined.as_bytes())
+                .map(|(key, value)| (key.into_owned(), value.into_owned())),
+        );
+        None
+    } else if json_body {
+        Some(serde_json::from_str(&joined).context("Invalid JSON passed to --json")?)
+    } else {
+        parse_body(&joined)
+    };
+
+    let method = method.unwrap_or_else(|| {
+        if head {
+            "HEAD".to_string()
+        } else if body.is_some() {
+            "POST".to_string()
+        } else {
+            "GET".to_string()
+        }
+    });
+
+    Ok(ImportedRequest {
+        name: format!("{} {}", method, base),
+        description: None,
+        method,
+        url: base,
+        query,
+        body,
+    })
+}
+
+/// Split attached option values: `-XPOST` and `--request=POST`.
+fn split_option(word: &str) -> (String, Option<String>) {
+    if let Some(long) = word.strip_prefix("--") {
+        return match long.split_once('=') {
+            Some((name, value)) => (format!("--{}", name), Some(value.to_string())),
+            None => (word.to_string(), None),
+        };
+    }
+    match word.strip_prefix('-') {
+        Some(short) if short.len() > 1 && "XHdFu".contains(&short[..1]) => {
+            (format!("-{}", &short[..1]), Some(short[1..].to_string()))
+        }
+        _ => (word.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_post_with_json_body_and_headers() {
+        let request = parse_command(
+            r#"curl -X POST https://api.example.com/v1/pets?dry_run=true \
+  -H 'Content-Type: application/json' \
+  -H "Authorization: Bearer secret" \
+  -d '{"name": "cat", "age": 2}'"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://api.example.com/v1/pets");
+        assert_eq!(
+            request.query,
+            vec![("dry_run".to_string(), "true".to_string())]
+        );
+        assert_eq!(request.body, Some(json!({"name": "cat", "age": 2})));
+    }
+
+    #[test]
+    fn infers_method_and_handles_get_form_data() {
+        let post = parse_command("curl -sSL https://api.example.com/login -d user=bob -d 'pw=a b'")
+            .unwrap();
+        assert_eq!(post.method, "POST");
+        assert_eq!(post.body, Some(json!({"user": "bob", "pw": "a b"})));
+
+        let get =
+            parse_command("curl -G --url api.example.com/search --data-urlencode 'q=a&b'").unwrap();
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "http://api.example.com/search");
+        assert_eq!(get.query, vec![("q".to_string(), "a&b".to_string())]);
+        assert!(get.body.is_none());
+    }
+
+    #[test]
+    fn attached_option_values_are_supported() {
+        let request =
+            parse_command("curl -XDELETE --header=Accept:application/json https://x.test/pets/1")
+                .unwrap();
+        assert_eq!(request.method, "DELETE");
+        assert_eq!(request.url, "https://x.test/pets/1");
+    }
+
+    #[test]
+    fn rejects_non_curl_commands_and_file_bodies() {
+        assert!(parse_command("wget https://x.test").is_err());
+        assert!(parse_command("curl -d @body.json https://x.test").is_err());
+        assert!(parse_command("curl -s").is_err());
+    }
+}
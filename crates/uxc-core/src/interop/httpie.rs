@@ -0,0 +1,200 @@
+//! HTTPie command import
+
+use super::{split_command, split_query, ImportedRequest};
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Options whose value is the next word.
+const VALUE_OPTIONS: &[&str] = &[
+    "-a",
+    "-A",
+    "-o",
+    "-p",
+    "--auth",
+    "--auth-type",
+    "--output",
+    "--print",
+    "--session",
+    "--session-read-only",
+    "--timeout",
+    "--proxy",
+    "--cert",
+    "--cert-key",
+    "--verify",
+    "--pretty",
+    "--style",
+    "--format-options",
+    "--boundary",
+];
+
+/// Parse an HTTPie (`http`/`https`) command line into a request.
+///
+/// Request items follow HTTPie syntax: `key==value` is a query parameter,
+/// `key=value` a string body field, `key:=json` a raw JSON body field and
+/// `Header:value` a header. Headers are parsed but not kept; credentials
+/// belong in auth profiles.
+pub fn parse_command(command: &str) -> Result<ImportedRequest> {
+    let words = split_command(command)?;
+    let mut words = words.into_iter();
+    let scheme = match words.next().as_deref() {
+        Some("http") => "http",
+        Some("https") => "https",
+        _ => bail!("Not an HTTPie command: expected it to start with 'http' or 'https'"),
+    };
+
+    let mut positional = Vec::new();
+    while let Some(word) = words.next() {
+        if word == "--" {
+            positional.extend(words.by_ref());
+            break;
+        }
+        let option = word.split_once('=').map_or(word.as_str(), |(name, _)| name);
+        if word.starts_with("--") && word.contains('=') {
+            continue;
+        }
+        if VALUE_OPTIONS.contains(&option) {
+            words.next();
+            continue;
+        }
+        if word.starts_with('-') && word.len() > 1 {
+            continue;
+        }
+        positional.push(word);
+    }
+
+    let mut positional = positional.into_iter().peekable();
+    let method = match positional.peek() {
+        Some(word) if METHODS.contains(&word.to_ascii_uppercase().as_str()) => {
+            positional.next().map(|word| word.to_ascii_uppercase())
+        }
+        _ => None,
+    };
+    let url = positional.next().context("HTTPie command has no URL")?;
+    let (base, mut query) = split_query(&expand_url(&url, scheme));
+
+    let mut body = Map::new();
+    for item in positional {
+        match parse_item(&item)? {
+            Item::Query(key, value) => query.push((key, value)),
+            Item::Field(key, value) => {
+                body.insert(key, value);
+            }
+            Item::Header => {}
+        }
+    }
+
+    let body = (!body.is_empty()).then_some(Value::Object(body));
+    let method = method.unwrap_or_else(|| {
+        if body.is_some() {
+            "POST".to_string()
+        } else {
+            "GET".to_string()
+        }
+    });
+
+    Ok(ImportedRequest {
+        name: format!("{} {}", method, base),
+        description: None,
+        method,
+        url: base,
+        query,
+        body,
+    })
+}
+
+/// HTTPie URL shorthands: `:3000/x` is localhost and a missing scheme
+/// defaults to the command's scheme.
+fn expand_url(url: &str, scheme: &str) -> String {
+    if url.contains("://") {
+        url.to_string()
+    } else if let Some(rest) = url.strip_prefix(':') {
+        if rest.starts_with('/') || rest.is_empty() {
+            format!("{}://localhost{}", scheme, rest)
+        } else {
+            format!("{}://localhost:{}", scheme, rest)
+        }
+    } else {
+        format!("{}://{}", scheme, url)
+    }
+}
+
+enum Item {
+    Query(String, String),
+    Field(String, Value),
+    Header,
+}
+
+fn parse_item(item: &str) -> Result<Item> {
+    // The earliest separator wins, so `a=b:c` is a field and `X-A:b=c` a header.
+    let separators = ["==", ":=@", "=@", ":=", "@", "=", ":"];
+    let found = separators
+        .iter()
+        .filter_map(|sep| item.find(sep).map(|idx| (idx, *sep)))
+        .min_by_key(|(idx, sep)| (*idx, std::cmp::Reverse(sep.len())));
+
+    let Some((idx, sep)) = found else {
+        bail!("Unrecognized HTTPie request item '{}'", item);
+    };
+    let key = item[..idx].to_string();
+    let value = &item[idx + sep.len()..];
+
+    match sep {
+        "==" => Ok(Item::Query(key, value.to_string())),
+        "=" => Ok(Item::Field(key, Value::String(value.to_string()))),
+        ":=" => Ok(Item::Field(
+            key,
+            serde_json::from_str(value)
+                .with_context(|| format!("Invalid JSON in HTTPie item '{}'", item))?,
+        )),
+        ":" => Ok(Item::Header),
+        _ => bail!(
+            "Reading request items from files ('{}') is not supported",
+            item
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_method_fields_and_query() {
+        let request = parse_command(
+            "http --check-status PUT api.example.com/pets/1 name=cat age:=2 tags:='[\"a\"]' \
+             verbose==true Authorization:'Bearer x'",
+        )
+        .unwrap();
+
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.url, "http://api.example.com/pets/1");
+        assert_eq!(
+            request.query,
+            vec![("verbose".to_string(), "true".to_string())]
+        );
+        assert_eq!(
+            request.body,
+            Some(json!({"name": "cat", "age": 2, "tags": ["a"]}))
+        );
+    }
+
+    #[test]
+    fn infers_method_and_expands_localhost_shorthand() {
+        let get = parse_command("https -a user:pass :8443/pets").unwrap();
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "https://localhost:8443/pets");
+
+        let post = parse_command("http :/login user=bob").unwrap();
+        assert_eq!(post.method, "POST");
+        assert_eq!(post.url, "http://localhost/login");
+    }
+
+    #[test]
+    fn rejects_file_items() {
+        assert!(parse_command("http POST x.test/pets photo@cat.png").is_err());
+        assert!(parse_command("curl x.test").is_err());
+    }
+}
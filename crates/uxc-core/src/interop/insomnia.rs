@@ -0,0 +1,258 @@
+//! Insomnia export format v4 export and import
+
+use super::{
+    auth_variables, normalize_variables, to_template, ExportedBody, ExportedRequest,
+    ImportedRequest, BASE_URL_VARIABLE,
+};
+use crate::auth::AuthType;
+use crate::templates::Template;
+use anyhow::{bail, Result};
+use serde_json::{json, Map, Value};
+
+const WORKSPACE_ID: &str = "wrk_uxc";
+const ENVIRONMENT_ID: &str = "env_uxc";
+
+/// Render an Insomnia v4 export for an endpoint.
+pub fn export_collection(
+    name: &str,
+    endpoint: &str,
+    requests: &[ExportedRequest],
+    auth_type: &AuthType,
+) -> Value {
+    let mut environment = Map::new();
+    environment.insert(BASE_URL_VARIABLE.to_string(), json!(endpoint));
+    for key in auth_variables(auth_type) {
+        environment.insert(key.to_string(), json!(""));
+    }
+
+    let mut resources = vec![
+        json!({
+            "_id": WORKSPACE_ID,
+            "_type": "workspace",
+            "name": name,
+            "description": format!("Generated by uxc from {}", endpoint),
+        }),
+        json!({
+            "_id": ENVIRONMENT_ID,
+            "_type": "environment",
+            "parentId": WORKSPACE_ID,
+            "name": "Base Environment",
+            "data": environment,
+        }),
+    ];
+    resources.extend(
+        requests
+            .iter()
+            .enumerate()
+            .map(|(idx, request)| export_request(idx, request, auth_type)),
+    );
+
+    json!({
+        "_type": "export",
+        "__export_format": 4,
+        "__export_source": format!("uxc:{}", env!("CARGO_PKG_VERSION")),
+        "resources": resources,
+    })
+}
+
+fn variable(name: &str) -> String {
+    format!("{{{{ _.{} }}}}", name)
+}
+
+fn authentication(auth_type: &AuthType) -> Value {
+    match auth_type {
+        AuthType::Bearer | AuthType::OAuth => {
+            json!({ "type": "bearer", "token": variable("token") })
+        }
+        AuthType::ApiKey => json!({
+            "type": "apikey",
+            "key": "X-API-Key",
+            "value": variable("apiKey"),
+            "addTo": "header",
+        }),
+        AuthType::Basic => json!({
+            "type": "basic",
+            "username": variable("username"),
+            "password": variable("password"),
+        }),
+        AuthType::Session => json!({}),
+    }
+}
+
+fn export_request(idx: usize, request: &ExportedRequest, auth_type: &AuthType) -> Value {
+    let path = request
+        .path
+        .split('/')
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => variable(name),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let body = match &request.body {
+        Some(ExportedBody::Json(body)) => json!({
+            "mimeType": "application/json",
+            "text": serde_json::to_string_pretty(body).unwrap_or_default(),
+        }),
+        Some(ExportedBody::GraphQl { query, variables }) => json!({
+            "mimeType": "application/graphql",
+            "text": json!({ "query": query, "variables": variables }).to_string(),
+        }),
+        None => json!({}),
+    };
+    let mut headers = request
+        .headers
+        .iter()
+        .map(|name| json!({ "name": name, "value": variable(name) }))
+        .collect::<Vec<_>>();
+    if request.body.is_some() {
+        headers.push(json!({ "name": "Content-Type", "value": "application/json" }));
+    }
+
+    json!({
+        "_id": format!("req_uxc_{}", idx + 1),
+        "_type": "request",
+        "parentId": WORKSPACE_ID,
+        "name": request.name,
+        "description": request.description.clone().unwrap_or_default(),
+        "method": request.method,
+        "url": format!("{}{}", variable(BASE_URL_VARIABLE), path),
+        "parameters": request
+            .query
+            .iter()
+            .map(|key| json!({ "name": key, "value": variable(key), "disabled": true }))
+            .collect::<Vec<_>>(),
+        "headers": headers,
+        "body": body,
+        "authentication": authentication(auth_type),
+    })
+}
+
+/// Convert an Insomnia v4 export into named templates.
+pub fn import_collection(export: &Value) -> Result<Vec<(String, Template)>> {
+    if export.get("_type").and_then(Value::as_str) != Some("export") {
+        bail!("Not an Insomnia export: expected _type = \"export\"");
+    }
+    let resources = export
+        .get("resources")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    // Base environments carry the variables referenced by request URLs.
+    let mut variables = Map::new();
+    for resource in &resources {
+        if resource.get("_type").and_then(Value::as_str) == Some("environment") {
+            if let Some(data) = resource.get("data").and_then(Value::as_object) {
+                variables.extend(data.clone());
+            }
+        }
+    }
+
+    Ok(resources
+        .iter()
+        .filter(|resource| resource.get("_type").and_then(Value::as_str) == Some("request"))
+        .filter_map(parse_request)
+        .map(|request| {
+            (
+                request.name.clone(),
+                to_template(&request, &variables, "insomnia"),
+            )
+        })
+        .collect())
+}
+
+fn parse_request(resource: &Value) -> Option<ImportedRequest> {
+    let url = resource.get("url").and_then(Value::as_str)?;
+    let query = resource
+        .get("parameters")
+        .and_then(Value::as_array)
+        .map(|params| {
+            params
+                .iter()
+                .filter(|param| {
+                    !param
+                        .get("disabled")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false)
+                })
+                .filter_map(|param| {
+                    Some((
+                        param.get("name")?.as_str()?.to_string(),
+                        param
+                            .get("value")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                    ))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let body = resource
+        .get("body")
+        .and_then(|body| body.get("text"))
+        .and_then(Value::as_str)
+        .and_then(|text| serde_json::from_str::<Value>(text).ok());
+
+    Some(ImportedRequest {
+        name: resource
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("request")
+            .to_string(),
+        description: resource
+            .get("description")
+            .and_then(Value::as_str)
+            .filter(|text| !text.is_empty())
+            .map(ToString::to_string),
+        method: resource
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or("GET")
+            .to_string(),
+        url: normalize_variables(url),
+        query,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_then_import_round_trips_graphql_operation() {
+        let request = ExportedRequest {
+            name: "viewer".to_string(),
+            description: None,
+            method: "POST".to_string(),
+            path: String::new(),
+            path_variables: Vec::new(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: Some(ExportedBody::GraphQl {
+                query: "query viewer {\n  viewer {\n    __typename\n  }\n}".to_string(),
+                variables: json!({}),
+            }),
+        };
+
+        let export = export_collection(
+            "gql",
+            "https://api.example.com/graphql",
+            &[request],
+            &AuthType::Bearer,
+        );
+        assert_eq!(export["resources"][1]["data"]["token"], "");
+        assert_eq!(export["resources"][2]["authentication"]["type"], "bearer");
+
+        let templates = import_collection(&export).unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].1.endpoint, "https://api.example.com/graphql");
+        assert_eq!(templates[0].1.operation, "query/viewer");
+    }
+}
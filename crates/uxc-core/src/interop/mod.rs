@@ -0,0 +1,774 @@
+//! Interoperability with other API tools
+//!
+//! Exporters render normalized operations as Postman or Insomnia collections;
+//! importers turn their requests (and curl/HTTPie command lines) back into
+//! uxc [`Template`]s. Single calls are exported as curl or grpcurl commands
+//! by [`command`].
+
+pub mod command;
+pub mod curl;
+pub mod httpie;
+pub mod insomnia;
+pub mod postman;
+
+use crate::adapters::{Operation, Parameter};
+use crate::auth::AuthType;
+use crate::templates::Template;
+use anyhow::{bail, Result};
+use serde_json::{json, Map, Value};
+
+/// Variable holding the endpoint URL in exported collections.
+pub const BASE_URL_VARIABLE: &str = "baseUrl";
+
+/// Protocol-neutral view of one exported request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub method: String,
+    /// Path relative to the endpoint; `{name}` marks a path variable
+    pub path: String,
+    pub path_variables: Vec<String>,
+    pub query: Vec<String>,
+    pub headers: Vec<String>,
+    pub body: Option<ExportedBody>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportedBody {
+    Json(Value),
+    GraphQl { query: String, variables: Value },
+}
+
+/// Auth placeholder variables for a collection, keyed by auth type.
+pub fn auth_variables(auth_type: &AuthType) -> Vec<&'static str> {
+    match auth_type {
+        AuthType::Bearer | AuthType::OAuth => vec!["token"],
+        AuthType::ApiKey => vec!["apiKey"],
+        AuthType::Basic => vec!["username", "password"],
+        // Both tools keep cookies from a login request on their own.
+        AuthType::Session => vec![],
+    }
+}
+
+/// Build exportable requests for the operations of an endpoint.
+pub fn export_requests(protocol: &str, operations: &[Operation]) -> Result<Vec<ExportedRequest>> {
+    operations
+        .iter()
+        .map(|op| export_request(protocol, op))
+        .collect()
+}
+
+fn export_request(protocol: &str, op: &Operation) -> Result<ExportedRequest> {
+    let mut request = ExportedRequest {
+        name: op.display_name.clone(),
+        description: op.description.clone(),
+        method: "POST".to_string(),
+        path: String::new(),
+        path_variables: Vec::new(),
+        query: Vec::new(),
+        headers: Vec::new(),
+        body: None,
+    };
+
+    match protocol {
+        "openapi" => {
+            let (method, path) = op.operation_id.split_once(':').ok_or_else(|| {
+                anyhow::anyhow!("Invalid OpenAPI operation ID '{}'", op.operation_id)
+            })?;
+            let path_variables = path_variables(path);
+            let mut body = Map::new();
+            for param in &op.parameters {
+                // Parameters without a declared location go where a call
+                // would send them
+                let location = param.location.as_deref().unwrap_or(
+                    if matches!(method, "get" | "delete" | "head" | "options") {
+                        "query"
+                    } else {
+                        "body"
+                    },
+                );
+                match location {
+                    _ if path_variables.contains(&param.name) => {}
+                    "path" => {}
+                    "query" => request.query.push(param.name.clone()),
+                    "header" => request.headers.push(param.name.clone()),
+                    // Both tools keep cookies on their own
+                    "cookie" => {}
+                    _ => {
+                        body.insert(param.name.clone(), placeholder(&param.name));
+                    }
+                }
+            }
+
+            request.method = method.to_ascii_uppercase();
+            request.path = path.to_string();
+            request.path_variables = path_variables;
+            if !body.is_empty() {
+                request.body = Some(ExportedBody::Json(Value::Object(body)));
+            }
+        }
+        "jsonrpc" => {
+            request.body = Some(ExportedBody::Json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": op.operation_id,
+                "params": placeholder_object(&op.parameters),
+            })));
+        }
+        "mcp" => {
+            request.body = Some(ExportedBody::Json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {
+                    "name": op.operation_id,
+                    "arguments": placeholder_object(&op.parameters),
+                },
+            })));
+        }
+        "graphql" => {
+            request.body = Some(graphql_body(op));
+        }
+        other => bail!(
+            "Collection export is not supported for {} endpoints (supported: openapi, jsonrpc, graphql, mcp)",
+            other
+        ),
+    }
+
+    Ok(request)
+}
+
+fn graphql_body(op: &Operation) -> ExportedBody {
+    let (kind, field) = op
+        .operation_id
+        .split_once('/')
+        .unwrap_or(("query", op.operation_id.as_str()));
+
+    let (declarations, arguments) = if op.parameters.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let declarations = op
+            .parameters
+            .iter()
+            .map(|p| format!("${}: {}", p.name, p.param_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arguments = op
+            .parameters
+            .iter()
+            .map(|p| format!("{}: ${}", p.name, p.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (format!("({})", declarations), format!("({})", arguments))
+    };
+
+    let query = format!(
+        "{} {}{} {{\n  {}{} {{\n    __typename\n  }}\n}}",
+        kind, field, declarations, field, arguments
+    );
+    let variables = op
+        .parameters
+        .iter()
+        .map(|p| (p.name.clone(), Value::Null))
+        .collect::<Map<_, _>>();
+
+    ExportedBody::GraphQl {
+        query,
+        variables: Value::Object(variables),
+    }
+}
+
+fn placeholder(name: &str) -> Value {
+    Value::String(format!("{{{{{}}}}}", name))
+}
+
+fn placeholder_object(parameters: &[Parameter]) -> Value {
+    Value::Object(
+        parameters
+            .iter()
+            .map(|p| (p.name.clone(), placeholder(&p.name)))
+            .collect(),
+    )
+}
+
+fn path_variables(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| {
+            segment
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .map(ToString::to_string)
+        })
+        .collect()
+}
+
+/// An HTTP request recovered from another tool's format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub method: String,
+    /// URL with variables already normalized to `{{name}}`
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub body: Option<Value>,
+}
+
+/// Convert `{{ _.name }}` (Insomnia) and `{{ name }}` to `{{name}}`.
+pub fn normalize_variables(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let inner = rest[start + 2..start + end].trim();
+        let inner = inner.strip_prefix("_.").unwrap_or(inner);
+        output.push_str(&format!("{{{{{}}}}}", inner));
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Map an imported request onto a uxc invocation.
+///
+/// Bodies are inspected first: JSON-RPC envelopes become method calls (MCP
+/// `tools/call` becomes a tool call) and GraphQL documents become
+/// `query/<field>` operations. Everything else is treated as an OpenAPI-style
+/// `method:/path` operation relative to the endpoint.
+pub fn to_template(
+    request: &ImportedRequest,
+    variables: &Map<String, Value>,
+    source: &str,
+) -> Template {
+    let (base, path) = split_endpoint(&request.url, variables);
+    let mut args = Map::new();
+
+    let (endpoint, operation) = match request.body.as_ref().and_then(Value::as_object) {
+        Some(body) if body.contains_key("jsonrpc") && body.contains_key("method") => {
+            let method = body["method"].as_str().unwrap_or_default();
+            let params = body.get("params").cloned().unwrap_or(Value::Null);
+            let operation = if method == "tools/call" {
+                if let Some(arguments) = params.get("arguments").and_then(Value::as_object) {
+                    args.extend(arguments.clone());
+                }
+                params.get("name").and_then(Value::as_str).unwrap_or(method)
+            } else {
+                if let Some(params) = params.as_object() {
+                    args.extend(params.clone());
+                }
+                method
+            };
+            (join_endpoint(&base, &path), operation.to_string())
+        }
+        Some(body) if body.get("query").and_then(Value::as_str).is_some() => {
+            if let Some(variables) = body.get("variables").and_then(Value::as_object) {
+                args.extend(variables.clone());
+            }
+            let query = body["query"].as_str().unwrap_or_default();
+            (join_endpoint(&base, &path), graphql_operation_id(query))
+        }
+        body => {
+            if let Some(body) = body {
+                args.extend(body.clone());
+            }
+            for (key, value) in &request.query {
+                args.insert(key.clone(), Value::String(value.clone()));
+            }
+            let operation = format!(
+                "{}:{}",
+                request.method.to_ascii_lowercase(),
+                operation_path(&path)
+            );
+            (base, operation)
+        }
+    };
+
+    Template {
+        endpoint,
+        operation,
+        args,
+        description: request.description.clone(),
+        source: Some(source.to_string()),
+    }
+}
+
+/// Split a URL into the endpoint and the path below it.
+///
+/// A leading `{{var}}` that resolves through `variables` is the endpoint, so
+/// `{{baseUrl}}/pets` with `baseUrl = https://api.example.com/v1` maps to
+/// endpoint `https://api.example.com/v1` and path `/pets`.
+fn split_endpoint(url: &str, variables: &Map<String, Value>) -> (String, String) {
+    let url = url.split('?').next().unwrap_or(url);
+
+    if let Some(rest) = url.strip_prefix("{{") {
+        if let Some((name, path)) = rest.split_once("}}") {
+            let endpoint = variables
+                .get(name)
+                .and_then(Value::as_str)
+                .map(|value| value.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| format!("{{{{{}}}}}", name));
+            return (endpoint, path.to_string());
+        }
+    }
+
+    match url::Url::parse(url) {
+        Ok(parsed) => {
+            let mut endpoint = format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or(""));
+            if let Some(port) = parsed.port() {
+                endpoint.push_str(&format!(":{}", port));
+            }
+            // `Url` percent-encodes braces; keep the raw path to preserve `{{var}}`.
+            let path = url
+                .find("://")
+                .and_then(|idx| url[idx + 3..].find('/').map(|p| &url[idx + 3 + p..]))
+                .unwrap_or("")
+                .to_string();
+            (endpoint, path)
+        }
+        Err(_) => (url.to_string(), String::new()),
+    }
+}
+
+fn join_endpoint(endpoint: &str, path: &str) -> String {
+    if path.is_empty() || path == "/" {
+        endpoint.to_string()
+    } else {
+        format!("{}{}", endpoint, path)
+    }
+}
+
+/// Turn `:id` and `{{id}}` path segments into `{id}`.
+fn operation_path(path: &str) -> String {
+    let path = if path.is_empty() { "/" } else { path };
+    path.split('/')
+        .map(|segment| {
+            if let Some(name) = segment.strip_prefix(':') {
+                format!("{{{}}}", name)
+            } else if let Some(name) = segment
+                .strip_prefix("{{")
+                .and_then(|s| s.strip_suffix("}}"))
+            {
+                format!("{{{}}}", name)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Derive `query/<field>` (or `mutation/`, `subscription/`) from a document.
+pub fn graphql_operation_id(document: &str) -> String {
+    let trimmed = document.trim_start();
+    let kind = ["mutation", "subscription"]
+        .into_iter()
+        .find(|kind| trimmed.starts_with(kind))
+        .unwrap_or("query");
+
+    let field = trimmed
+        .find('{')
+        .map(|idx| &trimmed[idx + 1..])
+        .and_then(|body| {
+            body.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .find(|token| !token.is_empty())
+        })
+        .unwrap_or("");
+
+    format!("{}/{}", kind, field)
+}
+
+/// Split a shell command line into words.
+///
+/// Handles single and double quotes, backslash escapes and line
+/// continuations; this is enough for commands copied from browser dev tools
+/// and API docs. Variable expansion and globbing are not performed.
+pub fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => bail!("Unterminated single quote in command"),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => bail!("Unterminated double quote in command"),
+                        },
+                        Some(c) => current.push(c),
+                        None => bail!("Unterminated double quote in command"),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(c) => {
+                    in_word = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
+/// Find the OpenAPI operation whose `method:/path` template matches a
+/// concrete request, returning its ID and the captured path parameters.
+///
+/// When several templates match, the one with the most literal segments
+/// wins, so `/pets/mine` beats `/pets/{id}`.
+pub fn match_operation(
+    method: &str,
+    path: &str,
+    operations: &[Operation],
+) -> Option<(String, Map<String, Value>)> {
+    let method = method.to_ascii_lowercase();
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut best: Option<(usize, String, Map<String, Value>)> = None;
+    for op in operations {
+        let Some((op_method, op_path)) = op.operation_id.split_once(':') else {
+            continue;
+        };
+        if op_method != method {
+            continue;
+        }
+        let template = op_path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        if template.len() != segments.len() {
+            continue;
+        }
+
+        let mut literals = 0;
+        let mut captures = Map::new();
+        let matched = template.iter().zip(&segments).all(|(expected, actual)| {
+            match expected.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => {
+                    captures.insert(name.to_string(), Value::String(actual.to_string()));
+                    true
+                }
+                None => {
+                    literals += 1;
+                    expected == actual
+                }
+            }
+        });
+
+        if matched && best.as_ref().is_none_or(|(score, _, _)| literals > *score) {
+            best = Some((literals, op.operation_id.clone(), captures));
+        }
+    }
+
+    best.map(|(_, operation, captures)| (operation, captures))
+}
+
+/// Candidate schema paths for a request target relative to `endpoint`.
+///
+/// `target` may be a path or a full URL; the query string is ignored. When
+/// the path starts with the endpoint's own base path, the stripped form is
+/// tried first, so `/v2/users` against `https://api.test/v2` yields `/users`
+/// and then `/v2/users`.
+pub fn relative_paths(endpoint: &str, target: &str) -> Vec<String> {
+    let path = if target.contains("://") {
+        url::Url::parse(target)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| target.to_string())
+    } else {
+        let (path, _) = split_query(target);
+        if path.starts_with('/') {
+            path
+        } else {
+            format!("/{}", path)
+        }
+    };
+
+    let base = url::Url::parse(endpoint)
+        .map(|url| url.path().trim_end_matches('/').to_string())
+        .unwrap_or_default();
+    let mut paths = Vec::new();
+    if !base.is_empty() {
+        if let Some(rest) = path.strip_prefix(&base) {
+            if rest.starts_with('/') {
+                paths.push(rest.to_string());
+            } else if rest.is_empty() {
+                paths.push("/".to_string());
+            }
+        }
+    }
+    paths.push(path);
+    paths
+}
+
+/// Parse a request body: JSON when possible, otherwise form-encoded pairs.
+fn parse_body(body: &str) -> Option<Value> {
+    let trimmed = body.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
+        return Some(value);
+    }
+    if trimmed.contains('=') {
+        let pairs = url::form_urlencoded::parse(trimmed.as_bytes())
+            .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+            .collect::<Map<_, _>>();
+        return Some(Value::Object(pairs));
+    }
+    Some(Value::String(trimmed.to_string()))
+}
+
+/// Split the query string off a URL into decoded pairs.
+fn split_query(url: &str) -> (String, Vec<(String, String)>) {
+    match url.split_once('?') {
+        Some((base, query)) => (
+            base.to_string(),
+            url::form_urlencoded::parse(query.as_bytes())
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect(),
+        ),
+        None => (url.to_string(), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn located(mut op: Operation, locations: &[&str]) -> Operation {
+        for (param, location) in op.parameters.iter_mut().zip(locations) {
+            param.location = Some(location.to_string());
+        }
+        op
+    }
+
+    fn operation(id: &str, params: &[(&str, bool)]) -> Operation {
+        Operation {
+            operation_id: id.to_string(),
+            display_name: id.to_string(),
+            description: None,
+            parameters: params
+                .iter()
+                .map(|(name, required)| Parameter {
+                    name: name.to_string(),
+                    param_type: "string".to_string(),
+                    required: *required,
+                    description: None,
+                    location: None,
+                })
+                .collect(),
+            return_type: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn openapi_export_splits_path_query_and_body() {
+        let get = export_request(
+            "openapi",
+            &operation("get:/pets/{id}", &[("id", true), ("verbose", false)]),
+        )
+        .unwrap();
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.path_variables, vec!["id"]);
+        assert_eq!(get.query, vec!["verbose"]);
+        assert!(get.body.is_none());
+
+        let post = export_request("openapi", &operation("post:/pets", &[("name", true)])).unwrap();
+        assert_eq!(
+            post.body,
+            Some(ExportedBody::Json(json!({"name": "{{name}}"})))
+        );
+    }
+
+    #[test]
+    fn openapi_export_follows_declared_locations() {
+        let op = located(
+            operation(
+                "post:/pets/{id}/tags",
+                &[
+                    ("id", true),
+                    ("dry_run", false),
+                    ("X-Request-Id", false),
+                    ("tag", true),
+                ],
+            ),
+            &["path", "query", "header", "body"],
+        );
+        let post = export_request("openapi", &op).unwrap();
+        assert_eq!(post.method, "POST");
+        assert_eq!(post.path_variables, vec!["id"]);
+        assert_eq!(post.query, vec!["dry_run"]);
+        assert_eq!(post.headers, vec!["X-Request-Id"]);
+        assert_eq!(
+            post.body,
+            Some(ExportedBody::Json(json!({"tag": "{{tag}}"})))
+        );
+
+        let op = located(operation("post:/pets", &[("dry_run", false)]), &["query"]);
+        let post = export_request("openapi", &op).unwrap();
+        assert_eq!(post.query, vec!["dry_run"]);
+        assert!(post.body.is_none());
+    }
+
+    #[test]
+    fn grpc_export_is_rejected() {
+        assert!(export_request("grpc", &operation("pkg.Svc/Call", &[])).is_err());
+    }
+
+    #[test]
+    fn imported_http_request_maps_to_operation() {
+        let variables = json!({"baseUrl": "https://api.example.com/v1"});
+        let request = ImportedRequest {
+            name: "Get pet".to_string(),
+            description: None,
+            method: "GET".to_string(),
+            url: "{{baseUrl}}/pets/:id".to_string(),
+            query: vec![("verbose".to_string(), "true".to_string())],
+            body: None,
+        };
+
+        let template = to_template(&request, variables.as_object().unwrap(), "postman");
+        assert_eq!(template.endpoint, "https://api.example.com/v1");
+        assert_eq!(template.operation, "get:/pets/{id}");
+        assert_eq!(template.args["verbose"], json!("true"));
+    }
+
+    #[test]
+    fn imported_jsonrpc_and_graphql_bodies_map_to_operations() {
+        let rpc = ImportedRequest {
+            name: "rpc".to_string(),
+            description: None,
+            method: "POST".to_string(),
+            url: "https://rpc.example.com".to_string(),
+            query: vec![],
+            body: Some(
+                json!({"jsonrpc": "2.0", "id": 1, "method": "eth_getBalance", "params": {"address": "0x1"}}),
+            ),
+        };
+        let template = to_template(&rpc, &Map::new(), "postman");
+        assert_eq!(template.endpoint, "https://rpc.example.com");
+        assert_eq!(template.operation, "eth_getBalance");
+        assert_eq!(template.args["address"], json!("0x1"));
+
+        let gql = ImportedRequest {
+            body: Some(
+                json!({"query": "mutation AddPet($name: String) { addPet(name: $name) { id } }", "variables": {"name": "cat"}}),
+            ),
+            url: "https://api.example.com/graphql".to_string(),
+            ..rpc
+        };
+        let template = to_template(&gql, &Map::new(), "postman");
+        assert_eq!(template.endpoint, "https://api.example.com/graphql");
+        assert_eq!(template.operation, "mutation/addPet");
+    }
+
+    #[test]
+    fn split_command_handles_quotes_and_continuations() {
+        let words = split_command(
+            "curl -X POST 'https://api.example.com/pets' \\\n  -d \"{\\\"name\\\": \\\"cat\\\"}\"",
+        )
+        .unwrap();
+        assert_eq!(
+            words,
+            vec![
+                "curl",
+                "-X",
+                "POST",
+                "https://api.example.com/pets",
+                "-d",
+                "{\"name\": \"cat\"}"
+            ]
+        );
+        assert!(split_command("curl 'oops").is_err());
+    }
+
+    #[test]
+    fn match_operation_prefers_literal_segments() {
+        let operations = vec![
+            operation("get:/pets/{id}", &[("id", true)]),
+            operation("get:/pets/mine", &[]),
+            operation("delete:/pets/{id}", &[("id", true)]),
+        ];
+
+        let (op, args) = match_operation("GET", "/pets/42", &operations).unwrap();
+        assert_eq!(op, "get:/pets/{id}");
+        assert_eq!(args["id"], json!("42"));
+
+        let (op, args) = match_operation("GET", "/pets/mine", &operations).unwrap();
+        assert_eq!(op, "get:/pets/mine");
+        assert!(args.is_empty());
+
+        assert!(match_operation("POST", "/pets/42", &operations).is_none());
+    }
+
+    #[test]
+    fn relative_paths_strip_endpoint_base_path() {
+        assert_eq!(
+            relative_paths("https://api.test/v2", "/v2/users?page=2"),
+            vec!["/users", "/v2/users"]
+        );
+        assert_eq!(
+            relative_paths("https://api.test/v2", "https://api.test/v2/users/7"),
+            vec!["/users/7", "/v2/users/7"]
+        );
+        assert_eq!(relative_paths("https://api.test", "pets"), vec!["/pets"]);
+        assert_eq!(
+            relative_paths("https://api.test/v2", "/v20/users"),
+            vec!["/v20/users"]
+        );
+    }
+
+    #[test]
+    fn normalize_variables_handles_insomnia_syntax() {
+        assert_eq!(
+            normalize_variables("{{ _.baseUrl }}/pets/{{ _.id }}"),
+            "{{baseUrl}}/pets/{{id}}"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Postman collection v2.1 export and import
+
+use super::{
+    auth_variables, normalize_variables, to_template, ExportedBody, ExportedRequest,
+    ImportedRequest, BASE_URL_VARIABLE,
+};
+use crate::auth::AuthType;
+use crate::templates::Template;
+use anyhow::{bail, Result};
+use serde_json::{json, Map, Value};
+
+const COLLECTION_SCHEMA: &str =
+    "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Render a Postman v2.1 collection for an endpoint.
+pub fn export_collection(
+    name: &str,
+    endpoint: &str,
+    requests: &[ExportedRequest],
+    auth_type: &AuthType,
+) -> Value {
+    let mut variables = vec![json!({ "key": BASE_URL_VARIABLE, "value": endpoint })];
+    for key in auth_variables(auth_type) {
+        variables.push(json!({ "key": key, "value": "" }));
+    }
+
+    json!({
+        "info": {
+            "name": name,
+            "description": format!("Generated by uxc from {}", endpoint),
+            "schema": COLLECTION_SCHEMA,
+        },
+        "auth": auth_block(auth_type),
+        "variable": variables,
+        "item": requests.iter().map(export_item).collect::<Vec<_>>(),
+    })
+}
+
+fn auth_block(auth_type: &AuthType) -> Value {
+    match auth_type {
+        AuthType::Bearer | AuthType::OAuth => json!({
+            "type": "bearer",
+            "bearer": [{ "key": "token", "value": "{{token}}", "type": "string" }],
+        }),
+        AuthType::ApiKey => json!({
+            "type": "apikey",
+            "apikey": [
+                { "key": "key", "value": "X-API-Key", "type": "string" },
+                { "key": "value", "value": "{{apiKey}}", "type": "string" },
+                { "key": "in", "value": "header", "type": "string" },
+            ],
+        }),
+        AuthType::Basic => json!({
+            "type": "basic",
+            "basic": [
+                { "key": "username", "value": "{{username}}", "type": "string" },
+                { "key": "password", "value": "{{password}}", "type": "string" },
+            ],
+        }),
+        AuthType::Session => json!({ "type": "noauth" }),
+    }
+}
+
+fn export_item(request: &ExportedRequest) -> Value {
+    let path = request
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{}", name),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let query = request
+        .query
+        .iter()
+        .map(|key| json!({ "key": key, "value": format!("{{{{{}}}}}", key), "disabled": true }))
+        .collect::<Vec<_>>();
+
+    let mut raw = format!("{{{{{}}}}}", BASE_URL_VARIABLE);
+    if !path.is_empty() {
+        raw.push('/');
+        raw.push_str(&path.join("/"));
+    }
+
+    let mut url = json!({
+        "raw": raw,
+        "host": [format!("{{{{{}}}}}", BASE_URL_VARIABLE)],
+        "path": path,
+    });
+    if !query.is_empty() {
+        url["query"] = Value::Array(query);
+    }
+    if !request.path_variables.is_empty() {
+        url["variable"] = Value::Array(
+            request
+                .path_variables
+                .iter()
+                .map(|key| json!({ "key": key, "value": "" }))
+                .collect(),
+        );
+    }
+
+    let mut headers = request
+        .headers
+        .iter()
+        .map(|key| json!({ "key": key, "value": format!("{{{{{}}}}}", key) }))
+        .collect::<Vec<_>>();
+    if matches!(request.body, Some(ExportedBody::Json(_))) {
+        headers.push(json!({ "key": "Content-Type", "value": "application/json" }));
+    }
+
+    let mut item_request = json!({
+        "method": request.method,
+        "header": headers,
+        "url": url,
+    });
+    if let Some(description) = &request.description {
+        item_request["description"] = Value::String(description.clone());
+    }
+
+    match &request.body {
+        Some(ExportedBody::Json(body)) => {
+            item_request["body"] = json!({
+                "mode": "raw",
+                "raw": serde_json::to_string_pretty(body).unwrap_or_default(),
+                "options": { "raw": { "language": "json" } },
+            });
+        }
+        Some(ExportedBody::GraphQl { query, variables }) => {
+            item_request["body"] = json!({
+                "mode": "graphql",
+                "graphql": {
+                    "query": query,
+                    "variables": serde_json::to_string_pretty(variables).unwrap_or_default(),
+                },
+            });
+        }
+        None => {}
+    }
+
+    json!({ "name": request.name, "request": item_request })
+}
+
+/// Convert a Postman collection into named templates.
+pub fn import_collection(collection: &Value) -> Result<Vec<(String, Template)>> {
+    let is_postman = collection
+        .pointer("/info/schema")
+        .and_then(Value::as_str)
+        .is_some_and(|schema| schema.contains("getpostman.com"));
+    if !is_postman {
+        bail!("Not a Postman collection: missing info.schema");
+    }
+
+    let variables = collection
+        .get("variable")
+        .and_then(Value::as_array)
+        .map(|vars| {
+            vars.iter()
+                .filter_map(|var| {
+                    let key = var.get("key")?.as_str()?;
+                    Some((
+                        key.to_string(),
+                        var.get("value").cloned().unwrap_or(Value::Null),
+                    ))
+                })
+                .collect::<Map<_, _>>()
+        })
+        .unwrap_or_default();
+
+    let mut requests = Vec::new();
+    collect_items(collection.get("item"), &mut requests);
+
+    Ok(requests
+        .iter()
+        .map(|request| {
+            (
+                request.name.clone(),
+                to_template(request, &variables, "postman"),
+            )
+        })
+        .collect())
+}
+
+/// Walk items depth-first; folders are items with a nested `item` array.
+fn collect_items(items: Option<&Value>, out: &mut Vec<ImportedRequest>) {
+    let Some(items) = items.and_then(Value::as_array) else {
+        return;
+    };
+
+    for item in items {
+        if item.get("item").is_some() {
+            collect_items(item.get("item"), out);
+            continue;
+        }
+        if let Some(request) = parse_item(item) {
+            out.push(request);
+        }
+    }
+}
+
+fn parse_item(item: &Value) -> Option<ImportedRequest> {
+    let request = item.get("request")?;
+    let name = item
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("request")
+        .to_string();
+
+    // `request` may be a bare URL string.
+    if let Some(url) = request.as_str() {
+        return Some(ImportedRequest {
+            name,
+            description: None,
+            method: "GET".to_string(),
+            url: normalize_variables(url),
+            query: Vec::new(),
+            body: None,
+        });
+    }
+
+    let url_value = request.get("url")?;
+    let raw_url = url_value
+        .as_str()
+        .or_else(|| url_value.get("raw").and_then(Value::as_str))?;
+
+    let query = url_value
+        .get("query")
+        .and_then(Value::as_array)
+        .map(|params| {
+            params
+                .iter()
+                .filter(|param| {
+                    !param
+                        .get("disabled")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false)
+                })
+                .filter_map(|param| {
+                    Some((
+                        param.get("key")?.as_str()?.to_string(),
+                        param
+                            .get("value")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                    ))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let body =
+        request
+            .get("body")
+            .and_then(|body| match body.get("mode").and_then(Value::as_str) {
+                Some("raw") => body
+                    .get("raw")
+                    .and_then(Value::as_str)
+                    .and_then(|raw| serde_json::from_str::<Value>(raw).ok()),
+                Some("graphql") => {
+                    let graphql = body.get("graphql")?;
+                    let variables = match graphql.get("variables") {
+                        Some(Value::String(text)) => {
+                            serde_json::from_str(text).unwrap_or(Value::Null)
+                        }
+                        Some(other) => other.clone(),
+                        None => Value::Null,
+                    };
+                    Some(json!({ "query": graphql.get("query")?, "variables": variables }))
+                }
+                _ => None,
+            });
+
+    Some(ImportedRequest {
+        name,
+        description: request
+            .get("description")
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        method: request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or("GET")
+            .to_string(),
+        url: normalize_variables(raw_url),
+        query,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pets_request() -> ExportedRequest {
+        ExportedRequest {
+            name: "Get pet".to_string(),
+            description: Some("Fetch one pet".to_string()),
+            method: "GET".to_string(),
+            path: "/pets/{id}".to_string(),
+            path_variables: vec!["id".to_string()],
+            query: vec!["verbose".to_string()],
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn export_uses_base_url_variable_and_auth_placeholders() {
+        let collection = export_collection(
+            "petstore",
+            "https://api.example.com",
+            &[pets_request()],
+            &AuthType::ApiKey,
+        );
+
+        assert_eq!(collection["info"]["schema"], COLLECTION_SCHEMA);
+        assert_eq!(collection["auth"]["type"], "apikey");
+        assert_eq!(
+            collection["variable"][0]["value"],
+            "https://api.example.com"
+        );
+        assert_eq!(collection["variable"][1]["key"], "apiKey");
+        assert_eq!(
+            collection["item"][0]["request"]["url"]["raw"],
+            "{{baseUrl}}/pets/:id"
+        );
+    }
+
+    #[test]
+    fn export_then_import_round_trips_operation() {
+        let collection = export_collection(
+            "petstore",
+            "https://api.example.com/v1",
+            &[pets_request()],
+            &AuthType::Bearer,
+        );
+
+        let templates = import_collection(&collection).unwrap();
+        assert_eq!(templates.len(), 1);
+        let (name, template) = &templates[0];
+        assert_eq!(name, "Get pet");
+        assert_eq!(template.endpoint, "https://api.example.com/v1");
+        assert_eq!(template.operation, "get:/pets/{id}");
+        // Exported query params are disabled placeholders and are not imported.
+        assert!(template.args.is_empty());
+    }
+
+    #[test]
+    fn import_walks_folders_and_raw_bodies() {
+        let collection = json!({
+            "info": { "name": "x", "schema": COLLECTION_SCHEMA },
+            "item": [{
+                "name": "Pets",
+                "item": [{
+                    "name": "Create pet",
+                    "request": {
+                        "method": "POST",
+                        "url": "https://api.example.com/pets",
+                        "body": { "mode": "raw", "raw": "{\"name\": \"cat\"}" }
+                    }
+                }]
+            }]
+        });
+
+        let templates = import_collection(&collection).unwrap();
+        assert_eq!(templates[0].1.endpoint, "https://api.example.com");
+        assert_eq!(templates[0].1.operation, "post:/pets");
+        assert_eq!(templates[0].1.args["name"], "cat");
+    }
+
+    #[test]
+    fn import_rejects_non_postman_documents() {
+        assert!(import_collection(&json!({ "item": [] })).is_err());
+    }
+}
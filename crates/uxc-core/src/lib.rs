@@ -0,0 +1,71 @@
+//! UXC - Universal X-Protocol Call
+//!
+//! Schema-driven, multi-protocol RPC execution runtime. This crate is the
+//! library; the `uxc` command line lives in `uxc-cli`.
+
+#![allow(non_camel_case_types)]
+
+pub mod adapters;
+pub mod alias;
+pub mod allowlist;
+pub mod arguments;
+pub mod assertions;
+pub mod auth;
+pub mod batch;
+pub mod bench;
+pub mod cache;
+pub mod cassette;
+pub mod cli;
+pub mod client;
+pub mod codegen;
+pub mod coerce;
+pub mod config;
+pub mod datagen;
+pub mod diff;
+pub mod env_file;
+pub mod error;
+pub mod explain;
+pub mod filter;
+pub mod gateway;
+pub mod history;
+pub mod hooks;
+pub mod http;
+pub mod idn;
+pub mod instrumentation;
+pub mod interop;
+pub mod masking;
+pub mod middleware;
+pub mod mock;
+pub mod output;
+pub mod pagination;
+pub mod plugins;
+pub mod project;
+pub mod protocol;
+pub mod query;
+pub mod request_file;
+pub mod resume;
+pub mod retry;
+pub mod schema_diff;
+pub mod schema_mapping;
+pub mod strict_args;
+pub mod suggest;
+pub mod suite;
+pub mod templates;
+#[cfg(feature = "test-kit")]
+pub mod testkit;
+pub mod trace;
+pub mod units;
+
+pub use adapters::{Adapter, ProtocolType};
+pub use cache::{create_cache, create_default_cache, Cache, CacheConfig, CacheResult};
+pub use client::{Client, ClientBuilder};
+pub use error::{Result, UxcError};
+pub use output::OutputEnvelope;
+
+/// UXC version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// UXC library initialization
+pub fn init() {
+    // Initialize logging, etc.
+}
@@ -0,0 +1,113 @@
+//! Secret masking
+//!
+//! Credential material seen during an invocation is registered here: resolved
+//! profile keys and `env:`/`cmd:` secrets, OAuth tokens, session cookies, and
+//! values substituted for credential-like `${VAR}` names (see
+//! [`is_secret_name`]). Output channels that may quote requests — error
+//! envelopes, history entries and log lines — pass their text through [`mask`]
+//! so none of it is written out.
+
+use std::io::{self, Write};
+use std::sync::{OnceLock, RwLock};
+
+/// Replacement for masked values
+pub const MASK: &str = "***";
+
+/// Values shorter than this are not registered; they would mask ordinary text
+pub const MIN_SECRET_LEN: usize = 4;
+
+/// Variable name fragments that mark a `${VAR}` value as a secret
+const SECRET_NAME_HINTS: &[&str] = &[
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "AUTH",
+    "CREDENTIAL",
+    "COOKIE",
+    "SESSION",
+];
+
+fn registry() -> &'static RwLock<Vec<String>> {
+    static SECRETS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Remember a secret so it is masked in every output channel.
+pub fn register(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    if let Ok(mut secrets) = registry().write() {
+        if !secrets.iter().any(|known| known == secret) {
+            secrets.push(secret.to_string());
+            // Longest first, so a secret containing another is masked whole
+            secrets.sort_by_key(|known| std::cmp::Reverse(known.len()));
+        }
+    }
+}
+
+/// Whether a variable name suggests its value is a credential.
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    SECRET_NAME_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// Replace every registered secret in `text` with [`MASK`].
+pub fn mask(text: &str) -> String {
+    let Ok(secrets) = registry().read() else {
+        return text.to_string();
+    };
+    let mut masked = text.to_string();
+    for secret in secrets.iter() {
+        if masked.contains(secret.as_str()) {
+            masked = masked.replace(secret.as_str(), MASK);
+        }
+    }
+    masked
+}
+
+/// Writer for log output that masks registered secrets.
+///
+/// Each formatted log event arrives as one write, so secrets are never split
+/// across calls.
+pub struct MaskedStderr;
+
+impl Write for MaskedStderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        io::stderr().write_all(mask(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_registered_secrets_longest_first() {
+        register("mask-test-token");
+        register("mask-test-token-extended");
+        register("abc");
+
+        assert_eq!(
+            mask("Bearer mask-test-token-extended failed; retry mask-test-token with abc"),
+            "Bearer *** failed; retry *** with abc"
+        );
+    }
+
+    #[test]
+    fn recognises_secret_variable_names() {
+        assert!(is_secret_name("GITHUB_TOKEN"));
+        assert!(is_secret_name("api_key"));
+        assert!(is_secret_name("DB_PASSWORD"));
+        assert!(!is_secret_name("API_HOST"));
+    }
+}
@@ -0,0 +1,546 @@
+//! Call middleware
+//!
+//! A [`Middleware`] takes part in operation calls, whichever adapter makes
+//! them: it can change the arguments and headers of a call before it is
+//! sent, reshape its result, and decide what happens when it fails.
+//! Middleware is composed into a [`Pipeline`]:
+//!
+//! ```no_run
+//! # async fn call(adapter: &uxc_core::adapters::AdapterEnum) -> anyhow::Result<()> {
+//! use std::sync::Arc;
+//! use uxc_core::middleware::{Pipeline, TracingMiddleware};
+//! use uxc_core::retry::RetryPolicy;
+//!
+//! let pipeline = Pipeline::new()
+//!     .with(Arc::new(RetryPolicy { retries: 2, ..Default::default() }))
+//!     .with(Arc::new(TracingMiddleware));
+//! let result = pipeline
+//!     .execute(adapter, "https://api.example.com", "get:/users", Default::default())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests go through the middleware in the order it was added, results
+//! and errors in the reverse order. Middleware added with [`install`] runs
+//! on every call of the process, inside any pipeline.
+//!
+//! Built-in middleware:
+//!
+//! - [`AuthMiddleware`]: logs session profiles in and refreshes OAuth tokens
+//!   before calls, and once more when a call is rejected with HTTP 401
+//! - [`crate::retry::RetryPolicy`]: per-attempt timeouts and retries
+//! - [`TracingMiddleware`]: one `tracing` event per attempt, at debug level
+//! - [`crate::hooks::Hooks`]: request and response hook scripts
+
+use crate::adapters::{Adapter, ExecutionResult, ProtocolType, RawResponse, RequestPlan};
+use crate::auth::{oauth, session, AuthType, Profile};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+static INSTALLED: RwLock<Vec<Arc<dyn Middleware>>> = RwLock::new(Vec::new());
+
+tokio::task_local! {
+    static HEADERS: HeaderMap;
+}
+
+/// One attempt of an operation call, as middleware sees it
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub url: String,
+    pub operation: String,
+    pub protocol: ProtocolType,
+    pub args: HashMap<String, Value>,
+
+    /// Headers set on the HTTP requests of the call, over the adapter's own
+    pub headers: HeaderMap,
+
+    /// Number of the attempt, from 1
+    pub attempt: u32,
+
+    /// Limit for the attempt
+    pub timeout: Option<Duration>,
+}
+
+/// What to do about a failed attempt
+#[derive(Debug)]
+pub enum Recovery {
+    /// Fail the call with this error, unless middleware handling the error
+    /// later recovers from it
+    Fail(anyhow::Error),
+
+    /// Make another attempt after this delay
+    Retry(Duration),
+}
+
+/// Participant in operation calls. Every method defaults to passing the call
+/// on unchanged.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Before each attempt; an error fails the call without sending it
+    async fn on_request(&self, _call: &mut Call) -> Result<()> {
+        Ok(())
+    }
+
+    /// After a successful attempt
+    async fn on_response(&self, _call: &Call, _result: &mut ExecutionResult) -> Result<()> {
+        Ok(())
+    }
+
+    /// After a failed attempt
+    async fn on_error(&self, _call: &Call, error: anyhow::Error) -> Recovery {
+        Recovery::Fail(error)
+    }
+}
+
+/// Middleware run around calls, in order
+#[derive(Clone, Default)]
+pub struct Pipeline {
+    layers: Vec<Arc<dyn Middleware>>,
+}
+
+impl std::fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("layers", &self.layers.len())
+            .finish()
+    }
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `middleware` after the middleware already in the pipeline
+    pub fn with(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.push(middleware);
+        self
+    }
+
+    pub fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.layers.push(middleware);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub(crate) fn layers(&self) -> &[Arc<dyn Middleware>] {
+        &self.layers
+    }
+
+    /// Call `operation` through `adapter`
+    pub async fn execute<A: Adapter + ?Sized>(
+        &self,
+        adapter: &A,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult> {
+        self.run(adapter.protocol_type(), url, operation, args, |args| {
+            adapter.execute(url, operation, args)
+        })
+        .await
+    }
+
+    /// Call `operation` through `adapter`, returning the response as sent.
+    /// `on_response` is not called: there is no result to reshape.
+    pub async fn execute_raw<A: Adapter + ?Sized>(
+        &self,
+        adapter: &A,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RawResponse> {
+        let call = self.call(adapter.protocol_type(), url, operation, args);
+        self.attempts(call, |args| adapter.execute_raw(url, operation, args))
+            .await
+            .map(|(_, response)| response)
+    }
+
+    /// The request `adapter` would send for `operation`, as the middleware
+    /// leaves it
+    pub async fn plan<A: Adapter + ?Sized>(
+        &self,
+        adapter: &A,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        let mut call = self.call(adapter.protocol_type(), url, operation, args);
+        self.prepare(&mut call).await?;
+        with_headers(call.headers, adapter.plan(url, operation, call.args)).await
+    }
+
+    /// Run a call, with `send` making each attempt
+    pub(crate) async fn run<F, Fut>(
+        &self,
+        protocol: ProtocolType,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+        send: F,
+    ) -> Result<ExecutionResult>
+    where
+        F: Fn(HashMap<String, Value>) -> Fut,
+        Fut: Future<Output = Result<ExecutionResult>>,
+    {
+        let call = self.call(protocol, url, operation, args);
+        let (call, mut result) = self.attempts(call, send).await?;
+        for layer in self.layers.iter().rev() {
+            layer.on_response(&call, &mut result).await?;
+        }
+        Ok(result)
+    }
+
+    /// Run `send` until an attempt succeeds or the middleware gives up,
+    /// returning the successful attempt
+    pub(crate) async fn attempts<T, F, Fut>(&self, call: Call, send: F) -> Result<(Call, T)>
+    where
+        F: Fn(HashMap<String, Value>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = call;
+        loop {
+            let mut call = attempt.clone();
+            self.prepare(&mut call).await?;
+            let sent = with_headers(call.headers.clone(), send(call.args.clone()));
+            let outcome = match call.timeout {
+                Some(limit) => tokio::time::timeout(limit, sent)
+                    .await
+                    .unwrap_or_else(|_| Err(crate::retry::timed_out(limit))),
+                None => sent.await,
+            };
+            let error = match outcome {
+                Ok(value) => return Ok((call, value)),
+                Err(error) => error,
+            };
+            let mut recovery = Recovery::Fail(error);
+            for layer in self.layers.iter().rev() {
+                let Recovery::Fail(error) = recovery else {
+                    break;
+                };
+                recovery = layer.on_error(&call, error).await;
+            }
+            match recovery {
+                Recovery::Fail(error) => return Err(error),
+                Recovery::Retry(delay) => tokio::time::sleep(delay).await,
+            }
+            attempt.attempt += 1;
+        }
+    }
+
+    /// Run the `on_request` middleware for an attempt
+    pub(crate) async fn prepare(&self, call: &mut Call) -> Result<()> {
+        for layer in &self.layers {
+            layer.on_request(call).await?;
+        }
+        Ok(())
+    }
+
+    /// First attempt of a call
+    pub(crate) fn call(
+        &self,
+        protocol: ProtocolType,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Call {
+        Call {
+            url: url.to_string(),
+            operation: operation.to_string(),
+            protocol,
+            args,
+            headers: HeaderMap::new(),
+            attempt: 1,
+            timeout: None,
+        }
+    }
+}
+
+/// Add `middleware` to every call of the rest of the process
+pub fn install(middleware: Arc<dyn Middleware>) {
+    INSTALLED
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(middleware);
+}
+
+/// Remove the middleware added with [`install`], before a long-lived process
+/// runs another command
+pub fn uninstall_all() {
+    INSTALLED.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Pipeline of the middleware added with [`install`]
+pub(crate) fn installed() -> Pipeline {
+    Pipeline {
+        layers: INSTALLED.read().unwrap_or_else(|e| e.into_inner()).clone(),
+    }
+}
+
+/// Run `call` with `headers` set on the HTTP requests it sends, over the
+/// headers of enclosing pipelines
+pub(crate) async fn with_headers<F: Future>(headers: HeaderMap, call: F) -> F::Output {
+    let mut merged = self::headers().unwrap_or_default();
+    for name in headers.keys() {
+        merged.remove(name);
+    }
+    for (name, value) in &headers {
+        merged.append(name.clone(), value.clone());
+    }
+    HEADERS.scope(merged, call).await
+}
+
+/// Headers middleware set for the current call
+pub(crate) fn headers() -> Option<HeaderMap> {
+    HEADERS
+        .try_with(|headers| headers.clone())
+        .ok()
+        .filter(|headers| !headers.is_empty())
+}
+
+/// Keeps the credentials of a session or OAuth profile fresh: logs in when
+/// there is no session yet and refreshes expired OAuth tokens before calls,
+/// and does it once more when the first attempt is rejected with HTTP 401.
+/// Other profiles need nothing.
+#[derive(Debug, Clone)]
+pub struct AuthMiddleware {
+    profile: Profile,
+}
+
+impl AuthMiddleware {
+    pub fn new(profile: Profile) -> Self {
+        Self { profile }
+    }
+
+    /// Log in when there is no session yet, or refresh an expired OAuth token
+    pub async fn ensure(&self) -> Result<()> {
+        // Calls are refused offline anyway
+        if crate::cache::is_offline() {
+            return Ok(());
+        }
+        match self.profile.auth_type {
+            AuthType::Session => session::ensure_session(&self.profile).await,
+            AuthType::OAuth => oauth::ensure_token(&self.profile).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Log in again or refresh the token, whatever is stored
+    async fn renew(&self) -> Result<()> {
+        match self.profile.auth_type {
+            AuthType::Session => session::login(&self.profile).await.map(|_| ()),
+            AuthType::OAuth => {
+                let config = oauth::oauth_config(&self.profile)?;
+                match oauth::OAuthTokens::load(&config.token_key()?)? {
+                    Some(tokens) => oauth::refresh(&self.profile, &tokens).await.map(|_| ()),
+                    None => oauth::ensure_token(&self.profile).await,
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for AuthMiddleware {
+    async fn on_request(&self, _call: &mut Call) -> Result<()> {
+        self.ensure().await
+    }
+
+    async fn on_error(&self, call: &Call, error: anyhow::Error) -> Recovery {
+        let renewable = matches!(self.profile.auth_type, AuthType::Session | AuthType::OAuth);
+        if !renewable || call.attempt > 1 || crate::error::http_status_of(&error) != Some(401) {
+            return Recovery::Fail(error);
+        }
+        match self.renew().await {
+            Ok(()) => {
+                crate::explain::record(
+                    "auth",
+                    "the call was rejected with HTTP 401; credentials were renewed and the call retried",
+                );
+                Recovery::Retry(Duration::ZERO)
+            }
+            Err(renewal) => {
+                warn!("Failed to renew credentials after HTTP 401: {:#}", renewal);
+                Recovery::Fail(error)
+            }
+        }
+    }
+}
+
+/// Logs every attempt and its outcome as `tracing` debug events
+/// (`RUST_LOG=uxc_core=debug`). Header values are left out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingMiddleware;
+
+#[async_trait]
+impl Middleware for TracingMiddleware {
+    async fn on_request(&self, call: &mut Call) -> Result<()> {
+        let headers: Vec<&str> = call.headers.keys().map(|name| name.as_str()).collect();
+        debug!(
+            url = call.url,
+            protocol = call.protocol.as_str(),
+            operation = call.operation,
+            attempt = call.attempt,
+            arguments = call.args.len(),
+            headers = headers.join(","),
+            "request"
+        );
+        Ok(())
+    }
+
+    async fn on_response(&self, call: &Call, result: &mut ExecutionResult) -> Result<()> {
+        debug!(
+            operation = call.operation,
+            attempt = call.attempt,
+            status = result.metadata.status,
+            duration_ms = result.metadata.duration_ms,
+            "response"
+        );
+        Ok(())
+    }
+
+    async fn on_error(&self, call: &Call, error: anyhow::Error) -> Recovery {
+        debug!(
+            operation = call.operation,
+            attempt = call.attempt,
+            code = crate::error::code_of(&error),
+            "error: {:#}",
+            error
+        );
+        Recovery::Fail(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::ExecutionMetadata;
+    use crate::error::UxcError;
+    use crate::retry::RetryPolicy;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Adds `signed = true` and an `x-signature` header, and moves results
+    /// under `wrapped`
+    struct Sign;
+
+    #[async_trait]
+    impl Middleware for Sign {
+        async fn on_request(&self, call: &mut Call) -> Result<()> {
+            call.args.insert("signed".to_string(), json!(true));
+            call.headers
+                .insert("x-signature", format!("{}", call.attempt).parse()?);
+            Ok(())
+        }
+
+        async fn on_response(&self, _call: &Call, result: &mut ExecutionResult) -> Result<()> {
+            result.data = json!({ "wrapped": result.data.take() });
+            Ok(())
+        }
+    }
+
+    fn result(data: Value) -> ExecutionResult {
+        ExecutionResult {
+            data,
+            metadata: ExecutionMetadata {
+                duration_ms: 0,
+                operation: "op".to_string(),
+                response_headers: Vec::new(),
+                status: Some(200),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_changes_requests_and_results() {
+        let pipeline = Pipeline::new().with(Arc::new(Sign));
+        let result = pipeline
+            .run(
+                ProtocolType::OpenAPI,
+                "https://api.example.com",
+                "op",
+                HashMap::from([("id".to_string(), json!(1))]),
+                |args| async move {
+                    let signature = headers().unwrap()["x-signature"].clone();
+                    Ok(result(
+                        json!({ "args": args, "signature": signature.to_str()? }),
+                    ))
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result.data,
+            json!({ "wrapped": { "args": { "id": 1, "signed": true }, "signature": "1" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_and_timeouts_come_from_the_retry_policy() {
+        let pipeline = Pipeline::new()
+            .with(Arc::new(Sign))
+            .with(Arc::new(RetryPolicy {
+                timeout: Some(Duration::from_millis(50)),
+                retries: 2,
+                ..Default::default()
+            }));
+        let sent = AtomicU32::new(0);
+        let result = pipeline
+            .run(ProtocolType::OpenAPI, "u", "op", HashMap::new(), |_| {
+                let attempt = sent.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    match attempt {
+                        1 => Err(UxcError::Timeout("slow".to_string()).into()),
+                        2 => {
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                            unreachable!()
+                        }
+                        _ => Ok(result(json!(headers().unwrap()["x-signature"].to_str()?))),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        // The third attempt succeeded, prepared afresh
+        assert_eq!(result.data, json!({ "wrapped": "3" }));
+
+        sent.store(0, Ordering::SeqCst);
+        let err = Pipeline::new()
+            .with(Arc::new(RetryPolicy {
+                retries: 1,
+                ..Default::default()
+            }))
+            .run(ProtocolType::OpenAPI, "u", "op", HashMap::new(), |_| {
+                sent.fetch_add(1, Ordering::SeqCst);
+                async { Err(UxcError::InvalidArguments("no".to_string()).into()) }
+            })
+            .await
+            .unwrap_err();
+        // Rejections that are not temporary are not retried
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        assert_eq!(crate::error::code_of(&err), "INVALID_ARGUMENT");
+    }
+
+    #[tokio::test]
+    async fn headers_of_nested_pipelines_are_merged() {
+        let mut outer = HeaderMap::new();
+        outer.insert("x-team", "payments".parse().unwrap());
+        outer.insert("x-signature", "outer".parse().unwrap());
+        let mut inner = HeaderMap::new();
+        inner.insert("x-signature", "inner".parse().unwrap());
+        let headers = with_headers(outer, with_headers(inner, async { headers().unwrap() })).await;
+        assert_eq!(headers["x-team"], "payments");
+        assert_eq!(headers["x-signature"], "inner");
+    }
+}
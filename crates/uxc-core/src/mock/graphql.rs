@@ -0,0 +1,486 @@
+//! GraphQL mocks: queries are answered from the introspection result, with a
+//! value of the declared type for every selected field
+
+use super::{json_body, MockResponse};
+use crate::datagen::Generator;
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Nesting beyond which objects are answered with `null`
+const MAX_DEPTH: usize = 16;
+/// Items in every generated list
+const LIST_LENGTH: usize = 2;
+
+pub(super) struct GraphQLMock {
+    /// Introspection result, `{"data": {"__schema": ...}}`
+    introspection: Value,
+    types: HashMap<String, Value>,
+}
+
+impl GraphQLMock {
+    pub(super) fn new(schema: Value) -> Result<Self> {
+        let introspection = if schema.get("__schema").is_some() {
+            json!({ "data": schema })
+        } else {
+            schema
+        };
+        let types = introspection
+            .pointer("/data/__schema/types")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                UxcError::InvalidArguments("GraphQL introspection result has no types".into())
+            })?
+            .iter()
+            .filter_map(|ty| Some((ty.get("name")?.as_str()?.to_string(), ty.clone())))
+            .collect();
+        Ok(Self {
+            introspection,
+            types,
+        })
+    }
+
+    pub(super) fn respond(
+        &self,
+        method: &str,
+        query: &str,
+        body: &[u8],
+        seed: u64,
+    ) -> MockResponse {
+        let request = match method {
+            "POST" => json_body(body),
+            "GET" => Value::Object(
+                url::form_urlencoded::parse(query.as_bytes())
+                    .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+                    .collect(),
+            ),
+            _ => return MockResponse::error(405, "GraphQL requests are sent with POST or GET"),
+        };
+        let Some(query) = request.get("query").and_then(Value::as_str) else {
+            return MockResponse::error(400, "request has no query");
+        };
+        if query.contains("__schema") {
+            return MockResponse::json(200, self.introspection.clone());
+        }
+        let operation_name = request.get("operationName").and_then(Value::as_str);
+        match self.execute(query, operation_name, seed) {
+            Ok(data) => MockResponse::json(200, json!({ "data": data })),
+            Err(message) => MockResponse::json(200, json!({ "errors": [{ "message": message }] })),
+        }
+    }
+
+    fn execute(
+        &self,
+        query: &str,
+        operation_name: Option<&str>,
+        seed: u64,
+    ) -> Result<Value, String> {
+        let document = Parser::new(query).document()?;
+        let operation = document
+            .operations
+            .iter()
+            .find(|operation| {
+                operation_name.is_none() || operation.name.as_deref() == operation_name
+            })
+            .ok_or_else(|| "no operation to execute".to_string())?;
+        let root = self
+            .introspection
+            .pointer(&format!("/data/__schema/{}Type/name", operation.kind))
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("schema has no {} type", operation.kind))?;
+
+        let mut resolver = Resolver {
+            mock: self,
+            fragments: &document.fragments,
+            generator: Generator::new(seed),
+        };
+        Ok(Value::Object(resolver.object(
+            root,
+            &operation.selections,
+            0,
+        )))
+    }
+}
+
+struct Resolver<'a> {
+    mock: &'a GraphQLMock,
+    fragments: &'a HashMap<String, Fragment>,
+    generator: Generator,
+}
+
+impl Resolver<'_> {
+    fn object(
+        &mut self,
+        type_name: &str,
+        selections: &[Selection],
+        depth: usize,
+    ) -> Map<String, Value> {
+        let mut object = Map::new();
+        for selection in selections {
+            match selection {
+                Selection::Field {
+                    alias,
+                    name,
+                    selections,
+                } => {
+                    let value = if name == "__typename" {
+                        Value::String(type_name.to_string())
+                    } else {
+                        self.mock
+                            .field_type(type_name, name)
+                            .map(|field_type| self.value(&field_type, selections, depth + 1))
+                            .unwrap_or(Value::Null)
+                    };
+                    object.insert(alias.clone().unwrap_or_else(|| name.clone()), value);
+                }
+                Selection::Spread(name) => {
+                    if let Some(fragment) = self.fragments.get(name) {
+                        if self.mock.applies(&fragment.condition, type_name) {
+                            object.extend(self.object(type_name, &fragment.selections, depth));
+                        }
+                    }
+                }
+                Selection::Inline {
+                    condition,
+                    selections,
+                } => {
+                    let applies = condition
+                        .as_ref()
+                        .is_none_or(|condition| self.mock.applies(condition, type_name));
+                    if applies {
+                        object.extend(self.object(type_name, selections, depth));
+                    }
+                }
+            }
+        }
+        object
+    }
+
+    /// Value of a type reference (`{kind, name, ofType}`)
+    fn value(&mut self, type_ref: &Value, selections: &[Selection], depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        let kind = type_ref.get("kind").and_then(Value::as_str).unwrap_or("");
+        let of_type = type_ref.get("ofType").unwrap_or(&Value::Null);
+        let name = type_ref.get("name").and_then(Value::as_str).unwrap_or("");
+        match kind {
+            "NON_NULL" => self.value(of_type, selections, depth),
+            "LIST" => Value::Array(
+                (0..LIST_LENGTH)
+                    .map(|_| self.value(of_type, selections, depth + 1))
+                    .collect(),
+            ),
+            "ENUM" => self
+                .mock
+                .types
+                .get(name)
+                .and_then(|ty| ty.pointer("/enumValues/0/name"))
+                .cloned()
+                .unwrap_or(Value::Null),
+            "OBJECT" => Value::Object(self.object(name, selections, depth)),
+            "INTERFACE" | "UNION" => match self.mock.possible_types(name).first() {
+                Some(concrete) => Value::Object(self.object(concrete, selections, depth)),
+                None => Value::Null,
+            },
+            _ => {
+                let schema = match name {
+                    "Int" => json!({ "type": "integer", "minimum": 0, "maximum": 1000 }),
+                    "Float" => json!({ "type": "number", "minimum": 0, "maximum": 1000 }),
+                    "Boolean" => json!({ "type": "boolean" }),
+                    "ID" => json!({ "type": "string", "format": "uuid" }),
+                    _ => json!({ "type": "string" }),
+                };
+                self.generator.value(&schema)
+            }
+        }
+    }
+}
+
+impl GraphQLMock {
+    fn field_type(&self, type_name: &str, field: &str) -> Option<Value> {
+        self.types
+            .get(type_name)?
+            .get("fields")?
+            .as_array()?
+            .iter()
+            .find(|candidate| candidate.get("name").and_then(Value::as_str) == Some(field))?
+            .get("type")
+            .cloned()
+    }
+
+    fn possible_types(&self, abstract_type: &str) -> Vec<&str> {
+        self.types
+            .get(abstract_type)
+            .and_then(|ty| ty.get("possibleTypes"))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|ty| ty.get("name").and_then(Value::as_str))
+            .collect()
+    }
+
+    /// Whether a fragment on `condition` applies to an object of `type_name`
+    fn applies(&self, condition: &str, type_name: &str) -> bool {
+        condition == type_name || self.possible_types(condition).contains(&type_name)
+    }
+}
+
+struct Document {
+    operations: Vec<Operation>,
+    fragments: HashMap<String, Fragment>,
+}
+
+struct Operation {
+    /// `query`, `mutation` or `subscription`
+    kind: String,
+    name: Option<String>,
+    selections: Vec<Selection>,
+}
+
+struct Fragment {
+    condition: String,
+    selections: Vec<Selection>,
+}
+
+enum Selection {
+    Field {
+        alias: Option<String>,
+        name: String,
+        selections: Vec<Selection>,
+    },
+    Spread(String),
+    Inline {
+        condition: Option<String>,
+        selections: Vec<Selection>,
+    },
+}
+
+/// Just enough of a GraphQL parser to know which fields a query selects.
+/// Arguments, variables and directives are skipped.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(query: &str) -> Self {
+        Self {
+            chars: query.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn document(&mut self) -> Result<Document, String> {
+        let mut document = Document {
+            operations: Vec::new(),
+            fragments: HashMap::new(),
+        };
+        while self.peek().is_some() {
+            if self.peek() == Some('{') {
+                document.operations.push(Operation {
+                    kind: "query".to_string(),
+                    name: None,
+                    selections: self.selection_set()?,
+                });
+                continue;
+            }
+            match self.name()?.as_str() {
+                "fragment" => {
+                    let name = self.name()?;
+                    if self.name()? != "on" {
+                        return Err(format!("fragment {} has no type condition", name));
+                    }
+                    let condition = self.name()?;
+                    self.directives()?;
+                    let selections = self.selection_set()?;
+                    document.fragments.insert(
+                        name,
+                        Fragment {
+                            condition,
+                            selections,
+                        },
+                    );
+                }
+                kind @ ("query" | "mutation" | "subscription") => {
+                    let kind = kind.to_string();
+                    let name = match self.peek() {
+                        Some(c) if is_name_start(c) => Some(self.name()?),
+                        _ => None,
+                    };
+                    if self.peek() == Some('(') {
+                        self.skip_group('(', ')')?;
+                    }
+                    self.directives()?;
+                    document.operations.push(Operation {
+                        kind,
+                        name,
+                        selections: self.selection_set()?,
+                    });
+                }
+                other => return Err(format!("unexpected '{}'", other)),
+            }
+        }
+        Ok(document)
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Selection>, String> {
+        self.expect('{')?;
+        let mut selections = Vec::new();
+        while self.peek() != Some('}') {
+            if self.peek().is_none() {
+                return Err("unterminated selection set".to_string());
+            }
+            selections.push(self.selection()?);
+        }
+        self.expect('}')?;
+        Ok(selections)
+    }
+
+    fn selection(&mut self) -> Result<Selection, String> {
+        if self.peek() == Some('.') {
+            for _ in 0..3 {
+                self.expect('.')?;
+            }
+            let condition = match self.peek() {
+                Some(c) if is_name_start(c) => {
+                    let name = self.name()?;
+                    if name != "on" {
+                        self.directives()?;
+                        return Ok(Selection::Spread(name));
+                    }
+                    Some(self.name()?)
+                }
+                _ => None,
+            };
+            self.directives()?;
+            return Ok(Selection::Inline {
+                condition,
+                selections: self.selection_set()?,
+            });
+        }
+
+        let mut name = self.name()?;
+        let mut alias = None;
+        if self.peek() == Some(':') {
+            self.expect(':')?;
+            alias = Some(name);
+            name = self.name()?;
+        }
+        if self.peek() == Some('(') {
+            self.skip_group('(', ')')?;
+        }
+        self.directives()?;
+        let selections = if self.peek() == Some('{') {
+            self.selection_set()?
+        } else {
+            Vec::new()
+        };
+        Ok(Selection::Field {
+            alias,
+            name,
+            selections,
+        })
+    }
+
+    fn directives(&mut self) -> Result<(), String> {
+        while self.peek() == Some('@') {
+            self.expect('@')?;
+            self.name()?;
+            if self.peek() == Some('(') {
+                self.skip_group('(', ')')?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Skip a bracketed group, strings and nested groups included
+    fn skip_group(&mut self, open: char, close: char) -> Result<(), String> {
+        self.expect(open)?;
+        let mut level = 1;
+        while level > 0 {
+            let Some(c) = self.chars.get(self.position).copied() else {
+                return Err(format!("missing '{}'", close));
+            };
+            self.position += 1;
+            match c {
+                '"' => self.skip_string()?,
+                c if c == open => level += 1,
+                c if c == close => level -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_string(&mut self) -> Result<(), String> {
+        let block = self.chars[self.position..].starts_with(&['"', '"']);
+        if block {
+            self.position += 2;
+        }
+        loop {
+            let Some(c) = self.chars.get(self.position).copied() else {
+                return Err("unterminated string".to_string());
+            };
+            self.position += 1;
+            match c {
+                '\\' => self.position += 1,
+                '"' if !block => return Ok(()),
+                '"' if self.chars[self.position..].starts_with(&['"', '"']) => {
+                    self.position += 2;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(c) if is_name_start(c) => {}
+            Some(c) => return Err(format!("unexpected '{}'", c)),
+            None => return Err("unexpected end of query".to_string()),
+        }
+        let start = self.position;
+        while self
+            .chars
+            .get(self.position)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            self.position += 1;
+        }
+        Ok(self.chars[start..self.position].iter().collect())
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(c) => Err(format!("expected '{}' but found '{}'", expected, c)),
+            None => Err(format!("expected '{}' at end of query", expected)),
+        }
+    }
+
+    /// Next significant character, after whitespace, commas and comments
+    fn peek(&mut self) -> Option<char> {
+        while let Some(c) = self.chars.get(self.position).copied() {
+            if c == '#' {
+                while self.chars.get(self.position).is_some_and(|c| *c != '\n') {
+                    self.position += 1;
+                }
+            } else if c.is_whitespace() || c == ',' || c == '\u{feff}' {
+                self.position += 1;
+            } else {
+                return Some(c);
+            }
+        }
+        None
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
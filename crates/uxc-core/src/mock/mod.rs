@@ -0,0 +1,373 @@
+//! Mock servers (`uxc mock`)
+//!
+//! A [`Mock`] answers requests with example responses built from an
+//! endpoint's schema: an OpenAPI document, a GraphQL introspection result or
+//! an OpenRPC document. Examples the schema declares are used as they are;
+//! other values are drawn by [`crate::datagen::Generator`] from the declared
+//! types. The seed is fixed, so a request always gets the same answer.
+//!
+//! Each mock also serves its schema the way uxc discovers it (the OpenAPI
+//! document at `/openapi.json`, GraphQL introspection, `rpc.discover`), so
+//! uxc and other schema-driven clients can be pointed at the mock as if it
+//! were the real endpoint.
+
+mod graphql;
+mod openapi;
+mod openrpc;
+
+use crate::adapters::ProtocolType;
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Seed used when none is given
+pub const DEFAULT_SEED: u64 = 1;
+
+/// A fake endpoint answering from a schema
+pub struct Mock {
+    protocol: ProtocolType,
+    kind: Kind,
+    seed: u64,
+}
+
+enum Kind {
+    OpenApi(openapi::OpenApiMock),
+    GraphQL(graphql::GraphQLMock),
+    OpenRpc(openrpc::OpenRpcMock),
+}
+
+/// Response of a mock to one request
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    pub status: u16,
+    /// JSON body, `None` for an empty one
+    pub body: Option<Value>,
+}
+
+impl MockResponse {
+    fn json(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            body: Some(body),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::json(status, json!({ "error": message.into() }))
+    }
+}
+
+impl Mock {
+    /// Mock of an endpoint speaking `protocol`, with the schema its adapter
+    /// fetched
+    pub fn new(protocol: ProtocolType, schema: Value, seed: u64) -> Result<Self> {
+        let kind = match protocol {
+            ProtocolType::OpenAPI => Kind::OpenApi(openapi::OpenApiMock::new(schema)?),
+            ProtocolType::GraphQL => Kind::GraphQL(graphql::GraphQLMock::new(schema)?),
+            ProtocolType::JsonRpc => Kind::OpenRpc(openrpc::OpenRpcMock::new(schema)?),
+            ProtocolType::GRpc | ProtocolType::Mcp | ProtocolType::Plugin => {
+                return Err(UxcError::UnsupportedProtocol(format!(
+                    "uxc mock serves OpenAPI, GraphQL and OpenRPC schemas, not {}",
+                    protocol.as_str()
+                ))
+                .into())
+            }
+        };
+        Ok(Self {
+            protocol,
+            kind,
+            seed,
+        })
+    }
+
+    /// Mock of the schema document at `path` (JSON or YAML), whose protocol is
+    /// recognised from its content
+    pub fn load(path: &Path, seed: u64) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            UxcError::InvalidArguments(format!("Failed to read schema {}: {}", path.display(), e))
+        })?;
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let document: Value = if is_json {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+        }
+        .map_err(|e| {
+            UxcError::InvalidArguments(format!("Invalid schema {}: {}", path.display(), e))
+        })?;
+
+        let protocol = if document.get("openapi").is_some() || document.get("swagger").is_some() {
+            ProtocolType::OpenAPI
+        } else if document.get("openrpc").is_some() {
+            ProtocolType::JsonRpc
+        } else if document.pointer("/data/__schema").is_some() || document.get("__schema").is_some()
+        {
+            ProtocolType::GraphQL
+        } else {
+            return Err(UxcError::InvalidArguments(format!(
+                "{} is not an OpenAPI or OpenRPC document or a GraphQL introspection result",
+                path.display()
+            ))
+            .into());
+        };
+        Self::new(protocol, document, seed)
+    }
+
+    pub fn protocol(&self) -> ProtocolType {
+        self.protocol
+    }
+
+    /// Answer a request. `path` may carry a query string.
+    pub fn respond(&self, method: &str, path: &str, body: &[u8]) -> MockResponse {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let method = method.to_ascii_uppercase();
+        match &self.kind {
+            Kind::OpenApi(mock) => mock.respond(&method, path, self.seed),
+            Kind::GraphQL(mock) => mock.respond(&method, query, body, self.seed),
+            Kind::OpenRpc(mock) => mock.respond(&method, path, body, self.seed),
+        }
+    }
+}
+
+/// Serve `mock` on `addr` until Ctrl-C. `ready` is called with the bound
+/// address once the server accepts connections; the number of requests
+/// served is returned.
+pub async fn serve(mock: Mock, addr: SocketAddr, ready: impl FnOnce(SocketAddr)) -> Result<u64> {
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+
+    let mock = Arc::new(mock);
+    let served = Arc::new(AtomicU64::new(0));
+    let make_service = {
+        let served = served.clone();
+        make_service_fn(move |_| {
+            let (mock, served) = (mock.clone(), served.clone());
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
+                    let (mock, served) = (mock.clone(), served.clone());
+                    async move {
+                        let method = request.method().to_string();
+                        let path = request
+                            .uri()
+                            .path_and_query()
+                            .map(|path| path.as_str().to_string())
+                            .unwrap_or_else(|| "/".to_string());
+                        let body = hyper::body::to_bytes(request.into_body()).await?;
+                        let response = mock.respond(&method, &path, &body);
+                        served.fetch_add(1, Ordering::Relaxed);
+                        tracing::info!("{} {} -> {}", method, path, response.status);
+
+                        let builder = Response::builder().status(response.status);
+                        let response = match response.body {
+                            Some(body) => builder
+                                .header("content-type", "application/json")
+                                .body(Body::from(body.to_string())),
+                            None => builder.body(Body::empty()),
+                        };
+                        Ok::<_, hyper::Error>(response.unwrap_or_default())
+                    }
+                }))
+            }
+        })
+    };
+
+    let server = Server::try_bind(&addr)
+        .map_err(|e| UxcError::InvalidArguments(format!("Cannot listen on {}: {}", addr, e)))?
+        .serve(make_service);
+    ready(server.local_addr());
+    server
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(served.load(Ordering::Relaxed))
+}
+
+/// JSON body of a request, `Null` when it is empty or not JSON
+fn json_body(body: &[u8]) -> Value {
+    serde_json::from_slice(body).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn respond(mock: &Mock, method: &str, path: &str, body: Value) -> MockResponse {
+        mock.respond(method, path, body.to_string().as_bytes())
+    }
+
+    #[test]
+    fn openapi_routes_answer_with_examples_or_generated_values() {
+        let document = json!({
+            "openapi": "3.0.0",
+            "servers": [{ "url": "https://api.example.com/v1" }],
+            "paths": {
+                "/pets": {
+                    "get": { "responses": { "200": { "content": { "application/json": {
+                        "example": [{ "id": 1, "name": "Rex" }]
+                    } } } } },
+                    "post": { "responses": { "201": { "content": { "application/json": {
+                        "schema": { "$ref": "#/components/schemas/Pet" }
+                    } } } } }
+                },
+                "/pets/{id}": {
+                    "delete": { "responses": { "204": { "description": "gone" } } }
+                }
+            },
+            "components": { "schemas": { "Pet": {
+                "type": "object",
+                "required": ["id", "name"],
+                "properties": {
+                    "id": { "type": "integer" },
+                    "name": { "type": "string", "example": "Tom" }
+                }
+            } } }
+        });
+        let mock = Mock::new(ProtocolType::OpenAPI, document, DEFAULT_SEED).unwrap();
+
+        let listed = respond(&mock, "get", "/v1/pets?limit=2", Value::Null);
+        assert_eq!(listed.status, 200);
+        assert_eq!(listed.body, Some(json!([{ "id": 1, "name": "Rex" }])));
+
+        let created = respond(&mock, "POST", "/pets", json!({ "name": "x" }));
+        assert_eq!(created.status, 201);
+        let pet = created.body.unwrap();
+        assert!(pet["id"].is_i64());
+        assert_eq!(pet["name"], "Tom");
+        assert_eq!(
+            respond(&mock, "POST", "/pets", Value::Null).body,
+            Some(pet),
+            "the same request gets the same answer"
+        );
+
+        let deleted = respond(&mock, "DELETE", "/pets/42", Value::Null);
+        assert_eq!((deleted.status, deleted.body), (204, None));
+        assert_eq!(respond(&mock, "PUT", "/pets", Value::Null).status, 405);
+        assert_eq!(respond(&mock, "GET", "/owners", Value::Null).status, 404);
+        assert_eq!(
+            respond(&mock, "GET", "/openapi.json", Value::Null)
+                .body
+                .unwrap()["openapi"],
+            "3.0.0"
+        );
+    }
+
+    #[test]
+    fn graphql_queries_get_values_for_the_selected_fields() {
+        let introspection = json!({ "data": { "__schema": {
+            "queryType": { "name": "Query" },
+            "types": [
+                { "kind": "OBJECT", "name": "Query", "fields": [
+                    { "name": "pets", "args": [], "type": { "kind": "NON_NULL", "name": null,
+                        "ofType": { "kind": "LIST", "name": null,
+                            "ofType": { "kind": "OBJECT", "name": "Pet", "ofType": null } } } }
+                ] },
+                { "kind": "OBJECT", "name": "Pet", "fields": [
+                    { "name": "id", "args": [], "type": { "kind": "SCALAR", "name": "ID" } },
+                    { "name": "age", "args": [], "type": { "kind": "SCALAR", "name": "Int" } },
+                    { "name": "kind", "args": [], "type": { "kind": "ENUM", "name": "Kind" } }
+                ] },
+                { "kind": "ENUM", "name": "Kind", "enumValues": [{ "name": "DOG" }, { "name": "CAT" }] }
+            ]
+        } } });
+        let mock = Mock::new(ProtocolType::GraphQL, introspection, DEFAULT_SEED).unwrap();
+
+        let query = r#"query Pets($n: Int) {
+            pets(first: $n, where: { name: "a)b" }) { key: id ...Details __typename }
+        }
+        fragment Details on Pet { age kind @include(if: true) }"#;
+        let response = respond(&mock, "POST", "/graphql", json!({ "query": query }));
+        let pets = response.body.unwrap()["data"]["pets"].clone();
+        let pets = pets.as_array().unwrap();
+        assert_eq!(pets.len(), 2);
+        assert!(pets[0]["key"].is_string());
+        assert!(pets[0]["age"].is_i64());
+        assert_eq!(pets[0]["kind"], "DOG");
+        assert_eq!(pets[0]["__typename"], "Pet");
+
+        let schema = respond(
+            &mock,
+            "POST",
+            "/",
+            json!({ "query": "{ __schema { types { name } } }" }),
+        );
+        assert!(schema
+            .body
+            .unwrap()
+            .pointer("/data/__schema/types")
+            .is_some());
+
+        let broken = respond(&mock, "POST", "/", json!({ "query": "{ pets { id " }));
+        assert!(broken.body.unwrap()["errors"][0]["message"].is_string());
+    }
+
+    #[test]
+    fn openrpc_methods_answer_calls_and_batches() {
+        let document = json!({
+            "openrpc": "1.2.6",
+            "methods": [
+                { "name": "add", "params": [], "result": { "name": "sum", "schema": { "type": "integer" } },
+                  "examples": [{ "name": "one", "params": [], "result": { "name": "sum", "value": 3 } }] },
+                { "name": "pet", "params": [], "result": { "name": "pet",
+                  "schema": { "$ref": "#/components/schemas/Pet" } } }
+            ],
+            "components": { "schemas": { "Pet": {
+                "type": "object", "required": ["name"], "properties": { "name": { "type": "string" } }
+            } } }
+        });
+        let mock = Mock::new(ProtocolType::JsonRpc, document, DEFAULT_SEED).unwrap();
+
+        let single = respond(
+            &mock,
+            "POST",
+            "/",
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "add" }),
+        );
+        assert_eq!(single.body.unwrap()["result"], 3);
+
+        let batch = respond(
+            &mock,
+            "POST",
+            "/",
+            json!([
+                { "jsonrpc": "2.0", "id": 1, "method": "pet" },
+                { "jsonrpc": "2.0", "method": "add" },
+                { "jsonrpc": "2.0", "id": 2, "method": "missing" }
+            ]),
+        );
+        let answers = batch.body.unwrap();
+        assert_eq!(answers.as_array().unwrap().len(), 2);
+        assert!(answers[0]["result"]["name"].is_string());
+        assert_eq!(answers[1]["error"]["code"], -32601);
+
+        let discovered = respond(
+            &mock,
+            "POST",
+            "/",
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "rpc.discover" }),
+        );
+        assert_eq!(discovered.body.unwrap()["result"]["openrpc"], "1.2.6");
+    }
+
+    #[test]
+    fn schema_files_are_recognised_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.yaml");
+        std::fs::write(&path, "openrpc: 1.2.6\nmethods: []\n").unwrap();
+        assert_eq!(
+            Mock::load(&path, DEFAULT_SEED).unwrap().protocol(),
+            ProtocolType::JsonRpc
+        );
+
+        std::fs::write(&path, "name: not a schema\n").unwrap();
+        assert!(Mock::load(&path, DEFAULT_SEED).is_err());
+        assert!(Mock::new(ProtocolType::Mcp, json!({}), DEFAULT_SEED).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! OpenAPI mocks: one route per path and method of the document
+
+use super::MockResponse;
+use crate::datagen::Generator;
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::Value;
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub(super) struct OpenApiMock {
+    document: Value,
+    /// Path of the first server URL, which requests may be prefixed with
+    base_path: String,
+    routes: Vec<Route>,
+}
+
+struct Route {
+    method: String,
+    segments: Vec<String>,
+    status: u16,
+    response: Option<Example>,
+}
+
+/// Body of a response: a declared example or a schema to generate from
+enum Example {
+    Value(Value),
+    Schema(Value),
+}
+
+impl OpenApiMock {
+    pub(super) fn new(document: Value) -> Result<Self> {
+        let paths = document
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(|| UxcError::InvalidArguments("OpenAPI document has no paths".into()))?;
+        let mut routes = Vec::new();
+        for (path, item) in paths {
+            for method in METHODS {
+                let Some(operation) = item.get(*method) else {
+                    continue;
+                };
+                let (status, response) = success_response(operation);
+                routes.push(Route {
+                    method: method.to_ascii_uppercase(),
+                    segments: segments(path),
+                    status,
+                    response: response.and_then(example_of),
+                });
+            }
+        }
+        let base_path = document
+            .pointer("/servers/0/url")
+            .and_then(Value::as_str)
+            .map(server_path)
+            .or_else(|| document.get("basePath").and_then(Value::as_str))
+            .unwrap_or("")
+            .trim_end_matches('/')
+            .to_string();
+        Ok(Self {
+            document,
+            base_path,
+            routes,
+        })
+    }
+
+    pub(super) fn respond(&self, method: &str, path: &str, seed: u64) -> MockResponse {
+        if method == "GET" && path == "/openapi.json" {
+            return MockResponse::json(200, self.document.clone());
+        }
+        let path = match path.strip_prefix(self.base_path.as_str()) {
+            Some(rest) if !self.base_path.is_empty() && rest.starts_with('/') => rest,
+            _ => path,
+        };
+        let requested = segments(path);
+        let matching: Vec<&Route> = self
+            .routes
+            .iter()
+            .filter(|route| matches(&route.segments, &requested))
+            .collect();
+        let Some(route) = matching.iter().find(|route| route.method == method) else {
+            return if matching.is_empty() {
+                MockResponse::error(404, format!("no operation for {} {}", method, path))
+            } else {
+                MockResponse::error(405, format!("{} is not allowed on {}", method, path))
+            };
+        };
+
+        let body = route.response.as_ref().map(|example| match example {
+            Example::Value(value) => value.clone(),
+            Example::Schema(schema) => Generator::new(seed)
+                .with_document(self.document.clone())
+                .with_examples()
+                .value(schema),
+        });
+        MockResponse {
+            status: route.status,
+            body,
+        }
+    }
+}
+
+/// Lowest 2xx response of an operation, else `default` as a 200
+fn success_response(operation: &Value) -> (u16, Option<&Value>) {
+    let Some(responses) = operation.get("responses").and_then(Value::as_object) else {
+        return (200, None);
+    };
+    let success = responses
+        .iter()
+        .filter_map(|(code, response)| Some((code.parse::<u16>().ok()?, response)))
+        .filter(|(code, _)| (200..300).contains(code))
+        .min_by_key(|(code, _)| *code);
+    match success {
+        Some((code, response)) => (code, Some(response)),
+        None => (200, responses.get("default")),
+    }
+}
+
+/// Example body of a response, OpenAPI 3 (`content`) or Swagger 2 (`schema`)
+fn example_of(response: &Value) -> Option<Example> {
+    if let Some(content) = response.get("content").and_then(Value::as_object) {
+        let media = content
+            .get("application/json")
+            .or_else(|| {
+                content
+                    .iter()
+                    .find(|(media_type, _)| media_type.contains("json"))
+                    .map(|(_, media)| media)
+            })
+            .or_else(|| content.values().next())?;
+        if let Some(example) = media.get("example") {
+            return Some(Example::Value(example.clone()));
+        }
+        if let Some(first) = media
+            .get("examples")
+            .and_then(Value::as_object)
+            .and_then(|examples| examples.values().next())
+        {
+            if let Some(value) = first.get("value") {
+                return Some(Example::Value(value.clone()));
+            }
+        }
+        return media.get("schema").cloned().map(Example::Schema);
+    }
+    if let Some(example) = response.pointer("/examples/application~1json") {
+        return Some(Example::Value(example.clone()));
+    }
+    response.get("schema").cloned().map(Example::Schema)
+}
+
+/// Path part of a server URL such as `https://api.example.com/v3`
+fn server_path(url: &str) -> &str {
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |index| &rest[index..]),
+        None => url,
+    }
+}
+
+fn segments(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Whether request segments fit a template such as `pets/{id}`
+fn matches(template: &[String], requested: &[String]) -> bool {
+    template.len() == requested.len()
+        && template
+            .iter()
+            .zip(requested)
+            .all(|(expected, actual)| expected.starts_with('{') || expected == actual)
+}
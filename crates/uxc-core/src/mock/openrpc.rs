@@ -0,0 +1,93 @@
+//! OpenRPC mocks: JSON-RPC 2.0 over HTTP POST, single calls and batches
+
+use super::{json_body, MockResponse};
+use crate::datagen::Generator;
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::{json, Value};
+
+pub(super) struct OpenRpcMock {
+    document: Value,
+}
+
+impl OpenRpcMock {
+    pub(super) fn new(document: Value) -> Result<Self> {
+        if document.get("methods").and_then(Value::as_array).is_none() {
+            return Err(
+                UxcError::InvalidArguments("OpenRPC document has no methods".into()).into(),
+            );
+        }
+        Ok(Self { document })
+    }
+
+    pub(super) fn respond(&self, method: &str, path: &str, body: &[u8], seed: u64) -> MockResponse {
+        if method == "GET" && path.ends_with("openrpc.json") {
+            return MockResponse::json(200, self.document.clone());
+        }
+        if method != "POST" {
+            return MockResponse::error(405, "JSON-RPC requests are sent with POST");
+        }
+        match json_body(body) {
+            Value::Array(calls) => {
+                let answers: Vec<Value> = calls
+                    .iter()
+                    .filter_map(|call| self.answer(call, seed))
+                    .collect();
+                if answers.is_empty() {
+                    MockResponse {
+                        status: 204,
+                        body: None,
+                    }
+                } else {
+                    MockResponse::json(200, Value::Array(answers))
+                }
+            }
+            call => match self.answer(&call, seed) {
+                Some(answer) => MockResponse::json(200, answer),
+                None => MockResponse {
+                    status: 204,
+                    body: None,
+                },
+            },
+        }
+    }
+
+    /// Response object for one call, `None` for a notification
+    fn answer(&self, call: &Value, seed: u64) -> Option<Value> {
+        let Some(name) = call.get("method").and_then(Value::as_str) else {
+            return Some(error(Value::Null, -32600, "Invalid Request"));
+        };
+        let id = call.get("id")?.clone();
+        if name == "rpc.discover" {
+            return Some(json!({ "jsonrpc": "2.0", "id": id, "result": self.document }));
+        }
+        let Some(method) = self
+            .document
+            .get("methods")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|method| method.get("name").and_then(Value::as_str) == Some(name))
+        else {
+            return Some(error(id, -32601, "Method not found"));
+        };
+
+        let example = method
+            .pointer("/examples/0/result/value")
+            .cloned()
+            .or_else(|| {
+                let schema = method.pointer("/result/schema")?;
+                Some(
+                    Generator::new(seed)
+                        .with_document(self.document.clone())
+                        .with_examples()
+                        .value(schema),
+                )
+            });
+        Some(json!({ "jsonrpc": "2.0", "id": id, "result": example.unwrap_or(Value::Null) }))
+    }
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
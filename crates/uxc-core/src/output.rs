@@ -0,0 +1,710 @@
+//! Output formatting - deterministic JSON envelope
+
+use crate::error::{ErrorDetail, Result as UxcResult, UxcError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static UTC_TIMESTAMPS: AtomicBool = AtomicBool::new(false);
+
+/// Standard UXC output envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputEnvelope {
+    /// Indicates success or failure
+    pub ok: bool,
+
+    /// Output kind (operation_list, operation_detail, host_help, call_result, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    /// Protocol type (present on success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+
+    /// Endpoint URL (present on success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Operation name (present when applicable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+
+    /// Payload data (present on success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+
+    /// Error information (present on failure)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorInfo>,
+
+    /// Suggested follow-up commands (may be empty)
+    #[serde(default)]
+    pub next: Vec<NextAction>,
+
+    /// Metadata
+    pub meta: Metadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorInfo {
+    /// Machine-readable error code
+    pub code: String,
+
+    /// Human-readable error message
+    pub message: String,
+
+    /// Whether repeating the same call may succeed (timeouts, throttling, unavailability)
+    #[serde(default)]
+    pub retriable: bool,
+
+    /// Protocol-level detail: HTTP status and body, gRPC status, GraphQL
+    /// errors or JSON-RPC error object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<ErrorDetail>,
+}
+
+/// A suggested follow-up command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NextAction {
+    /// Full command line to run
+    pub command: String,
+
+    /// Why the command is suggested
+    pub description: String,
+}
+
+impl NextAction {
+    pub fn new(command: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            description: description.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Envelope schema version
+    pub version: String,
+
+    /// Execution duration in milliseconds when applicable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+
+    /// Response headers selected with `--include-headers` (lowercase names)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+
+    /// Decisions recorded with `--explain`, in the order they were made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<Vec<crate::explain::Decision>>,
+
+    /// Differences from the previous result under `--watch --changes-only`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Vec<crate::diff::Change>>,
+
+    /// Pages fetched under `--paginate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<u64>,
+}
+
+impl OutputEnvelope {
+    /// Create a success response
+    pub fn success(
+        kind: &str,
+        protocol: &str,
+        endpoint: &str,
+        operation: Option<&str>,
+        data: Value,
+        duration_ms: Option<u64>,
+    ) -> Self {
+        Self {
+            ok: true,
+            kind: Some(kind.to_string()),
+            protocol: Some(protocol.to_string()),
+            endpoint: Some(endpoint.to_string()),
+            operation: operation.map(ToString::to_string),
+            data: Some(data),
+            error: None,
+            next: Vec::new(),
+            meta: Metadata {
+                version: "v1".to_string(),
+                duration_ms,
+                headers: None,
+                explain: None,
+                changes: None,
+                pages: None,
+            },
+        }
+    }
+
+    /// Create an error response. Registered secrets in `message` are masked.
+    pub fn error(code: &str, message: &str) -> Self {
+        Self {
+            ok: false,
+            kind: None,
+            protocol: None,
+            endpoint: None,
+            operation: None,
+            data: None,
+            error: Some(ErrorInfo {
+                code: code.to_string(),
+                message: crate::masking::mask(message),
+                retriable: false,
+                detail: None,
+            }),
+            next: Vec::new(),
+            meta: Metadata {
+                version: "v1".to_string(),
+                duration_ms: None,
+                headers: None,
+                explain: None,
+                changes: None,
+                pages: None,
+            },
+        }
+    }
+
+    /// Attach protocol detail and the retriable flag to an error response.
+    /// Registered secrets in the detail are masked.
+    pub fn with_error_detail(mut self, detail: Option<ErrorDetail>, retriable: bool) -> Self {
+        if let Some(error) = self.error.as_mut() {
+            error.retriable = retriable;
+            error.detail = detail.and_then(|detail| {
+                let json = serde_json::to_string(&detail).ok()?;
+                serde_json::from_str(&crate::masking::mask(&json)).ok()
+            });
+        }
+        self
+    }
+
+    /// Attach suggested follow-up commands
+    pub fn with_next(mut self, next: Vec<NextAction>) -> Self {
+        self.next = next;
+        self
+    }
+
+    /// Convert to JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+// Text-mode formatting shared by all envelope kinds
+
+/// Show timestamps in UTC instead of local time (`--utc`)
+pub fn set_utc_timestamps(utc: bool) {
+    UTC_TIMESTAMPS.store(utc, Ordering::Relaxed);
+}
+
+/// Duration for humans: "850 ms", "1.2 s", "2m 5s", "1h 3m"
+pub fn format_duration(duration_ms: u64) -> String {
+    const MINUTE: u64 = 60_000;
+    const HOUR: u64 = 60 * MINUTE;
+
+    if duration_ms < 1000 {
+        format!("{} ms", duration_ms)
+    } else if duration_ms < MINUTE {
+        format!("{:.1} s", duration_ms as f64 / 1000.0)
+    } else if duration_ms < HOUR {
+        format!("{}m {}s", duration_ms / MINUTE, duration_ms % MINUTE / 1000)
+    } else {
+        format!("{}h {}m", duration_ms / HOUR, duration_ms % HOUR / MINUTE)
+    }
+}
+
+/// Byte count for humans: "512 B", "3.4 MB" (binary units)
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Unix timestamp (seconds) in local time, or UTC after `set_utc_timestamps(true)`
+pub fn format_timestamp(unix_secs: u64) -> String {
+    let Some(utc) = i64::try_from(unix_secs)
+        .ok()
+        .and_then(|secs| chrono::DateTime::<chrono::Utc>::from_timestamp(secs, 0))
+    else {
+        return unix_secs.to_string();
+    };
+    if UTC_TIMESTAMPS.load(Ordering::Relaxed) {
+        utc.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+    } else {
+        utc.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S %:z")
+            .to_string()
+    }
+}
+
+/// A `--template` given inline or as `@path`, rendered with the envelope fields
+/// (`ok`, `kind`, `data`, `error`, `meta`, ...) as variables.
+///
+/// Besides the minijinja builtins (`tojson`, `join`, `length`, ...), the
+/// `duration`, `size` and `timestamp` filters apply the text-mode formatting.
+#[derive(Debug, Clone)]
+pub struct OutputTemplate {
+    source: String,
+}
+
+impl OutputTemplate {
+    /// Load and compile a template, so syntax errors surface before any call
+    pub fn parse(spec: &str) -> UxcResult<Self> {
+        let source = match spec.strip_prefix('@') {
+            Some(path) => std::fs::read_to_string(path).map_err(|e| {
+                UxcError::InvalidArguments(format!("Failed to read template {}: {}", path, e))
+            })?,
+            None => spec.to_string(),
+        };
+        template_environment()
+            .template_from_str(&source)
+            .map_err(|e| UxcError::InvalidArguments(format!("Invalid --template: {:#}", e)))?;
+        Ok(Self { source })
+    }
+
+    /// Render the template for `envelope`
+    pub fn render(&self, envelope: &OutputEnvelope) -> UxcResult<String> {
+        template_environment()
+            .render_str(&self.source, envelope)
+            .map_err(|e| UxcError::InvalidArguments(format!("--template failed: {:#}", e)))
+    }
+}
+
+fn template_environment() -> minijinja::Environment<'static> {
+    let mut env = minijinja::Environment::new();
+    env.add_filter("duration", format_duration);
+    env.add_filter("size", format_size);
+    env.add_filter("timestamp", format_timestamp);
+    env
+}
+
+/// Render envelope data as CSV for `--format csv`.
+///
+/// Rows come from an array, from the only array field of an object (such as
+/// `{"items": [...], "total": 3}`), or from a lone object. Nested objects are
+/// flattened into dotted columns (`address.city`) and arrays are written as
+/// JSON. Columns appear in the order their keys are first seen, or in the
+/// order of `columns` (the keys of a `--query` multi-select hash).
+pub fn to_csv(data: &Value, columns: Option<&[String]>) -> UxcResult<String> {
+    let rows: Vec<Vec<(String, String)>> = csv_rows(data)?
+        .iter()
+        .map(|row| {
+            let mut cells = Vec::new();
+            flatten_csv_value("", row, &mut cells);
+            cells
+        })
+        .collect();
+
+    let mut headers: Vec<String> = Vec::new();
+    for (header, _) in rows.iter().flatten() {
+        if !headers.contains(header) {
+            headers.push(header.clone());
+        }
+    }
+    if let Some(columns) = columns {
+        let rank = |header: &String| {
+            columns.iter().position(|column| {
+                header == column
+                    || header
+                        .strip_prefix(column.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+        };
+        headers.sort_by_key(|header| rank(header).unwrap_or(columns.len()));
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let csv_error = |e: csv::Error| UxcError::GenericError(e.into());
+    if !headers.is_empty() {
+        writer.write_record(&headers).map_err(csv_error)?;
+    }
+    for row in &rows {
+        let record = headers.iter().map(|header| {
+            row.iter()
+                .find(|(name, _)| name == header)
+                .map_or("", |(_, cell)| cell.as_str())
+        });
+        writer.write_record(record).map_err(csv_error)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| UxcError::GenericError(anyhow::anyhow!("{}", e)))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Response headers to copy into `meta.headers`
+///
+/// Patterns are case-insensitive header names where `*` matches any run of
+/// characters (`etag`, `x-ratelimit-*`, `*`). They come from
+/// `--include-headers` or from the `output.include_headers` setting:
+///
+/// ```toml
+/// [output]
+/// include_headers = ["etag", "location", "x-request-id"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderFilter {
+    patterns: Vec<String>,
+}
+
+impl HeaderFilter {
+    /// Parse a comma-separated pattern list. Returns `None` when empty.
+    pub fn parse(spec: &str) -> Option<Self> {
+        Self::from_patterns(spec.split(','))
+    }
+
+    fn from_patterns<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let patterns: Vec<String> = patterns
+            .into_iter()
+            .map(|pattern| pattern.trim().to_ascii_lowercase())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+        (!patterns.is_empty()).then_some(Self { patterns })
+    }
+
+    /// Patterns of the `output.include_headers` setting, if any
+    pub fn load_from_file() -> anyhow::Result<Option<Self>> {
+        let patterns = crate::config::get("output.include_headers")?.as_strings();
+        Ok(Self::from_patterns(patterns.iter().map(String::as_str)))
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| crate::allowlist::glob_match(pattern, &name))
+    }
+
+    /// Matching headers by lowercase name; repeated headers are joined with `, `
+    pub fn select(&self, headers: &[(String, String)]) -> BTreeMap<String, String> {
+        let mut selected: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in headers.iter().filter(|(name, _)| self.matches(name)) {
+            selected
+                .entry(name.to_ascii_lowercase())
+                .and_modify(|joined| {
+                    joined.push_str(", ");
+                    joined.push_str(value);
+                })
+                .or_insert_with(|| value.clone());
+        }
+        selected
+    }
+}
+
+/// Destination of `--output`
+///
+/// A path naming a directory (an existing one, or any path ending in `/`)
+/// receives numbered files (`000001.json`, `000002.json`, ...), continuing
+/// after the highest number already there, so every result of a stream or of
+/// repeated runs lands in its own file. Any other path is written as a single
+/// file. Missing parent directories are created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTarget {
+    path: PathBuf,
+    directory: bool,
+}
+
+impl OutputTarget {
+    pub fn new(path: &str) -> Self {
+        let directory = path.ends_with('/')
+            || path.ends_with(std::path::MAIN_SEPARATOR)
+            || Path::new(path).is_dir();
+        Self {
+            path: PathBuf::from(path),
+            directory,
+        }
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.directory
+    }
+
+    /// Write `bytes` and return the path written; `extension` names numbered files
+    pub fn write(&self, bytes: &[u8], extension: &str) -> UxcResult<PathBuf> {
+        let path = if self.directory {
+            std::fs::create_dir_all(&self.path)?;
+            self.path
+                .join(format!("{:06}.{}", self.last_number()? + 1, extension))
+        } else {
+            if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            self.path.clone()
+        };
+        std::fs::write(&path, bytes).map_err(|e| {
+            UxcError::GenericError(anyhow::anyhow!(
+                "Failed to write output to {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(path)
+    }
+
+    /// Highest `NNNNNN.<ext>` number in the directory, 0 when there is none
+    fn last_number(&self) -> UxcResult<u64> {
+        let mut last = 0;
+        for entry in std::fs::read_dir(&self.path)? {
+            let name = entry?.file_name();
+            let number = name
+                .to_str()
+                .and_then(|name| name.split_once('.'))
+                .and_then(|(stem, _)| stem.parse::<u64>().ok());
+            last = last.max(number.unwrap_or(0));
+        }
+        Ok(last)
+    }
+}
+
+/// File extension for a response content type (`image/png` -> `png`)
+pub fn extension_for_content_type(content_type: Option<&str>) -> String {
+    let subtype = content_type
+        .and_then(|value| value.split(';').next())
+        .and_then(|mime| mime.trim().split_once('/'))
+        .map(|(_, subtype)| subtype.rsplit('+').next().unwrap_or(subtype))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match subtype.as_str() {
+        "plain" => "txt".to_string(),
+        "jpeg" => "jpg".to_string(),
+        "" | "octet-stream" => "bin".to_string(),
+        other if other.chars().all(|c| c.is_ascii_alphanumeric()) => other.to_string(),
+        _ => "bin".to_string(),
+    }
+}
+
+fn csv_rows(data: &Value) -> UxcResult<Vec<Value>> {
+    match data {
+        Value::Null => Ok(Vec::new()),
+        Value::Array(items) => Ok(items.clone()),
+        Value::Object(fields) => {
+            let mut arrays = fields.values().filter_map(Value::as_array);
+            match (arrays.next(), arrays.next()) {
+                (Some(items), None) => Ok(items.clone()),
+                _ => Ok(vec![data.clone()]),
+            }
+        }
+        _ => Err(UxcError::InvalidArguments(
+            "CSV output needs an array or object; select one with --query".to_string(),
+        )),
+    }
+}
+
+fn flatten_csv_value(prefix: &str, value: &Value, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, field) in fields {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_csv_value(&name, field, cells);
+            }
+        }
+        _ => {
+            let name = if prefix.is_empty() { "value" } else { prefix };
+            let cell = match value {
+                Value::Null => String::new(),
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            cells.push((name.to_string(), cell));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_filter() {
+        let filter = HeaderFilter::parse("ETag, x-ratelimit-*").unwrap();
+        let headers = vec![
+            ("etag".to_string(), "\"v1\"".to_string()),
+            ("x-ratelimit-limit".to_string(), "60".to_string()),
+            ("x-ratelimit-remaining".to_string(), "59".to_string()),
+            ("set-cookie".to_string(), "a=1".to_string()),
+        ];
+        let selected = filter.select(&headers);
+        assert_eq!(selected.len(), 3);
+        assert_eq!(selected["etag"], "\"v1\"");
+        assert!(!selected.contains_key("set-cookie"));
+        assert!(HeaderFilter::parse(" , ").is_none());
+    }
+
+    #[test]
+    fn test_output_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nested/result.json");
+        let target = OutputTarget::new(file.to_str().unwrap());
+        assert!(!target.is_directory());
+        assert_eq!(target.write(b"{}", "json").unwrap(), file);
+
+        let stream = format!("{}/stream/", dir.path().display());
+        let target = OutputTarget::new(&stream);
+        assert!(target.is_directory());
+        let first = target.write(b"1", "json").unwrap();
+        let second = target.write(b"2", "json").unwrap();
+        assert!(first.ends_with("000001.json"));
+        assert!(second.ends_with("000002.json"));
+        assert!(OutputTarget::new(dir.path().join("stream").to_str().unwrap()).is_directory());
+
+        assert_eq!(extension_for_content_type(Some("image/png")), "png");
+        assert_eq!(
+            extension_for_content_type(Some("application/vnd.api+json; charset=utf-8")),
+            "json"
+        );
+        assert_eq!(extension_for_content_type(None), "bin");
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let data = serde_json::json!({
+            "total": 2,
+            "items": [
+                {"name": "ann", "address": {"city": "Oslo"}, "tags": ["a", "b"]},
+                {"name": "bob, jr", "age": 25}
+            ]
+        });
+        assert_eq!(
+            to_csv(&data, None).unwrap(),
+            "address.city,name,tags,age\n\
+             Oslo,ann,\"[\"\"a\"\",\"\"b\"\"]\",\n\
+             ,\"bob, jr\",,25\n"
+        );
+
+        let columns = vec!["name".to_string(), "address".to_string()];
+        let selected = serde_json::json!([{"address": {"city": "Oslo"}, "name": "ann"}]);
+        assert_eq!(
+            to_csv(&selected, Some(&columns)).unwrap(),
+            "name,address.city\nann,Oslo\n"
+        );
+        assert_eq!(
+            to_csv(&serde_json::json!(["x", 1]), None).unwrap(),
+            "value\nx\n1\n"
+        );
+        assert!(to_csv(&serde_json::json!(3), None).is_err());
+    }
+
+    #[test]
+    fn test_success_envelope() {
+        let envelope = OutputEnvelope::success(
+            "call_result",
+            "openapi",
+            "https://api.example.com",
+            Some("get:/users"),
+            serde_json::json!({"users": []}),
+            Some(128),
+        );
+
+        assert!(envelope.ok);
+        assert_eq!(envelope.kind, Some("call_result".to_string()));
+        assert_eq!(envelope.protocol, Some("openapi".to_string()));
+        assert_eq!(envelope.operation, Some("get:/users".to_string()));
+    }
+
+    #[test]
+    fn test_error_envelope() {
+        let envelope = OutputEnvelope::error("INVALID_ARGUMENT", "Field 'id' must be an integer");
+
+        assert!(!envelope.ok);
+        assert_eq!(
+            envelope.error.as_ref().map(|e| e.code.clone()),
+            Some("INVALID_ARGUMENT".to_string())
+        );
+        assert_eq!(envelope.meta.version, "v1");
+
+        let envelope = OutputEnvelope::error("EXECUTION_FAILED", "HTTP 503")
+            .with_error_detail(Some(ErrorDetail::http(503, "busy")), true);
+        let json: Value = serde_json::from_str(&envelope.to_json().unwrap()).unwrap();
+        assert_eq!(json["error"]["retriable"], true);
+        assert_eq!(
+            json["error"]["detail"],
+            serde_json::json!({"type": "http", "status": 503, "body": "busy"})
+        );
+    }
+
+    #[test]
+    fn test_human_formatting() {
+        assert_eq!(format_duration(850), "850 ms");
+        assert_eq!(format_duration(1234), "1.2 s");
+        assert_eq!(format_duration(125_000), "2m 5s");
+        assert_eq!(format_duration(3_780_000), "1h 3m");
+
+        assert_eq!(format_size(500), "500 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3_565_158), "3.4 MB");
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2.0 GB");
+
+        set_utc_timestamps(true);
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20 UTC");
+    }
+
+    #[test]
+    fn test_template_rendering() {
+        let envelope = OutputEnvelope::success(
+            "call_result",
+            "openapi",
+            "https://api.example.com",
+            Some("get:/users"),
+            serde_json::json!({"users": [{"name": "ann"}, {"name": "bob"}]}),
+            Some(1234),
+        );
+
+        let template = OutputTemplate::parse(
+            "{{ operation }}: {{ data.users | map(attribute='name') | join(', ') }} \
+             in {{ meta.duration_ms | duration }}",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&envelope).unwrap(),
+            "get:/users: ann, bob in 1.2 s"
+        );
+        assert_eq!(
+            OutputTemplate::parse("{{ data.users[0] | tojson }}")
+                .unwrap()
+                .render(&envelope)
+                .unwrap(),
+            r#"{"name":"ann"}"#
+        );
+
+        assert!(matches!(
+            OutputTemplate::parse("{{ data.users"),
+            Err(UxcError::InvalidArguments(_))
+        ));
+        assert!(matches!(
+            OutputTemplate::parse("@/nonexistent/template.j2"),
+            Err(UxcError::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn test_next_actions_always_serialized() {
+        let envelope = OutputEnvelope::error("OPERATION_NOT_FOUND", "get:/user");
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["next"], serde_json::json!([]));
+
+        let envelope = envelope.with_next(vec![NextAction::new(
+            "uxc https://api.example.com describe get:/users",
+            "Closest operation: get:/users",
+        )]);
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(
+            json["next"][0]["command"],
+            "uxc https://api.example.com describe get:/users"
+        );
+    }
+}
@@ -0,0 +1,526 @@
+//! Following the pages of list operations (`--paginate`)
+//!
+//! After each page a [`Pager`] looks for the next one, taking the first of:
+//!
+//! 1. a `Link: <…>; rel="next"` response header,
+//! 2. the body field configured as `pagination.next`, sent back as
+//!    `pagination.next_param` (default `cursor`),
+//! 3. well-known body fields: next-page URLs (`next`, `links.next`,
+//!    `_links.next.href`, `paging.next`) and cursors (`next_cursor`,
+//!    `nextPageToken`, …) when the operation declares the matching argument,
+//! 4. a declared `page` argument, counted up from 1, or `offset`, advanced by
+//!    the items received, until a page comes back empty or short.
+//!
+//! The convention that finds the second page is the only one followed after
+//! it, so a last page without a `Link` header is not taken for a `page`
+//! counter. The query of a next-page URL becomes the arguments of the next
+//! call, and a `has_more: false` in the body ends pagination.
+//!
+//! The items of the pages are concatenated: the array at `pagination.items`,
+//! the body when it is an array, else its well-known (`items`, `data`,
+//! `results`, …) or only array field. Pages without one count as one item.
+
+use crate::assertions::lookup_path;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Pages fetched when `pagination.max_pages` is not set
+pub const DEFAULT_MAX_PAGES: u64 = 100;
+
+/// Body fields holding the URL of the next page
+const NEXT_URL_FIELDS: &[&str] = &["next", "links.next", "_links.next.href", "paging.next"];
+
+/// Body fields holding a cursor, with the argument it is sent back as
+const CURSOR_FIELDS: &[(&str, &str)] = &[
+    ("next_cursor", "cursor"),
+    ("nextCursor", "cursor"),
+    ("meta.next_cursor", "cursor"),
+    ("response_metadata.next_cursor", "cursor"),
+    ("next_page_token", "page_token"),
+    ("nextPageToken", "pageToken"),
+    ("next_token", "next_token"),
+    ("nextToken", "nextToken"),
+];
+
+/// Body fields holding the items of a page
+const ITEM_FIELDS: &[&str] = &[
+    "items", "data", "results", "records", "values", "entries", "nodes", "edges",
+];
+
+/// Arguments bounding the size of a page, to tell a short (last) page
+const SIZE_ARGS: &[&str] = &["limit", "per_page", "page_size", "pageSize", "size"];
+
+/// The `[pagination]` settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaginationConfig {
+    /// Path of the items of a page in the body, e.g. `data.users`
+    pub items: Option<String>,
+    /// Path of the next-page cursor or URL in the body
+    pub next: Option<String>,
+    /// Argument the cursor at `next` is sent as
+    pub next_param: Option<String>,
+    /// Pages fetched at most
+    pub max_pages: u64,
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            items: None,
+            next: None,
+            next_param: None,
+            max_pages: DEFAULT_MAX_PAGES,
+        }
+    }
+}
+
+/// Where a next page was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Convention {
+    /// `Link` response header
+    Link,
+    /// `pagination.next`
+    Configured,
+    /// Next-page URL in a body field
+    NextUrl(&'static str),
+    /// Cursor in a body field, and the argument it is sent as
+    Cursor(&'static str, &'static str),
+    Page,
+    Offset,
+}
+
+impl Convention {
+    fn describe(&self, config: &PaginationConfig) -> String {
+        match self {
+            Self::Link => "the Link header".to_string(),
+            Self::Configured => format!(
+                "'{}' (pagination.next)",
+                config.next.as_deref().unwrap_or_default()
+            ),
+            Self::NextUrl(field) => format!("'{}' in the body", field),
+            Self::Cursor(field, param) => {
+                format!("'{}' in the body, sent as '{}'", field, param)
+            }
+            Self::Page => "the 'page' argument".to_string(),
+            Self::Offset => "the 'offset' argument".to_string(),
+        }
+    }
+}
+
+/// Walks the pages of one call, collecting their items
+#[derive(Debug)]
+pub struct Pager {
+    config: PaginationConfig,
+    declared: HashSet<String>,
+    items: Vec<Value>,
+    pages: u64,
+    requested: HashSet<String>,
+    truncated: bool,
+    convention: Option<Convention>,
+}
+
+impl Pager {
+    /// A pager for an operation declaring the `declared` arguments
+    pub fn new(config: PaginationConfig, declared: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            config,
+            declared: declared.into_iter().collect(),
+            items: Vec::new(),
+            pages: 0,
+            requested: HashSet::new(),
+            truncated: false,
+            convention: None,
+        }
+    }
+
+    /// A pager picking up after `pages` pages, as recorded in a
+    /// [`crate::resume::ResumeToken`]; it collects the items of the pages
+    /// still to come
+    pub fn resuming(
+        config: PaginationConfig,
+        declared: impl IntoIterator<Item = String>,
+        pages: u64,
+    ) -> Self {
+        Self {
+            pages,
+            ..Self::new(config, declared)
+        }
+    }
+
+    /// Take in the page returned for `args`; the arguments of the next page,
+    /// if there is one to fetch
+    pub fn page(
+        &mut self,
+        args: &HashMap<String, Value>,
+        data: Value,
+        headers: &[(String, String)],
+    ) -> Option<HashMap<String, Value>> {
+        self.pages += 1;
+        self.requested.insert(args_key(args));
+
+        let items = self.items_of(&data);
+        let next = self.next_args(args, &data, headers, items.as_ref().map(Vec::len));
+        match items {
+            Some(items) => self.items.extend(items),
+            None => self.items.push(data),
+        }
+
+        let (next, convention) = next?;
+        if self.requested.contains(&args_key(&next)) {
+            crate::explain::record("pagination", "stopped: the next page was already fetched");
+            return None;
+        }
+        if self.pages >= self.config.max_pages {
+            self.truncated = true;
+            crate::explain::record(
+                "pagination",
+                format!("stopped after {} pages (max_pages)", self.pages),
+            );
+            return None;
+        }
+        if self.convention.is_none() {
+            crate::explain::record(
+                "pagination",
+                format!("next pages from {}", convention.describe(&self.config)),
+            );
+            self.convention = Some(convention);
+        }
+        Some(next)
+    }
+
+    /// Pages taken in so far
+    pub fn pages(&self) -> u64 {
+        self.pages
+    }
+
+    /// Whether `max_pages` stopped pagination before the last page
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Items of the pages taken in so far
+    pub fn items(&self) -> &[Value] {
+        &self.items
+    }
+
+    /// Items of all pages, in order
+    pub fn into_items(self) -> Value {
+        Value::Array(self.items)
+    }
+
+    fn items_of(&self, data: &Value) -> Option<Vec<Value>> {
+        if let Some(path) = &self.config.items {
+            return lookup_path(data, path).and_then(Value::as_array).cloned();
+        }
+        let fields = match data {
+            Value::Array(items) => return Some(items.clone()),
+            Value::Object(fields) => fields,
+            _ => return None,
+        };
+        if let Some(items) = ITEM_FIELDS
+            .iter()
+            .find_map(|name| fields.get(*name).and_then(Value::as_array))
+        {
+            return Some(items.clone());
+        }
+        let mut arrays = fields.values().filter_map(Value::as_array);
+        match (arrays.next(), arrays.next()) {
+            (Some(items), None) => Some(items.clone()),
+            _ => None,
+        }
+    }
+
+    /// Arguments of the next page and the convention that gave them. The
+    /// convention that found the second page is the only one tried after it.
+    fn next_args(
+        &self,
+        args: &HashMap<String, Value>,
+        data: &Value,
+        headers: &[(String, String)],
+        count: Option<usize>,
+    ) -> Option<(HashMap<String, Value>, Convention)> {
+        let conventions = match self.convention {
+            Some(convention) => vec![convention],
+            None => self.conventions(),
+        };
+        conventions.into_iter().find_map(|convention| {
+            self.follow(convention, args, data, headers, count)
+                .map(|next| (next, convention))
+        })
+    }
+
+    /// Conventions to try on the first page, in order
+    fn conventions(&self) -> Vec<Convention> {
+        let mut conventions = vec![Convention::Link];
+        if self.config.next.is_some() {
+            conventions.push(Convention::Configured);
+            return conventions;
+        }
+        conventions.extend(NEXT_URL_FIELDS.iter().copied().map(Convention::NextUrl));
+        conventions.extend(
+            CURSOR_FIELDS
+                .iter()
+                .map(|(field, param)| Convention::Cursor(field, param)),
+        );
+        conventions.extend([Convention::Page, Convention::Offset]);
+        conventions
+    }
+
+    fn follow(
+        &self,
+        convention: Convention,
+        args: &HashMap<String, Value>,
+        data: &Value,
+        headers: &[(String, String)],
+        count: Option<usize>,
+    ) -> Option<HashMap<String, Value>> {
+        if convention != Convention::Link
+            && ["has_more", "hasMore"]
+                .iter()
+                .any(|flag| data.get(*flag) == Some(&Value::Bool(false)))
+        {
+            return None;
+        }
+        match convention {
+            Convention::Link => with_query(args, &link_next(headers)?),
+            Convention::Configured => {
+                let path = self.config.next.as_deref()?;
+                let token = lookup_path(data, path).and_then(token)?;
+                match token.as_str().filter(|text| is_url(text)) {
+                    Some(url) => with_query(args, url),
+                    None => {
+                        let param = self.config.next_param.as_deref().unwrap_or("cursor");
+                        Some(with_arg(args, param, token))
+                    }
+                }
+            }
+            Convention::NextUrl(field) => {
+                let url = lookup_path(data, field)
+                    .and_then(Value::as_str)
+                    .filter(|text| is_url(text))?;
+                with_query(args, url)
+            }
+            Convention::Cursor(field, param) => {
+                if !self.accepts(args, param) {
+                    return None;
+                }
+                let cursor = lookup_path(data, field).and_then(token)?;
+                Some(with_arg(args, param, cursor))
+            }
+            Convention::Page | Convention::Offset => {
+                let count = count.filter(|count| *count > 0)?;
+                let size = SIZE_ARGS
+                    .iter()
+                    .find_map(|name| args.get(*name).and_then(as_number));
+                if size.is_some_and(|size| (count as u64) < size) {
+                    return None;
+                }
+                if convention == Convention::Page {
+                    if !self.accepts(args, "page") {
+                        return None;
+                    }
+                    let page = args.get("page").and_then(as_number).unwrap_or(1);
+                    Some(with_arg(args, "page", Value::from(page + 1)))
+                } else {
+                    if !self.accepts(args, "offset") {
+                        return None;
+                    }
+                    let offset = args.get("offset").and_then(as_number).unwrap_or(0);
+                    Some(with_arg(args, "offset", Value::from(offset + count as u64)))
+                }
+            }
+        }
+    }
+
+    /// Whether the operation takes the argument, or it was given anyway
+    fn accepts(&self, args: &HashMap<String, Value>, name: &str) -> bool {
+        self.declared.contains(name) || args.contains_key(name)
+    }
+}
+
+/// Target of the `rel="next"` entry of `Link` headers
+fn link_next(headers: &[(String, String)]) -> Option<String> {
+    headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("link"))
+        .flat_map(|(_, value)| value.split(','))
+        .find_map(|link| {
+            let (target, params) = link.split_once(';')?;
+            let next = params.split(';').any(|param| {
+                param.trim().split_once('=').is_some_and(|(key, value)| {
+                    key.trim().eq_ignore_ascii_case("rel")
+                        && value
+                            .trim()
+                            .trim_matches('"')
+                            .split_whitespace()
+                            .any(|rel| rel.eq_ignore_ascii_case("next"))
+                })
+            });
+            next.then(|| {
+                target
+                    .trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+        })
+}
+
+/// A cursor worth sending: not null, false or empty
+fn token(value: &Value) -> Option<Value> {
+    match value {
+        Value::Null | Value::Bool(false) => None,
+        Value::String(text) if text.is_empty() => None,
+        other => Some(other.clone()),
+    }
+}
+
+fn is_url(text: &str) -> bool {
+    text.contains("://") || text.starts_with('/') || text.starts_with('?')
+}
+
+fn as_number(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+}
+
+fn with_arg(args: &HashMap<String, Value>, name: &str, value: Value) -> HashMap<String, Value> {
+    let mut next = args.clone();
+    next.insert(name.to_string(), value);
+    next
+}
+
+/// `args` with the query parameters of `url` over them; repeated parameters
+/// become arrays
+fn with_query(args: &HashMap<String, Value>, url: &str) -> Option<HashMap<String, Value>> {
+    let url = url::Url::parse("http://localhost/")
+        .and_then(|base| base.join(url))
+        .ok()?;
+    let mut query: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in url.query_pairs() {
+        query
+            .entry(name.into_owned())
+            .or_default()
+            .push(value.into_owned());
+    }
+    let mut next = args.clone();
+    for (name, mut values) in query {
+        let value = if values.len() == 1 {
+            Value::String(values.remove(0))
+        } else {
+            Value::Array(values.into_iter().map(Value::String).collect())
+        };
+        next.insert(name, value);
+    }
+    Some(next)
+}
+
+/// Arguments in a stable form, to notice a page asked for twice
+fn args_key(args: &HashMap<String, Value>) -> String {
+    let sorted: BTreeMap<_, _> = args.iter().collect();
+    serde_json::to_string(&sorted).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn new_pager(declared: &[&str]) -> Pager {
+        Pager::new(
+            PaginationConfig::default(),
+            declared.iter().map(|name| name.to_string()),
+        )
+    }
+
+    #[test]
+    fn follows_link_headers_and_body_cursors() {
+        let mut pager = new_pager(&[]);
+        let link = vec![(
+            "Link".to_string(),
+            r#"<https://api.test/items?page=1>; rel="prev", <https://api.test/items?page=3&tag=a&tag=b>; rel="next""#.to_string(),
+        )];
+        let next = pager.page(&args(json!({"page": 2})), json!([1, 2]), &link);
+        assert_eq!(next, Some(args(json!({"page": "3", "tag": ["a", "b"]}))));
+
+        let mut pager = new_pager(&["cursor"]);
+        let next = pager.page(
+            &args(json!({})),
+            json!({"data": [1], "next_cursor": "abc"}),
+            &[],
+        );
+        assert_eq!(next, Some(args(json!({"cursor": "abc"}))));
+        assert_eq!(
+            pager.page(
+                &next.unwrap(),
+                json!({"data": [2], "next_cursor": null}),
+                &[]
+            ),
+            None
+        );
+        assert_eq!(pager.pages(), 2);
+        assert_eq!(pager.into_items(), json!([1, 2]));
+
+        // A cursor the operation does not take is not sent
+        let mut pager = new_pager(&[]);
+        assert_eq!(
+            pager.page(
+                &args(json!({})),
+                json!({"items": [1], "nextCursor": "x"}),
+                &[]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn counts_pages_and_offsets_until_a_short_page() {
+        let mut pager = new_pager(&["page"]);
+        let next = pager.page(&args(json!({})), json!({"results": [1, 2]}), &[]);
+        assert_eq!(next, Some(args(json!({"page": 2}))));
+        assert_eq!(
+            pager.page(&next.unwrap(), json!({"results": []}), &[]),
+            None
+        );
+
+        let mut pager = new_pager(&["offset", "limit"]);
+        let next = pager.page(&args(json!({"limit": "2"})), json!([1, 2]), &[]);
+        assert_eq!(next, Some(args(json!({"limit": "2", "offset": 2}))));
+        assert_eq!(pager.page(&next.unwrap(), json!([3]), &[]), None);
+        assert_eq!(pager.into_items(), json!([1, 2, 3]));
+    }
+
+    #[test]
+    fn configured_paths_and_page_limit() {
+        let config = PaginationConfig {
+            items: Some("data.users".to_string()),
+            next: Some("meta.after".to_string()),
+            next_param: Some("after".to_string()),
+            max_pages: 2,
+        };
+        let mut pager = Pager::new(config, Vec::new());
+        let page = |after: &str| json!({"data": {"users": [after]}, "meta": {"after": after}});
+        let next = pager.page(&args(json!({})), page("a"), &[]).unwrap();
+        assert_eq!(next, args(json!({"after": "a"})));
+        assert_eq!(pager.page(&next, page("b"), &[]), None);
+        assert!(pager.truncated());
+        assert_eq!(pager.into_items(), json!(["a", "b"]));
+    }
+
+    #[test]
+    fn stops_on_has_more_and_repeated_pages() {
+        let mut pager = new_pager(&[]);
+        let body = json!({"items": [1], "has_more": false, "next": "/items?page=2"});
+        assert_eq!(pager.page(&args(json!({})), body, &[]), None);
+
+        let mut pager = new_pager(&[]);
+        let body = json!({"items": [1], "next": "/items?page=1"});
+        assert_eq!(pager.page(&args(json!({"page": "1"})), body, &[]), None);
+        assert!(!pager.truncated());
+    }
+}
@@ -0,0 +1,300 @@
+//! Protocol plugins
+//!
+//! A plugin adds a protocol (FIX, an in-house RPC) to uxc without forking
+//! it: a dynamic library implementing the plugin ABI described in
+//! [`crate::adapters::plugin`], with a `plugin.toml` manifest next to it:
+//!
+//! ```toml
+//! name = "fix"
+//! version = "0.1.0"
+//! description = "FIX 4.4 order entry"
+//! library = "libuxc_fix.so"
+//! schemes = ["fix", "fixs"]
+//! ```
+//!
+//! Installed plugins live in `~/.uxc/plugins/<name>/`. Endpoints whose URL
+//! scheme a plugin claims (`fix://broker.example.com:9876`) are served by it
+//! without probing the built-in protocols. Plugins are managed with
+//! `uxc plugin list|install|remove`.
+
+use crate::adapters::plugin::PluginLibrary;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_DIR: &str = ".uxc";
+const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.toml";
+
+/// Schemes the built-in adapters answer on, which no plugin may claim
+const RESERVED_SCHEMES: [&str; 4] = ["http", "https", "ws", "wss"];
+
+/// What `plugin.toml` declares
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    pub version: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// File name of the dynamic library, next to the manifest
+    pub library: String,
+
+    /// URL schemes of the endpoints the plugin serves
+    pub schemes: Vec<String>,
+}
+
+impl Manifest {
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+        {
+            bail!(
+                "Plugin name '{}' is not valid. Allowed characters: letters, digits, '_', '-'",
+                self.name
+            );
+        }
+        if self.library.is_empty() || Path::new(&self.library).components().count() != 1 {
+            bail!(
+                "Plugin library '{}' must be a file name next to {}",
+                self.library,
+                MANIFEST_FILE
+            );
+        }
+        if self.schemes.is_empty() {
+            bail!("Plugin '{}' declares no URL schemes", self.name);
+        }
+        for scheme in &self.schemes {
+            if !is_scheme(scheme) {
+                bail!("'{}' is not a valid URL scheme", scheme);
+            }
+            if RESERVED_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()) {
+                bail!(
+                    "The {} scheme belongs to the built-in protocols and cannot be claimed by a plugin",
+                    scheme
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn claims(&self, scheme: &str) -> bool {
+        self.schemes
+            .iter()
+            .any(|claimed| claimed.eq_ignore_ascii_case(scheme))
+    }
+}
+
+/// A plugin in `~/.uxc/plugins`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    #[serde(flatten)]
+    pub manifest: Manifest,
+
+    /// Directory holding the manifest and the library
+    pub path: PathBuf,
+}
+
+impl InstalledPlugin {
+    pub fn library_path(&self) -> PathBuf {
+        self.path.join(&self.manifest.library)
+    }
+
+    /// Load the plugin's library, checking it speaks the plugin ABI
+    pub fn load(&self) -> Result<PluginLibrary> {
+        PluginLibrary::load(&self.library_path())
+            .with_context(|| format!("Failed to load plugin '{}'", self.manifest.name))
+    }
+}
+
+/// Directory plugins are installed in
+pub fn plugins_dir() -> Result<PathBuf> {
+    let home = home_dir().context("Could not determine home directory")?;
+    Ok(home.join(CONFIG_DIR).join(PLUGINS_DIR))
+}
+
+/// Installed plugins, by name. Directories without a valid manifest are
+/// skipped with a warning.
+pub fn list() -> Result<Vec<InstalledPlugin>> {
+    let dir = plugins_dir()?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut plugins = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        match read_manifest(&path.join(MANIFEST_FILE)) {
+            Ok(manifest) => plugins.push(InstalledPlugin { manifest, path }),
+            Err(e) => tracing::warn!("Ignoring plugin in {:?}: {:#}", path, e),
+        }
+    }
+    plugins.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    Ok(plugins)
+}
+
+/// The installed plugin claiming the scheme of `url`, if any
+pub fn for_url(url: &str) -> Result<Option<InstalledPlugin>> {
+    let Some(scheme) = scheme_of(url) else {
+        return Ok(None);
+    };
+    if RESERVED_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()) {
+        return Ok(None);
+    }
+    Ok(list()?
+        .into_iter()
+        .find(|plugin| plugin.manifest.claims(scheme)))
+}
+
+/// Install the plugin whose manifest is `source` (or in the directory
+/// `source`), after loading its library to check it. An installed plugin of
+/// the same name is only replaced with `force`.
+pub fn install(source: &Path, force: bool) -> Result<InstalledPlugin> {
+    let manifest_path = if source.is_dir() {
+        source.join(MANIFEST_FILE)
+    } else {
+        source.to_path_buf()
+    };
+    let manifest = read_manifest(&manifest_path)?;
+    let source_dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let library = source_dir.join(&manifest.library);
+    if !library.is_file() {
+        bail!("Plugin library {:?} does not exist", library);
+    }
+    PluginLibrary::load(&library).with_context(|| format!("{:?} is not a uxc plugin", library))?;
+
+    let installed = list()?;
+    if let Some(conflict) = installed.iter().find(|plugin| {
+        plugin.manifest.name != manifest.name
+            && manifest
+                .schemes
+                .iter()
+                .any(|scheme| plugin.manifest.claims(scheme))
+    }) {
+        bail!(
+            "Plugin '{}' already serves {}",
+            conflict.manifest.name,
+            conflict.manifest.schemes.join(", ")
+        );
+    }
+    let target = plugins_dir()?.join(&manifest.name);
+    if installed
+        .iter()
+        .any(|plugin| plugin.manifest.name == manifest.name)
+    {
+        if !force {
+            bail!(
+                "Plugin '{}' is already installed; use --force to replace it",
+                manifest.name
+            );
+        }
+        fs::remove_dir_all(&target).with_context(|| format!("Failed to remove {:?}", target))?;
+    }
+
+    fs::create_dir_all(&target)
+        .with_context(|| format!("Failed to create plugin directory: {:?}", target))?;
+    fs::copy(&manifest_path, target.join(MANIFEST_FILE))
+        .with_context(|| format!("Failed to copy {:?}", manifest_path))?;
+    fs::copy(&library, target.join(&manifest.library))
+        .with_context(|| format!("Failed to copy {:?}", library))?;
+    Ok(InstalledPlugin {
+        manifest,
+        path: target,
+    })
+}
+
+/// Uninstall the plugin called `name`
+pub fn remove(name: &str) -> Result<InstalledPlugin> {
+    let plugin = list()?
+        .into_iter()
+        .find(|plugin| plugin.manifest.name == name)
+        .with_context(|| format!("Plugin '{}' is not installed", name))?;
+    fs::remove_dir_all(&plugin.path)
+        .with_context(|| format!("Failed to remove {:?}", plugin.path))?;
+    Ok(plugin)
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read plugin manifest: {:?}", path))?;
+    let manifest: Manifest = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse plugin manifest: {:?}", path))?;
+    manifest.validate()?;
+    Ok(manifest)
+}
+
+/// Scheme of `url`, when it has one (`fix` in `fix://host:9876`)
+fn scheme_of(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once("://")?;
+    is_scheme(scheme).then_some(scheme)
+}
+
+fn is_scheme(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_alphabetic())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(user_profile));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, library: &str, schemes: &[&str]) -> Manifest {
+        Manifest {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            library: library.to_string(),
+            schemes: schemes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn manifests_are_validated() {
+        assert!(manifest("fix", "libfix.so", &["fix", "fix+tls"])
+            .validate()
+            .is_ok());
+        assert!(manifest("f ix", "libfix.so", &["fix"]).validate().is_err());
+        assert!(manifest("fix", "../libfix.so", &["fix"])
+            .validate()
+            .is_err());
+        assert!(manifest("fix", "libfix.so", &[]).validate().is_err());
+        assert!(manifest("fix", "libfix.so", &["1fix"]).validate().is_err());
+        let err = manifest("web", "libweb.so", &["HTTPS"])
+            .validate()
+            .unwrap_err();
+        assert!(err.to_string().contains("built-in"), "{}", err);
+    }
+
+    #[test]
+    fn schemes_are_read_from_urls() {
+        assert_eq!(scheme_of("fix://broker:9876"), Some("fix"));
+        assert_eq!(scheme_of("acme-rpc+tls://host/path"), Some("acme-rpc+tls"));
+        assert_eq!(scheme_of("api.example.com"), None);
+        assert_eq!(scheme_of("npx server --url http://x"), None);
+        assert!(manifest("fix", "libfix.so", &["FIX"]).claims("fix"));
+    }
+}
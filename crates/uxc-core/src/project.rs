@@ -0,0 +1,311 @@
+//! Project-local configuration
+//!
+//! A `.uxc.toml` in the current directory or any parent applies to every
+//! invocation below it, so a repository can pin the endpoint, auth and
+//! cache behaviour its calls need:
+//!
+//! ```toml
+//! endpoint = "https://staging.example.com"   # used when no URL is given
+//! profile = "staging"                        # unless --profile or UXC_PROFILE
+//! headers = { "X-Team" = "payments" }        # sent with every HTTP request
+//!
+//! [hooks]                                    # scripts run around every call
+//! pre_request = "hooks/sign.rhai"
+//!
+//! [aliases.api]
+//! url = "https://staging.example.com/api"
+//!
+//! [cache]
+//! ttl = 300
+//! ```
+//!
+//! Aliases use the format of [`crate::alias`] and win over aliases of the
+//! same name in `~/.uxc/config.toml`. Settings sections (`[output]`,
+//! `[call]`, `[cache]`, ...) override the user config (see
+//! [`crate::config`]). The nearest file wins; files further up are not
+//! merged.
+//!
+//! A checked-out repository is not necessarily trusted, so a project file
+//! only gets to send credentials once it is trusted with `uxc project trust`.
+//! Trust is recorded in `~/.uxc/trusted_projects.json` for the file's path
+//! and a SHA-256 digest of its contents: editing the file revokes it. Until
+//! then, calls to the project's endpoint or aliases refuse to use an auth
+//! profile, and its hooks do not run.
+
+use crate::alias::Alias;
+use crate::hooks::HookConfig;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// File name looked up from the current directory upwards
+pub const PROJECT_FILE: &str = ".uxc.toml";
+
+const TRUST_FILE: &str = ".uxc/trusted_projects.json";
+
+/// Settings of a `.uxc.toml`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ProjectConfig {
+    /// File the settings were read from
+    #[serde(skip)]
+    pub path: PathBuf,
+
+    /// Endpoint (URL or alias) used when a command is given without one
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Default auth profile
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Headers added to every HTTP request
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub aliases: BTreeMap<String, Alias>,
+
+    /// Scripts run around every call, relative to the file (see
+    /// [`crate::hooks`])
+    #[serde(default)]
+    pub hooks: HookConfig,
+
+    /// The whole file, for the settings sections read by [`crate::config`]
+    #[serde(skip)]
+    pub settings: toml::Table,
+
+    /// SHA-256 digest of the file, which trust is recorded for
+    #[serde(skip)]
+    pub digest: String,
+
+    /// Whether the file, as it is now, was trusted with `uxc project trust`
+    #[serde(skip)]
+    pub trusted: bool,
+}
+
+impl ProjectConfig {
+    /// The project file for the current directory, read once per process
+    pub fn current() -> Result<Option<&'static Self>> {
+        static CURRENT: OnceLock<std::result::Result<Option<ProjectConfig>, String>> =
+            OnceLock::new();
+        let current = CURRENT.get_or_init(|| {
+            let dir = std::env::current_dir().map_err(|e| e.to_string())?;
+            Self::discover_from(&dir).map_err(|e| format!("{:#}", e))
+        });
+        current
+            .as_ref()
+            .map(Option::as_ref)
+            .map_err(|e| anyhow!("{}", e))
+    }
+
+    /// The nearest `.uxc.toml` in `dir` or one of its parents
+    pub fn discover_from(dir: &Path) -> Result<Option<Self>> {
+        for dir in dir.ancestors() {
+            let path = dir.join(PROJECT_FILE);
+            if path.is_file() {
+                return Self::load(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read a project file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read project config: {:?}", path))?;
+        let settings: toml::Table = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse project config: {:?}", path))?;
+        let mut config: Self = toml::Value::Table(settings.clone())
+            .try_into()
+            .with_context(|| format!("Failed to parse project config: {:?}", path))?;
+        config.path = path.to_path_buf();
+        config.settings = settings;
+        config.digest = digest(&contents);
+        config.trusted = TrustStore::load()
+            .map(|store| store.is_trusted(&config))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Ignoring project trust records: {:#}", e);
+                false
+            });
+        Ok(config)
+    }
+
+    /// Directory the file is in, which relative paths in it start from
+    pub fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// [`ProjectConfig::headers`] as request headers
+    pub fn header_map(&self) -> Result<reqwest::header::HeaderMap> {
+        crate::http::header_map(&self.headers)
+            .with_context(|| format!("Invalid headers in {:?}", self.path))
+    }
+}
+
+/// Project files trusted with `uxc project trust`, by path
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustStore {
+    /// Digest of the contents each file was trusted with
+    #[serde(default)]
+    pub projects: BTreeMap<PathBuf, String>,
+}
+
+impl TrustStore {
+    fn path() -> Result<PathBuf> {
+        let home = home_dir().context("Could not determine home directory")?;
+        Ok(home.join(TRUST_FILE))
+    }
+
+    /// Load the trust records. A missing file trusts nothing.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read trust records: {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse trust records: {:?}", path))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write trust records: {:?}", path))
+    }
+
+    /// Trust `project` as it is now
+    pub fn trust(&mut self, project: &ProjectConfig) {
+        self.projects
+            .insert(trust_path(&project.path), project.digest.clone());
+    }
+
+    /// Forget the trust of the file at `path`; false when it had none
+    pub fn untrust(&mut self, path: &Path) -> bool {
+        self.projects.remove(&trust_path(path)).is_some()
+    }
+
+    /// Whether `project` was trusted with its current contents
+    pub fn is_trusted(&self, project: &ProjectConfig) -> bool {
+        self.projects.get(&trust_path(&project.path)) == Some(&project.digest)
+    }
+}
+
+/// Path trust is recorded under, the same however the file was reached
+fn trust_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(user_profile));
+        }
+    }
+
+    None
+}
+
+fn digest(contents: &str) -> String {
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_project_file_is_found_from_subdirectories() {
+        let root = tempfile::TempDir::new().unwrap();
+        fs::write(
+            root.path().join(PROJECT_FILE),
+            r#"
+endpoint = "https://staging.example.com"
+profile = "staging"
+headers = { "X-Team" = "payments" }
+
+[hooks]
+post_response = "hooks/unwrap.rhai"
+
+[aliases.api]
+url = "https://staging.example.com/api"
+profile = "ci"
+
+[cache]
+ttl = 300
+"#,
+        )
+        .unwrap();
+        let nested = root.path().join("services/billing");
+        fs::create_dir_all(&nested).unwrap();
+
+        let config = ProjectConfig::discover_from(&nested).unwrap().unwrap();
+        assert_eq!(config.path, root.path().join(PROJECT_FILE));
+        assert_eq!(
+            config.endpoint.as_deref(),
+            Some("https://staging.example.com")
+        );
+        assert_eq!(config.profile.as_deref(), Some("staging"));
+        assert_eq!(config.aliases["api"].profile.as_deref(), Some("ci"));
+        assert_eq!(config.header_map().unwrap()["x-team"], "payments");
+        assert_eq!(
+            config.hooks.post_response.as_deref(),
+            Some(Path::new("hooks/unwrap.rhai"))
+        );
+        assert_eq!(config.dir(), root.path());
+        assert_eq!(config.settings["cache"]["ttl"].as_integer(), Some(300));
+    }
+
+    #[test]
+    fn missing_or_broken_project_files() {
+        let root = tempfile::TempDir::new().unwrap();
+        let nested = root.path().join("empty");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join(PROJECT_FILE), "endpoint = [").unwrap();
+        assert!(ProjectConfig::discover_from(&nested).is_err());
+        assert_eq!(ProjectConfig::discover_from(root.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn trust_follows_the_file_contents() {
+        let root = tempfile::TempDir::new().unwrap();
+        let path = root.path().join(PROJECT_FILE);
+        fs::write(&path, "endpoint = \"https://staging.example.com\"\n").unwrap();
+        let project = ProjectConfig::load(&path).unwrap();
+
+        let mut store = TrustStore::default();
+        assert!(!store.is_trusted(&project));
+        store.trust(&project);
+        assert!(store.is_trusted(&project));
+
+        // Reached through another path, the same file stays trusted
+        let nested = root.path().join("sub");
+        fs::create_dir_all(&nested).unwrap();
+        let found = ProjectConfig::discover_from(&nested.join(".."))
+            .unwrap()
+            .unwrap();
+        assert!(store.is_trusted(&found));
+
+        fs::write(&path, "endpoint = \"https://evil.example.com\"\n").unwrap();
+        assert!(!store.is_trusted(&ProjectConfig::load(&path).unwrap()));
+
+        assert!(store.untrust(&path));
+        assert!(!store.untrust(&path));
+    }
+}
@@ -0,0 +1,1005 @@
+//! Result filtering for `--query`
+//!
+//! A JMESPath subset evaluated against envelope data: fields (`a.b`, `"quoted key"`),
+//! indexes and slices (`[0]`, `[-1]`, `[1:3]`), projections (`[*]`, `[]`, `*`),
+//! filters (`[?age > `30` && active]`), multi-selects (`[a, b]`, `{name: a}`),
+//! pipes (`a | [0]`) and a few functions (`length`, `keys`, `sort`, `join`, ...).
+//! A leading `.` is accepted jq-style, so `.data[].name` equals `data[].name`.
+
+use crate::error::{Result, UxcError};
+use serde_json::{Map, Value};
+
+/// A parsed `--query` expression
+#[derive(Debug, Clone)]
+pub struct Query {
+    source: String,
+    expr: Expr,
+}
+
+impl Query {
+    /// Parse `source`, reporting syntax errors as invalid arguments
+    pub fn parse(source: &str) -> Result<Self> {
+        let trimmed = source.trim();
+        let normalized = match trimmed.strip_prefix('.') {
+            Some("") => "@",
+            Some(rest) if !rest.starts_with('.') => rest,
+            _ => trimmed,
+        };
+        let tokens = lex(normalized).map_err(|e| invalid(source, &e))?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.expression(0).map_err(|e| invalid(source, &e))?;
+        if parser.peek() != &Token::Eof {
+            return Err(invalid(
+                source,
+                &format!("unexpected {}", parser.peek().describe()),
+            ));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Keys of a trailing multi-select hash (`items[].{name: name, id: id}`), in
+    /// the order written; used as the column order of CSV output
+    pub fn selected_keys(&self) -> Option<Vec<String>> {
+        let mut expr = &self.expr;
+        loop {
+            expr = match expr {
+                Expr::Subexpr(_, rhs)
+                | Expr::Pipe(_, rhs)
+                | Expr::Projection(_, rhs)
+                | Expr::ValueProjection(_, rhs)
+                | Expr::FilterProjection(_, rhs, _) => rhs,
+                Expr::MultiHash(pairs) => {
+                    return Some(pairs.iter().map(|(key, _)| key.clone()).collect())
+                }
+                _ => return None,
+            };
+        }
+    }
+
+    /// Evaluate against `data`; unmatched paths yield `null`
+    pub fn search(&self, data: &Value) -> Result<Value> {
+        eval(&self.expr, data).map_err(|e| {
+            UxcError::InvalidArguments(format!("--query '{}' failed: {}", self.source, e))
+        })
+    }
+}
+
+fn invalid(source: &str, reason: &str) -> UxcError {
+    UxcError::InvalidArguments(format!("Invalid --query '{}': {}", source, reason))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparator {
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparator::Eq => "==",
+            Comparator::Ne => "!=",
+            Comparator::Lt => "<",
+            Comparator::Le => "<=",
+            Comparator::Gt => ">",
+            Comparator::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    Number(i64),
+    Literal(Value),
+    Dot,
+    Star,
+    Flatten,
+    Filter,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    At,
+    Compare(Comparator),
+    Eof,
+}
+
+impl Token {
+    fn binding_power(&self) -> u8 {
+        match self {
+            Token::Pipe => 1,
+            Token::Or => 2,
+            Token::And => 3,
+            Token::Compare(_) => 5,
+            Token::Flatten => 9,
+            Token::Star => 20,
+            Token::Filter => 21,
+            Token::Dot => 40,
+            Token::Not => 45,
+            Token::LBrace => 50,
+            Token::LBracket => 55,
+            Token::LParen => 60,
+            _ => 0,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Token::Identifier(name) | Token::QuotedIdentifier(name) => format!("'{}'", name),
+            Token::Number(n) => format!("'{}'", n),
+            Token::Literal(value) => format!("literal {}", value),
+            Token::Eof => "end of expression".to_string(),
+            Token::Compare(op) => format!("'{}'", op.symbol()),
+            punctuation => format!(
+                "'{}'",
+                match punctuation {
+                    Token::Dot => ".",
+                    Token::Star => "*",
+                    Token::Flatten => "[]",
+                    Token::Filter => "[?",
+                    Token::LBracket => "[",
+                    Token::RBracket => "]",
+                    Token::LBrace => "{",
+                    Token::RBrace => "}",
+                    Token::LParen => "(",
+                    Token::RParen => ")",
+                    Token::Comma => ",",
+                    Token::Colon => ":",
+                    Token::Pipe => "|",
+                    Token::Or => "||",
+                    Token::And => "&&",
+                    Token::Not => "!",
+                    _ => "@",
+                }
+            ),
+        }
+    }
+}
+
+fn lex(source: &str) -> std::result::Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            ' ' | '\t' | '\n' | '\r' => i += 1,
+            '.' => push(&mut tokens, &mut i, Token::Dot, 1),
+            '*' => push(&mut tokens, &mut i, Token::Star, 1),
+            ']' => push(&mut tokens, &mut i, Token::RBracket, 1),
+            '{' => push(&mut tokens, &mut i, Token::LBrace, 1),
+            '}' => push(&mut tokens, &mut i, Token::RBrace, 1),
+            '(' => push(&mut tokens, &mut i, Token::LParen, 1),
+            ')' => push(&mut tokens, &mut i, Token::RParen, 1),
+            ',' => push(&mut tokens, &mut i, Token::Comma, 1),
+            ':' => push(&mut tokens, &mut i, Token::Colon, 1),
+            '@' => push(&mut tokens, &mut i, Token::At, 1),
+            '[' => match next {
+                Some(']') => push(&mut tokens, &mut i, Token::Flatten, 2),
+                Some('?') => push(&mut tokens, &mut i, Token::Filter, 2),
+                _ => push(&mut tokens, &mut i, Token::LBracket, 1),
+            },
+            '|' if next == Some('|') => push(&mut tokens, &mut i, Token::Or, 2),
+            '|' => push(&mut tokens, &mut i, Token::Pipe, 1),
+            '&' if next == Some('&') => push(&mut tokens, &mut i, Token::And, 2),
+            '=' if next == Some('=') => {
+                push(&mut tokens, &mut i, Token::Compare(Comparator::Eq), 2)
+            }
+            '!' if next == Some('=') => {
+                push(&mut tokens, &mut i, Token::Compare(Comparator::Ne), 2)
+            }
+            '!' => push(&mut tokens, &mut i, Token::Not, 1),
+            '<' if next == Some('=') => {
+                push(&mut tokens, &mut i, Token::Compare(Comparator::Le), 2)
+            }
+            '<' => push(&mut tokens, &mut i, Token::Compare(Comparator::Lt), 1),
+            '>' if next == Some('=') => {
+                push(&mut tokens, &mut i, Token::Compare(Comparator::Ge), 2)
+            }
+            '>' => push(&mut tokens, &mut i, Token::Compare(Comparator::Gt), 1),
+            '"' => {
+                let (raw, end) = delimited(&chars, i, '"')?;
+                let name: String = serde_json::from_str(&format!("\"{}\"", raw))
+                    .map_err(|_| format!("invalid quoted identifier \"{}\"", raw))?;
+                tokens.push(Token::QuotedIdentifier(name));
+                i = end;
+            }
+            '\'' => {
+                let (raw, end) = delimited(&chars, i, '\'')?;
+                tokens.push(Token::Literal(Value::String(raw.replace("\\'", "'"))));
+                i = end;
+            }
+            '`' => {
+                let (raw, end) = delimited(&chars, i, '`')?;
+                let raw = raw.replace("\\`", "`");
+                // Bare words inside backticks are read as strings, like older JMESPath
+                let value = serde_json::from_str(raw.trim())
+                    .unwrap_or_else(|_| Value::String(raw.trim().to_string()));
+                tokens.push(Token::Literal(value));
+                i = end;
+            }
+            '-' | '0'..='9' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let number = text
+                    .parse()
+                    .map_err(|_| format!("invalid number '{}'", text))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Identifier(chars[start..i].iter().collect()));
+            }
+            other => return Err(format!("unexpected character '{}'", other)),
+        }
+    }
+
+    tokens.push(Token::Eof);
+    Ok(tokens)
+}
+
+fn push(tokens: &mut Vec<Token>, i: &mut usize, token: Token, width: usize) {
+    tokens.push(token);
+    *i += width;
+}
+
+/// Text between `quote` at `start` and its unescaped closing quote, and the
+/// index after the closing quote.
+fn delimited(
+    chars: &[char],
+    start: usize,
+    quote: char,
+) -> std::result::Result<(String, usize), String> {
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return Ok((chars[start + 1..i].iter().collect(), i + 1)),
+            _ => i += 1,
+        }
+    }
+    Err(format!("unterminated {}", quote))
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Current,
+    Field(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Literal(Value),
+    Subexpr(Box<Expr>, Box<Expr>),
+    Pipe(Box<Expr>, Box<Expr>),
+    /// Apply the right side to each element of the left side's array
+    Projection(Box<Expr>, Box<Expr>),
+    /// Apply the right side to each value of the left side's object
+    ValueProjection(Box<Expr>, Box<Expr>),
+    FilterProjection(Box<Expr>, Box<Expr>, Box<Expr>),
+    Flatten(Box<Expr>),
+    MultiList(Vec<Expr>),
+    MultiHash(Vec<(String, Expr)>),
+    Compare(Comparator, Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Function(String, Vec<Expr>),
+}
+
+/// Pratt parser following the JMESPath grammar's binding powers
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+/// Tokens binding weaker than this end a projection's right-hand side
+const PROJECTION_STOP: u8 = 10;
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        self.tokens.get(self.pos + offset).unwrap_or(&Token::Eof)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> std::result::Result<(), String> {
+        let token = self.next();
+        if token == expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected {}, found {}",
+                expected.describe(),
+                token.describe()
+            ))
+        }
+    }
+
+    fn expression(&mut self, binding_power: u8) -> std::result::Result<Expr, String> {
+        let token = self.next();
+        let mut left = self.nud(token)?;
+        while binding_power < self.peek().binding_power() {
+            let token = self.next();
+            left = self.led(token, left)?;
+        }
+        Ok(left)
+    }
+
+    fn nud(&mut self, token: Token) -> std::result::Result<Expr, String> {
+        match token {
+            Token::Identifier(name) => Ok(Expr::Field(name)),
+            Token::QuotedIdentifier(name) => {
+                if self.peek() == &Token::LParen {
+                    return Err("quoted identifiers cannot be function names".to_string());
+                }
+                Ok(Expr::Field(name))
+            }
+            Token::Literal(value) => Ok(Expr::Literal(value)),
+            Token::Number(n) => Ok(Expr::Literal(Value::from(n))),
+            Token::At => Ok(Expr::Current),
+            Token::Star => {
+                let rhs = self.projection_rhs(Token::Star.binding_power())?;
+                Ok(Expr::ValueProjection(
+                    Box::new(Expr::Current),
+                    Box::new(rhs),
+                ))
+            }
+            Token::Flatten => {
+                let rhs = self.projection_rhs(Token::Flatten.binding_power())?;
+                Ok(Expr::Projection(
+                    Box::new(Expr::Flatten(Box::new(Expr::Current))),
+                    Box::new(rhs),
+                ))
+            }
+            Token::Filter => self.filter(Expr::Current),
+            Token::LBrace => self.multi_hash(),
+            Token::LParen => {
+                let expr = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Token::Not => Ok(Expr::Not(Box::new(
+                self.expression(Token::Not.binding_power())?,
+            ))),
+            Token::LBracket => match (self.peek(), self.peek_at(1)) {
+                (Token::Number(_) | Token::Colon, _) => {
+                    let index = self.index_expression()?;
+                    self.project_if_slice(Expr::Current, index)
+                }
+                (Token::Star, Token::RBracket) => {
+                    self.pos += 2;
+                    let rhs = self.projection_rhs(Token::Star.binding_power())?;
+                    Ok(Expr::Projection(Box::new(Expr::Current), Box::new(rhs)))
+                }
+                _ => self.multi_list(),
+            },
+            other => Err(format!("unexpected {}", other.describe())),
+        }
+    }
+
+    fn led(&mut self, token: Token, left: Expr) -> std::result::Result<Expr, String> {
+        match token {
+            Token::Dot => {
+                if self.peek() == &Token::Star {
+                    self.next();
+                    let rhs = self.projection_rhs(Token::Dot.binding_power())?;
+                    return Ok(Expr::ValueProjection(Box::new(left), Box::new(rhs)));
+                }
+                let rhs = self.dot_rhs(Token::Dot.binding_power())?;
+                Ok(Expr::Subexpr(Box::new(left), Box::new(rhs)))
+            }
+            Token::Pipe => {
+                let rhs = self.expression(Token::Pipe.binding_power())?;
+                Ok(Expr::Pipe(Box::new(left), Box::new(rhs)))
+            }
+            Token::Or => {
+                let rhs = self.expression(Token::Or.binding_power())?;
+                Ok(Expr::Or(Box::new(left), Box::new(rhs)))
+            }
+            Token::And => {
+                let rhs = self.expression(Token::And.binding_power())?;
+                Ok(Expr::And(Box::new(left), Box::new(rhs)))
+            }
+            Token::Compare(op) => {
+                let rhs = self.expression(Token::Compare(op).binding_power())?;
+                Ok(Expr::Compare(op, Box::new(left), Box::new(rhs)))
+            }
+            Token::Flatten => {
+                let rhs = self.projection_rhs(Token::Flatten.binding_power())?;
+                Ok(Expr::Projection(
+                    Box::new(Expr::Flatten(Box::new(left))),
+                    Box::new(rhs),
+                ))
+            }
+            Token::Filter => self.filter(left),
+            Token::LBracket => match self.peek() {
+                Token::Number(_) | Token::Colon => {
+                    let index = self.index_expression()?;
+                    self.project_if_slice(left, index)
+                }
+                _ => {
+                    self.expect(Token::Star)?;
+                    self.expect(Token::RBracket)?;
+                    let rhs = self.projection_rhs(Token::Star.binding_power())?;
+                    Ok(Expr::Projection(Box::new(left), Box::new(rhs)))
+                }
+            },
+            Token::LParen => {
+                let Expr::Field(name) = left else {
+                    return Err("only names can be called as functions".to_string());
+                };
+                let mut args = Vec::new();
+                while self.peek() != &Token::RParen {
+                    args.push(self.expression(0)?);
+                    if self.peek() == &Token::Comma {
+                        self.next();
+                    } else if self.peek() != &Token::RParen {
+                        return Err(format!(
+                            "expected ',' or ')', found {}",
+                            self.peek().describe()
+                        ));
+                    }
+                }
+                self.next();
+                check_arity(&name, args.len())?;
+                Ok(Expr::Function(name, args))
+            }
+            other => Err(format!("unexpected {}", other.describe())),
+        }
+    }
+
+    fn filter(&mut self, left: Expr) -> std::result::Result<Expr, String> {
+        let condition = self.expression(0)?;
+        self.expect(Token::RBracket)?;
+        let rhs = if self.peek() == &Token::Flatten {
+            Expr::Current
+        } else {
+            self.projection_rhs(Token::Filter.binding_power())?
+        };
+        Ok(Expr::FilterProjection(
+            Box::new(left),
+            Box::new(rhs),
+            Box::new(condition),
+        ))
+    }
+
+    fn index_expression(&mut self) -> std::result::Result<Expr, String> {
+        if self.peek() == &Token::Colon || self.peek_at(1) == &Token::Colon {
+            return self.slice();
+        }
+        let Token::Number(index) = self.next() else {
+            return Err("expected an index".to_string());
+        };
+        self.expect(Token::RBracket)?;
+        Ok(Expr::Index(index))
+    }
+
+    fn slice(&mut self) -> std::result::Result<Expr, String> {
+        let mut parts = [None; 3];
+        let mut part = 0;
+        loop {
+            match self.next() {
+                Token::RBracket => break,
+                Token::Colon if part < 2 => part += 1,
+                Token::Number(n) if parts[part].is_none() => parts[part] = Some(n),
+                other => return Err(format!("unexpected {} in slice", other.describe())),
+            }
+        }
+        if parts[2] == Some(0) {
+            return Err("slice step cannot be 0".to_string());
+        }
+        Ok(Expr::Slice(parts[0], parts[1], parts[2]))
+    }
+
+    fn project_if_slice(&mut self, left: Expr, index: Expr) -> std::result::Result<Expr, String> {
+        let is_slice = matches!(index, Expr::Slice(..));
+        let indexed = Expr::Subexpr(Box::new(left), Box::new(index));
+        if !is_slice {
+            return Ok(indexed);
+        }
+        let rhs = self.projection_rhs(Token::Star.binding_power())?;
+        Ok(Expr::Projection(Box::new(indexed), Box::new(rhs)))
+    }
+
+    fn projection_rhs(&mut self, binding_power: u8) -> std::result::Result<Expr, String> {
+        match self.peek() {
+            token if token.binding_power() < PROJECTION_STOP => Ok(Expr::Current),
+            Token::LBracket | Token::Filter => self.expression(binding_power),
+            Token::Dot => {
+                self.next();
+                self.dot_rhs(binding_power)
+            }
+            other => Err(format!("unexpected {} after projection", other.describe())),
+        }
+    }
+
+    fn dot_rhs(&mut self, binding_power: u8) -> std::result::Result<Expr, String> {
+        match self.peek() {
+            Token::Identifier(_) | Token::QuotedIdentifier(_) | Token::Star => {
+                self.expression(binding_power)
+            }
+            Token::LBracket => {
+                self.next();
+                self.multi_list()
+            }
+            Token::LBrace => {
+                self.next();
+                self.multi_hash()
+            }
+            other => Err(format!("unexpected {} after '.'", other.describe())),
+        }
+    }
+
+    fn multi_list(&mut self) -> std::result::Result<Expr, String> {
+        let mut items = vec![self.expression(0)?];
+        while self.peek() == &Token::Comma {
+            self.next();
+            items.push(self.expression(0)?);
+        }
+        self.expect(Token::RBracket)?;
+        Ok(Expr::MultiList(items))
+    }
+
+    fn multi_hash(&mut self) -> std::result::Result<Expr, String> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match self.next() {
+                Token::Identifier(key) | Token::QuotedIdentifier(key) => key,
+                other => return Err(format!("expected a key, found {}", other.describe())),
+            };
+            self.expect(Token::Colon)?;
+            pairs.push((key, self.expression(0)?));
+            match self.next() {
+                Token::Comma => continue,
+                Token::RBrace => break,
+                other => return Err(format!("expected ',' or '}}', found {}", other.describe())),
+            }
+        }
+        Ok(Expr::MultiHash(pairs))
+    }
+}
+
+const FUNCTIONS: &[(&str, usize)] = &[
+    ("length", 1),
+    ("keys", 1),
+    ("values", 1),
+    ("sort", 1),
+    ("reverse", 1),
+    ("min", 1),
+    ("max", 1),
+    ("sum", 1),
+    ("type", 1),
+    ("to_string", 1),
+    ("to_number", 1),
+    ("join", 2),
+    ("contains", 2),
+    ("starts_with", 2),
+    ("ends_with", 2),
+];
+
+fn check_arity(name: &str, count: usize) -> std::result::Result<(), String> {
+    match FUNCTIONS.iter().find(|(known, _)| *known == name) {
+        Some((_, arity)) if *arity == count => Ok(()),
+        Some((_, arity)) => Err(format!(
+            "{}() takes {} argument(s), got {}",
+            name, arity, count
+        )),
+        None => Err(format!("unknown function {}()", name)),
+    }
+}
+
+fn eval(expr: &Expr, value: &Value) -> std::result::Result<Value, String> {
+    Ok(match expr {
+        Expr::Current => value.clone(),
+        Expr::Field(name) => value.get(name).cloned().unwrap_or(Value::Null),
+        Expr::Index(index) => match value {
+            Value::Array(items) => resolve_index(*index, items.len())
+                .and_then(|i| items.get(i))
+                .cloned()
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        },
+        Expr::Slice(start, stop, step) => match value {
+            Value::Array(items) => Value::Array(slice(items, *start, *stop, *step)),
+            _ => Value::Null,
+        },
+        Expr::Literal(literal) => literal.clone(),
+        Expr::Subexpr(left, right) | Expr::Pipe(left, right) => eval(right, &eval(left, value)?)?,
+        Expr::Projection(left, right) => match eval(left, value)? {
+            Value::Array(items) => project(items.iter(), right)?,
+            _ => Value::Null,
+        },
+        Expr::ValueProjection(left, right) => match eval(left, value)? {
+            Value::Object(map) => project(map.values(), right)?,
+            _ => Value::Null,
+        },
+        Expr::FilterProjection(left, right, condition) => match eval(left, value)? {
+            Value::Array(items) => {
+                let mut kept = Vec::new();
+                for item in items {
+                    if is_truthy(&eval(condition, &item)?) {
+                        kept.push(item);
+                    }
+                }
+                project(kept.iter(), right)?
+            }
+            _ => Value::Null,
+        },
+        Expr::Flatten(inner) => match eval(inner, value)? {
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .flat_map(|item| match item {
+                        Value::Array(nested) => nested,
+                        other => vec![other],
+                    })
+                    .collect(),
+            ),
+            _ => Value::Null,
+        },
+        Expr::MultiList(items) => {
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            Value::Array(
+                items
+                    .iter()
+                    .map(|item| eval(item, value))
+                    .collect::<std::result::Result<_, _>>()?,
+            )
+        }
+        Expr::MultiHash(pairs) => {
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            let mut map = Map::new();
+            for (key, item) in pairs {
+                map.insert(key.clone(), eval(item, value)?);
+            }
+            Value::Object(map)
+        }
+        Expr::Compare(op, left, right) => compare(*op, &eval(left, value)?, &eval(right, value)?),
+        Expr::Or(left, right) => {
+            let left = eval(left, value)?;
+            if is_truthy(&left) {
+                left
+            } else {
+                eval(right, value)?
+            }
+        }
+        Expr::And(left, right) => {
+            let left = eval(left, value)?;
+            if is_truthy(&left) {
+                eval(right, value)?
+            } else {
+                left
+            }
+        }
+        Expr::Not(inner) => Value::Bool(!is_truthy(&eval(inner, value)?)),
+        Expr::Function(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, value))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            call_function(name, &args)?
+        }
+    })
+}
+
+fn project<'a>(
+    items: impl Iterator<Item = &'a Value>,
+    right: &Expr,
+) -> std::result::Result<Value, String> {
+    let mut projected = Vec::new();
+    for item in items {
+        let value = eval(right, item)?;
+        if !value.is_null() {
+            projected.push(value);
+        }
+    }
+    Ok(Value::Array(projected))
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let len = i64::try_from(len).ok()?;
+    let resolved = if index < 0 { len + index } else { index };
+    usize::try_from(resolved).ok().filter(|i| (*i as i64) < len)
+}
+
+fn slice(items: &[Value], start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> Vec<Value> {
+    let len = items.len() as i64;
+    let step = step.unwrap_or(1);
+    let clamp = |bound: i64, low: i64, high: i64| {
+        let bound = if bound < 0 { bound + len } else { bound };
+        bound.clamp(low, high)
+    };
+
+    let mut picked = Vec::new();
+    if step > 0 {
+        let mut i = start.map_or(0, |s| clamp(s, 0, len));
+        let stop = stop.map_or(len, |s| clamp(s, 0, len));
+        while i < stop {
+            picked.push(items[i as usize].clone());
+            i += step;
+        }
+    } else {
+        let mut i = start.map_or(len - 1, |s| clamp(s, -1, len - 1));
+        let stop = stop.map_or(-1, |s| clamp(s, -1, len - 1));
+        while i > stop {
+            picked.push(items[i as usize].clone());
+            i += step;
+        }
+    }
+    picked
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        Value::Number(_) => true,
+    }
+}
+
+fn compare(op: Comparator, left: &Value, right: &Value) -> Value {
+    match op {
+        Comparator::Eq => Value::Bool(left == right || numbers_equal(left, right)),
+        Comparator::Ne => Value::Bool(left != right && !numbers_equal(left, right)),
+        _ => {
+            let (Some(l), Some(r)) = (left.as_f64(), right.as_f64()) else {
+                return Value::Null;
+            };
+            Value::Bool(match op {
+                Comparator::Lt => l < r,
+                Comparator::Le => l <= r,
+                Comparator::Gt => l > r,
+                _ => l >= r,
+            })
+        }
+    }
+}
+
+/// `1` and `1.0` compare equal
+fn numbers_equal(left: &Value, right: &Value) -> bool {
+    matches!((left.as_f64(), right.as_f64()), (Some(l), Some(r)) if l == r)
+}
+
+fn call_function(name: &str, args: &[Value]) -> std::result::Result<Value, String> {
+    let type_error = |expected: &str| {
+        format!(
+            "{}() expects {}, got {}",
+            name,
+            expected,
+            crate::assertions::type_name(&args[0])
+        )
+    };
+
+    Ok(match (name, args) {
+        ("length", [Value::String(s)]) => Value::from(s.chars().count()),
+        ("length", [Value::Array(items)]) => Value::from(items.len()),
+        ("length", [Value::Object(map)]) => Value::from(map.len()),
+        ("length", _) => return Err(type_error("a string, array or object")),
+        ("keys", [Value::Object(map)]) => {
+            Value::Array(map.keys().cloned().map(Value::String).collect())
+        }
+        ("values", [Value::Object(map)]) => Value::Array(map.values().cloned().collect()),
+        ("keys" | "values", _) => return Err(type_error("an object")),
+        ("sort", [Value::Array(items)]) => {
+            let mut sorted = items.clone();
+            if sorted.iter().all(Value::is_string) {
+                sorted.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+            } else if sorted.iter().all(Value::is_number) {
+                sorted.sort_by(|a, b| a.as_f64().partial_cmp(&b.as_f64()).unwrap());
+            } else {
+                return Err(type_error("an array of strings or numbers"));
+            }
+            Value::Array(sorted)
+        }
+        ("reverse", [Value::Array(items)]) => Value::Array(items.iter().rev().cloned().collect()),
+        ("reverse", [Value::String(s)]) => Value::String(s.chars().rev().collect()),
+        ("min" | "max", [Value::Array(items)]) => {
+            let pick_max = name == "max";
+            if items.iter().all(Value::is_number) {
+                items
+                    .iter()
+                    .cloned()
+                    .reduce(|a, b| {
+                        let b_wins = if pick_max {
+                            b.as_f64() > a.as_f64()
+                        } else {
+                            b.as_f64() < a.as_f64()
+                        };
+                        if b_wins {
+                            b
+                        } else {
+                            a
+                        }
+                    })
+                    .unwrap_or(Value::Null)
+            } else if items.iter().all(Value::is_string) {
+                let strings = items.iter().filter_map(Value::as_str);
+                let picked = if pick_max {
+                    strings.max()
+                } else {
+                    strings.min()
+                };
+                picked.map_or(Value::Null, |s| Value::String(s.to_string()))
+            } else {
+                return Err(type_error("an array of strings or numbers"));
+            }
+        }
+        ("sum", [Value::Array(items)]) if items.iter().all(Value::is_number) => {
+            if items.iter().all(|item| item.is_i64()) {
+                Value::from(items.iter().filter_map(Value::as_i64).sum::<i64>())
+            } else {
+                Value::from(items.iter().filter_map(Value::as_f64).sum::<f64>())
+            }
+        }
+        ("sort" | "reverse" | "min" | "max" | "sum", _) => {
+            return Err(type_error("an array"));
+        }
+        ("type", [value]) => Value::String(match value {
+            Value::Null => "null".to_string(),
+            Value::Bool(_) => "boolean".to_string(),
+            Value::Number(_) => "number".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::Array(_) => "array".to_string(),
+            Value::Object(_) => "object".to_string(),
+        }),
+        ("to_string", [Value::String(s)]) => Value::String(s.clone()),
+        ("to_string", [value]) => Value::String(value.to_string()),
+        ("to_number", [Value::Number(n)]) => Value::Number(n.clone()),
+        ("to_number", [Value::String(s)]) => serde_json::from_str::<serde_json::Number>(s.trim())
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        ("to_number", _) => Value::Null,
+        ("join", [Value::String(separator), Value::Array(items)])
+            if items.iter().all(Value::is_string) =>
+        {
+            Value::String(
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            )
+        }
+        ("join", _) => return Err(type_error("a separator string and an array of strings")),
+        ("contains", [Value::String(s), Value::String(needle)]) => {
+            Value::Bool(s.contains(needle.as_str()))
+        }
+        ("contains", [Value::Array(items), needle]) => Value::Bool(items.contains(needle)),
+        ("contains", _) => return Err(type_error("a string or array")),
+        ("starts_with", [Value::String(s), Value::String(prefix)]) => {
+            Value::Bool(s.starts_with(prefix.as_str()))
+        }
+        ("ends_with", [Value::String(s), Value::String(suffix)]) => {
+            Value::Bool(s.ends_with(suffix.as_str()))
+        }
+        ("starts_with" | "ends_with", _) => return Err(type_error("two strings")),
+        _ => return Err(format!("unknown function {}()", name)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "data": [
+                {"name": "ann", "age": 31, "tags": ["a", "b"], "active": true},
+                {"name": "bob", "age": 25, "tags": ["c"], "active": false},
+                {"name": "cy", "age": 40, "tags": [], "active": true}
+            ],
+            "meta": {"total": 3, "next page": "p2"}
+        })
+    }
+
+    fn search(expr: &str) -> Value {
+        Query::parse(expr).unwrap().search(&sample()).unwrap()
+    }
+
+    #[test]
+    fn fields_indexes_and_slices() {
+        assert_eq!(search("meta.total"), json!(3));
+        assert_eq!(search("meta.\"next page\""), json!("p2"));
+        assert_eq!(search("data[-1].name"), json!("cy"));
+        assert_eq!(search("data[0:2].name"), json!(["ann", "bob"]));
+        assert_eq!(search("data[::-1].age"), json!([40, 25, 31]));
+        assert_eq!(search("missing.field"), Value::Null);
+        assert_eq!(search(".meta.total"), json!(3));
+        assert_eq!(search("."), sample());
+    }
+
+    #[test]
+    fn projections_flatten_and_pipes() {
+        assert_eq!(search("data[].name"), json!(["ann", "bob", "cy"]));
+        assert_eq!(search("data[*].tags[]"), json!(["a", "b", "c"]));
+        assert_eq!(search("length(meta.*)"), json!(2));
+        assert_eq!(search("*.total"), json!([3]));
+        assert_eq!(search("data[].name | [0]"), json!("ann"));
+        assert_eq!(search("data[].name[0]"), json!([]));
+    }
+
+    #[test]
+    fn filters_multiselect_and_functions() {
+        assert_eq!(search("data[?age > `30`].name"), json!(["ann", "cy"]));
+        assert_eq!(search("data[?active && age < 35].name"), json!(["ann"]));
+        assert_eq!(search("data[?name == 'bob'] | [0].age"), json!(25));
+        assert_eq!(search("data[?!active].name"), json!(["bob"]));
+        assert_eq!(
+            search("data[0].{who: name, n: length(tags)}"),
+            json!({"who": "ann", "n": 2})
+        );
+        assert_eq!(search("data[1].[name, age]"), json!(["bob", 25]));
+        assert_eq!(search("length(data)"), json!(3));
+        assert_eq!(search("sort(data[].age)"), json!([25, 31, 40]));
+        assert_eq!(search("join(', ', data[].name)"), json!("ann, bob, cy"));
+        assert_eq!(search("max(data[].age)"), json!(40));
+
+        let keys = |expr: &str| Query::parse(expr).unwrap().selected_keys();
+        assert_eq!(
+            keys("data[?active].{who: name, n: age}"),
+            Some(vec!["who".to_string(), "n".to_string()])
+        );
+        assert_eq!(keys("data[].name"), None);
+    }
+
+    #[test]
+    fn errors_are_invalid_arguments() {
+        for expr in ["data[", "data..name", "nope(data)", "length(a, b)", "'open"] {
+            let err = Query::parse(expr).unwrap_err();
+            assert!(
+                matches!(err, UxcError::InvalidArguments(_)),
+                "{}: {}",
+                expr,
+                err
+            );
+        }
+        let err = Query::parse("length(meta.total)")
+            .unwrap()
+            .search(&sample())
+            .unwrap_err();
+        assert!(err.to_string().contains("length() expects"));
+    }
+}
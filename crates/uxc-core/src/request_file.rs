@@ -0,0 +1,651 @@
+//! Request files (`uxc run`)
+//!
+//! A request file is a YAML or JSON document listing calls: endpoint,
+//! operation and arguments, like a command line each. The calls run in order,
+//! or several at a time with `concurrency`, and each one reports the envelope
+//! `uxc <endpoint> <operation>` would print.
+//!
+//! A call can name a reference file with its `expected` response data. The
+//! call then fails when its result differs, and the differences are reported
+//! (see [`crate::diff`]). It can also `expect` a status, values, a schema or
+//! a latency like a test case (see [`Expectation`]); `uxc test` runs such a
+//! file as a contract test.
+//!
+//! Request files are also workflows. Arguments can refer to the outcome of
+//! earlier requests by name, `${steps.login.data.token}` (see [`Scope`]); a
+//! request runs only `if` its condition holds, once per item of a `for_each`
+//! array, and with its own number of `retries`. A file whose requests refer to
+//! each other runs them one after the other.
+
+use crate::assertions;
+use crate::batch::{BatchSummary, ItemError, TIMEOUT_CODE};
+use crate::diff::{self, Change};
+use crate::error::UxcError;
+use crate::output::OutputEnvelope;
+use crate::suite::{CallOutcome, CaseReport, Expectation, SuiteReport};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Request file definition.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestFile {
+    /// Name of the run (defaults to the file stem)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Default endpoint for requests that do not set their own
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Requests run at the same time (default: 1, one after the other)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+
+    /// Requests, reported in this order
+    #[serde(default, alias = "steps")]
+    pub requests: Vec<Request>,
+
+    /// Directory `expected` paths are relative to
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+/// One call of a request file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    /// Name in the report (defaults to the operation)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    pub operation: String,
+
+    #[serde(default)]
+    pub args: Map<String, Value>,
+
+    /// JSON or YAML file with the expected response data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+
+    /// Checks on the outcome; without them the call must succeed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expect: Option<Expectation>,
+
+    /// Run only when this holds, e.g. `${steps.login.ok}` or
+    /// `${steps.job.data.state} != done`
+    #[serde(default, rename = "if", skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
+    /// Run once per item of this array, or of the array a reference such as
+    /// `${steps.list.data}` points to, with `${loop.item}` and `${loop.index}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub for_each: Option<Value>,
+
+    /// Retries of this request, instead of `--retries`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl Request {
+    /// Name of the request in the report
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.operation)
+    }
+
+    /// Whether the request refers to the outcome of other requests
+    pub fn refers_to_steps(&self) -> bool {
+        let uses = |text: &str| text.contains("${steps.");
+        self.condition.as_deref().is_some_and(uses)
+            || self
+                .for_each
+                .as_ref()
+                .is_some_and(|items| uses(&items.to_string()))
+            || uses(&Value::Object(self.args.clone()).to_string())
+    }
+}
+
+/// Outcomes of the requests run so far, and the current `for_each` item.
+///
+/// `${steps.<name>.ok}`, `${steps.<name>.data...}` and
+/// `${steps.<name>.error...}` refer to a request by name, `${loop.item...}`
+/// and `${loop.index}` to the current item. A value that is a single
+/// reference becomes the value referred to; references within a longer text
+/// are replaced by the value as text. Other `${...}` are left alone.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    root: Value,
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self {
+            root: serde_json::json!({ "steps": {} }),
+        }
+    }
+}
+
+impl Scope {
+    /// Record the outcome of request `name`
+    pub fn record(&mut self, name: &str, outcome: Value) {
+        self.root["steps"][name] = outcome;
+    }
+
+    /// Scope for item `index` of a `for_each`
+    pub fn with_item(&self, index: usize, item: Value) -> Scope {
+        let mut scope = self.clone();
+        scope.root["loop"] = serde_json::json!({ "index": index, "item": item });
+        scope
+    }
+
+    /// `value` with its references replaced
+    pub fn resolve(&self, value: &Value) -> std::result::Result<Value, String> {
+        Ok(match value {
+            Value::String(text) => self.resolve_text(text)?,
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.resolve(item))
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| Ok((key.clone(), self.resolve(value)?)))
+                    .collect::<std::result::Result<_, String>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+
+    /// Items of a `for_each`
+    pub fn items(&self, for_each: &Value) -> std::result::Result<Vec<Value>, String> {
+        match self.resolve(for_each)? {
+            Value::Array(items) => Ok(items),
+            other => Err(format!("for_each must be an array, got {}", other)),
+        }
+    }
+
+    /// Whether an `if` condition holds: `a == b` and `a != b` compare the
+    /// two sides as text, anything else must be truthy (not `false`, `null`,
+    /// `0`, empty, `"false"` or `"0"`)
+    pub fn condition(&self, condition: &str) -> std::result::Result<bool, String> {
+        for (operator, equal) in [(" == ", true), (" != ", false)] {
+            if let Some((left, right)) = condition.split_once(operator) {
+                let (left, right) = (self.text(left.trim())?, self.text(right.trim())?);
+                return Ok((left == right) == equal);
+            }
+        }
+        Ok(match self.resolve_text(condition.trim())? {
+            Value::Null => false,
+            Value::Bool(value) => value,
+            Value::Number(number) => number.as_f64() != Some(0.0),
+            Value::String(text) => !matches!(text.as_str(), "" | "false" | "0"),
+            Value::Array(items) => !items.is_empty(),
+            Value::Object(map) => !map.is_empty(),
+        })
+    }
+
+    fn text(&self, text: &str) -> std::result::Result<String, String> {
+        Ok(match self.resolve_text(text)? {
+            Value::String(text) => text,
+            other => other.to_string(),
+        })
+    }
+
+    fn resolve_text(&self, text: &str) -> std::result::Result<Value, String> {
+        if let Some(expr) = text
+            .strip_prefix("${")
+            .and_then(|rest| rest.strip_suffix('}'))
+            .filter(|expr| is_reference(expr) && !expr.contains('}'))
+        {
+            return self.lookup(expr).cloned();
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find('}') else {
+                break;
+            };
+            output.push_str(&rest[..start]);
+            let expr = &after[..end];
+            if !is_reference(expr) {
+                output.push_str(&rest[start..start + end + 3]);
+            } else {
+                match self.lookup(expr)? {
+                    Value::String(value) => output.push_str(value),
+                    value => output.push_str(&value.to_string()),
+                }
+            }
+            rest = &after[end + 1..];
+        }
+        output.push_str(rest);
+        Ok(Value::String(output))
+    }
+
+    fn lookup(&self, expr: &str) -> std::result::Result<&Value, String> {
+        assertions::lookup_path(&self.root, expr)
+            .ok_or_else(|| format!("'${{{}}}' refers to nothing", expr))
+    }
+}
+
+fn is_reference(expr: &str) -> bool {
+    expr.starts_with("steps.") || expr.starts_with("loop.")
+}
+
+impl RequestFile {
+    /// Load a request file from a `.json`, `.yaml` or `.yml` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut file: RequestFile = read_document(path, "request file")?;
+        if file.name.is_none() {
+            file.name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(ToString::to_string);
+        }
+        file.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(file)
+    }
+
+    /// Endpoint for a request, falling back to the file default.
+    pub fn endpoint_for(&self, request: &Request) -> Result<String> {
+        request
+            .endpoint
+            .clone()
+            .or_else(|| self.endpoint.clone())
+            .ok_or_else(|| {
+                UxcError::InvalidArguments(format!(
+                    "Request '{}' has no endpoint and the file defines no default",
+                    request.name()
+                ))
+                .into()
+            })
+    }
+
+    /// Expected response data of a request, read from its reference file.
+    pub fn expected_for(&self, request: &Request) -> Result<Option<Value>> {
+        request
+            .expected
+            .as_ref()
+            .map(|path| read_document(&self.base_dir.join(path), "expected output"))
+            .transpose()
+    }
+}
+
+/// Read a JSON document, or a YAML one unless the extension says `.json`
+fn read_document<T: serde::de::DeserializeOwned>(path: &Path, what: &str) -> Result<T> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        UxcError::InvalidArguments(format!("Failed to read {} {}: {}", what, path.display(), e))
+    })?;
+    let is_json = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let parsed = if is_json {
+        serde_json::from_str(&content).map_err(|e| e.to_string())
+    } else {
+        serde_yaml::from_str(&content).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|e| {
+        UxcError::InvalidArguments(format!("Invalid {} {}: {}", what, path.display(), e)).into()
+    })
+}
+
+/// Outcome of one request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestReport {
+    pub name: String,
+    pub endpoint: String,
+    pub operation: String,
+    /// The call met its `expect` checks, or succeeded without them, and
+    /// matched its expected output
+    pub ok: bool,
+    pub duration_ms: u64,
+    /// Calls made, including retries
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    /// Not run: its `if` did not hold, or an earlier request failed under
+    /// `--fail-fast`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Envelope of the call, success or error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<OutputEnvelope>,
+    /// Differences between the expected output and the response data
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub differences: Vec<Change>,
+    /// Unmet `expect` checks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+impl RequestReport {
+    /// Report for a request that was not run
+    pub fn skipped(request: &Request, endpoint: &str) -> Self {
+        Self {
+            name: request.name().to_string(),
+            endpoint: endpoint.to_string(),
+            operation: request.operation.clone(),
+            skipped: true,
+            ..Default::default()
+        }
+    }
+
+    /// Report for a request that produced `envelope`, with HTTP `status`,
+    /// checked against its `expect` block and expected output
+    pub fn new(
+        request: &Request,
+        endpoint: &str,
+        envelope: OutputEnvelope,
+        status: Option<u16>,
+        expected: Option<&Value>,
+    ) -> Self {
+        let differences = match (expected, &envelope.data) {
+            (Some(expected), Some(data)) if envelope.ok => diff::diff(expected, data),
+            _ => Vec::new(),
+        };
+        let failures = request
+            .expect
+            .as_ref()
+            .map(|expect| expect.check(&outcome_of(&envelope, status)))
+            .unwrap_or_default();
+        let passed = match request.expect {
+            Some(_) => failures.is_empty(),
+            None => envelope.ok,
+        };
+        Self {
+            name: request.name().to_string(),
+            endpoint: endpoint.to_string(),
+            operation: request.operation.clone(),
+            ok: passed && differences.is_empty(),
+            duration_ms: envelope.meta.duration_ms.unwrap_or(0),
+            attempts: 1,
+            timed_out: envelope
+                .error
+                .as_ref()
+                .is_some_and(|error| error.code == TIMEOUT_CODE),
+            skipped: false,
+            envelope: Some(envelope),
+            differences,
+            failures,
+        }
+    }
+
+    /// The report as a test case, differences counted as failures
+    fn to_case_report(&self) -> CaseReport {
+        let error = self
+            .envelope
+            .as_ref()
+            .and_then(|envelope| envelope.error.as_ref())
+            .map(|error| ItemError {
+                code: error.code.clone(),
+                message: error.message.clone(),
+                status: None,
+            });
+        // A call that failed without an `expect` block has no failures of its own
+        let call_failed = error
+            .as_ref()
+            .filter(|_| !self.ok && self.failures.is_empty())
+            .map(|error| format!("call failed: {}: {}", error.code, error.message));
+        let failures = call_failed
+            .into_iter()
+            .chain(self.failures.iter().cloned())
+            .chain(
+                self.differences
+                    .iter()
+                    .map(|change| format!("expected output: {}", change)),
+            )
+            .collect();
+        CaseReport {
+            name: self.name.clone(),
+            endpoint: self.endpoint.clone(),
+            operation: self.operation.clone(),
+            passed: self.ok,
+            duration_ms: self.duration_ms,
+            attempts: self.attempts,
+            timed_out: self.timed_out,
+            skipped: self.skipped,
+            error,
+            failures,
+        }
+    }
+}
+
+/// The outcome an `expect` block is checked against
+fn outcome_of(envelope: &OutputEnvelope, status: Option<u16>) -> CallOutcome {
+    let duration_ms = envelope.meta.duration_ms.unwrap_or(0);
+    match &envelope.error {
+        Some(error) => CallOutcome::Failure {
+            code: error.code.clone(),
+            message: error.message.clone(),
+            duration_ms,
+            status,
+        },
+        None => CallOutcome::Success {
+            data: envelope.data.clone().unwrap_or(Value::Null),
+            duration_ms,
+            status,
+        },
+    }
+}
+
+/// Outcome of a whole request file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub name: String,
+    /// `failed` counts requests that ran and were not ok
+    #[serde(flatten)]
+    pub summary: BatchSummary,
+    pub duration_ms: u64,
+    pub requests: Vec<RequestReport>,
+}
+
+impl RunReport {
+    pub fn new(name: &str, requests: Vec<RequestReport>, duration_ms: u64) -> Self {
+        let succeeded = requests.iter().filter(|request| request.ok).count();
+        let skipped = requests.iter().filter(|request| request.skipped).count();
+        Self {
+            name: name.to_string(),
+            summary: BatchSummary {
+                total: requests.len(),
+                succeeded,
+                failed: requests.len() - succeeded - skipped,
+                timed_out: requests.iter().filter(|request| request.timed_out).count(),
+                skipped,
+            },
+            duration_ms,
+            requests,
+        }
+    }
+
+    /// The run as a test suite report, for `uxc test` and JUnit XML
+    pub fn to_suite_report(&self) -> SuiteReport {
+        let cases = self
+            .requests
+            .iter()
+            .map(RequestReport::to_case_report)
+            .collect();
+        SuiteReport::new(&self.name, cases, self.duration_ms)
+    }
+}
+
+/// Whether the document at `path` is a request file rather than a test suite:
+/// it lists `requests` or `steps`, not `cases`
+pub fn is_request_file(path: &Path) -> bool {
+    read_document::<Value>(path, "request file").is_ok_and(|document| {
+        document.get("cases").is_none()
+            && (document.get("requests").is_some() || document.get("steps").is_some())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn requests_are_checked_against_their_expected_output() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("pets.yaml"), "- name: Rex\n- name: Tom\n").unwrap();
+        let path = dir.path().join("smoke.yaml");
+        std::fs::write(
+            &path,
+            r#"
+endpoint: https://petstore.example.com
+requests:
+  - operation: get:/pets
+    expected: pets.yaml
+  - name: create
+    endpoint: https://other.example.com
+    operation: post:/pets
+    args: { name: Rex }
+"#,
+        )
+        .unwrap();
+        let file = RequestFile::load(&path).unwrap();
+        assert_eq!(file.name.as_deref(), Some("smoke"));
+        let (list, create) = (&file.requests[0], &file.requests[1]);
+        assert_eq!(
+            file.endpoint_for(list).unwrap(),
+            "https://petstore.example.com"
+        );
+        assert_eq!(
+            file.endpoint_for(create).unwrap(),
+            "https://other.example.com"
+        );
+        assert_eq!(file.expected_for(create).unwrap(), None);
+
+        let expected = file.expected_for(list).unwrap();
+        let envelope =
+            |data| OutputEnvelope::success("call_result", "openapi", "x", None, data, None);
+        let report = RequestReport::new(
+            list,
+            "x",
+            envelope(json!([{ "name": "Rex" }, { "name": "Tom" }])),
+            Some(200),
+            expected.as_ref(),
+        );
+        assert!(report.ok);
+        let report = RequestReport::new(
+            list,
+            "x",
+            envelope(json!([{ "name": "Rex" }])),
+            Some(200),
+            expected.as_ref(),
+        );
+        assert!(!report.ok);
+        assert_eq!(report.differences[0].path, "/1");
+
+        let summary = RunReport::new(
+            "smoke",
+            vec![report, RequestReport::skipped(create, "x")],
+            5,
+        )
+        .summary;
+        assert_eq!((summary.failed, summary.skipped), (1, 1));
+    }
+
+    #[test]
+    fn expect_blocks_decide_whether_a_request_passed() {
+        let request: Request = serde_yaml::from_str(
+            r#"
+name: missing
+operation: get:/pets/9
+expect:
+  status: 404
+  max_duration_ms: 100
+"#,
+        )
+        .unwrap();
+        let mut envelope = OutputEnvelope::error("EXECUTION_FAILED", "HTTP 404");
+        envelope.meta.duration_ms = Some(3);
+        let report = RequestReport::new(&request, "x", envelope.clone(), Some(404), None);
+        assert!(report.ok, "{:?}", report.failures);
+
+        envelope.meta.duration_ms = Some(250);
+        let report = RequestReport::new(&request, "x", envelope, Some(500), None);
+        assert!(!report.ok);
+        assert_eq!(
+            report.failures,
+            vec![
+                "expected status 404 but got 500",
+                "took 250ms, expected at most 100ms"
+            ]
+        );
+
+        let suite = RunReport::new("contract", vec![report], 250).to_suite_report();
+        assert_eq!((suite.total, suite.failed), (1, 1));
+        assert_eq!(
+            suite.cases[0].error.as_ref().unwrap().code,
+            "EXECUTION_FAILED"
+        );
+        assert!(suite
+            .to_junit_xml()
+            .contains("expected status 404 but got 500"));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("contract.yaml");
+        std::fs::write(&path, "steps:\n  - operation: get:/pets\n").unwrap();
+        assert!(is_request_file(&path));
+        std::fs::write(&path, "cases:\n  - name: a\n    operation: get:/pets\n").unwrap();
+        assert!(!is_request_file(&path));
+    }
+
+    #[test]
+    fn references_resolve_against_earlier_steps() {
+        let mut scope = Scope::default();
+        scope.record(
+            "login",
+            json!({ "ok": true, "data": { "token": "t0k", "user": { "id": 7 } } }),
+        );
+        scope.record(
+            "list",
+            json!({ "ok": true, "data": [{ "id": 1 }, { "id": 2 }] }),
+        );
+
+        let args = json!({
+            "token": "${steps.login.data.token}",
+            "owner": "${steps.login.data.user.id}",
+            "header": "Bearer ${steps.login.data.token} for ${steps.login.data.user.id}",
+            "home": "${HOME}"
+        });
+        assert_eq!(
+            scope.resolve(&args).unwrap(),
+            json!({
+                "token": "t0k",
+                "owner": 7,
+                "header": "Bearer t0k for 7",
+                "home": "${HOME}"
+            })
+        );
+        assert_eq!(
+            scope.resolve(&json!("${steps.logout.ok}")).unwrap_err(),
+            "'${steps.logout.ok}' refers to nothing"
+        );
+
+        let items = scope.items(&json!("${steps.list.data}")).unwrap();
+        assert_eq!(items.len(), 2);
+        let item = scope.with_item(1, items[1].clone());
+        assert_eq!(
+            item.resolve(&json!("${loop.item.id}/${loop.index}"))
+                .unwrap(),
+            json!("2/1")
+        );
+        assert!(scope.items(&json!("${steps.login.data}")).is_err());
+
+        assert!(scope.condition("${steps.login.ok}").unwrap());
+        assert!(scope.condition("${steps.login.data.user.id} == 7").unwrap());
+        assert!(!scope.condition("${steps.login.data.token} != t0k").unwrap());
+        assert!(item.condition("${loop.index}").unwrap());
+        assert!(!scope
+            .with_item(0, json!(null))
+            .condition("${loop.item}")
+            .unwrap());
+    }
+}
@@ -0,0 +1,335 @@
+//! Pagination resume tokens
+//!
+//! `--paginate` runs record the page count and the arguments that select the
+//! next page (the cursor or page number, with registered secrets masked)
+//! under `~/.uxc/resume` after every page, one JSON file per operation key,
+//! readable by the owner only. A rerun with `--resume` continues where an
+//! interrupted run stopped instead of refetching every page; it returns the
+//! items of the pages it fetches. Tokens expire after [`DEFAULT_RESUME_TTL`]
+//! and are removed once a run completes.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_RESUME_DIR: &str = ".uxc/resume";
+
+/// How long a resume token stays usable, in seconds (24 hours)
+pub const DEFAULT_RESUME_TTL: u64 = 86400;
+
+/// Where a paginated run stopped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeToken {
+    pub key: String,
+    pub endpoint: String,
+    pub operation: String,
+
+    /// Arguments of the page to request next that differ from the run's
+    /// own arguments
+    pub next: Value,
+
+    /// Pages already fetched before the interruption
+    pub pages: u64,
+
+    /// Seconds since the Unix epoch
+    pub updated_at: u64,
+    pub expires_at: u64,
+}
+
+impl ResumeToken {
+    /// Token of the run of `operation` with `args`, whose next page is
+    /// requested with `next`
+    pub fn new(
+        endpoint: &str,
+        operation: &str,
+        args: &Value,
+        next: &Value,
+        pages: u64,
+        ttl: u64,
+    ) -> Self {
+        let now = now();
+        let changed = match (args, next) {
+            (Value::Object(args), Value::Object(next)) => Value::Object(
+                next.iter()
+                    .filter(|(name, value)| args.get(*name) != Some(value))
+                    .map(|(name, value)| (name.clone(), masked(value)))
+                    .collect(),
+            ),
+            (_, next) => masked(next),
+        };
+        Self {
+            key: resume_key(endpoint, operation, args),
+            endpoint: endpoint.to_string(),
+            operation: operation.to_string(),
+            next: changed,
+            pages,
+            updated_at: now,
+            expires_at: now + ttl,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        now() >= self.expires_at
+    }
+}
+
+/// `value` with registered secrets masked
+fn masked(value: &Value) -> Value {
+    let text = crate::masking::mask(&value.to_string());
+    serde_json::from_str(&text).unwrap_or(Value::Null)
+}
+
+/// Key identifying one paginated run: the endpoint, operation and arguments.
+///
+/// Arguments are part of the key so a rerun with different filters starts
+/// from the first page. Keys name files, so they are a SHA-256 digest, which
+/// stays the same across builds.
+pub fn resume_key(endpoint: &str, operation: &str, args: &Value) -> String {
+    let mut hasher = Sha256::new();
+    for part in [endpoint, operation, &args.to_string()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Resume tokens stored as JSON files in one directory.
+pub struct ResumeStore {
+    dir: PathBuf,
+}
+
+impl ResumeStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Store under `~/.uxc/resume`.
+    pub fn open_default() -> Result<Self> {
+        let home = home_dir().context("Could not determine home directory")?;
+        Ok(Self::new(home.join(DEFAULT_RESUME_DIR)))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Persist a token, replacing any previous one for the same key.
+    ///
+    /// Written to a temporary file and renamed into place, so an interruption
+    /// mid-write leaves the previous token intact.
+    pub fn save(&self, token: &ResumeToken) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create resume directory: {:?}", self.dir))?;
+
+        let path = self.path(&token.key);
+        let tmp = path.with_extension("json.tmp");
+        let contents =
+            serde_json::to_string_pretty(token).context("Failed to serialize resume token")?;
+        // A leftover temporary file would keep its permissions
+        let _ = fs::remove_file(&tmp);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&tmp)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .with_context(|| format!("Failed to write resume token: {:?}", tmp))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to write resume token: {:?}", path))
+    }
+
+    /// Load the token for `key`. Expired or unreadable tokens are discarded.
+    pub fn load(&self, key: &str) -> Result<Option<ResumeToken>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read resume token: {:?}", path))?;
+        match serde_json::from_str::<ResumeToken>(&contents) {
+            Ok(token) if !token.is_expired() => Ok(Some(token)),
+            _ => {
+                self.remove(key)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Forget the token for `key`, e.g. once the last page has been fetched.
+    pub fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove resume token: {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// Remove every expired token. Returns how many were removed.
+    pub fn prune(&self) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read resume directory: {:?}", self.dir))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let expired = fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<ResumeToken>(&contents).ok())
+                .is_none_or(|token| token.is_expired());
+            if expired {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove resume token: {:?}", path))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(user_profile));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn key_depends_on_operation_and_args() {
+        let args = json!({"status": "open"});
+        let key = resume_key("https://a.test", "get:/issues", &args);
+
+        assert_eq!(key, resume_key("https://a.test", "get:/issues", &args));
+        assert_eq!(
+            key,
+            "78c7d854e28c76f3773f201b96ff0a3a8d1bd10f6d8e42242c0a6e3bdd803803"
+        );
+        assert_ne!(key, resume_key("https://a.test", "get:/pulls", &args));
+        assert_ne!(
+            key,
+            resume_key(
+                "https://a.test",
+                "get:/issues",
+                &json!({"status": "closed"})
+            )
+        );
+    }
+
+    #[test]
+    fn save_load_and_remove() {
+        let dir = TempDir::new().unwrap();
+        let store = ResumeStore::new(dir.path().to_path_buf());
+        let token = ResumeToken::new(
+            "https://a.test",
+            "get:/issues",
+            &json!({}),
+            &json!({ "cursor": "cursor-3" }),
+            3,
+            DEFAULT_RESUME_TTL,
+        );
+
+        store.save(&token).unwrap();
+        assert_eq!(store.load(&token.key).unwrap(), Some(token.clone()));
+
+        store.remove(&token.key).unwrap();
+        assert_eq!(store.load(&token.key).unwrap(), None);
+    }
+
+    #[test]
+    fn tokens_keep_only_the_masked_next_page_arguments() {
+        crate::masking::register("resume-secret-cursor");
+        let dir = TempDir::new().unwrap();
+        let store = ResumeStore::new(dir.path().to_path_buf());
+        let args = json!({ "api_key": "k", "status": "open" });
+        let token = ResumeToken::new(
+            "https://a.test",
+            "get:/issues",
+            &args,
+            &json!({ "api_key": "k", "status": "open", "after": "resume-secret-cursor", "page": 2 }),
+            1,
+            DEFAULT_RESUME_TTL,
+        );
+        assert_eq!(token.next, json!({ "after": "***", "page": 2 }));
+
+        store.save(&token).unwrap();
+        let contents = fs::read_to_string(store.path(&token.key)).unwrap();
+        assert!(!contents.contains("api_key"), "{}", contents);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(store.path(&token.key))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn expired_tokens_are_discarded() {
+        let dir = TempDir::new().unwrap();
+        let store = ResumeStore::new(dir.path().to_path_buf());
+        let expired = ResumeToken::new(
+            "https://a.test",
+            "get:/a",
+            &json!({}),
+            &json!({ "page": 2 }),
+            1,
+            0,
+        );
+        let live = ResumeToken::new(
+            "https://a.test",
+            "get:/b",
+            &json!({}),
+            &json!({ "page": 2 }),
+            1,
+            DEFAULT_RESUME_TTL,
+        );
+        store.save(&expired).unwrap();
+        store.save(&live).unwrap();
+
+        assert_eq!(store.prune().unwrap(), 1);
+        assert_eq!(store.load(&expired.key).unwrap(), None);
+        assert!(store.load(&live.key).unwrap().is_some());
+    }
+}
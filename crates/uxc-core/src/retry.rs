@@ -0,0 +1,376 @@
+//! Timeouts and retries of calls
+//!
+//! Every call, whichever adapter makes it, runs through [`run`] or a
+//! [`crate::middleware::Pipeline`] holding the policy: each attempt is
+//! limited by [`RetryPolicy::timeout`], and failures worth repeating are
+//! retried up to [`RetryPolicy::retries`] times. Timeouts and network errors
+//! are always retried. Calls the endpoint rejected are retried when their
+//! status is in [`RetryPolicy::retry_on`], or, when that is empty, when the
+//! status says the call may succeed later (HTTP 429/503, gRPC `UNAVAILABLE`,
+//! see [`ErrorDetail::is_retriable`]).
+//!
+//! Attempts are spaced by an exponential backoff starting at
+//! [`RetryPolicy::backoff`], unless the endpoint asked for a delay with
+//! `Retry-After`. Either wait is capped at a minute, or at the backoff when
+//! that is longer.
+
+use crate::error::{ErrorDetail, UxcError};
+use crate::middleware::{Call, Middleware, Recovery};
+use std::future::Future;
+use std::time::Duration;
+
+/// gRPC status codes by name, as `--retry-on` takes them
+const GRPC_CODES: &[(&str, i32)] = &[
+    ("CANCELLED", 1),
+    ("UNKNOWN", 2),
+    ("INVALID_ARGUMENT", 3),
+    ("DEADLINE_EXCEEDED", 4),
+    ("NOT_FOUND", 5),
+    ("ALREADY_EXISTS", 6),
+    ("PERMISSION_DENIED", 7),
+    ("RESOURCE_EXHAUSTED", 8),
+    ("FAILED_PRECONDITION", 9),
+    ("ABORTED", 10),
+    ("OUT_OF_RANGE", 11),
+    ("UNIMPLEMENTED", 12),
+    ("INTERNAL", 13),
+    ("UNAVAILABLE", 14),
+    ("DATA_LOSS", 15),
+    ("UNAUTHENTICATED", 16),
+];
+
+/// Longest wait between two attempts, also for delays asked with
+/// `Retry-After`
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A rejection that is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryOn {
+    HttpStatus(u16),
+    GrpcCode(i32),
+}
+
+impl RetryOn {
+    fn matches(&self, detail: &ErrorDetail) -> bool {
+        match (self, detail) {
+            (RetryOn::HttpStatus(expected), ErrorDetail::Http { status, .. }) => expected == status,
+            (RetryOn::GrpcCode(expected), ErrorDetail::Grpc { code, .. }) => expected == code,
+            _ => false,
+        }
+    }
+}
+
+/// Parse `--retry-on`: comma-separated HTTP statuses (`429,503`) and gRPC
+/// codes by name (`UNAVAILABLE`) or number (`grpc:14`)
+pub fn parse_retry_on(spec: &str) -> Result<Vec<RetryOn>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let invalid = || {
+                format!(
+                    "invalid retry condition '{}' (expected an HTTP status such as 503, or a gRPC code such as UNAVAILABLE or grpc:14)",
+                    item
+                )
+            };
+            if let Some(code) = item.strip_prefix("grpc:") {
+                return match code.parse::<i32>() {
+                    Ok(code) if (1..=16).contains(&code) => Ok(RetryOn::GrpcCode(code)),
+                    _ => Err(invalid()),
+                };
+            }
+            if let Ok(status) = item.parse::<u16>() {
+                return match status {
+                    100..=599 => Ok(RetryOn::HttpStatus(status)),
+                    _ => Err(invalid()),
+                };
+            }
+            GRPC_CODES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(item))
+                .map(|(_, code)| RetryOn::GrpcCode(*code))
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// Timeout and retry settings of calls
+#[derive(Debug, Clone, Default)]
+pub struct RetryPolicy {
+    /// Limit for one attempt
+    pub timeout: Option<Duration>,
+
+    /// Extra attempts after a failure worth repeating
+    pub retries: u32,
+
+    /// Delay before the first retry, doubled for each further one
+    pub backoff: Duration,
+
+    /// Rejections that are retried; empty for the ones that say they are
+    /// temporary
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl RetryPolicy {
+    /// Whether a failed attempt is worth repeating
+    pub fn should_retry(&self, err: &anyhow::Error) -> bool {
+        err.chain()
+            .any(|cause| match cause.downcast_ref::<UxcError>() {
+                Some(UxcError::CallFailed { detail, .. }) if !self.retry_on.is_empty() => self
+                    .retry_on
+                    .iter()
+                    .any(|retry_on| retry_on.matches(detail)),
+                _ => is_transient_cause(cause),
+            })
+    }
+
+    /// How long to wait before attempt `attempt + 1`
+    fn delay(&self, attempt: u32, err: &anyhow::Error) -> Duration {
+        let requested = err.chain().find_map(|cause| match cause.downcast_ref() {
+            Some(UxcError::CallFailed {
+                detail:
+                    ErrorDetail::Http {
+                        retry_after: Some(secs),
+                        ..
+                    },
+                ..
+            }) => Some(Duration::from_secs(*secs)),
+            _ => None,
+        });
+        let longest = MAX_BACKOFF.max(self.backoff);
+        match requested {
+            Some(requested) if requested > longest => {
+                tracing::warn!(
+                    "Endpoint asked to retry in {}, waiting {} instead",
+                    format_limit(requested),
+                    format_limit(longest)
+                );
+                crate::explain::record(
+                    "retry",
+                    format!(
+                        "Retry-After of {} capped at {}",
+                        format_limit(requested),
+                        format_limit(longest)
+                    ),
+                );
+                longest
+            }
+            Some(requested) => requested,
+            None => self
+                .backoff
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                .min(longest),
+        }
+    }
+
+    /// Log that attempt `attempt` failed and is retried after `delay`
+    fn announce(&self, attempt: u32, delay: Duration, err: &anyhow::Error) {
+        tracing::info!(
+            "Attempt {} of {} failed, retrying in {}: {:#}",
+            attempt,
+            self.retries + 1,
+            format_limit(delay),
+            err
+        );
+        crate::instrumentation::retry(attempt, delay, err);
+        crate::explain::record(
+            "retry",
+            format!(
+                "attempt {} failed ({}); retried after {}",
+                attempt,
+                err,
+                format_limit(delay)
+            ),
+        );
+    }
+}
+
+/// The policy as middleware: it sets the timeout of each attempt and retries
+/// failed ones, like [`run`]
+#[async_trait::async_trait]
+impl Middleware for RetryPolicy {
+    async fn on_request(&self, call: &mut Call) -> anyhow::Result<()> {
+        if self.timeout.is_some() {
+            call.timeout = self.timeout;
+        }
+        Ok(())
+    }
+
+    async fn on_error(&self, call: &Call, err: anyhow::Error) -> Recovery {
+        if call.attempt > self.retries || !self.should_retry(&err) {
+            return Recovery::Fail(err);
+        }
+        let delay = self.delay(call.attempt, &err);
+        self.announce(call.attempt, delay, &err);
+        Recovery::Retry(delay)
+    }
+}
+
+fn is_transient_cause(cause: &(dyn std::error::Error + 'static)) -> bool {
+    let transient = match cause.downcast_ref::<UxcError>() {
+        Some(UxcError::Timeout(_) | UxcError::NetworkError(_)) => true,
+        Some(UxcError::CallFailed { detail, .. }) => detail.is_retriable(),
+        _ => false,
+    };
+    transient
+        || cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_timeout() || e.is_connect())
+}
+
+/// Whether an error is worth retrying: timeouts, network failures and calls
+/// the server rejected as temporary (HTTP 503, gRPC `UNAVAILABLE`, ...).
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain().any(is_transient_cause)
+}
+
+/// Outcome of [`run`], with the number of attempts made
+#[derive(Debug)]
+pub struct Retried<T> {
+    pub result: anyhow::Result<T>,
+    pub attempts: u32,
+}
+
+/// Run `call` under `policy`, logging each failed attempt
+pub async fn run<T, F, Fut>(policy: &RetryPolicy, mut call: F) -> Retried<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let outcome = match policy.timeout {
+            Some(limit) => tokio::time::timeout(limit, call())
+                .await
+                .unwrap_or_else(|_| Err(timed_out(limit))),
+            None => call().await,
+        };
+
+        match outcome {
+            Err(err) if attempts <= policy.retries && policy.should_retry(&err) => {
+                let delay = policy.delay(attempts, &err);
+                policy.announce(attempts, delay, &err);
+                tokio::time::sleep(delay).await;
+            }
+            result => return Retried { result, attempts },
+        }
+    }
+}
+
+/// Error of an attempt that ran out of time
+pub(crate) fn timed_out(limit: Duration) -> anyhow::Error {
+    UxcError::Timeout(format!("no result within {}", format_limit(limit))).into()
+}
+
+/// `limit` in whole seconds, or milliseconds when it has a fraction
+fn format_limit(limit: Duration) -> String {
+    if limit.subsec_millis() == 0 {
+        format!("{}s", limit.as_secs())
+    } else {
+        format!("{}ms", limit.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn rejected(detail: ErrorDetail) -> anyhow::Error {
+        UxcError::call_failed("rejected", detail).into()
+    }
+
+    fn grpc(code: i32) -> ErrorDetail {
+        ErrorDetail::Grpc {
+            code,
+            status: String::new(),
+            message: String::new(),
+            details: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn retry_conditions_parse() {
+        assert_eq!(
+            parse_retry_on("429, 503,unavailable,grpc:4"),
+            Ok(vec![
+                RetryOn::HttpStatus(429),
+                RetryOn::HttpStatus(503),
+                RetryOn::GrpcCode(14),
+                RetryOn::GrpcCode(4),
+            ])
+        );
+        for invalid in ["42", "grpc:0", "SOMETIMES"] {
+            let err = parse_retry_on(invalid).unwrap_err();
+            assert!(err.contains("invalid retry condition"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn retry_on_replaces_the_default_statuses() {
+        let default = RetryPolicy::default();
+        assert!(default.should_retry(&rejected(ErrorDetail::http(503, ""))));
+        assert!(!default.should_retry(&rejected(ErrorDetail::http(500, ""))));
+        assert!(default.should_retry(&UxcError::Timeout("slow".to_string()).into()));
+
+        let policy = RetryPolicy {
+            retry_on: parse_retry_on("500,INTERNAL").unwrap(),
+            ..Default::default()
+        };
+        assert!(policy.should_retry(&rejected(ErrorDetail::http(500, ""))));
+        assert!(!policy.should_retry(&rejected(ErrorDetail::http(503, ""))));
+        assert!(policy.should_retry(&rejected(grpc(13))));
+        assert!(!policy.should_retry(&rejected(grpc(14))));
+        assert!(policy.should_retry(&UxcError::Timeout("slow".to_string()).into()));
+    }
+
+    #[test]
+    fn backoff_doubles_unless_the_endpoint_asks_for_a_delay() {
+        let policy = RetryPolicy {
+            backoff: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let err = rejected(ErrorDetail::http(503, ""));
+        assert_eq!(policy.delay(1, &err), Duration::from_millis(100));
+        assert_eq!(policy.delay(3, &err), Duration::from_millis(400));
+        assert_eq!(policy.delay(30, &err), MAX_BACKOFF);
+
+        let err = rejected(ErrorDetail::http(429, "").with_retry_after(Some(7)));
+        assert_eq!(policy.delay(1, &err), Duration::from_secs(7));
+
+        let err = rejected(ErrorDetail::http(429, "").with_retry_after(Some(86400)));
+        assert_eq!(policy.delay(1, &err), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn attempts_stop_at_success_or_the_retry_limit() {
+        let policy = RetryPolicy {
+            timeout: Some(Duration::from_millis(20)),
+            retries: 2,
+            ..Default::default()
+        };
+        let calls = AtomicU32::new(0);
+        let retried: Retried<()> = run(&policy, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        })
+        .await;
+        assert_eq!(retried.attempts, 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(retried.result.unwrap_err().to_string().contains("20ms"));
+
+        let calls = AtomicU32::new(0);
+        let retried = run(&policy, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(rejected(ErrorDetail::http(503, "")))
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+        assert_eq!(retried.attempts, 2);
+        assert_eq!(retried.result.unwrap(), 42);
+    }
+}
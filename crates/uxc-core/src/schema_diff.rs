@@ -0,0 +1,539 @@
+//! Differences between two versions of an API (`uxc diff`)
+//!
+//! Both sides are normalized the same way whatever the protocol: every
+//! operation becomes the JSON Schema of its arguments (see
+//! [`crate::datagen::payload_schema`]) and its return type. Operations are
+//! matched by ID, arguments by name, nested fields by path (`owner.name`,
+//! `tags[]`).
+//!
+//! Each change is classified by what it does to existing clients: removing an
+//! operation or argument, adding a required argument, making an argument
+//! required, changing a type, dropping an enum value or changing the return
+//! type is breaking; the opposite changes are not.
+
+use crate::adapters::OperationDetail;
+use crate::datagen::payload_schema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Nesting beyond which argument schemas are not compared
+const MAX_DEPTH: usize = 8;
+
+/// Whether a change can break existing clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Breaking,
+    NonBreaking,
+}
+
+/// What changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    OperationAdded,
+    OperationRemoved,
+    ParameterAdded,
+    ParameterRemoved,
+    ParameterRequired,
+    ParameterOptional,
+    TypeChanged,
+    EnumValueAdded,
+    EnumValueRemoved,
+    ReturnTypeChanged,
+}
+
+/// One difference between the two versions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaChange {
+    pub severity: Severity,
+    pub kind: ChangeKind,
+    pub operation: String,
+
+    /// Argument path, for changes to arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+
+    pub message: String,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Breaking => "BREAKING",
+            Severity::NonBreaking => "ok",
+        };
+        write!(f, "{:<8}  {}: {}", severity, self.operation, self.message)
+    }
+}
+
+/// All differences, breaking ones first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub operations_added: usize,
+    pub operations_removed: usize,
+    pub operations_changed: usize,
+    pub breaking: usize,
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Compare the operations of the old and the new version of an API
+pub fn compare(before: &[OperationDetail], after: &[OperationDetail]) -> SchemaDiff {
+    let before: BTreeMap<&str, &OperationDetail> = before
+        .iter()
+        .map(|detail| (detail.operation_id.as_str(), detail))
+        .collect();
+    let after: BTreeMap<&str, &OperationDetail> = after
+        .iter()
+        .map(|detail| (detail.operation_id.as_str(), detail))
+        .collect();
+
+    let mut diff = SchemaDiff::default();
+    let mut changes = Vec::new();
+    for (id, old) in &before {
+        let Some(new) = after.get(id) else {
+            diff.operations_removed += 1;
+            changes.push(change(
+                Severity::Breaking,
+                ChangeKind::OperationRemoved,
+                id,
+                None,
+                "operation removed".to_string(),
+            ));
+            continue;
+        };
+        let found = compare_operation(id, old, new);
+        if !found.is_empty() {
+            diff.operations_changed += 1;
+            changes.extend(found);
+        }
+    }
+    for id in after.keys().filter(|id| !before.contains_key(*id)) {
+        diff.operations_added += 1;
+        changes.push(change(
+            Severity::NonBreaking,
+            ChangeKind::OperationAdded,
+            id,
+            None,
+            "operation added".to_string(),
+        ));
+    }
+
+    // Stable: within a severity, changes stay in operation order
+    changes.sort_by_key(|change| change.severity);
+    diff.breaking = changes
+        .iter()
+        .filter(|change| change.severity == Severity::Breaking)
+        .count();
+    diff.changes = changes;
+    diff
+}
+
+fn compare_operation(id: &str, old: &OperationDetail, new: &OperationDetail) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    compare_schema(
+        &mut changes,
+        id,
+        "",
+        &payload_schema(old),
+        &payload_schema(new),
+        0,
+    );
+    if old.return_type != new.return_type {
+        changes.push(change(
+            Severity::Breaking,
+            ChangeKind::ReturnTypeChanged,
+            id,
+            None,
+            format!(
+                "return type changed from {} to {}",
+                old.return_type.as_deref().unwrap_or("none"),
+                new.return_type.as_deref().unwrap_or("none")
+            ),
+        ));
+    }
+    changes
+}
+
+/// Compare the argument schemas at `path` (`""` for the arguments object)
+fn compare_schema(
+    changes: &mut Vec<SchemaChange>,
+    id: &str,
+    path: &str,
+    old: &Value,
+    new: &Value,
+    depth: usize,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let parameter = (!path.is_empty()).then(|| path.to_string());
+    let (old_type, new_type) = (type_of(old), type_of(new));
+    if old_type != new_type {
+        changes.push(change(
+            Severity::Breaking,
+            ChangeKind::TypeChanged,
+            id,
+            parameter,
+            format!(
+                "'{}' type changed from {} to {}",
+                display(path),
+                old_type,
+                new_type
+            ),
+        ));
+        return;
+    }
+
+    let (old_values, new_values) = (enum_values(old), enum_values(new));
+    if let (Some(old_values), Some(new_values)) = (&old_values, &new_values) {
+        for removed in old_values.difference(new_values) {
+            changes.push(change(
+                Severity::Breaking,
+                ChangeKind::EnumValueRemoved,
+                id,
+                parameter.clone(),
+                format!("'{}' no longer accepts {}", display(path), removed),
+            ));
+        }
+        for added in new_values.difference(old_values) {
+            changes.push(change(
+                Severity::NonBreaking,
+                ChangeKind::EnumValueAdded,
+                id,
+                parameter.clone(),
+                format!("'{}' also accepts {}", display(path), added),
+            ));
+        }
+    }
+
+    if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+        compare_schema(
+            changes,
+            id,
+            &format!("{}[]", path),
+            old_items,
+            new_items,
+            depth + 1,
+        );
+    }
+
+    let (old_properties, new_properties) = (properties(old), properties(new));
+    if old_properties.is_empty() && new_properties.is_empty() {
+        return;
+    }
+    let (old_required, new_required) = (required(old), required(new));
+    for (name, old_property) in &old_properties {
+        let field = join(path, name);
+        let Some(new_property) = new_properties.get(name) else {
+            changes.push(change(
+                Severity::Breaking,
+                ChangeKind::ParameterRemoved,
+                id,
+                Some(field.clone()),
+                format!("parameter '{}' removed", field),
+            ));
+            continue;
+        };
+        match (old_required.contains(name), new_required.contains(name)) {
+            (false, true) => changes.push(change(
+                Severity::Breaking,
+                ChangeKind::ParameterRequired,
+                id,
+                Some(field.clone()),
+                format!("parameter '{}' is now required", field),
+            )),
+            (true, false) => changes.push(change(
+                Severity::NonBreaking,
+                ChangeKind::ParameterOptional,
+                id,
+                Some(field.clone()),
+                format!("parameter '{}' is now optional", field),
+            )),
+            _ => {}
+        }
+        compare_schema(changes, id, &field, old_property, new_property, depth + 1);
+    }
+    for name in new_properties
+        .keys()
+        .filter(|name| !old_properties.contains_key(*name))
+    {
+        let field = join(path, name);
+        let (severity, message) = if new_required.contains(name) {
+            (
+                Severity::Breaking,
+                format!("required parameter '{}' added", field),
+            )
+        } else {
+            (
+                Severity::NonBreaking,
+                format!("optional parameter '{}' added", field),
+            )
+        };
+        changes.push(change(
+            severity,
+            ChangeKind::ParameterAdded,
+            id,
+            Some(field),
+            message,
+        ));
+    }
+}
+
+fn change(
+    severity: Severity,
+    kind: ChangeKind,
+    operation: &str,
+    parameter: Option<String>,
+    message: String,
+) -> SchemaChange {
+    SchemaChange {
+        severity,
+        kind,
+        operation: operation.to_string(),
+        parameter,
+        message,
+    }
+}
+
+/// Declared type of a schema: its `type`, the target of its `$ref`, or the
+/// shape its keywords imply
+fn type_of(schema: &Value) -> String {
+    match schema.get("type") {
+        Some(Value::String(name)) => return name.clone(),
+        Some(Value::Array(names)) => {
+            let names: BTreeSet<&str> = names.iter().filter_map(Value::as_str).collect();
+            return names.into_iter().collect::<Vec<_>>().join("|");
+        }
+        _ => {}
+    }
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if schema.get("properties").is_some() {
+        "object".to_string()
+    } else if schema.get("items").is_some() {
+        "array".to_string()
+    } else if schema.get("oneOf").is_some() || schema.get("anyOf").is_some() {
+        "union".to_string()
+    } else {
+        "any".to_string()
+    }
+}
+
+fn enum_values(schema: &Value) -> Option<BTreeSet<String>> {
+    let values = schema.get("enum")?.as_array()?;
+    Some(values.iter().map(Value::to_string).collect())
+}
+
+fn properties(schema: &Value) -> BTreeMap<String, Value> {
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, schema)| (name.clone(), schema.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn required(schema: &Value) -> BTreeSet<String> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name.as_str().map(ToString::to_string))
+        .collect()
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// How a path is named in messages; the arguments object itself is `arguments`
+fn display(path: &str) -> &str {
+    if path.is_empty() {
+        "arguments"
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::Parameter;
+    use serde_json::json;
+
+    fn operation(id: &str, input: Value, return_type: Option<&str>) -> OperationDetail {
+        OperationDetail {
+            operation_id: id.to_string(),
+            display_name: id.to_string(),
+            description: None,
+            parameters: Vec::new(),
+            return_type: return_type.map(ToString::to_string),
+            input_schema: Some(input),
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
+        }
+    }
+
+    fn kinds(diff: &SchemaDiff) -> Vec<(Severity, ChangeKind, Option<&str>)> {
+        diff.changes
+            .iter()
+            .map(|change| (change.severity, change.kind, change.parameter.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn operations_added_and_removed_are_classified() {
+        let before = [
+            operation("get:/a", json!({}), None),
+            operation("get:/b", json!({}), None),
+        ];
+        let after = [
+            operation("get:/b", json!({}), None),
+            operation("get:/c", json!({}), None),
+        ];
+
+        let diff = compare(&before, &after);
+
+        assert_eq!((diff.operations_added, diff.operations_removed), (1, 1));
+        assert_eq!(diff.breaking, 1);
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                (Severity::Breaking, ChangeKind::OperationRemoved, None),
+                (Severity::NonBreaking, ChangeKind::OperationAdded, None),
+            ]
+        );
+        assert_eq!(diff.changes[0].operation, "get:/a");
+    }
+
+    #[test]
+    fn argument_changes_are_classified_by_their_effect_on_clients() {
+        let before = [operation(
+            "pets.create",
+            json!({
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": { "type": "integer" },
+                    "legacy": { "type": "boolean" },
+                    "kind": { "type": "string", "enum": ["cat", "dog"] },
+                    "owner": { "type": "object", "properties": { "email": { "type": "string" } } }
+                }
+            }),
+            Some("Pet"),
+        )];
+        let after = [operation(
+            "pets.create",
+            json!({
+                "type": "object",
+                "required": ["age", "tags"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": { "type": "integer" },
+                    "kind": { "type": "string", "enum": ["cat", "bird"] },
+                    "owner": { "type": "object", "properties": { "email": { "type": "integer" } } },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "note": { "type": "string" }
+                }
+            }),
+            Some("PetResult"),
+        )];
+
+        let diff = compare(&before, &after);
+
+        assert_eq!(diff.operations_changed, 1);
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                (
+                    Severity::Breaking,
+                    ChangeKind::ParameterRequired,
+                    Some("age")
+                ),
+                (
+                    Severity::Breaking,
+                    ChangeKind::EnumValueRemoved,
+                    Some("kind")
+                ),
+                (
+                    Severity::Breaking,
+                    ChangeKind::ParameterRemoved,
+                    Some("legacy")
+                ),
+                (
+                    Severity::Breaking,
+                    ChangeKind::TypeChanged,
+                    Some("owner.email")
+                ),
+                (Severity::Breaking, ChangeKind::ParameterAdded, Some("tags")),
+                (Severity::Breaking, ChangeKind::ReturnTypeChanged, None),
+                (
+                    Severity::NonBreaking,
+                    ChangeKind::EnumValueAdded,
+                    Some("kind")
+                ),
+                (
+                    Severity::NonBreaking,
+                    ChangeKind::ParameterOptional,
+                    Some("name")
+                ),
+                (
+                    Severity::NonBreaking,
+                    ChangeKind::ParameterAdded,
+                    Some("note")
+                ),
+            ]
+        );
+        assert_eq!(diff.breaking, 6);
+        assert_eq!(
+            diff.changes[3].message,
+            "'owner.email' type changed from string to integer"
+        );
+    }
+
+    #[test]
+    fn parameters_listed_by_name_are_compared_too() {
+        let mut before = operation("query/user", json!(null), Some("User"));
+        before.input_schema = None;
+        before.parameters = vec![Parameter {
+            name: "id".to_string(),
+            param_type: "ID".to_string(),
+            required: true,
+            description: None,
+            location: None,
+        }];
+        let mut after = before.clone();
+        after.parameters[0].param_type = "Int".to_string();
+
+        assert!(compare(&[before.clone()], &[before.clone()]).is_empty());
+        let diff = compare(&[before], &[after]);
+        assert_eq!(diff.breaking, 1);
+        assert_eq!(diff.changes[0].kind, ChangeKind::TypeChanged);
+    }
+}
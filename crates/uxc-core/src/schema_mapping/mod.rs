@@ -167,23 +167,42 @@ fn resolve_from_rules(
         rule,
     }));
 
-    candidates
+    let matching: Vec<CandidateRule> = candidates
         .into_iter()
         .filter(|candidate| candidate.rule.enabled && candidate.rule.matches(&target))
-        .max_by_key(|candidate| {
-            (
-                candidate.source.rank(),
-                candidate.rule.priority,
-                candidate
-                    .rule
-                    .normalized_path_prefix()
-                    .map_or(0usize, |prefix| prefix.len()),
-            )
-        })
-        .map(|candidate| ResolvedSchemaMapping {
-            schema_url: candidate.rule.schema_url,
-            source: candidate.source,
-        })
+        .collect();
+    let count = matching.len();
+    let winner = matching.into_iter().max_by_key(|candidate| {
+        (
+            candidate.source.rank(),
+            candidate.rule.priority,
+            candidate
+                .rule
+                .normalized_path_prefix()
+                .map_or(0usize, |prefix| prefix.len()),
+        )
+    })?;
+
+    // User rules beat builtin ones, then higher priority, then longer prefix
+    crate::explain::record(
+        "schema_mapping",
+        format!(
+            "{} rule host={} path_prefix={} priority={} won among {} matching rules",
+            winner.source.as_str(),
+            winner.rule.normalized_host(),
+            winner
+                .rule
+                .normalized_path_prefix()
+                .as_deref()
+                .unwrap_or("/"),
+            winner.rule.priority,
+            count
+        ),
+    );
+    Some(ResolvedSchemaMapping {
+        schema_url: winner.rule.schema_url,
+        source: winner.source,
+    })
 }
 
 pub fn resolve_openapi_schema_mapping(target_url: &str) -> Option<ResolvedSchemaMapping> {
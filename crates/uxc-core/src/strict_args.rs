@@ -0,0 +1,153 @@
+//! Strict argument checking for `--strict-args`
+//!
+//! By default argument names that an operation does not declare are sent
+//! as-is. In strict mode they are rejected before the call, with the closest
+//! declared names as suggestions. Strict mode is on with `--strict-args`, or
+//! by default with
+//!
+//! ```toml
+//! [call]
+//! strict_args = true
+//! ```
+//!
+//! in `~/.uxc/config.toml`, a project `.uxc.toml` or as
+//! `UXC_CALL_STRICT_ARGS=1` (see [`crate::config`]); `--allow-extra` turns it
+//! off for one call.
+//!
+//! Declared names are the properties of the operation's input schema (see
+//! [`crate::datagen::payload_schema`]). Operations whose input is free-form
+//! (no properties, or `additionalProperties: true`) accept any name.
+
+use crate::adapters::OperationDetail;
+use crate::error::UxcError;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Whether strict mode is configured as the default
+pub fn configured_default() -> Result<bool> {
+    Ok(crate::config::get("call.strict_args")?
+        .as_bool()
+        .unwrap_or(false))
+}
+
+/// Argument names `operation` declares; `None` when it accepts any name
+pub fn declared_names(detail: &OperationDetail) -> Option<Vec<String>> {
+    let schema = crate::datagen::payload_schema(detail);
+    if schema.get("additionalProperties") == Some(&Value::Bool(true)) {
+        return None;
+    }
+    let properties = schema.get("properties")?.as_object()?;
+    if properties.is_empty() {
+        return None;
+    }
+    let mut names = properties.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    Some(names)
+}
+
+/// Reject arguments `detail` does not declare
+pub fn check(detail: &OperationDetail, args: &HashMap<String, Value>) -> Result<(), UxcError> {
+    let Some(declared) = declared_names(detail) else {
+        return Ok(());
+    };
+    let mut unknown = args
+        .keys()
+        .filter(|name| !declared.contains(name))
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    unknown.sort();
+
+    let candidates = declared.iter().map(String::as_str).collect::<Vec<_>>();
+    let problems = unknown
+        .iter()
+        .map(|name| match likely_meant(name, &candidates) {
+            Some(suggestion) => format!("'{}' (did you mean '{}'?)", name, suggestion),
+            None => format!("'{}'", name),
+        })
+        .collect::<Vec<_>>();
+    Err(UxcError::InvalidArguments(format!(
+        "Unknown argument{} {} for {}; declared: {}. Pass --allow-extra to send {} anyway",
+        if unknown.len() == 1 { "" } else { "s" },
+        problems.join(", "),
+        detail.operation_id,
+        declared.join(", "),
+        if unknown.len() == 1 { "it" } else { "them" },
+    )))
+}
+
+/// The declared name closest to `name`, if it is close enough to be a typo
+fn likely_meant<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let closest = *crate::suggest::closest_matches(name, candidates, 1).first()?;
+    let (name, lower) = (name.to_lowercase(), closest.to_lowercase());
+    let close = lower.contains(&name)
+        || name.contains(&lower)
+        || crate::suggest::edit_distance(&name, &lower) <= (name.chars().count() / 3).max(2);
+    close.then_some(closest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::Parameter;
+    use serde_json::json;
+
+    fn detail(input_schema: Value) -> OperationDetail {
+        OperationDetail {
+            operation_id: "post:/pets".to_string(),
+            display_name: "post /pets".to_string(),
+            description: None,
+            parameters: vec![Parameter {
+                name: "dry_run".to_string(),
+                param_type: "boolean".to_string(),
+                required: false,
+                description: None,
+                location: None,
+            }],
+            return_type: None,
+            input_schema: Some(input_schema),
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
+        }
+    }
+
+    fn args(names: &[&str]) -> HashMap<String, Value> {
+        names
+            .iter()
+            .map(|name| (name.to_string(), json!(1)))
+            .collect()
+    }
+
+    #[test]
+    fn unknown_names_are_rejected_with_suggestions() {
+        let detail = detail(json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" }, "tag": { "type": "string" } }
+        }));
+        assert!(check(&detail, &args(&["name", "dry_run"])).is_ok());
+
+        let err = check(&detail, &args(&["nmae", "color"]))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("'color', 'nmae' (did you mean 'name'?)"),
+            "{}",
+            err
+        );
+        assert!(err.contains("declared: dry_run, name, tag"), "{}", err);
+        assert!(err.contains("--allow-extra"), "{}", err);
+    }
+
+    #[test]
+    fn free_form_inputs_accept_any_name() {
+        let open = detail(
+            json!({ "type": "object", "additionalProperties": true, "properties": { "a": {} } }),
+        );
+        assert!(check(&open, &args(&["anything"])).is_ok());
+        assert_eq!(declared_names(&open), None);
+    }
+}
@@ -0,0 +1,119 @@
+//! "Did you mean" suggestions for mistyped names, and operation search
+
+/// Up to `limit` candidates closest to `target`: candidates containing it (or
+/// contained in it) first, then by edit distance.
+pub fn closest_matches<'a>(target: &str, candidates: &[&'a str], limit: usize) -> Vec<&'a str> {
+    let target = target.to_lowercase();
+    let mut ranked = candidates
+        .iter()
+        .map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let overlaps = lower.contains(&target) || target.contains(&lower);
+            (!overlaps, edit_distance(&target, &lower), *candidate)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort();
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, candidate)| candidate)
+        .collect()
+}
+
+/// How well `term` matches an operation `id` and its `texts` (display name,
+/// description), or `None` when some word of the term matches nothing. Each
+/// word scores 4 when found in the id, 2 when found in a text, and 1 when it
+/// is a near miss of a word of either or spells out a subsequence of the id.
+pub fn search_score(term: &str, id: &str, texts: &[&str]) -> Option<usize> {
+    let id = id.to_lowercase();
+    let texts = texts
+        .iter()
+        .map(|text| text.to_lowercase())
+        .collect::<Vec<_>>();
+    let words = id
+        .split(|c: char| !c.is_alphanumeric())
+        .chain(
+            texts
+                .iter()
+                .flat_map(|text| text.split(|c: char| !c.is_alphanumeric())),
+        )
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    let mut score = 0;
+    for wanted in term.to_lowercase().split_whitespace() {
+        score += if id.contains(wanted) {
+            4
+        } else if texts.iter().any(|text| text.contains(wanted)) {
+            2
+        } else if words
+            .iter()
+            .any(|word| edit_distance(wanted, word) <= wanted.chars().count() / 4)
+            || is_subsequence(wanted, &id)
+        {
+            1
+        } else {
+            return None;
+        };
+    }
+    Some(score)
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// Levenshtein distance between `a` and `b`, in characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_matches_prefer_overlap_then_edit_distance() {
+        let ids = ["get:/users", "post:/users", "get:/users/{id}", "get:/pets"];
+        assert_eq!(
+            closest_matches("get:/user", &ids, 3),
+            vec!["get:/users", "get:/users/{id}", "get:/pets"]
+        );
+    }
+
+    #[test]
+    fn search_score_ranks_id_over_text_over_near_misses() {
+        let texts = ["List repositories", "Lists the repositories of a user"];
+        assert_eq!(search_score("repos", "get:/user/repos", &texts), Some(4));
+        assert_eq!(
+            search_score("user repositories", "get:/user/repos", &texts),
+            Some(6)
+        );
+        assert_eq!(
+            search_score("repositries", "get:/user/repos", &texts),
+            Some(1)
+        );
+        assert_eq!(search_score("gur", "get:/user/repos", &[]), Some(1));
+        assert_eq!(
+            search_score("repos issues", "get:/user/repos", &texts),
+            None
+        );
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("name", "nmae"), 2);
+        assert_eq!(edit_distance("limit", "limits"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
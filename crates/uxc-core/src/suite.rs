@@ -0,0 +1,601 @@
+//! Declarative test suites (`uxc test`)
+//!
+//! A suite is a YAML or JSON document listing named cases. Each case calls one
+//! operation and checks the outcome with [`Expectation`]s built on the
+//! assertions engine. Reports are available as JSON and JUnit XML.
+//!
+//! Cases can declare a [`Compensation`] that undoes their effect. When a case
+//! fails, the remaining cases are skipped and the compensations of every case
+//! whose call succeeded run in reverse order, so a multi-step provisioning
+//! flow does not leave half-created resources behind.
+
+use crate::assertions::{self, Assertion};
+use crate::batch::{ItemError, TIMEOUT_CODE};
+use crate::error::UxcError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Test suite definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestSuite {
+    /// Suite name (defaults to the file stem)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Default endpoint for cases that do not set their own
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Test cases, executed in order
+    #[serde(default)]
+    pub cases: Vec<TestCase>,
+}
+
+/// One call plus the expectations on its outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    pub operation: String,
+
+    #[serde(default)]
+    pub args: Map<String, Value>,
+
+    #[serde(default)]
+    pub expect: Expectation,
+
+    /// Call that undoes this case when a later case fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compensate: Option<Compensation>,
+}
+
+/// Undo call for a case, e.g. deleting what the case created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Compensation {
+    /// Defaults to the endpoint of the case
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    pub operation: String,
+
+    #[serde(default)]
+    pub args: Map<String, Value>,
+
+    /// Arguments taken from the case's response, as `name: path`
+    /// (e.g. `id: data.id`)
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub args_from: Map<String, Value>,
+}
+
+impl Compensation {
+    /// Arguments for the undo call, with `args_from` paths looked up in the
+    /// response `data` of the case being undone.
+    pub fn args_for(&self, data: &Value) -> std::result::Result<Map<String, Value>, String> {
+        let mut args = self.args.clone();
+        for (name, path) in &self.args_from {
+            let path = path
+                .as_str()
+                .ok_or_else(|| format!("args_from.{}: path must be a string", name))?;
+            let value = assertions::lookup_path(data, path).ok_or_else(|| {
+                format!("args_from.{}: '{}' not found in the response", name, path)
+            })?;
+            args.insert(name.clone(), value.clone());
+        }
+        Ok(args)
+    }
+}
+
+/// Expectations for a case. A case is expected to succeed unless `ok: false`
+/// or `error_code` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Expectation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ok: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+
+    /// HTTP status of the response, for calls answered by one HTTP request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_ms: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<Assertion>,
+
+    /// JSON Schema the response data must conform to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+}
+
+impl Expectation {
+    fn expects_success(&self) -> bool {
+        self.ok
+            .unwrap_or(self.error_code.is_none() && self.status.is_none_or(|s| s < 400))
+    }
+
+    /// Check an outcome and return one message per unmet expectation.
+    pub fn check(&self, outcome: &CallOutcome) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        match outcome {
+            CallOutcome::Success { data, .. } => {
+                if !self.expects_success() {
+                    failures.push(match &self.error_code {
+                        Some(code) => format!("expected error {} but call succeeded", code),
+                        None => "expected call to fail but it succeeded".to_string(),
+                    });
+                }
+                for assertion in &self.assertions {
+                    failures.extend(assertion.check(data));
+                }
+                if let Some(schema) = &self.schema {
+                    failures.extend(
+                        assertions::schema_violations(schema, data)
+                            .into_iter()
+                            .map(|violation| format!("schema: {}", violation)),
+                    );
+                }
+            }
+            CallOutcome::Failure { code, message, .. } => {
+                if self.expects_success() {
+                    failures.push(format!("call failed: {}: {}", code, message));
+                } else if let Some(expected_code) = &self.error_code {
+                    if expected_code != code {
+                        failures.push(format!(
+                            "expected error {} but got {}: {}",
+                            expected_code, code, message
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(expected) = self.status {
+            match outcome.status() {
+                Some(status) if status == expected => {}
+                Some(status) => {
+                    failures.push(format!("expected status {} but got {}", expected, status))
+                }
+                None => failures.push(format!(
+                    "expected status {} but the call has no HTTP status",
+                    expected
+                )),
+            }
+        }
+        if let Some(max) = self.max_duration_ms {
+            let duration_ms = outcome.duration_ms();
+            if duration_ms > max {
+                failures.push(format!(
+                    "took {}ms, expected at most {}ms",
+                    duration_ms, max
+                ));
+            }
+        }
+        failures
+    }
+}
+
+/// Outcome of executing a case, independent of the protocol adapter.
+/// `status` is the HTTP status, when the call was answered by one request.
+#[derive(Debug, Clone)]
+pub enum CallOutcome {
+    Success {
+        data: Value,
+        duration_ms: u64,
+        status: Option<u16>,
+    },
+    Failure {
+        code: String,
+        message: String,
+        duration_ms: u64,
+        status: Option<u16>,
+    },
+}
+
+impl CallOutcome {
+    fn duration_ms(&self) -> u64 {
+        match self {
+            CallOutcome::Success { duration_ms, .. } | CallOutcome::Failure { duration_ms, .. } => {
+                *duration_ms
+            }
+        }
+    }
+
+    fn status(&self) -> Option<u16> {
+        match self {
+            CallOutcome::Success { status, .. } | CallOutcome::Failure { status, .. } => *status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaseReport {
+    pub name: String,
+    pub endpoint: String,
+    pub operation: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    /// Calls made, including retries
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    /// Not run because an earlier case failed under `--fail-fast`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// The call's error, when it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ItemError>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+impl CaseReport {
+    /// Report for a case that was not run
+    pub fn skipped(case: &TestCase, endpoint: &str) -> Self {
+        Self {
+            name: case.name.clone(),
+            endpoint: endpoint.to_string(),
+            operation: case.operation.clone(),
+            skipped: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Outcome of one compensation call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompensationReport {
+    /// Case that was undone
+    pub case: String,
+    pub endpoint: String,
+    pub operation: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ItemError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuiteReport {
+    pub suite: String,
+    pub total: usize,
+    pub passed: usize,
+    /// Cases that ran and did not pass, timed-out ones included
+    pub failed: usize,
+    #[serde(default)]
+    pub timed_out: usize,
+    #[serde(default)]
+    pub skipped: usize,
+    pub duration_ms: u64,
+    pub cases: Vec<CaseReport>,
+    /// Undo calls run after a failure, in the order they ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compensations: Vec<CompensationReport>,
+}
+
+impl TestSuite {
+    /// Load a suite from a `.json`, `.yaml` or `.yml` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            UxcError::InvalidArguments(format!(
+                "Failed to read test suite {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        let mut suite: TestSuite = if is_json {
+            serde_json::from_str(&content).map_err(|e| {
+                UxcError::InvalidArguments(format!("Invalid test suite {}: {}", path.display(), e))
+            })?
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| {
+                UxcError::InvalidArguments(format!("Invalid test suite {}: {}", path.display(), e))
+            })?
+        };
+
+        if suite.name.is_none() {
+            suite.name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(ToString::to_string);
+        }
+
+        Ok(suite)
+    }
+
+    /// Endpoint for a case, falling back to the suite default.
+    pub fn endpoint_for(&self, case: &TestCase) -> Result<String> {
+        case.endpoint
+            .clone()
+            .or_else(|| self.endpoint.clone())
+            .ok_or_else(|| {
+                UxcError::InvalidArguments(format!(
+                    "Test case '{}' has no endpoint and the suite defines no default",
+                    case.name
+                ))
+                .into()
+            })
+    }
+}
+
+/// Check a case outcome against its expectations.
+pub fn evaluate_case(case: &TestCase, endpoint: &str, outcome: &CallOutcome) -> CaseReport {
+    let failures = case.expect.check(outcome);
+    let error = match outcome {
+        CallOutcome::Failure {
+            code,
+            message,
+            status,
+            ..
+        } => Some(ItemError {
+            code: code.clone(),
+            message: message.clone(),
+            status: *status,
+        }),
+        CallOutcome::Success { .. } => None,
+    };
+    let duration_ms = outcome.duration_ms();
+
+    CaseReport {
+        name: case.name.clone(),
+        endpoint: endpoint.to_string(),
+        operation: case.operation.clone(),
+        passed: failures.is_empty(),
+        duration_ms,
+        attempts: 1,
+        timed_out: error.as_ref().is_some_and(|e| e.code == TIMEOUT_CODE),
+        skipped: false,
+        error,
+        failures,
+    }
+}
+
+impl SuiteReport {
+    pub fn new(suite: &str, cases: Vec<CaseReport>, duration_ms: u64) -> Self {
+        let passed = cases.iter().filter(|case| case.passed).count();
+        let skipped = cases.iter().filter(|case| case.skipped).count();
+        Self {
+            suite: suite.to_string(),
+            total: cases.len(),
+            passed,
+            failed: cases.len() - passed - skipped,
+            timed_out: cases.iter().filter(|case| case.timed_out).count(),
+            skipped,
+            duration_ms,
+            cases,
+            compensations: Vec::new(),
+        }
+    }
+
+    /// Render the report as a JUnit XML document.
+    pub fn to_junit_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            self.total,
+            self.failed,
+            self.skipped,
+            seconds(self.duration_ms)
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            xml_escape(&self.suite),
+            self.total,
+            self.failed,
+            self.skipped,
+            seconds(self.duration_ms)
+        ));
+
+        for case in &self.cases {
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                xml_escape(&case.name),
+                xml_escape(&format!("{} {}", case.endpoint, case.operation)),
+                seconds(case.duration_ms)
+            );
+            if case.passed {
+                xml.push_str(&open);
+                xml.push_str("/>\n");
+                continue;
+            }
+            if case.skipped {
+                xml.push_str(&open);
+                xml.push_str(">\n      <skipped/>\n    </testcase>\n");
+                continue;
+            }
+
+            xml.push_str(&open);
+            xml.push_str(">\n");
+            xml.push_str(&format!(
+                "      <failure message=\"{}\">{}</failure>\n",
+                xml_escape(
+                    case.failures
+                        .first()
+                        .map(String::as_str)
+                        .unwrap_or("failed")
+                ),
+                xml_escape(&case.failures.join("\n"))
+            ));
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+fn seconds(duration_ms: u64) -> String {
+    format!("{:.3}", duration_ms as f64 / 1000.0)
+}
+
+fn xml_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn case(expect: Expectation) -> TestCase {
+        TestCase {
+            name: "list pets".to_string(),
+            endpoint: None,
+            operation: "get:/pets".to_string(),
+            args: Map::new(),
+            expect,
+            compensate: None,
+        }
+    }
+
+    #[test]
+    fn parses_yaml_suite() {
+        let suite: TestSuite = serde_yaml::from_str(
+            r#"
+name: petstore
+endpoint: https://petstore.example.com
+cases:
+  - name: list pets
+    operation: get:/pets
+    args: { limit: 2 }
+    expect:
+      assertions:
+        - path: "[0].name"
+          equals: cat
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(suite.cases.len(), 1);
+        assert_eq!(suite.cases[0].args["limit"], json!(2));
+        assert_eq!(
+            suite.endpoint_for(&suite.cases[0]).unwrap(),
+            "https://petstore.example.com"
+        );
+    }
+
+    #[test]
+    fn compensation_args_come_from_the_response() {
+        let suite: TestSuite = serde_yaml::from_str(
+            r#"
+endpoint: https://petstore.example.com
+cases:
+  - name: create pet
+    operation: post:/pets
+    args: { name: cat }
+    compensate:
+      operation: delete:/pets/{id}
+      args: { force: true }
+      args_from: { id: pet.id }
+"#,
+        )
+        .unwrap();
+        let compensation = suite.cases[0].compensate.as_ref().unwrap();
+
+        let args = compensation.args_for(&json!({"pet": {"id": 7}})).unwrap();
+        assert_eq!(args["id"], json!(7));
+        assert_eq!(args["force"], json!(true));
+        assert_eq!(
+            compensation.args_for(&json!({})).unwrap_err(),
+            "args_from.id: 'pet.id' not found in the response"
+        );
+    }
+
+    #[test]
+    fn success_with_failing_assertion_fails_case() {
+        let case = case(Expectation {
+            assertions: vec![Assertion {
+                path: "count".to_string(),
+                equals: Some(json!(2)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let outcome = CallOutcome::Success {
+            data: json!({"count": 1}),
+            duration_ms: 5,
+            status: Some(200),
+        };
+
+        let report = evaluate_case(&case, "http://x", &outcome);
+        assert!(!report.passed);
+        assert_eq!(report.failures, vec!["count: expected 2 but got 1"]);
+    }
+
+    #[test]
+    fn expected_error_code_passes() {
+        let case = case(Expectation {
+            error_code: Some("OPERATION_NOT_FOUND".to_string()),
+            ..Default::default()
+        });
+        let outcome = CallOutcome::Failure {
+            code: "OPERATION_NOT_FOUND".to_string(),
+            message: "missing".to_string(),
+            duration_ms: 1,
+            status: None,
+        };
+
+        assert!(evaluate_case(&case, "http://x", &outcome).passed);
+    }
+
+    #[test]
+    fn junit_report_counts_failures() {
+        let report = SuiteReport::new(
+            "smoke",
+            vec![
+                CaseReport {
+                    name: "ok".to_string(),
+                    endpoint: "http://x".to_string(),
+                    operation: "get:/a".to_string(),
+                    passed: true,
+                    duration_ms: 10,
+                    ..Default::default()
+                },
+                CaseReport {
+                    name: "bad <case>".to_string(),
+                    endpoint: "http://x".to_string(),
+                    operation: "get:/b".to_string(),
+                    passed: false,
+                    duration_ms: 20,
+                    failures: vec!["boom".to_string()],
+                    ..Default::default()
+                },
+                CaseReport {
+                    name: "later".to_string(),
+                    endpoint: "http://x".to_string(),
+                    operation: "get:/c".to_string(),
+                    skipped: true,
+                    ..Default::default()
+                },
+            ],
+            30,
+        );
+
+        let xml = report.to_junit_xml();
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.skipped, 1);
+        assert!(xml.contains("tests=\"3\" failures=\"1\" skipped=\"1\""));
+        assert!(xml.contains("<skipped/>"));
+        assert!(xml.contains("name=\"bad &lt;case&gt;\""));
+        assert!(xml.contains("<failure message=\"boom\">boom</failure>"));
+    }
+}
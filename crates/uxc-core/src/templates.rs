@@ -0,0 +1,189 @@
+//! Saved call templates
+//!
+//! A template is a named invocation (endpoint, operation, args) stored in
+//! `~/.uxc/templates.json`. Templates are created by importers and executed
+//! with `uxc template run <name>`.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_TEMPLATES_DIR: &str = ".uxc";
+const DEFAULT_TEMPLATES_FILE: &str = "templates.json";
+
+/// One saved invocation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Template {
+    pub endpoint: String,
+    pub operation: String,
+
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub args: Map<String, Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Where the template came from (e.g. "postman", "curl")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Template collection backed by `~/.uxc/templates.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateStore {
+    #[serde(default)]
+    pub version: u32,
+
+    #[serde(default)]
+    pub templates: BTreeMap<String, Template>,
+}
+
+impl TemplateStore {
+    fn store_path() -> Result<PathBuf> {
+        let home = home_dir().context("Could not determine home directory")?;
+        Ok(home
+            .join(DEFAULT_TEMPLATES_DIR)
+            .join(DEFAULT_TEMPLATES_FILE))
+    }
+
+    /// Load templates. A missing file yields an empty store.
+    pub fn load() -> Result<Self> {
+        let path = Self::store_path()?;
+        if !path.exists() {
+            return Ok(Self {
+                version: 1,
+                templates: BTreeMap::new(),
+            });
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read templates file: {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse templates file: {:?}", path))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::store_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create templates directory: {:?}", parent))?;
+        }
+
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize templates")?;
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write templates file: {:?}", path))
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Template> {
+        match self.templates.get(name) {
+            Some(template) => Ok(template),
+            None => bail!(
+                "Template '{}' not found. Available templates: {}",
+                name,
+                self.templates
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.templates.contains_key(name)
+    }
+
+    pub fn insert(&mut self, name: String, template: Template) -> Result<()> {
+        validate_template_name(&name)?;
+        self.templates.insert(name, template);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Template> {
+        self.templates
+            .remove(name)
+            .with_context(|| format!("Template '{}' not found", name))
+    }
+}
+
+fn validate_template_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Template name cannot be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        bail!(
+            "Template name '{}' contains invalid characters. Allowed characters: letters, digits, '_', '-', '.'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Turn a free-form label (e.g. a Postman request name) into a template name.
+pub fn slugify(label: &str) -> String {
+    let mut slug = String::new();
+    for c in label.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "template".to_string()
+    } else {
+        slug
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        return Some(PathBuf::from(home));
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(user_profile) = std::env::var_os("USERPROFILE") {
+            return Some(PathBuf::from(user_profile));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_normalizes_labels() {
+        assert_eq!(slugify("List Pets"), "list-pets");
+        assert_eq!(slugify("  Get /pets/{id} "), "get-pets-id");
+        assert_eq!(slugify("***"), "template");
+    }
+
+    #[test]
+    fn insert_rejects_invalid_names() {
+        let mut store = TemplateStore::default();
+        let template = Template {
+            endpoint: "https://api.example.com".to_string(),
+            operation: "get:/pets".to_string(),
+            args: Map::new(),
+            description: None,
+            source: None,
+        };
+        assert!(store
+            .insert("bad name".to_string(), template.clone())
+            .is_err());
+        assert!(store.insert("list-pets".to_string(), template).is_ok());
+        assert!(store.contains("list-pets"));
+        assert!(store.get("missing").is_err());
+    }
+}
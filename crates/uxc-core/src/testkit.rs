@@ -0,0 +1,323 @@
+//! Conformance suites for adapter implementations (feature `test-kit`)
+//!
+//! The uxc command line relies on a few behaviours every [`Adapter`] shares:
+//! detection probes call [`Adapter::can_handle`] on every adapter and stop at
+//! the first error, schemas are fetched once and then served from the cache,
+//! and failures carry the error codes the CLI reports and builds its
+//! suggestions from. Authors of adapters outside this crate describe a test
+//! endpoint with a [`Fixture`] and run the suites from their own tests:
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn my_adapter_conforms() {
+//!     let fixture = MyFixture::start().await;
+//!     uxc_core::testkit::run(&fixture).await.assert_passed();
+//! }
+//! ```
+
+use crate::adapters::Adapter;
+use crate::cache::{Cache, CacheConfig};
+use crate::error::{code_of, UxcError};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Default name of an operation the endpoint lacks
+pub const MISSING_OPERATION: &str = "uxc-testkit-missing-operation";
+
+/// Address nothing listens on, for probes that must fail quietly
+pub const UNREACHABLE_ENDPOINT: &str = "http://127.0.0.1:9";
+
+/// A test endpoint and a way to build the adapter under test
+pub trait Fixture: Send + Sync {
+    type Adapter: Adapter;
+
+    /// A new adapter, storing schemas in `cache` when one is given
+    fn adapter(&self, cache: Option<Arc<dyn Cache>>) -> Self::Adapter;
+
+    /// Endpoint the adapter serves
+    fn endpoint(&self) -> String;
+
+    /// Endpoints of other protocols, which the adapter must decline
+    fn foreign_endpoints(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// How many times the endpoint has served its schema so far; `None`
+    /// skips the caching suite
+    fn schema_requests(&self) -> Option<usize> {
+        None
+    }
+
+    /// A well-formed operation name the endpoint lacks
+    fn missing_operation(&self) -> String {
+        MISSING_OPERATION.to_string()
+    }
+
+    /// A call the endpoint rejects; `None` skips that check
+    fn rejected_call(&self) -> Option<(String, HashMap<String, Value>)> {
+        None
+    }
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub suite: &'static str,
+    pub name: String,
+    /// Why the check failed, if it did
+    pub failure: Option<String>,
+}
+
+impl Check {
+    fn new(suite: &'static str, name: impl Into<String>, failure: Option<String>) -> Self {
+        Self {
+            suite,
+            name: name.into(),
+            failure,
+        }
+    }
+}
+
+/// Outcomes of the suites that ran
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// Checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| check.failure.is_some())
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Panic with every failed check, for use in tests
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "adapter conformance failed:\n{}", self);
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            match &check.failure {
+                None => writeln!(f, "ok    {}: {}", check.suite, check.name)?,
+                Some(why) => writeln!(f, "FAIL  {}: {}: {}", check.suite, check.name, why)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run every suite against `fixture`
+pub async fn run<F: Fixture>(fixture: &F) -> Report {
+    let mut checks = can_handle(fixture).await;
+    checks.extend(schema_caching(fixture).await);
+    checks.extend(error_mapping(fixture).await);
+    Report { checks }
+}
+
+/// `Ok(expected)`, or why the probe did not answer that
+fn probe_failure(result: anyhow::Result<bool>, expected: bool) -> Option<String> {
+    match result {
+        Ok(found) if found == expected => None,
+        Ok(found) => Some(format!("returned {}, expected {}", found, expected)),
+        // Detection stops at the first probe error, so declining must not fail
+        Err(err) => Some(format!(
+            "failed instead of returning {}: {:#}",
+            expected, err
+        )),
+    }
+}
+
+/// The adapter accepts its endpoint and declines others without failing
+pub async fn can_handle<F: Fixture>(fixture: &F) -> Vec<Check> {
+    const SUITE: &str = "can_handle";
+    let adapter = fixture.adapter(None);
+
+    let endpoint = fixture.endpoint();
+    let mut checks = vec![Check::new(
+        SUITE,
+        format!("accepts {}", endpoint),
+        probe_failure(adapter.can_handle(&endpoint).await, true),
+    )];
+    for foreign in fixture
+        .foreign_endpoints()
+        .into_iter()
+        .chain([UNREACHABLE_ENDPOINT.to_string()])
+    {
+        checks.push(Check::new(
+            SUITE,
+            format!("declines {}", foreign),
+            probe_failure(adapter.can_handle(&foreign).await, false),
+        ));
+    }
+    checks
+}
+
+/// Schema requests made by `action`, or why it failed
+async fn requests_during<F, T>(
+    fixture: &F,
+    action: impl std::future::Future<Output = anyhow::Result<T>>,
+) -> Result<usize, String>
+where
+    F: Fixture,
+{
+    let before = fixture.schema_requests().unwrap_or_default();
+    action.await.map_err(|err| format!("failed: {:#}", err))?;
+    Ok(fixture.schema_requests().unwrap_or_default() - before)
+}
+
+fn expect_requests(result: Result<usize, String>, expected: usize) -> Option<String> {
+    match result {
+        Ok(made) if made == expected => None,
+        Ok(made) => Some(format!(
+            "{} schema request(s) made, expected {}",
+            made, expected
+        )),
+        Err(why) => Some(why),
+    }
+}
+
+fn fetched_from_endpoint(result: Result<usize, String>) -> Option<String> {
+    match result {
+        Ok(0) => Some("no schema request made".to_string()),
+        Ok(_) => None,
+        Err(why) => Some(why),
+    }
+}
+
+/// Schemas are fetched, stored in the cache and served from it,
+/// including to other adapter instances sharing that cache
+pub async fn schema_caching<F: Fixture>(fixture: &F) -> Vec<Check> {
+    const SUITE: &str = "schema_caching";
+    if fixture.schema_requests().is_none() {
+        return Vec::new();
+    }
+    let dir = match tempfile::TempDir::new() {
+        Ok(dir) => dir,
+        Err(err) => {
+            let why = format!("cannot create a cache directory: {}", err);
+            return vec![Check::new(SUITE, "cache setup", Some(why))];
+        }
+    };
+    let config = CacheConfig {
+        location: dir.path().to_path_buf(),
+        ..Default::default()
+    };
+    let cache = match crate::cache::create_cache(config) {
+        Ok(cache) => cache,
+        Err(err) => {
+            let why = format!("cannot create a cache: {:#}", err);
+            return vec![Check::new(SUITE, "cache setup", Some(why))];
+        }
+    };
+
+    let endpoint = fixture.endpoint();
+    let adapter = fixture.adapter(Some(cache.clone()));
+    let fetched = requests_during(fixture, adapter.fetch_schema(&endpoint)).await;
+    let mut checks = vec![Check::new(
+        SUITE,
+        "first fetch asks the endpoint",
+        fetched_from_endpoint(fetched),
+    )];
+    checks.push(Check::new(
+        SUITE,
+        "second fetch is served from the cache",
+        expect_requests(
+            requests_during(fixture, adapter.fetch_schema(&endpoint)).await,
+            0,
+        ),
+    ));
+
+    let shared = fixture.adapter(Some(cache));
+    checks.push(Check::new(
+        SUITE,
+        "another adapter sharing the cache lists operations without fetching",
+        expect_requests(
+            requests_during(fixture, shared.list_operations(&endpoint)).await,
+            0,
+        ),
+    ));
+
+    let uncached = fixture.adapter(None);
+    let fetched = requests_during(fixture, uncached.fetch_schema(&endpoint)).await;
+    checks.push(Check::new(
+        SUITE,
+        "an adapter without a cache fetches the schema",
+        fetched_from_endpoint(fetched),
+    ));
+    checks
+}
+
+/// Why `result` did not fail with `code`
+fn code_failure<T>(result: anyhow::Result<T>, code: &str) -> Option<String> {
+    match result {
+        Ok(_) => Some(format!("succeeded, expected {}", code)),
+        Err(err) if code_of(&err) == code => None,
+        Err(err) => Some(format!(
+            "failed with {} ({:#}), expected {}",
+            code_of(&err),
+            err,
+            code
+        )),
+    }
+}
+
+fn is_call_failed(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<UxcError>(),
+            Some(UxcError::CallFailed { .. })
+        )
+    })
+}
+
+/// Failures carry the codes the CLI reports: unknown operations are
+/// `OPERATION_NOT_FOUND`, and calls the endpoint rejects are
+/// [`UxcError::CallFailed`] with the protocol's error detail. Adapters that
+/// pass calls through without consulting the schema may report an unknown
+/// operation as the endpoint's rejection instead.
+pub async fn error_mapping<F: Fixture>(fixture: &F) -> Vec<Check> {
+    const SUITE: &str = "error_mapping";
+    let adapter = fixture.adapter(None);
+    let endpoint = fixture.endpoint();
+    let missing = fixture.missing_operation();
+
+    let mut checks = vec![Check::new(
+        SUITE,
+        "describing an unknown operation",
+        code_failure(
+            adapter.describe_operation(&endpoint, &missing).await,
+            "OPERATION_NOT_FOUND",
+        ),
+    )];
+    let called = adapter.execute(&endpoint, &missing, HashMap::new()).await;
+    checks.push(Check::new(
+        SUITE,
+        "calling an unknown operation",
+        match called {
+            Err(err) if is_call_failed(&err) => None,
+            called => code_failure(called, "OPERATION_NOT_FOUND"),
+        },
+    ));
+
+    if let Some((operation, args)) = fixture.rejected_call() {
+        let failure = match adapter.execute(&endpoint, &operation, args).await {
+            Ok(_) => Some("succeeded, expected the endpoint to reject it".to_string()),
+            Err(err) if is_call_failed(&err) => None,
+            Err(err) => Some(format!("not reported as CallFailed: {:#}", err)),
+        };
+        checks.push(Check::new(
+            SUITE,
+            format!("a rejected call to {}", operation),
+            failure,
+        ));
+    }
+    checks
+}
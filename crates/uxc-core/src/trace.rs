@@ -0,0 +1,255 @@
+//! Wire-level request/response tracing for `--verbose`
+//!
+//! With `--verbose` (`-v`) every outgoing request and its response are
+//! printed to stderr in the style of `curl -v`: `>` lines for the request
+//! (method, URL or target, headers, body), `<` lines for the response
+//! (status and headers) and a `*` line with timing. HTTP requests are traced
+//! in [`crate::http::send`], gRPC calls around `grpcurl`, and MCP stdio
+//! messages in the stdio transport.
+//!
+//! Credential headers (`Authorization`, `Cookie`, API key headers, ...) are
+//! shown as `***`, and everything printed passes through
+//! [`crate::masking::mask`].
+//!
+//! HTTP timing is split into DNS resolution and time to first byte; the HTTP
+//! client does not expose connect and TLS handshakes separately, so they are
+//! part of the first-byte time. DNS is only reported when a new connection
+//! looked up a host name.
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Request bodies longer than this are cut short in the trace
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Headers whose values are always hidden
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable tracing for this process
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether requests are being traced
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Timing of one traced exchange
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timing {
+    /// Host name lookup, when a new connection needed one
+    pub dns: Option<Duration>,
+
+    /// From sending the request to receiving the response head
+    pub first_byte: Duration,
+}
+
+/// Print an outgoing HTTP request
+pub fn http_request(request: &reqwest::Request) {
+    if !is_enabled() {
+        return;
+    }
+    let mut lines = vec![format!("> {} {}", request.method(), request.url())];
+    lines.extend(header_lines('>', request.headers()));
+    let body = request.body().and_then(reqwest::Body::as_bytes);
+    print(lines, body);
+}
+
+/// Print the head of an HTTP response
+pub fn http_response(response: &reqwest::Response, timing: Timing) {
+    if !is_enabled() {
+        return;
+    }
+    let status = response.status();
+    let mut lines = vec![format!(
+        "< {:?} {} {}",
+        response.version(),
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default()
+    )];
+    lines.extend(header_lines('<', response.headers()));
+    lines.push(format!("* timing: {}", describe_timing(timing)));
+    print(lines, None);
+}
+
+/// Print an outgoing non-HTTP call (gRPC, MCP stdio); `metadata` holds
+/// `Name: value` pairs
+pub fn rpc_request(target: &str, metadata: &[(String, String)], body: &str) {
+    if !is_enabled() {
+        return;
+    }
+    let mut lines = vec![format!("> {}", target)];
+    lines.extend(
+        metadata
+            .iter()
+            .map(|(name, value)| format!("> {}: {}", name, redact(name, value))),
+    );
+    print(lines, Some(body.as_bytes()));
+}
+
+/// Print the outcome of a non-HTTP call
+pub fn rpc_response(status: &str, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    print(
+        vec![
+            format!("< {}", status),
+            format!("* timing: total {}", format_ms(elapsed)),
+        ],
+        None,
+    );
+}
+
+/// Split a `Name: value` header string as passed to `grpcurl -H`
+pub fn split_header(header: &str) -> (String, String) {
+    match header.split_once(':') {
+        Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+        None => (header.trim().to_string(), String::new()),
+    }
+}
+
+fn header_lines(prefix: char, headers: &HeaderMap) -> Vec<String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if value.is_sensitive() {
+                crate::masking::MASK.to_string()
+            } else {
+                redact(name.as_str(), &String::from_utf8_lossy(value.as_bytes()))
+            };
+            format!("{} {}: {}", prefix, name, value)
+        })
+        .collect()
+}
+
+/// Header value as shown in the trace: credential headers become `***`
+pub fn redact(name: &str, value: &str) -> String {
+    if SENSITIVE_HEADERS
+        .iter()
+        .any(|sensitive| name.eq_ignore_ascii_case(sensitive))
+    {
+        crate::masking::MASK.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn print(mut lines: Vec<String>, body: Option<&[u8]>) {
+    if let Some(body) = body.filter(|body| !body.is_empty()) {
+        lines.push(">".to_string());
+        lines.push(describe_body(body));
+    }
+    eprintln!("{}", crate::masking::mask(&lines.join("\n")));
+}
+
+fn describe_body(body: &[u8]) -> String {
+    let shown = &body[..body.len().min(MAX_BODY_BYTES)];
+    match std::str::from_utf8(shown) {
+        Ok(text) if shown.len() < body.len() => {
+            format!("{}\n* body truncated ({} bytes)", text, body.len())
+        }
+        Ok(text) => text.to_string(),
+        Err(_) => format!("* binary body ({} bytes)", body.len()),
+    }
+}
+
+fn describe_timing(timing: Timing) -> String {
+    match timing.dns {
+        Some(dns) => format!(
+            "dns {}, first byte {} (includes connect and TLS)",
+            format_ms(dns),
+            format_ms(timing.first_byte)
+        ),
+        None => format!(
+            "first byte {} (includes connect and TLS)",
+            format_ms(timing.first_byte)
+        ),
+    }
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn dns_timings() -> &'static Mutex<HashMap<String, Duration>> {
+    static TIMINGS: OnceLock<Mutex<HashMap<String, Duration>>> = OnceLock::new();
+    TIMINGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Lookup time recorded for `host` since the last call, if a connection
+/// resolved it
+pub fn take_dns_timing(host: &str) -> Option<Duration> {
+    dns_timings()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(host)
+}
+
+/// Resolver that records how long each lookup took
+#[derive(Debug, Default)]
+pub struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let started = Instant::now();
+            let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            dns_timings()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(host, started.elapsed());
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn credential_headers_are_hidden() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer t0ken"));
+        headers.insert("accept", HeaderValue::from_static("application/json"));
+        let mut key = HeaderValue::from_static("k3y");
+        key.set_sensitive(true);
+        headers.insert("x-api-key", key);
+
+        let lines = header_lines('>', &headers);
+        assert!(lines.contains(&"> authorization: ***".to_string()));
+        assert!(lines.contains(&"> accept: application/json".to_string()));
+        assert!(lines.contains(&"> x-api-key: ***".to_string()));
+
+        assert_eq!(
+            split_header("Authorization: Bearer t"),
+            ("Authorization".to_string(), "Bearer t".to_string())
+        );
+        assert_eq!(redact("Cookie", "sid=1"), "***");
+    }
+
+    #[test]
+    fn long_and_binary_bodies_are_summarised() {
+        let long = vec![b'a'; MAX_BODY_BYTES + 1];
+        assert!(describe_body(&long)
+            .ends_with(&format!("* body truncated ({} bytes)", MAX_BODY_BYTES + 1)));
+        assert_eq!(describe_body(&[0xff, 0xfe]), "* binary body (2 bytes)");
+    }
+}
@@ -0,0 +1,179 @@
+//! Durations and sizes as flags and settings accept them
+//!
+//! Durations are a number of seconds (`90`, `1.5`) or numbers with units:
+//! `500ms`, `30s`, `2m30s`, `12h`, `7d`. Sizes are a number of bytes or a
+//! number with a unit: `512KB` and `10MB` count in thousands, `512KiB` and
+//! `10MiB` in 1024s. Units are case-insensitive.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::time::Duration;
+
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("ms", 0.001),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("h", 3600.0),
+    ("d", 86400.0),
+];
+
+const SIZE_UNITS: &[(&str, u64)] = &[
+    ("b", 1),
+    ("k", 1_000),
+    ("kb", 1_000),
+    ("kib", 1 << 10),
+    ("m", 1_000_000),
+    ("mb", 1_000_000),
+    ("mib", 1 << 20),
+    ("g", 1_000_000_000),
+    ("gb", 1_000_000_000),
+    ("gib", 1 << 30),
+];
+
+/// Split `text` into a leading number and the rest
+fn split_number(text: &str) -> Option<(f64, &str)> {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let number = text[..end].parse::<f64>().ok()?;
+    Some((number, &text[end..]))
+}
+
+/// Parse a duration: seconds, or numbers with units (`2m30s`)
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || {
+        format!(
+            "invalid duration '{}' (expected seconds or a duration such as 500ms, 30s, 2m30s, 12h)",
+            value
+        )
+    };
+    let text = value.trim().to_ascii_lowercase();
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(secs) = text.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).map_err(|_| invalid());
+    }
+
+    let mut rest = text.as_str();
+    let mut secs = 0.0;
+    while !rest.is_empty() {
+        let (number, after) = split_number(rest).ok_or_else(invalid)?;
+        let unit_len = after
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(after.len());
+        let (_, scale) = DURATION_UNITS
+            .iter()
+            .find(|(unit, _)| *unit == &after[..unit_len])
+            .ok_or_else(invalid)?;
+        secs += number * scale;
+        rest = &after[unit_len..];
+    }
+    Duration::try_from_secs_f64(secs).map_err(|_| invalid())
+}
+
+/// Parse a duration that must be longer than zero
+pub fn parse_positive_duration(value: &str) -> Result<Duration, String> {
+    let duration = parse_duration(value)?;
+    if duration.is_zero() {
+        return Err(format!(
+            "invalid duration '{}' (must be more than 0)",
+            value
+        ));
+    }
+    Ok(duration)
+}
+
+/// Parse a duration of whole seconds, for settings kept in seconds
+pub fn parse_seconds(value: &str) -> Result<u64, String> {
+    let duration = parse_duration(value)?;
+    if duration.subsec_nanos() != 0 {
+        return Err(format!(
+            "invalid duration '{}' (must be whole seconds)",
+            value
+        ));
+    }
+    Ok(duration.as_secs())
+}
+
+/// Parse a size: bytes, or a number with a unit (`10MB`, `512KiB`)
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let invalid = || {
+        format!(
+            "invalid size '{}' (expected bytes or a size such as 512KB, 10MB, 1GiB)",
+            value
+        )
+    };
+    let text = value.trim().to_ascii_lowercase();
+    let (number, unit) = split_number(&text).ok_or_else(invalid)?;
+    let scale = match unit.trim() {
+        "" => 1,
+        unit => {
+            SIZE_UNITS
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .ok_or_else(invalid)?
+                .1
+        }
+    };
+    let bytes = number * scale as f64;
+    if !bytes.is_finite() || bytes >= u64::MAX as f64 || bytes.fract() != 0.0 {
+        return Err(invalid());
+    }
+    Ok(bytes as u64)
+}
+
+/// Read whole seconds given as a number or as a duration string, for
+/// `#[serde(deserialize_with)]`
+pub fn deserialize_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Number(u64),
+        Text(String),
+    }
+
+    match Seconds::deserialize(deserializer)? {
+        Seconds::Number(secs) => Ok(secs),
+        Seconds::Text(text) => parse_seconds(&text).map_err(D::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_take_seconds_or_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_duration("2m30s"), Ok(Duration::from_secs(150)));
+        assert_eq!(parse_duration(" 1D "), Ok(Duration::from_secs(86400)));
+
+        for invalid in ["", "12x", "h", "2m30", "-5s", "1..5s"] {
+            let err = parse_duration(invalid).unwrap_err();
+            assert!(err.contains("expected seconds or a duration"), "{}", err);
+        }
+        assert!(parse_positive_duration("0s").is_err());
+        assert_eq!(parse_seconds("2m"), Ok(120));
+        assert!(parse_seconds("1.5s")
+            .unwrap_err()
+            .contains("must be whole seconds"));
+    }
+
+    #[test]
+    fn sizes_take_bytes_or_units() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("10MB"), Ok(10_000_000));
+        assert_eq!(parse_size("10 mib"), Ok(10 << 20));
+        assert_eq!(parse_size("1.5KB"), Ok(1500));
+        assert_eq!(parse_size("2GiB"), Ok(2 << 30));
+
+        for invalid in ["", "MB", "10XB", "0.5", "-1"] {
+            let err = parse_size(invalid).unwrap_err();
+            assert!(err.contains("expected bytes or a size"), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! The host allowlist holds for library callers, not only the CLI
+
+use mockito::Server;
+use tempfile::TempDir;
+use uxc_core::cache::CacheConfig;
+use uxc_core::{allowlist, Client};
+
+#[tokio::test]
+async fn clients_cannot_reach_hosts_outside_the_allowlist() {
+    let home = TempDir::new().unwrap();
+    std::env::set_var("HOME", home.path());
+    allowlist::set_allowed_hosts(Some("*.example.com"));
+
+    let mut server = Server::new_async().await;
+    let schema = server
+        .mock("GET", "/openapi.json")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let err = Client::builder()
+        .endpoint(server.url())
+        .cache(CacheConfig {
+            enabled: false,
+            ..Default::default()
+        })
+        .build()
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        uxc_core::error::code_of(&err),
+        "HOST_NOT_ALLOWED",
+        "{}",
+        err
+    );
+    schema.assert_async().await;
+
+    let history = std::fs::read_to_string(home.path().join(".uxc/history.jsonl")).unwrap();
+    assert!(history.contains("\"BLOCKED\""), "{}", history);
+}
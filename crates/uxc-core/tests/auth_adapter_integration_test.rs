@@ -6,7 +6,7 @@
 use std::env;
 use std::ffi::OsString;
 use tempfile::TempDir;
-use uxc::auth::{AuthType, Profile, Profiles};
+use uxc_core::auth::{AuthType, Profile, Profiles};
 
 struct TestEnv {
     _temp_dir: TempDir,
@@ -84,7 +84,7 @@ fn test_auth_apply_to_request_bearer() {
     let client = Client::new();
 
     let req = client.get("http://example.com");
-    let req = uxc::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+    let req = uxc_core::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
 
     // Build the request and verify headers
     let built_req = req.build().expect("Failed to build request");
@@ -102,7 +102,7 @@ fn test_auth_apply_to_request_api_key() {
     let client = Client::new();
 
     let req = client.post("http://example.com");
-    let req = uxc::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+    let req = uxc_core::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
 
     // Build the request and verify headers
     let built_req = req.build().expect("Failed to build request");
@@ -120,7 +120,7 @@ fn test_auth_apply_to_request_basic() {
     let client = Client::new();
 
     let req = client.get("http://example.com");
-    let req = uxc::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
+    let req = uxc_core::auth::apply_auth_to_request(req, &profile.auth_type, &profile.api_key);
 
     // Build the request and verify headers
     let built_req = req.build().expect("Failed to build request");
@@ -131,9 +131,41 @@ fn test_auth_apply_to_request_basic() {
     );
 }
 
+#[test]
+fn test_profile_apply_to_request_resolves_env_reference() {
+    use reqwest::Client;
+
+    env::set_var("UXC_TEST_ENV_TOKEN", "token-from-env");
+    let profile = Profile::new("env:UXC_TEST_ENV_TOKEN".to_string(), AuthType::Bearer);
+    assert_eq!(profile.mask_api_key(), "env:UXC_TEST_ENV_TOKEN");
+
+    let req = profile
+        .apply_to_request(Client::new().get("http://example.com"))
+        .expect("Failed to resolve env reference");
+    let built_req = req.build().expect("Failed to build request");
+    assert_eq!(
+        built_req.headers().get("authorization"),
+        Some(&"Bearer token-from-env".parse().unwrap())
+    );
+}
+
+#[test]
+fn test_profile_apply_to_request_reports_unresolved_reference() {
+    use reqwest::Client;
+
+    let profile = Profile::new("env:UXC_TEST_UNSET_TOKEN".to_string(), AuthType::Bearer);
+    let err = profile
+        .apply_to_request(Client::new().get("http://example.com"))
+        .unwrap_err();
+
+    let message = format!("{:#}", err);
+    assert!(message.contains("Failed to resolve profile api_key"));
+    assert!(message.contains("UXC_TEST_UNSET_TOKEN"));
+}
+
 #[test]
 fn test_auth_to_metadata_bearer() {
-    let metadata = uxc::auth::auth_to_metadata(&AuthType::Bearer, "test-token")
+    let metadata = uxc_core::auth::auth_to_metadata(&AuthType::Bearer, "test-token")
         .expect("Failed to create metadata");
 
     let auth_value = metadata
@@ -144,7 +176,7 @@ fn test_auth_to_metadata_bearer() {
 
 #[test]
 fn test_auth_to_metadata_api_key() {
-    let metadata = uxc::auth::auth_to_metadata(&AuthType::ApiKey, "test-api-key")
+    let metadata = uxc_core::auth::auth_to_metadata(&AuthType::ApiKey, "test-api-key")
         .expect("Failed to create metadata");
 
     let api_key_value = metadata
@@ -155,7 +187,7 @@ fn test_auth_to_metadata_api_key() {
 
 #[test]
 fn test_auth_to_metadata_basic() {
-    let metadata = uxc::auth::auth_to_metadata(&AuthType::Basic, "user:password")
+    let metadata = uxc_core::auth::auth_to_metadata(&AuthType::Basic, "user:password")
         .expect("Failed to create metadata");
 
     // "user:password" Base64-encoded is "dXNlcjpwYXNzd29yZA=="
@@ -168,7 +200,7 @@ fn test_auth_to_metadata_basic() {
 #[test]
 fn test_auth_to_metadata_invalid_token() {
     // Test with invalid metadata characters (e.g., newline)
-    let result = uxc::auth::auth_to_metadata(&AuthType::Bearer, "test\n token");
+    let result = uxc_core::auth::auth_to_metadata(&AuthType::Bearer, "test\n token");
 
     assert!(result.is_err());
 }
@@ -6,7 +6,7 @@
 use std::fs;
 use std::sync::{Mutex, MutexGuard, OnceLock};
 use tempfile::TempDir;
-use uxc::auth::{AuthType, Profile, Profiles};
+use uxc_core::auth::{AuthType, Profile, Profiles};
 
 fn home_env_lock() -> &'static Mutex<()> {
     static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -1,10 +1,9 @@
 //! Cache configuration tests
 
 use std::fs;
-use std::path::PathBuf;
 use tempfile::TempDir;
 
-use uxc::cache::CacheConfig;
+use uxc_core::cache::{CacheBackend, CacheConfig};
 
 #[test]
 fn test_load_from_file_not_exists() {
@@ -51,7 +50,9 @@ fn test_load_from_file_valid_config() {
 [cache]
 enabled = true
 ttl = 3600
-max_size = 1048576
+max_bytes = 1048576
+max_entries = 50
+backend = "sqlite"
 "#,
     )
     .expect("Failed to write config");
@@ -64,6 +65,12 @@ max_size = 1048576
     assert!(config.enabled, "Config should be enabled");
     assert_eq!(config.ttl, 3600, "TTL should be 3600");
     assert_eq!(config.max_size, 1048576, "Max size should be 1048576");
+    assert_eq!(config.max_entries, 50, "Max entries should be 50");
+    assert_eq!(
+        config.backend,
+        CacheBackend::Sqlite,
+        "Backend should be sqlite"
+    );
 
     // Restore HOME
     match prev_home {
@@ -100,7 +107,7 @@ max_size = -100
     let result = CacheConfig::load_from_file();
 
     assert!(result.is_ok(), "Should succeed with fallback defaults");
-    let config = result.unwrap();
+    let _config = result.unwrap();
     // Values should fall back to defaults when invalid
 
     // Restore HOME
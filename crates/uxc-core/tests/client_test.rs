@@ -0,0 +1,165 @@
+//! Library client tests against a mock OpenAPI endpoint
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use mockito::{Matcher, Server, ServerGuard};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use uxc_core::adapters::{ExecutionResult, ProtocolType};
+use uxc_core::auth::{AuthType, Profile};
+use uxc_core::cache::CacheConfig;
+use uxc_core::middleware::{Call, Middleware};
+use uxc_core::Client;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Pet {
+    id: u64,
+    name: String,
+}
+
+/// Tags calls with `x-caller` and drops pets named `hidden` from results
+struct Tag;
+
+#[async_trait]
+impl Middleware for Tag {
+    async fn on_request(&self, call: &mut Call) -> anyhow::Result<()> {
+        call.headers.insert("x-caller", "client-test".parse()?);
+        Ok(())
+    }
+
+    async fn on_response(&self, _call: &Call, result: &mut ExecutionResult) -> anyhow::Result<()> {
+        if let Some(pets) = result.data.as_array_mut() {
+            pets.retain(|pet| pet["name"] != "hidden");
+        }
+        Ok(())
+    }
+}
+
+async fn petstore() -> ServerGuard {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/openapi.json")
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "openapi": "3.0.0",
+                "info": { "title": "pets", "version": "1.0.0" },
+                "paths": {
+                    "/pets": {
+                        "get": { "responses": { "200": { "description": "ok" } } },
+                        "post": {
+                            "requestBody": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": { "name": { "type": "string" } }
+                                        }
+                                    }
+                                }
+                            },
+                            "responses": { "200": { "description": "ok" } }
+                        }
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+}
+
+fn no_cache() -> CacheConfig {
+    CacheConfig {
+        enabled: false,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn clients_list_describe_and_call_operations() {
+    let mut server = petstore().await;
+    let _created = server
+        .mock("POST", "/pets")
+        .match_body(Matcher::Json(json!({ "name": "Rex" })))
+        .match_header("authorization", "Bearer secret-token")
+        .match_header("x-caller", "client-test")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"id": 7, "name": "Rex"}"#)
+        .create_async()
+        .await;
+    let _pets = server
+        .mock("GET", "/pets")
+        .match_header("x-caller", "client-test")
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[{"id": 7, "name": "Rex"}, {"id": 8, "name": "hidden"}, {"id": 9, "name": "Tom"}]"#,
+        )
+        .create_async()
+        .await;
+
+    let client = Client::builder()
+        .endpoint(server.url())
+        .auth(Profile::new("secret-token".to_string(), AuthType::Bearer))
+        .cache(no_cache())
+        .middleware(Arc::new(Tag))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(client.protocol(), ProtocolType::OpenAPI);
+
+    let operations = client.list_operations().await.unwrap();
+    let ids: Vec<&str> = operations
+        .iter()
+        .map(|operation| operation.operation_id.as_str())
+        .collect();
+    assert!(ids.contains(&"post:/pets"), "{:?}", ids);
+    let detail = client.describe("post:/pets").await.unwrap();
+    assert_eq!(detail.operation_id, "post:/pets");
+
+    let pet: Pet = client
+        .execute_as("post:/pets", json!({ "name": "Rex" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        pet,
+        Pet {
+            id: 7,
+            name: "Rex".to_string()
+        }
+    );
+
+    let names: Vec<String> = client
+        .execute_stream::<Pet>("get:/pets", json!(null))
+        .map(|pet| pet.unwrap().name)
+        .collect()
+        .await;
+    assert_eq!(names, ["Rex", "Tom"]);
+
+    let err = client
+        .execute_as::<Vec<Pet>>("post:/pets", json!({ "name": "Rex" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("expected shape"), "{}", err);
+}
+
+#[tokio::test]
+async fn clients_need_an_endpoint_and_object_arguments() {
+    let err = Client::builder().build().await.err().unwrap();
+    assert!(err.to_string().contains("needs an endpoint"), "{}", err);
+
+    let server = petstore().await;
+    let client = Client::builder()
+        .endpoint(server.url())
+        .cache(no_cache())
+        .build()
+        .await
+        .unwrap();
+    let err = client
+        .execute("get:/pets", json!([1, 2]))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("JSON object"), "{}", err);
+}
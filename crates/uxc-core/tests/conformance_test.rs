@@ -0,0 +1,239 @@
+//! Built-in adapters against the `test-kit` conformance suites
+
+use mockito::{Matcher, Mock, ServerGuard};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uxc_core::adapters::graphql::GraphQLAdapter;
+use uxc_core::adapters::openapi::OpenAPIAdapter;
+use uxc_core::testkit::{self, Fixture};
+use uxc_core::Cache;
+
+/// A mock that counts how often it answered
+async fn counted(
+    server: &mut ServerGuard,
+    method: &str,
+    path: &str,
+    body: Matcher,
+    response: Value,
+) -> (Mock, Arc<AtomicUsize>) {
+    let count = Arc::new(AtomicUsize::new(0));
+    let counter = count.clone();
+    let mock = server
+        .mock(method, path)
+        .match_body(body)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            response.to_string().into_bytes()
+        })
+        .create_async()
+        .await;
+    (mock, count)
+}
+
+struct OpenApiFixture {
+    server: ServerGuard,
+    foreign: String,
+    schema_requests: Arc<AtomicUsize>,
+    _mocks: Vec<Mock>,
+}
+
+impl OpenApiFixture {
+    async fn start(foreign: String) -> Self {
+        let mut server = mockito::Server::new_async().await;
+        let (schema, schema_requests) = counted(
+            &mut server,
+            "GET",
+            "/openapi.json",
+            Matcher::Any,
+            json!({
+                "openapi": "3.0.0",
+                "info": { "title": "pets", "version": "1.0.0" },
+                "paths": {
+                    "/pets/missing": {
+                        "get": { "responses": { "404": { "description": "gone" } } }
+                    }
+                }
+            }),
+        )
+        .await;
+        let missing = server
+            .mock("GET", "/pets/missing")
+            .with_status(404)
+            .with_body("no such pet")
+            .create_async()
+            .await;
+        Self {
+            server,
+            foreign,
+            schema_requests,
+            _mocks: vec![schema, missing],
+        }
+    }
+}
+
+impl Fixture for OpenApiFixture {
+    type Adapter = OpenAPIAdapter;
+
+    fn adapter(&self, cache: Option<Arc<dyn Cache>>) -> OpenAPIAdapter {
+        match cache {
+            Some(cache) => OpenAPIAdapter::new().with_cache(cache),
+            None => OpenAPIAdapter::new(),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        self.server.url()
+    }
+
+    fn foreign_endpoints(&self) -> Vec<String> {
+        vec![self.foreign.clone()]
+    }
+
+    fn schema_requests(&self) -> Option<usize> {
+        Some(self.schema_requests.load(Ordering::SeqCst))
+    }
+
+    fn missing_operation(&self) -> String {
+        "get:/uxc-testkit-missing".to_string()
+    }
+
+    fn rejected_call(&self) -> Option<(String, HashMap<String, Value>)> {
+        Some(("get:/pets/missing".to_string(), HashMap::new()))
+    }
+}
+
+struct GraphQlFixture {
+    server: ServerGuard,
+    foreign: String,
+    schema_requests: Arc<AtomicUsize>,
+    _mocks: Vec<Mock>,
+}
+
+impl GraphQlFixture {
+    async fn start(foreign: String) -> Self {
+        let mut server = mockito::Server::new_async().await;
+        let string = json!({ "kind": "SCALAR", "name": "String", "ofType": null });
+        let (schema, schema_requests) = counted(
+            &mut server,
+            "POST",
+            "/",
+            Matcher::Regex("__schema".to_string()),
+            json!({ "data": { "__schema": {
+                "queryType": { "name": "Query" },
+                "mutationType": null,
+                "subscriptionType": null,
+                "types": [
+                    { "kind": "OBJECT", "name": "Query", "fields": [
+                        { "name": "pet", "description": null, "args": [
+                            { "name": "name", "description": null, "type": string, "defaultValue": null }
+                        ], "type": string }
+                    ] },
+                    { "kind": "SCALAR", "name": "String" }
+                ]
+            } } }),
+        )
+        .await;
+        let rejected = server
+            .mock("POST", "/")
+            .match_body(Matcher::Regex("pet".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data":null,"errors":[{"message":"no such pet"}]}"#)
+            .create_async()
+            .await;
+        Self {
+            server,
+            foreign,
+            schema_requests,
+            _mocks: vec![schema, rejected],
+        }
+    }
+}
+
+impl Fixture for GraphQlFixture {
+    type Adapter = GraphQLAdapter;
+
+    fn adapter(&self, cache: Option<Arc<dyn Cache>>) -> GraphQLAdapter {
+        match cache {
+            Some(cache) => GraphQLAdapter::new().with_cache(cache),
+            None => GraphQLAdapter::new(),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        self.server.url()
+    }
+
+    fn foreign_endpoints(&self) -> Vec<String> {
+        vec![self.foreign.clone()]
+    }
+
+    fn schema_requests(&self) -> Option<usize> {
+        Some(self.schema_requests.load(Ordering::SeqCst))
+    }
+
+    fn missing_operation(&self) -> String {
+        "query/uxcTestkitMissing".to_string()
+    }
+
+    fn rejected_call(&self) -> Option<(String, HashMap<String, Value>)> {
+        let args = HashMap::from([("name".to_string(), json!("Rex"))]);
+        Some(("query/pet".to_string(), args))
+    }
+}
+
+#[tokio::test]
+async fn openapi_adapter_conforms() {
+    let other = mockito::Server::new_async().await;
+    let fixture = OpenApiFixture::start(other.url()).await;
+    testkit::run(&fixture).await.assert_passed();
+}
+
+#[tokio::test]
+async fn graphql_adapter_conforms() {
+    let other = OpenApiFixture::start(String::new()).await;
+    let fixture = GraphQlFixture::start(other.endpoint()).await;
+    testkit::run(&fixture).await.assert_passed();
+}
+
+#[tokio::test]
+async fn failures_are_listed_in_the_report() {
+    struct Misbehaving(OpenApiFixture);
+
+    impl Fixture for Misbehaving {
+        type Adapter = OpenAPIAdapter;
+
+        // Ignores the cache it is given
+        fn adapter(&self, _: Option<Arc<dyn Cache>>) -> OpenAPIAdapter {
+            OpenAPIAdapter::new()
+        }
+
+        fn endpoint(&self) -> String {
+            self.0.endpoint()
+        }
+
+        fn schema_requests(&self) -> Option<usize> {
+            self.0.schema_requests()
+        }
+
+        fn missing_operation(&self) -> String {
+            self.0.missing_operation()
+        }
+    }
+
+    let fixture = Misbehaving(OpenApiFixture::start(String::new()).await);
+    let report = testkit::run(&fixture).await;
+    assert!(!report.passed());
+    let failed: Vec<&str> = report.failures().map(|check| check.name.as_str()).collect();
+    assert_eq!(
+        failed,
+        [
+            "second fetch is served from the cache",
+            "another adapter sharing the cache lists operations without fetching"
+        ],
+        "{}",
+        report
+    );
+}
@@ -6,8 +6,8 @@
 //!
 //! Reference: https://spec.graphql.org/
 
-use uxc::adapters::graphql::GraphQLAdapter;
-use uxc::adapters::Adapter;
+use uxc_core::adapters::graphql::GraphQLAdapter;
+use uxc_core::adapters::Adapter;
 
 /// Helper to run async code with a mock server
 fn run_async<F, R>(f: F) -> R
@@ -15,7 +15,7 @@ where
     F: FnOnce(mockito::ServerGuard) -> R,
     R: Send + 'static,
 {
-    let mut server = mockito::Server::new();
+    let server = mockito::Server::new();
     f(server)
 }
 
@@ -44,7 +44,7 @@ fn test_graphql_introspection_detects_valid_endpoint() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -52,7 +52,10 @@ fn test_graphql_introspection_detects_valid_endpoint() {
         let result = rt.block_on(async { adapter.can_handle(&server.url()).await });
 
         assert!(result.is_ok());
-        assert!(result.unwrap(), "Should detect GraphQL endpoint via introspection");
+        assert!(
+            result.unwrap(),
+            "Should detect GraphQL endpoint via introspection"
+        );
     });
 }
 
@@ -68,7 +71,7 @@ fn test_graphql_introspection_rejects_non_graphql() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&not_graphql.to_string())
+            .with_body(not_graphql.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -97,7 +100,7 @@ fn test_graphql_introspection_handles_errors_response() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&graphql_errors.to_string())
+            .with_body(graphql_errors.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -105,7 +108,10 @@ fn test_graphql_introspection_handles_errors_response() {
         let result = rt.block_on(async { adapter.can_handle(&server.url()).await });
 
         assert!(result.is_ok());
-        assert!(result.unwrap(), "GraphQL errors in response still indicate GraphQL endpoint");
+        assert!(
+            result.unwrap(),
+            "GraphQL errors in response still indicate GraphQL endpoint"
+        );
     });
 }
 
@@ -169,7 +175,7 @@ fn test_graphql_parses_query_fields() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -182,7 +188,10 @@ fn test_graphql_parses_query_fields() {
         assert!(operations.iter().any(|op| op.operation_id == "query/user"));
         assert!(operations.iter().any(|op| op.operation_id == "query/users"));
 
-        let user_op = operations.iter().find(|op| op.operation_id == "query/user").unwrap();
+        let user_op = operations
+            .iter()
+            .find(|op| op.operation_id == "query/user")
+            .unwrap();
         assert_eq!(user_op.description.as_ref().unwrap(), "Get a user by ID");
         assert_eq!(user_op.parameters.len(), 1);
         assert_eq!(user_op.parameters[0].name, "id");
@@ -231,7 +240,7 @@ fn test_graphql_parses_mutation_fields() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -242,7 +251,10 @@ fn test_graphql_parses_mutation_fields() {
 
         assert_eq!(operations.len(), 1);
         assert_eq!(operations[0].operation_id, "mutation/createUser");
-        assert_eq!(operations[0].description.as_ref().unwrap(), "Create a new user");
+        assert_eq!(
+            operations[0].description.as_ref().unwrap(),
+            "Create a new user"
+        );
         assert_eq!(operations[0].parameters.len(), 2);
         assert!(operations[0].parameters[0].required);
         assert!(!operations[0].parameters[1].required);
@@ -285,7 +297,7 @@ fn test_graphql_parses_subscription_fields() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -296,7 +308,10 @@ fn test_graphql_parses_subscription_fields() {
 
         assert_eq!(operations.len(), 1);
         assert_eq!(operations[0].operation_id, "subscription/userUpdated");
-        assert_eq!(operations[0].description.as_ref().unwrap(), "Subscribe to user updates");
+        assert_eq!(
+            operations[0].description.as_ref().unwrap(),
+            "Subscribe to user updates"
+        );
     });
 }
 
@@ -351,7 +366,7 @@ fn test_graphql_parses_scalar_types() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -361,19 +376,34 @@ fn test_graphql_parses_scalar_types() {
             .unwrap();
 
         assert_eq!(operations.len(), 5);
-        let int_op = operations.iter().find(|op| op.operation_id == "query/intField").unwrap();
+        let int_op = operations
+            .iter()
+            .find(|op| op.operation_id == "query/intField")
+            .unwrap();
         assert_eq!(int_op.parameters[0].param_type, "Int");
 
-        let float_op = operations.iter().find(|op| op.operation_id == "query/floatField").unwrap();
+        let float_op = operations
+            .iter()
+            .find(|op| op.operation_id == "query/floatField")
+            .unwrap();
         assert_eq!(float_op.parameters[0].param_type, "Float");
 
-        let string_op = operations.iter().find(|op| op.operation_id == "query/stringField").unwrap();
+        let string_op = operations
+            .iter()
+            .find(|op| op.operation_id == "query/stringField")
+            .unwrap();
         assert_eq!(string_op.parameters[0].param_type, "String");
 
-        let bool_op = operations.iter().find(|op| op.operation_id == "query/boolField").unwrap();
+        let bool_op = operations
+            .iter()
+            .find(|op| op.operation_id == "query/boolField")
+            .unwrap();
         assert_eq!(bool_op.parameters[0].param_type, "Boolean");
 
-        let id_op = operations.iter().find(|op| op.operation_id == "query/idField").unwrap();
+        let id_op = operations
+            .iter()
+            .find(|op| op.operation_id == "query/idField")
+            .unwrap();
         assert_eq!(id_op.parameters[0].param_type, "ID");
     });
 }
@@ -410,7 +440,7 @@ fn test_graphql_parses_non_null_types() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -457,7 +487,7 @@ fn test_graphql_parses_list_types() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -516,7 +546,7 @@ fn test_graphql_parses_complex_nested_types() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -570,13 +600,17 @@ fn test_graphql_builds_input_schema_for_query() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = GraphQLAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "query/user").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "query/user")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -639,21 +673,34 @@ fn test_graphql_builds_input_schema_with_input_object() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = GraphQLAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "query/search").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "query/search")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
         let schema = detail.input_schema.unwrap();
         assert!(schema["properties"]["filter"]["properties"]["limit"].is_object());
-        assert_eq!(schema["properties"]["filter"]["properties"]["limit"]["type"], "integer");
-        assert_eq!(schema["properties"]["filter"]["properties"]["limit"]["description"], "Max results");
-        assert_eq!(schema["properties"]["filter"]["properties"]["query"]["type"], "string");
+        assert_eq!(
+            schema["properties"]["filter"]["properties"]["limit"]["type"],
+            "integer"
+        );
+        assert_eq!(
+            schema["properties"]["filter"]["properties"]["limit"]["description"],
+            "Max results"
+        );
+        assert_eq!(
+            schema["properties"]["filter"]["properties"]["query"]["type"],
+            "string"
+        );
     });
 }
 
@@ -700,13 +747,17 @@ fn test_graphql_builds_input_schema_with_enum() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = GraphQLAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "query/items").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "query/items")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -745,13 +796,15 @@ fn test_graphql_handles_missing_operation() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = GraphQLAdapter::new();
         let result = rt.block_on(async {
-            adapter.describe_operation(&server.url(), "query/nonexistent").await
+            adapter
+                .describe_operation(&server.url(), "query/nonexistent")
+                .await
         });
 
         assert!(result.is_err());
@@ -777,7 +830,7 @@ fn test_graphql_handles_invalid_operation_format() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -788,7 +841,11 @@ fn test_graphql_handles_invalid_operation_format() {
         assert!(result.is_err(), "Should fail for operation without prefix");
 
         // Invalid prefix - should fail with format error
-        let result = rt.block_on(async { adapter.describe_operation(&server.url(), "invalid/user").await });
+        let result = rt.block_on(async {
+            adapter
+                .describe_operation(&server.url(), "invalid/user")
+                .await
+        });
         assert!(result.is_err(), "Should fail for invalid operation type");
         let err_msg = result.unwrap_err().to_string();
         // Check that error message mentions the invalid operation
@@ -813,7 +870,7 @@ fn test_graphql_handles_introspection_errors() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&error_response.to_string())
+            .with_body(error_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -821,7 +878,10 @@ fn test_graphql_handles_introspection_errors() {
         let result = rt.block_on(async { adapter.fetch_schema(&server.url()).await });
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("GraphQL introspection failed"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("GraphQL introspection failed"));
     });
 }
 
@@ -850,7 +910,7 @@ fn test_graphql_handles_empty_schema() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&empty_response.to_string())
+            .with_body(empty_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -891,7 +951,7 @@ fn test_graphql_handles_operation_without_args() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -940,7 +1000,7 @@ fn test_graphql_return_type_display_names() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -949,13 +1009,22 @@ fn test_graphql_return_type_display_names() {
             .block_on(async { adapter.list_operations(&server.url()).await })
             .unwrap();
 
-        let simple = operations.iter().find(|op| op.operation_id == "query/simple").unwrap();
+        let simple = operations
+            .iter()
+            .find(|op| op.operation_id == "query/simple")
+            .unwrap();
         assert_eq!(simple.return_type.as_ref().unwrap(), "String");
 
-        let required = operations.iter().find(|op| op.operation_id == "query/required").unwrap();
+        let required = operations
+            .iter()
+            .find(|op| op.operation_id == "query/required")
+            .unwrap();
         assert_eq!(required.return_type.as_ref().unwrap(), "String!");
 
-        let list = operations.iter().find(|op| op.operation_id == "query/listOfRequired").unwrap();
+        let list = operations
+            .iter()
+            .find(|op| op.operation_id == "query/listOfRequired")
+            .unwrap();
         assert_eq!(list.return_type.as_ref().unwrap(), "[Int!]");
     });
 }
@@ -993,7 +1062,7 @@ fn test_graphql_description_optional_fields() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1003,10 +1072,16 @@ fn test_graphql_description_optional_fields() {
             .unwrap();
 
         assert_eq!(operations.len(), 2);
-        let with_desc = operations.iter().find(|op| op.operation_id == "query/withDescription").unwrap();
+        let with_desc = operations
+            .iter()
+            .find(|op| op.operation_id == "query/withDescription")
+            .unwrap();
         assert_eq!(with_desc.description.as_ref().unwrap(), "Has description");
 
-        let without_desc = operations.iter().find(|op| op.operation_id == "query/withoutDescription").unwrap();
+        let without_desc = operations
+            .iter()
+            .find(|op| op.operation_id == "query/withoutDescription")
+            .unwrap();
         assert!(without_desc.description.is_none());
     });
 }
@@ -1055,13 +1130,17 @@ fn test_graphql_handles_circular_type_references() {
             .match_header("content-type", "application/json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&introspection_response.to_string())
+            .with_body(introspection_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = GraphQLAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "query/node").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "query/node")
+                    .await
+            })
             .unwrap();
 
         // Should handle circular references without infinite recursion
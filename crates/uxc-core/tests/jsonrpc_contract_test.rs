@@ -7,8 +7,8 @@
 //! Reference: https://www.jsonrpc.org/specification
 //! OpenRPC: https://spec.open-rpc.org/
 
-use uxc::adapters::jsonrpc::JsonRpcAdapter;
-use uxc::adapters::Adapter;
+use uxc_core::adapters::jsonrpc::JsonRpcAdapter;
+use uxc_core::adapters::Adapter;
 
 /// Helper to run async code with a mock server
 fn run_async<F, R>(f: F) -> R
@@ -16,7 +16,7 @@ where
     F: FnOnce(mockito::ServerGuard) -> R,
     R: Send + 'static,
 {
-    let mut server = mockito::Server::new();
+    let server = mockito::Server::new();
     f(server)
 }
 
@@ -49,7 +49,7 @@ fn test_jsonrpc_discovery_finds_openrpc_document() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -77,7 +77,7 @@ fn test_jsonrpc_discovery_finds_well_known_openrpc() {
             .mock("GET", "/.well-known/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -121,7 +121,7 @@ fn test_jsonrpc_discovery_via_rpc_discover() {
             })))
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&discover_response.to_string())
+            .with_body(discover_response.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -145,7 +145,7 @@ fn test_jsonrpc_rejects_non_openrpc_document() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&not_openrpc.to_string())
+            .with_body(not_openrpc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -197,7 +197,7 @@ fn test_jsonrpc_lists_all_methods() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -250,7 +250,7 @@ fn test_jsonrpc_method_parsing_parameters() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -267,7 +267,10 @@ fn test_jsonrpc_method_parsing_parameters() {
 
         assert_eq!(method.parameters[0].name, "query");
         assert!(method.parameters[0].required);
-        assert_eq!(method.parameters[0].description.as_ref().unwrap(), "Search query string");
+        assert_eq!(
+            method.parameters[0].description.as_ref().unwrap(),
+            "Search query string"
+        );
 
         assert_eq!(method.parameters[1].name, "limit");
         assert!(!method.parameters[1].required);
@@ -307,7 +310,7 @@ fn test_jsonrpc_method_return_type() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -317,10 +320,16 @@ fn test_jsonrpc_method_return_type() {
             .unwrap();
 
         assert_eq!(operations.len(), 2);
-        let data_method = operations.iter().find(|op| op.operation_id == "getData").unwrap();
+        let data_method = operations
+            .iter()
+            .find(|op| op.operation_id == "getData")
+            .unwrap();
         assert_eq!(data_method.return_type.as_ref().unwrap(), "object");
 
-        let string_method = operations.iter().find(|op| op.operation_id == "getString").unwrap();
+        let string_method = operations
+            .iter()
+            .find(|op| op.operation_id == "getString")
+            .unwrap();
         assert_eq!(string_method.return_type.as_ref().unwrap(), "string");
     });
 }
@@ -352,7 +361,7 @@ fn test_jsonrpc_param_structure_by_name() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -390,7 +399,7 @@ fn test_jsonrpc_param_structure_by_position() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -426,13 +435,17 @@ fn test_jsonrpc_param_structure_either_default() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = JsonRpcAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "methodWithEither").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "methodWithEither")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -467,7 +480,7 @@ fn test_jsonrpc_execution_successful_response() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let execution_response = serde_json::json!({
@@ -480,7 +493,7 @@ fn test_jsonrpc_execution_successful_response() {
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&execution_response.to_string())
+            .with_body(execution_response.to_string())
             .create();
 
         let url = server.url();
@@ -522,7 +535,7 @@ fn test_jsonrpc_execution_with_positional_params() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let execution_response = serde_json::json!({
@@ -539,7 +552,7 @@ fn test_jsonrpc_execution_with_positional_params() {
             })))
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&execution_response.to_string())
+            .with_body(execution_response.to_string())
             .create();
 
         let url = server.url();
@@ -576,7 +589,7 @@ fn test_jsonrpc_standard_error_codes() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         // Test method not found error
@@ -593,7 +606,7 @@ fn test_jsonrpc_standard_error_codes() {
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&error_response.to_string())
+            .with_body(error_response.to_string())
             .create();
 
         let url = server.url();
@@ -601,7 +614,9 @@ fn test_jsonrpc_standard_error_codes() {
         let adapter = JsonRpcAdapter::new();
 
         let result = rt.block_on(async {
-            adapter.execute(&url, "nonexistent", std::collections::HashMap::new()).await
+            adapter
+                .execute(&url, "nonexistent", std::collections::HashMap::new())
+                .await
         });
 
         // Should fail with an error
@@ -628,7 +643,7 @@ fn test_jsonrpc_error_with_data() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let error_response = serde_json::json!({
@@ -648,7 +663,7 @@ fn test_jsonrpc_error_with_data() {
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&error_response.to_string())
+            .with_body(error_response.to_string())
             .create();
 
         let url = server.url();
@@ -656,7 +671,9 @@ fn test_jsonrpc_error_with_data() {
         let adapter = JsonRpcAdapter::new();
 
         let result = rt.block_on(async {
-            adapter.execute(&url, "failingMethod", std::collections::HashMap::new()).await
+            adapter
+                .execute(&url, "failingMethod", std::collections::HashMap::new())
+                .await
         });
 
         assert!(result.is_err());
@@ -688,7 +705,7 @@ fn test_jsonrpc_missing_required_parameter() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let url = server.url();
@@ -697,7 +714,13 @@ fn test_jsonrpc_missing_required_parameter() {
 
         // Call without required parameter
         let result = rt.block_on(async {
-            adapter.execute(&url, "methodWithRequiredParam", std::collections::HashMap::new()).await
+            adapter
+                .execute(
+                    &url,
+                    "methodWithRequiredParam",
+                    std::collections::HashMap::new(),
+                )
+                .await
         });
 
         assert!(result.is_err());
@@ -727,7 +750,7 @@ fn test_jsonrpc_unknown_parameter() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let url = server.url();
@@ -765,7 +788,7 @@ fn test_jsonrpc_missing_result_field() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         // Response missing "result" field
@@ -778,7 +801,7 @@ fn test_jsonrpc_missing_result_field() {
             .mock("POST", "/")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&invalid_response.to_string())
+            .with_body(invalid_response.to_string())
             .create();
 
         let url = server.url();
@@ -786,7 +809,9 @@ fn test_jsonrpc_missing_result_field() {
         let adapter = JsonRpcAdapter::new();
 
         let result = rt.block_on(async {
-            adapter.execute(&url, "noResult", std::collections::HashMap::new()).await
+            adapter
+                .execute(&url, "noResult", std::collections::HashMap::new())
+                .await
         });
 
         assert!(result.is_err());
@@ -809,12 +834,16 @@ fn test_jsonrpc_handles_missing_operation() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = JsonRpcAdapter::new();
-        let result = rt.block_on(async { adapter.describe_operation(&server.url(), "nonexistent").await });
+        let result = rt.block_on(async {
+            adapter
+                .describe_operation(&server.url(), "nonexistent")
+                .await
+        });
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
@@ -844,7 +873,7 @@ fn test_jsonrpc_method_with_no_parameters() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -879,7 +908,7 @@ fn test_jsonrpc_url_resolution_from_servers_field() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         // Should execute against /rpc endpoint
@@ -893,7 +922,7 @@ fn test_jsonrpc_url_resolution_from_servers_field() {
             .mock("POST", "/rpc")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&execution_response.to_string())
+            .with_body(execution_response.to_string())
             .create();
 
         let url = server.url();
@@ -901,7 +930,9 @@ fn test_jsonrpc_url_resolution_from_servers_field() {
         let adapter = JsonRpcAdapter::new();
 
         let result = rt.block_on(async {
-            adapter.execute(&url, "test", std::collections::HashMap::new()).await
+            adapter
+                .execute(&url, "test", std::collections::HashMap::new())
+                .await
         });
 
         assert!(result.is_ok());
@@ -934,7 +965,7 @@ fn test_jsonrpc_description_fallback_to_summary() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -944,10 +975,16 @@ fn test_jsonrpc_description_fallback_to_summary() {
             .unwrap();
 
         assert_eq!(operations.len(), 2);
-        let m1 = operations.iter().find(|op| op.operation_id == "method1").unwrap();
+        let m1 = operations
+            .iter()
+            .find(|op| op.operation_id == "method1")
+            .unwrap();
         assert_eq!(m1.description.as_ref().unwrap(), "Has description");
 
-        let m2 = operations.iter().find(|op| op.operation_id == "method2").unwrap();
+        let m2 = operations
+            .iter()
+            .find(|op| op.operation_id == "method2")
+            .unwrap();
         assert_eq!(m2.description.as_ref().unwrap(), "Has summary");
     });
 }
@@ -965,7 +1002,7 @@ fn test_jsonrpc_empty_schema() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1002,7 +1039,7 @@ fn test_jsonrpc_handles_multiple_params_with_optional() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -1036,7 +1073,7 @@ fn test_jsonrpc_request_id_generation() {
             .mock("GET", "/openrpc.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openrpc_doc.to_string())
+            .with_body(openrpc_doc.to_string())
             .create();
 
         // Verify request has an ID
@@ -1050,11 +1087,14 @@ fn test_jsonrpc_request_id_generation() {
             })))
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(serde_json::json!({
-                "jsonrpc": "2.0",
-                "id": 1,
-                "result": "success"
-            }).to_string())
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": "success"
+                })
+                .to_string(),
+            )
             .create();
 
         let url = server.url();
@@ -1062,7 +1102,9 @@ fn test_jsonrpc_request_id_generation() {
         let adapter = JsonRpcAdapter::new();
 
         let result = rt.block_on(async {
-            adapter.execute(&url, "test", std::collections::HashMap::new()).await
+            adapter
+                .execute(&url, "test", std::collections::HashMap::new())
+                .await
         });
 
         assert!(result.is_ok());
@@ -6,8 +6,8 @@
 //!
 //! Reference: https://spec.openapis.org/oas/v3.0.0
 
-use uxc::adapters::openapi::OpenAPIAdapter;
-use uxc::adapters::Adapter;
+use uxc_core::adapters::openapi::OpenAPIAdapter;
+use uxc_core::adapters::Adapter;
 
 /// Helper to run async code with a mock server
 fn run_async<F, R>(f: F) -> R
@@ -15,7 +15,7 @@ where
     F: FnOnce(mockito::ServerGuard) -> R,
     R: Send + 'static,
 {
-    let mut server = mockito::Server::new();
+    let server = mockito::Server::new();
     f(server)
 }
 
@@ -48,7 +48,7 @@ fn test_openapi_discovery_finds_openapi_v3() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -85,7 +85,7 @@ fn test_openapi_discovery_finds_swagger_v2() {
             .mock("GET", "/swagger.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&swagger_doc.to_string())
+            .with_body(swagger_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -109,7 +109,7 @@ fn test_openapi_rejects_non_openapi_document() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&not_openapi.to_string())
+            .with_body(not_openapi.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -140,7 +140,7 @@ fn test_openapi_tries_multiple_schema_endpoints() {
             .mock("GET", "/api-docs")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -148,7 +148,10 @@ fn test_openapi_tries_multiple_schema_endpoints() {
         let result = rt.block_on(async { adapter.can_handle(&server.url()).await });
 
         assert!(result.is_ok());
-        assert!(result.unwrap(), "Should try multiple endpoints and find schema");
+        assert!(
+            result.unwrap(),
+            "Should try multiple endpoints and find schema"
+        );
     });
 }
 
@@ -192,7 +195,7 @@ fn test_openapi_lists_all_http_methods() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -202,11 +205,21 @@ fn test_openapi_lists_all_http_methods() {
             .unwrap();
 
         assert_eq!(operations.len(), 5);
-        assert!(operations.iter().any(|op| op.operation_id == "get:/resource"));
-        assert!(operations.iter().any(|op| op.operation_id == "post:/resource"));
-        assert!(operations.iter().any(|op| op.operation_id == "put:/resource"));
-        assert!(operations.iter().any(|op| op.operation_id == "patch:/resource"));
-        assert!(operations.iter().any(|op| op.operation_id == "delete:/resource"));
+        assert!(operations
+            .iter()
+            .any(|op| op.operation_id == "get:/resource"));
+        assert!(operations
+            .iter()
+            .any(|op| op.operation_id == "post:/resource"));
+        assert!(operations
+            .iter()
+            .any(|op| op.operation_id == "put:/resource"));
+        assert!(operations
+            .iter()
+            .any(|op| op.operation_id == "patch:/resource"));
+        assert!(operations
+            .iter()
+            .any(|op| op.operation_id == "delete:/resource"));
     });
 }
 
@@ -231,7 +244,7 @@ fn test_openapi_operation_display_name_format() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -280,7 +293,7 @@ fn test_openapi_parses_path_parameters() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -332,7 +345,7 @@ fn test_openapi_parses_query_parameters() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -382,7 +395,7 @@ fn test_openapi_parses_header_parameters() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -435,7 +448,7 @@ fn test_openapi_merges_path_and_operation_parameters() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -491,13 +504,17 @@ fn test_openapi_parses_request_body_schema() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = OpenAPIAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "post:/users").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "post:/users")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -538,13 +555,17 @@ fn test_openapi_request_body_with_multiple_content_types() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = OpenAPIAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "post:/upload").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "post:/upload")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -596,13 +617,17 @@ fn test_openapi_resolves_local_refs() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = OpenAPIAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "post:/users").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "post:/users")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -658,13 +683,17 @@ fn test_openapi_handles_nested_refs() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = OpenAPIAdapter::new();
         let detail = rt
-            .block_on(async { adapter.describe_operation(&server.url(), "post:/users").await })
+            .block_on(async {
+                adapter
+                    .describe_operation(&server.url(), "post:/users")
+                    .await
+            })
             .unwrap();
 
         assert!(detail.input_schema.is_some());
@@ -672,7 +701,10 @@ fn test_openapi_handles_nested_refs() {
         let expanded = &schema["content"]["application/json"]["schema"];
         // Nested refs should be expanded
         assert_eq!(expanded["properties"]["user"]["type"], "object");
-        assert_eq!(expanded["properties"]["user"]["properties"]["id"]["type"], "integer");
+        assert_eq!(
+            expanded["properties"]["user"]["properties"]["id"]["type"],
+            "integer"
+        );
     });
 }
 
@@ -700,13 +732,15 @@ fn test_openapi_handles_missing_operation() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = OpenAPIAdapter::new();
         let result = rt.block_on(async {
-            adapter.describe_operation(&server.url(), "get:/nonexistent").await
+            adapter
+                .describe_operation(&server.url(), "get:/nonexistent")
+                .await
         });
 
         assert!(result.is_err());
@@ -727,7 +761,7 @@ fn test_openapi_handles_invalid_operation_id_format() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -738,7 +772,11 @@ fn test_openapi_handles_invalid_operation_id_format() {
         assert!(result.is_err());
 
         // Invalid: wrong format
-        let result = rt.block_on(async { adapter.describe_operation(&server.url(), "GET /users").await });
+        let result = rt.block_on(async {
+            adapter
+                .describe_operation(&server.url(), "GET /users")
+                .await
+        });
         assert!(result.is_err());
     });
 }
@@ -756,15 +794,22 @@ fn test_openapi_handles_unsupported_http_method() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
         let adapter = OpenAPIAdapter::new();
-        let result = rt.block_on(async { adapter.describe_operation(&server.url(), "invalid:/path").await });
+        let result = rt.block_on(async {
+            adapter
+                .describe_operation(&server.url(), "invalid:/path")
+                .await
+        });
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Unsupported HTTP method"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported HTTP method"));
     });
 }
 
@@ -785,7 +830,7 @@ fn test_openapi_handles_empty_paths() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -818,7 +863,7 @@ fn test_openapi_handles_path_with_trailing_slash() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -858,7 +903,7 @@ fn test_openapi_descriptions_fallback_to_summary() {
             .mock("GET", "/openapi.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&openapi_doc.to_string())
+            .with_body(openapi_doc.to_string())
             .create();
 
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -868,10 +913,158 @@ fn test_openapi_descriptions_fallback_to_summary() {
             .unwrap();
 
         assert_eq!(operations.len(), 2);
-        let get_op = operations.iter().find(|op| op.operation_id == "get:/users").unwrap();
+        let get_op = operations
+            .iter()
+            .find(|op| op.operation_id == "get:/users")
+            .unwrap();
         assert_eq!(get_op.description.as_ref().unwrap(), "List all users");
 
-        let post_op = operations.iter().find(|op| op.operation_id == "post:/users").unwrap();
+        let post_op = operations
+            .iter()
+            .find(|op| op.operation_id == "post:/users")
+            .unwrap();
         assert_eq!(post_op.description.as_ref().unwrap(), "Create a new user");
     });
 }
+
+// ============================================================================
+// Schema Cache Revalidation Tests
+// ============================================================================
+
+#[test]
+fn test_openapi_revalidates_expired_schema_with_validators() {
+    run_async(|mut server| {
+        let openapi_doc = serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Cached API", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": { "responses": { "200": { "description": "Success" } } }
+                }
+            }
+        });
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = uxc_core::cache::create_cache(uxc_core::cache::CacheConfig {
+            enabled: true,
+            ttl: 0,
+            max_size: 0,
+            max_entries: 0,
+            location: temp_dir.path().to_path_buf(),
+            backend: Default::default(),
+            stale_while_revalidate: false,
+            encryption_key: None,
+            max_snapshots: uxc_core::cache::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        })
+        .unwrap();
+        let adapter = OpenAPIAdapter::new().with_cache(cache);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let full = server
+            .mock("GET", "/openapi.json")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"v1\"")
+            .with_header("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_body(openapi_doc.to_string())
+            .create();
+        let schema = rt.block_on(adapter.fetch_schema(&server.url())).unwrap();
+        assert_eq!(schema, openapi_doc);
+        full.remove();
+
+        // TTL 0 expires the entry immediately; the next fetch is conditional
+        let not_modified = server
+            .mock("GET", "/openapi.json")
+            .match_header("if-none-match", "\"v1\"")
+            .match_header("if-modified-since", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_status(304)
+            .expect(1)
+            .create();
+        let schema = rt.block_on(adapter.fetch_schema(&server.url())).unwrap();
+        assert_eq!(schema, openapi_doc);
+        not_modified.assert();
+        not_modified.remove();
+
+        // Server errors fall back to the stale copy
+        let _failing = server
+            .mock("GET", "/openapi.json")
+            .with_status(503)
+            .create();
+        let schema = rt.block_on(adapter.fetch_schema(&server.url())).unwrap();
+        assert_eq!(schema, openapi_doc);
+    });
+}
+
+#[test]
+fn test_openapi_serves_stale_schema_while_revalidating() {
+    run_async(|mut server| {
+        let schema_doc = |version: &str| {
+            serde_json::json!({
+                "openapi": "3.0.0",
+                "info": { "title": "Cached API", "version": version },
+                "paths": {}
+            })
+        };
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = uxc_core::cache::create_cache(uxc_core::cache::CacheConfig {
+            enabled: true,
+            ttl: 0,
+            max_size: 0,
+            max_entries: 0,
+            location: temp_dir.path().to_path_buf(),
+            backend: Default::default(),
+            stale_while_revalidate: true,
+            encryption_key: None,
+            max_snapshots: uxc_core::cache::DEFAULT_MAX_SNAPSHOTS,
+            as_of: None,
+        })
+        .unwrap();
+        let adapter = OpenAPIAdapter::new().with_cache(cache);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let v1 = server
+            .mock("GET", "/openapi.json")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"v1\"")
+            .with_body(schema_doc("1").to_string())
+            .create();
+        let schema = rt.block_on(adapter.fetch_schema(&server.url())).unwrap();
+        assert_eq!(schema, schema_doc("1"));
+        v1.remove();
+
+        // The expired copy is returned at once; the refresh happens in the background
+        let v2 = server
+            .mock("GET", "/openapi.json")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"v2\"")
+            .with_body(schema_doc("2").to_string())
+            .expect(1)
+            .create();
+        let schema = rt.block_on(adapter.fetch_schema(&server.url())).unwrap();
+        assert_eq!(schema, schema_doc("1"));
+        assert!(uxc_core::cache::served_stale());
+        rt.block_on(uxc_core::cache::wait_for_refreshes(
+            std::time::Duration::from_secs(5),
+        ));
+        v2.assert();
+        v2.remove();
+
+        let _v3 = server
+            .mock("GET", "/openapi.json")
+            .match_header("if-none-match", "\"v2\"")
+            .with_status(304)
+            .create();
+        let schema = rt.block_on(adapter.fetch_schema(&server.url())).unwrap();
+        assert_eq!(schema, schema_doc("2"));
+        rt.block_on(uxc_core::cache::wait_for_refreshes(
+            std::time::Duration::from_secs(5),
+        ));
+    });
+}
@@ -0,0 +1,229 @@
+//! Custom adapter registration tests
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uxc_core::adapters::{
+    registry, Adapter, ExecutionMetadata, ExecutionResult, Operation, OperationDetail,
+    ProtocolDetector, ProtocolType, RequestPlan,
+};
+use uxc_core::cache::CacheConfig;
+use uxc_core::Client;
+
+/// Serves `memory://` URLs with one operation, `echo`
+struct Memory;
+
+#[async_trait]
+impl Adapter for Memory {
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Plugin
+    }
+
+    async fn can_handle(&self, url: &str) -> Result<bool> {
+        Ok(url.starts_with("memory://"))
+    }
+
+    async fn fetch_schema(&self, _url: &str) -> Result<Value> {
+        Ok(json!({ "operations": ["echo"] }))
+    }
+
+    async fn list_operations(&self, _url: &str) -> Result<Vec<Operation>> {
+        Ok(vec![Operation {
+            operation_id: "echo".to_string(),
+            display_name: "echo".to_string(),
+            description: None,
+            parameters: Vec::new(),
+            return_type: None,
+            tags: Vec::new(),
+        }])
+    }
+
+    async fn describe_operation(&self, _url: &str, operation: &str) -> Result<OperationDetail> {
+        Ok(OperationDetail {
+            operation_id: operation.to_string(),
+            display_name: operation.to_string(),
+            description: None,
+            parameters: Vec::new(),
+            return_type: None,
+            input_schema: None,
+            responses: None,
+            security: None,
+            servers: None,
+            fields: None,
+        })
+    }
+
+    async fn execute(
+        &self,
+        url: &str,
+        operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult> {
+        Ok(ExecutionResult {
+            data: json!({ "url": url, "args": args }),
+            metadata: ExecutionMetadata {
+                duration_ms: 0,
+                operation: operation.to_string(),
+                response_headers: Vec::new(),
+                status: None,
+            },
+        })
+    }
+
+    async fn plan(
+        &self,
+        url: &str,
+        _operation: &str,
+        args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        Ok(RequestPlan::new(
+            "memory",
+            None,
+            url.to_string(),
+            json!(args),
+        ))
+    }
+}
+
+/// Answers probes of `scheme` URLs after `delay`
+struct Delayed {
+    name: &'static str,
+    scheme: &'static str,
+    delay: Duration,
+    matches: bool,
+}
+
+#[async_trait]
+impl Adapter for Delayed {
+    fn protocol_type(&self) -> ProtocolType {
+        ProtocolType::Plugin
+    }
+
+    async fn can_handle(&self, url: &str) -> Result<bool> {
+        if !url.starts_with(self.scheme) {
+            return Ok(false);
+        }
+        tokio::time::sleep(self.delay).await;
+        Ok(self.matches)
+    }
+
+    async fn fetch_schema(&self, _url: &str) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    async fn list_operations(&self, _url: &str) -> Result<Vec<Operation>> {
+        Ok(vec![Operation {
+            operation_id: self.name.to_string(),
+            display_name: self.name.to_string(),
+            description: None,
+            parameters: Vec::new(),
+            return_type: None,
+            tags: Vec::new(),
+        }])
+    }
+
+    async fn describe_operation(&self, _url: &str, _operation: &str) -> Result<OperationDetail> {
+        unimplemented!()
+    }
+
+    async fn execute(
+        &self,
+        _url: &str,
+        _operation: &str,
+        _args: HashMap<String, Value>,
+    ) -> Result<ExecutionResult> {
+        unimplemented!()
+    }
+
+    async fn plan(
+        &self,
+        _url: &str,
+        _operation: &str,
+        _args: HashMap<String, Value>,
+    ) -> Result<RequestPlan> {
+        unimplemented!()
+    }
+}
+
+fn delayed(name: &'static str, scheme: &'static str, millis: u64, matches: bool) -> Arc<Delayed> {
+    Arc::new(Delayed {
+        name,
+        scheme,
+        delay: Duration::from_millis(millis),
+        matches,
+    })
+}
+
+async fn detected(url: &str) -> String {
+    let adapter = ProtocolDetector::new().detect_adapter(url).await.unwrap();
+    let operations = adapter.list_operations(url).await.unwrap();
+    operations[0].operation_id.clone()
+}
+
+#[tokio::test]
+async fn detection_races_probes_with_a_tie_break_window() {
+    // A slow probe that does not match no longer holds up a fast match
+    registry::register(
+        "slow-miss",
+        900,
+        delayed("slow-miss", "race://", 10_000, false),
+    );
+    registry::register("fast", 800, delayed("fast", "race://", 0, true));
+    let start = Instant::now();
+    assert_eq!(detected("race://pets").await, "fast");
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "{:?}",
+        start.elapsed()
+    );
+
+    // A higher-priority match within the window still wins
+    registry::register("careful", 950, delayed("careful", "tie://", 50, true));
+    registry::register("eager", 850, delayed("eager", "tie://", 0, true));
+    assert_eq!(detected("tie://pets").await, "careful");
+
+    for name in ["slow-miss", "fast", "careful", "eager"] {
+        registry::unregister(name);
+    }
+}
+
+#[tokio::test]
+async fn registered_adapters_are_detected_and_called() {
+    registry::register("memory", 1000, Arc::new(Memory));
+    assert_eq!(registry::registry().registrations()[0].name(), "memory");
+
+    let adapter = ProtocolDetector::new()
+        .detect_adapter("memory://pets")
+        .await
+        .unwrap();
+    assert_eq!(adapter.protocol_type(), ProtocolType::Plugin);
+
+    let client = Client::builder()
+        .endpoint("memory://pets")
+        .cache(CacheConfig {
+            enabled: false,
+            ..Default::default()
+        })
+        .build()
+        .await
+        .unwrap();
+    let operations = client.list_operations().await.unwrap();
+    assert_eq!(operations[0].operation_id, "echo");
+    let result = client.execute("echo", json!({ "n": 1 })).await.unwrap();
+    assert_eq!(
+        result.data,
+        json!({ "url": "memory://pets", "args": { "n": 1 } })
+    );
+
+    // Unregistered, nothing serves the URL any more
+    assert!(registry::unregister("memory"));
+    let err = ProtocolDetector::new()
+        .detect_adapter("memory://pets")
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("No adapter found"), "{}", err);
+}
@@ -38,8 +38,8 @@ main() {
 
   local version
   version="$(awk -F\" '
-    /^\[package\]/ { in_pkg = 1; next }
-    /^\[/ && !/^\[package\]/ { if (in_pkg) exit }
+    /^\[workspace\.package\]/ { in_pkg = 1; next }
+    /^\[/ && !/^\[workspace\.package\]/ { if (in_pkg) exit }
     in_pkg && /^version[[:space:]]*=/ { print $2; exit }
   ' Cargo.toml)"
   [[ -n "${version}" ]] || fail "failed to read version from Cargo.toml"